@@ -1,6 +1,12 @@
 id = "template.tools.commit"
 version = "v1"
 
+params = {
+  cache_ttl_secs = "3600"
+  narrative_message = "chore: update narrative"
+  split = "false"
+}
+
 policy = {
   dependencies = {
     missing_producer = "wait"
@@ -26,21 +32,7 @@ if [ -z "$status" ]; then
   status="<none>"
 fi
 
-staged_diff="$(git diff --cached --no-ext-diff --unified=3 -- . | head -c 30000)"
-if [ -z "$staged_diff" ]; then
-  staged_diff="<none>"
-fi
-
-unstaged_diff="$(git diff --no-ext-diff --unified=3 -- . | head -c 30000)"
-if [ -z "$unstaged_diff" ]; then
-  unstaged_diff="<none>"
-fi
-
-{
-  printf '## Tracked Status\\n%s\\n\\n' "$status"
-  printf '## Staged Diff (truncated)\\n%s\\n\\n' "$staged_diff"
-  printf '## Unstaged Diff (truncated)\\n%s\\n' "$unstaged_diff"
-} > .vizier/tmp/commit-context.txt
+printf '## Tracked Status\\n%s\\n' "$status" > .vizier/tmp/commit-context.txt
 SCRIPT
     }
     on = {
@@ -67,6 +59,9 @@ SCRIPT
     kind = "agent"
     uses = "cap.agent.invoke"
     needs = [{ custom = { type_id = "prompt_text", key = "commit_prompt" } }]
+    args = {
+      cache_ttl_secs = "$${cache_ttl_secs}"
+    }
     produces = {
       succeeded = [{ custom = { type_id = "commit_message", key = "tracked_changes" } }]
     }
@@ -97,6 +92,8 @@ SCRIPT
     needs = [{ custom = { type_id = "commit_message", key = "tracked_changes" } }]
     args = {
       message = "read_payload(commit_message)"
+      narrative_message = "$${narrative_message}"
+      split = "$${split}"
     }
     on = {
       succeeded = ["terminal"]
@@ -5,6 +5,7 @@ cli = {
   positional = ["spec_file", "slug", "branch"]
   named = {
     file = "spec_file"
+    label = "labels"
     name = "slug"
   }
 }
@@ -12,10 +13,13 @@ cli = {
 params = {
   branch = ""
   commit_message = "chore: workflow stage commit"
+  labels = ""
   slug = ""
+  spawned_by = ""
   spec_file = ""
   spec_source = "inline"
   spec_text = ""
+  split = "false"
 }
 
 policy = {
@@ -83,10 +87,13 @@ nodes = [
     uses = "cap.env.builtin.plan.persist"
     args = {
       branch = "$${branch}"
+      labels = "$${labels}"
       name_override = "$${slug}"
+      spawned_by = "$${spawned_by}"
       spec_file = "$${spec_file}"
       spec_source = "$${spec_source}"
       spec_text = "$${spec_text}"
+      split = "$${split}"
     }
     needs = [{ custom = { type_id = "plan_text", key = "draft_plan:$${slug}" } }]
     produces = {
@@ -11,6 +11,8 @@ cli = {
 }
 
 params = {
+  also = ""
+  auto_revert = ""
   branch = ""
   cicd_auto_resolve = "false"
   cicd_auto_resolve_script = ""
@@ -18,8 +20,10 @@ params = {
   conflict_auto_resolve = "true"
   delete_branch = "true"
   merge_message = ""
+  no_cache = "false"
   slug = ""
   squash = "true"
+  strategy = ""
   target_branch = ""
 }
 
@@ -38,11 +42,15 @@ nodes = [
     kind = "builtin"
     uses = "cap.env.builtin.git.integrate_plan_branch"
     args = {
+      also = "$${also}"
+      auto_revert = "$${auto_revert}"
       branch = "$${branch}"
       delete_branch = "$${delete_branch}"
       message = "$${merge_message}"
+      no_cache = "$${no_cache}"
       slug = "$${slug}"
       squash = "$${squash}"
+      strategy = "$${strategy}"
       target_branch = "$${target_branch}"
     }
     needs = [{ custom = { type_id = "stage_token", key = "approve:$${slug}" } }]
@@ -73,6 +81,7 @@ nodes = [
     args = {
       auto_resolve = "$${cicd_auto_resolve}"
       auto_resolve_script = "$${cicd_auto_resolve_script}"
+      no_cache = "$${no_cache}"
     }
     gates = [
       {
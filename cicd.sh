@@ -11,4 +11,5 @@ mkdir -p "$CARGO_TARGET_DIR"
 cargo fmt
 cargo clippy --all --all-targets -- -D warnings
 cargo run -p vizier --bin gen-man -- --check
+cargo run -p vizier --bin gen-action -- --check
 cargo test --all --all-targets
@@ -85,8 +85,8 @@ fn test_cicd_defaults_cargo_target_dir_when_unset() -> TestResult {
     let entries = read_commands(&log)?;
     assert_eq!(
         entries.len(),
-        4,
-        "expected four cargo invocations; got {entries:?}"
+        5,
+        "expected five cargo invocations; got {entries:?}"
     );
 
     for entry in &entries {
@@ -110,6 +110,7 @@ fn test_cicd_defaults_cargo_target_dir_when_unset() -> TestResult {
             "fmt",
             "clippy --all --all-targets -- -D warnings",
             "run -p vizier --bin gen-man -- --check",
+            "run -p vizier --bin gen-action -- --check",
             "test --all --all-targets",
         ]
     );
@@ -157,8 +158,8 @@ fn test_cicd_respects_explicit_cargo_target_dir() -> TestResult {
     let entries = read_commands(&log)?;
     assert_eq!(
         entries.len(),
-        4,
-        "expected four cargo invocations; got {entries:?}"
+        5,
+        "expected five cargo invocations; got {entries:?}"
     );
     let expected_target = fs::canonicalize(&custom_target)?;
     for entry in &entries {
@@ -110,11 +110,23 @@ fn test_help_all_prints_reduced_reference() -> TestResult {
         "\n  list ",
         "\n  cd ",
         "\n  clean ",
+        "\n  rebase ",
+        "\n  pick ",
+        "\n  rescue ",
+        "\n  search ",
+        "\n  sessions ",
+        "\n  stats ",
+        "\n  plan ",
         "\n  jobs ",
         "\n  run ",
         "\n  audit ",
         "\n  completions ",
         "\n  release ",
+        "\n  changelog ",
+        "\n  agent ",
+        "\n  config ",
+        "\n  label ",
+        "\n  watch ",
     ] {
         assert!(
             stdout.contains(command),
@@ -129,7 +141,6 @@ fn test_help_all_prints_reduced_reference() -> TestResult {
         "\n  merge ",
         "\n  build ",
         "\n  patch ",
-        "\n  plan ",
         "\n  test-display ",
         "\n  __workflow-node ",
     ] {
@@ -167,7 +178,6 @@ fn test_removed_commands_fail_as_unknown_subcommands() -> TestResult {
         "review",
         "merge",
         "test-display",
-        "plan",
         "build",
         "patch",
     ] {
@@ -411,7 +421,6 @@ fn test_manpage_layout_uses_sectioned_real_files() -> TestResult {
         "\n  build ",
         "\n  patch ",
         "\n  test-display ",
-        "\n  plan ",
     ] {
         assert!(
             !root_page.contains(removed),
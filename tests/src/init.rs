@@ -220,6 +220,49 @@ fn test_init_creates_required_scaffold_and_ignore_rules() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn test_init_install_shims_and_smoke_check() -> TestResult {
+    let repo = IntegrationRepo::new()?;
+    clean_workdir(&repo)?;
+
+    let output = repo.vizier_output_no_follow(&["init", "--install-shims"])?;
+    assert!(
+        output.status.success(),
+        "vizier init --install-shims failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Agent shims: codex, claude, gemini"),
+        "expected installed shims, got: {stdout}"
+    );
+    assert!(
+        stdout.contains("Smoke check"),
+        "expected smoke check report, got: {stdout}"
+    );
+    for label in ["codex", "claude", "gemini"] {
+        let agent = repo.path().join(format!(".vizier/agents/{label}/agent.sh"));
+        assert!(agent.is_file(), "missing {}", agent.display());
+        #[cfg(unix)]
+        {
+            let mode = fs::metadata(&agent)?.permissions().mode() & 0o777;
+            assert_eq!(mode, 0o755, "shims should be executable");
+        }
+    }
+
+    let rerun = repo.vizier_output_no_follow(&["init", "--install-shims", "--no-smoke"])?;
+    let stdout = String::from_utf8_lossy(&rerun.stdout);
+    assert!(
+        stdout.contains("Agent shims: already installed"),
+        "rerun should keep existing shims: {stdout}"
+    );
+    assert!(
+        !stdout.contains("Smoke check"),
+        "--no-smoke should skip the check: {stdout}"
+    );
+    Ok(())
+}
+
 #[test]
 fn test_init_partial_repo_only_adds_missing_pieces() -> TestResult {
     let repo = IntegrationRepo::new()?;
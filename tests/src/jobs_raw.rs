@@ -330,3 +330,78 @@ fn test_jobs_show_format_json_raw_typed_envelope() -> TestResult {
 
     Ok(())
 }
+
+#[test]
+fn test_jobs_retry_continue_keeps_worktree_and_records_note() -> TestResult {
+    let repo = IntegrationRepo::new()?;
+    let job_id = "job-retry-continue";
+    let worktree_rel = ".vizier/tmp-worktrees/stage-approve-job-retry-continue";
+    let worktree_path = repo.path().join(worktree_rel);
+    fs::create_dir_all(&worktree_path)?;
+    fs::write(worktree_path.join("partial.rs"), "// prior agent work\n")?;
+    write_job_record(
+        &repo,
+        job_id,
+        json!({
+            "id": job_id,
+            "status": "failed",
+            "command": ["vizier", "__workflow-node", "--job-id", job_id],
+            "child_args": ["__workflow-node", "--job-id", job_id],
+            "created_at": "2026-01-30T02:00:00Z",
+            "started_at": "2026-01-30T02:00:01Z",
+            "finished_at": "2026-01-30T02:00:02Z",
+            "pid": null,
+            "exit_code": 1,
+            "stdout_path": format!(".vizier/jobs/{job_id}/stdout.log"),
+            "stderr_path": format!(".vizier/jobs/{job_id}/stderr.log"),
+            "session_path": null,
+            "outcome_path": null,
+            "metadata": {
+                "plan": "retry-continue",
+                "execution_root": worktree_rel,
+                "worktree_path": worktree_rel,
+                "worktree_owned": true
+            },
+            "config_snapshot": null,
+            "schedule": {
+                "dependencies": [
+                    { "artifact": { "target_branch": { "name": "missing-retry-continue-target" } } }
+                ]
+            }
+        }),
+    )?;
+
+    let output = repo
+        .vizier_cmd_background()
+        .args([
+            "jobs",
+            "retry",
+            "--continue",
+            "--note",
+            "use the existing retry helper",
+            "--format",
+            "json",
+            job_id,
+        ])
+        .output()?;
+    assert!(
+        output.status.success(),
+        "vizier jobs retry --continue failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let payload: Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(payload["continued"], Value::Bool(true));
+    assert_eq!(payload["note"], "use the existing retry helper");
+    assert!(
+        worktree_path.join("partial.rs").exists(),
+        "--continue should keep prior worktree contents"
+    );
+
+    let record = read_job_record(&repo, job_id)?;
+    let metadata = &record["metadata"];
+    assert_eq!(metadata["worktree_path"], worktree_rel);
+    assert_eq!(metadata["execution_root"], worktree_rel);
+    assert_eq!(metadata["retry_cleanup_status"], "preserved");
+    assert_eq!(metadata["operator_note"], "use the existing retry helper");
+    Ok(())
+}
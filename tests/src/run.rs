@@ -190,9 +190,6 @@ merge = "file:.vizier/workflows/merge.hcl"
 develop = "file:.vizier/develop.hcl"
 commit = "file:.vizier/workflows/commit.hcl"
 
-[agents.default]
-selector = "mock"
-
 [agents.default.agent]
 command = ["sh", "-lc", "{}"]
 "#,
@@ -883,6 +880,304 @@ fn test_run_workflow_help_writes_no_manifests_or_jobs() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn test_run_approve_warns_or_blocks_on_stale_plan_branch() -> TestResult {
+    let repo = IntegrationRepo::new_serial()?;
+    clean_workdir(&repo)?;
+    seed_plan_branch(&repo, "stale", "draft/stale")?;
+    for index in 0..2 {
+        let rel = format!("notes/drift-{index}.txt");
+        repo.write(&rel, "advance master\n")?;
+        repo.git(&["add", &rel])?;
+        repo.git(&["commit", "-m", &format!("chore: advance master {index}")])?;
+    }
+    write_stage_alias_test_config(&repo)?;
+    let config = repo.read(".vizier/config.toml")?;
+    repo.write(
+        ".vizier/config.toml",
+        &format!("{config}\n[workflow.drift]\nmax_commits_behind = 1\n"),
+    )?;
+
+    let warned = repo.vizier_output(&["run", "approve", "stale", "--check"])?;
+    let stderr = String::from_utf8_lossy(&warned.stderr);
+    assert!(
+        warned.status.success(),
+        "non-strict drift should only warn: {stderr}"
+    );
+    assert!(
+        stderr.contains("2 commits behind master") && stderr.contains("vizier rebase stale"),
+        "missing drift warning: {stderr}"
+    );
+
+    let blocked = repo.vizier_output(&["run", "approve", "stale", "--check", "--strict"])?;
+    let stderr = String::from_utf8_lossy(&blocked.stderr);
+    assert!(!blocked.status.success(), "--strict should block: {stderr}");
+    assert!(stderr.contains("blocked by --strict"), "{stderr}");
+
+    let listed = repo.vizier_output(&["list", "--fields", "Plan,Behind", "--format", "json"])?;
+    assert!(listed.status.success());
+    let payload: Value = serde_json::from_slice(&listed.stdout)?;
+    assert_eq!(payload["entries"][0]["plan"], "stale");
+    assert_eq!(payload["entries"][0]["commits_behind"], "2");
+    assert!(
+        String::from_utf8_lossy(&listed.stderr).contains("plan `stale` (draft/stale) is stale"),
+        "list should warn about stale plans"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_run_approve_cost_estimate_honors_max_cost_and_records_actuals() -> TestResult {
+    let repo = IntegrationRepo::new_serial()?;
+    clean_workdir(&repo)?;
+    seed_plan_branch(&repo, "costly", "draft/costly")?;
+    write_stage_alias_test_config(&repo)?;
+    let before_jobs = count_job_records(&repo)?;
+
+    let blocked = repo.vizier_output(&["run", "approve", "costly", "--max-cost", "0"])?;
+    let stderr = String::from_utf8_lossy(&blocked.stderr);
+    assert!(
+        !blocked.status.success(),
+        "--max-cost 0 should abort: {stderr}"
+    );
+    assert!(stderr.contains("exceeds --max-cost $0.00"), "{stderr}");
+    assert_eq!(
+        count_job_records(&repo)?,
+        before_jobs,
+        "an aborted approve must not enqueue jobs"
+    );
+
+    let payload = run_alias_follow_json(&repo, "approve", &["costly", "--max-cost", "100"])?;
+    assert_flagship_follow_success(&repo, &payload, "approve")?;
+    let agent_job = payload
+        .get("succeeded")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .map(|job_id| read_job_record(&repo, job_id))
+        .collect::<TestResult<Vec<_>>>()?
+        .into_iter()
+        .find(|record| {
+            record
+                .pointer("/metadata/workflow_node_id")
+                .and_then(Value::as_str)
+                == Some("invoke_agent")
+        })
+        .ok_or("missing invoke_agent job")?;
+    let estimated = agent_job
+        .pointer("/metadata/cost_estimate/context_tokens")
+        .and_then(Value::as_u64)
+        .unwrap_or_default();
+    assert!(estimated > 0, "missing cost estimate: {agent_job}");
+    assert!(
+        agent_job
+            .pointer("/metadata/cost_actual/input_tokens")
+            .and_then(Value::as_u64)
+            .is_some_and(|tokens| tokens > 0),
+        "missing measured cost: {agent_job}"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_run_approve_generate_tests_commits_separately_unless_disabled() -> TestResult {
+    let repo = IntegrationRepo::builder()
+        .mock_agent(false)
+        .serial(true)
+        .build()?;
+    clean_workdir(&repo)?;
+    seed_plan_branch(&repo, "covered", "draft/covered")?;
+    seed_plan_branch(&repo, "skipped", "draft/skipped")?;
+    write_stage_alias_test_config_with_agent_command(
+        &repo,
+        "prompt=$(cat); case \"$prompt\" in *'Add or extend automated tests'*) printf 'ok\\n' > generated_test.txt ;; esac; printf '%s\\n' 'mock agent response'",
+    )?;
+    // A label without a bundled shim keeps the raw shell agent free of any progress filter.
+    let config = fs::read_to_string(repo.path().join(".vizier/config.toml"))?;
+    repo.write(
+        ".vizier/config.toml",
+        &format!(
+            "{}\n[approve]\ngenerate_tests = true\n",
+            config.replace(
+                "[agents.default.agent]\n",
+                "[agents.default.agent]\nlabel = \"shell\"\n"
+            )
+        ),
+    )?;
+
+    let branch_tip_summary = |branch: &str| -> TestResult<String> {
+        let repo_handle = repo.repo();
+        let commit = repo_handle.revparse_single(branch)?.peel_to_commit()?;
+        Ok(commit.summary().unwrap_or_default().to_string())
+    };
+
+    let payload = run_alias_follow_json(&repo, "approve", &["covered"])?;
+    assert_flagship_follow_success(&repo, &payload, "approve")?;
+    assert_eq!(
+        branch_tip_summary("draft/covered")?,
+        "tests: cover covered acceptance criteria"
+    );
+
+    let payload = run_alias_follow_json(&repo, "approve", &["skipped", "--no-gen-tests"])?;
+    assert_flagship_follow_success(&repo, &payload, "approve")?;
+    assert!(
+        !branch_tip_summary("draft/skipped")?.starts_with("tests:"),
+        "--no-gen-tests should skip the tests commit"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_run_review_fix_loop_fixes_action_items_until_clean() -> TestResult {
+    let repo = IntegrationRepo::builder()
+        .mock_agent(false)
+        .serial(true)
+        .build()?;
+    clean_workdir(&repo)?;
+    seed_plan_branch(&repo, "looped", "draft/looped")?;
+    seed_plan_branch(&repo, "single", "draft/single")?;
+    // Fix prompts create the file; every review critique asks for it until it exists.
+    write_stage_alias_test_config_with_agent_command(
+        &repo,
+        "prompt=$(cat); case \"$prompt\" in *'after a Vizier review requested changes'*) printf 'fixed\\n' > review-fix.txt; printf '%s\\n' 'fixed' ;; *) if [ -f review-fix.txt ]; then printf '## Action Items\\n' ; else printf '## Action Items\\n- [major] add review-fix.txt\\n'; fi ;; esac",
+    )?;
+    let config = fs::read_to_string(repo.path().join(".vizier/config.toml"))?;
+    repo.write(
+        ".vizier/config.toml",
+        &config
+            .replace(
+                "[commands]\n",
+                "[commands]\nreview = \"file:.vizier/workflows/review.hcl\"\n",
+            )
+            .replace(
+                "[agents.default.agent]\n",
+                "[agents.default.agent]\nlabel = \"shell\"\n",
+            ),
+    )?;
+    repo.write(
+        ".vizier/workflows/review.hcl",
+        r#"id = "template.stage.review"
+version = "v1"
+
+cli = {
+  positional = ["slug"]
+}
+
+params = {
+  slug = ""
+}
+
+artifact_contracts = [
+  { id = "prompt_text", version = "v1" }
+]
+
+nodes = [
+  {
+    id = "worktree_prepare"
+    kind = "builtin"
+    uses = "cap.env.builtin.worktree.prepare"
+    args = {
+      slug = "$${slug}"
+      purpose = "stage-review"
+    }
+    on = {
+      succeeded = ["resolve_prompt"]
+    }
+  },
+  {
+    id = "resolve_prompt"
+    kind = "builtin"
+    uses = "cap.env.builtin.prompt.resolve"
+    args = {
+      prompt_text = "Review the plan branch."
+    }
+    produces = {
+      succeeded = [{ custom = { type_id = "prompt_text", key = "review_main" } }]
+    }
+    on = {
+      succeeded = ["review_agent"]
+    }
+    after = [{ node_id = "worktree_prepare" }]
+  },
+  {
+    id = "review_agent"
+    kind = "agent"
+    uses = "cap.agent.invoke"
+    needs = [{ custom = { type_id = "prompt_text", key = "review_main" } }]
+    on = {
+      succeeded = ["review_fix"]
+    }
+    after = [{ node_id = "resolve_prompt" }]
+  },
+  {
+    id = "review_fix"
+    kind = "agent"
+    uses = "cap.agent.review_fix_loop"
+    args = {
+      slug = "$${slug}"
+    }
+    on = {
+      succeeded = ["worktree_cleanup"]
+    }
+    after = [{ node_id = "review_agent" }]
+  },
+  {
+    id = "worktree_cleanup"
+    kind = "builtin"
+    uses = "cap.env.builtin.worktree.cleanup"
+    on = {
+      succeeded = ["terminal"]
+    }
+    after = [{ node_id = "review_fix" }]
+  },
+  {
+    id = "terminal"
+    kind = "gate"
+    uses = "control.terminal"
+    after = [{ node_id = "worktree_cleanup" }]
+  }
+]
+"#,
+    )?;
+    repo.git(&["add", "-A"])?;
+    repo.git(&["commit", "-m", "chore: add review workflow"])?;
+
+    let branch_tip_summary = |branch: &str| -> TestResult<String> {
+        let repo_handle = repo.repo();
+        let commit = repo_handle.revparse_single(branch)?.peel_to_commit()?;
+        Ok(commit.summary().unwrap_or_default().to_string())
+    };
+
+    let payload = run_alias_follow_json(&repo, "review", &["single"])?;
+    assert_flagship_follow_success(&repo, &payload, "review")?;
+    assert_eq!(
+        branch_tip_summary("draft/single")?,
+        "docs: seed plan single"
+    );
+
+    let payload = run_alias_follow_json(&repo, "review", &["looped", "--fix-loop"])?;
+    assert_flagship_follow_success(&repo, &payload, "review")?;
+    assert_eq!(
+        branch_tip_summary("draft/looped")?,
+        "fix: address review action items for looped (1/3)"
+    );
+    let run_id = payload
+        .get("run_id")
+        .and_then(Value::as_str)
+        .ok_or("missing review run_id")?;
+    let manifest = load_run_manifest(&repo, run_id)?;
+    let fix_record = read_job_record(&repo, &manifest_node_job_id(&manifest, "review_fix")?)?;
+    assert_eq!(
+        fix_record
+            .pointer("/metadata/workflow_node_outcome")
+            .and_then(Value::as_str),
+        Some("succeeded"),
+        "{fix_record}"
+    );
+    Ok(())
+}
+
 #[test]
 fn test_run_check_validates_and_writes_no_manifests_or_jobs() -> TestResult {
     let repo = IntegrationRepo::new_serial()?;
@@ -1222,6 +1517,36 @@ fn test_run_check_rejects_runtime_only_flags() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn test_run_edit_plan_requires_follow_and_a_terminal() -> TestResult {
+    let repo = IntegrationRepo::new_serial()?;
+    clean_workdir(&repo)?;
+    let before_jobs = count_job_records(&repo)?;
+
+    let output = repo.vizier_output(&["run", "draft", "--edit-plan", "spec"])?;
+    assert!(!output.status.success(), "--edit-plan without --follow");
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("--follow"),
+        "expected clap to require --follow: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let output = repo.vizier_output(&["run", "draft", "--edit-plan", "--follow", "spec"])?;
+    assert!(!output.status.success(), "--edit-plan without a terminal");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("--edit-plan needs an interactive terminal"),
+        "{stderr}"
+    );
+    assert_eq!(
+        count_job_records(&repo)?,
+        before_jobs,
+        "a refused --edit-plan run must not enqueue jobs"
+    );
+
+    Ok(())
+}
+
 #[test]
 fn test_run_ephemeral_enqueue_persists_manifest_and_job_metadata() -> TestResult {
     let repo = IntegrationRepo::new_serial()?;
@@ -1918,6 +2243,138 @@ fn test_run_draft_stage_persists_agent_output_as_plan_doc() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn test_run_draft_spawned_by_links_follow_up_in_list() -> TestResult {
+    let repo = IntegrationRepo::new_serial()?;
+    clean_workdir(&repo)?;
+    write_stage_alias_test_config(&repo)?;
+
+    for args in [
+        vec!["--set", "slug=parent", "--set", "spec_text=Parent plan."],
+        vec![
+            "--set",
+            "slug=child",
+            "--set",
+            "spec_text=Deferred action item.",
+            "--spawned-by",
+            "parent",
+        ],
+    ] {
+        let payload = run_alias_follow_json(&repo, "draft", &args)?;
+        assert_flagship_follow_success(&repo, &payload, "draft")?;
+    }
+
+    let child_doc = {
+        let repo_handle = repo.repo();
+        let commit = repo_handle
+            .revparse_single("draft/child")?
+            .peel_to_commit()?;
+        let blob = commit
+            .tree()?
+            .get_path(std::path::Path::new(
+                ".vizier/implementation-plans/child.md",
+            ))?
+            .to_object(&repo_handle)?
+            .peel_to_blob()?;
+        String::from_utf8_lossy(blob.content()).to_string()
+    };
+    assert!(child_doc.contains("spawned-by: parent\n"), "{child_doc}");
+
+    let list = repo.vizier_output(&["list"])?;
+    assert!(
+        list.status.success(),
+        "vizier list failed: {}",
+        String::from_utf8_lossy(&list.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&list.stdout);
+    assert!(
+        stdout
+            .lines()
+            .any(|line| line.trim_start().starts_with("Lineage")
+                && line.contains("follow-ups: child")),
+        "parent should list its follow-up: {stdout}"
+    );
+    assert!(
+        stdout
+            .lines()
+            .any(|line| line.trim_start().starts_with("Lineage")
+                && line.contains("spawned by parent")),
+        "child should link its parent: {stdout}"
+    );
+
+    let json = repo.vizier_output(&["list", "--format", "json"])?;
+    let payload: Value = serde_json::from_slice(&json.stdout)?;
+    let entries = payload
+        .get("entries")
+        .and_then(Value::as_array)
+        .ok_or("missing list entries")?;
+    let child = entries
+        .iter()
+        .find(|entry| entry.get("plan").and_then(Value::as_str) == Some("child"))
+        .ok_or("missing child entry")?;
+    assert_eq!(
+        child.pointer("/lineage/spawned_by"),
+        Some(&json!({ "plan": "parent", "merged": false })),
+        "{child}"
+    );
+
+    let invalid = repo.vizier_output(&[
+        "run",
+        "draft",
+        "--set",
+        "slug=orphan",
+        "--set",
+        "spec_text=Bad parent.",
+        "--spawned-by",
+        "a/b",
+        "--follow",
+    ])?;
+    assert!(
+        !invalid.status.success(),
+        "an invalid --spawned-by slug should fail the draft"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_run_draft_records_provenance_in_git_notes_when_configured() -> TestResult {
+    let repo = IntegrationRepo::new_serial()?;
+    clean_workdir(&repo)?;
+    write_stage_alias_test_config(&repo)?;
+    let config = fs::read_to_string(repo.path().join(".vizier/config.toml"))?;
+    repo.write(
+        ".vizier/config.toml",
+        &format!("{config}\n[commits.meta]\nstyle = \"notes\"\n"),
+    )?;
+
+    let payload = run_alias_follow_json(
+        &repo,
+        "draft",
+        &[
+            "--set",
+            "slug=noted",
+            "--set",
+            "spec_text=Keep messages clean.",
+        ],
+    )?;
+    assert_flagship_follow_success(&repo, &payload, "draft")?;
+
+    let repo_handle = repo.repo();
+    let tip = repo_handle
+        .revparse_single("draft/noted")?
+        .peel_to_commit()?;
+    let message = tip.message().unwrap_or_default().to_string();
+    assert!(
+        !message.contains("Session ID"),
+        "session metadata should stay out of the message: {message}"
+    );
+    let note = repo_handle.find_note(Some("refs/notes/vizier"), tip.id())?;
+    let note = note.message().unwrap_or_default().to_string();
+    assert!(note.contains("Session ID: "), "{note}");
+    assert!(note.contains("Vizier-Plan: noted"), "{note}");
+    Ok(())
+}
+
 #[test]
 fn test_run_draft_stage_force_stages_plan_doc_when_ignored() -> TestResult {
     let repo = IntegrationRepo::new_serial()?;
@@ -2654,6 +3111,123 @@ fn test_run_flagship_commit_user_outcome() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn test_run_commit_reuses_cached_agent_response_for_unchanged_prompt() -> TestResult {
+    let repo = IntegrationRepo::builder()
+        .mock_agent(false)
+        .serial(true)
+        .build()?;
+    clean_workdir(&repo)?;
+    let calls = repo.path().join("agent-calls.txt");
+    write_stage_alias_test_config_with_agent_command(
+        &repo,
+        &format!(
+            "cat >/dev/null; echo call >> '{}'; printf '%s\\n' 'feat: cached message'",
+            calls.display()
+        ),
+    )?;
+    let config = fs::read_to_string(repo.path().join(".vizier/config.toml"))?;
+    repo.write(
+        ".vizier/config.toml",
+        &config.replace(
+            "[agents.default.agent]\n",
+            "[agents.default.agent]\nlabel = \"shell\"\n",
+        ),
+    )?;
+    let agent_calls = || -> TestResult<usize> { Ok(fs::read_to_string(&calls)?.lines().count()) };
+    let undo_head_commit = || -> TestResult {
+        let repo_handle = repo.repo();
+        let parent = repo_handle.revparse_single("HEAD~1")?;
+        repo_handle.reset(&parent, ResetType::Mixed, None)?;
+        Ok(())
+    };
+    let invoke_stderr = |payload: &Value| -> TestResult<String> {
+        let job_id = payload
+            .get("succeeded")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .find(|job_id| {
+                read_job_record(&repo, job_id).is_ok_and(|record| {
+                    record
+                        .pointer("/metadata/workflow_node_id")
+                        .and_then(Value::as_str)
+                        == Some("invoke_agent")
+                })
+            })
+            .ok_or("missing invoke_agent job")?
+            .to_string();
+        Ok(repo.read(&format!(".vizier/jobs/{job_id}/stderr.log"))?)
+    };
+
+    let mut tracked = repo.read("a")?;
+    tracked.push_str("cached commit change\n");
+    repo.write("a", &tracked)?;
+    let first = run_alias_follow_json(&repo, "commit", &[])?;
+    assert_flagship_follow_success(&repo, &first, "commit")?;
+    assert_eq!(agent_calls()?, 1);
+    assert_eq!(head_subject(&repo)?, "feat: cached message");
+
+    // Undo the commit as an aborted run would leave things: same diff, same prompt.
+    undo_head_commit()?;
+    let second = run_alias_follow_json(&repo, "commit", &[])?;
+    assert_flagship_follow_success(&repo, &second, "commit")?;
+    assert_eq!(agent_calls()?, 1, "the second run should reuse the reply");
+    assert_eq!(head_subject(&repo)?, "feat: cached message");
+    let stderr = invoke_stderr(&second)?;
+    assert!(stderr.contains("agent: cached ("), "{stderr}");
+
+    undo_head_commit()?;
+    let uncached = run_alias_follow_json(&repo, "commit", &["--set", "cache_ttl_secs=0"])?;
+    assert_flagship_follow_success(&repo, &uncached, "commit")?;
+    assert_eq!(agent_calls()?, 2, "cache_ttl_secs=0 should call the agent");
+    Ok(())
+}
+
+#[test]
+fn test_run_hooks_wrap_phase_and_failing_pre_hook_blocks_it() -> TestResult {
+    let repo = IntegrationRepo::new_serial()?;
+    clean_workdir(&repo)?;
+    write_stage_alias_test_config(&repo)?;
+    let config = fs::read_to_string(repo.path().join(".vizier/config.toml"))?;
+    let with_hooks = |pre_commit: &str| {
+        format!(
+            "{config}\n[hooks]\npre_commit = \"{pre_commit}\"\npost_commit = \"echo post $VIZIER_PHASE $VIZIER_OUTCOME >> hooks.log\"\non_failure = \"echo failure $VIZIER_NODE_ID >> hooks.log\"\n"
+        )
+    };
+    let mut tracked = repo.read("a")?;
+    tracked.push_str("hooked change\n");
+    repo.write("a", &tracked)?;
+    let before_head = oid_for_spec(&repo.repo(), "HEAD")?;
+
+    repo.write(
+        ".vizier/config.toml",
+        &with_hooks("echo ticket closed >&2; exit 3"),
+    )?;
+    let blocked = repo.vizier_output(&["run", "commit", "--follow", "--format", "json"])?;
+    assert!(
+        !blocked.status.success(),
+        "a failing pre hook should fail the run"
+    );
+    assert_eq!(oid_for_spec(&repo.repo(), "HEAD")?, before_head);
+    assert_eq!(repo.read("hooks.log")?, "failure collect_context\n");
+
+    fs::remove_file(repo.path().join("hooks.log"))?;
+    repo.write(
+        ".vizier/config.toml",
+        &with_hooks("echo pre $VIZIER_PHASE >> hooks.log"),
+    )?;
+    let payload = run_alias_follow_json(&repo, "commit", &[])?;
+    assert_flagship_follow_success(&repo, &payload, "commit")?;
+    assert_ne!(oid_for_spec(&repo.repo(), "HEAD")?, before_head);
+    assert_eq!(
+        repo.read("hooks.log")?,
+        "pre commit\npost commit succeeded\n"
+    );
+    Ok(())
+}
+
 #[test]
 fn test_run_approve_stage_succeeds_after_draft_when_branch_is_implicit() -> TestResult {
     let repo = IntegrationRepo::new_serial()?;
@@ -5221,6 +5795,59 @@ uses = \"control.gate.approval\"\n",
     Ok(())
 }
 
+#[test]
+fn test_porcelain_emits_tab_separated_records_and_exit_trailer() -> TestResult {
+    let repo = IntegrationRepo::new_serial()?;
+    clean_workdir(&repo)?;
+    write_single_run_template(&repo, ".vizier/workflows/single.toml", "true")?;
+
+    let run = repo.vizier_output(&[
+        "--porcelain",
+        "run",
+        "file:.vizier/workflows/single.toml",
+        "--follow",
+    ])?;
+    let stdout = String::from_utf8_lossy(&run.stdout);
+    assert!(
+        run.status.success(),
+        "porcelain run failed: stderr={} stdout={stdout}",
+        String::from_utf8_lossy(&run.stderr)
+    );
+    let records = stdout
+        .lines()
+        .map(|line| line.split('\t').collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let run_record = records.first().ok_or("missing run record")?;
+    assert_eq!(run_record[0], "run", "{stdout}");
+    assert_eq!(run_record[2], "succeeded", "{stdout}");
+    assert!(
+        records.iter().any(|record| record[0] == "job"
+            && record[2] == "succeeded"
+            && record[3] == run_record[1]),
+        "expected a succeeded job record for the run: {stdout}"
+    );
+    assert_eq!(records.last(), Some(&vec!["exit", "0"]), "{stdout}");
+
+    let listed = repo.vizier_output(&["jobs", "list", "--all", "--porcelain=v1"])?;
+    let stdout = String::from_utf8_lossy(&listed.stdout);
+    assert!(listed.status.success(), "{stdout}");
+    assert!(
+        stdout
+            .lines()
+            .all(|line| line.starts_with("job\t") || line == "exit\t0"),
+        "jobs list porcelain should only carry job records: {stdout}"
+    );
+
+    let refused = repo.vizier_output(&["--porcelain", "stats"])?;
+    assert!(!refused.status.success());
+    assert!(
+        String::from_utf8_lossy(&refused.stderr).contains("has no --porcelain output"),
+        "stderr: {}",
+        String::from_utf8_lossy(&refused.stderr)
+    );
+    Ok(())
+}
+
 #[test]
 fn test_run_follow_reconciles_dead_worker_pid_to_terminal_failure() -> TestResult {
     let repo = IntegrationRepo::new_serial()?;
@@ -5522,3 +6149,148 @@ script = \"pwd\"\n",
 
     Ok(())
 }
+
+#[test]
+fn test_run_worktree_dir_checks_node_worktrees_out_externally() -> TestResult {
+    let repo = IntegrationRepo::new_serial()?;
+    clean_workdir(&repo)?;
+    let external = TempDir::new()?;
+
+    repo.write(
+        ".vizier/workflows/external-root.toml",
+        "id = \"template.external.root\"\n\
+version = \"v1\"\n\
+[[nodes]]\n\
+id = \"prepare\"\n\
+kind = \"builtin\"\n\
+uses = \"cap.env.builtin.worktree.prepare\"\n\
+[nodes.args]\n\
+branch = \"draft/external-root-run\"\n\
+[nodes.on]\n\
+succeeded = [\"in_worktree\"]\n\
+[[nodes]]\n\
+id = \"in_worktree\"\n\
+kind = \"shell\"\n\
+uses = \"cap.env.shell.command.run\"\n\
+[nodes.args]\n\
+script = \"pwd\"\n",
+    )?;
+
+    let worktree_dir = external.path().to_string_lossy().to_string();
+    let payload = run_json(
+        &repo,
+        &[
+            "run",
+            "file:.vizier/workflows/external-root.toml",
+            "--worktree-dir",
+            &worktree_dir,
+            "--format",
+            "json",
+        ],
+    )?;
+    let run_id = payload
+        .get("run_id")
+        .and_then(Value::as_str)
+        .ok_or("missing run_id")?;
+    let manifest_path = repo.path().join(format!(".vizier/jobs/runs/{run_id}.json"));
+    let manifest: Value = serde_json::from_str(&fs::read_to_string(&manifest_path)?)?;
+    let prepare_job = manifest
+        .pointer("/nodes/prepare/job_id")
+        .and_then(Value::as_str)
+        .ok_or("missing prepare job id")?
+        .to_string();
+    let in_worktree_job = manifest
+        .pointer("/nodes/in_worktree/job_id")
+        .and_then(Value::as_str)
+        .ok_or("missing in_worktree job id")?
+        .to_string();
+    for job_id in [&prepare_job, &in_worktree_job] {
+        wait_for_job_completion(&repo, job_id, Duration::from_secs(15))?;
+    }
+
+    let prepare = read_job_record(&repo, &prepare_job)?;
+    let execution_root = prepare
+        .pointer("/metadata/execution_root")
+        .and_then(Value::as_str)
+        .ok_or("prepare missing execution_root metadata")?;
+    assert!(
+        Path::new(execution_root).starts_with(external.path()),
+        "prepare should check out under --worktree-dir: {execution_root}"
+    );
+    assert!(
+        !repo.path().join(".vizier/tmp-worktrees").exists()
+            || fs::read_dir(repo.path().join(".vizier/tmp-worktrees"))?
+                .next()
+                .is_none(),
+        "no worktree should be created inside the repository"
+    );
+
+    let worktree_stdout = fs::read_to_string(
+        repo.path()
+            .join(".vizier/jobs")
+            .join(&in_worktree_job)
+            .join("stdout.log"),
+    )?;
+    let observed_pwd = worktree_stdout
+        .lines()
+        .rev()
+        .find(|line| !line.trim().is_empty())
+        .map(str::trim)
+        .ok_or("missing in_worktree pwd output")?;
+    assert_eq!(
+        observed_pwd,
+        Path::new(execution_root)
+            .canonicalize()?
+            .display()
+            .to_string(),
+        "in_worktree node should run in the external worktree"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_prompt_test_run_leaves_no_branch_worktree_or_commit() -> TestResult {
+    let repo = IntegrationRepo::new()?;
+    let head_before = repo.repo().head()?.peel_to_commit()?.id();
+    let output = repo
+        .vizier_cmd()
+        .args([
+            "prompt",
+            "test",
+            "implementation-plan",
+            "--input",
+            "Add a greeting to the README",
+            "--run",
+            "--format",
+            "json",
+        ])
+        .output()?;
+    assert!(
+        output.status.success(),
+        "prompt test --run failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let payload: Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(payload["outcome"], "prompt_test_ran");
+    assert_eq!(payload["prompt"], "implementation_plan");
+    let text = payload["text"].as_str().unwrap_or_default();
+    assert!(
+        text.contains("Add a greeting to the README"),
+        "rendered prompt should carry the operator input: {text}"
+    );
+    assert!(payload["estimated_tokens"].as_u64().unwrap_or(0) > 0);
+    assert!(payload["run"]["response"].is_string());
+
+    let git = repo.repo();
+    assert_eq!(git.head()?.peel_to_commit()?.id(), head_before);
+    let leftover = git
+        .branches(Some(BranchType::Local))?
+        .filter_map(Result::ok)
+        .filter_map(|(branch, _)| branch.name().ok().flatten().map(str::to_string))
+        .filter(|name| name.starts_with("vizier-prompt-test-"))
+        .collect::<Vec<_>>();
+    assert!(leftover.is_empty(), "sandbox branches left: {leftover:?}");
+    assert!(git.worktrees()?.is_empty(), "sandbox worktree left behind");
+    Ok(())
+}
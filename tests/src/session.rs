@@ -64,8 +64,8 @@ fn test_session_log_uses_v1_schema_and_repo_local_path() -> TestResult {
     let session_json: Value = serde_json::from_str(&contents)?;
     assert_eq!(
         session_json.get("schema").and_then(Value::as_str),
-        Some("vizier.session.v1"),
-        "session logs should carry the v1 schema marker"
+        Some("vizier.session.v2"),
+        "session logs should carry the v2 schema marker"
     );
 
     Ok(())
@@ -252,3 +252,552 @@ fn test_clean_keep_branches_preserves_draft_branch() -> TestResult {
     );
     Ok(())
 }
+
+fn add_plan_worktree(repo: &IntegrationRepo, name: &str, branch: &str) -> TestResult<PathBuf> {
+    repo.git(&["branch", branch])?;
+    let worktree_path = repo.path().join(".vizier/tmp-worktrees").join(name);
+    fs::create_dir_all(worktree_path.parent().expect("worktree parent"))?;
+    let repo_handle = repo.repo();
+    let reference = repo_handle.find_reference(&format!("refs/heads/{branch}"))?;
+    let mut opts = WorktreeAddOptions::new();
+    opts.reference(Some(&reference));
+    repo_handle.worktree(name, &worktree_path, Some(&opts))?;
+    Ok(worktree_path)
+}
+
+#[test]
+fn test_rescue_reports_and_commits_preserved_worktree_changes() -> TestResult {
+    let repo = IntegrationRepo::new()?;
+    let branch = "draft/rescue-alpha";
+    let job_id = "job-rescue-alpha";
+    let worktree_path = add_plan_worktree(&repo, "approve-job-rescue-alpha", branch)?;
+    write_job_record(
+        &repo,
+        job_id,
+        make_job_record(
+            job_id,
+            "failed",
+            json!({
+                "plan": "rescue-alpha",
+                "branch": branch,
+                "worktree_path": ".vizier/tmp-worktrees/approve-job-rescue-alpha",
+                "worktree_owned": true
+            }),
+            json!({}),
+        ),
+    )?;
+    fs::write(worktree_path.join("rescued.txt"), "agent output\n")?;
+
+    let inspect = repo.vizier_output(&["rescue", "rescue-alpha", "--format", "json"])?;
+    assert!(
+        inspect.status.success(),
+        "rescue inspect failed: {}",
+        String::from_utf8_lossy(&inspect.stderr)
+    );
+    let payload: Value = serde_json::from_slice(&inspect.stdout)?;
+    assert_eq!(payload["outcome"], "rescue_inspected");
+    assert_eq!(payload["job_id"], job_id);
+    assert!(
+        payload["changes"]
+            .as_array()
+            .is_some_and(|changes| changes.iter().any(|c| c == "?? rescued.txt")),
+        "expected untracked change in payload: {payload}"
+    );
+
+    let discard = repo.vizier_output(&["rescue", "rescue-alpha", "--discard"])?;
+    assert!(
+        !discard.status.success(),
+        "discard without --yes should fail on non-TTY stdin"
+    );
+    assert!(worktree_path.join("rescued.txt").exists());
+
+    let commit = repo.vizier_output(&[
+        "rescue",
+        "rescue-alpha",
+        "--commit",
+        "-m",
+        "chore: keep rescued work",
+        "--format",
+        "json",
+    ])?;
+    assert!(
+        commit.status.success(),
+        "rescue commit failed: {}",
+        String::from_utf8_lossy(&commit.stderr)
+    );
+    let payload: Value = serde_json::from_slice(&commit.stdout)?;
+    assert_eq!(payload["outcome"], "rescue_committed");
+
+    let repo_handle = repo.repo();
+    let tip = repo_handle
+        .find_branch(branch, BranchType::Local)?
+        .get()
+        .peel_to_commit()?;
+    assert_eq!(tip.summary(), Some("chore: keep rescued work"));
+    assert!(tip.tree()?.get_name("rescued.txt").is_some());
+    Ok(())
+}
+
+#[test]
+fn test_rescue_errors_without_preserved_worktree() -> TestResult {
+    let repo = IntegrationRepo::new()?;
+    let output = repo.vizier_output(&["rescue", "missing-plan"])?;
+    assert!(
+        !output.status.success(),
+        "rescue should fail without a worktree"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("no preserved worktree found for plan `missing-plan`"),
+        "unexpected stderr:\n{stderr}"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_search_ranks_narrative_threads_with_line_anchors() -> TestResult {
+    let repo = IntegrationRepo::new()?;
+    let threads = repo.path().join(".vizier/narrative/threads");
+    fs::create_dir_all(&threads)?;
+    fs::write(
+        threads.join("auth.md"),
+        "# Auth\n\nBackground notes.\n\n## Credentials\n\nRotate the auth token every 24 hours and revoke stale sessions.\n",
+    )?;
+    fs::write(
+        threads.join("release.md"),
+        "# Release\n\nTag releases from master after CI passes.\n",
+    )?;
+
+    let output = repo.vizier_output(&["search", "auth token rotation", "--format", "json"])?;
+    assert!(
+        output.status.success(),
+        "search failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let payload: Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(payload["outcome"], "search_completed");
+    let top = &payload["hits"][0];
+    assert_eq!(top["path"], ".vizier/narrative/threads/auth.md");
+    assert_eq!(top["kind"], "thread");
+    assert_eq!(top["line"], 7);
+    assert!(
+        top["snippet"]
+            .as_str()
+            .is_some_and(|snippet| snippet.contains("Rotate the auth token")),
+        "unexpected snippet: {top}"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_thread_resolve_commits_state_and_list_filters_by_it() -> TestResult {
+    let repo = IntegrationRepo::new()?;
+    let threads = repo.path().join(".vizier/narrative/threads");
+    fs::create_dir_all(&threads)?;
+    fs::write(threads.join("auth.md"), "---\nowners: alice\n---\n# Auth\n")?;
+    fs::write(threads.join("docs.md"), "# Docs\n")?;
+    repo.git(&["add", "-A"])?;
+    repo.git(&["commit", "-m", "add threads"])?;
+
+    let output = repo.vizier_output(&[
+        "thread", "resolve", "auth", "--by", "alpha", "--format", "json",
+    ])?;
+    assert!(
+        output.status.success(),
+        "thread resolve failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let payload: Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(payload["outcome"], "thread_resolved");
+    assert_eq!(payload["from"], "open");
+    assert_eq!(payload["status"]["resolved_by"], "alpha");
+    let document = fs::read_to_string(threads.join("auth.md"))?;
+    assert!(
+        document.contains("state: resolved\nresolved_by: alpha\n"),
+        "unexpected thread document: {document}"
+    );
+
+    let output =
+        repo.vizier_output(&["thread", "list", "--state", "resolved", "--format", "json"])?;
+    assert!(output.status.success());
+    let payload: Value = serde_json::from_slice(&output.stdout)?;
+    let listed = payload["threads"].as_array().expect("threads array");
+    assert_eq!(listed.len(), 1, "{payload}");
+    assert_eq!(listed[0]["thread"], "auth");
+    assert_eq!(listed[0]["owners"][0], "alice");
+
+    let output = repo.vizier_output(&["thread", "block", "auth", "--reason", "infra"])?;
+    assert!(
+        !output.status.success(),
+        "blocking a resolved thread should fail"
+    );
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("must be reopened"),
+        "unexpected stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_commands_write_outcome_file_beside_session_log() -> TestResult {
+    let repo = IntegrationRepo::new()?;
+    let output = repo.vizier_output(&["search", "anything", "--limit", "3", "--format", "json"])?;
+    assert!(
+        output.status.success(),
+        "search failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let relative = stderr
+        .lines()
+        .find_map(|line| line.strip_prefix("Outcome file: "))
+        .ok_or_else(|| format!("missing outcome path in stderr: {stderr}"))?;
+    assert!(
+        relative.starts_with(".vizier/sessions/") && relative.ends_with("/outcome.json"),
+        "unexpected outcome path: {relative}"
+    );
+
+    let outcome: Value = serde_json::from_str(&fs::read_to_string(repo.path().join(relative))?)?;
+    assert_eq!(outcome["schema"], "vizier.command_outcome.v1");
+    assert_eq!(outcome["command"], "search");
+    assert_eq!(outcome["flags"]["--limit"], serde_json::json!(["3"]));
+    assert_eq!(outcome["flags"]["--format"], serde_json::json!(["json"]));
+    assert_eq!(outcome["agent"]["invoked"], false);
+    assert_eq!(outcome["commits"], serde_json::json!([]));
+    assert_eq!(outcome["exit"]["status"], "succeeded");
+    assert_eq!(outcome["exit"]["code"], 0);
+
+    let quiet = repo.vizier_output(&["--no-session", "search", "anything"])?;
+    assert!(quiet.status.success());
+    assert!(
+        !String::from_utf8_lossy(&quiet.stderr).contains("Outcome file:"),
+        "--no-session should skip the outcome file"
+    );
+    Ok(())
+}
+
+fn seed_rebase_plan(repo: &IntegrationRepo, slug: &str, draft_change: (&str, &str)) -> TestResult {
+    let branch = format!("draft/{slug}");
+    repo.git(&["checkout", "-b", &branch])?;
+    let plan_rel = format!(".vizier/implementation-plans/{slug}.md");
+    repo.write(
+        &plan_rel,
+        &format!(
+            "---\nplan_id: pln_{slug}\nplan: {slug}\nbranch: {branch}\n---\n\n## Operator Spec\nRebase me.\n\n## Implementation Plan\n- step\n"
+        ),
+    )?;
+    repo.write(draft_change.0, draft_change.1)?;
+    repo.git(&["add", "-A"])?;
+    repo.git(&["commit", "-m", &format!("feat: draft {slug}")])?;
+    repo.git(&["checkout", "master"])?;
+    Ok(())
+}
+
+#[test]
+fn test_rebase_replays_draft_onto_target_and_records_base() -> TestResult {
+    let repo = IntegrationRepo::new()?;
+    clean_workdir(&repo)?;
+    seed_rebase_plan(&repo, "rebase-alpha", ("draft-only.txt", "draft\n"))?;
+    repo.write("target-only.txt", "target\n")?;
+    repo.git(&["add", "target-only.txt"])?;
+    repo.git(&["commit", "-m", "chore: advance master"])?;
+
+    let output = repo.vizier_output(&[
+        "rebase",
+        "rebase-alpha",
+        "--target",
+        "master",
+        "--format",
+        "json",
+    ])?;
+    assert!(
+        output.status.success(),
+        "rebase failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let payload: Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(payload["outcome"], "rebase_completed");
+    assert_eq!(payload["commits_behind"], 1);
+    assert_eq!(payload["plan_updated"], true);
+
+    let repo_handle = repo.repo();
+    let master_tip = repo_handle
+        .find_branch("master", BranchType::Local)?
+        .get()
+        .peel_to_commit()?;
+    let draft_tip = repo_handle
+        .find_branch("draft/rebase-alpha", BranchType::Local)?
+        .get()
+        .peel_to_commit()?;
+    assert_eq!(payload["head"], draft_tip.id().to_string());
+    assert_eq!(
+        repo_handle.merge_base(draft_tip.id(), master_tip.id())?,
+        master_tip.id()
+    );
+    let tree = draft_tip.tree()?;
+    assert!(tree.get_name("target-only.txt").is_some());
+    assert!(tree.get_name("draft-only.txt").is_some());
+    let plan_blob = tree
+        .get_path(Path::new(".vizier/implementation-plans/rebase-alpha.md"))?
+        .to_object(&repo_handle)?
+        .peel_to_blob()?;
+    let plan_doc = String::from_utf8(plan_blob.content().to_vec())?;
+    assert!(plan_doc.contains("base_branch: master\n"), "{plan_doc}");
+    assert!(
+        plan_doc.contains(&format!("base_commit: {}\n", master_tip.id())),
+        "{plan_doc}"
+    );
+
+    let again = repo.vizier_output(&[
+        "rebase",
+        "rebase-alpha",
+        "--target",
+        "master",
+        "--format",
+        "json",
+    ])?;
+    let payload: Value = serde_json::from_slice(&again.stdout)?;
+    assert_eq!(payload["outcome"], "rebase_up_to_date");
+    Ok(())
+}
+
+#[test]
+fn test_rebase_conflict_without_auto_resolve_leaves_branch_untouched() -> TestResult {
+    let repo = IntegrationRepo::new()?;
+    clean_workdir(&repo)?;
+    seed_rebase_plan(&repo, "rebase-beta", ("shared.txt", "draft side\n"))?;
+    repo.write("shared.txt", "target side\n")?;
+    repo.git(&["add", "shared.txt"])?;
+    repo.git(&["commit", "-m", "chore: conflicting master change"])?;
+    let before = repo
+        .repo()
+        .find_branch("draft/rebase-beta", BranchType::Local)?
+        .get()
+        .peel_to_commit()?
+        .id();
+
+    let output = repo.vizier_output(&[
+        "rebase",
+        "rebase-beta",
+        "--target",
+        "master",
+        "--no-auto-resolve",
+        "--format",
+        "json",
+    ])?;
+    assert_eq!(output.status.code(), Some(10));
+    let payload: Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(payload["outcome"], "rebase_conflicted");
+    assert_eq!(payload["conflicts"], json!(["shared.txt"]));
+
+    let after = repo
+        .repo()
+        .find_branch("draft/rebase-beta", BranchType::Local)?
+        .get()
+        .peel_to_commit()?
+        .id();
+    assert_eq!(
+        before, after,
+        "conflicted rebase should not move the branch"
+    );
+    let leftovers = fs::read_dir(repo.path().join(".vizier/tmp-worktrees"))?.count();
+    assert_eq!(leftovers, 0, "rebase worktree should be cleaned up");
+    Ok(())
+}
+
+#[test]
+fn test_rebase_checks_out_under_configured_worktree_dir() -> TestResult {
+    let repo = IntegrationRepo::new()?;
+    clean_workdir(&repo)?;
+    seed_rebase_plan(&repo, "rebase-gamma", ("draft-only.txt", "draft\n"))?;
+    repo.write("target-only.txt", "target\n")?;
+    repo.git(&["add", "target-only.txt"])?;
+    repo.git(&["commit", "-m", "chore: advance master"])?;
+
+    let external = TempDir::new()?;
+    let config_path = repo.path().join(".vizier/config.toml");
+    let mut config = fs::read_to_string(&config_path).unwrap_or_default();
+    config.push_str(&format!(
+        "\n[worktrees]\ndir = {:?}\n",
+        external.path().to_string_lossy()
+    ));
+    fs::write(&config_path, config)?;
+
+    let output = repo.vizier_output(&[
+        "rebase",
+        "rebase-gamma",
+        "--target",
+        "master",
+        "--format",
+        "json",
+    ])?;
+    assert!(
+        output.status.success(),
+        "rebase failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let payload: Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(payload["outcome"], "rebase_completed");
+
+    assert!(
+        !repo.path().join(".vizier/tmp-worktrees").exists(),
+        "rebase should not create worktrees inside the repository"
+    );
+    let namespaces = fs::read_dir(external.path())?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(
+        namespaces.len(),
+        1,
+        "rebase should check out under the configured dir: {namespaces:?}"
+    );
+    assert_eq!(
+        fs::read_dir(&namespaces[0])?.count(),
+        0,
+        "rebase worktree should be cleaned up"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_sessions_gc_keeps_newest_and_reports_listing() -> TestResult {
+    let repo = IntegrationRepo::new()?;
+    let sessions_root = repo.path().join(".vizier/sessions");
+    if sessions_root.exists() {
+        fs::remove_dir_all(&sessions_root)?;
+    }
+    for id in ["session-old", "session-mid", "session-new"] {
+        let dir = sessions_root.join(id);
+        fs::create_dir_all(&dir)?;
+        fs::write(
+            dir.join("session.json"),
+            format!("{{\"id\":\"{id}\",\"messages\":[]}}"),
+        )?;
+        std::thread::sleep(std::time::Duration::from_millis(30));
+    }
+
+    let output = repo.vizier_output(&["--no-session", "sessions", "list", "--format", "json"])?;
+    assert!(
+        output.status.success(),
+        "sessions list failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let listing: Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(listing["count"], 3);
+    assert_eq!(listing["sessions"][0]["id"], "session-new");
+
+    let output = repo.vizier_output(&[
+        "--no-session",
+        "sessions",
+        "gc",
+        "--keep-last",
+        "1",
+        "--dry-run",
+        "--format",
+        "json",
+    ])?;
+    let planned: Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(planned["outcome"], "sessions_gc_planned");
+    assert_eq!(planned["sessions"].as_array().map(Vec::len), Some(2));
+    assert!(sessions_root.join("session-old").is_dir());
+
+    let output = repo.vizier_output(&[
+        "--no-session",
+        "sessions",
+        "gc",
+        "--keep-last",
+        "1",
+        "--format",
+        "json",
+    ])?;
+    assert!(
+        output.status.success(),
+        "sessions gc failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(sessions_root.join("session-new").is_dir());
+    assert!(!sessions_root.join("session-mid").exists());
+    assert!(!sessions_root.join("session-old").exists());
+
+    let output = repo.vizier_output(&["--no-session", "sessions", "show", "session-new"])?;
+    assert!(
+        !output.status.success(),
+        "legacy logs should fail validation"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("vizier sessions migrate"), "{stderr}");
+
+    let output =
+        repo.vizier_output(&["--no-session", "sessions", "migrate", "--format", "json"])?;
+    assert!(
+        output.status.success(),
+        "sessions migrate failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let migrated: Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(migrated["outcome"], "sessions_migrated");
+    assert_eq!(migrated["migrated"], 1);
+    assert_eq!(migrated["sessions"][0]["from_version"], 0);
+    let log: Value = serde_json::from_str(&fs::read_to_string(
+        sessions_root.join("session-new/session.json"),
+    )?)?;
+    assert_eq!(log["schema"], "vizier.session.v2");
+    assert_eq!(log["schema_version"], 2);
+
+    let output = repo.vizier_output(&["--no-session", "sessions", "show", "session-new"])?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("session-new"), "{stdout}");
+    assert!(stdout.contains("Messages"), "{stdout}");
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_forge_issue_fetches_markdown_through_gh() -> TestResult {
+    let repo = IntegrationRepo::new()?;
+    repo.git(&[
+        "remote",
+        "add",
+        "origin",
+        "https://github.com/acme/widgets.git",
+    ])?;
+
+    let bin_dir = TempDir::new()?;
+    let gh = bin_dir.path().join("gh");
+    fs::write(
+        &gh,
+        "#!/bin/sh\n\
+         [ \"$1 $2 $3\" = \"issue view 42\" ] || exit 1\n\
+         echo '{\"number\":42,\"title\":\"Retry flaky uploads\",\"body\":\"Uploads drop on timeout.\",\"url\":\"https://github.com/acme/widgets/issues/42\"}'\n",
+    )?;
+    fs::set_permissions(&gh, fs::Permissions::from_mode(0o755))?;
+    let mut paths = vec![bin_dir.path().to_path_buf()];
+    paths.extend(env::split_paths(&env::var_os("PATH").unwrap_or_default()));
+    let path = env::join_paths(paths)?;
+
+    let status = repo
+        .vizier_cmd()
+        .env("PATH", &path)
+        .args(["forge", "status", "--format", "json"])
+        .output()?;
+    assert!(status.status.success(), "{status:?}");
+    let payload: Value = serde_json::from_slice(&status.stdout)?;
+    assert_eq!(payload["cli"], "gh");
+
+    let output = repo
+        .vizier_cmd()
+        .env("PATH", &path)
+        .args(["forge", "issue", "42", "-o", "specs/42.md"])
+        .output()?;
+    assert!(
+        output.status.success(),
+        "forge issue failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        repo.read("specs/42.md")?,
+        "# Retry flaky uploads\n\nUploads drop on timeout.\n\nSource: https://github.com/acme/widgets/issues/42\n"
+    );
+    Ok(())
+}
@@ -0,0 +1,96 @@
+use std::path::Path;
+
+use serde_json::json;
+use vizier_core::{plan, thread_owners, vcs};
+
+use super::shared::format_block;
+use super::types::{AckOptions, AckOutputFormat};
+
+pub(crate) fn run_ack(
+    project_root: &Path,
+    opts: AckOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if !vcs::branch_exists_in(project_root, &opts.branch)? {
+        return Err(format!("branch `{}` does not exist", opts.branch).into());
+    }
+    let plan_rel = plan::plan_rel_path(&opts.slug)
+        .to_string_lossy()
+        .replace('\\', "/");
+    let plan_document =
+        vcs::read_blob_at_revision_in(project_root, &format!("{}:{plan_rel}", opts.branch)).ok();
+    let threads = thread_owners::affected_threads(
+        project_root,
+        &opts.target,
+        &opts.branch,
+        plan_document.as_deref(),
+    )?;
+    if threads.is_empty() {
+        return Err(format!(
+            "plan `{}` affects no owned narrative threads; nothing to acknowledge",
+            opts.slug
+        )
+        .into());
+    }
+    let owned = threads
+        .iter()
+        .filter(|thread| {
+            thread.owners.iter().any(|owner| {
+                thread_owners::owner_matches(owner, &opts.owner)
+                    || thread_owners::owner_matches(&opts.owner, owner)
+            })
+        })
+        .map(|thread| thread.thread.clone())
+        .collect::<Vec<_>>();
+    if owned.is_empty() {
+        let owners = thread_owners::summary_line(&threads).unwrap_or_default();
+        return Err(format!(
+            "`{}` does not own a thread affected by plan `{}` ({owners})",
+            opts.owner, opts.slug
+        )
+        .into());
+    }
+
+    let tip = vcs::branch_tips_in(project_root)
+        .ok()
+        .and_then(|tips| tips.get(&opts.branch).map(|oid| oid.to_string()));
+    let ack = thread_owners::record_ack(project_root, &opts.slug, &opts.owner, tip)?;
+    let acks = thread_owners::load_acks(project_root, &opts.slug)?;
+    let remaining = thread_owners::unacknowledged(&threads, &acks)
+        .into_iter()
+        .map(|thread| thread.thread.clone())
+        .collect::<Vec<_>>();
+
+    match opts.format {
+        AckOutputFormat::Json => {
+            let payload = json!({
+                "outcome": "plan_acknowledged",
+                "plan": opts.slug,
+                "branch": opts.branch,
+                "ack": ack,
+                "threads": owned,
+                "unacknowledged": remaining,
+            });
+            println!("{}", serde_json::to_string_pretty(&payload)?);
+        }
+        AckOutputFormat::Text => {
+            println!(
+                "{}",
+                format_block(vec![
+                    ("Outcome".to_string(), "Plan acknowledged".to_string()),
+                    ("Plan".to_string(), opts.slug.clone()),
+                    ("Owner".to_string(), ack.owner.clone()),
+                    ("Threads".to_string(), owned.join(", ")),
+                    (
+                        "Awaiting".to_string(),
+                        if remaining.is_empty() {
+                            "none".to_string()
+                        } else {
+                            remaining.join(", ")
+                        },
+                    ),
+                ])
+            );
+        }
+    }
+    Ok(())
+}
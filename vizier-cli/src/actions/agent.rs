@@ -0,0 +1,90 @@
+use std::path::Path;
+
+use serde_json::json;
+use vizier_core::agent_protocol::{self, ShimUnderTest};
+
+use super::shared::format_table;
+use super::types::{AgentValidateOptions, AgentValidateOutputFormat};
+
+/// Violations listed per case in text output; JSON output carries all of them.
+const MAX_LISTED_VIOLATIONS: usize = 5;
+
+/// Exercises a custom agent shim with the canned `vizier-agent-protocol` cases and reports
+/// which ones it fails. Exits 10 when any case fails, like `vizier doctor`.
+pub(crate) fn run_agent_validate(
+    project_root: &Path,
+    opts: AgentValidateOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let shim = ShimUnderTest {
+        command: opts.command.clone(),
+        filter: opts.filter.clone().map(|filter| vec![filter]),
+        events: opts.events,
+        timeout: opts.timeout,
+        cwd: project_root.to_path_buf(),
+    };
+    let results = agent_protocol::validate(&shim);
+    let failures = results.iter().filter(|result| !result.passed).count();
+
+    match opts.format {
+        AgentValidateOutputFormat::Json => {
+            let payload = json!({
+                "outcome": if failures > 0 { "agent_validate_failed" } else { "agent_validate_passed" },
+                "protocol_version": agent_protocol::PROTOCOL_VERSION,
+                "command": opts.command,
+                "filter": opts.filter,
+                "event_stream": shim.filter.is_some() || opts.events,
+                "failures": failures,
+                "cases": results,
+            });
+            println!("{}", serde_json::to_string_pretty(&payload)?);
+        }
+        AgentValidateOutputFormat::Text => {
+            println!(
+                "Outcome: vizier agent validate: {failures} of {} case(s) failed (protocol v{})",
+                results.len(),
+                agent_protocol::PROTOCOL_VERSION
+            );
+            let rows = results
+                .iter()
+                .map(|result| {
+                    vec![
+                        if result.passed { "pass" } else { "fail" }.to_string(),
+                        result.case.clone(),
+                        result.detail.clone(),
+                    ]
+                })
+                .collect::<Vec<_>>();
+            println!("{}", format_table(&rows, 0));
+            let with_violations = results
+                .iter()
+                .filter(|result| !result.violations.is_empty())
+                .collect::<Vec<_>>();
+            if !with_violations.is_empty() {
+                println!();
+                println!("Violations:");
+                for result in with_violations {
+                    for violation in result.violations.iter().take(MAX_LISTED_VIOLATIONS) {
+                        match violation.line {
+                            Some(line) => {
+                                println!("  {} line {line}: {}", result.case, violation.message)
+                            }
+                            None => println!("  {}: {}", result.case, violation.message),
+                        }
+                    }
+                    let hidden = result
+                        .violations
+                        .len()
+                        .saturating_sub(MAX_LISTED_VIOLATIONS);
+                    if hidden > 0 {
+                        println!("  {}: {hidden} more (see --format json)", result.case);
+                    }
+                }
+            }
+        }
+    }
+
+    if failures > 0 {
+        crate::cli::outcome::exit(10);
+    }
+    Ok(())
+}
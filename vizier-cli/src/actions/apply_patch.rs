@@ -0,0 +1,268 @@
+use std::io::Read;
+use std::path::Path;
+
+use git2::{BranchType, Repository};
+use serde_json::json;
+use vizier_core::{
+    auditor::{Auditor, CommitMessageBuilder, CommitMessageType, Message},
+    display, vcs,
+};
+
+use crate::jobs;
+
+use super::shared::{format_block, short_hash};
+use super::types::{ApplyPatchOptions, ApplyPatchOutputFormat};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct AppliedCommit {
+    oid: String,
+    subject: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ApplyPatchResult {
+    Applied { commits: Vec<AppliedCommit> },
+    Rejected { patch: usize, reason: String },
+    ChecksFailed { failures: Vec<String> },
+}
+
+impl ApplyPatchResult {
+    fn outcome_key(&self) -> &'static str {
+        match self {
+            Self::Applied { .. } => "patch_applied",
+            Self::Rejected { .. } => "patch_rejected",
+            Self::ChecksFailed { .. } => "patch_checks_failed",
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Applied { .. } => "Patch applied to plan branch",
+            Self::Rejected { .. } => "Patch does not apply to plan branch",
+            Self::ChecksFailed { .. } => "Patch blocked by failing checks",
+        }
+    }
+}
+
+pub(crate) fn run_apply_patch(
+    project_root: &Path,
+    opts: ApplyPatchOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if !vcs::branch_exists_in(project_root, &opts.branch)? {
+        return Err(format!("branch `{}` does not exist", opts.branch).into());
+    }
+
+    let (source, input) = match opts.patch.as_ref() {
+        Some(path) => (
+            path.display().to_string(),
+            std::fs::read_to_string(path)
+                .map_err(|err| format!("unable to read {}: {err}", path.display()))?,
+        ),
+        None => {
+            let mut input = String::new();
+            std::io::stdin().read_to_string(&mut input)?;
+            ("stdin".to_string(), input)
+        }
+    };
+    let patches = vcs::parse_patch_input(&input)?;
+    if opts.message.is_some() && patches.len() > 1 {
+        return Err(format!(
+            "--message applies to a single patch, but {source} holds a series of {}",
+            patches.len()
+        )
+        .into());
+    }
+
+    let tip = Repository::open(project_root)?
+        .find_branch(&opts.branch, BranchType::Local)?
+        .get()
+        .peel_to_commit()?
+        .id();
+
+    // Each patch becomes a candidate commit stacked on the previous one; no ref moves until the
+    // whole series applied and the checks passed.
+    let commit_phase = display::start_phase(display::PHASE_COMMIT, None);
+    let mut head = tip;
+    let mut commits = Vec::new();
+    let mut note_builder = None;
+    for (index, patch) in patches.iter().enumerate() {
+        let tree = match vcs::apply_patch_to_commit_in(project_root, head, &patch.diff) {
+            Ok(tree) => tree,
+            Err(err) => {
+                commit_phase.finish();
+                let result = ApplyPatchResult::Rejected {
+                    patch: index + 1,
+                    reason: err.message().to_string(),
+                };
+                emit_apply_patch_report(&opts, &source, &result)?;
+                crate::cli::outcome::exit(10);
+            }
+        };
+        let body = opts
+            .message
+            .clone()
+            .or_else(|| patch.message.clone())
+            .unwrap_or_default();
+        let mut builder = CommitMessageBuilder::new(body);
+        builder
+            .set_header(CommitMessageType::CodeChange)
+            .with_author_note(format!("imported patch from {source}"));
+        let message = builder.build();
+        head =
+            vcs::create_patch_commit_in(project_root, head, tree, patch.author.as_ref(), &message)?;
+        commits.push(AppliedCommit {
+            oid: head.to_string(),
+            subject: message.lines().next().unwrap_or_default().to_string(),
+        });
+        note_builder = Some(builder);
+    }
+    commit_phase.finish();
+
+    if jobs::verification_configured() {
+        // Checks need a branch to check out; it only lives for the duration of the run.
+        let short = short_hash(&head.to_string());
+        let check_branch = format!("vizier/apply-patch/{}-{short}", opts.slug);
+        {
+            let repo = Repository::open(project_root)?;
+            repo.branch(&check_branch, &repo.find_commit(head)?, true)?;
+        }
+        let gate_phase = display::start_phase(display::PHASE_GATE, None);
+        let verification = jobs::verify_branch(
+            project_root,
+            &check_branch,
+            &opts.target,
+            &format!("apply-patch-{short}"),
+            &|line| eprintln!("{line}"),
+        );
+        gate_phase.finish();
+        if let Err(err) = vcs::delete_branch_in(project_root, &check_branch) {
+            display::warn(format!("unable to delete {check_branch}: {err}"));
+        }
+        let verification = verification?;
+        if !verification.passed() {
+            let mut failures = verification
+                .gates
+                .iter()
+                .filter(|gate| gate.status != 0)
+                .map(|gate| format!("gate `{}` failed (exit {})", gate.name, gate.status))
+                .collect::<Vec<_>>();
+            failures.extend(
+                verification
+                    .checks
+                    .results
+                    .iter()
+                    .filter(|check| !check.success)
+                    .map(|check| format!("review check `{}` failed", check.command)),
+            );
+            record_import(&opts, &source, &commits, false);
+            emit_apply_patch_report(&opts, &source, &ApplyPatchResult::ChecksFailed { failures })?;
+            Auditor::persist_session_log();
+            crate::cli::outcome::exit(10);
+        }
+    }
+
+    vcs::fast_forward_branch_in(project_root, &opts.branch, tip, head)?;
+    // Every commit in the series shares the same session fields and author note.
+    if let Some(builder) = note_builder.as_ref() {
+        for commit in &commits {
+            builder.record_note(
+                project_root,
+                git2::Oid::from_str(&commit.oid)?,
+                Some(&opts.slug),
+            );
+        }
+    }
+    record_import(&opts, &source, &commits, true);
+    emit_apply_patch_report(&opts, &source, &ApplyPatchResult::Applied { commits })
+}
+
+fn record_import(opts: &ApplyPatchOptions, source: &str, commits: &[AppliedCommit], landed: bool) {
+    Auditor::record_operation(
+        "apply_patch",
+        json!({
+            "plan": opts.slug,
+            "branch": opts.branch,
+            "source": source,
+            "landed": landed,
+            "commits": commits.iter().map(|commit| commit.oid.clone()).collect::<Vec<_>>(),
+        }),
+    );
+    Auditor::add_message(Message::system(format!(
+        "vizier apply-patch {} from {source}: {} commit(s) {}",
+        opts.slug,
+        commits.len(),
+        if landed {
+            format!("landed on {}", opts.branch)
+        } else {
+            "held back by failing checks".to_string()
+        }
+    )));
+}
+
+fn emit_apply_patch_report(
+    opts: &ApplyPatchOptions,
+    source: &str,
+    result: &ApplyPatchResult,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if matches!(opts.format, ApplyPatchOutputFormat::Json) {
+        let mut payload = json!({
+            "outcome": result.outcome_key(),
+            "plan": opts.slug,
+            "branch": opts.branch,
+            "source": source,
+        });
+        match result {
+            ApplyPatchResult::Applied { commits } => {
+                payload["commits"] = json!(
+                    commits
+                        .iter()
+                        .map(|commit| json!({"oid": commit.oid, "subject": commit.subject}))
+                        .collect::<Vec<_>>()
+                );
+            }
+            ApplyPatchResult::Rejected { patch, reason } => {
+                payload["patch"] = json!(patch);
+                payload["reason"] = json!(reason);
+            }
+            ApplyPatchResult::ChecksFailed { failures } => {
+                payload["failures"] = json!(failures);
+            }
+        }
+        println!("{}", serde_json::to_string_pretty(&payload)?);
+        return Ok(());
+    }
+
+    let mut rows = vec![
+        ("Outcome".to_string(), result.label().to_string()),
+        ("Plan".to_string(), opts.slug.clone()),
+        ("Branch".to_string(), opts.branch.clone()),
+        ("Source".to_string(), source.to_string()),
+    ];
+    match result {
+        ApplyPatchResult::Applied { commits } => {
+            for commit in commits {
+                rows.push((
+                    "Commit".to_string(),
+                    format!("{} {}", short_hash(&commit.oid), commit.subject),
+                ));
+            }
+        }
+        ApplyPatchResult::Rejected { patch, reason } => {
+            rows.push(("Patch".to_string(), patch.to_string()));
+            rows.push(("Reason".to_string(), reason.clone()));
+            rows.push((
+                "Next".to_string(),
+                format!("{} left unchanged; rebase the patch onto it", opts.branch),
+            ));
+        }
+        ApplyPatchResult::ChecksFailed { failures } => {
+            rows.push(("Failures".to_string(), failures.join("; ")));
+            rows.push((
+                "Next".to_string(),
+                format!("{} left unchanged", opts.branch),
+            ));
+        }
+    }
+    println!("{}", format_block(rows));
+    Ok(())
+}
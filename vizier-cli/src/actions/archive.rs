@@ -0,0 +1,190 @@
+use std::path::Path;
+
+use serde_json::json;
+use vizier_core::{archive, display, search};
+
+use crate::plan;
+
+use super::shared::{format_block, format_table};
+use super::types::{ArchiveOptions, ArchiveOutputFormat, ArchiveRequest};
+
+pub(crate) fn run_archive(
+    project_root: &Path,
+    opts: ArchiveOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match &opts.request {
+        ArchiveRequest::List => list_archives(project_root, opts.format),
+        ArchiveRequest::Show { slug } => show_archive(project_root, slug, opts.format),
+        ArchiveRequest::Search { query, limit } => {
+            search_archives(project_root, query, *limit, opts.format)
+        }
+    }
+}
+
+fn list_archives(
+    project_root: &Path,
+    format: ArchiveOutputFormat,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let archives = archive::list_archives(project_root)?;
+    if matches!(format, ArchiveOutputFormat::Json) {
+        let payload = json!({
+            "outcome": "archives_listed",
+            "count": archives.len(),
+            "archives": archives,
+        });
+        println!("{}", serde_json::to_string_pretty(&payload)?);
+        return Ok(());
+    }
+
+    if archives.is_empty() {
+        println!("{}", display::outcome_line("No archived plans"));
+        return Ok(());
+    }
+    let show_lineage = archives.iter().any(|entry| entry.spawned_by.is_some());
+    let mut header = vec![
+        "Plan".to_string(),
+        "Archived".to_string(),
+        "Commit".to_string(),
+        "Title".to_string(),
+    ];
+    if show_lineage {
+        header.push("Spawned by".to_string());
+    }
+    let mut rows = vec![header];
+    rows.extend(archives.iter().map(|entry| {
+        let mut row = vec![
+            entry.slug.clone(),
+            entry.archived_at.chars().take(10).collect(),
+            entry.branch_tip.chars().take(12).collect(),
+            entry.title.clone(),
+        ];
+        if show_lineage {
+            row.push(entry.spawned_by.clone().unwrap_or_else(|| "-".to_string()));
+        }
+        row
+    }));
+    println!("{}", format_table(&rows, 0));
+    Ok(())
+}
+
+fn show_archive(
+    project_root: &Path,
+    slug: &str,
+    format: ArchiveOutputFormat,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(entry) = archive::load_archive(project_root, slug)? else {
+        return Err(format!(
+            "plan `{slug}` is not archived under {}",
+            archive::ARCHIVE_DIR
+        )
+        .into());
+    };
+
+    // Follow-ups filed from this plan: merged ones from the archive, then still-pending drafts.
+    let mut follow_ups = archive::archived_follow_ups(&archive::list_archives(project_root)?, slug)
+        .into_iter()
+        .map(|child| (child, true))
+        .collect::<Vec<_>>();
+    for pending in plan::PlanSlugInventory::collect(None).unwrap_or_default() {
+        if pending.spawned_by.as_deref() == Some(slug)
+            && !follow_ups.iter().any(|(child, _)| *child == pending.slug)
+        {
+            follow_ups.push((pending.slug, false));
+        }
+    }
+
+    if matches!(format, ArchiveOutputFormat::Json) {
+        let payload = json!({
+            "outcome": "archive_shown",
+            "archive": entry.metadata,
+            "follow_ups": follow_ups
+                .iter()
+                .map(|(child, merged)| json!({ "plan": child, "merged": merged }))
+                .collect::<Vec<_>>(),
+            "plan": entry.plan,
+            "summary": entry.summary,
+            "critique": entry.critique,
+            "notes": entry.notes,
+        });
+        println!("{}", serde_json::to_string_pretty(&payload)?);
+        return Ok(());
+    }
+
+    let meta = &entry.metadata;
+    let mut rows = vec![
+        ("Plan".to_string(), meta.slug.clone()),
+        ("Title".to_string(), meta.title.clone()),
+        ("Branch".to_string(), meta.branch.clone()),
+        (
+            "Target".to_string(),
+            meta.target.clone().unwrap_or_else(|| "-".to_string()),
+        ),
+        ("Branch tip".to_string(), meta.branch_tip.clone()),
+        ("Archived".to_string(), meta.archived_at.clone()),
+    ];
+    if let Some(parent) = meta.spawned_by.as_ref() {
+        rows.push(("Spawned by".to_string(), parent.clone()));
+    }
+    if !follow_ups.is_empty() {
+        let links = follow_ups
+            .iter()
+            .map(|(child, merged)| {
+                if *merged {
+                    format!("{child} (merged)")
+                } else {
+                    format!("{child} (pending)")
+                }
+            })
+            .collect::<Vec<_>>();
+        rows.push(("Follow-ups".to_string(), links.join(", ")));
+    }
+    println!("{}", format_block(rows));
+    println!();
+    println!("{}", entry.plan.trim_end());
+    println!();
+    println!("{}", entry.summary.trim_end());
+    if let Some(critique) = entry.critique.as_ref() {
+        println!();
+        println!("# Review critique");
+        println!();
+        println!("{}", critique.trim_end());
+    }
+    if let Some(notes) = entry.notes.as_ref() {
+        println!();
+        println!("{}", notes.trim_end());
+    }
+    Ok(())
+}
+
+fn search_archives(
+    project_root: &Path,
+    query: &str,
+    limit: usize,
+    format: ArchiveOutputFormat,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let documents = archive::search_documents(project_root)?;
+    let hits = search::search_project(project_root, &documents, query, limit);
+    if matches!(format, ArchiveOutputFormat::Json) {
+        let payload = json!({
+            "outcome": "archive_search_completed",
+            "query": query,
+            "documents_searched": documents.len(),
+            "hits": hits,
+        });
+        println!("{}", serde_json::to_string_pretty(&payload)?);
+        return Ok(());
+    }
+
+    if hits.is_empty() {
+        println!(
+            "No matches for `{query}` ({} archived documents searched)",
+            documents.len()
+        );
+        return Ok(());
+    }
+    for hit in &hits {
+        println!("{}:{}", hit.path, hit.line);
+        println!("    {}", hit.snippet);
+    }
+    Ok(())
+}
@@ -24,7 +24,7 @@ pub(crate) fn run_workflow_audit(
     emit_audit_summary(cmd.format, &prepared.source, &prepared.template, &report)?;
 
     if cmd.strict && !report.untethered_inputs.is_empty() {
-        std::process::exit(10);
+        crate::cli::outcome::exit(10);
     }
 
     Ok(())
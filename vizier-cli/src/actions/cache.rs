@@ -0,0 +1,102 @@
+use serde_json::json;
+use vizier_core::{display, global_cache};
+
+use super::shared::{format_block, format_table};
+use super::types::{CacheOptions, CacheOutputFormat, CacheRequest};
+
+/// Reports on or prunes the per-user cache that backs bundled agent shims for every repo.
+pub(crate) fn run_cache(opts: CacheOptions) -> Result<(), Box<dyn std::error::Error>> {
+    let root = global_cache::cache_root()
+        .ok_or("no user cache directory found; set VIZIER_CACHE_DIR, XDG_CACHE_HOME, or HOME")?;
+    match opts.request {
+        CacheRequest::Status => {
+            let status = global_cache::status_in(&root)?;
+            match opts.format {
+                CacheOutputFormat::Json => {
+                    let payload = json!({
+                        "outcome": "cache_status",
+                        "root": status.root,
+                        "version": status.version,
+                        "bytes": status.bytes,
+                        "entries": status.entries,
+                        "stale": status.stale,
+                    });
+                    println!("{}", serde_json::to_string_pretty(&payload)?);
+                }
+                CacheOutputFormat::Text => {
+                    let repair = status
+                        .entries
+                        .iter()
+                        .filter(|entry| entry.state != global_cache::EntryState::Ok)
+                        .count();
+                    println!(
+                        "Outcome: vizier cache status: {} of {} file(s) need rewriting",
+                        repair,
+                        status.entries.len()
+                    );
+                    println!(
+                        "{}",
+                        format_block(vec![
+                            ("Root".to_string(), status.root.display().to_string()),
+                            ("Version".to_string(), format!("v{}", status.version)),
+                            (
+                                "Size".to_string(),
+                                format!("{} bytes", display::format_number(status.bytes as usize)),
+                            ),
+                            (
+                                "Stale".to_string(),
+                                if status.stale.is_empty() {
+                                    "none".to_string()
+                                } else {
+                                    status
+                                        .stale
+                                        .iter()
+                                        .map(|path| path.display().to_string())
+                                        .collect::<Vec<_>>()
+                                        .join(", ")
+                                },
+                            ),
+                        ])
+                    );
+                    let rows = status
+                        .entries
+                        .iter()
+                        .map(|entry| vec![entry.state.as_str().to_string(), entry.path.clone()])
+                        .collect::<Vec<_>>();
+                    println!("{}", format_table(&rows, 0));
+                    if repair > 0 {
+                        println!(
+                            "Missing or modified shims are rewritten the next time they are used."
+                        );
+                    }
+                }
+            }
+        }
+        CacheRequest::Clean { all } => {
+            let report = global_cache::clean_in(&root, all)?;
+            match opts.format {
+                CacheOutputFormat::Json => {
+                    let payload = json!({
+                        "outcome": "cache_cleaned",
+                        "root": root,
+                        "all": all,
+                        "removed": report.removed,
+                        "bytes": report.bytes,
+                    });
+                    println!("{}", serde_json::to_string_pretty(&payload)?);
+                }
+                CacheOutputFormat::Text => {
+                    println!(
+                        "Outcome: vizier cache clean: removed {} tree(s), {} bytes",
+                        report.removed.len(),
+                        display::format_number(report.bytes as usize)
+                    );
+                    for path in &report.removed {
+                        println!("  {}", path.display());
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,100 @@
+use std::path::Path;
+
+use serde_json::json;
+use vizier_core::{changelog, config, vcs};
+
+use super::types::{ChangelogOptions, ChangelogOutputFormat};
+
+/// Rolls the fragments merges left under `changelog.dir` into a new section of
+/// `changelog.file`, removes them, and commits both changes together.
+pub(crate) fn run_changelog(
+    project_root: &Path,
+    opts: ChangelogOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if opts.version.is_empty() {
+        return Err("VERSION must not be empty".into());
+    }
+    let cfg = config::get_config().changelog;
+    let Some(assembly) = changelog::assemble(project_root, &cfg, &opts.version, &opts.date)? else {
+        match opts.format {
+            ChangelogOutputFormat::Json => println!(
+                "{}",
+                serde_json::to_string_pretty(&json!({
+                    "outcome": "changelog_empty",
+                    "dir": cfg.dir,
+                }))?
+            ),
+            ChangelogOutputFormat::Text => println!(
+                "Outcome: No changelog fragments under {}; {} left unchanged",
+                cfg.dir, cfg.file
+            ),
+        }
+        return Ok(());
+    };
+
+    let mut commit = None;
+    if !opts.dry_run {
+        // Committing takes the whole index, so refuse to sweep unrelated changes into it.
+        if opts.commit {
+            vcs::ensure_clean_worktree_in(project_root).map_err(|err| {
+                format!("changelog assemble needs a clean worktree to commit: {err}")
+            })?;
+        }
+        let touched = changelog::write_assembly(project_root, &assembly)?;
+        if opts.commit {
+            let touched = touched.iter().map(String::as_str).collect::<Vec<_>>();
+            vcs::stage_paths_allow_missing_in(project_root, &touched)?;
+            let oid = vcs::commit_staged_in(
+                project_root,
+                &format!("docs(changelog): assemble {}", opts.version),
+                false,
+            )?;
+            commit = Some(oid.to_string());
+        }
+    }
+
+    let fragments = assembly
+        .fragments
+        .iter()
+        .map(|path| {
+            path.strip_prefix(project_root)
+                .unwrap_or(path)
+                .display()
+                .to_string()
+        })
+        .collect::<Vec<_>>();
+    match opts.format {
+        ChangelogOutputFormat::Json => {
+            let payload = json!({
+                "outcome": if opts.dry_run { "changelog_preview" } else { "changelog_assembled" },
+                "version": opts.version,
+                "file": cfg.file,
+                "fragments": fragments,
+                "section": assembly.section,
+                "commit": commit,
+            });
+            println!("{}", serde_json::to_string_pretty(&payload)?);
+        }
+        ChangelogOutputFormat::Text => {
+            let verb = if opts.dry_run {
+                "Would assemble"
+            } else {
+                "Assembled"
+            };
+            println!(
+                "Outcome: {verb} {} fragment(s) into {} as [{}]",
+                fragments.len(),
+                cfg.file,
+                opts.version
+            );
+            if let Some(commit) = &commit {
+                println!("Commit: {}", &commit[..commit.len().min(8)]);
+            }
+            if opts.dry_run {
+                println!();
+                print!("{}", assembly.section);
+            }
+        }
+    }
+    Ok(())
+}
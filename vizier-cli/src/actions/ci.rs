@@ -0,0 +1,587 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use serde_json::{Value, json};
+use vizier_core::{
+    auditor::{Auditor, Message},
+    display,
+    review_report::{ActionItem, ReviewReport, Severity},
+    vcs,
+};
+
+use crate::jobs;
+
+use super::shared::format_block;
+use super::types::{CiOptions, CiOutputFormat, CiRequest};
+
+/// Lines of gate output carried into a `--format github` annotation.
+const ANNOTATION_TAIL_LINES: usize = 20;
+
+/// Runs a CI check without prompting, writes its artifacts, and exits `10` when the change should
+/// not land (or with the review run's own exit code when the run itself failed).
+pub(crate) fn run_ci(
+    project_root: &Path,
+    opts: CiOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let artifacts_dir = if opts.artifacts_dir.is_absolute() {
+        opts.artifacts_dir.clone()
+    } else {
+        project_root.join(&opts.artifacts_dir)
+    };
+    fs::create_dir_all(&artifacts_dir).map_err(|err| {
+        format!(
+            "cannot create artifacts dir {}: {err}",
+            artifacts_dir.display()
+        )
+    })?;
+
+    let exit_code = match opts.request {
+        CiRequest::Review {
+            slug,
+            flow,
+            fail_on,
+            forwarded,
+        } => ci_review(
+            project_root,
+            &artifacts_dir,
+            opts.format,
+            &slug,
+            &flow,
+            fail_on,
+            &forwarded,
+        )?,
+        CiRequest::MergeQueue { branch, target } => {
+            ci_merge_queue(project_root, &artifacts_dir, opts.format, branch, target)?
+        }
+    };
+
+    if exit_code != 0 {
+        Auditor::persist_session_log();
+        crate::cli::outcome::exit(exit_code);
+    }
+    Ok(())
+}
+
+fn ci_review(
+    project_root: &Path,
+    artifacts_dir: &Path,
+    format: CiOutputFormat,
+    slug: &str,
+    flow: &str,
+    fail_on: Option<Severity>,
+    forwarded: &[String],
+) -> Result<i32, Box<dyn std::error::Error>> {
+    // The review runs as a followed child `vizier run` so the scheduler, approvals, and outcome
+    // files behave exactly as they do interactively; `--yes` and a closed stdin rule out prompts.
+    let output = Command::new(std::env::current_exe()?)
+        .args(forwarded)
+        .args(["run", flow, slug, "--follow", "--yes", "--format", "json"])
+        .current_dir(project_root)
+        .stdin(Stdio::null())
+        .stderr(Stdio::inherit())
+        .output()?;
+    let run: Value = serde_json::from_slice(&output.stdout).map_err(|_| {
+        format!(
+            "`vizier run {flow} {slug}` did not print a run summary (exit {})",
+            output
+                .status
+                .code()
+                .map(|code| code.to_string())
+                .unwrap_or_else(|| "signal".to_string())
+        )
+    })?;
+    fs::write(
+        artifacts_dir.join("run.json"),
+        serde_json::to_string_pretty(&run)?,
+    )?;
+
+    let run_id = run["run_id"].as_str().unwrap_or("-").to_string();
+    let terminal_state = run["terminal_state"]
+        .as_str()
+        .unwrap_or("failed")
+        .to_string();
+    let run_exit = run["exit_code"]
+        .as_i64()
+        .map(|code| code as i32)
+        .unwrap_or(1);
+    let job_ids = ["succeeded", "failed", "blocked"]
+        .iter()
+        .flat_map(|key| run[*key].as_array().cloned().unwrap_or_default())
+        .filter_map(|id| id.as_str().map(str::to_string))
+        .collect::<Vec<_>>();
+    let jobs_root = jobs::ensure_jobs_root(project_root)?;
+    let critique = review_critique(&jobs_root, &job_ids)?;
+
+    let Some((review_job, critique)) = critique else {
+        if run_exit == 0 {
+            return Err(
+                format!("review run {run_id} finished without a critique on stdout").into(),
+            );
+        }
+        let exit = if terminal_state == "blocked" {
+            10
+        } else {
+            run_exit
+        };
+        let message = format!("review run {run_id} {terminal_state} before a critique was written");
+        match format {
+            CiOutputFormat::Json => print_json(&json!({
+                "outcome": "ci_review_failed",
+                "plan": slug,
+                "run_id": run_id,
+                "terminal_state": terminal_state,
+                "exit_code": exit,
+                "artifacts_dir": artifacts_dir.display().to_string(),
+            }))?,
+            CiOutputFormat::Github => println!(
+                "{}",
+                github_annotation(
+                    "error",
+                    &[("title", "vizier ci review".to_string())],
+                    &message
+                )
+            ),
+            CiOutputFormat::Text => display::warn(message),
+        }
+        return Ok(exit);
+    };
+
+    let report = ReviewReport::parse(&critique);
+    let failing = report
+        .action_items
+        .iter()
+        .filter(|item| fails(item, fail_on))
+        .count();
+    let verdict = report.verdict();
+    fs::write(artifacts_dir.join("critique.md"), &critique)?;
+    fs::write(
+        artifacts_dir.join("review.json"),
+        serde_json::to_string_pretty(&json!({
+            "plan": slug,
+            "run_id": run_id,
+            "job": review_job,
+            "verdict": verdict,
+            "fail_on": fail_on.map(Severity::as_str),
+            "failing_items": failing,
+            "sections": report.sections,
+            "action_items": report.action_items,
+        }))?,
+    )?;
+
+    let exit = if run_exit != 0 && terminal_state != "blocked" {
+        run_exit
+    } else if failing > 0 || terminal_state == "blocked" {
+        10
+    } else {
+        0
+    };
+    let threshold = match fail_on {
+        Some(Severity::Unspecified) => "any severity".to_string(),
+        Some(severity) => format!("{} or worse", severity.as_str()),
+        None => "never".to_string(),
+    };
+    Auditor::add_message(Message::system(format!(
+        "vizier ci review {slug}: {verdict}; {failing} action item(s) fail at {threshold}; exit {exit}"
+    )));
+
+    let outcome = if exit == 0 {
+        "ci_review_passed"
+    } else {
+        "ci_review_failed"
+    };
+    match format {
+        CiOutputFormat::Json => print_json(&json!({
+            "outcome": outcome,
+            "plan": slug,
+            "run_id": run_id,
+            "job": review_job,
+            "terminal_state": terminal_state,
+            "exit_code": exit,
+            "verdict": verdict,
+            "fail_on": fail_on.map(Severity::as_str),
+            "failing_items": failing,
+            "action_items": report.action_items,
+            "artifacts_dir": artifacts_dir.display().to_string(),
+        }))?,
+        CiOutputFormat::Github => {
+            for item in &report.action_items {
+                let level = if fails(item, fail_on) {
+                    "error"
+                } else {
+                    "warning"
+                };
+                println!("{}", item_annotation(level, item));
+            }
+            append_step_summary(&format!(
+                "## vizier review: {slug}\n\n**Verdict:** {verdict} (fails on {threshold})\n\n{}\n",
+                report.render_markdown()
+            ))?;
+        }
+        CiOutputFormat::Text => {
+            let rows = vec![
+                (
+                    "Outcome".to_string(),
+                    if exit == 0 {
+                        "CI review passed".to_string()
+                    } else {
+                        "CI review failed".to_string()
+                    },
+                ),
+                ("Plan".to_string(), slug.to_string()),
+                ("Run".to_string(), run_id.clone()),
+                ("Review job".to_string(), review_job.clone()),
+                ("Verdict".to_string(), verdict.clone()),
+                (
+                    "Failing".to_string(),
+                    format!("{failing} action item(s) at {threshold}"),
+                ),
+                ("Artifacts".to_string(), artifacts_dir.display().to_string()),
+            ];
+            println!("{}", format_block(rows));
+        }
+    }
+    Ok(exit)
+}
+
+fn ci_merge_queue(
+    project_root: &Path,
+    artifacts_dir: &Path,
+    format: CiOutputFormat,
+    branch: Option<String>,
+    target: Option<String>,
+) -> Result<i32, Box<dyn std::error::Error>> {
+    let branch = match branch {
+        Some(branch) => branch,
+        None => vcs::current_branch_name_in(project_root)?
+            .ok_or("HEAD is detached; pass --branch with the queued branch")?,
+    };
+    let target = match target {
+        Some(target) => target,
+        None => vcs::detect_primary_branch_in(project_root)
+            .ok_or("unable to detect the target branch; use --target")?,
+    };
+    if !vcs::branch_exists_in(project_root, &branch)? {
+        return Err(format!("branch `{branch}` does not exist").into());
+    }
+
+    let worktree_id = format!(
+        "ci-{}",
+        branch
+            .chars()
+            .map(|ch| if ch.is_ascii_alphanumeric() { ch } else { '-' })
+            .collect::<String>()
+    );
+    let gate_phase = display::start_phase(display::PHASE_GATE, None);
+    let verification = jobs::verify_branch(project_root, &branch, &target, &worktree_id, &|line| {
+        eprintln!("{line}")
+    });
+    gate_phase.finish();
+    let verification = verification?;
+    let passed = verification.passed();
+
+    let gates_dir = artifacts_dir.join("gates");
+    fs::create_dir_all(&gates_dir)?;
+    for gate in &verification.gates {
+        fs::write(
+            gates_dir.join(format!("{}.log", artifact_name(&gate.name))),
+            format!("$ {}\n{}{}", gate.script, gate.stdout, gate.stderr),
+        )?;
+    }
+    let gates = verification
+        .gates
+        .iter()
+        .map(|gate| {
+            json!({
+                "name": gate.name,
+                "script": gate.script,
+                "status": gate.status,
+                "passed": gate.status == 0,
+            })
+        })
+        .collect::<Vec<_>>();
+    let outcome = if passed {
+        "ci_merge_queue_passed"
+    } else {
+        "ci_merge_queue_failed"
+    };
+    let payload = json!({
+        "outcome": outcome,
+        "branch": branch,
+        "target": target,
+        "gates": gates,
+        "checks": verification.checks.to_json(),
+        "artifacts_dir": artifacts_dir.display().to_string(),
+    });
+    fs::write(
+        artifacts_dir.join("verify.json"),
+        serde_json::to_string_pretty(&payload)?,
+    )?;
+
+    let failed_gates = verification
+        .gates
+        .iter()
+        .filter(|gate| gate.status != 0)
+        .count();
+    Auditor::add_message(Message::system(format!(
+        "vizier ci merge-queue {branch} -> {target}: {} of {} gate(s) failed, {} of {} review check(s) failed",
+        failed_gates,
+        verification.gates.len(),
+        verification.checks.failed(),
+        verification.checks.results.len()
+    )));
+
+    match format {
+        CiOutputFormat::Json => print_json(&payload)?,
+        CiOutputFormat::Github => {
+            let mut summary = format!(
+                "## vizier merge queue: {branch} → {target}\n\n| Check | Result |\n| --- | --- |\n"
+            );
+            for gate in &verification.gates {
+                if gate.status == 0 {
+                    summary.push_str(&format!("| gate {} | passed |\n", gate.name));
+                    continue;
+                }
+                summary.push_str(&format!(
+                    "| gate {} | failed (exit {}) |\n",
+                    gate.name, gate.status
+                ));
+                let output = format!("{}{}", gate.stdout, gate.stderr);
+                println!(
+                    "{}",
+                    github_annotation(
+                        "error",
+                        &[("title", format!("vizier gate {}", gate.name))],
+                        &format!(
+                            "`{}` exited {}\n{}",
+                            gate.script,
+                            gate.status,
+                            tail_lines(&output, ANNOTATION_TAIL_LINES)
+                        ),
+                    )
+                );
+            }
+            for check in &verification.checks.results {
+                let status = match (check.success, check.status_code) {
+                    (true, _) => "passed".to_string(),
+                    (false, Some(code)) => format!("failed (exit {code})"),
+                    (false, None) => "failed (signal)".to_string(),
+                };
+                summary.push_str(&format!("| check `{}` | {status} |\n", check.command));
+                if !check.success {
+                    let output = format!("{}{}", check.stdout, check.stderr);
+                    println!(
+                        "{}",
+                        github_annotation(
+                            "error",
+                            &[("title", "vizier review check".to_string())],
+                            &format!(
+                                "`{}` {status}\n{}",
+                                check.command,
+                                tail_lines(&output, ANNOTATION_TAIL_LINES)
+                            ),
+                        )
+                    );
+                }
+            }
+            append_step_summary(&summary)?;
+        }
+        CiOutputFormat::Text => {
+            let mut rows = vec![
+                (
+                    "Outcome".to_string(),
+                    if passed {
+                        "Merge queue checks passed".to_string()
+                    } else {
+                        "Merge queue checks failed".to_string()
+                    },
+                ),
+                ("Branch".to_string(), branch.clone()),
+                ("Target".to_string(), target.clone()),
+            ];
+            for gate in &verification.gates {
+                let status = if gate.status == 0 {
+                    "passed".to_string()
+                } else {
+                    format!("failed (exit {})", gate.status)
+                };
+                rows.push((format!("Gate {}", gate.name), status));
+            }
+            rows.push(("Artifacts".to_string(), artifacts_dir.display().to_string()));
+            println!("{}", format_block(rows));
+            if !verification.checks.results.is_empty() {
+                println!();
+                for line in verification.checks.summary_lines() {
+                    println!("{line}");
+                }
+            }
+        }
+    }
+    Ok(if passed { 0 } else { 10 })
+}
+
+// The newest critique a review agent node among `job_ids` wrote to stdout, with its job id.
+fn review_critique(
+    jobs_root: &Path,
+    job_ids: &[String],
+) -> Result<Option<(String, String)>, Box<dyn std::error::Error>> {
+    let mut newest: Option<(jobs::JobRecord, String)> = None;
+    for job_id in job_ids {
+        let record = jobs::read_record(jobs_root, job_id)?;
+        let Some(metadata) = record.metadata.as_ref() else {
+            continue;
+        };
+        let is_review = metadata.workflow_executor_operation.as_deref() == Some("agent.invoke")
+            && (metadata.command_alias.as_deref() == Some("review")
+                || metadata.scope.as_deref() == Some("review")
+                || metadata
+                    .workflow_node_id
+                    .as_deref()
+                    .is_some_and(|node| node.contains("review")));
+        if !is_review {
+            continue;
+        }
+        let critique = fs::read_to_string(&record.stdout_path).unwrap_or_default();
+        if critique.trim().is_empty() {
+            continue;
+        }
+        if newest
+            .as_ref()
+            .is_none_or(|(current, _)| record.finished_at >= current.finished_at)
+        {
+            newest = Some((record, critique));
+        }
+    }
+    Ok(newest.map(|(record, critique)| (record.id, critique)))
+}
+
+fn severity_rank(severity: Severity) -> u8 {
+    match severity {
+        Severity::Blocker => 0,
+        Severity::Major => 1,
+        Severity::Minor => 2,
+        Severity::Nit => 3,
+        Severity::Unspecified => 4,
+    }
+}
+
+fn fails(item: &ActionItem, fail_on: Option<Severity>) -> bool {
+    fail_on.is_some_and(|threshold| severity_rank(item.severity) <= severity_rank(threshold))
+}
+
+fn item_annotation(level: &str, item: &ActionItem) -> String {
+    let mut properties = Vec::new();
+    if let Some(anchor) = item.anchors.first() {
+        properties.push(("file", anchor.path.clone()));
+        if let Some(line) = anchor.line {
+            properties.push(("line", line.to_string()));
+        }
+        if let Some(end) = anchor.end_line {
+            properties.push(("endLine", end.to_string()));
+        }
+    }
+    properties.push((
+        "title",
+        format!("vizier review ({})", item.severity.as_str()),
+    ));
+    github_annotation(level, &properties, &item.text)
+}
+
+/// A GitHub Actions workflow command such as `::error file=src/lib.rs,line=3::message`, with the
+/// property and message escaping the runner expects.
+fn github_annotation(level: &str, properties: &[(&str, String)], message: &str) -> String {
+    let properties = properties
+        .iter()
+        .map(|(key, value)| {
+            format!(
+                "{key}={}",
+                escape_annotation(value)
+                    .replace(':', "%3A")
+                    .replace(',', "%2C")
+            )
+        })
+        .collect::<Vec<_>>();
+    if properties.is_empty() {
+        format!("::{level}::{}", escape_annotation(message))
+    } else {
+        format!(
+            "::{level} {}::{}",
+            properties.join(","),
+            escape_annotation(message)
+        )
+    }
+}
+
+fn escape_annotation(value: &str) -> String {
+    value
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn tail_lines(text: &str, count: usize) -> String {
+    let lines = text.trim_end().lines().collect::<Vec<_>>();
+    lines[lines.len().saturating_sub(count)..].join("\n")
+}
+
+fn artifact_name(name: &str) -> String {
+    name.chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || ch == '-' || ch == '_' {
+                ch
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+// Appends to the job summary page when running under GitHub Actions.
+fn append_step_summary(markdown: &str) -> std::io::Result<()> {
+    let Some(path) = std::env::var_os("GITHUB_STEP_SUMMARY").filter(|path| !path.is_empty()) else {
+        return Ok(());
+    };
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(PathBuf::from(path))?
+        .write_all(markdown.as_bytes())
+}
+
+fn print_json(payload: &Value) -> Result<(), Box<dyn std::error::Error>> {
+    println!("{}", serde_json::to_string_pretty(payload)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vizier_core::review_report::FileAnchor;
+
+    #[test]
+    fn github_annotations_escape_messages_and_properties() {
+        let item = ActionItem {
+            severity: Severity::Major,
+            text: "handle 100% of\nthe cases".to_string(),
+            anchors: vec![FileAnchor {
+                path: "src/a,b.rs".to_string(),
+                line: Some(3),
+                end_line: Some(9),
+            }],
+        };
+        assert_eq!(
+            item_annotation("error", &item),
+            "::error file=src/a%2Cb.rs,line=3,endLine=9,title=vizier review (major)::handle 100%25 of%0Athe cases"
+        );
+        assert_eq!(
+            github_annotation("warning", &[], "plain"),
+            "::warning::plain"
+        );
+
+        assert!(fails(&item, Some(Severity::Major)));
+        assert!(!fails(&item, Some(Severity::Blocker)));
+        assert!(fails(&item, Some(Severity::Unspecified)));
+        assert!(!fails(&item, None));
+    }
+}
@@ -0,0 +1,128 @@
+use std::fs;
+use std::path::Path;
+
+use serde_json::json;
+use vizier_core::{ci_import, config};
+
+use super::shared::format_table;
+use super::types::{ConfigImportCiOptions, ConfigImportOutputFormat};
+
+/// Proposes `[review.checks]` commands from the repo's GitHub Actions and GitLab CI config,
+/// listing the CI steps it could not map, and with `--write` appends them to the repo config.
+pub(crate) fn run_config_import_ci(
+    project_root: &Path,
+    opts: ConfigImportCiOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let import = ci_import::import_ci(project_root)?;
+
+    let mut written = None;
+    if opts.write && !import.checks.is_empty() {
+        written = Some(append_to_repo_config(project_root, &import.to_toml())?);
+    }
+
+    match opts.format {
+        ConfigImportOutputFormat::Toml => print!("{}", import.to_toml()),
+        ConfigImportOutputFormat::Json => {
+            let payload = json!({
+                "outcome": if import.checks.is_empty() { "config_import_ci_empty" } else { "config_import_ci" },
+                "files": import.files,
+                "checks": import.checks,
+                "skipped": import.skipped,
+                "written": written,
+            });
+            println!("{}", serde_json::to_string_pretty(&payload)?);
+        }
+        ConfigImportOutputFormat::Text => {
+            if import.files.is_empty() {
+                println!(
+                    "Outcome: vizier config import-ci: no CI config found ({}/*.yml, {})",
+                    ci_import::GITHUB_WORKFLOWS_DIR,
+                    ci_import::GITLAB_CI_FILE
+                );
+                return Ok(());
+            }
+            println!(
+                "Outcome: vizier config import-ci: {} check(s) proposed from {} CI file(s); {} step(s) skipped",
+                import.checks.len(),
+                import.files.len(),
+                import.skipped.len()
+            );
+            if !import.checks.is_empty() {
+                println!();
+                print!("{}", import.to_toml());
+            }
+            if !import.skipped.is_empty() {
+                println!();
+                println!("Skipped:");
+                let rows = import
+                    .skipped
+                    .iter()
+                    .map(|skipped| vec![skipped.source.clone(), skipped.reason.clone()])
+                    .collect::<Vec<_>>();
+                println!("{}", format_table(&rows, 2));
+            }
+            match &written {
+                Some(path) => println!("\nAppended [review.checks] to {path}"),
+                None if !import.checks.is_empty() => {
+                    println!("\nRe-run with --write to append this block to .vizier/config.toml.")
+                }
+                None => {}
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Appends `block` to the repo config, refusing when that config already has review checks
+/// (or is JSON) so an existing list is never silently doubled up or shadowed.
+fn append_to_repo_config(
+    project_root: &Path,
+    block: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let path = config::project_config_path(project_root)
+        .unwrap_or_else(|| project_root.join(".vizier").join("config.toml"));
+    let display = path
+        .strip_prefix(project_root)
+        .unwrap_or(&path)
+        .display()
+        .to_string();
+    if path.extension().and_then(|ext| ext.to_str()) == Some("json") {
+        return Err(format!(
+            "{display} is JSON; add the proposed checks to it by hand (see --format json)"
+        )
+        .into());
+    }
+
+    let existing = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(format!("failed to read {display}: {err}").into()),
+    };
+    let parsed: toml::Value =
+        toml::from_str(&existing).map_err(|err| format!("failed to parse {display}: {err}"))?;
+    if parsed
+        .get("review")
+        .and_then(|review| review.get("checks"))
+        .is_some()
+    {
+        return Err(format!(
+            "{display} already configures [review.checks]; merge the proposal by hand (see --format toml)"
+        )
+        .into());
+    }
+
+    let mut contents = existing;
+    if !contents.is_empty() {
+        if !contents.ends_with('\n') {
+            contents.push('\n');
+        }
+        contents.push('\n');
+    }
+    contents.push_str("# Imported from CI by `vizier config import-ci`\n");
+    contents.push_str(block);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, contents).map_err(|err| format!("failed to write {display}: {err}"))?;
+    Ok(display)
+}
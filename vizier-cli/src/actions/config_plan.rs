@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde_json::json;
+use vizier_core::config::{self, ConfigSetting, ConfigSourceKind};
+
+use super::shared::format_table;
+use super::types::{PlanOptions, PlanOutputFormat};
+
+/// Reports the keys each config layer sets, in the order they are applied, or with `--diff`
+/// only the keys that override a built-in default together with the layer that won.
+pub(crate) fn run_config_plan(
+    project_root: &Path,
+    opts: PlanOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let sources = config::config_sources(project_root, opts.config_file.as_deref());
+    let profile = opts.profile.as_ref().map(|(name, _)| name.as_str());
+    let mut settings = config::config_settings(&sources, profile)?;
+    if let Some((name, source)) = &opts.profile {
+        settings.push(flag_setting("profile", json!(name), *source));
+    }
+    if opts.no_session {
+        settings.push(flag_setting(
+            "no_session",
+            json!(true),
+            ConfigSourceKind::Cli,
+        ));
+    }
+
+    // The last setting applied for a key wins; earlier ones are shadowed by it.
+    let winners = settings
+        .iter()
+        .enumerate()
+        .map(|(index, setting)| (setting.key.as_str(), index))
+        .collect::<HashMap<_, _>>();
+    let rows = if opts.diff {
+        config::effective_settings(&settings)
+            .into_iter()
+            .map(|setting| (setting, None))
+            .collect::<Vec<_>>()
+    } else {
+        settings
+            .iter()
+            .enumerate()
+            .map(|(index, setting)| {
+                let winner = winners[setting.key.as_str()];
+                (
+                    setting.clone(),
+                    (winner != index).then(|| settings[winner].source),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    let effective = winners.len();
+
+    match opts.format {
+        PlanOutputFormat::Json => {
+            let payload = json!({
+                "outcome": if opts.diff { "config_diff" } else { "config_plan" },
+                "profile": profile,
+                "layers": sources,
+                "overrides": effective,
+                "settings": rows
+                    .iter()
+                    .map(|(setting, shadowed_by)| {
+                        json!({
+                            "key": setting.key,
+                            "value": setting.value,
+                            "source": setting.source,
+                            "path": setting.path,
+                            "profile": setting.profile,
+                            "overridden_by": shadowed_by,
+                        })
+                    })
+                    .collect::<Vec<_>>(),
+            });
+            println!("{}", serde_json::to_string_pretty(&payload)?);
+        }
+        PlanOutputFormat::Text => {
+            if opts.diff {
+                println!("Outcome: vizier plan: {effective} key(s) override built-in defaults");
+            } else {
+                println!(
+                    "Outcome: vizier plan: {} setting(s) across {} layer(s); {effective} in effect",
+                    settings.len(),
+                    sources.len()
+                );
+                if !sources.is_empty() {
+                    println!("Layers (lowest precedence first):");
+                    let layer_rows = sources
+                        .iter()
+                        .map(|source| {
+                            vec![
+                                source.source.as_str().to_string(),
+                                display_path(project_root, &source.path),
+                            ]
+                        })
+                        .collect::<Vec<_>>();
+                    println!("{}", format_table(&layer_rows, 2));
+                }
+            }
+            if rows.is_empty() {
+                println!("No config keys are set; every value is a built-in default.");
+                return Ok(());
+            }
+            let table = rows
+                .iter()
+                .map(|(setting, shadowed_by)| {
+                    let mut row = vec![
+                        setting.key.clone(),
+                        setting.value.to_string(),
+                        source_label(project_root, setting),
+                    ];
+                    if !opts.diff {
+                        row.push(match shadowed_by {
+                            Some(source) => format!("overridden by {}", source.as_str()),
+                            None => "in effect".to_string(),
+                        });
+                    }
+                    row
+                })
+                .collect::<Vec<_>>();
+            if !opts.diff {
+                println!("Settings:");
+            }
+            println!("{}", format_table(&table, if opts.diff { 0 } else { 2 }));
+        }
+    }
+    Ok(())
+}
+
+fn flag_setting(key: &str, value: serde_json::Value, source: ConfigSourceKind) -> ConfigSetting {
+    ConfigSetting {
+        key: key.to_string(),
+        value,
+        source,
+        path: None,
+        profile: None,
+    }
+}
+
+fn source_label(project_root: &Path, setting: &ConfigSetting) -> String {
+    let mut label = setting.source.as_str().to_string();
+    match (&setting.path, setting.key.as_str()) {
+        (Some(path), _) => label.push_str(&format!(" ({})", display_path(project_root, path))),
+        (None, "profile") if setting.source == ConfigSourceKind::Env => {
+            label.push_str(" (VIZIER_PROFILE)")
+        }
+        (None, "profile") => label.push_str(" (--profile)"),
+        (None, _) => label.push_str(&format!(" (--{})", setting.key.replace('_', "-"))),
+    }
+    if let Some(profile) = &setting.profile {
+        label.push_str(&format!(", profile {profile}"));
+    }
+    label
+}
+
+fn display_path(project_root: &Path, path: &Path) -> String {
+    path.strip_prefix(project_root)
+        .unwrap_or(path)
+        .display()
+        .to_string()
+}
@@ -0,0 +1,543 @@
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[cfg(unix)]
+use std::os::unix::fs::PermissionsExt;
+
+use serde_json::json;
+use vizier_core::{config, tools};
+
+use super::init::AGENT_SHIMS_DIR;
+use super::shared::{find_program, format_table};
+use super::types::{DoctorOptions, DoctorOutputFormat};
+
+/// Oldest git release Vizier's worktree and merge flows are exercised against.
+const MIN_GIT_VERSION: (u32, u32) = (2, 20);
+const REMOTE_PROBE_TIMEOUT: Duration = Duration::from_secs(20);
+const WRITABLE_DIRS: &[&str] = &[".vizier", ".vizier/jobs", ".vizier/sessions", ".vizier/tmp"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl CheckStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Pass => "pass",
+            Self::Warn => "warn",
+            Self::Fail => "fail",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct DoctorCheck {
+    name: String,
+    status: CheckStatus,
+    detail: String,
+    hint: Option<String>,
+}
+
+impl DoctorCheck {
+    fn pass(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status: CheckStatus::Pass,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn problem(
+        status: CheckStatus,
+        name: impl Into<String>,
+        detail: impl Into<String>,
+        hint: impl Into<String>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            status,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+}
+
+pub(crate) fn run_doctor(
+    project_root: &Path,
+    opts: DoctorOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut checks = vec![check_git(), check_libgit2()];
+    checks.extend(check_config_layers(
+        project_root,
+        opts.config_file.as_deref(),
+    ));
+    checks.push(check_agent(&config::get_config()));
+    checks.push(check_agent_shims(project_root));
+    checks.push(check_output_buffering());
+    checks.push(check_writable_dirs(project_root));
+    if opts.remote {
+        checks.extend(check_remotes(project_root));
+    }
+
+    let failures = count(&checks, CheckStatus::Fail);
+    let warnings = count(&checks, CheckStatus::Warn);
+    let outcome = if failures > 0 {
+        "doctor_failed"
+    } else if warnings > 0 {
+        "doctor_warned"
+    } else {
+        "doctor_passed"
+    };
+    match opts.format {
+        DoctorOutputFormat::Json => {
+            let payload = json!({
+                "outcome": outcome,
+                "failures": failures,
+                "warnings": warnings,
+                "checks": checks
+                    .iter()
+                    .map(|check| {
+                        json!({
+                            "name": check.name,
+                            "status": check.status.as_str(),
+                            "detail": check.detail,
+                            "hint": check.hint,
+                        })
+                    })
+                    .collect::<Vec<_>>(),
+            });
+            println!("{}", serde_json::to_string_pretty(&payload)?);
+        }
+        DoctorOutputFormat::Text => {
+            println!("Outcome: vizier doctor: {failures} failure(s), {warnings} warning(s)");
+            let rows = checks
+                .iter()
+                .map(|check| {
+                    vec![
+                        check.status.as_str().to_string(),
+                        check.name.clone(),
+                        check.detail.clone(),
+                    ]
+                })
+                .collect::<Vec<_>>();
+            println!("{}", format_table(&rows, 0));
+            let hints = checks
+                .iter()
+                .filter_map(|check| check.hint.as_ref().map(|hint| (check, hint)))
+                .collect::<Vec<_>>();
+            if !hints.is_empty() {
+                println!();
+                println!("Hints:");
+                for (check, hint) in hints {
+                    println!("  {}: {hint}", check.name);
+                }
+            }
+        }
+    }
+
+    if failures > 0 {
+        crate::cli::outcome::exit(10);
+    }
+    Ok(())
+}
+
+fn count(checks: &[DoctorCheck], status: CheckStatus) -> usize {
+    checks.iter().filter(|check| check.status == status).count()
+}
+
+fn check_git() -> DoctorCheck {
+    let output = match Command::new("git").arg("--version").output() {
+        Ok(output) if output.status.success() => output,
+        Ok(output) => {
+            return DoctorCheck::problem(
+                CheckStatus::Fail,
+                "git",
+                format!("`git --version` exited with {}", output.status),
+                "install git and make sure it is on PATH",
+            );
+        }
+        Err(err) => {
+            return DoctorCheck::problem(
+                CheckStatus::Fail,
+                "git",
+                format!("git not found: {err}"),
+                "install git and make sure it is on PATH",
+            );
+        }
+    };
+    let text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    match parse_git_version(&text) {
+        Some(version) if version < MIN_GIT_VERSION => DoctorCheck::problem(
+            CheckStatus::Warn,
+            "git",
+            text,
+            format!(
+                "upgrade git to {}.{} or newer; worktree-based stages are untested on older releases",
+                MIN_GIT_VERSION.0, MIN_GIT_VERSION.1
+            ),
+        ),
+        _ => DoctorCheck::pass("git", text),
+    }
+}
+
+fn parse_git_version(text: &str) -> Option<(u32, u32)> {
+    let version = text.split_whitespace().nth(2)?;
+    let mut parts = version.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts
+        .next()?
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect::<String>()
+        .parse()
+        .ok()?;
+    Some((major, minor))
+}
+
+fn check_libgit2() -> DoctorCheck {
+    let version = git2::Version::get();
+    let (major, minor, patch) = version.libgit2_version();
+    let mut missing = Vec::new();
+    if !version.https() {
+        missing.push("https");
+    }
+    if !version.ssh() {
+        missing.push("ssh");
+    }
+    if !version.threads() {
+        missing.push("threads");
+    }
+    let detail = format!(
+        "libgit2 {major}.{minor}.{patch}{}",
+        if version.vendored() {
+            " (vendored)"
+        } else {
+            ""
+        }
+    );
+    if missing.is_empty() {
+        DoctorCheck::pass("libgit2", format!("{detail}, https, ssh, threads"))
+    } else {
+        DoctorCheck::problem(
+            CheckStatus::Warn,
+            "libgit2",
+            format!("{detail} without {}", missing.join(", ")),
+            "push and fetch fall back to the git CLI for the missing transports; rebuild vizier against a full libgit2 to avoid it",
+        )
+    }
+}
+
+// Parses every layer separately so one broken file is named on its own instead of failing the
+// merged load that startup performs.
+fn check_config_layers(project_root: &Path, explicit: Option<&Path>) -> Vec<DoctorCheck> {
+    let layers = [
+        ("config (--config-file)", explicit.map(Path::to_path_buf)),
+        ("config (repo)", config::project_config_path(project_root)),
+        ("config (global)", config::global_config_path()),
+        ("config (VIZIER_CONFIG_FILE)", config::env_config_path()),
+    ];
+    let mut checks = Vec::new();
+    for (name, path) in layers {
+        let Some(path) = path.filter(|path| path.exists()) else {
+            continue;
+        };
+        checks.push(match config::load_config_layer_from_path(&path) {
+            Ok(_) => DoctorCheck::pass(name, path.display().to_string()),
+            Err(err) => DoctorCheck::problem(
+                CheckStatus::Fail,
+                name,
+                format!("{}: {err}", path.display()),
+                format!(
+                    "fix or move aside {}; see docs/user/config-reference.md",
+                    path.display()
+                ),
+            ),
+        });
+    }
+    if checks.is_empty() {
+        checks.push(DoctorCheck::pass(
+            "config",
+            "no config files found; using built-in defaults",
+        ));
+    }
+    checks
+}
+
+fn check_agent(cfg: &config::Config) -> DoctorCheck {
+    let settings = match config::resolve_default_agent_settings(cfg, None) {
+        Ok(settings) => settings,
+        Err(err) => {
+            return DoctorCheck::problem(
+                CheckStatus::Fail,
+                "agent",
+                err.to_string(),
+                "set agent.command (or select a bundled agent) in .vizier/config.toml",
+            );
+        }
+    };
+    let Some(program) = settings.agent_runtime.command.first().cloned() else {
+        return DoctorCheck::problem(
+            CheckStatus::Fail,
+            "agent",
+            format!("{} has an empty command", settings.selector),
+            "set agent.command in .vizier/config.toml",
+        );
+    };
+    match find_program(&program) {
+        Some(path) if is_executable(&path) => DoctorCheck::pass(
+            "agent",
+            format!(
+                "{} ({}) -> {}",
+                settings.selector,
+                settings.backend,
+                path.display()
+            ),
+        ),
+        Some(path) => DoctorCheck::problem(
+            CheckStatus::Fail,
+            "agent",
+            format!("{} is not executable", path.display()),
+            format!("chmod +x {}", path.display()),
+        ),
+        None => DoctorCheck::problem(
+            CheckStatus::Fail,
+            "agent",
+            format!("`{program}` not found"),
+            "run `vizier init --install-shims` or point agent.command at an installed agent",
+        ),
+    }
+}
+
+fn check_agent_shims(project_root: &Path) -> DoctorCheck {
+    let shims_root = project_root.join(tools::VIZIER_DIR).join(AGENT_SHIMS_DIR);
+    let Ok(entries) = std::fs::read_dir(&shims_root) else {
+        return DoctorCheck::pass("agent shims", "no repo-local shims; bundled shims apply");
+    };
+    let mut shims = entries
+        .filter_map(Result::ok)
+        .flat_map(|entry| {
+            ["agent.sh", "filter.sh"]
+                .into_iter()
+                .map(move |name| entry.path().join(name))
+        })
+        .filter(|path| path.is_file())
+        .collect::<Vec<_>>();
+    shims.sort();
+    let broken = shims
+        .iter()
+        .filter(|path| !is_executable(path))
+        .map(|path| {
+            path.strip_prefix(project_root)
+                .unwrap_or(path)
+                .display()
+                .to_string()
+        })
+        .collect::<Vec<_>>();
+    if broken.is_empty() {
+        DoctorCheck::pass(
+            "agent shims",
+            format!("{} repo-local shim file(s) executable", shims.len()),
+        )
+    } else {
+        DoctorCheck::problem(
+            CheckStatus::Fail,
+            "agent shims",
+            format!("not executable: {}", broken.join(", ")),
+            format!("chmod +x {}", broken.join(" ")),
+        )
+    }
+}
+
+fn check_output_buffering() -> DoctorCheck {
+    if !cfg!(unix) {
+        return DoctorCheck::pass("output buffering", "line buffering wrappers are unix-only");
+    }
+    let disabled = std::env::var("VIZIER_DISABLE_STDBUF")
+        .map(|value| {
+            matches!(
+                value.trim().to_ascii_lowercase().as_str(),
+                "1" | "true" | "yes"
+            )
+        })
+        .unwrap_or(false);
+    if disabled {
+        return DoctorCheck::pass("output buffering", "disabled by VIZIER_DISABLE_STDBUF");
+    }
+    match ["stdbuf", "unbuffer"]
+        .into_iter()
+        .find_map(|program| find_program(program).map(|path| (program, path)))
+    {
+        Some((program, path)) => DoctorCheck::pass(
+            "output buffering",
+            format!("{program} at {}", path.display()),
+        ),
+        None => DoctorCheck::problem(
+            CheckStatus::Warn,
+            "output buffering",
+            "neither stdbuf nor unbuffer is on PATH",
+            "install coreutils (stdbuf) or expect (unbuffer) so agent output streams line by line",
+        ),
+    }
+}
+
+fn check_writable_dirs(project_root: &Path) -> DoctorCheck {
+    if !project_root.join(".vizier").is_dir() {
+        return DoctorCheck::problem(
+            CheckStatus::Fail,
+            "writable .vizier",
+            ".vizier/ is missing",
+            "run `vizier init`",
+        );
+    }
+    let mut problems = Vec::new();
+    for dir in WRITABLE_DIRS {
+        // Directories Vizier creates on demand only need a writable parent.
+        let mut probe = project_root.join(dir);
+        while !probe.is_dir() {
+            match probe.parent() {
+                Some(parent) => probe = parent.to_path_buf(),
+                None => break,
+            }
+        }
+        if let Err(err) = tempfile::Builder::new()
+            .prefix(".vizier-doctor-")
+            .tempfile_in(&probe)
+        {
+            problems.push(format!("{dir} ({err})"));
+        }
+    }
+    if problems.is_empty() {
+        DoctorCheck::pass("writable .vizier", WRITABLE_DIRS.join(", "))
+    } else {
+        DoctorCheck::problem(
+            CheckStatus::Fail,
+            "writable .vizier",
+            format!("cannot write {}", problems.join(", ")),
+            "fix ownership or permissions of .vizier/ (for example `chown -R $USER .vizier`)",
+        )
+    }
+}
+
+fn check_remotes(project_root: &Path) -> Vec<DoctorCheck> {
+    let remotes = git2::Repository::open(project_root)
+        .and_then(|repo| repo.remotes())
+        .map(|names| {
+            names
+                .iter()
+                .flatten()
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    if remotes.is_empty() {
+        return vec![DoctorCheck::pass("remote", "no remotes configured")];
+    }
+    remotes
+        .into_iter()
+        .map(|remote| {
+            let name = format!("remote {remote}");
+            match probe_remote(project_root, &remote) {
+                Ok(()) => DoctorCheck::pass(name, "authenticated (git ls-remote)"),
+                Err(err) => DoctorCheck::problem(
+                    CheckStatus::Fail,
+                    name,
+                    err,
+                    format!(
+                        "check credentials with `git ls-remote {remote}` (ssh agent/keys, or a credential helper for https)"
+                    ),
+                ),
+            }
+        })
+        .collect()
+}
+
+// Runs `git ls-remote` without prompting so missing credentials fail instead of hanging.
+fn probe_remote(project_root: &Path, remote: &str) -> Result<(), String> {
+    let mut command = Command::new("git");
+    command
+        .current_dir(project_root)
+        .args(["ls-remote", "--quiet", remote, "HEAD"])
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped());
+    if std::env::var_os("GIT_SSH_COMMAND").is_none() {
+        command.env("GIT_SSH_COMMAND", "ssh -o BatchMode=yes");
+    }
+    let mut child = command.spawn().map_err(|err| err.to_string())?;
+    let started = Instant::now();
+    loop {
+        match child.try_wait().map_err(|err| err.to_string())? {
+            Some(status) if status.success() => return Ok(()),
+            Some(status) => {
+                let output = child.wait_with_output().map_err(|err| err.to_string())?;
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                let reason = stderr
+                    .lines()
+                    .map(str::trim)
+                    .rfind(|line| !line.is_empty())
+                    .map(str::to_string)
+                    .unwrap_or_else(|| format!("git ls-remote exited with {status}"));
+                return Err(reason);
+            }
+            None if started.elapsed() > REMOTE_PROBE_TIMEOUT => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!(
+                    "git ls-remote timed out after {}s",
+                    REMOTE_PROBE_TIMEOUT.as_secs()
+                ));
+            }
+            None => thread::sleep(Duration::from_millis(100)),
+        }
+    }
+}
+
+fn is_executable(path: &PathBuf) -> bool {
+    #[cfg(unix)]
+    {
+        std::fs::metadata(path)
+            .map(|metadata| metadata.permissions().mode() & 0o111 != 0)
+            .unwrap_or(false)
+    }
+    #[cfg(not(unix))]
+    {
+        path.is_file()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_git_version_handles_vendor_suffixes() {
+        assert_eq!(parse_git_version("git version 2.43.0"), Some((2, 43)));
+        assert_eq!(
+            parse_git_version("git version 2.39.3 (Apple Git-146)"),
+            Some((2, 39))
+        );
+        assert_eq!(
+            parse_git_version("git version 2.45.windows.1"),
+            Some((2, 45))
+        );
+        assert_eq!(parse_git_version("not git"), None);
+    }
+
+    #[test]
+    fn config_layer_errors_name_the_broken_file() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let broken = dir.path().join("broken.toml");
+        std::fs::write(&broken, "[agent\n").expect("write config");
+        let checks = check_config_layers(dir.path(), Some(&broken));
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].status, CheckStatus::Fail);
+        assert!(checks[0].detail.contains("broken.toml"), "{:?}", checks[0]);
+    }
+}
@@ -0,0 +1,95 @@
+use std::path::Path;
+
+use serde_json::json;
+use vizier_core::{config, display, forge, vcs};
+
+use super::shared::format_block;
+use super::types::{ForgeOptions, ForgeOutputFormat, ForgeRequest};
+
+const NO_FORGE_CLI: &str =
+    "no forge CLI available; install gh or glab (and log in), or set [forge] cli";
+
+/// Reports the forge CLI remote operations go through, or fetches an issue with it.
+pub(crate) fn run_forge(
+    project_root: &Path,
+    opts: ForgeOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match opts.request {
+        ForgeRequest::Status => {
+            let configured = config::get_config().forge.cli;
+            let remote = vcs::remote_url_in(project_root, "origin");
+            let provider = forge::provider_for(project_root);
+            let resolved = provider.as_ref().map(|provider| provider.name());
+            match opts.format {
+                ForgeOutputFormat::Json => {
+                    let payload = json!({
+                        "outcome": "forge_status",
+                        "configured": configured.as_str(),
+                        "remote": remote,
+                        "cli": resolved,
+                    });
+                    println!("{}", serde_json::to_string_pretty(&payload)?);
+                }
+                ForgeOutputFormat::Text => {
+                    let outcome = match resolved {
+                        Some(cli) => format!("Remote operations go through {cli}"),
+                        None => "No forge CLI; pull requests are left to you".to_string(),
+                    };
+                    println!(
+                        "{}",
+                        format_block(vec![
+                            ("Outcome".to_string(), outcome),
+                            ("Setting".to_string(), configured.as_str().to_string()),
+                            (
+                                "Remote".to_string(),
+                                remote.unwrap_or_else(|| "none".to_string()),
+                            ),
+                            ("CLI".to_string(), resolved.unwrap_or("none").to_string()),
+                        ])
+                    );
+                }
+            }
+        }
+        ForgeRequest::Issue { id, output } => {
+            let provider = forge::provider_for(project_root).ok_or(NO_FORGE_CLI)?;
+            let issue = provider.fetch_issue(project_root, &id)?;
+            let markdown = issue.to_markdown();
+            if let Some(path) = output.as_ref() {
+                if let Some(parent) = path
+                    .parent()
+                    .filter(|parent| !parent.as_os_str().is_empty())
+                {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(path, &markdown)
+                    .map_err(|err| format!("failed to write {}: {err}", path.display()))?;
+            }
+            match opts.format {
+                ForgeOutputFormat::Json => {
+                    let payload = json!({
+                        "outcome": "forge_issue_fetched",
+                        "cli": provider.name(),
+                        "id": issue.id,
+                        "title": issue.title,
+                        "url": issue.url,
+                        "body": issue.body,
+                        "output": output,
+                    });
+                    println!("{}", serde_json::to_string_pretty(&payload)?);
+                }
+                ForgeOutputFormat::Text => match output {
+                    Some(path) => println!(
+                        "{}",
+                        display::outcome_line(&format!(
+                            "Wrote issue {} to {}",
+                            issue.id,
+                            path.display()
+                        ))
+                    ),
+                    None => print!("{markdown}"),
+                },
+            }
+        }
+    }
+    Ok(())
+}
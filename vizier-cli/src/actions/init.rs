@@ -3,7 +3,10 @@ use std::path::Path;
 
 #[cfg(unix)]
 use std::os::unix::fs::PermissionsExt;
-use vizier_core::tools;
+use vizier_core::{config, display, global_cache, tools};
+
+use super::shared::{find_program, format_block};
+use super::types::InitOptions;
 
 const SNAPSHOT_STARTER: &str = "\
 # Running Snapshot
@@ -33,6 +36,7 @@ const PROMPT_APPROVE_STARTER: &str =
 const PROMPT_MERGE_STARTER: &str = include_str!("../../templates/init/prompts/MERGE_PROMPTS.md");
 const PROMPT_COMMIT_STARTER: &str = include_str!("../../templates/init/prompts/COMMIT_PROMPTS.md");
 const CI_SCRIPT_STARTER: &str = include_str!("../../templates/init/ci.sh");
+pub(crate) const AGENT_SHIMS_DIR: &str = "agents";
 const VIZIER_GITIGNORE_HEADING: &str = "# Vizier";
 const CANONICAL_VIZIER_GITIGNORE_ITEM: &str = ".gitignore: canonical # Vizier block";
 
@@ -78,10 +82,13 @@ struct GitignoreEvaluation {
     needs_canonicalization: bool,
 }
 
-pub(crate) fn run_init(repo_root: &Path, check: bool) -> Result<(), Box<dyn std::error::Error>> {
+pub(crate) fn run_init(
+    repo_root: &Path,
+    opts: InitOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
     let before = evaluate_init_state(repo_root)?;
 
-    if check {
+    if opts.check {
         if before.contract_satisfied() {
             println!("Outcome: vizier init check: satisfied");
             return Ok(());
@@ -94,6 +101,8 @@ pub(crate) fn run_init(repo_root: &Path, check: bool) -> Result<(), Box<dyn std:
         return Err("vizier init --check failed".into());
     }
 
+    let config_path = repo_root.join(format!("{}config.toml", tools::VIZIER_DIR));
+    let config_existed = config_path.is_file();
     apply_initialization(repo_root)?;
     let after = evaluate_init_state(repo_root)?;
 
@@ -107,9 +116,88 @@ pub(crate) fn run_init(repo_root: &Path, check: bool) -> Result<(), Box<dyn std:
         println!("Outcome: vizier init newly initialized");
     }
 
+    if opts.install_shims {
+        let installed = install_agent_shims(repo_root)?;
+        println!(
+            "Agent shims: {}",
+            if installed.is_empty() {
+                "already installed".to_string()
+            } else {
+                installed.join(", ")
+            }
+        );
+    }
+
+    if opts.smoke {
+        // A config written just now is not part of the config loaded at startup.
+        let cfg = if config_existed {
+            config::get_config()
+        } else {
+            config::load_config_from_path(&config_path)?
+        };
+        run_smoke_check(&cfg);
+    }
+
     Ok(())
 }
 
+/// Writes the bundled agent shims under `.vizier/agents/<label>/`, leaving existing files alone.
+/// Returns the labels that gained at least one file.
+fn install_agent_shims(repo_root: &Path) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let shims_root = repo_root.join(tools::VIZIER_DIR).join(AGENT_SHIMS_DIR);
+    let mut installed = Vec::new();
+    for (label, agent, filter) in global_cache::BUNDLED_AGENT_SHIMS {
+        let dir = shims_root.join(label);
+        std::fs::create_dir_all(&dir).map_err(|err| io_error("create directory", &dir, err))?;
+        let mut wrote = false;
+        for (name, contents) in [("agent.sh", agent), ("filter.sh", filter)] {
+            let path = dir.join(name);
+            if path.exists() {
+                continue;
+            }
+            std::fs::write(&path, contents).map_err(|err| io_error("write file", &path, err))?;
+            set_executable_if_requested(&path, true)?;
+            wrote = true;
+        }
+        if wrote {
+            installed.push(label.to_string());
+        }
+    }
+    Ok(installed)
+}
+
+// Resolves the default agent the way a workflow node would and checks that its command can be
+// launched, so a fresh repo learns about a missing shim before the first `vizier run`.
+fn run_smoke_check(cfg: &config::Config) {
+    let (status, mut rows) = match config::resolve_default_agent_settings(cfg, None) {
+        Ok(settings) => {
+            let runtime = &settings.agent_runtime;
+            let program = runtime.command.first().cloned().unwrap_or_default();
+            let rows = vec![
+                (
+                    "Agent".to_string(),
+                    format!("{} ({})", settings.selector, settings.backend),
+                ),
+                ("Command".to_string(), runtime.command.join(" ")),
+            ];
+            if program.is_empty() || find_program(&program).is_some() {
+                ("passed".to_string(), rows)
+            } else {
+                (format!("failed: `{program}` is not executable"), rows)
+            }
+        }
+        Err(err) => (format!("failed: {err}"), Vec::new()),
+    };
+    let passed = status == "passed";
+    rows.insert(0, ("Smoke check".to_string(), status));
+    println!("{}", format_block(rows));
+    if !passed {
+        display::warn(
+            "agent smoke check failed; rerun `vizier init --install-shims` or set agent.command in .vizier/config.toml",
+        );
+    }
+}
+
 fn apply_initialization(repo_root: &Path) -> Result<(), Box<dyn std::error::Error>> {
     let vizier_dir = repo_root.join(tools::VIZIER_DIR.trim_end_matches('/'));
     std::fs::create_dir_all(&vizier_dir)
@@ -141,6 +229,11 @@ fn apply_initialization(repo_root: &Path) -> Result<(), Box<dyn std::error::Erro
     }
 
     ensure_gitignore_rules(repo_root)?;
+    let version_path = repo_root.join(vizier_core::migrations::VERSION_FILE);
+    if !version_path.exists() {
+        vizier_core::migrations::stamp_current(repo_root)
+            .map_err(|err| io_error("write file", &version_path, err))?;
+    }
     Ok(())
 }
 
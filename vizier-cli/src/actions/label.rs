@@ -0,0 +1,102 @@
+use std::path::Path;
+
+use serde_json::json;
+use vizier_core::{plan, vcs};
+
+use super::shared::format_block;
+use super::types::{LabelChange, LabelOptions, LabelOutputFormat};
+
+/// Shows a plan's labels, or adds/removes some by committing an updated `labels:` front-matter
+/// line on the plan branch.
+pub(crate) fn run_label(
+    project_root: &Path,
+    opts: LabelOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if !vcs::branch_exists_in(project_root, &opts.branch)? {
+        return Err(format!("branch `{}` does not exist", opts.branch).into());
+    }
+    let plan_rel = plan::plan_rel_path(&opts.slug);
+    let plan_rel_display = plan_rel.to_string_lossy().replace('\\', "/");
+    let document =
+        vcs::read_blob_at_revision_in(project_root, &format!("{}:{plan_rel_display}", opts.branch))
+            .map_err(|err| {
+                format!(
+                    "plan `{}` has no {plan_rel_display} on {}: {err}",
+                    opts.slug, opts.branch
+                )
+            })?;
+    let before = crate::plan::PlanMetadata::from_document(&document)?.labels;
+
+    let mut labels = before.clone();
+    match &opts.change {
+        Some(LabelChange::Add(added)) => {
+            for label in added {
+                if !labels.contains(label) {
+                    labels.push(label.clone());
+                }
+            }
+        }
+        Some(LabelChange::Remove(removed)) => labels.retain(|label| !removed.contains(label)),
+        None => {}
+    }
+
+    let commit = if labels != before {
+        let updated = crate::plan::set_front_matter_fields(
+            &document,
+            &[("labels", &plan::format_labels(&labels))],
+        )?;
+        let oid = vcs::commit_files_to_branch_in(
+            project_root,
+            &opts.branch,
+            &[(plan_rel.as_path(), updated.as_str())],
+            &format!("chore: label plan {}", opts.slug),
+        )?;
+        Some(oid.to_string())
+    } else {
+        None
+    };
+
+    let outcome = match (&opts.change, &commit) {
+        (None, _) => "Plan labels",
+        (Some(_), None) => "Plan labels unchanged",
+        (Some(_), Some(_)) => "Plan labels updated",
+    };
+    match opts.format {
+        LabelOutputFormat::Json => {
+            let payload = json!({
+                "outcome": match (&opts.change, &commit) {
+                    (None, _) => "plan_labels",
+                    (Some(_), None) => "plan_labels_unchanged",
+                    (Some(_), Some(_)) => "plan_labels_updated",
+                },
+                "plan": opts.slug,
+                "branch": opts.branch,
+                "labels": labels,
+                "commit": commit,
+            });
+            println!("{}", serde_json::to_string_pretty(&payload)?);
+        }
+        LabelOutputFormat::Text => {
+            let mut rows = vec![
+                ("Outcome".to_string(), outcome.to_string()),
+                ("Plan".to_string(), opts.slug.clone()),
+                (
+                    "Labels".to_string(),
+                    if labels.is_empty() {
+                        "none".to_string()
+                    } else {
+                        labels.join(", ")
+                    },
+                ),
+            ];
+            if let Some(commit) = &commit {
+                rows.push((
+                    "Commit".to_string(),
+                    format!("{} on {}", &commit[..commit.len().min(12)], opts.branch),
+                ));
+            }
+            println!("{}", format_block(rows));
+        }
+    }
+    Ok(())
+}
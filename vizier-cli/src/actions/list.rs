@@ -1,14 +1,17 @@
+use crate::cli::porcelain;
 use crate::cli::prompt::prompt_yes_no;
 use crate::{jobs, plan};
 
 use serde_json::{Map, Value, json};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::io::IsTerminal;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use vizier_core::{
     config,
     display::{self, format_number},
+    drift::{self, PlanDrift},
+    thread_state, vcs,
 };
 
 use super::shared::{format_block, format_block_with_indent, format_table};
@@ -118,6 +121,11 @@ enum ListEntryField {
     Plan,
     Branch,
     Summary,
+    Behind,
+    Age,
+    Labels,
+    Changes,
+    Lineage,
 }
 
 impl ListEntryField {
@@ -126,6 +134,11 @@ impl ListEntryField {
             "plan" => Some(Self::Plan),
             "branch" => Some(Self::Branch),
             "summary" => Some(Self::Summary),
+            "behind" => Some(Self::Behind),
+            "age" => Some(Self::Age),
+            "labels" => Some(Self::Labels),
+            "changes" => Some(Self::Changes),
+            "lineage" => Some(Self::Lineage),
             _ => None,
         }
     }
@@ -135,6 +148,11 @@ impl ListEntryField {
             Self::Plan => "Plan",
             Self::Branch => "Branch",
             Self::Summary => "Summary",
+            Self::Behind => "Behind",
+            Self::Age => "Age",
+            Self::Labels => "Labels",
+            Self::Changes => "Changes",
+            Self::Lineage => "Lineage",
         }
     }
 
@@ -143,10 +161,119 @@ impl ListEntryField {
             Self::Plan => "plan",
             Self::Branch => "branch",
             Self::Summary => "summary",
+            Self::Behind => "commits_behind",
+            Self::Age => "age_days",
+            Self::Labels => "labels",
+            Self::Changes => "changes",
+            Self::Lineage => "lineage",
         }
     }
 }
 
+fn entry_field_value(
+    field: ListEntryField,
+    entry: &plan::PlanSlugEntry,
+    summary: &str,
+    drift: Option<&PlanDrift>,
+    changes: Option<&vcs::DiffStats>,
+    lineage: Option<&PlanLineage>,
+) -> String {
+    match field {
+        ListEntryField::Plan => entry.slug.clone(),
+        ListEntryField::Branch => entry.branch.clone(),
+        ListEntryField::Summary => summary.to_string(),
+        ListEntryField::Behind => drift
+            .map(|drift| drift.commits_behind.to_string())
+            .unwrap_or_default(),
+        ListEntryField::Age => drift
+            .and_then(|drift| drift.age_days)
+            .map(|days| days.to_string())
+            .unwrap_or_default(),
+        ListEntryField::Labels => entry.labels.join(", "),
+        ListEntryField::Changes => changes
+            .map(vcs::DiffStats::summary_line)
+            .unwrap_or_default(),
+        ListEntryField::Lineage => lineage.map(PlanLineage::describe).unwrap_or_default(),
+    }
+}
+
+/// A plan's `spawned-by:` parent and the plans filed as its follow-ups. Links to archived plans
+/// are marked merged, so follow-ups deferred from a merged plan stay visible.
+#[derive(Debug, Default)]
+struct PlanLineage {
+    spawned_by: Option<(String, bool)>,
+    follow_ups: Vec<(String, bool)>,
+}
+
+impl PlanLineage {
+    fn is_empty(&self) -> bool {
+        self.spawned_by.is_none() && self.follow_ups.is_empty()
+    }
+
+    fn describe(&self) -> String {
+        let link = |(slug, merged): &(String, bool)| {
+            if *merged {
+                format!("{slug} (merged)")
+            } else {
+                slug.clone()
+            }
+        };
+        let mut parts = Vec::new();
+        if let Some(parent) = self.spawned_by.as_ref() {
+            parts.push(format!("spawned by {}", link(parent)));
+        }
+        if !self.follow_ups.is_empty() {
+            parts.push(format!(
+                "follow-ups: {}",
+                self.follow_ups
+                    .iter()
+                    .map(link)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+        parts.join("; ")
+    }
+
+    fn to_json(&self) -> Value {
+        let link = |(slug, merged): &(String, bool)| json!({ "plan": slug, "merged": merged });
+        json!({
+            "spawned_by": self.spawned_by.as_ref().map(link),
+            "follow_ups": self.follow_ups.iter().map(link).collect::<Vec<_>>(),
+        })
+    }
+}
+
+fn collect_lineage(
+    inventory: &[plan::PlanSlugEntry],
+    archives: &[vizier_core::archive::ArchiveMetadata],
+) -> HashMap<String, PlanLineage> {
+    let archived = |slug: &str| archives.iter().any(|entry| entry.slug == slug);
+    let mut lineage = HashMap::<String, PlanLineage>::new();
+    for entry in inventory {
+        let Some(parent) = entry.spawned_by.as_ref() else {
+            continue;
+        };
+        lineage.entry(entry.slug.clone()).or_default().spawned_by =
+            Some((parent.clone(), archived(parent)));
+        lineage
+            .entry(parent.clone())
+            .or_default()
+            .follow_ups
+            .push((entry.slug.clone(), false));
+    }
+    for archive in archives {
+        if let Some(parent) = archive.spawned_by.as_ref() {
+            lineage
+                .entry(parent.clone())
+                .or_default()
+                .follow_ups
+                .push((archive.slug.clone(), true));
+        }
+    }
+    lineage
+}
+
 #[derive(Clone, Copy, Debug)]
 enum ListJobField {
     Job,
@@ -316,7 +443,7 @@ pub(crate) fn run_clean(
             for reason in err.reasons() {
                 display::emit(display::LogLevel::Error, format!("  - {reason}"));
             }
-            std::process::exit(10);
+            crate::cli::outcome::exit(10);
         }
         Err(err) => return Err(Box::new(err)),
     };
@@ -324,7 +451,7 @@ pub(crate) fn run_clean(
     emit_clean_summary(opts.format, &outcome)?;
 
     if outcome.degraded && !opts.force {
-        std::process::exit(1);
+        crate::cli::outcome::exit(1);
     }
 
     Ok(())
@@ -456,8 +583,132 @@ fn emit_clean_summary(
     Ok(())
 }
 
+/// `plan <slug> <branch> <status> <head-sha>` per entry; the status is the plan's latest job,
+/// or `idle` when no job has touched it.
+fn emit_plans_porcelain(entries: &[plan::PlanSlugEntry]) -> Result<(), Box<dyn std::error::Error>> {
+    let repo_root =
+        vcs::repo_root().map_err(|err| -> Box<dyn std::error::Error> { Box::new(err) })?;
+    let records = jobs::list_records(&repo_root.join(".vizier").join("jobs")).unwrap_or_default();
+    let tips = vcs::branch_tips_in(&repo_root).unwrap_or_default();
+    for entry in entries {
+        let status = select_inline_job(&records, entry)
+            .map(|record| jobs::status_label(record.status))
+            .unwrap_or("idle");
+        let head = tips.get(&entry.branch).map(ToString::to_string);
+        porcelain::emit(
+            "plan",
+            &[
+                entry.slug.clone(),
+                entry.branch.clone(),
+                status.to_string(),
+                porcelain::field(head.as_deref()),
+            ],
+        );
+    }
+    Ok(())
+}
+
+/// The branch plans are compared against: `--target` when given, otherwise the detected target
+/// with the source it came from.
+struct ListTarget {
+    branch: String,
+    detected: Option<vcs::TargetSource>,
+}
+
+impl ListTarget {
+    fn resolve(explicit: Option<&str>) -> Option<Self> {
+        if let Some(branch) = explicit {
+            return Some(Self {
+                branch: branch.to_string(),
+                detected: None,
+            });
+        }
+        let repo_root = vcs::repo_root().ok()?;
+        let detected = vcs::detect_target_branch_in(&repo_root)?;
+        Some(Self {
+            branch: detected.branch,
+            detected: Some(detected.source),
+        })
+    }
+
+    fn display(&self) -> String {
+        match self.detected {
+            Some(source) => format!("{} ({})", self.branch, source.label()),
+            None => self.branch.clone(),
+        }
+    }
+
+    fn insert_json(&self, header: &mut Map<String, Value>, key: &str) {
+        header.insert(key.to_string(), json!(self.branch));
+        header.insert(
+            "target_source".to_string(),
+            json!(self.detected.map_or("flag", |source| source.as_str())),
+        );
+    }
+}
+
+// Drift is best-effort: plans whose branch or target cannot be resolved simply omit it.
+fn measure_entry_drift(
+    entries: &[plan::PlanSlugEntry],
+    target: Option<&str>,
+) -> HashMap<String, PlanDrift> {
+    let mut drifts = HashMap::new();
+    let Ok(repo_root) = vcs::repo_root() else {
+        return drifts;
+    };
+    let Some(target) = target
+        .map(str::to_string)
+        .or_else(|| vcs::detect_primary_branch_in(&repo_root))
+    else {
+        return drifts;
+    };
+    let thresholds = config::get_config().workflow.drift;
+    for entry in entries {
+        let Ok(drift) = drift::measure_plan_drift(&repo_root, &entry.branch, &target) else {
+            continue;
+        };
+        if let Some(message) = drift.stale_message(&entry.slug, &thresholds) {
+            display::warn(message);
+        }
+        drifts.insert(entry.slug.clone(), drift);
+    }
+    drifts
+}
+
+// Like drift, change stats are best-effort and only computed when the `Changes` field is shown.
+fn measure_entry_changes(
+    entries: &[plan::PlanSlugEntry],
+    target: Option<&str>,
+) -> HashMap<String, vcs::DiffStats> {
+    let Ok(repo_root) = vcs::repo_root() else {
+        return HashMap::new();
+    };
+    let Some(target) = target
+        .map(str::to_string)
+        .or_else(|| vcs::detect_primary_branch_in(&repo_root))
+    else {
+        return HashMap::new();
+    };
+    entries
+        .iter()
+        .filter_map(|entry| {
+            vcs::diff_stats_between(&repo_root, &target, &entry.branch)
+                .ok()
+                .map(|stats| (entry.slug.clone(), stats))
+        })
+        .collect()
+}
+
 fn list_pending_plans(opts: ListOptions) -> Result<(), Box<dyn std::error::Error>> {
-    let entries = plan::PlanSlugInventory::collect(opts.target.as_deref())?;
+    let inventory = plan::PlanSlugInventory::collect(opts.target.as_deref())?;
+    let entries = inventory
+        .iter()
+        .filter(|entry| opts.labels.iter().all(|label| entry.labels.contains(label)))
+        .cloned()
+        .collect::<Vec<_>>();
+    if porcelain::enabled() {
+        return emit_plans_porcelain(&entries);
+    }
     let mut list_config = config::get_config().display.lists.list.clone();
     if let Some(format) = opts.format {
         list_config.format = format;
@@ -487,8 +738,30 @@ fn list_pending_plans(opts: ListOptions) -> Result<(), Box<dyn std::error::Error
         ListCommandField::parse,
     );
     let labels = normalize_labels(&list_config.labels);
+    let drifts = measure_entry_drift(&entries, opts.target.as_deref());
+    let changes = if entry_fields
+        .iter()
+        .any(|field| matches!(field, ListEntryField::Changes))
+    {
+        measure_entry_changes(&entries, opts.target.as_deref())
+    } else {
+        HashMap::new()
+    };
+    // Lineage spans the whole inventory and the archive, not just the plans shown.
+    let lineage = if entry_fields
+        .iter()
+        .any(|field| matches!(field, ListEntryField::Lineage))
+    {
+        let archives = vcs::repo_root()
+            .ok()
+            .and_then(|root| vizier_core::archive::list_archives(&root).ok())
+            .unwrap_or_default();
+        collect_lineage(&inventory, &archives)
+    } else {
+        HashMap::new()
+    };
 
-    let outcome = if entries.is_empty() {
+    let mut outcome = if entries.is_empty() {
         "No pending draft branches".to_string()
     } else {
         format!(
@@ -501,6 +774,22 @@ fn list_pending_plans(opts: ListOptions) -> Result<(), Box<dyn std::error::Error
             }
         )
     };
+    if !opts.labels.is_empty() {
+        outcome.push_str(&format!(" labeled {}", opts.labels.join(", ")));
+    }
+
+    let list_target = ListTarget::resolve(opts.target.as_deref());
+
+    if opts.by_thread {
+        return emit_plans_by_thread(
+            &entries,
+            &outcome,
+            list_target.as_ref(),
+            &list_config,
+            &header_fields,
+            &labels,
+        );
+    }
 
     if matches!(list_config.format, config::ListFormat::Json) {
         let mut job_records = Vec::new();
@@ -523,8 +812,8 @@ fn list_pending_plans(opts: ListOptions) -> Result<(), Box<dyn std::error::Error
                     header.insert(field.json_key().to_string(), Value::String(outcome.clone()));
                 }
                 ListHeaderField::Target => {
-                    if let Some(target) = opts.target.as_ref() {
-                        header.insert(field.json_key().to_string(), Value::String(target.clone()));
+                    if let Some(target) = &list_target {
+                        target.insert_json(&mut header, field.json_key());
                     }
                 }
             }
@@ -540,11 +829,31 @@ fn list_pending_plans(opts: ListOptions) -> Result<(), Box<dyn std::error::Error
             );
             for field in &entry_fields {
                 let value = match field {
-                    ListEntryField::Plan => entry.slug.clone(),
-                    ListEntryField::Branch => entry.branch.clone(),
-                    ListEntryField::Summary => summary.clone(),
+                    ListEntryField::Labels => json!(entry.labels),
+                    ListEntryField::Changes => match changes.get(&entry.slug) {
+                        Some(stats) => json!({
+                            "files": stats.files.len(),
+                            "insertions": stats.insertions(),
+                            "deletions": stats.deletions(),
+                            "renames": stats.renames(),
+                            "binary_files": stats.binary_files(),
+                        }),
+                        None => Value::Null,
+                    },
+                    ListEntryField::Lineage => lineage
+                        .get(&entry.slug)
+                        .map(PlanLineage::to_json)
+                        .unwrap_or_else(|| PlanLineage::default().to_json()),
+                    _ => Value::String(entry_field_value(
+                        *field,
+                        entry,
+                        &summary,
+                        drifts.get(&entry.slug),
+                        None,
+                        None,
+                    )),
                 };
-                obj.insert(field.json_key().to_string(), Value::String(value));
+                obj.insert(field.json_key().to_string(), value);
             }
 
             let record = select_inline_job(&job_records, entry);
@@ -598,8 +907,8 @@ fn list_pending_plans(opts: ListOptions) -> Result<(), Box<dyn std::error::Error
                 header_rows.push((resolve_label(&labels, field.label()), outcome.clone()));
             }
             ListHeaderField::Target => {
-                if let Some(target) = opts.target.as_ref() {
-                    header_rows.push((resolve_label(&labels, field.label()), target.clone()));
+                if let Some(target) = &list_target {
+                    header_rows.push((resolve_label(&labels, field.label()), target.display()));
                 }
             }
         }
@@ -660,12 +969,14 @@ fn list_pending_plans(opts: ListOptions) -> Result<(), Box<dyn std::error::Error
                 );
                 let mut row = Vec::new();
                 for field in &entry_fields {
-                    let value = match field {
-                        ListEntryField::Plan => entry.slug.clone(),
-                        ListEntryField::Branch => entry.branch.clone(),
-                        ListEntryField::Summary => summary.clone(),
-                    };
-                    row.push(value);
+                    row.push(entry_field_value(
+                        *field,
+                        entry,
+                        &summary,
+                        drifts.get(&entry.slug),
+                        changes.get(&entry.slug),
+                        lineage.get(&entry.slug),
+                    ));
                 }
                 for field in &job_fields {
                     let value = record.and_then(|record| match field {
@@ -712,11 +1023,23 @@ fn list_pending_plans(opts: ListOptions) -> Result<(), Box<dyn std::error::Error
                 );
                 let mut rows = Vec::new();
                 for field in &entry_fields {
-                    let value = match field {
-                        ListEntryField::Plan => entry.slug.clone(),
-                        ListEntryField::Branch => entry.branch.clone(),
-                        ListEntryField::Summary => summary.clone(),
-                    };
+                    // Most plans carry no labels or lineage; an empty row would only add noise.
+                    if matches!(field, ListEntryField::Labels) && entry.labels.is_empty() {
+                        continue;
+                    }
+                    if matches!(field, ListEntryField::Lineage)
+                        && lineage.get(&entry.slug).is_none_or(PlanLineage::is_empty)
+                    {
+                        continue;
+                    }
+                    let value = entry_field_value(
+                        *field,
+                        entry,
+                        &summary,
+                        drifts.get(&entry.slug),
+                        changes.get(&entry.slug),
+                        lineage.get(&entry.slug),
+                    );
                     rows.push((resolve_label(&labels, field.label()), value));
                 }
 
@@ -767,11 +1090,183 @@ fn list_pending_plans(opts: ListOptions) -> Result<(), Box<dyn std::error::Error
     Ok(())
 }
 
+// Splits pending plans into thread groups (a plan linked to several threads appears under each)
+// and the plans that name no thread at all.
+fn group_plans_by_thread<'a>(
+    entries: &'a [plan::PlanSlugEntry],
+    anchors: &HashMap<String, Vec<String>>,
+) -> (
+    BTreeMap<String, Vec<&'a plan::PlanSlugEntry>>,
+    Vec<&'a plan::PlanSlugEntry>,
+) {
+    let mut groups: BTreeMap<String, Vec<&plan::PlanSlugEntry>> = BTreeMap::new();
+    let mut unanchored = Vec::new();
+    for entry in entries {
+        match anchors
+            .get(&entry.slug)
+            .filter(|threads| !threads.is_empty())
+        {
+            Some(threads) => {
+                for thread in threads {
+                    groups.entry(thread.clone()).or_default().push(entry);
+                }
+            }
+            None => unanchored.push(entry),
+        }
+    }
+    (groups, unanchored)
+}
+
+fn thread_doc_path(thread: &str) -> Option<PathBuf> {
+    let path = vcs::repo_root()
+        .ok()?
+        .join(".vizier/narrative/threads")
+        .join(format!("{thread}.md"));
+    path.is_file().then_some(path)
+}
+
+// First `# ` heading of the thread document, when it exists.
+fn thread_title(path: &Path) -> Option<String> {
+    std::fs::read_to_string(path)
+        .ok()?
+        .lines()
+        .find_map(|line| line.strip_prefix("# "))
+        .map(|title| title.trim().to_string())
+}
+
+fn thread_doc_status(path: &Path) -> Option<thread_state::ThreadStatus> {
+    thread_state::thread_status(&std::fs::read_to_string(path).ok()?).ok()
+}
+
+fn emit_plans_by_thread(
+    entries: &[plan::PlanSlugEntry],
+    outcome: &str,
+    target: Option<&ListTarget>,
+    list_config: &config::DisplayListConfig,
+    header_fields: &[ListHeaderField],
+    labels: &HashMap<String, String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let anchors = entries
+        .iter()
+        .map(|entry| {
+            let threads = plan::load_plan_from_branch(&entry.slug, &entry.branch)
+                .map(|meta| meta.threads)
+                .unwrap_or_default();
+            (entry.slug.clone(), threads)
+        })
+        .collect::<HashMap<_, _>>();
+    let (groups, unanchored) = group_plans_by_thread(entries, &anchors);
+    let summary = |entry: &plan::PlanSlugEntry| {
+        format_summary(
+            &entry.summary,
+            list_config.summary_max_len,
+            list_config.summary_single_line,
+        )
+    };
+
+    if matches!(list_config.format, config::ListFormat::Json) {
+        let mut header = Map::new();
+        for field in header_fields {
+            match field {
+                ListHeaderField::Outcome => {
+                    header.insert(field.json_key().to_string(), json!(outcome));
+                }
+                ListHeaderField::Target => {
+                    if let Some(target) = target {
+                        target.insert_json(&mut header, field.json_key());
+                    }
+                }
+            }
+        }
+        let plan_json = |entry: &&plan::PlanSlugEntry| json!({"plan": entry.slug, "branch": entry.branch, "summary": summary(entry)});
+        let threads = groups
+            .iter()
+            .map(|(thread, plans)| {
+                let doc = thread_doc_path(thread);
+                json!({
+                    "thread": thread,
+                    "title": doc.as_deref().and_then(thread_title),
+                    "document_exists": doc.is_some(),
+                    "state": doc
+                        .as_deref()
+                        .and_then(thread_doc_status)
+                        .map(|status| status.state.as_str()),
+                    "plans": plans.iter().map(plan_json).collect::<Vec<_>>(),
+                })
+            })
+            .collect::<Vec<_>>();
+        let payload = json!({
+            "header": header,
+            "threads": threads,
+            "unanchored": unanchored.iter().map(plan_json).collect::<Vec<_>>(),
+        });
+        println!("{}", serde_json::to_string_pretty(&payload)?);
+        return Ok(());
+    }
+
+    let mut blocks = Vec::new();
+    let mut header_rows = Vec::new();
+    for field in header_fields {
+        match field {
+            ListHeaderField::Outcome => {
+                header_rows.push((resolve_label(labels, field.label()), outcome.to_string()));
+            }
+            ListHeaderField::Target => {
+                if let Some(target) = target {
+                    header_rows.push((resolve_label(labels, field.label()), target.display()));
+                }
+            }
+        }
+    }
+    if !header_rows.is_empty() {
+        blocks.push(format_block(header_rows));
+    }
+    for (thread, plans) in &groups {
+        let doc = thread_doc_path(thread);
+        let title = match &doc {
+            Some(path) => thread_title(path).unwrap_or_default(),
+            None => format!("no .vizier/narrative/threads/{thread}.md"),
+        };
+        let mut rows = vec![("Thread".to_string(), thread.clone())];
+        if !title.is_empty() {
+            rows.push(("Title".to_string(), title));
+        }
+        if let Some(status) = doc.as_deref().and_then(thread_doc_status) {
+            rows.push(("State".to_string(), status.state.as_str().to_string()));
+        }
+        for entry in plans {
+            rows.push((
+                "Plan".to_string(),
+                format!("{} ({})", entry.slug, summary(entry)),
+            ));
+        }
+        blocks.push(format_block(rows));
+    }
+    if !unanchored.is_empty() {
+        let mut rows = vec![(
+            "Thread".to_string(),
+            "none; add `threads:` to the plan front matter".to_string(),
+        )];
+        for entry in &unanchored {
+            rows.push((
+                "Plan".to_string(),
+                format!("{} ({})", entry.slug, summary(entry)),
+            ));
+        }
+        blocks.push(format_block(rows));
+    }
+    if !blocks.is_empty() {
+        println!("{}", blocks.join("\n\n"));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
-    use super::select_inline_job;
+    use super::{group_plans_by_thread, select_inline_job};
     use crate::jobs::{JobMetadata, JobRecord, JobStatus};
     use chrono::TimeZone;
+    use std::collections::HashMap;
 
     fn job_record(
         id: &str,
@@ -811,6 +1306,8 @@ mod tests {
             slug: "alpha".to_string(),
             branch: "draft/alpha".to_string(),
             summary: "Alpha spec".to_string(),
+            labels: Vec::new(),
+            spawned_by: None,
         };
 
         let records = vec![
@@ -837,4 +1334,37 @@ mod tests {
         let selected = select_inline_job(&records, &entry).expect("select job");
         assert_eq!(selected.id, "third");
     }
+
+    #[test]
+    fn group_plans_by_thread_lists_multi_thread_plans_under_each_and_flags_unanchored() {
+        let entry = |slug: &str| crate::plan::PlanSlugEntry {
+            plan_id: format!("pln_{slug}"),
+            slug: slug.to_string(),
+            branch: format!("draft/{slug}"),
+            summary: String::new(),
+            labels: Vec::new(),
+            spawned_by: None,
+        };
+        let entries = vec![entry("alpha"), entry("beta"), entry("gamma")];
+        let anchors = HashMap::from([
+            (
+                "alpha".to_string(),
+                vec!["jobs".to_string(), "workflows".to_string()],
+            ),
+            ("beta".to_string(), vec!["jobs".to_string()]),
+            ("gamma".to_string(), Vec::new()),
+        ]);
+
+        let (groups, unanchored) = group_plans_by_thread(&entries, &anchors);
+        let slugs = |plans: &[&crate::plan::PlanSlugEntry]| {
+            plans
+                .iter()
+                .map(|plan| plan.slug.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(groups.keys().collect::<Vec<_>>(), vec!["jobs", "workflows"]);
+        assert_eq!(slugs(&groups["jobs"]), vec!["alpha", "beta"]);
+        assert_eq!(slugs(&groups["workflows"]), vec!["alpha"]);
+        assert_eq!(slugs(&unanchored), vec!["gamma"]);
+    }
 }
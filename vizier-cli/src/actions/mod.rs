@@ -1,15 +1,81 @@
+mod ack;
+mod agent;
+mod apply_patch;
+mod archive;
 mod audit;
+mod cache;
+mod changelog;
+mod ci;
+mod config_import;
+mod config_plan;
+mod doctor;
+mod forge;
 mod init;
+mod label;
 mod list;
+mod note;
+mod pick;
+mod prompt_test;
+mod rebase;
 mod release;
+mod remote;
+mod rescue;
 mod run;
+mod run_confirm;
+mod search;
+mod sessions;
 pub(crate) mod shared;
+mod snapshot;
+mod stats;
+mod thread;
+mod tool;
 mod types;
+mod verify;
+mod watch;
 mod workflow_preflight;
 
+pub(crate) use ack::run_ack;
+pub(crate) use agent::run_agent_validate;
+pub(crate) use apply_patch::run_apply_patch;
+pub(crate) use archive::run_archive;
 pub(crate) use audit::run_workflow_audit;
+pub(crate) use cache::run_cache;
+pub(crate) use changelog::run_changelog;
+pub(crate) use ci::run_ci;
+pub(crate) use config_import::run_config_import_ci;
+pub(crate) use config_plan::run_config_plan;
+pub(crate) use doctor::run_doctor;
+pub(crate) use forge::run_forge;
 pub(crate) use init::run_init;
+pub(crate) use label::run_label;
 pub(crate) use list::{run_cd, run_clean, run_list};
+pub(crate) use note::run_note;
+pub(crate) use pick::run_pick;
+pub(crate) use prompt_test::run_prompt_test;
+pub(crate) use rebase::run_rebase;
 pub(crate) use release::run_release;
+pub(crate) use rescue::run_rescue;
 pub(crate) use run::run_workflow;
-pub(crate) use types::{CdOptions, CleanOptions, CleanOutputFormat, ListOptions};
+pub(crate) use search::run_search;
+pub(crate) use sessions::run_sessions;
+pub(crate) use snapshot::run_snapshot;
+pub(crate) use stats::run_stats;
+pub(crate) use thread::run_thread;
+pub(crate) use tool::run_tool;
+pub(crate) use types::{
+    AckOptions, AckOutputFormat, AgentValidateOptions, AgentValidateOutputFormat,
+    ApplyPatchOptions, ApplyPatchOutputFormat, ArchiveOptions, ArchiveOutputFormat, ArchiveRequest,
+    CacheOptions, CacheOutputFormat, CacheRequest, CdOptions, ChangelogOptions,
+    ChangelogOutputFormat, CiOptions, CiOutputFormat, CiRequest, CleanOptions, CleanOutputFormat,
+    ConfigImportCiOptions, ConfigImportOutputFormat, DoctorOptions, DoctorOutputFormat,
+    ForgeOptions, ForgeOutputFormat, ForgeRequest, InitOptions, LabelChange, LabelOptions,
+    LabelOutputFormat, ListOptions, NoteOptions, NoteOutputFormat, PickOptions, PickOutputFormat,
+    PlanOptions, PlanOutputFormat, PromptTestOptions, PromptTestOutputFormat, RebaseOptions,
+    RebaseOutputFormat, RescueAction, RescueOptions, RescueOutputFormat, SearchOptions,
+    SearchOutputFormat, SessionsOptions, SessionsOutputFormat, SessionsRequest, SnapshotOptions,
+    SnapshotOutputFormat, SnapshotRequest, StatsOptions, StatsOutputFormat, ThreadOptions,
+    ThreadOutputFormat, ThreadRequest, ToolOptions, VerifyOptions, VerifyOutputFormat,
+    WatchOptions, WatchOutputFormat,
+};
+pub(crate) use verify::run_verify;
+pub(crate) use watch::run_watch;
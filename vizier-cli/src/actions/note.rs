@@ -0,0 +1,81 @@
+use std::path::Path;
+
+use chrono::Utc;
+use serde_json::json;
+use vizier_core::{plan, vcs};
+
+use super::shared::format_block;
+use super::types::{NoteOptions, NoteOutputFormat};
+
+/// Shows a plan's operator notes, or appends one by committing the updated notes document on the
+/// plan branch.
+pub(crate) fn run_note(
+    project_root: &Path,
+    opts: NoteOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if !vcs::branch_exists_in(project_root, &opts.branch)? {
+        return Err(format!("branch `{}` does not exist", opts.branch).into());
+    }
+    let notes_rel = plan::plan_notes_rel_path(&opts.slug);
+    let notes_rel_display = notes_rel.to_string_lossy().replace('\\', "/");
+    let revision = format!("{}:{notes_rel_display}", opts.branch);
+    let existing = if vcs::blob_exists_at_revision_in(project_root, &revision)? {
+        Some(vcs::read_blob_at_revision_in(project_root, &revision)?)
+    } else {
+        None
+    };
+
+    let (document, commit) = match opts.note.as_deref() {
+        Some(note) => {
+            let stamp = Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
+            let updated = plan::append_plan_note(
+                existing.as_deref(),
+                &opts.slug,
+                &stamp,
+                &plan::branch_user(),
+                note,
+            );
+            let oid = vcs::commit_files_to_branch_in(
+                project_root,
+                &opts.branch,
+                &[(notes_rel.as_path(), updated.as_str())],
+                &format!("chore: note on plan {}", opts.slug),
+            )?;
+            (Some(updated), Some(oid.to_string()))
+        }
+        None => (existing, None),
+    };
+
+    match opts.format {
+        NoteOutputFormat::Json => {
+            let payload = json!({
+                "outcome": if commit.is_some() { "plan_note_added" } else { "plan_notes" },
+                "plan": opts.slug,
+                "branch": opts.branch,
+                "path": notes_rel_display,
+                "notes": document,
+                "commit": commit,
+            });
+            println!("{}", serde_json::to_string_pretty(&payload)?);
+        }
+        NoteOutputFormat::Text => match &commit {
+            Some(commit) => {
+                let rows = vec![
+                    ("Outcome".to_string(), "Plan note added".to_string()),
+                    ("Plan".to_string(), opts.slug.clone()),
+                    ("Notes".to_string(), notes_rel_display),
+                    (
+                        "Commit".to_string(),
+                        format!("{} on {}", &commit[..commit.len().min(12)], opts.branch),
+                    ),
+                ];
+                println!("{}", format_block(rows));
+            }
+            None => match document {
+                Some(document) => print!("{}", document),
+                None => println!("No notes for plan {}", opts.slug),
+            },
+        },
+    }
+    Ok(())
+}
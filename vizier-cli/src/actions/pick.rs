@@ -0,0 +1,261 @@
+use std::path::Path;
+
+use git2::{DiffFormat, Repository};
+use serde_json::json;
+use vizier_core::{
+    auditor::{Auditor, record_commit_note},
+    config, display, vcs,
+};
+
+use crate::jobs;
+
+use super::shared::{format_block, short_hash};
+use super::types::{PickOptions, PickOutputFormat};
+
+const PICK_MESSAGE_INSTRUCTION: &str = "Given the diff of a single commit cherry-picked from an implementation plan branch as a hotfix, return a commit message for these changes.";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PickResult {
+    Completed { head: String, subject: String },
+    Conflicted { files: Vec<String> },
+    GateFailed { reason: String },
+}
+
+impl PickResult {
+    fn outcome_key(&self) -> &'static str {
+        match self {
+            Self::Completed { .. } => "pick_completed",
+            Self::Conflicted { .. } => "pick_conflicted",
+            Self::GateFailed { .. } => "pick_gate_failed",
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Completed { .. } => "Commit picked onto target",
+            Self::Conflicted { .. } => "Pick blocked by conflicts",
+            Self::GateFailed { .. } => "Pick blocked by a failing gate",
+        }
+    }
+}
+
+pub(crate) async fn run_pick(
+    project_root: &Path,
+    opts: PickOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    for branch in [&opts.branch, &opts.target] {
+        if !vcs::branch_exists_in(project_root, branch)? {
+            return Err(format!("branch `{branch}` does not exist").into());
+        }
+    }
+    if config::get_config().merge.is_protected(&opts.target) {
+        return Err(format!(
+            "`{}` is protected ([merge] protected); open a pull request from `{}` instead",
+            opts.target, opts.branch
+        )
+        .into());
+    }
+
+    let commits = vcs::branch_commits_in(project_root, &opts.branch, &opts.target)?;
+    let (step, picked) = select_commit(project_root, &opts, &commits)?;
+
+    let ready = match vcs::prepare_pick_in(project_root, picked.oid, &opts.target)? {
+        vcs::PickPreparation::Ready(ready) => ready,
+        vcs::PickPreparation::Conflicted { files } => {
+            emit_pick_report(&opts, step, &picked, &PickResult::Conflicted { files })?;
+            crate::cli::outcome::exit(10);
+        }
+    };
+
+    let message = match opts.message.clone() {
+        Some(message) => message,
+        None => {
+            let agent_phase = display::start_phase(display::PHASE_AGENT_RUN, None);
+            let diff = commit_patch(project_root, picked.oid)?;
+            let message = Auditor::llm_request(PICK_MESSAGE_INSTRUCTION.to_string(), diff).await;
+            agent_phase.finish();
+            message?.content
+        }
+    };
+    let message = format!(
+        "{}\n\n(cherry picked from commit {})\n",
+        message.trim_end(),
+        picked.oid
+    );
+    let candidate = vcs::create_pick_commit_in(project_root, &ready, &message)?;
+
+    // Gates need a branch to check out; it only lives for the duration of the gate run.
+    let short = short_hash(&candidate.to_string());
+    let gate_branch = format!("vizier/pick/{}-{short}", opts.slug);
+    {
+        let repo = Repository::open(project_root)?;
+        repo.branch(&gate_branch, &repo.find_commit(candidate)?, true)?;
+    }
+    let gate_phase = display::start_phase(display::PHASE_GATE, None);
+    let gates =
+        jobs::run_merge_gates_on_branch(project_root, &gate_branch, &format!("pick-{short}"));
+    gate_phase.finish();
+    if let Err(err) = vcs::delete_branch_in(project_root, &gate_branch) {
+        display::warn(format!("unable to delete {gate_branch}: {err}"));
+    }
+    if let Err(reason) = gates {
+        emit_pick_report(&opts, step, &picked, &PickResult::GateFailed { reason })?;
+        crate::cli::outcome::exit(10);
+    }
+
+    let commit_phase = display::start_phase(display::PHASE_COMMIT, None);
+    vcs::fast_forward_branch_in(project_root, &opts.target, ready.onto_oid, candidate)?;
+    record_commit_note(project_root, candidate, Some(&opts.slug));
+    commit_phase.finish();
+
+    let result = PickResult::Completed {
+        head: candidate.to_string(),
+        subject: message.lines().next().unwrap_or_default().to_string(),
+    };
+    emit_pick_report(&opts, step, &picked, &result)
+}
+
+// Short all-digit arguments are plan step numbers; anything else is a revision that must name
+// one of the plan's own commits.
+fn select_commit(
+    project_root: &Path,
+    opts: &PickOptions,
+    commits: &[vcs::BranchCommit],
+) -> Result<(usize, vcs::BranchCommit), Box<dyn std::error::Error>> {
+    if commits.is_empty() {
+        return Err(format!(
+            "`{}` has no commits that are not already on `{}`",
+            opts.branch, opts.target
+        )
+        .into());
+    }
+
+    if opts.pick.len() <= 3 && opts.pick.chars().all(|ch| ch.is_ascii_digit()) {
+        let step: usize = opts.pick.parse()?;
+        if step == 0 || step > commits.len() {
+            return Err(format!(
+                "plan step {step} is out of range; `{}` has {} step(s)",
+                opts.branch,
+                commits.len()
+            )
+            .into());
+        }
+        return Ok((step, commits[step - 1].clone()));
+    }
+
+    let oid = Repository::open(project_root)?
+        .revparse_single(&opts.pick)?
+        .peel_to_commit()?
+        .id();
+    commits
+        .iter()
+        .position(|commit| commit.oid == oid)
+        .map(|index| (index + 1, commits[index].clone()))
+        .ok_or_else(|| {
+            format!(
+                "{} is not a step of `{}` (merge commits and commits already on `{}` cannot be picked)",
+                opts.pick, opts.branch, opts.target
+            )
+            .into()
+        })
+}
+
+fn commit_patch(project_root: &Path, oid: git2::Oid) -> Result<String, git2::Error> {
+    let repo = Repository::open(project_root)?;
+    let commit = repo.find_commit(oid)?;
+    let parent_tree = commit.parent(0)?.tree()?;
+    let diff = repo.diff_tree_to_tree(Some(&parent_tree), Some(&commit.tree()?), None)?;
+    let mut patch = String::new();
+    diff.print(DiffFormat::Patch, |_, _, line| {
+        if matches!(line.origin(), '+' | '-' | ' ') {
+            patch.push(line.origin());
+        }
+        patch.push_str(&String::from_utf8_lossy(line.content()));
+        true
+    })?;
+    Ok(patch)
+}
+
+fn emit_pick_report(
+    opts: &PickOptions,
+    step: usize,
+    picked: &vcs::BranchCommit,
+    result: &PickResult,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if matches!(opts.format, PickOutputFormat::Json) {
+        let mut payload = json!({
+            "outcome": result.outcome_key(),
+            "plan": opts.slug,
+            "branch": opts.branch,
+            "target": opts.target,
+            "step": step,
+            "commit": picked.oid.to_string(),
+            "commit_summary": picked.summary,
+        });
+        match result {
+            PickResult::Completed { head, subject } => {
+                payload["head"] = json!(head);
+                payload["subject"] = json!(subject);
+            }
+            PickResult::Conflicted { files } => {
+                payload["conflicts"] = json!(files);
+            }
+            PickResult::GateFailed { reason } => {
+                payload["reason"] = json!(reason);
+            }
+        }
+        println!("{}", serde_json::to_string_pretty(&payload)?);
+        return Ok(());
+    }
+
+    let mut rows = vec![
+        ("Outcome".to_string(), result.label().to_string()),
+        ("Plan".to_string(), opts.slug.clone()),
+        ("Branch".to_string(), opts.branch.clone()),
+        ("Target".to_string(), opts.target.clone()),
+        (
+            "Picked".to_string(),
+            format!(
+                "step {step}: {} {}",
+                short_hash(&picked.oid.to_string()),
+                picked.summary
+            ),
+        ),
+    ];
+    match result {
+        PickResult::Completed { head, subject } => {
+            rows.push(("Head".to_string(), head.clone()));
+            rows.push(("Subject".to_string(), subject.clone()));
+        }
+        PickResult::Conflicted { files } => {
+            rows.push(("Conflicts".to_string(), files.join(", ")));
+            rows.push((
+                "Next".to_string(),
+                format!(
+                    "{} left unchanged; rebase the plan or pick by hand",
+                    opts.target
+                ),
+            ));
+        }
+        PickResult::GateFailed { reason } => {
+            let mut lines = reason.lines();
+            rows.push((
+                "Reason".to_string(),
+                lines.next().unwrap_or_default().to_string(),
+            ));
+            rows.push((
+                "Next".to_string(),
+                format!("{} left unchanged", opts.target),
+            ));
+            println!("{}", format_block(rows));
+            let output = lines.collect::<Vec<_>>().join("\n");
+            if !output.is_empty() {
+                println!();
+                println!("{output}");
+            }
+            return Ok(());
+        }
+    }
+    println!("{}", format_block(rows));
+    Ok(())
+}
@@ -0,0 +1,298 @@
+//! `vizier prompt test`: builds one prompt the way its stage would against the current repo,
+//! prints it with size stats, and optionally sends it to the agent in a throwaway worktree.
+
+use std::fs;
+use std::path::Path;
+use std::time::Instant;
+
+use serde_json::json;
+use uuid::Uuid;
+use vizier_core::never_send::NeverSend;
+use vizier_core::{agent_prompt, auditor::Auditor, config, cost, diff_context, display, i18n, vcs};
+
+use super::shared::format_block;
+use super::types::{PromptTestOptions, PromptTestOutputFormat};
+
+/// Stand-in for the operator text when neither `--input` nor `--input-file` is given.
+const SAMPLE_INPUT: &str = "<operator input; pass --input or --input-file>";
+const SAMPLE_SLUG: &str = "prompt-test";
+
+struct SandboxRun {
+    response: String,
+    changed_files: usize,
+    duration_ms: u128,
+}
+
+pub(crate) async fn run_prompt_test(
+    project_root: &Path,
+    opts: PromptTestOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let cfg = config::get_config();
+    let agent = config::resolve_prompt_profile_for_alias(&cfg, &opts.alias, opts.kind, None)?;
+    let selection = agent
+        .prompt_selection()
+        .cloned()
+        .unwrap_or_else(|| cfg.prompt_for_alias(&opts.alias, opts.kind));
+
+    let (system_prompt, user_message) =
+        build_prompt(project_root, &opts, &selection, &agent.documentation, &cfg)?;
+    let prompt = i18n::with_language_directive(Auditor::render_prompt(
+        &system_prompt,
+        &user_message,
+        Some(opts.kind),
+    ));
+    let bytes = prompt.len();
+    let lines = prompt.lines().count();
+    let tokens = cost::estimate_tokens(&prompt);
+
+    let sandbox = if opts.run {
+        Some(
+            run_in_sandbox(
+                project_root,
+                &agent,
+                opts.kind,
+                system_prompt.clone(),
+                user_message.clone(),
+            )
+            .await?,
+        )
+    } else {
+        None
+    };
+
+    let source = match &selection.source_path {
+        Some(path) => format!("{} ({})", selection.origin.label(), path.display()),
+        None => selection.origin.label().to_string(),
+    };
+
+    match opts.format {
+        PromptTestOutputFormat::Json => {
+            let payload = json!({
+                "outcome": if sandbox.is_some() { "prompt_test_ran" } else { "prompt_test_rendered" },
+                "prompt": opts.kind.as_str(),
+                "scope": opts.alias.as_str(),
+                "origin": selection.origin.label(),
+                "source_path": selection.source_path.as_ref().map(|path| path.display().to_string()),
+                "agent": agent.agent_runtime.label,
+                "command": agent.agent_runtime.command,
+                "bytes": bytes,
+                "lines": lines,
+                "estimated_tokens": tokens,
+                "text": prompt,
+                "run": sandbox.as_ref().map(|run| json!({
+                    "response": run.response,
+                    "changed_files": run.changed_files,
+                    "duration_ms": run.duration_ms,
+                })),
+            });
+            println!("{}", serde_json::to_string_pretty(&payload)?);
+        }
+        PromptTestOutputFormat::Text => {
+            println!(
+                "Outcome: vizier prompt test: {} prompt rendered for `{}`",
+                opts.kind.as_str(),
+                opts.alias.as_str()
+            );
+            let mut rows = vec![
+                ("Source".to_string(), source),
+                (
+                    "Agent".to_string(),
+                    format!(
+                        "{} ({})",
+                        agent.agent_runtime.label,
+                        agent.agent_runtime.command.join(" ")
+                    ),
+                ),
+                (
+                    "Size".to_string(),
+                    format!(
+                        "{} bytes, {} lines, ~{} tokens",
+                        display::format_number(bytes),
+                        display::format_number(lines),
+                        display::format_number(tokens as usize)
+                    ),
+                ),
+            ];
+            if let Some(run) = &sandbox {
+                rows.push((
+                    "Run".to_string(),
+                    format!(
+                        "{} ms, {} file(s) changed in the sandbox (discarded)",
+                        run.duration_ms, run.changed_files
+                    ),
+                ));
+            }
+            println!("{}", format_block(rows));
+            println!("{}", prompt.trim_end());
+            if let Some(run) = &sandbox {
+                println!();
+                println!("Response:");
+                println!("{}", run.response.trim_end());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// System prompt and user message for the chosen kind, filled from `--input`, `--plan`, and the
+/// working tree, with placeholders where the operator gave nothing.
+fn build_prompt(
+    project_root: &Path,
+    opts: &PromptTestOptions,
+    selection: &config::PromptSelection,
+    documentation: &config::DocumentationSettings,
+    cfg: &config::Config,
+) -> Result<(String, String), Box<dyn std::error::Error>> {
+    let input = opts.input.as_deref().unwrap_or(SAMPLE_INPUT);
+    let (slug, branch) = match &opts.plan {
+        Some((slug, branch)) => (slug.clone(), branch.clone()),
+        None => (
+            SAMPLE_SLUG.to_string(),
+            vizier_core::plan::default_branch_for_slug(SAMPLE_SLUG),
+        ),
+    };
+    let target = opts
+        .target
+        .clone()
+        .or_else(|| vcs::detect_primary_branch_in(project_root))
+        .unwrap_or_else(|| "main".to_string());
+    let plan_document = opts.plan.as_ref().and_then(|(slug, branch)| {
+        vcs::read_blob_at_revision_in(
+            project_root,
+            &format!(
+                "{branch}:{}",
+                vizier_core::plan::plan_rel_path(slug).to_string_lossy()
+            ),
+        )
+        .ok()
+    });
+
+    let system_prompt = match opts.kind {
+        config::PromptKind::Documentation => {
+            agent_prompt::build_documentation_prompt(Some(selection), input, documentation)?
+        }
+        config::PromptKind::Commit => {
+            let diff = match &opts.input {
+                Some(input) => input.clone(),
+                None => {
+                    let diff = vcs::get_diff(&project_root.to_string_lossy(), None, None)?;
+                    NeverSend::from_config(project_root).filter_diff(&diff).0
+                }
+            };
+            return Ok((selection.text.clone(), diff));
+        }
+        config::PromptKind::ImplementationPlan => agent_prompt::build_implementation_plan_prompt(
+            selection,
+            agent_prompt::ImplementationPlanPromptInput {
+                plan_id: SAMPLE_SLUG,
+                plan_slug: &slug,
+                branch_name: &branch,
+                operator_spec: input,
+                documentation,
+            },
+        )?,
+        config::PromptKind::Review => {
+            let diff_summary = if opts.plan.is_some() {
+                diff_context::diff_context_for_spec(
+                    project_root,
+                    &format!("{target}..{branch}"),
+                    &cfg.workflow.diff_context,
+                    |_| Err("prompt test does not summarize diffs".to_string()),
+                )
+                .map(|context| context.text)
+                .unwrap_or_default()
+            } else {
+                String::new()
+            };
+            agent_prompt::build_review_prompt(
+                selection,
+                agent_prompt::ReviewPromptInput {
+                    plan_id: None,
+                    plan_slug: &slug,
+                    branch_name: &branch,
+                    target_branch: &target,
+                    plan_document: plan_document.as_deref().unwrap_or(input),
+                    diff_summary: &diff_summary,
+                    check_results: &[],
+                    cicd_gate: None,
+                    documentation,
+                },
+            )?
+        }
+        config::PromptKind::MergeConflict => {
+            let conflicts = opts
+                .input
+                .as_deref()
+                .map(|input| {
+                    input
+                        .lines()
+                        .map(str::trim)
+                        .filter(|line| !line.is_empty())
+                        .map(str::to_string)
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            agent_prompt::build_merge_conflict_prompt(
+                selection,
+                &target,
+                &branch,
+                &conflicts,
+                plan_document.as_deref(),
+                documentation,
+            )?
+        }
+    };
+    Ok((system_prompt, String::new()))
+}
+
+/// Runs the prompt in a worktree on a temporary branch cut from HEAD, then removes both, so
+/// whatever the agent edits never reaches the operator's checkout or history.
+async fn run_in_sandbox(
+    project_root: &Path,
+    agent: &config::AgentSettings,
+    kind: config::PromptKind,
+    system_prompt: String,
+    user_message: String,
+) -> Result<SandboxRun, Box<dyn std::error::Error>> {
+    let id = Uuid::new_v4().simple().to_string();
+    let short = &id[..8];
+    let branch = format!("vizier-prompt-test-{short}");
+    let worktree_name = format!("vizier-prompt-test-{short}");
+    let worktree_path =
+        vizier_core::worktrees::worktrees_root(project_root).join(format!("prompt-test-{short}"));
+    if let Some(parent) = worktree_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    vcs::create_branch_from_head_in(project_root, &branch)?;
+    if let Err(err) =
+        vcs::add_worktree_for_branch_in(project_root, &worktree_name, &worktree_path, &branch)
+    {
+        let _ = vcs::delete_branch_in(project_root, &branch);
+        return Err(format!("prompt test could not create its sandbox worktree: {err}").into());
+    }
+
+    let started = Instant::now();
+    let result = Auditor::llm_request_with_tools(
+        agent,
+        Some(kind),
+        system_prompt,
+        user_message,
+        Some(worktree_path.clone()),
+    )
+    .await;
+    let duration_ms = started.elapsed().as_millis();
+    let changed_files = vcs::get_diff(&worktree_path.to_string_lossy(), None, None)
+        .map(|diff| diff.matches("diff --git ").count())
+        .unwrap_or(0);
+
+    let _ = vcs::remove_worktree_in(project_root, &worktree_name, true);
+    let _ = fs::remove_dir_all(&worktree_path);
+    let _ = vcs::delete_branch_in(project_root, &branch);
+
+    let message = result?;
+    Ok(SandboxRun {
+        response: message.content,
+        changed_files,
+        duration_ms,
+    })
+}
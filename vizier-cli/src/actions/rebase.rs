@@ -0,0 +1,376 @@
+use std::fs;
+use std::path::Path;
+
+use git2::{BranchType, Oid, Repository};
+use serde_json::json;
+use vizier_core::{display, review_ledger, vcs};
+
+use crate::cli::prompt::prompt_yes_no;
+use crate::jobs;
+use crate::plan::{self, PlanWorktree};
+
+use super::shared::{format_block, short_hash};
+use super::types::{RebaseOptions, RebaseOutputFormat};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum RebaseResult {
+    UpToDate,
+    Completed {
+        head: String,
+        applied: usize,
+        resolved: Vec<String>,
+        plan_updated: bool,
+    },
+    Conflicted {
+        commit: String,
+        summary: String,
+        files: Vec<String>,
+        reason: String,
+    },
+}
+
+impl RebaseResult {
+    fn outcome_key(&self) -> &'static str {
+        match self {
+            Self::UpToDate => "rebase_up_to_date",
+            Self::Completed { .. } => "rebase_completed",
+            Self::Conflicted { .. } => "rebase_conflicted",
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::UpToDate => "Already up to date",
+            Self::Completed { .. } => "Plan branch rebased",
+            Self::Conflicted { .. } => "Rebase blocked by conflicts",
+        }
+    }
+}
+
+pub(crate) fn run_rebase(
+    project_root: &Path,
+    opts: RebaseOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    for branch in [&opts.branch, &opts.target] {
+        if !vcs::branch_exists_in(project_root, branch)? {
+            return Err(format!("branch `{branch}` does not exist").into());
+        }
+    }
+
+    let divergence = vcs::branch_divergence_in(project_root, &opts.branch, &opts.target)?;
+    if divergence.behind == 0 {
+        return emit_rebase_report(&opts, divergence.behind, &RebaseResult::UpToDate, None);
+    }
+
+    let base_commit = Repository::open(project_root)?
+        .find_branch(&opts.target, BranchType::Local)?
+        .get()
+        .peel_to_commit()?
+        .id()
+        .to_string();
+
+    let setup = display::start_phase(display::PHASE_WORKTREE_SETUP, None);
+    let worktree = match vcs::external_worktree_for_branch_in(project_root, &opts.branch)? {
+        Some(existing) => adopt_external_worktree(&opts, existing)?,
+        None => PlanWorktree::create(&opts.slug, &opts.branch, "rebase")?,
+    };
+    setup.finish();
+    let result = rebase_in_worktree(&opts, &worktree, &base_commit);
+    if let Err(err) = worktree.cleanup() {
+        display::warn(format!("unable to remove rebase worktree: {err}"));
+    }
+    let result = result?;
+
+    let since_review = match result {
+        RebaseResult::Completed { .. } => review_delta_after_rebase(project_root, &opts),
+        _ => None,
+    };
+    emit_rebase_report(&opts, divergence.behind, &result, since_review.as_ref())?;
+    if matches!(result, RebaseResult::Conflicted { .. }) {
+        crate::cli::outcome::exit(10);
+    }
+    Ok(())
+}
+
+// Git refuses a second checkout of a branch, so a plan branch the operator already has open in
+// their own worktree is rebased there instead. The rebase rewrites that checkout, so it must be
+// clean and the operator must agree.
+fn adopt_external_worktree(
+    opts: &RebaseOptions,
+    existing: vcs::ExternalWorktree,
+) -> Result<PlanWorktree, Box<dyn std::error::Error>> {
+    let location = existing.path.display().to_string();
+    vcs::ensure_clean_worktree_in(&existing.path).map_err(|err| {
+        format!(
+            "branch `{}` is checked out in {location} and that {err}; commit or stash there first",
+            opts.branch
+        )
+    })?;
+    if !opts.assume_yes {
+        let prompt = format!(
+            "Branch `{}` is checked out in {location}. Rebase it in place?",
+            opts.branch
+        );
+        if !prompt_yes_no(&prompt)? {
+            return Err(format!(
+                "aborted; release {location} (for example `git worktree remove {location}`) or \
+                 rerun with --yes"
+            )
+            .into());
+        }
+    }
+    display::info(format!("Reusing worktree {location} for `{}`", opts.branch));
+    Ok(PlanWorktree::adopt(existing))
+}
+
+// Replays the plan branch inside a throwaway worktree so the operator's checkout is never left
+// mid-rebase. Any stop short of completion aborts the rebase, leaving the branch untouched.
+fn rebase_in_worktree(
+    opts: &RebaseOptions,
+    worktree: &PlanWorktree,
+    base_commit: &str,
+) -> Result<RebaseResult, Box<dyn std::error::Error>> {
+    let path = worktree.path();
+    let mut resolved = Vec::new();
+    let mut progress = vcs::start_rebase_in(path, &opts.target)?;
+    let (head, applied) = loop {
+        let (commit, summary, files) = match progress {
+            vcs::RebaseProgress::Completed { head, applied } => break (head, applied),
+            vcs::RebaseProgress::Conflicted {
+                commit,
+                summary,
+                files,
+            } => (commit, summary, files),
+        };
+
+        let blocked = |reason: String, files: Vec<String>| {
+            if let Err(err) = vcs::abort_rebase_in(path) {
+                display::warn(format!("unable to abort rebase: {err}"));
+            }
+            Ok(RebaseResult::Conflicted {
+                commit: commit.to_string(),
+                summary: summary.clone(),
+                files,
+                reason,
+            })
+        };
+
+        if !opts.auto_resolve {
+            return blocked(
+                "auto-resolve disabled (--no-auto-resolve)".to_string(),
+                files,
+            );
+        }
+
+        display::info(format!(
+            "Conflicts replaying {} ({summary}): {}; invoking merge-conflict agent",
+            short_hash(&commit.to_string()),
+            files.join(", ")
+        ));
+        let agent_phase = display::start_phase(display::PHASE_AGENT_RUN, None);
+        let resolution = jobs::resolve_conflicts_with_merge_agent(
+            path,
+            &opts.target,
+            &opts.branch,
+            &opts.slug,
+            &files,
+        );
+        agent_phase.finish();
+        if let Err(reason) = resolution {
+            display::warn(reason.clone());
+            return blocked(reason, files);
+        }
+
+        let staged = files.iter().map(String::as_str).collect::<Vec<_>>();
+        vcs::stage_paths_allow_missing_in(path, &staged)?;
+        let remaining = vcs::list_conflicted_paths_in(path)?;
+        if !remaining.is_empty() {
+            return blocked(
+                "merge-conflict agent left unmerged paths".to_string(),
+                remaining,
+            );
+        }
+        resolved.extend(files);
+        progress = vcs::continue_rebase_in(path)?;
+    };
+
+    let commit_phase = display::start_phase(display::PHASE_COMMIT, None);
+    let plan_commit = record_plan_base(opts, worktree, base_commit)?;
+    commit_phase.finish();
+    let plan_updated = plan_commit.is_some();
+    let head = plan_commit.unwrap_or(head);
+
+    Ok(RebaseResult::Completed {
+        head: head.to_string(),
+        applied,
+        resolved,
+        plan_updated,
+    })
+}
+
+// Stamps the new base into the plan front matter so later stages (and humans) can see what
+// the draft was last rebased onto.
+fn record_plan_base(
+    opts: &RebaseOptions,
+    worktree: &PlanWorktree,
+    base_commit: &str,
+) -> Result<Option<Oid>, Box<dyn std::error::Error>> {
+    let plan_path = worktree.plan_path(&opts.slug);
+    if !plan_path.is_file() {
+        display::warn(format!(
+            "plan document {} not found on {}; skipping front-matter update",
+            plan::plan_rel_path(&opts.slug).display(),
+            opts.branch
+        ));
+        return Ok(None);
+    }
+
+    let document = fs::read_to_string(&plan_path)?;
+    let updated = plan::set_front_matter_fields(
+        &document,
+        &[
+            ("base_branch", opts.target.as_str()),
+            ("base_commit", base_commit),
+        ],
+    )?;
+    if updated == document {
+        return Ok(None);
+    }
+    plan::write_plan_file(&plan_path, &updated)?;
+
+    let plan_rel = plan::plan_rel_path(&opts.slug)
+        .to_string_lossy()
+        .replace('\\', "/");
+    vcs::stage_paths_allow_missing_in(worktree.path(), &[plan_rel.as_str()])?;
+    let oid = vcs::commit_staged_in(
+        worktree.path(),
+        &format!(
+            "docs: record plan {} base {}@{}",
+            opts.slug,
+            opts.target,
+            short_hash(base_commit)
+        ),
+        false,
+    )?;
+    Ok(Some(oid))
+}
+
+// A branch that was reviewed before the rebase gets a range-diff against the reviewed tip, so
+// the operator sees what the replay changed before asking for another review.
+fn review_delta_after_rebase(
+    project_root: &Path,
+    opts: &RebaseOptions,
+) -> Option<review_ledger::ReviewDelta> {
+    match review_ledger::review_delta(project_root, project_root, &opts.target, &opts.branch) {
+        Ok((review_ledger::ReviewDelta::FirstReview, _)) => None,
+        Ok((delta, _)) => Some(delta),
+        Err(err) => {
+            display::warn(format!(
+                "unable to compute range-diff since last review: {err}"
+            ));
+            None
+        }
+    }
+}
+
+fn emit_rebase_report(
+    opts: &RebaseOptions,
+    behind: usize,
+    result: &RebaseResult,
+    since_review: Option<&review_ledger::ReviewDelta>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let since_review =
+        since_review.map(|delta| review_ledger::render_review_delta(delta, &opts.branch));
+    if matches!(opts.format, RebaseOutputFormat::Json) {
+        let mut payload = json!({
+            "outcome": result.outcome_key(),
+            "plan": opts.slug,
+            "branch": opts.branch,
+            "target": opts.target,
+            "commits_behind": behind,
+        });
+        match result {
+            RebaseResult::UpToDate => {}
+            RebaseResult::Completed {
+                head,
+                applied,
+                resolved,
+                plan_updated,
+            } => {
+                payload["head"] = json!(head);
+                payload["applied"] = json!(applied);
+                payload["resolved"] = json!(resolved);
+                payload["plan_updated"] = json!(plan_updated);
+                payload["range_diff_since_review"] = json!(since_review);
+            }
+            RebaseResult::Conflicted {
+                commit,
+                summary,
+                files,
+                reason,
+            } => {
+                payload["commit"] = json!(commit);
+                payload["commit_summary"] = json!(summary);
+                payload["conflicts"] = json!(files);
+                payload["reason"] = json!(reason);
+            }
+        }
+        println!("{}", serde_json::to_string_pretty(&payload)?);
+        return Ok(());
+    }
+
+    let mut rows = vec![
+        ("Outcome".to_string(), result.label().to_string()),
+        ("Plan".to_string(), opts.slug.clone()),
+        ("Branch".to_string(), opts.branch.clone()),
+        ("Target".to_string(), opts.target.clone()),
+        ("Behind".to_string(), behind.to_string()),
+    ];
+    match result {
+        RebaseResult::UpToDate => {}
+        RebaseResult::Completed {
+            head,
+            applied,
+            resolved,
+            plan_updated,
+        } => {
+            rows.push(("Head".to_string(), head.clone()));
+            rows.push(("Replayed".to_string(), applied.to_string()));
+            if !resolved.is_empty() {
+                rows.push(("Resolved".to_string(), resolved.join(", ")));
+            }
+            rows.push((
+                "Plan doc".to_string(),
+                if *plan_updated {
+                    "base recorded"
+                } else {
+                    "unchanged"
+                }
+                .to_string(),
+            ));
+        }
+        RebaseResult::Conflicted {
+            commit,
+            summary,
+            files,
+            reason,
+        } => {
+            rows.push((
+                "Stopped at".to_string(),
+                format!("{} {summary}", short_hash(commit)),
+            ));
+            rows.push(("Conflicts".to_string(), files.join(", ")));
+            rows.push(("Reason".to_string(), reason.clone()));
+            rows.push((
+                "Next".to_string(),
+                format!("{} left unchanged; resolve by hand or rerun", opts.branch),
+            ));
+        }
+    }
+    println!("{}", format_block(rows));
+    if let Some(text) = since_review {
+        println!();
+        print!("{text}");
+    }
+    Ok(())
+}
@@ -9,7 +9,7 @@ use vizier_core::{
     vcs::{self, ReleaseBump, ReleaseCommit, ReleaseNotes, ReleaseTag, ReleaseVersion},
 };
 
-use super::shared::format_block;
+use super::shared::{format_block, short_hash};
 use crate::cli::args::ReleaseCmd;
 use crate::cli::prompt::prompt_yes_no;
 
@@ -463,22 +463,25 @@ fn print_release_failure(
                 format!(
                     "restored {} to {}",
                     txn.branch_name,
-                    short_oid(txn.start_head)
+                    short_hash(&txn.start_head.to_string())
                 )
             } else {
                 format!(
                     "FAILED to restore {} to {}",
                     txn.branch_name,
-                    short_oid(txn.start_head)
+                    short_hash(&txn.start_head.to_string())
                 )
             },
         ),
         (
             "Worktree rollback".to_string(),
             if rollback.worktree_restored {
-                format!("restored to {}", short_oid(txn.start_head))
+                format!("restored to {}", short_hash(&txn.start_head.to_string()))
             } else {
-                format!("FAILED to restore to {}", short_oid(txn.start_head))
+                format!(
+                    "FAILED to restore to {}",
+                    short_hash(&txn.start_head.to_string())
+                )
             },
         ),
     ];
@@ -561,11 +564,6 @@ fn repository_state_message(state: RepositoryState) -> Option<&'static str> {
     }
 }
 
-fn short_oid(oid: Oid) -> String {
-    let text = oid.to_string();
-    text.chars().take(8).collect()
-}
-
 fn commit_range_label(last_tag: Option<&ReleaseTag>) -> String {
     match last_tag {
         Some(tag) => format!("{}..HEAD", tag.name),
@@ -755,7 +753,7 @@ fn print_release_outcome(
     let mut rows = vec![
         ("Outcome".to_string(), "Release complete".to_string()),
         ("Version".to_string(), format!("v{}", plan.next_version)),
-        ("Commit".to_string(), short_oid(commit_oid)),
+        ("Commit".to_string(), short_hash(&commit_oid.to_string())),
         (
             "Tag".to_string(),
             if tag_created {
@@ -0,0 +1,402 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use git2::{BranchType, Repository};
+use serde_json::{Value, json};
+use uuid::Uuid;
+use vizier_core::{config, display, sessions, vcs};
+
+use crate::cli::args::{RunCmd, RunFormatArg};
+
+use super::shared::format_block;
+use super::workflow_preflight::{plan_branch_refs, prepare_workflow_template};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FetchedBranch {
+    branch: String,
+    head: String,
+    status: &'static str,
+}
+
+/// Runs `cmd` on the `[remote]` build host: pushes the plan branches the workflow touches,
+/// executes `vizier run --follow` over SSH with output streamed through, then fetches the
+/// branches and the run's session logs back.
+pub(crate) fn run_remote_workflow(
+    project_root: &Path,
+    cmd: RunCmd,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let cfg = config::get_config();
+    let remote = cfg.remote.clone();
+    let (Some(host), Some(path)) = (remote.host.clone(), remote.path.clone()) else {
+        return Err("`vizier run --remote` needs [remote] host and path in the config".into());
+    };
+
+    // Resolve locally first so template and input errors surface before anything is pushed.
+    let prepared = prepare_workflow_template(project_root, &cmd.flow, &cmd.inputs, &cmd.set, &cfg)?;
+    let branches = plan_branch_refs(&prepared.template)
+        .into_iter()
+        .map(|plan_ref| plan_ref.branch)
+        .collect::<BTreeSet<_>>();
+
+    let mut pushed = Vec::new();
+    for branch in &branches {
+        if !vcs::branch_exists_in(project_root, branch)? {
+            continue;
+        }
+        display::log(
+            display::Subsystem::Git,
+            display::LogLevel::Info,
+            format!("Pushing {branch} to {}", remote.git_remote),
+        );
+        vcs::push_branch_in(project_root, &remote.git_remote, branch)?;
+        pushed.push(branch.clone());
+    }
+
+    let marker = format!(".vizier/tmp/remote-{}", Uuid::new_v4().simple());
+    let script = remote_run_script(
+        &path,
+        &remote.git_remote,
+        &remote.vizier,
+        &marker,
+        &pushed,
+        &branches,
+        &remote_run_args(&cmd),
+    );
+    display::info(format!("Running `{}` on {host}", cmd.flow));
+    let json_mode = matches!(cmd.format, RunFormatArg::Json);
+    let mut ssh = ssh_command(&remote.ssh, &host, &script);
+    ssh.stdin(Stdio::null()).stderr(Stdio::inherit());
+    let (exit_code, run_output) = if json_mode {
+        let output = ssh.stdout(Stdio::piped()).output()?;
+        let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        let run = serde_json::from_str::<Value>(&stdout).unwrap_or(Value::String(stdout));
+        (output.status.code(), Some(run))
+    } else {
+        (ssh.stdout(Stdio::inherit()).status()?.code(), None)
+    };
+    // ssh reports 255 for its own connection failures; a missing code means it was signalled.
+    let exit_code = exit_code.unwrap_or(1);
+
+    let copied_sessions =
+        match copy_remote_sessions(project_root, &remote.ssh, &host, &path, &marker) {
+            Ok(copied) => copied,
+            Err(err) => {
+                display::warn(format!("unable to copy session logs from {host}: {err}"));
+                Vec::new()
+            }
+        };
+
+    let mut fetched = Vec::new();
+    for branch in &branches {
+        match fetch_back(project_root, &remote.git_remote, branch) {
+            Ok(result) => fetched.push(result),
+            // Branches the remote run never created have nothing to fetch.
+            Err(err) if !pushed.contains(branch) => {
+                display::log(
+                    display::Subsystem::Git,
+                    display::LogLevel::Debug,
+                    format!("not fetching {branch}: {err}"),
+                );
+            }
+            Err(err) => display::log(
+                display::Subsystem::Git,
+                display::LogLevel::Warn,
+                format!("unable to fetch {branch}: {err}"),
+            ),
+        }
+    }
+
+    let succeeded = exit_code == 0;
+    if json_mode {
+        let payload = json!({
+            "outcome": if succeeded { "remote_run_succeeded" } else { "remote_run_failed" },
+            "host": host,
+            "path": path,
+            "flow": cmd.flow,
+            "exit_code": exit_code,
+            "pushed": pushed,
+            "branches": fetched.iter().map(|entry| json!({
+                "branch": entry.branch,
+                "head": entry.head,
+                "status": entry.status,
+            })).collect::<Vec<_>>(),
+            "sessions": copied_sessions,
+            "run": run_output,
+        });
+        println!("{}", serde_json::to_string_pretty(&payload)?);
+    } else {
+        let describe = |values: Vec<String>| {
+            if values.is_empty() {
+                "none".to_string()
+            } else {
+                values.join(", ")
+            }
+        };
+        let rows = vec![
+            (
+                "Outcome".to_string(),
+                if succeeded {
+                    "Remote run succeeded".to_string()
+                } else {
+                    "Remote run failed".to_string()
+                },
+            ),
+            ("Host".to_string(), format!("{host}:{path}")),
+            ("Flow".to_string(), cmd.flow.clone()),
+            ("Exit code".to_string(), exit_code.to_string()),
+            ("Pushed".to_string(), describe(pushed)),
+            (
+                "Fetched".to_string(),
+                describe(
+                    fetched
+                        .iter()
+                        .map(|entry| format!("{} ({})", entry.branch, entry.status))
+                        .collect(),
+                ),
+            ),
+            ("Sessions".to_string(), describe(copied_sessions)),
+        ];
+        println!("{}", format_block(rows));
+    }
+
+    if succeeded {
+        Ok(())
+    } else {
+        crate::cli::outcome::exit(exit_code)
+    }
+}
+
+// Follow is forced: the remote side has no operator to watch a detached run.
+fn remote_run_args(cmd: &RunCmd) -> Vec<String> {
+    let mut args = vec!["run".to_string(), cmd.flow.clone()];
+    args.extend(cmd.inputs.iter().cloned());
+    for set in &cmd.set {
+        args.push("--set".to_string());
+        args.push(set.clone());
+    }
+    args.push("--follow".to_string());
+    if cmd.strict {
+        args.push("--strict".to_string());
+    }
+    if cmd.strict_overlap {
+        args.push("--strict-overlap".to_string());
+    }
+    if cmd.no_gen_tests {
+        args.push("--no-gen-tests".to_string());
+    }
+    if cmd.fix_loop {
+        args.push("--fix-loop".to_string());
+    }
+    if cmd.require_approval {
+        args.push("--require-approval".to_string());
+    }
+    if cmd.no_require_approval {
+        args.push("--no-require-approval".to_string());
+    }
+    if let Some(priority) = cmd.priority {
+        args.push(format!("--priority={priority}"));
+    }
+    if matches!(cmd.format, RunFormatArg::Json) {
+        args.extend(["--format".to_string(), "json".to_string()]);
+    }
+    args
+}
+
+fn remote_run_script(
+    path: &str,
+    git_remote: &str,
+    vizier: &str,
+    marker: &str,
+    pushed: &[String],
+    branches: &BTreeSet<String>,
+    run_args: &[String],
+) -> String {
+    let mut lines = vec!["set -e".to_string(), format!("cd {}", shell_quote(path))];
+    for branch in pushed {
+        lines.push(format!(
+            "git fetch --quiet {} {}",
+            shell_quote(git_remote),
+            shell_quote(&format!("+refs/heads/{branch}:refs/heads/{branch}"))
+        ));
+    }
+    lines.push("mkdir -p .vizier/tmp".to_string());
+    lines.push(format!("touch {}", shell_quote(marker)));
+    lines.push("set +e".to_string());
+    lines.push(
+        std::iter::once(vizier)
+            .chain(run_args.iter().map(String::as_str))
+            .map(shell_quote)
+            .collect::<Vec<_>>()
+            .join(" "),
+    );
+    lines.push("status=$?".to_string());
+    for branch in branches {
+        lines.push(format!(
+            "if git rev-parse --quiet --verify {ref_name} >/dev/null; then git push --quiet {remote} {branch} || echo {warning} >&2; fi",
+            ref_name = shell_quote(&format!("refs/heads/{branch}")),
+            remote = shell_quote(git_remote),
+            branch = shell_quote(branch),
+            warning = shell_quote(&format!("vizier: unable to push {branch} from the remote host")),
+        ));
+    }
+    lines.push("exit $status".to_string());
+    lines.join("\n")
+}
+
+fn ssh_command(ssh: &[String], host: &str, script: &str) -> Command {
+    let mut command = Command::new(&ssh[0]);
+    // The remote login shell may not be POSIX; run the script under sh explicitly.
+    command
+        .args(&ssh[1..])
+        .arg(host)
+        .arg(format!("sh -c {}", shell_quote(script)));
+    command
+}
+
+fn shell_quote(value: &str) -> String {
+    if !value.is_empty()
+        && value
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || "-_./=:+@%,".contains(ch))
+    {
+        return value.to_string();
+    }
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+// Streams every session directory created after `marker` back as a tar archive and unpacks it
+// under the local sessions root.
+fn copy_remote_sessions(
+    project_root: &Path,
+    ssh: &[String],
+    host: &str,
+    path: &str,
+    marker: &str,
+) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let script = format!(
+        "cd {path} || exit 1\nif [ -d .vizier/sessions ] && [ -f {cleanup} ]; then (cd .vizier/sessions && find . -mindepth 1 -maxdepth 1 -type d -newer {marker} | tar -cf - -T -); fi\nrm -f {cleanup}",
+        path = shell_quote(path),
+        marker = shell_quote(&format!("../../{marker}")),
+        cleanup = shell_quote(marker),
+    );
+    let archive = ssh_command(ssh, host, &script)
+        .stdin(Stdio::null())
+        .stderr(Stdio::inherit())
+        .output()?;
+    if !archive.status.success() {
+        return Err(format!("remote exited with {}", archive.status).into());
+    }
+    if archive.stdout.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let sessions_root = sessions::sessions_root(project_root);
+    fs::create_dir_all(&sessions_root)?;
+    let before = session_dirs(&sessions_root)?;
+    let mut tar = Command::new("tar")
+        .arg("-xf")
+        .arg("-")
+        .arg("-C")
+        .arg(&sessions_root)
+        .stdin(Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = tar.stdin.take() {
+        std::io::Write::write_all(&mut stdin, &archive.stdout)?;
+    }
+    let status = tar.wait()?;
+    if !status.success() {
+        return Err(format!("tar exited with {status}").into());
+    }
+    Ok(session_dirs(&sessions_root)?
+        .difference(&before)
+        .cloned()
+        .collect())
+}
+
+fn session_dirs(root: &Path) -> Result<BTreeSet<String>, std::io::Error> {
+    let mut dirs = BTreeSet::new();
+    for entry in fs::read_dir(root)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            dirs.insert(entry.file_name().to_string_lossy().into_owned());
+        }
+    }
+    Ok(dirs)
+}
+
+// Fetches `branch` and fast-forwards the local branch when it is behind; a diverged local
+// branch is left alone so the fetched result stays on the remote-tracking ref for inspection.
+fn fetch_back(
+    project_root: &Path,
+    git_remote: &str,
+    branch: &str,
+) -> Result<FetchedBranch, Box<dyn std::error::Error>> {
+    let fetched = vcs::fetch_branch_in(project_root, git_remote, branch)?;
+    let repo = Repository::open(project_root)?;
+    let status = match repo.find_branch(branch, BranchType::Local) {
+        Err(_) => {
+            repo.branch(branch, &repo.find_commit(fetched)?, false)?;
+            "created"
+        }
+        Ok(local) => match local.get().target() {
+            Some(current) if current == fetched => "unchanged",
+            Some(current) if repo.graph_descendant_of(fetched, current)? => {
+                vcs::fast_forward_branch_in(project_root, branch, current, fetched)?;
+                "fast-forwarded"
+            }
+            _ => {
+                display::warn(format!(
+                    "{branch} diverged from {git_remote}/{branch}; local branch left unchanged"
+                ));
+                "diverged"
+            }
+        },
+    };
+    Ok(FetchedBranch {
+        branch: branch.to_string(),
+        head: fetched.to_string(),
+        status,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remote_run_script_syncs_branches_and_quotes_arguments() {
+        let branches = BTreeSet::from(["draft/alpha".to_string()]);
+        let script = remote_run_script(
+            "/srv/my repo",
+            "origin",
+            "vizier",
+            ".vizier/tmp/remote-1",
+            &["draft/alpha".to_string()],
+            &branches,
+            &[
+                "run".to_string(),
+                "approve".to_string(),
+                "alpha".to_string(),
+                "--set".to_string(),
+                "commit_message=it's done".to_string(),
+            ],
+        );
+        let lines = script.lines().collect::<Vec<_>>();
+        assert_eq!(lines[1], "cd '/srv/my repo'");
+        assert_eq!(
+            lines[2],
+            "git fetch --quiet origin +refs/heads/draft/alpha:refs/heads/draft/alpha"
+        );
+        assert!(
+            script.contains("vizier run approve alpha --set 'commit_message=it'\\''s done'"),
+            "{script}"
+        );
+        let push = script
+            .find("git push --quiet origin draft/alpha")
+            .expect("push back");
+        assert!(push > script.find("status=$?").unwrap());
+        assert!(script.ends_with("exit $status"));
+    }
+}
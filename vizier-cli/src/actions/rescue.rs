@@ -0,0 +1,351 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+
+use serde_json::json;
+use vizier_core::{display, temp_registry, vcs};
+
+use crate::cli::prompt::prompt_yes_no;
+use crate::jobs;
+
+use super::shared::format_block;
+use super::types::{RescueAction, RescueOptions, RescueOutputFormat};
+
+const DEFAULT_RESCUE_COMMIT_MESSAGE: &str = "chore: rescue interrupted workflow changes";
+
+#[derive(Debug, Clone)]
+struct PreservedWorktree {
+    path: PathBuf,
+    job_id: String,
+    job_status: jobs::JobStatus,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum RescueResult {
+    Inspected,
+    Clean,
+    Committed { commit: String },
+    Discarded { entries: usize },
+    PatchWritten { path: PathBuf },
+}
+
+impl RescueResult {
+    fn outcome_key(&self) -> &'static str {
+        match self {
+            Self::Inspected => "rescue_inspected",
+            Self::Clean => "rescue_clean",
+            Self::Committed { .. } => "rescue_committed",
+            Self::Discarded { .. } => "rescue_discarded",
+            Self::PatchWritten { .. } => "rescue_patch_written",
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Inspected => "Preserved changes found",
+            Self::Clean => "No uncommitted changes",
+            Self::Committed { .. } => "Changes committed",
+            Self::Discarded { .. } => "Changes discarded",
+            Self::PatchWritten { .. } => "Patch written",
+        }
+    }
+}
+
+pub(crate) fn run_rescue(
+    project_root: &Path,
+    opts: RescueOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let jobs_root = jobs::ensure_jobs_root(project_root)?;
+    let candidates = find_preserved_worktrees(project_root, &jobs_root, &opts)?;
+    let Some(worktree) = candidates.first().cloned() else {
+        return Err(format!(
+            "no preserved worktree found for plan `{}` (branch `{}`)",
+            opts.slug, opts.branch
+        )
+        .into());
+    };
+    if candidates.len() > 1 {
+        display::warn(format!(
+            "{} preserved worktrees found for `{}`; rescuing the most recent ({})",
+            candidates.len(),
+            opts.slug,
+            worktree.job_id
+        ));
+    }
+
+    let status = vcs::status_with_branch(&worktree.path)?;
+    let changes = status
+        .lines()
+        .skip(1)
+        .map(|line| line.to_string())
+        .collect::<Vec<_>>();
+
+    let mut reported = false;
+    let action = if changes.is_empty() {
+        None
+    } else {
+        match opts.action {
+            Some(action) => Some(action),
+            None if matches!(opts.format, RescueOutputFormat::Text)
+                && io::stdin().is_terminal() =>
+            {
+                emit_rescue_report(&opts, &worktree, &changes, &RescueResult::Inspected)?;
+                reported = true;
+                println!();
+                prompt_rescue_action()?
+            }
+            None => None,
+        }
+    };
+
+    let result = match action {
+        _ if changes.is_empty() => RescueResult::Clean,
+        None => RescueResult::Inspected,
+        Some(RescueAction::Commit) => commit_rescued_changes(&opts, &worktree)?,
+        Some(RescueAction::Discard) => discard_rescued_changes(&opts, &worktree)?,
+        Some(RescueAction::Patch) => write_rescue_patch(project_root, &opts, &worktree)?,
+    };
+
+    if !reported {
+        return emit_rescue_report(&opts, &worktree, &changes, &result);
+    }
+    if !matches!(result, RescueResult::Inspected) {
+        println!("{}", format_block(result_rows(&result)));
+    }
+    Ok(())
+}
+
+fn find_preserved_worktrees(
+    project_root: &Path,
+    jobs_root: &Path,
+    opts: &RescueOptions,
+) -> Result<Vec<PreservedWorktree>, Box<dyn std::error::Error>> {
+    let mut seen = HashSet::new();
+    let mut found = Vec::new();
+    for record in jobs::list_records(jobs_root)? {
+        let Some(metadata) = record.metadata.as_ref() else {
+            continue;
+        };
+        let Some(recorded) = metadata.worktree_path.as_deref() else {
+            continue;
+        };
+        let path = resolve_recorded_path(project_root, recorded);
+        if !path.is_dir() || !seen.insert(path.clone()) {
+            continue;
+        }
+
+        let matches_plan = metadata.plan.as_deref() == Some(opts.slug.as_str())
+            || metadata.branch.as_deref() == Some(opts.branch.as_str());
+        let checked_out = vcs::current_branch_name_in(&path)
+            .ok()
+            .flatten()
+            .is_some_and(|branch| branch == opts.branch);
+        if matches_plan || checked_out {
+            found.push(PreservedWorktree {
+                path,
+                job_id: record.id.clone(),
+                job_status: record.status,
+            });
+        }
+    }
+    Ok(found)
+}
+
+fn resolve_recorded_path(project_root: &Path, recorded: &str) -> PathBuf {
+    let path = PathBuf::from(recorded);
+    if path.is_absolute() {
+        path
+    } else {
+        project_root.join(path)
+    }
+}
+
+fn prompt_rescue_action() -> Result<Option<RescueAction>, Box<dyn std::error::Error>> {
+    eprint!("Rescue action: [c]ommit, [d]iscard, [p]atch, or [l]eave as-is [l]: ");
+    io::stderr().flush()?;
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    parse_rescue_action(&answer)
+        .ok_or_else(|| format!("unrecognized rescue action `{}`", answer.trim()).into())
+}
+
+fn parse_rescue_action(answer: &str) -> Option<Option<RescueAction>> {
+    match answer.trim().to_ascii_lowercase().as_str() {
+        "" | "l" | "leave" => Some(None),
+        "c" | "commit" => Some(Some(RescueAction::Commit)),
+        "d" | "discard" => Some(Some(RescueAction::Discard)),
+        "p" | "patch" => Some(Some(RescueAction::Patch)),
+        _ => None,
+    }
+}
+
+fn commit_rescued_changes(
+    opts: &RescueOptions,
+    worktree: &PreservedWorktree,
+) -> Result<RescueResult, Box<dyn std::error::Error>> {
+    let checked_out = vcs::current_branch_name_in(&worktree.path)?;
+    if checked_out.as_deref() != Some(opts.branch.as_str()) {
+        return Err(format!(
+            "preserved worktree {} is not on `{}` (found {}); refusing to commit",
+            worktree.path.display(),
+            opts.branch,
+            checked_out.as_deref().unwrap_or("detached HEAD")
+        )
+        .into());
+    }
+
+    let message = opts
+        .message
+        .as_deref()
+        .map(str::trim)
+        .filter(|message| !message.is_empty())
+        .unwrap_or(DEFAULT_RESCUE_COMMIT_MESSAGE);
+    vcs::stage_all_in(&worktree.path)?;
+    let oid = vcs::commit_staged_in(&worktree.path, message, false)?;
+    Ok(RescueResult::Committed {
+        commit: oid.to_string(),
+    })
+}
+
+fn discard_rescued_changes(
+    opts: &RescueOptions,
+    worktree: &PreservedWorktree,
+) -> Result<RescueResult, Box<dyn std::error::Error>> {
+    if !opts.assume_yes {
+        if !io::stdin().is_terminal() {
+            return Err("vizier rescue --discard requires --yes when stdin is not a TTY".into());
+        }
+        let prompt = format!(
+            "Discard all uncommitted changes in {}?",
+            worktree.path.display()
+        );
+        if !prompt_yes_no(&prompt)? {
+            return Err("aborted by user".into());
+        }
+    }
+
+    let entries = vcs::discard_worktree_changes_in(&worktree.path)?;
+    Ok(RescueResult::Discarded { entries })
+}
+
+fn write_rescue_patch(
+    project_root: &Path,
+    opts: &RescueOptions,
+    worktree: &PreservedWorktree,
+) -> Result<RescueResult, Box<dyn std::error::Error>> {
+    // Stage first so untracked files the agent created land in the patch too.
+    vcs::stage_all_in(&worktree.path)?;
+    let patch = vcs::diff_binary_against_head_in(&worktree.path)?;
+
+    let path = match opts.output.as_ref() {
+        Some(path) if path.is_absolute() => path.clone(),
+        Some(path) => std::env::current_dir()?.join(path),
+        None => project_root
+            .join(temp_registry::RESCUE_DIR)
+            .join(format!("{}-{}.patch", opts.slug, worktree.job_id)),
+    };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, patch)?;
+    Ok(RescueResult::PatchWritten { path })
+}
+
+fn emit_rescue_report(
+    opts: &RescueOptions,
+    worktree: &PreservedWorktree,
+    changes: &[String],
+    result: &RescueResult,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if matches!(opts.format, RescueOutputFormat::Json) {
+        let payload = json!({
+            "outcome": result.outcome_key(),
+            "plan": opts.slug,
+            "branch": opts.branch,
+            "worktree": worktree.path.display().to_string(),
+            "job_id": worktree.job_id,
+            "job_status": jobs::status_label(worktree.job_status),
+            "changes": changes,
+            "commit": match result {
+                RescueResult::Committed { commit } => Some(commit.clone()),
+                _ => None,
+            },
+            "patch": match result {
+                RescueResult::PatchWritten { path } => Some(path.display().to_string()),
+                _ => None,
+            },
+        });
+        println!("{}", serde_json::to_string_pretty(&payload)?);
+        return Ok(());
+    }
+
+    let mut details = result_rows(result);
+    let mut rows = vec![
+        details.remove(0),
+        ("Plan".to_string(), opts.slug.clone()),
+        ("Branch".to_string(), opts.branch.clone()),
+        ("Worktree".to_string(), worktree.path.display().to_string()),
+        (
+            "Job".to_string(),
+            format!(
+                "{} ({})",
+                worktree.job_id,
+                jobs::status_label(worktree.job_status)
+            ),
+        ),
+        ("Changes".to_string(), changes.len().to_string()),
+    ];
+    rows.extend(details);
+    println!("{}", format_block(rows));
+
+    if matches!(result, RescueResult::Inspected) && !changes.is_empty() {
+        println!();
+        println!("Uncommitted changes:");
+        for change in changes {
+            println!("  {change}");
+        }
+    }
+
+    Ok(())
+}
+
+// Outcome row plus the action-specific detail, shared by the full report and the short
+// confirmation printed after an interactive choice.
+fn result_rows(result: &RescueResult) -> Vec<(String, String)> {
+    let mut rows = vec![("Outcome".to_string(), result.label().to_string())];
+    match result {
+        RescueResult::Committed { commit } => {
+            rows.push(("Commit".to_string(), commit.clone()));
+        }
+        RescueResult::Discarded { entries } => {
+            rows.push(("Discarded".to_string(), entries.to_string()));
+        }
+        RescueResult::PatchWritten { path } => {
+            rows.push(("Patch".to_string(), path.display().to_string()));
+        }
+        RescueResult::Inspected | RescueResult::Clean => {}
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RescueAction, parse_rescue_action};
+
+    #[test]
+    fn parse_rescue_action_accepts_short_and_long_forms() {
+        assert_eq!(parse_rescue_action(""), Some(None));
+        assert_eq!(parse_rescue_action(" L "), Some(None));
+        assert_eq!(parse_rescue_action("c"), Some(Some(RescueAction::Commit)));
+        assert_eq!(
+            parse_rescue_action("Discard"),
+            Some(Some(RescueAction::Discard))
+        );
+        assert_eq!(
+            parse_rescue_action("patch"),
+            Some(Some(RescueAction::Patch))
+        );
+        assert_eq!(parse_rescue_action("nope"), None);
+    }
+}
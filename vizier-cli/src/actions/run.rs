@@ -1,5 +1,6 @@
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
+use std::io::{self, IsTerminal};
 use std::path::{Path, PathBuf};
 use std::thread;
 use std::time::Duration;
@@ -7,14 +8,20 @@ use std::time::Duration;
 use serde::Deserialize;
 use serde_json::json;
 use uuid::Uuid;
-use vizier_core::display;
+use vizier_core::{auditor, config, cost, display, temp_registry::TempRegistry, vcs};
 
-use crate::actions::shared::format_block;
+use crate::actions::run_confirm::{ConfirmPane, ConfirmStage};
+use crate::actions::shared::{audit_disposition, format_block};
+use crate::actions::types::CommitMode;
 use crate::actions::workflow_preflight::{
-    PreparedWorkflowInvocation, prepare_workflow_invocation, prepare_workflow_template,
+    PreparedWorkflowInvocation, check_plan_branch_drift, check_plan_overlaps,
+    estimate_approve_cost, prepare_workflow_invocation, prepare_workflow_template,
     prepare_workflow_template_from_invocation,
 };
 use crate::cli::args::{RunCmd, RunFormatArg};
+use crate::cli::conflict_resolver::edit_file;
+use crate::cli::porcelain;
+use crate::cli::prompt::{prompt_with_preview, prompt_yes_no};
 use crate::jobs;
 use crate::workflow_templates::ResolvedWorkflowSource;
 
@@ -107,6 +114,12 @@ pub(crate) fn run_workflow(
     cmd: RunCmd,
     vizier_root_existed_before_runtime: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    if cmd.remote {
+        if matches!(cmd.format, RunFormatArg::Porcelain) {
+            return Err("--remote has no --porcelain output; use --format json".into());
+        }
+        return crate::actions::remote::run_remote_workflow(project_root, cmd);
+    }
     let cfg = vizier_core::config::get_config();
     let approval_override = if cmd.require_approval {
         Some(true)
@@ -117,6 +130,21 @@ pub(crate) fn run_workflow(
     };
     let binary = std::env::current_exe()?;
     let invocation_args = std::env::args().collect::<Vec<_>>();
+    let strict_drift = cmd.strict || cfg.workflow.drift.strict;
+    let context_files = resolve_context_files(&cmd.context)?;
+    if cmd.edit_plan && !io::stdin().is_terminal() {
+        return Err("--edit-plan needs an interactive terminal to open the editor".into());
+    }
+    let review_baseline = cmd
+        .baseline
+        .as_deref()
+        .map(|rev| resolve_review_baseline(project_root, rev))
+        .transpose()?;
+    let commit_mode = if cmd.patch {
+        CommitMode::Patch
+    } else {
+        CommitMode::AutoCommit
+    };
 
     if let Some(spec_dir) = cmd.spec_dir.as_ref() {
         let prepared =
@@ -127,6 +155,10 @@ pub(crate) fn run_workflow(
             .first()
             .map(|item| &item.template)
             .ok_or("batch discovery returned no items")?;
+        for item in &batch.items {
+            check_plan_branch_drift(project_root, &item.template, strict_drift)?;
+            check_plan_overlaps(project_root, &item.template, cmd.strict_overlap)?;
+        }
 
         if cmd.check {
             emit_validation_summary(cmd.format, &prepared.source, first_template, Some(&batch))?;
@@ -140,10 +172,18 @@ pub(crate) fn run_workflow(
             &batch.items,
             &cmd.after,
             approval_override,
+            cmd.priority,
             &binary,
             &invocation_args,
             cmd.ephemeral,
             vizier_root_existed_before_runtime,
+            &context_files,
+            review_baseline.as_deref(),
+            commit_mode,
+            None,
+            cmd.no_gen_tests,
+            cmd.fix_loop,
+            cmd.edit_plan,
         )?;
         let mode = MultiRunMode::Batch {
             batch_dir: batch.batch_dir.clone(),
@@ -183,12 +223,14 @@ pub(crate) fn run_workflow(
         if exit_code == 0 {
             return Ok(());
         }
-        std::process::exit(exit_code);
+        crate::cli::outcome::exit(exit_code);
     }
 
     let prepared = prepare_workflow_template(project_root, &cmd.flow, &cmd.inputs, &cmd.set, &cfg)?;
     let source = prepared.source;
     let template = prepared.template;
+    check_plan_branch_drift(project_root, &template, strict_drift)?;
+    check_plan_overlaps(project_root, &template, cmd.strict_overlap)?;
 
     if cmd.check {
         jobs::validate_workflow_run_template(&template)?;
@@ -196,6 +238,22 @@ pub(crate) fn run_workflow(
         return Ok(());
     }
 
+    let cost_estimate =
+        estimate_approve_cost(project_root, &source, &template, &context_files, &cfg);
+    if let Some(estimate) = cost_estimate.as_ref() {
+        check_max_cost(estimate, &cmd)?;
+    }
+    if let Some(stage) =
+        confirm_stage_run(project_root, &template, cost_estimate.as_ref(), &cmd, &cfg)?
+    {
+        let cancelled = match stage {
+            ConfirmStage::Approve => "Approve cancelled; nothing queued",
+            ConfirmStage::Merge => "Merge cancelled; nothing queued",
+        };
+        println!("{}", display::outcome_line(cancelled));
+        return Ok(());
+    }
+
     let repeat = cmd.repeat.get();
     let items = (1..=repeat)
         .map(|index| PreparedRunItem {
@@ -211,10 +269,18 @@ pub(crate) fn run_workflow(
         &items,
         &cmd.after,
         approval_override,
+        cmd.priority,
         &binary,
         &invocation_args,
         cmd.ephemeral,
         vizier_root_existed_before_runtime,
+        &context_files,
+        review_baseline.as_deref(),
+        commit_mode,
+        cost_estimate.as_ref(),
+        cmd.no_gen_tests,
+        cmd.fix_loop,
+        cmd.edit_plan,
     )?;
 
     if repeat == 1 {
@@ -226,6 +292,7 @@ pub(crate) fn run_workflow(
                 &summary.enqueue,
                 &summary.root_jobs,
                 cmd.ephemeral,
+                review_baseline.as_deref(),
             )?;
             return Ok(());
         }
@@ -245,13 +312,14 @@ pub(crate) fn run_workflow(
             &summary.enqueue,
             &summary.root_jobs,
             cmd.ephemeral,
+            review_baseline.as_deref(),
             &terminal,
         )?;
 
         if terminal.exit_code == 0 {
             return Ok(());
         }
-        std::process::exit(terminal.exit_code);
+        crate::cli::outcome::exit(terminal.exit_code);
     }
 
     let mode = MultiRunMode::Repeat { repeat };
@@ -283,7 +351,7 @@ pub(crate) fn run_workflow(
     if exit_code == 0 {
         Ok(())
     } else {
-        std::process::exit(exit_code)
+        crate::cli::outcome::exit(exit_code)
     }
 }
 
@@ -295,10 +363,18 @@ fn enqueue_serial_runs(
     items: &[PreparedRunItem],
     requested_after: &[String],
     approval_override: Option<bool>,
+    priority: Option<i32>,
     binary: &Path,
     invocation_args: &[String],
     ephemeral: bool,
     vizier_root_existed_before_runtime: bool,
+    context_files: &[String],
+    review_baseline: Option<&str>,
+    commit_mode: CommitMode,
+    cost_estimate: Option<&cost::CostEstimate>,
+    no_gen_tests: bool,
+    fix_loop: bool,
+    edit_plan: bool,
 ) -> Result<Vec<EnqueuedRunSummary>, Box<dyn std::error::Error>> {
     let mut summaries = Vec::<EnqueuedRunSummary>::with_capacity(items.len());
     let mut previous_run_id = None::<String>;
@@ -327,6 +403,27 @@ fn enqueue_serial_runs(
         if let Some(alias) = source.command_alias.as_ref() {
             annotate_alias_metadata(jobs_root, &job_ids, alias.as_str())?;
         }
+        if !context_files.is_empty() {
+            annotate_context_files(jobs_root, &job_ids, context_files)?;
+        }
+        if let Some(baseline) = review_baseline {
+            annotate_review_baseline(jobs_root, &job_ids, baseline)?;
+        }
+        if !commit_mode.should_commit() {
+            annotate_commit_mode(jobs_root, &job_ids, commit_mode)?;
+        }
+        if let Some(estimate) = cost_estimate {
+            annotate_cost_estimate(jobs_root, &job_ids, estimate)?;
+        }
+        if no_gen_tests {
+            annotate_no_gen_tests(jobs_root, &job_ids)?;
+        }
+        if fix_loop {
+            annotate_fix_loop(jobs_root, &job_ids)?;
+        }
+        if edit_plan {
+            annotate_plan_edit(jobs_root, &item.template, &enqueue.job_ids)?;
+        }
 
         let mut current_after = requested_after.to_vec();
         if let Some(previous) = previous_run_id.as_ref() {
@@ -349,6 +446,11 @@ fn enqueue_serial_runs(
                 apply_approval_override(jobs_root, root, required)?;
             }
         }
+        if let Some(priority) = priority {
+            for job_id in &job_ids {
+                apply_priority(jobs_root, job_id, priority)?;
+            }
+        }
 
         // Keep deterministic startup by applying per-run root overrides before ticking.
         let _ = jobs::scheduler_tick(project_root, jobs_root, binary)?;
@@ -801,6 +903,191 @@ fn annotate_alias_metadata(
     Ok(())
 }
 
+// Context documents are read when each agent node runs, so they are recorded as absolute paths
+// that still resolve from a job worktree.
+fn resolve_context_files(paths: &[String]) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let mut resolved = Vec::new();
+    for path in paths {
+        let canonical =
+            std::fs::canonicalize(path).map_err(|err| format!("--context {path}: {err}"))?;
+        if !canonical.is_file() {
+            return Err(format!("--context {path}: not a file").into());
+        }
+        let canonical = canonical.display().to_string();
+        if !resolved.contains(&canonical) {
+            resolved.push(canonical);
+        }
+    }
+    Ok(resolved)
+}
+
+fn annotate_context_files(
+    jobs_root: &Path,
+    job_ids: &[String],
+    context_files: &[String],
+) -> Result<(), Box<dyn std::error::Error>> {
+    for job_id in job_ids {
+        jobs::update_job_record(jobs_root, job_id, |record| {
+            let metadata = record.metadata.get_or_insert_with(Default::default);
+            metadata.context_files = Some(context_files.to_vec());
+        })?;
+    }
+    Ok(())
+}
+
+// Resolved up front so every job in the run reviews against the same commit even if REV is a
+// branch that moves while the run waits in the queue.
+fn resolve_review_baseline(
+    project_root: &Path,
+    rev: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let repo = git2::Repository::discover(project_root)?;
+    let commit = repo
+        .revparse_single(rev)
+        .and_then(|object| object.peel_to_commit())
+        .map_err(|err| format!("--baseline {rev}: {}", err.message()))?;
+    Ok(commit.id().to_string())
+}
+
+fn annotate_review_baseline(
+    jobs_root: &Path,
+    job_ids: &[String],
+    baseline: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    for job_id in job_ids {
+        jobs::update_job_record(jobs_root, job_id, |record| {
+            let metadata = record.metadata.get_or_insert_with(Default::default);
+            metadata.review_baseline = Some(baseline.to_string());
+        })?;
+    }
+    Ok(())
+}
+
+fn annotate_no_gen_tests(
+    jobs_root: &Path,
+    job_ids: &[String],
+) -> Result<(), Box<dyn std::error::Error>> {
+    for job_id in job_ids {
+        jobs::update_job_record(jobs_root, job_id, |record| {
+            let metadata = record.metadata.get_or_insert_with(Default::default);
+            metadata.generate_tests = Some(false);
+        })?;
+    }
+    Ok(())
+}
+
+fn annotate_fix_loop(
+    jobs_root: &Path,
+    job_ids: &[String],
+) -> Result<(), Box<dyn std::error::Error>> {
+    for job_id in job_ids {
+        jobs::update_job_record(jobs_root, job_id, |record| {
+            let metadata = record.metadata.get_or_insert_with(Default::default);
+            metadata.review_fix_loop = Some(true);
+        })?;
+    }
+    Ok(())
+}
+
+// Holds each plan.persist job for approval so the following CLI can open the draft in an
+// editor before the plan is written.
+fn annotate_plan_edit(
+    jobs_root: &Path,
+    template: &vizier_core::workflow_template::WorkflowTemplate,
+    node_jobs: &BTreeMap<String, String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let persist_jobs = template
+        .nodes
+        .iter()
+        .filter(|node| node.uses == "cap.env.builtin.plan.persist")
+        .filter_map(|node| node_jobs.get(&node.id))
+        .collect::<Vec<_>>();
+    if persist_jobs.is_empty() {
+        return Err(format!(
+            "--edit-plan needs a workflow with a plan.persist node; `{}` has none",
+            template.id
+        )
+        .into());
+    }
+    for job_id in persist_jobs {
+        jobs::update_job_record(jobs_root, job_id, |record| {
+            let metadata = record.metadata.get_or_insert_with(Default::default);
+            metadata.plan_edit = Some(true);
+            let schedule = record.schedule.get_or_insert_with(Default::default);
+            schedule.approval = Some(jobs::pending_job_approval());
+        })?;
+    }
+    Ok(())
+}
+
+fn annotate_commit_mode(
+    jobs_root: &Path,
+    job_ids: &[String],
+    commit_mode: CommitMode,
+) -> Result<(), Box<dyn std::error::Error>> {
+    for job_id in job_ids {
+        jobs::update_job_record(jobs_root, job_id, |record| {
+            let metadata = record.metadata.get_or_insert_with(Default::default);
+            metadata.commit_disposition = Some(audit_disposition(commit_mode));
+        })?;
+    }
+    Ok(())
+}
+
+fn annotate_cost_estimate(
+    jobs_root: &Path,
+    job_ids: &[String],
+    estimate: &cost::CostEstimate,
+) -> Result<(), Box<dyn std::error::Error>> {
+    for job_id in job_ids {
+        jobs::update_job_record(jobs_root, job_id, |record| {
+            let metadata = record.metadata.get_or_insert_with(Default::default);
+            metadata.cost_estimate = Some(estimate.clone());
+        })?;
+    }
+    Ok(())
+}
+
+// `--max-cost` is checked against the top of the range so the ceiling is conservative.
+fn check_max_cost(
+    estimate: &cost::CostEstimate,
+    cmd: &RunCmd,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(max_cost) = cmd.max_cost
+        && estimate.high_usd > max_cost
+    {
+        return Err(format!(
+            "approve cost estimate {} exceeds --max-cost {} ({} context tokens, {})",
+            estimate.range_label(),
+            cost::format_usd(max_cost),
+            display::format_number(estimate.context_tokens as usize),
+            estimate.model
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Shows the approve/merge confirmation pane and returns the stage when the operator declined.
+/// The pane only appears for text output on a terminal, so scripts and `--yes` go straight on.
+fn confirm_stage_run(
+    project_root: &Path,
+    template: &vizier_core::workflow_template::WorkflowTemplate,
+    estimate: Option<&cost::CostEstimate>,
+    cmd: &RunCmd,
+    cfg: &config::Config,
+) -> Result<Option<ConfirmStage>, Box<dyn std::error::Error>> {
+    if cmd.assume_yes || !matches!(cmd.format, RunFormatArg::Text) || !io::stdin().is_terminal() {
+        return Ok(None);
+    }
+    let Some(pane) = ConfirmPane::gather(project_root, template, cfg) else {
+        return Ok(None);
+    };
+    let prompt = format!("Run {}?", pane.stage().label());
+    let confirmed = prompt_with_preview(&pane.render(estimate), &prompt, &pane.views())?;
+    Ok((!confirmed).then_some(pane.stage()))
+}
+
 fn apply_after_dependencies(
     jobs_root: &Path,
     job_id: &str,
@@ -836,17 +1123,56 @@ fn apply_approval_override(
     Ok(())
 }
 
+fn apply_priority(
+    jobs_root: &Path,
+    job_id: &str,
+    priority: i32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    jobs::update_job_record(jobs_root, job_id, |record| {
+        record
+            .schedule
+            .get_or_insert_with(Default::default)
+            .priority = priority;
+    })?;
+    Ok(())
+}
+
+/// `run <run-id> <state> <template>@<version>`, then a `job` record for each of the run's nodes.
+fn emit_run_porcelain(
+    enqueue: &jobs::EnqueueWorkflowRunResult,
+    state: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    porcelain::emit(
+        "run",
+        &[
+            enqueue.run_id.clone(),
+            state.to_string(),
+            format!("{}@{}", enqueue.template_id, enqueue.template_version),
+        ],
+    );
+    let jobs_root = vcs::repo_root()?.join(".vizier").join("jobs");
+    for job_id in enqueue.job_ids.values() {
+        porcelain::emit_job(&jobs::read_record(&jobs_root, job_id)?);
+    }
+    Ok(())
+}
+
 fn emit_enqueue_summary(
     format: RunFormatArg,
     source: &ResolvedWorkflowSource,
     enqueue: &jobs::EnqueueWorkflowRunResult,
     root_jobs: &[String],
     ephemeral: bool,
+    baseline: Option<&str>,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    if matches!(format, RunFormatArg::Porcelain) {
+        return emit_run_porcelain(enqueue, "enqueued");
+    }
     if matches!(format, RunFormatArg::Json) {
-        let payload = json!({
+        let mut payload = json!({
             "outcome": "workflow_run_enqueued",
             "run_id": enqueue.run_id,
+            "profile": config::get_config().profile,
             "ephemeral": ephemeral,
             "workflow_template_selector": source.selector,
             "workflow_template_id": enqueue.template_id,
@@ -858,10 +1184,14 @@ fn emit_enqueue_summary(
                 "tail": "vizier jobs tail <job-id> --follow"
             }
         });
+        if let Some(baseline) = baseline {
+            payload["review_baseline"] = json!(baseline);
+        }
         println!("{}", serde_json::to_string_pretty(&payload)?);
         return Ok(());
     }
 
+    let profile = config::get_config().profile;
     let next_hint = if let Some(root) = root_jobs.first() {
         format!(
             "vizier jobs schedule --job {root}\nvizier jobs show {root}\nvizier jobs tail {root} --follow"
@@ -870,31 +1200,38 @@ fn emit_enqueue_summary(
         "vizier jobs schedule".to_string()
     };
 
-    println!(
-        "{}",
-        format_block(vec![
-            ("Outcome".to_string(), "Workflow run enqueued".to_string()),
-            ("Run".to_string(), enqueue.run_id.clone()),
-            (
-                "Template".to_string(),
-                format!("{}@{}", enqueue.template_id, enqueue.template_version),
-            ),
-            ("Selector".to_string(), source.selector.clone(),),
-            (
-                "Root jobs".to_string(),
-                if root_jobs.is_empty() {
-                    "none".to_string()
-                } else {
-                    root_jobs.join(", ")
-                },
-            ),
-            (
-                "Ephemeral".to_string(),
-                if ephemeral { "yes" } else { "no" }.to_string(),
-            ),
-            ("Next".to_string(), next_hint),
-        ])
-    );
+    let mut rows = vec![
+        ("Outcome".to_string(), "Workflow run enqueued".to_string()),
+        ("Run".to_string(), enqueue.run_id.clone()),
+        (
+            "Template".to_string(),
+            format!("{}@{}", enqueue.template_id, enqueue.template_version),
+        ),
+        ("Selector".to_string(), source.selector.clone()),
+        (
+            "Root jobs".to_string(),
+            if root_jobs.is_empty() {
+                "none".to_string()
+            } else {
+                root_jobs.join(", ")
+            },
+        ),
+        (
+            "Ephemeral".to_string(),
+            if ephemeral { "yes" } else { "no" }.to_string(),
+        ),
+        ("Next".to_string(), next_hint),
+    ];
+    if let Some(baseline) = baseline {
+        rows.insert(
+            rows.len() - 1,
+            ("Baseline".to_string(), baseline.to_string()),
+        );
+    }
+    if let Some(profile) = profile {
+        rows.insert(4, ("Profile".to_string(), profile));
+    }
+    println!("{}", format_block(rows));
 
     Ok(())
 }
@@ -905,6 +1242,17 @@ fn emit_validation_summary(
     template: &vizier_core::workflow_template::WorkflowTemplate,
     batch: Option<&PreparedBatchRun>,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    if matches!(format, RunFormatArg::Porcelain) {
+        porcelain::emit(
+            "check",
+            &[
+                format!("{}@{}", template.id, template.version),
+                source.selector.clone(),
+                "valid".to_string(),
+            ],
+        );
+        return Ok(());
+    }
     if matches!(format, RunFormatArg::Json) {
         let mut payload = serde_json::Map::from_iter([
             ("outcome".to_string(), json!("workflow_validation_passed")),
@@ -988,6 +1336,12 @@ fn emit_multi_enqueue_summary(
     summaries: &[EnqueuedRunSummary],
     ephemeral: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    if matches!(format, RunFormatArg::Porcelain) {
+        for summary in summaries {
+            emit_run_porcelain(&summary.enqueue, "enqueued")?;
+        }
+        return Ok(());
+    }
     if matches!(format, RunFormatArg::Json) {
         let runs = summaries
             .iter()
@@ -1130,6 +1484,8 @@ struct FollowResult {
     failed: Vec<String>,
     blocked: Vec<String>,
     cancelled: Vec<String>,
+    /// `command.patch` files written by `--patch` commit nodes.
+    patches: Vec<String>,
     cleanup: Option<jobs::EphemeralRunCleanupEvent>,
 }
 
@@ -1145,6 +1501,7 @@ fn follow_run(
     let stream_logs = matches!(format, RunFormatArg::Text);
     let mut last_status = HashMap::<String, jobs::JobStatus>::new();
     let mut last_log_line = HashMap::<String, String>::new();
+    let mut phases = HashMap::<String, Option<display::Phase>>::new();
 
     loop {
         let _ = jobs::scheduler_tick_without_ephemeral_cleanup(project_root, jobs_root, binary)?;
@@ -1153,19 +1510,39 @@ fn follow_run(
         let mut failed = Vec::new();
         let mut blocked = Vec::new();
         let mut cancelled = Vec::new();
+        let mut patches = Vec::new();
 
         for job_id in job_ids {
             let record = jobs::read_record(jobs_root, job_id)?;
             let status = record.status;
+            if let Some(metadata) = record.metadata.as_ref()
+                && metadata.commit_disposition == Some(auditor::CommitDisposition::Patch)
+                && let Some(patch) = metadata.patch_file.as_ref()
+            {
+                patches.push(patch.clone());
+            }
+            track_node_phase(&mut phases, &record);
+            if status == jobs::JobStatus::WaitingOnApproval
+                && record
+                    .metadata
+                    .as_ref()
+                    .and_then(|metadata| metadata.plan_edit)
+                    == Some(true)
+            {
+                edit_drafted_plan(project_root, jobs_root, binary, job_id)?;
+                continue;
+            }
 
             if stream_logs {
                 if last_status.get(job_id) != Some(&status) {
+                    display::clear_phase_line();
                     println!("[run:{run_id}] {job_id} => {}", jobs::status_label(status));
                     last_status.insert(job_id.clone(), status);
                 }
                 if let Some(line) = jobs::latest_job_log_line(jobs_root, job_id, 2048)? {
                     let marker = format!("{}:{}", line.stream.label(), line.line);
                     if last_log_line.get(job_id) != Some(&marker) {
+                        display::clear_phase_line();
                         println!("[{job_id}/{}] {}", line.stream.label(), line.line);
                         last_log_line.insert(job_id.clone(), marker);
                     }
@@ -1193,7 +1570,12 @@ fn follow_run(
             failed.sort();
             blocked.sort();
             cancelled.sort();
+            patches.sort();
 
+            record_gate_results(jobs_root, job_ids)?;
+            if stream_logs {
+                offer_post_merge_reverts(project_root, jobs_root, &failed)?;
+            }
             let (terminal_state, exit_code) = if !failed.is_empty() || !cancelled.is_empty() {
                 ("failed".to_string(), 1)
             } else if !blocked.is_empty() {
@@ -1224,6 +1606,7 @@ fn follow_run(
                 failed,
                 blocked,
                 cancelled,
+                patches,
                 cleanup,
             });
         }
@@ -1232,16 +1615,202 @@ fn follow_run(
     }
 }
 
+// Post-merge gate failures without auto-revert leave the merge on the target; offer to revert
+// it here when someone is at the terminal, otherwise point at the manual fix.
+fn offer_post_merge_reverts(
+    project_root: &Path,
+    jobs_root: &Path,
+    failed: &[String],
+) -> Result<(), Box<dyn std::error::Error>> {
+    for job_id in failed {
+        let Some(candidate) = jobs::pending_post_merge_revert(jobs_root, job_id)? else {
+            continue;
+        };
+        let target = candidate
+            .target_branch
+            .as_deref()
+            .unwrap_or("the target branch");
+        display::clear_phase_line();
+        if !io::stdin().is_terminal() {
+            display::warn(format!(
+                "post-merge gate `{}` failed; merge {} is still on {target} (revert it with `git revert -m 1 {}` or rerun with --auto-revert)",
+                candidate.gate, candidate.merge_commit, candidate.merge_commit
+            ));
+            continue;
+        }
+        let prompt = format!(
+            "Post-merge gate `{}` failed. Revert merge {} on {target}?",
+            candidate.gate, candidate.merge_commit
+        );
+        if !prompt_yes_no(&prompt)? {
+            display::warn(format!("merge {} left on {target}", candidate.merge_commit));
+            continue;
+        }
+        let revert = jobs::apply_post_merge_revert(project_root, jobs_root, &candidate)?;
+        println!(
+            "{}",
+            format_block(vec![
+                ("Outcome".to_string(), "Merge reverted".to_string()),
+                ("Merge".to_string(), candidate.merge_commit.clone()),
+                ("Revert".to_string(), revert),
+            ])
+        );
+    }
+    Ok(())
+}
+
+// Opens the agent's draft for an `--edit-plan` run while its plan.persist job waits on approval,
+// then releases the job. An unchanged file keeps the draft as written; an emptied one rejects
+// the job so nothing is committed.
+fn edit_drafted_plan(
+    project_root: &Path,
+    jobs_root: &Path,
+    binary: &Path,
+    job_id: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    display::clear_phase_line();
+    match jobs::drafted_plan_text(project_root, jobs_root, job_id)? {
+        Some(draft) => {
+            let scratch = TempRegistry::new(project_root).scratch("plan-edit")?;
+            let temp = scratch.path().join("plan.md");
+            fs::write(&temp, &draft)?;
+            let edited = edit_file(&temp)?;
+            if edited.trim().is_empty() {
+                jobs::reject_job(
+                    project_root,
+                    jobs_root,
+                    job_id,
+                    Some("plan edit left the plan empty"),
+                )?;
+                display::warn("edited plan is empty; nothing was committed");
+                return Ok(());
+            }
+            if edited.trim() != draft.trim() {
+                fs::write(jobs::plan_amendment_path(jobs_root, job_id), &edited)?;
+                display::info("committing the edited plan");
+            }
+        }
+        None => display::warn(format!(
+            "job {job_id} has no drafted plan to edit; committing it unchanged"
+        )),
+    }
+    jobs::approve_job(project_root, jobs_root, binary, job_id)?;
+    Ok(())
+}
+
+// Mirrors node jobs onto display phases: the spinner shows running nodes, and once a node is
+// terminal its recorded start/finish times feed the phase timing table.
+fn track_node_phase(
+    phases: &mut HashMap<String, Option<display::Phase>>,
+    record: &jobs::JobRecord,
+) {
+    let Some(name) = node_phase_name(record) else {
+        return;
+    };
+    let label = record
+        .metadata
+        .as_ref()
+        .and_then(|metadata| metadata.workflow_node_id.clone());
+    match record.status {
+        jobs::JobStatus::Running => {
+            phases
+                .entry(record.id.clone())
+                .or_insert_with(|| Some(display::start_phase(name, label)));
+        }
+        jobs::JobStatus::Succeeded
+        | jobs::JobStatus::Failed
+        | jobs::JobStatus::Cancelled
+        | jobs::JobStatus::BlockedByDependency
+        | jobs::JobStatus::BlockedByApproval => {
+            // `None` marks a node whose timing was already recorded.
+            let phase = match phases.insert(record.id.clone(), None) {
+                Some(None) => return,
+                Some(Some(phase)) => phase,
+                None if record.started_at.is_none() => return,
+                None => display::start_phase(name, label),
+            };
+            match (record.started_at, record.finished_at) {
+                (Some(started_at), Some(finished_at)) => {
+                    phase.finish_with((finished_at - started_at).to_std().unwrap_or_default());
+                }
+                _ => {
+                    phase.finish();
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn node_phase_name(record: &jobs::JobRecord) -> Option<&'static str> {
+    let metadata = record.metadata.as_ref()?;
+    if metadata
+        .workflow_control_policy
+        .as_deref()
+        .is_some_and(|policy| policy.starts_with("gate."))
+    {
+        return Some(display::PHASE_GATE);
+    }
+    match metadata.workflow_executor_operation.as_deref()? {
+        "worktree.prepare" => Some(display::PHASE_WORKTREE_SETUP),
+        "agent.invoke" | "agent.generate_tests" | "agent.review_fix_loop" => {
+            Some(display::PHASE_AGENT_RUN)
+        }
+        operation if operation.starts_with("git.") => Some(display::PHASE_COMMIT),
+        _ => None,
+    }
+}
+
+// Gate nodes run in their own job processes; surface their results in this invocation's
+// outcome.json once the followed run is terminal.
+fn record_gate_results(
+    jobs_root: &Path,
+    job_ids: &[String],
+) -> Result<(), Box<dyn std::error::Error>> {
+    for job_id in job_ids {
+        let record = jobs::read_record(jobs_root, job_id)?;
+        let Some(metadata) = record.metadata.as_ref() else {
+            continue;
+        };
+        let Some(policy) = metadata
+            .workflow_control_policy
+            .as_deref()
+            .filter(|policy| policy.starts_with("gate."))
+        else {
+            continue;
+        };
+        auditor::Auditor::record_gate(&auditor::CommandOutcomeGate {
+            job_id: Some(record.id.clone()),
+            node_id: metadata.workflow_node_id.clone().unwrap_or_default(),
+            policy: policy.to_string(),
+            outcome: metadata
+                .workflow_node_outcome
+                .clone()
+                .unwrap_or_else(|| jobs::status_label(record.status).to_string()),
+            exit_code: record.exit_code,
+            summary: metadata
+                .workflow_gates
+                .as_ref()
+                .map(|gates| gates.join("; ")),
+        });
+    }
+    Ok(())
+}
+
 fn emit_follow_summary(
     format: RunFormatArg,
     source: &ResolvedWorkflowSource,
     enqueue: &jobs::EnqueueWorkflowRunResult,
     root_jobs: &[String],
     ephemeral: bool,
+    baseline: Option<&str>,
     result: &FollowResult,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    if matches!(format, RunFormatArg::Porcelain) {
+        return emit_run_porcelain(enqueue, &result.terminal_state);
+    }
     if matches!(format, RunFormatArg::Json) {
-        let payload = json!({
+        let mut payload = json!({
             "outcome": "workflow_run_terminal",
             "terminal_state": result.terminal_state,
             "exit_code": result.exit_code,
@@ -1255,8 +1824,12 @@ fn emit_follow_summary(
             "failed": result.failed,
             "blocked": result.blocked,
             "cancelled": result.cancelled,
+            "patches": result.patches,
             "ephemeral_cleanup": result.cleanup,
         });
+        if let Some(baseline) = baseline {
+            payload["review_baseline"] = json!(baseline);
+        }
         println!("{}", serde_json::to_string_pretty(&payload)?);
         return Ok(());
     }
@@ -1289,6 +1862,9 @@ fn emit_follow_summary(
         ),
         ("Exit".to_string(), result.exit_code.to_string()),
     ];
+    if let Some(baseline) = baseline {
+        rows.push(("Baseline".to_string(), baseline.to_string()));
+    }
 
     if !result.succeeded.is_empty() {
         rows.push(("Succeeded".to_string(), result.succeeded.join(", ")));
@@ -1302,6 +1878,9 @@ fn emit_follow_summary(
     if !result.cancelled.is_empty() {
         rows.push(("Cancelled".to_string(), result.cancelled.join(", ")));
     }
+    if !result.patches.is_empty() {
+        rows.push(("Patches".to_string(), result.patches.join(", ")));
+    }
     if let Some(cleanup) = result.cleanup.as_ref() {
         rows.push((
             "Ephemeral cleanup".to_string(),
@@ -1337,6 +1916,18 @@ fn emit_multi_follow_summary(
     terminal_state: &str,
     exit_code: i32,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    if matches!(format, RunFormatArg::Porcelain) {
+        for summary in summaries {
+            let state = followed_runs
+                .iter()
+                .find(|entry| entry.run_id == summary.run_id)
+                .map_or("not_started", |entry| {
+                    entry.terminal.terminal_state.as_str()
+                });
+            emit_run_porcelain(&summary.enqueue, state)?;
+        }
+        return Ok(());
+    }
     if matches!(format, RunFormatArg::Json) {
         let runs = followed_runs
             .iter()
@@ -1350,6 +1941,7 @@ fn emit_multi_follow_summary(
                     "failed": &entry.terminal.failed,
                     "blocked": &entry.terminal.blocked,
                     "cancelled": &entry.terminal.cancelled,
+                    "patches": &entry.terminal.patches,
                     "ephemeral_cleanup": &entry.terminal.cleanup,
                     "spec_file": entry.batch.as_ref().map(|batch| batch.spec_file.as_str()),
                     "slug": entry.batch.as_ref().map(|batch| batch.slug.as_str()),
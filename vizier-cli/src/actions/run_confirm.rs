@@ -0,0 +1,303 @@
+//! Confirmation pane shown before an interactive approve or merge run: the plan overview, diff
+//! stats against the target, the gates that will run, and the narrative threads the branch
+//! touches, with the full plan and diff one key away.
+
+use std::path::Path;
+
+use vizier_core::{config, cost, display, thread_owners, vcs};
+
+use crate::actions::shared::format_block;
+use crate::actions::workflow_preflight::plan_branch_refs;
+use crate::cli::prompt::PreviewView;
+
+const PANE_FILE_LIMIT: usize = 8;
+const OVERVIEW_LINE_LIMIT: usize = 6;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ConfirmStage {
+    Approve,
+    Merge,
+}
+
+impl ConfirmStage {
+    /// The stage a template runs, judged by the builtin nodes it uses.
+    pub(crate) fn of(template: &vizier_core::workflow_template::WorkflowTemplate) -> Option<Self> {
+        let uses = |name: &str| template.nodes.iter().any(|node| node.uses == name);
+        if uses("cap.env.builtin.git.integrate_plan_branch") {
+            return Some(Self::Merge);
+        }
+        template
+            .nodes
+            .iter()
+            .any(|node| {
+                node.uses == "cap.env.builtin.worktree.prepare"
+                    && node.args.get("purpose").map(String::as_str) == Some("stage-approve")
+            })
+            .then_some(Self::Approve)
+    }
+
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            Self::Approve => "approve",
+            Self::Merge => "merge",
+        }
+    }
+}
+
+pub(crate) struct ConfirmPane {
+    stage: ConfirmStage,
+    slug: String,
+    branch: String,
+    target: Option<String>,
+    /// Where `target` came from when the template did not name one.
+    target_source: Option<vcs::TargetSource>,
+    overview: Option<String>,
+    changes: Option<vcs::DiffStats>,
+    gates: Vec<String>,
+    threads: Vec<String>,
+    plan_document: Option<String>,
+    diff: Option<String>,
+}
+
+impl ConfirmPane {
+    /// Reads what the pane shows for the template's plan branch. `None` when the template is not
+    /// an approve or merge stage or names no concrete plan branch; pieces that cannot be read
+    /// (a branch queued earlier in the same chain, say) are left out of the pane.
+    pub(crate) fn gather(
+        project_root: &Path,
+        template: &vizier_core::workflow_template::WorkflowTemplate,
+        cfg: &config::Config,
+    ) -> Option<Self> {
+        let stage = ConfirmStage::of(template)?;
+        let plan = plan_branch_refs(template).into_iter().next()?;
+        let slug = plan
+            .slug
+            .or_else(|| vizier_core::plan::slug_from_branch(&plan.branch))
+            .unwrap_or_else(|| plan.branch.clone());
+        let (target, target_source) = match plan.target {
+            Some(target) => (Some(target), None),
+            None => match vcs::detect_target_branch_in(project_root) {
+                Some(detected) => (Some(detected.branch), Some(detected.source)),
+                None => (None, None),
+            },
+        };
+
+        let plan_path = vizier_core::plan::plan_rel_path(&slug);
+        let plan_document = vcs::read_blob_at_revision_in(
+            project_root,
+            &format!("{}:{}", plan.branch, plan_path.to_string_lossy()),
+        )
+        .ok();
+        let overview = plan_document.as_deref().and_then(|document| {
+            crate::plan::extract_section(document, "Overview")
+                .or_else(|| crate::plan::extract_section(document, "Operator Spec"))
+        });
+
+        let (changes, diff, threads) = match target.as_deref() {
+            Some(target) if target != plan.branch => (
+                vcs::diff_stats_between(project_root, target, &plan.branch).ok(),
+                vcs::diff_patch_between(project_root, target, &plan.branch).ok(),
+                affected_threads(project_root, target, &plan.branch, plan_document.as_deref()),
+            ),
+            _ => (None, None, Vec::new()),
+        };
+
+        Some(Self {
+            stage,
+            slug,
+            branch: plan.branch,
+            target,
+            target_source,
+            overview,
+            changes,
+            gates: configured_gates(stage, cfg),
+            threads,
+            plan_document,
+            diff,
+        })
+    }
+
+    pub(crate) fn stage(&self) -> ConfirmStage {
+        self.stage
+    }
+
+    /// The pane text; approve panes carry the cost estimate rows when one was computed.
+    pub(crate) fn render(&self, estimate: Option<&cost::CostEstimate>) -> String {
+        let mut rows = vec![
+            ("Plan".to_string(), self.slug.clone()),
+            ("Branch".to_string(), self.branch.clone()),
+        ];
+        if let Some(target) = &self.target {
+            let target = match self.target_source {
+                Some(source) => format!("{target} ({})", source.label()),
+                None => target.clone(),
+            };
+            rows.push(("Target".to_string(), target));
+        }
+        rows.push((
+            "Changes".to_string(),
+            self.changes
+                .as_ref()
+                .map(vcs::DiffStats::summary_line)
+                .unwrap_or_else(|| "unavailable".to_string()),
+        ));
+        rows.push((
+            "Gates".to_string(),
+            if self.gates.is_empty() {
+                "none".to_string()
+            } else {
+                self.gates.join("; ")
+            },
+        ));
+        if !self.threads.is_empty() {
+            rows.push(("Threads".to_string(), self.threads.join(", ")));
+        }
+        if let Some(estimate) = estimate {
+            rows.push((
+                "Context".to_string(),
+                format!(
+                    "~{} tokens",
+                    display::format_number(estimate.context_tokens as usize)
+                ),
+            ));
+            rows.push(("Model".to_string(), estimate.model.clone()));
+            rows.push(("Estimated cost".to_string(), estimate.range_label()));
+        }
+
+        let mut pane = format_block(rows);
+        if let Some(changes) = &self.changes {
+            for file in changes.files.iter().take(PANE_FILE_LIMIT) {
+                let lines = if file.binary {
+                    "binary".to_string()
+                } else {
+                    format!("+{} -{}", file.insertions, file.deletions)
+                };
+                pane.push_str(&format!(
+                    "\n  {} {} ({lines})",
+                    file.status.code(),
+                    file.path
+                ));
+            }
+            if changes.files.len() > PANE_FILE_LIMIT {
+                pane.push_str(&format!(
+                    "\n  ... {} more",
+                    changes.files.len() - PANE_FILE_LIMIT
+                ));
+            }
+        }
+        if let Some(overview) = &self.overview {
+            pane.push_str("\n\nOverview:");
+            let lines = overview.lines().collect::<Vec<_>>();
+            for line in lines.iter().take(OVERVIEW_LINE_LIMIT) {
+                pane.push_str(&format!("\n  {line}"));
+            }
+            if lines.len() > OVERVIEW_LINE_LIMIT {
+                pane.push_str("\n  ...");
+            }
+        }
+        pane
+    }
+
+    /// Documents the operator can page from the prompt.
+    pub(crate) fn views(&self) -> Vec<PreviewView> {
+        let mut views = Vec::new();
+        if let Some(document) = &self.plan_document {
+            views.push(PreviewView {
+                key: 'p',
+                label: "plan",
+                content: document.clone(),
+            });
+        }
+        if let Some(diff) = self.diff.as_ref().filter(|diff| !diff.is_empty()) {
+            views.push(PreviewView {
+                key: 'd',
+                label: "diff",
+                content: diff.clone(),
+            });
+        }
+        views
+    }
+}
+
+fn configured_gates(stage: ConfirmStage, cfg: &config::Config) -> Vec<String> {
+    let mut gates = Vec::new();
+    match stage {
+        ConfirmStage::Approve => {
+            let stop = &cfg.approve.stop_condition;
+            if let Some(script) = &stop.script {
+                gates.push(format!(
+                    "stop condition {} (retries {})",
+                    script.display(),
+                    stop.retries
+                ));
+            }
+            if let Some(conditions) = &stop.conditions {
+                gates.push(format!("conditions {conditions}"));
+            }
+            if let Some(tests) = &stop.tests {
+                gates.push(format!("tests `{tests}`"));
+            }
+            if cfg.approve.generate_tests {
+                gates.push("generated tests".to_string());
+            }
+        }
+        ConfirmStage::Merge => {
+            if let Some(strategy) = cfg.merge.strategy {
+                gates.push(format!("strategy {}", strategy.as_str()));
+            }
+            let cicd = &cfg.merge.cicd_gate;
+            if let Some(script) = &cicd.script {
+                gates.push(format!(
+                    "cicd {} (retries {}{})",
+                    script.display(),
+                    cicd.retries,
+                    if cicd.auto_resolve {
+                        ", auto-resolve"
+                    } else {
+                        ""
+                    }
+                ));
+            }
+            for gate in &cfg.merge.gates {
+                gates.push(format!(
+                    "{} {} ({})",
+                    gate.stage.as_str(),
+                    gate.name,
+                    gate.script.display()
+                ));
+            }
+            if cfg.merge.require_owner_ack {
+                gates.push("owner acks".to_string());
+            }
+        }
+    }
+    gates
+}
+
+/// Affected thread names, each followed by its owners when the thread has any.
+fn affected_threads(
+    project_root: &Path,
+    target: &str,
+    branch: &str,
+    plan_document: Option<&str>,
+) -> Vec<String> {
+    let Ok(names) =
+        thread_owners::affected_thread_names(project_root, target, branch, plan_document)
+    else {
+        return Vec::new();
+    };
+    let owned = thread_owners::affected_threads(project_root, target, branch, plan_document)
+        .unwrap_or_default();
+    names
+        .into_iter()
+        .map(|name| {
+            match owned
+                .iter()
+                .find(|thread| thread.thread == name && !thread.owners.is_empty())
+            {
+                Some(thread) => format!("{name} ({})", thread.owners.join(", ")),
+                None => name,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,169 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::json;
+use vizier_core::archive;
+use vizier_core::search::{self, SearchDocument, SearchHit, SearchSourceKind};
+
+use crate::plan::PLAN_DIR;
+
+use super::types::{SearchOptions, SearchOutputFormat};
+
+pub(crate) fn run_search(
+    project_root: &Path,
+    opts: SearchOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let documents = collect_search_documents(project_root)?;
+    let hits = search::search_project(project_root, &documents, &opts.query, opts.limit);
+    emit_search_results(&opts, documents.len(), &hits)
+}
+
+fn collect_search_documents(
+    project_root: &Path,
+) -> Result<Vec<SearchDocument>, Box<dyn std::error::Error>> {
+    let mut documents = Vec::new();
+    let narrative_dir = project_root.join(".vizier/narrative");
+
+    for (name, kind) in [
+        ("snapshot.md", SearchSourceKind::Snapshot),
+        ("glossary.md", SearchSourceKind::Glossary),
+    ] {
+        push_markdown(
+            project_root,
+            &narrative_dir.join(name),
+            kind,
+            &mut documents,
+        )?;
+    }
+
+    for path in markdown_files(&narrative_dir.join("threads"))? {
+        push_markdown(
+            project_root,
+            &path,
+            SearchSourceKind::Thread,
+            &mut documents,
+        )?;
+    }
+
+    for path in markdown_files(&project_root.join(PLAN_DIR))? {
+        push_markdown(project_root, &path, SearchSourceKind::Plan, &mut documents)?;
+    }
+
+    documents.extend(archive::search_documents(project_root)?);
+
+    let sessions_dir = project_root.join(".vizier/sessions");
+    if sessions_dir.is_dir() {
+        let mut session_files = fs::read_dir(&sessions_dir)?
+            .filter_map(Result::ok)
+            .map(|entry| entry.path().join("session.json"))
+            .filter(|path| path.is_file())
+            .collect::<Vec<_>>();
+        session_files.sort();
+        for path in session_files {
+            if let Some(document) = session_summary_document(project_root, &path) {
+                documents.push(document);
+            }
+        }
+    }
+
+    Ok(documents)
+}
+
+fn push_markdown(
+    project_root: &Path,
+    path: &Path,
+    kind: SearchSourceKind,
+    documents: &mut Vec<SearchDocument>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if !path.is_file() {
+        return Ok(());
+    }
+    documents.push(SearchDocument {
+        path: relative_display(project_root, path),
+        kind,
+        text: fs::read_to_string(path)?,
+        first_line: 1,
+    });
+    Ok(())
+}
+
+fn markdown_files(dir: &Path) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+    let mut files = Vec::new();
+    if !dir.is_dir() {
+        return Ok(files);
+    }
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        for entry in fs::read_dir(&current)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if path.extension().and_then(|ext| ext.to_str()) == Some("md") {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+// Session logs are indexed by their assistant summary only; the hit is anchored to the line
+// holding the `summary` key so editors can jump straight to it.
+fn session_summary_document(project_root: &Path, path: &Path) -> Option<SearchDocument> {
+    let contents = fs::read_to_string(path).ok()?;
+    let value: serde_json::Value = serde_json::from_str(&contents).ok()?;
+    let summary = value
+        .pointer("/outcome/summary")
+        .and_then(|summary| summary.as_str())
+        .map(str::trim)
+        .filter(|summary| !summary.is_empty())?;
+    let first_line = contents
+        .lines()
+        .position(|line| line.trim_start().starts_with("\"summary\""))
+        .map(|index| index + 1)
+        .unwrap_or(1);
+    Some(SearchDocument {
+        path: relative_display(project_root, path),
+        kind: SearchSourceKind::Session,
+        text: summary.lines().collect::<Vec<_>>().join(" "),
+        first_line,
+    })
+}
+
+fn relative_display(project_root: &Path, path: &Path) -> String {
+    path.strip_prefix(project_root)
+        .unwrap_or(path)
+        .display()
+        .to_string()
+}
+
+fn emit_search_results(
+    opts: &SearchOptions,
+    searched: usize,
+    hits: &[SearchHit],
+) -> Result<(), Box<dyn std::error::Error>> {
+    if matches!(opts.format, SearchOutputFormat::Json) {
+        let payload = json!({
+            "outcome": "search_completed",
+            "query": opts.query,
+            "documents_searched": searched,
+            "hits": hits,
+        });
+        println!("{}", serde_json::to_string_pretty(&payload)?);
+        return Ok(());
+    }
+
+    if hits.is_empty() {
+        println!(
+            "No matches for `{}` ({searched} documents searched)",
+            opts.query
+        );
+        return Ok(());
+    }
+
+    for hit in hits {
+        println!("{}:{} [{}]", hit.path, hit.line, hit.kind.label());
+        println!("    {}", hit.snippet);
+    }
+    Ok(())
+}
@@ -0,0 +1,478 @@
+use std::path::Path;
+
+use chrono::Utc;
+use serde_json::{Value, json};
+use vizier_core::{
+    auditor::Auditor,
+    display, session_schema,
+    sessions::{self, SessionEntry, SessionGcAction, SessionMigrationStatus},
+};
+
+use super::shared::{format_block, format_table};
+use super::types::{SessionsOptions, SessionsOutputFormat, SessionsRequest};
+
+pub(crate) fn run_sessions(
+    project_root: &Path,
+    opts: SessionsOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match &opts.request {
+        SessionsRequest::List => list_sessions(project_root, opts.format),
+        SessionsRequest::Show { session_id } => show_session(project_root, session_id, opts.format),
+        SessionsRequest::Gc { policy, dry_run } => {
+            gc_sessions(project_root, policy, *dry_run, opts.format)
+        }
+        SessionsRequest::Fsck { repair } => fsck_sessions(project_root, *repair, opts.format),
+        SessionsRequest::Migrate { dry_run } => {
+            migrate_sessions(project_root, *dry_run, opts.format)
+        }
+        SessionsRequest::Schema => {
+            print!("{}", session_schema::SESSION_SCHEMA_JSON);
+            Ok(())
+        }
+    }
+}
+
+fn session_json(entry: &SessionEntry) -> Value {
+    json!({
+        "id": entry.id,
+        "modified_at": entry.modified.to_rfc3339(),
+        "age_days": entry.age_days(Utc::now()),
+        "size_bytes": entry.size_bytes,
+        "compressed": entry.is_compressed(),
+        "files": entry.files,
+    })
+}
+
+fn list_sessions(
+    project_root: &Path,
+    format: SessionsOutputFormat,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let entries = sessions::list_sessions(project_root)?;
+    let total_bytes = entries.iter().map(|entry| entry.size_bytes).sum::<u64>();
+
+    if matches!(format, SessionsOutputFormat::Json) {
+        let payload = json!({
+            "outcome": "sessions_listed",
+            "count": entries.len(),
+            "total_bytes": total_bytes,
+            "sessions": entries.iter().map(session_json).collect::<Vec<_>>(),
+        });
+        println!("{}", serde_json::to_string_pretty(&payload)?);
+        return Ok(());
+    }
+
+    if entries.is_empty() {
+        println!("{}", display::outcome_line("No sessions recorded"));
+        return Ok(());
+    }
+
+    let now = Utc::now();
+    let mut rows = vec![vec![
+        "Session".to_string(),
+        "Modified".to_string(),
+        "Age".to_string(),
+        "Size".to_string(),
+        "State".to_string(),
+    ]];
+    rows.extend(entries.iter().map(|entry| {
+        vec![
+            entry.id.clone(),
+            entry.modified.format("%Y-%m-%d %H:%M").to_string(),
+            format!("{}d", entry.age_days(now)),
+            display::format_bytes(entry.size_bytes),
+            if entry.is_compressed() {
+                "compressed"
+            } else {
+                "plain"
+            }
+            .to_string(),
+        ]
+    }));
+    println!("{}", format_table(&rows, 0));
+    println!();
+    println!(
+        "{}",
+        format_block(vec![
+            ("Sessions".to_string(), entries.len().to_string()),
+            ("Total size".to_string(), display::format_bytes(total_bytes)),
+        ])
+    );
+    Ok(())
+}
+
+fn show_session(
+    project_root: &Path,
+    session_id: &str,
+    format: SessionsOutputFormat,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(entry) = sessions::find_session(project_root, session_id)? else {
+        return Err(format!("session `{session_id}` not found under .vizier/sessions").into());
+    };
+    let session = sessions::read_session_log(&entry)?;
+    let outcome = sessions::read_session_json(&entry, "outcome.json")?;
+
+    if matches!(format, SessionsOutputFormat::Json) {
+        let payload = json!({
+            "outcome": "session_shown",
+            "session": session_json(&entry),
+            "log": session,
+            "command_outcome": outcome,
+        });
+        println!("{}", serde_json::to_string_pretty(&payload)?);
+        return Ok(());
+    }
+
+    let field = |value: &Option<Value>, pointer: &str| {
+        value
+            .as_ref()
+            .and_then(|value| value.pointer(pointer))
+            .and_then(display::value_to_string)
+            .unwrap_or_default()
+    };
+    let rows = vec![
+        ("Session".to_string(), entry.id.clone()),
+        ("Path".to_string(), entry.path.display().to_string()),
+        (
+            "Modified".to_string(),
+            entry.modified.format("%Y-%m-%d %H:%M:%S").to_string(),
+        ),
+        ("Size".to_string(), display::format_bytes(entry.size_bytes)),
+        ("Files".to_string(), entry.files.join(", ")),
+        ("Workflow".to_string(), field(&session, "/workflow_type")),
+        ("Model".to_string(), field(&session, "/model/name")),
+        (
+            "Messages".to_string(),
+            session
+                .as_ref()
+                .and_then(|value| value.pointer("/messages"))
+                .and_then(Value::as_array)
+                .map(|messages| messages.len().to_string())
+                .unwrap_or_default(),
+        ),
+        ("Command".to_string(), field(&outcome, "/command")),
+        ("Exit".to_string(), field(&outcome, "/exit/status")),
+    ];
+    println!("{}", format_block(rows));
+    Ok(())
+}
+
+fn gc_sessions(
+    project_root: &Path,
+    policy: &vizier_core::config::SessionsConfig,
+    dry_run: bool,
+    format: SessionsOutputFormat,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if policy.keep_days == 0 && policy.keep_last == 0 {
+        display::warn(
+            "no session retention configured; set [sessions] keep_days/keep_last or pass --since/--keep-last",
+        );
+    }
+
+    let entries = sessions::list_sessions(project_root)?;
+    let current = Auditor::session_id();
+    let candidates = sessions::plan_session_gc(&entries, policy, Utc::now(), &[current.as_str()]);
+
+    let mut results = Vec::new();
+    let mut reclaimed = 0u64;
+    for candidate in &candidates {
+        let error = if dry_run {
+            None
+        } else {
+            sessions::apply_session_gc(candidate)
+                .err()
+                .map(|err| err.to_string())
+        };
+        match &error {
+            Some(err) => display::warn(format!(
+                "unable to {} session {}: {err}",
+                candidate.action.as_str(),
+                candidate.entry.id
+            )),
+            None if candidate.action == SessionGcAction::Remove => {
+                reclaimed += candidate.entry.size_bytes
+            }
+            None => {}
+        }
+        results.push((candidate, error));
+    }
+    let failed = results.iter().filter(|(_, error)| error.is_some()).count();
+
+    if matches!(format, SessionsOutputFormat::Json) {
+        let payload = json!({
+            "outcome": if dry_run { "sessions_gc_planned" } else { "sessions_gc_completed" },
+            "dry_run": dry_run,
+            "keep_days": policy.keep_days,
+            "keep_last": policy.keep_last,
+            "compress": policy.compress,
+            "scanned": entries.len(),
+            "reclaimed_bytes": reclaimed,
+            "sessions": results
+                .iter()
+                .map(|(candidate, error)| json!({
+                    "id": candidate.entry.id,
+                    "action": candidate.action.as_str(),
+                    "reason": candidate.reason,
+                    "size_bytes": candidate.entry.size_bytes,
+                    "error": error,
+                }))
+                .collect::<Vec<_>>(),
+        });
+        println!("{}", serde_json::to_string_pretty(&payload)?);
+    } else {
+        let verb = match (dry_run, policy.compress) {
+            (true, true) => "Would compress",
+            (true, false) => "Would remove",
+            (false, true) => "Compressed",
+            (false, false) => "Removed",
+        };
+        let mut rows = vec![
+            (
+                "Outcome".to_string(),
+                if dry_run {
+                    "Session gc dry run"
+                } else {
+                    "Session gc complete"
+                }
+                .to_string(),
+            ),
+            ("Scanned".to_string(), entries.len().to_string()),
+            (verb.to_string(), (candidates.len() - failed).to_string()),
+        ];
+        if !policy.compress {
+            rows.push(("Reclaimed".to_string(), display::format_bytes(reclaimed)));
+        }
+        if failed > 0 {
+            rows.push(("Failed".to_string(), failed.to_string()));
+        }
+        println!("{}", format_block(rows));
+        for (candidate, _) in &results {
+            display::info(format!(
+                "{} {} ({})",
+                candidate.action.as_str(),
+                candidate.entry.id,
+                candidate.reason
+            ));
+        }
+    }
+
+    if failed > 0 {
+        return Err(format!("failed to collect {failed} session(s)").into());
+    }
+    Ok(())
+}
+
+fn fsck_sessions(
+    project_root: &Path,
+    repair: bool,
+    format: SessionsOutputFormat,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let entries = sessions::list_sessions(project_root)?;
+    // The running command's own session may be mid-write.
+    let current = Auditor::session_id();
+    let mut results = Vec::new();
+    for entry in entries.iter().filter(|entry| entry.id != current) {
+        for issue in sessions::fsck_session(entry)? {
+            let repaired = if repair {
+                match sessions::repair_session_issue(entry, &issue) {
+                    Ok(action) => Ok(Some(action)),
+                    Err(err) => {
+                        display::warn(format!(
+                            "unable to repair {} in session {}: {err}",
+                            issue.artifact, issue.session
+                        ));
+                        Err(err.to_string())
+                    }
+                }
+            } else {
+                Ok(None)
+            };
+            results.push((issue, repaired));
+        }
+    }
+    let failed = results.iter().filter(|(_, result)| result.is_err()).count();
+    let unrepaired = results.len()
+        - results
+            .iter()
+            .filter(|(_, r)| matches!(r, Ok(Some(_))))
+            .count();
+
+    if matches!(format, SessionsOutputFormat::Json) {
+        let payload = json!({
+            "outcome": match (results.is_empty(), repair) {
+                (true, _) => "sessions_fsck_clean",
+                (false, false) => "sessions_fsck_issues",
+                (false, true) => "sessions_fsck_repaired",
+            },
+            "repair": repair,
+            "scanned": entries.len(),
+            "issues": results
+                .iter()
+                .map(|(issue, result)| json!({
+                    "session": issue.session,
+                    "artifact": issue.artifact,
+                    "kind": issue.kind.as_str(),
+                    "path": issue.path.display().to_string(),
+                    "detail": issue.detail,
+                    "repaired": result.as_ref().ok().cloned().flatten(),
+                    "error": result.as_ref().err(),
+                }))
+                .collect::<Vec<_>>(),
+        });
+        println!("{}", serde_json::to_string_pretty(&payload)?);
+    } else {
+        let outcome = match (results.is_empty(), repair) {
+            (true, _) => "Session logs are intact".to_string(),
+            (false, false) => format!(
+                "{} session log issue(s) found (rerun with --repair to fix)",
+                results.len()
+            ),
+            (false, true) => format!("Repaired {} session log issue(s)", results.len() - failed),
+        };
+        let mut rows = vec![
+            ("Outcome".to_string(), outcome),
+            ("Scanned".to_string(), entries.len().to_string()),
+        ];
+        if failed > 0 {
+            rows.push(("Failed".to_string(), failed.to_string()));
+        }
+        println!("{}", format_block(rows));
+        if !results.is_empty() {
+            let mut table = vec![vec![
+                "Session".to_string(),
+                "Artifact".to_string(),
+                "Issue".to_string(),
+                "Detail".to_string(),
+            ]];
+            table.extend(results.iter().map(|(issue, result)| {
+                vec![
+                    issue.session.clone(),
+                    issue.artifact.clone(),
+                    issue.kind.as_str().to_string(),
+                    match result {
+                        Ok(Some(action)) => action.clone(),
+                        Ok(None) => issue.detail.clone(),
+                        Err(err) => format!("repair failed: {err}"),
+                    },
+                ]
+            }));
+            println!();
+            println!("{}", format_table(&table, 0));
+        }
+    }
+
+    if failed > 0 {
+        return Err(format!("failed to repair {failed} session log issue(s)").into());
+    }
+    if unrepaired > 0 {
+        crate::cli::outcome::exit(10);
+    }
+    Ok(())
+}
+
+fn migrate_sessions(
+    project_root: &Path,
+    dry_run: bool,
+    format: SessionsOutputFormat,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let entries = sessions::list_sessions(project_root)?;
+    // The running command's own session is written by this build, so it is already current.
+    let current = Auditor::session_id();
+    let mut results = Vec::new();
+    for entry in entries.iter().filter(|entry| entry.id != current) {
+        if let Some(migration) = sessions::migrate_session(entry, dry_run)? {
+            results.push(migration);
+        }
+    }
+    let migrated = results
+        .iter()
+        .filter(|result| result.status == SessionMigrationStatus::Migrated)
+        .count();
+    let failed = results
+        .iter()
+        .filter(|result| matches!(result.status, SessionMigrationStatus::Failed(_)))
+        .count();
+    let status_label = |status: &SessionMigrationStatus| match status {
+        SessionMigrationStatus::Current => "current",
+        SessionMigrationStatus::Migrated if dry_run => "would_migrate",
+        SessionMigrationStatus::Migrated => "migrated",
+        SessionMigrationStatus::Failed(_) => "failed",
+    };
+
+    if matches!(format, SessionsOutputFormat::Json) {
+        let payload = json!({
+            "outcome": if dry_run { "sessions_migrate_planned" } else { "sessions_migrated" },
+            "dry_run": dry_run,
+            "schema": session_schema::SESSION_SCHEMA,
+            "schema_version": session_schema::SESSION_SCHEMA_VERSION,
+            "scanned": results.len(),
+            "migrated": migrated,
+            "failed": failed,
+            "sessions": results
+                .iter()
+                .filter(|result| result.status != SessionMigrationStatus::Current)
+                .map(|result| json!({
+                    "id": result.session,
+                    "from_version": result.from_version,
+                    "status": status_label(&result.status),
+                    "error": match &result.status {
+                        SessionMigrationStatus::Failed(err) => Some(err),
+                        _ => None,
+                    },
+                }))
+                .collect::<Vec<_>>(),
+        });
+        println!("{}", serde_json::to_string_pretty(&payload)?);
+    } else {
+        let mut rows = vec![
+            (
+                "Outcome".to_string(),
+                match (migrated, dry_run) {
+                    (0, _) if failed == 0 => "Session logs are current".to_string(),
+                    (count, true) => format!("{count} session log(s) would be migrated"),
+                    (count, false) => format!("Migrated {count} session log(s)"),
+                },
+            ),
+            (
+                "Schema".to_string(),
+                format!(
+                    "{} (v{})",
+                    session_schema::SESSION_SCHEMA,
+                    session_schema::SESSION_SCHEMA_VERSION
+                ),
+            ),
+            ("Scanned".to_string(), results.len().to_string()),
+        ];
+        if failed > 0 {
+            rows.push(("Failed".to_string(), failed.to_string()));
+        }
+        println!("{}", format_block(rows));
+        let changed = results
+            .iter()
+            .filter(|result| result.status != SessionMigrationStatus::Current)
+            .collect::<Vec<_>>();
+        if !changed.is_empty() {
+            let mut table = vec![vec![
+                "Session".to_string(),
+                "From".to_string(),
+                "Result".to_string(),
+            ]];
+            table.extend(changed.iter().map(|result| {
+                vec![
+                    result.session.clone(),
+                    format!("v{}", result.from_version),
+                    match &result.status {
+                        SessionMigrationStatus::Failed(err) => err.clone(),
+                        status => status_label(status).replace('_', " "),
+                    },
+                ]
+            }));
+            println!();
+            println!("{}", format_table(&table, 0));
+        }
+    }
+
+    if failed > 0 {
+        crate::cli::outcome::exit(10);
+    }
+    Ok(())
+}
@@ -10,12 +10,11 @@ use vizier_core::{
     agent::{AgentRequest, DEFAULT_AGENT_TIMEOUT},
     auditor::{self, Auditor},
     config,
-    display::{self, LogLevel, ProgressEvent, Verbosity, format_label_value_block},
+    display::{self, LogLevel, ProgressEvent, Subsystem, Verbosity, format_label_value_block},
     vcs::{self, AttemptOutcome, CredentialAttempt, PushErrorKind, RemoteScheme},
 };
 
 use super::types::CommitMode;
-use crate::jobs;
 
 pub(crate) fn clip_message(msg: &str) -> String {
     const LIMIT: usize = 90;
@@ -30,6 +29,20 @@ pub(crate) fn clip_message(msg: &str) -> String {
     clipped
 }
 
+/// Path of `program` as a process spawn would find it: taken as-is when it has a directory
+/// component, otherwise searched on `PATH`.
+pub(crate) fn find_program(program: &str) -> Option<PathBuf> {
+    let path = PathBuf::from(program);
+    if path.components().count() > 1 {
+        return path.is_file().then_some(path);
+    }
+    std::env::var_os("PATH").and_then(|paths| {
+        std::env::split_paths(&paths)
+            .map(|dir| dir.join(program))
+            .find(|candidate| candidate.is_file())
+    })
+}
+
 pub(crate) fn format_block(rows: Vec<(String, String)>) -> String {
     format_label_value_block(&rows, 0)
 }
@@ -38,10 +51,18 @@ pub(crate) fn format_block_with_indent(rows: Vec<(String, String)>, indent: usiz
     format_label_value_block(&rows, indent)
 }
 
+/// Renders rows as aligned columns; the first row is the header, whose cells are translated
+/// for the configured `[output] language`.
 pub(crate) fn format_table(rows: &[Vec<String>], indent: usize) -> String {
     if rows.is_empty() {
         return String::new();
     }
+    let mut rows = std::borrow::Cow::Borrowed(rows);
+    if let Some(language) = vizier_core::config::output_language() {
+        for cell in &mut rows.to_mut()[0] {
+            *cell = vizier_core::i18n::translate(Some(&language), cell).into_owned();
+        }
+    }
 
     let column_count = rows.iter().map(|row| row.len()).max().unwrap_or(0);
     if column_count == 0 {
@@ -49,7 +70,7 @@ pub(crate) fn format_table(rows: &[Vec<String>], indent: usize) -> String {
     }
 
     let mut widths = vec![0usize; column_count];
-    for row in rows {
+    for row in rows.iter() {
         for (idx, cell) in row.iter().enumerate() {
             widths[idx] = widths[idx].max(cell.chars().count());
         }
@@ -200,10 +221,14 @@ pub(crate) fn push_origin_if_requested(
         return Ok(());
     }
 
-    display::info("Pushing current branch to origin...");
+    display::log(
+        Subsystem::Git,
+        LogLevel::Info,
+        "Pushing current branch to origin...",
+    );
     match vcs::push_current_branch("origin") {
         Ok(_) => {
-            display::info("Push to origin completed.");
+            display::log(Subsystem::Git, LogLevel::Info, "Push to origin completed.");
             Ok(())
         }
         Err(err) => {
@@ -216,6 +241,28 @@ pub(crate) fn push_origin_if_requested(
                 } => {
                     render_push_auth_failure(remote, url, scheme, attempts);
                 }
+                PushErrorKind::Network { url, message, .. } => {
+                    display::emit(
+                        LogLevel::Error,
+                        format!("Push to origin failed: unable to reach {url}: {message}"),
+                    );
+                    display::emit(
+                        LogLevel::Error,
+                        "Hint: this is a network or transport error, not a credential problem; check connectivity and proxy settings, then retry.",
+                    );
+                }
+                PushErrorKind::HostKey { host, message, .. } => {
+                    display::emit(
+                        LogLevel::Error,
+                        format!("Push to origin failed: could not verify {host}: {message}"),
+                    );
+                    display::emit(
+                        LogLevel::Error,
+                        format!(
+                            "Hint: confirm the host's fingerprint and add it to ~/.ssh/known_hosts (for example by running `ssh -T {host}` once), or check the system certificate store for HTTPS."
+                        ),
+                    );
+                }
                 PushErrorKind::General(message) => {
                     display::emit(
                         LogLevel::Error,
@@ -289,6 +336,9 @@ pub(crate) fn build_agent_request(
         config::AgentRuntimeResolution::ProvidedCommand => {
             metadata.insert("agent_command_source".to_string(), "configured".to_string());
         }
+        config::AgentRuntimeResolution::Mock => {
+            metadata.insert("agent_command_source".to_string(), "mock".to_string());
+        }
     }
 
     AgentRequest {
@@ -301,18 +351,18 @@ pub(crate) fn build_agent_request(
         scope: agent.scope,
         metadata,
         timeout: Some(DEFAULT_AGENT_TIMEOUT),
+        env: agent.env.clone(),
     }
 }
 
 pub(crate) fn spawn_plain_progress_logger(
     mut rx: mpsc::Receiver<ProgressEvent>,
 ) -> Option<JoinHandle<()>> {
-    let cfg = display::get_display_config();
-    if matches!(cfg.verbosity, Verbosity::Quiet) {
+    let verbosity = display::get_display_config().verbosity_for(display::Subsystem::Agent);
+    if matches!(verbosity, Verbosity::Quiet) {
         return None;
     }
 
-    let verbosity = cfg.verbosity;
     Some(tokio::spawn(async move {
         while let Some(event) = rx.recv().await {
             for line in display::render_progress_event(&event, verbosity) {
@@ -326,6 +376,7 @@ pub(crate) fn audit_disposition(mode: CommitMode) -> auditor::CommitDisposition
     match mode {
         CommitMode::AutoCommit => auditor::CommitDisposition::Auto,
         CommitMode::HoldForReview => auditor::CommitDisposition::Hold,
+        CommitMode::Patch => auditor::CommitDisposition::Patch,
     }
 }
 
@@ -358,52 +409,6 @@ impl Drop for WorkdirGuard {
     }
 }
 
-pub(crate) struct TempWorktree {
-    name: String,
-    path: PathBuf,
-}
-
-impl TempWorktree {
-    pub(crate) fn create(
-        job_id: &str,
-        branch: &str,
-        purpose: &str,
-    ) -> Result<Self, Box<dyn std::error::Error>> {
-        let repo_root = vcs::repo_root()
-            .map_err(|err| -> Box<dyn std::error::Error> { Box::new(err) })?
-            .to_path_buf();
-        let tmp_root = repo_root.join(".vizier/tmp-worktrees");
-        fs::create_dir_all(&tmp_root)?;
-        let dir_name = format!("{purpose}-{job_id}");
-        let worktree_path = tmp_root.join(&dir_name);
-        let worktree_name = format!("vizier-{purpose}-{job_id}");
-
-        vcs::add_worktree_for_branch(&worktree_name, &worktree_path, branch)?;
-        jobs::record_current_job_worktree(&repo_root, Some(&worktree_name), &worktree_path);
-
-        Ok(Self {
-            name: worktree_name,
-            path: worktree_path,
-        })
-    }
-
-    pub(crate) fn path(&self) -> &Path {
-        &self.path
-    }
-
-    pub(crate) fn cleanup(self) -> Result<(), Box<dyn std::error::Error>> {
-        if let Err(err) = vcs::remove_worktree(&self.name, true) {
-            display::warn(format!(
-                "temporary worktree cleanup failed ({err}); remove manually with `git worktree prune`"
-            ));
-        }
-        if self.path.exists() {
-            let _ = fs::remove_dir_all(&self.path);
-        }
-        Ok(())
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::build_agent_request;
@@ -0,0 +1,158 @@
+use std::fs;
+use std::path::Path;
+
+use serde_json::json;
+use vizier_core::{atomic_file, narrative_history};
+
+use super::shared::format_block;
+use super::types::{SnapshotOptions, SnapshotOutputFormat, SnapshotRequest};
+
+pub(crate) fn run_snapshot(
+    project_root: &Path,
+    opts: SnapshotOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match &opts.request {
+        SnapshotRequest::Show { revision } => show_snapshot(project_root, revision, opts.format),
+        SnapshotRequest::Diff { from, to } => diff_snapshots(project_root, from, to, opts.format),
+        SnapshotRequest::Rebuild {
+            force,
+            dry_run,
+            sessions,
+        } => rebuild_snapshot(project_root, *force, *dry_run, *sessions, opts.format),
+    }
+}
+
+fn show_snapshot(
+    project_root: &Path,
+    revision: &str,
+    format: SnapshotOutputFormat,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let narrative = narrative_history::narrative_at(project_root, revision)
+        .map_err(|err| format!("unable to read the narrative at `{revision}`: {err}"))?;
+
+    if matches!(format, SnapshotOutputFormat::Json) {
+        let payload = json!({
+            "outcome": "snapshot_shown",
+            "narrative": narrative,
+        });
+        println!("{}", serde_json::to_string_pretty(&payload)?);
+        return Ok(());
+    }
+
+    println!(
+        "{}",
+        format_block(vec![
+            ("Revision".to_string(), narrative.revision.clone()),
+            ("Commit".to_string(), narrative.commit.clone()),
+            ("Committed".to_string(), narrative.committed_at.clone()),
+            ("Summary".to_string(), narrative.summary.clone()),
+            ("Threads".to_string(), narrative.threads.len().to_string()),
+        ])
+    );
+    if narrative.snapshot.is_none() && narrative.threads.is_empty() {
+        println!();
+        println!(
+            "No narrative committed under {} at this revision",
+            narrative_history::NARRATIVE_DIR
+        );
+        return Ok(());
+    }
+    if let Some(snapshot) = narrative.snapshot.as_ref() {
+        println!();
+        println!("{}", snapshot.trim_end());
+    }
+    if let Some(glossary) = narrative.glossary.as_ref() {
+        println!();
+        println!("{}", glossary.trim_end());
+    }
+    for (name, thread) in &narrative.threads {
+        println!();
+        println!("--- thread: {name} ---");
+        println!("{}", thread.trim_end());
+    }
+    Ok(())
+}
+
+fn diff_snapshots(
+    project_root: &Path,
+    from: &str,
+    to: &str,
+    format: SnapshotOutputFormat,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let changelog =
+        narrative_history::narrative_changelog(project_root, from, to).map_err(|err| {
+            format!("unable to diff the narrative between `{from}` and `{to}`: {err}")
+        })?;
+
+    if matches!(format, SnapshotOutputFormat::Json) {
+        let payload = json!({
+            "outcome": "snapshot_diffed",
+            "changelog": changelog,
+        });
+        println!("{}", serde_json::to_string_pretty(&payload)?);
+        return Ok(());
+    }
+
+    print!("{}", changelog.render());
+    Ok(())
+}
+
+fn rebuild_snapshot(
+    project_root: &Path,
+    force: bool,
+    dry_run: bool,
+    sessions: usize,
+    format: SnapshotOutputFormat,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let rel_path = Path::new(narrative_history::NARRATIVE_DIR).join("snapshot.md");
+    let path = project_root.join(&rel_path);
+    let existing = fs::read_to_string(&path).unwrap_or_default();
+    if !dry_run && !force && !existing.trim().is_empty() {
+        return Err(format!(
+            "{} already has content; pass --force to replace it or --dry-run to preview the rebuild",
+            rel_path.display()
+        )
+        .into());
+    }
+
+    let rebuilt = narrative_history::rebuild_snapshot_from_ledger(project_root, sessions)
+        .map_err(|err| format!("unable to rebuild the snapshot from the ledger: {err}"))?;
+    if !dry_run {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        atomic_file::write_atomic(&path, rebuilt.document.as_bytes())?;
+    }
+
+    if matches!(format, SnapshotOutputFormat::Json) {
+        let payload = json!({
+            "outcome": if dry_run { "snapshot_rebuild_previewed" } else { "snapshot_rebuilt" },
+            "path": rel_path,
+            "written": !dry_run,
+            "plans": rebuilt.plans,
+            "activity": rebuilt.activity,
+            "document": rebuilt.document,
+        });
+        println!("{}", serde_json::to_string_pretty(&payload)?);
+        return Ok(());
+    }
+
+    if dry_run {
+        print!("{}", rebuilt.document);
+        return Ok(());
+    }
+    println!(
+        "{}",
+        format_block(vec![
+            ("Outcome".to_string(), "Snapshot rebuilt".to_string()),
+            ("Path".to_string(), rel_path.display().to_string()),
+            ("Plans".to_string(), rebuilt.plans.len().to_string()),
+            ("Sessions".to_string(), rebuilt.activity.len().to_string()),
+            (
+                "Next".to_string(),
+                format!("review and commit {}", rel_path.display()),
+            ),
+        ])
+    );
+    Ok(())
+}
@@ -0,0 +1,158 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use chrono::{Duration, Utc};
+use serde_json::{Value, json};
+use vizier_core::display;
+use vizier_core::stats::{self, StatsBucket, StatsReport};
+
+use super::shared::{format_block, format_table};
+use super::types::{StatsOptions, StatsOutputFormat};
+
+pub(crate) fn run_stats(
+    project_root: &Path,
+    jobs_root: &Path,
+    opts: StatsOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let since = opts
+        .since_days
+        .map(|days| Utc::now() - Duration::days(i64::from(days)));
+    let report = stats::collect_stats(project_root, jobs_root, since)?;
+
+    if matches!(opts.format, StatsOutputFormat::Json) {
+        let payload = json!({
+            "outcome": "stats_reported",
+            "since": report.since.map(|since| since.to_rfc3339()),
+            "sessions_scanned": report.sessions_scanned,
+            "jobs_scanned": report.jobs_scanned,
+            "totals": bucket_json(&report.totals),
+            "by_scope": buckets_json(&report.by_scope),
+            "by_plan": buckets_json(&report.by_plan),
+        });
+        println!("{}", serde_json::to_string_pretty(&payload)?);
+        return Ok(());
+    }
+
+    if report.sessions_scanned == 0 && report.jobs_scanned == 0 {
+        println!("{}", display::outcome_line("No sessions or jobs recorded"));
+        return Ok(());
+    }
+
+    print_summary(&report);
+    for (heading, buckets) in [("Scope", &report.by_scope), ("Plan", &report.by_plan)] {
+        println!();
+        println!("{}", format_table(&bucket_rows(heading, buckets), 0));
+    }
+    Ok(())
+}
+
+fn bucket_json(bucket: &StatsBucket) -> Value {
+    let mut value = serde_json::to_value(bucket).unwrap_or(Value::Null);
+    value["mean_agent_ms"] = json!(bucket.mean_agent_ms());
+    value["gate_failure_rate"] = json!(bucket.gate_failure_rate());
+    value
+}
+
+fn buckets_json(buckets: &BTreeMap<String, StatsBucket>) -> Value {
+    Value::Object(
+        buckets
+            .iter()
+            .map(|(key, bucket)| (key.clone(), bucket_json(bucket)))
+            .collect(),
+    )
+}
+
+fn print_summary(report: &StatsReport) {
+    let totals = &report.totals;
+    let rows = vec![
+        (
+            "Window".to_string(),
+            report
+                .since
+                .map(|since| format!("since {}", since.format("%Y-%m-%d %H:%M")))
+                .unwrap_or_else(|| "all time".to_string()),
+        ),
+        ("Sessions".to_string(), report.sessions_scanned.to_string()),
+        ("Jobs".to_string(), report.jobs_scanned.to_string()),
+        ("Agent runs".to_string(), totals.agent_runs.to_string()),
+        ("Agent time".to_string(), format_ms(totals.agent_wall_ms)),
+        ("Exit codes".to_string(), exit_codes(totals)),
+        ("Gate failures".to_string(), gate_failures(totals)),
+        ("Retries".to_string(), totals.retries.to_string()),
+        ("Tokens".to_string(), tokens(totals)),
+    ];
+    println!("{}", format_block(rows));
+}
+
+fn bucket_rows(heading: &str, buckets: &BTreeMap<String, StatsBucket>) -> Vec<Vec<String>> {
+    let mut rows = vec![vec![
+        heading.to_string(),
+        "Sessions".to_string(),
+        "Agent runs".to_string(),
+        "Agent time".to_string(),
+        "Mean".to_string(),
+        "Exit codes".to_string(),
+        "Gate failures".to_string(),
+        "Retries".to_string(),
+        "Tokens".to_string(),
+    ]];
+    rows.extend(buckets.iter().map(|(key, bucket)| {
+        vec![
+            key.clone(),
+            bucket.sessions.to_string(),
+            bucket.agent_runs.to_string(),
+            format_ms(bucket.agent_wall_ms),
+            bucket
+                .mean_agent_ms()
+                .map(format_ms)
+                .unwrap_or_else(|| "-".to_string()),
+            exit_codes(bucket),
+            gate_failures(bucket),
+            bucket.retries.to_string(),
+            tokens(bucket),
+        ]
+    }));
+    rows
+}
+
+fn exit_codes(bucket: &StatsBucket) -> String {
+    if bucket.exit_codes.is_empty() {
+        return "-".to_string();
+    }
+    bucket
+        .exit_codes
+        .iter()
+        .map(|(code, count)| format!("{code}:{count}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn gate_failures(bucket: &StatsBucket) -> String {
+    match bucket.gate_failure_rate() {
+        Some(rate) => format!(
+            "{}/{} ({:.0}%)",
+            bucket.gate_failures,
+            bucket.gate_runs,
+            rate * 100.0
+        ),
+        None => "-".to_string(),
+    }
+}
+
+fn tokens(bucket: &StatsBucket) -> String {
+    bucket
+        .tokens
+        .map(|tokens| tokens.to_string())
+        .unwrap_or_else(|| "n/a".to_string())
+}
+
+fn format_ms(ms: u128) -> String {
+    let secs = ms / 1000;
+    if secs >= 3600 {
+        format!("{}h{:02}m", secs / 3600, (secs % 3600) / 60)
+    } else if secs >= 60 {
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{}.{}s", secs, (ms % 1000) / 100)
+    }
+}
@@ -0,0 +1,283 @@
+use std::path::Path;
+
+use serde_json::json;
+use vizier_core::never_send::{NeverSend, record_withheld};
+use vizier_core::thread_revise::{self, RevisionOutcome, ThreadRevision};
+use vizier_core::{auditor::Auditor, display, thread_state, vcs};
+
+use super::shared::{format_block, format_table};
+use super::types::{ThreadOptions, ThreadOutputFormat, ThreadRequest};
+
+const SNAPSHOT_PATH: &str = ".vizier/narrative/snapshot.md";
+
+/// Lists narrative threads by state, moves one thread to a new state by committing its updated
+/// front matter, or revises threads in bulk through the agent.
+pub(crate) async fn run_thread(
+    project_root: &Path,
+    opts: ThreadOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match opts.request {
+        ThreadRequest::List { state } => {
+            let threads = thread_state::list_threads(project_root)?
+                .into_iter()
+                .filter(|thread| state.is_none_or(|state| thread.status.state == state))
+                .collect::<Vec<_>>();
+            match opts.format {
+                ThreadOutputFormat::Json => {
+                    let payload = json!({
+                        "outcome": "thread_list",
+                        "state": state.map(|state| state.as_str()),
+                        "threads": threads,
+                    });
+                    println!("{}", serde_json::to_string_pretty(&payload)?);
+                }
+                ThreadOutputFormat::Text => {
+                    println!(
+                        "Outcome: vizier thread list: {} thread(s){}",
+                        threads.len(),
+                        state
+                            .map(|state| format!(" {}", state.as_str()))
+                            .unwrap_or_default()
+                    );
+                    let rows = threads
+                        .iter()
+                        .map(|thread| {
+                            let detail =
+                                match (&thread.status.resolved_by, &thread.status.blocked_reason) {
+                                    (Some(plan), _) => format!("by {plan}"),
+                                    (None, Some(reason)) => reason.clone(),
+                                    (None, None) => String::new(),
+                                };
+                            vec![
+                                thread.status.state.as_str().to_string(),
+                                thread.thread.clone(),
+                                detail,
+                                thread.owners.join(", "),
+                            ]
+                        })
+                        .collect::<Vec<_>>();
+                    if !rows.is_empty() {
+                        println!("{}", format_table(&rows, 0));
+                    }
+                }
+            }
+        }
+        ThreadRequest::Transition {
+            thread,
+            status,
+            plan,
+        } => {
+            // A pending plan carries the change so it lands with the merge; once the plan branch
+            // is gone the change goes on the current branch.
+            let branch = match &plan {
+                Some((_, branch)) if vcs::branch_exists_in(project_root, branch)? => branch.clone(),
+                _ => vcs::current_branch_name_in(project_root)?
+                    .ok_or("HEAD is detached; check out a branch or pass --plan")?,
+            };
+            let rel = thread_state::thread_rel_path(&thread);
+            let rel_display = rel.to_string_lossy().replace('\\', "/");
+            let document =
+                vcs::read_blob_at_revision_in(project_root, &format!("{branch}:{rel_display}"))
+                    .map_err(|_| format!("thread `{thread}` has no {rel_display} on {branch}"))?;
+            let before = thread_state::thread_status(&document)
+                .map_err(|err| format!("thread `{thread}`: {err}"))?;
+            thread_state::check_transition(&before, &status)
+                .map_err(|err| format!("thread `{thread}`: {err}"))?;
+
+            let (verb, outcome) = match status.state {
+                thread_state::ThreadState::Open => ("reopen", "thread_reopened"),
+                thread_state::ThreadState::Blocked => ("block", "thread_blocked"),
+                thread_state::ThreadState::Resolved => ("resolve", "thread_resolved"),
+            };
+            let updated = thread_state::with_status(&document, &status);
+            let message = match &status.resolved_by {
+                Some(plan) => format!("chore: {verb} thread {thread} by {plan}"),
+                None => format!("chore: {verb} thread {thread}"),
+            };
+            let commit = vcs::commit_files_to_branch_in(
+                project_root,
+                &branch,
+                &[(rel.as_path(), updated.as_str())],
+                &message,
+            )?;
+
+            match opts.format {
+                ThreadOutputFormat::Json => {
+                    let payload = json!({
+                        "outcome": outcome,
+                        "thread": thread,
+                        "from": before.state.as_str(),
+                        "status": status,
+                        "branch": branch,
+                        "commit": commit.to_string(),
+                    });
+                    println!("{}", serde_json::to_string_pretty(&payload)?);
+                }
+                ThreadOutputFormat::Text => {
+                    let mut rows = vec![
+                        (
+                            "Outcome".to_string(),
+                            format!("Thread {}", status.state.as_str()),
+                        ),
+                        ("Thread".to_string(), thread.clone()),
+                        (
+                            "State".to_string(),
+                            format!("{} -> {}", before.state.as_str(), status.state.as_str()),
+                        ),
+                    ];
+                    if let Some(plan) = &status.resolved_by {
+                        rows.push(("Plan".to_string(), plan.clone()));
+                    }
+                    if let Some(reason) = &status.blocked_reason {
+                        rows.push(("Reason".to_string(), reason.clone()));
+                    }
+                    rows.push(("Branch".to_string(), branch.clone()));
+                    rows.push(("Commit".to_string(), commit.to_string()));
+                    println!("{}", format_block(rows));
+                }
+            }
+        }
+        ThreadRequest::Revise { threads } => {
+            revise_threads(project_root, threads.as_deref(), opts.format).await?
+        }
+    }
+    Ok(())
+}
+
+/// Sends threads on the current branch to the agent in batches and commits every revision and
+/// deletion in a single commit. Nothing is committed when a batch fails or nothing changed.
+async fn revise_threads(
+    project_root: &Path,
+    selected: Option<&[String]>,
+    format: ThreadOutputFormat,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let branch = vcs::current_branch_name_in(project_root)?
+        .ok_or("HEAD is detached; check out a branch to revise threads")?;
+    let mut threads = thread_revise::threads_at_revision(project_root, &branch)?;
+    if let Some(selected) = selected {
+        if let Some(missing) = selected
+            .iter()
+            .find(|name| !threads.iter().any(|thread| &thread.thread == *name))
+        {
+            return Err(format!("thread `{missing}` has no document on {branch}").into());
+        }
+        threads.retain(|thread| selected.contains(&thread.thread));
+    }
+
+    let never_send = NeverSend::from_config(project_root);
+    let mut withheld = Vec::new();
+    threads.retain(|thread| {
+        let rel = thread_state::thread_rel_path(&thread.thread);
+        let keep = !never_send.withholds(&rel);
+        if !keep {
+            withheld.push(rel.to_string_lossy().replace('\\', "/"));
+        }
+        keep
+    });
+    record_withheld("thread_revise", &withheld);
+
+    let snapshot =
+        vcs::read_blob_at_revision_in(project_root, &format!("{branch}:{SNAPSHOT_PATH}"))
+            .unwrap_or_default();
+    let mut revisions: Vec<ThreadRevision> = Vec::new();
+    for batch in thread_revise::batch_threads(threads, thread_revise::MAX_BATCH_BYTES) {
+        let message = thread_revise::batch_message(&snapshot, &batch);
+        let agent_phase = display::start_phase(display::PHASE_AGENT_RUN, None);
+        let response = Auditor::llm_request(
+            thread_revise::REVISE_THREADS_INSTRUCTION.to_string(),
+            message,
+        )
+        .await;
+        agent_phase.finish();
+        revisions.extend(thread_revise::parse_revisions(&response?.content, &batch)?);
+    }
+
+    let count = |outcome: RevisionOutcome| {
+        revisions
+            .iter()
+            .filter(|revision| revision.outcome == outcome)
+            .count()
+    };
+    let (revised, deleted, unchanged) = (
+        count(RevisionOutcome::Revised),
+        count(RevisionOutcome::Deleted),
+        count(RevisionOutcome::Unchanged),
+    );
+
+    let paths = revisions
+        .iter()
+        .map(|revision| thread_state::thread_rel_path(&revision.thread))
+        .collect::<Vec<_>>();
+    let mut writes = Vec::new();
+    let mut removes = Vec::new();
+    for (revision, path) in revisions.iter().zip(&paths) {
+        match (revision.outcome, revision.document.as_deref()) {
+            (RevisionOutcome::Revised, Some(document)) => writes.push((path.as_path(), document)),
+            (RevisionOutcome::Deleted, _) => removes.push(path.as_path()),
+            _ => {}
+        }
+    }
+    let commit = if writes.is_empty() && removes.is_empty() {
+        None
+    } else {
+        let message =
+            format!("chore: revise narrative threads ({revised} revised, {deleted} deleted)");
+        Some(vcs::commit_file_changes_to_branch_in(
+            project_root,
+            &branch,
+            &writes,
+            &removes,
+            &message,
+        )?)
+    };
+
+    match format {
+        ThreadOutputFormat::Json => {
+            let payload = json!({
+                "outcome": "thread_revise",
+                "threads": revisions,
+                "revised": revised,
+                "deleted": deleted,
+                "unchanged": unchanged,
+                "withheld": withheld,
+                "branch": branch,
+                "commit": commit.map(|oid| oid.to_string()),
+            });
+            println!("{}", serde_json::to_string_pretty(&payload)?);
+        }
+        ThreadOutputFormat::Text => {
+            println!(
+                "Outcome: vizier thread revise: {revised} revised, {deleted} deleted, {unchanged} unchanged"
+            );
+            let rows = revisions
+                .iter()
+                .map(|revision| {
+                    vec![
+                        revision.outcome.as_str().to_string(),
+                        revision.thread.clone(),
+                        revision.reason.clone().unwrap_or_default(),
+                    ]
+                })
+                .collect::<Vec<_>>();
+            if !rows.is_empty() {
+                println!("{}", format_table(&rows, 0));
+            }
+            if !withheld.is_empty() {
+                display::warn(format!(
+                    "withheld by [context] never_send: {}",
+                    withheld.join(", ")
+                ));
+            }
+            if let Some(commit) = commit {
+                println!(
+                    "{}",
+                    format_block(vec![
+                        ("Branch".to_string(), branch),
+                        ("Commit".to_string(), commit.to_string()),
+                    ])
+                );
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use std::path::Path;
+
+use vizier_core::review_tools;
+
+use super::types::ToolOptions;
+
+/// Runs one repo-navigation tool, printing plain text for the calling agent and logging the call
+/// when it runs under a review session.
+pub(crate) fn run_tool(
+    project_root: &Path,
+    opts: ToolOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (name, args, result) = match &opts {
+        ToolOptions::File { path, lines } => {
+            let mut args = vec![path.clone()];
+            if let Some((start, end)) = lines {
+                args.extend(["--lines".to_string(), format!("{start}:{end}")]);
+            }
+            (
+                "file",
+                args,
+                review_tools::file_slice(project_root, path, *lines),
+            )
+        }
+        ToolOptions::Blame { path, lines } => (
+            "blame",
+            vec![
+                path.clone(),
+                "--lines".to_string(),
+                format!("{}:{}", lines.0, lines.1),
+            ],
+            review_tools::blame_range(project_root, path, *lines),
+        ),
+        ToolOptions::Search { text, limit } => (
+            "search",
+            vec![text.clone(), "--limit".to_string(), limit.to_string()],
+            review_tools::search(project_root, text, *limit),
+        ),
+    };
+    if let Err(err) = review_tools::log_call(name, &args, result.is_ok()) {
+        eprintln!("warning: could not log tool call: {err}");
+    }
+    print!("{}", result?);
+    Ok(())
+}
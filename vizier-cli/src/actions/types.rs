@@ -1,10 +1,15 @@
-use vizier_core::config;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use vizier_core::{config, review_report::Severity, thread_state};
 
 #[allow(dead_code)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum CommitMode {
     AutoCommit,
     HoldForReview,
+    /// Capture edits as a patch (`vizier run --patch`) and leave the tree as it was.
+    Patch,
 }
 
 impl CommitMode {
@@ -18,6 +23,7 @@ impl CommitMode {
         match self {
             CommitMode::AutoCommit => "auto",
             CommitMode::HoldForReview => "manual",
+            CommitMode::Patch => "patch",
         }
     }
 }
@@ -27,6 +33,9 @@ pub struct ListOptions {
     pub target: Option<String>,
     pub format: Option<config::ListFormat>,
     pub fields: Option<Vec<String>>,
+    pub by_thread: bool,
+    /// Only list plans carrying every one of these labels.
+    pub labels: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -50,3 +59,466 @@ pub enum CleanOutputFormat {
     Text,
     Json,
 }
+
+#[derive(Debug, Clone)]
+pub struct RescueOptions {
+    pub slug: String,
+    pub branch: String,
+    pub action: Option<RescueAction>,
+    pub message: Option<String>,
+    pub output: Option<PathBuf>,
+    pub assume_yes: bool,
+    pub format: RescueOutputFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RescueAction {
+    Commit,
+    Discard,
+    Patch,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RescueOutputFormat {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone)]
+pub struct RebaseOptions {
+    pub slug: String,
+    pub branch: String,
+    pub target: String,
+    pub auto_resolve: bool,
+    pub assume_yes: bool,
+    pub format: RebaseOutputFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RebaseOutputFormat {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone)]
+pub struct PickOptions {
+    pub slug: String,
+    pub branch: String,
+    pub target: String,
+    pub pick: String,
+    pub message: Option<String>,
+    pub format: PickOutputFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PickOutputFormat {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone)]
+pub struct ApplyPatchOptions {
+    pub slug: String,
+    pub branch: String,
+    pub target: String,
+    /// `None` reads the patch from stdin.
+    pub patch: Option<PathBuf>,
+    pub message: Option<String>,
+    pub format: ApplyPatchOutputFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApplyPatchOutputFormat {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone)]
+pub struct VerifyOptions {
+    pub slug: String,
+    pub branch: String,
+    pub target: String,
+    pub format: VerifyOutputFormat,
+}
+
+#[derive(Debug, Clone)]
+pub struct SnapshotOptions {
+    pub request: SnapshotRequest,
+    pub format: SnapshotOutputFormat,
+}
+
+#[derive(Debug, Clone)]
+pub enum SnapshotRequest {
+    Show {
+        revision: String,
+    },
+    Diff {
+        from: String,
+        to: String,
+    },
+    Rebuild {
+        force: bool,
+        dry_run: bool,
+        sessions: usize,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotOutputFormat {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone)]
+pub struct AckOptions {
+    pub slug: String,
+    pub branch: String,
+    pub target: String,
+    pub owner: String,
+    pub format: AckOutputFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AckOutputFormat {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone)]
+pub struct WatchOptions {
+    pub settle: Duration,
+    pub once: bool,
+    pub record: bool,
+    pub format: WatchOutputFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchOutputFormat {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone)]
+pub struct LabelOptions {
+    pub slug: String,
+    pub branch: String,
+    pub change: Option<LabelChange>,
+    pub format: LabelOutputFormat,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LabelChange {
+    Add(Vec<String>),
+    Remove(Vec<String>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LabelOutputFormat {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone)]
+pub struct NoteOptions {
+    pub slug: String,
+    pub branch: String,
+    pub note: Option<String>,
+    pub format: NoteOutputFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoteOutputFormat {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerifyOutputFormat {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone)]
+pub struct SessionsOptions {
+    pub request: SessionsRequest,
+    pub format: SessionsOutputFormat,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionsRequest {
+    List,
+    Show {
+        session_id: String,
+    },
+    Gc {
+        policy: config::SessionsConfig,
+        dry_run: bool,
+    },
+    Fsck {
+        repair: bool,
+    },
+    Migrate {
+        dry_run: bool,
+    },
+    Schema,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionsOutputFormat {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AgentValidateOptions {
+    pub command: Vec<String>,
+    pub filter: Option<String>,
+    pub events: bool,
+    pub timeout: Duration,
+    pub format: AgentValidateOutputFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AgentValidateOutputFormat {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone)]
+pub struct PromptTestOptions {
+    pub kind: config::PromptKind,
+    pub alias: config::CommandAlias,
+    pub input: Option<String>,
+    /// Slug and branch of `--plan`.
+    pub plan: Option<(String, String)>,
+    pub target: Option<String>,
+    pub run: bool,
+    pub format: PromptTestOutputFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromptTestOutputFormat {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThreadOptions {
+    pub request: ThreadRequest,
+    pub format: ThreadOutputFormat,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ThreadRequest {
+    List {
+        state: Option<thread_state::ThreadState>,
+    },
+    Transition {
+        thread: String,
+        status: thread_state::ThreadStatus,
+        /// Slug and branch of the plan whose branch carries the change; the current branch
+        /// when `None` or when the plan branch is gone.
+        plan: Option<(String, String)>,
+    },
+    Revise {
+        /// Threads to revise; every thread on the current branch when `None`.
+        threads: Option<Vec<String>>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThreadOutputFormat {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheOptions {
+    pub request: CacheRequest,
+    pub format: CacheOutputFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheRequest {
+    Status,
+    Clean { all: bool },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheOutputFormat {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForgeOptions {
+    pub request: ForgeRequest,
+    pub format: ForgeOutputFormat,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ForgeRequest {
+    Status,
+    Issue { id: String, output: Option<PathBuf> },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ForgeOutputFormat {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CiOptions {
+    pub request: CiRequest,
+    pub artifacts_dir: PathBuf,
+    pub format: CiOutputFormat,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CiRequest {
+    Review {
+        slug: String,
+        flow: String,
+        /// Lowest failing severity; `None` never fails on action items.
+        fail_on: Option<Severity>,
+        /// Global flags forwarded to the `vizier run` child.
+        forwarded: Vec<String>,
+    },
+    /// Unset branches fall back to the checked-out branch and the detected target.
+    MergeQueue {
+        branch: Option<String>,
+        target: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CiOutputFormat {
+    Text,
+    Json,
+    Github,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ToolOptions {
+    File {
+        path: String,
+        lines: Option<(usize, usize)>,
+    },
+    Blame {
+        path: String,
+        lines: (usize, usize),
+    },
+    Search {
+        text: String,
+        limit: usize,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConfigImportCiOptions {
+    pub write: bool,
+    pub format: ConfigImportOutputFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigImportOutputFormat {
+    Text,
+    Json,
+    Toml,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangelogOptions {
+    pub version: String,
+    pub date: String,
+    pub dry_run: bool,
+    pub commit: bool,
+    pub format: ChangelogOutputFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangelogOutputFormat {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatsOptions {
+    pub since_days: Option<u32>,
+    pub format: StatsOutputFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatsOutputFormat {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveOptions {
+    pub request: ArchiveRequest,
+    pub format: ArchiveOutputFormat,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArchiveRequest {
+    List,
+    Show { slug: String },
+    Search { query: String, limit: usize },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveOutputFormat {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DoctorOptions {
+    pub format: DoctorOutputFormat,
+    pub remote: bool,
+    pub config_file: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DoctorOutputFormat {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlanOptions {
+    pub diff: bool,
+    pub format: PlanOutputFormat,
+    pub config_file: Option<PathBuf>,
+    /// `--profile`, else `VIZIER_PROFILE`, with the layer that selected it.
+    pub profile: Option<(String, config::ConfigSourceKind)>,
+    pub no_session: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlanOutputFormat {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InitOptions {
+    pub check: bool,
+    pub install_shims: bool,
+    pub smoke: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct SearchOptions {
+    pub query: String,
+    pub limit: usize,
+    pub format: SearchOutputFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchOutputFormat {
+    Text,
+    Json,
+}
@@ -0,0 +1,115 @@
+use std::path::Path;
+
+use serde_json::json;
+use vizier_core::{
+    auditor::{Auditor, Message},
+    display, vcs,
+};
+
+use crate::jobs;
+
+use super::shared::format_block;
+use super::types::{VerifyOptions, VerifyOutputFormat};
+
+pub(crate) fn run_verify(
+    project_root: &Path,
+    opts: VerifyOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if !vcs::branch_exists_in(project_root, &opts.branch)? {
+        return Err(format!("branch `{}` does not exist", opts.branch).into());
+    }
+
+    let gate_phase = display::start_phase(display::PHASE_GATE, None);
+    let verification = jobs::verify_branch(
+        project_root,
+        &opts.branch,
+        &opts.target,
+        &format!("verify-{}", opts.slug),
+        &|line| eprintln!("{line}"),
+    );
+    gate_phase.finish();
+    let verification = verification?;
+
+    let failed_gates = verification
+        .gates
+        .iter()
+        .filter(|gate| gate.status != 0)
+        .count();
+    let passed = verification.passed();
+    Auditor::add_message(Message::system(format!(
+        "vizier verify {} ({}): {} of {} gate(s) failed, {} of {} review check(s) failed",
+        opts.slug,
+        opts.branch,
+        failed_gates,
+        verification.gates.len(),
+        verification.checks.failed(),
+        verification.checks.results.len()
+    )));
+
+    let outcome = if passed {
+        "verify_passed"
+    } else {
+        "verify_failed"
+    };
+    match opts.format {
+        VerifyOutputFormat::Json => {
+            let gates = verification
+                .gates
+                .iter()
+                .map(|gate| {
+                    json!({
+                        "name": gate.name,
+                        "script": gate.script,
+                        "status": gate.status,
+                        "passed": gate.status == 0,
+                        "stdout": gate.stdout,
+                        "stderr": gate.stderr,
+                    })
+                })
+                .collect::<Vec<_>>();
+            let payload = json!({
+                "outcome": outcome,
+                "plan": opts.slug,
+                "branch": opts.branch,
+                "gates": gates,
+                "checks": verification.checks.to_json(),
+            });
+            println!("{}", serde_json::to_string_pretty(&payload)?);
+        }
+        VerifyOutputFormat::Text => {
+            let mut rows = vec![
+                (
+                    "Outcome".to_string(),
+                    if passed {
+                        "Plan branch verified".to_string()
+                    } else {
+                        "Plan branch failed verification".to_string()
+                    },
+                ),
+                ("Plan".to_string(), opts.slug.clone()),
+                ("Branch".to_string(), opts.branch.clone()),
+            ];
+            for gate in &verification.gates {
+                let status = if gate.status == 0 {
+                    "passed".to_string()
+                } else {
+                    format!("failed (exit {})", gate.status)
+                };
+                rows.push((format!("Gate {}", gate.name), status));
+            }
+            println!("{}", format_block(rows));
+            if !verification.checks.results.is_empty() {
+                println!();
+                for line in verification.checks.summary_lines() {
+                    println!("{line}");
+                }
+            }
+        }
+    }
+
+    if !passed {
+        Auditor::persist_session_log();
+        crate::cli::outcome::exit(10);
+    }
+    Ok(())
+}
@@ -0,0 +1,225 @@
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use chrono::Local;
+use serde_json::json;
+use vizier_core::never_send::{NeverSend, record_withheld};
+use vizier_core::{auditor::Auditor, display, interrupt, vcs, watch};
+
+use super::types::{WatchOptions, WatchOutputFormat};
+
+const WATCH_SUGGESTION_INSTRUCTION: &str = "You are watching a developer's uncommitted work. Given the files that changed, their diff against HEAD, and the current narrative snapshot and thread list, suggest concrete updates to the snapshot and its threads: facts that are now stale, TODOs to open, close, or reword. Do not edit any files and do not commit. Reply with a short Markdown list of suggestions, or `No narrative updates suggested.` when the changes do not affect the narrative.";
+
+const SNAPSHOT_PATH: &str = ".vizier/narrative/snapshot.md";
+const THREADS_DIR: &str = ".vizier/narrative/threads";
+const RECORD_PATH: &str = ".vizier/tmp/watch/suggestions.md";
+/// Diff bytes sent per batch; the changed-file list always goes in full.
+const MAX_DIFF_BYTES: usize = 60_000;
+
+/// Watches the working tree, batches changes once they settle, and asks the agent for narrative
+/// suggestions for each batch. Nothing is staged or committed; `--record` keeps a local log.
+pub(crate) async fn run_watch(
+    project_root: &Path,
+    opts: WatchOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if opts.format == WatchOutputFormat::Text && !opts.once {
+        println!(
+            "Outcome: vizier watch: watching for changes; suggestions after {}s without new changes (Ctrl-C to stop)",
+            opts.settle.as_secs()
+        );
+    }
+
+    let settle = if opts.once {
+        Duration::ZERO
+    } else {
+        opts.settle
+    };
+    let mut batcher = watch::ChangeBatcher::new(settle);
+    let mut batches = 0usize;
+    // Start watching before reading the dirty set so no edit slips between the two.
+    let mut watcher = if opts.once {
+        None
+    } else {
+        Some(watch::WorktreeWatcher::start(project_root)?)
+    };
+    batcher.record(watch::dirty_paths(project_root)?, Instant::now());
+    loop {
+        if let Some(files) = batcher.take_settled(Instant::now()) {
+            suggest(project_root, &opts, &files).await?;
+            batches += 1;
+        }
+        let Some(watcher) = watcher.as_mut() else {
+            break;
+        };
+        if interrupt::is_interrupted() {
+            break;
+        }
+        let deadline = batcher.deadline();
+        tokio::select! {
+            _ = interrupt::interrupted() => break,
+            changes = watcher.next_changes() => match changes {
+                Some(Ok(paths)) => batcher.record(paths, Instant::now()),
+                // A dropped or overflowed event should not end an ambient watcher.
+                Some(Err(err)) => display::warn(format!("vizier watch: {err}")),
+                None => break,
+            },
+            _ = tokio::time::sleep_until(deadline.unwrap_or_else(Instant::now).into()), if deadline.is_some() => {}
+        }
+    }
+
+    if opts.format == WatchOutputFormat::Text {
+        if opts.once && batches == 0 {
+            println!("Outcome: vizier watch: no uncommitted changes to suggest from");
+        } else if !opts.once {
+            println!("vizier watch stopped after {batches} batch(es)");
+        }
+    }
+    Ok(())
+}
+
+async fn suggest(
+    project_root: &Path,
+    opts: &WatchOptions,
+    files: &[String],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let at = Local::now();
+    let message = batch_message(project_root, files);
+    let agent_phase = display::start_phase(display::PHASE_AGENT_RUN, None);
+    let response = Auditor::llm_request(WATCH_SUGGESTION_INSTRUCTION.to_string(), message).await;
+    agent_phase.finish();
+    let suggestion = match response {
+        Ok(response) => response.content.trim().to_string(),
+        Err(err) if opts.once => return Err(err),
+        Err(err) => {
+            // One failed batch should not end an ambient watcher; the next batch retries.
+            match opts.format {
+                WatchOutputFormat::Json => println!(
+                    "{}",
+                    json!({
+                        "outcome": "watch_suggestion_failed",
+                        "at": at.to_rfc3339(),
+                        "files": files,
+                        "error": err.to_string(),
+                    })
+                ),
+                WatchOutputFormat::Text => {
+                    display::warn(format!("vizier watch: suggestion request failed: {err}"))
+                }
+            }
+            return Ok(());
+        }
+    };
+
+    let recorded = if opts.record {
+        Some(record(project_root, &at, files, &suggestion)?)
+    } else {
+        None
+    };
+    match opts.format {
+        WatchOutputFormat::Json => println!(
+            "{}",
+            json!({
+                "outcome": "watch_suggestion",
+                "at": at.to_rfc3339(),
+                "files": files,
+                "suggestion": suggestion,
+                "recorded": recorded,
+            })
+        ),
+        WatchOutputFormat::Text => {
+            println!();
+            println!(
+                "[{}] {} file(s) changed: {}",
+                at.format("%H:%M:%S"),
+                files.len(),
+                files.join(", ")
+            );
+            println!("{suggestion}");
+        }
+    }
+    Ok(())
+}
+
+fn batch_message(project_root: &Path, files: &[String]) -> String {
+    let mut message = String::from("<changedFiles>\n");
+    for file in files {
+        let state = if project_root.join(file).exists() {
+            ""
+        } else {
+            " (deleted or reverted)"
+        };
+        message.push_str(&format!("- {file}{state}\n"));
+    }
+    message.push_str("</changedFiles>\n\n");
+
+    // Stream per file so a huge change set stops being read once the budget is spent.
+    let mut diff = String::new();
+    let never_send = NeverSend::from_config(project_root);
+    let mut withheld = Vec::new();
+    if let Some(root) = project_root.to_str() {
+        let _ = vcs::for_each_file_patch(
+            root,
+            None,
+            Some(&[".vizier"]),
+            vcs::DiffLimits::default(),
+            |file| {
+                if never_send.withholds(Path::new(&file.path)) {
+                    withheld.push(file.path);
+                    return true;
+                }
+                if diff.len() + file.patch.len() > MAX_DIFF_BYTES {
+                    diff.push_str("[diff truncated]\n");
+                    return false;
+                }
+                diff.push_str(&file.patch);
+                true
+            },
+        );
+    }
+    record_withheld("watch", &withheld);
+    message.push_str(&format!("<diff>\n{diff}</diff>\n\n"));
+
+    let snapshot = fs::read_to_string(project_root.join(SNAPSHOT_PATH)).unwrap_or_default();
+    message.push_str(&format!("<snapshot>\n{snapshot}</snapshot>\n\n"));
+
+    let mut threads = fs::read_dir(project_root.join(THREADS_DIR))
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|entry| {
+                    let name = entry.file_name().to_string_lossy().to_string();
+                    name.strip_suffix(".md").map(str::to_string)
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    threads.sort();
+    message.push_str(&format!("<threads>\n{}\n</threads>\n", threads.join("\n")));
+    message
+}
+
+fn record(
+    project_root: &Path,
+    at: &chrono::DateTime<Local>,
+    files: &[String],
+    suggestion: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let path = project_root.join(RECORD_PATH);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)?;
+    write!(
+        file,
+        "## {} ({})\n\n{}\n\n",
+        at.to_rfc3339(),
+        files.join(", "),
+        suggestion
+    )?;
+    Ok(RECORD_PATH.to_string())
+}
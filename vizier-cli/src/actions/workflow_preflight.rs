@@ -66,6 +66,214 @@ pub(crate) fn prepare_workflow_template_from_invocation(
     Ok(template)
 }
 
+/// Warns (or, with `strict`, fails) when a template approves or merges a draft branch that has
+/// drifted past the `[workflow.drift]` thresholds relative to its target.
+/// Plan branch a workflow node operates on, resolved from literal node args (the slug's default
+/// branch when only a slug is given). Args still holding `${...}` placeholders are ignored.
+pub(crate) struct PlanBranchRef {
+    pub(crate) slug: Option<String>,
+    pub(crate) branch: String,
+    pub(crate) target: Option<String>,
+}
+
+pub(crate) fn plan_branch_refs(
+    template: &vizier_core::workflow_template::WorkflowTemplate,
+) -> Vec<PlanBranchRef> {
+    let mut refs = Vec::new();
+    for node in &template.nodes {
+        if !matches!(
+            node.uses.as_str(),
+            "cap.env.builtin.worktree.prepare" | "cap.env.builtin.git.integrate_plan_branch"
+        ) {
+            continue;
+        }
+        let arg = |keys: &[&str]| {
+            keys.iter()
+                .filter_map(|key| node.args.get(*key))
+                .map(|value| value.trim())
+                .find(|value| !value.is_empty() && !value.contains("${"))
+                .map(str::to_string)
+        };
+        let slug = arg(&["slug", "plan"]);
+        let Some(branch) = arg(&["branch", "source_branch", "plan_branch"]).or_else(|| {
+            slug.as_deref()
+                .map(vizier_core::plan::default_branch_for_slug)
+        }) else {
+            continue;
+        };
+        refs.push(PlanBranchRef {
+            slug,
+            branch,
+            target: arg(&["target", "target_branch"]),
+        });
+    }
+    refs
+}
+
+pub(crate) fn check_plan_branch_drift(
+    project_root: &Path,
+    template: &vizier_core::workflow_template::WorkflowTemplate,
+    strict: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let thresholds = vizier_core::config::get_config().workflow.drift;
+    let mut checked = HashSet::new();
+    let mut stale = Vec::new();
+    for PlanBranchRef {
+        slug,
+        branch,
+        target,
+    } in plan_branch_refs(template)
+    {
+        let Some(target) =
+            target.or_else(|| vizier_core::vcs::detect_primary_branch_in(project_root))
+        else {
+            continue;
+        };
+        if branch == target || !checked.insert((branch.clone(), target.clone())) {
+            continue;
+        }
+        // Branches that do not exist yet (e.g. a draft queued in the same chain) have no drift.
+        let Ok(drift) = vizier_core::drift::measure_plan_drift(project_root, &branch, &target)
+        else {
+            continue;
+        };
+        let slug = slug
+            .or_else(|| vizier_core::plan::slug_from_branch(&branch))
+            .unwrap_or_else(|| branch.clone());
+        if let Some(message) = drift.stale_message(&slug, &thresholds) {
+            stale.push(message);
+        }
+    }
+
+    if stale.is_empty() {
+        return Ok(());
+    }
+    if strict {
+        return Err(format!(
+            "{} (blocked by --strict / workflow.drift.strict)",
+            stale.join("; ")
+        )
+        .into());
+    }
+    for message in stale {
+        vizier_core::display::warn(message);
+    }
+    Ok(())
+}
+
+/// Warns (or fails with `strict`) when a plan branch the run operates on touches files that
+/// other pending plans also change or plan to change.
+pub(crate) fn check_plan_overlaps(
+    project_root: &Path,
+    template: &vizier_core::workflow_template::WorkflowTemplate,
+    strict: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut checked = HashSet::new();
+    let mut overlaps = Vec::new();
+    for PlanBranchRef {
+        slug,
+        branch,
+        target,
+    } in plan_branch_refs(template)
+    {
+        let Some(target) =
+            target.or_else(|| vizier_core::vcs::detect_primary_branch_in(project_root))
+        else {
+            continue;
+        };
+        if branch == target || !checked.insert(branch.clone()) {
+            continue;
+        }
+        let slug = slug
+            .or_else(|| vizier_core::plan::slug_from_branch(&branch))
+            .unwrap_or_else(|| branch.clone());
+        // Branches that do not exist yet are checked when their plan is persisted.
+        let Ok(surface) =
+            vizier_core::overlap::PlanSurface::for_branch(project_root, &slug, &branch, &target)
+        else {
+            continue;
+        };
+        let found = vizier_core::overlap::find_overlaps(project_root, &surface, &target)?;
+        overlaps.extend(found.iter().map(|overlap| overlap.message(&slug)));
+    }
+
+    if overlaps.is_empty() {
+        return Ok(());
+    }
+    if strict {
+        return Err(format!("{} (blocked by --strict-overlap)", overlaps.join("; ")).into());
+    }
+    for message in overlaps {
+        vizier_core::display::warn(message);
+    }
+    Ok(())
+}
+
+/// Rough size and cost of the agent run an approve template is about to start: its prompt file,
+/// the plan document and narrative on the plan branch, and any `--context` documents. `None` when
+/// the template is not an approve stage or names no concrete plan branch.
+pub(crate) fn estimate_approve_cost(
+    project_root: &Path,
+    source: &ResolvedWorkflowSource,
+    template: &vizier_core::workflow_template::WorkflowTemplate,
+    context_files: &[String],
+    cfg: &vizier_core::config::Config,
+) -> Option<vizier_core::cost::CostEstimate> {
+    let is_approve = template.nodes.iter().any(|node| {
+        node.uses == "cap.env.builtin.worktree.prepare"
+            && node.args.get("purpose").map(String::as_str) == Some("stage-approve")
+    });
+    if !is_approve {
+        return None;
+    }
+    let plan = plan_branch_refs(template).into_iter().next()?;
+    let slug = plan
+        .slug
+        .or_else(|| vizier_core::plan::slug_from_branch(&plan.branch))?;
+
+    let mut context = Vec::new();
+    for node in &template.nodes {
+        if let Some(prompt_file) = node.args.get("prompt_file")
+            && let Ok(text) = fs::read_to_string(project_root.join(prompt_file.trim()))
+        {
+            context.push(text);
+        }
+    }
+    let narrative = Path::new(".vizier").join("narrative");
+    for path in [
+        vizier_core::plan::plan_rel_path(&slug),
+        narrative.join("snapshot.md"),
+        narrative.join("glossary.md"),
+    ] {
+        let revision = format!("{}:{}", plan.branch, path.to_string_lossy());
+        if let Ok(text) = vizier_core::vcs::read_blob_at_revision_in(project_root, &revision) {
+            context.push(text);
+        }
+    }
+    for path in context_files {
+        if let Ok(text) = fs::read_to_string(path) {
+            context.push(text);
+        }
+    }
+    let context_tokens = context
+        .iter()
+        .map(|text| vizier_core::cost::estimate_tokens(text))
+        .sum();
+
+    let alias = source
+        .command_alias
+        .clone()
+        .or_else(|| vizier_core::config::CommandAlias::parse("approve"))?;
+    let agent = vizier_core::config::resolve_agent_settings_for_alias(cfg, &alias, None).ok()?;
+    let model =
+        vizier_core::cost::model_label(&agent.agent_runtime.command, &agent.agent_runtime.label);
+    Some(vizier_core::cost::CostEstimate::new(
+        context_tokens,
+        model,
+        &cfg.approve.cost,
+    ))
+}
+
 fn parse_set_overrides(
     values: &[String],
 ) -> Result<BTreeMap<String, String>, Box<dyn std::error::Error>> {
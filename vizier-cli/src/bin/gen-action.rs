@@ -0,0 +1,17 @@
+use clap::Parser;
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "gen-action",
+    about = "Generate the composite GitHub Action from `vizier ci` Clap metadata"
+)]
+struct GenActionArgs {
+    /// Fail if the checked-in action.yml differs from generated output.
+    #[arg(long = "check")]
+    check: bool,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = GenActionArgs::parse();
+    vizier::generate_ci_action(args.check)
+}
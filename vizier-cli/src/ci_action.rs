@@ -0,0 +1,207 @@
+use std::error::Error;
+use std::fs;
+
+use clap::{Arg, ArgAction, CommandFactory};
+
+use crate::cli::args::Cli;
+
+const ACTION_PATH: &str = "action.yml";
+/// Inputs the action defaults differently from the CLI: CI logs want annotations.
+const ACTION_DEFAULTS: &[(&str, &str)] = &[("format", "github")];
+
+/// One `vizier ci` option surfaced as an action input, with the subcommands that accept it.
+struct ActionInput {
+    name: String,
+    env: String,
+    help: String,
+    default: Option<String>,
+    commands: Vec<String>,
+}
+
+/// Writes (or with `check`, compares) the composite GitHub Action at the repo root, built from the
+/// `vizier ci` subcommands and their arguments.
+pub fn generate_ci_action(check: bool) -> Result<(), Box<dyn Error>> {
+    let content = render_action()?;
+    if check {
+        return match fs::read_to_string(ACTION_PATH) {
+            Ok(existing) if existing == content => Ok(()),
+            _ => Err(format!(
+                "generated GitHub Action is stale; run `cargo run -p vizier --bin gen-action --`:\n{ACTION_PATH}"
+            )
+            .into()),
+        };
+    }
+    if fs::read_to_string(ACTION_PATH).ok().as_deref() != Some(content.as_str()) {
+        fs::write(ACTION_PATH, content)?;
+    }
+    Ok(())
+}
+
+fn render_action() -> Result<String, Box<dyn Error>> {
+    let cli = Cli::command();
+    let ci = cli
+        .find_subcommand("ci")
+        .ok_or("vizier has no `ci` subcommand")?;
+    let commands = ci.get_subcommands().collect::<Vec<_>>();
+
+    let mut inputs: Vec<ActionInput> = Vec::new();
+    for command in &commands {
+        for arg in command.get_arguments().filter(|arg| is_input(arg)) {
+            let name = input_name(arg);
+            let default = arg
+                .get_default_values()
+                .first()
+                .map(|value| value.to_string_lossy().into_owned());
+            match inputs.iter_mut().find(|input| input.name == name) {
+                Some(input) => {
+                    if input.default != default {
+                        input.default = None;
+                    }
+                    input.commands.push(command.get_name().to_string());
+                }
+                None => inputs.push(ActionInput {
+                    env: format!("VIZIER_CI_{}", name.replace('-', "_").to_ascii_uppercase()),
+                    help: arg
+                        .get_help()
+                        .map(|help| help.to_string())
+                        .unwrap_or_default(),
+                    name,
+                    default,
+                    commands: vec![command.get_name().to_string()],
+                }),
+            }
+        }
+    }
+    // Only a default every subcommand shares is safe to pass unconditionally.
+    for input in &mut inputs {
+        if input.commands.len() != commands.len() {
+            input.default = None;
+        }
+        if let Some((_, value)) = ACTION_DEFAULTS.iter().find(|(name, _)| *name == input.name) {
+            input.default = Some(value.to_string());
+        }
+    }
+
+    let mut out = String::new();
+    out.push_str(
+        "# Generated by `cargo run -p vizier --bin gen-action --` from the `vizier ci` command metadata.\n",
+    );
+    out.push_str("# Do not edit by hand.\n");
+    out.push_str("name: vizier\n");
+    out.push_str(&format!(
+        "description: {}\n",
+        yaml_string(
+            &ci.get_about()
+                .map(|about| about.to_string())
+                .unwrap_or_default()
+        )
+    ));
+    out.push_str("inputs:\n  command:\n");
+    let command_help = commands
+        .iter()
+        .map(|command| {
+            format!(
+                "{} ({})",
+                command.get_name(),
+                command
+                    .get_about()
+                    .map(|about| about.to_string())
+                    .unwrap_or_default()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("; ");
+    out.push_str(&format!(
+        "    description: {}\n    required: true\n",
+        yaml_string(&format!("vizier ci subcommand: {command_help}"))
+    ));
+    for input in &inputs {
+        out.push_str(&format!("  {}:\n", input.name));
+        out.push_str(&format!(
+            "    description: {}\n    required: false\n",
+            yaml_string(&format!("{} [{}]", input.help, input.commands.join(", ")))
+        ));
+        if let Some(default) = &input.default {
+            out.push_str(&format!("    default: {}\n", yaml_string(default)));
+        }
+    }
+    out.push_str(
+        "outputs:\n  exit-code:\n    description: \"vizier ci exit code (0 passed, 10 findings or failing gates)\"\n    value: ${{ steps.vizier.outputs.exit-code }}\n",
+    );
+
+    out.push_str("runs:\n  using: composite\n  steps:\n");
+    out.push_str("    - name: Install vizier\n      shell: bash\n      run: |\n");
+    out.push_str("        PREFIX=\"$RUNNER_TEMP/vizier\" \"$GITHUB_ACTION_PATH/install.sh\"\n");
+    out.push_str("        echo \"$RUNNER_TEMP/vizier/bin\" >> \"$GITHUB_PATH\"\n");
+    out.push_str("    - name: Run vizier ci\n      id: vizier\n      shell: bash\n      env:\n");
+    out.push_str("        VIZIER_CI_COMMAND: ${{ inputs.command }}\n");
+    for input in &inputs {
+        out.push_str(&format!(
+            "        {}: ${{{{ inputs.{} }}}}\n",
+            input.env, input.name
+        ));
+    }
+    out.push_str("      run: |\n");
+    out.push_str("        args=(ci \"$VIZIER_CI_COMMAND\")\n");
+    out.push_str("        case \"$VIZIER_CI_COMMAND\" in\n");
+    for command in &commands {
+        out.push_str(&format!("          {})\n", command.get_name()));
+        for arg in command.get_arguments().filter(|arg| is_input(arg)) {
+            let name = input_name(arg);
+            let env = &inputs
+                .iter()
+                .find(|input| input.name == name)
+                .ok_or("action input missing")?
+                .env;
+            let append = if arg.is_positional() {
+                format!("args+=(\"${env}\")")
+            } else if matches!(arg.get_action(), ArgAction::SetTrue) {
+                format!("args+=(--{name})")
+            } else {
+                format!("args+=(--{name} \"${env}\")")
+            };
+            let test = if matches!(arg.get_action(), ArgAction::SetTrue) {
+                format!("[ \"${env}\" = \"true\" ]")
+            } else {
+                format!("[ -n \"${env}\" ]")
+            };
+            out.push_str(&format!("            if {test}; then {append}; fi\n"));
+        }
+        out.push_str("            ;;\n");
+    }
+    let names = commands
+        .iter()
+        .map(|command| command.get_name())
+        .collect::<Vec<_>>()
+        .join(", ");
+    out.push_str("          *)\n");
+    out.push_str(&format!(
+        "            echo \"::error title=vizier ci::unknown command '$VIZIER_CI_COMMAND' (expected {names})\"\n"
+    ));
+    out.push_str("            exit 2\n            ;;\n        esac\n");
+    out.push_str("        status=0\n        vizier \"${args[@]}\" || status=$?\n");
+    out.push_str("        echo \"exit-code=$status\" >> \"$GITHUB_OUTPUT\"\n");
+    out.push_str("        exit \"$status\"\n");
+    out.push_str("    - name: Upload vizier artifacts\n      if: always()\n");
+    out.push_str("      uses: actions/upload-artifact@v4\n      with:\n");
+    out.push_str("        name: vizier-ci-${{ inputs.command }}\n");
+    out.push_str("        path: ${{ inputs.artifacts-dir }}\n");
+    out.push_str("        include-hidden-files: true\n");
+    out.push_str("        if-no-files-found: ignore\n");
+    Ok(out)
+}
+
+fn is_input(arg: &Arg) -> bool {
+    !arg.is_global_set() && !matches!(arg.get_id().as_str(), "help" | "version")
+}
+
+fn input_name(arg: &Arg) -> String {
+    arg.get_long()
+        .map(str::to_string)
+        .unwrap_or_else(|| arg.get_id().as_str().replace('_', "-"))
+}
+
+// JSON strings are valid double-quoted YAML scalars.
+fn yaml_string(value: &str) -> String {
+    serde_json::Value::String(value.to_string()).to_string()
+}
@@ -1,12 +1,13 @@
 use std::collections::HashMap;
 use std::num::NonZeroU32;
+use std::path::PathBuf;
 
 use clap::{
     ArgAction, ArgGroup, Args as ClapArgs, Parser, Subcommand, ValueEnum,
     builder::NonEmptyStringValueParser,
 };
 use clap_complete::Shell;
-use vizier_core::{config, display};
+use vizier_core::{config, cost, display};
 
 use crate::jobs;
 
@@ -42,6 +43,10 @@ pub(crate) struct GlobalOpts {
     #[arg(short = 'd', long, global = true)]
     pub(crate) debug: bool,
 
+    /// Per-subsystem stderr filters such as `agent=debug,gate=info,git=warn` (subsystems: agent, gate, git, scheduler, config; levels: off, error, warn, info, debug); unlisted subsystems follow -v/-q, and entries override `[display] log`
+    #[arg(long = "log", value_name = "FILTERS", value_parser = parse_log_filters, global = true)]
+    pub(crate) log: Option<display::LogFilters>,
+
     /// Disable ANSI control sequences even on TTYs (non-TTY is always plain); useful for CI/log scrapers
     #[arg(long = "no-ansi", global = true)]
     pub(crate) no_ansi: bool,
@@ -58,9 +63,43 @@ pub(crate) struct GlobalOpts {
     #[arg(short = 'n', long = "no-session", global = true)]
     pub(crate) no_session: bool,
 
-    /// Config file to load (supports JSON or TOML); bypasses the normal global+repo layering
+    /// Config file to load (TOML, JSON, or YAML); bypasses the normal global+repo layering
     #[arg(short = 'C', long = "config-file", global = true)]
     pub(crate) config_file: Option<String>,
+
+    /// Apply the named `[profiles.<name>]` config overlay (overrides VIZIER_PROFILE)
+    #[arg(long = "profile", value_name = "NAME", global = true)]
+    pub(crate) profile: Option<String>,
+
+    /// Check plan workspaces and job worktrees out under this directory (namespaced per repo) instead of .vizier/tmp-worktrees; overrides `[worktrees] dir`
+    #[arg(long = "worktree-dir", value_name = "DIR", global = true)]
+    pub(crate) worktree_dir: Option<PathBuf>,
+
+    /// Stable tab-separated records on stdout (plan, run, job, commit, exit) instead of the human display; supported by list, run, and jobs list
+    #[arg(
+        long = "porcelain",
+        value_name = "VERSION",
+        value_enum,
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "v1",
+        global = true
+    )]
+    pub(crate) porcelain: Option<PorcelainArg>,
+}
+
+/// Porcelain format versions; records only change shape across a version bump.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub(crate) enum PorcelainArg {
+    V1,
+}
+
+fn parse_log_filters(value: &str) -> Result<display::LogFilters, String> {
+    display::LogFilters::parse(value)
+}
+
+fn parse_max_cost(value: &str) -> Result<f64, String> {
+    cost::parse_usd(value)
 }
 
 #[derive(Clone, Copy, Debug, ValueEnum)]
@@ -100,10 +139,20 @@ pub(crate) enum JobsActionFormatArg {
     Json,
 }
 
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub(crate) enum ReviewFormatArg {
+    Markdown,
+    Plain,
+    Json,
+}
+
 #[derive(Clone, Copy, Debug, ValueEnum)]
 pub(crate) enum RunFormatArg {
     Text,
     Json,
+    /// Set by the global `--porcelain` flag rather than `--format`.
+    #[value(skip)]
+    Porcelain,
 }
 
 #[derive(Clone, Copy, Debug, ValueEnum)]
@@ -118,11 +167,147 @@ pub(crate) enum CleanFormatArg {
     Json,
 }
 
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub(crate) enum RescueFormatArg {
+    Text,
+    Json,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub(crate) enum RebaseFormatArg {
+    Text,
+    Json,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub(crate) enum PickFormatArg {
+    Text,
+    Json,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub(crate) enum ApplyPatchFormatArg {
+    Text,
+    Json,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub(crate) enum VerifyFormatArg {
+    Text,
+    Json,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub(crate) enum CiFormatArg {
+    Text,
+    Json,
+    Github,
+}
+
+/// Lowest review action-item severity that fails `vizier ci review`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub(crate) enum CiFailOnArg {
+    Blocker,
+    Major,
+    Minor,
+    Nit,
+    Any,
+    Never,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub(crate) enum AckFormatArg {
+    Text,
+    Json,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub(crate) enum WatchFormatArg {
+    Text,
+    Json,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub(crate) enum LabelFormatArg {
+    Text,
+    Json,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub(crate) enum LabelActionArg {
+    Add,
+    Remove,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub(crate) enum NoteFormatArg {
+    Text,
+    Json,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub(crate) enum SessionsFormatArg {
+    Text,
+    Json,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub(crate) enum AgentFormatArg {
+    Text,
+    Json,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub(crate) enum ChangelogFormatArg {
+    Text,
+    Json,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub(crate) enum ConfigImportFormatArg {
+    Text,
+    Json,
+    Toml,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub(crate) enum StatsFormatArg {
+    Text,
+    Json,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub(crate) enum ArchiveFormatArg {
+    Text,
+    Json,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub(crate) enum SnapshotFormatArg {
+    Text,
+    Json,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub(crate) enum DoctorFormatArg {
+    Text,
+    Json,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub(crate) enum SearchFormatArg {
+    Text,
+    Json,
+}
+
 #[derive(Clone, Copy, Debug)]
 pub(crate) enum JobsListField {
     Job,
     Status,
     Created,
+    Priority,
+    QueuePosition,
+    EstimatedStart,
     After,
     Dependencies,
     Locks,
@@ -143,6 +328,9 @@ impl JobsListField {
             "job" => Some(Self::Job),
             "status" => Some(Self::Status),
             "created" => Some(Self::Created),
+            "priority" => Some(Self::Priority),
+            "queue position" => Some(Self::QueuePosition),
+            "estimated start" => Some(Self::EstimatedStart),
             "after" => Some(Self::After),
             "dependencies" => Some(Self::Dependencies),
             "locks" => Some(Self::Locks),
@@ -164,6 +352,9 @@ impl JobsListField {
             Self::Job => "Job",
             Self::Status => "Status",
             Self::Created => "Created",
+            Self::Priority => "Priority",
+            Self::QueuePosition => "Queue position",
+            Self::EstimatedStart => "Estimated start",
             Self::After => "After",
             Self::Dependencies => "Dependencies",
             Self::Locks => "Locks",
@@ -184,6 +375,9 @@ impl JobsListField {
             Self::Job => "job",
             Self::Status => "status",
             Self::Created => "created",
+            Self::Priority => "priority",
+            Self::QueuePosition => "queue_position",
+            Self::EstimatedStart => "estimated_start",
             Self::After => "after",
             Self::Dependencies => "dependencies",
             Self::Locks => "locks",
@@ -259,10 +453,13 @@ pub(crate) enum JobsShowField {
     AgentLabel,
     AgentCommand,
     AgentExit,
+    CostEstimate,
+    CostActual,
     CancelCleanup,
     CancelCleanupError,
     RetryCleanup,
     RetryCleanupError,
+    OperatorNote,
     ConfigSnapshot,
     Command,
 }
@@ -327,10 +524,13 @@ impl JobsShowField {
             "agent label" => Some(Self::AgentLabel),
             "agent command" => Some(Self::AgentCommand),
             "agent exit" => Some(Self::AgentExit),
+            "cost estimate" => Some(Self::CostEstimate),
+            "cost actual" | "cost" => Some(Self::CostActual),
             "cancel cleanup" => Some(Self::CancelCleanup),
             "cancel cleanup error" => Some(Self::CancelCleanupError),
             "retry cleanup" => Some(Self::RetryCleanup),
             "retry cleanup error" => Some(Self::RetryCleanupError),
+            "operator note" | "note" => Some(Self::OperatorNote),
             "config snapshot" => Some(Self::ConfigSnapshot),
             "command" => Some(Self::Command),
             _ => None,
@@ -396,10 +596,13 @@ impl JobsShowField {
             Self::AgentLabel => "Agent label",
             Self::AgentCommand => "Agent command",
             Self::AgentExit => "Agent exit",
+            Self::CostEstimate => "Cost estimate",
+            Self::CostActual => "Cost actual",
             Self::CancelCleanup => "Cancel cleanup",
             Self::CancelCleanupError => "Cancel cleanup error",
             Self::RetryCleanup => "Retry cleanup",
             Self::RetryCleanupError => "Retry cleanup error",
+            Self::OperatorNote => "Operator note",
             Self::ConfigSnapshot => "Config snapshot",
             Self::Command => "Command",
         }
@@ -464,10 +667,13 @@ impl JobsShowField {
             Self::AgentLabel => "agent_label",
             Self::AgentCommand => "agent_command",
             Self::AgentExit => "agent_exit",
+            Self::CostEstimate => "cost_estimate",
+            Self::CostActual => "cost_actual",
             Self::CancelCleanup => "cancel_cleanup",
             Self::CancelCleanupError => "cancel_cleanup_error",
             Self::RetryCleanup => "retry_cleanup",
             Self::RetryCleanupError => "retry_cleanup_error",
+            Self::OperatorNote => "operator_note",
             Self::ConfigSnapshot => "config_snapshot",
             Self::Command => "command",
         }
@@ -501,6 +707,78 @@ pub(crate) enum Commands {
     /// Remove Vizier-managed runtime residue for a job or workflow run
     Clean(CleanCmd),
 
+    /// Inspect a preserved plan worktree and commit, discard, or export its uncommitted changes
+    Rescue(RescueCmd),
+
+    /// Rebase a plan branch onto the latest target, resolving conflicts with the merge-conflict agent
+    Rebase(RebaseCmd),
+
+    /// Cherry-pick one plan commit (by SHA or step number) onto the target, gated like a merge
+    Pick(PickCmd),
+
+    /// Run a plan branch's gates and review checks in a throwaway worktree without an agent
+    Verify(VerifyCmd),
+
+    /// Record a narrative thread owner's acknowledgment of a plan before merge
+    Ack(AckCmd),
+
+    /// List narrative threads by lifecycle state, resolve, block, or reopen them, or revise them in bulk
+    Thread(ThreadCmd),
+
+    /// Show, add, or remove the labels in a plan's front matter (committed on the plan branch)
+    Label(LabelCmd),
+
+    /// Append a timestamped operator note to a plan's notes (committed on the plan branch), or show them
+    Note(NoteCmd),
+
+    /// Import a unified diff or `git format-patch` mbox onto a plan branch, checked like a merge
+    ApplyPatch(ApplyPatchCmd),
+
+    /// Search narrative snapshot, threads, plan docs, and session summaries
+    Search(SearchCmd),
+
+    /// Render the committed narrative snapshot and threads at any revision, or diff two revisions
+    Snapshot(SnapshotCmd),
+
+    /// List, inspect, and garbage-collect session logs under .vizier/sessions
+    Sessions(SessionsCmd),
+
+    /// Browse merged plans archived under .vizier/archive (plan, diff summary, critique)
+    Archive(ArchiveCmd),
+
+    /// Report agent wall time, exit codes, gate failure rates, and retries per scope and plan
+    Stats(StatsCmd),
+
+    /// Watch the working tree and print suggested narrative updates as uncommitted changes settle
+    Watch(WatchCmd),
+
+    /// Check git, libgit2, the agent, output buffering, config layers, .vizier permissions, and remote auth
+    Doctor(DoctorCmd),
+
+    /// Check a custom agent shim (and progress filter) against the vizier-agent-protocol contract
+    Agent(AgentCmd),
+
+    /// Render a prompt against the current repo, with size stats, and optionally try it on the agent
+    Prompt(PromptCmd),
+
+    /// Inspect or clean the per-user cache of bundled agent shims shared by every repo
+    Cache(CacheCmd),
+
+    /// Fetch issues and check which forge CLI (gh, glab) remote operations are delegated to
+    Forge(ForgeCmd),
+
+    /// Read-only repo navigation (file slices, blame, search) offered to review agents
+    Tool(ToolCmd),
+
+    /// Non-interactive review and merge-queue checks for CI: strict exit codes, artifacts, annotations
+    Ci(CiCmd),
+
+    /// Show the config keys each layer sets (global file, repo file, env, CLI) and which one wins
+    Plan(PlanCmd),
+
+    /// Generate config from the repository, e.g. [review.checks] from its CI workflows
+    Config(ConfigCmd),
+
     /// Inspect detached Vizier background jobs
     Jobs(JobsCmd),
 
@@ -521,8 +799,15 @@ pub(crate) enum Commands {
     #[command(name = "__workflow-node", hide = true)]
     WorkflowNode(HiddenWorkflowNodeCmd),
 
+    /// Internal email delivery entry point (spawned detached by job finalization and scheduler ticks)
+    #[command(name = "__notifications-drain", hide = true)]
+    NotificationsDrain,
+
     /// Create a local release commit and optional annotated tag from conventional commits
     Release(ReleaseCmd),
+
+    /// Roll the changelog fragments written at merge time into CHANGELOG.md
+    Changelog(ChangelogCmd),
 }
 
 #[derive(ClapArgs, Debug)]
@@ -547,6 +832,14 @@ pub(crate) struct InitCmd {
     /// Validate initialization state without mutating files
     #[arg(long = "check", action = ArgAction::SetTrue)]
     pub(crate) check: bool,
+
+    /// Copy the bundled agent shims (codex, claude, gemini) into .vizier/agents/
+    #[arg(long = "install-shims", action = ArgAction::SetTrue, conflicts_with = "check")]
+    pub(crate) install_shims: bool,
+
+    /// Skip the post-init smoke check of agent resolution
+    #[arg(long = "no-smoke", action = ArgAction::SetTrue)]
+    pub(crate) no_smoke: bool,
 }
 
 #[derive(ClapArgs, Debug)]
@@ -562,6 +855,14 @@ pub(crate) struct ListCmd {
     /// Comma-separated list of entry fields (e.g., Plan,Summary)
     #[arg(long = "fields", value_name = "FIELDS")]
     pub(crate) fields: Option<String>,
+
+    /// Group pending plans under the narrative threads named in their `threads:` front matter
+    #[arg(long = "by-thread", action = ArgAction::SetTrue)]
+    pub(crate) by_thread: bool,
+
+    /// Only list plans carrying this label (repeatable; a plan must carry all of them)
+    #[arg(long = "label", value_name = "LABEL")]
+    pub(crate) label: Vec<String>,
 }
 
 #[derive(ClapArgs, Debug)]
@@ -570,7 +871,7 @@ pub(crate) struct CdCmd {
     #[arg(value_name = "PLAN", add = crate::completions::plan_slug_completer())]
     pub(crate) plan: Option<String>,
 
-    /// Branch to use instead of draft/<plan>
+    /// Branch to use instead of the plan branch (draft/<plan> unless [branches] prefix is set)
     #[arg(long = "branch", value_name = "BRANCH")]
     pub(crate) branch: Option<String>,
 
@@ -603,111 +904,1047 @@ pub(crate) struct CleanCmd {
 }
 
 #[derive(ClapArgs, Debug)]
-pub(crate) struct JobsCmd {
-    #[command(subcommand)]
-    pub(crate) action: JobsAction,
-}
+#[command(group(
+    ArgGroup::new("rescue_action")
+        .args(["commit", "discard", "patch"])
+        .multiple(false)
+        .required(false)
+))]
+pub(crate) struct RescueCmd {
+    /// Plan slug whose preserved worktree should be inspected (tab-completes from pending plans)
+    #[arg(value_name = "PLAN", add = crate::completions::plan_slug_completer())]
+    pub(crate) plan: String,
 
-#[derive(ClapArgs, Debug)]
-pub(crate) struct RunCmd {
-    /// Workflow source: alias, selector, file:<path>, or direct .hcl/.toml/.json path
-    #[arg(value_name = "FLOW")]
-    pub(crate) flow: String,
+    /// Branch to use instead of the plan branch (draft/<plan> unless [branches] prefix is set)
+    #[arg(long = "branch", value_name = "BRANCH")]
+    pub(crate) branch: Option<String>,
 
-    /// Ordered workflow inputs; mapped by template [cli].positional before enqueue
-    #[arg(value_name = "INPUT")]
-    pub(crate) inputs: Vec<String>,
+    /// Commit the uncommitted changes onto the plan branch
+    #[arg(long = "commit", action = ArgAction::SetTrue)]
+    pub(crate) commit: bool,
 
-    /// Template parameter override (KEY=VALUE); repeatable
-    #[arg(long = "set", value_name = "KEY=VALUE", action = ArgAction::Append)]
-    pub(crate) set: Vec<String>,
+    /// Throw away the uncommitted changes (requires confirmation or --yes)
+    #[arg(long = "discard", action = ArgAction::SetTrue)]
+    pub(crate) discard: bool,
 
-    /// Validate queue-time workflow checks without enqueueing jobs or manifests
-    #[arg(
-        long = "check",
-        action = ArgAction::SetTrue,
-        conflicts_with_all = [
-            "after",
-            "ephemeral",
-            "require_approval",
-            "no_require_approval",
-            "follow",
-            "repeat"
-        ]
-    )]
-    pub(crate) check: bool,
+    /// Write the uncommitted changes to a patch file (defaults under .vizier/tmp/rescue/)
+    #[arg(long = "patch", action = ArgAction::SetTrue)]
+    pub(crate) patch: bool,
 
-    /// Expand a directory of markdown specs into serial workflow runs
+    /// Commit message for --commit
     #[arg(
-        long = "spec-dir",
-        value_name = "DIR",
-        conflicts_with = "repeat",
-        value_parser = NonEmptyStringValueParser::new()
+        long = "message",
+        short = 'm',
+        value_name = "MESSAGE",
+        requires = "commit"
     )]
-    pub(crate) spec_dir: Option<String>,
+    pub(crate) message: Option<String>,
 
-    /// External predecessor dependency; root jobs wait on JOB_ID or run:RUN_ID
-    #[arg(long = "after", value_name = "REF", action = ArgAction::Append)]
-    pub(crate) after: Vec<String>,
+    /// Patch destination for --patch
+    #[arg(long = "output", short = 'o', value_name = "PATH", requires = "patch")]
+    pub(crate) output: Option<String>,
 
-    /// Auto-clean Vizier-owned runtime material after the run is terminal and cleanup is safe
-    #[arg(long = "ephemeral", action = ArgAction::SetTrue)]
-    pub(crate) ephemeral: bool,
+    /// Skip the discard confirmation prompt
+    #[arg(long = "yes", short = 'y', action = ArgAction::SetTrue)]
+    pub(crate) assume_yes: bool,
 
-    /// Require explicit approval before root jobs can start
-    #[arg(long = "require-approval", action = ArgAction::SetTrue, conflicts_with = "no_require_approval")]
-    pub(crate) require_approval: bool,
+    /// Output format (text, json)
+    #[arg(long = "format", value_enum, default_value_t = RescueFormatArg::Text)]
+    pub(crate) format: RescueFormatArg,
+}
 
-    /// Disable approval gating for root jobs (overrides template-root approval)
-    #[arg(long = "no-require-approval", action = ArgAction::SetTrue)]
-    pub(crate) no_require_approval: bool,
+#[derive(ClapArgs, Debug)]
+pub(crate) struct RebaseCmd {
+    /// Plan slug whose branch should be rebased (tab-completes from pending plans)
+    #[arg(value_name = "PLAN", add = crate::completions::plan_slug_completer())]
+    pub(crate) plan: String,
 
-    /// Wait for terminal run state and stream progress in text mode
-    #[arg(long = "follow", action = ArgAction::SetTrue)]
-    pub(crate) follow: bool,
+    /// Branch to use instead of the plan branch (draft/<plan> unless [branches] prefix is set)
+    #[arg(long = "branch", value_name = "BRANCH")]
+    pub(crate) branch: Option<String>,
 
-    /// Number of times to enqueue and execute the same workflow in strict sequence
-    #[arg(long = "repeat", value_name = "N", default_value_t = NonZeroU32::MIN)]
-    pub(crate) repeat: NonZeroU32,
+    /// Branch to rebase onto (defaults to the detected primary branch)
+    #[arg(long = "target", value_name = "BRANCH")]
+    pub(crate) target: Option<String>,
+
+    /// Stop at the first conflict instead of invoking the merge-conflict agent
+    #[arg(long = "no-auto-resolve", action = ArgAction::SetTrue)]
+    pub(crate) no_auto_resolve: bool,
+
+    /// Rebase in a user-created worktree that already has the branch checked out without prompting
+    #[arg(short = 'y', long = "yes", action = ArgAction::SetTrue)]
+    pub(crate) assume_yes: bool,
 
     /// Output format (text, json)
-    #[arg(long = "format", value_enum, default_value_t = RunFormatArg::Text)]
-    pub(crate) format: RunFormatArg,
+    #[arg(long = "format", value_enum, default_value_t = RebaseFormatArg::Text)]
+    pub(crate) format: RebaseFormatArg,
 }
 
 #[derive(ClapArgs, Debug)]
-pub(crate) struct AuditCmd {
-    /// Workflow source: alias, selector, file:<path>, or direct .hcl/.toml/.json path
-    #[arg(value_name = "FLOW")]
-    pub(crate) flow: String,
+pub(crate) struct PickCmd {
+    /// Plan slug whose branch holds the commit (tab-completes from pending plans)
+    #[arg(value_name = "PLAN", add = crate::completions::plan_slug_completer())]
+    pub(crate) plan: String,
 
-    /// Ordered workflow inputs; mapped by template [cli].positional before analysis
-    #[arg(value_name = "INPUT")]
-    pub(crate) inputs: Vec<String>,
+    /// Commit to pick: a revision on the plan branch, or a 1-based plan step number
+    #[arg(value_name = "SHA_OR_STEP")]
+    pub(crate) pick: String,
 
-    /// Template parameter override (KEY=VALUE); repeatable
-    #[arg(long = "set", value_name = "KEY=VALUE", action = ArgAction::Append)]
-    pub(crate) set: Vec<String>,
+    /// Branch to use instead of the plan branch (draft/<plan> unless [branches] prefix is set)
+    #[arg(long = "branch", value_name = "BRANCH")]
+    pub(crate) branch: Option<String>,
 
-    /// Output format (text, json)
-    #[arg(long = "format", value_enum, default_value_t = AuditFormatArg::Text)]
-    pub(crate) format: AuditFormatArg,
+    /// Branch to pick onto (defaults to the detected primary branch)
+    #[arg(long = "target", value_name = "BRANCH")]
+    pub(crate) target: Option<String>,
 
-    /// Return exit code 10 when untethered inputs are detected
-    #[arg(long = "strict", action = ArgAction::SetTrue)]
-    pub(crate) strict: bool,
+    /// Commit message to use instead of asking the agent for one
+    #[arg(short = 'm', long = "message", value_name = "MESSAGE")]
+    pub(crate) message: Option<String>,
+
+    /// Output format (text, json)
+    #[arg(long = "format", value_enum, default_value_t = PickFormatArg::Text)]
+    pub(crate) format: PickFormatArg,
 }
 
-#[derive(Subcommand, Debug)]
-pub(crate) enum JobsAction {
-    /// List tracked background jobs (succeeded hidden by default; failures optional; `--format json --raw` for typed monitoring JSON)
-    List {
-        /// Include succeeded jobs (default hides them)
-        #[arg(long = "all", short = 'a')]
-        all: bool,
+#[derive(ClapArgs, Debug)]
+pub(crate) struct VerifyCmd {
+    /// Plan slug whose branch should be verified (tab-completes from pending plans)
+    #[arg(value_name = "PLAN", add = crate::completions::plan_slug_completer())]
+    pub(crate) plan: String,
 
-        /// Hide failed jobs from the visible list (use --all to include them)
-        #[arg(long = "dismiss-failures")]
+    /// Branch to use instead of the plan branch (draft/<plan> unless [branches] prefix is set)
+    #[arg(long = "branch", value_name = "BRANCH")]
+    pub(crate) branch: Option<String>,
+
+    /// Output format (text, json)
+    #[arg(long = "format", value_enum, default_value_t = VerifyFormatArg::Text)]
+    pub(crate) format: VerifyFormatArg,
+}
+
+#[derive(ClapArgs, Debug)]
+pub(crate) struct AckCmd {
+    /// Plan slug to acknowledge (tab-completes from pending plans)
+    #[arg(value_name = "PLAN", add = crate::completions::plan_slug_completer())]
+    pub(crate) plan: String,
+
+    /// Owner recording the acknowledgment (defaults to the git user.email local part)
+    #[arg(long = "as", value_name = "OWNER")]
+    pub(crate) owner: Option<String>,
+
+    /// Branch to use instead of the plan branch (draft/<plan> unless [branches] prefix is set)
+    #[arg(long = "branch", value_name = "BRANCH")]
+    pub(crate) branch: Option<String>,
+
+    /// Output format (text, json)
+    #[arg(long = "format", value_enum, default_value_t = AckFormatArg::Text)]
+    pub(crate) format: AckFormatArg,
+}
+
+#[derive(ClapArgs, Debug)]
+pub(crate) struct LabelCmd {
+    /// Plan slug to label (tab-completes from pending plans)
+    #[arg(value_name = "PLAN", add = crate::completions::plan_slug_completer())]
+    pub(crate) plan: String,
+
+    /// Change to make; omit to show the plan's current labels
+    #[arg(value_name = "ACTION", value_enum, requires = "labels")]
+    pub(crate) action: Option<LabelActionArg>,
+
+    /// Labels to add or remove (comma-separated or repeated)
+    #[arg(value_name = "LABEL")]
+    pub(crate) labels: Vec<String>,
+
+    /// Branch to use instead of the plan branch (draft/<plan> unless [branches] prefix is set)
+    #[arg(long = "branch", value_name = "BRANCH")]
+    pub(crate) branch: Option<String>,
+
+    /// Output format (text, json)
+    #[arg(long = "format", value_enum, default_value_t = LabelFormatArg::Text)]
+    pub(crate) format: LabelFormatArg,
+}
+
+#[derive(ClapArgs, Debug)]
+pub(crate) struct NoteCmd {
+    /// Plan slug to annotate (tab-completes from pending plans)
+    #[arg(value_name = "PLAN", add = crate::completions::plan_slug_completer())]
+    pub(crate) plan: String,
+
+    /// Note to append; omit to show the plan's notes
+    #[arg(value_name = "NOTE")]
+    pub(crate) note: Option<String>,
+
+    /// Branch to use instead of the plan branch (draft/<plan> unless [branches] prefix is set)
+    #[arg(long = "branch", value_name = "BRANCH")]
+    pub(crate) branch: Option<String>,
+
+    /// Output format (text, json)
+    #[arg(long = "format", value_enum, default_value_t = NoteFormatArg::Text)]
+    pub(crate) format: NoteFormatArg,
+}
+
+#[derive(ClapArgs, Debug)]
+pub(crate) struct ApplyPatchCmd {
+    /// Plan slug whose branch receives the patch (tab-completes from pending plans)
+    #[arg(value_name = "PLAN", add = crate::completions::plan_slug_completer())]
+    pub(crate) plan: String,
+
+    /// Patch file to read (unified diff or format-patch mbox); reads stdin when omitted or `-`
+    #[arg(value_name = "PATCH")]
+    pub(crate) patch: Option<String>,
+
+    /// Branch to use instead of the plan branch (draft/<plan> unless [branches] prefix is set)
+    #[arg(long = "branch", value_name = "BRANCH")]
+    pub(crate) branch: Option<String>,
+
+    /// Commit message for a single patch (defaults to the mbox subject/body)
+    #[arg(short = 'm', long = "message", value_name = "MESSAGE")]
+    pub(crate) message: Option<String>,
+
+    /// Output format (text, json)
+    #[arg(long = "format", value_enum, default_value_t = ApplyPatchFormatArg::Text)]
+    pub(crate) format: ApplyPatchFormatArg,
+}
+
+#[derive(ClapArgs, Debug)]
+pub(crate) struct SessionsCmd {
+    #[command(subcommand)]
+    pub(crate) action: SessionsAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum SessionsAction {
+    /// List session logs (newest first) with their age and on-disk size
+    List {
+        /// Output format (text, json)
+        #[arg(long = "format", value_enum, default_value_t = SessionsFormatArg::Text)]
+        format: SessionsFormatArg,
+    },
+
+    /// Show one session's summary and command outcome (compressed sessions are read transparently)
+    Show {
+        #[arg(value_name = "SESSION")]
+        session: String,
+
+        /// Output format (text, json)
+        #[arg(long = "format", value_enum, default_value_t = SessionsFormatArg::Text)]
+        format: SessionsFormatArg,
+    },
+
+    /// Remove (or compress) sessions outside the [sessions] retention policy
+    Gc {
+        /// Keep sessions modified within the last DAYS days (overrides sessions.keep_days)
+        #[arg(long = "since", value_name = "DAYS")]
+        since: Option<u32>,
+
+        /// Keep at most the newest N sessions (overrides sessions.keep_last)
+        #[arg(long = "keep-last", value_name = "N")]
+        keep_last: Option<u32>,
+
+        /// Compress expired sessions to *.json.zst instead of deleting them
+        #[arg(long = "compress", action = ArgAction::SetTrue, conflicts_with = "no_compress")]
+        compress: bool,
+
+        /// Delete expired sessions even if sessions.compress is enabled
+        #[arg(long = "no-compress", action = ArgAction::SetTrue)]
+        no_compress: bool,
+
+        /// Report what would be collected without touching disk
+        #[arg(long = "dry-run", action = ArgAction::SetTrue)]
+        dry_run: bool,
+
+        /// Output format (text, json)
+        #[arg(long = "format", value_enum, default_value_t = SessionsFormatArg::Text)]
+        format: SessionsFormatArg,
+    },
+
+    /// Check session logs for truncated JSON and leftovers from interrupted writes; exits 10
+    /// when issues remain
+    Fsck {
+        /// Restore complete copies from interrupted writes, move truncated logs aside to
+        /// *.corrupt, and delete stale temp files
+        #[arg(long = "repair", action = ArgAction::SetTrue)]
+        repair: bool,
+
+        /// Output format (text, json)
+        #[arg(long = "format", value_enum, default_value_t = SessionsFormatArg::Text)]
+        format: SessionsFormatArg,
+    },
+
+    /// Upgrade session.json logs written by older versions to the current schema; exits 10 when
+    /// a log cannot be migrated
+    Migrate {
+        /// Report which logs would change without touching disk
+        #[arg(long = "dry-run", action = ArgAction::SetTrue)]
+        dry_run: bool,
+
+        /// Output format (text, json)
+        #[arg(long = "format", value_enum, default_value_t = SessionsFormatArg::Text)]
+        format: SessionsFormatArg,
+    },
+
+    /// Print the JSON Schema that session.json logs are validated against
+    Schema,
+}
+
+#[derive(ClapArgs, Debug)]
+pub(crate) struct ChangelogCmd {
+    #[command(subcommand)]
+    pub(crate) action: ChangelogAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum ChangelogAction {
+    /// Move every fragment under changelog.dir into a new release section and commit the result
+    Assemble {
+        /// Version heading for the new section (`Unreleased` omits the date)
+        #[arg(value_name = "VERSION", default_value = "Unreleased")]
+        release: String,
+
+        /// Release date for the heading (defaults to today, YYYY-MM-DD)
+        #[arg(long = "date", value_name = "DATE")]
+        date: Option<String>,
+
+        /// Print the section that would be added without touching disk
+        #[arg(long = "dry-run", action = ArgAction::SetTrue)]
+        dry_run: bool,
+
+        /// Update CHANGELOG.md and remove the fragments but leave the changes uncommitted
+        #[arg(long = "no-commit", action = ArgAction::SetTrue, conflicts_with = "dry_run")]
+        no_commit: bool,
+
+        /// Output format (text, json)
+        #[arg(long = "format", value_enum, default_value_t = ChangelogFormatArg::Text)]
+        format: ChangelogFormatArg,
+    },
+}
+
+#[derive(ClapArgs, Debug)]
+pub(crate) struct StatsCmd {
+    /// Only count sessions and jobs created within the last DAYS days
+    #[arg(long = "since", value_name = "DAYS")]
+    pub(crate) since: Option<u32>,
+
+    /// Output format (text, json)
+    #[arg(long = "format", value_enum, default_value_t = StatsFormatArg::Text)]
+    pub(crate) format: StatsFormatArg,
+}
+
+#[derive(ClapArgs, Debug)]
+pub(crate) struct ArchiveCmd {
+    #[command(subcommand)]
+    pub(crate) action: ArchiveAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum ArchiveAction {
+    /// List archived plans (newest first) with their merge commit and title
+    List {
+        /// Output format (text, json)
+        #[arg(long = "format", value_enum, default_value_t = ArchiveFormatArg::Text)]
+        format: ArchiveFormatArg,
+    },
+
+    /// Show an archived plan document, its diff summary, and the final review critique
+    Show {
+        #[arg(value_name = "PLAN")]
+        plan: String,
+
+        /// Output format (text, json)
+        #[arg(long = "format", value_enum, default_value_t = ArchiveFormatArg::Text)]
+        format: ArchiveFormatArg,
+    },
+
+    /// Rank archived plan, summary, and critique passages for a query
+    Search {
+        /// Free-text query; paraphrases also match when [search] embedder is set
+        #[arg(value_name = "QUERY", required = true, num_args = 1..)]
+        query: Vec<String>,
+
+        /// Maximum number of hits to print
+        #[arg(long = "limit", value_name = "N", default_value_t = 10)]
+        limit: usize,
+
+        /// Output format (text, json)
+        #[arg(long = "format", value_enum, default_value_t = ArchiveFormatArg::Text)]
+        format: ArchiveFormatArg,
+    },
+}
+
+#[derive(ClapArgs, Debug)]
+pub(crate) struct SnapshotCmd {
+    #[command(subcommand)]
+    pub(crate) action: SnapshotAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum SnapshotAction {
+    /// Show the snapshot, glossary, and threads as committed at a revision (read from git, not the worktree)
+    Show {
+        /// Revision to read the narrative from
+        #[arg(
+            long = "at",
+            value_name = "REV",
+            default_value = "HEAD",
+            conflicts_with = "diff"
+        )]
+        at: String,
+
+        /// Print a narrative-level changelog between two revisions instead
+        #[arg(long = "diff", value_names = ["REV1", "REV2"], num_args = 2)]
+        diff: Option<Vec<String>>,
+
+        /// Output format (text, json)
+        #[arg(long = "format", value_enum, default_value_t = SnapshotFormatArg::Text)]
+        format: SnapshotFormatArg,
+    },
+
+    /// Rebuild a best-effort snapshot from merged plans, merge commits, and session logs
+    Rebuild {
+        /// Reconstruct from Vizier's own records (archived plans, plan merge commits, session operations)
+        #[arg(long = "from-ledger", action = ArgAction::SetTrue, required = true)]
+        from_ledger: bool,
+
+        /// Replace an existing non-empty snapshot
+        #[arg(long = "force", action = ArgAction::SetTrue)]
+        force: bool,
+
+        /// Print the rebuilt snapshot without writing it
+        #[arg(long = "dry-run", action = ArgAction::SetTrue)]
+        dry_run: bool,
+
+        /// Most recent sessions to summarize under Recent Agent Activity
+        #[arg(long = "sessions", value_name = "N", default_value_t = 20)]
+        sessions: usize,
+
+        /// Output format (text, json)
+        #[arg(long = "format", value_enum, default_value_t = SnapshotFormatArg::Text)]
+        format: SnapshotFormatArg,
+    },
+}
+
+#[derive(ClapArgs, Debug)]
+pub(crate) struct DoctorCmd {
+    /// Output format (text, json); json emits a machine-readable report of every check
+    #[arg(long = "format", value_enum, default_value_t = DoctorFormatArg::Text)]
+    pub(crate) format: DoctorFormatArg,
+
+    /// Skip the `git ls-remote` authentication probe for each remote
+    #[arg(long = "no-remote")]
+    pub(crate) no_remote: bool,
+}
+
+#[derive(ClapArgs, Debug)]
+pub(crate) struct WatchCmd {
+    /// Seconds without new changes before a batch is sent for suggestions
+    #[arg(long = "settle", value_name = "SECS", default_value_t = 60)]
+    pub(crate) settle: u64,
+
+    /// Suggest once for the current uncommitted changes and exit
+    #[arg(long = "once", action = ArgAction::SetTrue)]
+    pub(crate) once: bool,
+
+    /// Also append each suggestion to .vizier/tmp/watch/suggestions.md
+    #[arg(long = "record", action = ArgAction::SetTrue)]
+    pub(crate) record: bool,
+
+    /// Output format (text, json); json prints one object per batch
+    #[arg(long = "format", value_enum, default_value_t = WatchFormatArg::Text)]
+    pub(crate) format: WatchFormatArg,
+}
+
+#[derive(ClapArgs, Debug)]
+pub(crate) struct AgentCmd {
+    #[command(subcommand)]
+    pub(crate) action: AgentAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum AgentAction {
+    /// Run canned prompts through a shim and check its exit codes, stdin handling, and output
+    Validate {
+        /// Progress filter that turns the shim's event stream into the final reply
+        #[arg(long = "filter", value_name = "PROGRAM")]
+        filter: Option<String>,
+
+        /// Check the shim's stdout as an event stream even without --filter
+        #[arg(long = "events", action = ArgAction::SetTrue)]
+        events: bool,
+
+        /// Seconds each case may run before it is killed and fails
+        #[arg(long = "timeout", value_name = "SECS", default_value_t = 120)]
+        timeout: u64,
+
+        /// Output format (text, json)
+        #[arg(long = "format", value_enum, default_value_t = AgentFormatArg::Text)]
+        format: AgentFormatArg,
+
+        /// Shim command and its arguments
+        #[arg(value_name = "COMMAND", required = true, num_args = 1.., trailing_var_arg = true, allow_hyphen_values = true)]
+        command: Vec<String>,
+    },
+}
+
+#[derive(ClapArgs, Debug)]
+pub(crate) struct PromptCmd {
+    #[command(subcommand)]
+    pub(crate) action: PromptAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum PromptAction {
+    /// Print the fully expanded prompt with size and token stats; --run sends it to the agent
+    Test {
+        /// Prompt to render
+        #[arg(value_name = "PROMPT", value_enum)]
+        prompt: PromptKindArg,
+
+        /// Command alias whose prompt overrides and agent apply (defaults to the prompt's stage)
+        #[arg(long = "scope", value_name = "ALIAS")]
+        scope: Option<String>,
+
+        /// Operator input the prompt wraps: the task, plan spec, or commit diff
+        #[arg(long = "input", value_name = "TEXT", conflicts_with = "input_file")]
+        input: Option<String>,
+
+        /// Read the operator input from a file (`-` for stdin)
+        #[arg(long = "input-file", value_name = "PATH")]
+        input_file: Option<PathBuf>,
+
+        /// Plan whose slug, branch, document, and diff fill plan-based prompts
+        #[arg(long = "plan", value_name = "PLAN", add = crate::completions::plan_slug_completer())]
+        plan: Option<String>,
+
+        /// Branch to compare the plan against (defaults to the detected primary branch)
+        #[arg(long = "target", value_name = "BRANCH")]
+        target: Option<String>,
+
+        /// Send the prompt to the scope's agent in a throwaway worktree; nothing is committed
+        #[arg(long = "run", action = ArgAction::SetTrue)]
+        run: bool,
+
+        /// Output format (text, json)
+        #[arg(long = "format", value_enum, default_value_t = PromptFormatArg::Text)]
+        format: PromptFormatArg,
+    },
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub(crate) enum PromptKindArg {
+    Documentation,
+    Commit,
+    #[value(alias = "implementation_plan")]
+    ImplementationPlan,
+    Review,
+    #[value(alias = "merge_conflict")]
+    MergeConflict,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub(crate) enum PromptFormatArg {
+    Text,
+    Json,
+}
+
+#[derive(ClapArgs, Debug)]
+pub(crate) struct ThreadCmd {
+    #[command(subcommand)]
+    pub(crate) action: ThreadAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum ThreadAction {
+    /// List threads under .vizier/narrative/threads with their state and owners
+    List {
+        /// Only show threads in this state
+        #[arg(long = "state", value_enum)]
+        state: Option<ThreadStateArg>,
+
+        /// Output format (text, json)
+        #[arg(long = "format", value_enum, default_value_t = ThreadFormatArg::Text)]
+        format: ThreadFormatArg,
+    },
+
+    /// Mark a thread resolved by a plan (committed on the plan branch while it is pending)
+    Resolve {
+        #[arg(value_name = "THREAD")]
+        thread: String,
+
+        /// Plan that resolves the thread
+        #[arg(long = "by", value_name = "PLAN", add = crate::completions::plan_slug_completer())]
+        by: String,
+
+        /// Output format (text, json)
+        #[arg(long = "format", value_enum, default_value_t = ThreadFormatArg::Text)]
+        format: ThreadFormatArg,
+    },
+
+    /// Mark a thread blocked, optionally with the reason
+    Block {
+        #[arg(value_name = "THREAD")]
+        thread: String,
+
+        /// Why the thread cannot move forward
+        #[arg(long = "reason", value_name = "TEXT")]
+        reason: Option<String>,
+
+        /// Commit the change on this plan's branch instead of the current branch
+        #[arg(long = "plan", value_name = "PLAN", add = crate::completions::plan_slug_completer())]
+        plan: Option<String>,
+
+        /// Output format (text, json)
+        #[arg(long = "format", value_enum, default_value_t = ThreadFormatArg::Text)]
+        format: ThreadFormatArg,
+    },
+
+    /// Return a blocked or resolved thread to open
+    Reopen {
+        #[arg(value_name = "THREAD")]
+        thread: String,
+
+        /// Commit the change on this plan's branch instead of the current branch
+        #[arg(long = "plan", value_name = "PLAN", add = crate::completions::plan_slug_completer())]
+        plan: Option<String>,
+
+        /// Output format (text, json)
+        #[arg(long = "format", value_enum, default_value_t = ThreadFormatArg::Text)]
+        format: ThreadFormatArg,
+    },
+
+    /// Ask the agent to revise, delete, or keep threads and commit the results once
+    Revise {
+        /// Threads to revise
+        #[arg(value_name = "THREAD", required_unless_present = "all")]
+        threads: Vec<String>,
+
+        /// Revise every thread on the current branch
+        #[arg(long = "all", conflicts_with = "threads")]
+        all: bool,
+
+        /// Output format (text, json)
+        #[arg(long = "format", value_enum, default_value_t = ThreadFormatArg::Text)]
+        format: ThreadFormatArg,
+    },
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub(crate) enum ThreadStateArg {
+    Open,
+    Blocked,
+    Resolved,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub(crate) enum ThreadFormatArg {
+    Text,
+    Json,
+}
+
+#[derive(ClapArgs, Debug)]
+pub(crate) struct CacheCmd {
+    #[command(subcommand)]
+    pub(crate) action: CacheAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum CacheAction {
+    /// Show the cache location, its size, and whether each cached shim matches this binary
+    Status {
+        /// Output format (text, json)
+        #[arg(long = "format", value_enum, default_value_t = CacheFormatArg::Text)]
+        format: CacheFormatArg,
+    },
+
+    /// Remove cache trees left by other cache versions (or everything with --all)
+    Clean {
+        /// Remove the whole cache; shims are written again on next use
+        #[arg(long = "all", action = ArgAction::SetTrue)]
+        all: bool,
+
+        /// Output format (text, json)
+        #[arg(long = "format", value_enum, default_value_t = CacheFormatArg::Text)]
+        format: CacheFormatArg,
+    },
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub(crate) enum CacheFormatArg {
+    Text,
+    Json,
+}
+
+#[derive(ClapArgs, Debug)]
+pub(crate) struct ForgeCmd {
+    #[command(subcommand)]
+    pub(crate) action: ForgeAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum ForgeAction {
+    /// Show `[forge] cli`, the origin remote, and the CLI it resolves to
+    Status {
+        /// Output format (text, json)
+        #[arg(long = "format", value_enum, default_value_t = ForgeFormatArg::Text)]
+        format: ForgeFormatArg,
+    },
+
+    /// Fetch an issue through the forge CLI as a markdown spec (`# title`, body, source link)
+    Issue {
+        /// Issue number (or anything the CLI accepts, such as its URL)
+        #[arg(value_name = "ISSUE")]
+        id: String,
+
+        /// Write the spec to this file instead of stdout
+        #[arg(long = "output", short = 'o', value_name = "FILE")]
+        output: Option<PathBuf>,
+
+        /// Output format (text, json)
+        #[arg(long = "format", value_enum, default_value_t = ForgeFormatArg::Text)]
+        format: ForgeFormatArg,
+    },
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub(crate) enum ForgeFormatArg {
+    Text,
+    Json,
+}
+
+#[derive(ClapArgs, Debug)]
+pub(crate) struct ToolCmd {
+    #[command(subcommand)]
+    pub(crate) action: ToolAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum ToolAction {
+    /// Print a numbered slice of a worktree file
+    File {
+        /// Path relative to the repository root
+        #[arg(value_name = "PATH")]
+        path: String,
+
+        /// Inclusive 1-based range, e.g. 40:80 (defaults to the first 400 lines)
+        #[arg(long = "lines", value_name = "START:END")]
+        lines: Option<String>,
+    },
+
+    /// Show the commit, date, and author that last changed each committed line in a range
+    Blame {
+        /// Path relative to the repository root
+        #[arg(value_name = "PATH")]
+        path: String,
+
+        /// Inclusive 1-based range, e.g. 40:80
+        #[arg(long = "lines", value_name = "START:END")]
+        lines: String,
+    },
+
+    /// List tracked-file lines containing the text (case-sensitive) as path:line: text
+    Search {
+        /// Literal text to look for
+        #[arg(value_name = "TEXT")]
+        text: String,
+
+        /// Stop after this many matches
+        #[arg(long = "limit", value_name = "N", default_value_t = vizier_core::review_tools::DEFAULT_SEARCH_LIMIT)]
+        limit: usize,
+    },
+}
+
+#[derive(ClapArgs, Debug)]
+pub(crate) struct CiCmd {
+    #[command(subcommand)]
+    pub(crate) action: CiAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum CiAction {
+    /// Run a plan's review workflow to completion and fail on action items at or above a severity
+    Review {
+        /// Plan slug whose branch should be reviewed
+        #[arg(value_name = "PLAN")]
+        plan: String,
+
+        /// Workflow alias or selector that runs the review
+        #[arg(long = "flow", value_name = "FLOW", default_value = "review")]
+        flow: String,
+
+        /// Lowest action-item severity that fails the job (blocker, major, minor, nit, any, never)
+        #[arg(long = "fail-on", value_enum, default_value_t = CiFailOnArg::Major)]
+        fail_on: CiFailOnArg,
+
+        /// Directory CI artifacts are written to (review: run.json, critique.md, review.json; merge-queue: verify.json, gates/*.log)
+        #[arg(
+            long = "artifacts-dir",
+            value_name = "DIR",
+            default_value = ".vizier/ci"
+        )]
+        artifacts_dir: PathBuf,
+
+        /// Output format (text, json, github workflow-command annotations)
+        #[arg(long = "format", value_enum, default_value_t = CiFormatArg::Text)]
+        format: CiFormatArg,
+    },
+
+    /// Run the gates and review checks against a queued branch, as `vizier verify` does for plans
+    MergeQueue {
+        /// Branch to verify (defaults to the checked-out branch)
+        #[arg(long = "branch", value_name = "BRANCH")]
+        branch: Option<String>,
+
+        /// Target branch the queued branch merges into (defaults to the detected target)
+        #[arg(long = "target", value_name = "BRANCH")]
+        target: Option<String>,
+
+        /// Directory CI artifacts are written to (review: run.json, critique.md, review.json; merge-queue: verify.json, gates/*.log)
+        #[arg(
+            long = "artifacts-dir",
+            value_name = "DIR",
+            default_value = ".vizier/ci"
+        )]
+        artifacts_dir: PathBuf,
+
+        /// Output format (text, json, github workflow-command annotations)
+        #[arg(long = "format", value_enum, default_value_t = CiFormatArg::Text)]
+        format: CiFormatArg,
+    },
+}
+
+#[derive(ClapArgs, Debug)]
+pub(crate) struct ConfigCmd {
+    #[command(subcommand)]
+    pub(crate) action: ConfigAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum ConfigAction {
+    /// Propose [review.checks] commands from .github/workflows/*.yml and .gitlab-ci.yml
+    ImportCi {
+        /// Append the proposed [review.checks] block to .vizier/config.toml
+        #[arg(long = "write", action = ArgAction::SetTrue)]
+        write: bool,
+
+        /// Output format (text, json, toml)
+        #[arg(long = "format", value_enum, default_value_t = ConfigImportFormatArg::Text)]
+        format: ConfigImportFormatArg,
+    },
+}
+
+#[derive(ClapArgs, Debug)]
+pub(crate) struct PlanCmd {
+    /// Print only the keys that override built-in defaults, each with the layer that won
+    #[arg(long = "diff")]
+    pub(crate) diff: bool,
+
+    /// Emit the report as JSON
+    #[arg(long = "json")]
+    pub(crate) json: bool,
+}
+
+#[derive(ClapArgs, Debug)]
+pub(crate) struct SearchCmd {
+    /// Free-text query; paraphrases also match when [search] embedder is set
+    #[arg(value_name = "QUERY", required = true, num_args = 1..)]
+    pub(crate) query: Vec<String>,
+
+    /// Maximum number of hits to print
+    #[arg(long = "limit", value_name = "N", default_value_t = 10)]
+    pub(crate) limit: usize,
+
+    /// Output format (text, json)
+    #[arg(long = "format", value_enum, default_value_t = SearchFormatArg::Text)]
+    pub(crate) format: SearchFormatArg,
+}
+
+#[derive(ClapArgs, Debug)]
+pub(crate) struct JobsCmd {
+    #[command(subcommand)]
+    pub(crate) action: JobsAction,
+}
+
+#[derive(ClapArgs, Debug)]
+pub(crate) struct RunCmd {
+    /// Workflow source: alias, selector, file:<path>, or direct .hcl/.toml/.json path
+    #[arg(value_name = "FLOW")]
+    pub(crate) flow: String,
+
+    /// Ordered workflow inputs; mapped by template [cli].positional before enqueue
+    #[arg(value_name = "INPUT")]
+    pub(crate) inputs: Vec<String>,
+
+    /// Template parameter override (KEY=VALUE); repeatable
+    #[arg(long = "set", value_name = "KEY=VALUE", action = ArgAction::Append)]
+    pub(crate) set: Vec<String>,
+
+    /// Validate queue-time workflow checks without enqueueing jobs or manifests
+    #[arg(
+        long = "check",
+        action = ArgAction::SetTrue,
+        conflicts_with_all = [
+            "after",
+            "ephemeral",
+            "require_approval",
+            "no_require_approval",
+            "follow",
+            "repeat"
+        ]
+    )]
+    pub(crate) check: bool,
+
+    /// Expand a directory of markdown specs into serial workflow runs
+    #[arg(
+        long = "spec-dir",
+        value_name = "DIR",
+        conflicts_with = "repeat",
+        value_parser = NonEmptyStringValueParser::new()
+    )]
+    pub(crate) spec_dir: Option<String>,
+
+    /// External predecessor dependency; root jobs wait on JOB_ID or run:RUN_ID
+    #[arg(long = "after", value_name = "REF", action = ArgAction::Append)]
+    pub(crate) after: Vec<String>,
+
+    /// Attach a document (design doc, incident report, acceptance criteria) to every agent
+    /// prompt in the run; repeatable
+    #[arg(long = "context", value_name = "PATH", action = ArgAction::Append)]
+    pub(crate) context: Vec<String>,
+
+    /// Diff review placeholders against REV (e.g. the previously reviewed tip) instead of the
+    /// target, so an iterative review critiques only the commits since
+    #[arg(
+        long = "baseline",
+        value_name = "REV",
+        value_parser = NonEmptyStringValueParser::new()
+    )]
+    pub(crate) baseline: Option<String>,
+
+    /// Auto-clean Vizier-owned runtime material after the run is terminal and cleanup is safe
+    #[arg(long = "ephemeral", action = ArgAction::SetTrue)]
+    pub(crate) ephemeral: bool,
+
+    /// Never commit: commit nodes write the changes to the job's command.patch and restore the
+    /// worktree, leaving nothing staged
+    #[arg(
+        long = "patch",
+        action = ArgAction::SetTrue,
+        conflicts_with_all = ["check", "ephemeral", "remote"]
+    )]
+    pub(crate) patch: bool,
+
+    /// Require explicit approval before root jobs can start
+    #[arg(long = "require-approval", action = ArgAction::SetTrue, conflicts_with = "no_require_approval")]
+    pub(crate) require_approval: bool,
+
+    /// Disable approval gating for root jobs (overrides template-root approval)
+    #[arg(long = "no-require-approval", action = ArgAction::SetTrue)]
+    pub(crate) no_require_approval: bool,
+
+    /// Queue priority for the run's jobs; higher values start first when [jobs] max_concurrent
+    /// limits how many run at once (default 0)
+    #[arg(
+        long = "priority",
+        value_name = "N",
+        allow_negative_numbers = true,
+        conflicts_with = "check"
+    )]
+    pub(crate) priority: Option<i32>,
+
+    /// Abort an approve run when the upper end of its cost estimate exceeds this many dollars
+    #[arg(
+        long = "max-cost",
+        value_name = "USD",
+        value_parser = parse_max_cost,
+        conflicts_with = "check"
+    )]
+    pub(crate) max_cost: Option<f64>,
+
+    /// Skip the test-generation phase of an approve run even when [approve] generate_tests is on
+    #[arg(long = "no-gen-tests", action = ArgAction::SetTrue)]
+    pub(crate) no_gen_tests: bool,
+
+    /// Let a review run fix its own Action Items and re-review until clean or
+    /// [review.fix_loop] max_iterations is spent
+    #[arg(long = "fix-loop", action = ArgAction::SetTrue)]
+    pub(crate) fix_loop: bool,
+
+    /// Open the agent's drafted plan in $VISUAL/$EDITOR before it is committed to the draft
+    /// branch; edits are kept and the plan is marked `human_amended: true`
+    #[arg(
+        long = "edit-plan",
+        action = ArgAction::SetTrue,
+        requires = "follow",
+        conflicts_with_all = ["check", "remote"]
+    )]
+    pub(crate) edit_plan: bool,
+
+    /// Skip the confirmation pane shown before an interactive approve or merge run
+    #[arg(long = "yes", short = 'y', action = ArgAction::SetTrue)]
+    pub(crate) assume_yes: bool,
+
+    /// Wait for terminal run state and stream progress in text mode
+    #[arg(long = "follow", action = ArgAction::SetTrue)]
+    pub(crate) follow: bool,
+
+    /// Number of times to enqueue and execute the same workflow in strict sequence
+    #[arg(long = "repeat", value_name = "N", default_value_t = NonZeroU32::MIN)]
+    pub(crate) repeat: NonZeroU32,
+
+    /// Fail instead of warning when an approved/merged plan branch exceeds [workflow.drift] limits
+    #[arg(long = "strict", action = ArgAction::SetTrue)]
+    pub(crate) strict: bool,
+
+    /// Fail instead of warning when the plan branch touches files another pending plan changes
+    #[arg(long = "strict-overlap", action = ArgAction::SetTrue)]
+    pub(crate) strict_overlap: bool,
+
+    /// Push the plan branch and execute the run on the [remote] build host over SSH, then fetch
+    /// the resulting branch and session logs back
+    #[arg(
+        long = "remote",
+        action = ArgAction::SetTrue,
+        conflicts_with_all = ["check", "spec_dir", "after", "context", "baseline", "ephemeral", "repeat"]
+    )]
+    pub(crate) remote: bool,
+
+    /// Output format (text, json)
+    #[arg(long = "format", value_enum, default_value_t = RunFormatArg::Text)]
+    pub(crate) format: RunFormatArg,
+}
+
+#[derive(ClapArgs, Debug)]
+pub(crate) struct AuditCmd {
+    /// Workflow source: alias, selector, file:<path>, or direct .hcl/.toml/.json path
+    #[arg(value_name = "FLOW")]
+    pub(crate) flow: String,
+
+    /// Ordered workflow inputs; mapped by template [cli].positional before analysis
+    #[arg(value_name = "INPUT")]
+    pub(crate) inputs: Vec<String>,
+
+    /// Template parameter override (KEY=VALUE); repeatable
+    #[arg(long = "set", value_name = "KEY=VALUE", action = ArgAction::Append)]
+    pub(crate) set: Vec<String>,
+
+    /// Output format (text, json)
+    #[arg(long = "format", value_enum, default_value_t = AuditFormatArg::Text)]
+    pub(crate) format: AuditFormatArg,
+
+    /// Return exit code 10 when untethered inputs are detected
+    #[arg(long = "strict", action = ArgAction::SetTrue)]
+    pub(crate) strict: bool,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum JobsAction {
+    /// List tracked background jobs (succeeded hidden by default; failures optional; `--format json --raw` for typed monitoring JSON)
+    List {
+        /// Include succeeded jobs (default hides them)
+        #[arg(long = "all", short = 'a')]
+        all: bool,
+
+        /// Hide failed jobs from the visible list (use --all to include them)
+        #[arg(long = "dismiss-failures")]
         dismiss_failures: bool,
 
         /// Output format (block, table, json); overrides display.lists.jobs.format
@@ -778,16 +2015,48 @@ pub(crate) enum JobsAction {
         format: JobsActionFormatArg,
     },
 
+    /// Render a review job's critique with severity-tagged, file-anchored Action Items
+    Review {
+        #[arg(value_name = "JOB")]
+        job: String,
+
+        /// Output format (markdown, plain, json)
+        #[arg(long = "format", value_enum, default_value_t = ReviewFormatArg::Markdown)]
+        format: ReviewFormatArg,
+
+        /// Also post the markdown critique as a review comment on this pull request via the forge CLI
+        #[arg(long = "publish", value_name = "PR")]
+        publish: Option<String>,
+    },
+
     /// Rewind a failed/blocked job chain to its predecessor state and re-queue it
     Retry {
         #[arg(value_name = "JOB")]
         job: String,
 
+        /// Resume on the job's recorded worktree instead of cleaning it up first
+        #[arg(long = "continue", action = ArgAction::SetTrue)]
+        continue_worktree: bool,
+
+        /// Corrective guidance appended to agent prompts in the retry set
+        #[arg(long = "note", value_name = "TEXT")]
+        note: Option<String>,
+
         /// Output format (text, json)
         #[arg(long = "format", value_enum, default_value_t = JobsActionFormatArg::Text)]
         format: JobsActionFormatArg,
     },
 
+    /// Resolve a blocked job's merge conflicts in a terminal UI (ours/theirs/both/edit per hunk), then retry it in place
+    Resolve {
+        #[arg(value_name = "JOB")]
+        job: String,
+
+        /// Stage the resolutions without retrying the job
+        #[arg(long = "no-retry", action = ArgAction::SetTrue)]
+        no_retry: bool,
+    },
+
     /// Approve a queued job that is waiting on explicit human approval
     Approve {
         #[arg(value_name = "JOB")]
@@ -988,33 +2257,91 @@ where
 mod tests {
     use super::{Cli, Commands};
     use clap::Parser;
+    use vizier_core::display;
+
+    #[test]
+    fn run_check_parses() {
+        let cli = Cli::try_parse_from(["vizier", "run", "draft", "--check"]).expect("parse check");
+        let Commands::Run(cmd) = cli.command else {
+            panic!("expected run command");
+        };
+        assert!(cmd.check, "expected --check to set RunCmd::check");
+    }
+
+    #[test]
+    fn run_check_conflicts_with_runtime_flags() {
+        for args in [
+            vec!["vizier", "run", "draft", "--check", "--follow"],
+            vec!["vizier", "run", "draft", "--check", "--after", "job-1"],
+            vec!["vizier", "run", "draft", "--check", "--ephemeral"],
+            vec!["vizier", "run", "draft", "--check", "--require-approval"],
+            vec!["vizier", "run", "draft", "--check", "--no-require-approval"],
+            vec!["vizier", "run", "draft", "--check", "--repeat", "2"],
+        ] {
+            let err = Cli::try_parse_from(args).expect_err("expected clap conflict");
+            let rendered = err.to_string();
+            assert!(
+                rendered.contains("--check"),
+                "expected clap error to mention --check: {rendered}"
+            );
+        }
+    }
+
+    #[test]
+    fn run_remote_parses_and_rejects_local_queue_flags() {
+        let cli = Cli::try_parse_from(["vizier", "run", "approve", "alpha", "--remote"])
+            .expect("parse remote");
+        let Commands::Run(cmd) = cli.command else {
+            panic!("expected run command");
+        };
+        assert!(cmd.remote, "expected --remote to set RunCmd::remote");
+
+        for args in [
+            vec!["vizier", "run", "approve", "--remote", "--check"],
+            vec!["vizier", "run", "approve", "--remote", "--after", "job-1"],
+            vec!["vizier", "run", "approve", "--remote", "--repeat", "2"],
+        ] {
+            let err = Cli::try_parse_from(args).expect_err("expected clap conflict");
+            assert!(err.to_string().contains("--remote"), "{err}");
+        }
+    }
+
+    #[test]
+    fn run_patch_parses_and_rejects_ephemeral() {
+        let cli = Cli::try_parse_from(["vizier", "run", "approve", "alpha", "--patch"])
+            .expect("parse run --patch");
+        let Commands::Run(cmd) = cli.command else {
+            panic!("expected run command");
+        };
+        assert!(cmd.patch);
+
+        let err = Cli::try_parse_from(["vizier", "run", "approve", "--patch", "--ephemeral"])
+            .expect_err("expected clap conflict");
+        assert!(err.to_string().contains("--patch"), "{err}");
+    }
 
     #[test]
-    fn run_check_parses() {
-        let cli = Cli::try_parse_from(["vizier", "run", "draft", "--check"]).expect("parse check");
+    fn run_baseline_parses_and_rejects_remote() {
+        let cli = Cli::try_parse_from(["vizier", "run", "review", "--baseline", "abc1234"])
+            .expect("parse run --baseline");
         let Commands::Run(cmd) = cli.command else {
             panic!("expected run command");
         };
-        assert!(cmd.check, "expected --check to set RunCmd::check");
+        assert_eq!(cmd.baseline.as_deref(), Some("abc1234"));
+
+        let err = Cli::try_parse_from(["vizier", "run", "review", "--baseline", "abc", "--remote"])
+            .expect_err("expected clap conflict");
+        assert!(err.to_string().contains("--baseline"), "{err}");
     }
 
     #[test]
-    fn run_check_conflicts_with_runtime_flags() {
-        for args in [
-            vec!["vizier", "run", "draft", "--check", "--follow"],
-            vec!["vizier", "run", "draft", "--check", "--after", "job-1"],
-            vec!["vizier", "run", "draft", "--check", "--ephemeral"],
-            vec!["vizier", "run", "draft", "--check", "--require-approval"],
-            vec!["vizier", "run", "draft", "--check", "--no-require-approval"],
-            vec!["vizier", "run", "draft", "--check", "--repeat", "2"],
-        ] {
-            let err = Cli::try_parse_from(args).expect_err("expected clap conflict");
-            let rendered = err.to_string();
-            assert!(
-                rendered.contains("--check"),
-                "expected clap error to mention --check: {rendered}"
-            );
-        }
+    fn run_priority_accepts_negative_values() {
+        let cli = Cli::try_parse_from(["vizier", "run", "approve", "alpha", "--priority", "-2"])
+            .expect("parse run --priority");
+        let Commands::Run(cmd) = cli.command else {
+            panic!("expected run command");
+        };
+        assert_eq!(cmd.priority, Some(-2));
     }
 
     #[test]
@@ -1163,6 +2490,20 @@ mod tests {
         assert!(raw, "expected --raw on jobs list");
     }
 
+    #[test]
+    fn jobs_review_parses_format() {
+        let cli = Cli::try_parse_from(["vizier", "jobs", "review", "job-1", "--format", "plain"])
+            .expect("parse jobs review");
+        let Commands::Jobs(cmd) = cli.command else {
+            panic!("expected jobs command");
+        };
+        let super::JobsAction::Review { job, format, .. } = cmd.action else {
+            panic!("expected jobs review action");
+        };
+        assert_eq!(job, "job-1");
+        assert!(matches!(format, super::ReviewFormatArg::Plain));
+    }
+
     #[test]
     fn clean_parse_contract_accepts_scope_and_flags() {
         let cli = Cli::try_parse_from([
@@ -1185,4 +2526,701 @@ mod tests {
         assert!(cmd.keep_branches);
         assert!(cmd.force);
     }
+
+    #[test]
+    fn rescue_parse_contract_accepts_single_action() {
+        let cli = Cli::try_parse_from([
+            "vizier",
+            "rescue",
+            "alpha",
+            "--commit",
+            "-m",
+            "keep work",
+            "--format",
+            "json",
+        ])
+        .expect("parse rescue args");
+        let Commands::Rescue(cmd) = cli.command else {
+            panic!("expected rescue command");
+        };
+        assert_eq!(cmd.plan, "alpha");
+        assert!(cmd.commit);
+        assert_eq!(cmd.message.as_deref(), Some("keep work"));
+        assert!(matches!(cmd.format, super::RescueFormatArg::Json));
+
+        Cli::try_parse_from(["vizier", "rescue", "alpha", "--commit", "--discard"])
+            .expect_err("rescue actions should be mutually exclusive");
+        Cli::try_parse_from(["vizier", "rescue", "alpha", "--output", "out.patch"])
+            .expect_err("--output should require --patch");
+    }
+
+    #[test]
+    fn rebase_parse_contract_accepts_target_and_auto_resolve_toggle() {
+        let cli = Cli::try_parse_from([
+            "vizier",
+            "rebase",
+            "alpha",
+            "--target",
+            "trunk",
+            "--no-auto-resolve",
+            "--format",
+            "json",
+        ])
+        .expect("parse rebase args");
+        let Commands::Rebase(cmd) = cli.command else {
+            panic!("expected rebase command");
+        };
+        assert_eq!(cmd.plan, "alpha");
+        assert_eq!(cmd.target.as_deref(), Some("trunk"));
+        assert!(cmd.no_auto_resolve);
+        assert!(matches!(cmd.format, super::RebaseFormatArg::Json));
+
+        Cli::try_parse_from(["vizier", "rebase"]).expect_err("rebase should require a plan");
+    }
+
+    #[test]
+    fn pick_parse_contract_accepts_step_and_message() {
+        let cli = Cli::try_parse_from([
+            "vizier",
+            "pick",
+            "alpha",
+            "2",
+            "--target",
+            "trunk",
+            "-m",
+            "fix: hotfix",
+            "--format",
+            "json",
+        ])
+        .expect("parse pick args");
+        let Commands::Pick(cmd) = cli.command else {
+            panic!("expected pick command");
+        };
+        assert_eq!(cmd.plan, "alpha");
+        assert_eq!(cmd.pick, "2");
+        assert_eq!(cmd.target.as_deref(), Some("trunk"));
+        assert_eq!(cmd.message.as_deref(), Some("fix: hotfix"));
+        assert!(matches!(cmd.format, super::PickFormatArg::Json));
+
+        Cli::try_parse_from(["vizier", "pick", "alpha"])
+            .expect_err("pick should require a commit or step");
+    }
+
+    #[test]
+    fn verify_parse_contract_accepts_branch_and_format() {
+        let cli = Cli::try_parse_from([
+            "vizier",
+            "verify",
+            "alpha",
+            "--branch",
+            "feature/alpha",
+            "--format",
+            "json",
+        ])
+        .expect("parse verify args");
+        let Commands::Verify(cmd) = cli.command else {
+            panic!("expected verify command");
+        };
+        assert_eq!(cmd.plan, "alpha");
+        assert_eq!(cmd.branch.as_deref(), Some("feature/alpha"));
+        assert!(matches!(cmd.format, super::VerifyFormatArg::Json));
+
+        Cli::try_parse_from(["vizier", "verify"]).expect_err("verify should require a plan");
+    }
+
+    #[test]
+    fn ci_parse_contract_reads_review_and_merge_queue_flags() {
+        let cli = Cli::try_parse_from([
+            "vizier",
+            "ci",
+            "review",
+            "alpha",
+            "--fail-on",
+            "minor",
+            "--format",
+            "github",
+        ])
+        .expect("parse ci review args");
+        let Commands::Ci(cmd) = cli.command else {
+            panic!("expected ci command");
+        };
+        let super::CiAction::Review {
+            plan,
+            flow,
+            fail_on,
+            artifacts_dir,
+            format,
+        } = cmd.action
+        else {
+            panic!("expected ci review");
+        };
+        assert_eq!(plan, "alpha");
+        assert_eq!(flow, "review");
+        assert_eq!(fail_on, super::CiFailOnArg::Minor);
+        assert_eq!(artifacts_dir, std::path::PathBuf::from(".vizier/ci"));
+        assert!(matches!(format, super::CiFormatArg::Github));
+
+        let cli = Cli::try_parse_from([
+            "vizier",
+            "ci",
+            "merge-queue",
+            "--branch",
+            "gh-readonly-queue/main/pr-7",
+            "--target",
+            "main",
+        ])
+        .expect("parse ci merge-queue args");
+        let Commands::Ci(cmd) = cli.command else {
+            panic!("expected ci command");
+        };
+        let super::CiAction::MergeQueue { branch, target, .. } = cmd.action else {
+            panic!("expected ci merge-queue");
+        };
+        assert_eq!(branch.as_deref(), Some("gh-readonly-queue/main/pr-7"));
+        assert_eq!(target.as_deref(), Some("main"));
+
+        Cli::try_parse_from(["vizier", "ci", "review"]).expect_err("ci review needs a plan");
+    }
+
+    #[test]
+    fn ack_parse_contract_reads_owner() {
+        let cli = Cli::try_parse_from(["vizier", "ack", "alpha", "--as", "alice"])
+            .expect("parse ack args");
+        let Commands::Ack(cmd) = cli.command else {
+            panic!("expected ack command");
+        };
+        assert_eq!(cmd.plan, "alpha");
+        assert_eq!(cmd.owner.as_deref(), Some("alice"));
+        assert!(matches!(cmd.format, super::AckFormatArg::Text));
+    }
+
+    #[test]
+    fn watch_parses_timing_flags() {
+        let cli = Cli::try_parse_from(["vizier", "watch", "--settle", "10", "--record"])
+            .expect("watch should parse");
+        let Commands::Watch(cmd) = cli.command else {
+            panic!("expected watch command");
+        };
+        assert_eq!(cmd.settle, 10);
+        assert!(cmd.record);
+        assert!(!cmd.once);
+    }
+
+    #[test]
+    fn label_parses_action_and_labels() {
+        let cli = Cli::try_parse_from(["vizier", "label", "alpha", "add", "backend", "urgent"])
+            .expect("label should parse");
+        let Commands::Label(cmd) = cli.command else {
+            panic!("expected label command");
+        };
+        assert_eq!(cmd.plan, "alpha");
+        assert_eq!(cmd.action, Some(super::LabelActionArg::Add));
+        assert_eq!(cmd.labels, vec!["backend", "urgent"]);
+
+        let cli = Cli::try_parse_from(["vizier", "label", "alpha"]).expect("bare label parses");
+        let Commands::Label(cmd) = cli.command else {
+            panic!("expected label command");
+        };
+        assert!(cmd.action.is_none());
+        assert!(Cli::try_parse_from(["vizier", "label", "alpha", "remove"]).is_err());
+    }
+
+    #[test]
+    fn note_parses_plan_and_optional_text() {
+        let cli = Cli::try_parse_from(["vizier", "note", "alpha", "found a flaky test in ci"])
+            .expect("note should parse");
+        let Commands::Note(cmd) = cli.command else {
+            panic!("expected note command");
+        };
+        assert_eq!(cmd.plan, "alpha");
+        assert_eq!(cmd.note.as_deref(), Some("found a flaky test in ci"));
+
+        let cli = Cli::try_parse_from(["vizier", "note", "alpha"]).expect("bare note parses");
+        let Commands::Note(cmd) = cli.command else {
+            panic!("expected note command");
+        };
+        assert!(cmd.note.is_none());
+    }
+
+    #[test]
+    fn apply_patch_parse_contract_defaults_to_stdin() {
+        let cli = Cli::try_parse_from(["vizier", "apply-patch", "alpha", "-m", "fix: ci patch"])
+            .expect("parse apply-patch args");
+        let Commands::ApplyPatch(cmd) = cli.command else {
+            panic!("expected apply-patch command");
+        };
+        assert_eq!(cmd.plan, "alpha");
+        assert!(cmd.patch.is_none());
+        assert_eq!(cmd.message.as_deref(), Some("fix: ci patch"));
+
+        let cli = Cli::try_parse_from([
+            "vizier",
+            "apply-patch",
+            "alpha",
+            "ci.patch",
+            "--format",
+            "json",
+        ])
+        .expect("parse apply-patch file");
+        let Commands::ApplyPatch(cmd) = cli.command else {
+            panic!("expected apply-patch command");
+        };
+        assert_eq!(cmd.patch.as_deref(), Some("ci.patch"));
+        assert!(matches!(cmd.format, super::ApplyPatchFormatArg::Json));
+    }
+
+    #[test]
+    fn agent_validate_parse_contract() {
+        let cli = Cli::try_parse_from([
+            "vizier",
+            "agent",
+            "validate",
+            "--filter",
+            "./filter.sh",
+            "--timeout",
+            "30",
+            "./agent.sh",
+            "--model",
+            "fast",
+        ])
+        .expect("parse agent validate args");
+        let Commands::Agent(cmd) = cli.command else {
+            panic!("expected agent command");
+        };
+        let super::AgentAction::Validate {
+            filter,
+            timeout,
+            command,
+            events,
+            ..
+        } = cmd.action;
+        assert_eq!(filter.as_deref(), Some("./filter.sh"));
+        assert_eq!(timeout, 30);
+        assert_eq!(command, vec!["./agent.sh", "--model", "fast"]);
+        assert!(!events);
+
+        Cli::try_parse_from(["vizier", "agent", "validate"]).expect_err("a command is required");
+    }
+
+    #[test]
+    fn prompt_test_parse_contract() {
+        let cli = Cli::try_parse_from([
+            "vizier",
+            "prompt",
+            "test",
+            "implementation_plan",
+            "--scope",
+            "draft",
+            "--input",
+            "add a flag",
+            "--run",
+        ])
+        .expect("parse prompt test args");
+        let Commands::Prompt(cmd) = cli.command else {
+            panic!("expected prompt command");
+        };
+        let super::PromptAction::Test {
+            prompt,
+            scope,
+            input,
+            run,
+            ..
+        } = cmd.action;
+        assert!(matches!(prompt, super::PromptKindArg::ImplementationPlan));
+        assert_eq!(scope.as_deref(), Some("draft"));
+        assert_eq!(input.as_deref(), Some("add a flag"));
+        assert!(run);
+
+        Cli::try_parse_from(["vizier", "prompt", "test", "bogus"])
+            .expect_err("unknown prompt kinds are rejected");
+        Cli::try_parse_from([
+            "vizier",
+            "prompt",
+            "test",
+            "review",
+            "--input",
+            "x",
+            "--input-file",
+            "spec.md",
+        ])
+        .expect_err("--input and --input-file conflict");
+    }
+
+    #[test]
+    fn thread_parse_contract() {
+        let cli = Cli::try_parse_from(["vizier", "thread", "resolve", "auth", "--by", "alpha"])
+            .expect("parse thread resolve args");
+        let Commands::Thread(cmd) = cli.command else {
+            panic!("expected thread command");
+        };
+        let super::ThreadAction::Resolve { thread, by, .. } = cmd.action else {
+            panic!("expected thread resolve");
+        };
+        assert_eq!(thread, "auth");
+        assert_eq!(by, "alpha");
+
+        let cli = Cli::try_parse_from(["vizier", "thread", "list", "--state", "blocked"])
+            .expect("parse thread list args");
+        let Commands::Thread(cmd) = cli.command else {
+            panic!("expected thread command");
+        };
+        assert!(matches!(
+            cmd.action,
+            super::ThreadAction::List {
+                state: Some(super::ThreadStateArg::Blocked),
+                ..
+            }
+        ));
+
+        let cli = Cli::try_parse_from(["vizier", "thread", "revise", "--all"])
+            .expect("parse thread revise args");
+        let Commands::Thread(cmd) = cli.command else {
+            panic!("expected thread command");
+        };
+        assert!(matches!(
+            cmd.action,
+            super::ThreadAction::Revise { ref threads, all: true, .. } if threads.is_empty()
+        ));
+
+        Cli::try_parse_from(["vizier", "thread", "resolve", "auth"])
+            .expect_err("resolve requires --by");
+        Cli::try_parse_from(["vizier", "thread", "revise"])
+            .expect_err("revise requires threads or --all");
+        Cli::try_parse_from(["vizier", "thread", "revise", "auth", "--all"])
+            .expect_err("--all conflicts with named threads");
+        Cli::try_parse_from(["vizier", "thread", "list", "--state", "done"])
+            .expect_err("unknown states are rejected");
+    }
+
+    #[test]
+    fn tool_subcommands_parse_contract() {
+        let cli = Cli::try_parse_from(["vizier", "tool", "file", "src/lib.rs", "--lines", "10:20"])
+            .expect("parse tool file args");
+        let Commands::Tool(cmd) = cli.command else {
+            panic!("expected tool command");
+        };
+        assert!(matches!(
+            cmd.action,
+            super::ToolAction::File { ref path, lines: Some(ref lines) }
+                if path == "src/lib.rs" && lines == "10:20"
+        ));
+
+        let cli = Cli::try_parse_from(["vizier", "tool", "search", "fn main"])
+            .expect("parse tool search args");
+        let Commands::Tool(cmd) = cli.command else {
+            panic!("expected tool command");
+        };
+        assert!(matches!(
+            cmd.action,
+            super::ToolAction::Search { ref text, limit: 50 } if text == "fn main"
+        ));
+
+        Cli::try_parse_from(["vizier", "tool", "blame", "src/lib.rs"])
+            .expect_err("blame requires --lines");
+    }
+
+    #[test]
+    fn cache_clean_parse_contract() {
+        let cli = Cli::try_parse_from(["vizier", "cache", "clean", "--all", "--format", "json"])
+            .expect("parse cache clean args");
+        let Commands::Cache(cmd) = cli.command else {
+            panic!("expected cache command");
+        };
+        let super::CacheAction::Clean { all, format } = cmd.action else {
+            panic!("expected cache clean");
+        };
+        assert!(all);
+        assert!(matches!(format, super::CacheFormatArg::Json));
+
+        Cli::try_parse_from(["vizier", "cache", "status", "--all"])
+            .expect_err("--all belongs to cache clean");
+    }
+
+    #[test]
+    fn forge_issue_and_review_publish_parse_contract() {
+        let cli = Cli::try_parse_from(["vizier", "forge", "issue", "42", "-o", "issue.md"])
+            .expect("parse forge issue args");
+        let Commands::Forge(cmd) = cli.command else {
+            panic!("expected forge command");
+        };
+        let super::ForgeAction::Issue { id, output, format } = cmd.action else {
+            panic!("expected forge issue");
+        };
+        assert_eq!(id, "42");
+        assert_eq!(output, Some(std::path::PathBuf::from("issue.md")));
+        assert!(matches!(format, super::ForgeFormatArg::Text));
+
+        let cli = Cli::try_parse_from(["vizier", "jobs", "review", "job-1", "--publish", "17"])
+            .expect("parse jobs review --publish");
+        let Commands::Jobs(cmd) = cli.command else {
+            panic!("expected jobs command");
+        };
+        let super::JobsAction::Review { publish, .. } = cmd.action else {
+            panic!("expected jobs review");
+        };
+        assert_eq!(publish.as_deref(), Some("17"));
+    }
+
+    #[test]
+    fn config_import_ci_parse_contract() {
+        let cli = Cli::try_parse_from([
+            "vizier",
+            "config",
+            "import-ci",
+            "--write",
+            "--format",
+            "toml",
+        ])
+        .expect("parse config import-ci args");
+        let Commands::Config(cmd) = cli.command else {
+            panic!("expected config command");
+        };
+        let super::ConfigAction::ImportCi { write, format } = cmd.action;
+        assert!(write);
+        assert!(matches!(format, super::ConfigImportFormatArg::Toml));
+
+        Cli::try_parse_from(["vizier", "config"]).expect_err("a config action is required");
+    }
+
+    #[test]
+    fn changelog_assemble_parse_contract() {
+        let cli = Cli::try_parse_from([
+            "vizier",
+            "changelog",
+            "assemble",
+            "1.4.0",
+            "--date",
+            "2026-02-01",
+            "--no-commit",
+        ])
+        .expect("parse changelog assemble args");
+        let Commands::Changelog(cmd) = cli.command else {
+            panic!("expected changelog command");
+        };
+        let super::ChangelogAction::Assemble {
+            release,
+            date,
+            dry_run,
+            no_commit,
+            ..
+        } = cmd.action;
+        assert_eq!(release, "1.4.0");
+        assert_eq!(date.as_deref(), Some("2026-02-01"));
+        assert!(no_commit && !dry_run);
+
+        Cli::try_parse_from([
+            "vizier",
+            "changelog",
+            "assemble",
+            "--dry-run",
+            "--no-commit",
+        ])
+        .expect_err("--dry-run conflicts with --no-commit");
+    }
+
+    #[test]
+    fn sessions_gc_parse_contract() {
+        let cli = Cli::try_parse_from([
+            "vizier",
+            "sessions",
+            "gc",
+            "--since",
+            "14",
+            "--keep-last",
+            "5",
+            "--compress",
+            "--dry-run",
+        ])
+        .expect("parse sessions gc args");
+        let Commands::Sessions(cmd) = cli.command else {
+            panic!("expected sessions command");
+        };
+        let super::SessionsAction::Gc {
+            since,
+            keep_last,
+            compress,
+            dry_run,
+            ..
+        } = cmd.action
+        else {
+            panic!("expected sessions gc");
+        };
+        assert_eq!(since, Some(14));
+        assert_eq!(keep_last, Some(5));
+        assert!(compress && dry_run);
+
+        Cli::try_parse_from(["vizier", "sessions", "gc", "--compress", "--no-compress"])
+            .expect_err("--compress conflicts with --no-compress");
+    }
+
+    #[test]
+    fn stats_parse_contract_accepts_since_and_format() {
+        let cli = Cli::try_parse_from(["vizier", "stats", "--since", "7", "--format", "json"])
+            .expect("parse stats args");
+        let Commands::Stats(cmd) = cli.command else {
+            panic!("expected stats command");
+        };
+        assert_eq!(cmd.since, Some(7));
+        assert!(matches!(cmd.format, super::StatsFormatArg::Json));
+    }
+
+    #[test]
+    fn snapshot_show_parse_contract_reads_revisions() {
+        let cli = Cli::try_parse_from(["vizier", "snapshot", "show", "--at", "v1.2.0"])
+            .expect("parse snapshot show --at");
+        let Commands::Snapshot(cmd) = cli.command else {
+            panic!("expected snapshot command");
+        };
+        let super::SnapshotAction::Show { at, diff, .. } = cmd.action else {
+            panic!("expected snapshot show");
+        };
+        assert_eq!(at, "v1.2.0");
+        assert_eq!(diff, None);
+
+        let cli = Cli::try_parse_from(["vizier", "snapshot", "show", "--diff", "v1", "v2"])
+            .expect("parse snapshot show --diff");
+        let Commands::Snapshot(cmd) = cli.command else {
+            panic!("expected snapshot command");
+        };
+        let super::SnapshotAction::Show { diff, .. } = cmd.action else {
+            panic!("expected snapshot show");
+        };
+        assert_eq!(diff, Some(vec!["v1".to_string(), "v2".to_string()]));
+
+        Cli::try_parse_from([
+            "vizier", "snapshot", "show", "--at", "v1", "--diff", "v1", "v2",
+        ])
+        .expect_err("--at conflicts with --diff");
+    }
+
+    #[test]
+    fn snapshot_rebuild_parse_contract_requires_from_ledger() {
+        let cli = Cli::try_parse_from([
+            "vizier",
+            "snapshot",
+            "rebuild",
+            "--from-ledger",
+            "--dry-run",
+        ])
+        .expect("parse snapshot rebuild");
+        let Commands::Snapshot(cmd) = cli.command else {
+            panic!("expected snapshot command");
+        };
+        let super::SnapshotAction::Rebuild {
+            dry_run, sessions, ..
+        } = cmd.action
+        else {
+            panic!("expected snapshot rebuild");
+        };
+        assert!(dry_run);
+        assert_eq!(sessions, 20);
+
+        Cli::try_parse_from(["vizier", "snapshot", "rebuild"]).expect_err("rebuild needs a source");
+    }
+
+    #[test]
+    fn archive_parse_contract_joins_search_query() {
+        let cli = Cli::try_parse_from([
+            "vizier", "archive", "search", "token", "rotation", "--limit", "2",
+        ])
+        .expect("parse archive search");
+        let Commands::Archive(cmd) = cli.command else {
+            panic!("expected archive command");
+        };
+        let super::ArchiveAction::Search { query, limit, .. } = cmd.action else {
+            panic!("expected archive search");
+        };
+        assert_eq!(query, vec!["token", "rotation"]);
+        assert_eq!(limit, 2);
+    }
+
+    #[test]
+    fn doctor_parse_contract_accepts_format_and_no_remote() {
+        let cli = Cli::try_parse_from(["vizier", "doctor", "--format", "json", "--no-remote"])
+            .expect("parse doctor args");
+        let Commands::Doctor(cmd) = cli.command else {
+            panic!("expected doctor command");
+        };
+        assert!(cmd.no_remote);
+        assert!(matches!(cmd.format, super::DoctorFormatArg::Json));
+    }
+
+    #[test]
+    fn plan_parse_contract_accepts_diff_and_json() {
+        let cli =
+            Cli::try_parse_from(["vizier", "plan", "--diff", "--json"]).expect("parse plan args");
+        let Commands::Plan(cmd) = cli.command else {
+            panic!("expected plan command");
+        };
+        assert!(cmd.diff);
+        assert!(cmd.json);
+    }
+
+    #[test]
+    fn search_parse_contract_joins_query_words() {
+        let cli = Cli::try_parse_from([
+            "vizier", "search", "auth", "token", "rotation", "--limit", "3", "--format", "json",
+        ])
+        .expect("parse search args");
+        let Commands::Search(cmd) = cli.command else {
+            panic!("expected search command");
+        };
+        assert_eq!(cmd.query, vec!["auth", "token", "rotation"]);
+        assert_eq!(cmd.limit, 3);
+        assert!(matches!(cmd.format, super::SearchFormatArg::Json));
+
+        Cli::try_parse_from(["vizier", "search"]).expect_err("search should require a query");
+    }
+
+    #[test]
+    fn init_parse_contract_accepts_bootstrap_flags() {
+        let cli = Cli::try_parse_from(["vizier", "init", "--install-shims", "--no-smoke"])
+            .expect("parse init flags");
+        let Commands::Init(cmd) = cli.command else {
+            panic!("expected init command");
+        };
+        assert!(cmd.install_shims && cmd.no_smoke && !cmd.check);
+
+        Cli::try_parse_from(["vizier", "init", "--check", "--install-shims"])
+            .expect_err("--install-shims conflicts with --check");
+    }
+
+    #[test]
+    fn global_log_flag_parses_subsystem_filters() {
+        let cli = Cli::try_parse_from(["vizier", "--log", "agent=debug,git=warn", "list"])
+            .expect("parse --log");
+        let filters = cli.global.log.expect("filters");
+        assert_eq!(
+            filters.level_for(display::Subsystem::Agent),
+            Some(display::Verbosity::Debug)
+        );
+        assert_eq!(
+            filters.level_for(display::Subsystem::Git),
+            Some(display::Verbosity::Normal)
+        );
+        assert_eq!(filters.level_for(display::Subsystem::Gate), None);
+
+        assert!(Cli::try_parse_from(["vizier", "list", "--log", "network=debug"]).is_err());
+    }
+
+    #[test]
+    fn global_profile_flag_parses_before_and_after_subcommand() {
+        let cli = Cli::try_parse_from(["vizier", "--profile", "work", "list"])
+            .expect("parse leading --profile");
+        assert_eq!(cli.global.profile.as_deref(), Some("work"));
+
+        let cli = Cli::try_parse_from(["vizier", "jobs", "list", "--profile", "personal"])
+            .expect("parse trailing --profile");
+        assert_eq!(cli.global.profile.as_deref(), Some("personal"));
+    }
+
+    #[test]
+    fn global_worktree_dir_flag_parses_after_subcommand() {
+        let cli = Cli::try_parse_from(["vizier", "cd", "alpha", "--worktree-dir", "/var/tmp/wt"])
+            .expect("parse --worktree-dir");
+        assert_eq!(
+            cli.global.worktree_dir.as_deref(),
+            Some(std::path::Path::new("/var/tmp/wt"))
+        );
+    }
 }
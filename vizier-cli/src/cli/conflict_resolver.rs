@@ -0,0 +1,752 @@
+use std::fs;
+use std::io::{self, IsTerminal, Stderr};
+use std::path::Path;
+use std::process::Command;
+
+use ratatui::{
+    Frame, Terminal,
+    backend::CrosstermBackend,
+    crossterm::{
+        event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+        execute,
+        terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+    },
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, List, ListItem, ListState, Paragraph, Wrap},
+};
+use syntect::{
+    easy::HighlightLines,
+    highlighting::{Theme, ThemeSet},
+    parsing::SyntaxSet,
+    util::LinesWithEndings,
+};
+use vizier_core::{
+    conflict_markers::{ConflictHunk, ConflictedFile, HunkResolution},
+    temp_registry::TempRegistry,
+    vcs,
+};
+
+const OURS_COLOR: Color = Color::Green;
+const THEIRS_COLOR: Color = Color::Cyan;
+const BASE_COLOR: Color = Color::DarkGray;
+const HIGHLIGHT_THEME: &str = "base16-ocean.dark";
+const KEY_HELP: &str = "o ours  t theirs  b both  e edit  a accept file  u undo  ↑↓ hunk  ←→ file  PgUp/PgDn scroll  q stage & quit  ^C abort";
+
+#[derive(Debug, Default)]
+pub(crate) struct ResolverOutcome {
+    pub(crate) resolved: Vec<String>,
+    pub(crate) skipped: Vec<String>,
+    pub(crate) remaining: Vec<String>,
+}
+
+/// One unmerged path and the choices made for it so far.
+struct FileState {
+    path: String,
+    contents: String,
+    /// `Err` when the file cannot be read or its markers cannot be parsed; such files can only
+    /// be left for hand resolution.
+    parsed: Result<ConflictedFile, String>,
+    resolutions: Vec<Option<HunkResolution>>,
+    /// Delete/modify and binary conflicts carry no markers; the working copy is the answer.
+    accepted: bool,
+}
+
+impl FileState {
+    fn load(root: &Path, path: &str) -> Self {
+        let (contents, parsed) = match fs::read_to_string(root.join(path)) {
+            Ok(contents) => {
+                let parsed = ConflictedFile::parse(&contents);
+                (contents, parsed)
+            }
+            Err(err) => (
+                String::new(),
+                Err(format!(
+                    "cannot be shown ({err}); resolve it by hand and stage it"
+                )),
+            ),
+        };
+        Self::new(path, contents, parsed)
+    }
+
+    fn new(path: &str, contents: String, parsed: Result<ConflictedFile, String>) -> Self {
+        let hunks = parsed.as_ref().map_or(0, |file| file.hunks().count());
+        Self {
+            path: path.to_string(),
+            contents,
+            parsed,
+            resolutions: vec![None; hunks],
+            accepted: false,
+        }
+    }
+
+    fn hunk(&self, index: usize) -> Option<&ConflictHunk> {
+        self.parsed.as_ref().ok()?.hunks().nth(index)
+    }
+
+    fn settled_count(&self) -> usize {
+        self.resolutions
+            .iter()
+            .filter(|choice| choice.is_some())
+            .count()
+    }
+
+    fn settled(&self) -> bool {
+        match &self.parsed {
+            Ok(_) if self.resolutions.is_empty() => self.accepted,
+            Ok(_) => self.resolutions.iter().all(Option::is_some),
+            Err(_) => false,
+        }
+    }
+
+    /// The file's new contents once every hunk has a resolution.
+    fn resolved_contents(&self) -> Option<Result<String, String>> {
+        if !self.settled() {
+            return None;
+        }
+        let file = self.parsed.as_ref().ok()?;
+        if self.resolutions.is_empty() {
+            return Some(Ok(self.contents.clone()));
+        }
+        let resolutions = self
+            .resolutions
+            .iter()
+            .flatten()
+            .cloned()
+            .collect::<Vec<_>>();
+        Some(file.resolve(&resolutions))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Step {
+    Continue,
+    Edit,
+    Finish,
+    Abort,
+}
+
+/// Selection and choices for the resolver screen, kept apart from the terminal so key handling
+/// and rendering can be exercised directly.
+struct Resolver {
+    files: Vec<FileState>,
+    file: usize,
+    hunk: usize,
+    scroll: u16,
+    message: Option<String>,
+}
+
+impl Resolver {
+    fn new(files: Vec<FileState>) -> Self {
+        Self {
+            files,
+            file: 0,
+            hunk: 0,
+            scroll: 0,
+            message: None,
+        }
+    }
+
+    fn current(&self) -> Option<&FileState> {
+        self.files.get(self.file)
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Step {
+        self.message = None;
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return Step::Abort;
+        }
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Step::Finish,
+            KeyCode::Up | KeyCode::Char('k') => self.select_hunk(self.hunk.saturating_sub(1)),
+            KeyCode::Down | KeyCode::Char('j') => self.select_hunk(self.hunk + 1),
+            KeyCode::Left | KeyCode::Char('h') | KeyCode::BackTab => {
+                let count = self.files.len().max(1);
+                self.select_file((self.file + count - 1) % count);
+            }
+            KeyCode::Right | KeyCode::Char('l') | KeyCode::Tab => {
+                self.select_file((self.file + 1) % self.files.len().max(1));
+            }
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_add(10),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(10),
+            KeyCode::Char('o') => self.choose(HunkResolution::Ours),
+            KeyCode::Char('t') => self.choose(HunkResolution::Theirs),
+            KeyCode::Char('b') => self.choose(HunkResolution::Both),
+            KeyCode::Char('e') => {
+                if self
+                    .current()
+                    .and_then(|file| file.hunk(self.hunk))
+                    .is_some()
+                {
+                    return Step::Edit;
+                }
+                self.message = Some("nothing to edit in this file".to_string());
+            }
+            KeyCode::Char('a') => self.accept_working_copy(),
+            KeyCode::Char('u') => {
+                if let Some(file) = self.files.get_mut(self.file) {
+                    file.accepted = false;
+                    if let Some(choice) = file.resolutions.get_mut(self.hunk) {
+                        *choice = None;
+                    }
+                }
+            }
+            _ => {}
+        }
+        Step::Continue
+    }
+
+    fn select_file(&mut self, index: usize) {
+        self.file = index;
+        self.hunk = 0;
+        self.scroll = 0;
+    }
+
+    fn select_hunk(&mut self, index: usize) {
+        let count = self.current().map_or(0, |file| file.resolutions.len());
+        if index < count {
+            self.hunk = index;
+            self.scroll = 0;
+        }
+    }
+
+    fn choose(&mut self, resolution: HunkResolution) {
+        let Some(choice) = self
+            .files
+            .get_mut(self.file)
+            .and_then(|file| file.resolutions.get_mut(self.hunk))
+        else {
+            self.message = Some("this file has no conflict hunks; press a to accept it".into());
+            return;
+        };
+        *choice = Some(resolution);
+        self.advance();
+    }
+
+    /// Takes hand-edited text for the selected hunk; text that still has markers is refused.
+    fn apply_edit(&mut self, edited: String) {
+        if edited.lines().any(|line| {
+            ["<<<<<<<", "=======", ">>>>>>>"]
+                .iter()
+                .any(|marker| line.starts_with(marker))
+        }) {
+            self.message = Some("edited hunk still has conflict markers; try again".to_string());
+            return;
+        }
+        self.choose(HunkResolution::Edited(edited));
+    }
+
+    fn accept_working_copy(&mut self) {
+        match self.files.get_mut(self.file) {
+            Some(file) if file.parsed.is_ok() && file.resolutions.is_empty() => {
+                file.accepted = true;
+                self.advance();
+            }
+            Some(_) => {
+                self.message = Some("only files without conflict markers can be accepted".into())
+            }
+            None => {}
+        }
+    }
+
+    // Moves to the next unsettled hunk in this file, then to the next unsettled file.
+    fn advance(&mut self) {
+        if let Some(next) = self.current().and_then(|file| {
+            (self.hunk + 1..file.resolutions.len()).find(|index| file.resolutions[*index].is_none())
+        }) {
+            self.select_hunk(next);
+            return;
+        }
+        let count = self.files.len();
+        let next_file = (1..=count)
+            .map(|offset| (self.file + offset) % count)
+            .find(|index| !self.files[*index].settled() && self.files[*index].parsed.is_ok());
+        match next_file {
+            Some(index) => {
+                self.select_file(index);
+                if let Some(first) = self.files[index]
+                    .resolutions
+                    .iter()
+                    .position(Option::is_none)
+                {
+                    self.hunk = first;
+                }
+            }
+            None => self.message = Some("every conflict has a resolution; press q to stage".into()),
+        }
+    }
+
+    fn render(&self, frame: &mut Frame, highlighter: &Highlighter) {
+        let [body, status, help] = Layout::vertical([
+            Constraint::Min(3),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [list_area, sides_area] =
+            Layout::horizontal([Constraint::Percentage(28), Constraint::Min(20)]).areas(body);
+
+        let items = self
+            .files
+            .iter()
+            .map(|file| {
+                let (mark, color) = match (&file.parsed, file.settled()) {
+                    (Err(_), _) => ("!", Color::Red),
+                    (Ok(_), true) => ("✓", Color::Green),
+                    (Ok(_), false) => ("•", Color::Yellow),
+                };
+                let progress = if file.resolutions.is_empty() {
+                    String::new()
+                } else {
+                    format!(" {}/{}", file.settled_count(), file.resolutions.len())
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(format!("{mark} "), Style::default().fg(color)),
+                    Span::raw(file.path.clone()),
+                    Span::styled(progress, Style::default().fg(Color::DarkGray)),
+                ]))
+            })
+            .collect::<Vec<_>>();
+        let mut list_state = ListState::default().with_selected(Some(self.file));
+        frame.render_stateful_widget(
+            List::new(items)
+                .block(Block::bordered().title(" Conflicted files "))
+                .highlight_style(Style::default().add_modifier(Modifier::REVERSED)),
+            list_area,
+            &mut list_state,
+        );
+
+        self.render_sides(frame, sides_area, highlighter);
+
+        let status_line = match (&self.message, self.current()) {
+            (Some(message), _) => Line::styled(message.clone(), Style::default().fg(Color::Yellow)),
+            (None, Some(file)) => Line::raw(self.status_text(file)),
+            (None, None) => Line::raw("no conflicted files"),
+        };
+        frame.render_widget(Paragraph::new(status_line), status);
+        frame.render_widget(
+            Paragraph::new(Line::styled(KEY_HELP, Style::default().fg(Color::DarkGray))),
+            help,
+        );
+    }
+
+    fn status_text(&self, file: &FileState) -> String {
+        if file.resolutions.is_empty() {
+            return format!(
+                "{}: {}",
+                file.path,
+                if file.accepted {
+                    "working copy accepted"
+                } else {
+                    "no conflict markers"
+                }
+            );
+        }
+        let choice = match file.resolutions.get(self.hunk).cloned().flatten() {
+            Some(HunkResolution::Ours) => "ours".to_string(),
+            Some(HunkResolution::Theirs) => "theirs".to_string(),
+            Some(HunkResolution::Both) => "both (ours then theirs)".to_string(),
+            Some(HunkResolution::Edited(text)) => {
+                format!("edited ({} lines)", text.lines().count())
+            }
+            None => "unresolved".to_string(),
+        };
+        format!(
+            "{}: hunk {}/{} · {choice}",
+            file.path,
+            self.hunk + 1,
+            file.resolutions.len()
+        )
+    }
+
+    fn render_sides(&self, frame: &mut Frame, area: Rect, highlighter: &Highlighter) {
+        let Some(file) = self.current() else {
+            return;
+        };
+        let hunk = match (&file.parsed, file.hunk(self.hunk)) {
+            (Err(err), _) => {
+                frame.render_widget(
+                    Paragraph::new(err.clone())
+                        .wrap(Wrap { trim: false })
+                        .block(Block::bordered().title(format!(" {} ", file.path))),
+                    area,
+                );
+                return;
+            }
+            (Ok(_), None) => {
+                frame.render_widget(
+                    Paragraph::new(highlighter.lines(&file.path, &file.contents))
+                        .scroll((self.scroll, 0))
+                        .block(Block::bordered().title(format!(
+                            " {} has no conflict markers; a accepts the working copy ",
+                            file.path
+                        ))),
+                    area,
+                );
+                return;
+            }
+            (Ok(_), Some(hunk)) => hunk,
+        };
+
+        let choice = file.resolutions.get(self.hunk).cloned().flatten();
+        let mut panes = vec![(
+            format!(" ours ({}) ", hunk.ours_label),
+            &hunk.ours,
+            OURS_COLOR,
+            matches!(choice, Some(HunkResolution::Ours | HunkResolution::Both)),
+        )];
+        if let Some(base) = hunk.base.as_ref() {
+            panes.push((" base ".to_string(), base, BASE_COLOR, false));
+        }
+        panes.push((
+            format!(" theirs ({}) ", hunk.theirs_label),
+            &hunk.theirs,
+            THEIRS_COLOR,
+            matches!(choice, Some(HunkResolution::Theirs | HunkResolution::Both)),
+        ));
+
+        let areas = Layout::vertical(vec![Constraint::Fill(1); panes.len()]).split(area);
+        for ((title, text, color, chosen), pane) in panes.into_iter().zip(areas.iter()) {
+            let mut border = Style::default().fg(color);
+            if chosen {
+                border = border.add_modifier(Modifier::BOLD);
+            }
+            let title = if chosen {
+                format!("{title}✓ ")
+            } else {
+                title
+            };
+            frame.render_widget(
+                Paragraph::new(highlighter.lines(&file.path, text))
+                    .scroll((self.scroll, 0))
+                    .block(Block::bordered().title(title).border_style(border)),
+                *pane,
+            );
+        }
+    }
+}
+
+/// Syntax highlighting for hunk sides, chosen by the conflicted file's extension.
+struct Highlighter {
+    syntaxes: SyntaxSet,
+    theme: Theme,
+}
+
+impl Highlighter {
+    fn new() -> Self {
+        let mut themes = ThemeSet::load_defaults();
+        Self {
+            syntaxes: SyntaxSet::load_defaults_newlines(),
+            theme: themes.themes.remove(HIGHLIGHT_THEME).unwrap_or_default(),
+        }
+    }
+
+    fn lines(&self, path: &str, text: &str) -> Vec<Line<'static>> {
+        let syntax = Path::new(path)
+            .extension()
+            .and_then(|ext| {
+                self.syntaxes
+                    .find_syntax_by_extension(&ext.to_string_lossy())
+            })
+            .unwrap_or_else(|| self.syntaxes.find_syntax_plain_text());
+        let mut highlighter = HighlightLines::new(syntax, &self.theme);
+        LinesWithEndings::from(text)
+            .map(
+                |line| match highlighter.highlight_line(line, &self.syntaxes) {
+                    Ok(ranges) => Line::from(
+                        ranges
+                            .into_iter()
+                            .map(|(style, piece)| {
+                                let fg = style.foreground;
+                                Span::styled(
+                                    piece.trim_end_matches(['\n', '\r']).to_string(),
+                                    Style::default().fg(Color::Rgb(fg.r, fg.g, fg.b)),
+                                )
+                            })
+                            .collect::<Vec<_>>(),
+                    ),
+                    Err(_) => Line::raw(line.trim_end_matches(['\n', '\r']).to_string()),
+                },
+            )
+            .collect()
+    }
+}
+
+type ResolverTerminal = Terminal<CrosstermBackend<Stderr>>;
+
+// Restores the operator's terminal even when the resolver errors or panics.
+struct TerminalGuard {
+    terminal: ResolverTerminal,
+}
+
+impl TerminalGuard {
+    fn enter() -> io::Result<Self> {
+        enable_raw_mode()?;
+        let mut stderr = io::stderr();
+        if let Err(err) = execute!(stderr, EnterAlternateScreen) {
+            let _ = disable_raw_mode();
+            return Err(err);
+        }
+        let terminal = Terminal::new(CrosstermBackend::new(stderr))?;
+        Ok(Self { terminal })
+    }
+
+    fn suspend(&mut self) -> io::Result<()> {
+        disable_raw_mode()?;
+        execute!(self.terminal.backend_mut(), LeaveAlternateScreen)?;
+        self.terminal.show_cursor()
+    }
+
+    fn resume(&mut self) -> io::Result<()> {
+        enable_raw_mode()?;
+        execute!(self.terminal.backend_mut(), EnterAlternateScreen)?;
+        self.terminal.clear()
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = disable_raw_mode();
+        let _ = execute!(self.terminal.backend_mut(), LeaveAlternateScreen);
+        let _ = self.terminal.show_cursor();
+    }
+}
+
+/// Opens a full-screen resolver over every unmerged path under `root`: the conflicted files
+/// on the left, the selected hunk's ours/theirs (and base) sides syntax-highlighted on the
+/// right. Quitting writes and stages each file whose hunks all have a resolution; the rest,
+/// and everything after an abort, stay unmerged.
+pub(crate) fn resolve_conflicts_interactively(
+    project_root: &Path,
+    root: &Path,
+) -> Result<ResolverOutcome, Box<dyn std::error::Error>> {
+    if !io::stdin().is_terminal() || !io::stderr().is_terminal() {
+        return Err("interactive conflict resolution requires a TTY".into());
+    }
+    let conflicts = vcs::list_conflicted_paths_in(root)?;
+    let mut resolver = Resolver::new(
+        conflicts
+            .iter()
+            .map(|path| FileState::load(root, path))
+            .collect(),
+    );
+    let highlighter = Highlighter::new();
+
+    let step = {
+        let mut guard = TerminalGuard::enter()?;
+        run_resolver(&mut guard, &mut resolver, &highlighter, project_root)?
+    };
+
+    let mut outcome = ResolverOutcome::default();
+    for file in &resolver.files {
+        let contents = match step {
+            Step::Finish => file.resolved_contents(),
+            _ => None,
+        };
+        match contents {
+            Some(contents) => {
+                let contents = contents.map_err(|err| format!("{}: {err}", file.path))?;
+                fs::write(root.join(&file.path), contents)?;
+                vcs::stage_paths_allow_missing_in(root, &[file.path.as_str()])?;
+                outcome.resolved.push(file.path.clone());
+            }
+            None => outcome.skipped.push(file.path.clone()),
+        }
+    }
+    outcome.remaining = vcs::list_conflicted_paths_in(root)?;
+    Ok(outcome)
+}
+
+fn run_resolver(
+    guard: &mut TerminalGuard,
+    resolver: &mut Resolver,
+    highlighter: &Highlighter,
+    project_root: &Path,
+) -> Result<Step, Box<dyn std::error::Error>> {
+    loop {
+        guard
+            .terminal
+            .draw(|frame| resolver.render(frame, highlighter))?;
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match resolver.handle_key(key) {
+            Step::Continue => {}
+            Step::Edit => {
+                let Some((path, hunk)) = resolver.current().and_then(|file| {
+                    file.hunk(resolver.hunk)
+                        .map(|hunk| (file.path.clone(), hunk.clone()))
+                }) else {
+                    continue;
+                };
+                guard.suspend()?;
+                let edited = edit_hunk(project_root, &path, &hunk);
+                guard.resume()?;
+                match edited {
+                    Ok(edited) => resolver.apply_edit(edited),
+                    Err(err) => resolver.message = Some(format!("edit failed: {err}")),
+                }
+            }
+            step => return Ok(step),
+        }
+    }
+}
+
+// Opens the hunk (markers included) in $VISUAL/$EDITOR; the temp file keeps the conflicted
+// file's extension so the editor can highlight it.
+fn edit_hunk(
+    project_root: &Path,
+    path: &str,
+    hunk: &ConflictHunk,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let suffix = Path::new(path)
+        .extension()
+        .map(|ext| format!(".{}", ext.to_string_lossy()))
+        .unwrap_or_default();
+    let scratch = TempRegistry::new(project_root).scratch("hunk")?;
+    let temp = scratch.path().join(format!("hunk{suffix}"));
+    fs::write(&temp, hunk.render_markers())?;
+    edit_file(&temp)
+}
+
+/// Opens `path` in $VISUAL/$EDITOR (falling back to `vi`) and returns its contents once the
+/// editor exits.
+pub(crate) fn edit_file(path: &Path) -> Result<String, Box<dyn std::error::Error>> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    let status = Command::new("sh")
+        .arg("-c")
+        .arg(format!("{editor} \"$1\""))
+        .arg("vizier-edit")
+        .arg(path)
+        .status()?;
+    if !status.success() {
+        return Err(format!("editor `{editor}` exited with {status}").into());
+    }
+    Ok(fs::read_to_string(path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+
+    const CONFLICTED: &str = "keep\n<<<<<<< HEAD\nmain side\n=======\nplan side\n>>>>>>> draft/alpha\ntail\n<<<<<<< HEAD\nsecond main\n=======\nsecond plan\n>>>>>>> draft/alpha\n";
+
+    fn resolver() -> Resolver {
+        Resolver::new(vec![
+            FileState::new(
+                "a.rs",
+                CONFLICTED.to_string(),
+                ConflictedFile::parse(CONFLICTED),
+            ),
+            FileState::new(
+                "gone.txt",
+                "kept by hand\n".to_string(),
+                ConflictedFile::parse("kept by hand\n"),
+            ),
+        ])
+    }
+
+    fn press(resolver: &mut Resolver, keys: &str) -> Step {
+        let mut step = Step::Continue;
+        for ch in keys.chars() {
+            step = resolver.handle_key(KeyEvent::new(KeyCode::Char(ch), KeyModifiers::NONE));
+        }
+        step
+    }
+
+    fn screen(resolver: &Resolver) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(120, 24)).expect("terminal");
+        let highlighter = Highlighter::new();
+        terminal
+            .draw(|frame| resolver.render(frame, &highlighter))
+            .expect("draw");
+        let buffer = terminal.backend().buffer();
+        buffer
+            .content()
+            .chunks(buffer.area.width as usize)
+            .map(|row| row.iter().map(|cell| cell.symbol()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn choices_advance_through_hunks_and_files() {
+        let mut resolver = resolver();
+        assert_eq!(press(&mut resolver, "xt"), Step::Continue);
+        assert_eq!(resolver.hunk, 1);
+        press(&mut resolver, "b");
+        assert_eq!(resolver.file, 1, "a settled file moves on to the next one");
+        assert_eq!(
+            resolver.files[0].resolved_contents(),
+            Some(Ok(
+                "keep\nplan side\ntail\nsecond main\nsecond plan\n".to_string()
+            ))
+        );
+
+        press(&mut resolver, "o");
+        assert!(
+            !resolver.files[1].settled(),
+            "markerless files need an accept"
+        );
+        press(&mut resolver, "a");
+        assert_eq!(
+            resolver.files[1].resolved_contents(),
+            Some(Ok("kept by hand\n".to_string()))
+        );
+        assert_eq!(press(&mut resolver, "q"), Step::Finish);
+    }
+
+    #[test]
+    fn edits_with_markers_are_refused_and_undo_clears_a_choice() {
+        let mut resolver = resolver();
+        assert_eq!(press(&mut resolver, "e"), Step::Edit);
+        let hunk = resolver.files[0].hunk(0).expect("hunk").clone();
+        resolver.apply_edit(hunk.render_markers());
+        assert!(resolver.files[0].resolutions[0].is_none());
+        assert!(
+            resolver
+                .message
+                .as_deref()
+                .unwrap_or_default()
+                .contains("markers")
+        );
+
+        resolver.apply_edit("merged\n".to_string());
+        press(&mut resolver, "k");
+        assert_eq!(
+            resolver.files[0].resolutions[0],
+            Some(HunkResolution::Edited("merged\n".to_string()))
+        );
+        press(&mut resolver, "u");
+        assert!(resolver.files[0].resolutions[0].is_none());
+        assert_eq!(
+            resolver.handle_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)),
+            Step::Abort
+        );
+    }
+
+    #[test]
+    fn render_lists_files_and_shows_both_sides() {
+        let mut resolver = resolver();
+        let first = screen(&resolver);
+        assert!(first.contains("Conflicted files"), "{first}");
+        assert!(first.contains("a.rs 0/2"), "{first}");
+        assert!(first.contains("ours (HEAD)"), "{first}");
+        assert!(first.contains("main side"), "{first}");
+        assert!(first.contains("theirs (draft/alpha)"), "{first}");
+        assert!(first.contains("plan side"), "{first}");
+        assert!(first.contains("hunk 1/2 · unresolved"), "{first}");
+
+        press(&mut resolver, "t");
+        let second = screen(&resolver);
+        assert!(second.contains("a.rs 1/2"), "{second}");
+        assert!(second.contains("second main"), "{second}");
+    }
+}
@@ -5,10 +5,17 @@ use clap::{ColorChoice, CommandFactory, FromArgMatches, error::ErrorKind};
 use vizier_core::{
     auditor, config,
     display::{self, LogLevel},
+    interrupt, migrations,
+    temp_registry::TempRegistry,
+    vcs,
 };
 
 use crate::actions::{
-    run_cd, run_clean, run_init, run_list, run_release, run_workflow, run_workflow_audit,
+    run_ack, run_agent_validate, run_apply_patch, run_archive, run_cache, run_cd, run_changelog,
+    run_ci, run_clean, run_config_import_ci, run_config_plan, run_doctor, run_forge, run_init,
+    run_label, run_list, run_note, run_pick, run_prompt_test, run_rebase, run_release, run_rescue,
+    run_search, run_sessions, run_snapshot, run_stats, run_thread, run_tool, run_verify, run_watch,
+    run_workflow, run_workflow_audit,
 };
 use crate::cli::args::*;
 use crate::cli::help::{
@@ -17,7 +24,19 @@ use crate::cli::help::{
     strip_ansi_codes, subcommand_from_raw_args,
 };
 use crate::cli::jobs_view::run_jobs_command;
-use crate::cli::resolve::{resolve_cd_options, resolve_clean_options, resolve_list_options};
+use crate::cli::outcome;
+use crate::cli::porcelain;
+use crate::cli::resolve::{
+    resolve_ack_options, resolve_agent_validate_options, resolve_apply_patch_options,
+    resolve_archive_options, resolve_cache_options, resolve_cd_options, resolve_changelog_options,
+    resolve_ci_options, resolve_clean_options, resolve_config_import_ci_options,
+    resolve_doctor_options, resolve_forge_options, resolve_init_options, resolve_label_options,
+    resolve_list_options, resolve_note_options, resolve_pick_options, resolve_plan_options,
+    resolve_prompt_test_options, resolve_rebase_options, resolve_rescue_options,
+    resolve_search_options, resolve_sessions_options, resolve_snapshot_options,
+    resolve_stats_options, resolve_thread_options, resolve_tool_options, resolve_verify_options,
+    resolve_watch_options,
+};
 use crate::cli::util::{
     flag_present, global_option_value, normalize_run_invocation_args, run_flow_help_target,
 };
@@ -53,8 +72,12 @@ pub(crate) async fn run() -> Result<(), Box<dyn std::error::Error>> {
                     let project_root = resolve_project_root()?;
                     let explicit_config_file =
                         global_option_value(&normalized_args, Some('C'), "--config-file");
-                    let cfg =
-                        load_effective_config(&project_root, explicit_config_file.as_deref())?;
+                    let profile = global_option_value(&normalized_args, None, "--profile");
+                    let cfg = load_effective_config(
+                        &project_root,
+                        explicit_config_file.as_deref(),
+                        profile.as_deref(),
+                    )?;
                     let rendered = render_run_workflow_help_text(&project_root, &flow, &cfg)?;
                     let help_text = if color_choice != ColorChoice::Never {
                         rendered
@@ -113,10 +136,13 @@ pub(crate) async fn run() -> Result<(), Box<dyn std::error::Error>> {
         verbosity = display::Verbosity::Debug;
     }
 
+    let cli_log_filters = cli.global.log.unwrap_or_default();
     display::set_display_config(display::DisplayConfig {
         verbosity,
+        log_filters: cli_log_filters,
         stdout_is_tty,
         stderr_is_tty,
+        ansi: !no_ansi_requested,
     });
 
     if let Commands::Help(cmd) = &cli.command {
@@ -148,7 +174,39 @@ pub(crate) async fn run() -> Result<(), Box<dyn std::error::Error>> {
     }
 
     let project_root = resolve_project_root()?;
-    let mut cfg = load_effective_config(&project_root, cli.global.config_file.as_deref())?;
+    if vcs::is_bare_repo(&project_root) && needs_worktree(&cli.command) {
+        let command = matches.subcommand_name().unwrap_or("this command");
+        display::emit(
+            LogLevel::Error,
+            format!(
+                "vizier {command} needs a working tree, but {} is a bare repository",
+                project_root.display()
+            ),
+        );
+        return Err("bare repository".into());
+    }
+    if cli.global.porcelain.is_some() && !porcelain::supports(&cli.command) {
+        let command = matches.subcommand_name().unwrap_or("this command");
+        return Err(format!(
+            "vizier {command} has no --porcelain output; use its --format json where available"
+        )
+        .into());
+    }
+    // Layout migrations run before config loading since they may move config-adjacent files;
+    // `init --check` must stay non-mutating.
+    if !matches!(&cli.command, Commands::Init(cmd) if cmd.check) {
+        run_layout_migrations(&project_root)?;
+    }
+    // `doctor` reports broken config layers itself, so it falls back to the defaults instead of
+    // failing before any check runs.
+    let mut cfg = match load_effective_config(
+        &project_root,
+        cli.global.config_file.as_deref(),
+        cli.global.profile.as_deref(),
+    ) {
+        Err(_) if matches!(cli.command, Commands::Doctor(_)) => config::Config::default(),
+        loaded => loaded?,
+    };
 
     if let Some(session_id) = &cli.global.load_session {
         let repo_session = project_root
@@ -167,20 +225,111 @@ pub(crate) async fn run() -> Result<(), Box<dyn std::error::Error>> {
     }
 
     cfg.no_session = cli.global.no_session;
+    if let Some(dir) = cli.global.worktree_dir.as_ref() {
+        cfg.worktrees.dir = (!dir.as_os_str().is_empty()).then(|| dir.clone());
+    }
+    if let Some(config_filters) = cfg
+        .display
+        .log
+        .as_deref()
+        .and_then(|spec| display::LogFilters::parse(spec).ok())
+    {
+        let mut display_config = display::get_display_config();
+        display_config.log_filters = cli_log_filters.or(config_filters);
+        display::set_display_config(display_config);
+    }
+    let tmp_keep_days = cfg.tmp.keep_days;
     config::set_config(cfg);
+    if !matches!(&cli.command, Commands::Init(cmd) if cmd.check) {
+        collect_orphaned_tmp(&project_root, tmp_keep_days);
+    }
 
-    match cli.command {
+    // Workflow node jobs already write `outcome.json` into their job directory, and
+    // `init --check` must stay non-mutating.
+    let records_outcome = match &cli.command {
+        Commands::WorkflowNode(_) | Commands::NotificationsDrain => false,
+        Commands::Init(cmd) => !cmd.check,
+        _ => true,
+    };
+    if cli.global.porcelain.is_some() {
+        porcelain::begin(project_root.clone());
+    }
+    if records_outcome {
+        outcome::begin(project_root.clone(), &Cli::command(), &matches, raw_args);
+    }
+
+    // Commands that drive an agent or half-apply git state trap SIGINT/SIGTERM so they can stop
+    // the agent's process group and clean up before exiting; everything else keeps the default
+    // handlers so Ctrl-C stays immediate.
+    if matches!(
+        cli.command,
+        Commands::Rebase(_)
+            | Commands::Pick(_)
+            | Commands::Verify(_)
+            | Commands::Ci(_)
+            | Commands::ApplyPatch(_)
+            | Commands::WorkflowNode(_)
+            | Commands::Watch(_)
+    ) {
+        interrupt::install();
+    }
+
+    let result = match cli.command {
         Commands::Help(_) => Ok(()),
         Commands::Completions(_) | Commands::Complete(_) => Ok(()),
-        Commands::Init(cmd) => run_init(&project_root, cmd.check),
+        Commands::Init(cmd) => run_init(&project_root, resolve_init_options(&cmd)),
         Commands::List(cmd) => run_list(resolve_list_options(&cmd)?),
         Commands::Cd(cmd) => run_cd(resolve_cd_options(&cmd)?),
         Commands::Clean(cmd) => run_clean(&project_root, resolve_clean_options(&cmd)?),
+        Commands::Rebase(cmd) => run_rebase(&project_root, resolve_rebase_options(&cmd)?),
+        Commands::Pick(cmd) => run_pick(&project_root, resolve_pick_options(&cmd)?).await,
+        Commands::Verify(cmd) => run_verify(&project_root, resolve_verify_options(&cmd)?),
+        Commands::Ci(cmd) => run_ci(
+            &project_root,
+            resolve_ci_options(
+                &cmd,
+                cli.global.config_file.as_deref(),
+                cli.global.profile.as_deref(),
+            )?,
+        ),
+        Commands::Ack(cmd) => run_ack(&project_root, resolve_ack_options(&cmd)?),
+        Commands::Thread(cmd) => run_thread(&project_root, resolve_thread_options(&cmd)?).await,
+        Commands::Label(cmd) => run_label(&project_root, resolve_label_options(&cmd)?),
+        Commands::Note(cmd) => run_note(&project_root, resolve_note_options(&cmd)?),
+        Commands::ApplyPatch(cmd) => {
+            run_apply_patch(&project_root, resolve_apply_patch_options(&cmd)?)
+        }
+        Commands::Rescue(cmd) => run_rescue(&project_root, resolve_rescue_options(&cmd)?),
+        Commands::Search(cmd) => run_search(&project_root, resolve_search_options(&cmd)?),
+        Commands::Snapshot(cmd) => run_snapshot(&project_root, resolve_snapshot_options(&cmd)),
+        Commands::Sessions(cmd) => run_sessions(&project_root, resolve_sessions_options(&cmd)),
+        Commands::Archive(cmd) => run_archive(&project_root, resolve_archive_options(&cmd)?),
+        Commands::Watch(cmd) => run_watch(&project_root, resolve_watch_options(&cmd)).await,
+        Commands::Doctor(cmd) => run_doctor(
+            &project_root,
+            resolve_doctor_options(&cmd, cli.global.config_file.as_deref()),
+        ),
+        Commands::Plan(cmd) => run_config_plan(
+            &project_root,
+            resolve_plan_options(
+                &cmd,
+                cli.global.config_file.as_deref(),
+                cli.global.profile.as_deref(),
+                cli.global.no_session,
+            ),
+        ),
+        Commands::Stats(cmd) => {
+            let jobs_root = jobs::ensure_jobs_root(&project_root)?;
+            run_stats(&project_root, &jobs_root, resolve_stats_options(&cmd))
+        }
         Commands::Jobs(cmd) => {
             let jobs_root = jobs::ensure_jobs_root(&project_root)?;
             run_jobs_command(&project_root, &jobs_root, cmd, cli.global.no_ansi)
         }
-        Commands::Run(cmd) => {
+        Commands::Run(mut cmd) => {
+            if porcelain::enabled() {
+                cmd.format = RunFormatArg::Porcelain;
+            }
             let vizier_root_existed_before_runtime = project_root.join(".vizier").exists();
             let jobs_root = jobs::ensure_jobs_root(&project_root)?;
             run_workflow(
@@ -195,8 +344,91 @@ pub(crate) async fn run() -> Result<(), Box<dyn std::error::Error>> {
             let jobs_root = jobs::ensure_jobs_root(&project_root)?;
             jobs::run_workflow_node_command(&project_root, &jobs_root, &cmd.job_id)
         }
+        Commands::NotificationsDrain => {
+            vizier_core::notifications::drain_notifications(&project_root).map_err(Into::into)
+        }
         Commands::Release(cmd) => run_release(cmd),
+        Commands::Agent(cmd) => {
+            run_agent_validate(&project_root, resolve_agent_validate_options(&cmd))
+        }
+        Commands::Prompt(cmd) => {
+            run_prompt_test(&project_root, resolve_prompt_test_options(&cmd)?).await
+        }
+        Commands::Cache(cmd) => run_cache(resolve_cache_options(&cmd)),
+        Commands::Forge(cmd) => run_forge(&project_root, resolve_forge_options(&cmd)),
+        Commands::Tool(cmd) => run_tool(&project_root, resolve_tool_options(&cmd)?),
+        Commands::Config(cmd) => {
+            run_config_import_ci(&project_root, resolve_config_import_ci_options(&cmd))
+        }
+        Commands::Changelog(cmd) => run_changelog(&project_root, resolve_changelog_options(&cmd)),
+    };
+
+    if interrupt::is_interrupted() {
+        auditor::Auditor::persist_session_log();
+        outcome::exit(interrupt::INTERRUPTED_EXIT_CODE);
+    }
+    match &result {
+        Ok(()) => outcome::finish(0, None),
+        Err(err) => outcome::finish(1, Some(err.to_string())),
+    }
+    result
+}
+
+fn run_layout_migrations(project_root: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(report) = migrations::run_pending(project_root)? {
+        display::emit(
+            LogLevel::Info,
+            format!(
+                "Migrated .vizier layout v{} -> v{} ({}); backup at {}",
+                report.from,
+                report.to,
+                report.applied.join("; "),
+                report.backup.display()
+            ),
+        );
     }
+    Ok(())
+}
+
+/// Removes scratch directories that crashed commands left under `.vizier/tmp/sessions/`; only
+/// reported in verbose mode since nothing the operator relies on lives there.
+fn collect_orphaned_tmp(project_root: &Path, keep_days: u32) {
+    match TempRegistry::new(project_root).cleanup_orphans(keep_days) {
+        Ok(removed) => {
+            for orphan in removed {
+                display::emit(
+                    LogLevel::Info,
+                    format!(
+                        "Removed orphaned temp dir {} ({} days old)",
+                        orphan.path.display(),
+                        orphan.age_days
+                    ),
+                );
+            }
+        }
+        Err(err) => display::emit(
+            LogLevel::Info,
+            format!("Skipped orphaned temp dir cleanup: {err}"),
+        ),
+    }
+}
+
+/// Commands that edit or check out files in the operator's checkout. A bare repository (a
+/// server-side deployment) still serves the read-only commands and `vizier run`, whose merges
+/// happen in job-owned worktrees.
+fn needs_worktree(command: &Commands) -> bool {
+    matches!(
+        command,
+        Commands::Init(_)
+            | Commands::Cd(_)
+            | Commands::Rebase(_)
+            | Commands::Pick(_)
+            | Commands::ApplyPatch(_)
+            | Commands::Rescue(_)
+            | Commands::Release(_)
+            | Commands::Changelog(_)
+            | Commands::Config(_)
+    )
 }
 
 fn resolve_project_root() -> Result<PathBuf, Box<dyn std::error::Error>> {
@@ -219,9 +451,19 @@ fn resolve_project_root() -> Result<PathBuf, Box<dyn std::error::Error>> {
 fn load_effective_config(
     project_root: &Path,
     explicit_config_file: Option<&str>,
+    explicit_profile: Option<&str>,
 ) -> Result<config::Config, Box<dyn std::error::Error>> {
+    let profile = explicit_profile
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .or_else(config::env_profile);
+    if let Some(name) = profile.as_deref() {
+        display::emit(LogLevel::Info, format!("Using config profile `{name}`"));
+    }
+    let profile = profile.as_deref();
+
     if let Some(config_file) = explicit_config_file {
-        return config::load_config_from_path(PathBuf::from(config_file));
+        return config::load_config_from_path_with_profile(PathBuf::from(config_file), profile);
     }
 
     let mut layers = Vec::new();
@@ -243,7 +485,7 @@ fn load_effective_config(
     }
 
     if !layers.is_empty() {
-        return Ok(config::Config::from_layers(&layers));
+        return config::config_from_layers_with_profile(&layers, profile);
     }
 
     if let Some(path) = config::env_config_path().filter(|path| path.exists()) {
@@ -251,7 +493,11 @@ fn load_effective_config(
             LogLevel::Info,
             format!("Loading env config from {}", path.display()),
         );
-        return config::load_config_from_path(path);
+        return config::load_config_from_path_with_profile(path, profile);
+    }
+
+    if profile.is_some() {
+        return config::config_from_layers_with_profile(&[], profile);
     }
 
     Ok(config::get_config())
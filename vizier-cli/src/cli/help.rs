@@ -214,7 +214,7 @@ pub(crate) fn render_run_workflow_help_text(
             "  --follow                      Wait for terminal run state and stream progress",
             "  --repeat <N>                  Enqueue N serial runs",
             "  --format <text|json>          Output format",
-            "  -q/--quiet, -v/--verbose, -d/--debug, --no-ansi, -C/--config-file, -l/--load-session, -n/--no-session",
+            "  -q/--quiet, -v/--verbose, -d/--debug, --no-ansi, -C/--config-file, --profile, -l/--load-session, -n/--no-session",
         ]
         .iter()
         .map(|value| value.to_string()),
@@ -295,7 +295,10 @@ pub(crate) fn strip_ansi_codes(input: &str) -> String {
 }
 
 fn global_arg_takes_value(arg: &str) -> bool {
-    matches!(arg, "-C" | "--config-file" | "-l" | "--load-session")
+    matches!(
+        arg,
+        "-C" | "--config-file" | "--profile" | "-l" | "--load-session"
+    )
 }
 
 fn try_page_output(command: &str, contents: &str) -> std::io::Result<()> {
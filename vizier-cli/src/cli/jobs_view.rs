@@ -1,22 +1,26 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::io::{self, Write};
 use std::path::Path;
 use std::thread;
 use std::time::Duration as StdDuration;
 
-use chrono::Local;
+use chrono::{Local, Utc};
 use git2::Repository;
 use serde_json::{Map, Value, json};
 use vizier_core::{
-    config,
-    display::{format_label_value_block, format_number},
+    config, cost,
+    display::{self, format_label_value_block, format_number},
+    review_report::ReviewReport,
+    vcs,
 };
 
 use crate::actions::shared::format_table;
 use crate::cli::args::{
     JobsAction, JobsActionFormatArg, JobsCmd, JobsListField, JobsScheduleFormatArg, JobsShowField,
-    normalize_labels, parse_fields, resolve_label,
+    ReviewFormatArg, normalize_labels, parse_fields, resolve_label,
 };
+use crate::cli::conflict_resolver::resolve_conflicts_interactively;
+use crate::cli::porcelain;
 use crate::jobs::{self, JobStatus};
 
 fn join_or_none(items: Vec<String>) -> String {
@@ -711,12 +715,26 @@ fn run_schedule_watch_loop(
     }
 }
 
-fn jobs_list_field_value(field: JobsListField, record: &jobs::JobRecord) -> Option<String> {
+fn jobs_list_field_value(
+    field: JobsListField,
+    record: &jobs::JobRecord,
+    queue: &HashMap<String, jobs::QueueSlot>,
+) -> Option<String> {
     let schedule = record.schedule.as_ref();
     match field {
         JobsListField::Job => Some(record.id.clone()),
         JobsListField::Status => Some(jobs::status_label(record.status).to_string()),
         JobsListField::Created => Some(record.created_at.to_rfc3339()),
+        JobsListField::Priority => schedule
+            .filter(|sched| sched.priority != 0)
+            .map(|sched| sched.priority.to_string()),
+        JobsListField::QueuePosition => queue
+            .get(&record.id)
+            .map(|slot| format!("{} of {}", slot.position, queue.len())),
+        JobsListField::EstimatedStart => queue
+            .get(&record.id)
+            .and_then(|slot| slot.estimated_start)
+            .map(|start| start.to_rfc3339()),
         JobsListField::After => schedule.map(|sched| format_after_dependencies(&sched.after)),
         JobsListField::Dependencies => schedule.map(|sched| {
             join_or_none(
@@ -960,6 +978,26 @@ fn jobs_show_field_value(field: JobsShowField, record: &jobs::JobRecord) -> Opti
         JobsShowField::AgentExit => {
             metadata.and_then(|meta| meta.agent_exit_code.map(|code| code.to_string()))
         }
+        JobsShowField::CostEstimate => metadata.and_then(|meta| {
+            meta.cost_estimate.as_ref().map(|estimate| {
+                format!(
+                    "{} ({} context tokens, {})",
+                    estimate.range_label(),
+                    format_number(estimate.context_tokens as usize),
+                    estimate.model
+                )
+            })
+        }),
+        JobsShowField::CostActual => metadata.and_then(|meta| {
+            meta.cost_actual.as_ref().map(|actual| {
+                format!(
+                    "{} ({} in, {} out tokens)",
+                    cost::format_usd(actual.usd),
+                    format_number(actual.input_tokens as usize),
+                    format_number(actual.output_tokens as usize)
+                )
+            })
+        }),
         JobsShowField::CancelCleanup => metadata.and_then(|meta| {
             meta.cancel_cleanup_status
                 .map(|status| status.label().to_string())
@@ -974,6 +1012,7 @@ fn jobs_show_field_value(field: JobsShowField, record: &jobs::JobRecord) -> Opti
         JobsShowField::RetryCleanupError => {
             metadata.and_then(|meta| meta.retry_cleanup_error.clone())
         }
+        JobsShowField::OperatorNote => metadata.and_then(|meta| meta.operator_note.clone()),
         JobsShowField::ConfigSnapshot => record
             .config_snapshot
             .as_ref()
@@ -1014,7 +1053,12 @@ pub(crate) fn run_jobs_command(
                 list_config.show_succeeded
             };
             let records = jobs::list_records(jobs_root)?;
-            if records.is_empty() {
+            let queue = jobs::queue_slots(
+                &records,
+                config::get_config().jobs.max_concurrent,
+                Utc::now(),
+            );
+            if records.is_empty() && !porcelain::enabled() {
                 if raw {
                     let payload = jobs::build_job_monitor_list_envelope(&records);
                     println!("{}", serde_json::to_string_pretty(&payload)?);
@@ -1025,7 +1069,7 @@ pub(crate) fn run_jobs_command(
                     });
                     println!("{}", serde_json::to_string_pretty(&payload)?);
                 } else {
-                    println!("Outcome: No background jobs found");
+                    println!("{}", display::outcome_line("No background jobs found"));
                 }
                 return Ok(());
             }
@@ -1077,6 +1121,13 @@ pub(crate) fn run_jobs_command(
                 )
             };
 
+            if porcelain::enabled() {
+                for record in &visible {
+                    porcelain::emit_job(record);
+                }
+                return Ok(());
+            }
+
             if raw {
                 let payload = jobs::build_job_monitor_list_envelope(&visible);
                 println!("{}", serde_json::to_string_pretty(&payload)?);
@@ -1100,7 +1151,7 @@ pub(crate) fn run_jobs_command(
                 for record in &visible {
                     let mut obj = Map::new();
                     for field in &fields {
-                        let value = jobs_list_field_value(*field, record);
+                        let value = jobs_list_field_value(*field, record, &queue);
                         if let Some(value) = value {
                             obj.insert(field.json_key().to_string(), Value::String(value));
                         }
@@ -1144,7 +1195,7 @@ pub(crate) fn run_jobs_command(
                     for record in &visible {
                         let mut row = Vec::new();
                         for field in &fields {
-                            let value = jobs_list_field_value(*field, record);
+                            let value = jobs_list_field_value(*field, record, &queue);
                             row.push(value.unwrap_or_default());
                         }
                         rows.push(row);
@@ -1159,7 +1210,8 @@ pub(crate) fn run_jobs_command(
                     for record in &visible {
                         let mut rows = Vec::new();
                         for field in &fields {
-                            let value = jobs_list_field_value(*field, record).unwrap_or_default();
+                            let value =
+                                jobs_list_field_value(*field, record, &queue).unwrap_or_default();
                             rows.push((resolve_label(&labels, field.label()), value));
                         }
                         let block = format_label_value_block(&rows, 2);
@@ -1231,7 +1283,7 @@ pub(crate) fn run_jobs_command(
                     let snapshot = jobs::ScheduleSnapshot::empty();
                     println!("{}", serde_json::to_string_pretty(&snapshot)?);
                 } else {
-                    println!("Outcome: No scheduled jobs");
+                    println!("{}", display::outcome_line("No scheduled jobs"));
                 }
                 return Ok(());
             }
@@ -1361,9 +1413,73 @@ pub(crate) fn run_jobs_command(
             }
             Ok(())
         }
-        JobsAction::Retry { job, format } => {
+        JobsAction::Review {
+            job,
+            format,
+            publish,
+        } => {
+            let record = jobs::read_record(jobs_root, &job)?;
+            let critique = std::fs::read_to_string(&record.stdout_path).map_err(|err| {
+                format!(
+                    "cannot read stdout for job {job} ({}): {err}",
+                    record.stdout_path
+                )
+            })?;
+            if critique.trim().is_empty() {
+                return Err(format!("job {job} has no critique on stdout yet").into());
+            }
+            let report = ReviewReport::parse(&critique);
+            let published = match publish.as_deref() {
+                Some(pull_request) => {
+                    let provider = vizier_core::forge::provider_for(project_root).ok_or(
+                        "--publish needs a forge CLI; install gh or glab, or set [forge] cli",
+                    )?;
+                    provider.publish_review(
+                        project_root,
+                        pull_request,
+                        &report.render_markdown(),
+                    )?;
+                    if !matches!(format, ReviewFormatArg::Json) {
+                        display::info(format!(
+                            "Published review of job {job} to {pull_request} via {}",
+                            provider.name()
+                        ));
+                    }
+                    Some(json!({ "pull_request": pull_request, "cli": provider.name() }))
+                }
+                None => None,
+            };
+            match format {
+                ReviewFormatArg::Markdown => println!("{}", report.render_markdown()),
+                ReviewFormatArg::Plain => println!("{}", report.render_plain()),
+                ReviewFormatArg::Json => {
+                    let payload = json!({
+                        "job": record.id,
+                        "status": jobs::status_label(record.status),
+                        "sections": report.sections,
+                        "action_items": report.action_items,
+                        "published": published,
+                    });
+                    println!("{}", serde_json::to_string_pretty(&payload)?);
+                }
+            }
+            Ok(())
+        }
+        JobsAction::Retry {
+            job,
+            continue_worktree,
+            note,
+            format,
+        } => {
             let binary = std::env::current_exe()?;
-            let outcome = jobs::retry_job(project_root, jobs_root, &binary, &job)?;
+            let options = jobs::RetryOptions {
+                keep_worktree: continue_worktree,
+                note: note
+                    .map(|note| note.trim().to_string())
+                    .filter(|note| !note.is_empty()),
+            };
+            let outcome =
+                jobs::retry_job_with_options(project_root, jobs_root, &binary, &job, &options)?;
             if matches!(format, JobsActionFormatArg::Json) {
                 let payload = json!({
                     "outcome": "Jobs retried",
@@ -1374,6 +1490,8 @@ pub(crate) fn run_jobs_command(
                     "reset": outcome.reset,
                     "restarted": outcome.restarted,
                     "updated": outcome.updated,
+                    "continued": options.keep_worktree,
+                    "note": options.note,
                 });
                 println!("{}", serde_json::to_string_pretty(&payload)?);
             } else {
@@ -1389,6 +1507,12 @@ pub(crate) fn run_jobs_command(
                     ("Reset".to_string(), join_or_none(outcome.reset)),
                     ("Restarted".to_string(), join_or_none(outcome.restarted)),
                 ];
+                if options.keep_worktree {
+                    rows.push(("Worktree".to_string(), "kept".to_string()));
+                }
+                if let Some(note) = options.note.as_ref() {
+                    rows.push(("Note".to_string(), note.clone()));
+                }
                 if !outcome.updated.is_empty() {
                     rows.push(("Updated".to_string(), join_or_none(outcome.updated)));
                 }
@@ -1396,6 +1520,62 @@ pub(crate) fn run_jobs_command(
             }
             Ok(())
         }
+        JobsAction::Resolve { job, no_retry } => {
+            let root = jobs::job_execution_root(project_root, jobs_root, &job)?;
+            if vcs::list_conflicted_paths_in(&root)?.is_empty() {
+                return Err(
+                    format!("job {job} has no unmerged paths in {}", root.display()).into(),
+                );
+            }
+            let outcome = resolve_conflicts_interactively(project_root, &root)?;
+            let mut rows = vec![
+                ("Job".to_string(), job.clone()),
+                ("Resolved".to_string(), join_or_none(outcome.resolved)),
+            ];
+            if !outcome.skipped.is_empty() {
+                rows.push(("Skipped".to_string(), outcome.skipped.join(", ")));
+            }
+            if !outcome.remaining.is_empty() || no_retry {
+                let next = if outcome.remaining.is_empty() {
+                    format!("vizier jobs retry {job} --continue")
+                } else {
+                    format!("vizier jobs resolve {job}")
+                };
+                rows.insert(
+                    0,
+                    (
+                        "Outcome".to_string(),
+                        if outcome.remaining.is_empty() {
+                            "Conflicts resolved".to_string()
+                        } else {
+                            "Conflicts remain".to_string()
+                        },
+                    ),
+                );
+                rows.push(("Remaining".to_string(), join_or_none(outcome.remaining)));
+                rows.push(("Next".to_string(), next));
+                println!("{}", format_label_value_block(&rows, 0));
+                return Ok(());
+            }
+
+            let binary = std::env::current_exe()?;
+            let options = jobs::RetryOptions {
+                keep_worktree: true,
+                note: None,
+            };
+            let retried =
+                jobs::retry_job_with_options(project_root, jobs_root, &binary, &job, &options)?;
+            rows.insert(
+                0,
+                (
+                    "Outcome".to_string(),
+                    "Conflicts resolved; job retried".to_string(),
+                ),
+            );
+            rows.push(("Restarted".to_string(), join_or_none(retried.restarted)));
+            println!("{}", format_label_value_block(&rows, 0));
+            Ok(())
+        }
         JobsAction::Approve { job, format } => {
             let binary = std::env::current_exe()?;
             let outcome = jobs::approve_job(project_root, jobs_root, &binary, &job)?;
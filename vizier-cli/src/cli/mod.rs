@@ -1,7 +1,10 @@
 pub(crate) mod args;
+pub(crate) mod conflict_resolver;
 pub(crate) mod dispatch;
 pub(crate) mod help;
 pub(crate) mod jobs_view;
+pub(crate) mod outcome;
+pub(crate) mod porcelain;
 pub(crate) mod prompt;
 pub(crate) mod resolve;
 pub(crate) mod util;
@@ -0,0 +1,240 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use chrono::Utc;
+use clap::parser::ValueSource;
+use clap::{ArgMatches, Command};
+use git2::Oid;
+use vizier_core::{
+    auditor::{
+        Auditor, COMMAND_OUTCOME_SCHEMA, CommandOutcome, CommandOutcomeAgent, CommandOutcomeCommit,
+        CommandOutcomeExit, CommandOutcomePhase,
+    },
+    config, display, interrupt, vcs,
+};
+
+static RECORDER: Mutex<Option<OutcomeRecorder>> = Mutex::new(None);
+
+struct OutcomeRecorder {
+    project_root: PathBuf,
+    command: String,
+    argv: Vec<String>,
+    flags: BTreeMap<String, Vec<String>>,
+    started_at: String,
+    branch_tips: BTreeMap<String, Oid>,
+}
+
+/// Starts tracking the current invocation so `finish` can write `outcome.json` for it.
+pub(crate) fn begin(
+    project_root: PathBuf,
+    command: &Command,
+    matches: &ArgMatches,
+    argv: Vec<String>,
+) {
+    let (path, flags) = collect_invocation(command, matches);
+    let recorder = OutcomeRecorder {
+        branch_tips: vcs::branch_tips_in(&project_root).unwrap_or_default(),
+        project_root,
+        command: path.join(" "),
+        argv,
+        flags,
+        started_at: Utc::now().to_rfc3339(),
+    };
+    if let Ok(mut slot) = RECORDER.lock() {
+        *slot = Some(recorder);
+    }
+}
+
+/// Writes `outcome.json` for the tracked invocation (at most once) and reports its path on
+/// stderr so stdout contracts stay untouched. Porcelain invocations get their closing records
+/// here too.
+pub(crate) fn finish(code: i32, error: Option<String>) {
+    crate::cli::porcelain::finish(code);
+    let Some(recorder) = RECORDER.lock().ok().and_then(|mut slot| slot.take()) else {
+        return;
+    };
+    display::emit_phase_summary();
+    let cfg = config::get_config();
+    // `--no-session` opts out, and uninitialized repos stay free of `.vizier/` residue.
+    if cfg.no_session || !recorder.project_root.join(".vizier").is_dir() {
+        return;
+    }
+
+    let commits = vcs::commits_since_tips_in(&recorder.project_root, &recorder.branch_tips)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(branch, oid, summary)| CommandOutcomeCommit {
+            branch,
+            oid: oid.to_string(),
+            summary,
+        })
+        .collect();
+
+    let outcome = CommandOutcome {
+        schema: COMMAND_OUTCOME_SCHEMA.to_string(),
+        session_id: Auditor::session_id(),
+        command: recorder.command,
+        argv: recorder.argv,
+        flags: recorder.flags,
+        agent: resolved_agent(&cfg),
+        commits,
+        gates: Auditor::recorded_gates(),
+        phases: display::phase_timings()
+            .into_iter()
+            .map(|timing| CommandOutcomePhase {
+                name: timing.name,
+                label: timing.label,
+                duration_ms: timing.duration_ms,
+            })
+            .collect(),
+        exit: CommandOutcomeExit::from_code(code, error),
+        started_at: recorder.started_at,
+        finished_at: Utc::now().to_rfc3339(),
+    };
+
+    if let Some(artifact) = Auditor::persist_command_outcome(&recorder.project_root, &outcome)
+        && !display::get_display_config()
+            .verbosity
+            .eq(&display::Verbosity::Quiet)
+    {
+        eprintln!(
+            "{}: {}",
+            vizier_core::i18n::tr("Outcome file"),
+            artifact.display_path()
+        );
+    }
+}
+
+/// Replacement for `std::process::exit` inside command handlers so early exits still leave
+/// an outcome file behind. Once the invocation has been interrupted, every exit reports
+/// [`interrupt::INTERRUPTED_EXIT_CODE`] so wrappers can tell cancellation from failure.
+pub(crate) fn exit(code: i32) -> ! {
+    if interrupt::is_interrupted() {
+        finish(
+            interrupt::INTERRUPTED_EXIT_CODE,
+            Some("interrupted by signal".to_string()),
+        );
+        std::process::exit(interrupt::INTERRUPTED_EXIT_CODE)
+    }
+    finish(code, None);
+    std::process::exit(code)
+}
+
+fn resolved_agent(cfg: &config::Config) -> CommandOutcomeAgent {
+    match Auditor::latest_agent_context() {
+        Some(context) => CommandOutcomeAgent {
+            selector: context.selector,
+            backend: context.backend.to_string(),
+            label: context.backend_label,
+            command: context.command,
+            invoked: true,
+            exit_code: context.exit_code,
+            duration_ms: context.duration_ms,
+        },
+        None => match config::resolve_default_agent_settings(cfg, None) {
+            Ok(settings) => CommandOutcomeAgent {
+                selector: settings.selector,
+                backend: settings.backend.to_string(),
+                label: settings.agent_runtime.label,
+                command: settings.agent_runtime.command,
+                invoked: false,
+                exit_code: None,
+                duration_ms: None,
+            },
+            Err(_) => CommandOutcomeAgent {
+                selector: cfg.agent_selector.clone(),
+                backend: cfg.backend.to_string(),
+                label: cfg.agent_runtime.label.clone().unwrap_or_default(),
+                command: cfg.agent_runtime.command.clone(),
+                invoked: false,
+                exit_code: None,
+                duration_ms: None,
+            },
+        },
+    }
+}
+
+// Walks the matched subcommand chain and keeps only arguments the caller typed, keyed by
+// their long flag (`--format`) or positional id (`plan`).
+fn collect_invocation(
+    command: &Command,
+    matches: &ArgMatches,
+) -> (Vec<String>, BTreeMap<String, Vec<String>>) {
+    let mut path = Vec::new();
+    let mut flags = BTreeMap::new();
+    let mut command = command;
+    let mut matches = matches;
+    loop {
+        for id in matches.ids() {
+            let id = id.as_str();
+            if matches.value_source(id) != Some(ValueSource::CommandLine) {
+                continue;
+            }
+            let Ok(Some(values)) = matches.try_get_raw(id) else {
+                continue;
+            };
+            let values = values
+                .map(|value| value.to_string_lossy().into_owned())
+                .collect::<Vec<_>>();
+            flags.insert(argument_key(command, id), values);
+        }
+
+        let Some((name, sub_matches)) = matches.subcommand() else {
+            break;
+        };
+        path.push(name.to_string());
+        let Some(sub_command) = command.find_subcommand(name) else {
+            break;
+        };
+        command = sub_command;
+        matches = sub_matches;
+    }
+    (path, flags)
+}
+
+fn argument_key(command: &Command, id: &str) -> String {
+    command
+        .get_arguments()
+        .find(|arg| arg.get_id() == id)
+        .and_then(|arg| arg.get_long())
+        .map(|long| format!("--{long}"))
+        .unwrap_or_else(|| id.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+
+    use super::collect_invocation;
+    use crate::cli::args::Cli;
+
+    #[test]
+    fn collect_invocation_keeps_only_explicit_arguments() {
+        let command = Cli::command();
+        let matches = command
+            .clone()
+            .try_get_matches_from([
+                "vizier",
+                "--no-ansi",
+                "jobs",
+                "retry",
+                "job-1",
+                "--note",
+                "use the helper",
+            ])
+            .expect("parse jobs retry");
+        let (path, flags) = collect_invocation(&command, &matches);
+        assert_eq!(path, vec!["jobs", "retry"]);
+        assert_eq!(
+            flags.get("--note"),
+            Some(&vec!["use the helper".to_string()])
+        );
+        assert_eq!(flags.get("job"), Some(&vec!["job-1".to_string()]));
+        assert!(flags.contains_key("--no-ansi"), "flags were {flags:?}");
+        assert!(
+            !flags.contains_key("--format"),
+            "defaulted arguments should be omitted: {flags:?}"
+        );
+    }
+}
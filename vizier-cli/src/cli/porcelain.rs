@@ -0,0 +1,146 @@
+//! `--porcelain` output: one record per line, tab-separated, for scripts that must not break
+//! when the human display changes. The first field names the record type; fields never contain
+//! raw tabs or newlines (`\t`, `\n`, `\r`, and `\\` are escaped) and absent values are `-`.
+//!
+//! Records and their field order are fixed for a porcelain version. Adding a record type or
+//! appending fields to the end of a record is allowed within `v1`; anything else needs `v2`.
+//!
+//! ```text
+//! plan    <slug> <branch> <status> <head-sha>
+//! run     <run-id> <state> <template-id>@<version>
+//! job     <job-id> <status> <run-id> <node-id> <plan> <branch> <revision>
+//! check   <template-id>@<version> <selector> valid
+//! commit  <branch> <sha> <summary>
+//! exit    <code>
+//! ```
+//!
+//! Every porcelain invocation ends with its `commit` records (commits the command created, in
+//! order) and a single `exit` record, so a reader can tell a complete stream from a cut-off one.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use git2::Oid;
+use vizier_core::vcs;
+
+use crate::cli::args::{Commands, JobsAction};
+use crate::jobs;
+
+static STATE: Mutex<Option<PorcelainState>> = Mutex::new(None);
+
+struct PorcelainState {
+    project_root: PathBuf,
+    branch_tips: BTreeMap<String, Oid>,
+}
+
+/// Commands with a porcelain form; the rest refuse `--porcelain` rather than mix human output
+/// into the stream.
+pub(crate) fn supports(command: &Commands) -> bool {
+    match command {
+        Commands::List(_) | Commands::Run(_) => true,
+        Commands::Jobs(cmd) => matches!(cmd.action, JobsAction::List { .. }),
+        _ => false,
+    }
+}
+
+/// Switches the invocation to porcelain output and snapshots branch tips for the trailing
+/// `commit` records.
+pub(crate) fn begin(project_root: PathBuf) {
+    let state = PorcelainState {
+        branch_tips: vcs::branch_tips_in(&project_root).unwrap_or_default(),
+        project_root,
+    };
+    if let Ok(mut slot) = STATE.lock() {
+        *slot = Some(state);
+    }
+}
+
+pub(crate) fn enabled() -> bool {
+    STATE.lock().map(|slot| slot.is_some()).unwrap_or(false)
+}
+
+/// Prints one record.
+pub(crate) fn emit<S: AsRef<str>>(kind: &str, fields: &[S]) {
+    println!("{}", record(kind, fields));
+}
+
+/// `job <id> <status> <run-id> <node-id> <plan> <branch> <revision>`.
+pub(crate) fn emit_job(record: &jobs::JobRecord) {
+    let meta = record.metadata.as_ref();
+    let value = |get: fn(&jobs::JobMetadata) -> Option<&String>| {
+        field(meta.and_then(get).map(String::as_str))
+    };
+    emit(
+        "job",
+        &[
+            record.id.clone(),
+            jobs::status_label(record.status).to_string(),
+            value(|meta| meta.workflow_run_id.as_ref()),
+            value(|meta| meta.workflow_node_id.as_ref()),
+            value(|meta| meta.plan.as_ref()),
+            value(|meta| meta.branch.as_ref()),
+            value(|meta| meta.revision.as_ref()),
+        ],
+    );
+}
+
+/// Prints the `commit` records and the final `exit` record (at most once).
+pub(crate) fn finish(code: i32) {
+    let Some(state) = STATE.lock().ok().and_then(|mut slot| slot.take()) else {
+        return;
+    };
+    for (branch, oid, summary) in
+        vcs::commits_since_tips_in(&state.project_root, &state.branch_tips).unwrap_or_default()
+    {
+        emit("commit", &[branch, oid.to_string(), summary]);
+    }
+    emit("exit", &[code.to_string()]);
+}
+
+/// An optional field: the value, or `-` when absent.
+pub(crate) fn field(value: Option<&str>) -> String {
+    match value {
+        Some(value) if !value.is_empty() => value.to_string(),
+        _ => "-".to_string(),
+    }
+}
+
+fn record<S: AsRef<str>>(kind: &str, fields: &[S]) -> String {
+    let mut line = kind.to_string();
+    for value in fields {
+        line.push('\t');
+        line.push_str(&escape(value.as_ref()));
+    }
+    line
+}
+
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_escape_separators_and_mark_absent_fields() {
+        assert_eq!(
+            record("commit", &["draft/a", "abc123", "fix:\ttabs\nand \\ lines"]),
+            "commit\tdraft/a\tabc123\tfix:\\ttabs\\nand \\\\ lines"
+        );
+        assert_eq!(field(None), "-");
+        assert_eq!(field(Some("")), "-");
+        assert_eq!(field(Some("alpha")), "alpha");
+    }
+}
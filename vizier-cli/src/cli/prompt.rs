@@ -1,5 +1,7 @@
 use std::io::{self, IsTerminal, Write};
 
+use crate::cli::help::{PagerMode, render_help_with_pager};
+
 pub(crate) fn prompt_yes_no(prompt: &str) -> Result<bool, Box<dyn std::error::Error>> {
     if !io::stdin().is_terminal() {
         return Err("confirmation requires a TTY; rerun with --yes".into());
@@ -15,9 +17,80 @@ fn parse_yes_no(answer: &str) -> bool {
     matches!(answer.trim().to_ascii_lowercase().as_str(), "y" | "yes")
 }
 
+/// A document the operator can open from [`prompt_with_preview`] before answering.
+pub(crate) struct PreviewView {
+    pub(crate) key: char,
+    pub(crate) label: &'static str,
+    pub(crate) content: String,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum PreviewAnswer {
+    Yes,
+    No,
+    View(usize),
+    Unknown,
+}
+
+/// Prints `pane` and asks `prompt`, paging any of `views` on request until the operator answers.
+/// Falls back to [`prompt_yes_no`] when stderr is not a terminal so the pane never lands in a
+/// redirected log.
+pub(crate) fn prompt_with_preview(
+    pane: &str,
+    prompt: &str,
+    views: &[PreviewView],
+) -> Result<bool, Box<dyn std::error::Error>> {
+    if !io::stderr().is_terminal() {
+        return prompt_yes_no(prompt);
+    }
+    if !io::stdin().is_terminal() {
+        return Err("confirmation requires a TTY; rerun with --yes".into());
+    }
+    let choices = views
+        .iter()
+        .map(|view| format!(", {}={}", view.key, view.label))
+        .collect::<String>();
+    eprintln!("{}", pane.trim_end());
+    loop {
+        eprint!("{prompt} [y/N{choices}]: ");
+        io::stderr().flush()?;
+        let mut answer = String::new();
+        if io::stdin().read_line(&mut answer)? == 0 {
+            return Ok(false);
+        }
+        match parse_preview_answer(&answer, views) {
+            PreviewAnswer::Yes => return Ok(true),
+            PreviewAnswer::No => return Ok(false),
+            PreviewAnswer::View(index) => {
+                let content = &views[index].content;
+                if io::stdout().is_terminal() {
+                    render_help_with_pager(content, PagerMode::Auto, true, false)?;
+                } else {
+                    eprint!("{content}");
+                }
+            }
+            PreviewAnswer::Unknown => eprintln!("answer y or n{choices}"),
+        }
+    }
+}
+
+fn parse_preview_answer(answer: &str, views: &[PreviewView]) -> PreviewAnswer {
+    let answer = answer.trim().to_ascii_lowercase();
+    if answer.is_empty() || matches!(answer.as_str(), "n" | "no") {
+        return PreviewAnswer::No;
+    }
+    if parse_yes_no(&answer) {
+        return PreviewAnswer::Yes;
+    }
+    views
+        .iter()
+        .position(|view| answer == view.key.to_string() || answer == view.label)
+        .map_or(PreviewAnswer::Unknown, PreviewAnswer::View)
+}
+
 #[cfg(test)]
 mod tests {
-    use super::parse_yes_no;
+    use super::{PreviewAnswer, PreviewView, parse_preview_answer, parse_yes_no};
 
     #[test]
     fn parse_yes_no_accepts_yes_variants() {
@@ -29,4 +102,29 @@ mod tests {
         assert!(!parse_yes_no("no"));
         assert!(!parse_yes_no(""));
     }
+
+    #[test]
+    fn parse_preview_answer_opens_views_by_key_or_label() {
+        let views = [
+            PreviewView {
+                key: 'p',
+                label: "plan",
+                content: String::new(),
+            },
+            PreviewView {
+                key: 'd',
+                label: "diff",
+                content: String::new(),
+            },
+        ];
+        assert_eq!(parse_preview_answer("y\n", &views), PreviewAnswer::Yes);
+        assert_eq!(parse_preview_answer("\n", &views), PreviewAnswer::No);
+        assert_eq!(parse_preview_answer("No", &views), PreviewAnswer::No);
+        assert_eq!(parse_preview_answer("p", &views), PreviewAnswer::View(0));
+        assert_eq!(
+            parse_preview_answer(" DIFF ", &views),
+            PreviewAnswer::View(1)
+        );
+        assert_eq!(parse_preview_answer("x", &views), PreviewAnswer::Unknown);
+    }
 }
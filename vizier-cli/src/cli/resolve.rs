@@ -1,7 +1,38 @@
 use std::io;
 
-use crate::actions::{CdOptions, CleanOptions, CleanOutputFormat, ListOptions};
-use crate::cli::args::{CdCmd, CleanCmd, CleanFormatArg, ListCmd};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use vizier_core::{config, review_report::Severity, review_tools, thread_state};
+
+use crate::actions::{
+    AckOptions, AckOutputFormat, AgentValidateOptions, AgentValidateOutputFormat,
+    ApplyPatchOptions, ApplyPatchOutputFormat, ArchiveOptions, ArchiveOutputFormat, ArchiveRequest,
+    CacheOptions, CacheOutputFormat, CacheRequest, CdOptions, ChangelogOptions,
+    ChangelogOutputFormat, CiOptions, CiOutputFormat, CiRequest, CleanOptions, CleanOutputFormat,
+    ConfigImportCiOptions, ConfigImportOutputFormat, DoctorOptions, DoctorOutputFormat,
+    ForgeOptions, ForgeOutputFormat, ForgeRequest, InitOptions, LabelChange, LabelOptions,
+    LabelOutputFormat, ListOptions, NoteOptions, NoteOutputFormat, PickOptions, PickOutputFormat,
+    PlanOptions, PlanOutputFormat, PromptTestOptions, PromptTestOutputFormat, RebaseOptions,
+    RebaseOutputFormat, RescueAction, RescueOptions, RescueOutputFormat, SearchOptions,
+    SearchOutputFormat, SessionsOptions, SessionsOutputFormat, SessionsRequest, SnapshotOptions,
+    SnapshotOutputFormat, SnapshotRequest, StatsOptions, StatsOutputFormat, ThreadOptions,
+    ThreadOutputFormat, ThreadRequest, ToolOptions, VerifyOptions, VerifyOutputFormat,
+    WatchOptions, WatchOutputFormat,
+};
+use crate::cli::args::{
+    AckCmd, AckFormatArg, AgentAction, AgentCmd, AgentFormatArg, ApplyPatchCmd,
+    ApplyPatchFormatArg, ArchiveAction, ArchiveCmd, ArchiveFormatArg, CacheAction, CacheCmd,
+    CacheFormatArg, CdCmd, ChangelogAction, ChangelogCmd, ChangelogFormatArg, CiAction, CiCmd,
+    CiFailOnArg, CiFormatArg, CleanCmd, CleanFormatArg, ConfigAction, ConfigCmd,
+    ConfigImportFormatArg, DoctorCmd, DoctorFormatArg, ForgeAction, ForgeCmd, ForgeFormatArg,
+    InitCmd, LabelActionArg, LabelCmd, LabelFormatArg, ListCmd, NoteCmd, NoteFormatArg, PickCmd,
+    PickFormatArg, PlanCmd, PromptAction, PromptCmd, PromptFormatArg, PromptKindArg, RebaseCmd,
+    RebaseFormatArg, RescueCmd, RescueFormatArg, SearchCmd, SearchFormatArg, SessionsAction,
+    SessionsCmd, SessionsFormatArg, SnapshotAction, SnapshotCmd, SnapshotFormatArg, StatsCmd,
+    StatsFormatArg, ThreadAction, ThreadCmd, ThreadFormatArg, ThreadStateArg, ToolAction, ToolCmd,
+    VerifyCmd, VerifyFormatArg, WatchCmd, WatchFormatArg,
+};
 use crate::plan;
 
 pub(crate) fn resolve_list_options(
@@ -26,10 +57,23 @@ pub(crate) fn resolve_list_options(
         None
     };
 
+    let labels = cmd
+        .label
+        .iter()
+        .flat_map(|raw| raw.split(','))
+        .filter(|label| !label.trim().is_empty())
+        .map(|label| {
+            vizier_core::plan::sanitize_label(label)
+                .map_err(|err| Box::<dyn std::error::Error>::from(format!("--label: {err}")))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
     Ok(ListOptions {
         target: cmd.target.clone(),
         format: cmd.format.map(Into::into),
         fields,
+        by_thread: cmd.by_thread,
+        labels,
     })
 }
 
@@ -69,3 +113,757 @@ pub(crate) fn resolve_clean_options(
         force: cmd.force,
     })
 }
+
+pub(crate) fn resolve_rescue_options(
+    cmd: &RescueCmd,
+) -> Result<RescueOptions, Box<dyn std::error::Error>> {
+    let slug = plan::sanitize_name_override(&cmd.plan).map_err(|err| {
+        Box::<dyn std::error::Error>::from(io::Error::new(io::ErrorKind::InvalidInput, err))
+    })?;
+    let branch = cmd
+        .branch
+        .clone()
+        .unwrap_or_else(|| plan::default_branch_for_slug(&slug));
+    let action = if cmd.commit {
+        Some(RescueAction::Commit)
+    } else if cmd.discard {
+        Some(RescueAction::Discard)
+    } else if cmd.patch {
+        Some(RescueAction::Patch)
+    } else {
+        None
+    };
+    let format = match cmd.format {
+        RescueFormatArg::Text => RescueOutputFormat::Text,
+        RescueFormatArg::Json => RescueOutputFormat::Json,
+    };
+
+    Ok(RescueOptions {
+        slug,
+        branch,
+        action,
+        message: cmd.message.clone(),
+        output: cmd.output.as_ref().map(PathBuf::from),
+        assume_yes: cmd.assume_yes,
+        format,
+    })
+}
+
+pub(crate) fn resolve_rebase_options(
+    cmd: &RebaseCmd,
+) -> Result<RebaseOptions, Box<dyn std::error::Error>> {
+    let spec = plan::PlanBranchSpec::resolve(
+        Some(cmd.plan.as_str()),
+        cmd.branch.as_deref(),
+        cmd.target.as_deref(),
+    )?;
+    let format = match cmd.format {
+        RebaseFormatArg::Text => RebaseOutputFormat::Text,
+        RebaseFormatArg::Json => RebaseOutputFormat::Json,
+    };
+
+    Ok(RebaseOptions {
+        slug: spec.slug,
+        branch: spec.branch,
+        target: spec.target_branch,
+        auto_resolve: !cmd.no_auto_resolve,
+        assume_yes: cmd.assume_yes,
+        format,
+    })
+}
+
+pub(crate) fn resolve_pick_options(
+    cmd: &PickCmd,
+) -> Result<PickOptions, Box<dyn std::error::Error>> {
+    let spec = plan::PlanBranchSpec::resolve(
+        Some(cmd.plan.as_str()),
+        cmd.branch.as_deref(),
+        cmd.target.as_deref(),
+    )?;
+    let pick = cmd.pick.trim();
+    if pick.is_empty() {
+        return Err("pick requires a commit or plan step number".into());
+    }
+    let message = cmd
+        .message
+        .as_deref()
+        .map(str::trim)
+        .filter(|message| !message.is_empty())
+        .map(str::to_string);
+    let format = match cmd.format {
+        PickFormatArg::Text => PickOutputFormat::Text,
+        PickFormatArg::Json => PickOutputFormat::Json,
+    };
+
+    Ok(PickOptions {
+        slug: spec.slug,
+        branch: spec.branch,
+        target: spec.target_branch,
+        pick: pick.to_string(),
+        message,
+        format,
+    })
+}
+
+pub(crate) fn resolve_apply_patch_options(
+    cmd: &ApplyPatchCmd,
+) -> Result<ApplyPatchOptions, Box<dyn std::error::Error>> {
+    let spec = plan::PlanBranchSpec::resolve(Some(cmd.plan.as_str()), cmd.branch.as_deref(), None)?;
+    let patch = cmd
+        .patch
+        .as_deref()
+        .map(str::trim)
+        .filter(|path| !path.is_empty() && *path != "-")
+        .map(PathBuf::from);
+    let message = cmd
+        .message
+        .as_deref()
+        .map(str::trim)
+        .filter(|message| !message.is_empty())
+        .map(str::to_string);
+    let format = match cmd.format {
+        ApplyPatchFormatArg::Text => ApplyPatchOutputFormat::Text,
+        ApplyPatchFormatArg::Json => ApplyPatchOutputFormat::Json,
+    };
+
+    Ok(ApplyPatchOptions {
+        slug: spec.slug,
+        branch: spec.branch,
+        target: spec.target_branch,
+        patch,
+        message,
+        format,
+    })
+}
+
+pub(crate) fn resolve_verify_options(
+    cmd: &VerifyCmd,
+) -> Result<VerifyOptions, Box<dyn std::error::Error>> {
+    let spec = plan::PlanBranchSpec::resolve(Some(cmd.plan.as_str()), cmd.branch.as_deref(), None)?;
+    let format = match cmd.format {
+        VerifyFormatArg::Text => VerifyOutputFormat::Text,
+        VerifyFormatArg::Json => VerifyOutputFormat::Json,
+    };
+
+    Ok(VerifyOptions {
+        slug: spec.slug,
+        branch: spec.branch,
+        target: spec.target_branch,
+        format,
+    })
+}
+
+pub(crate) fn resolve_ack_options(cmd: &AckCmd) -> Result<AckOptions, Box<dyn std::error::Error>> {
+    let spec = plan::PlanBranchSpec::resolve(Some(cmd.plan.as_str()), cmd.branch.as_deref(), None)?;
+    let owner = cmd
+        .owner
+        .as_deref()
+        .map(str::trim)
+        .filter(|owner| !owner.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(vizier_core::plan::branch_user);
+    let format = match cmd.format {
+        AckFormatArg::Text => AckOutputFormat::Text,
+        AckFormatArg::Json => AckOutputFormat::Json,
+    };
+
+    Ok(AckOptions {
+        slug: spec.slug,
+        branch: spec.branch,
+        target: spec.target_branch,
+        owner,
+        format,
+    })
+}
+
+pub(crate) fn resolve_label_options(
+    cmd: &LabelCmd,
+) -> Result<LabelOptions, Box<dyn std::error::Error>> {
+    let spec = plan::PlanBranchSpec::resolve(Some(cmd.plan.as_str()), cmd.branch.as_deref(), None)?;
+    let labels = cmd
+        .labels
+        .iter()
+        .flat_map(|raw| raw.split(','))
+        .filter(|label| !label.trim().is_empty())
+        .map(vizier_core::plan::sanitize_label)
+        .collect::<Result<Vec<_>, _>>()?;
+    let change = match cmd.action {
+        None if !labels.is_empty() => {
+            return Err("name an action (add or remove) before the labels".into());
+        }
+        None => None,
+        Some(_) if labels.is_empty() => return Err("no labels given".into()),
+        Some(LabelActionArg::Add) => Some(LabelChange::Add(labels)),
+        Some(LabelActionArg::Remove) => Some(LabelChange::Remove(labels)),
+    };
+    let format = match cmd.format {
+        LabelFormatArg::Text => LabelOutputFormat::Text,
+        LabelFormatArg::Json => LabelOutputFormat::Json,
+    };
+
+    Ok(LabelOptions {
+        slug: spec.slug,
+        branch: spec.branch,
+        change,
+        format,
+    })
+}
+
+pub(crate) fn resolve_note_options(
+    cmd: &NoteCmd,
+) -> Result<NoteOptions, Box<dyn std::error::Error>> {
+    let spec = plan::PlanBranchSpec::resolve(Some(cmd.plan.as_str()), cmd.branch.as_deref(), None)?;
+    let note = match cmd.note.as_deref().map(str::trim) {
+        Some("") => return Err("note text is empty".into()),
+        note => note.map(str::to_string),
+    };
+    let format = match cmd.format {
+        NoteFormatArg::Text => NoteOutputFormat::Text,
+        NoteFormatArg::Json => NoteOutputFormat::Json,
+    };
+
+    Ok(NoteOptions {
+        slug: spec.slug,
+        branch: spec.branch,
+        note,
+        format,
+    })
+}
+
+pub(crate) fn resolve_watch_options(cmd: &WatchCmd) -> WatchOptions {
+    WatchOptions {
+        settle: Duration::from_secs(cmd.settle),
+        once: cmd.once,
+        record: cmd.record,
+        format: match cmd.format {
+            WatchFormatArg::Text => WatchOutputFormat::Text,
+            WatchFormatArg::Json => WatchOutputFormat::Json,
+        },
+    }
+}
+
+pub(crate) fn resolve_init_options(cmd: &InitCmd) -> InitOptions {
+    InitOptions {
+        check: cmd.check,
+        install_shims: cmd.install_shims,
+        smoke: !cmd.check && !cmd.no_smoke,
+    }
+}
+
+pub(crate) fn resolve_sessions_options(cmd: &SessionsCmd) -> SessionsOptions {
+    let (request, format) = match &cmd.action {
+        SessionsAction::List { format } => (SessionsRequest::List, *format),
+        SessionsAction::Show { session, format } => (
+            SessionsRequest::Show {
+                session_id: session.clone(),
+            },
+            *format,
+        ),
+        SessionsAction::Gc {
+            since,
+            keep_last,
+            compress,
+            no_compress,
+            dry_run,
+            format,
+        } => {
+            let mut policy = config::get_config().sessions;
+            if let Some(days) = since {
+                policy.keep_days = *days;
+            }
+            if let Some(keep_last) = keep_last {
+                policy.keep_last = *keep_last;
+            }
+            if *compress {
+                policy.compress = true;
+            } else if *no_compress {
+                policy.compress = false;
+            }
+            (
+                SessionsRequest::Gc {
+                    policy,
+                    dry_run: *dry_run,
+                },
+                *format,
+            )
+        }
+        SessionsAction::Fsck { repair, format } => {
+            (SessionsRequest::Fsck { repair: *repair }, *format)
+        }
+        SessionsAction::Migrate { dry_run, format } => {
+            (SessionsRequest::Migrate { dry_run: *dry_run }, *format)
+        }
+        SessionsAction::Schema => (SessionsRequest::Schema, SessionsFormatArg::Json),
+    };
+    let format = match format {
+        SessionsFormatArg::Text => SessionsOutputFormat::Text,
+        SessionsFormatArg::Json => SessionsOutputFormat::Json,
+    };
+
+    SessionsOptions { request, format }
+}
+
+pub(crate) fn resolve_agent_validate_options(cmd: &AgentCmd) -> AgentValidateOptions {
+    let AgentAction::Validate {
+        filter,
+        events,
+        timeout,
+        format,
+        command,
+    } = &cmd.action;
+    AgentValidateOptions {
+        command: command.clone(),
+        filter: filter.clone(),
+        events: *events,
+        timeout: Duration::from_secs((*timeout).max(1)),
+        format: match format {
+            AgentFormatArg::Text => AgentValidateOutputFormat::Text,
+            AgentFormatArg::Json => AgentValidateOutputFormat::Json,
+        },
+    }
+}
+
+pub(crate) fn resolve_prompt_test_options(
+    cmd: &PromptCmd,
+) -> Result<PromptTestOptions, Box<dyn std::error::Error>> {
+    let PromptAction::Test {
+        prompt,
+        scope,
+        input,
+        input_file,
+        plan,
+        target,
+        run,
+        format,
+    } = &cmd.action;
+    let (kind, default_alias) = match prompt {
+        PromptKindArg::Documentation => (config::PromptKind::Documentation, "save"),
+        PromptKindArg::Commit => (config::PromptKind::Commit, "save"),
+        PromptKindArg::ImplementationPlan => (config::PromptKind::ImplementationPlan, "draft"),
+        PromptKindArg::Review => (config::PromptKind::Review, "review"),
+        PromptKindArg::MergeConflict => (config::PromptKind::MergeConflict, "merge"),
+    };
+    let alias_name = scope.as_deref().unwrap_or(default_alias);
+    let alias = config::CommandAlias::parse(alias_name)
+        .ok_or_else(|| format!("invalid --scope `{alias_name}`"))?;
+    let input = match input_file {
+        Some(path) if path.as_os_str() == "-" => Some(io::read_to_string(io::stdin())?),
+        Some(path) => Some(
+            std::fs::read_to_string(path)
+                .map_err(|err| format!("unable to read {}: {err}", path.display()))?,
+        ),
+        None => input.clone(),
+    };
+    let (plan, target) = match plan {
+        Some(plan_name) => {
+            let spec = plan::PlanBranchSpec::resolve(Some(plan_name), None, target.as_deref())?;
+            (Some((spec.slug, spec.branch)), Some(spec.target_branch))
+        }
+        None => (None, target.clone()),
+    };
+    Ok(PromptTestOptions {
+        kind,
+        alias,
+        input,
+        plan,
+        target,
+        run: *run,
+        format: match format {
+            PromptFormatArg::Text => PromptTestOutputFormat::Text,
+            PromptFormatArg::Json => PromptTestOutputFormat::Json,
+        },
+    })
+}
+
+pub(crate) fn resolve_thread_options(
+    cmd: &ThreadCmd,
+) -> Result<ThreadOptions, Box<dyn std::error::Error>> {
+    let plan_branch = |plan: Option<&String>| -> Result<_, Box<dyn std::error::Error>> {
+        plan.map(|plan| {
+            let spec = plan::PlanBranchSpec::resolve(Some(plan.as_str()), None, None)?;
+            Ok((spec.slug, spec.branch))
+        })
+        .transpose()
+    };
+    let (request, format) = match &cmd.action {
+        ThreadAction::List { state, format } => (
+            ThreadRequest::List {
+                state: state.map(|state| match state {
+                    ThreadStateArg::Open => thread_state::ThreadState::Open,
+                    ThreadStateArg::Blocked => thread_state::ThreadState::Blocked,
+                    ThreadStateArg::Resolved => thread_state::ThreadState::Resolved,
+                }),
+            },
+            *format,
+        ),
+        ThreadAction::Resolve { thread, by, format } => {
+            let plan = plan_branch(Some(by))?;
+            (
+                ThreadRequest::Transition {
+                    thread: sanitize_thread_name(thread)?,
+                    status: thread_state::ThreadStatus {
+                        state: thread_state::ThreadState::Resolved,
+                        resolved_by: plan.as_ref().map(|(slug, _)| slug.clone()),
+                        blocked_reason: None,
+                    },
+                    plan,
+                },
+                *format,
+            )
+        }
+        ThreadAction::Block {
+            thread,
+            reason,
+            plan,
+            format,
+        } => (
+            ThreadRequest::Transition {
+                thread: sanitize_thread_name(thread)?,
+                status: thread_state::ThreadStatus {
+                    state: thread_state::ThreadState::Blocked,
+                    resolved_by: None,
+                    blocked_reason: reason
+                        .as_deref()
+                        .map(str::trim)
+                        .filter(|reason| !reason.is_empty())
+                        .map(str::to_string),
+                },
+                plan: plan_branch(plan.as_ref())?,
+            },
+            *format,
+        ),
+        ThreadAction::Reopen {
+            thread,
+            plan,
+            format,
+        } => (
+            ThreadRequest::Transition {
+                thread: sanitize_thread_name(thread)?,
+                status: thread_state::ThreadStatus::open(),
+                plan: plan_branch(plan.as_ref())?,
+            },
+            *format,
+        ),
+        ThreadAction::Revise {
+            threads,
+            all,
+            format,
+        } => (
+            ThreadRequest::Revise {
+                threads: if *all {
+                    None
+                } else {
+                    let mut names = threads
+                        .iter()
+                        .map(|thread| sanitize_thread_name(thread))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    names.sort();
+                    names.dedup();
+                    Some(names)
+                },
+            },
+            *format,
+        ),
+    };
+    Ok(ThreadOptions {
+        request,
+        format: match format {
+            ThreadFormatArg::Text => ThreadOutputFormat::Text,
+            ThreadFormatArg::Json => ThreadOutputFormat::Json,
+        },
+    })
+}
+
+// Thread names are file stems under .vizier/narrative/threads; `threads/auth.md` style links
+// are accepted the same way plan front matter accepts them.
+fn sanitize_thread_name(raw: &str) -> Result<String, String> {
+    match vizier_core::plan::parse_thread_links(raw).as_slice() {
+        [thread] if !thread.starts_with('.') => Ok(thread.clone()),
+        _ => Err(format!("invalid thread name `{}`", raw.trim())),
+    }
+}
+
+pub(crate) fn resolve_cache_options(cmd: &CacheCmd) -> CacheOptions {
+    let (request, format) = match &cmd.action {
+        CacheAction::Status { format } => (CacheRequest::Status, *format),
+        CacheAction::Clean { all, format } => (CacheRequest::Clean { all: *all }, *format),
+    };
+    CacheOptions {
+        request,
+        format: match format {
+            CacheFormatArg::Text => CacheOutputFormat::Text,
+            CacheFormatArg::Json => CacheOutputFormat::Json,
+        },
+    }
+}
+
+pub(crate) fn resolve_forge_options(cmd: &ForgeCmd) -> ForgeOptions {
+    let (request, format) = match &cmd.action {
+        ForgeAction::Status { format } => (ForgeRequest::Status, *format),
+        ForgeAction::Issue { id, output, format } => (
+            ForgeRequest::Issue {
+                id: id.clone(),
+                output: output.clone(),
+            },
+            *format,
+        ),
+    };
+    ForgeOptions {
+        request,
+        format: match format {
+            ForgeFormatArg::Text => ForgeOutputFormat::Text,
+            ForgeFormatArg::Json => ForgeOutputFormat::Json,
+        },
+    }
+}
+
+pub(crate) fn resolve_tool_options(
+    cmd: &ToolCmd,
+) -> Result<ToolOptions, Box<dyn std::error::Error>> {
+    Ok(match &cmd.action {
+        ToolAction::File { path, lines } => ToolOptions::File {
+            path: path.clone(),
+            lines: lines
+                .as_deref()
+                .map(review_tools::parse_line_range)
+                .transpose()?,
+        },
+        ToolAction::Blame { path, lines } => ToolOptions::Blame {
+            path: path.clone(),
+            lines: review_tools::parse_line_range(lines)?,
+        },
+        ToolAction::Search { text, limit } => ToolOptions::Search {
+            text: text.clone(),
+            limit: (*limit).max(1),
+        },
+    })
+}
+
+pub(crate) fn resolve_ci_options(
+    cmd: &CiCmd,
+    config_file: Option<&str>,
+    profile: Option<&str>,
+) -> Result<CiOptions, Box<dyn std::error::Error>> {
+    let (request, artifacts_dir, format) = match &cmd.action {
+        CiAction::Review {
+            plan,
+            flow,
+            fail_on,
+            artifacts_dir,
+            format,
+        } => {
+            let spec = plan::PlanBranchSpec::resolve(Some(plan.as_str()), None, None)?;
+            let mut forwarded = Vec::new();
+            if let Some(path) = config_file {
+                forwarded.extend(["--config-file".to_string(), path.to_string()]);
+            }
+            if let Some(name) = profile {
+                forwarded.extend(["--profile".to_string(), name.to_string()]);
+            }
+            let fail_on = match fail_on {
+                CiFailOnArg::Blocker => Some(Severity::Blocker),
+                CiFailOnArg::Major => Some(Severity::Major),
+                CiFailOnArg::Minor => Some(Severity::Minor),
+                CiFailOnArg::Nit => Some(Severity::Nit),
+                CiFailOnArg::Any => Some(Severity::Unspecified),
+                CiFailOnArg::Never => None,
+            };
+            (
+                CiRequest::Review {
+                    slug: spec.slug,
+                    flow: flow.clone(),
+                    fail_on,
+                    forwarded,
+                },
+                artifacts_dir,
+                format,
+            )
+        }
+        CiAction::MergeQueue {
+            branch,
+            target,
+            artifacts_dir,
+            format,
+        } => (
+            CiRequest::MergeQueue {
+                branch: branch.clone(),
+                target: target.clone(),
+            },
+            artifacts_dir,
+            format,
+        ),
+    };
+    Ok(CiOptions {
+        request,
+        artifacts_dir: artifacts_dir.clone(),
+        format: match format {
+            CiFormatArg::Text => CiOutputFormat::Text,
+            CiFormatArg::Json => CiOutputFormat::Json,
+            CiFormatArg::Github => CiOutputFormat::Github,
+        },
+    })
+}
+
+pub(crate) fn resolve_config_import_ci_options(cmd: &ConfigCmd) -> ConfigImportCiOptions {
+    let ConfigAction::ImportCi { write, format } = &cmd.action;
+    ConfigImportCiOptions {
+        write: *write,
+        format: match format {
+            ConfigImportFormatArg::Text => ConfigImportOutputFormat::Text,
+            ConfigImportFormatArg::Json => ConfigImportOutputFormat::Json,
+            ConfigImportFormatArg::Toml => ConfigImportOutputFormat::Toml,
+        },
+    }
+}
+
+pub(crate) fn resolve_changelog_options(cmd: &ChangelogCmd) -> ChangelogOptions {
+    let ChangelogAction::Assemble {
+        release,
+        date,
+        dry_run,
+        no_commit,
+        format,
+    } = &cmd.action;
+    ChangelogOptions {
+        version: release.trim().to_string(),
+        date: date
+            .clone()
+            .unwrap_or_else(|| chrono::Local::now().format("%Y-%m-%d").to_string()),
+        dry_run: *dry_run,
+        commit: !*no_commit,
+        format: match format {
+            ChangelogFormatArg::Text => ChangelogOutputFormat::Text,
+            ChangelogFormatArg::Json => ChangelogOutputFormat::Json,
+        },
+    }
+}
+
+pub(crate) fn resolve_stats_options(cmd: &StatsCmd) -> StatsOptions {
+    StatsOptions {
+        since_days: cmd.since,
+        format: match cmd.format {
+            StatsFormatArg::Text => StatsOutputFormat::Text,
+            StatsFormatArg::Json => StatsOutputFormat::Json,
+        },
+    }
+}
+
+pub(crate) fn resolve_snapshot_options(cmd: &SnapshotCmd) -> SnapshotOptions {
+    let (request, format) = match &cmd.action {
+        SnapshotAction::Show { at, diff, format } => {
+            let request = match diff.as_deref() {
+                Some([from, to]) => SnapshotRequest::Diff {
+                    from: from.clone(),
+                    to: to.clone(),
+                },
+                _ => SnapshotRequest::Show {
+                    revision: at.clone(),
+                },
+            };
+            (request, format)
+        }
+        SnapshotAction::Rebuild {
+            force,
+            dry_run,
+            sessions,
+            format,
+            ..
+        } => (
+            SnapshotRequest::Rebuild {
+                force: *force,
+                dry_run: *dry_run,
+                sessions: *sessions,
+            },
+            format,
+        ),
+    };
+    let format = match format {
+        SnapshotFormatArg::Text => SnapshotOutputFormat::Text,
+        SnapshotFormatArg::Json => SnapshotOutputFormat::Json,
+    };
+    SnapshotOptions { request, format }
+}
+
+pub(crate) fn resolve_archive_options(
+    cmd: &ArchiveCmd,
+) -> Result<ArchiveOptions, Box<dyn std::error::Error>> {
+    let (request, format) = match &cmd.action {
+        ArchiveAction::List { format } => (ArchiveRequest::List, *format),
+        ArchiveAction::Show { plan, format } => {
+            let slug = plan::sanitize_name_override(plan).map_err(|err| {
+                Box::<dyn std::error::Error>::from(io::Error::new(io::ErrorKind::InvalidInput, err))
+            })?;
+            (ArchiveRequest::Show { slug }, *format)
+        }
+        ArchiveAction::Search {
+            query,
+            limit,
+            format,
+        } => (
+            ArchiveRequest::Search {
+                query: query.join(" "),
+                limit: *limit,
+            },
+            *format,
+        ),
+    };
+    let format = match format {
+        ArchiveFormatArg::Text => ArchiveOutputFormat::Text,
+        ArchiveFormatArg::Json => ArchiveOutputFormat::Json,
+    };
+    Ok(ArchiveOptions { request, format })
+}
+
+pub(crate) fn resolve_doctor_options(cmd: &DoctorCmd, config_file: Option<&str>) -> DoctorOptions {
+    DoctorOptions {
+        format: match cmd.format {
+            DoctorFormatArg::Text => DoctorOutputFormat::Text,
+            DoctorFormatArg::Json => DoctorOutputFormat::Json,
+        },
+        remote: !cmd.no_remote,
+        config_file: config_file.map(PathBuf::from),
+    }
+}
+
+pub(crate) fn resolve_plan_options(
+    cmd: &PlanCmd,
+    config_file: Option<&str>,
+    profile: Option<&str>,
+    no_session: bool,
+) -> PlanOptions {
+    let profile = profile
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .map(|name| (name, config::ConfigSourceKind::Cli))
+        .or_else(|| config::env_profile().map(|name| (name, config::ConfigSourceKind::Env)));
+    PlanOptions {
+        diff: cmd.diff,
+        format: if cmd.json {
+            PlanOutputFormat::Json
+        } else {
+            PlanOutputFormat::Text
+        },
+        config_file: config_file.map(PathBuf::from),
+        profile,
+        no_session,
+    }
+}
+
+pub(crate) fn resolve_search_options(
+    cmd: &SearchCmd,
+) -> Result<SearchOptions, Box<dyn std::error::Error>> {
+    let query = cmd.query.join(" ").trim().to_string();
+    if query.is_empty() {
+        return Err("search query cannot be empty".into());
+    }
+    let format = match cmd.format {
+        SearchFormatArg::Text => SearchOutputFormat::Text,
+        SearchFormatArg::Json => SearchOutputFormat::Json,
+    };
+
+    Ok(SearchOptions {
+        query,
+        limit: cmd.limit,
+        format,
+    })
+}
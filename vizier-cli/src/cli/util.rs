@@ -90,6 +90,9 @@ pub(crate) fn run_flow_help_target(args: &[String]) -> Option<String> {
             if run_option_with_value(token)
                 || is_option_with_value(token, "--load-session")
                 || is_option_with_value(token, "--config-file")
+                || is_option_with_value(token, "--log")
+                || is_option_with_value(token, "--profile")
+                || is_option_with_value(token, "--worktree-dir")
                 || is_short_option_with_value(token, 'l')
                 || is_short_option_with_value(token, 'C')
             {
@@ -154,11 +157,18 @@ pub(crate) fn normalize_run_invocation_args(args: &[String]) -> Vec<String> {
 
         if is_option_with_value(token, "--set")
             || is_option_with_value(token, "--after")
+            || is_option_with_value(token, "--context")
+            || is_option_with_value(token, "--baseline")
             || is_option_with_value(token, "--spec-dir")
             || is_option_with_value(token, "--repeat")
+            || is_option_with_value(token, "--priority")
+            || is_option_with_value(token, "--max-cost")
             || is_option_with_value(token, "--format")
             || is_option_with_value(token, "--load-session")
             || is_option_with_value(token, "--config-file")
+            || is_option_with_value(token, "--profile")
+            || is_option_with_value(token, "--worktree-dir")
+            || is_option_with_value(token, "--log")
             || is_short_option_with_value(token, 'l')
             || is_short_option_with_value(token, 'C')
         {
@@ -176,6 +186,14 @@ pub(crate) fn normalize_run_invocation_args(args: &[String]) -> Vec<String> {
             || is_flag_option(token, "--ephemeral")
             || is_flag_option(token, "--follow")
             || is_flag_option(token, "--check")
+            || is_flag_option(token, "--strict")
+            || is_flag_option(token, "--strict-overlap")
+            || is_flag_option(token, "--remote")
+            || is_flag_option(token, "--patch")
+            || is_flag_option(token, "--yes")
+            || is_flag_option(token, "--no-gen-tests")
+            || is_flag_option(token, "--fix-loop")
+            || is_flag_option(token, "--edit-plan")
             || is_flag_option(token, "--verbose")
             || is_flag_option(token, "--quiet")
             || is_flag_option(token, "--debug")
@@ -220,8 +238,12 @@ pub(crate) fn normalize_run_invocation_args(args: &[String]) -> Vec<String> {
 fn run_option_with_value(token: &str) -> bool {
     is_option_with_value(token, "--set")
         || is_option_with_value(token, "--after")
+        || is_option_with_value(token, "--context")
+        || is_option_with_value(token, "--baseline")
         || is_option_with_value(token, "--spec-dir")
         || is_option_with_value(token, "--repeat")
+        || is_option_with_value(token, "--priority")
+        || is_option_with_value(token, "--max-cost")
         || is_option_with_value(token, "--format")
 }
 
@@ -231,6 +253,14 @@ fn run_flag_option(token: &str) -> bool {
         || is_flag_option(token, "--ephemeral")
         || is_flag_option(token, "--follow")
         || is_flag_option(token, "--check")
+        || is_flag_option(token, "--strict")
+        || is_flag_option(token, "--strict-overlap")
+        || is_flag_option(token, "--remote")
+        || is_flag_option(token, "--patch")
+        || is_flag_option(token, "--yes")
+        || is_flag_option(token, "--no-gen-tests")
+        || is_flag_option(token, "--fix-loop")
+        || is_flag_option(token, "--edit-plan")
         || is_flag_option(token, "--verbose")
         || is_flag_option(token, "--quiet")
         || is_flag_option(token, "--debug")
@@ -253,6 +283,9 @@ fn find_run_subcommand_index(args: &[String]) -> Option<usize> {
 
         if is_option_with_value(token, "--load-session")
             || is_option_with_value(token, "--config-file")
+            || is_option_with_value(token, "--profile")
+            || is_option_with_value(token, "--worktree-dir")
+            || is_option_with_value(token, "--log")
             || is_short_option_with_value(token, 'l')
             || is_short_option_with_value(token, 'C')
         {
@@ -420,6 +453,10 @@ mod tests {
             "draft".to_string(),
             "--after".to_string(),
             "job-123".to_string(),
+            "--context".to_string(),
+            "docs/design.md".to_string(),
+            "--baseline".to_string(),
+            "abc1234".to_string(),
             "--spec-dir".to_string(),
             "specs".to_string(),
             "--repeat".to_string(),
@@ -446,6 +483,34 @@ mod tests {
         assert_eq!(normalize_run_invocation_args(&args), args);
     }
 
+    #[test]
+    fn normalize_run_preserves_global_log_filters() {
+        let args = vec![
+            "vizier".to_string(),
+            "--log".to_string(),
+            "git=warn".to_string(),
+            "run".to_string(),
+            "draft".to_string(),
+            "--log".to_string(),
+            "agent=debug".to_string(),
+        ];
+
+        assert_eq!(normalize_run_invocation_args(&args), args);
+    }
+
+    #[test]
+    fn normalize_run_preserves_global_worktree_dir() {
+        let args = vec![
+            "vizier".to_string(),
+            "run".to_string(),
+            "draft".to_string(),
+            "--worktree-dir".to_string(),
+            "/var/tmp/wt".to_string(),
+        ];
+
+        assert_eq!(normalize_run_invocation_args(&args), args);
+    }
+
     #[test]
     fn normalize_run_preserves_check_flag() {
         let args = vec![
@@ -458,6 +523,100 @@ mod tests {
         assert_eq!(normalize_run_invocation_args(&args), args);
     }
 
+    #[test]
+    fn normalize_run_preserves_strict_flag() {
+        let args = vec![
+            "vizier".to_string(),
+            "run".to_string(),
+            "approve".to_string(),
+            "alpha".to_string(),
+            "--strict".to_string(),
+        ];
+
+        assert_eq!(normalize_run_invocation_args(&args), args);
+    }
+
+    #[test]
+    fn normalize_run_preserves_strict_overlap_flag() {
+        let args = vec![
+            "vizier".to_string(),
+            "run".to_string(),
+            "approve".to_string(),
+            "alpha".to_string(),
+            "--strict-overlap".to_string(),
+        ];
+
+        assert_eq!(normalize_run_invocation_args(&args), args);
+    }
+
+    #[test]
+    fn normalize_run_preserves_cost_flags() {
+        let args = vec![
+            "vizier".to_string(),
+            "run".to_string(),
+            "approve".to_string(),
+            "alpha".to_string(),
+            "--max-cost".to_string(),
+            "2.50".to_string(),
+            "--yes".to_string(),
+        ];
+
+        assert_eq!(normalize_run_invocation_args(&args), args);
+    }
+
+    #[test]
+    fn normalize_run_preserves_no_gen_tests_flag() {
+        let args = vec![
+            "vizier".to_string(),
+            "run".to_string(),
+            "approve".to_string(),
+            "alpha".to_string(),
+            "--no-gen-tests".to_string(),
+        ];
+
+        assert_eq!(normalize_run_invocation_args(&args), args);
+    }
+
+    #[test]
+    fn normalize_run_preserves_fix_loop_flag() {
+        let args = vec![
+            "vizier".to_string(),
+            "run".to_string(),
+            "review".to_string(),
+            "alpha".to_string(),
+            "--fix-loop".to_string(),
+        ];
+
+        assert_eq!(normalize_run_invocation_args(&args), args);
+    }
+
+    #[test]
+    fn normalize_run_preserves_edit_plan_flag() {
+        let args = vec![
+            "vizier".to_string(),
+            "run".to_string(),
+            "draft".to_string(),
+            "spec.md".to_string(),
+            "--edit-plan".to_string(),
+            "--follow".to_string(),
+        ];
+
+        assert_eq!(normalize_run_invocation_args(&args), args);
+    }
+
+    #[test]
+    fn normalize_run_preserves_remote_flag() {
+        let args = vec![
+            "vizier".to_string(),
+            "run".to_string(),
+            "approve".to_string(),
+            "alpha".to_string(),
+            "--remote".to_string(),
+        ];
+
+        assert_eq!(normalize_run_invocation_args(&args), args);
+    }
+
     #[test]
     fn normalize_run_preserves_spec_dir_equals_form() {
         let args = vec![
@@ -535,4 +694,23 @@ mod tests {
             Some("config/alt.toml".to_string())
         );
     }
+
+    #[test]
+    fn run_flow_help_target_skips_profile_values() {
+        let args = vec![
+            "vizier".to_string(),
+            "--profile".to_string(),
+            "run".to_string(),
+            "run".to_string(),
+            "--profile".to_string(),
+            "draft".to_string(),
+            "approve".to_string(),
+            "--help".to_string(),
+        ];
+        assert_eq!(run_flow_help_target(&args), Some("approve".to_string()));
+        assert_eq!(
+            global_option_value(&args, None, "--profile"),
+            Some("draft".to_string())
+        );
+    }
 }
@@ -1,4 +1,5 @@
 mod actions;
+mod ci_action;
 mod cli;
 mod completions;
 mod jobs;
@@ -6,6 +7,7 @@ mod man;
 mod plan;
 mod workflow_templates;
 
+pub use ci_action::generate_ci_action;
 pub use man::generate_man_pages;
 
 pub async fn run_cli() -> Result<(), Box<dyn std::error::Error>> {
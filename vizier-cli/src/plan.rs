@@ -11,7 +11,8 @@ use uuid::Uuid;
 
 use crate::jobs;
 use vizier_core::vcs::{
-    add_worktree_for_branch, branch_exists, detect_primary_branch, remove_worktree, repo_root,
+    ExternalWorktree, add_worktree_for_branch, branch_exists, detect_primary_branch,
+    remove_worktree, repo_root,
 };
 
 pub const PLAN_DIR: &str = ".vizier/implementation-plans";
@@ -40,7 +41,7 @@ fn legacy_plan_id_from_slug(slug: &str) -> String {
 }
 
 pub fn default_branch_for_slug(slug: &str) -> String {
-    format!("draft/{slug}")
+    vizier_core::plan::default_branch_for_slug(slug)
 }
 
 #[derive(Debug, Clone)]
@@ -97,6 +98,7 @@ impl PlanBranchSpec {
 pub struct PlanWorktree {
     pub name: String,
     pub path: PathBuf,
+    owned: bool,
 }
 
 impl PlanWorktree {
@@ -108,7 +110,7 @@ impl PlanWorktree {
         let repo_root = repo_root()
             .map_err(|err| -> Box<dyn std::error::Error> { Box::new(err) })?
             .to_path_buf();
-        let tmp_root = repo_root.join(".vizier/tmp-worktrees");
+        let tmp_root = vizier_core::worktrees::worktrees_root(&repo_root);
         fs::create_dir_all(&tmp_root)?;
 
         let suffix = short_suffix();
@@ -123,9 +125,23 @@ impl PlanWorktree {
         Ok(Self {
             name: worktree_name,
             path: worktree_path,
+            owned: true,
         })
     }
 
+    /// Works in a worktree the operator created outside Vizier; `cleanup` leaves it in place.
+    pub fn adopt(existing: ExternalWorktree) -> Self {
+        Self {
+            name: existing.name,
+            path: existing.path,
+            owned: false,
+        }
+    }
+
+    pub fn is_owned(&self) -> bool {
+        self.owned
+    }
+
     pub fn plan_path(&self, slug: &str) -> PathBuf {
         self.path.join(plan_rel_path(slug))
     }
@@ -135,6 +151,9 @@ impl PlanWorktree {
     }
 
     pub fn cleanup(self) -> Result<(), Box<dyn std::error::Error>> {
+        if !self.owned {
+            return Ok(());
+        }
         remove_worktree(&self.name, true)
             .map_err(|err| -> Box<dyn std::error::Error> { Box::new(err) })?;
         if self.path.exists() {
@@ -359,6 +378,12 @@ pub struct PlanMetadata {
     pub branch: String,
     pub spec_excerpt: Option<String>,
     pub spec_summary: Option<String>,
+    /// Narrative threads the plan serves, from the `threads:` front-matter cross-links.
+    pub threads: Vec<String>,
+    /// Triage labels from the `labels:` front matter.
+    pub labels: Vec<String>,
+    /// The plan this one was filed as a follow-up of, from `spawned-by:`.
+    pub spawned_by: Option<String>,
 }
 
 impl PlanMetadata {
@@ -369,10 +394,7 @@ impl PlanMetadata {
         let slug = if let Some(value) = fields.get("plan") {
             value.to_string()
         } else if let Some(branch) = fields.get("branch") {
-            branch
-                .strip_prefix("draft/")
-                .map(|value| value.to_string())
-                .ok_or(PlanError::MissingField("plan"))?
+            vizier_core::plan::slug_from_branch(branch).ok_or(PlanError::MissingField("plan"))?
         } else {
             return Err(PlanError::MissingField("plan"));
         };
@@ -387,6 +409,20 @@ impl PlanMetadata {
 
         let spec_excerpt = extract_section(body, "Operator Spec");
         let spec_summary = spec_excerpt.as_ref().and_then(|text| summarize_line(text));
+        let threads = fields
+            .get("threads")
+            .or_else(|| fields.get("thread"))
+            .map(|value| vizier_core::plan::parse_thread_links(value))
+            .unwrap_or_default();
+        let labels = fields
+            .get("labels")
+            .or_else(|| fields.get("label"))
+            .map(|value| vizier_core::plan::parse_labels(value))
+            .unwrap_or_default();
+        let spawned_by = fields
+            .get("spawned-by")
+            .or_else(|| fields.get("spawned_by"))
+            .and_then(|value| vizier_core::plan::parse_spawned_by(value));
 
         Ok(Self {
             plan_id,
@@ -394,6 +430,9 @@ impl PlanMetadata {
             branch,
             spec_excerpt,
             spec_summary,
+            threads,
+            labels,
+            spawned_by,
         })
     }
 }
@@ -451,6 +490,36 @@ pub fn plan_id_from_document(contents: &str) -> Option<String> {
     fields.get("plan_id").cloned()
 }
 
+/// Rewrites front-matter `fields` in place, appending keys the document does not carry yet.
+/// The plan body is left byte-for-byte untouched.
+pub fn set_front_matter_fields(
+    document: &str,
+    fields: &[(&str, &str)],
+) -> Result<String, PlanError> {
+    let (front_matter, body) = split_front_matter(document)?;
+    let mut pending = fields.to_vec();
+    let mut out = String::from("---\n");
+    for line in front_matter.lines() {
+        let key = line.split_once(':').map(|(key, _)| key.trim());
+        match key.and_then(|key| pending.iter().position(|(field, _)| *field == key)) {
+            Some(idx) => {
+                let (field, value) = pending.remove(idx);
+                out.push_str(&format!("{field}: {value}\n"));
+            }
+            None => {
+                out.push_str(line.trim_end_matches('\r'));
+                out.push('\n');
+            }
+        }
+    }
+    for (field, value) in pending {
+        out.push_str(&format!("{field}: {value}\n"));
+    }
+    out.push_str("---\n");
+    out.push_str(body);
+    Ok(out)
+}
+
 pub fn upsert_plan_record(
     repo_root: &Path,
     update: PlanRecordUpsert,
@@ -570,6 +639,10 @@ pub struct PlanSlugEntry {
     pub slug: String,
     pub branch: String,
     pub summary: String,
+    /// Labels from the plan document at the branch tip.
+    pub labels: Vec<String>,
+    /// Parent plan from the branch tip's `spawned-by:` front matter.
+    pub spawned_by: Option<String>,
 }
 
 pub struct PlanSlugInventory;
@@ -602,15 +675,11 @@ impl PlanSlugInventory {
                 .or(record.work_ref.as_ref())
                 .cloned()
                 .unwrap_or_default();
-            if branch.is_empty() || !branch.starts_with("draft/") {
+            let Some(branch_slug) = vizier_core::plan::slug_from_branch(&branch) else {
                 continue;
-            }
+            };
 
-            let slug = record
-                .slug
-                .clone()
-                .or_else(|| branch.strip_prefix("draft/").map(|value| value.to_string()))
-                .unwrap_or_default();
+            let slug = record.slug.clone().unwrap_or(branch_slug);
             if slug.is_empty() {
                 continue;
             }
@@ -644,6 +713,8 @@ impl PlanSlugInventory {
                 slug,
                 branch,
                 summary,
+                labels: Vec::new(),
+                spawned_by: None,
             });
         }
 
@@ -671,10 +742,9 @@ impl PlanSlugInventory {
             let Some(name) = branch.name()? else {
                 continue;
             };
-            if !name.starts_with("draft/") {
+            let Some(slug) = vizier_core::plan::slug_from_branch(name) else {
                 continue;
-            }
-            let slug = name.trim_start_matches("draft/").to_string();
+            };
             if seen_slugs.contains(&slug) {
                 continue;
             }
@@ -697,12 +767,22 @@ impl PlanSlugInventory {
                         slug: meta.slug.clone(),
                         branch: meta.branch.clone(),
                         summary,
+                        labels: Vec::new(),
+                        spawned_by: None,
                     });
                 }
                 Err(_) => continue,
             }
         }
 
+        // Labels and lineage are read from the branch tip, where `vizier label` commits them.
+        for entry in &mut entries {
+            if let Ok(meta) = load_plan_from_branch(&entry.slug, &entry.branch) {
+                entry.labels = meta.labels;
+                entry.spawned_by = meta.spawned_by;
+            }
+        }
+
         entries.sort_by(|a, b| a.slug.cmp(&b.slug));
         Ok(entries)
     }
@@ -750,7 +830,10 @@ impl PlanSlugInventory {
             Err(_) => return Ok(None),
         };
 
-        if meta.slug.is_empty() || meta.branch.is_empty() || !meta.branch.starts_with("draft/") {
+        if meta.slug.is_empty()
+            || meta.branch.is_empty()
+            || vizier_core::plan::slug_from_branch(&meta.branch).is_none()
+        {
             return Ok(None);
         }
 
@@ -773,6 +856,8 @@ impl PlanSlugInventory {
             slug: meta.slug.clone(),
             branch: meta.branch.clone(),
             summary,
+            labels: Vec::new(),
+            spawned_by: None,
         }))
     }
 }
@@ -910,7 +995,7 @@ fn parse_front_matter(front: &str) -> HashMap<String, String> {
     fields
 }
 
-fn extract_section(document: &str, header: &str) -> Option<String> {
+pub(crate) fn extract_section(document: &str, header: &str) -> Option<String> {
     let needle = format!("## {header}");
     let start = document.find(&needle)?;
     let after_header = &document[start + needle.len()..];
@@ -989,6 +1074,25 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn set_front_matter_fields_updates_and_appends() -> Result<(), Box<dyn std::error::Error>> {
+        let doc = render_plan_document("pln_alpha", "alpha", "draft/alpha", "spec", "plan");
+        let updated = set_front_matter_fields(&doc, &[("base_branch", "main")])?;
+        let updated = set_front_matter_fields(
+            &updated,
+            &[("base_branch", "trunk"), ("base_commit", "abc123")],
+        )?;
+        assert!(updated.starts_with(
+            "---\nplan_id: pln_alpha\nplan: alpha\nbranch: draft/alpha\nbase_branch: trunk\nbase_commit: abc123\n---\n"
+        ));
+        assert!(updated.ends_with(doc.split_once("\n---\n").unwrap().1));
+        assert_eq!(
+            plan_id_from_document(&updated).as_deref(),
+            Some("pln_alpha")
+        );
+        Ok(())
+    }
+
     #[test]
     fn ensure_unique_slug_skips_existing() -> Result<(), Box<dyn std::error::Error>> {
         let dir = tempdir()?;
@@ -1037,6 +1141,9 @@ Add streaming UI with guard rails.
             branch: "draft/alpha".into(),
             spec_excerpt: Some("Line one\nLine two".into()),
             spec_summary: None,
+            threads: Vec::new(),
+            labels: Vec::new(),
+            spawned_by: None,
         };
 
         assert_eq!(summarize_spec(&meta), "Line one\nLine two".to_string());
@@ -1073,6 +1180,38 @@ Unknown fields should be ignored.
         Ok(())
     }
 
+    #[test]
+    fn from_document_reads_thread_cross_links() -> Result<(), Box<dyn std::error::Error>> {
+        let doc = render_plan_document("pln_alpha", "alpha", "draft/alpha", "spec", "plan");
+        assert!(PlanMetadata::from_document(&doc)?.threads.is_empty());
+
+        let linked = set_front_matter_fields(
+            &doc,
+            &[(
+                "threads",
+                "[executor_first, .vizier/narrative/threads/jobs_ops.md, executor_first]",
+            )],
+        )?;
+        assert_eq!(
+            PlanMetadata::from_document(&linked)?.threads,
+            vec!["executor_first".to_string(), "jobs_ops".to_string()]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn from_document_reads_labels() -> Result<(), Box<dyn std::error::Error>> {
+        let doc = render_plan_document("pln_alpha", "alpha", "draft/alpha", "spec", "plan");
+        assert!(PlanMetadata::from_document(&doc)?.labels.is_empty());
+
+        let labeled = set_front_matter_fields(&doc, &[("labels", "[Backend, urgent, bad label]")])?;
+        assert_eq!(
+            PlanMetadata::from_document(&labeled)?.labels,
+            vec!["backend".to_string(), "urgent".to_string()]
+        );
+        Ok(())
+    }
+
     #[test]
     fn slug_inventory_lists_pending_slugs_sorted() -> Result<(), Box<dyn std::error::Error>> {
         let (_tmp, _guard, repo) = initialize_repo()?;
@@ -5,6 +5,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use vizier_core::worktrees;
 
 const WORKSPACE_DIR_PREFIX: &str = "workspace-";
 const WORKTREE_NAME_PREFIX: &str = "vizier-workspace-";
@@ -69,7 +70,9 @@ pub struct WorkspaceStore {
 
 impl WorkspaceStore {
     pub fn load(repo_root: &Path) -> Result<Self, Box<dyn std::error::Error>> {
-        let tmp_root = repo_root.join(".vizier/tmp-worktrees");
+        // The manifest stays in the repository so workspaces created before `[worktrees] dir`
+        // changed are still found.
+        let tmp_root = repo_root.join(worktrees::DEFAULT_WORKTREES_DIR);
         fs::create_dir_all(&tmp_root)?;
         let manifest_path = tmp_root.join(MANIFEST_FILE);
         let manifest = WorkspaceManifest::from_path(&manifest_path)?;
@@ -132,6 +135,9 @@ impl WorkspaceStore {
             .into());
         }
 
+        if let Some(parent) = expected_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
         add_worktree(&repo, &expected_worktree, &expected_path, branch)?;
 
         let status = WorkspaceStatus {
@@ -216,9 +222,13 @@ impl WorkspaceStore {
             }
         }
 
-        let tmp_root = self.repo_root.join(".vizier/tmp-worktrees");
-        if tmp_root.exists() {
-            for entry in fs::read_dir(&tmp_root)? {
+        let mut roots = vec![worktrees::worktrees_root(&self.repo_root)];
+        let legacy_root = self.repo_root.join(worktrees::DEFAULT_WORKTREES_DIR);
+        if !roots.contains(&legacy_root) {
+            roots.push(legacy_root);
+        }
+        for tmp_root in roots.iter().filter(|root| root.exists()) {
+            for entry in fs::read_dir(tmp_root)? {
                 let entry = entry?;
                 if !entry.file_type()?.is_dir() {
                     continue;
@@ -369,9 +379,7 @@ impl WorkspaceStore {
 }
 
 pub fn workspace_path(repo_root: &Path, slug: &str) -> PathBuf {
-    repo_root
-        .join(".vizier/tmp-worktrees")
-        .join(format!("{WORKSPACE_DIR_PREFIX}{slug}"))
+    worktrees::worktrees_root(repo_root).join(format!("{WORKSPACE_DIR_PREFIX}{slug}"))
 }
 
 pub fn workspace_worktree_name(slug: &str) -> String {
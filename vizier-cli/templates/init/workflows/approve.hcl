@@ -11,7 +11,9 @@ cli = {
 params = {
   branch = ""
   commit_message = "chore: workflow stage commit"
+  only = ""
   slug = ""
+  stop_condition_conditions = ""
   stop_condition_retries = "3"
   stop_condition_script = ""
 }
@@ -70,6 +72,9 @@ nodes = [
     kind = "agent"
     uses = "cap.agent.invoke"
     needs = [{ custom = { type_id = "prompt_text", key = "approve_main" } }]
+    args = {
+      only = "$${only}"
+    }
     on = {
       succeeded = ["stage_files"]
     }
@@ -106,6 +111,9 @@ nodes = [
     name = "Approve / Stop Gate"
     kind = "gate"
     uses = "control.gate.stop_condition"
+    args = {
+      conditions = "$${stop_condition_conditions}"
+    }
     gates = [
       {
         kind = "script"
@@ -119,10 +127,24 @@ nodes = [
     }
     after = [{ node_id = "stage_commit" }]
     on = {
-      succeeded = ["worktree_cleanup"]
+      succeeded = ["generate_tests"]
       failed = ["stage_commit"]
     }
   },
+  {
+    id = "generate_tests"
+    name = "Approve / Generate Tests"
+    kind = "agent"
+    uses = "cap.agent.generate_tests"
+    args = {
+      branch = "$${branch}"
+      slug = "$${slug}"
+    }
+    after = [{ node_id = "stop_gate" }]
+    on = {
+      succeeded = ["worktree_cleanup"]
+    }
+  },
   {
     id = "worktree_cleanup"
     name = "Approve / Cleanup Worktree"
@@ -131,7 +153,7 @@ nodes = [
     on = {
       succeeded = ["terminal"]
     }
-    after = [{ node_id = "stop_gate" }]
+    after = [{ node_id = "generate_tests" }]
   },
   {
     id = "terminal"
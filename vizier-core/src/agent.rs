@@ -5,6 +5,10 @@ use std::{
     future::Future,
     path::{Path, PathBuf},
     pin::Pin,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
     time::Duration,
 };
 
@@ -12,7 +16,7 @@ use std::process::Stdio;
 
 use tokio::{
     io::{self, AsyncBufRead, AsyncBufReadExt},
-    sync::mpsc,
+    sync::{mpsc, oneshot},
 };
 
 use tokio::{
@@ -21,9 +25,15 @@ use tokio::{
     time,
 };
 
+use sha2::{Digest, Sha256};
+
 use crate::{
+    agent_env, agent_protocol,
+    auditor::Auditor,
+    command_audit::{self, AuditDecision},
     config,
     display::{self, ProgressEvent, ProgressKind, Status},
+    interrupt,
 };
 pub use vizier_kernel::prompt::{ReviewCheckContext, ReviewGateContext, ReviewGateStatus};
 
@@ -41,6 +51,7 @@ pub struct AgentRequest {
     pub scope: Option<config::CommandScope>,
     pub metadata: BTreeMap<String, String>,
     pub timeout: Option<Duration>,
+    pub env: config::AgentEnvSettings,
 }
 
 impl AgentRequest {
@@ -55,6 +66,7 @@ impl AgentRequest {
             scope: None,
             metadata: BTreeMap::new(),
             timeout: None,
+            env: config::AgentEnvSettings::default(),
         }
     }
 }
@@ -94,8 +106,13 @@ pub enum AgentError {
     Io(std::io::Error),
     NonZeroExit(i32, Vec<String>),
     Timeout(u64),
+    /// Stopped after this many seconds without a line on stdout or stderr.
+    Stalled(u64),
+    Interrupted,
+    CommandAborted(String),
     BoundsRead(PathBuf, std::io::Error),
     MissingPrompt(config::PromptKind),
+    Env(String),
 }
 
 impl fmt::Display for AgentError {
@@ -117,6 +134,16 @@ impl fmt::Display for AgentError {
             AgentError::Timeout(secs) => {
                 write!(f, "agent command exceeded timeout after {secs}s")
             }
+            AgentError::Stalled(secs) => {
+                write!(f, "agent command was stopped after {secs}s without output")
+            }
+            AgentError::Interrupted => write!(f, "agent command was interrupted"),
+            AgentError::CommandAborted(command) => {
+                write!(
+                    f,
+                    "agent run was aborted after it started command `{command}`"
+                )
+            }
             AgentError::BoundsRead(path, err) => {
                 write!(
                     f,
@@ -132,6 +159,7 @@ impl fmt::Display for AgentError {
                     kind.as_str()
                 )
             }
+            AgentError::Env(message) => write!(f, "{message}"),
         }
     }
 }
@@ -169,6 +197,74 @@ pub trait AgentRunner: Send + Sync {
 
 pub struct ScriptRunner;
 
+const STALL_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// When any agent or progress-filter stream last produced a line; shared by the stream readers
+/// and the stall watchdog.
+#[derive(Clone)]
+struct StreamActivity {
+    start: Instant,
+    last_ms: Arc<AtomicU64>,
+}
+
+impl StreamActivity {
+    fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            last_ms: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    fn touch(&self) {
+        let elapsed = u64::try_from(self.start.elapsed().as_millis()).unwrap_or(u64::MAX);
+        self.last_ms.store(elapsed, Ordering::Relaxed);
+    }
+
+    fn idle(&self) -> Duration {
+        self.start
+            .elapsed()
+            .saturating_sub(Duration::from_millis(self.last_ms.load(Ordering::Relaxed)))
+    }
+}
+
+/// Warns (on stderr and in the session log) each time the agent's silence grows by another
+/// `warn_after_secs`, and resolves with the silence once it reaches `kill_after_secs`. Never
+/// resolves when no kill threshold is set.
+async fn watch_for_stall(
+    activity: StreamActivity,
+    stall: config::AgentStallConfig,
+    source: String,
+) -> u64 {
+    let mut warned = 0;
+    loop {
+        time::sleep(STALL_POLL_INTERVAL).await;
+        let idle = activity.idle().as_secs();
+        if stall.kill_after_secs > 0 && idle >= stall.kill_after_secs {
+            display::warn(format!(
+                "{source} stalled for {idle}s with no output; stopping it"
+            ));
+            Auditor::record_operation(
+                "agent_stall",
+                serde_json::json!({"agent": source, "idle_secs": idle, "stopped": true}),
+            );
+            return idle;
+        }
+        if stall.warn_after_secs == 0 {
+            continue;
+        }
+        let intervals = idle / stall.warn_after_secs;
+        if intervals > warned {
+            let secs = intervals * stall.warn_after_secs;
+            display::warn(format!("{source} stalled for {secs}s with no output"));
+            Auditor::record_operation(
+                "agent_stall",
+                serde_json::json!({"agent": source, "idle_secs": secs, "stopped": false}),
+            );
+        }
+        warned = intervals;
+    }
+}
+
 impl ScriptRunner {
     #[cfg(unix)]
     fn should_use_stdbuf() -> bool {
@@ -211,10 +307,14 @@ impl ScriptRunner {
         }
     }
 
+    // Each child leads its own process group so an interrupt can take down wrappers and
+    // anything the agent spawned along with it.
     fn configure_stdio(cmd: &mut Command) {
         cmd.stdin(Stdio::piped());
         cmd.stdout(Stdio::piped());
         cmd.stderr(Stdio::piped());
+        #[cfg(unix)]
+        cmd.process_group(0);
     }
 
     fn render_source(
@@ -246,13 +346,15 @@ impl ScriptRunner {
         source: String,
         progress_hook: Option<ProgressHook>,
         capture_raw: bool,
+        activity: StreamActivity,
     ) -> io::Result<(Vec<String>, String)> {
         let mut lines = Vec::new();
         let mut raw = String::new();
-        let verbosity = display::get_display_config().verbosity;
+        let verbosity = display::get_display_config().verbosity_for(display::Subsystem::Agent);
 
         let mut stream = reader.lines();
         while let Some(line) = stream.next_line().await? {
+            activity.touch();
             if capture_raw {
                 raw.push_str(&line);
                 raw.push('\n');
@@ -306,8 +408,10 @@ impl ScriptRunner {
         reader: impl AsyncBufRead + Unpin,
         source: String,
         progress_hook: Option<ProgressHook>,
+        activity: StreamActivity,
     ) -> io::Result<Vec<String>> {
-        let (lines, _) = Self::read_progress_stream(reader, source, progress_hook, false).await?;
+        let (lines, _) =
+            Self::read_progress_stream(reader, source, progress_hook, false, activity).await?;
         Ok(lines)
     }
 
@@ -315,17 +419,12 @@ impl ScriptRunner {
         reader: impl AsyncBufRead + Unpin,
         source: String,
         progress_hook: Option<ProgressHook>,
+        activity: StreamActivity,
     ) -> io::Result<(Vec<String>, String)> {
-        Self::read_progress_stream(reader, source, progress_hook, true).await
-    }
-}
-
-impl AgentRunner for ScriptRunner {
-    fn backend_name(&self) -> &'static str {
-        "script"
+        Self::read_progress_stream(reader, source, progress_hook, true, activity).await
     }
 
-    fn execute(&self, request: AgentRequest, progress_hook: Option<ProgressHook>) -> AgentFuture {
+    fn execute_once(request: AgentRequest, progress_hook: Option<ProgressHook>) -> AgentFuture {
         Box::pin(async move {
             if mock_agent_enabled() {
                 if std::env::var("VIZIER_FORCE_AGENT_ERROR")
@@ -369,6 +468,12 @@ impl AgentRunner for ScriptRunner {
                 });
             }
 
+            let env = agent_env::resolve_agent_env(&request.env, &request.repo_root)
+                .map_err(AgentError::Env)?;
+            if !env.is_empty() {
+                Auditor::record_agent_env(env.redacted());
+            }
+
             let mut spawn_warnings = Vec::new();
             let (program, base_args) = request
                 .command
@@ -378,7 +483,12 @@ impl AgentRunner for ScriptRunner {
             let mut command = Command::new(program);
             command.args(base_args);
             command.current_dir(&request.repo_root);
+            command.envs(&env.vars);
             Self::configure_stdio(&mut command);
+            command.env(
+                agent_protocol::PROTOCOL_ENV,
+                agent_protocol::PROTOCOL_VERSION.to_string(),
+            );
 
             #[cfg(unix)]
             let allow_script_wrapper = {
@@ -407,7 +517,12 @@ impl AgentRunner for ScriptRunner {
                         wrapped.args(&wrapper[1..]);
                         wrapped.args(&request.command);
                         wrapped.current_dir(&request.repo_root);
+                        wrapped.envs(&env.vars);
                         Self::configure_stdio(&mut wrapped);
+                        wrapped.env(
+                            agent_protocol::PROTOCOL_ENV,
+                            agent_protocol::PROTOCOL_VERSION.to_string(),
+                        );
                         match wrapped.spawn() {
                             Ok(child) => {
                                 spawned = Some(child);
@@ -463,6 +578,7 @@ impl AgentRunner for ScriptRunner {
             };
 
             let start = Instant::now();
+            let activity = StreamActivity::new();
             let source = request
                 .metadata
                 .get("agent_label")
@@ -480,8 +596,10 @@ impl AgentRunner for ScriptRunner {
             let source_for_stderr = source.clone();
             let stderr_handle = if let Some(stderr) = child.stderr.take() {
                 let hook = progress_hook.clone();
+                let activity = activity.clone();
                 Some(tokio::spawn(async move {
-                    Self::read_stderr(BufReader::new(stderr), source_for_stderr, hook).await
+                    Self::read_stderr(BufReader::new(stderr), source_for_stderr, hook, activity)
+                        .await
                 }))
             } else {
                 None
@@ -507,6 +625,10 @@ impl AgentRunner for ScriptRunner {
                 filter.args(filter_args);
                 filter.current_dir(&request.repo_root);
                 Self::configure_stdio(&mut filter);
+                filter.env(
+                    agent_protocol::PROTOCOL_ENV,
+                    agent_protocol::PROTOCOL_VERSION.to_string(),
+                );
 
                 #[cfg(unix)]
                 let mut spawned_filter = {
@@ -518,6 +640,10 @@ impl AgentRunner for ScriptRunner {
                             wrapped.args(filter_cmd.clone());
                             wrapped.current_dir(&request.repo_root);
                             Self::configure_stdio(&mut wrapped);
+                            wrapped.env(
+                                agent_protocol::PROTOCOL_ENV,
+                                agent_protocol::PROTOCOL_VERSION.to_string(),
+                            );
                             match wrapped.spawn() {
                                 Ok(child) => {
                                     spawned = Some(child);
@@ -568,16 +694,25 @@ impl AgentRunner for ScriptRunner {
                 let filter_source = source.clone();
                 if let Some(stdout) = spawned_filter.stdout.take() {
                     let hook = progress_hook.clone();
+                    let activity = activity.clone();
                     filter_stdout_handle = Some(tokio::spawn(async move {
-                        Self::read_filter_stdout(BufReader::new(stdout), filter_source, hook).await
+                        Self::read_filter_stdout(
+                            BufReader::new(stdout),
+                            filter_source,
+                            hook,
+                            activity,
+                        )
+                        .await
                     }));
                 }
 
                 if let Some(stderr) = spawned_filter.stderr.take() {
                     let hook = progress_hook.clone();
                     let filter_source_err = source.clone();
+                    let activity = activity.clone();
                     filter_stderr_handle = Some(tokio::spawn(async move {
-                        Self::read_stderr(BufReader::new(stderr), filter_source_err, hook).await
+                        Self::read_stderr(BufReader::new(stderr), filter_source_err, hook, activity)
+                            .await
                     }));
                 }
 
@@ -585,13 +720,28 @@ impl AgentRunner for ScriptRunner {
                 filter_child = Some(spawned_filter);
             }
 
+            // Agents report a command only once it has started, so the audit cannot stop that
+            // command; an `Abort` ends the rest of the agent run.
+            let audit = config::get_config().command_audit;
+            let (aborted_tx, mut aborted_rx) = oneshot::channel::<String>();
             let stdout_handle = if let Some(stdout) = child.stdout.take() {
                 let mut writer = filter_stdin.take();
+                let mut aborted_tx = Some(aborted_tx);
+                let activity = activity.clone();
                 Some(tokio::spawn(async move {
                     let mut lines = BufReader::new(stdout).lines();
                     let mut buffer = String::new();
 
                     while let Some(line) = lines.next_line().await? {
+                        activity.touch();
+                        if audit.enabled
+                            && aborted_tx.is_some()
+                            && let Some(command) = command_audit::started_command(&line)
+                            && command_audit::review(&audit, &command).await == AuditDecision::Abort
+                            && let Some(tx) = aborted_tx.take()
+                        {
+                            let _ = tx.send(command);
+                        }
                         if let Some(ref mut pipe) = writer {
                             pipe.write_all(line.as_bytes()).await?;
                             pipe.write_all(b"\n").await?;
@@ -624,21 +774,47 @@ impl AgentRunner for ScriptRunner {
                 }
             }
 
-            let wait_future = child.wait();
-            let status = if let Some(timeout) = request.timeout {
-                match time::timeout(timeout, wait_future).await {
-                    Ok(result) => result?,
-                    Err(_) => {
-                        let _ = child.kill().await;
-                        if let Some(mut filter) = filter_child.take() {
-                            let _ = filter.kill().await;
-                        }
-                        let secs = timeout.as_secs();
-                        return Err(AgentError::Timeout(secs));
+            let agent_pid = child.id();
+            let filter_pid = filter_child.as_ref().and_then(|filter| filter.id());
+            let waited = tokio::select! {
+                waited = async {
+                    match request.timeout {
+                        Some(timeout) => time::timeout(timeout, child.wait()).await.ok(),
+                        None => Some(child.wait().await),
+                    }
+                } => waited,
+                _ = interrupt::interrupted() => {
+                    for pid in [agent_pid, filter_pid].into_iter().flatten() {
+                        interrupt::terminate_process_group(pid);
                     }
+                    return Err(AgentError::Interrupted);
+                }
+                Ok(command) = &mut aborted_rx => {
+                    for pid in [agent_pid, filter_pid].into_iter().flatten() {
+                        interrupt::terminate_process_group(pid);
+                    }
+                    return Err(AgentError::CommandAborted(command));
+                }
+                idle = watch_for_stall(activity.clone(), config::get_config().agent_stall, source.clone()) => {
+                    for pid in [agent_pid, filter_pid].into_iter().flatten() {
+                        interrupt::terminate_process_group(pid);
+                    }
+                    return Err(AgentError::Stalled(idle));
+                }
+            };
+            let status = match waited {
+                Some(result) => result?,
+                None => {
+                    let _ = child.kill().await;
+                    if let Some(mut filter) = filter_child.take() {
+                        let _ = filter.kill().await;
+                    }
+                    let secs = request
+                        .timeout
+                        .map(|timeout| timeout.as_secs())
+                        .unwrap_or(0);
+                    return Err(AgentError::Timeout(secs));
                 }
-            } else {
-                wait_future.await?
             };
 
             let duration_ms = start.elapsed().as_millis();
@@ -714,6 +890,280 @@ impl AgentRunner for ScriptRunner {
     }
 }
 
+impl AgentRunner for ScriptRunner {
+    fn backend_name(&self) -> &'static str {
+        "script"
+    }
+
+    // A stalled agent is rerun from scratch with the same prompt, up to `[agent_stall] retries`.
+    fn execute(&self, request: AgentRequest, progress_hook: Option<ProgressHook>) -> AgentFuture {
+        Box::pin(async move {
+            let retries = config::get_config().agent_stall.retries;
+            let mut attempt = 0;
+            loop {
+                match Self::execute_once(request.clone(), progress_hook.clone()).await {
+                    Err(AgentError::Stalled(_)) if attempt < retries => {
+                        attempt += 1;
+                        display::warn(format!(
+                            "retrying stalled agent (attempt {} of {})",
+                            attempt + 1,
+                            retries + 1
+                        ));
+                    }
+                    result => return result,
+                }
+            }
+        })
+    }
+}
+
+/// Deterministic backend for downstream tests: answers each request with a canned response
+/// from `[mock_agent] fixtures`, keyed by command scope and prompt hash.
+///
+/// Lookup order, relative to the fixtures directory: a `<scope>/<hash>/` recording written by
+/// [`RecordingRunner`], `<scope>/<hash>.txt`, `<scope>/default.txt`, then `default.txt`.
+/// `<scope>` is the request scope (or `default`) and `<hash>` is `mock_prompt_hash` of the full
+/// prompt text.
+pub struct MockRunner;
+
+/// First 16 hex digits of the SHA-256 of `prompt`; the fixture file stem for that prompt.
+pub fn mock_prompt_hash(prompt: &str) -> String {
+    let digest = Sha256::digest(prompt.as_bytes());
+    digest[..8]
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+fn request_scope_label(request: &AgentRequest) -> String {
+    request
+        .scope
+        .map(|scope| scope.as_str().to_string())
+        .unwrap_or_else(|| "default".to_string())
+}
+
+impl MockRunner {
+    fn fixture_candidates(fixtures: &Path, scope: &str, hash: &str) -> Vec<PathBuf> {
+        vec![
+            fixtures.join(scope).join(format!("{hash}.txt")),
+            fixtures.join(scope).join("default.txt"),
+            fixtures.join("default.txt"),
+        ]
+    }
+
+    async fn replay_recording(dir: &Path) -> Result<AgentResponse, AgentError> {
+        let assistant_text = tokio::fs::read_to_string(dir.join(RECORDING_STDOUT)).await?;
+        let stderr = match tokio::fs::read_to_string(dir.join(RECORDING_STDERR)).await {
+            Ok(text) => text.lines().map(str::to_string).collect(),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(err.into()),
+        };
+        let meta = match tokio::fs::read(dir.join(RECORDING_META)).await {
+            Ok(bytes) => serde_json::from_slice::<AgentRecordingMeta>(&bytes).map_err(|err| {
+                AgentError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+            })?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => AgentRecordingMeta::default(),
+            Err(err) => return Err(err.into()),
+        };
+        if meta.exit_code != 0 {
+            return Err(AgentError::NonZeroExit(meta.exit_code, stderr));
+        }
+        Ok(AgentResponse {
+            assistant_text,
+            stderr,
+            exit_code: meta.exit_code,
+            duration_ms: meta.duration_ms,
+        })
+    }
+}
+
+impl AgentRunner for MockRunner {
+    fn backend_name(&self) -> &'static str {
+        "mock"
+    }
+
+    fn execute(&self, request: AgentRequest, progress_hook: Option<ProgressHook>) -> AgentFuture {
+        Box::pin(async move {
+            let started = Instant::now();
+            let fixtures = request
+                .repo_root
+                .join(&config::get_config().mock_agent.fixtures);
+            let scope = request_scope_label(&request);
+            let hash = mock_prompt_hash(&request.prompt);
+            let recording = fixtures.join(&scope).join(&hash);
+            if recording.join(RECORDING_STDOUT).is_file() {
+                if let Some(ref hook) = progress_hook {
+                    let event = ProgressEvent {
+                        kind: ProgressKind::Agent,
+                        source: Some(format!("[mock:{scope}]")),
+                        phase: None,
+                        label: None,
+                        message: Some(format!("replayed recording {}", recording.display())),
+                        detail: None,
+                        path: None,
+                        progress: None,
+                        status: None,
+                        timestamp: None,
+                        raw: None,
+                    };
+                    hook.send_event(event).await;
+                }
+                return Self::replay_recording(&recording).await;
+            }
+            let candidates = Self::fixture_candidates(&fixtures, &scope, &hash);
+            let Some(path) = candidates.iter().find(|path| path.is_file()) else {
+                return Err(AgentError::Io(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!(
+                        "mock agent has no fixture for scope `{scope}` prompt hash `{hash}`; add {}",
+                        candidates[0].display()
+                    ),
+                )));
+            };
+            let assistant_text = tokio::fs::read_to_string(path).await?;
+
+            if let Some(ref hook) = progress_hook {
+                let event = ProgressEvent {
+                    kind: ProgressKind::Agent,
+                    source: Some(format!("[mock:{scope}]")),
+                    phase: None,
+                    label: None,
+                    message: Some(format!("replayed {}", path.display())),
+                    detail: None,
+                    path: None,
+                    progress: None,
+                    status: None,
+                    timestamp: None,
+                    raw: None,
+                };
+                hook.send_event(event).await;
+            }
+
+            Ok(AgentResponse {
+                assistant_text,
+                stderr: vec![format!("mock fixture {}", path.display())],
+                exit_code: 0,
+                duration_ms: started.elapsed().as_millis(),
+            })
+        })
+    }
+}
+
+/// Names the directory that [`RecordingRunner`] captures agent I/O into.
+pub const RECORD_AGENT_IO_ENV: &str = "VIZIER_RECORD_AGENT_IO";
+
+const RECORDING_PROMPT: &str = "prompt.txt";
+const RECORDING_STDOUT: &str = "stdout.txt";
+const RECORDING_STDERR: &str = "stderr.txt";
+const RECORDING_META: &str = "meta.json";
+
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+struct AgentRecordingMeta {
+    #[serde(default)]
+    backend: String,
+    #[serde(default)]
+    exit_code: i32,
+    #[serde(default)]
+    duration_ms: u128,
+}
+
+/// Wraps another runner and captures every invocation under `<dir>/<scope>/<hash>/` as
+/// `prompt.txt`, `stdout.txt`, `stderr.txt`, and `meta.json`. That is the layout `MockRunner`
+/// replays, so pointing `[mock_agent] fixtures` at a recording directory reproduces the run
+/// without any agent installed. A later invocation with the same scope and prompt overwrites
+/// the earlier recording. Relative directories resolve against the request's repo root.
+pub struct RecordingRunner {
+    inner: std::sync::Arc<dyn AgentRunner>,
+    dir: PathBuf,
+}
+
+impl RecordingRunner {
+    pub fn new(inner: std::sync::Arc<dyn AgentRunner>, dir: PathBuf) -> Self {
+        Self { inner, dir }
+    }
+
+    /// Wraps `inner` when `VIZIER_RECORD_AGENT_IO` names a directory; otherwise returns it as-is.
+    pub fn wrap_from_env(
+        inner: std::sync::Arc<dyn AgentRunner>,
+    ) -> std::sync::Arc<dyn AgentRunner> {
+        match std::env::var(RECORD_AGENT_IO_ENV) {
+            Ok(dir) if !dir.trim().is_empty() => {
+                std::sync::Arc::new(Self::new(inner, PathBuf::from(dir.trim())))
+            }
+            _ => inner,
+        }
+    }
+
+    async fn write_recording(
+        dir: &Path,
+        prompt: &str,
+        stdout: &str,
+        stderr: &[String],
+        meta: &AgentRecordingMeta,
+    ) -> std::io::Result<()> {
+        tokio::fs::create_dir_all(dir).await?;
+        tokio::fs::write(dir.join(RECORDING_PROMPT), prompt).await?;
+        tokio::fs::write(dir.join(RECORDING_STDOUT), stdout).await?;
+        let mut stderr_text = String::new();
+        for line in stderr {
+            stderr_text.push_str(line);
+            stderr_text.push('\n');
+        }
+        tokio::fs::write(dir.join(RECORDING_STDERR), stderr_text).await?;
+        let meta = serde_json::to_vec_pretty(meta)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+        tokio::fs::write(dir.join(RECORDING_META), meta).await
+    }
+}
+
+impl AgentRunner for RecordingRunner {
+    fn backend_name(&self) -> &'static str {
+        self.inner.backend_name()
+    }
+
+    fn execute(&self, request: AgentRequest, progress_hook: Option<ProgressHook>) -> AgentFuture {
+        let inner = std::sync::Arc::clone(&self.inner);
+        let backend = inner.backend_name().to_string();
+        let dir = request
+            .repo_root
+            .join(&self.dir)
+            .join(request_scope_label(&request))
+            .join(mock_prompt_hash(&request.prompt));
+        let prompt = request.prompt.clone();
+        Box::pin(async move {
+            let result = inner.execute(request, progress_hook).await;
+            // Only outcomes the agent itself produced are replayable; spawn failures and
+            // timeouts say more about the host than about the orchestration under test.
+            let recorded = match &result {
+                Ok(response) => Some((
+                    response.assistant_text.as_str(),
+                    response.stderr.as_slice(),
+                    response.exit_code,
+                    response.duration_ms,
+                )),
+                Err(AgentError::NonZeroExit(code, lines)) => Some(("", lines.as_slice(), *code, 0)),
+                Err(_) => None,
+            };
+            if let Some((stdout, stderr, exit_code, duration_ms)) = recorded {
+                let meta = AgentRecordingMeta {
+                    backend,
+                    exit_code,
+                    duration_ms,
+                };
+                if let Err(err) = Self::write_recording(&dir, &prompt, stdout, stderr, &meta).await
+                {
+                    display::log(
+                        display::Subsystem::Agent,
+                        display::LogLevel::Warn,
+                        format!("unable to record agent I/O to {}: {err}", dir.display()),
+                    );
+                }
+            }
+            result
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -801,6 +1251,7 @@ mod tests {
             scope: Some(CommandScope::Save),
             metadata: BTreeMap::new(),
             timeout: Some(Duration::from_secs(5)),
+            env: config::AgentEnvSettings::default(),
         };
 
         let result = runner
@@ -812,6 +1263,26 @@ mod tests {
         assert!(rx.recv().await.is_some(), "expected progress event");
     }
 
+    #[tokio::test]
+    async fn stall_watchdog_resolves_once_silence_reaches_kill_threshold() {
+        let activity = StreamActivity::new();
+        let stall = config::AgentStallConfig {
+            warn_after_secs: 0,
+            kill_after_secs: 1,
+            retries: 0,
+        };
+        let idle = time::timeout(
+            Duration::from_secs(5),
+            watch_for_stall(activity.clone(), stall, "[agent]".to_string()),
+        )
+        .await
+        .expect("watchdog should stop a silent agent");
+        assert!(idle >= 1, "reported silence {idle}s");
+
+        activity.touch();
+        assert!(activity.idle() < Duration::from_secs(1));
+    }
+
     #[tokio::test]
     async fn forwards_prompt_text_to_agent_stdin() {
         let runner = ScriptRunner;
@@ -846,6 +1317,7 @@ mod tests {
             scope: Some(CommandScope::Save),
             metadata: BTreeMap::new(),
             timeout: Some(Duration::from_secs(2)),
+            env: config::AgentEnvSettings::default(),
         };
 
         let result = runner
@@ -914,6 +1386,7 @@ printf '%s\n' "$last"
             scope: Some(CommandScope::Save),
             metadata: BTreeMap::new(),
             timeout: Some(Duration::from_secs(2)),
+            env: config::AgentEnvSettings::default(),
         };
 
         let mut handle =
@@ -1003,6 +1476,7 @@ printf '%s\n' "$content"
             scope: Some(CommandScope::Save),
             metadata: BTreeMap::new(),
             timeout: Some(Duration::from_secs(2)),
+            env: config::AgentEnvSettings::default(),
         };
 
         let response = runner
@@ -1108,6 +1582,7 @@ printf '%s\n' "$last"
             scope: Some(CommandScope::Save),
             metadata: BTreeMap::new(),
             timeout: Some(Duration::from_secs(5)),
+            env: config::AgentEnvSettings::default(),
         };
 
         let mut handle =
@@ -1157,12 +1632,98 @@ printf '%s\n' "$last"
             scope: Some(CommandScope::Save),
             metadata: BTreeMap::new(),
             timeout: Some(Duration::from_secs(1)),
+            env: config::AgentEnvSettings::default(),
         };
 
         let result = runner.execute(request, None).await;
         assert!(matches!(result, Err(AgentError::MissingCommand)));
     }
 
+    #[tokio::test]
+    async fn mock_runner_replays_fixture_by_scope_and_prompt_hash() {
+        let tmp = tempfile::tempdir().unwrap();
+        let fixtures = tmp.path().join(".vizier/mock-agent");
+        let hash = mock_prompt_hash("draft the plan");
+        std::fs::create_dir_all(fixtures.join("save")).unwrap();
+        std::fs::write(fixtures.join("save").join(format!("{hash}.txt")), "exact\n").unwrap();
+        std::fs::write(fixtures.join("default.txt"), "fallback\n").unwrap();
+
+        let request = |prompt: &str| AgentRequest {
+            scope: Some(CommandScope::Save),
+            ..AgentRequest::new(prompt.to_string(), tmp.path().to_path_buf())
+        };
+        let exact = MockRunner
+            .execute(request("draft the plan"), None)
+            .await
+            .expect("exact fixture");
+        assert_eq!(exact.assistant_text, "exact\n");
+        let fallback = MockRunner
+            .execute(request("something else"), None)
+            .await
+            .expect("fallback fixture");
+        assert_eq!(fallback.assistant_text, "fallback\n");
+
+        std::fs::remove_file(fixtures.join("default.txt")).unwrap();
+        let err = MockRunner
+            .execute(request("something else"), None)
+            .await
+            .expect_err("missing fixture");
+        assert!(
+            err.to_string()
+                .contains(&mock_prompt_hash("something else")),
+            "{err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn recording_runner_captures_io_that_mock_runner_replays() {
+        let tmp = tempfile::tempdir().unwrap();
+        let script = tmp.path().join("agent.sh");
+        std::fs::write(
+            &script,
+            "#!/bin/sh\ncat >/dev/null\nprintf 'plan body\\n\\nsecond paragraph\\n'\necho 'working' >&2\n",
+        )
+        .unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+
+        let request = AgentRequest {
+            command: vec![script.display().to_string()],
+            scope: Some(CommandScope::Draft),
+            ..AgentRequest::new("draft alpha".to_string(), tmp.path().to_path_buf())
+        };
+        let recorder = RecordingRunner::new(
+            std::sync::Arc::new(ScriptRunner),
+            PathBuf::from(".vizier/mock-agent"),
+        );
+        assert_eq!(recorder.backend_name(), "script");
+        let live = recorder
+            .execute(request.clone(), None)
+            .await
+            .expect("live run");
+
+        let recording = tmp
+            .path()
+            .join(".vizier/mock-agent/draft")
+            .join(mock_prompt_hash("draft alpha"));
+        assert_eq!(
+            std::fs::read_to_string(recording.join("prompt.txt")).unwrap(),
+            "draft alpha"
+        );
+
+        let replayed = MockRunner
+            .execute(request, None)
+            .await
+            .expect("replayed run");
+        assert_eq!(replayed.assistant_text, live.assistant_text);
+        assert_eq!(replayed.assistant_text, "plan body\n\nsecond paragraph\n");
+        assert_eq!(replayed.stderr, live.stderr);
+        assert_eq!(replayed.exit_code, 0);
+    }
+
     #[tokio::test]
     async fn fails_on_non_executable_script() {
         let runner = ScriptRunner;
@@ -1181,6 +1742,7 @@ printf '%s\n' "$last"
             scope: Some(CommandScope::Save),
             metadata: BTreeMap::new(),
             timeout: Some(Duration::from_secs(1)),
+            env: config::AgentEnvSettings::default(),
         };
 
         let result = runner.execute(request, None).await;
@@ -1232,6 +1794,7 @@ printf '%s\n' "$last"
             scope: Some(CommandScope::Save),
             metadata: BTreeMap::new(),
             timeout: Some(Duration::from_secs(2)),
+            env: config::AgentEnvSettings::default(),
         };
 
         let result = runner.execute(request, None).await;
@@ -1271,6 +1834,7 @@ printf '%s\n' "$last"
             scope: Some(CommandScope::Save),
             metadata: BTreeMap::new(),
             timeout: Some(Duration::from_secs(1)),
+            env: config::AgentEnvSettings::default(),
         };
 
         let result = runner.execute(request, None).await;
@@ -1279,4 +1843,28 @@ printf '%s\n' "$last"
             "expected timeout error, got {result:?}"
         );
     }
+
+    #[tokio::test]
+    async fn injects_scoped_env_into_agent_process() {
+        let runner = ScriptRunner;
+        let tmp = tempfile::tempdir().unwrap();
+        let script = tmp.path().join("env.sh");
+        std::fs::write(
+            &script,
+            "#!/bin/sh\ncat >/dev/null\nprintf '%s' \"$MODEL_REGION\"\n",
+        )
+        .unwrap();
+
+        let mut env = config::AgentEnvSettings::default();
+        env.vars
+            .insert("MODEL_REGION".to_string(), "us-east".to_string());
+        let request = AgentRequest {
+            command: vec!["sh".to_string(), script.display().to_string()],
+            env,
+            ..AgentRequest::new("env".to_string(), tmp.path().to_path_buf())
+        };
+
+        let response = runner.execute(request, None).await.expect("agent run");
+        assert_eq!(response.assistant_text.trim_end(), "us-east");
+    }
 }
@@ -0,0 +1,138 @@
+//! Reuse of agent replies for workflow nodes that opt in with `cache_ttl_secs`. A reply is keyed
+//! by a hash of the exact prompt sent and the agent that answered it, so rerunning `vizier run
+//! commit` after an aborted commit (same diff, same prompt) skips the second model call.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+pub const AGENT_CACHE_DIR: &str = crate::temp_registry::AGENT_CACHE_DIR;
+
+/// Hash of everything that decides an agent's reply: the selector, the command line it ran, and
+/// the prompt text as sent.
+pub fn prompt_hash(agent_selector: &str, command: &[String], prompt: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(agent_selector.as_bytes());
+    hasher.update([0]);
+    for part in command {
+        hasher.update(part.as_bytes());
+        hasher.update([0]);
+    }
+    hasher.update([0]);
+    hasher.update(prompt.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// A successful agent reply recorded for a prompt hash.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedResponse {
+    pub prompt_hash: String,
+    pub agent_selector: String,
+    pub text: String,
+    pub duration_ms: u128,
+    pub recorded_at: String,
+}
+
+impl CachedResponse {
+    /// Seconds since the reply was recorded; `None` when the timestamp does not parse.
+    pub fn age_secs(&self, now: DateTime<Utc>) -> Option<u64> {
+        let recorded = DateTime::parse_from_rfc3339(&self.recorded_at).ok()?;
+        u64::try_from((now - recorded.with_timezone(&Utc)).num_seconds()).ok()
+    }
+
+    /// `agent: cached (<hash>, <age>s old)` notation shown in place of an agent run.
+    pub fn notation(&self, now: DateTime<Utc>) -> String {
+        format!(
+            "agent: cached ({}, {}s old)",
+            &self.prompt_hash[..self.prompt_hash.len().min(12)],
+            self.age_secs(now).unwrap_or_default()
+        )
+    }
+}
+
+pub fn entry_path(project_root: &Path, prompt_hash: &str) -> PathBuf {
+    project_root
+        .join(AGENT_CACHE_DIR)
+        .join(format!("{prompt_hash}.json"))
+}
+
+/// The reply recorded for `prompt_hash` if it is at most `ttl_secs` old. Expired entries are
+/// removed on the way.
+pub fn lookup(
+    project_root: &Path,
+    prompt_hash: &str,
+    ttl_secs: u64,
+    now: DateTime<Utc>,
+) -> Option<CachedResponse> {
+    let path = entry_path(project_root, prompt_hash);
+    let bytes = fs::read(&path).ok()?;
+    let entry: CachedResponse = serde_json::from_slice(&bytes).ok()?;
+    match entry.age_secs(now) {
+        Some(age) if age <= ttl_secs && entry.prompt_hash == prompt_hash => Some(entry),
+        _ => {
+            let _ = fs::remove_file(path);
+            None
+        }
+    }
+}
+
+/// Records a successful reply for `prompt_hash`, replacing any earlier one.
+pub fn store(project_root: &Path, entry: &CachedResponse) -> io::Result<()> {
+    let path = entry_path(project_root, &entry.prompt_hash);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let bytes = serde_json::to_vec_pretty(entry)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    crate::atomic_file::write_atomic(&path, &bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn replies_are_reused_until_the_ttl_or_prompt_changes() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let command = vec!["codex".to_string(), "exec".to_string()];
+        let hash = prompt_hash("default", &command, "diff A");
+        assert_ne!(hash, prompt_hash("default", &command, "diff B"));
+        assert_ne!(hash, prompt_hash("claude", &command, "diff A"));
+
+        let recorded = Utc::now();
+        store(
+            dir.path(),
+            &CachedResponse {
+                prompt_hash: hash.clone(),
+                agent_selector: "default".to_string(),
+                text: "feat: add cache".to_string(),
+                duration_ms: 1200,
+                recorded_at: recorded.to_rfc3339(),
+            },
+        )
+        .expect("store");
+
+        let later = recorded + Duration::seconds(90);
+        let hit = lookup(dir.path(), &hash, 600, later).expect("hit");
+        assert_eq!(hit.text, "feat: add cache");
+        assert!(hit.notation(later).starts_with("agent: cached ("));
+        assert!(hit.notation(later).ends_with(", 90s old)"));
+        assert!(
+            lookup(
+                dir.path(),
+                &prompt_hash("default", &command, "diff B"),
+                600,
+                later
+            )
+            .is_none()
+        );
+
+        assert!(lookup(dir.path(), &hash, 60, later).is_none());
+        assert!(!entry_path(dir.path(), &hash).exists());
+    }
+}
@@ -316,6 +316,48 @@ pub fn build_cicd_failure_prompt(input: CicdFailurePromptInput<'_>) -> Result<St
     kernel_prompt::build_cicd_failure_prompt(kernel_input).map_err(map_prompt_error)
 }
 
+pub fn build_test_generation_prompt(
+    plan_slug: &str,
+    plan_branch: &str,
+    plan_document: &str,
+    test_command: Option<&str>,
+) -> Result<String, AgentError> {
+    let bounds = load_bounds_prompt()?;
+    Ok(kernel_prompt::build_test_generation_prompt(
+        kernel_prompt::TestGenerationPromptInput {
+            plan_slug,
+            plan_branch,
+            plan_document,
+            test_command,
+            bounds: &bounds,
+        },
+    ))
+}
+
+pub fn build_review_fix_prompt(
+    plan_slug: &str,
+    plan_branch: &str,
+    target_branch: &str,
+    plan_document: &str,
+    critique: &str,
+    iteration: u32,
+    max_iterations: u32,
+) -> Result<String, AgentError> {
+    let bounds = load_bounds_prompt()?;
+    Ok(kernel_prompt::build_review_fix_prompt(
+        kernel_prompt::ReviewFixPromptInput {
+            plan_slug,
+            plan_branch,
+            target_branch,
+            plan_document,
+            critique,
+            iteration,
+            max_iterations,
+            bounds: &bounds,
+        },
+    ))
+}
+
 fn load_bounds_prompt() -> Result<String, AgentError> {
     Ok(kernel_prompt::DEFAULT_AGENT_BOUNDS.to_string())
 }
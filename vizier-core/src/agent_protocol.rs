@@ -0,0 +1,449 @@
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+use std::process::{Command, ExitStatus, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+/// Version of the stdin/stdout/stderr/exit-code contract agent shims implement
+/// (`docs/user/agent-protocol.md`).
+pub const PROTOCOL_VERSION: u32 = 1;
+/// Environment variable carrying [`PROTOCOL_VERSION`] to every shim and progress filter.
+pub const PROTOCOL_ENV: &str = "VIZIER_AGENT_PROTOCOL";
+/// Longest event line a shim may write; longer lines are a protocol violation.
+pub const MAX_EVENT_LINE_BYTES: usize = 1024 * 1024;
+
+const CANNED_PROMPT: &str = "This is a vizier agent protocol check. Do not edit any files or run \
+any commands. Reply with the single word: ready\n";
+const LARGE_PROMPT_BYTES: usize = 512 * 1024;
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// One way a shim's output breaks the protocol.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Violation {
+    /// 1-based stdout line, when the violation belongs to one.
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl Violation {
+    fn at(line: usize, message: impl Into<String>) -> Self {
+        Self {
+            line: Some(line),
+            message: message.into(),
+        }
+    }
+
+    fn whole(message: impl Into<String>) -> Self {
+        Self {
+            line: None,
+            message: message.into(),
+        }
+    }
+}
+
+/// Checks an event stream (agent stdout when a progress filter reads it): newline-terminated
+/// JSON objects with a string `type`, each under [`MAX_EVENT_LINE_BYTES`]. Blank lines are
+/// allowed.
+pub fn check_event_stream(stdout: &[u8]) -> Vec<Violation> {
+    if stdout.iter().all(u8::is_ascii_whitespace) {
+        return vec![Violation::whole("no events were written to stdout")];
+    }
+    let mut violations = Vec::new();
+    let lines = stdout.split(|byte| *byte == b'\n').collect::<Vec<_>>();
+    // `split` yields an empty tail after a trailing newline; anything else there was cut off.
+    let last = lines.len() - 1;
+    for (index, line) in lines.iter().enumerate() {
+        let number = index + 1;
+        if index == last {
+            if !line.is_empty() {
+                violations.push(Violation::at(
+                    number,
+                    "stream ended mid-line (early EOF); every event must end with a newline",
+                ));
+            }
+            break;
+        }
+        if line.iter().all(u8::is_ascii_whitespace) {
+            continue;
+        }
+        if line.len() > MAX_EVENT_LINE_BYTES {
+            violations.push(Violation::at(
+                number,
+                format!(
+                    "event is {} bytes; events must stay under {MAX_EVENT_LINE_BYTES} bytes",
+                    line.len()
+                ),
+            ));
+            continue;
+        }
+        let Ok(text) = std::str::from_utf8(line) else {
+            violations.push(Violation::at(number, "event is not valid UTF-8"));
+            continue;
+        };
+        match serde_json::from_str::<serde_json::Value>(text) {
+            Err(err) => violations.push(Violation::at(number, format!("malformed JSON: {err}"))),
+            Ok(serde_json::Value::Object(event)) => {
+                if !event.get("type").is_some_and(serde_json::Value::is_string) {
+                    violations.push(Violation::at(number, "event has no string `type` field"));
+                }
+            }
+            Ok(_) => violations.push(Violation::at(number, "event is not a JSON object")),
+        }
+    }
+    violations
+}
+
+/// Checks a final reply (agent stdout without a filter, or filter stdout): non-empty UTF-8.
+pub fn check_reply(stdout: &[u8]) -> Vec<Violation> {
+    match std::str::from_utf8(stdout) {
+        Err(_) => vec![Violation::whole("reply is not valid UTF-8")],
+        Ok(text) if text.trim().is_empty() => vec![Violation::whole("reply on stdout is empty")],
+        Ok(_) => Vec::new(),
+    }
+}
+
+/// A shim (and optional progress filter) to exercise.
+#[derive(Clone, Debug)]
+pub struct ShimUnderTest {
+    pub command: Vec<String>,
+    pub filter: Option<Vec<String>>,
+    /// Treat agent stdout as an event stream even without a filter.
+    pub events: bool,
+    pub timeout: Duration,
+    pub cwd: PathBuf,
+}
+
+impl ShimUnderTest {
+    fn emits_events(&self) -> bool {
+        self.events || self.filter.is_some()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct CaseResult {
+    pub case: String,
+    pub passed: bool,
+    pub detail: String,
+    pub violations: Vec<Violation>,
+}
+
+/// Runs the canned cases against `shim`: a short prompt, an empty prompt, and a large prompt
+/// through the agent; then, with a filter, the short prompt's output plus truncated, malformed,
+/// and oversized event streams through the filter.
+pub fn validate(shim: &ShimUnderTest) -> Vec<CaseResult> {
+    let mut results = Vec::new();
+
+    let prompt = run(shim, &shim.command, CANNED_PROMPT.as_bytes().to_vec());
+    let sample_events = match &prompt {
+        Ok(run) if run.succeeded() => Some(run.stdout.clone()),
+        _ => None,
+    };
+    results.push(agent_case(shim, "prompt", prompt, true));
+    results.push(agent_case(
+        shim,
+        "empty_prompt",
+        run(shim, &shim.command, Vec::new()),
+        false,
+    ));
+    results.push(agent_case(
+        shim,
+        "large_prompt",
+        run(shim, &shim.command, large_prompt()),
+        true,
+    ));
+
+    let Some(filter) = &shim.filter else {
+        return results;
+    };
+    results.push(match sample_events {
+        Some(events) => {
+            let outcome = run(shim, filter, events);
+            let mut result = exit_case("filter_reply", &outcome, true);
+            if let Ok(run) = &outcome
+                && run.succeeded()
+            {
+                result.violations = check_reply(&run.stdout);
+                result.passed = result.violations.is_empty();
+            }
+            result
+        }
+        None => CaseResult {
+            case: "filter_reply".to_string(),
+            passed: false,
+            detail: "skipped: the `prompt` case produced no events to filter".to_string(),
+            violations: Vec::new(),
+        },
+    });
+    let event = "{\"type\":\"vizier.protocol_check\"}\n";
+    let streams = [
+        (
+            "filter_early_eof",
+            format!("{event}{{\"type\":\"message\",\"text\":\"cut off"),
+        ),
+        (
+            "filter_malformed_json",
+            format!("{event}this is not json\n{event}"),
+        ),
+        (
+            "filter_huge_line",
+            format!(
+                "{event}{{\"type\":\"message\",\"text\":\"{}\"}}\n",
+                "x".repeat(2 * MAX_EVENT_LINE_BYTES)
+            ),
+        ),
+    ];
+    for (case, stream) in streams {
+        results.push(exit_case(
+            case,
+            &run(shim, filter, stream.into_bytes()),
+            false,
+        ));
+    }
+    results
+}
+
+fn agent_case(
+    shim: &ShimUnderTest,
+    case: &str,
+    outcome: io::Result<Run>,
+    must_succeed: bool,
+) -> CaseResult {
+    let mut result = exit_case(case, &outcome, must_succeed);
+    if let Ok(run) = &outcome
+        && run.succeeded()
+    {
+        result.violations = if shim.emits_events() {
+            check_event_stream(&run.stdout)
+        } else {
+            check_reply(&run.stdout)
+        };
+        // An empty prompt may legitimately produce no reply.
+        if !must_succeed && run.stdout.iter().all(u8::is_ascii_whitespace) {
+            result.violations.clear();
+        }
+        result.passed = result.passed && result.violations.is_empty();
+    }
+    result
+}
+
+/// Judges how a process ended. `must_succeed` cases need exit 0; the others may also fail, as
+/// long as they exit on their own and say why on stderr.
+fn exit_case(case: &str, outcome: &io::Result<Run>, must_succeed: bool) -> CaseResult {
+    let (passed, detail) = match outcome {
+        Err(err) => (false, format!("could not start: {err}")),
+        Ok(run) => match run.status {
+            None => (
+                false,
+                format!("still running after {}s; killed", run.timeout.as_secs()),
+            ),
+            Some(status) => {
+                let stderr = String::from_utf8_lossy(&run.stderr);
+                let stderr = stderr.lines().rev().find(|line| !line.trim().is_empty());
+                let ms = run.elapsed.as_millis();
+                match (status.code(), &run.stdin_error) {
+                    (_, Some(err)) => (
+                        false,
+                        format!("stopped reading the prompt early ({err}); read stdin to EOF"),
+                    ),
+                    (None, None) => (false, format!("killed by a signal after {ms}ms")),
+                    (Some(0), None) => (true, format!("exit 0 in {ms}ms")),
+                    (Some(code), None) if must_succeed => (
+                        false,
+                        format!("exit {code} in {ms}ms: {}", stderr.unwrap_or("<no stderr>")),
+                    ),
+                    (Some(code), None) => match stderr {
+                        Some(line) => (true, format!("exit {code} in {ms}ms: {line}")),
+                        None => (
+                            false,
+                            format!("exit {code} in {ms}ms with nothing on stderr to explain it"),
+                        ),
+                    },
+                }
+            }
+        },
+    };
+    CaseResult {
+        case: case.to_string(),
+        passed,
+        detail,
+        violations: Vec::new(),
+    }
+}
+
+fn large_prompt() -> Vec<u8> {
+    let mut prompt = CANNED_PROMPT.to_string();
+    let filler = "Filler line for the large-prompt check; ignore it.\n";
+    while prompt.len() < LARGE_PROMPT_BYTES {
+        prompt.push_str(filler);
+    }
+    prompt.push_str(CANNED_PROMPT);
+    prompt.into_bytes()
+}
+
+struct Run {
+    /// `None` when the process outlived `timeout` and was killed.
+    status: Option<ExitStatus>,
+    stdout: Vec<u8>,
+    stderr: Vec<u8>,
+    /// Set when the process closed stdin before taking the whole input.
+    stdin_error: Option<String>,
+    elapsed: Duration,
+    timeout: Duration,
+}
+
+impl Run {
+    fn succeeded(&self) -> bool {
+        self.stdin_error.is_none() && self.status.is_some_and(|status| status.success())
+    }
+}
+
+fn run(shim: &ShimUnderTest, command: &[String], input: Vec<u8>) -> io::Result<Run> {
+    let (program, args) = command
+        .split_first()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "empty command"))?;
+    let start = Instant::now();
+    let mut child = Command::new(program)
+        .args(args)
+        .current_dir(&shim.cwd)
+        .env(PROTOCOL_ENV, PROTOCOL_VERSION.to_string())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    let mut stdin = child.stdin.take();
+    let writer = thread::spawn(move || -> Option<String> {
+        let stdin = stdin.as_mut()?;
+        stdin.write_all(&input).err().map(|err| err.to_string())
+    });
+    let read = |pipe: Option<Box<dyn Read + Send>>| {
+        thread::spawn(move || {
+            let mut buffer = Vec::new();
+            if let Some(mut pipe) = pipe {
+                let _ = pipe.read_to_end(&mut buffer);
+            }
+            buffer
+        })
+    };
+    let stdout = read(child.stdout.take().map(|pipe| Box::new(pipe) as _));
+    let stderr = read(child.stderr.take().map(|pipe| Box::new(pipe) as _));
+
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break Some(status);
+        }
+        if start.elapsed() >= shim.timeout {
+            let _ = child.kill();
+            let _ = child.wait();
+            break None;
+        }
+        thread::sleep(POLL_INTERVAL);
+    };
+    let elapsed = start.elapsed();
+    Ok(Run {
+        status,
+        stdin_error: writer.join().unwrap_or(None),
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+        elapsed,
+        timeout: shim.timeout,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn event_stream_checks_flag_each_broken_line() {
+        assert!(check_event_stream(b"{\"type\":\"a\"}\n\n{\"type\":\"b\",\"n\":1}\n").is_empty());
+        let huge = format!(
+            "{{\"type\":\"big\",\"text\":\"{}\"}}\n",
+            "x".repeat(MAX_EVENT_LINE_BYTES)
+        );
+        let stream = format!("not json\n[1]\n{{\"kind\":\"a\"}}\n{huge}{{\"type\":\"cut");
+        let violations = check_event_stream(stream.as_bytes());
+        assert_eq!(
+            violations
+                .iter()
+                .map(|violation| (violation.line, violation.message.split(':').next().unwrap()))
+                .collect::<Vec<_>>(),
+            vec![
+                (Some(1), "malformed JSON"),
+                (Some(2), "event is not a JSON object"),
+                (Some(3), "event has no string `type` field"),
+                (
+                    Some(4),
+                    "event is 1048600 bytes; events must stay under 1048576 bytes"
+                ),
+                (
+                    Some(5),
+                    "stream ended mid-line (early EOF); every event must end with a newline"
+                ),
+            ]
+        );
+        assert_eq!(check_event_stream(b"\n").len(), 1);
+        assert_eq!(check_reply(b"  \n").len(), 1);
+    }
+
+    fn sh(script: &str) -> Vec<String> {
+        vec!["sh".to_string(), "-c".to_string(), script.to_string()]
+    }
+
+    #[test]
+    fn validate_runs_agent_and_filter_cases() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let shim = ShimUnderTest {
+            command: sh(
+                "cat >/dev/null; echo \"{\\\"type\\\":\\\"done\\\",\\\"v\\\":\\\"$VIZIER_AGENT_PROTOCOL\\\"}\"",
+            ),
+            filter: Some(sh("cat >/dev/null; echo ready")),
+            events: false,
+            timeout: Duration::from_secs(10),
+            cwd: dir.path().to_path_buf(),
+        };
+        let results = validate(&shim);
+        assert_eq!(
+            results
+                .iter()
+                .map(|result| (result.case.as_str(), result.passed))
+                .collect::<Vec<_>>(),
+            vec![
+                ("prompt", true),
+                ("empty_prompt", true),
+                ("large_prompt", true),
+                ("filter_reply", true),
+                ("filter_early_eof", true),
+                ("filter_malformed_json", true),
+                ("filter_huge_line", true),
+            ],
+            "{results:#?}"
+        );
+
+        let broken = ShimUnderTest {
+            command: sh("head -c 10 >/dev/null; printf 'ready'"),
+            filter: Some(sh("cat >/dev/null; exit 3")),
+            events: true,
+            ..shim
+        };
+        let results = validate(&broken);
+        let prompt = &results[0];
+        assert!(!prompt.passed);
+        assert_eq!(prompt.violations.len(), 1, "{prompt:#?}");
+        assert!(prompt.violations[0].message.contains("early EOF"));
+        let large = &results[2];
+        assert!(!large.passed);
+        assert!(
+            large.detail.contains("stopped reading the prompt early"),
+            "{large:#?}"
+        );
+        assert!(results[3].detail.starts_with("exit 3"), "{:#?}", results[3]);
+        assert!(
+            results[4].detail.contains("nothing on stderr"),
+            "{:#?}",
+            results[4]
+        );
+    }
+}
@@ -0,0 +1,242 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use crate::search::{SearchDocument, SearchSourceKind};
+
+/// Committed home of merged plans:
+/// `.vizier/archive/<slug>/{archive.json,plan.md,summary.md,critique.md,notes.md}`.
+pub const ARCHIVE_DIR: &str = ".vizier/archive";
+const METADATA_FILE: &str = "archive.json";
+const PLAN_FILE: &str = "plan.md";
+const SUMMARY_FILE: &str = "summary.md";
+const CRITIQUE_FILE: &str = "critique.md";
+const NOTES_FILE: &str = "notes.md";
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchiveMetadata {
+    pub slug: String,
+    pub branch: String,
+    pub target: Option<String>,
+    pub branch_tip: String,
+    pub archived_at: String,
+    /// First `# ` heading of the plan document, or the slug when it has none.
+    pub title: String,
+    /// The plan this one was filed as a follow-up of (`spawned-by:` front matter).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spawned_by: Option<String>,
+}
+
+/// Everything recorded for one merged plan.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlanArchive {
+    pub metadata: ArchiveMetadata,
+    pub plan: String,
+    pub summary: String,
+    pub critique: Option<String>,
+    /// The plan's operator notes (`vizier note`).
+    pub notes: Option<String>,
+}
+
+impl PlanArchive {
+    pub fn new(
+        slug: &str,
+        branch: &str,
+        target: Option<&str>,
+        branch_tip: &str,
+        plan: &str,
+    ) -> Self {
+        let title = crate::plan::plan_title(plan).unwrap_or_else(|| slug.to_string());
+        Self {
+            metadata: ArchiveMetadata {
+                slug: slug.to_string(),
+                branch: branch.to_string(),
+                target: target.map(str::to_string),
+                branch_tip: branch_tip.to_string(),
+                archived_at: Utc::now().to_rfc3339(),
+                title,
+                spawned_by: crate::plan::spawned_by(plan),
+            },
+            plan: plan.to_string(),
+            summary: String::new(),
+            critique: None,
+            notes: None,
+        }
+    }
+}
+
+pub fn archive_dir(project_root: &Path, slug: &str) -> PathBuf {
+    project_root.join(ARCHIVE_DIR).join(slug)
+}
+
+/// Writes the archive for `archive.metadata.slug`, replacing an earlier archive of the same slug,
+/// and returns the written paths relative to `project_root` for staging.
+pub fn write_archive(project_root: &Path, archive: &PlanArchive) -> io::Result<Vec<String>> {
+    let slug = &archive.metadata.slug;
+    let dir = archive_dir(project_root, slug);
+    if dir.exists() {
+        fs::remove_dir_all(&dir)?;
+    }
+    fs::create_dir_all(&dir)?;
+
+    let metadata = serde_json::to_vec_pretty(&archive.metadata)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    let mut files = vec![
+        (METADATA_FILE, metadata),
+        (PLAN_FILE, archive.plan.clone().into_bytes()),
+        (SUMMARY_FILE, archive.summary.clone().into_bytes()),
+    ];
+    if let Some(critique) = archive.critique.as_ref() {
+        files.push((CRITIQUE_FILE, critique.clone().into_bytes()));
+    }
+    if let Some(notes) = archive.notes.as_ref() {
+        files.push((NOTES_FILE, notes.clone().into_bytes()));
+    }
+
+    let mut written = Vec::new();
+    for (name, contents) in files {
+        fs::write(dir.join(name), contents)?;
+        written.push(format!("{ARCHIVE_DIR}/{slug}/{name}"));
+    }
+    Ok(written)
+}
+
+/// Archived plans, newest first.
+pub fn list_archives(project_root: &Path) -> io::Result<Vec<ArchiveMetadata>> {
+    let root = project_root.join(ARCHIVE_DIR);
+    let entries = match fs::read_dir(&root) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    let mut archives = Vec::new();
+    for entry in entries {
+        let path = entry?.path().join(METADATA_FILE);
+        if path.is_file() {
+            archives.push(read_metadata(&path)?);
+        }
+    }
+    archives.sort_by(|left, right| {
+        right
+            .archived_at
+            .cmp(&left.archived_at)
+            .then_with(|| left.slug.cmp(&right.slug))
+    });
+    Ok(archives)
+}
+
+/// Archived plans whose `spawned_by` names `slug`, newest first.
+pub fn archived_follow_ups(archives: &[ArchiveMetadata], slug: &str) -> Vec<String> {
+    archives
+        .iter()
+        .filter(|entry| entry.spawned_by.as_deref() == Some(slug))
+        .map(|entry| entry.slug.clone())
+        .collect()
+}
+
+pub fn load_archive(project_root: &Path, slug: &str) -> io::Result<Option<PlanArchive>> {
+    let dir = archive_dir(project_root, slug);
+    let metadata_path = dir.join(METADATA_FILE);
+    if !metadata_path.is_file() {
+        return Ok(None);
+    }
+    let read_optional = |name: &str| match fs::read_to_string(dir.join(name)) {
+        Ok(text) => Ok(Some(text)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err),
+    };
+    Ok(Some(PlanArchive {
+        metadata: read_metadata(&metadata_path)?,
+        plan: read_optional(PLAN_FILE)?.unwrap_or_default(),
+        summary: read_optional(SUMMARY_FILE)?.unwrap_or_default(),
+        critique: read_optional(CRITIQUE_FILE)?,
+        notes: read_optional(NOTES_FILE)?,
+    }))
+}
+
+/// Search documents for every archived plan, summary, critique, and notes file.
+pub fn search_documents(project_root: &Path) -> io::Result<Vec<SearchDocument>> {
+    let mut documents = Vec::new();
+    for metadata in list_archives(project_root)? {
+        for name in [PLAN_FILE, SUMMARY_FILE, CRITIQUE_FILE, NOTES_FILE] {
+            let path = archive_dir(project_root, &metadata.slug).join(name);
+            if !path.is_file() {
+                continue;
+            }
+            documents.push(SearchDocument {
+                path: format!("{ARCHIVE_DIR}/{}/{name}", metadata.slug),
+                kind: SearchSourceKind::Archive,
+                text: fs::read_to_string(&path)?,
+                first_line: 1,
+            });
+        }
+    }
+    Ok(documents)
+}
+
+fn read_metadata(path: &Path) -> io::Result<ArchiveMetadata> {
+    let bytes = fs::read(path)?;
+    serde_json::from_slice(&bytes).map_err(|err| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}: {err}", path.display()),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn written_archives_round_trip_and_are_searchable() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let root = dir.path();
+        assert!(list_archives(root).expect("empty list").is_empty());
+
+        let mut archive = PlanArchive::new(
+            "alpha",
+            "draft/alpha",
+            Some("main"),
+            "abc123",
+            "---\nplan: alpha\nspawned-by: origin\n---\n\n# Rotate session tokens\n\nRotate tokens nightly.\n",
+        );
+        assert_eq!(archive.metadata.spawned_by.as_deref(), Some("origin"));
+        archive.summary = " src/auth.rs | 4 ++--\n".to_string();
+        archive.critique = Some("## Action Items\n- [minor] rename `ttl`\n".to_string());
+        archive.notes = Some("- 2026-10-17T09:00:00Z ana: token clock skew\n".to_string());
+        let written = write_archive(root, &archive).expect("write archive");
+        assert_eq!(
+            written,
+            vec![
+                ".vizier/archive/alpha/archive.json",
+                ".vizier/archive/alpha/plan.md",
+                ".vizier/archive/alpha/summary.md",
+                ".vizier/archive/alpha/critique.md",
+                ".vizier/archive/alpha/notes.md",
+            ]
+        );
+
+        let listed = list_archives(root).expect("list");
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].title, "Rotate session tokens");
+        assert_eq!(archived_follow_ups(&listed, "origin"), vec!["alpha"]);
+        assert!(archived_follow_ups(&listed, "alpha").is_empty());
+        assert_eq!(
+            load_archive(root, "alpha").expect("load"),
+            Some(archive.clone())
+        );
+        assert_eq!(load_archive(root, "missing").expect("load missing"), None);
+
+        let documents = search_documents(root).expect("documents");
+        assert_eq!(documents.len(), 4);
+        assert!(
+            documents
+                .iter()
+                .all(|doc| doc.kind == SearchSourceKind::Archive)
+        );
+    }
+}
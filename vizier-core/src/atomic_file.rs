@@ -0,0 +1,107 @@
+//! Crash-safe file replacement for session logs, outcomes, and job records.
+//!
+//! Writers put the full contents in a sibling temp file, fsync it, and rename it over the
+//! target, so readers only ever see the previous or the new contents. A crash can still leave
+//! the temp file behind; [`temp_siblings`] finds those so loaders and `vizier sessions fsck`
+//! can recover or clean them up.
+
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Infix between a target's file name and its temp-file suffix (`session.json.tmp.<pid>.<n>`).
+pub const TEMP_INFIX: &str = ".tmp";
+
+static TEMP_NONCE: AtomicU64 = AtomicU64::new(0);
+
+/// Replaces `path` with `bytes` via temp file + fsync + rename, then fsyncs the directory so
+/// the rename itself survives a crash.
+pub fn write_atomic(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    let parent = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    fs::create_dir_all(parent)?;
+
+    let name = path
+        .file_name()
+        .ok_or_else(|| io::Error::other(format!("{} has no file name", path.display())))?;
+    let nonce = TEMP_NONCE.fetch_add(1, Ordering::Relaxed);
+    let tmp = parent.join(format!(
+        "{}{TEMP_INFIX}.{}.{nonce}",
+        name.to_string_lossy(),
+        std::process::id()
+    ));
+
+    let written = File::create(&tmp).and_then(|mut file| {
+        file.write_all(bytes)?;
+        file.sync_all()
+    });
+    if let Err(err) = written.and_then(|()| fs::rename(&tmp, path)) {
+        let _ = fs::remove_file(&tmp);
+        return Err(err);
+    }
+    sync_dir(parent);
+    Ok(())
+}
+
+#[cfg(unix)]
+fn sync_dir(dir: &Path) {
+    // Best effort: some filesystems refuse fsync on directories.
+    if let Ok(handle) = File::open(dir) {
+        let _ = handle.sync_all();
+    }
+}
+
+#[cfg(not(unix))]
+fn sync_dir(_dir: &Path) {}
+
+/// Temp files a crashed [`write_atomic`] left next to `path`, newest first.
+pub fn temp_siblings(path: &Path) -> Vec<PathBuf> {
+    let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else {
+        return Vec::new();
+    };
+    let prefix = format!("{}{TEMP_INFIX}", name.to_string_lossy());
+    let Ok(entries) = fs::read_dir(parent) else {
+        return Vec::new();
+    };
+    let mut temps = entries
+        .flatten()
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_string_lossy()
+                .strip_prefix(&prefix)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+        })
+        .filter_map(|entry| {
+            let modified = entry.metadata().and_then(|meta| meta.modified()).ok()?;
+            Some((modified, entry.path()))
+        })
+        .collect::<Vec<_>>();
+    temps.sort_by(|a, b| b.cmp(a));
+    temps.into_iter().map(|(_, path)| path).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_atomic_replaces_contents_and_leaves_no_temp_files() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("nested/session.json");
+        write_atomic(&path, b"{\"a\":1}").expect("first write");
+        write_atomic(&path, b"{\"a\":2}").expect("second write");
+        assert_eq!(fs::read_to_string(&path).expect("read"), "{\"a\":2}");
+        assert!(temp_siblings(&path).is_empty());
+
+        fs::write(dir.path().join("nested/session.json.tmp.7.0"), "{").expect("stale temp");
+        fs::write(dir.path().join("nested/session.json.tmpfile"), "x").expect("unrelated");
+        assert_eq!(
+            temp_siblings(&path),
+            vec![dir.path().join("nested/session.json.tmp.7.0")]
+        );
+    }
+}
@@ -6,8 +6,7 @@ use serde_json::json;
 use sha2::{Digest, Sha256};
 use std::collections::BTreeMap;
 use std::env;
-use std::fs::{self, File};
-use std::io::Write;
+use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use tokio::sync::mpsc::Sender;
@@ -15,12 +14,14 @@ use tokio::sync::mpsc::Sender;
 use crate::{
     agent::{AgentRequest, DEFAULT_AGENT_TIMEOUT, ProgressHook},
     config::{self, PromptOrigin, SystemPrompt},
-    display, file_tracking, tools, vcs,
+    display, file_tracking, interrupt, session_schema, tools, vcs,
 };
 
 pub use vizier_kernel::audit::{
-    AgentRunRecord, AuditResult, AuditState, CommitDisposition, Message, MessageRole,
-    NarrativeChangeSet, SessionArtifact,
+    AgentRunRecord, AuditResult, AuditState, COMMAND_OUTCOME_SCHEMA, CommandOutcome,
+    CommandOutcomeAgent, CommandOutcomeCommit, CommandOutcomeExit, CommandOutcomeGate,
+    CommandOutcomePhase, CommitDisposition, Message, MessageRole, NarrativeChangeSet,
+    SessionArtifact,
 };
 
 lazy_static! {
@@ -74,6 +75,8 @@ impl Drop for AuditorCleanup {
     }
 }
 
+/// The checkout containing the current directory, or the git directory itself when it is a bare
+/// repository (a server-side deployment has no working tree).
 pub fn find_project_root() -> std::io::Result<Option<PathBuf>> {
     let mut current_dir = std::env::current_dir()?;
 
@@ -83,6 +86,10 @@ pub fn find_project_root() -> std::io::Result<Option<PathBuf>> {
             return Ok(Some(current_dir));
         }
 
+        if crate::vcs::is_bare_repo(&current_dir) {
+            return Ok(Some(current_dir));
+        }
+
         if dot_git.is_file() {
             // Worktrees expose a .git file pointing at the real gitdir.
             if fs::read_to_string(&dot_git)
@@ -114,6 +121,8 @@ pub struct Auditor {
     last_agent: Option<AgentInvocationContext>,
     #[serde(skip)]
     last_run: Option<AgentRunRecord>,
+    #[serde(skip)]
+    agent_env: BTreeMap<String, String>,
     #[serde(default)]
     operations: Vec<serde_json::Value>,
 }
@@ -135,6 +144,7 @@ impl Auditor {
             last_session_artifact: None,
             last_agent: None,
             last_run: None,
+            agent_env: BTreeMap::new(),
             operations: Vec::new(),
         }
     }
@@ -195,6 +205,13 @@ impl Auditor {
         }
     }
 
+    /// Records the extra environment (already redacted) the latest agent process was given.
+    pub fn record_agent_env(env: BTreeMap<String, String>) {
+        if let Ok(mut auditor) = AUDITOR.lock() {
+            auditor.agent_env = env;
+        }
+    }
+
     pub fn record_operation(kind: &str, details: serde_json::Value) {
         if let Ok(mut auditor) = AUDITOR.lock() {
             auditor
@@ -203,6 +220,51 @@ impl Auditor {
         }
     }
 
+    pub fn record_gate(gate: &CommandOutcomeGate) {
+        if let Ok(details) = serde_json::to_value(gate) {
+            Self::record_operation("gate", details);
+        }
+    }
+
+    pub fn recorded_gates() -> Vec<CommandOutcomeGate> {
+        let Ok(auditor) = AUDITOR.lock() else {
+            return Vec::new();
+        };
+        auditor
+            .operations
+            .iter()
+            .filter(|operation| {
+                operation.get("kind").and_then(|kind| kind.as_str()) == Some("gate")
+            })
+            .filter_map(|operation| operation.get("details").cloned())
+            .filter_map(|details| serde_json::from_value(details).ok())
+            .collect()
+    }
+
+    pub fn session_id() -> String {
+        AUDITOR
+            .lock()
+            .map(|auditor| auditor.session_id.clone())
+            .unwrap_or_default()
+    }
+
+    /// Writes `outcome.json` into this session's directory (next to `session.json`).
+    pub fn persist_command_outcome(
+        project_root: &Path,
+        outcome: &CommandOutcome,
+    ) -> Option<SessionArtifact> {
+        match Self::write_outcome_file(project_root, outcome) {
+            Ok(artifact) => Some(artifact),
+            Err(err) => {
+                display::warn(format!(
+                    "Failed to write command outcome for {}: {}",
+                    outcome.session_id, err
+                ));
+                None
+            }
+        }
+    }
+
     pub fn latest_agent_context() -> Option<AgentInvocationContext> {
         AUDITOR
             .lock()
@@ -253,19 +315,27 @@ impl Auditor {
         };
 
         if let Err(err) = file_tracking::FileTracker::sync_vizier_changes(&project_root) {
-            display::debug(format!(
-                "Unable to auto-detect .vizier changes; continuing without sync ({})",
-                err
-            ));
+            display::log(
+                display::Subsystem::Git,
+                display::LogLevel::Debug,
+                format!(
+                    "Unable to auto-detect .vizier changes; continuing without sync ({})",
+                    err
+                ),
+            );
         }
 
         let pending_paths = match file_tracking::FileTracker::pending_paths(&project_root) {
             Ok(paths) => paths,
             Err(err) => {
-                display::debug(format!(
-                    "Unable to enumerate pending .vizier changes; treating as clean ({})",
-                    err
-                ));
+                display::log(
+                    display::Subsystem::Git,
+                    display::LogLevel::Debug,
+                    format!(
+                        "Unable to enumerate pending .vizier changes; treating as clean ({})",
+                        err
+                    ),
+                );
                 Vec::new()
             }
         };
@@ -280,13 +350,20 @@ impl Auditor {
 
         let root = project_root.to_str().unwrap();
 
+        let never_send = crate::never_send::NeverSend::from_config(&project_root);
         let mut diff_chunks = Vec::new();
         for target in tools::story_diff_targets() {
             match vcs::get_diff(root, Some(&target), None) {
-                Ok(diff) => diff_chunks.push(diff),
-                Err(err) => display::debug(format!(
-                    "Unable to compute narrative diff for {target}: {err}"
-                )),
+                Ok(diff) => {
+                    let (diff, withheld) = never_send.filter_diff(&diff);
+                    crate::never_send::record_withheld("narrative_diff", &withheld);
+                    diff_chunks.push(diff);
+                }
+                Err(err) => display::log(
+                    display::Subsystem::Git,
+                    display::LogLevel::Debug,
+                    format!("Unable to compute narrative diff for {target}: {err}"),
+                ),
             }
         }
 
@@ -309,7 +386,10 @@ impl Auditor {
             );
         }
 
-        if matches!(disposition, CommitDisposition::Hold) {
+        if matches!(
+            disposition,
+            CommitDisposition::Hold | CommitDisposition::Patch
+        ) {
             return Ok(AuditResult {
                 session_artifact,
                 state: AuditState::Pending,
@@ -334,15 +414,10 @@ impl Auditor {
         path: &Path,
     ) -> Result<Vec<Message>, Box<dyn std::error::Error>> {
         let contents = fs::read_to_string(path)?;
-        Ok(Self::parse_session_messages(&contents)?)
-    }
-
-    fn parse_session_messages(contents: &str) -> Result<Vec<Message>, serde_json::Error> {
-        if let Ok(wrapper) = serde_json::from_str::<SessionLogWrapper>(contents) {
-            return Ok(wrapper.messages);
-        }
-
-        serde_json::from_str(contents)
+        let log: serde_json::Value = serde_json::from_str(&contents)?;
+        session_schema::validate_session_log(&log)
+            .map_err(|err| format!("{}: {err}", path.display()))?;
+        Ok(serde_json::from_value::<SessionLogMessages>(log)?.messages)
     }
 
     pub fn persist_session_log() -> Option<SessionArtifact> {
@@ -381,7 +456,8 @@ impl Auditor {
 
         let cfg = config::get_config();
         Some(SessionLog {
-            schema: "vizier.session.v1".to_string(),
+            schema: session_schema::SESSION_SCHEMA.to_string(),
+            schema_version: session_schema::SESSION_SCHEMA_VERSION,
             id: self.session_id.clone(),
             created_at: self.session_start.clone(),
             updated_at: Utc::now().to_rfc3339(),
@@ -394,10 +470,16 @@ impl Auditor {
             model: Self::model_snapshot(self.last_agent.as_ref(), &cfg),
             messages: self.messages.clone(),
             agent: self.last_run.as_ref().map(SessionAgentRun::from),
+            agent_env: self.agent_env.clone(),
             operations: self.operations.clone(),
             artifacts: Vec::new(),
             outcome: SessionOutcome {
-                status: "completed".to_string(),
+                status: if interrupt::is_interrupted() {
+                    "cancelled"
+                } else {
+                    "completed"
+                }
+                .to_string(),
                 summary: Self::summarize_assistant(&self.messages),
             },
         })
@@ -474,6 +556,7 @@ impl Auditor {
 
     fn config_snapshot(cfg: &config::Config) -> serde_json::Value {
         json!({
+            "profile": cfg.profile.clone(),
             "backend": cfg.backend.to_string(),
             "agent": {
                 "label": cfg.agent_runtime.label.clone(),
@@ -545,16 +628,35 @@ impl Auditor {
         fs::create_dir_all(&sessions_dir)?;
 
         let session_path = sessions_dir.join("session.json");
-        let tmp_path = sessions_dir.join("session.json.tmp");
-        let buffer = serde_json::to_vec_pretty(log).map_err(std::io::Error::other)?;
-        let mut tmp = File::create(&tmp_path)?;
-        tmp.write_all(&buffer)?;
-        tmp.sync_all()?;
-        fs::rename(&tmp_path, &session_path)?;
+        let value = serde_json::to_value(log).map_err(std::io::Error::other)?;
+        session_schema::validate_session_log(&value).map_err(std::io::Error::other)?;
+        let buffer = serde_json::to_vec_pretty(&value).map_err(std::io::Error::other)?;
+        crate::atomic_file::write_atomic(&session_path, &buffer)?;
 
         Ok(SessionArtifact::new(&log.id, session_path, project_root))
     }
 
+    fn write_outcome_file(
+        project_root: &Path,
+        outcome: &CommandOutcome,
+    ) -> Result<SessionArtifact, std::io::Error> {
+        let sessions_dir = project_root
+            .join(".vizier")
+            .join("sessions")
+            .join(&outcome.session_id);
+        fs::create_dir_all(&sessions_dir)?;
+
+        let outcome_path = sessions_dir.join("outcome.json");
+        let buffer = serde_json::to_vec_pretty(outcome).map_err(std::io::Error::other)?;
+        crate::atomic_file::write_atomic(&outcome_path, &buffer)?;
+
+        Ok(SessionArtifact::new(
+            &outcome.session_id,
+            outcome_path,
+            project_root,
+        ))
+    }
+
     /// Basic LLM request without tool usage for an explicit command alias/template context.
     pub async fn llm_request_for_alias_template(
         alias: &config::CommandAlias,
@@ -621,7 +723,7 @@ impl Auditor {
 
     /// Builds the prompt string sent to the agent runner, embedding the user
     /// message for commit flows so the diff is present in the request payload.
-    fn render_prompt(
+    pub fn render_prompt(
         system_prompt: &str,
         user_message: &str,
         prompt_variant: Option<SystemPrompt>,
@@ -649,7 +751,11 @@ impl Auditor {
         Self::record_agent(agent, prompt_variant);
         let runtime_opts = agent.agent_runtime.clone();
         let agent_scope = agent.scope;
-        let rendered_prompt = Self::render_prompt(&system_prompt, &user_message, prompt_variant);
+        let rendered_prompt = crate::i18n::with_language_directive(Self::render_prompt(
+            &system_prompt,
+            &user_message,
+            prompt_variant,
+        ));
         let mut messages = AUDITOR.lock().unwrap().messages.clone();
         messages.push(Message::user(user_message));
         Self::replace_messages(&messages);
@@ -662,6 +768,7 @@ impl Auditor {
         };
         let messages_clone = messages.clone();
         let opts_clone = runtime_opts.clone();
+        let env_clone = agent.env.clone();
         let prompt_clone = rendered_prompt.clone();
         let mut metadata = BTreeMap::new();
         metadata.insert("agent_backend".to_string(), agent.backend.to_string());
@@ -687,6 +794,9 @@ impl Auditor {
             config::AgentRuntimeResolution::ProvidedCommand => {
                 metadata.insert("agent_command_source".to_string(), "configured".to_string());
             }
+            config::AgentRuntimeResolution::Mock => {
+                metadata.insert("agent_command_source".to_string(), "mock".to_string());
+            }
         }
 
         let codex_run = display::call_with_status(async move |tx| {
@@ -700,6 +810,7 @@ impl Auditor {
                 scope: agent_scope,
                 metadata,
                 timeout: Some(DEFAULT_AGENT_TIMEOUT),
+                env: env_clone.clone(),
             };
 
             let response = runner
@@ -748,7 +859,11 @@ impl Auditor {
 
         let runtime_opts = agent.agent_runtime.clone();
         let agent_scope = agent.scope;
-        let rendered_prompt = Self::render_prompt(&system_prompt, &user_message, prompt_variant);
+        let rendered_prompt = crate::i18n::with_language_directive(Self::render_prompt(
+            &system_prompt,
+            &user_message,
+            prompt_variant,
+        ));
         let mut messages = AUDITOR.lock().unwrap().messages.clone();
         messages.push(Message::user(user_message));
         Self::replace_messages(&messages);
@@ -786,6 +901,9 @@ impl Auditor {
             config::AgentRuntimeResolution::ProvidedCommand => {
                 metadata.insert("agent_command_source".to_string(), "configured".to_string());
             }
+            config::AgentRuntimeResolution::Mock => {
+                metadata.insert("agent_command_source".to_string(), "mock".to_string());
+            }
         }
         let request = AgentRequest {
             prompt: rendered_prompt.clone(),
@@ -797,6 +915,7 @@ impl Auditor {
             scope: agent_scope,
             metadata,
             timeout: Some(DEFAULT_AGENT_TIMEOUT),
+            env: agent.env.clone(),
         };
 
         match runner.execute(request, progress_hook).await {
@@ -933,6 +1052,7 @@ struct SessionOutcome {
 #[derive(Serialize, Deserialize, Clone)]
 struct SessionLog {
     schema: String,
+    schema_version: u64,
     id: String,
     created_at: String,
     updated_at: String,
@@ -945,16 +1065,24 @@ struct SessionLog {
     model: SessionModelInfo,
     messages: Vec<Message>,
     agent: Option<SessionAgentRun>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    agent_env: BTreeMap<String, String>,
     operations: Vec<serde_json::Value>,
     artifacts: Vec<String>,
     outcome: SessionOutcome,
 }
 
 #[derive(Deserialize)]
-struct SessionLogWrapper {
+struct SessionLogMessages {
     messages: Vec<Message>,
 }
 
+/// Records the current session's provenance for `commit` in `refs/notes/vizier` when
+/// `[commits.meta] style = "notes"`; a no-op for every other style.
+pub fn record_commit_note(repo_path: &Path, commit: git2::Oid, plan: Option<&str>) {
+    CommitMessageBuilder::new(String::new()).record_note(repo_path, commit, plan);
+}
+
 fn meta_lines(
     meta: &config::CommitMetaConfig,
     session_id: &str,
@@ -1117,6 +1245,39 @@ impl CommitMessageBuilder {
         message
     }
 
+    /// Note lines for a commit built from this message: the `[commits.meta]` fields plus
+    /// `Vizier-Plan` when the plan is known. Empty unless `style = "notes"`, since every other
+    /// style keeps the fields in the message.
+    pub fn note_lines(&self, plan: Option<&str>) -> Vec<String> {
+        let cfg = config::get_config();
+        let meta = &cfg.commits.meta;
+        if !meta.enabled || !matches!(meta.style, config::CommitMetaStyle::Notes) {
+            return Vec::new();
+        }
+        let mut lines = meta_lines(
+            meta,
+            &self.session_id,
+            self.session_artifact.as_ref(),
+            self.author_note.as_ref(),
+        );
+        if let Some(plan) = plan.map(str::trim).filter(|plan| !plan.is_empty()) {
+            lines.push(format!("Vizier-Plan: {plan}"));
+        }
+        lines
+    }
+
+    /// Writes [`Self::note_lines`] to `commit`'s `refs/notes/vizier` note. A failed write only
+    /// warns: the commit itself already landed.
+    pub fn record_note(&self, repo_path: &Path, commit: git2::Oid, plan: Option<&str>) {
+        let lines = self.note_lines(plan);
+        if let Err(err) = crate::vcs::write_commit_note_in(repo_path, commit, &lines) {
+            crate::display::warn(format!(
+                "unable to record {} note on {commit}: {err}",
+                crate::vcs::VIZIER_NOTES_REF
+            ));
+        }
+    }
+
     fn split_subject_from_body(body: &str) -> (Option<String>, String) {
         let mut subject = None;
         let mut seen_subject = false;
@@ -1272,6 +1433,34 @@ mod tests {
         });
     }
 
+    #[test]
+    fn commit_builder_moves_meta_to_notes() {
+        let mut cfg = config::Config::default();
+        cfg.commits.meta.style = config::CommitMetaStyle::Notes;
+        cfg.commits.meta.include = vec![
+            config::CommitMetaField::SessionId,
+            config::CommitMetaField::AuthorNote,
+        ];
+
+        with_config(cfg, || {
+            let mut builder = CommitMessageBuilder::new("feat: notes test\n\nBody".to_string());
+            builder
+                .set_header(CommitMessageType::CodeChange)
+                .with_author_note("imported".to_string());
+            assert_eq!(builder.build(), "feat: notes test\n\nBody");
+            let lines = builder.note_lines(Some("alpha"));
+            assert_eq!(lines.len(), 3, "{lines:?}");
+            assert!(lines[0].starts_with("Session ID: "), "{lines:?}");
+            assert_eq!(lines[1], "Author note: imported");
+            assert_eq!(lines[2], "Vizier-Plan: alpha");
+        });
+
+        with_config(config::Config::default(), || {
+            let builder = CommitMessageBuilder::new("feat: header".to_string());
+            assert!(builder.note_lines(Some("alpha")).is_empty());
+        });
+    }
+
     #[test]
     fn commit_builder_respects_meta_labels_and_includes() {
         let mut cfg = config::Config::default();
@@ -0,0 +1,477 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use git2::{Repository, Sort};
+
+use crate::config::{CHANGELOG_TYPES, ChangelogConfig, ChangelogStyle};
+
+/// Commit trailer naming the change type of a plan's fragment (`Changelog-Type: fixed`).
+pub const TYPE_TRAILER: &str = "Changelog-Type";
+/// Commit trailer adding an entry to a plan's fragment; `Changelog: skip` writes no fragment.
+pub const ENTRY_TRAILER: &str = "Changelog";
+const SUMMARY_MAX_CHARS: usize = 200;
+const CHANGELOG_HEADER: &str =
+    "# Changelog\n\nAll notable changes to this project are documented in this file.\n";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChangelogEntry {
+    /// One of [`CHANGELOG_TYPES`].
+    pub change_type: String,
+    pub text: String,
+}
+
+/// The changelog entries one merged plan contributes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChangelogFragment {
+    pub slug: String,
+    pub entries: Vec<ChangelogEntry>,
+}
+
+/// Builds the fragment for merging `source_branch`: the plan title and summary, plus any
+/// `Changelog:` trailers on the commits the merge brings in. The change type comes from the
+/// newest `Changelog-Type:` trailer, else `config.default_type`. Returns `None` when a commit
+/// opts out with `Changelog: skip`.
+pub fn fragment_for_plan(
+    repo_root: &Path,
+    source_branch: &str,
+    target: Option<&str>,
+    slug: &str,
+    plan_document: &str,
+    config: &ChangelogConfig,
+) -> Result<Option<ChangelogFragment>, git2::Error> {
+    let repo = Repository::open(repo_root)?;
+    let source = repo.revparse_single(source_branch)?.peel_to_commit()?;
+    let mut walk = repo.revwalk()?;
+    walk.push(source.id())?;
+    if let Ok(target) = repo
+        .revparse_single(target.unwrap_or("HEAD"))
+        .and_then(|object| object.peel_to_commit())
+        && let Ok(base) = repo.merge_base(source.id(), target.id())
+    {
+        walk.hide(base)?;
+    }
+    walk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+
+    let mut change_type = config.default_type.clone();
+    let mut extra = Vec::new();
+    for oid in walk {
+        let commit = repo.find_commit(oid?)?;
+        let Some(message) = commit.message() else {
+            continue;
+        };
+        let Ok(trailers) = git2::message_trailers_strs(message) else {
+            continue;
+        };
+        for (key, value) in trailers.iter() {
+            let value = value.trim();
+            if key.eq_ignore_ascii_case(TYPE_TRAILER) {
+                let value = value.to_ascii_lowercase();
+                if CHANGELOG_TYPES.contains(&value.as_str()) {
+                    change_type = value;
+                }
+            } else if key.eq_ignore_ascii_case(ENTRY_TRAILER) {
+                if value.eq_ignore_ascii_case("skip") || value.eq_ignore_ascii_case("none") {
+                    return Ok(None);
+                }
+                if !value.is_empty() && !extra.iter().any(|entry| entry == value) {
+                    extra.push(value.to_string());
+                }
+            }
+        }
+    }
+
+    let title = crate::plan::plan_title(plan_document).unwrap_or_else(|| slug.to_string());
+    let headline = match plan_summary(plan_document) {
+        Some(summary) if summary != title => format!("{title} — {summary}"),
+        _ => title,
+    };
+    let entries = std::iter::once(headline)
+        .chain(extra)
+        .map(|text| ChangelogEntry {
+            change_type: change_type.clone(),
+            text,
+        })
+        .collect();
+    Ok(Some(ChangelogFragment {
+        slug: slug.to_string(),
+        entries,
+    }))
+}
+
+/// Writes `fragment` under `config.dir` in the configured style and returns the repo-relative
+/// paths written.
+pub fn write_fragment(
+    repo_root: &Path,
+    config: &ChangelogConfig,
+    fragment: &ChangelogFragment,
+) -> io::Result<Vec<String>> {
+    let dir = repo_root.join(&config.dir);
+    fs::create_dir_all(&dir)?;
+    let mut written = Vec::new();
+    let mut write = |name: String, contents: String| -> io::Result<()> {
+        fs::write(dir.join(&name), contents)?;
+        written.push(format!("{}/{name}", config.dir.trim_end_matches('/')));
+        Ok(())
+    };
+    match config.style {
+        ChangelogStyle::KeepAChangelog => {
+            write(
+                format!("{}.md", fragment.slug),
+                render_sections(&fragment.entries),
+            )?;
+        }
+        ChangelogStyle::Towncrier => {
+            for change_type in CHANGELOG_TYPES {
+                let lines = fragment
+                    .entries
+                    .iter()
+                    .filter(|entry| entry.change_type == change_type)
+                    .map(|entry| format!("{}\n", entry.text))
+                    .collect::<String>();
+                if !lines.is_empty() {
+                    write(format!("{}.{change_type}.md", fragment.slug), lines)?;
+                }
+            }
+        }
+    }
+    Ok(written)
+}
+
+/// Fragments waiting under `config.dir`, in file-name order. Both styles are read regardless of
+/// `config.style`: `<name>.<type>.md` files are towncrier fragments, anything else `.md` is read
+/// for `### <Type>` sections.
+pub fn read_fragments(
+    repo_root: &Path,
+    config: &ChangelogConfig,
+) -> io::Result<Vec<(PathBuf, Vec<ChangelogEntry>)>> {
+    let dir = repo_root.join(&config.dir);
+    let mut paths = match fs::read_dir(&dir) {
+        Ok(entries) => entries
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "md"))
+            .filter(|path| {
+                path.file_name()
+                    .is_some_and(|name| !name.eq_ignore_ascii_case("README.md"))
+            })
+            .collect::<Vec<_>>(),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+        Err(err) => return Err(err),
+    };
+    paths.sort();
+
+    let mut fragments = Vec::new();
+    for path in paths {
+        let contents = fs::read_to_string(&path)?;
+        let stem = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let towncrier_type = stem
+            .rsplit_once('.')
+            .map(|(_, change_type)| change_type.to_ascii_lowercase())
+            .filter(|change_type| CHANGELOG_TYPES.contains(&change_type.as_str()));
+        let entries = match towncrier_type {
+            Some(change_type) => contents
+                .lines()
+                .map(strip_bullet)
+                .filter(|line| !line.is_empty())
+                .map(|text| ChangelogEntry {
+                    change_type: change_type.clone(),
+                    text: text.to_string(),
+                })
+                .collect(),
+            None => parse_sections(&contents),
+        };
+        if !entries.is_empty() {
+            fragments.push((path, entries));
+        }
+    }
+    Ok(fragments)
+}
+
+/// A release section built from the waiting fragments, and the changelog with it inserted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChangelogAssembly {
+    pub section: String,
+    pub changelog: String,
+    pub changelog_path: PathBuf,
+    pub fragments: Vec<PathBuf>,
+}
+
+/// Rolls every waiting fragment into a `## [<version>] - <date>` section placed above the newest
+/// release in `config.file` (which is created when missing). `None` when no fragments wait.
+pub fn assemble(
+    repo_root: &Path,
+    config: &ChangelogConfig,
+    version: &str,
+    date: &str,
+) -> io::Result<Option<ChangelogAssembly>> {
+    let fragments = read_fragments(repo_root, config)?;
+    if fragments.is_empty() {
+        return Ok(None);
+    }
+    let entries = fragments
+        .iter()
+        .flat_map(|(_, entries)| entries.iter().cloned())
+        .collect::<Vec<_>>();
+    let heading = if version.eq_ignore_ascii_case("unreleased") {
+        "## [Unreleased]".to_string()
+    } else {
+        format!("## [{version}] - {date}")
+    };
+    let section = format!("{heading}\n\n{}", render_sections(&entries));
+
+    let changelog_path = repo_root.join(&config.file);
+    let existing = match fs::read_to_string(&changelog_path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(err),
+    };
+    let changelog = insert_section(&existing, &section);
+    Ok(Some(ChangelogAssembly {
+        section,
+        changelog,
+        changelog_path,
+        fragments: fragments.into_iter().map(|(path, _)| path).collect(),
+    }))
+}
+
+/// Writes the assembled changelog and removes the fragments it consumed, returning every
+/// repo-relative path touched.
+pub fn write_assembly(repo_root: &Path, assembly: &ChangelogAssembly) -> io::Result<Vec<String>> {
+    if let Some(parent) = assembly.changelog_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&assembly.changelog_path, &assembly.changelog)?;
+    let mut touched = vec![relative(repo_root, &assembly.changelog_path)];
+    for fragment in &assembly.fragments {
+        fs::remove_file(fragment)?;
+        touched.push(relative(repo_root, fragment));
+    }
+    Ok(touched)
+}
+
+fn render_sections(entries: &[ChangelogEntry]) -> String {
+    CHANGELOG_TYPES
+        .iter()
+        .filter_map(|change_type| {
+            let bullets = entries
+                .iter()
+                .filter(|entry| entry.change_type == *change_type)
+                .map(|entry| format!("- {}\n", entry.text))
+                .collect::<String>();
+            (!bullets.is_empty()).then(|| format!("### {}\n\n{bullets}", title_case(change_type)))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn parse_sections(contents: &str) -> Vec<ChangelogEntry> {
+    let mut entries: Vec<ChangelogEntry> = Vec::new();
+    let mut change_type: Option<String> = None;
+    for line in contents.lines() {
+        if let Some(heading) = line.trim().strip_prefix("### ") {
+            change_type = Some(heading.trim().to_ascii_lowercase())
+                .filter(|heading| CHANGELOG_TYPES.contains(&heading.as_str()));
+            continue;
+        }
+        let Some(change_type) = change_type.as_ref() else {
+            continue;
+        };
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        if trimmed.starts_with("- ") || trimmed.starts_with("* ") {
+            entries.push(ChangelogEntry {
+                change_type: change_type.clone(),
+                text: strip_bullet(trimmed).to_string(),
+            });
+        } else if let Some(last) = entries.last_mut() {
+            last.text.push(' ');
+            last.text.push_str(trimmed);
+        }
+    }
+    entries
+}
+
+fn insert_section(existing: &str, section: &str) -> String {
+    if existing.trim().is_empty() {
+        return format!("{CHANGELOG_HEADER}\n{section}");
+    }
+    let mut offset = 0;
+    for line in existing.split_inclusive('\n') {
+        if line.starts_with("## ") {
+            return format!("{}{section}\n{}", &existing[..offset], &existing[offset..]);
+        }
+        offset += line.len();
+    }
+    format!("{}\n\n{section}", existing.trim_end())
+}
+
+/// First line of the plan's `## Overview` (or `## Operator Spec`) section, clipped.
+fn plan_summary(plan_document: &str) -> Option<String> {
+    for heading in ["## Overview", "## Operator Spec"] {
+        let mut lines = plan_document
+            .lines()
+            .skip_while(|line| line.trim() != heading);
+        if lines.next().is_none() {
+            continue;
+        }
+        let summary = lines
+            .take_while(|line| !line.starts_with("## "))
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| strip_bullet(line).to_string());
+        if let Some(summary) = summary {
+            return Some(clip(&summary));
+        }
+    }
+    None
+}
+
+fn clip(text: &str) -> String {
+    if text.chars().count() <= SUMMARY_MAX_CHARS {
+        return text.to_string();
+    }
+    let mut clipped = text.chars().take(SUMMARY_MAX_CHARS - 1).collect::<String>();
+    clipped.push('…');
+    clipped
+}
+
+fn strip_bullet(line: &str) -> &str {
+    let line = line.trim();
+    line.strip_prefix("- ")
+        .or_else(|| line.strip_prefix("* "))
+        .unwrap_or(line)
+        .trim()
+}
+
+fn title_case(word: &str) -> String {
+    let mut chars = word.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+fn relative(repo_root: &Path, path: &Path) -> String {
+    path.strip_prefix(repo_root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit(repo: &Repository, message: &str) {
+        let mut index = repo.index().expect("index");
+        index
+            .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+            .expect("add");
+        index.write().expect("write index");
+        let tree = repo
+            .find_tree(index.write_tree().expect("tree"))
+            .expect("find tree");
+        let sig = git2::Signature::now("vizier", "vizier@example.com").expect("sig");
+        let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+        let parents = parent.iter().collect::<Vec<_>>();
+        repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+            .expect("commit");
+    }
+
+    #[test]
+    fn plan_fragment_uses_title_summary_and_trailers_then_assembles() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let root = dir.path();
+        let repo = Repository::init(root).expect("init");
+        fs::write(root.join("lib.rs"), "fn a() {}\n").expect("write");
+        commit(&repo, "base");
+        let base = repo.head().expect("head").peel_to_commit().expect("commit");
+        repo.branch("main", &base, true).expect("branch");
+        repo.branch("draft/login", &base, true).expect("branch");
+        repo.set_head("refs/heads/draft/login").expect("set head");
+        fs::write(root.join("lib.rs"), "fn a() {}\nfn b() {}\n").expect("write");
+        commit(
+            &repo,
+            "feat: add b\n\nChangelog-Type: fixed\nChangelog: Sessions survive restarts\n",
+        );
+
+        let plan = "---\nplan: login\n---\n\n# Fix login redirects\n\n## Overview\nUsers land on the right page after login.\n";
+        let config = ChangelogConfig::default();
+        let fragment = fragment_for_plan(root, "draft/login", Some("main"), "login", plan, &config)
+            .expect("fragment")
+            .expect("not skipped");
+        assert_eq!(
+            fragment.entries,
+            vec![
+                ChangelogEntry {
+                    change_type: "fixed".to_string(),
+                    text: "Fix login redirects — Users land on the right page after login."
+                        .to_string(),
+                },
+                ChangelogEntry {
+                    change_type: "fixed".to_string(),
+                    text: "Sessions survive restarts".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            write_fragment(root, &config, &fragment).expect("write"),
+            vec![".changelog/login.md"]
+        );
+        let towncrier = ChangelogConfig {
+            style: ChangelogStyle::Towncrier,
+            ..ChangelogConfig::default()
+        };
+        let other = ChangelogFragment {
+            slug: "docs".to_string(),
+            entries: vec![ChangelogEntry {
+                change_type: "added".to_string(),
+                text: "Document the changelog".to_string(),
+            }],
+        };
+        assert_eq!(
+            write_fragment(root, &towncrier, &other).expect("write"),
+            vec![".changelog/docs.added.md"]
+        );
+
+        fs::write(
+            root.join("CHANGELOG.md"),
+            "# Changelog\n\n## [0.1.0] - 2026-01-01\n\n### Added\n\n- First release\n",
+        )
+        .expect("write");
+        let assembly = assemble(root, &config, "0.2.0", "2026-02-01")
+            .expect("assemble")
+            .expect("fragments");
+        assert_eq!(
+            assembly.changelog,
+            "# Changelog\n\n## [0.2.0] - 2026-02-01\n\n### Added\n\n- Document the changelog\n\n### Fixed\n\n- Fix login redirects — Users land on the right page after login.\n- Sessions survive restarts\n\n## [0.1.0] - 2026-01-01\n\n### Added\n\n- First release\n"
+        );
+        let touched = write_assembly(root, &assembly).expect("write assembly");
+        assert_eq!(
+            touched,
+            vec![
+                "CHANGELOG.md",
+                ".changelog/docs.added.md",
+                ".changelog/login.md"
+            ]
+        );
+        assert!(
+            assemble(root, &config, "0.3.0", "2026-03-01")
+                .expect("assemble")
+                .is_none()
+        );
+
+        commit(&repo, "chore: opt out\n\nChangelog: skip\n");
+        assert!(
+            fragment_for_plan(root, "draft/login", Some("main"), "login", plan, &config)
+                .expect("fragment")
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,732 @@
+//! Proposes `[review.checks]` commands from the repository's CI config
+//! (`.github/workflows/*.yml` and `.gitlab-ci.yml`), so review runs what CI runs.
+
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_yaml_ng::Value as Yaml;
+
+pub const GITHUB_WORKFLOWS_DIR: &str = ".github/workflows";
+pub const GITLAB_CI_FILE: &str = ".gitlab-ci.yml";
+
+/// Top-level `.gitlab-ci.yml` keys that configure the pipeline rather than name a job.
+const GITLAB_RESERVED_KEYS: &[&str] = &[
+    "after_script",
+    "before_script",
+    "cache",
+    "default",
+    "image",
+    "include",
+    "pages:deploy",
+    "services",
+    "stages",
+    "variables",
+    "workflow",
+];
+
+/// Actions that only prepare the runner; a local checkout already has what they provide.
+const SETUP_ACTIONS: &[&str] = &[
+    "actions/cache",
+    "actions/checkout",
+    "actions/download-artifact",
+    "actions/upload-artifact",
+    "dtolnay/rust-toolchain",
+    "Swatinem/rust-cache",
+];
+
+/// Commands that install tooling on the runner; locally they are assumed done already.
+const SETUP_COMMANDS: &[&str] = &[
+    "apk add",
+    "apt install",
+    "apt-get install",
+    "apt-get update",
+    "brew install",
+    "cargo install",
+    "choco install",
+    "corepack enable",
+    "dnf install",
+    "gem install",
+    "go install",
+    "npm install -g",
+    "pip install",
+    "pip3 install",
+    "python -m pip install",
+    "rustup",
+    "yum install",
+];
+
+/// A proposed review check and the CI step it came from.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ImportedCheck {
+    pub command: String,
+    pub source: String,
+}
+
+/// A CI step that was not turned into a check, with the reason.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct SkippedStep {
+    pub source: String,
+    pub reason: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct CiImport {
+    /// CI files read, relative to the repo root.
+    pub files: Vec<String>,
+    pub checks: Vec<ImportedCheck>,
+    pub skipped: Vec<SkippedStep>,
+}
+
+impl CiImport {
+    fn extend(&mut self, other: CiImport) {
+        self.files.extend(other.files);
+        for check in other.checks {
+            // The same command in several jobs (or matrix legs) only needs to run once.
+            if !self
+                .checks
+                .iter()
+                .any(|existing| existing.command == check.command)
+            {
+                self.checks.push(check);
+            }
+        }
+        self.skipped.extend(other.skipped);
+    }
+
+    /// Renders the proposal as a `[review.checks]` block, each command annotated with its source.
+    pub fn to_toml(&self) -> String {
+        let mut out = String::from("[review.checks]\ncommands = [\n");
+        for check in &self.checks {
+            out.push_str(&format!(
+                "  {},  # {}\n",
+                toml::Value::String(check.command.clone()),
+                check.source
+            ));
+        }
+        out.push_str("]\n");
+        out
+    }
+}
+
+/// Reads every GitHub Actions workflow and the GitLab CI file under `root`.
+pub fn import_ci(root: &Path) -> Result<CiImport, Box<dyn std::error::Error>> {
+    let mut import = CiImport::default();
+    let workflows_dir = root.join(GITHUB_WORKFLOWS_DIR);
+    if workflows_dir.is_dir() {
+        let mut paths = fs::read_dir(&workflows_dir)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.is_file()
+                    && matches!(
+                        path.extension().and_then(|ext| ext.to_str()),
+                        Some("yml" | "yaml")
+                    )
+            })
+            .collect::<Vec<_>>();
+        paths.sort();
+        for path in paths {
+            let name = format!(
+                "{GITHUB_WORKFLOWS_DIR}/{}",
+                path.file_name().unwrap_or_default().to_string_lossy()
+            );
+            let text = fs::read_to_string(&path)?;
+            let workflow = import_github_workflow(&name, &text)
+                .map_err(|err| format!("failed to parse {name}: {err}"))?;
+            import.extend(workflow);
+        }
+    }
+    let gitlab = root.join(GITLAB_CI_FILE);
+    if gitlab.is_file() {
+        let text = fs::read_to_string(&gitlab)?;
+        let pipeline = import_gitlab_ci(GITLAB_CI_FILE, &text)
+            .map_err(|err| format!("failed to parse {GITLAB_CI_FILE}: {err}"))?;
+        import.extend(pipeline);
+    }
+    Ok(import)
+}
+
+/// Maps the `run` steps of a GitHub Actions workflow to local commands.
+pub fn import_github_workflow(name: &str, text: &str) -> Result<CiImport, serde_yaml_ng::Error> {
+    let doc = parse_yaml(text)?;
+    let mut import = CiImport {
+        files: vec![name.to_string()],
+        ..CiImport::default()
+    };
+    let workflow_env = env_entries(doc.get("env"));
+    for (job_name, job) in mapping_entries(doc.get("jobs")) {
+        let mut env = workflow_env.clone();
+        merge_env(&mut env, env_entries(job.get("env")));
+        let job_defaults = job.get("defaults").and_then(|defaults| defaults.get("run"));
+        let steps = job
+            .get("steps")
+            .and_then(Yaml::as_sequence)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        if steps.is_empty() && job.get("uses").is_some() {
+            import.skipped.push(SkippedStep {
+                source: format!("{name}: {job_name}"),
+                reason: "calls a reusable workflow; import that workflow's file instead"
+                    .to_string(),
+            });
+        }
+        for (index, step) in steps.iter().enumerate() {
+            let label = step
+                .get("name")
+                .and_then(scalar_text)
+                .unwrap_or_else(|| format!("step {}", index + 1));
+            let source = format!("{name}: {job_name} / {label}");
+            let mut skip = |reason: String| {
+                import.skipped.push(SkippedStep {
+                    source: source.clone(),
+                    reason,
+                })
+            };
+
+            let script = match (
+                step.get("run").and_then(scalar_text),
+                step.get("uses").and_then(Yaml::as_str),
+            ) {
+                (Some(script), _) => script,
+                (None, Some(action)) => match map_action(action, step.get("with")) {
+                    Ok(command) => command,
+                    Err(reason) => {
+                        skip(reason);
+                        continue;
+                    }
+                },
+                (None, None) => continue,
+            };
+            let shell = step
+                .get("shell")
+                .or_else(|| job_defaults.and_then(|run| run.get("shell")))
+                .and_then(Yaml::as_str)
+                .unwrap_or("bash");
+            if !matches!(shell.split_whitespace().next(), Some("bash" | "sh")) {
+                skip(format!("runs under `{shell}`, not a POSIX shell"));
+                continue;
+            }
+            if script.contains("${{") {
+                skip("uses a GitHub Actions `${{ ... }}` expression".to_string());
+                continue;
+            }
+            let mut step_env = env.clone();
+            merge_env(&mut step_env, env_entries(step.get("env")));
+            let working_directory = step
+                .get("working-directory")
+                .or_else(|| job_defaults.and_then(|run| run.get("working-directory")))
+                .and_then(scalar_text);
+            match local_command(
+                &script,
+                &["$GITHUB_"],
+                &step_env,
+                working_directory.as_deref(),
+            ) {
+                Ok((command, dropped)) => {
+                    if let Some(dropped) = dropped {
+                        skip(dropped);
+                    }
+                    import.checks.push(ImportedCheck {
+                        command,
+                        source: source.clone(),
+                    });
+                }
+                Err(reason) => skip(reason),
+            }
+        }
+    }
+    Ok(import)
+}
+
+/// Maps the `script` of each GitLab CI job to a local command.
+pub fn import_gitlab_ci(name: &str, text: &str) -> Result<CiImport, serde_yaml_ng::Error> {
+    let doc = parse_yaml(text)?;
+    let mut import = CiImport {
+        files: vec![name.to_string()],
+        ..CiImport::default()
+    };
+    let global_env = env_entries(doc.get("variables"));
+    for (job_name, job) in mapping_entries(Some(&doc)) {
+        if job_name.starts_with('.') || GITLAB_RESERVED_KEYS.contains(&job_name.as_str()) {
+            continue;
+        }
+        if job.as_mapping().is_none() {
+            continue;
+        }
+        let source = format!("{name}: {job_name}");
+        let Some(script) = gitlab_job_key(&doc, job, "script", 0) else {
+            continue;
+        };
+        let mut lines = Vec::new();
+        flatten_script(&doc, script, &mut lines);
+        let mut env = global_env.clone();
+        if let Some(extended) = gitlab_job_key(&doc, job, "variables", 0) {
+            merge_env(&mut env, env_entries(Some(extended)));
+        }
+        match local_command(&lines.join("\n"), &["$CI_", "${CI_"], &env, None) {
+            Ok((command, dropped)) => {
+                if let Some(dropped) = dropped {
+                    import.skipped.push(SkippedStep {
+                        source: source.clone(),
+                        reason: dropped,
+                    });
+                }
+                import.checks.push(ImportedCheck { command, source });
+            }
+            Err(reason) => import.skipped.push(SkippedStep { source, reason }),
+        }
+    }
+    Ok(import)
+}
+
+/// Looks `key` up on a job, following `extends` through hidden template jobs.
+fn gitlab_job_key<'a>(doc: &'a Yaml, job: &'a Yaml, key: &str, depth: usize) -> Option<&'a Yaml> {
+    if let Some(value) = job.get(key) {
+        return Some(value);
+    }
+    if depth > 10 {
+        return None;
+    }
+    let parents = match job.get("extends")? {
+        Yaml::Sequence(items) => items.iter().filter_map(Yaml::as_str).collect::<Vec<_>>(),
+        other => other.as_str().into_iter().collect(),
+    };
+    // Later `extends` entries win, so look at them first.
+    parents
+        .iter()
+        .rev()
+        .filter_map(|parent| doc.get(parent))
+        .find_map(|parent| gitlab_job_key(doc, parent, key, depth + 1))
+}
+
+/// Flattens a `script` (string, list, nested lists, `!reference [job, key]`) into lines.
+fn flatten_script(doc: &Yaml, script: &Yaml, lines: &mut Vec<String>) {
+    match script {
+        Yaml::Tagged(tagged) if tagged.tag == "reference" => {
+            let path = tagged
+                .value
+                .as_sequence()
+                .map(Vec::as_slice)
+                .unwrap_or_default()
+                .iter()
+                .filter_map(Yaml::as_str)
+                .collect::<Vec<_>>();
+            let target = path.iter().try_fold(doc, |node, segment| node.get(segment));
+            if let Some(target) = target {
+                flatten_script(doc, target, lines);
+            }
+        }
+        Yaml::Sequence(items) => {
+            for item in items {
+                flatten_script(doc, item, lines);
+            }
+        }
+        other => lines.extend(scalar_text(other)),
+    }
+}
+
+/// Turns a known action into the command it wraps, or explains why it has no local form.
+fn map_action(action: &str, with: Option<&Yaml>) -> Result<String, String> {
+    let name = action.split('@').next().unwrap_or(action);
+    let input = |key: &str| {
+        with.and_then(|with| with.get(key))
+            .and_then(scalar_text)
+            .unwrap_or_default()
+    };
+    let joined = |parts: &[&str]| {
+        parts
+            .iter()
+            .filter(|part| !part.trim().is_empty())
+            .map(|part| part.trim())
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    match name {
+        "actions-rs/cargo" => Ok(joined(&["cargo", &input("command"), &input("args")])),
+        "actions-rs/clippy-check" => Ok(joined(&["cargo clippy", &input("args")])),
+        "golangci/golangci-lint-action" => Ok(joined(&["golangci-lint run", &input("args")])),
+        "pre-commit/action" => Ok(joined(&[
+            "pre-commit run",
+            &with
+                .and_then(|with| with.get("extra_args"))
+                .and_then(scalar_text)
+                .unwrap_or_else(|| "--all-files".to_string()),
+        ])),
+        _ if SETUP_ACTIONS.contains(&name) || name.starts_with("actions/setup-") => Err(format!(
+            "`{action}` prepares the runner; not needed locally"
+        )),
+        _ => Err(format!(
+            "runs the `{action}` action, which has no local equivalent"
+        )),
+    }
+}
+
+/// Builds a local command from a CI script: drops setup and CI-only lines, applies plain env
+/// values and the working directory, and keeps multi-line scripts failing fast like CI does.
+/// Returns the command and a note about dropped lines, or why nothing runnable is left.
+fn local_command(
+    script: &str,
+    ci_only_markers: &[&str],
+    env: &[(String, String)],
+    working_directory: Option<&str>,
+) -> Result<(String, Option<String>), String> {
+    let mut lines = Vec::new();
+    let mut dropped = Vec::new();
+    let mut pending = String::new();
+    for line in script.lines() {
+        let line = line.trim_end();
+        // Rejoin `\` continuations so each entry is one whole shell command.
+        let line = if pending.is_empty() {
+            line
+        } else {
+            line.trim_start()
+        };
+        if let Some(head) = line.strip_suffix('\\') {
+            pending.push_str(head.trim_end());
+            pending.push(' ');
+            continue;
+        }
+        pending.push_str(line);
+        let command = std::mem::take(&mut pending);
+        let trimmed = command.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if is_setup_command(trimmed)
+            || ci_only_markers
+                .iter()
+                .any(|marker| trimmed.contains(marker))
+        {
+            dropped.push(trimmed.to_string());
+            continue;
+        }
+        lines.push(command);
+    }
+    if !pending.trim().is_empty() {
+        lines.push(pending);
+    }
+
+    if lines.is_empty() {
+        return Err(match dropped.first() {
+            Some(first) => format!("only installs tooling or sets CI state (`{first}`)"),
+            None => "empty script".to_string(),
+        });
+    }
+    let mut prefix = Vec::new();
+    if !env.is_empty() {
+        prefix.push(format!(
+            "export {}",
+            env.iter()
+                .map(|(key, value)| format!("{key}={}", shell_quote(value)))
+                .collect::<Vec<_>>()
+                .join(" ")
+        ));
+    }
+    if let Some(dir) = working_directory {
+        prefix.push(format!("cd {}", shell_quote(dir)));
+    }
+    let simple = lines.iter().all(|line| !has_control_flow(line));
+    // Dropping a line out of an `if`/loop body can leave the script meaning something else.
+    if let (false, Some(first)) = (simple, dropped.first()) {
+        return Err(format!(
+            "script with shell control flow needs `{first}`, which only works in CI; port it by hand"
+        ));
+    }
+    let command = if simple {
+        prefix
+            .into_iter()
+            .chain(lines.iter().map(|line| line.trim().to_string()))
+            .collect::<Vec<_>>()
+            .join(" && ")
+    } else {
+        let mut body = vec!["set -e".to_string()];
+        body.extend(prefix);
+        body.extend(lines);
+        body.join("\n")
+    };
+    let note = match dropped.as_slice() {
+        [] => None,
+        [only] => Some(format!("dropped setup/CI-only line `{only}`")),
+        [first, rest @ ..] => Some(format!(
+            "dropped {} setup/CI-only lines (`{first}`, ...)",
+            rest.len() + 1
+        )),
+    };
+    Ok((command, note))
+}
+
+fn is_setup_command(line: &str) -> bool {
+    let line = line.strip_prefix("sudo ").unwrap_or(line).trim_start();
+    SETUP_COMMANDS
+        .iter()
+        .any(|setup| line == *setup || line.starts_with(&format!("{setup} ")))
+        || (line.starts_with("curl ") && (line.contains("| sh") || line.contains("| bash")))
+}
+
+fn has_control_flow(line: &str) -> bool {
+    let first = line.split_whitespace().next().unwrap_or_default();
+    matches!(
+        first,
+        "if" | "then"
+            | "else"
+            | "elif"
+            | "fi"
+            | "for"
+            | "while"
+            | "until"
+            | "do"
+            | "done"
+            | "case"
+            | "esac"
+            | "function"
+            | "{"
+            | "}"
+    ) || line.trim_end().ends_with(['{', '(', '|'])
+        || line.trim_end().ends_with("&&")
+}
+
+/// Applies narrower-scope variables over broader ones, keeping each key's first position.
+fn merge_env(env: &mut Vec<(String, String)>, overrides: Vec<(String, String)>) {
+    for (key, value) in overrides {
+        match env.iter_mut().find(|(existing, _)| *existing == key) {
+            Some(entry) => entry.1 = value,
+            None => env.push((key, value)),
+        }
+    }
+}
+
+/// Plain `env`/`variables` entries; values using CI expressions are left out.
+fn env_entries(value: Option<&Yaml>) -> Vec<(String, String)> {
+    mapping_entries(value)
+        .into_iter()
+        .filter_map(|(key, value)| {
+            let text = scalar_text(value).or_else(|| value.get("value").and_then(scalar_text))?;
+            (!text.contains("${{") && !text.contains("$CI_") && !text.contains("${CI_"))
+                .then_some((key, text))
+        })
+        .collect()
+}
+
+/// Reads the first document of `text` with `<<` merge keys applied; an empty file is null.
+fn parse_yaml(text: &str) -> Result<Yaml, serde_yaml_ng::Error> {
+    let Some(document) = serde_yaml_ng::Deserializer::from_str(text).next() else {
+        return Ok(Yaml::Null);
+    };
+    let mut doc = Yaml::deserialize(document)?;
+    doc.apply_merge()?;
+    Ok(doc)
+}
+
+/// A scalar the way it reads as text (`true`, `3`, ...); `None` for collections and null.
+fn scalar_text(value: &Yaml) -> Option<String> {
+    match value {
+        Yaml::Bool(flag) => Some(flag.to_string()),
+        Yaml::Number(number) => Some(number.to_string()),
+        Yaml::String(text) => Some(text.clone()),
+        Yaml::Tagged(tagged) => scalar_text(&tagged.value),
+        _ => None,
+    }
+}
+
+/// The entries of a mapping in file order, keyed by their scalar text.
+fn mapping_entries(value: Option<&Yaml>) -> Vec<(String, &Yaml)> {
+    value
+        .and_then(Yaml::as_mapping)
+        .into_iter()
+        .flatten()
+        .filter_map(|(key, value)| Some((scalar_text(key)?, value)))
+        .collect()
+}
+
+fn shell_quote(value: &str) -> String {
+    if !value.is_empty()
+        && value
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || "-_./:=,+@".contains(ch))
+    {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', r"'\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn github_workflow_maps_run_steps_and_skips_setup() {
+        let import = import_github_workflow(
+            ".github/workflows/ci.yml",
+            r#"
+name: CI
+on: [push]
+env:
+  CARGO_TERM_COLOR: always
+  TOKEN: ${{ secrets.TOKEN }}
+jobs:
+  lint:
+    runs-on: ubuntu-latest
+    steps:
+      - uses: actions/checkout@v4
+      - uses: dtolnay/rust-toolchain@stable
+      - name: Format
+        run: cargo fmt --all -- --check
+      - uses: actions-rs/cargo@v1
+        with:
+          command: clippy
+          args: --all-targets -- -D warnings
+  test:
+    runs-on: ${{ matrix.os }}
+    env:
+      RUSTFLAGS: -D warnings
+    steps:
+      - run: sudo apt-get install -y libssl-dev
+      - name: Tests
+        working-directory: crates/app
+        run: |
+          rustup component add llvm-tools
+          cargo test \
+            --workspace
+          echo "done" >> $GITHUB_STEP_SUMMARY
+      - run: cargo fmt --all -- --check
+        env:
+          CARGO_TERM_COLOR: never
+      - run: Write-Host hi
+        shell: pwsh
+      - run: echo ${{ github.sha }}
+      - uses: codecov/codecov-action@v4
+"#,
+        )
+        .expect("import");
+
+        let commands = import
+            .checks
+            .iter()
+            .map(|check| check.command.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            commands,
+            [
+                "export CARGO_TERM_COLOR=always && cargo fmt --all -- --check",
+                "export CARGO_TERM_COLOR=always && cargo clippy --all-targets -- -D warnings",
+                "export CARGO_TERM_COLOR=always RUSTFLAGS='-D warnings' && cd crates/app && cargo test --workspace",
+                "export CARGO_TERM_COLOR=never RUSTFLAGS='-D warnings' && cargo fmt --all -- --check",
+            ]
+        );
+        assert_eq!(
+            import.checks[0].source,
+            ".github/workflows/ci.yml: lint / Format"
+        );
+        let reasons = import
+            .skipped
+            .iter()
+            .map(|skipped| skipped.reason.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(reasons.len(), 7, "{reasons:?}");
+        assert!(reasons[0].contains("prepares the runner"));
+        assert!(reasons[2].contains("only installs tooling"));
+        assert!(reasons[3].starts_with("dropped 2 setup/CI-only lines"));
+        assert!(reasons[4].contains("pwsh"));
+        assert!(reasons[5].contains("expression"));
+        assert!(reasons[6].contains("no local equivalent"));
+    }
+
+    #[test]
+    fn gitlab_ci_follows_extends_references_and_skips_templates() {
+        let import = import_gitlab_ci(
+            ".gitlab-ci.yml",
+            r#"
+stages: [test]
+variables:
+  NODE_ENV: test
+.node: &node
+  image: node:20
+  before_script:
+    - npm ci
+.shared:
+  script:
+    - npm run lint
+lint:
+  <<: *node
+  stage: test
+  script:
+    - !reference [.shared, script]
+    - npm run typecheck
+unit:
+  extends: .node
+  script: |
+    if [ -f package-lock.json ]; then
+      npm ci --ignore-scripts
+    fi
+    npm test
+guarded:
+  script:
+    - |
+      if [ -n "$CI_COMMIT_TAG" ]; then
+        echo "$CI_COMMIT_TAG"
+      fi
+inherited:
+  extends: .shared
+deploy:
+  script:
+    - echo "$CI_JOB_TOKEN" | docker login -u ci --password-stdin
+"#,
+        )
+        .expect("import");
+
+        let commands = import
+            .checks
+            .iter()
+            .map(|check| check.command.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            commands,
+            [
+                "export NODE_ENV=test && npm run lint && npm run typecheck",
+                "set -e\nexport NODE_ENV=test\nif [ -f package-lock.json ]; then\n  npm ci --ignore-scripts\nfi\nnpm test",
+                "export NODE_ENV=test && npm run lint",
+            ]
+        );
+        assert_eq!(import.skipped.len(), 2, "{:?}", import.skipped);
+        assert_eq!(import.skipped[0].source, ".gitlab-ci.yml: guarded");
+        assert!(import.skipped[0].reason.contains("port it by hand"));
+        assert_eq!(import.skipped[1].source, ".gitlab-ci.yml: deploy");
+    }
+
+    #[test]
+    fn import_reads_workflows_dedupes_and_renders_toml() {
+        let temp = tempfile::tempdir().unwrap();
+        let workflows = temp.path().join(GITHUB_WORKFLOWS_DIR);
+        fs::create_dir_all(&workflows).unwrap();
+        fs::write(
+            workflows.join("a.yml"),
+            "jobs:\n  t:\n    steps:\n      - run: make test\n",
+        )
+        .unwrap();
+        fs::write(
+            workflows.join("b.yaml"),
+            "jobs:\n  t:\n    steps:\n      - name: \"again\"\n        run: make test\n      - run: make \"lint\"\n",
+        )
+        .unwrap();
+        fs::write(workflows.join("notes.txt"), "not a workflow").unwrap();
+
+        let import = import_ci(temp.path()).expect("import");
+        assert_eq!(
+            import.files,
+            [".github/workflows/a.yml", ".github/workflows/b.yaml"]
+        );
+        assert_eq!(
+            import.to_toml(),
+            "[review.checks]\ncommands = [\n  \"make test\",  # .github/workflows/a.yml: t / step 1\n  'make \"lint\"',  # .github/workflows/b.yaml: t / step 2\n]\n"
+        );
+        let parsed: toml::Value = toml::from_str(&import.to_toml()).expect("valid toml");
+        assert_eq!(
+            parsed["review"]["checks"]["commands"]
+                .as_array()
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+}
@@ -0,0 +1,221 @@
+//! Post-hoc audit of the shell commands an agent backend reports starting. Backends announce a
+//! command as it begins rather than asking first, so this cannot prevent one; it can only record
+//! each command and abort the rest of the run when one is unwelcome.
+
+use std::io::{self, IsTerminal, Write};
+
+use serde::Serialize;
+use serde_json::json;
+use vizier_kernel::glob::glob_matches;
+
+use crate::{
+    auditor::Auditor,
+    config::CommandAuditConfig,
+    display::{self, LogLevel, Subsystem, Verbosity},
+};
+
+/// Whether the agent run may continue after starting a command.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditDecision {
+    Continue,
+    Abort,
+}
+
+/// Why a decision was reached; recorded next to it in the session log.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditReason {
+    AbortList,
+    AllowList,
+    Prompt,
+    NonInteractive,
+}
+
+/// Outcome of matching a command against the policy before anyone is asked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AuditVerdict {
+    Decided(AuditDecision, AuditReason),
+    Ask,
+}
+
+/// The shell command an agent stream line reports it has started, if any. Codex emits
+/// `{"type":"item.started","item":{"type":"command_execution","command":"..."}}`.
+pub fn started_command(line: &str) -> Option<String> {
+    let event: serde_json::Value = serde_json::from_str(line.trim()).ok()?;
+    if event.get("type")?.as_str()? != "item.started" {
+        return None;
+    }
+    let item = event.get("item")?;
+    if item.get("type")?.as_str()? != "command_execution" {
+        return None;
+    }
+    match item.get("command")? {
+        serde_json::Value::String(command) => Some(command.clone()),
+        serde_json::Value::Array(parts) => Some(
+            parts
+                .iter()
+                .filter_map(|part| part.as_str())
+                .collect::<Vec<_>>()
+                .join(" "),
+        ),
+        _ => None,
+    }
+}
+
+/// The command a `bash -lc '<command>'` style wrapper runs; other commands are returned as-is.
+pub fn unwrap_shell(command: &str) -> &str {
+    let trimmed = command.trim();
+    for shell in ["bash", "sh", "zsh"] {
+        for flag in ["-lc", "-c"] {
+            let Some(rest) = trimmed
+                .strip_prefix(&format!("/bin/{shell} {flag} "))
+                .or_else(|| trimmed.strip_prefix(&format!("/usr/bin/{shell} {flag} ")))
+                .or_else(|| trimmed.strip_prefix(&format!("{shell} {flag} ")))
+            else {
+                continue;
+            };
+            let rest = rest.trim();
+            for quote in ['\'', '"'] {
+                if rest.len() >= 2 && rest.starts_with(quote) && rest.ends_with(quote) {
+                    return &rest[1..rest.len() - 1];
+                }
+            }
+            return rest;
+        }
+    }
+    trimmed
+}
+
+/// Matches `command` (unwrapped from its shell) against the abort list, then the allow list.
+pub fn evaluate(policy: &CommandAuditConfig, command: &str) -> AuditVerdict {
+    let command = unwrap_shell(command);
+    let matches = |patterns: &[String]| {
+        patterns
+            .iter()
+            .any(|pattern| glob_matches(pattern.trim(), command))
+    };
+    if matches(&policy.abort) {
+        AuditVerdict::Decided(AuditDecision::Abort, AuditReason::AbortList)
+    } else if matches(&policy.allow) {
+        AuditVerdict::Decided(AuditDecision::Continue, AuditReason::AllowList)
+    } else {
+        AuditVerdict::Ask
+    }
+}
+
+/// Decides whether the run may continue after the agent started `command`, asking on the
+/// terminal when the policy has no answer and a user is there to reply. Every decision is
+/// recorded in the session log.
+pub async fn review(policy: &CommandAuditConfig, command: &str) -> AuditDecision {
+    let (decision, reason) = match evaluate(policy, command) {
+        AuditVerdict::Decided(decision, reason) => (decision, reason),
+        AuditVerdict::Ask if can_prompt() => {
+            let question = command.to_string();
+            let approved = tokio::task::spawn_blocking(move || prompt(&question))
+                .await
+                .ok()
+                .and_then(Result::ok)
+                .unwrap_or(false);
+            let decision = if approved {
+                AuditDecision::Continue
+            } else {
+                AuditDecision::Abort
+            };
+            (decision, AuditReason::Prompt)
+        }
+        AuditVerdict::Ask => (AuditDecision::Abort, AuditReason::NonInteractive),
+    };
+
+    Auditor::record_operation(
+        "agent_command_audit",
+        json!({
+            "command": command,
+            "decision": decision,
+            "reason": reason,
+        }),
+    );
+    if decision == AuditDecision::Abort {
+        display::log(
+            Subsystem::Agent,
+            LogLevel::Warn,
+            format!(
+                "aborting agent run after it started `{command}` ({})",
+                reason_label(reason)
+            ),
+        );
+    }
+    decision
+}
+
+fn reason_label(reason: AuditReason) -> &'static str {
+    match reason {
+        AuditReason::AbortList => "matched command_audit.abort",
+        AuditReason::AllowList => "matched command_audit.allow",
+        AuditReason::Prompt => "declined at prompt",
+        AuditReason::NonInteractive => "not in command_audit.allow and no TTY to ask",
+    }
+}
+
+/// Quiet and background runs never prompt; neither does anything without a terminal to ask on.
+fn can_prompt() -> bool {
+    let display = display::get_display_config();
+    display.verbosity != Verbosity::Quiet && display.stderr_is_tty && io::stdin().is_terminal()
+}
+
+fn prompt(command: &str) -> io::Result<bool> {
+    eprint!("agent started `{command}`; let it continue? [y/N]: ");
+    io::stderr().flush()?;
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(matches!(
+        answer.trim().to_ascii_lowercase().as_str(),
+        "y" | "yes"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn started_command_reads_codex_command_events() {
+        let started = r#"{"type":"item.started","item":{"id":"1","type":"command_execution","command":"bash -lc 'cargo test'"}}"#;
+        assert_eq!(
+            started_command(started).as_deref(),
+            Some("bash -lc 'cargo test'")
+        );
+        let completed = started.replace("item.started", "item.completed");
+        assert_eq!(started_command(&completed), None);
+        assert_eq!(
+            started_command(r#"{"type":"item.started","item":{"type":"agent_message"}}"#),
+            None
+        );
+        assert_eq!(started_command("plain text"), None);
+        assert_eq!(unwrap_shell("bash -lc 'cargo test'"), "cargo test");
+        assert_eq!(unwrap_shell("/bin/sh -c \"ls -la\""), "ls -la");
+        assert_eq!(unwrap_shell("git status"), "git status");
+    }
+
+    #[test]
+    fn evaluate_prefers_abort_then_allow_then_asks() {
+        let policy = CommandAuditConfig {
+            enabled: true,
+            allow: vec!["cargo *".to_string(), "git status".to_string()],
+            abort: vec!["cargo publish*".to_string(), "rm -rf *".to_string()],
+        };
+        assert_eq!(
+            evaluate(&policy, "bash -lc 'cargo test -p vizier'"),
+            AuditVerdict::Decided(AuditDecision::Continue, AuditReason::AllowList)
+        );
+        assert_eq!(
+            evaluate(&policy, "cargo publish --dry-run"),
+            AuditVerdict::Decided(AuditDecision::Abort, AuditReason::AbortList)
+        );
+        assert_eq!(
+            evaluate(&policy, "bash -lc 'rm -rf target'"),
+            AuditVerdict::Decided(AuditDecision::Abort, AuditReason::AbortList)
+        );
+        assert_eq!(evaluate(&policy, "git status --short"), AuditVerdict::Ask);
+    }
+}
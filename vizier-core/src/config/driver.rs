@@ -3,13 +3,14 @@ use std::io;
 use std::path::PathBuf;
 use std::sync::Arc;
 
-use crate::agent::{AgentRunner, ScriptRunner};
+use crate::agent::{AgentRunner, MockRunner, RecordingRunner, ScriptRunner};
 
 use super::{
-    AgentOutputHandling, AgentOverrides, AgentRuntimeOptions, AgentRuntimeResolution, BackendKind,
-    CommandAlias, CommandScope, Config, DocumentationSettings, ProfileScope, PromptKind,
-    PromptOverrides, PromptSelection, ResolvedAgentRuntime, TemplateSelector,
-    backend_kind_for_selector, compatibility_scope_for_alias, default_selector_for_backend,
+    AgentEnvSettings, AgentOutputHandling, AgentOverrides, AgentRuntimeOptions,
+    AgentRuntimeResolution, BackendKind, CommandAlias, CommandScope, Config, DocumentationSettings,
+    ProfileScope, PromptKind, PromptOverrides, PromptSelection, ResolvedAgentRuntime,
+    TemplateSelector, backend_kind_for_selector, compatibility_scope_for_alias,
+    default_selector_for_backend,
 };
 
 #[derive(Clone)]
@@ -23,6 +24,8 @@ pub struct AgentSettings {
     pub runner: Option<Arc<dyn AgentRunner>>,
     pub agent_runtime: ResolvedAgentRuntime,
     pub documentation: DocumentationSettings,
+    /// Merged `[agents.*.env]`/`env_file` settings, resolved when the agent is spawned.
+    pub env: AgentEnvSettings,
     pub prompt: Option<PromptSelection>,
     pub cli_override: Option<AgentOverrides>,
 }
@@ -294,6 +297,7 @@ struct AgentSettingsBuilder {
     backend: BackendKind,
     agent_runtime: AgentRuntimeOptions,
     documentation: DocumentationSettings,
+    env: AgentEnvSettings,
 }
 
 impl AgentSettingsBuilder {
@@ -304,6 +308,7 @@ impl AgentSettingsBuilder {
             backend: backend_kind_for_selector(&selector),
             agent_runtime: cfg.agent_runtime.clone(),
             documentation: DocumentationSettings::default(),
+            env: AgentEnvSettings::default(),
         }
     }
 
@@ -331,6 +336,7 @@ impl AgentSettingsBuilder {
         }
 
         overrides.documentation.apply_to(&mut self.documentation);
+        self.env.merge(&overrides.env);
     }
 
     fn apply_cli_override(&mut self, overrides: &AgentOverrides) {
@@ -361,6 +367,7 @@ impl AgentSettingsBuilder {
         }
 
         overrides.documentation.apply_to(&mut self.documentation);
+        self.env.merge(&overrides.env);
     }
 
     fn apply_prompt_overrides(&mut self, overrides: &PromptOverrides) {
@@ -402,6 +409,7 @@ impl AgentSettingsBuilder {
             runner: resolve_agent_runner(self.backend)?,
             agent_runtime: resolved_runtime,
             documentation: self.documentation.clone(),
+            env: self.env.clone(),
             prompt,
             cli_override: cli_override.cloned(),
         })
@@ -430,6 +438,9 @@ fn bundled_agent_shim_dir_candidates() -> Vec<PathBuf> {
         }
     }
 
+    // Repo-local shims written by `vizier init --install-shims`.
+    dirs.push(PathBuf::from(".vizier").join("agents"));
+
     if let Ok(exe) = std::env::current_exe()
         && let Some(dir) = exe.parent()
     {
@@ -470,7 +481,9 @@ fn find_in_shim_dirs(filename: &str) -> Option<PathBuf> {
             return Some(candidate);
         }
     }
-    None
+    // Nothing installed alongside the repo or binary: fall back to the per-user cache, which
+    // is filled from the shims compiled into this binary.
+    crate::global_cache::cached_agent_shim(filename)
 }
 
 fn find_first_in_shim_dirs(candidates: Vec<String>) -> Option<PathBuf> {
@@ -497,6 +510,17 @@ pub(crate) fn resolve_agent_runtime(
     let mut progress_filter = runtime.progress_filter.clone();
     let output = AgentOutputHandling::Wrapped;
 
+    if backend == BackendKind::Mock {
+        return Ok(ResolvedAgentRuntime {
+            label,
+            command: Vec::new(),
+            progress_filter: None,
+            output,
+            enable_script_wrapper: false,
+            resolution: AgentRuntimeResolution::Mock,
+        });
+    }
+
     if progress_filter.is_none() {
         progress_filter = default_progress_filter_for_label(&label);
     }
@@ -564,5 +588,10 @@ fn resolve_agent_runner(
         return Ok(None);
     }
 
-    Ok(Some(Arc::new(ScriptRunner)))
+    let runner: Arc<dyn AgentRunner> = if backend == BackendKind::Mock {
+        Arc::new(MockRunner)
+    } else {
+        Arc::new(ScriptRunner)
+    };
+    Ok(Some(RecordingRunner::wrap_from_env(runner)))
 }
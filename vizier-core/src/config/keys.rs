@@ -0,0 +1,470 @@
+//! Catalog of the keys the loader reads, so strict mode can reject typos such as
+//! `[merged.cicd_gate]` that would otherwise load as a silent no-op. Keys are compared with `-`
+//! folded to `_`, matching the loader's hyphenated aliases.
+
+use serde_json::Value;
+
+enum KeyNode {
+    /// Any value; nested tables are not checked.
+    Any,
+    /// A table whose keys must come from the list; non-table values are left to the loader.
+    Table(&'static [(&'static str, KeyNode)]),
+    /// A table keyed by user-chosen names (aliases, selectors, profiles).
+    Map(&'static KeyNode),
+    /// An array whose table entries are checked against the node.
+    List(&'static KeyNode),
+}
+
+use KeyNode::{Any, List, Map, Table};
+
+const AGENT_RUNTIME: KeyNode = Table(&[
+    ("label", Any),
+    ("command", Any),
+    ("progress_filter", Any),
+    ("output", Any),
+    ("enable_script_wrapper", Any),
+]);
+
+const PROMPT_OVERRIDE: KeyNode = Table(&[
+    ("path", Any),
+    ("file", Any),
+    ("text", Any),
+    ("prompt", Any),
+    ("template", Any),
+    ("inline", Any),
+    ("agent", AGENT_RUNTIME),
+    ("env", Any),
+    ("env_file", Any),
+]);
+
+const AGENT_SCOPE: KeyNode = Table(&[
+    ("agent", AGENT_RUNTIME),
+    ("env", Any),
+    ("env_file", Any),
+    (
+        "documentation",
+        Table(&[
+            ("enabled", Any),
+            ("enable", Any),
+            ("use_prompt", Any),
+            ("use_documentation_prompt", Any),
+            ("include_snapshot", Any),
+            ("snapshot", Any),
+            ("include_narrative_docs", Any),
+            ("include_narrative", Any),
+        ]),
+    ),
+    ("prompts", Map(&PROMPT_OVERRIDE)),
+]);
+
+static ROOT: KeyNode = Table(&[
+    ("config", Table(&[("strict", Any)])),
+    ("agent", AGENT_RUNTIME),
+    (
+        "agents",
+        Table(&[
+            ("default", AGENT_SCOPE),
+            ("commands", Map(&AGENT_SCOPE)),
+            ("templates", Map(&AGENT_SCOPE)),
+        ]),
+    ),
+    ("commands", Any),
+    ("profiles", Map(&ROOT)),
+    (
+        "review",
+        Table(&[
+            ("checks", Table(&[("commands", Any), ("jobs", Any)])),
+            ("fix_loop", Table(&[("max_iterations", Any)])),
+            ("tools", Any),
+        ]),
+    ),
+    (
+        "approve",
+        Table(&[
+            (
+                "stop_condition",
+                Table(&[
+                    ("script", Any),
+                    ("retries", Any),
+                    ("max_attempts", Any),
+                    ("conditions", Any),
+                    ("tests", Any),
+                ]),
+            ),
+            ("generate_tests", Any),
+            (
+                "cost",
+                Table(&[("input_usd_per_mtok", Any), ("output_usd_per_mtok", Any)]),
+            ),
+        ]),
+    ),
+    (
+        "merge",
+        Table(&[
+            ("squash", Any),
+            ("squash_mainline", Any),
+            ("strategy", Any),
+            ("squash_granularity", Any),
+            (
+                "cicd_gate",
+                Table(&[
+                    ("script", Any),
+                    ("path", Any),
+                    ("file", Any),
+                    ("auto_resolve", Any),
+                    ("auto_fix", Any),
+                    ("retries", Any),
+                    ("max_attempts", Any),
+                ]),
+            ),
+            (
+                "gates",
+                List(&Table(&[
+                    ("script", Any),
+                    ("command", Any),
+                    ("path", Any),
+                    ("stage", Any),
+                    ("name", Any),
+                    ("label", Any),
+                ])),
+            ),
+            ("auto_revert", Any),
+            ("protected", Any),
+            (
+                "commit_template",
+                Table(&[
+                    ("path", Any),
+                    ("file", Any),
+                    ("text", Any),
+                    ("prompt", Any),
+                    ("template", Any),
+                    ("inline", Any),
+                ]),
+            ),
+            ("require_owner_ack", Any),
+            ("conflicts", Table(&[("auto_resolve", Any)])),
+            ("conflict", Table(&[("auto_resolve", Any)])),
+        ]),
+    ),
+    ("release", Table(&[("gate", Table(&[("script", Any)]))])),
+    (
+        "build",
+        Table(&[
+            ("default_pipeline", Any),
+            ("default_merge_target", Any),
+            ("stage_barrier", Any),
+            ("failure_mode", Any),
+            ("default_review_mode", Any),
+            ("default_skip_checks", Any),
+            ("default_keep_draft_branch", Any),
+            ("default_profile", Any),
+            (
+                "profiles",
+                Map(&Table(&[
+                    ("pipeline", Any),
+                    ("default_pipeline", Any),
+                    ("merge_target", Any),
+                    ("review_mode", Any),
+                    ("skip_checks", Any),
+                    ("keep_branch", Any),
+                ])),
+            ),
+        ]),
+    ),
+    (
+        "commits",
+        Table(&[
+            (
+                "meta",
+                Table(&[
+                    ("enabled", Any),
+                    ("enable", Any),
+                    ("style", Any),
+                    ("include", Any),
+                    ("session_log_path", Any),
+                    ("session_log", Any),
+                    (
+                        "labels",
+                        Table(&[
+                            ("session_id", Any),
+                            ("session_log", Any),
+                            ("author_note", Any),
+                            ("narrative_summary", Any),
+                        ]),
+                    ),
+                ]),
+            ),
+            (
+                "fallback_subjects",
+                Table(&[
+                    ("code_change", Any),
+                    ("narrative_change", Any),
+                    ("conversation", Any),
+                    ("conversation_subject", Any),
+                ]),
+            ),
+            (
+                "implementation",
+                Table(&[("subject", Any), ("fields", Any)]),
+            ),
+            (
+                "merge",
+                Table(&[
+                    ("subject", Any),
+                    ("include_operator_note", Any),
+                    ("operator_note_label", Any),
+                    ("plan_mode", Any),
+                    ("plan_label", Any),
+                ]),
+            ),
+        ]),
+    ),
+    (
+        "display",
+        Table(&[
+            ("log", Any),
+            (
+                "lists",
+                Table(&[
+                    (
+                        "list",
+                        Table(&[
+                            ("format", Any),
+                            ("header_fields", Any),
+                            ("entry_fields", Any),
+                            ("job_fields", Any),
+                            ("command_fields", Any),
+                            ("summary_max_len", Any),
+                            ("summary_single_line", Any),
+                            ("labels", Any),
+                        ]),
+                    ),
+                    (
+                        "jobs",
+                        Table(&[
+                            ("format", Any),
+                            ("show_succeeded", Any),
+                            ("fields", Any),
+                            ("labels", Any),
+                        ]),
+                    ),
+                    (
+                        "jobs_show",
+                        Table(&[("format", Any), ("fields", Any), ("labels", Any)]),
+                    ),
+                ]),
+            ),
+        ]),
+    ),
+    (
+        "jobs",
+        Table(&[
+            ("cancel", Table(&[("cleanup_worktree", Any)])),
+            ("max_concurrent", Any),
+        ]),
+    ),
+    (
+        "sessions",
+        Table(&[("keep_days", Any), ("keep_last", Any), ("compress", Any)]),
+    ),
+    ("tmp", Table(&[("keep_days", Any)])),
+    ("search", Table(&[("embedder", Any)])),
+    ("worktrees", Table(&[("dir", Any)])),
+    (
+        "hooks",
+        Table(&[
+            ("pre_draft", Any),
+            ("post_draft", Any),
+            ("pre_approve", Any),
+            ("post_approve", Any),
+            ("pre_review", Any),
+            ("post_review", Any),
+            ("pre_merge", Any),
+            ("post_merge", Any),
+            ("pre_commit", Any),
+            ("post_commit", Any),
+            ("on_failure", Any),
+        ]),
+    ),
+    ("forge", Table(&[("cli", Any)])),
+    (
+        "agent_stall",
+        Table(&[
+            ("warn_after_secs", Any),
+            ("kill_after_secs", Any),
+            ("retries", Any),
+        ]),
+    ),
+    ("context", Table(&[("never_send", Any)])),
+    ("output", Table(&[("language", Any)])),
+    (
+        "draft",
+        Table(&[("max_steps", Any), ("max_files", Any), ("auto_push", Any)]),
+    ),
+    ("mock_agent", Table(&[("fixtures", Any)])),
+    ("branches", Table(&[("prefix", Any), ("target", Any)])),
+    (
+        "remote",
+        Table(&[
+            ("host", Any),
+            ("path", Any),
+            ("git_remote", Any),
+            ("vizier", Any),
+            ("ssh", Any),
+        ]),
+    ),
+    (
+        "notifications",
+        Table(&[(
+            "email",
+            Table(&[
+                ("smtp_host", Any),
+                ("smtp_port", Any),
+                ("tls", Any),
+                ("username", Any),
+                ("password_env", Any),
+                ("from", Any),
+                ("to", Any),
+                ("digest_hours", Any),
+                ("events", Any),
+            ]),
+        )]),
+    ),
+    (
+        "command_audit",
+        Table(&[("enabled", Any), ("allow", Any), ("abort", Any)]),
+    ),
+    (
+        "changelog",
+        Table(&[
+            ("enabled", Any),
+            ("dir", Any),
+            ("file", Any),
+            ("style", Any),
+            ("default_type", Any),
+        ]),
+    ),
+    (
+        "workflow",
+        Table(&[
+            ("no_commit_default", Any),
+            ("background", Table(&[("enabled", Any), ("quiet", Any)])),
+            (
+                "global_workflows",
+                Table(&[("enabled", Any), ("enable", Any), ("dir", Any)]),
+            ),
+            (
+                "drift",
+                Table(&[
+                    ("max_commits_behind", Any),
+                    ("max_age_days", Any),
+                    ("strict", Any),
+                ]),
+            ),
+            (
+                "diff_context",
+                Table(&[
+                    ("max_bytes", Any),
+                    ("chunk_bytes", Any),
+                    ("raw_hunk_bytes", Any),
+                    ("summarizer", Any),
+                ]),
+            ),
+            ("prompt_budget", Table(&[("max_bytes", Any)])),
+        ]),
+    ),
+]);
+
+/// Rejects keys in `value` that no part of the loader reads, suggesting the closest known key
+/// at the same level.
+pub(super) fn check_unknown_keys(value: &Value) -> Result<(), Box<dyn std::error::Error>> {
+    let mut unknown = Vec::new();
+    walk(value, &ROOT, "", &mut unknown);
+    if unknown.is_empty() {
+        return Ok(());
+    }
+    let noun = if unknown.len() == 1 { "key" } else { "keys" };
+    Err(format!(
+        "unknown config {noun} {}; set `[config] strict = false` to load it anyway",
+        unknown.join(", ")
+    )
+    .into())
+}
+
+fn walk(value: &Value, node: &KeyNode, path: &str, unknown: &mut Vec<String>) {
+    match (node, value) {
+        (Table(keys), Value::Object(table)) => {
+            for (key, child) in table {
+                let normalized = key.replace('-', "_");
+                let child_path = join_path(path, key);
+                match keys.iter().find(|(name, _)| *name == normalized) {
+                    Some((_, child_node)) => walk(child, child_node, &child_path, unknown),
+                    None => unknown.push(match suggest(&normalized, keys) {
+                        Some(name) => {
+                            format!("`{child_path}` (did you mean `{}`?)", join_path(path, name))
+                        }
+                        None => format!("`{child_path}`"),
+                    }),
+                }
+            }
+        }
+        (Map(entry), Value::Object(table)) => {
+            for (key, child) in table {
+                walk(child, entry, &join_path(path, key), unknown);
+            }
+        }
+        (List(entry), Value::Array(items)) => {
+            for (index, item) in items.iter().enumerate() {
+                walk(item, entry, &format!("{path}[{index}]"), unknown);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn join_path(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{path}.{key}")
+    }
+}
+
+fn suggest<'a>(key: &str, keys: &'a [(&'static str, KeyNode)]) -> Option<&'a str> {
+    let limit = (key.chars().count() / 3).clamp(1, 3);
+    keys.iter()
+        .map(|(name, _)| (edit_distance(key, name), *name))
+        .filter(|(distance, _)| *distance <= limit)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, name)| name)
+}
+
+/// Edit distance where swapping two adjacent characters counts as one edit (`jbos` -> `jobs`).
+fn edit_distance(left: &str, right: &str) -> usize {
+    let left: Vec<char> = left.chars().collect();
+    let right: Vec<char> = right.chars().collect();
+    let mut rows = vec![vec![0usize; right.len() + 1]; left.len() + 1];
+    for (row, cells) in rows.iter_mut().enumerate() {
+        cells[0] = row;
+    }
+    for (col, cell) in rows[0].iter_mut().enumerate() {
+        *cell = col;
+    }
+    for row in 1..=left.len() {
+        for col in 1..=right.len() {
+            let cost = usize::from(left[row - 1] != right[col - 1]);
+            let mut best = (rows[row - 1][col] + 1)
+                .min(rows[row][col - 1] + 1)
+                .min(rows[row - 1][col - 1] + cost);
+            if row > 1
+                && col > 1
+                && left[row - 1] == right[col - 2]
+                && left[row - 2] == right[col - 1]
+            {
+                best = best.min(rows[row - 2][col - 2] + 1);
+            }
+            rows[row][col] = best;
+        }
+    }
+    rows[left.len()][right.len()]
+}
@@ -3,9 +3,10 @@ use std::io;
 use std::path::{Path, PathBuf};
 use std::sync::RwLock;
 
-use crate::{display, tools, tree};
+use crate::{context_cache, display, tools};
+use vizier_kernel::stop_condition::{self, StopExpression};
 
-use super::*;
+use super::{keys, *};
 
 use lazy_static::lazy_static;
 
@@ -13,6 +14,7 @@ use lazy_static::lazy_static;
 enum FileFormat {
     Json,
     Toml,
+    Yaml,
 }
 
 const MODEL_KEY_PATHS: &[&[&str]] = &[
@@ -125,6 +127,8 @@ fn parse_agent_overrides(
         )));
     }
 
+    overrides.env = parse_agent_env(value, base_dir)?;
+
     if allow_prompt_children {
         if let Some(doc_settings) = parse_documentation_settings(value)? {
             overrides.documentation = doc_settings;
@@ -143,6 +147,50 @@ fn parse_agent_overrides(
     }
 }
 
+fn parse_agent_env(
+    value: &serde_json::Value,
+    base_dir: Option<&Path>,
+) -> Result<AgentEnvSettings, Box<dyn std::error::Error>> {
+    let invalid = |message: String| -> Box<dyn std::error::Error> {
+        Box::new(io::Error::new(io::ErrorKind::InvalidInput, message))
+    };
+    let mut env = AgentEnvSettings::default();
+
+    if let Some(table) = value.get("env") {
+        let table = table
+            .as_object()
+            .ok_or_else(|| invalid("agent env must be a table of NAME = \"value\"".to_string()))?;
+        for (name, raw) in table {
+            if !crate::agent_env::is_env_var_name(name) {
+                return Err(invalid(format!(
+                    "agent env name `{name}` is not a valid variable name"
+                )));
+            }
+            let value = match raw {
+                serde_json::Value::String(text) => text.clone(),
+                serde_json::Value::Number(number) => number.to_string(),
+                serde_json::Value::Bool(flag) => flag.to_string(),
+                _ => return Err(invalid(format!("agent env `{name}` must be a string"))),
+            };
+            env.vars.insert(name.clone(), value);
+        }
+    }
+
+    if let Some(raw) = value.get("env_file") {
+        let path = parse_nonempty_string(Some(raw))
+            .ok_or_else(|| invalid("agent env_file must be a non-empty path".to_string()))?;
+        let mut path = PathBuf::from(path);
+        if path.is_relative()
+            && let Some(base) = base_dir
+        {
+            path = base.join(path);
+        }
+        env.env_file = Some(path);
+    }
+
+    Ok(env)
+}
+
 fn parse_prompt_override_table(
     value: &serde_json::Value,
     base_dir: Option<&Path>,
@@ -231,6 +279,36 @@ fn parse_prompt_path(
     Ok(Some(resolved))
 }
 
+// `commit_template` is inline text or `{ path = "..." }` / `{ text = "..." }`, like prompt
+// overrides; placeholders are checked here so a typo fails at load instead of at merge time.
+fn parse_merge_commit_template(
+    value: &serde_json::Value,
+    base_dir: Option<&Path>,
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    let text = match value {
+        serde_json::Value::String(text) => Some(text.clone()),
+        serde_json::Value::Object(_) => match parse_prompt_path(value, base_dir)? {
+            Some(path) => Some(std::fs::read_to_string(&path).map_err(|err| {
+                format!(
+                    "[merge] commit_template could not read {}: {err}",
+                    path.display()
+                )
+            })?),
+            None => parse_inline_prompt_text(value).map(str::to_string),
+        },
+        _ => {
+            return Err(
+                "[merge] commit_template must be a string or a { path } / { text } table".into(),
+            );
+        }
+    };
+    let Some(text) = text.filter(|text| !text.trim().is_empty()) else {
+        return Ok(None);
+    };
+    render_merge_commit_template(&text, &[])?;
+    Ok(Some(text))
+}
+
 fn parse_inline_prompt_text(entry: &serde_json::Value) -> Option<&str> {
     let object = entry.as_object()?;
     for key in ["text", "prompt", "template", "inline"] {
@@ -439,74 +517,364 @@ pub fn load_config_layer_from_toml(
     load_config_layer_from_reader(filepath.as_path(), FileFormat::Toml)
 }
 
+pub fn load_config_layer_from_yaml(
+    filepath: PathBuf,
+) -> Result<ConfigLayer, Box<dyn std::error::Error>> {
+    load_config_layer_from_reader(filepath.as_path(), FileFormat::Yaml)
+}
+
 pub fn load_config_layer_from_path<P: AsRef<Path>>(
     filepath: P,
 ) -> Result<ConfigLayer, Box<dyn std::error::Error>> {
     let path = filepath.as_ref();
+    let file_config = read_config_value(path)?;
+    load_config_layer_from_parsed(&file_config, path.parent())
+}
+
+fn load_config_layer_from_reader(
+    path: &Path,
+    format: FileFormat,
+) -> Result<ConfigLayer, Box<dyn std::error::Error>> {
+    let contents = std::fs::read_to_string(path)?;
+    let file_config = parse_config_value(&contents, format)?;
+    load_config_layer_from_parsed(&file_config, path.parent())
+}
 
+/// Reads a config file into a value tree, picking the format from the extension; files without
+/// a known extension are tried as TOML, then JSON, then YAML, reporting the TOML error if none fit.
+pub(super) fn read_config_value(
+    path: &Path,
+) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+    let contents = std::fs::read_to_string(path)?;
     let ext = path
         .extension()
         .and_then(|ext| ext.to_str())
         .map(|ext| ext.to_ascii_lowercase());
 
     match ext.as_deref() {
-        Some("json") => load_config_layer_from_reader(path, FileFormat::Json),
-        Some("toml") => load_config_layer_from_reader(path, FileFormat::Toml),
-        _ => load_config_layer_from_reader(path, FileFormat::Toml)
-            .or_else(|_| load_config_layer_from_reader(path, FileFormat::Json)),
+        Some("json") => parse_config_value(&contents, FileFormat::Json),
+        Some("toml") => parse_config_value(&contents, FileFormat::Toml),
+        Some("yaml") | Some("yml") => parse_config_value(&contents, FileFormat::Yaml),
+        _ => parse_config_value(&contents, FileFormat::Toml).or_else(|err| {
+            parse_config_value(&contents, FileFormat::Json)
+                .or_else(|_| parse_config_value(&contents, FileFormat::Yaml))
+                .map_err(|_| err)
+        }),
     }
 }
 
-fn load_config_layer_from_reader(
-    path: &Path,
+fn parse_config_value(
+    contents: &str,
     format: FileFormat,
-) -> Result<ConfigLayer, Box<dyn std::error::Error>> {
-    let contents = std::fs::read_to_string(path)?;
-    let base_dir = path.parent();
-    load_config_layer_from_str(&contents, format, base_dir)
+) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+    Ok(match format {
+        FileFormat::Json => serde_json::from_str(contents)?,
+        FileFormat::Toml => toml::from_str(contents)?,
+        FileFormat::Yaml => {
+            let mut value = serde_yaml_ng::from_str::<serde_yaml_ng::Value>(contents)?;
+            value.apply_merge()?;
+            serde_json::to_value(value)?
+        }
+    })
 }
 
-fn load_config_layer_from_str(
-    contents: &str,
-    format: FileFormat,
+/// Builds a layer from one parsed file, then (unless the file sets `[config] strict = false`)
+/// rejects keys the loader never reads so typos fail loudly instead of silently no-oping.
+fn load_config_layer_from_parsed(
+    file_config: &serde_json::Value,
     base_dir: Option<&Path>,
 ) -> Result<ConfigLayer, Box<dyn std::error::Error>> {
-    let file_config: serde_json::Value = match format {
-        FileFormat::Json => serde_json::from_str(contents)?,
-        FileFormat::Toml => toml::from_str(contents)?,
+    let layer = load_config_layer_from_value(file_config, base_dir)?;
+    if let Some(strict) = value_at_path(file_config, &["config", "strict"])
+        && parse_bool(Some(strict)).is_none()
+    {
+        return Err("config.strict must be a boolean".into());
+    }
+    if parse_bool(value_at_path(file_config, &["config", "strict"])).unwrap_or(true) {
+        keys::check_unknown_keys(file_config)?;
+    }
+    Ok(layer)
+}
+
+fn parse_branch_prefix(value: &serde_json::Value) -> Result<String, Box<dyn std::error::Error>> {
+    let invalid = |reason: &str| -> Box<dyn std::error::Error> {
+        Box::new(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("branches.prefix {reason}"),
+        ))
+    };
+    let prefix = value
+        .as_str()
+        .map(str::trim)
+        .ok_or_else(|| invalid("must be a string"))?;
+    if prefix.is_empty() {
+        return Err(invalid("cannot be empty"));
+    }
+    if prefix.matches(BRANCH_USER_PLACEHOLDER).count() > 1 {
+        return Err(invalid("may contain `{user}` at most once"));
+    }
+    let sample = BranchesConfig {
+        prefix: prefix.to_string(),
+        target: None,
+    }
+    .branch_for_slug("plan", "user");
+    if !git2::Reference::is_valid_name(&format!("refs/heads/{sample}")) {
+        return Err(invalid(&format!(
+            "`{prefix}` does not produce valid branch names (e.g. `{sample}`)"
+        )));
+    }
+    Ok(prefix.to_string())
+}
+
+fn parse_remote_table(
+    value: &serde_json::Value,
+    layer: &mut RemoteLayer,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let invalid = |message: &str| -> Box<dyn std::error::Error> {
+        Box::new(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            message.to_string(),
+        ))
+    };
+    if !value.is_object() {
+        return Err(invalid("[remote] must be a table"));
+    }
+    layer.host = parse_nonempty_string(value.get("host"));
+    layer.path = parse_nonempty_string(value.get("path"));
+    layer.git_remote = parse_nonempty_string(value.get("git_remote"));
+    layer.vizier = parse_nonempty_string(value.get("vizier"));
+    if let Some(ssh) = value.get("ssh") {
+        layer.ssh = Some(
+            parse_command_value(ssh)
+                .ok_or_else(|| invalid("remote.ssh must be a non-empty string or array"))?,
+        );
+    }
+    Ok(())
+}
+
+fn parse_email_notifications_table(
+    value: &serde_json::Value,
+    layer: &mut EmailNotificationLayer,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let invalid = |message: String| -> Box<dyn std::error::Error> {
+        Box::new(io::Error::new(io::ErrorKind::InvalidInput, message))
+    };
+    let Some(table) = value.as_object() else {
+        return Err(invalid("[notifications.email] must be a table".to_string()));
+    };
+    layer.smtp_host = parse_nonempty_string(table.get("smtp_host"));
+    if let Some(port) = table.get("smtp_port") {
+        layer.smtp_port = Some(
+            parse_u32(Some(port))
+                .and_then(|port| u16::try_from(port).ok())
+                .filter(|port| *port > 0)
+                .ok_or_else(|| invalid("notifications.email.smtp_port must be 1-65535".into()))?,
+        );
+    }
+    if let Some(tls) = table.get("tls") {
+        layer.tls = Some(tls.as_str().and_then(SmtpTls::parse).ok_or_else(|| {
+            invalid("notifications.email.tls must be starttls, implicit, or none".into())
+        })?);
+    }
+    layer.username = parse_nonempty_string(table.get("username"));
+    layer.password_env = parse_nonempty_string(table.get("password_env"));
+    layer.from = parse_nonempty_string(table.get("from"));
+    if let Some(to) = table.get("to") {
+        layer.to = Some(
+            parse_nonempty_string(Some(to))
+                .map(|address| vec![address])
+                .or_else(|| parse_string_array(Some(to)))
+                .ok_or_else(|| {
+                    invalid(
+                        "notifications.email.to must be an address or array of addresses".into(),
+                    )
+                })?,
+        );
+    }
+    if let Some(hours) = table.get("digest_hours") {
+        layer.digest_hours = Some(
+            parse_u32(Some(hours))
+                .filter(|hours| *hours > 0)
+                .ok_or_else(|| {
+                    invalid("notifications.email.digest_hours must be positive".into())
+                })?,
+        );
+    }
+    if let Some(events) = table.get("events") {
+        let Some(events) = events.as_object() else {
+            return Err(invalid(
+                "[notifications.email.events] must be a table".to_string(),
+            ));
+        };
+        for (name, delivery) in events {
+            let event = NotificationEvent::parse(name).ok_or_else(|| {
+                invalid(format!(
+                    "notifications.email.events: unknown event `{name}` (expected draft, merge, gate_failure, job_failure)"
+                ))
+            })?;
+            let delivery = delivery
+                .as_str()
+                .and_then(NotificationDelivery::parse)
+                .ok_or_else(|| {
+                    invalid(format!(
+                        "notifications.email.events.{name} must be immediate, digest, or off"
+                    ))
+                })?;
+            layer.events.insert(event, delivery);
+        }
+    }
+    Ok(())
+}
+
+/// `approve.stop_condition.conditions` as one expression string; a list becomes the conjunction
+/// of its entries. Either form is parsed here so typos fail at load time, not mid-approve.
+fn parse_stop_condition_expression(
+    value: &serde_json::Value,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let invalid = |message: String| -> Box<dyn std::error::Error> {
+        Box::new(io::Error::new(io::ErrorKind::InvalidInput, message))
+    };
+    let expression = match value {
+        serde_json::Value::String(expression) => expression.trim().to_string(),
+        serde_json::Value::Array(_) => parse_string_array(Some(value))
+            .map(|conditions| stop_condition::conjunction(&conditions))
+            .ok_or_else(|| {
+                invalid("approve.stop_condition.conditions must list condition strings".into())
+            })?,
+        _ => {
+            return Err(invalid(
+                "approve.stop_condition.conditions must be an expression or a list of conditions"
+                    .into(),
+            ));
+        }
+    };
+    StopExpression::parse(&expression)
+        .map_err(|err| invalid(format!("approve.stop_condition.conditions: {err}")))?;
+    Ok(expression)
+}
+
+/// A non-negative dollar amount (integer or float), converted to micro-dollars.
+fn parse_usd_rate(key: &str, value: &serde_json::Value) -> Result<u64, Box<dyn std::error::Error>> {
+    match value.as_f64() {
+        Some(usd) if usd.is_finite() && usd >= 0.0 => Ok((usd * 1_000_000.0).round() as u64),
+        _ => Err(Box::new(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{key} must be a non-negative number of dollars"),
+        ))),
+    }
+}
+
+fn parse_command_audit_table(
+    value: &serde_json::Value,
+    layer: &mut CommandAuditLayer,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let invalid = |message: &str| -> Box<dyn std::error::Error> {
+        Box::new(io::Error::new(io::ErrorKind::InvalidInput, message))
     };
+    if !value.is_object() {
+        return Err(invalid("[command_audit] must be a table"));
+    }
+    if let Some(enabled) = value.get("enabled") {
+        layer.enabled = Some(
+            parse_bool(Some(enabled))
+                .ok_or_else(|| invalid("command_audit.enabled must be a boolean"))?,
+        );
+    }
+    for (key, slot) in [("allow", &mut layer.allow), ("abort", &mut layer.abort)] {
+        if let Some(patterns) = value.get(key) {
+            *slot = Some(
+                parse_string_array_allow_empty(Some(patterns)).ok_or_else(|| {
+                    invalid(&format!(
+                        "command_audit.{key} must be an array of command patterns"
+                    ))
+                })?,
+            );
+        }
+    }
+    Ok(())
+}
 
-    load_config_layer_from_value(file_config, base_dir)
+fn parse_changelog_table(
+    value: &serde_json::Value,
+    layer: &mut ChangelogLayer,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let invalid = |message: String| -> Box<dyn std::error::Error> {
+        Box::new(io::Error::new(io::ErrorKind::InvalidInput, message))
+    };
+    if !value.is_object() {
+        return Err(invalid("[changelog] must be a table".to_string()));
+    }
+    if let Some(enabled) = value.get("enabled") {
+        layer.enabled = Some(
+            parse_bool(Some(enabled))
+                .ok_or_else(|| invalid("changelog.enabled must be a boolean".to_string()))?,
+        );
+    }
+    for (key, slot) in [("dir", &mut layer.dir), ("file", &mut layer.file)] {
+        if let Some(path) = value.get(key) {
+            *slot = Some(parse_nonempty_string(Some(path)).ok_or_else(|| {
+                invalid(format!(
+                    "changelog.{key} must be a non-empty repo-relative path"
+                ))
+            })?);
+        }
+    }
+    if let Some(style) = value.get("style") {
+        layer.style = Some(
+            style
+                .as_str()
+                .and_then(ChangelogStyle::parse)
+                .ok_or_else(|| {
+                    invalid(
+                        "changelog.style must be \"keep-a-changelog\" or \"towncrier\"".to_string(),
+                    )
+                })?,
+        );
+    }
+    if let Some(default_type) = value.get("default_type") {
+        let parsed = default_type
+            .as_str()
+            .map(|value| value.trim().to_ascii_lowercase())
+            .filter(|value| CHANGELOG_TYPES.contains(&value.as_str()))
+            .ok_or_else(|| {
+                invalid(format!(
+                    "changelog.default_type must be one of: {}",
+                    CHANGELOG_TYPES.join(", ")
+                ))
+            })?;
+        layer.default_type = Some(parsed);
+    }
+    Ok(())
 }
 
 fn load_config_layer_from_value(
-    file_config: serde_json::Value,
+    file_config: &serde_json::Value,
     base_dir: Option<&Path>,
 ) -> Result<ConfigLayer, Box<dyn std::error::Error>> {
     let mut layer = ConfigLayer::default();
 
-    if find_string(&file_config, MODEL_KEY_PATHS).is_some() {
+    if find_string(file_config, MODEL_KEY_PATHS).is_some() {
         return Err(Box::new(io::Error::new(
             io::ErrorKind::InvalidInput,
             MODEL_CONFIG_REMOVED_MESSAGE,
         )));
     }
 
-    if find_string(&file_config, REASONING_EFFORT_KEY_PATHS).is_some() {
+    if find_string(file_config, REASONING_EFFORT_KEY_PATHS).is_some() {
         return Err(Box::new(io::Error::new(
             io::ErrorKind::InvalidInput,
             REASONING_CONFIG_REMOVED_MESSAGE,
         )));
     }
 
-    if find_string(&file_config, FALLBACK_BACKEND_KEY_PATHS).is_some() {
+    if find_string(file_config, FALLBACK_BACKEND_KEY_PATHS).is_some() {
         return Err(Box::new(io::Error::new(
             io::ErrorKind::InvalidInput,
             FALLBACK_BACKEND_DEPRECATION_MESSAGE,
         )));
     }
 
-    if let Some(agent_value) = value_at_path(&file_config, &["agent"]) {
+    if let Some(agent_value) = value_at_path(file_config, &["agent"]) {
         if let Some(raw) = agent_value.as_str() {
             if let Some(selector) = normalize_selector_value(raw) {
                 layer.agent_selector = Some(selector);
@@ -516,7 +884,7 @@ fn load_config_layer_from_value(
         }
     }
 
-    if find_string(&file_config, BACKEND_KEY_PATHS).is_some() {
+    if find_string(file_config, BACKEND_KEY_PATHS).is_some() {
         return Err(Box::new(io::Error::new(
             io::ErrorKind::InvalidInput,
             "backend entries are unsupported; use agent selectors instead",
@@ -524,17 +892,29 @@ fn load_config_layer_from_value(
     }
 
     if let Some(commands) = parse_string_array(value_at_path(
-        &file_config,
+        file_config,
         &["review", "checks", "commands"],
     )) {
         layer.review.checks = Some(commands);
     } else if let Some(commands) =
-        parse_string_array(value_at_path(&file_config, &["review", "checks"]))
+        parse_string_array(value_at_path(file_config, &["review", "checks"]))
     {
         layer.review.checks = Some(commands);
     }
+    if let Some(jobs) = parse_u32(value_at_path(file_config, &["review", "checks", "jobs"])) {
+        layer.review.check_jobs = Some(jobs);
+    }
+    if let Some(max_iterations) = parse_u32(value_at_path(
+        file_config,
+        &["review", "fix_loop", "max_iterations"],
+    )) {
+        layer.review.fix_loop_max_iterations = Some(max_iterations);
+    }
+    if let Some(tools) = parse_bool(value_at_path(file_config, &["review", "tools"])) {
+        layer.review.tools = Some(tools);
+    }
 
-    if let Some(stop_condition) = value_at_path(&file_config, &["approve", "stop_condition"]) {
+    if let Some(stop_condition) = value_at_path(file_config, &["approve", "stop_condition"]) {
         if let Some(script) = stop_condition
             .get("script")
             .and_then(|value| value.as_str())
@@ -551,9 +931,33 @@ fn load_config_layer_from_value(
         ) {
             layer.approve.stop_condition.retries = Some(retries);
         }
+
+        if let Some(conditions) = stop_condition.get("conditions") {
+            layer.approve.stop_condition.conditions =
+                Some(parse_stop_condition_expression(conditions)?);
+        }
+        layer.approve.stop_condition.tests = parse_nonempty_string(stop_condition.get("tests"));
+    }
+
+    if let Some(generate_tests) = parse_bool(
+        value_at_path(file_config, &["approve", "generate_tests"])
+            .or_else(|| value_at_path(file_config, &["approve", "generate-tests"])),
+    ) {
+        layer.approve.generate_tests = Some(generate_tests);
+    }
+
+    if let Some(cost) = value_at_path(file_config, &["approve", "cost"]) {
+        if let Some(rate) = cost.get("input_usd_per_mtok") {
+            layer.approve.cost.input_micro_usd_per_mtok =
+                Some(parse_usd_rate("approve.cost.input_usd_per_mtok", rate)?);
+        }
+        if let Some(rate) = cost.get("output_usd_per_mtok") {
+            layer.approve.cost.output_micro_usd_per_mtok =
+                Some(parse_usd_rate("approve.cost.output_usd_per_mtok", rate)?);
+        }
     }
 
-    if let Some(merge_table) = value_at_path(&file_config, &["merge"]) {
+    if let Some(merge_table) = value_at_path(file_config, &["merge"]) {
         if let Some(squash) = merge_table.get("squash").and_then(|value| value.as_bool()) {
             layer.merge.squash_default = Some(squash);
         }
@@ -567,6 +971,26 @@ fn load_config_layer_from_value(
             layer.merge.squash_mainline = Some(mainline as u32);
         }
 
+        if let Some(strategy) = parse_nonempty_string(merge_table.get("strategy")) {
+            let Some(parsed) = MergeStrategy::parse(&strategy) else {
+                return Err(format!(
+                    "merge.strategy must be squash, merge, or rebase (got `{strategy}`)"
+                )
+                .into());
+            };
+            layer.merge.strategy = Some(parsed);
+        }
+
+        if let Some(granularity) = parse_nonempty_string(merge_table.get("squash_granularity")) {
+            let Some(parsed) = SquashGranularity::parse(&granularity) else {
+                return Err(format!(
+                    "merge.squash_granularity must be plan or step (got `{granularity}`)"
+                )
+                .into());
+            };
+            layer.merge.squash_granularity = Some(parsed);
+        }
+
         if let Some(gate) = merge_table
             .get("cicd_gate")
             .or_else(|| merge_table.get("cicd-gate"))
@@ -574,6 +998,37 @@ fn load_config_layer_from_value(
             parse_merge_cicd_gate(gate, base_dir, &mut layer.merge.cicd_gate)?;
         }
 
+        if let Some(gates) = merge_table.get("gates") {
+            layer.merge.gates = Some(parse_merge_gates(gates)?);
+        }
+
+        if let Some(auto_revert) = parse_bool(
+            merge_table
+                .get("auto_revert")
+                .or_else(|| merge_table.get("auto-revert")),
+        ) {
+            layer.merge.auto_revert = Some(auto_revert);
+        }
+
+        if let Some(protected) = parse_string_array_allow_empty(merge_table.get("protected")) {
+            layer.merge.protected = Some(protected);
+        }
+
+        if let Some(template) = merge_table
+            .get("commit_template")
+            .or_else(|| merge_table.get("commit-template"))
+        {
+            layer.merge.commit_template = parse_merge_commit_template(template, base_dir)?;
+        }
+
+        if let Some(require) = parse_bool(
+            merge_table
+                .get("require_owner_ack")
+                .or_else(|| merge_table.get("require-owner-ack")),
+        ) {
+            layer.merge.require_owner_ack = Some(require);
+        }
+
         if let Some(conflicts) = merge_table
             .get("conflicts")
             .or_else(|| merge_table.get("conflict"))
@@ -586,41 +1041,184 @@ fn load_config_layer_from_value(
         }
     }
 
-    if let Some(release_table) = value_at_path(&file_config, &["release"]) {
+    if let Some(release_table) = value_at_path(file_config, &["release"]) {
         parse_release_table(release_table, &mut layer.release)?;
     }
 
-    if let Some(build_table) = value_at_path(&file_config, &["build"]) {
+    if let Some(build_table) = value_at_path(file_config, &["build"]) {
         parse_build_table(build_table, &mut layer.build)?;
     }
 
-    if let Some(commits_table) = value_at_path(&file_config, &["commits"]) {
+    if let Some(commits_table) = value_at_path(file_config, &["commits"]) {
         parse_commit_table(commits_table, &mut layer.commits)?;
     }
 
-    if let Some(display_table) = value_at_path(&file_config, &["display"]) {
+    if let Some(display_table) = value_at_path(file_config, &["display"]) {
         parse_display_table(display_table, &mut layer.display)?;
     }
 
-    if let Some(jobs_table) = value_at_path(&file_config, &["jobs"]) {
+    if let Some(jobs_table) = value_at_path(file_config, &["jobs"]) {
         parse_jobs_table(jobs_table, &mut layer.jobs)?;
     }
 
-    if let Some(workflow_table) = value_at_path(&file_config, &["workflow"]) {
+    if let Some(sessions_table) = value_at_path(file_config, &["sessions"]) {
+        parse_sessions_table(sessions_table, &mut layer.sessions);
+    }
+
+    if let Some(keep_days) = parse_u32(
+        value_at_path(file_config, &["tmp", "keep_days"])
+            .or_else(|| value_at_path(file_config, &["tmp", "keep-days"])),
+    ) {
+        layer.tmp.keep_days = Some(keep_days);
+    }
+
+    if let Some(embedder) = value_at_path(file_config, &["search", "embedder"]) {
+        layer.search.embedder = Some(parse_string_array_allow_empty(Some(embedder)).ok_or_else(
+            || -> Box<dyn std::error::Error> {
+                Box::new(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "search.embedder must be an array of command arguments",
+                ))
+            },
+        )?);
+    }
+
+    if let Some(stall_table) = value_at_path(file_config, &["agent_stall"]) {
+        parse_agent_stall_table(stall_table, &mut layer.agent_stall);
+    }
+
+    if let Some(never_send) = parse_string_array_allow_empty(
+        value_at_path(file_config, &["context", "never_send"])
+            .or_else(|| value_at_path(file_config, &["context", "never-send"])),
+    ) {
+        crate::never_send::validate_patterns(&never_send)?;
+        layer.context.never_send = Some(never_send);
+    }
+
+    if let Some(dir) = value_at_path(file_config, &["worktrees", "dir"])
+        .and_then(|value| value.as_str())
+        .map(str::trim)
+    {
+        layer.worktrees.dir = Some(PathBuf::from(dir));
+    }
+
+    if let Some(hooks_table) = value_at_path(file_config, &["hooks"]) {
+        parse_hooks_table(hooks_table, &mut layer.hooks)?;
+    }
+
+    if let Some(cli) = value_at_path(file_config, &["forge", "cli"]) {
+        layer.forge.cli = Some(cli.as_str().and_then(ForgeCli::parse).ok_or_else(
+            || -> Box<dyn std::error::Error> {
+                Box::new(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "forge.cli must be \"auto\", \"gh\", \"glab\", or \"off\"",
+                ))
+            },
+        )?);
+    }
+
+    if let Some(language) = value_at_path(file_config, &["output", "language"]) {
+        layer.output.language = Some(parse_output_language(language)?);
+    }
+
+    if let Some(draft_table) = value_at_path(file_config, &["draft"]) {
+        parse_draft_table(draft_table, &mut layer.draft);
+    }
+
+    if let Some(fixtures) = value_at_path(file_config, &["mock_agent", "fixtures"])
+        .and_then(|value| value.as_str())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+    {
+        layer.mock_agent.fixtures = Some(PathBuf::from(fixtures));
+    }
+
+    if let Some(prefix) = value_at_path(file_config, &["branches", "prefix"]) {
+        layer.branches.prefix = Some(parse_branch_prefix(prefix)?);
+    }
+
+    if let Some(target) = value_at_path(file_config, &["branches", "target"])
+        .and_then(|value| value.as_str())
+        .map(str::trim)
+    {
+        layer.branches.target = Some(target.to_string());
+    }
+
+    if let Some(remote_table) = value_at_path(file_config, &["remote"]) {
+        parse_remote_table(remote_table, &mut layer.remote)?;
+    }
+
+    if let Some(email_table) = value_at_path(file_config, &["notifications", "email"]) {
+        parse_email_notifications_table(email_table, &mut layer.notifications.email)?;
+    }
+
+    if let Some(audit_table) = value_at_path(file_config, &["command_audit"]) {
+        parse_command_audit_table(audit_table, &mut layer.command_audit)?;
+    }
+
+    if let Some(changelog_table) = value_at_path(file_config, &["changelog"]) {
+        parse_changelog_table(changelog_table, &mut layer.changelog)?;
+    }
+
+    if let Some(workflow_table) = value_at_path(file_config, &["workflow"]) {
         parse_workflow_table(workflow_table, &mut layer.workflow, base_dir)?;
     }
 
-    if let Some(commands_value) = value_at_path(&file_config, &["commands"]) {
+    if let Some(commands_value) = value_at_path(file_config, &["commands"]) {
         parse_commands_table(&mut layer, commands_value)?;
     }
 
-    if let Some(agents_value) = value_at_path(&file_config, &["agents"]) {
+    if let Some(agents_value) = value_at_path(file_config, &["agents"]) {
         parse_agent_sections_into_layer(&mut layer, agents_value, base_dir)?;
     }
 
+    if let Some(profiles_value) = value_at_path(file_config, &["profiles"]) {
+        parse_profiles_table(&mut layer, profiles_value, base_dir)?;
+    }
+
     Ok(layer)
 }
 
+fn parse_profiles_table(
+    layer: &mut ConfigLayer,
+    value: &serde_json::Value,
+    base_dir: Option<&Path>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(table) = value.as_object() else {
+        return Err(Box::new(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "[profiles] must be a table of named profiles",
+        )));
+    };
+
+    for (raw_name, profile_value) in table {
+        let name = raw_name.trim();
+        if name.is_empty() {
+            return Err(Box::new(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "profile names must not be empty",
+            )));
+        }
+        if !profile_value.is_object() {
+            return Err(Box::new(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("[profiles.{name}] must be a table"),
+            )));
+        }
+        if profile_value.get("profiles").is_some() {
+            return Err(Box::new(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("[profiles.{name}] cannot declare nested profiles"),
+            )));
+        }
+
+        let overlay = load_config_layer_from_value(profile_value, base_dir)?;
+        layer.profiles.insert(name.to_string(), overlay);
+    }
+
+    Ok(())
+}
+
 pub fn load_config_from_json(filepath: PathBuf) -> Result<Config, Box<dyn std::error::Error>> {
     load_config_from_layer(load_config_layer_from_json(filepath)?)
 }
@@ -635,6 +1233,45 @@ pub fn load_config_from_path<P: AsRef<Path>>(
     load_config_from_layer(load_config_layer_from_path(filepath)?)
 }
 
+/// Load a single config file and apply its `[profiles.<name>]` overlay when `profile` is set.
+pub fn load_config_from_path_with_profile<P: AsRef<Path>>(
+    filepath: P,
+    profile: Option<&str>,
+) -> Result<Config, Box<dyn std::error::Error>> {
+    let layers = [load_config_layer_from_path(filepath)?];
+    let mut config = config_from_layers_with_profile(&layers, profile)?;
+    attach_repo_prompts(&mut config);
+    Ok(config)
+}
+
+/// Merge `layers` with the named profile overlay, failing when no layer defines it.
+pub fn config_from_layers_with_profile(
+    layers: &[ConfigLayer],
+    profile: Option<&str>,
+) -> Result<Config, Box<dyn std::error::Error>> {
+    if let Some(name) = profile
+        && !layers.iter().any(|layer| layer.profiles.contains_key(name))
+    {
+        let mut available = layers
+            .iter()
+            .flat_map(|layer| layer.profiles.keys().cloned())
+            .collect::<Vec<_>>();
+        available.sort();
+        available.dedup();
+        let hint = if available.is_empty() {
+            "no [profiles.*] tables are defined".to_string()
+        } else {
+            format!("available: {}", available.join(", "))
+        };
+        return Err(Box::new(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("config profile `{name}` is not defined ({hint})"),
+        )));
+    }
+
+    Ok(Config::from_layers_with_profile(layers, profile))
+}
+
 fn load_config_from_layer(layer: ConfigLayer) -> Result<Config, Box<dyn std::error::Error>> {
     let mut config = Config::from_layers(&[layer]);
     attach_repo_prompts(&mut config);
@@ -864,10 +1501,11 @@ fn parse_commit_meta_fields(values: &[String]) -> Vec<CommitMetaField> {
         if let Some(field) = CommitMetaField::parse(value) {
             fields.push(field);
         } else {
-            display::warn(format!(
-                "unknown commits.meta.include field `{}`; ignoring",
-                value
-            ));
+            display::log(
+                display::Subsystem::Config,
+                display::LogLevel::Warn,
+                format!("unknown commits.meta.include field `{}`; ignoring", value),
+            );
         }
     }
     fields
@@ -879,10 +1517,14 @@ fn parse_commit_implementation_fields(values: &[String]) -> Vec<CommitImplementa
         if let Some(field) = CommitImplementationField::parse(value) {
             fields.push(field);
         } else {
-            display::warn(format!(
-                "unknown commits.implementation.fields entry `{}`; ignoring",
-                value
-            ));
+            display::log(
+                display::Subsystem::Config,
+                display::LogLevel::Warn,
+                format!(
+                    "unknown commits.implementation.fields entry `{}`; ignoring",
+                    value
+                ),
+            );
         }
     }
     fields
@@ -931,10 +1573,14 @@ fn parse_build_pipeline_value(
     if let Some(parsed) = BuildPipeline::parse(&raw) {
         Some(parsed)
     } else {
-        display::warn(format!(
-            "unknown {} value `{}`; expected approve|approve-review|approve-review-merge",
-            key_path, raw
-        ));
+        display::log(
+            display::Subsystem::Config,
+            display::LogLevel::Warn,
+            format!(
+                "unknown {} value `{}`; expected approve|approve-review|approve-review-merge",
+                key_path, raw
+            ),
+        );
         None
     }
 }
@@ -947,10 +1593,14 @@ fn parse_build_review_mode_value(
     if let Some(parsed) = BuildReviewMode::parse(&raw) {
         Some(parsed)
     } else {
-        display::warn(format!(
-            "unknown {} value `{}`; expected apply_fixes|review_only|review_file",
-            key_path, raw
-        ));
+        display::log(
+            display::Subsystem::Config,
+            display::LogLevel::Warn,
+            format!(
+                "unknown {} value `{}`; expected apply_fixes|review_only|review_file",
+                key_path, raw
+            ),
+        );
         None
     }
 }
@@ -963,10 +1613,14 @@ fn parse_build_stage_barrier_value(
     if let Some(parsed) = BuildStageBarrier::parse(&raw) {
         Some(parsed)
     } else {
-        display::warn(format!(
-            "unknown {} value `{}`; expected strict|explicit",
-            key_path, raw
-        ));
+        display::log(
+            display::Subsystem::Config,
+            display::LogLevel::Warn,
+            format!(
+                "unknown {} value `{}`; expected strict|explicit",
+                key_path, raw
+            ),
+        );
         None
     }
 }
@@ -979,10 +1633,14 @@ fn parse_build_failure_mode_value(
     if let Some(parsed) = BuildFailureMode::parse(&raw) {
         Some(parsed)
     } else {
-        display::warn(format!(
-            "unknown {} value `{}`; expected block_downstream|continue_independent",
-            key_path, raw
-        ));
+        display::log(
+            display::Subsystem::Config,
+            display::LogLevel::Warn,
+            format!(
+                "unknown {} value `{}`; expected block_downstream|continue_independent",
+                key_path, raw
+            ),
+        );
         None
     }
 }
@@ -995,7 +1653,11 @@ fn parse_build_merge_target_value(
     if let Some(parsed) = BuildMergeTarget::parse(&raw) {
         Some(parsed)
     } else {
-        display::warn(format!("unknown {} value `{}`; ignoring", key_path, raw));
+        display::log(
+            display::Subsystem::Config,
+            display::LogLevel::Warn,
+            format!("unknown {} value `{}`; ignoring", key_path, raw),
+        );
         None
     }
 }
@@ -1186,6 +1848,45 @@ fn parse_merge_cicd_gate(
     Ok(())
 }
 
+fn parse_merge_gates(
+    value: &serde_json::Value,
+) -> Result<Vec<MergeGateConfig>, Box<dyn std::error::Error>> {
+    let Some(entries) = value.as_array() else {
+        return Err("merge.gates must be an array of tables".into());
+    };
+
+    let mut gates = Vec::with_capacity(entries.len());
+    for (index, entry) in entries.iter().enumerate() {
+        let Some(table) = entry.as_object() else {
+            return Err(format!("merge.gates[{index}] must be a table").into());
+        };
+        let Some(script) = parse_nonempty_string(
+            table
+                .get("script")
+                .or_else(|| table.get("command"))
+                .or_else(|| table.get("path")),
+        ) else {
+            return Err(format!("merge.gates[{index}] requires a non-empty script").into());
+        };
+        let stage = match parse_nonempty_string(table.get("stage")) {
+            Some(raw) => MergeGateStage::parse(&raw).ok_or_else(|| {
+                format!(
+                    "merge.gates[{index}].stage `{raw}` is invalid (expected pre-merge or post-merge)"
+                )
+            })?,
+            None => MergeGateStage::PreMerge,
+        };
+        let name = parse_nonempty_string(table.get("name").or_else(|| table.get("label")))
+            .unwrap_or_else(|| format!("{}-{}", stage.as_str(), index + 1));
+        gates.push(MergeGateConfig {
+            name,
+            stage,
+            script: PathBuf::from(script),
+        });
+    }
+    Ok(gates)
+}
+
 fn parse_release_table(
     value: &serde_json::Value,
     layer: &mut ReleaseLayer,
@@ -1266,10 +1967,11 @@ fn parse_commit_meta_table(
         if let Some(parsed) = CommitMetaStyle::parse(&style) {
             layer.style = Some(parsed);
         } else {
-            display::warn(format!(
-                "unknown commits.meta.style value `{}`; ignoring",
-                style
-            ));
+            display::log(
+                display::Subsystem::Config,
+                display::LogLevel::Warn,
+                format!("unknown commits.meta.style value `{}`; ignoring", style),
+            );
         }
     }
 
@@ -1286,10 +1988,14 @@ fn parse_commit_meta_table(
         if let Some(parsed) = CommitSessionLogPath::parse(&path_mode) {
             layer.session_log_path = Some(parsed);
         } else {
-            display::warn(format!(
-                "unknown commits.meta.session_log_path value `{}`; ignoring",
-                path_mode
-            ));
+            display::log(
+                display::Subsystem::Config,
+                display::LogLevel::Warn,
+                format!(
+                    "unknown commits.meta.session_log_path value `{}`; ignoring",
+                    path_mode
+                ),
+            );
         }
     }
 
@@ -1418,10 +2124,14 @@ fn parse_commit_merge_table(
         if let Some(parsed) = CommitMergePlanMode::parse(&plan_mode) {
             layer.plan_mode = Some(parsed);
         } else {
-            display::warn(format!(
-                "unknown commits.merge.plan_mode value `{}`; ignoring",
-                plan_mode
-            ));
+            display::log(
+                display::Subsystem::Config,
+                display::LogLevel::Warn,
+                format!(
+                    "unknown commits.merge.plan_mode value `{}`; ignoring",
+                    plan_mode
+                ),
+            );
         }
     }
 
@@ -1447,6 +2157,17 @@ fn parse_display_table(
         parse_display_lists_table(lists, &mut layer.lists)?;
     }
 
+    if let Some(log) = parse_nonempty_string(table.get("log")) {
+        match display::LogFilters::parse(&log) {
+            Ok(_) => layer.log = Some(log),
+            Err(err) => display::log(
+                display::Subsystem::Config,
+                display::LogLevel::Warn,
+                format!("display.log: {err}; ignoring"),
+            ),
+        }
+    }
+
     Ok(())
 }
 
@@ -1487,10 +2208,14 @@ fn parse_display_list_table(
         if let Some(parsed) = ListFormat::parse(&format) {
             layer.format = Some(parsed);
         } else {
-            display::warn(format!(
-                "unknown display.lists.list.format value `{}`; ignoring",
-                format
-            ));
+            display::log(
+                display::Subsystem::Config,
+                display::LogLevel::Warn,
+                format!(
+                    "unknown display.lists.list.format value `{}`; ignoring",
+                    format
+                ),
+            );
         }
     }
 
@@ -1560,10 +2285,14 @@ fn parse_display_jobs_list_table(
         if let Some(parsed) = ListFormat::parse(&format) {
             layer.format = Some(parsed);
         } else {
-            display::warn(format!(
-                "unknown display.lists.jobs.format value `{}`; ignoring",
-                format
-            ));
+            display::log(
+                display::Subsystem::Config,
+                display::LogLevel::Warn,
+                format!(
+                    "unknown display.lists.jobs.format value `{}`; ignoring",
+                    format
+                ),
+            );
         }
     }
 
@@ -1599,10 +2328,14 @@ fn parse_display_jobs_show_table(
         if let Some(parsed) = ListFormat::parse(&format) {
             layer.format = Some(parsed);
         } else {
-            display::warn(format!(
-                "unknown display.lists.jobs_show.format value `{}`; ignoring",
-                format
-            ));
+            display::log(
+                display::Subsystem::Config,
+                display::LogLevel::Warn,
+                format!(
+                    "unknown display.lists.jobs_show.format value `{}`; ignoring",
+                    format
+                ),
+            );
         }
     }
 
@@ -1636,9 +2369,131 @@ fn parse_jobs_table(
         layer.cancel.cleanup_worktree = Some(cleanup_worktree);
     }
 
+    if let Some(max_concurrent) = parse_usize(
+        table
+            .get("max_concurrent")
+            .or_else(|| table.get("max-concurrent")),
+    ) {
+        layer.max_concurrent = Some(max_concurrent);
+    }
+
+    Ok(())
+}
+
+/// Normalizes `[output] language` to a lowercase tag (`de`, `pt-br`); English maps to `None`.
+fn parse_output_language(
+    value: &serde_json::Value,
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    let Some(raw) = value.as_str() else {
+        return Err("output.language must be a string such as \"de\"".into());
+    };
+    let tag = raw.trim().to_ascii_lowercase().replace('_', "-");
+    let mut parts = tag.split('-');
+    let primary = parts.next().unwrap_or_default();
+    let valid = (2..=3).contains(&primary.len())
+        && primary.chars().all(|ch| ch.is_ascii_lowercase())
+        && parts.all(|part| !part.is_empty() && part.chars().all(|ch| ch.is_ascii_alphanumeric()));
+    if tag.is_empty() || primary == "en" {
+        return Ok(None);
+    }
+    if !valid {
+        return Err(format!(
+            "output.language `{raw}` is not a language tag (expected e.g. \"de\" or \"pt-BR\")"
+        )
+        .into());
+    }
+    Ok(Some(tag))
+}
+
+fn parse_sessions_table(value: &serde_json::Value, layer: &mut SessionsLayer) {
+    let Some(table) = value.as_object() else {
+        return;
+    };
+
+    if let Some(keep_days) = parse_u32(table.get("keep_days").or_else(|| table.get("keep-days"))) {
+        layer.keep_days = Some(keep_days);
+    }
+    if let Some(keep_last) = parse_u32(table.get("keep_last").or_else(|| table.get("keep-last"))) {
+        layer.keep_last = Some(keep_last);
+    }
+    if let Some(compress) = parse_bool(table.get("compress")) {
+        layer.compress = Some(compress);
+    }
+}
+
+fn parse_hooks_table(
+    value: &serde_json::Value,
+    layer: &mut HooksLayer,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(table) = value.as_object() else {
+        return Err(Box::new(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "[hooks] must be a table",
+        )));
+    };
+
+    for (key, script) in table {
+        let hook = key.replace('-', "_");
+        let known = hook == ON_FAILURE_HOOK
+            || HOOK_PHASES.iter().any(|phase| {
+                hook.strip_prefix("pre_")
+                    .or_else(|| hook.strip_prefix("post_"))
+                    == Some(phase)
+            });
+        if !known {
+            continue;
+        }
+        let Some(script) = script.as_str() else {
+            return Err(Box::new(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("hooks.{hook} must be a shell command string"),
+            )));
+        };
+        layer.scripts.insert(hook, script.to_string());
+    }
     Ok(())
 }
 
+fn parse_agent_stall_table(value: &serde_json::Value, layer: &mut AgentStallLayer) {
+    let Some(table) = value.as_object() else {
+        return;
+    };
+
+    if let Some(warn_after_secs) = parse_u32(
+        table
+            .get("warn_after_secs")
+            .or_else(|| table.get("warn-after-secs")),
+    ) {
+        layer.warn_after_secs = Some(u64::from(warn_after_secs));
+    }
+    if let Some(kill_after_secs) = parse_u32(
+        table
+            .get("kill_after_secs")
+            .or_else(|| table.get("kill-after-secs")),
+    ) {
+        layer.kill_after_secs = Some(u64::from(kill_after_secs));
+    }
+    if let Some(retries) = parse_u32(table.get("retries")) {
+        layer.retries = Some(retries);
+    }
+}
+
+fn parse_draft_table(value: &serde_json::Value, layer: &mut DraftLayer) {
+    let Some(table) = value.as_object() else {
+        return;
+    };
+
+    if let Some(max_steps) = parse_u32(table.get("max_steps").or_else(|| table.get("max-steps"))) {
+        layer.max_steps = Some(max_steps);
+    }
+    if let Some(max_files) = parse_u32(table.get("max_files").or_else(|| table.get("max-files"))) {
+        layer.max_files = Some(max_files);
+    }
+    if let Some(auto_push) = parse_bool(table.get("auto_push").or_else(|| table.get("auto-push"))) {
+        layer.auto_push = Some(auto_push);
+    }
+}
+
 fn parse_workflow_table(
     value: &serde_json::Value,
     layer: &mut WorkflowLayer,
@@ -1697,6 +2552,79 @@ fn parse_workflow_table(
         }
     }
 
+    if let Some(drift) = table.get("drift").and_then(|value| value.as_object()) {
+        if let Some(max_commits_behind) = parse_u32(
+            drift
+                .get("max_commits_behind")
+                .or_else(|| drift.get("max-commits-behind")),
+        ) {
+            layer.drift.max_commits_behind = Some(max_commits_behind);
+        }
+        if let Some(max_age_days) = parse_u32(
+            drift
+                .get("max_age_days")
+                .or_else(|| drift.get("max-age-days")),
+        ) {
+            layer.drift.max_age_days = Some(max_age_days);
+        }
+        if let Some(strict) = parse_bool(drift.get("strict")) {
+            layer.drift.strict = Some(strict);
+        }
+    }
+
+    if let Some(diff_context) = table
+        .get("diff_context")
+        .or_else(|| table.get("diff-context"))
+        .and_then(|value| value.as_object())
+    {
+        if let Some(max_bytes) = parse_u32(
+            diff_context
+                .get("max_bytes")
+                .or_else(|| diff_context.get("max-bytes")),
+        ) {
+            layer.diff_context.max_bytes = Some(max_bytes);
+        }
+        if let Some(chunk_bytes) = parse_u32(
+            diff_context
+                .get("chunk_bytes")
+                .or_else(|| diff_context.get("chunk-bytes")),
+        ) {
+            layer.diff_context.chunk_bytes = Some(chunk_bytes);
+        }
+        if let Some(raw_hunk_bytes) = parse_u32(
+            diff_context
+                .get("raw_hunk_bytes")
+                .or_else(|| diff_context.get("raw-hunk-bytes")),
+        ) {
+            layer.diff_context.raw_hunk_bytes = Some(raw_hunk_bytes);
+        }
+        if let Some(raw) = parse_nonempty_string(diff_context.get("summarizer")) {
+            match DiffSummarizer::parse(&raw) {
+                Some(summarizer) => layer.diff_context.summarizer = Some(summarizer),
+                None => display::log(
+                    display::Subsystem::Config,
+                    display::LogLevel::Warn,
+                    format!(
+                        "unknown workflow.diff_context.summarizer value `{raw}`; expected agent|none"
+                    ),
+                ),
+            }
+        }
+    }
+
+    if let Some(prompt_budget) = table
+        .get("prompt_budget")
+        .or_else(|| table.get("prompt-budget"))
+        .and_then(|value| value.as_object())
+        && let Some(max_bytes) = parse_u32(
+            prompt_budget
+                .get("max_bytes")
+                .or_else(|| prompt_budget.get("max-bytes")),
+        )
+    {
+        layer.prompt_budget.max_bytes = Some(max_bytes);
+    }
+
     if table.get("templates").is_some() {
         return Err(Box::new(io::Error::new(
             io::ErrorKind::InvalidInput,
@@ -1707,31 +2635,41 @@ fn parse_workflow_table(
     Ok(())
 }
 
-/// Returns the repo-local config path if `.vizier/config.toml` or `.vizier/config.json` exists.
+/// Config file names checked in a config directory, in order of preference.
+const CONFIG_FILE_NAMES: &[&str] = &["config.toml", "config.json", "config.yaml", "config.yml"];
+
+/// Returns the repo-local config path if `.vizier/config.{toml,json,yaml,yml}` exists.
 ///
 /// Canonical search order (highest precedence first):
 /// 1. CLI `--config-file` flag (handled in the CLI entrypoint)
-/// 2. Repo-local `.vizier/config.toml` (falling back to `.vizier/config.json`)
+/// 2. Repo-local `.vizier/config.toml` (falling back to `.json`, then `.yaml`/`.yml`)
 /// 3. Global config under `$XDG_CONFIG_HOME`/platform default (`~/.config/vizier/config.toml`)
 /// 4. `VIZIER_CONFIG_FILE` environment variable (lowest precedence)
 pub fn project_config_path(project_root: &Path) -> Option<PathBuf> {
     let vizier_dir = project_root.join(".vizier");
-    let toml_path = vizier_dir.join("config.toml");
-    if toml_path.is_file() {
-        return Some(toml_path);
-    }
-    let json_path = vizier_dir.join("config.json");
-    if json_path.is_file() {
-        Some(json_path)
-    } else {
-        None
-    }
+    CONFIG_FILE_NAMES
+        .iter()
+        .map(|name| vizier_dir.join(name))
+        .find(|path| path.is_file())
 }
 
-/// Returns the user-global config path (`~/.config/vizier/config.toml` on Unix).
+/// Returns the user-global config path (`~/.config/vizier/config.toml` on Unix), or the
+/// `config.json`/`config.yaml`/`config.yml` beside it when only one of those exists.
 pub fn global_config_path() -> Option<PathBuf> {
-    let base_dir = base_config_dir()?;
-    Some(base_dir.join("vizier").join("config.toml"))
+    let config_dir = base_config_dir()?.join("vizier");
+    CONFIG_FILE_NAMES
+        .iter()
+        .map(|name| config_dir.join(name))
+        .find(|path| path.is_file())
+        .or_else(|| Some(config_dir.join("config.toml")))
+}
+
+/// Returns the profile name provided via `VIZIER_PROFILE`, ignoring blank values.
+pub fn env_profile() -> Option<String> {
+    std::env::var("VIZIER_PROFILE")
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
 }
 
 /// Returns the config path provided via `VIZIER_CONFIG_FILE`, ignoring blank values.
@@ -1829,6 +2767,11 @@ pub fn get_config() -> Config {
     CONFIG.read().unwrap().clone()
 }
 
+/// The configured `[output] language`, without cloning the whole config.
+pub fn output_language() -> Option<String> {
+    CONFIG.read().unwrap().output.language.clone()
+}
+
 pub fn get_system_prompt_with_meta(
     scope: CommandScope,
     prompt_kind: Option<SystemPrompt>,
@@ -1842,12 +2785,9 @@ pub fn get_system_prompt_with_meta(
 
     prompt.push_str("<meta>");
 
-    let file_tree = tree::build_tree()?;
+    let (file_tree, _) = context_cache::file_tree(&std::env::current_dir()?)?;
 
-    prompt.push_str(&format!(
-        "<fileTree>{}</fileTree>",
-        tree::tree_to_string(&file_tree, "")
-    ));
+    prompt.push_str(&format!("<fileTree>{file_tree}</fileTree>"));
 
     prompt.push_str(&format!(
         "<narrativeDocs>{}</narrativeDocs>",
@@ -1,7 +1,9 @@
 pub use vizier_kernel::config::*;
 
 mod driver;
+mod keys;
 mod load;
+mod provenance;
 mod validate;
 
 pub use driver::{
@@ -11,10 +13,15 @@ pub use driver::{
     resolve_prompt_profile_for_alias_template,
 };
 pub use load::{
-    base_config_dir, env_config_path, get_config, get_system_prompt_with_meta, global_config_path,
-    load_config_from_json, load_config_from_path, load_config_from_toml,
-    load_config_layer_from_json, load_config_layer_from_path, load_config_layer_from_toml,
-    project_config_path, set_config,
+    base_config_dir, config_from_layers_with_profile, env_config_path, env_profile, get_config,
+    get_system_prompt_with_meta, global_config_path, load_config_from_json, load_config_from_path,
+    load_config_from_path_with_profile, load_config_from_toml, load_config_layer_from_json,
+    load_config_layer_from_path, load_config_layer_from_toml, load_config_layer_from_yaml,
+    output_language, project_config_path, set_config,
+};
+pub use provenance::{
+    ConfigSetting, ConfigSource, ConfigSourceKind, config_settings, config_sources,
+    effective_settings,
 };
 
 #[cfg(test)]
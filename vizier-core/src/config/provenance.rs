@@ -0,0 +1,155 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use super::load::{env_config_path, global_config_path, project_config_path, read_config_value};
+
+/// Which layer set a config value, in increasing precedence.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigSourceKind {
+    Env,
+    Global,
+    Repo,
+    Cli,
+}
+
+impl ConfigSourceKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Env => "env",
+            Self::Global => "global",
+            Self::Repo => "repo",
+            Self::Cli => "cli",
+        }
+    }
+}
+
+/// A config file a run loads.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ConfigSource {
+    pub source: ConfigSourceKind,
+    pub path: PathBuf,
+}
+
+/// One key a layer sets, flattened to its dotted path (`merge.cicd_gate.retries`). Arrays are
+/// reported whole.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ConfigSetting {
+    pub key: String,
+    pub value: serde_json::Value,
+    pub source: ConfigSourceKind,
+    /// File the value was read from; `None` for values set by flags or environment variables.
+    pub path: Option<PathBuf>,
+    /// Profile whose `[profiles.<name>]` overlay set the value.
+    pub profile: Option<String>,
+}
+
+/// The config files a run loads, lowest precedence first: `--config-file` alone when given;
+/// otherwise the global then repo files, with `VIZIER_CONFIG_FILE` used only when neither exists.
+pub fn config_sources(project_root: &Path, explicit: Option<&Path>) -> Vec<ConfigSource> {
+    if let Some(path) = explicit {
+        return vec![ConfigSource {
+            source: ConfigSourceKind::Cli,
+            path: path.to_path_buf(),
+        }];
+    }
+
+    let mut sources = Vec::new();
+    if let Some(path) = global_config_path().filter(|path| path.exists()) {
+        sources.push(ConfigSource {
+            source: ConfigSourceKind::Global,
+            path,
+        });
+    }
+    if let Some(path) = project_config_path(project_root) {
+        sources.push(ConfigSource {
+            source: ConfigSourceKind::Repo,
+            path,
+        });
+    }
+    if sources.is_empty()
+        && let Some(path) = env_config_path().filter(|path| path.exists())
+    {
+        sources.push(ConfigSource {
+            source: ConfigSourceKind::Env,
+            path,
+        });
+    }
+    sources
+}
+
+/// Every key the files in `sources` set, in the order they are applied: each file's own keys,
+/// then its overlay for `profile`. Overlays of other profiles are skipped since they change
+/// nothing.
+pub fn config_settings(
+    sources: &[ConfigSource],
+    profile: Option<&str>,
+) -> Result<Vec<ConfigSetting>, Box<dyn std::error::Error>> {
+    let mut settings = Vec::new();
+    for source in sources {
+        let mut value = read_config_value(&source.path)?;
+        let overlay = match (profile, value.as_object_mut()) {
+            (Some(name), Some(table)) => table
+                .remove("profiles")
+                .and_then(|mut profiles| profiles.get_mut(name).map(serde_json::Value::take)),
+            (None, Some(table)) => {
+                table.remove("profiles");
+                None
+            }
+            _ => None,
+        };
+
+        let mut push = |key: String, value: serde_json::Value, profile: Option<&str>| {
+            settings.push(ConfigSetting {
+                key,
+                value,
+                source: source.source,
+                path: Some(source.path.clone()),
+                profile: profile.map(str::to_string),
+            });
+        };
+        for (key, value) in flatten(&value) {
+            push(key, value, None);
+        }
+        if let Some(overlay) = overlay {
+            for (key, value) in flatten(&overlay) {
+                push(key, value, profile);
+            }
+        }
+    }
+    Ok(settings)
+}
+
+/// The setting that wins for each key (the last one applied), sorted by key.
+pub fn effective_settings(settings: &[ConfigSetting]) -> Vec<ConfigSetting> {
+    let mut winners = BTreeMap::new();
+    for setting in settings {
+        winners.insert(setting.key.clone(), setting.clone());
+    }
+    winners.into_values().collect()
+}
+
+fn flatten(value: &serde_json::Value) -> Vec<(String, serde_json::Value)> {
+    fn walk(prefix: &str, value: &serde_json::Value, out: &mut Vec<(String, serde_json::Value)>) {
+        match value {
+            serde_json::Value::Object(table) => {
+                for (key, value) in table {
+                    let path = if prefix.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{prefix}.{key}")
+                    };
+                    walk(&path, value, out);
+                }
+            }
+            leaf => out.push((prefix.to_string(), leaf.clone())),
+        }
+    }
+    let mut out = Vec::new();
+    if value.is_object() {
+        walk("", value, &mut out);
+    }
+    out
+}
@@ -284,6 +284,7 @@ backend = "gemini"
         let toml = r#"
 [review.checks]
 commands = ["npm test", "cargo fmt -- --check"]
+jobs = 3
 "#;
         let mut file = NamedTempFile::new().expect("temp toml");
         file.write_all(toml.as_bytes()).unwrap();
@@ -292,6 +293,165 @@ commands = ["npm test", "cargo fmt -- --check"]
             cfg.review.checks.commands,
             vec!["npm test", "cargo fmt -- --check"]
         );
+        assert_eq!(cfg.review.checks.jobs, 3);
+        assert_eq!(cfg.review.fix_loop.max_iterations, 3);
+    }
+
+    #[test]
+    fn test_review_fix_loop_max_iterations_from_toml() {
+        let toml = r#"
+[review.fix_loop]
+max_iterations = 5
+"#;
+        let mut file = NamedTempFile::new().expect("temp toml");
+        file.write_all(toml.as_bytes()).unwrap();
+        let cfg = load_config_from_toml(file.path().to_path_buf()).expect("parse review config");
+        assert_eq!(cfg.review.fix_loop.max_iterations, 5);
+    }
+
+    #[test]
+    fn test_config_from_yaml_matches_toml() {
+        let dir = tempdir().expect("temp dir");
+        let path = dir.path().join("config.yaml");
+        fs::write(
+            &path,
+            r#"
+# YAML mirrors the TOML tables
+review:
+  checks:
+    commands: [npm test, "cargo fmt -- --check"]
+    jobs: 2
+merge:
+  cicd_gate:
+    script: ./scripts/run-ci.sh
+    retries: 4
+  gates:
+    - stage: pre
+      script: ./pre.sh
+"#,
+        )
+        .expect("write yaml config");
+
+        let cfg = load_config_from_path(&path).expect("parse yaml config");
+        assert_eq!(
+            cfg.review.checks.commands,
+            vec!["npm test", "cargo fmt -- --check"]
+        );
+        assert_eq!(cfg.review.checks.jobs, 2);
+        assert_eq!(
+            cfg.merge.cicd_gate.script,
+            Some(PathBuf::from("./scripts/run-ci.sh"))
+        );
+        assert_eq!(cfg.merge.cicd_gate.retries, 4);
+        assert_eq!(cfg.merge.gates.len(), 1);
+    }
+
+    #[test]
+    fn test_config_from_yaml_resolves_anchors_and_merge_keys() {
+        let dir = tempdir().expect("temp dir");
+        let path = dir.path().join("config.yml");
+        fs::write(
+            &path,
+            r#"
+review:
+  checks:
+    jobs: &parallel 3
+merge:
+  cicd_gate:
+    retries: *parallel
+  gates:
+    - &pre
+      stage: pre
+      script: ./pre.sh
+    - <<: *pre
+      stage: post
+"#,
+        )
+        .expect("write yaml config");
+
+        let cfg = load_config_from_path(&path).expect("parse yaml config");
+        assert_eq!(cfg.review.checks.jobs, 3);
+        assert_eq!(cfg.merge.cicd_gate.retries, 3);
+        assert_eq!(cfg.merge.gates.len(), 2);
+        assert_eq!(cfg.merge.gates[1].script, PathBuf::from("./pre.sh"));
+        assert_eq!(cfg.merge.gates[1].stage, MergeGateStage::PostMerge);
+    }
+
+    #[test]
+    fn test_config_from_yaml_rejects_multiple_documents() {
+        let dir = tempdir().expect("temp dir");
+        let path = dir.path().join("config.yaml");
+        fs::write(&path, "review:\n  checks:\n    jobs: 2\n---\nreview: {}\n")
+            .expect("write yaml config");
+        let Err(err) = load_config_from_path(&path) else {
+            panic!("multi-document yaml should fail");
+        };
+        assert!(err.to_string().contains("more than one document"), "{err}");
+    }
+
+    #[test]
+    fn test_strict_config_rejects_unknown_keys_with_suggestion() {
+        let dir = tempdir().expect("temp dir");
+        let path = dir.path().join("config.toml");
+        fs::write(
+            &path,
+            "[merged.cicd_gate]\nscript = \"./ci.sh\"\n\n[review.checks]\njbos = 2\n",
+        )
+        .expect("write config");
+
+        let Err(err) = load_config_from_path(&path) else {
+            panic!("unknown keys should fail");
+        };
+        let err = err.to_string();
+        assert!(
+            err.contains("`merged` (did you mean `merge`?)"),
+            "unexpected error: {err}"
+        );
+        assert!(
+            err.contains("`review.checks.jbos` (did you mean `review.checks.jobs`?)"),
+            "unexpected error: {err}"
+        );
+
+        let yaml = dir.path().join("config.yaml");
+        fs::write(&yaml, "workflow:\n  drift:\n    max_commit_behind: 3\n").expect("write yaml");
+        let Err(err) = load_config_from_path(&yaml) else {
+            panic!("unknown yaml key should fail");
+        };
+        let err = err.to_string();
+        assert!(
+            err.contains("did you mean `workflow.drift.max_commits_behind`?"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn test_strict_config_can_be_disabled_per_file() {
+        let dir = tempdir().expect("temp dir");
+        let path = dir.path().join("config.toml");
+        fs::write(
+            &path,
+            "[config]\nstrict = false\n\n[merged.cicd_gate]\nscript = \"./ci.sh\"\n",
+        )
+        .expect("write config");
+
+        let cfg = load_config_from_path(&path).expect("non-strict config should load");
+        assert!(cfg.merge.cicd_gate.script.is_none());
+    }
+
+    #[test]
+    fn test_shipped_configs_pass_strict_key_check() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+        for relative in [
+            "example-config.toml",
+            ".vizier/config.toml",
+            "vizier-cli/templates/init/config.toml",
+        ] {
+            let value = crate::config::load::read_config_value(&root.join(relative))
+                .unwrap_or_else(|err| panic!("read {relative}: {err}"));
+            if let Err(err) = crate::config::keys::check_unknown_keys(&value) {
+                panic!("{relative} should pass the strict key check: {err}");
+            }
+        }
     }
 
     #[test]
@@ -313,6 +473,104 @@ retries = 3
         assert_eq!(cfg.merge.cicd_gate.retries, 3);
     }
 
+    #[test]
+    fn test_merge_commit_template_inline_file_and_unknown_placeholder() {
+        let dir = tempdir().expect("temp dir");
+        let inline = dir.path().join("inline.toml");
+        fs::write(
+            &inline,
+            "[merge]\ncommit_template = \"feat({slug}): {title}\\n\\n{plan}\"\n",
+        )
+        .unwrap();
+        let cfg = load_config_from_toml(inline).expect("parse inline template");
+        assert_eq!(
+            cfg.merge.commit_template.as_deref(),
+            Some("feat({slug}): {title}\n\n{plan}")
+        );
+
+        fs::write(dir.path().join("merge-msg.txt"), "chore: land {slug}\n").unwrap();
+        let from_file = dir.path().join("file.toml");
+        fs::write(
+            &from_file,
+            "[merge]\ncommit_template = { path = \"merge-msg.txt\" }\n",
+        )
+        .unwrap();
+        let cfg = load_config_from_toml(from_file).expect("parse template file");
+        assert_eq!(
+            cfg.merge.commit_template.as_deref(),
+            Some("chore: land {slug}\n")
+        );
+
+        let unknown = dir.path().join("unknown.toml");
+        fs::write(&unknown, "[merge]\ncommit_template = \"{verdict}\"\n").unwrap();
+        let err = match load_config_from_toml(unknown) {
+            Ok(_) => panic!("expected unknown placeholder error"),
+            Err(err) => err,
+        };
+        assert!(err.to_string().contains("unknown placeholder"), "{err}");
+    }
+
+    #[test]
+    fn test_merge_staged_gates_config_from_toml() {
+        let toml = r#"
+[merge]
+auto-revert = true
+protected = ["main", "release/*"]
+
+[merge.cicd_gate]
+script = "./legacy.sh"
+
+[[merge.gates]]
+name = "unit"
+stage = "pre-merge"
+script = "cargo test"
+
+[[merge.gates]]
+name = "smoke"
+stage = "post_merge"
+script = "./smoke.sh"
+
+[[merge.gates]]
+script = "./lint.sh"
+"#;
+        let mut file = NamedTempFile::new().expect("temp toml");
+        file.write_all(toml.as_bytes()).unwrap();
+        let cfg = load_config_from_toml(file.path().to_path_buf()).expect("parse merge gates");
+        assert!(cfg.merge.auto_revert);
+        assert!(cfg.merge.is_protected("release/2.0"));
+        assert!(!cfg.merge.is_protected("develop"));
+
+        let pre = cfg.merge.staged_gates(MergeGateStage::PreMerge);
+        assert_eq!(
+            pre.iter()
+                .map(|gate| gate.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["unit", "pre-merge-3"],
+            "gates keep their configured order"
+        );
+        let post = cfg.merge.staged_gates(MergeGateStage::PostMerge);
+        assert_eq!(post.len(), 1);
+        assert_eq!(post[0].script, PathBuf::from("./smoke.sh"));
+
+        assert_eq!(
+            cfg.merge.cicd_gate.script,
+            Some(PathBuf::from("./legacy.sh")),
+            "[merge.cicd_gate] keeps driving the template cicd gate"
+        );
+
+        let bad = r#"
+[[merge.gates]]
+stage = "sometime"
+script = "true"
+"#;
+        let mut file = NamedTempFile::new().expect("temp toml");
+        file.write_all(bad.as_bytes()).unwrap();
+        let err = load_config_from_toml(file.path().to_path_buf())
+            .err()
+            .expect("invalid stage should fail");
+        assert!(err.to_string().contains("sometime"), "{err}");
+    }
+
     #[test]
     fn test_merge_cicd_gate_config_from_json_aliases() {
         let json = r#"
@@ -425,6 +683,422 @@ retries = 5
             Some(PathBuf::from("./scripts/approve-stop.sh"))
         );
         assert_eq!(cfg.approve.stop_condition.retries, 5);
+        assert_eq!(cfg.approve.stop_condition.conditions, None);
+    }
+
+    #[test]
+    fn approve_stop_conditions_load_as_expression_and_reject_unknown_names() {
+        let mut file = NamedTempFile::new().expect("temp toml");
+        file.write_all(
+            br#"[approve.stop_condition]
+tests = "cargo test"
+conditions = ["tests_pass", "diff_lines < 400"]
+"#,
+        )
+        .unwrap();
+        let cfg = load_config_from_toml(file.path().to_path_buf()).expect("parse conditions");
+        assert_eq!(
+            cfg.approve.stop_condition.conditions.as_deref(),
+            Some("(tests_pass) && (diff_lines < 400)")
+        );
+        assert_eq!(
+            cfg.approve.stop_condition.tests.as_deref(),
+            Some("cargo test")
+        );
+
+        let mut file = NamedTempFile::new().expect("temp toml");
+        file.write_all(b"[approve.stop_condition]\nconditions = \"tests_pass && lint_clean\"\n")
+            .unwrap();
+        let Err(err) = load_config_from_toml(file.path().to_path_buf()) else {
+            panic!("unknown conditions should be rejected");
+        };
+        assert!(
+            err.to_string()
+                .contains("unknown stop condition `lint_clean`"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_workflow_drift_config_from_toml() {
+        let defaults = Config::default();
+        assert_eq!(defaults.workflow.drift.max_commits_behind, 50);
+        assert_eq!(defaults.workflow.drift.max_age_days, 14);
+        assert!(!defaults.workflow.drift.strict);
+
+        let toml = r#"
+[workflow.drift]
+max_commits_behind = 10
+max-age-days = 0
+strict = true
+"#;
+        let mut file = NamedTempFile::new().expect("temp toml");
+        file.write_all(toml.as_bytes()).unwrap();
+        let cfg =
+            load_config_from_toml(file.path().to_path_buf()).expect("parse workflow drift config");
+        assert_eq!(cfg.workflow.drift.max_commits_behind, 10);
+        assert_eq!(cfg.workflow.drift.max_age_days, 0);
+        assert!(cfg.workflow.drift.strict);
+    }
+
+    #[test]
+    fn test_workflow_diff_context_config_from_toml() {
+        let defaults = Config::default();
+        assert_eq!(defaults.workflow.diff_context.max_bytes, 40_000);
+        assert_eq!(
+            defaults.workflow.diff_context.summarizer,
+            DiffSummarizer::Agent
+        );
+
+        let toml = r#"
+[workflow.diff-context]
+max_bytes = 1000
+chunk-bytes = 200
+raw_hunk_bytes = 300
+summarizer = "none"
+
+[workflow.prompt_budget]
+max-bytes = 5000
+"#;
+        let mut file = NamedTempFile::new().expect("temp toml");
+        file.write_all(toml.as_bytes()).unwrap();
+        let cfg = load_config_from_toml(file.path().to_path_buf())
+            .expect("parse workflow diff context config");
+        assert_eq!(cfg.workflow.diff_context.max_bytes, 1000);
+        assert_eq!(cfg.workflow.diff_context.chunk_bytes, 200);
+        assert_eq!(cfg.workflow.diff_context.raw_hunk_bytes, 300);
+        assert_eq!(cfg.workflow.diff_context.summarizer, DiffSummarizer::None);
+        assert_eq!(defaults.workflow.prompt_budget.max_bytes, 480_000);
+        assert_eq!(cfg.workflow.prompt_budget.max_bytes, 5000);
+    }
+
+    #[test]
+    fn test_sessions_retention_config_from_toml() {
+        assert_eq!(Config::default().sessions, SessionsConfig::default());
+
+        let toml = r#"
+[sessions]
+keep_days = 30
+keep-last = 50
+compress = true
+"#;
+        let mut file = NamedTempFile::new().expect("temp toml");
+        file.write_all(toml.as_bytes()).unwrap();
+        let cfg = load_config_from_toml(file.path().to_path_buf())
+            .expect("parse sessions retention config");
+        assert_eq!(
+            cfg.sessions,
+            SessionsConfig {
+                keep_days: 30,
+                keep_last: 50,
+                compress: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_tmp_keep_days_from_toml() {
+        assert_eq!(Config::default().tmp.keep_days, 7);
+
+        let mut file = NamedTempFile::new().expect("temp toml");
+        file.write_all(b"[tmp]\nkeep-days = 0\n").unwrap();
+        let cfg = load_config_from_toml(file.path().to_path_buf()).expect("parse tmp config");
+        assert_eq!(cfg.tmp.keep_days, 0);
+    }
+
+    #[test]
+    fn search_embedder_loads_as_command_arguments() {
+        assert!(Config::default().search.embedder.is_empty());
+
+        let mut file = NamedTempFile::new().expect("temp toml");
+        file.write_all(b"[search]\nembedder = [\"dewey\", \"embed\"]\n")
+            .unwrap();
+        let cfg = load_config_from_toml(file.path().to_path_buf()).expect("parse search config");
+        assert_eq!(cfg.search.embedder, vec!["dewey", "embed"]);
+
+        let mut file = NamedTempFile::new().expect("temp toml");
+        file.write_all(b"[search]\nembedder = \"dewey embed\"\n")
+            .unwrap();
+        let Err(err) = load_config_from_toml(file.path().to_path_buf()) else {
+            panic!("a bare string embedder should be rejected");
+        };
+        assert!(
+            err.to_string().contains("search.embedder must be an array"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_agent_stall_from_toml() {
+        assert_eq!(Config::default().agent_stall.warn_after_secs, 300);
+
+        let mut file = NamedTempFile::new().expect("temp toml");
+        file.write_all(
+            b"[agent_stall]\nwarn-after-secs = 60\nkill_after_secs = 600\nretries = 2\n",
+        )
+        .unwrap();
+        let cfg = load_config_from_toml(file.path().to_path_buf()).expect("parse stall config");
+        assert_eq!(
+            cfg.agent_stall,
+            AgentStallConfig {
+                warn_after_secs: 60,
+                kill_after_secs: 600,
+                retries: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn test_context_never_send_layers_accumulate() {
+        let mut global_file = NamedTempFile::new().expect("temp toml");
+        global_file
+            .write_all(b"[context]\nnever_send = [\"secrets/**\"]\n")
+            .unwrap();
+        let mut repo_file = NamedTempFile::new().expect("temp toml");
+        repo_file
+            .write_all(b"[context]\nnever-send = [\"customer-data/**\", \"secrets/**\"]\n")
+            .unwrap();
+
+        let layers = vec![
+            load_config_layer_from_toml(global_file.path().to_path_buf()).expect("global layer"),
+            load_config_layer_from_toml(repo_file.path().to_path_buf()).expect("repo layer"),
+        ];
+        let cfg = config_from_layers_with_profile(&layers, None).expect("merge layers");
+        assert_eq!(
+            cfg.context.never_send,
+            vec!["secrets/**".to_string(), "customer-data/**".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_worktrees_dir_layers_and_empty_reset() {
+        let mut global_file = NamedTempFile::new().expect("temp toml");
+        global_file
+            .write_all(b"[worktrees]\ndir = \"/var/tmp/vizier-worktrees\"\n")
+            .unwrap();
+        let global =
+            load_config_layer_from_toml(global_file.path().to_path_buf()).expect("global layer");
+        let cfg = config_from_layers_with_profile(std::slice::from_ref(&global), None)
+            .expect("merge layers");
+        assert_eq!(
+            cfg.worktrees.dir,
+            Some(PathBuf::from("/var/tmp/vizier-worktrees"))
+        );
+
+        let mut repo_file = NamedTempFile::new().expect("temp toml");
+        repo_file.write_all(b"[worktrees]\ndir = \"\"\n").unwrap();
+        let repo = load_config_layer_from_toml(repo_file.path().to_path_buf()).expect("repo layer");
+        let cfg = config_from_layers_with_profile(&[global, repo], None).expect("merge layers");
+        assert_eq!(cfg.worktrees.dir, None);
+    }
+
+    #[test]
+    fn test_hooks_layer_by_name_and_empty_script_removes() {
+        let mut global_file = NamedTempFile::new().expect("temp toml");
+        global_file
+            .write_all(b"[hooks]\npre_merge = \"./ticket.sh start\"\non-failure = \"./chat.sh\"\n")
+            .unwrap();
+        let global =
+            load_config_layer_from_toml(global_file.path().to_path_buf()).expect("global layer");
+        let mut repo_file = NamedTempFile::new().expect("temp toml");
+        repo_file
+            .write_all(b"[hooks]\npost_approve = \"./ticket.sh review\"\non_failure = \"\"\n")
+            .unwrap();
+        let repo = load_config_layer_from_toml(repo_file.path().to_path_buf()).expect("repo layer");
+        let cfg = config_from_layers_with_profile(&[global, repo], None).expect("merge layers");
+        assert_eq!(cfg.hooks.script("pre_merge"), Some("./ticket.sh start"));
+        assert_eq!(cfg.hooks.script("post_approve"), Some("./ticket.sh review"));
+        assert_eq!(cfg.hooks.script("on_failure"), None);
+
+        let mut bad_file = NamedTempFile::new().expect("temp toml");
+        bad_file.write_all(b"[hooks]\npre_draft = 1\n").unwrap();
+        let err = load_config_layer_from_toml(bad_file.path().to_path_buf())
+            .expect_err("non-string hook");
+        assert!(err.to_string().contains("hooks.pre_draft"), "{err}");
+    }
+
+    #[test]
+    fn test_branches_target_layer_and_empty_reset() {
+        let layer = |toml: &[u8]| {
+            let mut file = NamedTempFile::new().expect("temp toml");
+            file.write_all(toml).unwrap();
+            load_config_layer_from_toml(file.path().to_path_buf()).expect("layer")
+        };
+        let global = layer(b"[branches]\ntarget = \"develop\"\n");
+        let cfg = config_from_layers_with_profile(std::slice::from_ref(&global), None)
+            .expect("merge global");
+        assert_eq!(cfg.branches.target.as_deref(), Some("develop"));
+        assert_eq!(cfg.branches.prefix, "draft/");
+
+        let repo = layer(b"[branches]\ntarget = \"\"\n");
+        let cfg = config_from_layers_with_profile(&[global, repo], None).expect("merge layers");
+        assert_eq!(cfg.branches.target, None);
+    }
+
+    #[test]
+    fn test_output_language_config_from_toml() {
+        let parse = |toml: &str| {
+            let mut file = NamedTempFile::new().expect("temp toml");
+            file.write_all(toml.as_bytes()).unwrap();
+            load_config_from_toml(file.path().to_path_buf())
+        };
+        let cfg = parse("[output]\nlanguage = \"pt_BR\"\n").expect("parse output language");
+        assert_eq!(cfg.output.language.as_deref(), Some("pt-br"));
+        let cfg = parse("[output]\nlanguage = \"en-US\"\n").expect("parse english");
+        assert_eq!(cfg.output.language, None);
+        assert!(parse("[output]\nlanguage = \"Deutsch!\"\n").is_err());
+    }
+
+    #[test]
+    fn test_forge_cli_from_toml() {
+        let parse = |toml: &str| {
+            let mut file = NamedTempFile::new().expect("temp toml");
+            file.write_all(toml.as_bytes()).unwrap();
+            load_config_from_toml(file.path().to_path_buf())
+        };
+        let cfg = parse("").expect("parse empty");
+        assert_eq!(cfg.forge.cli, ForgeCli::Auto);
+        let cfg = parse("[forge]\ncli = \"glab\"\n").expect("parse glab");
+        assert_eq!(cfg.forge.cli, ForgeCli::Glab);
+        let cfg = parse("[forge]\ncli = \"none\"\n").expect("parse none");
+        assert_eq!(cfg.forge.cli, ForgeCli::Off);
+        assert!(parse("[forge]\ncli = \"gitea\"\n").is_err());
+    }
+
+    #[test]
+    fn test_merge_squash_granularity_from_toml() {
+        let parse = |toml: &str| {
+            let mut file = NamedTempFile::new().expect("temp toml");
+            file.write_all(toml.as_bytes()).unwrap();
+            load_config_from_toml(file.path().to_path_buf())
+        };
+        let cfg = parse("").expect("parse empty");
+        assert_eq!(cfg.merge.squash_granularity, SquashGranularity::Plan);
+        let cfg = parse("[merge]\nsquash_granularity = \"step\"\n").expect("parse step");
+        assert_eq!(cfg.merge.squash_granularity, SquashGranularity::Step);
+        assert!(parse("[merge]\nsquash_granularity = \"file\"\n").is_err());
+    }
+
+    #[test]
+    fn test_review_tools_toggle_from_toml() {
+        let parse = |toml: &str| {
+            let mut file = NamedTempFile::new().expect("temp toml");
+            file.write_all(toml.as_bytes()).unwrap();
+            load_config_from_toml(file.path().to_path_buf())
+        };
+        assert!(parse("").expect("parse empty").review.tools);
+        let cfg = parse("[review]\ntools = false\n").expect("parse review tools");
+        assert!(!cfg.review.tools);
+    }
+
+    #[test]
+    fn test_approve_cost_rates_from_toml() {
+        let parse = |toml: &str| {
+            let mut file = NamedTempFile::new().expect("temp toml");
+            file.write_all(toml.as_bytes()).unwrap();
+            load_config_from_toml(file.path().to_path_buf())
+        };
+        let cfg = parse("[approve.cost]\ninput_usd_per_mtok = 1.25\noutput_usd_per_mtok = 10\n")
+            .expect("parse approve cost");
+        assert_eq!(cfg.approve.cost.input_micro_usd_per_mtok, 1_250_000);
+        assert_eq!(cfg.approve.cost.output_micro_usd_per_mtok, 10_000_000);
+        assert!(parse("[approve.cost]\ninput_usd_per_mtok = -1\n").is_err());
+    }
+
+    #[test]
+    fn test_approve_generate_tests_from_toml() {
+        assert!(!Config::default().approve.generate_tests);
+        let mut file = NamedTempFile::new().expect("temp toml");
+        file.write_all(b"[approve]\ngenerate_tests = true\n")
+            .unwrap();
+        let cfg = load_config_from_toml(file.path().to_path_buf()).expect("parse approve");
+        assert!(cfg.approve.generate_tests);
+    }
+
+    #[test]
+    fn test_named_profile_overlays_base_config() {
+        let toml = r#"
+agent = "codex"
+
+[approve.stop_condition]
+script = "./scripts/approve-stop.sh"
+retries = 2
+
+[profiles.local]
+agent = "gemini"
+
+[profiles.local.approve.stop_condition]
+retries = 7
+"#;
+        let mut file = NamedTempFile::new().expect("temp toml");
+        file.write_all(toml.as_bytes()).unwrap();
+        let path = file.path();
+
+        let base = load_config_from_path_with_profile(path, None).expect("base config");
+        assert_eq!(base.profile, None);
+        assert_eq!(base.agent_selector, "codex");
+        assert_eq!(base.approve.stop_condition.retries, 2);
+
+        let local =
+            load_config_from_path_with_profile(path, Some("local")).expect("profile config");
+        assert_eq!(local.profile.as_deref(), Some("local"));
+        assert_eq!(local.agent_selector, "gemini");
+        assert_eq!(local.approve.stop_condition.retries, 7);
+        assert_eq!(
+            local.approve.stop_condition.script,
+            Some(PathBuf::from("./scripts/approve-stop.sh")),
+            "unset profile keys should fall through to the base config"
+        );
+
+        let err = match load_config_from_path_with_profile(path, Some("cloud")) {
+            Ok(_) => panic!("unknown profile should be rejected"),
+            Err(err) => err.to_string(),
+        };
+        assert!(
+            err.contains("config profile `cloud` is not defined (available: local)"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn test_profile_overlays_apply_per_layer_in_order() {
+        let global = r#"
+[profiles.work.approve.stop_condition]
+retries = 9
+"#;
+        let repo = r#"
+[approve.stop_condition]
+retries = 3
+"#;
+        let mut global_file = NamedTempFile::new().expect("temp toml");
+        global_file.write_all(global.as_bytes()).unwrap();
+        let mut repo_file = NamedTempFile::new().expect("temp toml");
+        repo_file.write_all(repo.as_bytes()).unwrap();
+
+        let layers = vec![
+            load_config_layer_from_toml(global_file.path().to_path_buf()).expect("global layer"),
+            load_config_layer_from_toml(repo_file.path().to_path_buf()).expect("repo layer"),
+        ];
+        let cfg = config_from_layers_with_profile(&layers, Some("work")).expect("merge layers");
+        assert_eq!(
+            cfg.approve.stop_condition.retries, 3,
+            "later layers still override earlier profile overlays"
+        );
+    }
+
+    #[test]
+    fn test_nested_profiles_are_rejected() {
+        let toml = r#"
+[profiles.outer.profiles.inner]
+agent = "codex"
+"#;
+        let mut file = NamedTempFile::new().expect("temp toml");
+        file.write_all(toml.as_bytes()).unwrap();
+        let err = match load_config_layer_from_toml(file.path().to_path_buf()) {
+            Ok(_) => panic!("nested profiles should be rejected"),
+            Err(err) => err.to_string(),
+        };
+        assert!(err.contains("cannot declare nested profiles"), "{err}");
     }
 
     #[test]
@@ -502,6 +1176,9 @@ plan_label = "Plan Summary"
     #[test]
     fn config_parses_display_list_settings() {
         let toml = r#"
+[display]
+log = "agent=debug,git=warn"
+
 [display.lists.list]
 format = "table"
 header_fields = ["Outcome"]
@@ -523,6 +1200,7 @@ fields = ["Job", "Status", "Command"]
         let mut file = NamedTempFile::new().expect("temp toml");
         file.write_all(toml.as_bytes()).unwrap();
         let cfg = load_config_from_toml(file.path().to_path_buf()).expect("parse display config");
+        assert_eq!(cfg.display.log.as_deref(), Some("agent=debug,git=warn"));
         assert_eq!(cfg.display.lists.list.format, ListFormat::Table);
         assert_eq!(cfg.display.lists.list.header_fields, vec!["Outcome"]);
         assert_eq!(cfg.display.lists.list.entry_fields, vec!["Plan", "Summary"]);
@@ -915,6 +1593,348 @@ agent = "gemini"
         assert_eq!(agent.backend, BackendKind::Gemini);
     }
 
+    #[test]
+    fn branches_prefix_loads_and_rejects_invalid_schemes() {
+        let load = |toml: &str| {
+            let mut file = NamedTempFile::new().expect("temp toml");
+            file.write_all(toml.as_bytes()).unwrap();
+            load_config_from_toml(file.path().to_path_buf())
+        };
+
+        let cfg = load("[branches]\nprefix = \"draft/{user}/\"\n").expect("parse branches");
+        assert_eq!(cfg.branches.prefix, "draft/{user}/");
+        assert_eq!(
+            Config::default().branches.prefix,
+            "draft/",
+            "default scheme stays draft/<slug>"
+        );
+
+        for invalid in [
+            "[branches]\nprefix = \"\"\n",
+            "[branches]\nprefix = \"a b/\"\n",
+            "[branches]\nprefix = \"{user}/{user}/\"\n",
+        ] {
+            let Err(err) = load(invalid) else {
+                panic!("prefix should be rejected: {invalid}");
+            };
+            assert!(err.to_string().contains("branches.prefix"), "{err}");
+        }
+    }
+
+    #[test]
+    fn remote_table_loads_build_host_settings() {
+        let toml = r#"
+[remote]
+host = "ci@build-01"
+path = "/srv/checkouts/app"
+vizier = "/opt/vizier/bin/vizier"
+ssh = ["ssh", "-p", "2222"]
+"#;
+        let mut file = NamedTempFile::new().expect("temp toml");
+        file.write_all(toml.as_bytes()).unwrap();
+        let cfg = load_config_from_toml(file.path().to_path_buf()).expect("parse remote");
+        assert_eq!(cfg.remote.host.as_deref(), Some("ci@build-01"));
+        assert_eq!(cfg.remote.path.as_deref(), Some("/srv/checkouts/app"));
+        assert_eq!(
+            cfg.remote.git_remote, "origin",
+            "git_remote defaults to origin"
+        );
+        assert_eq!(cfg.remote.vizier, "/opt/vizier/bin/vizier");
+        assert_eq!(cfg.remote.ssh, vec!["ssh", "-p", "2222"]);
+        assert!(Config::default().remote.host.is_none());
+
+        let mut file = NamedTempFile::new().expect("temp toml");
+        file.write_all(b"[remote]\nssh = []\n").unwrap();
+        let Err(err) = load_config_from_toml(file.path().to_path_buf()) else {
+            panic!("an empty ssh command should be rejected");
+        };
+        assert!(err.to_string().contains("remote.ssh"), "{err}");
+    }
+
+    #[test]
+    fn email_notifications_load_events_and_reject_unknown_ones() {
+        let toml = r#"
+[notifications.email]
+smtp_host = "smtp.example.com"
+tls = "implicit"
+smtp_port = 465
+username = "vizier-bot"
+from = "vizier@example.com"
+to = "team@example.com"
+digest_hours = 12
+
+[notifications.email.events]
+merge = "immediate"
+job-failure = "off"
+"#;
+        let mut file = NamedTempFile::new().expect("temp toml");
+        file.write_all(toml.as_bytes()).unwrap();
+        let cfg = load_config_from_toml(file.path().to_path_buf()).expect("parse notifications");
+        let email = &cfg.notifications.email;
+        assert!(email.enabled());
+        assert_eq!(email.tls, SmtpTls::Implicit);
+        assert_eq!(email.smtp_port, 465);
+        assert_eq!(email.to, vec!["team@example.com"]);
+        assert_eq!(email.digest_hours, 12);
+        assert_eq!(email.password_env, "VIZIER_SMTP_PASSWORD");
+        assert_eq!(
+            email.delivery(NotificationEvent::Merge),
+            NotificationDelivery::Immediate
+        );
+        assert_eq!(
+            email.delivery(NotificationEvent::JobFailure),
+            NotificationDelivery::Off
+        );
+        assert_eq!(
+            email.delivery(NotificationEvent::Draft),
+            NotificationDelivery::Digest,
+            "unlisted events keep their defaults"
+        );
+        assert!(!Config::default().notifications.email.enabled());
+
+        let mut file = NamedTempFile::new().expect("temp toml");
+        file.write_all(
+            b"[notifications.email.events]
+release = \"digest\"\n",
+        )
+        .unwrap();
+        let Err(err) = load_config_from_toml(file.path().to_path_buf()) else {
+            panic!("an unknown event should be rejected");
+        };
+        assert!(err.to_string().contains("unknown event `release`"), "{err}");
+    }
+
+    #[test]
+    fn command_audit_loads_patterns_and_stays_off_by_default() {
+        let mut file = NamedTempFile::new().expect("temp toml");
+        file.write_all(
+            b"[command_audit]\nenabled = true\nallow = [\"cargo *\", \"git status*\"]\nabort = [\"git push*\"]\n",
+        )
+        .unwrap();
+        let cfg = load_config_from_toml(file.path().to_path_buf()).expect("parse command audit");
+        assert!(cfg.command_audit.enabled);
+        assert_eq!(cfg.command_audit.allow, vec!["cargo *", "git status*"]);
+        assert_eq!(cfg.command_audit.abort, vec!["git push*"]);
+        assert_eq!(
+            Config::default().command_audit,
+            CommandAuditConfig::default()
+        );
+
+        let mut file = NamedTempFile::new().expect("temp toml");
+        file.write_all(b"[command_audit]\nallow = \"cargo *\"\n")
+            .unwrap();
+        let Err(err) = load_config_from_toml(file.path().to_path_buf()) else {
+            panic!("a bare string allow list should be rejected");
+        };
+        assert!(
+            err.to_string()
+                .contains("command_audit.allow must be an array"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn changelog_table_parses_style_and_validates_type() {
+        let mut file = NamedTempFile::new().expect("temp toml");
+        file.write_all(
+            b"[changelog]\nenabled = true\ndir = \"changes\"\nstyle = \"towncrier\"\ndefault_type = \"Fixed\"\n",
+        )
+        .unwrap();
+        let cfg = load_config_from_toml(file.path().to_path_buf()).expect("parse changelog");
+        assert!(cfg.changelog.enabled);
+        assert_eq!(cfg.changelog.dir, "changes");
+        assert_eq!(cfg.changelog.style, ChangelogStyle::Towncrier);
+        assert_eq!(cfg.changelog.default_type, "fixed");
+        assert_eq!(cfg.changelog.file, "CHANGELOG.md");
+        assert!(!Config::default().changelog.enabled);
+
+        let mut file = NamedTempFile::new().expect("temp toml");
+        file.write_all(b"[changelog]\ndefault_type = \"misc\"\n")
+            .unwrap();
+        let Err(err) = load_config_from_toml(file.path().to_path_buf()) else {
+            panic!("an unknown change type should be rejected");
+        };
+        assert!(err.to_string().contains("changelog.default_type"), "{err}");
+    }
+
+    #[test]
+    fn config_settings_flatten_layers_and_keep_the_last_writer() {
+        let dir = tempdir().expect("tempdir");
+        let global = dir.path().join("global.toml");
+        let repo = dir.path().join("repo.json");
+        fs::write(
+            &global,
+            "agent = \"codex\"\n[merge.cicd_gate]\nscript = \"./ci.sh\"\nretries = 2\n\n[profiles.ci.merge.cicd_gate]\nretries = 5\n\n[profiles.other]\nagent = \"gemini\"\n",
+        )
+        .unwrap();
+        fs::write(&repo, r#"{"merge": {"cicd_gate": {"retries": 3}}, "review": {"checks": {"commands": ["cargo test"]}}}"#).unwrap();
+        let sources = [
+            ConfigSource {
+                source: ConfigSourceKind::Global,
+                path: global,
+            },
+            ConfigSource {
+                source: ConfigSourceKind::Repo,
+                path: repo,
+            },
+        ];
+
+        let settings = config_settings(&sources, Some("ci")).expect("settings");
+        let summary = settings
+            .iter()
+            .map(|setting| {
+                (
+                    setting.key.as_str(),
+                    setting.value.to_string(),
+                    setting.source,
+                    setting.profile.as_deref(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    "agent",
+                    "\"codex\"".to_string(),
+                    ConfigSourceKind::Global,
+                    None
+                ),
+                (
+                    "merge.cicd_gate.retries",
+                    "2".to_string(),
+                    ConfigSourceKind::Global,
+                    None
+                ),
+                (
+                    "merge.cicd_gate.script",
+                    "\"./ci.sh\"".to_string(),
+                    ConfigSourceKind::Global,
+                    None
+                ),
+                (
+                    "merge.cicd_gate.retries",
+                    "5".to_string(),
+                    ConfigSourceKind::Global,
+                    Some("ci")
+                ),
+                (
+                    "merge.cicd_gate.retries",
+                    "3".to_string(),
+                    ConfigSourceKind::Repo,
+                    None
+                ),
+                (
+                    "review.checks.commands",
+                    "[\"cargo test\"]".to_string(),
+                    ConfigSourceKind::Repo,
+                    None
+                ),
+            ]
+        );
+
+        let effective = effective_settings(&settings);
+        assert_eq!(
+            effective
+                .iter()
+                .map(|setting| (setting.key.as_str(), setting.source))
+                .collect::<Vec<_>>(),
+            vec![
+                ("agent", ConfigSourceKind::Global),
+                ("merge.cicd_gate.retries", ConfigSourceKind::Repo),
+                ("merge.cicd_gate.script", ConfigSourceKind::Global),
+                ("review.checks.commands", ConfigSourceKind::Repo),
+            ]
+        );
+        assert_eq!(config_settings(&sources, None).expect("settings").len(), 5);
+    }
+
+    #[test]
+    fn draft_size_limits_load_with_defaults() {
+        let mut file = NamedTempFile::new().expect("temp toml");
+        file.write_all(b"[draft]\nmax_steps = 20\n").unwrap();
+        let cfg = load_config_from_toml(file.path().to_path_buf()).expect("parse draft");
+        assert_eq!(cfg.draft.max_steps, 20);
+        assert_eq!(cfg.draft.max_files, 15, "unset limits keep their defaults");
+        assert!(!cfg.draft.auto_push);
+
+        let mut file = NamedTempFile::new().expect("temp toml");
+        file.write_all(b"[draft]\nauto-push = true\n").unwrap();
+        let cfg = load_config_from_toml(file.path().to_path_buf()).expect("parse auto_push");
+        assert!(cfg.draft.auto_push);
+    }
+
+    #[test]
+    fn agent_env_tables_merge_by_scope() {
+        let dir = tempdir().expect("tempdir");
+        let path = dir.path().join("config.toml");
+        fs::write(
+            &path,
+            r#"
+[agents.default]
+env_file = "agent.env"
+
+[agents.default.env]
+MODEL_REGION = "us-east"
+LOG_LEVEL = "info"
+
+[agents.commands.draft.env]
+LOG_LEVEL = "debug"
+MAX_TURNS = 12
+"#,
+        )
+        .expect("write config");
+        let cfg = load_config_from_toml(path).expect("parse agent env");
+
+        let draft = "draft".parse::<CommandAlias>().expect("draft alias");
+        let resolved = resolve_agent_settings_for_alias(&cfg, &draft, None).expect("resolve draft");
+        assert_eq!(resolved.env.vars["MODEL_REGION"], "us-east");
+        assert_eq!(resolved.env.vars["LOG_LEVEL"], "debug");
+        assert_eq!(resolved.env.vars["MAX_TURNS"], "12");
+        assert_eq!(
+            resolved.env.env_file.as_deref(),
+            Some(dir.path().join("agent.env").as_path()),
+            "env_file resolves relative to the config file"
+        );
+
+        let approve = "approve".parse::<CommandAlias>().expect("approve alias");
+        let resolved =
+            resolve_agent_settings_for_alias(&cfg, &approve, None).expect("resolve approve");
+        assert_eq!(resolved.env.vars["LOG_LEVEL"], "info");
+
+        let mut file = NamedTempFile::new().expect("temp toml");
+        file.write_all(b"[agents.default.env]\n\"BAD-NAME\" = \"x\"\n")
+            .unwrap();
+        let Err(err) = load_config_from_toml(file.path().to_path_buf()) else {
+            panic!("invalid env names should be rejected");
+        };
+        assert!(err.to_string().contains("BAD-NAME"), "{err}");
+    }
+
+    #[test]
+    fn mock_selector_resolves_fixture_backend() {
+        let toml = r#"
+[agents.default]
+agent = "mock"
+
+[mock_agent]
+fixtures = "tests/fixtures/agent"
+"#;
+        let mut file = NamedTempFile::new().expect("temp toml");
+        file.write_all(toml.as_bytes()).unwrap();
+        let cfg = load_config_from_toml(file.path().to_path_buf()).expect("parse mock config");
+        assert_eq!(
+            cfg.mock_agent.fixtures,
+            PathBuf::from("tests/fixtures/agent")
+        );
+
+        let agent = resolve_default_agent_settings(&cfg, None).expect("resolve mock agent");
+        assert_eq!(agent.backend, BackendKind::Mock);
+        assert!(agent.agent_runtime.command.is_empty());
+        assert_eq!(agent.agent_runtime.resolution, AgentRuntimeResolution::Mock);
+        assert_eq!(agent.agent_runner().expect("runner").backend_name(), "mock");
+    }
+
     #[test]
     fn agent_command_accepts_command_tokens() {
         let toml = r#"
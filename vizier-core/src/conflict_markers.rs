@@ -0,0 +1,214 @@
+/// One conflicted region left by git between `<<<<<<<` and `>>>>>>>` markers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictHunk {
+    pub ours_label: String,
+    pub theirs_label: String,
+    pub ours: String,
+    /// Merge-base side, present when the file was written with `merge.conflictStyle = diff3`.
+    pub base: Option<String>,
+    pub theirs: String,
+}
+
+impl ConflictHunk {
+    /// The hunk as git wrote it, markers included; used as the starting point for hand edits.
+    pub fn render_markers(&self) -> String {
+        let mut text = format!("<<<<<<< {}\n{}", self.ours_label, self.ours);
+        if let Some(base) = self.base.as_ref() {
+            text.push_str(&format!("||||||| base\n{base}"));
+        }
+        text.push_str(&format!(
+            "=======\n{}>>>>>>> {}\n",
+            self.theirs, self.theirs_label
+        ));
+        text
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConflictSegment {
+    Text(String),
+    Hunk(ConflictHunk),
+}
+
+/// How the operator settled one [`ConflictHunk`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HunkResolution {
+    Ours,
+    Theirs,
+    /// Ours followed by theirs.
+    Both,
+    /// Replacement text written by hand.
+    Edited(String),
+}
+
+/// A conflicted file split into clean text and conflict hunks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictedFile {
+    pub segments: Vec<ConflictSegment>,
+}
+
+impl ConflictedFile {
+    /// Splits `contents` at git conflict markers. Unterminated markers are an error so a
+    /// half-parsed file is never written back.
+    pub fn parse(contents: &str) -> Result<Self, String> {
+        enum Side {
+            Ours,
+            Base,
+            Theirs,
+        }
+
+        let mut segments = Vec::new();
+        let mut text = String::new();
+        let mut open: Option<(Side, ConflictHunk)> = None;
+        for (index, line) in contents.split_inclusive('\n').enumerate() {
+            let bare = line.trim_end_matches(['\n', '\r']);
+            match open.as_mut() {
+                None => {
+                    if let Some(label) = marker_label(bare, "<<<<<<<") {
+                        if !text.is_empty() {
+                            segments.push(ConflictSegment::Text(std::mem::take(&mut text)));
+                        }
+                        open = Some((
+                            Side::Ours,
+                            ConflictHunk {
+                                ours_label: label,
+                                theirs_label: String::new(),
+                                ours: String::new(),
+                                base: None,
+                                theirs: String::new(),
+                            },
+                        ));
+                    } else {
+                        text.push_str(line);
+                    }
+                }
+                Some((side, hunk)) => {
+                    if marker_label(bare, "|||||||").is_some() && matches!(side, Side::Ours) {
+                        *side = Side::Base;
+                        hunk.base = Some(String::new());
+                    } else if bare == "=======" && !matches!(side, Side::Theirs) {
+                        *side = Side::Theirs;
+                    } else if let Some(label) = marker_label(bare, ">>>>>>>")
+                        && matches!(side, Side::Theirs)
+                    {
+                        hunk.theirs_label = label;
+                        let (_, hunk) = open.take().expect("open hunk");
+                        segments.push(ConflictSegment::Hunk(hunk));
+                    } else if bare.starts_with("<<<<<<<") {
+                        return Err(format!("line {}: nested conflict marker", index + 1));
+                    } else {
+                        match side {
+                            Side::Ours => hunk.ours.push_str(line),
+                            Side::Base => hunk.base.get_or_insert_default().push_str(line),
+                            Side::Theirs => hunk.theirs.push_str(line),
+                        }
+                    }
+                }
+            }
+        }
+        if open.is_some() {
+            return Err("unterminated conflict marker".to_string());
+        }
+        if !text.is_empty() {
+            segments.push(ConflictSegment::Text(text));
+        }
+        Ok(Self { segments })
+    }
+
+    pub fn hunks(&self) -> impl Iterator<Item = &ConflictHunk> {
+        self.segments.iter().filter_map(|segment| match segment {
+            ConflictSegment::Hunk(hunk) => Some(hunk),
+            ConflictSegment::Text(_) => None,
+        })
+    }
+
+    /// Rebuilds the file with each hunk replaced by its resolution, in order. There must be
+    /// exactly one resolution per hunk.
+    pub fn resolve(&self, resolutions: &[HunkResolution]) -> Result<String, String> {
+        let hunk_count = self.hunks().count();
+        if resolutions.len() != hunk_count {
+            return Err(format!(
+                "{} resolution(s) given for {hunk_count} conflict hunk(s)",
+                resolutions.len()
+            ));
+        }
+        let mut resolutions = resolutions.iter();
+        let mut output = String::new();
+        for segment in &self.segments {
+            match segment {
+                ConflictSegment::Text(text) => output.push_str(text),
+                ConflictSegment::Hunk(hunk) => match resolutions.next().expect("resolution") {
+                    HunkResolution::Ours => output.push_str(&hunk.ours),
+                    HunkResolution::Theirs => output.push_str(&hunk.theirs),
+                    HunkResolution::Both => {
+                        output.push_str(&hunk.ours);
+                        output.push_str(&hunk.theirs);
+                    }
+                    HunkResolution::Edited(text) => output.push_str(text),
+                },
+            }
+        }
+        Ok(output)
+    }
+}
+
+fn marker_label(line: &str, marker: &str) -> Option<String> {
+    let rest = line.strip_prefix(marker)?;
+    if rest.is_empty() {
+        return Some(String::new());
+    }
+    rest.strip_prefix(' ').map(|label| label.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFLICTED: &str = "header\n<<<<<<< HEAD\nours one\n||||||| base\nbase one\n=======\ntheirs one\n>>>>>>> draft/alpha\nmiddle\n<<<<<<< HEAD\nours two\n=======\ntheirs two\n>>>>>>> draft/alpha\n";
+
+    #[test]
+    fn parse_splits_text_and_hunks_with_labels() {
+        let file = ConflictedFile::parse(CONFLICTED).expect("parse");
+        let hunks = file.hunks().collect::<Vec<_>>();
+        assert_eq!(hunks.len(), 2);
+        assert_eq!(hunks[0].ours_label, "HEAD");
+        assert_eq!(hunks[0].theirs_label, "draft/alpha");
+        assert_eq!(hunks[0].ours, "ours one\n");
+        assert_eq!(hunks[0].base.as_deref(), Some("base one\n"));
+        assert_eq!(hunks[0].theirs, "theirs one\n");
+        assert_eq!(hunks[1].base, None);
+        assert_eq!(
+            hunks[1].render_markers(),
+            "<<<<<<< HEAD\nours two\n=======\ntheirs two\n>>>>>>> draft/alpha\n"
+        );
+    }
+
+    #[test]
+    fn resolve_applies_one_choice_per_hunk() {
+        let file = ConflictedFile::parse(CONFLICTED).expect("parse");
+        let resolved = file
+            .resolve(&[HunkResolution::Theirs, HunkResolution::Both])
+            .expect("resolve");
+        assert_eq!(
+            resolved,
+            "header\ntheirs one\nmiddle\nours two\ntheirs two\n"
+        );
+        let edited = file
+            .resolve(&[
+                HunkResolution::Ours,
+                HunkResolution::Edited("merged two\n".to_string()),
+            ])
+            .expect("resolve edited");
+        assert_eq!(edited, "header\nours one\nmiddle\nmerged two\n");
+
+        file.resolve(&[HunkResolution::Ours])
+            .expect_err("one resolution per hunk");
+    }
+
+    #[test]
+    fn parse_rejects_unterminated_markers() {
+        let err = ConflictedFile::parse("<<<<<<< HEAD\nours\n=======\ntheirs\n")
+            .expect_err("unterminated");
+        assert!(err.contains("unterminated"), "{err}");
+    }
+}
@@ -0,0 +1,308 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use ignore::WalkBuilder;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::atomic_file::write_atomic;
+use crate::tree;
+
+pub const CONTEXT_CACHE_DIR: &str = crate::temp_registry::CONTEXT_CACHE_DIR;
+const MANIFEST_FILE: &str = "manifest.json";
+const CHUNKS_DIR: &str = "chunks";
+/// Bumped whenever chunk rendering changes so stale chunks are never reused.
+const CACHE_VERSION: u32 = 1;
+
+/// Size, modification time, and content hash of a file as last read. A file whose size and
+/// mtime still match is not re-read; one that was touched but not changed keeps its hash.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileStamp {
+    pub size: u64,
+    pub modified_ns: u64,
+    pub hash: String,
+}
+
+/// A prepared chunk: `digest` is the hash of everything it was rendered from; `covered` counts
+/// the source bytes (documents) or paths (file-tree chunks) it stands for.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChunkEntry {
+    pub digest: String,
+    pub covered: usize,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct Manifest {
+    version: u32,
+    #[serde(default)]
+    files: BTreeMap<String, FileStamp>,
+    #[serde(default)]
+    chunks: BTreeMap<String, ChunkEntry>,
+}
+
+/// How many chunks a build reused versus regenerated.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct CacheStats {
+    pub reused: usize,
+    pub regenerated: usize,
+}
+
+/// A chunk as handed back to a context builder.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PreparedChunk {
+    pub text: String,
+    pub covered: usize,
+    pub cached: bool,
+}
+
+/// Content-addressed cache of prepared agent context under `.vizier/tmp/context-cache/`.
+///
+/// The manifest is written after every regenerated chunk, so an interrupted build resumes from
+/// the chunks it already finished instead of starting over.
+pub struct ContextCache {
+    dir: PathBuf,
+    manifest: Manifest,
+    stats: CacheStats,
+}
+
+impl ContextCache {
+    /// Opens the cache for `project_root`; a missing, unreadable, or outdated manifest starts empty.
+    pub fn open(project_root: &Path) -> Self {
+        let dir = project_root.join(CONTEXT_CACHE_DIR);
+        let manifest = fs::read(dir.join(MANIFEST_FILE))
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<Manifest>(&bytes).ok())
+            .filter(|manifest| manifest.version == CACHE_VERSION)
+            .unwrap_or(Manifest {
+                version: CACHE_VERSION,
+                ..Manifest::default()
+            });
+        Self {
+            dir,
+            manifest,
+            stats: CacheStats::default(),
+        }
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+
+    /// Content hash of `path`, recorded under `key`. Returns the bytes as well when the file had
+    /// to be read, so callers never read it twice.
+    pub fn file_hash(&mut self, key: &str, path: &Path) -> io::Result<(String, Option<Vec<u8>>)> {
+        let metadata = fs::metadata(path)?;
+        let modified_ns = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map(|elapsed| elapsed.as_nanos() as u64)
+            .unwrap_or_default();
+        if let Some(stamp) = self.manifest.files.get(key)
+            && stamp.size == metadata.len()
+            && stamp.modified_ns == modified_ns
+            && modified_ns != 0
+        {
+            return Ok((stamp.hash.clone(), None));
+        }
+        let bytes = fs::read(path)?;
+        let hash = hash_bytes(&bytes);
+        self.manifest.files.insert(
+            key.to_string(),
+            FileStamp {
+                size: bytes.len() as u64,
+                modified_ns,
+                hash: hash.clone(),
+            },
+        );
+        Ok((hash, Some(bytes)))
+    }
+
+    /// The chunk stored under `key` when it was prepared from `digest`; otherwise renders it
+    /// again, stores it, and persists the manifest.
+    pub fn chunk(
+        &mut self,
+        key: &str,
+        digest: &str,
+        render: impl FnOnce() -> io::Result<(String, usize)>,
+    ) -> io::Result<PreparedChunk> {
+        let path = self.chunk_path(key);
+        if let Some(entry) = self.manifest.chunks.get(key)
+            && entry.digest == digest
+            && let Ok(text) = fs::read_to_string(&path)
+        {
+            self.stats.reused += 1;
+            return Ok(PreparedChunk {
+                text,
+                covered: entry.covered,
+                cached: true,
+            });
+        }
+
+        let (text, covered) = render()?;
+        fs::create_dir_all(self.dir.join(CHUNKS_DIR))?;
+        write_atomic(&path, text.as_bytes())?;
+        self.manifest.chunks.insert(
+            key.to_string(),
+            ChunkEntry {
+                digest: digest.to_string(),
+                covered,
+            },
+        );
+        self.stats.regenerated += 1;
+        self.save()?;
+        Ok(PreparedChunk {
+            text,
+            covered,
+            cached: false,
+        })
+    }
+
+    /// Drops chunks whose key fails `keep`, along with their files.
+    pub fn retain_chunks(&mut self, keep: impl Fn(&str) -> bool) {
+        let stale = self
+            .manifest
+            .chunks
+            .keys()
+            .filter(|key| !keep(key))
+            .cloned()
+            .collect::<Vec<_>>();
+        for key in stale {
+            let _ = fs::remove_file(self.chunk_path(&key));
+            self.manifest.chunks.remove(&key);
+        }
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let bytes = serde_json::to_vec(&self.manifest)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        write_atomic(&self.dir.join(MANIFEST_FILE), &bytes)
+    }
+
+    fn chunk_path(&self, key: &str) -> PathBuf {
+        self.dir
+            .join(CHUNKS_DIR)
+            .join(format!("{}.txt", hash_bytes(key.as_bytes())))
+    }
+}
+
+/// The repository file tree as rendered into `<fileTree>`, built one chunk per top-level entry.
+/// Only entries whose set of paths changed are re-rendered.
+pub fn file_tree(project_root: &Path) -> io::Result<(String, CacheStats)> {
+    let mut groups: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+    let walker = WalkBuilder::new(project_root)
+        .add_custom_ignore_filename("vizier.db")
+        .build();
+    for entry in walker.filter_map(Result::ok) {
+        let Ok(relative) = entry.path().strip_prefix(project_root) else {
+            continue;
+        };
+        if relative.as_os_str().is_empty() || relative.starts_with(CONTEXT_CACHE_DIR) {
+            continue;
+        }
+        let Some(first) = relative.components().next() else {
+            continue;
+        };
+        let first = first.as_os_str().to_string_lossy().into_owned();
+        groups.entry(first.clone()).or_default().push(
+            relative
+                .strip_prefix(&first)
+                .unwrap_or(relative)
+                .to_path_buf(),
+        );
+    }
+
+    let mut cache = ContextCache::open(project_root);
+    let name = project_root
+        .file_name()
+        .unwrap_or(project_root.as_os_str())
+        .to_string_lossy();
+    let mut rendered = format!("- {name}\n");
+    for (first, paths) in groups.iter_mut() {
+        paths.sort();
+        let mut hasher = Sha256::new();
+        for path in paths.iter() {
+            hasher.update(path.to_string_lossy().as_bytes());
+            hasher.update([0]);
+        }
+        let digest = format!("{:x}", hasher.finalize());
+        let chunk = cache.chunk(&format!("tree:{first}"), &digest, || {
+            let subtree = tree::tree_from_paths(project_root.join(first), paths);
+            Ok((tree::tree_to_string(&subtree, "  "), paths.len()))
+        })?;
+        rendered.push_str(&chunk.text);
+    }
+    cache.retain_chunks(|key| {
+        key.strip_prefix("tree:")
+            .is_none_or(|first| groups.contains_key(first))
+    });
+    cache.save()?;
+    Ok((rendered, cache.stats()))
+}
+
+fn hash_bytes(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_tree_rerenders_only_changed_top_level_entries() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let root = dir.path();
+        fs::create_dir_all(root.join("api/src")).expect("dirs");
+        fs::create_dir_all(root.join("web")).expect("dirs");
+        fs::write(root.join("api/src/lib.rs"), "fn main() {}\n").expect("write");
+        fs::write(root.join("web/index.ts"), "export {}\n").expect("write");
+        fs::write(root.join("README.md"), "# Repo\n").expect("write");
+
+        let (first, stats) = file_tree(root).expect("tree");
+        assert!(first.contains("  - api\n    - src\n      - lib.rs\n"));
+        assert!(first.contains("  - web\n    - index.ts\n"));
+        assert_eq!(stats.regenerated, 3);
+        assert_eq!(stats.reused, 0);
+
+        let (second, stats) = file_tree(root).expect("tree");
+        assert_eq!(second, first);
+        assert_eq!(stats.reused, 3);
+
+        fs::write(root.join("web/app.ts"), "export {}\n").expect("write");
+        let (third, stats) = file_tree(root).expect("tree");
+        assert!(third.contains("    - app.ts\n"));
+        assert_eq!(
+            stats,
+            CacheStats {
+                reused: 2,
+                regenerated: 1
+            }
+        );
+    }
+
+    #[test]
+    fn file_hash_skips_reads_for_unchanged_files() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("notes.md");
+        fs::write(&path, "one\n").expect("write");
+
+        let mut cache = ContextCache::open(dir.path());
+        let (hash, bytes) = cache.file_hash("notes.md", &path).expect("hash");
+        assert_eq!(bytes.as_deref(), Some(&b"one\n"[..]));
+        cache.save().expect("save");
+
+        let mut cache = ContextCache::open(dir.path());
+        let (again, bytes) = cache.file_hash("notes.md", &path).expect("hash");
+        assert_eq!(again, hash);
+        assert!(bytes.is_none());
+
+        fs::write(&path, "two, longer\n").expect("write");
+        let (changed, bytes) = cache.file_hash("notes.md", &path).expect("hash");
+        assert_ne!(changed, hash);
+        assert!(bytes.is_some());
+    }
+}
@@ -0,0 +1,136 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config::ApproveCostConfig;
+
+/// Characters per token used for estimates; close enough for English prose and code.
+pub const CHARS_PER_TOKEN: u64 = 4;
+
+/// Pre-run guess at what an agent run will cost, recorded on the run's jobs.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CostEstimate {
+    /// Estimated tokens of context the agent starts from (prompt, plan, narrative, `--context`).
+    pub context_tokens: u64,
+    pub model: String,
+    pub low_usd: f64,
+    pub high_usd: f64,
+}
+
+impl CostEstimate {
+    /// Agents re-read context across turns, so the range spans one pass with a short reply up
+    /// to several passes with a long one.
+    pub fn new(context_tokens: u64, model: impl Into<String>, rates: &ApproveCostConfig) -> Self {
+        Self {
+            context_tokens,
+            model: model.into(),
+            low_usd: usd(rates, context_tokens, context_tokens / 10),
+            high_usd: usd(rates, context_tokens * 4, context_tokens / 2),
+        }
+    }
+
+    pub fn range_label(&self) -> String {
+        format!("{}–{}", format_usd(self.low_usd), format_usd(self.high_usd))
+    }
+}
+
+/// What an agent run consumed, measured from the prompt it was sent and the text it returned.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CostActual {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub usd: f64,
+}
+
+impl CostActual {
+    pub fn measure(prompt: &str, response: &str, rates: &ApproveCostConfig) -> Self {
+        let input_tokens = estimate_tokens(prompt);
+        let output_tokens = estimate_tokens(response);
+        Self {
+            input_tokens,
+            output_tokens,
+            usd: usd(rates, input_tokens, output_tokens),
+        }
+    }
+}
+
+pub fn estimate_tokens(text: &str) -> u64 {
+    (text.chars().count() as u64).div_ceil(CHARS_PER_TOKEN)
+}
+
+/// Model named by the agent command (`--model X`, `--model=X`, `-m X`), else the agent label.
+pub fn model_label(command: &[String], fallback: &str) -> String {
+    let mut args = command.iter();
+    while let Some(arg) = args.next() {
+        if let Some(model) = arg.strip_prefix("--model=") {
+            return model.to_string();
+        }
+        if (arg == "--model" || arg == "-m")
+            && let Some(model) = args.next()
+        {
+            return model.clone();
+        }
+    }
+    fallback.to_string()
+}
+
+pub fn format_usd(value: f64) -> String {
+    if value > 0.0 && value < 0.01 {
+        "<$0.01".to_string()
+    } else {
+        format!("${value:.2}")
+    }
+}
+
+/// Parses a `--max-cost` value such as `0.50` or `$2`.
+pub fn parse_usd(raw: &str) -> Result<f64, String> {
+    let trimmed = raw.trim();
+    let digits = trimmed.strip_prefix('$').unwrap_or(trimmed);
+    match digits.parse::<f64>() {
+        Ok(value) if value.is_finite() && value >= 0.0 => Ok(value),
+        _ => Err(format!(
+            "invalid cost `{raw}`; expected a dollar amount like 0.50"
+        )),
+    }
+}
+
+fn usd(rates: &ApproveCostConfig, input_tokens: u64, output_tokens: u64) -> f64 {
+    let micro = input_tokens as f64 * rates.input_micro_usd_per_mtok as f64
+        + output_tokens as f64 * rates.output_micro_usd_per_mtok as f64;
+    micro / 1_000_000.0 / 1_000_000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn estimate_range_scales_with_context_and_rates() {
+        let rates = ApproveCostConfig::default();
+        let estimate = CostEstimate::new(100_000, "sonnet", &rates);
+        // 100k in + 10k out at $3/$15 per Mtok, then 400k in + 50k out.
+        assert!((estimate.low_usd - 0.45).abs() < 1e-9);
+        assert!((estimate.high_usd - 1.95).abs() < 1e-9);
+        assert_eq!(estimate.range_label(), "$0.45–$1.95");
+    }
+
+    #[test]
+    fn model_label_reads_model_flags_from_the_agent_command() {
+        let command = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            model_label(&command(&["claude", "--model", "opus"]), "claude"),
+            "opus"
+        );
+        assert_eq!(
+            model_label(&command(&["codex", "exec", "--model=gpt-5"]), "codex"),
+            "gpt-5"
+        );
+        assert_eq!(model_label(&command(&["gemini"]), "gemini"), "gemini");
+    }
+
+    #[test]
+    fn parse_usd_accepts_dollar_prefix_and_rejects_negatives() {
+        assert_eq!(parse_usd("$2"), Ok(2.0));
+        assert_eq!(parse_usd("0.5"), Ok(0.5));
+        assert!(parse_usd("-1").is_err());
+        assert!(parse_usd("lots").is_err());
+    }
+}
@@ -0,0 +1,38 @@
+use std::path::Path;
+
+use crate::config::WorkflowDiffContextConfig;
+use crate::never_send::{NeverSend, record_withheld};
+use crate::vcs;
+
+pub use vizier_kernel::diff_context::*;
+
+/// Resolves a `{{diff:<spec>}}` placeholder: computes the diff for `spec` (anything
+/// `vcs::get_diff` accepts; empty means HEAD against the working tree), drops files matching
+/// `[context] never_send`, and shrinks it through [`build_diff_context`] when it exceeds the
+/// configured budget.
+pub fn diff_context_for_spec<F>(
+    repo_path: &Path,
+    spec: &str,
+    config: &WorkflowDiffContextConfig,
+    summarize: F,
+) -> Result<DiffContext, git2::Error>
+where
+    F: FnMut(String) -> Result<String, String>,
+{
+    let spec = spec.trim();
+    let diff = vcs::get_diff(
+        &repo_path.to_string_lossy(),
+        (!spec.is_empty()).then_some(spec),
+        None,
+    )?;
+    let (diff, withheld) = NeverSend::from_config(repo_path).filter_diff(&diff);
+    record_withheld("diff", &withheld);
+    let mut context = build_diff_context(&diff, config, summarize);
+    if !withheld.is_empty() {
+        context.warnings.push(format!(
+            "withheld {} file(s) matching [context] never_send from diff `{spec}`",
+            withheld.len()
+        ));
+    }
+    Ok(context)
+}
@@ -1,7 +1,10 @@
 use lazy_static::lazy_static;
 use serde_json::Value;
 use std::error::Error;
-use std::io::IsTerminal;
+use std::io::{IsTerminal, Write};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc::{Receiver, Sender, channel};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -20,11 +23,126 @@ pub enum LogLevel {
     Debug,
 }
 
+/// Areas whose stderr chatter can be filtered independently of the global verbosity.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Subsystem {
+    /// Agent streaming progress and lifecycle.
+    Agent,
+    /// Merge/approve gates and review checks.
+    Gate,
+    /// Git plumbing: diffs, staging, pushes, worktrees.
+    Git,
+    /// Job scheduling, workflow routing, and cleanup.
+    Scheduler,
+    /// Config loading and validation.
+    Config,
+}
+
+impl Subsystem {
+    pub const ALL: [Subsystem; 5] = [
+        Subsystem::Agent,
+        Subsystem::Gate,
+        Subsystem::Git,
+        Subsystem::Scheduler,
+        Subsystem::Config,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Subsystem::Agent => "agent",
+            Subsystem::Gate => "gate",
+            Subsystem::Git => "git",
+            Subsystem::Scheduler => "scheduler",
+            Subsystem::Config => "config",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|subsystem| subsystem.as_str().eq_ignore_ascii_case(value.trim()))
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Per-subsystem verbosity overrides parsed from `agent=debug,gate=info,git=warn`. Subsystems
+/// without an entry follow the global verbosity.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LogFilters {
+    levels: [Option<Verbosity>; 5],
+}
+
+impl LogFilters {
+    /// Parses comma-separated `subsystem=level` pairs. Levels are `off`/`error`, `warn`, `info`,
+    /// and `debug`; `*=level` sets every subsystem.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut filters = Self::default();
+        for entry in spec
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+        {
+            let Some((name, level)) = entry.split_once('=') else {
+                return Err(format!("log filter `{entry}` must be `subsystem=level`"));
+            };
+            let level = match level.trim().to_ascii_lowercase().as_str() {
+                "off" | "quiet" | "error" => Verbosity::Quiet,
+                "warn" | "normal" => Verbosity::Normal,
+                "info" => Verbosity::Info,
+                "debug" => Verbosity::Debug,
+                other => {
+                    return Err(format!(
+                        "unknown log level `{other}` (expected off, error, warn, info, or debug)"
+                    ));
+                }
+            };
+            if name.trim() == "*" {
+                filters.levels = [Some(level); 5];
+                continue;
+            }
+            let Some(subsystem) = Subsystem::parse(name) else {
+                let known = Subsystem::ALL.map(Subsystem::as_str).join(", ");
+                return Err(format!(
+                    "unknown log subsystem `{}` (expected one of: {known})",
+                    name.trim()
+                ));
+            };
+            filters.levels[subsystem.index()] = Some(level);
+        }
+        Ok(filters)
+    }
+
+    pub fn level_for(&self, subsystem: Subsystem) -> Option<Verbosity> {
+        self.levels[subsystem.index()]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.levels.iter().all(Option::is_none)
+    }
+
+    /// Entries from `self` win; `fallback` fills the subsystems `self` leaves unset.
+    pub fn or(self, fallback: LogFilters) -> LogFilters {
+        let mut merged = self;
+        for (level, fallback) in merged.levels.iter_mut().zip(fallback.levels) {
+            if level.is_none() {
+                *level = fallback;
+            }
+        }
+        merged
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct DisplayConfig {
     pub verbosity: Verbosity,
+    pub log_filters: LogFilters,
     pub stdout_is_tty: bool,
     pub stderr_is_tty: bool,
+    /// Whether ANSI control sequences (spinners, line rewrites) may be written to the terminal.
+    pub ansi: bool,
 }
 
 impl Default for DisplayConfig {
@@ -34,8 +152,10 @@ impl Default for DisplayConfig {
 
         Self {
             verbosity: Verbosity::Normal,
+            log_filters: LogFilters::default(),
             stdout_is_tty,
             stderr_is_tty,
+            ansi: true,
         }
     }
 }
@@ -45,6 +165,15 @@ lazy_static! {
         std::sync::RwLock::new(DisplayConfig::default());
 }
 
+impl DisplayConfig {
+    /// Effective verbosity for `subsystem`: its log filter when set, else the global verbosity.
+    pub fn verbosity_for(&self, subsystem: Subsystem) -> Verbosity {
+        self.log_filters
+            .level_for(subsystem)
+            .unwrap_or(self.verbosity)
+    }
+}
+
 pub fn set_display_config(config: DisplayConfig) {
     if let Ok(mut cfg) = CONFIG.write() {
         *cfg = config;
@@ -73,6 +202,14 @@ pub fn emit(level: LogLevel, message: impl AsRef<str>) {
     }
 }
 
+/// Like [`emit`], but gated by `subsystem`'s log filter instead of the global verbosity.
+pub fn log(subsystem: Subsystem, level: LogLevel, message: impl AsRef<str>) {
+    let cfg = get_display_config();
+    if level == LogLevel::Error || cfg.verbosity_for(subsystem).allows(level) {
+        eprintln!("{}", message.as_ref());
+    }
+}
+
 pub fn warn(message: impl AsRef<str>) {
     emit(LogLevel::Warn, message);
 }
@@ -177,10 +314,11 @@ struct DisplayRuntime {
 
 impl DisplayRuntime {
     fn from_config(cfg: DisplayConfig) -> Self {
+        let verbosity = cfg.verbosity_for(Subsystem::Agent);
         Self {
-            verbosity: cfg.verbosity,
-            log_events: !matches!(cfg.verbosity, Verbosity::Quiet),
-            info_once: matches!(cfg.verbosity, Verbosity::Info),
+            verbosity,
+            log_events: !matches!(verbosity, Verbosity::Quiet),
+            info_once: matches!(verbosity, Verbosity::Info),
         }
     }
 }
@@ -192,9 +330,9 @@ async fn render_line_runtime(mut rx: Receiver<Status>, runtime: DisplayRuntime)
         match status {
             Status::Working(msg) => {
                 if runtime.verbosity == Verbosity::Debug {
-                    debug(format!("Working: {}", msg));
+                    eprintln!("Working: {}", msg);
                 } else if runtime.info_once && !printed_once {
-                    info(format!("Working: {}", msg));
+                    eprintln!("Working: {}", msg);
                     printed_once = true;
                 }
             }
@@ -330,12 +468,13 @@ pub fn value_to_string(value: &Value) -> Option<String> {
 }
 
 pub fn format_number(value: usize) -> String {
+    let separator = crate::i18n::thousands_separator(crate::config::output_language().as_deref());
     let digits: Vec<char> = value.to_string().chars().collect();
     let mut formatted = String::with_capacity(digits.len() + digits.len() / 3);
 
     for (idx, ch) in digits.iter().rev().enumerate() {
         if idx > 0 && idx % 3 == 0 {
-            formatted.push(',');
+            formatted.push(separator);
         }
         formatted.push(*ch);
     }
@@ -343,10 +482,36 @@ pub fn format_number(value: usize) -> String {
     formatted.chars().rev().collect()
 }
 
+/// Binary-unit size for listings: `512 B`, `4.0 KiB`, `1.3 MiB`.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+/// Renders `Label: value` rows with aligned colons, skipping empty values. Labels, and the
+/// `Outcome` value, are translated for the configured `[output] language`.
 pub fn format_label_value_block(rows: &[(String, String)], indent: usize) -> String {
+    let language = crate::config::output_language();
     let filtered: Vec<_> = rows
         .iter()
         .filter(|(_, value)| !value.trim().is_empty())
+        .map(|(label, value)| {
+            let value = if label == "Outcome" {
+                crate::i18n::translate(language.as_deref(), value)
+            } else {
+                std::borrow::Cow::Borrowed(value.as_str())
+            };
+            (crate::i18n::translate(language.as_deref(), label), value)
+        })
         .collect();
     if filtered.is_empty() {
         return String::new();
@@ -354,7 +519,7 @@ pub fn format_label_value_block(rows: &[(String, String)], indent: usize) -> Str
 
     let width = filtered
         .iter()
-        .map(|(label, _)| label.len())
+        .map(|(label, _)| label.chars().count())
         .max()
         .unwrap_or(0);
     let padding = " ".repeat(indent);
@@ -366,6 +531,253 @@ pub fn format_label_value_block(rows: &[(String, String)], indent: usize) -> Str
         .join("\n")
 }
 
+/// `Outcome: <message>` summary line, translated for the configured `[output] language`.
+pub fn outcome_line(message: &str) -> String {
+    format!(
+        "{}: {}",
+        crate::i18n::tr("Outcome"),
+        crate::i18n::tr(message)
+    )
+}
+
+/// Well-known phase names so callers and the summary table agree on spelling.
+pub const PHASE_WORKTREE_SETUP: &str = "worktree setup";
+pub const PHASE_AGENT_RUN: &str = "agent run";
+pub const PHASE_GATE: &str = "gate";
+pub const PHASE_COMMIT: &str = "commit";
+
+const SPINNER_FRAMES: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+const SPINNER_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How long one named phase took; `label` narrows it down (e.g. the workflow node id).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PhaseTiming {
+    pub name: String,
+    pub label: Option<String>,
+    pub duration_ms: u64,
+}
+
+struct ActivePhase {
+    id: u64,
+    name: String,
+    label: Option<String>,
+    started: Instant,
+}
+
+#[derive(Default)]
+struct PhaseRegistry {
+    next_id: u64,
+    active: Vec<ActivePhase>,
+    finished: Vec<PhaseTiming>,
+    spinner_running: bool,
+}
+
+static PHASES: Mutex<PhaseRegistry> = Mutex::new(PhaseRegistry {
+    next_id: 0,
+    active: Vec::new(),
+    finished: Vec::new(),
+    spinner_running: false,
+});
+static SPINNER_DRAWN: AtomicBool = AtomicBool::new(false);
+
+/// A running phase. Dropping it (or calling `finish`) records its duration for the summary
+/// table and the command's outcome file.
+pub struct Phase {
+    id: u64,
+    done: bool,
+}
+
+impl Phase {
+    pub fn finish(mut self) -> Duration {
+        self.complete(None)
+    }
+
+    /// Ends the phase but records `elapsed` instead of the locally observed time, for phases
+    /// that really ran elsewhere (e.g. a workflow node in its own job process).
+    pub fn finish_with(mut self, elapsed: Duration) -> Duration {
+        self.complete(Some(elapsed))
+    }
+
+    fn complete(&mut self, elapsed: Option<Duration>) -> Duration {
+        if self.done {
+            return Duration::ZERO;
+        }
+        self.done = true;
+        let Ok(mut registry) = PHASES.lock() else {
+            return Duration::ZERO;
+        };
+        let Some(index) = registry.active.iter().position(|phase| phase.id == self.id) else {
+            return Duration::ZERO;
+        };
+        let phase = registry.active.remove(index);
+        let elapsed = elapsed.unwrap_or_else(|| phase.started.elapsed());
+        registry.finished.push(PhaseTiming {
+            name: phase.name,
+            label: phase.label,
+            duration_ms: u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX),
+        });
+        elapsed
+    }
+}
+
+impl Drop for Phase {
+    fn drop(&mut self) {
+        self.complete(None);
+    }
+}
+
+/// Starts timing `name`. On an interactive stderr (and unless `--quiet`/`--no-ansi`), a spinner
+/// shows every active phase with its elapsed time until the phase finishes.
+pub fn start_phase(name: impl Into<String>, label: Option<String>) -> Phase {
+    let Ok(mut registry) = PHASES.lock() else {
+        return Phase { id: 0, done: true };
+    };
+    registry.next_id += 1;
+    let id = registry.next_id;
+    registry.active.push(ActivePhase {
+        id,
+        name: name.into(),
+        label,
+        started: Instant::now(),
+    });
+    if spinner_enabled(get_display_config()) && !registry.spinner_running {
+        registry.spinner_running = true;
+        std::thread::spawn(run_spinner);
+    }
+    Phase { id, done: false }
+}
+
+/// Phases finished so far in this process, in completion order.
+pub fn phase_timings() -> Vec<PhaseTiming> {
+    PHASES
+        .lock()
+        .map(|registry| registry.finished.clone())
+        .unwrap_or_default()
+}
+
+/// Erases the spinner line so regular output does not land in the middle of it; the spinner
+/// redraws itself on its next tick.
+pub fn clear_phase_line() {
+    if SPINNER_DRAWN.swap(false, Ordering::SeqCst) {
+        let mut stderr = std::io::stderr().lock();
+        let _ = write!(stderr, "\r\x1b[2K");
+        let _ = stderr.flush();
+    }
+}
+
+fn spinner_enabled(cfg: DisplayConfig) -> bool {
+    cfg.stderr_is_tty && cfg.ansi && !matches!(cfg.verbosity, Verbosity::Quiet)
+}
+
+fn run_spinner() {
+    let mut frame = 0usize;
+    loop {
+        let line = {
+            let Ok(mut registry) = PHASES.lock() else {
+                return;
+            };
+            if registry.active.is_empty() {
+                registry.spinner_running = false;
+                None
+            } else {
+                Some(
+                    registry
+                        .active
+                        .iter()
+                        .map(|phase| {
+                            let name = match phase.label.as_deref() {
+                                Some(label) => format!("{} ({label})", phase.name),
+                                None => phase.name.clone(),
+                            };
+                            format!("{name} {}", format_duration(phase.started.elapsed()))
+                        })
+                        .collect::<Vec<_>>()
+                        .join(" · "),
+                )
+            }
+        };
+
+        let Some(line) = line else {
+            clear_phase_line();
+            return;
+        };
+        let mut stderr = std::io::stderr().lock();
+        let _ = write!(
+            stderr,
+            "\r\x1b[2K{} {line}",
+            SPINNER_FRAMES[frame % SPINNER_FRAMES.len()]
+        );
+        let _ = stderr.flush();
+        drop(stderr);
+        SPINNER_DRAWN.store(true, Ordering::SeqCst);
+        frame += 1;
+        std::thread::sleep(SPINNER_INTERVAL);
+    }
+}
+
+/// Compact elapsed time: `850ms`, `4.2s`, `3m 07s`, `1h 02m`.
+pub fn format_duration(duration: Duration) -> String {
+    let millis = duration.as_millis();
+    if millis < 1_000 {
+        return format!("{millis}ms");
+    }
+    let secs = duration.as_secs();
+    if secs < 60 {
+        return format!("{:.1}s", duration.as_secs_f64());
+    }
+    if secs < 3_600 {
+        return format!("{}m {:02}s", secs / 60, secs % 60);
+    }
+    format!("{}h {:02}m", secs / 3_600, (secs % 3_600) / 60)
+}
+
+/// Renders per-phase totals in first-seen order, followed by an overall total.
+pub fn render_phase_table(timings: &[PhaseTiming]) -> String {
+    if timings.is_empty() {
+        return String::new();
+    }
+
+    let mut totals: Vec<(String, u64, usize)> = Vec::new();
+    for timing in timings {
+        match totals.iter_mut().find(|(name, _, _)| name == &timing.name) {
+            Some(entry) => {
+                entry.1 += timing.duration_ms;
+                entry.2 += 1;
+            }
+            None => totals.push((timing.name.clone(), timing.duration_ms, 1)),
+        }
+    }
+    let overall = totals.iter().map(|(_, ms, _)| *ms).sum::<u64>();
+
+    let mut rows = totals
+        .into_iter()
+        .map(|(name, ms, count)| {
+            let mut value = format_duration(Duration::from_millis(ms));
+            if count > 1 {
+                value.push_str(&format!(" ({count}×)"));
+            }
+            (name, value)
+        })
+        .collect::<Vec<_>>();
+    rows.push((
+        "total".to_string(),
+        format_duration(Duration::from_millis(overall)),
+    ));
+
+    format!("Phase timings:\n{}", format_label_value_block(&rows, 2))
+}
+
+/// Prints the phase table to stderr once a command is done; silent under `--quiet` or when no
+/// phases ran.
+pub fn emit_phase_summary() {
+    let timings = phase_timings();
+    if timings.is_empty() || matches!(get_display_config().verbosity, Verbosity::Quiet) {
+        return;
+    }
+    clear_phase_line();
+    eprintln!("{}", render_phase_table(&timings));
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -445,4 +857,90 @@ mod tests {
         assert!(lines[1].contains("timestamp=2024-01-01T00:00:00Z"));
         assert!(lines[2].contains("event={\"type\":\"sample\"}"));
     }
+
+    #[test]
+    fn log_filters_override_global_verbosity_per_subsystem() {
+        let filters = LogFilters::parse("agent=debug, gate=info,git=off").expect("parse filters");
+        let cfg = DisplayConfig {
+            verbosity: Verbosity::Normal,
+            log_filters: filters,
+            stdout_is_tty: false,
+            stderr_is_tty: false,
+            ansi: false,
+        };
+        assert_eq!(cfg.verbosity_for(Subsystem::Agent), Verbosity::Debug);
+        assert_eq!(cfg.verbosity_for(Subsystem::Gate), Verbosity::Info);
+        assert_eq!(cfg.verbosity_for(Subsystem::Git), Verbosity::Quiet);
+        assert_eq!(cfg.verbosity_for(Subsystem::Scheduler), Verbosity::Normal);
+
+        let merged = LogFilters::parse("git=warn")
+            .expect("parse cli filters")
+            .or(filters);
+        assert_eq!(merged.level_for(Subsystem::Git), Some(Verbosity::Normal));
+        assert_eq!(merged.level_for(Subsystem::Agent), Some(Verbosity::Debug));
+        assert_eq!(
+            LogFilters::parse("*=info")
+                .expect("wildcard")
+                .level_for(Subsystem::Config),
+            Some(Verbosity::Info)
+        );
+
+        assert!(LogFilters::parse("").expect("empty").is_empty());
+        assert!(LogFilters::parse("network=debug").is_err());
+        assert!(LogFilters::parse("agent=loud").is_err());
+        assert!(LogFilters::parse("agent").is_err());
+    }
+
+    #[test]
+    fn formats_byte_sizes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(4096), "4.0 KiB");
+        assert_eq!(format_bytes(1_363_149), "1.3 MiB");
+    }
+
+    #[test]
+    fn formats_durations_compactly() {
+        assert_eq!(format_duration(Duration::from_millis(850)), "850ms");
+        assert_eq!(format_duration(Duration::from_millis(4_250)), "4.2s");
+        assert_eq!(format_duration(Duration::from_secs(187)), "3m 07s");
+        assert_eq!(format_duration(Duration::from_secs(3_720)), "1h 02m");
+    }
+
+    #[test]
+    fn phase_table_totals_repeated_phases() {
+        let timing = |name: &str, ms: u64| PhaseTiming {
+            name: name.to_string(),
+            label: None,
+            duration_ms: ms,
+        };
+        let table = render_phase_table(&[
+            timing(PHASE_WORKTREE_SETUP, 400),
+            timing(PHASE_AGENT_RUN, 30_000),
+            timing(PHASE_AGENT_RUN, 12_000),
+            timing(PHASE_COMMIT, 600),
+        ]);
+        let lines = table.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "Phase timings:");
+        assert!(lines[1].contains("worktree setup") && lines[1].ends_with("400ms"));
+        assert!(lines[2].contains("agent run") && lines[2].ends_with("42.0s (2×)"));
+        assert!(lines[4].contains("total") && lines[4].ends_with("43.0s"));
+        assert!(render_phase_table(&[]).is_empty());
+    }
+
+    #[test]
+    fn finished_phases_are_recorded() {
+        let phase = start_phase("unit-test phase", Some("node-a".to_string()));
+        phase.finish();
+        drop(start_phase("unit-test dropped", None));
+        let timings = phase_timings();
+        assert!(
+            timings.iter().any(|timing| timing.name == "unit-test phase"
+                && timing.label.as_deref() == Some("node-a"))
+        );
+        assert!(
+            timings
+                .iter()
+                .any(|timing| timing.name == "unit-test dropped")
+        );
+    }
 }
@@ -0,0 +1,27 @@
+use std::path::Path;
+
+use crate::vcs;
+
+pub use vizier_kernel::drift::*;
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Measures how far `branch` has drifted from `target` in the repository at `repo_path`.
+pub fn measure_plan_drift<P: AsRef<Path>>(
+    repo_path: P,
+    branch: &str,
+    target: &str,
+) -> Result<PlanDrift, git2::Error> {
+    let divergence = vcs::branch_divergence_in(repo_path, branch, target)?;
+    let now = chrono::Utc::now().timestamp();
+    let age_days = divergence
+        .oldest_unique_commit_time
+        .map(|seconds| ((now - seconds).max(0) / SECONDS_PER_DAY) as u64);
+    Ok(PlanDrift {
+        branch: branch.to_string(),
+        target: target.to_string(),
+        commits_ahead: divergence.ahead,
+        commits_behind: divergence.behind,
+        age_days,
+    })
+}
@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::fs::OpenOptions;
 use std::io::Write;
 use std::path::{Path, PathBuf};
@@ -7,7 +7,8 @@ use std::sync::Mutex;
 use crate::tools;
 use fuzzy_matcher::FuzzyMatcher;
 use fuzzy_matcher::skim::SkimMatcherV2;
-use git2::{Repository, StatusOptions, StatusShow};
+use git2::build::CheckoutBuilder;
+use git2::{DiffFormat, DiffOptions, Repository, Status, StatusOptions, StatusShow};
 use lazy_static::lazy_static;
 
 lazy_static! {
@@ -126,6 +127,87 @@ impl FileTracker {
         Ok(())
     }
 
+    /// Captures every staged, unstaged, and untracked change against `HEAD` as a patch, then
+    /// restores those paths to `HEAD` so nothing is left staged or dirty. Vizier runtime
+    /// directories are neither captured nor restored. `None` when there is nothing to capture.
+    pub fn capture_patch(repo_root: &Path) -> Result<Option<CapturedPatch>, git2::Error> {
+        let repo = Repository::open(repo_root)?;
+        let mut opts = StatusOptions::new();
+        opts.include_untracked(true)
+            .include_ignored(false)
+            .recurse_untracked_dirs(true)
+            .exclude_submodules(true)
+            .show(StatusShow::IndexAndWorkdir);
+        let mut paths = Vec::new();
+        let mut created = Vec::new();
+        for entry in repo.statuses(Some(&mut opts))?.iter() {
+            let Some(path) = entry
+                .path()
+                .map(|path| Self::normalize_repo_path(Path::new(path)))
+            else {
+                continue;
+            };
+            if RUNTIME_DIRS.iter().any(|dir| path.starts_with(dir)) {
+                continue;
+            }
+            if entry
+                .status()
+                .intersects(Status::WT_NEW | Status::INDEX_NEW)
+            {
+                created.push(path.clone());
+            }
+            paths.push(path);
+        }
+        if paths.is_empty() {
+            return Ok(None);
+        }
+
+        let head = repo.head()?.peel_to_commit()?;
+        let mut diff_opts = DiffOptions::new();
+        diff_opts
+            .include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .show_untracked_content(true)
+            .show_binary(true)
+            .ignore_submodules(true)
+            .disable_pathspec_match(true);
+        for path in &paths {
+            diff_opts.pathspec(path);
+        }
+        let diff =
+            repo.diff_tree_to_workdir_with_index(Some(&head.tree()?), Some(&mut diff_opts))?;
+        let mut patch = Vec::new();
+        diff.print(DiffFormat::Patch, |_, _, line| {
+            if matches!(line.origin(), '+' | '-' | ' ') {
+                patch.push(line.origin() as u8);
+            }
+            patch.extend_from_slice(line.content());
+            true
+        })?;
+
+        repo.reset_default(Some(head.as_object()), paths.iter())?;
+        let mut checkout = CheckoutBuilder::new();
+        checkout.force().disable_pathspec_match(true);
+        for path in &paths {
+            checkout.path(path);
+        }
+        repo.checkout_head(Some(&mut checkout))?;
+        for path in &created {
+            let abs = repo_root.join(path);
+            if let Err(err) = std::fs::remove_file(&abs)
+                && err.kind() != std::io::ErrorKind::NotFound
+            {
+                return Err(git2::Error::from_str(&format!(
+                    "failed to remove {}: {err}",
+                    abs.display()
+                )));
+            }
+        }
+
+        Self::clear_tracked(&paths);
+        Ok(Some(CapturedPatch { patch, paths }))
+    }
+
     fn collect_vizier_changes(repo_root: &Path) -> Result<Vec<String>, git2::Error> {
         let repo = Repository::open(repo_root)?;
         let mut opts = StatusOptions::new();
@@ -177,9 +259,142 @@ impl FileTracker {
     }
 }
 
-fn is_vizier_path(path: &str) -> bool {
+/// Vizier-owned runtime state that patch capture leaves alone.
+const RUNTIME_DIRS: [&str; 5] = [
+    ".vizier/jobs/",
+    ".vizier/sessions/",
+    ".vizier/state/",
+    ".vizier/tmp/",
+    ".vizier/tmp-worktrees/",
+];
+
+/// Changes taken out of a worktree by [`FileTracker::capture_patch`].
+#[derive(Clone, Debug)]
+pub struct CapturedPatch {
+    pub patch: Vec<u8>,
+    /// Repo-relative paths the patch covers.
+    pub paths: Vec<String>,
+}
+
+/// Narrative artifacts an agent run may edit. Changes to any other canonical narrative file are
+/// rolled back after the run, so a scoped request cannot rewrite unrelated threads or the snapshot.
+#[derive(Clone, Debug)]
+pub struct NarrativeScope {
+    allowed: Vec<String>,
+    baseline: BTreeMap<String, Vec<u8>>,
+}
+
+impl NarrativeScope {
+    /// Parses a comma- or space-separated target list such as `snapshot,threads/auth` into repo
+    /// paths (`snapshot`, `glossary`, `threads/<name>`, or any path under `narrative/`).
+    pub fn parse_targets(spec: &str) -> Result<Vec<String>, String> {
+        let mut paths = Vec::new();
+        for target in spec
+            .split(|ch: char| ch == ',' || ch.is_whitespace())
+            .map(str::trim)
+            .filter(|target| !target.is_empty())
+        {
+            let relative = target
+                .trim_start_matches("./")
+                .trim_start_matches(tools::VIZIER_DIR)
+                .trim_start_matches(tools::NARRATIVE_DIR);
+            let relative = match relative {
+                "snapshot" => tools::SNAPSHOT_FILE.to_string(),
+                "glossary" => tools::GLOSSARY_FILE.to_string(),
+                other if other.ends_with(".md") => other.to_string(),
+                other => format!("{other}.md"),
+            };
+            if relative
+                .split('/')
+                .any(|part| part.is_empty() || part == "..")
+            {
+                return Err(format!("invalid narrative target `{target}`"));
+            }
+            paths.push(format!(
+                "{}{}{relative}",
+                tools::VIZIER_DIR,
+                tools::NARRATIVE_DIR
+            ));
+        }
+        if paths.is_empty() {
+            return Err("narrative scope requires at least one target".to_string());
+        }
+        paths.sort();
+        paths.dedup();
+        Ok(paths)
+    }
+
+    /// Records the current contents of every narrative file under `repo_root` so out-of-scope
+    /// edits can be restored by `enforce`.
+    pub fn capture(repo_root: &Path, allowed: Vec<String>) -> std::io::Result<Self> {
+        Ok(Self {
+            allowed,
+            baseline: narrative_file_contents(repo_root)?,
+        })
+    }
+
+    pub fn allowed(&self) -> &[String] {
+        &self.allowed
+    }
+
+    /// Restores narrative files outside the scope to their captured contents (removing files the
+    /// run created) and returns the reverted paths.
+    pub fn enforce(&self, repo_root: &Path) -> std::io::Result<Vec<String>> {
+        let current = narrative_file_contents(repo_root)?;
+        let mut reverted = Vec::new();
+        let touched = current
+            .keys()
+            .chain(self.baseline.keys())
+            .collect::<std::collections::BTreeSet<_>>();
+        for path in touched {
+            if self.allowed.contains(path) || current.get(path) == self.baseline.get(path) {
+                continue;
+            }
+            let abs = repo_root.join(path);
+            match self.baseline.get(path) {
+                Some(contents) => std::fs::write(&abs, contents)?,
+                None => std::fs::remove_file(&abs)?,
+            }
+            reverted.push(path.clone());
+        }
+        FileTracker::clear_tracked(&reverted);
+        Ok(reverted)
+    }
+}
+
+fn narrative_file_contents(repo_root: &Path) -> std::io::Result<BTreeMap<String, Vec<u8>>> {
+    let mut files = BTreeMap::new();
+    let mut pending = vec![repo_root.join(tools::VIZIER_DIR).join(tools::NARRATIVE_DIR)];
+    while let Some(dir) = pending.pop() {
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(err),
+        };
+        for entry in entries {
+            let path = entry?.path();
+            if path.is_dir() {
+                pending.push(path);
+                continue;
+            }
+            let Ok(relative) = path.strip_prefix(repo_root) else {
+                continue;
+            };
+            let relative = FileTracker::normalize_repo_path(relative);
+            if is_canonical_story_path(&relative) {
+                files.insert(relative, std::fs::read(&path)?);
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// Whether `path` (repo-relative, `/` or `\` separated, optionally `./`-prefixed) is `.vizier`
+/// or sits under it.
+pub(crate) fn is_vizier_path(path: &str) -> bool {
     let normalized = path.replace('\\', "/");
-    normalized.starts_with(".vizier/") || normalized.starts_with("./.vizier/")
+    let normalized = normalized.strip_prefix("./").unwrap_or(&normalized);
+    normalized == ".vizier" || normalized.starts_with(".vizier/")
 }
 
 pub fn is_canonical_story_path(path: &str) -> bool {
@@ -300,4 +515,42 @@ mod tests {
             "expected staged-only canonical narrative path in collected changes: {changes:?}"
         );
     }
+
+    #[test]
+    fn narrative_scope_reverts_out_of_scope_edits() {
+        let temp = tempdir().expect("tempdir");
+        let root = temp.path();
+        let narrative = root.join(".vizier/narrative");
+        fs::create_dir_all(narrative.join("threads")).expect("narrative dirs");
+        fs::write(narrative.join("snapshot.md"), "snapshot\n").expect("snapshot");
+        fs::write(narrative.join("threads/auth.md"), "auth\n").expect("thread");
+
+        let allowed = NarrativeScope::parse_targets("threads/auth").expect("targets");
+        assert_eq!(allowed, vec![".vizier/narrative/threads/auth.md"]);
+        let scope = NarrativeScope::capture(root, allowed).expect("capture");
+
+        fs::write(narrative.join("snapshot.md"), "rewritten\n").expect("edit snapshot");
+        fs::write(narrative.join("threads/auth.md"), "auth v2\n").expect("edit thread");
+        fs::write(narrative.join("threads/new.md"), "new\n").expect("new thread");
+
+        let reverted = scope.enforce(root).expect("enforce");
+        assert_eq!(
+            reverted,
+            vec![
+                ".vizier/narrative/snapshot.md".to_string(),
+                ".vizier/narrative/threads/new.md".to_string(),
+            ]
+        );
+        assert_eq!(
+            fs::read_to_string(narrative.join("snapshot.md")).unwrap(),
+            "snapshot\n"
+        );
+        assert_eq!(
+            fs::read_to_string(narrative.join("threads/auth.md")).unwrap(),
+            "auth v2\n"
+        );
+        assert!(!narrative.join("threads/new.md").exists());
+        assert!(NarrativeScope::parse_targets("threads/../config").is_err());
+        assert!(NarrativeScope::parse_targets(" , ").is_err());
+    }
 }
@@ -0,0 +1,393 @@
+//! Remote forge operations (opening pull requests, fetching issues, publishing reviews) behind
+//! one provider trait. The providers delegate to the locally installed `gh` or `glab` CLI, so
+//! they reuse whatever authentication the operator already set up for it instead of asking for
+//! a Vizier-specific API token. `[forge] cli` picks the provider; `auto` matches the `origin`
+//! host to a CLI found on `PATH`.
+
+use std::fmt;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use serde_json::Value;
+
+use crate::config::{self, ForgeCli};
+
+/// A pull (or merge) request to open from `source_branch` into `target_branch`. Without a title
+/// the CLI fills the title and body from the branch's commits.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PullRequestSpec {
+    pub source_branch: String,
+    pub target_branch: String,
+    pub title: Option<String>,
+    pub body: Option<String>,
+}
+
+/// An issue as fetched from the forge.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ForgeIssue {
+    pub id: String,
+    pub title: String,
+    pub body: String,
+    pub url: Option<String>,
+}
+
+impl ForgeIssue {
+    /// The issue as a markdown spec: its title as the heading, then its body and a source link.
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = format!("# {}\n", self.title.trim());
+        let body = self.body.trim();
+        if !body.is_empty() {
+            markdown.push('\n');
+            markdown.push_str(body);
+            markdown.push('\n');
+        }
+        if let Some(url) = self.url.as_deref() {
+            markdown.push_str(&format!("\nSource: {url}\n"));
+        }
+        markdown
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForgeError(String);
+
+impl fmt::Display for ForgeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ForgeError {}
+
+pub trait ForgeProvider {
+    /// The CLI the provider runs (`gh`, `glab`).
+    fn name(&self) -> &'static str;
+
+    /// Opens a pull request and returns its URL. An already open pull request for the same
+    /// branches counts as success.
+    fn create_pull_request(
+        &self,
+        repo_root: &Path,
+        spec: &PullRequestSpec,
+    ) -> Result<String, ForgeError>;
+
+    fn fetch_issue(&self, repo_root: &Path, id: &str) -> Result<ForgeIssue, ForgeError>;
+
+    /// Posts `body` as a review comment on pull request `pull_request`.
+    fn publish_review(
+        &self,
+        repo_root: &Path,
+        pull_request: &str,
+        body: &str,
+    ) -> Result<(), ForgeError>;
+}
+
+/// GitHub through `gh`.
+pub struct GhCli;
+
+/// GitLab through `glab`.
+pub struct GlabCli;
+
+impl ForgeProvider for GhCli {
+    fn name(&self) -> &'static str {
+        "gh"
+    }
+
+    fn create_pull_request(
+        &self,
+        repo_root: &Path,
+        spec: &PullRequestSpec,
+    ) -> Result<String, ForgeError> {
+        let mut args = vec![
+            "pr".to_string(),
+            "create".to_string(),
+            "--base".to_string(),
+            spec.target_branch.clone(),
+            "--head".to_string(),
+            spec.source_branch.clone(),
+        ];
+        push_title_and_body(&mut args, spec, "--body");
+        create_and_read_url(self.name(), repo_root, &args)
+    }
+
+    fn fetch_issue(&self, repo_root: &Path, id: &str) -> Result<ForgeIssue, ForgeError> {
+        let output = run_cli(
+            self.name(),
+            repo_root,
+            &["issue", "view", id, "--json", "number,title,body,url"],
+            None,
+        )?;
+        let value = parse_json(self.name(), &output)?;
+        Ok(ForgeIssue {
+            id: json_text(&value, "number").unwrap_or_else(|| id.to_string()),
+            title: json_text(&value, "title").unwrap_or_default(),
+            body: json_text(&value, "body").unwrap_or_default(),
+            url: json_text(&value, "url"),
+        })
+    }
+
+    fn publish_review(
+        &self,
+        repo_root: &Path,
+        pull_request: &str,
+        body: &str,
+    ) -> Result<(), ForgeError> {
+        run_cli(
+            self.name(),
+            repo_root,
+            &[
+                "pr",
+                "review",
+                pull_request,
+                "--comment",
+                "--body-file",
+                "-",
+            ],
+            Some(body),
+        )
+        .map(|_| ())
+    }
+}
+
+impl ForgeProvider for GlabCli {
+    fn name(&self) -> &'static str {
+        "glab"
+    }
+
+    fn create_pull_request(
+        &self,
+        repo_root: &Path,
+        spec: &PullRequestSpec,
+    ) -> Result<String, ForgeError> {
+        let mut args = vec![
+            "mr".to_string(),
+            "create".to_string(),
+            "--source-branch".to_string(),
+            spec.source_branch.clone(),
+            "--target-branch".to_string(),
+            spec.target_branch.clone(),
+            "--yes".to_string(),
+        ];
+        push_title_and_body(&mut args, spec, "--description");
+        create_and_read_url(self.name(), repo_root, &args)
+    }
+
+    fn fetch_issue(&self, repo_root: &Path, id: &str) -> Result<ForgeIssue, ForgeError> {
+        let output = run_cli(
+            self.name(),
+            repo_root,
+            &["issue", "view", id, "--output", "json"],
+            None,
+        )?;
+        let value = parse_json(self.name(), &output)?;
+        Ok(ForgeIssue {
+            id: json_text(&value, "iid").unwrap_or_else(|| id.to_string()),
+            title: json_text(&value, "title").unwrap_or_default(),
+            body: json_text(&value, "description").unwrap_or_default(),
+            url: json_text(&value, "web_url"),
+        })
+    }
+
+    fn publish_review(
+        &self,
+        repo_root: &Path,
+        pull_request: &str,
+        body: &str,
+    ) -> Result<(), ForgeError> {
+        run_cli(
+            self.name(),
+            repo_root,
+            &["mr", "note", pull_request, "--message", body],
+            None,
+        )
+        .map(|_| ())
+    }
+}
+
+/// The provider `[forge] cli` selects for `repo_root`, or `None` when it is `off` or no
+/// matching CLI is installed.
+pub fn provider_for(repo_root: &Path) -> Option<Box<dyn ForgeProvider>> {
+    let remote = crate::vcs::remote_url_in(repo_root, "origin");
+    match select_cli(config::get_config().forge.cli, remote.as_deref(), on_path)? {
+        ForgeCli::Gh => Some(Box::new(GhCli)),
+        ForgeCli::Glab => Some(Box::new(GlabCli)),
+        ForgeCli::Auto | ForgeCli::Off => None,
+    }
+}
+
+/// Resolves `configured` to `gh` or `glab`. An explicit choice only needs the CLI installed;
+/// `auto` prefers the CLI matching the remote host and otherwise takes the only one installed.
+pub fn select_cli(
+    configured: ForgeCli,
+    remote_url: Option<&str>,
+    installed: impl Fn(&str) -> bool,
+) -> Option<ForgeCli> {
+    let available = |cli: ForgeCli| installed(cli.as_str()).then_some(cli);
+    match configured {
+        ForgeCli::Off => None,
+        ForgeCli::Gh | ForgeCli::Glab => available(configured),
+        ForgeCli::Auto => {
+            let host = remote_url.unwrap_or_default().to_ascii_lowercase();
+            if host.contains("github") {
+                available(ForgeCli::Gh)
+            } else if host.contains("gitlab") {
+                available(ForgeCli::Glab)
+            } else {
+                match (available(ForgeCli::Gh), available(ForgeCli::Glab)) {
+                    (Some(cli), None) | (None, Some(cli)) => Some(cli),
+                    _ => None,
+                }
+            }
+        }
+    }
+}
+
+fn on_path(program: &str) -> bool {
+    std::env::var_os("PATH").is_some_and(|paths| {
+        std::env::split_paths(&paths).any(|dir: PathBuf| dir.join(program).is_file())
+    })
+}
+
+fn push_title_and_body(args: &mut Vec<String>, spec: &PullRequestSpec, body_flag: &str) {
+    match spec.title.as_deref() {
+        Some(title) => {
+            args.push("--title".to_string());
+            args.push(title.to_string());
+            args.push(body_flag.to_string());
+            args.push(spec.body.clone().unwrap_or_default());
+        }
+        None => args.push("--fill".to_string()),
+    }
+}
+
+// Both CLIs print the new request's URL; when one is already open they fail with a message
+// naming its URL, which is just as good.
+fn create_and_read_url(
+    program: &str,
+    repo_root: &Path,
+    args: &[String],
+) -> Result<String, ForgeError> {
+    let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+    match run_cli(program, repo_root, &args, None) {
+        Ok(stdout) => last_url(&stdout)
+            .ok_or_else(|| ForgeError(format!("{program} did not report a pull request URL"))),
+        Err(err) if err.0.contains("already exists") => last_url(&err.0).ok_or(err),
+        Err(err) => Err(err),
+    }
+}
+
+fn last_url(text: &str) -> Option<String> {
+    text.split_whitespace()
+        .rev()
+        .find(|token| token.starts_with("https://") || token.starts_with("http://"))
+        .map(|token| token.trim_end_matches(['.', ',']).to_string())
+}
+
+fn run_cli(
+    program: &str,
+    repo_root: &Path,
+    args: &[&str],
+    stdin: Option<&str>,
+) -> Result<String, ForgeError> {
+    let mut child = Command::new(program)
+        .args(args)
+        .current_dir(repo_root)
+        .stdin(if stdin.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| ForgeError(format!("failed to run {program}: {err}")))?;
+    if let (Some(input), Some(mut pipe)) = (stdin, child.stdin.take()) {
+        pipe.write_all(input.as_bytes())
+            .map_err(|err| ForgeError(format!("failed to write to {program}: {err}")))?;
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|err| ForgeError(format!("failed to run {program}: {err}")))?;
+    let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+    if output.status.success() {
+        return Ok(stdout);
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let detail = if stderr.trim().is_empty() {
+        stdout.trim().to_string()
+    } else {
+        stderr.trim().to_string()
+    };
+    Err(ForgeError(format!(
+        "{program} {} failed: {detail}",
+        args.iter().take(2).copied().collect::<Vec<_>>().join(" ")
+    )))
+}
+
+fn parse_json(program: &str, output: &str) -> Result<Value, ForgeError> {
+    serde_json::from_str(output)
+        .map_err(|err| ForgeError(format!("{program} returned unreadable JSON: {err}")))
+}
+
+fn json_text(value: &Value, key: &str) -> Option<String> {
+    match value.get(key)? {
+        Value::String(text) => Some(text.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn auto_selection_follows_the_remote_host_and_installed_clis() {
+        let both = |_: &str| true;
+        let only_glab = |program: &str| program == "glab";
+        let github = Some("git@github.com:acme/widgets.git");
+        let gitlab = Some("https://gitlab.example.com/acme/widgets.git");
+
+        assert_eq!(select_cli(ForgeCli::Auto, github, both), Some(ForgeCli::Gh));
+        assert_eq!(
+            select_cli(ForgeCli::Auto, gitlab, both),
+            Some(ForgeCli::Glab)
+        );
+        assert_eq!(select_cli(ForgeCli::Auto, github, only_glab), None);
+        assert_eq!(
+            select_cli(
+                ForgeCli::Auto,
+                Some("ssh://git.internal/acme.git"),
+                only_glab
+            ),
+            Some(ForgeCli::Glab)
+        );
+        assert_eq!(select_cli(ForgeCli::Auto, None, both), None);
+        assert_eq!(
+            select_cli(ForgeCli::Glab, github, both),
+            Some(ForgeCli::Glab)
+        );
+        assert_eq!(select_cli(ForgeCli::Off, github, both), None);
+    }
+
+    #[test]
+    fn existing_pull_request_urls_and_issue_markdown() {
+        assert_eq!(
+            last_url(
+                "a pull request for branch \"draft/a\" into branch \"main\" already exists:\nhttps://github.com/acme/widgets/pull/7\n"
+            ),
+            Some("https://github.com/acme/widgets/pull/7".to_string())
+        );
+        let issue = ForgeIssue {
+            id: "42".to_string(),
+            title: "Retry flaky uploads".to_string(),
+            body: "Uploads fail on slow links.\n".to_string(),
+            url: Some("https://github.com/acme/widgets/issues/42".to_string()),
+        };
+        assert_eq!(
+            issue.to_markdown(),
+            "# Retry flaky uploads\n\nUploads fail on slow links.\n\nSource: https://github.com/acme/widgets/issues/42\n"
+        );
+    }
+}
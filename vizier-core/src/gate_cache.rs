@@ -0,0 +1,294 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use git2::{BranchType, Oid, Repository};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::config;
+
+pub const GATE_CACHE_DIR: &str = crate::temp_registry::GATE_CACHE_DIR;
+
+/// What a gate pass is valid for: the plan branch tip, the target tip it was checked against,
+/// and the gate configuration it ran under.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GateCacheKey {
+    pub branch_tip: String,
+    pub target_tip: String,
+    pub config_hash: String,
+}
+
+impl GateCacheKey {
+    /// Resolves both branches to their tips; `None` when either branch is missing.
+    pub fn for_branches(
+        repo_path: &Path,
+        branch: &str,
+        target: &str,
+        gates: &[config::MergeGateConfig],
+    ) -> Option<Self> {
+        let repo = Repository::open(repo_path).ok()?;
+        let tip = |name: &str| {
+            repo.find_branch(name, BranchType::Local)
+                .and_then(|branch| branch.get().peel_to_commit())
+                .map(|commit| commit.id().to_string())
+                .ok()
+        };
+        Some(Self {
+            branch_tip: tip(branch)?,
+            target_tip: tip(target)?,
+            config_hash: config_hash(gates),
+        })
+    }
+
+    pub fn digest(&self) -> String {
+        let mut hasher = Sha256::new();
+        for part in [&self.branch_tip, &self.target_tip, &self.config_hash] {
+            hasher.update(part.as_bytes());
+            hasher.update([0]);
+        }
+        format!("{:x}", hasher.finalize())
+    }
+}
+
+/// A gate that passed for a [`GateCacheKey`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedGate {
+    pub name: String,
+    pub script: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GateCacheEntry {
+    pub branch_tip: String,
+    pub target_tip: String,
+    pub config_hash: String,
+    /// Tree of the branch tip; merging a branch that already contains the target yields this
+    /// tree, which is what lets post-merge gates reuse the pass.
+    pub tree: String,
+    pub gates: Vec<CachedGate>,
+    pub recorded_at: String,
+}
+
+impl GateCacheEntry {
+    fn passed(&self, script: &str) -> bool {
+        self.gates.iter().any(|gate| gate.script == script.trim())
+    }
+}
+
+/// Hash of the gate definitions (name, stage, script) a pass was recorded under; editing any gate
+/// invalidates every cached pass.
+pub fn config_hash(gates: &[config::MergeGateConfig]) -> String {
+    let mut hasher = Sha256::new();
+    for gate in gates {
+        hasher.update(
+            format!(
+                "{}\t{}\t{}\n",
+                gate.name,
+                gate.stage.as_str(),
+                gate.script.to_string_lossy()
+            )
+            .as_bytes(),
+        );
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// `gate: cached-pass (<sha>)` notation shown in place of a gate run.
+pub fn cached_pass_notation(entry: &GateCacheEntry) -> String {
+    format!(
+        "gate: cached-pass ({})",
+        &entry.branch_tip[..entry.branch_tip.len().min(12)]
+    )
+}
+
+pub fn entry_path(project_root: &Path, key: &GateCacheKey) -> PathBuf {
+    project_root
+        .join(GATE_CACHE_DIR)
+        .join(format!("{}.json", key.digest()))
+}
+
+/// Records the gates that passed for `key`, merging with anything already recorded for it.
+pub fn record_passes(
+    project_root: &Path,
+    key: &GateCacheKey,
+    gates: &[CachedGate],
+) -> io::Result<()> {
+    if gates.is_empty() {
+        return Ok(());
+    }
+    let tree = Repository::open(project_root)
+        .and_then(|repo| {
+            repo.find_commit(Oid::from_str(&key.branch_tip)?)
+                .map(|commit| commit.tree_id())
+        })
+        .map_err(io::Error::other)?;
+    let mut entry = load_entry(&entry_path(project_root, key))?.unwrap_or(GateCacheEntry {
+        branch_tip: key.branch_tip.clone(),
+        target_tip: key.target_tip.clone(),
+        config_hash: key.config_hash.clone(),
+        tree: tree.to_string(),
+        gates: Vec::new(),
+        recorded_at: String::new(),
+    });
+    for gate in gates {
+        let gate = CachedGate {
+            name: gate.name.clone(),
+            script: gate.script.trim().to_string(),
+        };
+        if !entry.gates.contains(&gate) {
+            entry.gates.push(gate);
+        }
+    }
+    entry.recorded_at = Utc::now().to_rfc3339();
+
+    let path = entry_path(project_root, key);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let bytes = serde_json::to_vec_pretty(&entry)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    fs::write(path, bytes)
+}
+
+/// Cached pass of `script` for exactly `key`; used for gates that run on the branch itself.
+pub fn cached_pass(
+    project_root: &Path,
+    key: &GateCacheKey,
+    script: &str,
+) -> Option<GateCacheEntry> {
+    load_entry(&entry_path(project_root, key))
+        .ok()
+        .flatten()
+        .filter(|entry| entry.passed(script))
+}
+
+/// Cached pass of `script` for a merge result: the merge commit's first parent must be the
+/// recorded target tip and its tree the recorded branch tree, so the gate would see the same
+/// content it already passed on.
+pub fn cached_pass_for_merge(
+    project_root: &Path,
+    merge_root: &Path,
+    config_hash: &str,
+    script: &str,
+) -> Option<GateCacheEntry> {
+    let repo = Repository::open(merge_root).ok()?;
+    let head = repo.head().ok()?.peel_to_commit().ok()?;
+    let target_tip = head.parent_id(0).ok()?.to_string();
+    let tree = head.tree_id().to_string();
+    fs::read_dir(project_root.join(GATE_CACHE_DIR))
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| load_entry(&entry.path()).ok().flatten())
+        .find(|entry| {
+            entry.target_tip == target_tip
+                && entry.tree == tree
+                && entry.config_hash == config_hash
+                && entry.passed(script)
+        })
+}
+
+fn load_entry(path: &Path) -> io::Result<Option<GateCacheEntry>> {
+    match fs::read(path) {
+        Ok(bytes) => serde_json::from_slice(&bytes)
+            .map(Some)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::Signature;
+
+    fn commit(repo: &Repository, path: &str, contents: &str, parents: &[Oid]) -> Oid {
+        fs::write(repo.workdir().expect("workdir").join(path), contents).expect("write file");
+        let mut index = repo.index().expect("index");
+        index.add_path(Path::new(path)).expect("add path");
+        let tree = repo
+            .find_tree(index.write_tree().expect("write tree"))
+            .expect("tree");
+        let sig = Signature::now("Tester", "tester@example.com").expect("signature");
+        let parents = parents
+            .iter()
+            .map(|oid| repo.find_commit(*oid).expect("parent"))
+            .collect::<Vec<_>>();
+        repo.commit(
+            None,
+            &sig,
+            &sig,
+            "commit",
+            &tree,
+            &parents.iter().collect::<Vec<_>>(),
+        )
+        .expect("commit")
+    }
+
+    fn gates(script: &str) -> Vec<config::MergeGateConfig> {
+        vec![config::MergeGateConfig {
+            name: "cicd".to_string(),
+            stage: config::MergeGateStage::PreMerge,
+            script: PathBuf::from(script),
+        }]
+    }
+
+    #[test]
+    fn recorded_pass_is_reused_until_tips_or_config_change() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let repo = Repository::init(dir.path()).expect("init repo");
+        let base = commit(&repo, "a.txt", "base\n", &[]);
+        let tip = commit(&repo, "a.txt", "plan\n", &[base]);
+        repo.branch("main", &repo.find_commit(base).expect("base"), true)
+            .expect("main");
+        repo.branch("draft/alpha", &repo.find_commit(tip).expect("tip"), true)
+            .expect("draft");
+
+        let key = GateCacheKey::for_branches(dir.path(), "draft/alpha", "main", &gates("make"))
+            .expect("key");
+        assert_eq!(key.branch_tip, tip.to_string());
+        assert!(cached_pass(dir.path(), &key, "make").is_none());
+        record_passes(
+            dir.path(),
+            &key,
+            &[CachedGate {
+                name: "cicd".to_string(),
+                script: "make".to_string(),
+            }],
+        )
+        .expect("record");
+
+        let entry = cached_pass(dir.path(), &key, "make").expect("cached pass");
+        assert_eq!(
+            cached_pass_notation(&entry),
+            format!("gate: cached-pass ({})", &tip.to_string()[..12])
+        );
+        assert!(cached_pass(dir.path(), &key, "make test").is_none());
+        let edited =
+            GateCacheKey::for_branches(dir.path(), "draft/alpha", "main", &gates("make test"))
+                .expect("edited key");
+        assert!(cached_pass(dir.path(), &edited, "make").is_none());
+
+        // A squash merge onto the recorded target tip carries the branch tree; the same tree
+        // on top of a moved target does not count.
+        let moved = commit(&repo, "b.txt", "moved\n", &[base]);
+        fs::remove_file(dir.path().join("b.txt")).expect("remove b.txt");
+        let mut index = repo.index().expect("index");
+        index
+            .remove_path(Path::new("b.txt"))
+            .expect("unstage b.txt");
+        index.write().expect("write index");
+        let off_target = commit(&repo, "a.txt", "plan\n", &[moved]);
+        repo.set_head_detached(off_target).expect("detach");
+        assert!(cached_pass_for_merge(dir.path(), dir.path(), &key.config_hash, "make").is_none());
+
+        let squash = commit(&repo, "a.txt", "plan\n", &[base]);
+        repo.set_head_detached(squash).expect("detach");
+        assert!(cached_pass_for_merge(dir.path(), dir.path(), &key.config_hash, "make").is_some());
+        assert!(
+            cached_pass_for_merge(dir.path(), dir.path(), &edited.config_hash, "make").is_none()
+        );
+    }
+}
@@ -0,0 +1,341 @@
+//! Per-user cache shared by every repo, holding the bundled agent shims so a repo without
+//! `.vizier/agents/` still resolves `codex`/`claude`/`gemini` without copying them in.
+//!
+//! Everything lives under a versioned directory (`<cache>/v1/`); a layout change bumps
+//! [`CACHE_VERSION`] and `vizier cache clean` drops the old tree. Each file's SHA-256 is kept in
+//! `manifest.json` and checked against the embedded copy, so an edited or truncated shim is
+//! rewritten instead of run.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::atomic_file::write_atomic;
+
+/// Bumped whenever the cache layout changes; older trees are left for `vizier cache clean`.
+pub const CACHE_VERSION: u32 = 1;
+pub const AGENT_SHIMS_DIR: &str = "agents";
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// Agent shims compiled into the binary: label, `agent.sh`, `filter.sh`.
+pub const BUNDLED_AGENT_SHIMS: &[(&str, &str, &str)] = &[
+    (
+        "codex",
+        include_str!("../../examples/agents/codex/agent.sh"),
+        include_str!("../../examples/agents/codex/filter.sh"),
+    ),
+    (
+        "claude",
+        include_str!("../../examples/agents/claude/agent.sh"),
+        include_str!("../../examples/agents/claude/filter.sh"),
+    ),
+    (
+        "gemini",
+        include_str!("../../examples/agents/gemini/agent.sh"),
+        include_str!("../../examples/agents/gemini/filter.sh"),
+    ),
+];
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Manifest {
+    version: u32,
+    vizier_version: String,
+    /// SHA-256 of each cached file, keyed by its path under the versioned root.
+    files: BTreeMap<String, String>,
+}
+
+/// State of one cached file against the copy embedded in this binary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryState {
+    Ok,
+    Modified,
+    Missing,
+}
+
+impl EntryState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Ok => "ok",
+            Self::Modified => "modified",
+            Self::Missing => "missing",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CacheEntry {
+    pub path: String,
+    pub bytes: u64,
+    pub state: EntryState,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CacheStatus {
+    pub root: PathBuf,
+    pub version: u32,
+    pub entries: Vec<CacheEntry>,
+    /// Versioned trees other than [`CACHE_VERSION`].
+    pub stale: Vec<PathBuf>,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CleanReport {
+    pub removed: Vec<PathBuf>,
+    pub bytes: u64,
+}
+
+/// The user cache directory: `VIZIER_CACHE_DIR`, else `vizier/` under `XDG_CACHE_HOME`,
+/// `LOCALAPPDATA`, or `HOME/.cache`.
+pub fn cache_root() -> Option<PathBuf> {
+    let env_dir = |name: &str| {
+        std::env::var(name)
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)
+    };
+    if let Some(dir) = env_dir("VIZIER_CACHE_DIR") {
+        return Some(dir);
+    }
+    if let Some(dir) = env_dir("XDG_CACHE_HOME").or_else(|| env_dir("LOCALAPPDATA")) {
+        return Some(dir.join("vizier"));
+    }
+    env_dir("HOME").map(|home| home.join(".cache").join("vizier"))
+}
+
+pub fn versioned_root(root: &Path) -> PathBuf {
+    root.join(format!("v{CACHE_VERSION}"))
+}
+
+/// Path of a bundled shim file (`codex/agent.sh`) in the user cache, writing or repairing the
+/// cached shims on first use in this process. `None` for labels this binary does not bundle or
+/// when no cache directory can be written.
+pub fn cached_agent_shim(relative: &str) -> Option<PathBuf> {
+    let label = relative.split('/').next()?;
+    if !BUNDLED_AGENT_SHIMS
+        .iter()
+        .any(|(name, _, _)| *name == label)
+    {
+        return None;
+    }
+    static SHIMS_DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
+    let dir = SHIMS_DIR.get_or_init(|| {
+        let root = cache_root()?;
+        match ensure_agent_shims_in(&root) {
+            Ok(dir) => Some(dir),
+            Err(err) => {
+                crate::display::debug(format!(
+                    "agent shim cache unavailable at {}: {err}",
+                    root.display()
+                ));
+                None
+            }
+        }
+    });
+    let path = dir.as_ref()?.join(relative);
+    path.is_file().then_some(path)
+}
+
+/// Writes any bundled shim that is missing from `root` or no longer matches the embedded copy,
+/// refreshes the manifest, and returns the shims directory.
+pub fn ensure_agent_shims_in(root: &Path) -> io::Result<PathBuf> {
+    let versioned = versioned_root(root);
+    let mut manifest = read_manifest(&versioned);
+    let mut changed = manifest.version != CACHE_VERSION;
+    for (relative, contents) in bundled_files() {
+        let path = versioned.join(&relative);
+        let expected = sha256_hex(contents.as_bytes());
+        if entry_state(&path, &expected) != EntryState::Ok {
+            write_atomic(&path, contents.as_bytes())?;
+            set_executable(&path)?;
+        }
+        if manifest.files.get(&relative) != Some(&expected) {
+            manifest.files.insert(relative, expected);
+            changed = true;
+        }
+    }
+    if changed {
+        manifest.version = CACHE_VERSION;
+        manifest.vizier_version = env!("CARGO_PKG_VERSION").to_string();
+        let json = serde_json::to_vec_pretty(&manifest).map_err(io::Error::other)?;
+        write_atomic(&versioned.join(MANIFEST_FILE), &json)?;
+    }
+    Ok(versioned.join(AGENT_SHIMS_DIR))
+}
+
+/// Integrity of each bundled file in `root` and the stale versioned trees beside it.
+pub fn status_in(root: &Path) -> io::Result<CacheStatus> {
+    let versioned = versioned_root(root);
+    let entries = bundled_files()
+        .into_iter()
+        .map(|(relative, contents)| {
+            let path = versioned.join(&relative);
+            CacheEntry {
+                bytes: fs::metadata(&path).map(|meta| meta.len()).unwrap_or(0),
+                state: entry_state(&path, &sha256_hex(contents.as_bytes())),
+                path: relative,
+            }
+        })
+        .collect();
+    Ok(CacheStatus {
+        root: root.to_path_buf(),
+        version: CACHE_VERSION,
+        entries,
+        stale: stale_versions(root)?,
+        bytes: dir_size(root),
+    })
+}
+
+/// Removes versioned trees older or newer than [`CACHE_VERSION`]; with `all`, the whole cache.
+pub fn clean_in(root: &Path, all: bool) -> io::Result<CleanReport> {
+    let mut report = CleanReport::default();
+    let targets = if all {
+        if root.exists() {
+            vec![root.to_path_buf()]
+        } else {
+            Vec::new()
+        }
+    } else {
+        stale_versions(root)?
+    };
+    for target in targets {
+        report.bytes += dir_size(&target);
+        fs::remove_dir_all(&target)?;
+        report.removed.push(target);
+    }
+    Ok(report)
+}
+
+fn bundled_files() -> Vec<(String, &'static str)> {
+    BUNDLED_AGENT_SHIMS
+        .iter()
+        .flat_map(|(label, agent, filter)| {
+            [
+                (format!("{AGENT_SHIMS_DIR}/{label}/agent.sh"), *agent),
+                (format!("{AGENT_SHIMS_DIR}/{label}/filter.sh"), *filter),
+            ]
+        })
+        .collect()
+}
+
+fn entry_state(path: &Path, expected: &str) -> EntryState {
+    match fs::read(path) {
+        Ok(bytes) if sha256_hex(&bytes) == expected => EntryState::Ok,
+        Ok(_) => EntryState::Modified,
+        Err(_) => EntryState::Missing,
+    }
+}
+
+fn read_manifest(versioned: &Path) -> Manifest {
+    fs::read(versioned.join(MANIFEST_FILE))
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default()
+}
+
+fn stale_versions(root: &Path) -> io::Result<Vec<PathBuf>> {
+    let current = format!("v{CACHE_VERSION}");
+    let entries = match fs::read_dir(root) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    let mut stale = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        let versioned = name
+            .strip_prefix('v')
+            .is_some_and(|rest| !rest.is_empty() && rest.chars().all(|ch| ch.is_ascii_digit()));
+        if versioned && name != current && entry.path().is_dir() {
+            stale.push(entry.path());
+        }
+    }
+    stale.sort();
+    Ok(stale)
+}
+
+fn dir_size(path: &Path) -> u64 {
+    let Ok(meta) = fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !meta.is_dir() {
+        return meta.len();
+    }
+    fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .map(|entry| dir_size(&entry.path()))
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+#[cfg(unix)]
+fn set_executable(path: &Path) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))
+}
+
+#[cfg(not(unix))]
+fn set_executable(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ensure_repairs_edited_shims_and_clean_drops_stale_versions() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let root = dir.path();
+        let shims = ensure_agent_shims_in(root).expect("ensure");
+        let agent = shims.join("codex/agent.sh");
+        assert!(agent.is_file());
+        assert!(
+            status_in(root)
+                .expect("status")
+                .entries
+                .iter()
+                .all(|entry| entry.state == EntryState::Ok)
+        );
+
+        fs::write(&agent, "#!/bin/sh\necho tampered\n").expect("tamper");
+        let status = status_in(root).expect("status");
+        let codex = status
+            .entries
+            .iter()
+            .find(|entry| entry.path == "agents/codex/agent.sh")
+            .expect("codex entry");
+        assert_eq!(codex.state, EntryState::Modified);
+
+        ensure_agent_shims_in(root).expect("repair");
+        assert_eq!(
+            fs::read_to_string(&agent).expect("read"),
+            BUNDLED_AGENT_SHIMS[0].1
+        );
+
+        fs::create_dir_all(root.join("v0/agents")).expect("stale");
+        fs::create_dir_all(root.join("vendor")).expect("unrelated");
+        let report = clean_in(root, false).expect("clean");
+        assert_eq!(report.removed, vec![root.join("v0")]);
+        assert!(versioned_root(root).is_dir());
+        assert!(root.join("vendor").is_dir());
+
+        clean_in(root, true).expect("clean all");
+        assert!(!root.exists());
+    }
+}
@@ -0,0 +1,253 @@
+//! `[output] language`: translated labels and summaries for human-facing CLI output, plus the
+//! directive that asks agents to write responses and documents in the team language.
+//!
+//! The catalog is keyed by the English source text, so untranslated strings (and every
+//! language without a catalog) fall back to English unchanged. JSON output is never translated.
+
+use std::borrow::Cow;
+
+use crate::config;
+
+/// Languages with a message catalog, in the column order of [`CATALOG`].
+const CATALOG_LANGUAGES: [&str; 3] = ["de", "fr", "es"];
+
+/// English source text and its `de`, `fr`, and `es` translations.
+const CATALOG: &[(&str, [&str; 3])] = &[
+    ("Outcome", ["Ergebnis", "Résultat", "Resultado"]),
+    (
+        "Outcome file",
+        [
+            "Ergebnisdatei",
+            "Fichier de résultat",
+            "Archivo de resultado",
+        ],
+    ),
+    ("Plan", ["Plan", "Plan", "Plan"]),
+    ("Branch", ["Branch", "Branche", "Rama"]),
+    ("Target", ["Ziel", "Cible", "Destino"]),
+    ("Selector", ["Selektor", "Sélecteur", "Selector"]),
+    ("Run", ["Lauf", "Exécution", "Ejecución"]),
+    ("Job", ["Job", "Tâche", "Trabajo"]),
+    ("Status", ["Status", "Statut", "Estado"]),
+    ("Created", ["Erstellt", "Créé", "Creado"]),
+    ("Started", ["Gestartet", "Démarré", "Iniciado"]),
+    ("Finished", ["Beendet", "Terminé", "Finalizado"]),
+    ("Priority", ["Priorität", "Priorité", "Prioridad"]),
+    (
+        "Queue position",
+        [
+            "Warteschlangenplatz",
+            "Position dans la file",
+            "Posición en la cola",
+        ],
+    ),
+    (
+        "Estimated start",
+        ["Voraussichtlicher Start", "Début estimé", "Inicio estimado"],
+    ),
+    ("Wait", ["Wartet auf", "Attente", "Espera"]),
+    ("Reason", ["Grund", "Raison", "Motivo"]),
+    (
+        "Next",
+        ["Nächster Schritt", "Étape suivante", "Siguiente paso"],
+    ),
+    ("Commit", ["Commit", "Commit", "Commit"]),
+    (
+        "Worktree",
+        ["Arbeitsbaum", "Arbre de travail", "Árbol de trabajo"],
+    ),
+    ("Template", ["Vorlage", "Modèle", "Plantilla"]),
+    ("Version", ["Version", "Version", "Versión"]),
+    ("Title", ["Titel", "Titre", "Título"]),
+    ("Summary", ["Zusammenfassung", "Résumé", "Resumen"]),
+    ("Labels", ["Labels", "Étiquettes", "Etiquetas"]),
+    ("Lineage", ["Herkunft", "Filiation", "Linaje"]),
+    ("Spawned by", ["Abgeleitet von", "Issu de", "Derivado de"]),
+    ("Follow-ups", ["Folgepläne", "Suites", "Seguimientos"]),
+    ("Threads", ["Threads", "Fils", "Hilos"]),
+    ("Command", ["Befehl", "Commande", "Comando"]),
+    ("Exit", ["Exit-Code", "Code de sortie", "Código de salida"]),
+    ("Conflicts", ["Konflikte", "Conflits", "Conflictos"]),
+    ("Updated", ["Aktualisiert", "Mis à jour", "Actualizado"]),
+    ("Scanned", ["Geprüft", "Analysés", "Analizados"]),
+    ("Skipped", ["Übersprungen", "Ignorés", "Omitidos"]),
+    ("Succeeded", ["Erfolgreich", "Réussis", "Exitosos"]),
+    ("Failed", ["Fehlgeschlagen", "Échecs", "Fallidos"]),
+    ("Session", ["Sitzung", "Session", "Sesión"]),
+    ("Sessions", ["Sitzungen", "Sessions", "Sesiones"]),
+    ("Path", ["Pfad", "Chemin", "Ruta"]),
+    ("Files", ["Dateien", "Fichiers", "Archivos"]),
+    ("Size", ["Größe", "Taille", "Tamaño"]),
+    (
+        "Total size",
+        ["Gesamtgröße", "Taille totale", "Tamaño total"],
+    ),
+    (
+        "No background jobs found",
+        [
+            "Keine Hintergrundjobs gefunden",
+            "Aucune tâche en arrière-plan",
+            "No se encontraron trabajos en segundo plano",
+        ],
+    ),
+    (
+        "No scheduled jobs",
+        [
+            "Keine geplanten Jobs",
+            "Aucune tâche planifiée",
+            "No hay trabajos programados",
+        ],
+    ),
+    (
+        "No sessions recorded",
+        [
+            "Keine Sitzungen aufgezeichnet",
+            "Aucune session enregistrée",
+            "No hay sesiones registradas",
+        ],
+    ),
+    (
+        "No sessions or jobs recorded",
+        [
+            "Keine Sitzungen oder Jobs aufgezeichnet",
+            "Aucune session ni tâche enregistrée",
+            "No hay sesiones ni trabajos registrados",
+        ],
+    ),
+    (
+        "No archived plans",
+        [
+            "Keine archivierten Pläne",
+            "Aucun plan archivé",
+            "No hay planes archivados",
+        ],
+    ),
+    (
+        "Session logs are intact",
+        [
+            "Sitzungsprotokolle sind intakt",
+            "Les journaux de session sont intacts",
+            "Los registros de sesión están intactos",
+        ],
+    ),
+];
+
+/// English names for common language tags, used in the agent directive.
+const LANGUAGE_NAMES: &[(&str, &str)] = &[
+    ("de", "German"),
+    ("fr", "French"),
+    ("es", "Spanish"),
+    ("it", "Italian"),
+    ("pt", "Portuguese"),
+    ("pt-br", "Brazilian Portuguese"),
+    ("nl", "Dutch"),
+    ("sv", "Swedish"),
+    ("da", "Danish"),
+    ("nb", "Norwegian Bokmål"),
+    ("fi", "Finnish"),
+    ("pl", "Polish"),
+    ("cs", "Czech"),
+    ("tr", "Turkish"),
+    ("ru", "Russian"),
+    ("uk", "Ukrainian"),
+    ("ja", "Japanese"),
+    ("ko", "Korean"),
+    ("zh", "Chinese"),
+    ("zh-cn", "Simplified Chinese"),
+    ("zh-tw", "Traditional Chinese"),
+];
+
+/// Primary subtag of a language tag (`pt-br` -> `pt`).
+fn primary(language: &str) -> &str {
+    language.split('-').next().unwrap_or(language)
+}
+
+/// Translates `text` for `language`, falling back to the English text.
+pub fn translate<'a>(language: Option<&str>, text: &'a str) -> Cow<'a, str> {
+    let Some(column) = language
+        .map(primary)
+        .and_then(|code| CATALOG_LANGUAGES.iter().position(|known| *known == code))
+    else {
+        return Cow::Borrowed(text);
+    };
+    CATALOG
+        .iter()
+        .find(|(english, _)| *english == text)
+        .map(|(_, translations)| Cow::Borrowed(translations[column]))
+        .unwrap_or(Cow::Borrowed(text))
+}
+
+/// Translates `text` for the configured `[output] language`.
+pub fn tr(text: &str) -> Cow<'_, str> {
+    translate(config::output_language().as_deref(), text)
+}
+
+/// Thousands separator for numbers shown to humans in `language`.
+pub fn thousands_separator(language: Option<&str>) -> char {
+    match language.map(primary) {
+        Some("de" | "es" | "it" | "pt" | "nl" | "da" | "tr") => '.',
+        Some("fr" | "sv" | "nb" | "fi" | "pl" | "cs" | "ru" | "uk") => ' ',
+        _ => ',',
+    }
+}
+
+/// Human-readable name for a language tag, or the tag itself when unknown.
+pub fn language_name(language: &str) -> Cow<'_, str> {
+    LANGUAGE_NAMES
+        .iter()
+        .find(|(tag, _)| *tag == language)
+        .or_else(|| {
+            LANGUAGE_NAMES
+                .iter()
+                .find(|(tag, _)| *tag == primary(language))
+        })
+        .map(|(_, name)| Cow::Borrowed(*name))
+        .unwrap_or_else(|| Cow::Owned(format!("the language with tag `{language}`")))
+}
+
+/// Directive appended to agent prompts so replies and written documents use `language`.
+pub fn prompt_directive(language: &str) -> String {
+    let name = language_name(language);
+    format!(
+        "<outputLanguage>\nWrite your reply and any prose you produce (plans, critiques, review notes, narrative and commit message bodies) in {name}. Keep code, identifiers, file paths, shell commands, front-matter keys, and required section headings exactly as specified.\n</outputLanguage>"
+    )
+}
+
+/// Appends the `[output] language` directive to `prompt` when a language is configured.
+pub fn with_language_directive(prompt: String) -> String {
+    match config::output_language() {
+        Some(language) => format!("{}\n\n{}\n", prompt.trim_end(), prompt_directive(&language)),
+        None => prompt,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn catalog_translates_known_text_and_falls_back_to_english() {
+        assert_eq!(translate(Some("de"), "Outcome"), "Ergebnis");
+        assert_eq!(translate(Some("fr-ca"), "Outcome"), "Résultat");
+        assert_eq!(
+            translate(Some("de"), "Outcome: 3 plans"),
+            "Outcome: 3 plans"
+        );
+        assert_eq!(translate(Some("ja"), "Outcome"), "Outcome");
+        assert_eq!(translate(None, "Outcome"), "Outcome");
+        for (english, translations) in CATALOG {
+            assert!(
+                translations.iter().all(|text| !text.is_empty()),
+                "missing translation for {english}"
+            );
+        }
+    }
+
+    #[test]
+    fn prompt_directive_names_the_language() {
+        assert!(prompt_directive("de").contains("in German."));
+        assert!(prompt_directive("pt-br").contains("in Brazilian Portuguese."));
+        assert!(prompt_directive("de-at").contains("in German."));
+        assert!(prompt_directive("xx").contains("tag `xx`"));
+    }
+}
@@ -0,0 +1,518 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use git2::{Patch, Repository, Tree};
+use quote::ToTokens;
+use serde::Serialize;
+
+use crate::thread_owners;
+
+/// Files a branch changed under one subsystem (see [`subsystem_for`]).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct SubsystemImpact {
+    pub name: String,
+    pub files: Vec<String>,
+    pub insertions: usize,
+    pub deletions: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// One public Rust item whose signature the branch added, removed, or changed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ApiChange {
+    pub path: String,
+    /// `fn name`, `struct Name`, `Type::method`, ... qualified by inline modules.
+    pub item: String,
+    pub kind: ApiChangeKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+}
+
+/// Human-readable summary of what merging `branch` into `target` touches.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct ImpactReport {
+    pub branch: String,
+    pub target: String,
+    pub base: String,
+    pub tip: String,
+    pub subsystems: Vec<SubsystemImpact>,
+    pub api_changes: Vec<ApiChange>,
+    /// Changed Rust sources that failed to parse on either side; their API is not compared.
+    pub unparsed: Vec<String>,
+    pub test_files: Vec<String>,
+    pub source_files: Vec<String>,
+    pub threads: Vec<String>,
+}
+
+impl ImpactReport {
+    /// Test files touched per source file touched; `None` when no source file changed.
+    pub fn test_ratio(&self) -> Option<f64> {
+        (!self.source_files.is_empty())
+            .then(|| self.test_files.len() as f64 / self.source_files.len() as f64)
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = format!("# Impact report: {} -> {}\n", self.branch, self.target);
+
+        out.push_str("\n## Files by subsystem\n\n");
+        if self.subsystems.is_empty() {
+            out.push_str("- none\n");
+        }
+        for subsystem in &self.subsystems {
+            out.push_str(&format!(
+                "- {} ({} file(s), +{} -{})\n",
+                subsystem.name,
+                subsystem.files.len(),
+                subsystem.insertions,
+                subsystem.deletions
+            ));
+            for file in &subsystem.files {
+                out.push_str(&format!("  - {file}\n"));
+            }
+        }
+
+        out.push_str("\n## Public API changes\n\n");
+        if self.api_changes.is_empty() {
+            out.push_str("- none\n");
+        }
+        for change in &self.api_changes {
+            match change.kind {
+                ApiChangeKind::Added => out.push_str(&format!(
+                    "- added `{}` in {}\n",
+                    change.after.as_deref().unwrap_or(&change.item),
+                    change.path
+                )),
+                ApiChangeKind::Removed => out.push_str(&format!(
+                    "- removed `{}` in {}\n",
+                    change.before.as_deref().unwrap_or(&change.item),
+                    change.path
+                )),
+                ApiChangeKind::Changed => out.push_str(&format!(
+                    "- changed `{}` in {}\n  - before: `{}`\n  - after: `{}`\n",
+                    change.item,
+                    change.path,
+                    change.before.as_deref().unwrap_or_default(),
+                    change.after.as_deref().unwrap_or_default()
+                )),
+            }
+        }
+        for path in &self.unparsed {
+            out.push_str(&format!("- not compared (parse failed): {path}\n"));
+        }
+
+        out.push_str("\n## Tests\n\n");
+        let ratio = self
+            .test_ratio()
+            .map(|ratio| format!("{ratio:.2}"))
+            .unwrap_or_else(|| "n/a".to_string());
+        out.push_str(&format!(
+            "{} test file(s) / {} source file(s) touched (ratio {ratio})\n",
+            self.test_files.len(),
+            self.source_files.len()
+        ));
+
+        out.push_str("\n## Narrative threads\n\n");
+        if self.threads.is_empty() {
+            out.push_str("- none\n");
+        }
+        for thread in &self.threads {
+            out.push_str(&format!("- {thread}\n"));
+        }
+        out
+    }
+}
+
+/// Builds the impact report for merging `branch` into `target`, diffing from their merge base.
+/// `plan_document` contributes the threads its `threads:` front matter links.
+pub fn impact_report(
+    repo_path: &Path,
+    target: &str,
+    branch: &str,
+    plan_document: Option<&str>,
+) -> Result<ImpactReport, git2::Error> {
+    let repo = Repository::discover(repo_path)?;
+    let tip = repo.revparse_single(branch)?.peel_to_commit()?;
+    let target_commit = repo.revparse_single(target)?.peel_to_commit()?;
+    let base = repo.find_commit(repo.merge_base(target_commit.id(), tip.id())?)?;
+    let (base_tree, tip_tree) = (base.tree()?, tip.tree()?);
+    let diff = repo.diff_tree_to_tree(Some(&base_tree), Some(&tip_tree), None)?;
+
+    let mut report = ImpactReport {
+        branch: branch.to_string(),
+        target: target.to_string(),
+        base: base.id().to_string(),
+        tip: tip.id().to_string(),
+        threads: thread_owners::affected_thread_names(repo_path, target, branch, plan_document)?,
+        ..ImpactReport::default()
+    };
+    let mut subsystems: BTreeMap<String, SubsystemImpact> = BTreeMap::new();
+    for (index, delta) in diff.deltas().enumerate() {
+        let Some(path) = delta
+            .new_file()
+            .path()
+            .or_else(|| delta.old_file().path())
+            .map(|path| path.to_string_lossy().replace('\\', "/"))
+        else {
+            continue;
+        };
+        let (insertions, deletions) = match Patch::from_diff(&diff, index)? {
+            Some(patch) => {
+                let (_, insertions, deletions) = patch.line_stats()?;
+                (insertions, deletions)
+            }
+            None => (0, 0),
+        };
+        let name = subsystem_for(&path);
+        let entry = subsystems
+            .entry(name.clone())
+            .or_insert_with(|| SubsystemImpact {
+                name,
+                ..SubsystemImpact::default()
+            });
+        entry.files.push(path.clone());
+        entry.insertions += insertions;
+        entry.deletions += deletions;
+
+        if is_test_path(&path) {
+            report.test_files.push(path.clone());
+        } else if is_source_path(&path) {
+            report.source_files.push(path.clone());
+            if path.ends_with(".rs") {
+                let before = read_blob(&repo, &base_tree, delta.old_file().path());
+                let after = read_blob(&repo, &tip_tree, delta.new_file().path());
+                match api_changes(&path, before.as_deref(), after.as_deref()) {
+                    Some(changes) => report.api_changes.extend(changes),
+                    None => report.unparsed.push(path.clone()),
+                }
+            }
+        }
+    }
+    report.subsystems = subsystems.into_values().collect();
+    Ok(report)
+}
+
+/// Groups a path by where it lives: `<crate>/src/<module>/...` becomes `<crate>/<module>`,
+/// files directly under `<crate>/src/` become `<crate>`, and anything else is keyed by its first
+/// directory (`(root)` for top-level files).
+pub fn subsystem_for(path: &str) -> String {
+    let parts = path.split('/').collect::<Vec<_>>();
+    if let Some(src) = parts.iter().position(|part| *part == "src") {
+        let prefix = parts[..src].join("/");
+        let rest = &parts[src + 1..];
+        return match (prefix.is_empty(), rest.len() > 1) {
+            (true, true) => rest[0].to_string(),
+            (true, false) => "src".to_string(),
+            (false, true) => format!("{prefix}/{}", rest[0]),
+            (false, false) => prefix,
+        };
+    }
+    match parts.as_slice() {
+        [_] => "(root)".to_string(),
+        [first, ..] => first.to_string(),
+        [] => "(root)".to_string(),
+    }
+}
+
+fn is_test_path(path: &str) -> bool {
+    let file = path.rsplit('/').next().unwrap_or(path);
+    path.starts_with("tests/")
+        || path.contains("/tests/")
+        || file == "tests.rs"
+        || file.ends_with("_test.rs")
+        || file.ends_with("_tests.rs")
+        || file.starts_with("test_")
+}
+
+fn is_source_path(path: &str) -> bool {
+    !(path.starts_with(".vizier/")
+        || path.starts_with("docs/")
+        || path.ends_with(".md")
+        || path.ends_with(".lock"))
+}
+
+fn read_blob(repo: &Repository, tree: &Tree<'_>, path: Option<&Path>) -> Option<String> {
+    let entry = tree.get_path(path?).ok()?;
+    let blob = repo.find_blob(entry.id()).ok()?;
+    Some(String::from_utf8_lossy(blob.content()).into_owned())
+}
+
+/// Public-item signature changes between two versions of a Rust file; `None` when either side
+/// fails to parse.
+fn api_changes(path: &str, before: Option<&str>, after: Option<&str>) -> Option<Vec<ApiChange>> {
+    let before = match before {
+        Some(source) => public_api(source)?,
+        None => BTreeMap::new(),
+    };
+    let after = match after {
+        Some(source) => public_api(source)?,
+        None => BTreeMap::new(),
+    };
+    let mut changes = Vec::new();
+    for (item, signature) in &after {
+        let kind = match before.get(item) {
+            None => ApiChangeKind::Added,
+            Some(old) if old != signature => ApiChangeKind::Changed,
+            Some(_) => continue,
+        };
+        changes.push(ApiChange {
+            path: path.to_string(),
+            item: item.clone(),
+            kind,
+            before: before.get(item).cloned(),
+            after: Some(signature.clone()),
+        });
+    }
+    for (item, signature) in &before {
+        if !after.contains_key(item) {
+            changes.push(ApiChange {
+                path: path.to_string(),
+                item: item.clone(),
+                kind: ApiChangeKind::Removed,
+                before: Some(signature.clone()),
+                after: None,
+            });
+        }
+    }
+    Some(changes)
+}
+
+/// `pub` items of a Rust source file keyed by item name, valued by a signature without bodies,
+/// docs, or private fields.
+fn public_api(source: &str) -> Option<BTreeMap<String, String>> {
+    let file = syn::parse_file(source).ok()?;
+    let mut api = BTreeMap::new();
+    collect_public_items(&file.items, "", &mut api);
+    Some(api)
+}
+
+fn collect_public_items(items: &[syn::Item], prefix: &str, api: &mut BTreeMap<String, String>) {
+    let public = |vis: &syn::Visibility| matches!(vis, syn::Visibility::Public(_));
+    let tokens = |item: &dyn ToTokens| item.to_token_stream().to_string();
+    for item in items {
+        match item {
+            syn::Item::Fn(item) if public(&item.vis) => {
+                let name = format!("fn {prefix}{}", item.sig.ident);
+                api.insert(name, format!("pub {}", tokens(&item.sig)));
+            }
+            syn::Item::Struct(item) if public(&item.vis) => {
+                let fields = item
+                    .fields
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, field)| public(&field.vis))
+                    .map(|(index, field)| match field.ident.as_ref() {
+                        Some(ident) => format!("pub {ident}: {}", tokens(&field.ty)),
+                        None => format!("pub {index}: {}", tokens(&field.ty)),
+                    })
+                    .collect::<Vec<_>>();
+                api.insert(
+                    format!("struct {prefix}{}", item.ident),
+                    format!(
+                        "pub struct {}{} {{ {} }}",
+                        item.ident,
+                        tokens(&item.generics),
+                        fields.join(", ")
+                    ),
+                );
+            }
+            syn::Item::Enum(item) if public(&item.vis) => {
+                let mut item = item.clone();
+                item.attrs.clear();
+                for variant in &mut item.variants {
+                    variant.attrs.clear();
+                    for field in variant.fields.iter_mut() {
+                        field.attrs.clear();
+                    }
+                }
+                api.insert(format!("enum {prefix}{}", item.ident), tokens(&item));
+            }
+            syn::Item::Trait(item) if public(&item.vis) => {
+                let mut item = item.clone();
+                item.attrs.clear();
+                for trait_item in &mut item.items {
+                    match trait_item {
+                        syn::TraitItem::Fn(method) => {
+                            method.attrs.clear();
+                            method.default = None;
+                        }
+                        syn::TraitItem::Const(constant) => {
+                            constant.attrs.clear();
+                            constant.default = None;
+                        }
+                        syn::TraitItem::Type(ty) => {
+                            ty.attrs.clear();
+                            ty.default = None;
+                        }
+                        _ => {}
+                    }
+                }
+                api.insert(format!("trait {prefix}{}", item.ident), tokens(&item));
+            }
+            syn::Item::Const(item) if public(&item.vis) => {
+                api.insert(
+                    format!("const {prefix}{}", item.ident),
+                    format!("pub const {}: {}", item.ident, tokens(&item.ty)),
+                );
+            }
+            syn::Item::Static(item) if public(&item.vis) => {
+                api.insert(
+                    format!("static {prefix}{}", item.ident),
+                    format!("pub static {}: {}", item.ident, tokens(&item.ty)),
+                );
+            }
+            syn::Item::Type(item) if public(&item.vis) => {
+                let mut item = item.clone();
+                item.attrs.clear();
+                api.insert(format!("type {prefix}{}", item.ident), tokens(&item));
+            }
+            syn::Item::Use(item) if public(&item.vis) => {
+                let mut item = item.clone();
+                item.attrs.clear();
+                let signature = tokens(&item);
+                api.insert(format!("use {prefix}{}", tokens(&item.tree)), signature);
+            }
+            syn::Item::Mod(item) if public(&item.vis) => match item.content.as_ref() {
+                Some((_, items)) => {
+                    collect_public_items(items, &format!("{prefix}{}::", item.ident), api)
+                }
+                None => {
+                    api.insert(
+                        format!("mod {prefix}{}", item.ident),
+                        format!("pub mod {}", item.ident),
+                    );
+                }
+            },
+            syn::Item::Impl(item) if item.trait_.is_none() => {
+                let self_ty = tokens(&item.self_ty);
+                for impl_item in &item.items {
+                    if let syn::ImplItem::Fn(method) = impl_item
+                        && public(&method.vis)
+                    {
+                        api.insert(
+                            format!("{prefix}{self_ty}::{}", method.sig.ident),
+                            format!("pub {}", tokens(&method.sig)),
+                        );
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn commit_files(
+        repo: &Repository,
+        refname: &str,
+        parent: Option<git2::Oid>,
+        files: &[(&str, &str)],
+    ) -> git2::Oid {
+        let root = repo.workdir().expect("workdir");
+        let mut index = repo.index().expect("index");
+        for (path, contents) in files {
+            let abs = root.join(path);
+            fs::create_dir_all(abs.parent().expect("parent")).expect("dirs");
+            fs::write(&abs, contents).expect("write");
+            index.add_path(Path::new(path)).expect("add");
+        }
+        let tree = repo
+            .find_tree(index.write_tree().expect("tree"))
+            .expect("find tree");
+        let sig = git2::Signature::now("vizier", "vizier@example.com").expect("sig");
+        let parents = parent
+            .map(|oid| repo.find_commit(oid).expect("parent"))
+            .into_iter()
+            .collect::<Vec<_>>();
+        let parents = parents.iter().collect::<Vec<_>>();
+        repo.commit(Some(refname), &sig, &sig, "commit", &tree, &parents)
+            .expect("commit")
+    }
+
+    #[test]
+    fn subsystems_group_crate_modules() {
+        assert_eq!(
+            subsystem_for("vizier-core/src/jobs/mod.rs"),
+            "vizier-core/jobs"
+        );
+        assert_eq!(subsystem_for("vizier-core/src/display.rs"), "vizier-core");
+        assert_eq!(subsystem_for("src/parser/lex.rs"), "parser");
+        assert_eq!(subsystem_for("src/lib.rs"), "src");
+        assert_eq!(subsystem_for("docs/user/index.md"), "docs");
+        assert_eq!(subsystem_for("Cargo.toml"), "(root)");
+    }
+
+    #[test]
+    fn impact_report_diffs_public_api_tests_and_threads() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let repo = Repository::init(dir.path()).expect("init");
+        let base = commit_files(
+            &repo,
+            "refs/heads/main",
+            None,
+            &[
+                (
+                    "core/src/lib.rs",
+                    "pub fn parse(input: &str) -> u32 { 0 }\npub fn gone() {}\nfn private() {}\npub struct Config { pub name: String, secret: u8 }\n",
+                ),
+                (".vizier/narrative/threads/parser.md", "# Parser\n"),
+            ],
+        );
+        commit_files(
+            &repo,
+            "refs/heads/draft/alpha",
+            Some(base),
+            &[
+                (
+                    "core/src/lib.rs",
+                    "/// Parses.\npub fn parse(input: &str, strict: bool) -> u32 { 1 }\nfn private() { let _ = 1; }\npub struct Config { pub name: String, secret: u16 }\nimpl Config { pub fn new() -> Self { todo!() } }\n",
+                ),
+                ("core/tests/parse.rs", "#[test]\nfn parses() {}\n"),
+                (".vizier/narrative/threads/parser.md", "# Parser v2\n"),
+            ],
+        );
+
+        let report = impact_report(dir.path(), "main", "draft/alpha", None).expect("report");
+        assert_eq!(
+            report
+                .subsystems
+                .iter()
+                .map(|subsystem| subsystem.name.as_str())
+                .collect::<Vec<_>>(),
+            vec![".vizier", "core"]
+        );
+        assert_eq!(
+            report
+                .api_changes
+                .iter()
+                .map(|change| (change.item.as_str(), change.kind))
+                .collect::<Vec<_>>(),
+            vec![
+                ("Config::new", ApiChangeKind::Added),
+                ("fn parse", ApiChangeKind::Changed),
+                ("fn gone", ApiChangeKind::Removed),
+            ]
+        );
+        assert_eq!(report.test_files, vec!["core/tests/parse.rs"]);
+        assert_eq!(report.source_files, vec!["core/src/lib.rs"]);
+        assert_eq!(report.test_ratio(), Some(1.0));
+        assert_eq!(report.threads, vec!["parser"]);
+
+        let rendered = report.render();
+        assert!(rendered.contains("- core (2 file(s), +"));
+        assert!(rendered.contains("- removed `pub fn gone ()` in core/src/lib.rs"));
+        assert!(rendered.contains("1 test file(s) / 1 source file(s) touched (ratio 1.00)"));
+    }
+}
@@ -0,0 +1,91 @@
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use lazy_static::lazy_static;
+use tokio::sync::Notify;
+
+use crate::display;
+
+pub use vizier_kernel::audit::INTERRUPTED_EXIT_CODE;
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+lazy_static! {
+    static ref INTERRUPT_NOTIFY: Notify = Notify::new();
+}
+
+/// Routes SIGINT/SIGTERM into a process-wide interrupt flag so long-running commands can stop
+/// their agent, clean up git state, and exit with [`INTERRUPTED_EXIT_CODE`]. A second signal
+/// exits immediately. Must be called from within a Tokio runtime.
+pub fn install() {
+    tokio::spawn(async {
+        #[cfg(unix)]
+        let mut terminate =
+            match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+                Ok(signal) => Some(signal),
+                Err(err) => {
+                    display::debug(format!("unable to listen for SIGTERM: {err}"));
+                    None
+                }
+            };
+
+        loop {
+            #[cfg(unix)]
+            {
+                let term = async {
+                    match terminate.as_mut() {
+                        Some(signal) => {
+                            signal.recv().await;
+                        }
+                        None => std::future::pending::<()>().await,
+                    }
+                };
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = term => {}
+                }
+            }
+            #[cfg(not(unix))]
+            if tokio::signal::ctrl_c().await.is_err() {
+                return;
+            }
+
+            if INTERRUPTED.swap(true, Ordering::SeqCst) {
+                std::process::exit(INTERRUPTED_EXIT_CODE);
+            }
+            display::warn(
+                "Interrupt received; stopping the agent and cleaning up (signal again to exit immediately)",
+            );
+            INTERRUPT_NOTIFY.notify_waiters();
+        }
+    });
+}
+
+pub fn is_interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+/// Resolves once the process has been interrupted; usable from any runtime or thread.
+pub async fn interrupted() {
+    loop {
+        let notified = INTERRUPT_NOTIFY.notified();
+        tokio::pin!(notified);
+        notified.as_mut().enable();
+        if is_interrupted() {
+            return;
+        }
+        notified.await;
+    }
+}
+
+/// Sends SIGTERM to every process in the group led by `pid` (children spawned with
+/// `process_group(0)` on Unix), so wrappers like `script`/`stdbuf` do not outlive the agent.
+pub fn terminate_process_group(pid: u32) {
+    let _ = Command::new("kill")
+        .arg("-TERM")
+        .arg("--")
+        .arg(format!("-{pid}"))
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+}
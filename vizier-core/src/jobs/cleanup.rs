@@ -150,6 +150,14 @@ pub struct RetryOutcome {
     pub updated: Vec<String>,
 }
 
+#[derive(Debug, Clone, Default)]
+pub struct RetryOptions {
+    /// Re-enter the retry set on the recorded worktree instead of cleaning it up first.
+    pub keep_worktree: bool,
+    /// Operator guidance appended to agent prompts for every job in the retry set.
+    pub note: Option<String>,
+}
+
 /// Retry contract:
 /// - `retry_root` is the requested job id.
 /// - `last_successful_point` is the set of direct predecessors currently succeeded.
@@ -161,7 +169,32 @@ pub fn retry_job(
     binary: &Path,
     requested_job_id: &str,
 ) -> Result<RetryOutcome, Box<dyn std::error::Error>> {
-    retry_job_internal(project_root, jobs_root, binary, requested_job_id, None)
+    retry_job_with_options(
+        project_root,
+        jobs_root,
+        binary,
+        requested_job_id,
+        &RetryOptions::default(),
+    )
+}
+
+pub fn retry_job_with_options(
+    project_root: &Path,
+    jobs_root: &Path,
+    binary: &Path,
+    requested_job_id: &str,
+    options: &RetryOptions,
+) -> Result<RetryOutcome, Box<dyn std::error::Error>> {
+    let _lock = SchedulerLock::acquire(jobs_root)?;
+    retry_job_internal_locked(
+        project_root,
+        jobs_root,
+        binary,
+        requested_job_id,
+        None,
+        true,
+        options,
+    )
 }
 
 pub(crate) fn retry_job_internal(
@@ -179,6 +212,7 @@ pub(crate) fn retry_job_internal(
         requested_job_id,
         propagated_context,
         true,
+        &RetryOptions::default(),
     )
 }
 
@@ -189,12 +223,16 @@ pub(crate) fn retry_job_internal_locked(
     requested_job_id: &str,
     propagated_context: Option<&WorkflowExecutionContext>,
     advance_scheduler: bool,
+    options: &RetryOptions,
 ) -> Result<RetryOutcome, Box<dyn std::error::Error>> {
     let records = list_records(jobs_root)?;
     let graph = ScheduleGraph::new(records);
 
-    if graph.record(requested_job_id).is_none() {
+    let Some(root_record) = graph.record(requested_job_id) else {
         return Err(format!("no background job {}", requested_job_id).into());
+    };
+    if options.keep_worktree {
+        ensure_retry_worktree_present(project_root, root_record)?;
     }
 
     let retry_root = requested_job_id.to_string();
@@ -242,7 +280,7 @@ pub(crate) fn retry_job_internal_locked(
     for job_id in &retry_set {
         let paths = paths_for(jobs_root, job_id);
         let mut record = load_record(&paths)?;
-        rewind_job_record_for_retry(project_root, jobs_root, &mut record)?;
+        rewind_job_record_for_retry(project_root, jobs_root, &mut record, options)?;
         persist_record(&paths, &record)?;
         reset.push(job_id.clone());
     }
@@ -398,7 +436,7 @@ pub(crate) fn remove_merge_sentinel_files(
         return Ok(());
     }
 
-    let sentinel_root = project_root.join(".vizier/tmp/merge-conflicts");
+    let sentinel_root = project_root.join(crate::temp_registry::MERGE_CONFLICTS_DIR);
     for slug in slugs {
         let sentinel = sentinel_root.join(format!("{slug}.json"));
         remove_file_if_exists(&sentinel)?;
@@ -426,21 +464,56 @@ pub(crate) fn truncate_log(path: &Path) -> io::Result<()> {
     File::create(path).map(|_| ())
 }
 
+fn ensure_retry_worktree_present(
+    project_root: &Path,
+    record: &JobRecord,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let recorded = record
+        .metadata
+        .as_ref()
+        .and_then(|metadata| metadata.worktree_path.as_deref())
+        .ok_or_else(|| {
+            format!(
+                "job {} has no recorded worktree to continue in; retry without --continue",
+                record.id
+            )
+        })?;
+    let worktree_path = resolve_recorded_path(project_root, recorded);
+    if !worktree_path.is_dir() {
+        return Err(format!(
+            "recorded worktree for job {} no longer exists ({}); retry without --continue",
+            record.id,
+            worktree_path.display()
+        )
+        .into());
+    }
+    Ok(())
+}
+
 pub(crate) fn rewind_job_record_for_retry(
     project_root: &Path,
     jobs_root: &Path,
     record: &mut JobRecord,
+    options: &RetryOptions,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let retry_cleanup = attempt_retry_cleanup(project_root, record);
+    let retry_cleanup = if options.keep_worktree {
+        RetryCleanupResult::preserved()
+    } else {
+        attempt_retry_cleanup(project_root, record)
+    };
     if retry_cleanup.status == RetryCleanupStatus::Degraded {
         let detail = retry_cleanup
             .detail
             .as_deref()
             .unwrap_or("retry cleanup degraded");
-        display::warn(format!(
-            "retry cleanup degraded for {}: {}; worktree metadata retained for future cleanup",
-            record.id, detail
-        ));
+        display::log(
+            display::Subsystem::Scheduler,
+            display::LogLevel::Warn,
+            format!(
+                "retry cleanup degraded for {}: {}; worktree metadata retained for future cleanup",
+                record.id, detail
+            ),
+        );
     }
 
     let paths = paths_for(jobs_root, &record.id);
@@ -492,6 +565,7 @@ pub(crate) fn rewind_job_record_for_retry(
         metadata.cancel_cleanup_error = None;
         metadata.retry_cleanup_status = Some(retry_cleanup.status);
         metadata.retry_cleanup_error = retry_cleanup.detail.clone();
+        metadata.operator_note = options.note.clone();
     }
 
     record.status = JobStatus::Queued;
@@ -711,9 +785,11 @@ pub fn record_current_job_worktree(
     let jobs_root = match ensure_jobs_root(project_root) {
         Ok(path) => path,
         Err(err) => {
-            display::warn(format!(
-                "unable to ensure jobs root for worktree recording: {err}"
-            ));
+            display::log(
+                display::Subsystem::Scheduler,
+                display::LogLevel::Warn,
+                format!("unable to ensure jobs root for worktree recording: {err}"),
+            );
             return;
         }
     };
@@ -725,10 +801,14 @@ pub fn record_current_job_worktree(
         worktree_name,
         worktree_path,
     ) {
-        display::warn(format!(
-            "unable to record worktree metadata for job {}: {err}",
-            job_id
-        ));
+        display::log(
+            display::Subsystem::Scheduler,
+            display::LogLevel::Warn,
+            format!(
+                "unable to record worktree metadata for job {}: {err}",
+                job_id
+            ),
+        );
     }
 }
 
@@ -771,6 +851,89 @@ pub(crate) fn attempt_cancel_cleanup(
     }
 }
 
+/// Cleanup for a workflow node stopped by SIGINT/SIGTERM: abandons any merge or cherry-pick the
+/// node left half-applied, then removes its owned worktree, or preserves it (with the reason
+/// recorded) when the interrupted agent left uncommitted edits behind for `vizier rescue`.
+/// `abort_in_project_root` is false when the checkout already had an operation in progress
+/// before the node started, so the operator's own state is never reset.
+pub(crate) fn attempt_interrupt_cleanup(
+    project_root: &Path,
+    record: &JobRecord,
+    abort_in_project_root: bool,
+) -> CancelCleanupResult {
+    let worktree = record.metadata.as_ref().and_then(|metadata| {
+        (metadata.worktree_owned == Some(true))
+            .then_some(metadata.worktree_path.as_deref())
+            .flatten()
+            .map(|path| {
+                (
+                    resolve_recorded_path(project_root, path),
+                    metadata.worktree_name.clone(),
+                )
+            })
+    });
+
+    let execution_root = match &worktree {
+        Some((path, _)) => Some(path.clone()),
+        None => abort_in_project_root.then(|| project_root.to_path_buf()),
+    };
+    if let Some(root) = execution_root.filter(|root| root.exists())
+        && let Err(err) = crate::vcs::abort_in_progress_operation_in(&root)
+    {
+        return CancelCleanupResult {
+            status: CancelCleanupStatus::Failed,
+            error: Some(format!(
+                "unable to abort the in-progress operation in {}: {err}",
+                root.display()
+            )),
+        };
+    }
+
+    let Some((worktree_path, worktree_name)) = worktree else {
+        return CancelCleanupResult::skipped();
+    };
+    if !worktree_path.exists() {
+        return CancelCleanupResult::skipped();
+    }
+    if !worktree_safe_to_remove(project_root, &worktree_path, worktree_name.as_deref()) {
+        return CancelCleanupResult::preserved(format!(
+            "worktree {} is not a Vizier temp worktree",
+            worktree_path.display()
+        ));
+    }
+
+    let dirty = Repository::open(&worktree_path)
+        .and_then(|repo| {
+            let mut options = git2::StatusOptions::new();
+            options.include_untracked(true);
+            repo.statuses(Some(&mut options))
+                .map(|statuses| !statuses.is_empty())
+        })
+        .unwrap_or(true);
+    if dirty {
+        let plan = record
+            .metadata
+            .as_ref()
+            .and_then(|metadata| metadata.plan.as_deref())
+            .unwrap_or("<plan>");
+        return CancelCleanupResult::preserved(format!(
+            "interrupted with uncommitted changes in {}; inspect them with `vizier rescue {plan}`",
+            worktree_path.display()
+        ));
+    }
+
+    match cleanup_worktree(project_root, &worktree_path, worktree_name.as_deref()) {
+        Ok(()) => CancelCleanupResult {
+            status: CancelCleanupStatus::Done,
+            error: None,
+        },
+        Err(err) => CancelCleanupResult {
+            status: CancelCleanupStatus::Failed,
+            error: Some(err),
+        },
+    }
+}
+
 pub(crate) fn attempt_retry_cleanup(project_root: &Path, record: &JobRecord) -> RetryCleanupResult {
     let Some(metadata) = record.metadata.as_ref() else {
         return RetryCleanupResult::skipped();
@@ -815,8 +978,7 @@ pub(crate) fn worktree_safe_to_remove(
     worktree_path: &Path,
     worktree_name: Option<&str>,
 ) -> bool {
-    let tmp_root = project_root.join(".vizier/tmp-worktrees");
-    if !worktree_path.starts_with(&tmp_root) {
+    if !crate::worktrees::is_managed_worktree_path(project_root, worktree_path) {
         return false;
     }
 
@@ -1039,7 +1201,11 @@ pub fn clean_job_scope(
     }
     if options.force {
         for reason in &bypassable_reasons {
-            display::warn(format!("--force: {reason}"));
+            display::log(
+                display::Subsystem::Scheduler,
+                display::LogLevel::Warn,
+                format!("--force: {reason}"),
+            );
         }
     }
 
@@ -1969,7 +2135,7 @@ pub(crate) fn delete_scoped_branches(
         .iter()
         .map(|branch| branch.trim())
         .filter(|branch| !branch.is_empty())
-        .filter(|branch| branch.starts_with("draft/"))
+        .filter(|branch| crate::plan::slug_from_branch(branch).is_some())
         .map(|branch| branch.to_string())
         .collect::<Vec<_>>();
     candidates.sort();
@@ -19,7 +19,7 @@ use crate::workflow_template::{
 };
 use crate::{
     agent::{AgentError, AgentRequest, DEFAULT_AGENT_TIMEOUT},
-    config, display,
+    atomic_file, config, display, interrupt,
 };
 use chrono::{DateTime, Duration, Utc};
 use git2::{ErrorCode, Oid, Repository, WorktreePruneOptions};
@@ -31,12 +31,9 @@ use std::{
     io::{self, Read, Seek, SeekFrom, Write},
     path::{Path, PathBuf},
     process::{Command, Stdio},
-    sync::{
-        Mutex, OnceLock,
-        atomic::{AtomicU64, Ordering as AtomicOrdering},
-    },
+    sync::{Mutex, OnceLock},
     thread,
-    time::{Duration as StdDuration, SystemTime, UNIX_EPOCH},
+    time::{Duration as StdDuration, SystemTime},
 };
 
 const PLAN_TEXT_ARTIFACT_TYPE_ID: &str = "plan_text";
@@ -79,6 +76,13 @@ pub struct JobSchedule {
     pub wait_reason: Option<JobWaitReason>,
     #[serde(default)]
     pub waited_on: Vec<JobWaitKind>,
+    /// Higher-priority jobs leave the queue first; ties keep submission order.
+    #[serde(default, skip_serializing_if = "is_zero_priority")]
+    pub priority: i32,
+}
+
+fn is_zero_priority(priority: &i32) -> bool {
+    *priority == 0
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -233,6 +237,34 @@ pub struct JobMetadata {
     pub cancel_cleanup_error: Option<String>,
     pub retry_cleanup_status: Option<RetryCleanupStatus>,
     pub retry_cleanup_error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operator_note: Option<String>,
+    /// Documents passed with `vizier run --context`, as absolute paths.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_files: Option<Vec<String>>,
+    /// Commit passed with `vizier run --baseline`; review diffs start here instead of the target.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub review_baseline: Option<String>,
+    /// `patch` when enqueued with `vizier run --patch`: commit nodes write a patch instead.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit_disposition: Option<crate::auditor::CommitDisposition>,
+    /// Estimate shown before `vizier run approve` enqueued this job.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_estimate: Option<crate::cost::CostEstimate>,
+    /// Measured prompt/response size of the agent run, recorded when an estimate exists.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_actual: Option<crate::cost::CostActual>,
+    /// `false` when enqueued with `vizier run --no-gen-tests`; unset defers to
+    /// `[approve] generate_tests`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generate_tests: Option<bool>,
+    /// Set by `vizier run --fix-loop`; `agent.review_fix_loop` nodes are inert without it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub review_fix_loop: Option<bool>,
+    /// Set by `vizier run --edit-plan` on `plan.persist` jobs; the following CLI opens the
+    /// drafted plan in an editor while the job waits on approval.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plan_edit: Option<bool>,
     pub process_liveness_state: Option<ProcessLivenessState>,
     pub process_liveness_checked_at: Option<DateTime<Utc>>,
     pub process_liveness_failure_reason: Option<String>,
@@ -302,6 +334,7 @@ pub enum CancelCleanupStatus {
     Skipped,
     Done,
     Failed,
+    Preserved,
 }
 
 impl CancelCleanupStatus {
@@ -310,6 +343,7 @@ impl CancelCleanupStatus {
             CancelCleanupStatus::Skipped => "skipped",
             CancelCleanupStatus::Done => "done",
             CancelCleanupStatus::Failed => "failed",
+            CancelCleanupStatus::Preserved => "preserved",
         }
     }
 }
@@ -327,6 +361,13 @@ impl CancelCleanupResult {
             error: None,
         }
     }
+
+    fn preserved(reason: impl Into<String>) -> Self {
+        Self {
+            status: CancelCleanupStatus::Preserved,
+            error: Some(reason.into()),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
@@ -335,6 +376,7 @@ pub enum RetryCleanupStatus {
     Skipped,
     Done,
     Degraded,
+    Preserved,
 }
 
 impl RetryCleanupStatus {
@@ -343,6 +385,7 @@ impl RetryCleanupStatus {
             RetryCleanupStatus::Skipped => "skipped",
             RetryCleanupStatus::Done => "done",
             RetryCleanupStatus::Degraded => "degraded",
+            RetryCleanupStatus::Preserved => "preserved",
         }
     }
 }
@@ -375,8 +418,18 @@ impl RetryCleanupResult {
         }
     }
 
+    fn preserved() -> Self {
+        Self {
+            status: RetryCleanupStatus::Preserved,
+            detail: None,
+        }
+    }
+
     fn should_clear_worktree_metadata(&self) -> bool {
-        !matches!(self.status, RetryCleanupStatus::Degraded)
+        !matches!(
+            self.status,
+            RetryCleanupStatus::Degraded | RetryCleanupStatus::Preserved
+        )
     }
 }
 
@@ -386,6 +439,7 @@ mod logs;
 mod monitor;
 mod scheduler;
 #[cfg(test)]
+#[allow(clippy::unnecessary_sort_by)]
 mod tests;
 mod workflow;
 
@@ -402,27 +456,29 @@ use scheduler::*;
 #[allow(unused_imports)]
 use workflow::*;
 
+pub(crate) use cleanup::pid_is_running;
 pub use cleanup::{
     CancelJobOutcome, CleanJobError, CleanJobErrorKind, CleanJobOptions, CleanJobOutcome,
-    CleanRemovedCounts, CleanScope, CleanSkippedItems, RetryOutcome, approve_job,
+    CleanRemovedCounts, CleanScope, CleanSkippedItems, RetryOptions, RetryOutcome, approve_job,
     cancel_job_with_cleanup, clean_job_scope, gc_jobs, record_current_job_worktree,
-    record_job_worktree, reject_job, retry_job,
+    record_job_worktree, reject_job, retry_job, retry_job_with_options,
 };
 pub use graph::ScheduleGraph;
 pub use logs::{follow_job_logs_raw, latest_job_log_line, tail_job_logs};
 pub use monitor::*;
 pub use scheduler::{
-    EphemeralRunCleanupEvent, SchedulerOutcome, scheduler_tick,
-    scheduler_tick_without_ephemeral_cleanup,
+    EphemeralRunCleanupEvent, QueueSlot, SchedulerOutcome, queue_slots, scheduler_tick,
+    scheduler_tick_without_ephemeral_cleanup, sort_by_queue_order,
 };
 pub use workflow::{
-    EnqueueWorkflowRunResult, WorkflowRunEnqueueOptions, audit_workflow_run_template,
-    enqueue_workflow_run, enqueue_workflow_run_with_options, run_workflow_node_command,
-    validate_workflow_run_template,
+    BranchVerification, EnqueueWorkflowRunResult, VerifyGateRun, WorkflowRunEnqueueOptions,
+    audit_workflow_run_template, enqueue_workflow_run, enqueue_workflow_run_with_options,
+    job_execution_root, resolve_conflicts_with_merge_agent, run_merge_gates_on_branch,
+    run_workflow_node_command, validate_workflow_run_template, verification_configured,
+    verify_branch,
 };
 
 static CURRENT_JOB_ID: OnceLock<Mutex<Option<String>>> = OnceLock::new();
-static RECORD_TMP_NONCE: AtomicU64 = AtomicU64::new(0);
 
 fn current_job_id_state() -> &'static Mutex<Option<String>> {
     CURRENT_JOB_ID.get_or_init(|| Mutex::new(None))
@@ -533,10 +589,112 @@ pub fn paths_for(jobs_root: &Path, job_id: &str) -> JobPaths {
     }
 }
 
+/// Where `vizier run --edit-plan` leaves the operator's edited plan for a `plan.persist` job.
+pub fn plan_amendment_path(jobs_root: &Path, job_id: &str) -> PathBuf {
+    jobs_root.join(job_id).join("plan-amendment.md")
+}
+
+/// The agent-drafted plan a waiting `plan.persist` job will write, read from its `plan_text`
+/// dependency; `None` when the job takes its plan from somewhere else.
+pub fn drafted_plan_text(
+    project_root: &Path,
+    jobs_root: &Path,
+    job_id: &str,
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    let record = read_record(jobs_root, job_id)?;
+    let Some((type_id, key)) = record.schedule.as_ref().and_then(|schedule| {
+        schedule
+            .dependencies
+            .iter()
+            .find_map(|dependency| match &dependency.artifact {
+                JobArtifact::Custom { type_id, key } if type_id == PLAN_TEXT_ARTIFACT_TYPE_ID => {
+                    Some((type_id.clone(), key.clone()))
+                }
+                _ => None,
+            })
+    }) else {
+        return Ok(None);
+    };
+    Ok(
+        read_latest_custom_artifact_payload(project_root, &type_id, &key)?
+            .and_then(|(_producer, payload, _path)| resolve_custom_payload_text(&payload)),
+    )
+}
+
 pub(crate) fn command_patch_path(jobs_root: &Path, job_id: &str) -> PathBuf {
     jobs_root.join(job_id).join("command.patch")
 }
 
+pub(crate) fn gate_context_path(jobs_root: &Path, job_id: &str) -> PathBuf {
+    jobs_root.join(job_id).join("gate-context.json")
+}
+
+pub(crate) fn post_merge_revert_path(jobs_root: &Path, job_id: &str) -> PathBuf {
+    jobs_root.join(job_id).join("post-merge-revert.json")
+}
+
+/// A merge commit whose post-merge gate failed and was left on the target branch pending a
+/// revert decision (written when `auto_revert` is off).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PostMergeRevertCandidate {
+    pub job_id: String,
+    pub target_branch: Option<String>,
+    pub merge_commit: String,
+    /// Earlier commits a rebase-and-merge landed before `merge_commit`, oldest first; they are
+    /// reverted after it.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rebased_commits: Vec<String>,
+    pub gate: String,
+    pub exit_code: i32,
+    pub execution_root: String,
+}
+
+pub fn pending_post_merge_revert(
+    jobs_root: &Path,
+    job_id: &str,
+) -> Result<Option<PostMergeRevertCandidate>, Box<dyn std::error::Error>> {
+    let path = post_merge_revert_path(jobs_root, job_id);
+    if !path.is_file() {
+        return Ok(None);
+    }
+    Ok(Some(serde_json::from_slice(&fs::read(path)?)?))
+}
+
+/// Reverts a pending post-merge candidate on its target branch and clears the candidate file.
+/// Returns the revert commit id.
+pub fn apply_post_merge_revert(
+    project_root: &Path,
+    jobs_root: &Path,
+    candidate: &PostMergeRevertCandidate,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let root = project_root.join(&candidate.execution_root);
+    let root = if root.is_dir() {
+        root
+    } else {
+        project_root.to_path_buf()
+    };
+    if let Some(target) = candidate.target_branch.as_deref() {
+        let current = Repository::open(&root)?
+            .head()
+            .ok()
+            .and_then(|head| head.shorthand().map(str::to_string));
+        if current.as_deref() != Some(target) {
+            return Err(format!(
+                "cannot revert {}: `{target}` is not checked out in {}",
+                candidate.merge_commit,
+                root.display()
+            )
+            .into());
+        }
+    }
+    let mut revert = crate::vcs::revert_commit_in(&root, Oid::from_str(&candidate.merge_commit)?)?;
+    for commit in candidate.rebased_commits.iter().rev() {
+        revert = crate::vcs::revert_commit_in(&root, Oid::from_str(commit)?)?;
+    }
+    let _ = fs::remove_file(post_merge_revert_path(jobs_root, &candidate.job_id));
+    Ok(revert.to_string())
+}
+
 pub(crate) fn legacy_command_patch_path(jobs_root: &Path, job_id: &str) -> PathBuf {
     jobs_root.join(job_id).join("ask-save.patch")
 }
@@ -939,6 +1097,33 @@ fn merge_metadata(
             if update.retry_cleanup_error.is_some() {
                 base.retry_cleanup_error = update.retry_cleanup_error;
             }
+            if update.operator_note.is_some() {
+                base.operator_note = update.operator_note;
+            }
+            if update.context_files.is_some() {
+                base.context_files = update.context_files;
+            }
+            if update.review_baseline.is_some() {
+                base.review_baseline = update.review_baseline;
+            }
+            if update.commit_disposition.is_some() {
+                base.commit_disposition = update.commit_disposition;
+            }
+            if update.cost_estimate.is_some() {
+                base.cost_estimate = update.cost_estimate;
+            }
+            if update.cost_actual.is_some() {
+                base.cost_actual = update.cost_actual;
+            }
+            if update.generate_tests.is_some() {
+                base.generate_tests = update.generate_tests;
+            }
+            if update.review_fix_loop.is_some() {
+                base.review_fix_loop = update.review_fix_loop;
+            }
+            if update.plan_edit.is_some() {
+                base.plan_edit = update.plan_edit;
+            }
             if update.process_liveness_state.is_some() {
                 base.process_liveness_state = update.process_liveness_state;
             }
@@ -958,33 +1143,35 @@ fn persist_record(paths: &JobPaths, record: &JobRecord) -> Result<(), Box<dyn st
         fs::create_dir_all(parent)?;
     }
 
-    let nonce = RECORD_TMP_NONCE.fetch_add(1, AtomicOrdering::Relaxed);
-    let epoch_nanos = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_nanos();
-    let tmp = paths.record_path.with_extension(format!(
-        "json.tmp.{}.{}.{}",
-        std::process::id(),
-        epoch_nanos,
-        nonce
-    ));
     let contents = serde_json::to_vec_pretty(record)?;
-    fs::write(&tmp, contents)?;
-    match fs::rename(&tmp, &paths.record_path) {
-        Ok(()) => Ok(()),
-        Err(err) => {
-            let _ = fs::remove_file(&tmp);
-            Err(Box::new(err))
-        }
-    }
+    atomic_file::write_atomic(&paths.record_path, &contents)?;
+    Ok(())
 }
 
+/// Loads a job record. A record left truncated by a crash falls back to the newest complete
+/// temp file from an interrupted write, which is then moved into place.
 fn load_record(paths: &JobPaths) -> Result<JobRecord, Box<dyn std::error::Error>> {
     let mut buf = String::new();
     File::open(&paths.record_path)?.read_to_string(&mut buf)?;
-    let record: JobRecord = serde_json::from_str(&buf)?;
-    Ok(record)
+    let err = match serde_json::from_str::<JobRecord>(&buf) {
+        Ok(record) => return Ok(record),
+        Err(err) => err,
+    };
+    for tmp in atomic_file::temp_siblings(&paths.record_path) {
+        let Ok(record) = fs::read_to_string(&tmp)
+            .map_err(|err| err.to_string())
+            .and_then(|raw| serde_json::from_str::<JobRecord>(&raw).map_err(|err| err.to_string()))
+        else {
+            continue;
+        };
+        let _ = fs::rename(&tmp, &paths.record_path);
+        return Ok(record);
+    }
+    Err(format!(
+        "{} is truncated or corrupt: {err}",
+        paths.record_path.display()
+    )
+    .into())
 }
 
 fn find_after_cycle(graph: &HashMap<String, Vec<String>>) -> Option<Vec<String>> {
@@ -1295,6 +1482,7 @@ fn finalize_job_with_artifacts(
         }
     }
 
+    crate::notifications::notify_job_finished(project_root, &record);
     Ok(record)
 }
 
@@ -1350,7 +1538,7 @@ pub fn list_records(jobs_root: &Path) -> Result<Vec<JobRecord>, Box<dyn std::err
         }
     }
 
-    records.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    records.sort_by_key(|record| std::cmp::Reverse(record.created_at));
     Ok(records)
 }
 
@@ -184,6 +184,7 @@ fn wait_kind_label(kind: JobWaitKind) -> &'static str {
         JobWaitKind::Locks => "locks",
         JobWaitKind::PinnedHead => "pinned_head",
         JobWaitKind::Preconditions => "preconditions",
+        JobWaitKind::Capacity => "capacity",
     }
 }
 
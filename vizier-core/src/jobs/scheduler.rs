@@ -21,6 +21,127 @@ pub struct EphemeralRunCleanupEvent {
     pub degraded_notes: Vec<String>,
 }
 
+/// Finished jobs whose run time feeds the queue's start estimates.
+const QUEUE_ESTIMATE_SAMPLE: usize = 20;
+
+/// A waiting job's place in the scheduler queue.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueueSlot {
+    /// 1-based position among jobs that have not started yet.
+    pub position: usize,
+    /// Projected start from the average run time of recent jobs; `None` without history or
+    /// while the job awaits approval.
+    pub estimated_start: Option<DateTime<Utc>>,
+}
+
+fn job_priority(record: &JobRecord) -> i32 {
+    record
+        .schedule
+        .as_ref()
+        .map(|schedule| schedule.priority)
+        .unwrap_or_default()
+}
+
+/// Orders records the way the scheduler considers them: higher priority first, then
+/// submission order.
+pub fn sort_by_queue_order(records: &mut [JobRecord]) {
+    records.sort_by_key(|record| (std::cmp::Reverse(job_priority(record)), record.created_at));
+}
+
+/// Queue positions and estimated starts for jobs that have not started. Jobs take the earliest
+/// free slot under `max_concurrent` (0 = unlimited) in queue order, and never before the
+/// estimated finish of the jobs they run `--after`.
+pub fn queue_slots(
+    records: &[JobRecord],
+    max_concurrent: usize,
+    now: DateTime<Utc>,
+) -> HashMap<String, QueueSlot> {
+    let mut finished = records
+        .iter()
+        .filter_map(|record| Some((record.finished_at?, record.started_at?)))
+        .collect::<Vec<_>>();
+    finished.sort_by_key(|(finished_at, _)| std::cmp::Reverse(*finished_at));
+    finished.truncate(QUEUE_ESTIMATE_SAMPLE);
+    let average = (!finished.is_empty()).then(|| {
+        let total = finished
+            .iter()
+            .map(|(finished_at, started_at)| (*finished_at - *started_at).max(Duration::zero()))
+            .fold(Duration::zero(), |sum, duration| sum + duration);
+        total / finished.len() as i32
+    });
+
+    let mut queued = records
+        .iter()
+        .filter(|record| !job_is_terminal(record.status) && record.status != JobStatus::Running)
+        .cloned()
+        .collect::<Vec<_>>();
+    sort_by_queue_order(&mut queued);
+
+    let mut finishes = HashMap::new();
+    let mut running = Vec::new();
+    if let Some(average) = average {
+        for record in records {
+            if record.status == JobStatus::Running {
+                let finish = record
+                    .started_at
+                    .map(|started| started + average)
+                    .unwrap_or(now)
+                    .max(now);
+                finishes.insert(record.id.clone(), finish);
+                running.push(finish);
+            }
+        }
+    }
+    running.sort();
+    // When more jobs run than the limit allows, the earliest finishes only bring the count back
+    // down to the limit; each remaining finish frees one slot.
+    let mut slots = if max_concurrent == 0 {
+        Vec::new()
+    } else {
+        let mut slots = running.split_off(running.len().saturating_sub(max_concurrent));
+        slots.resize(max_concurrent.max(slots.len()), now);
+        slots
+    };
+
+    let mut result = HashMap::new();
+    for (index, record) in queued.iter().enumerate() {
+        let awaiting_approval = record.status == JobStatus::WaitingOnApproval;
+        let estimated_start = match average {
+            Some(average) if !awaiting_approval => {
+                let after = record
+                    .schedule
+                    .as_ref()
+                    .map(|schedule| schedule.after.as_slice())
+                    .unwrap_or_default()
+                    .iter()
+                    .filter_map(|dependency| finishes.get(&dependency.job_id).copied())
+                    .max()
+                    .unwrap_or(now);
+                let start = if slots.is_empty() {
+                    after.max(now)
+                } else {
+                    slots.sort();
+                    slots[0].max(after)
+                };
+                if !slots.is_empty() {
+                    slots[0] = start + average;
+                }
+                finishes.insert(record.id.clone(), start + average);
+                Some(start)
+            }
+            _ => None,
+        };
+        result.insert(
+            record.id.clone(),
+            QueueSlot {
+                position: index + 1,
+                estimated_start,
+            },
+        );
+    }
+    result
+}
+
 pub(crate) fn job_is_terminal(status: JobStatus) -> bool {
     matches!(
         status,
@@ -86,7 +207,7 @@ pub(crate) fn artifact_exists(repo: &Repository, artifact: &JobArtifact) -> bool
         JobArtifact::MergeSentinel { slug } => {
             let path = repo
                 .path()
-                .join(".vizier/tmp/merge-conflicts")
+                .join(crate::temp_registry::MERGE_CONFLICTS_DIR)
                 .join(format!("{slug}.json"));
             path.exists()
         }
@@ -569,18 +690,26 @@ pub(crate) fn apply_stale_workflow_failed_routes(
     let manifest = match load_workflow_run_manifest(project_root, run_id) {
         Ok(manifest) => manifest,
         Err(err) => {
-            display::warn(format!(
-                "unable to load workflow manifest {run_id} for stale running job {}: {}",
-                record.id, err
-            ));
+            display::log(
+                display::Subsystem::Scheduler,
+                display::LogLevel::Warn,
+                format!(
+                    "unable to load workflow manifest {run_id} for stale running job {}: {}",
+                    record.id, err
+                ),
+            );
             return;
         }
     };
 
     let Some(node) = manifest.nodes.get(node_id) else {
-        display::warn(format!(
-            "workflow node `{node_id}` missing from run manifest {run_id} during stale-running reconciliation"
-        ));
+        display::log(
+            display::Subsystem::Scheduler,
+            display::LogLevel::Warn,
+            format!(
+                "workflow node `{node_id}` missing from run manifest {run_id} during stale-running reconciliation"
+            ),
+        );
         return;
     };
 
@@ -704,18 +833,26 @@ fn persist_ephemeral_cleanup_state(
     if let Err(err) =
         write_ephemeral_cleanup_state_to_manifest(project_root, run_id, requested, state, detail)
     {
-        display::warn(format!(
-            "unable to persist ephemeral cleanup manifest state for run {}: {}",
-            run_id, err
-        ));
+        display::log(
+            display::Subsystem::Scheduler,
+            display::LogLevel::Warn,
+            format!(
+                "unable to persist ephemeral cleanup manifest state for run {}: {}",
+                run_id, err
+            ),
+        );
     }
     if let Err(err) =
         write_ephemeral_cleanup_state_to_jobs(jobs_root, job_ids, requested, state, detail)
     {
-        display::warn(format!(
-            "unable to persist ephemeral cleanup job state for run {}: {}",
-            run_id, err
-        ));
+        display::log(
+            display::Subsystem::Scheduler,
+            display::LogLevel::Warn,
+            format!(
+                "unable to persist ephemeral cleanup job state for run {}: {}",
+                run_id, err
+            ),
+        );
     }
 }
 
@@ -955,8 +1092,9 @@ fn scheduler_tick_locked_inner(
 
     let repo = Repository::discover(project_root)?;
 
-    records.sort_by(|a, b| a.created_at.cmp(&b.created_at));
-    let facts = build_scheduler_facts(&repo, jobs_root, &records)?;
+    sort_by_queue_order(&mut records);
+    let mut facts = build_scheduler_facts(&repo, jobs_root, &records)?;
+    facts.max_concurrent = crate::config::get_config().jobs.max_concurrent;
     let decisions = spec::evaluate_all(&facts);
 
     for mut record in records {
@@ -1044,6 +1182,7 @@ pub fn scheduler_tick(
             let _ = fs::remove_dir(&vizier_root);
         }
     }
+    crate::notifications::spawn_due_delivery(project_root, binary);
     Ok(outcome)
 }
 
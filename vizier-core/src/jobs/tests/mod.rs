@@ -853,6 +853,43 @@ fn after_dependency(job_id: &str) -> JobAfterDependency {
     }
 }
 
+#[test]
+fn queue_slots_order_by_priority_and_fill_free_slots() {
+    let now = Utc.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap();
+    let minutes = |m: i64| Duration::minutes(m);
+
+    let mut finished = make_record("job-done", JobStatus::Succeeded, now - minutes(60), None);
+    finished.started_at = Some(now - minutes(40));
+    finished.finished_at = Some(now - minutes(30));
+    let mut running = make_record("job-running", JobStatus::Running, now - minutes(20), None);
+    running.started_at = Some(now - minutes(4));
+    let low = make_record("job-low", JobStatus::Queued, now - minutes(9), None);
+    let urgent = make_record(
+        "job-urgent",
+        JobStatus::Queued,
+        now - minutes(1),
+        Some(JobSchedule {
+            priority: 5,
+            ..JobSchedule::default()
+        }),
+    );
+    let gated = make_record(
+        "job-gated",
+        JobStatus::WaitingOnApproval,
+        now - minutes(8),
+        None,
+    );
+
+    let slots = queue_slots(&[finished, running, low, urgent, gated], 1, now);
+    assert_eq!(slots.len(), 3);
+    assert_eq!(slots["job-urgent"].position, 1);
+    assert_eq!(slots["job-urgent"].estimated_start, Some(now + minutes(6)));
+    assert_eq!(slots["job-low"].position, 2);
+    assert_eq!(slots["job-low"].estimated_start, Some(now + minutes(16)));
+    assert_eq!(slots["job-gated"].position, 3);
+    assert_eq!(slots["job-gated"].estimated_start, None);
+}
+
 #[test]
 fn resolve_after_dependencies_rejects_unknown_job_id() {
     let temp = TempDir::new().expect("temp dir");
@@ -2773,7 +2810,13 @@ fn rewind_job_record_for_retry_clears_runtime_and_artifacts() {
     })
     .expect("set runtime fields");
 
-    rewind_job_record_for_retry(project_root, &jobs_root, &mut record).expect("rewind record");
+    rewind_job_record_for_retry(
+        project_root,
+        &jobs_root,
+        &mut record,
+        &RetryOptions::default(),
+    )
+    .expect("rewind record");
     persist_record(&paths, &record).expect("persist rewinded record");
 
     assert_eq!(record.status, JobStatus::Queued);
@@ -2870,7 +2913,13 @@ fn rewind_job_record_for_retry_retains_worktree_metadata_when_cleanup_degrades()
     })
     .expect("set runtime fields");
 
-    rewind_job_record_for_retry(project_root, &jobs_root, &mut record).expect("rewind record");
+    rewind_job_record_for_retry(
+        project_root,
+        &jobs_root,
+        &mut record,
+        &RetryOptions::default(),
+    )
+    .expect("rewind record");
 
     let metadata = record.metadata.as_ref().expect("metadata");
     assert_eq!(
@@ -2937,7 +2986,13 @@ fn rewind_job_record_for_retry_clears_worktree_metadata_when_fallback_succeeds()
     })
     .expect("set runtime fields");
 
-    rewind_job_record_for_retry(project_root, &jobs_root, &mut record).expect("rewind record");
+    rewind_job_record_for_retry(
+        project_root,
+        &jobs_root,
+        &mut record,
+        &RetryOptions::default(),
+    )
+    .expect("rewind record");
 
     let metadata = record.metadata.as_ref().expect("metadata");
     assert!(metadata.worktree_name.is_none());
@@ -2955,6 +3010,536 @@ fn rewind_job_record_for_retry_clears_worktree_metadata_when_fallback_succeeds()
     );
 }
 
+#[test]
+fn rewind_job_record_for_retry_keeps_worktree_and_records_note_when_continuing() {
+    let temp = TempDir::new().expect("temp dir");
+    init_repo(&temp).expect("init repo");
+    let project_root = temp.path();
+    let jobs_root = project_root.join(".vizier/jobs");
+
+    enqueue_job(
+        project_root,
+        &jobs_root,
+        "job-retry-continue",
+        &["--help".to_string()],
+        &["vizier".to_string(), "save".to_string()],
+        None,
+        None,
+        Some(JobSchedule::default()),
+    )
+    .expect("enqueue");
+
+    let worktree_rel = ".vizier/tmp-worktrees/retry-continue";
+    let worktree_path = project_root.join(worktree_rel);
+    fs::create_dir_all(&worktree_path).expect("create worktree path");
+    fs::write(worktree_path.join("partial.txt"), "prior work").expect("write prior work");
+
+    let mut record = update_job_record(&jobs_root, "job-retry-continue", |record| {
+        record.status = JobStatus::Failed;
+        record.metadata = Some(JobMetadata {
+            execution_root: Some(worktree_rel.to_string()),
+            worktree_owned: Some(true),
+            worktree_path: Some(worktree_rel.to_string()),
+            ..JobMetadata::default()
+        });
+    })
+    .expect("set runtime fields");
+
+    let options = RetryOptions {
+        keep_worktree: true,
+        note: Some("use the existing retry helper".to_string()),
+    };
+    rewind_job_record_for_retry(project_root, &jobs_root, &mut record, &options)
+        .expect("rewind record");
+
+    let metadata = record.metadata.as_ref().expect("metadata");
+    assert_eq!(metadata.worktree_path.as_deref(), Some(worktree_rel));
+    assert_eq!(metadata.execution_root.as_deref(), Some(worktree_rel));
+    assert_eq!(metadata.worktree_owned, Some(true));
+    assert_eq!(
+        metadata.retry_cleanup_status,
+        Some(RetryCleanupStatus::Preserved)
+    );
+    assert_eq!(
+        metadata.operator_note.as_deref(),
+        Some("use the existing retry helper")
+    );
+    assert!(worktree_path.join("partial.txt").exists());
+
+    rewind_job_record_for_retry(
+        project_root,
+        &jobs_root,
+        &mut record,
+        &RetryOptions {
+            keep_worktree: true,
+            note: None,
+        },
+    )
+    .expect("rewind record again");
+    assert!(
+        record
+            .metadata
+            .as_ref()
+            .and_then(|metadata| metadata.operator_note.as_ref())
+            .is_none(),
+        "a retry without --note should drop the previous note"
+    );
+}
+
+#[test]
+fn retry_job_with_continue_requires_recorded_worktree() {
+    let temp = TempDir::new().expect("temp dir");
+    init_repo(&temp).expect("init repo");
+    let project_root = temp.path();
+    let jobs_root = project_root.join(".vizier/jobs");
+
+    enqueue_job(
+        project_root,
+        &jobs_root,
+        "job-no-worktree",
+        &["--help".to_string()],
+        &["vizier".to_string(), "save".to_string()],
+        None,
+        None,
+        Some(JobSchedule::default()),
+    )
+    .expect("enqueue");
+    update_job_record(&jobs_root, "job-no-worktree", |record| {
+        record.status = JobStatus::Failed;
+    })
+    .expect("mark failed");
+
+    let binary = std::env::current_exe().expect("current exe");
+    let err = retry_job_with_options(
+        project_root,
+        &jobs_root,
+        &binary,
+        "job-no-worktree",
+        &RetryOptions {
+            keep_worktree: true,
+            note: None,
+        },
+    )
+    .expect_err("continue without worktree should fail");
+    assert!(
+        err.to_string().contains("no recorded worktree"),
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+fn append_operator_note_wraps_guidance_and_skips_blank_notes() {
+    let prompt = append_operator_note(
+        "Implement the plan.\n".to_string(),
+        Some("  use the existing retry helper "),
+    );
+    assert!(prompt.starts_with("Implement the plan.\n\n<operatorGuidance>"));
+    assert!(prompt.contains("use the existing retry helper\n</operatorGuidance>"));
+
+    assert_eq!(
+        append_operator_note("Implement the plan.".to_string(), Some("   ")),
+        "Implement the plan."
+    );
+    assert_eq!(
+        append_operator_note("Implement the plan.".to_string(), None),
+        "Implement the plan."
+    );
+}
+
+#[test]
+fn append_context_documents_bounds_and_records_files() -> Result<(), Box<dyn std::error::Error>> {
+    let temp = TempDir::new()?;
+    let design = temp.path().join("design.md");
+    fs::write(&design, "Latency must stay under 50ms.\n")?;
+    let large = temp.path().join("incident.log");
+    fs::write(&large, "x".repeat(20 * 1024))?;
+    let files = vec![
+        design.display().to_string(),
+        large.display().to_string(),
+        temp.path().join("missing.md").display().to_string(),
+    ];
+
+    let (prompt, recorded) =
+        append_context_documents("Review the plan.".to_string(), Some(&files), temp.path());
+    assert!(prompt.starts_with("Review the plan.\n\n<contextDocuments>"));
+    assert!(prompt.contains("<document path=\"design.md\">\nLatency must stay under 50ms.\n"));
+    assert!(prompt.contains("[truncated: 16384 of 20480 bytes shown]"));
+    assert_eq!(recorded.len(), 3);
+    assert_eq!(recorded[1]["truncated"], true);
+    assert_eq!(recorded[2]["path"], "missing.md");
+    assert!(recorded[2].get("error").is_some());
+    assert_eq!(recorded[0]["cached"], false);
+
+    let (again, recorded) =
+        append_context_documents("Review the plan.".to_string(), Some(&files), temp.path());
+    assert_eq!(again, prompt);
+    assert_eq!(recorded[0]["cached"], true);
+    assert_eq!(recorded[1]["included_bytes"], 16384);
+    assert_eq!(recorded[1]["truncated"], true);
+
+    let (unchanged, recorded) =
+        append_context_documents("Review the plan.".to_string(), None, temp.path());
+    assert_eq!(unchanged, "Review the plan.");
+    assert!(recorded.is_empty());
+    Ok(())
+}
+
+#[test]
+fn never_send_withholds_context_documents_and_file_placeholders()
+-> Result<(), Box<dyn std::error::Error>> {
+    let _guard = config::test_config_lock().lock().unwrap();
+    let original = config::get_config();
+    let mut cfg = original.clone();
+    cfg.context.never_send = vec!["secrets/**".to_string()];
+    config::set_config(cfg);
+
+    let temp = TempDir::new()?;
+    fs::create_dir_all(temp.path().join("secrets"))?;
+    let secret = temp.path().join("secrets/prod.env");
+    fs::write(&secret, "TOKEN=hunter2\n")?;
+    let design = temp.path().join("design.md");
+    fs::write(&design, "Latency budget.\n")?;
+    let files = vec![secret.display().to_string(), design.display().to_string()];
+
+    let (prompt, recorded) =
+        append_context_documents("Review the plan.".to_string(), Some(&files), temp.path());
+    let mut stderr_lines = Vec::new();
+    let placeholder = resolve_prompt_template_placeholder(
+        "file:secrets/prod.env",
+        &BTreeMap::new(),
+        temp.path(),
+        false,
+        &mut |_| Err("no summaries".to_string()),
+        &mut stderr_lines,
+    );
+    config::set_config(original);
+
+    assert!(!prompt.contains("hunter2"), "{prompt}");
+    assert!(prompt.contains("Latency budget."));
+    assert_eq!(recorded[0]["path"], "secrets/prod.env");
+    assert_eq!(recorded[0]["error"], "withheld by [context] never_send");
+    assert_eq!(placeholder?, crate::never_send::WITHHELD_PLACEHOLDER);
+    assert!(
+        stderr_lines
+            .iter()
+            .any(|line| line.contains("withheld placeholder file `secrets/prod.env`")),
+        "{stderr_lines:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn plan_archive_for_merge_collects_plan_summary_critique_and_notes()
+-> Result<(), Box<dyn std::error::Error>> {
+    let temp = TempDir::new()?;
+    let repo = init_repo(&temp)?;
+    let seed = seed_repo(&repo)?;
+    let project_root = temp.path();
+    let jobs_root = project_root.join(".vizier/jobs");
+
+    let notes = "# Operator notes: alpha\n\n- 2026-10-17T09:00:00Z ana: flaky upload test in ci\n";
+    fs::write(project_root.join("README.md"), "seed\nmerged change\n")?;
+    fs::create_dir_all(project_root.join(".vizier/notes"))?;
+    fs::write(project_root.join(".vizier/notes/alpha.md"), notes)?;
+    let mut index = repo.index()?;
+    index.add_path(Path::new("README.md"))?;
+    index.add_path(Path::new(".vizier/notes/alpha.md"))?;
+    let tree = repo.find_tree(index.write_tree()?)?;
+    index.write()?;
+    let sig = Signature::now("vizier", "vizier@example.com")?;
+    let tip = repo.commit(
+        Some("refs/heads/draft/alpha"),
+        &sig,
+        &sig,
+        "feat: alpha change",
+        &tree,
+        &[&repo.find_commit(seed)?],
+    )?;
+
+    let review = enqueue_job(
+        project_root,
+        &jobs_root,
+        "job-review",
+        &["--help".to_string()],
+        &[
+            "vizier".to_string(),
+            "run".to_string(),
+            "review".to_string(),
+        ],
+        Some(JobMetadata {
+            plan: Some("alpha".to_string()),
+            scope: Some("review".to_string()),
+            ..JobMetadata::default()
+        }),
+        None,
+        None,
+    )?;
+    fs::write(
+        project_root.join(&review.stdout_path),
+        "## Action Items\n- [minor] tighten wording\n",
+    )?;
+    update_job_record(&jobs_root, "job-review", |record| {
+        record.status = JobStatus::Succeeded;
+    })?;
+
+    let archive = plan_archive_for_merge(
+        project_root,
+        &jobs_root,
+        project_root,
+        "alpha",
+        "draft/alpha",
+        Some("master"),
+        "# Alpha plan\n\nDo the thing.\n",
+    )?;
+
+    assert_eq!(archive.metadata.title, "Alpha plan");
+    assert_eq!(archive.metadata.branch_tip, tip.to_string());
+    assert_eq!(archive.metadata.target.as_deref(), Some("master"));
+    assert!(
+        archive.summary.contains("M\tREADME.md"),
+        "{}",
+        archive.summary
+    );
+    assert_eq!(
+        archive.critique.as_deref(),
+        Some("## Action Items\n- [minor] tighten wording\n")
+    );
+    assert_eq!(archive.notes.as_deref(), Some(notes));
+
+    let mut summarize = |_prompt: String| Ok(String::new());
+    let (rendered, _) = render_prompt_template(
+        "{{plan_notes:draft/alpha}}|{{plan_notes:master}}",
+        &BTreeMap::new(),
+        project_root,
+        false,
+        &mut summarize,
+    )?;
+    assert_eq!(
+        rendered,
+        format!("{notes}|Branch `master` is not a plan branch; no operator notes.")
+    );
+    Ok(())
+}
+
+#[test]
+fn render_prompt_template_expands_diff_placeholders() -> Result<(), Box<dyn std::error::Error>> {
+    let temp = TempDir::new()?;
+    let repo = init_repo(&temp)?;
+    let seed = seed_repo(&repo)?;
+    let mut index = repo.index()?;
+    index.read_tree(&repo.find_commit(seed)?.tree()?)?;
+    index.write()?;
+    fs::write(temp.path().join("README.md"), "seed\nsmall edit\n")?;
+
+    let calls = std::cell::Cell::new(0usize);
+    let mut summarize = |_prompt: String| {
+        calls.set(calls.get() + 1);
+        Ok("- summarized".to_string())
+    };
+    let (rendered, _) = render_prompt_template(
+        "changes:\n{{diff:HEAD}}",
+        &BTreeMap::new(),
+        temp.path(),
+        false,
+        &mut summarize,
+    )?;
+    assert!(rendered.contains("+small edit"), "{rendered}");
+    assert_eq!(calls.get(), 0, "small diffs should be inlined verbatim");
+
+    let large = (0..4000)
+        .map(|line| format!("generated line {line}\n"))
+        .collect::<String>();
+    fs::write(temp.path().join("README.md"), large)?;
+    let (rendered, stderr_lines) = render_prompt_template(
+        "{{diff:HEAD}}",
+        &BTreeMap::new(),
+        temp.path(),
+        false,
+        &mut summarize,
+    )?;
+    assert_eq!(
+        calls.get(),
+        1,
+        "one oversized hunk becomes one clipped chunk"
+    );
+    assert!(rendered.starts_with("[diff summarized:"), "{rendered}");
+    assert!(rendered.contains("### README.md (+4000 -1)\n- summarized"));
+    assert!(
+        stderr_lines
+            .iter()
+            .any(|line| line.contains("summarized oversized diff placeholder `HEAD`"))
+    );
+    Ok(())
+}
+
+#[test]
+fn render_prompt_template_drops_low_priority_context_over_budget()
+-> Result<(), Box<dyn std::error::Error>> {
+    let _guard = config::test_config_lock().lock().unwrap();
+    let original = config::get_config();
+    let temp = TempDir::new()?;
+    fs::create_dir_all(temp.path().join(".vizier/implementation-plans"))?;
+    fs::create_dir_all(temp.path().join(".vizier/narrative"))?;
+    fs::write(
+        temp.path().join(".vizier/implementation-plans/a.md"),
+        "## Execution Plan\n1. Ship it.\n",
+    )?;
+    fs::write(
+        temp.path().join(".vizier/narrative/snapshot.md"),
+        format!("## Parser\nstable\n## Telemetry\n{}\n", "t".repeat(300)),
+    )?;
+    fs::write(temp.path().join("extra.md"), "e".repeat(600))?;
+    let template = "<instruction>review</instruction>\n{{file:.vizier/implementation-plans/a.md}}\n{{file:.vizier/narrative/snapshot.md}}\n{{file:extra.md}}\n";
+
+    let mut cfg = original.clone();
+    cfg.workflow.prompt_budget.max_bytes = 300;
+    config::set_config(cfg);
+    let rendered =
+        render_prompt_template(template, &BTreeMap::new(), temp.path(), false, &mut |_| {
+            Err("no summaries".to_string())
+        });
+    config::set_config(original);
+
+    let (rendered, stderr_lines) = rendered?;
+    assert!(rendered.len() <= 300, "{rendered}");
+    assert!(rendered.contains("1. Ship it."));
+    assert!(rendered.contains("## Parser\nstable\n"));
+    assert!(!rendered.contains("eeee"));
+    assert!(!rendered.contains("tttt"));
+    assert!(rendered.contains("[prompt budget: dropped 1 chunk(s) of file:extra.md: (preamble)]"));
+    assert!(
+        stderr_lines.iter().any(|line| line
+            .contains("over workflow.prompt_budget.max_bytes (300); dropped 2 chunk(s)")
+            && line.contains("threads file:.vizier/narrative/snapshot.md (Telemetry)")),
+        "{stderr_lines:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn render_prompt_template_range_diff_tracks_last_reviewed_tip()
+-> Result<(), Box<dyn std::error::Error>> {
+    let temp = TempDir::new()?;
+    let repo = init_repo(&temp)?;
+    let seed = repo.find_commit(seed_repo(&repo)?)?;
+    repo.branch("target", &seed, false)?;
+    let sig = Signature::now("vizier", "vizier@example.com")?;
+    let commit_on_draft = |name: &str, parent: &git2::Commit<'_>| -> Result<Oid, git2::Error> {
+        let blob = repo.blob(name.as_bytes())?;
+        let mut builder = repo.treebuilder(Some(&parent.tree()?))?;
+        builder.insert(format!("{name}.txt"), blob, 0o100644)?;
+        let tree = repo.find_tree(builder.write()?)?;
+        repo.commit(
+            Some("refs/heads/draft/review"),
+            &sig,
+            &sig,
+            name,
+            &tree,
+            &[parent],
+        )
+    };
+    let first = repo.find_commit(commit_on_draft("first", &seed)?)?;
+
+    let mut summarize = |_prompt: String| Ok(String::new());
+    let (rendered, _) = render_prompt_template(
+        "{{range_diff:target..draft/review}}",
+        &BTreeMap::new(),
+        temp.path(),
+        false,
+        &mut summarize,
+    )?;
+    assert!(rendered.contains("no previous review"), "{rendered}");
+
+    commit_on_draft("second", &first)?;
+    let (rendered, stderr_lines) = render_prompt_template(
+        "{{range_diff:target..draft/review}}",
+        &BTreeMap::new(),
+        temp.path(),
+        false,
+        &mut summarize,
+    )?;
+    assert!(
+        rendered.contains("(1 unchanged, 0 modified, 1 added, 0 dropped)"),
+        "{rendered}"
+    );
+    assert!(rendered.contains("-:  ------- > 2:  "), "{rendered}");
+    assert!(
+        stderr_lines
+            .iter()
+            .any(|line| line.contains("info range-diff Range-diff of `draft/review`"))
+    );
+
+    let (rendered, _) = render_prompt_template(
+        "{{range_diff:target..draft/review}}",
+        &BTreeMap::new(),
+        temp.path(),
+        false,
+        &mut summarize,
+    )?;
+    assert!(
+        rendered.contains("unchanged since the last review"),
+        "{rendered}"
+    );
+    Ok(())
+}
+
+#[test]
+fn render_prompt_template_review_baseline_narrows_diffs() -> Result<(), Box<dyn std::error::Error>>
+{
+    let temp = TempDir::new()?;
+    let repo = init_repo(&temp)?;
+    let seed = repo.find_commit(seed_repo(&repo)?)?;
+    repo.branch("target", &seed, false)?;
+    let sig = Signature::now("vizier", "vizier@example.com")?;
+    let commit_on_draft = |name: &str, parent: &git2::Commit<'_>| -> Result<Oid, git2::Error> {
+        let blob = repo.blob(name.as_bytes())?;
+        let mut builder = repo.treebuilder(Some(&parent.tree()?))?;
+        builder.insert(format!("{name}.txt"), blob, 0o100644)?;
+        let tree = repo.find_tree(builder.write()?)?;
+        repo.commit(
+            Some("refs/heads/draft/review"),
+            &sig,
+            &sig,
+            name,
+            &tree,
+            &[parent],
+        )
+    };
+    let first = repo.find_commit(commit_on_draft("first", &seed)?)?;
+    commit_on_draft("second", &first)?;
+
+    let variables =
+        BTreeMap::from([(REVIEW_BASELINE_VARIABLE.to_string(), first.id().to_string())]);
+    let mut summarize = |_prompt: String| Ok(String::new());
+    let (rendered, stderr_lines) = render_prompt_template(
+        "{{diff:target..draft/review}}",
+        &variables,
+        temp.path(),
+        false,
+        &mut summarize,
+    )?;
+    assert!(rendered.contains("second.txt"), "{rendered}");
+    assert!(!rendered.contains("first.txt"), "{rendered}");
+    assert!(
+        stderr_lines
+            .iter()
+            .any(|line| line.contains("instead of `target`"))
+    );
+
+    let (rendered, _) = render_prompt_template(
+        "{{range_diff:target..draft/review}}",
+        &variables,
+        temp.path(),
+        false,
+        &mut summarize,
+    )?;
+    assert!(
+        rendered.contains("(1 unchanged, 0 modified, 1 added, 0 dropped)"),
+        "{rendered}"
+    );
+    Ok(())
+}
+
 #[test]
 fn prune_error_mentions_missing_shallow_detects_known_message() {
     let sample = "could not find '/tmp/repo/.git/shallow' to stat";
@@ -5022,51 +5607,134 @@ fn workflow_runtime_plan_persist_writes_plan_doc_and_state() {
 }
 
 #[test]
-fn workflow_runtime_plan_persist_prefers_plan_text_dependency_payload() {
+fn workflow_runtime_plan_persist_splits_plan_into_dependent_branches() {
     let temp = TempDir::new().expect("temp dir");
     let repo = init_repo(&temp).expect("init repo");
     seed_repo(&repo).expect("seed repo");
     let project_root = temp.path();
     let jobs_root = project_root.join(".vizier/jobs");
 
-    let artifact = JobArtifact::Custom {
-        type_id: PLAN_TEXT_ARTIFACT_TYPE_ID.to_string(),
-        key: "draft_plan:runtime-plan".to_string(),
-    };
     enqueue_job(
         project_root,
         &jobs_root,
-        "job-plan-persist-from-artifact",
+        "job-plan-split",
         &["--help".to_string()],
         &["vizier".to_string(), "__workflow-node".to_string()],
         None,
         None,
-        Some(JobSchedule {
-            dependencies: vec![JobDependency {
-                artifact: artifact.clone(),
-            }],
-            ..JobSchedule::default()
-        }),
+        Some(JobSchedule::default()),
     )
     .expect("enqueue");
-
-    write_custom_artifact_payload(
-        project_root,
-        "job-agent-output",
-        PLAN_TEXT_ARTIFACT_TYPE_ID,
-        "draft_plan:runtime-plan",
-        &serde_json::json!({
-            "text": "- Generated from agent artifact"
-        }),
-    )
-    .expect("write plan payload");
-    write_custom_artifact_markers(
-        project_root,
-        "job-agent-output",
-        std::slice::from_ref(&artifact),
-    )
-    .expect("write artifact marker");
-
+    let record = read_record(&jobs_root, "job-plan-split").expect("record");
+    let oversized_steps = (1..=13)
+        .map(|step| format!("{step}. Step {step}\n"))
+        .collect::<String>();
+    let plan_body = format!(
+        "<!-- vizier:plan schema -->\n## Execution Plan\n{oversized_steps}\n<!-- vizier:plan cli -->\n## Execution Plan\n1. Wire `src/cli.rs`\n"
+    );
+    let node = runtime_executor_node(
+        "persist",
+        "job-plan-split",
+        "cap.env.builtin.plan.persist",
+        "plan.persist",
+        BTreeMap::from([
+            ("name_override".to_string(), "big-change".to_string()),
+            ("spec_text".to_string(), "Big change spec".to_string()),
+            ("plan_body".to_string(), plan_body),
+            ("split".to_string(), "true".to_string()),
+        ]),
+    );
+    let result =
+        execute_workflow_executor(project_root, &jobs_root, &record, &node).expect("persist");
+    assert_eq!(result.outcome, WorkflowNodeOutcome::Succeeded);
+    assert_eq!(
+        result.summary.as_deref(),
+        Some("plan persisted (split into 2 plans)")
+    );
+    assert!(
+        result.artifacts_written.contains(&JobArtifact::PlanBranch {
+            slug: "big-change-cli".to_string(),
+            branch: "draft/big-change-cli".to_string(),
+        }),
+        "expected follow-up plan branch artifact: {:?}",
+        result.artifacts_written
+    );
+    assert!(
+        result
+            .stderr_lines
+            .iter()
+            .any(|line| line.contains("`big-change` looks too large") && line.contains("13 steps")),
+        "expected oversized warning: {:?}",
+        result.stderr_lines
+    );
+
+    let first = fs::read_to_string(project_root.join(".vizier/implementation-plans/big-change.md"))
+        .expect("first plan doc");
+    assert!(first.contains("13. Step 13"), "{first}");
+    assert!(!first.contains("src/cli.rs"), "{first}");
+
+    let followup = repo
+        .revparse_single("draft/big-change-cli:.vizier/implementation-plans/big-change-cli.md")
+        .expect("follow-up plan doc on its branch")
+        .peel_to_blob()
+        .expect("blob");
+    let followup = String::from_utf8_lossy(followup.content()).to_string();
+    assert!(followup.contains("depends_on: big-change"), "{followup}");
+    assert!(followup.contains("Wire `src/cli.rs`"), "{followup}");
+    assert!(
+        !project_root
+            .join(".vizier/implementation-plans/big-change-cli.md")
+            .exists(),
+        "follow-up plans stay off the draft worktree"
+    );
+}
+
+#[test]
+fn workflow_runtime_plan_persist_prefers_plan_text_dependency_payload() {
+    let temp = TempDir::new().expect("temp dir");
+    let repo = init_repo(&temp).expect("init repo");
+    seed_repo(&repo).expect("seed repo");
+    let project_root = temp.path();
+    let jobs_root = project_root.join(".vizier/jobs");
+
+    let artifact = JobArtifact::Custom {
+        type_id: PLAN_TEXT_ARTIFACT_TYPE_ID.to_string(),
+        key: "draft_plan:runtime-plan".to_string(),
+    };
+    enqueue_job(
+        project_root,
+        &jobs_root,
+        "job-plan-persist-from-artifact",
+        &["--help".to_string()],
+        &["vizier".to_string(), "__workflow-node".to_string()],
+        None,
+        None,
+        Some(JobSchedule {
+            dependencies: vec![JobDependency {
+                artifact: artifact.clone(),
+            }],
+            ..JobSchedule::default()
+        }),
+    )
+    .expect("enqueue");
+
+    write_custom_artifact_payload(
+        project_root,
+        "job-agent-output",
+        PLAN_TEXT_ARTIFACT_TYPE_ID,
+        "draft_plan:runtime-plan",
+        &serde_json::json!({
+            "text": "- Generated from agent artifact"
+        }),
+    )
+    .expect("write plan payload");
+    write_custom_artifact_markers(
+        project_root,
+        "job-agent-output",
+        std::slice::from_ref(&artifact),
+    )
+    .expect("write artifact marker");
+
     let record = read_record(&jobs_root, "job-plan-persist-from-artifact").expect("record");
     let node = runtime_executor_node(
         "persist",
@@ -5099,6 +5767,95 @@ fn workflow_runtime_plan_persist_prefers_plan_text_dependency_payload() {
     );
 }
 
+#[test]
+fn workflow_runtime_plan_persist_writes_operator_amendment_and_marks_front_matter() {
+    let temp = TempDir::new().expect("temp dir");
+    let repo = init_repo(&temp).expect("init repo");
+    seed_repo(&repo).expect("seed repo");
+    let project_root = temp.path();
+    let jobs_root = project_root.join(".vizier/jobs");
+
+    let artifact = JobArtifact::Custom {
+        type_id: PLAN_TEXT_ARTIFACT_TYPE_ID.to_string(),
+        key: "draft_plan:amended-plan".to_string(),
+    };
+    enqueue_job(
+        project_root,
+        &jobs_root,
+        "job-plan-persist-amended",
+        &["--help".to_string()],
+        &["vizier".to_string(), "__workflow-node".to_string()],
+        None,
+        None,
+        Some(JobSchedule {
+            dependencies: vec![JobDependency {
+                artifact: artifact.clone(),
+            }],
+            ..JobSchedule::default()
+        }),
+    )
+    .expect("enqueue");
+    write_custom_artifact_payload(
+        project_root,
+        "job-agent-output",
+        PLAN_TEXT_ARTIFACT_TYPE_ID,
+        "draft_plan:amended-plan",
+        &serde_json::json!({ "text": "- Agent draft" }),
+    )
+    .expect("write plan payload");
+    write_custom_artifact_markers(
+        project_root,
+        "job-agent-output",
+        std::slice::from_ref(&artifact),
+    )
+    .expect("write artifact marker");
+
+    assert_eq!(
+        drafted_plan_text(project_root, &jobs_root, "job-plan-persist-amended")
+            .expect("drafted plan")
+            .as_deref(),
+        Some("- Agent draft")
+    );
+    fs::write(
+        plan_amendment_path(&jobs_root, "job-plan-persist-amended"),
+        "- Operator rewrite\n",
+    )
+    .expect("write amendment");
+
+    let record = read_record(&jobs_root, "job-plan-persist-amended").expect("record");
+    let node = runtime_executor_node(
+        "persist",
+        "job-plan-persist-amended",
+        "cap.env.builtin.plan.persist",
+        "plan.persist",
+        BTreeMap::from([
+            ("name_override".to_string(), "amended-plan".to_string()),
+            ("spec_source".to_string(), "inline".to_string()),
+            ("spec_text".to_string(), "Spec".to_string()),
+        ]),
+    );
+    let result = execute_workflow_executor(project_root, &jobs_root, &record, &node)
+        .expect("execute plan.persist");
+    assert_eq!(result.outcome, WorkflowNodeOutcome::Succeeded);
+    assert_eq!(
+        result.summary.as_deref(),
+        Some("plan persisted (amended by operator)")
+    );
+
+    let plan_doc =
+        fs::read_to_string(project_root.join(".vizier/implementation-plans/amended-plan.md"))
+            .expect("read plan doc");
+    assert!(
+        plan_doc.contains("human_amended: true\n---\n"),
+        "{plan_doc}"
+    );
+    assert!(
+        plan_doc.contains("## Implementation Plan\n- Operator rewrite"),
+        "{plan_doc}"
+    );
+    assert!(!plan_doc.contains("Agent draft"), "{plan_doc}");
+}
+
 #[test]
 fn workflow_runtime_integrate_plan_branch_blocks_on_conflict_and_writes_sentinel() {
     let temp = TempDir::new().expect("temp dir");
@@ -5314,6 +6071,173 @@ fn workflow_runtime_integrate_plan_branch_derives_branch_from_slug_when_branch_m
     );
 }
 
+#[test]
+fn workflow_runtime_integrate_plan_branch_rebase_strategy_keeps_linear_history() {
+    let temp = TempDir::new().expect("temp dir");
+    let repo = init_repo(&temp).expect("init repo");
+    seed_repo(&repo).expect("seed repo");
+    let project_root = temp.path();
+    let jobs_root = project_root.join(".vizier/jobs");
+    let target = current_branch_name(project_root).expect("target branch");
+
+    let checkout = git_status(project_root, &["checkout", "-b", "draft/runtime-rebase"]);
+    assert!(checkout.is_ok(), "create draft branch: {checkout:?}");
+    fs::write(project_root.join("one.txt"), "one\n").expect("write one");
+    git_commit_all(project_root, "feat: first step");
+    fs::write(project_root.join("two.txt"), "two\n").expect("write two");
+    git_commit_all(project_root, "feat: second step");
+    let checkout_target = git_status(project_root, &["checkout", &target]);
+    assert!(
+        checkout_target.is_ok(),
+        "checkout target: {checkout_target:?}"
+    );
+    fs::write(project_root.join("target.txt"), "target\n").expect("write target");
+    git_commit_all(project_root, "chore: target moved");
+
+    enqueue_job(
+        project_root,
+        &jobs_root,
+        "job-integrate-rebase",
+        &["--help".to_string()],
+        &["vizier".to_string(), "__workflow-node".to_string()],
+        None,
+        None,
+        Some(JobSchedule::default()),
+    )
+    .expect("enqueue");
+    let record = read_record(&jobs_root, "job-integrate-rebase").expect("record");
+    let node = runtime_executor_node(
+        "integrate",
+        "job-integrate-rebase",
+        "cap.env.builtin.git.integrate_plan_branch",
+        "git.integrate_plan_branch",
+        BTreeMap::from([
+            ("slug".to_string(), "runtime-rebase".to_string()),
+            ("target_branch".to_string(), target),
+            ("squash".to_string(), "true".to_string()),
+            ("strategy".to_string(), "rebase".to_string()),
+        ]),
+    );
+    let result =
+        execute_workflow_executor(project_root, &jobs_root, &record, &node).expect("integrate");
+    assert_eq!(
+        result.outcome,
+        WorkflowNodeOutcome::Succeeded,
+        "integrate result: {result:?}"
+    );
+
+    let repo = Repository::open(project_root).expect("open repo");
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    assert_eq!(head.parent_count(), 1);
+    let previous = head.parent(0).unwrap();
+    assert_eq!(previous.parent_count(), 1);
+    assert!(head.message().unwrap().starts_with("feat: second step"));
+    assert!(
+        head.message()
+            .unwrap()
+            .contains("Vizier-Plan: runtime-rebase\n")
+    );
+    assert!(previous.message().unwrap().starts_with("feat: first step"));
+    assert_eq!(
+        previous.parent(0).unwrap().summary(),
+        Some("chore: target moved")
+    );
+}
+
+#[test]
+fn workflow_runtime_integrate_plan_branch_backports_to_also_targets() {
+    let temp = TempDir::new().expect("temp dir");
+    let repo = init_repo(&temp).expect("init repo");
+    seed_repo(&repo).expect("seed repo");
+    let project_root = temp.path();
+    let jobs_root = project_root.join(".vizier/jobs");
+    let target = current_branch_name(project_root).expect("target branch");
+
+    crate::vcs::create_branch_from_head_in(project_root, "release/1").expect("release/1");
+    let checkout = git_status(project_root, &["checkout", "-b", "release/2"]);
+    assert!(checkout.is_ok(), "create release/2: {checkout:?}");
+    fs::write(project_root.join("feature.txt"), "release two\n").expect("write release");
+    git_commit_all(project_root, "fix: release-only feature");
+    let checkout = git_status(project_root, &["checkout", &target]);
+    assert!(checkout.is_ok(), "checkout target: {checkout:?}");
+
+    let checkout = git_status(project_root, &["checkout", "-b", "draft/runtime-also"]);
+    assert!(checkout.is_ok(), "create draft branch: {checkout:?}");
+    fs::write(project_root.join("feature.txt"), "from plan\n").expect("write feature");
+    git_commit_all(project_root, "feat: plan feature");
+    let checkout = git_status(project_root, &["checkout", &target]);
+    assert!(checkout.is_ok(), "checkout target: {checkout:?}");
+
+    enqueue_job(
+        project_root,
+        &jobs_root,
+        "job-integrate-also",
+        &["--help".to_string()],
+        &["vizier".to_string(), "__workflow-node".to_string()],
+        None,
+        None,
+        Some(JobSchedule::default()),
+    )
+    .expect("enqueue");
+    let record = read_record(&jobs_root, "job-integrate-also").expect("record");
+    let node = runtime_executor_node(
+        "integrate",
+        "job-integrate-also",
+        "cap.env.builtin.git.integrate_plan_branch",
+        "git.integrate_plan_branch",
+        BTreeMap::from([
+            ("slug".to_string(), "runtime-also".to_string()),
+            ("target_branch".to_string(), target.clone()),
+            (
+                "also".to_string(),
+                format!("release/1, {target}, release/2"),
+            ),
+        ]),
+    );
+    let result =
+        execute_workflow_executor(project_root, &jobs_root, &record, &node).expect("integrate");
+    assert_eq!(result.outcome, WorkflowNodeOutcome::Failed, "{result:?}");
+    assert_eq!(result.exit_code, Some(10));
+    let summary = result.summary.clone().unwrap_or_default();
+    assert!(
+        summary.contains("backports: release/1: landed "),
+        "{summary}"
+    );
+    assert!(
+        summary.contains("release/2: replaying ") && summary.contains("feature.txt"),
+        "{summary}"
+    );
+    assert!(!summary.contains(&format!("{target}: ")), "{summary}");
+
+    let repo = Repository::open(project_root).expect("open repo");
+    let release = repo
+        .revparse_single("release/1")
+        .and_then(|object| object.peel_to_commit())
+        .expect("release/1");
+    assert!(
+        release
+            .message()
+            .unwrap_or_default()
+            .contains("(cherry picked from commit ")
+    );
+    let blob = release
+        .tree()
+        .expect("tree")
+        .get_path(Path::new("feature.txt"))
+        .expect("feature on release/1")
+        .to_object(&repo)
+        .expect("blob");
+    assert_eq!(
+        blob.as_blob().map(|blob| blob.content()),
+        Some(&b"from plan\n"[..])
+    );
+    let untouched = repo
+        .revparse_single("release/2")
+        .and_then(|object| object.peel_to_commit())
+        .expect("release/2");
+    assert_eq!(untouched.summary(), Some("fix: release-only feature"));
+}
+
 #[test]
 fn workflow_runtime_integrate_plan_branch_embeds_plan_and_cleans_source_plan_doc() {
     let temp = TempDir::new().expect("temp dir");
@@ -5420,6 +6344,12 @@ fn workflow_runtime_integrate_plan_branch_embeds_plan_and_cleans_source_plan_doc
             .is_err(),
         "expected source branch tip to remove plan doc before merge finalization"
     );
+    assert!(
+        project_root
+            .join(format!(".vizier/archive/{slug}/plan.md"))
+            .is_file(),
+        "expected merged plan to be archived on the target"
+    );
 }
 
 #[test]
@@ -5562,6 +6492,161 @@ fn workflow_runtime_git_save_worktree_patch_writes_command_patch() {
     assert!(patch_path.exists(), "expected command patch output");
 }
 
+#[test]
+fn workflow_runtime_git_commit_in_patch_mode_captures_patch_and_restores_worktree() {
+    let temp = TempDir::new().expect("temp dir");
+    let repo = init_repo(&temp).expect("init repo");
+    let seed = seed_repo(&repo).expect("seed repo");
+    let project_root = temp.path();
+    let jobs_root = project_root.join(".vizier/jobs");
+
+    enqueue_job(
+        project_root,
+        &jobs_root,
+        "job-patch-commit",
+        &["--help".to_string()],
+        &["vizier".to_string(), "__workflow-node".to_string()],
+        None,
+        None,
+        Some(JobSchedule::default()),
+    )
+    .expect("enqueue");
+    update_job_record(&jobs_root, "job-patch-commit", |record| {
+        let metadata = record.metadata.get_or_insert_with(Default::default);
+        metadata.commit_disposition = Some(crate::auditor::CommitDisposition::Patch);
+    })
+    .expect("mark patch mode");
+
+    fs::write(project_root.join("README.md"), "updated\n").expect("update readme");
+    let mut index = repo.index().expect("index");
+    index
+        .add_path(Path::new("README.md"))
+        .expect("stage readme");
+    index.write().expect("write index");
+    fs::create_dir_all(project_root.join("src")).expect("src dir");
+    fs::write(project_root.join("src/new.rs"), "fn added() {}\n").expect("write new file");
+
+    let record = read_record(&jobs_root, "job-patch-commit").expect("record");
+    let node = runtime_executor_node(
+        "commit",
+        "job-patch-commit",
+        "cap.env.builtin.git.commit",
+        "git.commit",
+        BTreeMap::from([("message".to_string(), "unused".to_string())]),
+    );
+    let result =
+        execute_workflow_executor(project_root, &jobs_root, &record, &node).expect("git.commit");
+    assert_eq!(result.outcome, WorkflowNodeOutcome::Succeeded);
+
+    let patch = fs::read_to_string(command_patch_path(&jobs_root, "job-patch-commit"))
+        .expect("command patch");
+    assert!(patch.contains("+updated"), "patch: {patch}");
+    assert!(patch.contains("+fn added() {}"), "patch: {patch}");
+    assert_eq!(
+        fs::read_to_string(project_root.join("README.md")).expect("readme"),
+        "seed"
+    );
+    assert!(!project_root.join("src/new.rs").exists());
+    assert_eq!(
+        repo.head().expect("head").target(),
+        Some(seed),
+        "patch mode must not commit"
+    );
+    let staged = repo
+        .diff_tree_to_index(
+            Some(&repo.find_commit(seed).expect("seed").tree().expect("tree")),
+            None,
+            None,
+        )
+        .expect("staged diff");
+    assert_eq!(
+        staged.deltas().len(),
+        0,
+        "patch mode must leave nothing staged"
+    );
+}
+
+#[test]
+fn workflow_runtime_git_commit_split_pairs_code_and_narrative_commits() {
+    let temp = TempDir::new().expect("temp dir");
+    let repo = init_repo(&temp).expect("init repo");
+    let seed = seed_repo(&repo).expect("seed repo");
+    let project_root = temp.path();
+    let jobs_root = project_root.join(".vizier/jobs");
+
+    enqueue_job(
+        project_root,
+        &jobs_root,
+        "job-split-commit",
+        &["--help".to_string()],
+        &["vizier".to_string(), "__workflow-node".to_string()],
+        None,
+        None,
+        Some(JobSchedule::default()),
+    )
+    .expect("enqueue");
+
+    fs::write(project_root.join("README.md"), "updated\n").expect("update readme");
+    fs::create_dir_all(project_root.join(".vizier/narrative")).expect("narrative dir");
+    fs::write(
+        project_root.join(".vizier/narrative/snapshot.md"),
+        "README updated\n",
+    )
+    .expect("write snapshot");
+    let mut index = repo.index().expect("index");
+    for path in ["README.md", ".vizier/narrative/snapshot.md"] {
+        index.add_path(Path::new(path)).expect("stage");
+    }
+    index.write().expect("write index");
+
+    let record = read_record(&jobs_root, "job-split-commit").expect("record");
+    let node = runtime_executor_node(
+        "commit",
+        "job-split-commit",
+        "cap.env.builtin.git.commit",
+        "git.commit",
+        BTreeMap::from([
+            ("message".to_string(), "docs: update readme".to_string()),
+            ("split".to_string(), "true".to_string()),
+        ]),
+    );
+    let result =
+        execute_workflow_executor(project_root, &jobs_root, &record, &node).expect("git.commit");
+    assert_eq!(result.outcome, WorkflowNodeOutcome::Succeeded);
+    assert!(
+        result
+            .summary
+            .as_deref()
+            .is_some_and(|summary| summary.contains("separately")),
+        "summary: {:?}",
+        result.summary
+    );
+
+    let narrative = repo
+        .head()
+        .expect("head")
+        .peel_to_commit()
+        .expect("narrative");
+    let code = narrative.parent(0).expect("code commit");
+    assert_eq!(code.parent_id(0).expect("seed parent"), seed);
+    assert!(
+        code.tree()
+            .expect("tree")
+            .get_path(Path::new(".vizier/narrative/snapshot.md"))
+            .is_err(),
+        "code commit must not carry narrative changes"
+    );
+    assert_eq!(code.summary(), Some("docs: update readme"));
+    assert_eq!(narrative.summary(), Some("chore: update narrative"));
+    assert!(
+        narrative
+            .message()
+            .is_some_and(|message| message.contains(&format!("Vizier-Code-Commit: {}", code.id()))),
+        "narrative message: {:?}",
+        narrative.message()
+    );
+}
+
 #[test]
 fn workflow_runtime_patch_pipeline_prepare_execute_and_finalize() {
     let temp = TempDir::new().expect("temp dir");
@@ -6178,6 +7263,131 @@ fn stop_condition_runtime_blocks_when_retry_budget_is_exhausted() {
     );
 }
 
+#[test]
+fn stop_condition_runtime_evaluates_named_conditions_per_attempt() {
+    let temp = TempDir::new().expect("temp dir");
+    init_repo(&temp).expect("init repo");
+    let project_root = temp.path();
+    let jobs_root = project_root.join(".vizier/jobs");
+
+    enqueue_job(
+        project_root,
+        &jobs_root,
+        "job-stop-conditions",
+        &["--help".to_string()],
+        &["vizier".to_string(), "__workflow-node".to_string()],
+        Some(JobMetadata {
+            workflow_node_attempt: Some(1),
+            ..JobMetadata::default()
+        }),
+        None,
+        Some(JobSchedule::default()),
+    )
+    .expect("enqueue");
+    let record = read_record(&jobs_root, "job-stop-conditions").expect("record");
+    fs::write(project_root.join("notes.txt"), "TODO: later\n").expect("write notes");
+
+    let node = |conditions: &str| WorkflowRuntimeNodeManifest {
+        node_id: "gate".to_string(),
+        name: None,
+        job_id: "job-stop-conditions".to_string(),
+        uses: "control.gate.stop_condition".to_string(),
+        kind: WorkflowNodeKind::Gate,
+        args: BTreeMap::from([
+            ("conditions".to_string(), conditions.to_string()),
+            ("tests".to_string(), "true".to_string()),
+        ]),
+        executor_operation: None,
+        control_policy: Some("gate.stop_condition".to_string()),
+        gates: Vec::new(),
+        retry: crate::workflow_template::WorkflowRetryPolicy {
+            mode: WorkflowRetryMode::UntilGate,
+            budget: 2,
+        },
+        routes: WorkflowRouteTargets::default(),
+        artifacts_by_outcome: WorkflowOutcomeArtifactsByOutcome::default(),
+    };
+
+    let result = execute_workflow_control(
+        project_root,
+        &record,
+        &node("tests_pass && no_todo_added && diff_lines > 0"),
+    )
+    .expect("execute stop-condition gate");
+    assert_eq!(result.outcome, WorkflowNodeOutcome::Failed);
+    let summary = result.summary.as_deref().unwrap_or("");
+    assert!(
+        summary.contains("fail no_todo_added (todo_added = 1)"),
+        "{summary}"
+    );
+    let stdout = result.stdout_text.as_deref().unwrap_or("");
+    assert!(stdout.contains("pass tests_pass"), "{stdout}");
+    assert!(stdout.contains("pass diff_lines > 0"), "{stdout}");
+
+    let result = execute_workflow_control(
+        project_root,
+        &record,
+        &node("tests_pass && todo_added <= 1"),
+    )
+    .expect("execute stop-condition gate");
+    assert_eq!(result.outcome, WorkflowNodeOutcome::Succeeded);
+}
+
+#[test]
+fn stop_condition_runtime_exports_gate_context_to_script() {
+    let temp = TempDir::new().expect("temp dir");
+    init_repo(&temp).expect("init repo");
+    let project_root = temp.path();
+    let jobs_root = project_root.join(".vizier/jobs");
+
+    enqueue_job(
+        project_root,
+        &jobs_root,
+        "job-stop-context",
+        &["--help".to_string()],
+        &["vizier".to_string(), "__workflow-node".to_string()],
+        Some(JobMetadata {
+            branch: Some("draft/alpha".to_string()),
+            target: Some("main".to_string()),
+            workflow_node_attempt: Some(2),
+            ..JobMetadata::default()
+        }),
+        None,
+        Some(JobSchedule::default()),
+    )
+    .expect("enqueue");
+    let record = read_record(&jobs_root, "job-stop-context").expect("record");
+
+    let script = r#"test "$VIZIER_PLAN" = alpha \
+        && test "$VIZIER_BRANCH" = draft/alpha \
+        && test "$VIZIER_TARGET" = main \
+        && test "$VIZIER_ATTEMPT" = 2 \
+        && test -n "$VIZIER_SESSION_ID" \
+        && grep -q '"plan": "alpha"' "$VIZIER_GATE_CONTEXT" \
+        && printf '%s' "$VIZIER_WORKTREE""#;
+    let node = runtime_control_node(
+        "gate",
+        "job-stop-context",
+        "control.gate.stop_condition",
+        "gate.stop_condition",
+        BTreeMap::from([("script".to_string(), script.to_string())]),
+    );
+
+    let result = execute_workflow_control(project_root, &record, &node)
+        .expect("execute stop-condition gate");
+    assert_eq!(
+        result.outcome,
+        WorkflowNodeOutcome::Succeeded,
+        "{:?}",
+        result.summary
+    );
+    assert_eq!(
+        result.stdout_text.as_deref().map(PathBuf::from),
+        Some(project_root.canonicalize().expect("canonical root"))
+    );
+    assert!(gate_context_path(&jobs_root, "job-stop-context").is_file());
+}
+
 #[test]
 fn gc_jobs_preserves_terminal_records_referenced_by_active_after_dependencies() {
     let temp = TempDir::new().expect("temp dir");
@@ -6285,6 +7495,8 @@ fn clean_job_scope_single_job_removes_job_record_artifacts_and_plan_state() {
         work_ref: Some("workflow-job:job-clean-single".to_string()),
         status: None,
         summary: None,
+        size: None,
+        depends_on: None,
         created_at: timestamp.clone(),
         updated_at: timestamp,
         job_ids: HashMap::from([("persist".to_string(), "job-clean-single".to_string())]),
@@ -6565,6 +7777,8 @@ fn clean_job_scope_rewrites_plan_state_when_non_scoped_refs_remain() {
         work_ref: Some("workflow-job:job-clean-plan-target".to_string()),
         status: None,
         summary: None,
+        size: None,
+        depends_on: None,
         created_at: timestamp.clone(),
         updated_at: timestamp,
         job_ids: HashMap::from([
@@ -6605,3 +7819,47 @@ fn clean_job_scope_rewrites_plan_state_when_non_scoped_refs_remain() {
         Some("job-clean-plan-keep")
     );
 }
+
+#[test]
+fn interrupt_cleanup_removes_clean_worktrees_and_preserves_dirty_ones() {
+    let temp = TempDir::new().expect("temp dir");
+    let repo = init_repo(&temp).expect("init repo");
+    seed_repo(&repo).expect("seed repo");
+    let project_root = temp.path();
+
+    let mut results = Vec::new();
+    for (name, dirty) in [("interrupt-clean", false), ("interrupt-dirty", true)] {
+        ensure_branch(&repo, name).expect("branch");
+        let worktree_rel = format!(".vizier/tmp-worktrees/{name}");
+        let worktree_path = project_root.join(&worktree_rel);
+        fs::create_dir_all(worktree_path.parent().expect("parent")).expect("worktree parent");
+        crate::vcs::add_worktree_for_branch_in(project_root, name, &worktree_path, name)
+            .expect("add worktree");
+        if dirty {
+            fs::write(worktree_path.join("README.md"), "partial agent edit").expect("edit");
+        }
+
+        let mut record = make_record(name, JobStatus::Running, Utc::now(), None);
+        record.metadata = Some(JobMetadata {
+            plan: Some("alpha".to_string()),
+            worktree_name: Some(name.to_string()),
+            worktree_owned: Some(true),
+            worktree_path: Some(worktree_rel),
+            ..JobMetadata::default()
+        });
+        results.push((
+            worktree_path,
+            attempt_interrupt_cleanup(project_root, &record, true),
+        ));
+    }
+
+    let (clean_path, clean) = &results[0];
+    assert_eq!(clean.status, CancelCleanupStatus::Done);
+    assert!(!clean_path.exists(), "clean worktree should be removed");
+
+    let (dirty_path, dirty) = &results[1];
+    assert_eq!(dirty.status, CancelCleanupStatus::Preserved);
+    assert!(dirty_path.exists(), "dirty worktree should be preserved");
+    let reason = dirty.error.as_deref().unwrap_or_default();
+    assert!(reason.contains("vizier rescue alpha"), "{reason}");
+}
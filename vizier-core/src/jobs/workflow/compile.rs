@@ -471,6 +471,7 @@ pub fn enqueue_workflow_run_with_options(
             approval,
             wait_reason: None,
             waited_on: Vec::new(),
+            priority: 0,
         };
 
         let metadata = JobMetadata {
@@ -504,11 +505,24 @@ pub fn enqueue_workflow_run_with_options(
             ..JobMetadata::default()
         };
 
-        let child_args = vec![
+        let mut child_args = Vec::new();
+        let cfg = crate::config::get_config();
+        if let Some(profile) = cfg.profile {
+            // Node processes reload config, so carry the selected profile forward.
+            child_args.extend(["--profile".to_string(), profile]);
+        }
+        if let Some(dir) = cfg.worktrees.dir {
+            // Likewise `--worktree-dir`, so node worktrees land where the run was told.
+            child_args.extend([
+                "--worktree-dir".to_string(),
+                dir.to_string_lossy().into_owned(),
+            ]);
+        }
+        child_args.extend([
             "__workflow-node".to_string(),
             "--job-id".to_string(),
             job_id.clone(),
-        ];
+        ]);
         let command = if recorded_args.is_empty() {
             vec![
                 "vizier".to_string(),
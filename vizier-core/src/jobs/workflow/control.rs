@@ -1,4 +1,5 @@
 use super::*;
+use crate::stop_condition;
 
 pub(crate) fn execute_workflow_control(
     project_root: &Path,
@@ -25,13 +26,32 @@ pub(crate) fn execute_workflow_control(
             let script = first_non_empty_arg(&node.args, &["script"])
                 .or_else(|| script_gate_script(node))
                 .unwrap_or_default();
-            if script.is_empty() {
+            let conditions = stop_condition_expression(record, node);
+            if script.is_empty() && conditions.is_none() {
                 return Ok(WorkflowNodeResult::succeeded(
                     "stop-condition gate skipped (no script configured)",
                 ));
             }
 
-            let (status, stdout, stderr) = run_shell_text_command(&execution_root, &script)?;
+            let attempt = record
+                .metadata
+                .as_ref()
+                .and_then(|meta| meta.workflow_node_attempt)
+                .unwrap_or(1);
+            let env = GateScriptContext::for_job(&execution_root, record, Some(node))
+                .env(project_root)?;
+            let (status, stdout, stderr) = match conditions {
+                Some(conditions) => run_stop_conditions(
+                    &execution_root,
+                    record,
+                    node,
+                    &conditions,
+                    &script,
+                    attempt,
+                    &env,
+                )?,
+                None => run_shell_text_command_with_env(&execution_root, &script, &env)?,
+            };
             print_stdout_text(&stdout);
             print_stderr_text(&stderr);
             let stderr_lines = stderr_lines_from_text(&stderr);
@@ -44,11 +64,6 @@ pub(crate) fn execute_workflow_control(
                 return Ok(result);
             }
 
-            let attempt = record
-                .metadata
-                .as_ref()
-                .and_then(|meta| meta.workflow_node_attempt)
-                .unwrap_or(1);
             let retry_budget = node.retry.budget.saturating_add(1);
             if matches!(node.retry.mode, WorkflowRetryMode::UntilGate) && attempt > retry_budget {
                 let result = WorkflowNodeResult {
@@ -164,6 +179,10 @@ pub(crate) fn execute_workflow_control(
                     "remaining unmerged paths: {}",
                     conflict_paths.join(", ")
                 ));
+                stderr_lines.push(format!(
+                    "resolve them interactively with `vizier jobs resolve {}`",
+                    record.id
+                ));
                 let summary = format!(
                     "merge conflict resolution incomplete for slug `{slug}`: unmerged index entries remain"
                 );
@@ -208,10 +227,28 @@ pub(crate) fn execute_workflow_control(
                 .as_ref()
                 .and_then(|meta| meta.workflow_node_attempt)
                 .unwrap_or(1);
+            if !bool_arg(&node.args, "no_cache").unwrap_or(false)
+                && let Some(entry) = crate::gate_cache::cached_pass_for_merge(
+                    project_root,
+                    &execution_root,
+                    &crate::gate_cache::config_hash(&pre_merge_and_cicd_gates()),
+                    &script,
+                )
+            {
+                let notation = crate::gate_cache::cached_pass_notation(&entry);
+                let line = format!("[workflow-node] cicd {notation}");
+                eprintln!("{line}");
+                let mut result = WorkflowNodeResult::succeeded(format!("cicd {notation}"));
+                result.stderr_lines = vec![line];
+                return Ok(result);
+            }
             let mut stdout_text = String::new();
             let mut stderr_lines = Vec::new();
+            let env = GateScriptContext::for_job(&execution_root, record, Some(node))
+                .env(project_root)?;
 
-            let (status, stdout, stderr) = run_shell_text_command(&execution_root, &script)?;
+            let (status, stdout, stderr) =
+                run_shell_text_command_with_env(&execution_root, &script, &env)?;
             print_stdout_text(&stdout);
             print_stderr_text(&stderr);
             if !stdout.is_empty() {
@@ -235,7 +272,7 @@ pub(crate) fn execute_workflow_control(
                 )
             {
                 let (fix_status, fix_stdout, fix_stderr) =
-                    run_shell_text_command(&execution_root, &fix_script)?;
+                    run_shell_text_command_with_env(&execution_root, &fix_script, &env)?;
                 print_stdout_text(&fix_stdout);
                 print_stderr_text(&fix_stderr);
                 if !fix_stdout.is_empty() {
@@ -244,7 +281,7 @@ pub(crate) fn execute_workflow_control(
                 stderr_lines.extend(stderr_lines_from_text(&fix_stderr));
                 if fix_status == 0 {
                     let (retry_status, retry_stdout, retry_stderr) =
-                        run_shell_text_command(&execution_root, &script)?;
+                        run_shell_text_command_with_env(&execution_root, &script, &env)?;
                     print_stdout_text(&retry_stdout);
                     print_stderr_text(&retry_stderr);
                     if !retry_stdout.is_empty() {
@@ -323,3 +360,94 @@ pub(crate) fn execute_workflow_control(
         )),
     }
 }
+
+/// Named conditions for a stop gate: the node's `conditions` arg, else
+/// `approve.stop_condition.conditions` for approve runs.
+fn stop_condition_expression(
+    record: &JobRecord,
+    node: &WorkflowRuntimeNodeManifest,
+) -> Option<String> {
+    first_non_empty_arg(&node.args, &["conditions"]).or_else(|| {
+        let approve = record
+            .metadata
+            .as_ref()
+            .and_then(|meta| meta.command_alias.as_deref())
+            == Some("approve");
+        if approve {
+            config::get_config().approve.stop_condition.conditions
+        } else {
+            None
+        }
+    })
+}
+
+/// Evaluates a stop gate's named conditions, logging each result for this attempt. A configured
+/// script still has to pass: it is required alongside the expression unless the expression
+/// already references `script_pass`.
+fn run_stop_conditions(
+    execution_root: &Path,
+    record: &JobRecord,
+    node: &WorkflowRuntimeNodeManifest,
+    conditions: &str,
+    script: &str,
+    attempt: u32,
+    env: &[(String, String)],
+) -> Result<(i32, String, String), Box<dyn std::error::Error>> {
+    let mut expression = stop_condition::StopExpression::parse(conditions)
+        .map_err(|err| format!("invalid stop-condition expression `{conditions}`: {err}"))?;
+    if !script.is_empty()
+        && !expression
+            .conditions()
+            .iter()
+            .any(|condition| condition.metric() == "script_pass")
+    {
+        expression =
+            stop_condition::StopExpression::parse(&format!("script_pass && ({conditions})"))?;
+    }
+    let tests = first_non_empty_arg(&node.args, &["tests"])
+        .or_else(|| config::get_config().approve.stop_condition.tests);
+    let target = record
+        .metadata
+        .as_ref()
+        .and_then(|meta| meta.target.as_deref());
+    let evaluation = stop_condition::evaluate(
+        &expression,
+        &stop_condition::StopContext {
+            root: execution_root,
+            target,
+            script: (!script.is_empty()).then_some(script),
+            tests: tests.as_deref(),
+            env,
+        },
+    );
+
+    let mut stdout = evaluation.output.clone();
+    let mut stderr = String::new();
+    for result in &evaluation.results {
+        let verdict = if result.passed { "pass" } else { "fail" };
+        let line = format!("{verdict} {} ({})", result.condition, result.detail);
+        display::log(
+            display::Subsystem::Gate,
+            display::LogLevel::Info,
+            format!("stop-condition attempt {attempt}: {line}"),
+        );
+        stdout.push_str(&line);
+        stdout.push('\n');
+        if !result.passed {
+            stderr.push_str(&line);
+            stderr.push('\n');
+        }
+    }
+    crate::auditor::Auditor::record_operation(
+        "stop_condition",
+        serde_json::json!({
+            "node": node.node_id,
+            "attempt": attempt,
+            "expression": conditions,
+            "passed": evaluation.passed,
+            "conditions": evaluation.results,
+        }),
+    );
+    let status = if evaluation.passed { 0 } else { 1 };
+    Ok((status, stdout, stderr))
+}
@@ -7,6 +7,11 @@ pub(crate) fn execute_workflow_executor(
     node: &WorkflowRuntimeNodeManifest,
 ) -> Result<WorkflowNodeResult, Box<dyn std::error::Error>> {
     let execution_root = resolve_execution_root(project_root, record)?;
+    if node.executor_operation.as_deref() == Some("git.integrate_plan_branch")
+        && crate::vcs::is_bare_repo(&execution_root)
+    {
+        return integrate_in_bare_repo(project_root, jobs_root, record, node);
+    }
     match node.executor_operation.as_deref() {
         Some("worktree.prepare") => {
             let branch = first_non_empty_arg(&node.args, &["branch"])
@@ -46,7 +51,7 @@ pub(crate) fn execute_workflow_executor(
             let purpose = first_non_empty_arg(&node.args, &["purpose"])
                 .unwrap_or_else(|| sanitize_workflow_component(&node.node_id));
             let dir_name = format!("{}-{}", sanitize_workflow_component(&purpose), record.id);
-            let worktree_path = project_root.join(".vizier/tmp-worktrees").join(&dir_name);
+            let worktree_path = crate::worktrees::worktrees_root(project_root).join(&dir_name);
             if let Some(parent) = worktree_path.parent() {
                 fs::create_dir_all(parent)?;
             }
@@ -216,8 +221,50 @@ pub(crate) fn execute_workflow_executor(
                 )?;
             let prompt_text = resolve_prompt_payload_text(&payload)
                 .ok_or_else(|| "prompt payload missing text field".to_string())?;
+            let prompt_text = append_operator_note(
+                prompt_text,
+                record
+                    .metadata
+                    .as_ref()
+                    .and_then(|metadata| metadata.operator_note.as_deref()),
+            );
+            let (prompt_text, context_documents) = append_context_documents(
+                prompt_text,
+                record
+                    .metadata
+                    .as_ref()
+                    .and_then(|metadata| metadata.context_files.as_deref()),
+                project_root,
+            );
+            let mut context_lines = Vec::new();
+            if !context_documents.is_empty() {
+                for document in &context_documents {
+                    let line = match document.get("error").and_then(|err| err.as_str()) {
+                        Some(err) => format!(
+                            "[workflow-node] warn agent.invoke context `{}` skipped: {err}",
+                            document["path"].as_str().unwrap_or_default()
+                        ),
+                        None => format!(
+                            "[workflow-node] info agent.invoke context `{}` ({} bytes{})",
+                            document["path"].as_str().unwrap_or_default(),
+                            document["included_bytes"],
+                            if document["truncated"].as_bool().unwrap_or(false) {
+                                ", truncated"
+                            } else {
+                                ""
+                            }
+                        ),
+                    };
+                    eprintln!("{line}");
+                    context_lines.push(line);
+                }
+                crate::auditor::Auditor::record_operation(
+                    "context_documents",
+                    serde_json::json!({ "job": record.id, "documents": context_documents }),
+                );
+            }
 
-            let agent_settings = match resolve_workflow_agent_settings(record) {
+            let mut agent_settings = match resolve_workflow_agent_settings(record) {
                 Ok(settings) => settings,
                 Err(err) => {
                     return Ok(WorkflowNodeResult::failed(
@@ -235,24 +282,181 @@ pub(crate) fn execute_workflow_executor(
                     ));
                 }
             };
+            let narrative_scope = match first_non_empty_arg(&node.args, &["only"]) {
+                Some(spec) => {
+                    let allowed = match crate::file_tracking::NarrativeScope::parse_targets(&spec) {
+                        Ok(allowed) => allowed,
+                        Err(err) => {
+                            return Ok(WorkflowNodeResult::failed(
+                                format!("agent.invoke {err}"),
+                                Some(1),
+                            ));
+                        }
+                    };
+                    Some(crate::file_tracking::NarrativeScope::capture(
+                        &execution_root,
+                        allowed,
+                    )?)
+                }
+                None => None,
+            };
+            // Review agents may navigate the repo through `vizier tool`; their calls land in the job dir.
+            let review_tool_log = (agent_settings.scope == Some(config::CommandScope::Review)
+                && config::get_config().review.tools)
+                .then(|| {
+                    paths_for(jobs_root, &record.id)
+                        .job_dir
+                        .join("review-tools.jsonl")
+                });
+            let prompt_text = match review_tool_log.as_ref() {
+                Some(log_path) => {
+                    let binary =
+                        std::env::current_exe().unwrap_or_else(|_| PathBuf::from("vizier"));
+                    agent_settings.env.vars.insert(
+                        crate::review_tools::TOOL_LOG_ENV.to_string(),
+                        log_path.display().to_string(),
+                    );
+                    format!(
+                        "{prompt_text}\n\n{}",
+                        crate::review_tools::prompt_block(&binary)
+                    )
+                }
+                None => prompt_text,
+            };
+            // Reviews call out work the stored plan does not describe, such as commits pushed by
+            // hand after approve.
+            let prompt_text = if agent_settings.scope == Some(config::CommandScope::Review) {
+                let (block, lines) = plan_scope_block(project_root, &execution_root, record, node);
+                context_lines.extend(lines);
+                match block {
+                    Some(block) => format!("{prompt_text}\n\n{block}"),
+                    None => prompt_text,
+                }
+            } else {
+                prompt_text
+            };
+            // Squash-by-step merges group the plan branch by the step markers approve commits carry.
+            let prompt_text = if agent_settings.scope == Some(config::CommandScope::Approve)
+                && config::get_config().merge.squash_granularity == config::SquashGranularity::Step
+            {
+                format!("{prompt_text}\n\n{}", crate::plan::STEP_COMMIT_INSTRUCTIONS)
+            } else {
+                prompt_text
+            };
             let request = build_workflow_agent_request(
                 &agent_settings,
                 prompt_text,
                 execution_root.to_path_buf(),
             );
-            let response = execute_agent_request_blocking(runner, request);
+            // Nodes whose reply depends only on the prompt (commit messages) may opt into reuse.
+            let cache_ttl_secs = node
+                .args
+                .get("cache_ttl_secs")
+                .and_then(|value| value.trim().parse::<u64>().ok())
+                .filter(|ttl| *ttl > 0);
+            let cache_key = cache_ttl_secs.map(|_| {
+                crate::agent_cache::prompt_hash(
+                    &agent_settings.selector,
+                    &agent_settings.agent_runtime.command,
+                    &request.prompt,
+                )
+            });
+            let cached = match (cache_key.as_deref(), cache_ttl_secs) {
+                (Some(hash), Some(ttl)) => {
+                    crate::agent_cache::lookup(project_root, hash, ttl, Utc::now())
+                }
+                _ => None,
+            };
+            let sent_prompt = record
+                .metadata
+                .as_ref()
+                .and_then(|metadata| metadata.cost_estimate.as_ref())
+                .filter(|_| cached.is_none())
+                .map(|_| request.prompt.clone());
+            let response = match cached.as_ref() {
+                Some(entry) => Ok(crate::agent::AgentResponse {
+                    assistant_text: entry.text.clone(),
+                    stderr: Vec::new(),
+                    exit_code: 0,
+                    duration_ms: 0,
+                }),
+                None => execute_agent_request_blocking(runner, request),
+            };
+            let mut scope_lines = Vec::new();
+            if let Some(scope) = narrative_scope.as_ref() {
+                for path in scope.enforce(&execution_root)? {
+                    let line = format!(
+                        "[workflow-node] warn agent.invoke reverted `{path}` (outside --only {})",
+                        scope.allowed().join(", ")
+                    );
+                    eprintln!("{line}");
+                    scope_lines.push(line);
+                }
+            }
             match response {
                 Ok(response) => {
                     let assistant_text = response.assistant_text.clone();
-                    let stderr_lines = response.stderr.clone();
+                    let mut stderr_lines = response.stderr.clone();
                     print_stdout_text(&assistant_text);
                     for line in &stderr_lines {
                         eprintln!("{line}");
                     }
+                    stderr_lines.extend(scope_lines);
+                    stderr_lines.extend(context_lines);
+                    let tool_calls = review_tool_log
+                        .as_deref()
+                        .map(crate::review_tools::read_calls)
+                        .unwrap_or_default();
+                    if !tool_calls.is_empty() {
+                        let line = format!(
+                            "[workflow-node] info agent.invoke review tools: {} call(s) logged to {}",
+                            tool_calls.len(),
+                            review_tool_log
+                                .as_deref()
+                                .map(|path| relative_path(project_root, path))
+                                .unwrap_or_default()
+                        );
+                        eprintln!("{line}");
+                        stderr_lines.push(line);
+                        crate::auditor::Auditor::record_operation(
+                            "review_tool_calls",
+                            serde_json::json!({ "job": record.id, "calls": tool_calls }),
+                        );
+                    }
 
-                    let mut result = WorkflowNodeResult::succeeded(
-                        "agent.invoke completed via configured runner",
-                    );
+                    let summary = match (cached.as_ref(), cache_key, cache_ttl_secs) {
+                        (Some(entry), _, _) => {
+                            let notation = entry.notation(Utc::now());
+                            let line = format!("[workflow-node] info agent.invoke {notation}");
+                            eprintln!("{line}");
+                            stderr_lines.push(line);
+                            format!("agent.invoke reused a cached response ({notation})")
+                        }
+                        (None, Some(prompt_hash), Some(ttl)) if response.exit_code == 0 => {
+                            let entry = crate::agent_cache::CachedResponse {
+                                prompt_hash,
+                                agent_selector: agent_settings.selector.clone(),
+                                text: assistant_text.clone(),
+                                duration_ms: response.duration_ms,
+                                recorded_at: Utc::now().to_rfc3339(),
+                            };
+                            match crate::agent_cache::store(project_root, &entry) {
+                                Ok(()) => format!(
+                                    "agent.invoke completed via configured runner; response cached for {ttl}s"
+                                ),
+                                Err(err) => {
+                                    let line = format!(
+                                        "[workflow-node] warn agent.invoke could not cache response: {err}"
+                                    );
+                                    eprintln!("{line}");
+                                    stderr_lines.push(line);
+                                    "agent.invoke completed via configured runner".to_string()
+                                }
+                            }
+                        }
+                        _ => "agent.invoke completed via configured runner".to_string(),
+                    };
+                    let mut result = WorkflowNodeResult::succeeded(summary);
                     if !assistant_text.is_empty() {
                         result.stdout_text = Some(assistant_text.clone());
                     }
@@ -300,6 +504,13 @@ pub(crate) fn execute_workflow_executor(
                         config_agent_label: Some(agent_settings.agent_runtime.label.clone()),
                         config_agent_command: Some(agent_settings.agent_runtime.command.clone()),
                         agent_exit_code: Some(response.exit_code),
+                        cost_actual: sent_prompt.as_deref().map(|prompt| {
+                            crate::cost::CostActual::measure(
+                                prompt,
+                                &assistant_text,
+                                &crate::config::get_config().approve.cost,
+                            )
+                        }),
                         ..JobMetadata::default()
                     });
                     Ok(result)
@@ -325,6 +536,12 @@ pub(crate) fn execute_workflow_executor(
                 )),
             }
         }
+        Some("agent.generate_tests") => {
+            generate_tests(project_root, jobs_root, &execution_root, record, node)
+        }
+        Some("agent.review_fix_loop") => {
+            review_fix_loop(project_root, jobs_root, &execution_root, record, node)
+        }
         Some("plan.persist") => {
             let spec_source = node
                 .args
@@ -444,8 +661,92 @@ pub(crate) fn execute_workflow_executor(
             let plan_body = first_non_empty_arg(&node.args, &["plan_body", "plan_text", "content"])
                 .or(plan_body_from_dependency)
                 .unwrap_or_else(|| spec_text.clone());
-            let doc_contents =
-                crate::plan::render_plan_document(&plan_id, &slug, &branch, &spec_text, &plan_body);
+            // `vizier run --edit-plan` leaves the operator's edit beside the job while it waits
+            // on approval; it replaces the agent's draft and is flagged in the front matter.
+            let amendment = fs::read_to_string(plan_amendment_path(jobs_root, &record.id)).ok();
+            let human_amended = amendment.is_some();
+            let plan_body = amendment.unwrap_or(plan_body);
+            let finish_doc = |doc: String| {
+                if human_amended {
+                    crate::plan::mark_human_amended(&doc)
+                } else {
+                    doc
+                }
+            };
+            let mut labels = Vec::new();
+            for raw in first_non_empty_arg(&node.args, &["labels", "label"])
+                .unwrap_or_default()
+                .split(',')
+                .filter(|raw| !raw.trim().is_empty())
+            {
+                match crate::plan::sanitize_label(raw) {
+                    Ok(label) if !labels.contains(&label) => labels.push(label),
+                    Ok(_) => {}
+                    Err(err) => {
+                        return Ok(WorkflowNodeResult::failed(
+                            format!("plan.persist invalid --label: {err}"),
+                            Some(1),
+                        ));
+                    }
+                }
+            }
+            let spawned_by = match first_non_empty_arg(&node.args, &["spawned_by"]) {
+                Some(parent) => match crate::plan::sanitize_name_override(&parent) {
+                    Ok(parent) => Some(parent),
+                    Err(err) => {
+                        return Ok(WorkflowNodeResult::failed(
+                            format!("plan.persist invalid --spawned-by: {err}"),
+                            Some(1),
+                        ));
+                    }
+                },
+                None => None,
+            };
+            let split = bool_arg(&node.args, "split").unwrap_or(false);
+            let mut parts = if split {
+                crate::plan::split_plan_parts(&plan_body)
+            } else {
+                Vec::new()
+            };
+            let plan_body = if parts.is_empty() {
+                plan_body
+            } else {
+                parts.remove(0).body
+            };
+            let draft_limits = config::get_config().draft;
+            let earlier_sizes = crate::plan::recorded_plan_sizes(&execution_root);
+            let mut stderr_lines = Vec::new();
+            let size = crate::plan::estimate_plan_size(&plan_body);
+            warn_oversized_plan(
+                &slug,
+                size,
+                &draft_limits,
+                &earlier_sizes,
+                !split,
+                &mut stderr_lines,
+            );
+            let target_branch = first_non_empty_arg(&node.args, &["target_branch"]).or_else(|| {
+                record
+                    .metadata
+                    .as_ref()
+                    .and_then(|meta| meta.target.clone())
+            });
+            warn_plan_overlaps(
+                &execution_root,
+                &crate::overlap::PlanSurface::from_plan_body(&slug, &branch, &plan_body),
+                target_branch.as_deref(),
+                &mut stderr_lines,
+            );
+            let doc_contents = finish_doc(crate::plan::render_plan_document(
+                &plan_id,
+                &slug,
+                &branch,
+                &spec_text,
+                &plan_body,
+                None,
+                &labels,
+                spawned_by.as_deref(),
+            ));
             let plan_rel = crate::plan::plan_rel_path(&slug);
             let plan_abs = execution_root.join(&plan_rel);
             if let Err(err) = crate::plan::write_plan_file(&plan_abs, &doc_contents) {
@@ -469,25 +770,19 @@ pub(crate) fn execute_workflow_executor(
                     branch: Some(branch.clone()),
                     source: Some(spec_source),
                     intent: first_non_empty_arg(&node.args, &["intent"]),
-                    target_branch: first_non_empty_arg(&node.args, &["target_branch"]).or_else(
-                        || {
-                            record
-                                .metadata
-                                .as_ref()
-                                .and_then(|meta| meta.target.clone())
-                        },
-                    ),
+                    target_branch: target_branch.clone(),
                     work_ref: Some(format!("workflow-job:{}", record.id)),
                     status: Some("proposed".to_string()),
-                    summary,
+                    summary: summary.clone(),
+                    size: Some(size),
+                    depends_on: None,
                     updated_at: now.clone(),
-                    created_at: Some(now),
+                    created_at: Some(now.clone()),
                     job_ids: Some(HashMap::from([("persist".to_string(), record.id.clone())])),
                 },
             )?;
 
-            let mut result = WorkflowNodeResult::succeeded("plan persisted");
-            result.artifacts_written = vec![
+            let mut artifacts = vec![
                 JobArtifact::PlanBranch {
                     slug: slug.clone(),
                     branch: branch.clone(),
@@ -497,6 +792,110 @@ pub(crate) fn execute_workflow_executor(
                     branch: branch.clone(),
                 },
             ];
+            let mut owned_branches = if created_branch {
+                vec![branch.clone()]
+            } else {
+                Vec::new()
+            };
+
+            // Later parts of a split plan each get their own branch off the draft base, holding
+            // just their plan doc and state record, so they can be approved one after another.
+            let mut previous_slug = slug.clone();
+            for (index, part) in parts.into_iter().enumerate() {
+                let part_name = part.slug.unwrap_or_else(|| (index + 2).to_string());
+                let part_slug =
+                    match crate::plan::sanitize_name_override(&format!("{slug}-{part_name}")) {
+                        Ok(value) => value,
+                        Err(err) => {
+                            return Ok(WorkflowNodeResult::failed(
+                                format!("plan.persist invalid split slug `{part_name}`: {err}"),
+                                Some(1),
+                            ));
+                        }
+                    };
+                let part_branch = crate::plan::default_branch_for_slug(&part_slug);
+                let part_size = crate::plan::estimate_plan_size(&part.body);
+                warn_oversized_plan(
+                    &part_slug,
+                    part_size,
+                    &draft_limits,
+                    &earlier_sizes,
+                    false,
+                    &mut stderr_lines,
+                );
+                let part_id = crate::plan::new_plan_id();
+                let part_doc = finish_doc(crate::plan::render_plan_document(
+                    &part_id,
+                    &part_slug,
+                    &part_branch,
+                    &spec_text,
+                    &part.body,
+                    Some(&previous_slug),
+                    &labels,
+                    spawned_by.as_deref(),
+                ));
+                let part_record = crate::plan::PlanRecord {
+                    plan_id: part_id.clone(),
+                    slug: Some(part_slug.clone()),
+                    branch: Some(part_branch.clone()),
+                    source: Some("split".to_string()),
+                    intent: first_non_empty_arg(&node.args, &["intent"]),
+                    target_branch: first_non_empty_arg(&node.args, &["target_branch"]),
+                    work_ref: Some(format!("workflow-job:{}", record.id)),
+                    status: Some("proposed".to_string()),
+                    summary: summary.clone(),
+                    size: Some(part_size),
+                    depends_on: Some(previous_slug.clone()),
+                    created_at: now.clone(),
+                    updated_at: now.clone(),
+                    job_ids: HashMap::from([("persist".to_string(), record.id.clone())]),
+                };
+                let part_record_json = serde_json::to_string_pretty(&part_record)?;
+                let part_doc_rel = crate::plan::plan_rel_path(&part_slug);
+                let part_state_rel = crate::plan::plan_state_rel_path(&part_id);
+                if let Err(err) = crate::vcs::commit_files_to_new_branch_in(
+                    &execution_root,
+                    "HEAD",
+                    &part_branch,
+                    &[
+                        (part_doc_rel.as_path(), part_doc.as_str()),
+                        (part_state_rel.as_path(), part_record_json.as_str()),
+                    ],
+                    &format!("chore: draft split plan {part_slug}"),
+                ) {
+                    return Ok(WorkflowNodeResult::failed(
+                        format!(
+                            "plan.persist could not create split plan branch `{part_branch}`: {err}"
+                        ),
+                        Some(1),
+                    ));
+                }
+                let line = format!(
+                    "[workflow-node] info plan.persist split plan `{part_slug}` (after `{previous_slug}`) on `{part_branch}`"
+                );
+                eprintln!("{line}");
+                stderr_lines.push(line);
+                artifacts.push(JobArtifact::PlanBranch {
+                    slug: part_slug.clone(),
+                    branch: part_branch.clone(),
+                });
+                artifacts.push(JobArtifact::PlanDoc {
+                    slug: part_slug.clone(),
+                    branch: part_branch.clone(),
+                });
+                owned_branches.push(part_branch);
+                previous_slug = part_slug;
+            }
+
+            let split_count = artifacts.len() / 2;
+            let mut result =
+                WorkflowNodeResult::succeeded(match (split_count > 1, human_amended) {
+                    (true, _) => format!("plan persisted (split into {split_count} plans)"),
+                    (false, true) => "plan persisted (amended by operator)".to_string(),
+                    (false, false) => "plan persisted".to_string(),
+                });
+            result.artifacts_written = artifacts;
+            result.stderr_lines = stderr_lines;
             result.payload_refs = vec![
                 relative_path(project_root, &plan_abs),
                 relative_path(project_root, &execution_root.join(state_rel)),
@@ -506,10 +905,16 @@ pub(crate) fn execute_workflow_executor(
                     .payload_refs
                     .push(relative_path(project_root, &payload_path));
             }
+            if human_amended {
+                result.payload_refs.push(relative_path(
+                    project_root,
+                    &plan_amendment_path(jobs_root, &record.id),
+                ));
+            }
             result.metadata = Some(JobMetadata {
                 plan: Some(slug),
                 branch: Some(branch.clone()),
-                ephemeral_owned_branches: created_branch.then_some(vec![branch.clone()]),
+                ephemeral_owned_branches: (!owned_branches.is_empty()).then_some(owned_branches),
                 ..JobMetadata::default()
             });
             Ok(result)
@@ -540,6 +945,15 @@ pub(crate) fn execute_workflow_executor(
             Ok(WorkflowNodeResult::succeeded("git.stage staged changes"))
         }
         Some("git.commit") => {
+            if let Some(result) = commit_patch_result(
+                project_root,
+                jobs_root,
+                &execution_root,
+                record,
+                "git.commit",
+            )? {
+                return Ok(result);
+            }
             let staged = match crate::vcs::snapshot_staged(&execution_root.to_string_lossy()) {
                 Ok(staged) => staged,
                 Err(err) => {
@@ -564,10 +978,54 @@ pub(crate) fn execute_workflow_executor(
                     ));
                 }
             };
-            match crate::vcs::commit_staged_in(&execution_root, &message, false) {
-                Ok(_) => Ok(WorkflowNodeResult::succeeded(
-                    "git.commit committed changes",
-                )),
+            // `split` keeps `.vizier/` changes out of the code commit and lands them in a
+            // second, paired commit.
+            let committed = if bool_arg(&node.args, "split").unwrap_or(false) {
+                let narrative_message = first_non_empty_arg(&node.args, &["narrative_message"])
+                    .unwrap_or_else(|| "chore: update narrative".to_string());
+                crate::vcs::commit_staged_split_in(&execution_root, &message, &narrative_message)
+                    .map(|split| match (split.code, split.narrative) {
+                        (Some(code), Some(narrative)) if code != narrative => (
+                            vec![code, narrative],
+                            format!(
+                                "git.commit committed code ({}) and narrative ({}) separately",
+                                &code.to_string()[..7],
+                                &narrative.to_string()[..7]
+                            ),
+                        ),
+                        (code, narrative) => (
+                            code.or(narrative).into_iter().collect(),
+                            "git.commit committed changes".to_string(),
+                        ),
+                    })
+            } else {
+                crate::vcs::commit_staged_in(&execution_root, &message, false)
+                    .map(|oid| (vec![oid], "git.commit committed changes".to_string()))
+            };
+            match committed {
+                Ok((oids, summary)) => {
+                    let plan_slug = commit_plan_slug(&execution_root, record);
+                    for oid in oids {
+                        crate::auditor::record_commit_note(
+                            &execution_root,
+                            oid,
+                            plan_slug.as_deref(),
+                        );
+                    }
+                    let mut result = WorkflowNodeResult::succeeded(summary.clone());
+                    if let Some(check) =
+                        expected_surface_check(project_root, &execution_root, record)
+                    {
+                        let line = format!("[workflow-node] info {}", check.summary_line());
+                        eprintln!("{line}");
+                        result.stderr_lines.push(line);
+                        result.summary = Some(format!("{summary}; {}", check.summary_line()));
+                    }
+                    result
+                        .stderr_lines
+                        .extend(auto_push_plan_branch(&execution_root));
+                    Ok(result)
+                }
                 Err(err) => Ok(WorkflowNodeResult::failed(
                     format!("git.commit failed to create commit: {err}"),
                     Some(1),
@@ -575,6 +1033,15 @@ pub(crate) fn execute_workflow_executor(
             }
         }
         Some("git.stage_commit") => {
+            if let Some(result) = commit_patch_result(
+                project_root,
+                jobs_root,
+                &execution_root,
+                record,
+                "git.stage_commit",
+            )? {
+                return Ok(result);
+            }
             if let Err(err) = crate::vcs::stage_all_in(&execution_root) {
                 return Ok(WorkflowNodeResult::failed(
                     format!("git.stage_commit failed to stage changes: {err}"),
@@ -613,9 +1080,19 @@ pub(crate) fn execute_workflow_executor(
                 }
             };
             match crate::vcs::commit_staged_in(&execution_root, &message, false) {
-                Ok(_) => Ok(WorkflowNodeResult::succeeded(
-                    "git.stage_commit committed changes",
-                )),
+                Ok(oid) => {
+                    crate::auditor::record_commit_note(
+                        &execution_root,
+                        oid,
+                        commit_plan_slug(&execution_root, record).as_deref(),
+                    );
+                    let mut result =
+                        WorkflowNodeResult::succeeded("git.stage_commit committed changes");
+                    result
+                        .stderr_lines
+                        .extend(auto_push_plan_branch(&execution_root));
+                    Ok(result)
+                }
                 Err(err) => Ok(WorkflowNodeResult::failed(
                     format!("git.stage_commit failed to create commit: {err}"),
                     Some(1),
@@ -655,7 +1132,41 @@ pub(crate) fn execute_workflow_executor(
                         .as_ref()
                         .and_then(|meta| meta.target.clone())
                 });
-            let squash = bool_arg(&node.args, "squash").unwrap_or(true);
+            let strategy = match first_non_empty_arg(&node.args, &["strategy"]) {
+                Some(value) => match config::MergeStrategy::parse(&value) {
+                    Some(strategy) => strategy,
+                    None => {
+                        return Ok(WorkflowNodeResult::failed(
+                            format!(
+                                "git.integrate_plan_branch strategy must be squash, merge, or rebase (got `{value}`)"
+                            ),
+                            Some(1),
+                        ));
+                    }
+                },
+                None => config::get_config().merge.strategy.unwrap_or(
+                    if bool_arg(&node.args, "squash").unwrap_or(true) {
+                        config::MergeStrategy::Squash
+                    } else {
+                        config::MergeStrategy::Merge
+                    },
+                ),
+            };
+            let squash = strategy == config::MergeStrategy::Squash;
+            let granularity = match first_non_empty_arg(&node.args, &["squash_granularity"]) {
+                Some(value) => match config::SquashGranularity::parse(&value) {
+                    Some(granularity) => granularity,
+                    None => {
+                        return Ok(WorkflowNodeResult::failed(
+                            format!(
+                                "git.integrate_plan_branch squash_granularity must be plan or step (got `{value}`)"
+                            ),
+                            Some(1),
+                        ));
+                    }
+                },
+                None => config::get_config().merge.squash_granularity,
+            };
             let delete_branch = bool_arg(&node.args, "delete_branch").unwrap_or(false);
             let slug = workflow_slug_from_record(record, node);
             let sentinel = merge_sentinel_path(project_root, &slug);
@@ -664,6 +1175,25 @@ pub(crate) fn execute_workflow_executor(
             let merge_subject = first_non_empty_arg(&node.args, &["message"])
                 .unwrap_or_else(|| format!("feat: merge plan {merge_slug}"));
 
+            if let Some(target) = target_branch
+                .clone()
+                .or_else(|| current_branch_name(&execution_root))
+                && let Some(result) =
+                    protected_target_result(&execution_root, &target, &source_branch)
+            {
+                return Ok(result);
+            }
+            let also = also_targets(
+                node,
+                target_branch
+                    .clone()
+                    .or_else(|| current_branch_name(&execution_root))
+                    .as_deref(),
+            );
+            if let Some(result) = also_targets_block(&execution_root, &also) {
+                return Ok(result);
+            }
+
             if let Some(target) = target_branch.as_ref() {
                 let current = current_branch_name(&execution_root);
                 if current.as_deref() != Some(target.as_str())
@@ -689,12 +1219,118 @@ pub(crate) fn execute_workflow_executor(
                     ));
                 }
             };
+            let owned_threads = match crate::thread_owners::affected_threads(
+                &execution_root,
+                target_branch.as_deref().unwrap_or("HEAD"),
+                &source_branch,
+                plan_document.as_deref(),
+            ) {
+                Ok(threads) => threads,
+                Err(err) => {
+                    display::warn(format!(
+                        "git.integrate_plan_branch could not read narrative thread owners: {err}"
+                    ));
+                    Vec::new()
+                }
+            };
+            if let Some(result) = owner_ack_block(project_root, &merge_slug, &owned_threads) {
+                return Ok(result);
+            }
+            if let Some(result) = thread_state_block(
+                &execution_root,
+                target_branch.as_deref().unwrap_or("HEAD"),
+                &source_branch,
+                &merge_slug,
+                plan_document.as_deref(),
+            ) {
+                return Ok(result);
+            }
+            if bool_arg(&node.args, "impact_report").unwrap_or(true) {
+                emit_impact_report(
+                    project_root,
+                    &execution_root,
+                    target_branch.as_deref().unwrap_or("HEAD"),
+                    &source_branch,
+                    plan_document.as_deref(),
+                );
+            }
+            let with_owners =
+                |summary: &str| match crate::thread_owners::summary_line(&owned_threads) {
+                    Some(owners) => format!("{summary}; {owners}"),
+                    None => summary.to_string(),
+                };
+            // `also` targets get the commits that landed on the primary target, after its
+            // post-merge gates passed.
+            let finish = |summary: String, landed: &[Oid]| {
+                let backports = backport_to_targets(
+                    project_root,
+                    &execution_root,
+                    record,
+                    node,
+                    &merge_slug,
+                    landed,
+                    &also,
+                );
+                with_backports(summary, &backports)
+            };
+            // Archive against the checked-out target before the plan doc leaves the branch; a
+            // failure only skips the archive.
+            let archive = match plan_document.as_deref() {
+                Some(plan) if bool_arg(&node.args, "archive").unwrap_or(true) => {
+                    match plan_archive_for_merge(
+                        project_root,
+                        jobs_root,
+                        &execution_root,
+                        &merge_slug,
+                        &source_branch,
+                        target_branch.as_deref(),
+                        plan,
+                    ) {
+                        Ok(archive) => Some(archive),
+                        Err(err) => {
+                            display::warn(format!(
+                                "git.integrate_plan_branch could not archive plan {merge_slug}: {err}"
+                            ));
+                            None
+                        }
+                    }
+                }
+                _ => None,
+            };
+            // The changelog fragment rides along in the plan cleanup commit so the merge carries
+            // it; a failure only skips the fragment.
+            let changelog_config = config::get_config().changelog;
+            let fragment = match plan_document.as_deref() {
+                Some(plan) if changelog_config.enabled => {
+                    match crate::changelog::fragment_for_plan(
+                        &execution_root,
+                        &source_branch,
+                        target_branch.as_deref(),
+                        &merge_slug,
+                        plan,
+                        &changelog_config,
+                    ) {
+                        Ok(fragment) => fragment,
+                        Err(err) => {
+                            display::warn(format!(
+                                "git.integrate_plan_branch could not build a changelog fragment for {merge_slug}: {err}"
+                            ));
+                            None
+                        }
+                    }
+                }
+                _ => None,
+            };
             if plan_document.is_some()
                 && let Err(err) = ensure_source_plan_doc_removed_before_merge(
                     &execution_root,
                     &source_branch,
                     target_branch.as_deref(),
                     &merge_slug,
+                    archive.as_ref(),
+                    fragment
+                        .as_ref()
+                        .map(|fragment| (fragment, &changelog_config)),
                 )
             {
                 return Ok(WorkflowNodeResult::failed(
@@ -704,8 +1340,19 @@ pub(crate) fn execute_workflow_executor(
                     Some(1),
                 ));
             }
-            let merge_message =
-                merge_commit_message_with_plan(&merge_subject, plan_document.as_deref());
+            let merge_target = target_branch
+                .clone()
+                .or_else(|| current_branch_name(&execution_root));
+            let merge_message = plan_merge_commit_message(
+                project_root,
+                jobs_root,
+                record,
+                &merge_slug,
+                &source_branch,
+                merge_target.as_deref(),
+                &merge_subject,
+                plan_document.as_deref(),
+            );
 
             let finalize_in_progress = match Repository::open(&execution_root) {
                 Ok(repo) if repo.state() == git2::RepositoryState::Merge => match repo.index() {
@@ -771,16 +1418,35 @@ pub(crate) fn execute_workflow_executor(
                         source_oid,
                     )
                 };
-                if let Err(err) = commit_result {
-                    let summary = if squash {
-                        format!("git.integrate_plan_branch finalize squash merge failed: {err}")
-                    } else {
-                        format!("git.integrate_plan_branch finalize merge failed: {err}")
-                    };
-                    return Ok(WorkflowNodeResult::failed(summary, Some(1)));
-                }
+                let merge_commit = match commit_result {
+                    Ok(oid) => oid,
+                    Err(err) => {
+                        let summary = if squash {
+                            format!("git.integrate_plan_branch finalize squash merge failed: {err}")
+                        } else {
+                            format!("git.integrate_plan_branch finalize merge failed: {err}")
+                        };
+                        return Ok(WorkflowNodeResult::failed(summary, Some(1)));
+                    }
+                };
+                crate::auditor::record_commit_note(
+                    &execution_root,
+                    merge_commit,
+                    Some(&merge_slug),
+                );
 
                 let _ = remove_file_if_exists(&sentinel);
+                if let Some(result) = run_post_merge_gates(
+                    project_root,
+                    jobs_root,
+                    &execution_root,
+                    record,
+                    node,
+                    target_branch.as_deref(),
+                    &[merge_commit],
+                )? {
+                    return Ok(result);
+                }
                 if delete_branch
                     && current_branch_name(&execution_root).as_deref()
                         != Some(source_branch.as_str())
@@ -788,14 +1454,184 @@ pub(crate) fn execute_workflow_executor(
                     let _ = crate::vcs::delete_branch_in(&execution_root, &source_branch);
                 }
 
-                return Ok(WorkflowNodeResult::succeeded(
-                    "git.integrate_plan_branch finalized resolved merge",
+                return Ok(finish(
+                    with_owners("git.integrate_plan_branch finalized resolved merge"),
+                    &[merge_commit],
                 ));
             }
 
+            let use_cache = !bool_arg(&node.args, "no_cache").unwrap_or(false);
+            if let Some(result) = run_pre_merge_gates(
+                project_root,
+                &execution_root,
+                record,
+                &source_branch,
+                use_cache,
+            )? {
+                return Ok(result);
+            }
+
+            if strategy == config::MergeStrategy::Rebase {
+                // With `style = "notes"` the provenance goes to each landed commit's note instead.
+                let notes = matches!(
+                    config::get_config().commits.meta.style,
+                    config::CommitMetaStyle::Notes
+                );
+                let trailers = if notes {
+                    Vec::new()
+                } else {
+                    vec![
+                        format!("Vizier-Plan: {merge_slug}"),
+                        format!("Vizier-Session: {}", crate::auditor::Auditor::session_id()),
+                    ]
+                };
+                let landed = match crate::vcs::rebase_and_fast_forward_in(
+                    &execution_root,
+                    &source_branch,
+                    &trailers,
+                ) {
+                    Ok(crate::vcs::RebaseMergeOutcome::Landed(landed)) => landed,
+                    Ok(crate::vcs::RebaseMergeOutcome::Conflicted { commit, files }) => {
+                        return Ok(WorkflowNodeResult::failed(
+                            format!(
+                                "git.integrate_plan_branch rebase-and-merge stopped: replaying {} conflicts in {}; run `vizier rebase {merge_slug}` to resolve on the plan branch, then rerun the merge",
+                                &commit.to_string()[..7],
+                                files.join(", ")
+                            ),
+                            Some(10),
+                        ));
+                    }
+                    Err(err) => {
+                        return Ok(WorkflowNodeResult::failed(
+                            format!("git.integrate_plan_branch rebase-and-merge failed: {err}"),
+                            Some(1),
+                        ));
+                    }
+                };
+                for commit in &landed {
+                    crate::auditor::record_commit_note(&execution_root, *commit, Some(&merge_slug));
+                }
+                let _ = remove_file_if_exists(&sentinel);
+                if let Some(result) = run_post_merge_gates(
+                    project_root,
+                    jobs_root,
+                    &execution_root,
+                    record,
+                    node,
+                    target_branch.as_deref(),
+                    &landed,
+                )? {
+                    return Ok(result);
+                }
+                if delete_branch
+                    && current_branch_name(&execution_root).as_deref()
+                        != Some(source_branch.as_str())
+                {
+                    let _ = crate::vcs::delete_branch_in(&execution_root, &source_branch);
+                }
+                return Ok(finish(
+                    with_owners(&format!(
+                        "git.integrate_plan_branch rebased {} commit(s) onto the target and fast-forwarded",
+                        landed.len()
+                    )),
+                    &landed,
+                ));
+            }
+
+            // Squash by step lands one commit per marked plan step; anything it cannot replay
+            // (no markers, a conflict) falls back to the single squash commit below.
+            if squash && granularity == config::SquashGranularity::Step {
+                let landed = match crate::vcs::plan_step_groups_in(&execution_root, &source_branch)
+                {
+                    Ok(groups) if groups.len() > 1 => {
+                        let messages = step_squash_messages(
+                            &merge_message,
+                            &merge_subject,
+                            &merge_slug,
+                            &groups,
+                            plan_document.as_deref(),
+                        );
+                        match crate::vcs::squash_step_groups_in(
+                            &execution_root,
+                            &source_branch,
+                            &groups,
+                            &messages,
+                        ) {
+                            Ok(crate::vcs::RebaseMergeOutcome::Landed(landed))
+                                if !landed.is_empty() =>
+                            {
+                                Some(landed)
+                            }
+                            Ok(crate::vcs::RebaseMergeOutcome::Landed(_)) => None,
+                            Ok(crate::vcs::RebaseMergeOutcome::Conflicted { commit, files }) => {
+                                display::warn(format!(
+                                    "git.integrate_plan_branch could not squash {merge_slug} by step: replaying {} conflicts in {}; squashing into one commit",
+                                    &commit.to_string()[..7],
+                                    files.join(", ")
+                                ));
+                                None
+                            }
+                            Err(err) => {
+                                display::warn(format!(
+                                    "git.integrate_plan_branch could not squash {merge_slug} by step: {err}; squashing into one commit"
+                                ));
+                                None
+                            }
+                        }
+                    }
+                    Ok(_) => {
+                        display::info(format!(
+                            "{source_branch} has no {} markers; squashing into one commit",
+                            crate::vcs::STEP_TRAILER
+                        ));
+                        None
+                    }
+                    Err(err) => {
+                        display::warn(format!(
+                            "git.integrate_plan_branch could not squash {merge_slug} by step: {err}; squashing into one commit"
+                        ));
+                        None
+                    }
+                };
+                if let Some(landed) = landed {
+                    for commit in &landed {
+                        crate::auditor::record_commit_note(
+                            &execution_root,
+                            *commit,
+                            Some(&merge_slug),
+                        );
+                    }
+                    let _ = remove_file_if_exists(&sentinel);
+                    if let Some(result) = run_post_merge_gates(
+                        project_root,
+                        jobs_root,
+                        &execution_root,
+                        record,
+                        node,
+                        target_branch.as_deref(),
+                        &landed,
+                    )? {
+                        return Ok(result);
+                    }
+                    if delete_branch
+                        && current_branch_name(&execution_root).as_deref()
+                            != Some(source_branch.as_str())
+                    {
+                        let _ = crate::vcs::delete_branch_in(&execution_root, &source_branch);
+                    }
+                    return Ok(finish(
+                        with_owners(&format!(
+                            "git.integrate_plan_branch squashed the plan into {} step commit(s)",
+                            landed.len()
+                        )),
+                        &landed,
+                    ));
+                }
+            }
+
             let merge_ready = match crate::vcs::prepare_merge_in(&execution_root, &source_branch) {
                 Ok(crate::vcs::MergePreparation::Ready(ready)) => ready,
-                Ok(crate::vcs::MergePreparation::Conflicted(_conflict)) => {
+                Ok(crate::vcs::MergePreparation::Conflicted(conflict)) => {
                     if let Some(parent) = sentinel.parent() {
                         fs::create_dir_all(parent)?;
                     }
@@ -806,6 +1642,8 @@ pub(crate) fn execute_workflow_executor(
                         "job_id": record.id,
                         "node_id": node.node_id,
                         "created_at": Utc::now().to_rfc3339(),
+                        "conflicted_files": conflict.files,
+                        "narrative_merged": conflict.narrative_merged,
                     });
                     fs::write(&sentinel, serde_json::to_vec_pretty(&payload)?)?;
 
@@ -827,6 +1665,7 @@ pub(crate) fn execute_workflow_executor(
                 }
             };
 
+            let narrative_merged = merge_ready.narrative_merged.clone();
             let should_commit = match Repository::open(&execution_root) {
                 Ok(repo) => {
                     if merge_ready.head_oid == merge_ready.source_oid {
@@ -855,6 +1694,7 @@ pub(crate) fn execute_workflow_executor(
                 }
             };
 
+            let mut landed = Vec::new();
             if should_commit {
                 let commit_result = if squash {
                     crate::vcs::commit_squashed_merge_in(
@@ -865,14 +1705,35 @@ pub(crate) fn execute_workflow_executor(
                 } else {
                     crate::vcs::commit_ready_merge_in(&execution_root, &merge_message, merge_ready)
                 };
-                if let Err(err) = commit_result {
-                    let summary = if squash {
-                        format!("git.integrate_plan_branch squash commit failed: {err}")
-                    } else {
-                        format!("git.integrate_plan_branch merge commit failed: {err}")
-                    };
-                    return Ok(WorkflowNodeResult::failed(summary, Some(1)));
+                let merge_commit = match commit_result {
+                    Ok(oid) => oid,
+                    Err(err) => {
+                        let summary = if squash {
+                            format!("git.integrate_plan_branch squash commit failed: {err}")
+                        } else {
+                            format!("git.integrate_plan_branch merge commit failed: {err}")
+                        };
+                        return Ok(WorkflowNodeResult::failed(summary, Some(1)));
+                    }
+                };
+                crate::auditor::record_commit_note(
+                    &execution_root,
+                    merge_commit,
+                    Some(&merge_slug),
+                );
+                let _ = remove_file_if_exists(&sentinel);
+                if let Some(result) = run_post_merge_gates(
+                    project_root,
+                    jobs_root,
+                    &execution_root,
+                    record,
+                    node,
+                    target_branch.as_deref(),
+                    &[merge_commit],
+                )? {
+                    return Ok(result);
                 }
+                landed.push(merge_commit);
             }
 
             let _ = remove_file_if_exists(&sentinel);
@@ -882,9 +1743,14 @@ pub(crate) fn execute_workflow_executor(
                 let _ = crate::vcs::delete_branch_in(&execution_root, &source_branch);
             }
 
-            Ok(WorkflowNodeResult::succeeded(
-                "git.integrate_plan_branch merged source branch",
-            ))
+            let mut summary = "git.integrate_plan_branch merged source branch".to_string();
+            if !narrative_merged.is_empty() {
+                summary.push_str(&format!(
+                    " (narrative edits merged by section: {})",
+                    narrative_merged.join(", ")
+                ));
+            }
+            Ok(finish(with_owners(&summary), &landed))
         }
         Some("git.save_worktree_patch") => {
             let patch = match crate::vcs::diff_binary_against_head_in(&execution_root) {
@@ -1084,6 +1950,9 @@ pub(crate) fn execute_workflow_executor(
                         branch,
                         "Generated by build.materialize_step",
                         "Build materialization placeholder.",
+                        None,
+                        &[],
+                        None,
                     );
                     let _ = crate::plan::write_plan_file(&plan_abs, &doc);
                 }
@@ -1195,7 +2064,10 @@ pub(crate) fn execute_workflow_executor(
                     Some(1),
                 ));
             };
-            let (status, stdout, stderr) = run_shell_text_command(&execution_root, &script)?;
+            let env = GateScriptContext::for_job(&execution_root, record, Some(node))
+                .env(project_root)?;
+            let (status, stdout, stderr) =
+                run_shell_text_command_with_env(&execution_root, &script, &env)?;
             print_stdout_text(&stdout);
             print_stderr_text(&stderr);
             let stderr_lines = stderr_lines_from_text(&stderr);
@@ -1228,3 +2100,464 @@ pub(crate) fn execute_workflow_executor(
         )),
     }
 }
+
+/// Optional approve phase: once the implementation passed its stop gate, asks the agent to cover
+/// the plan's acceptance criteria with tests, runs `[approve.stop_condition] tests` when set, and
+/// commits the result on its own as `tests: ...`. Disabled runs succeed without invoking anything.
+fn generate_tests(
+    project_root: &Path,
+    jobs_root: &Path,
+    execution_root: &Path,
+    record: &JobRecord,
+    node: &WorkflowRuntimeNodeManifest,
+) -> Result<WorkflowNodeResult, Box<dyn std::error::Error>> {
+    let cfg = crate::config::get_config();
+    let enabled = record
+        .metadata
+        .as_ref()
+        .and_then(|metadata| metadata.generate_tests)
+        .unwrap_or(cfg.approve.generate_tests);
+    if !enabled {
+        return Ok(WorkflowNodeResult::succeeded(
+            "agent.generate_tests skipped (disabled)",
+        ));
+    }
+    let Some(slug) = first_non_empty_arg(&node.args, &["slug", "plan"]) else {
+        return Ok(WorkflowNodeResult::failed(
+            "agent.generate_tests requires args.slug",
+            Some(1),
+        ));
+    };
+    let branch = first_non_empty_arg(&node.args, &["branch"])
+        .unwrap_or_else(|| crate::plan::default_branch_for_slug(&slug));
+    let plan_document =
+        match fs::read_to_string(execution_root.join(crate::plan::plan_rel_path(&slug))) {
+            Ok(document) => document,
+            Err(err) => {
+                return Ok(WorkflowNodeResult::failed(
+                    format!("agent.generate_tests could not read the plan for `{slug}`: {err}"),
+                    Some(1),
+                ));
+            }
+        };
+    let test_command = cfg.approve.stop_condition.tests.clone();
+    let prompt = crate::agent_prompt::build_test_generation_prompt(
+        &slug,
+        &branch,
+        &plan_document,
+        test_command.as_deref(),
+    )?;
+
+    let agent_settings = match resolve_workflow_agent_settings(record) {
+        Ok(settings) => settings,
+        Err(err) => {
+            return Ok(WorkflowNodeResult::failed(
+                format!("agent.generate_tests could not resolve agent settings: {err}"),
+                Some(1),
+            ));
+        }
+    };
+    let runner = match agent_settings.agent_runner() {
+        Ok(runner) => runner.clone(),
+        Err(err) => {
+            return Ok(WorkflowNodeResult::failed(
+                format!("agent.generate_tests requires agent backend runner: {err}"),
+                Some(1),
+            ));
+        }
+    };
+    let request =
+        build_workflow_agent_request(&agent_settings, prompt, execution_root.to_path_buf());
+    let response = match execute_agent_request_blocking(runner, request) {
+        Ok(response) => response,
+        Err(err) => return Ok(agent_failure_result("agent.generate_tests", err)),
+    };
+    print_stdout_text(&response.assistant_text);
+    for line in &response.stderr {
+        eprintln!("{line}");
+    }
+    let mut stderr_lines = response.stderr.clone();
+
+    // The agent was told to run the tests; the configured command is the check that counts.
+    if let Some(command) = test_command.as_deref() {
+        let (status, stdout, stderr) = run_shell_text_command(execution_root, command)?;
+        print_stdout_text(&stdout);
+        print_stderr_text(&stderr);
+        stderr_lines.extend(stderr_lines_from_text(&stderr));
+        if status != 0 {
+            let mut result = WorkflowNodeResult::failed(
+                format!(
+                    "agent.generate_tests: `{command}` failed (exit {status}) after test generation"
+                ),
+                Some(status),
+            );
+            result.stderr_lines = stderr_lines;
+            return Ok(result);
+        }
+    }
+
+    let mut result = match commit_patch_result(
+        project_root,
+        jobs_root,
+        execution_root,
+        record,
+        "agent.generate_tests",
+    )? {
+        Some(result) => result,
+        None => {
+            crate::vcs::stage_all_in(execution_root)?;
+            let staged = crate::vcs::snapshot_staged(&execution_root.to_string_lossy())?;
+            if staged.is_empty() {
+                WorkflowNodeResult::succeeded("agent.generate_tests: agent added no tests")
+            } else {
+                let message = format!("tests: cover {slug} acceptance criteria");
+                let oid = crate::vcs::commit_staged_in(execution_root, &message, false)?;
+                crate::auditor::record_commit_note(execution_root, oid, Some(&slug));
+                stderr_lines.extend(auto_push_plan_branch(execution_root));
+                WorkflowNodeResult::succeeded(format!("agent.generate_tests committed `{message}`"))
+            }
+        }
+    };
+    if !response.assistant_text.is_empty() {
+        result.stdout_text = Some(response.assistant_text);
+    }
+    result.stderr_lines.extend(stderr_lines);
+    result.metadata = Some(JobMetadata {
+        agent_selector: Some(agent_settings.selector.clone()),
+        agent_backend: Some(agent_settings.backend.to_string()),
+        agent_label: Some(agent_settings.agent_runtime.label.clone()),
+        agent_command: Some(agent_settings.agent_runtime.command.clone()),
+        agent_exit_code: Some(response.exit_code),
+        ..JobMetadata::default()
+    });
+    Ok(result)
+}
+
+/// Plan a workflow commit belongs to, for its provenance note: the checked-out plan branch,
+/// else the job's plan metadata.
+fn commit_plan_slug(execution_root: &Path, record: &JobRecord) -> Option<String> {
+    current_branch_name(execution_root)
+        .and_then(|branch| crate::plan::slug_from_branch(&branch))
+        .or_else(|| {
+            record
+                .metadata
+                .as_ref()
+                .and_then(|meta| meta.plan.clone())
+                .filter(|plan| !plan.trim().is_empty())
+        })
+}
+
+fn agent_failure_result(operation: &str, err: AgentError) -> WorkflowNodeResult {
+    match err {
+        AgentError::NonZeroExit(code, lines) => {
+            for line in &lines {
+                eprintln!("{line}");
+            }
+            let mut result =
+                WorkflowNodeResult::failed(format!("{operation} failed (exit {code})"), Some(code));
+            result.stderr_lines = lines;
+            result
+        }
+        AgentError::Timeout(secs) => {
+            WorkflowNodeResult::failed(format!("{operation} timed out after {secs}s"), Some(124))
+        }
+        err => WorkflowNodeResult::failed(format!("{operation} failed: {err}"), Some(1)),
+    }
+}
+
+/// Feeds the plan's latest review critique back to the agent as fix instructions, commits the
+/// fixes, reruns `[review.checks]`, and asks for a fresh critique, until one comes back with no
+/// Action Items or `max_iterations` (node arg, else `[review.fix_loop]`) is spent. Each
+/// iteration is recorded as a `review_fix_iteration` operation in the session log. Inert unless
+/// the run was enqueued with `vizier run --fix-loop`.
+fn review_fix_loop(
+    project_root: &Path,
+    jobs_root: &Path,
+    execution_root: &Path,
+    record: &JobRecord,
+    node: &WorkflowRuntimeNodeManifest,
+) -> Result<WorkflowNodeResult, Box<dyn std::error::Error>> {
+    let metadata = record.metadata.as_ref();
+    if metadata.and_then(|meta| meta.review_fix_loop) != Some(true) {
+        return Ok(WorkflowNodeResult::succeeded(
+            "agent.review_fix_loop skipped (run without --fix-loop)",
+        ));
+    }
+    let Some(slug) = first_non_empty_arg(&node.args, &["slug", "plan"]) else {
+        return Ok(WorkflowNodeResult::failed(
+            "agent.review_fix_loop requires args.slug",
+            Some(1),
+        ));
+    };
+    let branch = first_non_empty_arg(&node.args, &["branch"])
+        .unwrap_or_else(|| crate::plan::default_branch_for_slug(&slug));
+    let target = first_non_empty_arg(&node.args, &["target", "target_branch"])
+        .or_else(|| metadata.and_then(|meta| meta.target.clone()))
+        .or_else(|| crate::vcs::detect_primary_branch_in(execution_root))
+        .unwrap_or_else(|| "main".to_string());
+    let cfg = config::get_config();
+    let max_iterations = node
+        .args
+        .get("max_iterations")
+        .and_then(|value| value.trim().parse::<u32>().ok())
+        .unwrap_or(cfg.review.fix_loop.max_iterations);
+    // The review node this one runs after, else the plan's newest review job.
+    let upstream_critique = record
+        .schedule
+        .iter()
+        .flat_map(|schedule| schedule.after.iter())
+        .filter_map(|dependency| read_record(jobs_root, &dependency.job_id).ok())
+        .filter(|upstream| upstream.status == JobStatus::Succeeded)
+        .find_map(|upstream| {
+            fs::read_to_string(resolve_recorded_path(project_root, &upstream.stdout_path))
+                .ok()
+                .filter(|critique| !critique.trim().is_empty())
+        });
+    let Some(mut critique) =
+        upstream_critique.or_else(|| latest_review_critique(project_root, jobs_root, &slug))
+    else {
+        return Ok(WorkflowNodeResult::failed(
+            format!(
+                "agent.review_fix_loop found no review critique for `{slug}`; route it after the review agent node"
+            ),
+            Some(1),
+        ));
+    };
+    let plan_document =
+        match fs::read_to_string(execution_root.join(crate::plan::plan_rel_path(&slug))) {
+            Ok(document) => document,
+            Err(err) => {
+                return Ok(WorkflowNodeResult::failed(
+                    format!("agent.review_fix_loop could not read the plan for `{slug}`: {err}"),
+                    Some(1),
+                ));
+            }
+        };
+    let plan_id = crate::plan::front_matter_fields(&plan_document)
+        .get("plan_id")
+        .cloned();
+
+    let agent_settings = match resolve_workflow_agent_settings(record) {
+        Ok(settings) => settings,
+        Err(err) => {
+            return Ok(WorkflowNodeResult::failed(
+                format!("agent.review_fix_loop could not resolve agent settings: {err}"),
+                Some(1),
+            ));
+        }
+    };
+    let runner = match agent_settings.agent_runner() {
+        Ok(runner) => runner.clone(),
+        Err(err) => {
+            return Ok(WorkflowNodeResult::failed(
+                format!("agent.review_fix_loop requires agent backend runner: {err}"),
+                Some(1),
+            ));
+        }
+    };
+    let review_prompt = cfg.prompt_for(config::CommandScope::Review, config::PromptKind::Review);
+
+    let mut stderr_lines = Vec::new();
+    let mut iteration = 0;
+    let mut result = loop {
+        let report = crate::review_report::ReviewReport::parse(&critique);
+        if report.action_items.is_empty() {
+            break WorkflowNodeResult::succeeded(format!(
+                "agent.review_fix_loop: review clean after {iteration} fix iteration{}",
+                if iteration == 1 { "" } else { "s" }
+            ));
+        }
+        if iteration >= max_iterations {
+            break WorkflowNodeResult::failed(
+                format!(
+                    "agent.review_fix_loop: {} after {iteration} fix iteration{}; budget exhausted",
+                    report.verdict(),
+                    if iteration == 1 { "" } else { "s" }
+                ),
+                Some(1),
+            );
+        }
+        iteration += 1;
+
+        let prompt = crate::agent_prompt::build_review_fix_prompt(
+            &slug,
+            &branch,
+            &target,
+            &plan_document,
+            &critique,
+            iteration,
+            max_iterations,
+        )?;
+        let request =
+            build_workflow_agent_request(&agent_settings, prompt, execution_root.to_path_buf());
+        let response = match execute_agent_request_blocking(runner.clone(), request) {
+            Ok(response) => response,
+            Err(err) => return Ok(agent_failure_result("agent.review_fix_loop", err)),
+        };
+        for line in &response.stderr {
+            eprintln!("{line}");
+        }
+        stderr_lines.extend(response.stderr);
+
+        crate::vcs::stage_all_in(execution_root)?;
+        let committed = !crate::vcs::snapshot_staged(&execution_root.to_string_lossy())?.is_empty();
+        if committed {
+            let message = format!(
+                "fix: address review action items for {slug} ({iteration}/{max_iterations})"
+            );
+            let oid = crate::vcs::commit_staged_in(execution_root, &message, false)?;
+            crate::auditor::record_commit_note(execution_root, oid, Some(&slug));
+        }
+
+        let checks = crate::review_checks::run_review_checks(
+            execution_root,
+            &cfg.review.checks.commands,
+            cfg.review.checks.jobs,
+            &|line| eprintln!("[workflow-node] {line}"),
+        );
+        let diff = crate::diff_context::diff_context_for_spec(
+            execution_root,
+            &format!("{target}..HEAD"),
+            &cfg.workflow.diff_context,
+            |prompt| summarize_diff_chunk(execution_root, record, prompt),
+        )
+        .map(|context| context.text)
+        .unwrap_or_default();
+        let prompt = crate::agent_prompt::build_review_prompt(
+            &review_prompt,
+            crate::agent_prompt::ReviewPromptInput {
+                plan_id: plan_id.as_deref(),
+                plan_slug: &slug,
+                branch_name: &branch,
+                target_branch: &target,
+                plan_document: &plan_document,
+                diff_summary: &diff,
+                check_results: &checks.results,
+                cicd_gate: None,
+                documentation: &agent_settings.documentation,
+            },
+        )?;
+        let request =
+            build_workflow_agent_request(&agent_settings, prompt, execution_root.to_path_buf());
+        let response = match execute_agent_request_blocking(runner.clone(), request) {
+            Ok(response) => response,
+            Err(err) => return Ok(agent_failure_result("agent.review_fix_loop", err)),
+        };
+        stderr_lines.extend(response.stderr);
+        critique = response.assistant_text;
+
+        let verdict_after = crate::review_report::ReviewReport::parse(&critique).verdict();
+        crate::auditor::Auditor::record_operation(
+            "review_fix_iteration",
+            serde_json::json!({
+                "plan": slug,
+                "iteration": iteration,
+                "max_iterations": max_iterations,
+                "action_items": report.action_items.len(),
+                "verdict_before": report.verdict(),
+                "committed": committed,
+                "checks_passed": checks.passed(),
+                "checks_failed": checks.failed(),
+                "verdict_after": verdict_after,
+            }),
+        );
+        let line = format!(
+            "[workflow-node] info review fix iteration {iteration}/{max_iterations}: {} -> {verdict_after}",
+            report.verdict()
+        );
+        eprintln!("{line}");
+        stderr_lines.push(line);
+    };
+    if iteration > 0 {
+        stderr_lines.extend(auto_push_plan_branch(execution_root));
+    }
+
+    // The final critique is this node's stdout, so it becomes the plan's latest review.
+    print_stdout_text(&critique);
+    result.stdout_text = Some(critique);
+    result.stderr_lines = stderr_lines;
+    result.metadata = Some(JobMetadata {
+        agent_selector: Some(agent_settings.selector.clone()),
+        agent_backend: Some(agent_settings.backend.to_string()),
+        agent_label: Some(agent_settings.agent_runtime.label.clone()),
+        agent_command: Some(agent_settings.agent_runtime.command.clone()),
+        ..JobMetadata::default()
+    });
+    Ok(result)
+}
+
+/// A bare repository has no checkout to merge in, so the merge runs in a job-owned worktree of
+/// the target branch. The worktree is handed to the nodes routed after the merge (conflict
+/// resolution, the CI/CD gate) and removed straight away only when the merge fails.
+fn integrate_in_bare_repo(
+    project_root: &Path,
+    jobs_root: &Path,
+    record: &JobRecord,
+    node: &WorkflowRuntimeNodeManifest,
+) -> Result<WorkflowNodeResult, Box<dyn std::error::Error>> {
+    let target = first_non_empty_arg(&node.args, &["target", "target_branch"])
+        .or_else(|| {
+            record
+                .metadata
+                .as_ref()
+                .and_then(|meta| meta.target.clone())
+        })
+        .or_else(|| current_branch_name(project_root));
+    let Some(target) = target else {
+        return Ok(WorkflowNodeResult::failed(
+            "git.integrate_plan_branch needs a target branch in a bare repository",
+            Some(1),
+        ));
+    };
+
+    let dir_name = format!("merge-{}", record.id);
+    let worktree_path = crate::worktrees::worktrees_root(project_root).join(&dir_name);
+    if !worktree_path.exists() {
+        if let Some(parent) = worktree_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        if let Err(err) =
+            crate::vcs::add_worktree_for_branch_in(project_root, &dir_name, &worktree_path, &target)
+        {
+            return Ok(WorkflowNodeResult::failed(
+                format!(
+                    "git.integrate_plan_branch could not check out `{target}` in a worktree: {err}"
+                ),
+                Some(1),
+            ));
+        }
+    }
+    let worktree_name =
+        find_worktree_name_by_path(&Repository::open(project_root)?, &worktree_path);
+    let context = JobMetadata {
+        execution_root: Some(relative_path(project_root, &worktree_path)),
+        worktree_owned: Some(true),
+        worktree_path: Some(relative_path(project_root, &worktree_path)),
+        worktree_name: worktree_name.clone(),
+        ..JobMetadata::default()
+    };
+
+    let mut scoped = record.clone();
+    let mut metadata = scoped.metadata.take().unwrap_or_default();
+    metadata.execution_root = context.execution_root.clone();
+    metadata.worktree_path = context.worktree_path.clone();
+    scoped.metadata = Some(metadata);
+    let mut result = execute_workflow_executor(project_root, jobs_root, &scoped, node)?;
+
+    if result.outcome == WorkflowNodeOutcome::Failed {
+        if let Err(err) = cleanup_worktree(project_root, &worktree_path, worktree_name.as_deref()) {
+            display::warn(format!(
+                "git.integrate_plan_branch could not remove merge worktree {}: {err}",
+                worktree_path.display()
+            ));
+        }
+        return Ok(result);
+    }
+    let mut metadata = result.metadata.take().unwrap_or_default();
+    metadata.execution_root = context.execution_root;
+    metadata.worktree_owned = context.worktree_owned;
+    metadata.worktree_path = context.worktree_path;
+    metadata.worktree_name = context.worktree_name;
+    result.metadata = Some(metadata);
+    Ok(result)
+}
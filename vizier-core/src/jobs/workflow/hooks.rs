@@ -0,0 +1,157 @@
+//! `[hooks]` scripts around lifecycle phases. A phase is the alias a run was started under
+//! (`vizier run approve`): `pre_<phase>` runs before the run's first node and a non-zero exit
+//! fails that node, `post_<phase>` runs when the run reaches its terminal node, and `on_failure`
+//! runs after any failed node. Hooks run from the repository root with the gate script context
+//! plus `VIZIER_HOOK`, `VIZIER_PHASE`, `VIZIER_RUN_ID`, `VIZIER_NODE_ID`, and `VIZIER_OUTCOME`.
+
+use super::*;
+
+use crate::config::{HOOK_PHASES, ON_FAILURE_HOOK};
+
+/// A hook that ran for a node: its name, exit code, and the lines it added to the node's stderr.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct HookRun {
+    pub(crate) hook: String,
+    pub(crate) exit_code: i32,
+    pub(crate) lines: Vec<String>,
+}
+
+impl HookRun {
+    pub(crate) fn passed(&self) -> bool {
+        self.exit_code == 0
+    }
+}
+
+/// The lifecycle phase `record` belongs to, when its run was started under a hookable alias.
+fn hook_phase(record: &JobRecord) -> Option<String> {
+    let alias = record.metadata.as_ref()?.command_alias.as_deref()?;
+    HOOK_PHASES.contains(&alias).then(|| alias.to_string())
+}
+
+/// Whether `record` starts its run: none of its `after` dependencies belong to the same run, so
+/// `--after` ordering on another run still counts as a start.
+fn starts_run(manifest: &WorkflowRunManifest, record: &JobRecord) -> bool {
+    record.schedule.as_ref().is_none_or(|schedule| {
+        !schedule.after.iter().any(|dependency| {
+            manifest
+                .nodes
+                .values()
+                .any(|node| node.job_id == dependency.job_id)
+        })
+    })
+}
+
+/// Runs `pre_<phase>` when `record` is the first node of a phase run.
+pub(crate) fn run_pre_phase_hook(
+    project_root: &Path,
+    manifest: &WorkflowRunManifest,
+    record: &JobRecord,
+    node: &WorkflowRuntimeNodeManifest,
+) -> Result<Option<HookRun>, Box<dyn std::error::Error>> {
+    let Some(phase) = hook_phase(record) else {
+        return Ok(None);
+    };
+    if !starts_run(manifest, record) {
+        return Ok(None);
+    }
+    run_hook(
+        project_root,
+        record,
+        node,
+        &format!("pre_{phase}"),
+        Some(&phase),
+        "pending",
+    )
+}
+
+/// Runs `post_<phase>` after a terminal node succeeds, or `on_failure` after any node fails.
+pub(crate) fn run_outcome_hook(
+    project_root: &Path,
+    record: &JobRecord,
+    node: &WorkflowRuntimeNodeManifest,
+    outcome: WorkflowNodeOutcome,
+) -> Result<Option<HookRun>, Box<dyn std::error::Error>> {
+    let phase = hook_phase(record);
+    let hook = match outcome {
+        WorkflowNodeOutcome::Failed => ON_FAILURE_HOOK.to_string(),
+        WorkflowNodeOutcome::Succeeded if node.control_policy.as_deref() == Some("terminal") => {
+            match phase.as_deref() {
+                Some(phase) => format!("post_{phase}"),
+                None => return Ok(None),
+            }
+        }
+        _ => return Ok(None),
+    };
+    run_hook(
+        project_root,
+        record,
+        node,
+        &hook,
+        phase.as_deref(),
+        outcome.as_str(),
+    )
+}
+
+fn run_hook(
+    project_root: &Path,
+    record: &JobRecord,
+    node: &WorkflowRuntimeNodeManifest,
+    hook: &str,
+    phase: Option<&str>,
+    outcome: &str,
+) -> Result<Option<HookRun>, Box<dyn std::error::Error>> {
+    let cfg = config::get_config();
+    let Some(script) = cfg.hooks.script(hook) else {
+        return Ok(None);
+    };
+
+    let mut env = GateScriptContext::for_job(project_root, record, Some(node)).env(project_root)?;
+    env.push(("VIZIER_HOOK".to_string(), hook.to_string()));
+    if let Some(phase) = phase {
+        env.push(("VIZIER_PHASE".to_string(), phase.to_string()));
+    }
+    if let Some(run_id) = record
+        .metadata
+        .as_ref()
+        .and_then(|meta| meta.workflow_run_id.as_ref())
+    {
+        env.push(("VIZIER_RUN_ID".to_string(), run_id.clone()));
+    }
+    env.push(("VIZIER_NODE_ID".to_string(), node.node_id.clone()));
+    env.push(("VIZIER_OUTCOME".to_string(), outcome.to_string()));
+
+    let (exit_code, stdout, stderr) = run_shell_text_command_with_env(project_root, script, &env)?;
+    let mut lines = Vec::new();
+    let status = if exit_code == 0 {
+        format!("[workflow-node] info hook {hook} passed")
+    } else {
+        format!("[workflow-node] warn hook {hook} failed (exit {exit_code})")
+    };
+    lines.push(status);
+    lines.extend(
+        stdout
+            .lines()
+            .chain(stderr.lines())
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| format!("[hook {hook}] {line}")),
+    );
+    for line in &lines {
+        eprintln!("{line}");
+    }
+    crate::auditor::Auditor::record_operation(
+        "lifecycle_hook",
+        serde_json::json!({
+            "hook": hook,
+            "phase": phase,
+            "job": record.id,
+            "node": node.node_id,
+            "exit_code": exit_code,
+        }),
+    );
+
+    Ok(Some(HookRun {
+        hook: hook.to_string(),
+        exit_code,
+        lines,
+    }))
+}
@@ -3,6 +3,7 @@ use super::*;
 mod compile;
 mod control;
 mod executor;
+mod hooks;
 mod runtime;
 
 #[allow(unused_imports)]
@@ -12,13 +13,18 @@ pub(crate) use control::*;
 #[allow(unused_imports)]
 pub(crate) use executor::*;
 #[allow(unused_imports)]
+pub(crate) use hooks::*;
+#[allow(unused_imports)]
 pub(crate) use runtime::*;
 
 pub use compile::{
     WorkflowRunEnqueueOptions, audit_workflow_run_template, enqueue_workflow_run,
     enqueue_workflow_run_with_options, validate_workflow_run_template,
 };
-pub use runtime::run_workflow_node_command;
+pub use runtime::{
+    BranchVerification, VerifyGateRun, job_execution_root, resolve_conflicts_with_merge_agent,
+    run_merge_gates_on_branch, run_workflow_node_command, verification_configured, verify_branch,
+};
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
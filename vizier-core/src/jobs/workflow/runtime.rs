@@ -48,17 +48,147 @@ pub(crate) fn run_shell_text_command(
     execution_root: &Path,
     script: &str,
 ) -> Result<(i32, String, String), Box<dyn std::error::Error>> {
-    let output = Command::new("sh")
+    run_shell_text_command_with_env(execution_root, script, &[])
+}
+
+pub(crate) fn run_shell_text_command_with_env(
+    execution_root: &Path,
+    script: &str,
+    env: &[(String, String)],
+) -> Result<(i32, String, String), Box<dyn std::error::Error>> {
+    let mut command = Command::new("sh");
+    command
         .arg("-lc")
         .arg(script)
         .current_dir(execution_root)
-        .output()?;
-    let status = output.status.code().unwrap_or(1);
-    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        .envs(env.iter().map(|(key, value)| (key, value)))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    // Like agent children, the shell leads its own process group so an interrupt can stop it
+    // and everything it spawned instead of waiting for the script to finish.
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    let mut child = command.spawn()?;
+
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    let (status, stdout, stderr) = thread::scope(|scope| {
+        let out = scope.spawn(|| read_pipe(stdout));
+        let err = scope.spawn(|| read_pipe(stderr));
+        let mut signalled = false;
+        let status = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break Ok(status),
+                Ok(None) => {}
+                Err(err) => break Err(err),
+            }
+            if !signalled && interrupt::is_interrupted() {
+                interrupt::terminate_process_group(child.id());
+                signalled = true;
+            }
+            thread::sleep(StdDuration::from_millis(50));
+        };
+        (
+            status,
+            out.join().unwrap_or_default(),
+            err.join().unwrap_or_default(),
+        )
+    });
+    let status = status?.code().unwrap_or(1);
     Ok((status, stdout, stderr))
 }
 
+fn read_pipe<R: Read>(pipe: Option<R>) -> String {
+    let mut buffer = Vec::new();
+    if let Some(mut pipe) = pipe {
+        let _ = pipe.read_to_end(&mut buffer);
+    }
+    String::from_utf8_lossy(&buffer).to_string()
+}
+
+/// What a gate or stop-condition script learns about the run it is checking. Exported as
+/// `VIZIER_PLAN`, `VIZIER_BRANCH`, `VIZIER_TARGET`, `VIZIER_SESSION_ID`, `VIZIER_ATTEMPT`, and
+/// `VIZIER_WORKTREE`, and written as JSON to the file named by `VIZIER_GATE_CONTEXT`.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub(crate) struct GateScriptContext {
+    pub(crate) plan: Option<String>,
+    pub(crate) branch: Option<String>,
+    pub(crate) target: Option<String>,
+    pub(crate) session_id: String,
+    pub(crate) attempt: u32,
+    pub(crate) worktree: String,
+    pub(crate) job_id: String,
+    pub(crate) run_id: Option<String>,
+    pub(crate) node_id: Option<String>,
+}
+
+impl GateScriptContext {
+    /// Context for a script running in `worktree` on behalf of `record`; node args win over job
+    /// metadata for the plan, branch, and target.
+    pub(crate) fn for_job(
+        worktree: &Path,
+        record: &JobRecord,
+        node: Option<&WorkflowRuntimeNodeManifest>,
+    ) -> Self {
+        let metadata = record.metadata.as_ref();
+        let node_arg = |keys: &[&str]| node.and_then(|node| first_non_empty_arg(&node.args, keys));
+        let branch = node_arg(&["branch"])
+            .or_else(|| normalized_metadata_value(metadata.and_then(|meta| meta.branch.as_ref())));
+        let plan = node_arg(&["slug", "plan"])
+            .or_else(|| normalized_metadata_value(metadata.and_then(|meta| meta.plan.as_ref())))
+            .or_else(|| branch.as_deref().and_then(crate::plan::slug_from_branch));
+        Self {
+            plan,
+            branch,
+            target: node_arg(&["target"]).or_else(|| {
+                normalized_metadata_value(metadata.and_then(|meta| meta.target.as_ref()))
+            }),
+            session_id: crate::auditor::Auditor::session_id(),
+            attempt: metadata
+                .and_then(|meta| meta.workflow_node_attempt)
+                .unwrap_or(1),
+            worktree: worktree.display().to_string(),
+            job_id: record.id.clone(),
+            run_id: metadata.and_then(|meta| meta.workflow_run_id.clone()),
+            node_id: metadata.and_then(|meta| meta.workflow_node_id.clone()),
+        }
+    }
+
+    /// Writes the JSON context under the job's directory and returns the variables to export.
+    pub(crate) fn env(&self, project_root: &Path) -> io::Result<Vec<(String, String)>> {
+        // Absolute, since the script runs from the worktree rather than the repository root.
+        let jobs_root = ensure_jobs_root(project_root)?.canonicalize()?;
+        let path = gate_context_path(&jobs_root, &self.job_id);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, serde_json::to_vec_pretty(self)?)?;
+
+        let mut env = Vec::new();
+        for (key, value) in [
+            ("VIZIER_PLAN", self.plan.as_deref()),
+            ("VIZIER_BRANCH", self.branch.as_deref()),
+            ("VIZIER_TARGET", self.target.as_deref()),
+        ] {
+            if let Some(value) = value {
+                env.push((key.to_string(), value.to_string()));
+            }
+        }
+        env.push(("VIZIER_SESSION_ID".to_string(), self.session_id.clone()));
+        env.push(("VIZIER_ATTEMPT".to_string(), self.attempt.to_string()));
+        env.push(("VIZIER_WORKTREE".to_string(), self.worktree.clone()));
+        env.push((
+            "VIZIER_GATE_CONTEXT".to_string(),
+            path.display().to_string(),
+        ));
+        Ok(env)
+    }
+}
+
 pub(crate) fn parse_bool_like(value: &str) -> Option<bool> {
     match value.trim().to_ascii_lowercase().as_str() {
         "1" | "true" | "yes" | "on" => Some(true),
@@ -107,6 +237,17 @@ pub(crate) fn resolve_execution_root(
     Ok(canonical_project_root)
 }
 
+/// The checkout a job's workflow node ran in (its recorded worktree, or the repo root), for
+/// operator tooling such as `vizier jobs resolve`.
+pub fn job_execution_root(
+    project_root: &Path,
+    jobs_root: &Path,
+    job_id: &str,
+) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let record = read_record(jobs_root, job_id)?;
+    resolve_execution_root(project_root, &record)
+}
+
 pub(crate) fn resolve_execution_root_candidate(
     project_root: &Path,
     canonical_project_root: &Path,
@@ -121,7 +262,11 @@ pub(crate) fn resolve_execution_root_candidate(
             err
         )
     })?;
-    if !canonical.starts_with(canonical_project_root) {
+    // Worktrees relocated by `[worktrees] dir` live outside the repository but are still ours.
+    let in_worktrees_root = crate::worktrees::worktrees_root(project_root)
+        .canonicalize()
+        .is_ok_and(|root| canonical.starts_with(root));
+    if !canonical.starts_with(canonical_project_root) && !in_worktrees_root {
         return Err(format!(
             "workflow metadata.{field_name} path {} is outside repository root {}",
             canonical.display(),
@@ -220,18 +365,677 @@ pub(crate) fn workflow_slug_from_record(
         .metadata
         .as_ref()
         .and_then(|meta| meta.branch.as_ref())
-        .and_then(|branch| branch.strip_prefix("draft/"))
-        .map(|value| value.trim())
+        .and_then(|branch| crate::plan::slug_from_branch(branch))
+        .map(|value| value.trim().to_string())
         .filter(|value| !value.is_empty())
     {
-        return value.to_string();
+        return value;
     }
     sanitize_workflow_component(&record.id)
 }
 
+pub(crate) struct MergeGateFailure {
+    pub(crate) gate: config::MergeGateConfig,
+    pub(crate) status: i32,
+    pub(crate) stdout: String,
+    pub(crate) stderr: String,
+}
+
+/// Runs `[[merge.gates]]` entries in order from `root` with `env` exported, stopping at the
+/// first failing gate.
+pub(crate) fn run_merge_gates(
+    root: &Path,
+    gates: &[config::MergeGateConfig],
+    env: &[(String, String)],
+) -> Result<Option<MergeGateFailure>, Box<dyn std::error::Error>> {
+    for gate in gates {
+        let script = gate.script.to_string_lossy();
+        display::log(
+            display::Subsystem::Gate,
+            display::LogLevel::Info,
+            format!(
+                "running {} gate `{}`: {script}",
+                gate.stage.as_str(),
+                gate.name
+            ),
+        );
+        let (status, stdout, stderr) = run_shell_text_command_with_env(root, &script, env)?;
+        if status != 0 {
+            return Ok(Some(MergeGateFailure {
+                gate: gate.clone(),
+                status,
+                stdout,
+                stderr,
+            }));
+        }
+    }
+    Ok(None)
+}
+
+/// Protected targets (`[merge] protected`) are never committed to locally: the source branch is
+/// pushed to `origin`, a pull request is opened through the forge CLI when one is available, and
+/// the node blocks until the change lands through it.
+pub(crate) fn protected_target_result(
+    execution_root: &Path,
+    target: &str,
+    source_branch: &str,
+) -> Option<WorkflowNodeResult> {
+    if !config::get_config().merge.is_protected(target) {
+        return None;
+    }
+    if let Err(err) = crate::vcs::push_branch_in(execution_root, "origin", source_branch) {
+        return Some(WorkflowNodeResult::failed(
+            format!(
+                "git.integrate_plan_branch refused to merge into protected branch `{target}`; pushing `{source_branch}` to origin failed: {err}"
+            ),
+            Some(1),
+        ));
+    }
+    let mut lines = Vec::new();
+    let opened = crate::forge::provider_for(execution_root).and_then(|provider| {
+        let spec = crate::forge::PullRequestSpec {
+            source_branch: source_branch.to_string(),
+            target_branch: target.to_string(),
+            ..Default::default()
+        };
+        match provider.create_pull_request(execution_root, &spec) {
+            Ok(url) => Some(format!(
+                "pull request {url} (opened with {})",
+                provider.name()
+            )),
+            Err(err) => {
+                lines.push(format!("[workflow-node] warn {err}"));
+                None
+            }
+        }
+    });
+    let review = opened.unwrap_or_else(|| {
+        crate::vcs::pull_request_url(&execution_root.to_string_lossy(), target, source_branch)
+            .unwrap_or_else(|| {
+                format!("open a pull request from `{source_branch}` into `{target}`")
+            })
+    });
+    lines.push(format!(
+        "[workflow-node] info `{target}` is protected; pushed `{source_branch}` to origin: {review}"
+    ));
+    for line in &lines {
+        eprintln!("{line}");
+    }
+    let mut result = WorkflowNodeResult::blocked(
+        format!(
+            "git.integrate_plan_branch did not merge into protected branch `{target}`; pushed `{source_branch}` for review: {review}"
+        ),
+        Some(10),
+    );
+    result.stderr_lines = lines;
+    Some(result)
+}
+
+/// Runs pre-merge gates against `source_branch` in a throwaway worktree so the target checkout
+/// is untouched when a gate fails. Returns the failed node result, if any.
+pub(crate) fn run_pre_merge_gates(
+    project_root: &Path,
+    execution_root: &Path,
+    record: &JobRecord,
+    source_branch: &str,
+    use_cache: bool,
+) -> Result<Option<WorkflowNodeResult>, Box<dyn std::error::Error>> {
+    let mut gates = config::get_config()
+        .merge
+        .staged_gates(config::MergeGateStage::PreMerge);
+    if use_cache
+        && let Some(target) = current_branch_name(execution_root)
+        && let Some(key) = crate::gate_cache::GateCacheKey::for_branches(
+            execution_root,
+            source_branch,
+            &target,
+            &pre_merge_and_cicd_gates(),
+        )
+    {
+        gates.retain(|gate| {
+            let script = gate.script.to_string_lossy();
+            match crate::gate_cache::cached_pass(project_root, &key, &script) {
+                Some(entry) => {
+                    eprintln!(
+                        "[workflow-node] pre-merge gate `{}` {}",
+                        gate.name,
+                        crate::gate_cache::cached_pass_notation(&entry)
+                    );
+                    false
+                }
+                None => true,
+            }
+        });
+    }
+    if gates.is_empty() {
+        return Ok(None);
+    }
+
+    let worktree_name = format!("vizier-merge-gate-{}", record.id);
+    let worktree_path =
+        crate::worktrees::worktrees_root(project_root).join(format!("merge-gate-{}", record.id));
+    let mut context = GateScriptContext::for_job(&worktree_path, record, None);
+    context.branch = Some(source_branch.to_string());
+    context.target = current_branch_name(execution_root).or(context.target);
+    let env = context.env(project_root)?;
+    if let Some(parent) = worktree_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if let Err(err) = crate::vcs::add_worktree_for_branch_in(
+        execution_root,
+        &worktree_name,
+        &worktree_path,
+        source_branch,
+    ) {
+        return Ok(Some(WorkflowNodeResult::failed(
+            format!("pre-merge gates could not check out `{source_branch}`: {err}"),
+            Some(1),
+        )));
+    }
+    let outcome = run_merge_gates(&worktree_path, &gates, &env);
+    let _ = crate::vcs::remove_worktree_in(execution_root, &worktree_name, true);
+    let _ = fs::remove_dir_all(&worktree_path);
+
+    Ok(outcome?.map(|failure| {
+        gate_failure_result(
+            format!(
+                "pre-merge gate `{}` failed (exit {}); merge not attempted",
+                failure.gate.name, failure.status
+            ),
+            &failure,
+        )
+    }))
+}
+
+/// Pre-merge `[[merge.gates]]` followed by the `[merge.cicd_gate]` script (named `cicd`).
+pub(crate) fn pre_merge_and_cicd_gates() -> Vec<config::MergeGateConfig> {
+    let merge_cfg = config::get_config().merge;
+    let mut gates = merge_cfg.staged_gates(config::MergeGateStage::PreMerge);
+    if let Some(script) = merge_cfg
+        .cicd_gate
+        .script
+        .filter(|script| !script.as_os_str().is_empty())
+    {
+        gates.push(config::MergeGateConfig {
+            name: "cicd".to_string(),
+            stage: config::MergeGateStage::PreMerge,
+            script,
+        });
+    }
+    gates
+}
+
+/// Checks `branch` out in a throwaway worktree under the worktrees root, runs `run` there,
+/// and removes the worktree again.
+fn with_gate_worktree<T>(
+    project_root: &Path,
+    branch: &str,
+    worktree_id: &str,
+    run: impl FnOnce(&Path) -> T,
+) -> Result<T, String> {
+    let worktree_name = format!("vizier-merge-gate-{worktree_id}");
+    let worktree_path =
+        crate::worktrees::worktrees_root(project_root).join(format!("merge-gate-{worktree_id}"));
+    if let Some(parent) = worktree_path.parent() {
+        fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+    }
+    crate::vcs::add_worktree_for_branch_in(project_root, &worktree_name, &worktree_path, branch)
+        .map_err(|err| format!("gates could not check out `{branch}`: {err}"))?;
+    let outcome = run(&worktree_path);
+    let _ = crate::vcs::remove_worktree_in(project_root, &worktree_name, true);
+    let _ = fs::remove_dir_all(&worktree_path);
+    Ok(outcome)
+}
+
+/// Runs the pre-merge gates plus the `[merge.cicd_gate]` script against `branch` in a throwaway
+/// worktree, for callers outside a workflow job (for example `vizier pick`). The error names the
+/// failing gate and carries its output.
+pub fn run_merge_gates_on_branch(
+    project_root: &Path,
+    branch: &str,
+    worktree_id: &str,
+) -> Result<(), String> {
+    let gates = pre_merge_and_cicd_gates();
+    if gates.is_empty() {
+        return Ok(());
+    }
+
+    let outcome = with_gate_worktree(project_root, branch, worktree_id, |root| {
+        run_merge_gates(root, &gates, &[])
+    })?;
+    match outcome.map_err(|err| err.to_string())? {
+        None => Ok(()),
+        Some(failure) => {
+            let mut message = format!(
+                "gate `{}` failed (exit {})",
+                failure.gate.name, failure.status
+            );
+            for text in [&failure.stdout, &failure.stderr] {
+                if !text.trim().is_empty() {
+                    message.push('\n');
+                    message.push_str(text.trim_end());
+                }
+            }
+            Err(message)
+        }
+    }
+}
+
+/// One gate script run by [`verify_branch`].
+#[derive(Debug, Clone)]
+pub struct VerifyGateRun {
+    pub name: String,
+    pub script: String,
+    pub status: i32,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// Everything [`verify_branch`] ran against a plan branch.
+#[derive(Debug, Clone)]
+pub struct BranchVerification {
+    pub gates: Vec<VerifyGateRun>,
+    pub checks: crate::review_checks::ReviewChecksRun,
+}
+
+impl BranchVerification {
+    pub fn passed(&self) -> bool {
+        self.gates.iter().all(|gate| gate.status == 0) && self.checks.failed() == 0
+    }
+}
+
+/// Whether [`verify_branch`] has any gate or review check to run under the current config.
+pub fn verification_configured() -> bool {
+    !pre_merge_and_cicd_gates().is_empty()
+        || !config::get_config().review.checks.commands.is_empty()
+}
+
+/// Runs every pre-merge gate, the `[merge.cicd_gate]` script, and `[review.checks]` against
+/// `branch` in a throwaway worktree, without an agent. Unlike a merge, a failing gate does not
+/// stop the remaining gates or checks. Results are recorded on the session log, and passing gates
+/// are cached against the current `target` tip so a following merge can skip them.
+pub fn verify_branch(
+    project_root: &Path,
+    branch: &str,
+    target: &str,
+    worktree_id: &str,
+    on_line: &(dyn Fn(&str) + Sync),
+) -> Result<BranchVerification, String> {
+    if !verification_configured() {
+        return Err(
+            "nothing to verify; configure [merge.cicd_gate] script, pre-merge [[merge.gates]], or [review.checks] commands"
+                .to_string(),
+        );
+    }
+    let gates = pre_merge_and_cicd_gates();
+    let cfg = config::get_config();
+    let verification = with_gate_worktree(project_root, branch, worktree_id, |root| {
+        let mut runs = Vec::new();
+        for gate in &gates {
+            let script = gate.script.to_string_lossy().to_string();
+            on_line(&format!("[gate {}] $ {script}", gate.name));
+            let (status, stdout, stderr) =
+                run_shell_text_command(root, &script).map_err(|err| err.to_string())?;
+            for line in stdout.lines().chain(stderr.lines()) {
+                on_line(&format!("[gate {}] {line}", gate.name));
+            }
+            runs.push(VerifyGateRun {
+                name: gate.name.clone(),
+                script,
+                status,
+                stdout,
+                stderr,
+            });
+        }
+        let checks = crate::review_checks::run_review_checks(
+            root,
+            &cfg.review.checks.commands,
+            cfg.review.checks.jobs,
+            on_line,
+        );
+        Ok::<_, String>(BranchVerification {
+            gates: runs,
+            checks,
+        })
+    })??;
+
+    for gate in &verification.gates {
+        crate::auditor::Auditor::record_gate(&crate::auditor::CommandOutcomeGate {
+            job_id: None,
+            node_id: format!("verify.{}", gate.name),
+            policy: "gate.verify".to_string(),
+            outcome: if gate.status == 0 {
+                "succeeded"
+            } else {
+                "failed"
+            }
+            .to_string(),
+            exit_code: Some(gate.status),
+            summary: Some(gate.script.clone()),
+        });
+    }
+    crate::auditor::Auditor::record_operation("review_checks", verification.checks.to_json());
+
+    let passed = verification
+        .gates
+        .iter()
+        .filter(|gate| gate.status == 0)
+        .map(|gate| crate::gate_cache::CachedGate {
+            name: gate.name.clone(),
+            script: gate.script.clone(),
+        })
+        .collect::<Vec<_>>();
+    if let Some(key) =
+        crate::gate_cache::GateCacheKey::for_branches(project_root, branch, target, &gates)
+        && let Err(err) = crate::gate_cache::record_passes(project_root, &key, &passed)
+    {
+        display::log(
+            display::Subsystem::Gate,
+            display::LogLevel::Warn,
+            format!("unable to cache gate results for `{branch}`: {err}"),
+        );
+    }
+    Ok(verification)
+}
+
+/// Runs post-merge gates on the target checkout after `landed` (the merge commit, or every
+/// commit a rebase-and-merge replayed, oldest first) reached it. On failure the merge is
+/// reverted when `auto_revert` is set (node arg, then `[merge] auto_revert`); otherwise a
+/// revert candidate is recorded for `vizier run --follow` to confirm.
+pub(crate) fn run_post_merge_gates(
+    project_root: &Path,
+    jobs_root: &Path,
+    execution_root: &Path,
+    record: &JobRecord,
+    node: &WorkflowRuntimeNodeManifest,
+    target_branch: Option<&str>,
+    landed: &[Oid],
+) -> Result<Option<WorkflowNodeResult>, Box<dyn std::error::Error>> {
+    let Some((&merge_commit, rebased)) = landed.split_last() else {
+        return Ok(None);
+    };
+    let merge_cfg = config::get_config().merge;
+    let gates = merge_cfg.staged_gates(config::MergeGateStage::PostMerge);
+    if gates.is_empty() {
+        return Ok(None);
+    }
+    let mut context = GateScriptContext::for_job(execution_root, record, Some(node));
+    context.target = target_branch.map(str::to_string).or(context.target);
+    let env = context.env(project_root)?;
+    let Some(failure) = run_merge_gates(execution_root, &gates, &env)? else {
+        return Ok(None);
+    };
+
+    let auto_revert = bool_arg(&node.args, "auto_revert").unwrap_or(merge_cfg.auto_revert);
+    let short = &merge_commit.to_string()[..7];
+    if auto_revert {
+        let reverted = std::iter::once(merge_commit)
+            .chain(rebased.iter().rev().copied())
+            .try_fold(merge_commit, |_, commit| {
+                crate::vcs::revert_commit_in(execution_root, commit)
+            });
+        let summary = match reverted {
+            Ok(revert) => format!(
+                "post-merge gate `{}` failed (exit {}); reverted merge {short} with {}",
+                failure.gate.name,
+                failure.status,
+                &revert.to_string()[..7]
+            ),
+            Err(err) => format!(
+                "post-merge gate `{}` failed (exit {}) and reverting merge {short} failed: {err}",
+                failure.gate.name, failure.status
+            ),
+        };
+        return Ok(Some(gate_failure_result(summary, &failure)));
+    }
+
+    let candidate = PostMergeRevertCandidate {
+        job_id: record.id.clone(),
+        target_branch: target_branch.map(str::to_string),
+        merge_commit: merge_commit.to_string(),
+        rebased_commits: rebased.iter().map(Oid::to_string).collect(),
+        gate: failure.gate.name.clone(),
+        exit_code: failure.status,
+        execution_root: relative_path(project_root, execution_root),
+    };
+    let path = post_merge_revert_path(jobs_root, &record.id);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, serde_json::to_vec_pretty(&candidate)?)?;
+
+    let mut result = gate_failure_result(
+        format!(
+            "post-merge gate `{}` failed (exit {}); merge {short} left in place (rerun with --auto-revert or revert it manually)",
+            failure.gate.name, failure.status
+        ),
+        &failure,
+    );
+    result.payload_refs = vec![relative_path(project_root, &path)];
+    Ok(Some(result))
+}
+
+fn gate_failure_result(summary: String, failure: &MergeGateFailure) -> WorkflowNodeResult {
+    let mut result = WorkflowNodeResult::failed(summary, Some(failure.status.max(1)));
+    if !failure.stdout.is_empty() {
+        result.stdout_text = Some(failure.stdout.clone());
+    }
+    result.stderr_lines = stderr_lines_from_text(&failure.stderr);
+    result
+}
+
+/// Extra targets from the merge node's `also` arg (comma or whitespace separated), in order,
+/// without the primary target or repeats.
+pub(crate) fn also_targets(
+    node: &WorkflowRuntimeNodeManifest,
+    primary: Option<&str>,
+) -> Vec<String> {
+    let mut targets: Vec<String> = Vec::new();
+    for target in first_non_empty_arg(&node.args, &["also"])
+        .unwrap_or_default()
+        .split(|ch: char| ch == ',' || ch.is_whitespace())
+        .map(str::trim)
+        .filter(|target| !target.is_empty())
+    {
+        if Some(target) != primary && !targets.iter().any(|seen| seen == target) {
+            targets.push(target.to_string());
+        }
+    }
+    targets
+}
+
+/// Refuses extra targets that are missing or `[merge] protected` before anything lands.
+pub(crate) fn also_targets_block(
+    execution_root: &Path,
+    targets: &[String],
+) -> Option<WorkflowNodeResult> {
+    let merge_cfg = config::get_config().merge;
+    for target in targets {
+        if merge_cfg.is_protected(target) {
+            return Some(WorkflowNodeResult::failed(
+                format!(
+                    "git.integrate_plan_branch cannot backport to `{target}`: it is protected ([merge] protected)"
+                ),
+                Some(1),
+            ));
+        }
+        if !crate::vcs::branch_exists_in(execution_root, target).unwrap_or(false) {
+            return Some(WorkflowNodeResult::failed(
+                format!("git.integrate_plan_branch cannot backport to `{target}`: no such branch"),
+                Some(1),
+            ));
+        }
+    }
+    None
+}
+
+/// Outcome of replaying a landed plan onto one extra target.
+#[derive(Clone, Debug)]
+pub(crate) struct Backport {
+    pub(crate) target: String,
+    pub(crate) ok: bool,
+    pub(crate) detail: String,
+}
+
+/// Cherry-picks the commits that landed on the primary target onto each of `targets` in turn.
+/// Each target's pre-merge, `[merge.cicd_gate]`, and post-merge gates run against the candidate
+/// in a throwaway worktree before the branch moves, so a failing target is left untouched.
+pub(crate) fn backport_to_targets(
+    project_root: &Path,
+    execution_root: &Path,
+    record: &JobRecord,
+    node: &WorkflowRuntimeNodeManifest,
+    slug: &str,
+    landed: &[Oid],
+    targets: &[String],
+) -> Vec<Backport> {
+    targets
+        .iter()
+        .map(|target| {
+            let backport = if landed.is_empty() {
+                Backport {
+                    target: target.clone(),
+                    ok: true,
+                    detail: "nothing landed to backport".to_string(),
+                }
+            } else {
+                backport_to_target(
+                    project_root,
+                    execution_root,
+                    record,
+                    node,
+                    slug,
+                    landed,
+                    target,
+                )
+                .unwrap_or_else(|err| Backport {
+                    target: target.clone(),
+                    ok: false,
+                    detail: format!("backport failed: {err}"),
+                })
+            };
+            display::info(format!(
+                "git.integrate_plan_branch backport to {}: {}",
+                backport.target, backport.detail
+            ));
+            crate::auditor::Auditor::record_operation(
+                "backport",
+                serde_json::json!({
+                    "plan": slug,
+                    "target": backport.target,
+                    "ok": backport.ok,
+                    "detail": backport.detail,
+                }),
+            );
+            backport
+        })
+        .collect()
+}
+
+fn backport_to_target(
+    project_root: &Path,
+    execution_root: &Path,
+    record: &JobRecord,
+    node: &WorkflowRuntimeNodeManifest,
+    slug: &str,
+    landed: &[Oid],
+    target: &str,
+) -> Result<Backport, Box<dyn std::error::Error>> {
+    let backport = |ok: bool, detail: String| Backport {
+        target: target.to_string(),
+        ok,
+        detail,
+    };
+    let (onto, head, picked) =
+        match crate::vcs::prepare_backport_in(execution_root, landed, target)? {
+            crate::vcs::BackportPreparation::Ready {
+                onto_oid,
+                head_oid,
+                picked,
+            } => (onto_oid, head_oid, picked),
+            crate::vcs::BackportPreparation::Conflicted { commit, files } => {
+                return Ok(backport(
+                    false,
+                    format!(
+                        "replaying {} conflicts in {}; not backported",
+                        &commit.to_string()[..7],
+                        files.join(", ")
+                    ),
+                ));
+            }
+        };
+    if picked.is_empty() {
+        return Ok(backport(true, "already has the changes".to_string()));
+    }
+
+    let short = head.to_string()[..7].to_string();
+    let mut gates = pre_merge_and_cicd_gates();
+    gates.extend(
+        config::get_config()
+            .merge
+            .staged_gates(config::MergeGateStage::PostMerge),
+    );
+    if !gates.is_empty() {
+        // Gates need a branch to check out; it only lives for the duration of the gate run.
+        let gate_branch = format!("vizier/backport/{slug}-{short}");
+        {
+            let repo = Repository::open(execution_root)?;
+            repo.branch(&gate_branch, &repo.find_commit(head)?, true)?;
+        }
+        let outcome = with_gate_worktree(
+            project_root,
+            &gate_branch,
+            &format!("backport-{short}"),
+            |root| {
+                let mut context = GateScriptContext::for_job(root, record, Some(node));
+                context.target = Some(target.to_string());
+                run_merge_gates(root, &gates, &context.env(project_root)?)
+            },
+        );
+        let _ = crate::vcs::delete_branch_in(execution_root, &gate_branch);
+        if let Some(failure) = outcome?? {
+            return Ok(backport(
+                false,
+                format!(
+                    "gate `{}` failed (exit {}); not backported",
+                    failure.gate.name, failure.status
+                ),
+            ));
+        }
+    }
+
+    crate::vcs::fast_forward_branch_in(execution_root, target, onto, head)?;
+    for commit in &picked {
+        crate::auditor::record_commit_note(execution_root, *commit, Some(slug));
+    }
+    Ok(backport(
+        true,
+        format!("landed {short} ({} commit(s))", picked.len()),
+    ))
+}
+
+/// Folds per-target backport results into the merge node's result: the primary landing stands
+/// either way, but any target left behind fails the node so the gap is not missed.
+pub(crate) fn with_backports(summary: String, backports: &[Backport]) -> WorkflowNodeResult {
+    if backports.is_empty() {
+        return WorkflowNodeResult::succeeded(summary);
+    }
+    let detail = backports
+        .iter()
+        .map(|backport| format!("{}: {}", backport.target, backport.detail))
+        .collect::<Vec<_>>()
+        .join("; ");
+    let summary = format!("{summary}; backports: {detail}");
+    if backports.iter().all(|backport| backport.ok) {
+        WorkflowNodeResult::succeeded(summary)
+    } else {
+        WorkflowNodeResult::failed(summary, Some(10))
+    }
+}
+
 pub(crate) fn merge_sentinel_path(project_root: &Path, slug: &str) -> PathBuf {
     project_root
-        .join(".vizier/tmp/merge-conflicts")
+        .join(crate::temp_registry::MERGE_CONFLICTS_DIR)
         .join(format!("{slug}.json"))
 }
 
@@ -339,7 +1143,7 @@ pub(crate) fn resolve_merge_conflict_branches(
                 .as_ref()
                 .and_then(|meta| meta.branch.clone())
         })
-        .unwrap_or_else(|| format!("draft/{slug}"));
+        .unwrap_or_else(|| crate::plan::default_branch_for_slug(slug));
     let target_branch = sentinel_target
         .or_else(|| first_non_empty_arg(&node.args, &["target", "target_branch"]))
         .or_else(|| {
@@ -375,15 +1179,6 @@ pub(crate) fn run_merge_conflict_auto_resolve_agent(
             ));
         }
     };
-    let runner = match prompt_settings.agent_runner() {
-        Ok(runner) => runner.clone(),
-        Err(err) => {
-            return Some(format!(
-                "merge-conflict auto-resolve requires agent runner: {err}"
-            ));
-        }
-    };
-
     let mut prompt_selection = prompt_settings
         .prompt_selection()
         .cloned()
@@ -417,12 +1212,95 @@ pub(crate) fn run_merge_conflict_auto_resolve_agent(
                 None
             }
         };
-    let prompt = match crate::agent_prompt::build_merge_conflict_prompt(
+    let source_plan_document = plan_context_with_notes(
+        execution_root,
+        &source_branch,
+        &merge_slug,
+        source_plan_document,
+    );
+    run_merge_conflict_prompt(
+        execution_root,
+        &prompt_settings,
         &prompt_selection,
         &target_branch,
         &source_branch,
         conflicts,
         source_plan_document.as_deref(),
+    )
+}
+
+/// Runs the merge-conflict agent outside a workflow job (for example while `vizier rebase`
+/// replays a draft branch) against the conflicts left in `execution_root`. The agent is
+/// resolved from the `merge` scope so operators get the same profile and prompt as the
+/// workflow auto-resolve path.
+pub fn resolve_conflicts_with_merge_agent(
+    execution_root: &Path,
+    target_branch: &str,
+    source_branch: &str,
+    slug: &str,
+    conflicts: &[String],
+) -> Result<(), String> {
+    let cfg = config::get_config();
+    let base_settings = config::resolve_agent_settings(&cfg, config::CommandScope::Merge, None)
+        .map_err(|err| format!("merge-conflict agent settings unavailable: {err}"))?;
+    let prompt_settings = base_settings
+        .for_prompt(config::PromptKind::MergeConflict)
+        .map_err(|err| format!("merge-conflict prompt profile unavailable: {err}"))?;
+    let prompt_selection = prompt_settings
+        .prompt_selection()
+        .cloned()
+        .unwrap_or_else(|| {
+            cfg.prompt_for(
+                config::CommandScope::Merge,
+                config::PromptKind::MergeConflict,
+            )
+        });
+    let plan_document =
+        match load_plan_document_for_merge_message(execution_root, source_branch, slug) {
+            Ok(document) => document,
+            Err(err) => {
+                eprintln!("merge-conflict source plan context unavailable: {err}");
+                None
+            }
+        };
+    let plan_document = plan_context_with_notes(execution_root, source_branch, slug, plan_document);
+    match run_merge_conflict_prompt(
+        execution_root,
+        &prompt_settings,
+        &prompt_selection,
+        target_branch,
+        source_branch,
+        conflicts,
+        plan_document.as_deref(),
+    ) {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
+
+fn run_merge_conflict_prompt(
+    execution_root: &Path,
+    prompt_settings: &config::AgentSettings,
+    prompt_selection: &config::PromptSelection,
+    target_branch: &str,
+    source_branch: &str,
+    conflicts: &[String],
+    source_plan_document: Option<&str>,
+) -> Option<String> {
+    let runner = match prompt_settings.agent_runner() {
+        Ok(runner) => runner.clone(),
+        Err(err) => {
+            return Some(format!(
+                "merge-conflict auto-resolve requires agent runner: {err}"
+            ));
+        }
+    };
+    let prompt = match crate::agent_prompt::build_merge_conflict_prompt(
+        prompt_selection,
+        target_branch,
+        source_branch,
+        conflicts,
+        source_plan_document,
         &prompt_settings.documentation,
     ) {
         Ok(prompt) => prompt,
@@ -432,7 +1310,7 @@ pub(crate) fn run_merge_conflict_auto_resolve_agent(
     };
 
     let request =
-        build_workflow_agent_request(&prompt_settings, prompt, execution_root.to_path_buf());
+        build_workflow_agent_request(prompt_settings, prompt, execution_root.to_path_buf());
     match execute_agent_request_blocking(runner, request) {
         Ok(response) => {
             if !response.assistant_text.is_empty() {
@@ -464,11 +1342,7 @@ pub(crate) fn merge_plan_slug_from_context(
 ) -> Option<String> {
     first_non_empty_arg(&node.args, &["slug", "plan"])
         .or_else(|| record.metadata.as_ref().and_then(|meta| meta.plan.clone()))
-        .or_else(|| {
-            source_branch
-                .strip_prefix("draft/")
-                .map(|value| value.to_string())
-        })
+        .or_else(|| crate::plan::slug_from_branch(source_branch))
         .map(|value| value.trim().to_string())
         .filter(|value| !value.is_empty())
 }
@@ -481,6 +1355,260 @@ pub(crate) fn merge_commit_message_with_plan(subject: &str, plan_document: Optio
     }
 }
 
+/// Message for a plan merge commit: `[merge] commit_template` when configured, otherwise the
+/// subject followed by the plan document. A template that renders empty or fails falls back to
+/// the default with a warning.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn plan_merge_commit_message(
+    project_root: &Path,
+    jobs_root: &Path,
+    record: &JobRecord,
+    slug: &str,
+    source_branch: &str,
+    target_branch: Option<&str>,
+    subject: &str,
+    plan_document: Option<&str>,
+) -> String {
+    let Some(template) = config::get_config().merge.commit_template.clone() else {
+        return merge_commit_message_with_plan(subject, plan_document);
+    };
+    let plan = plan_document.map(str::trim).unwrap_or_default();
+    let review_verdict = latest_review_critique(project_root, jobs_root, slug)
+        .map(|critique| crate::review_report::ReviewReport::parse(&critique).verdict())
+        .unwrap_or_else(|| "not reviewed".to_string());
+    let diff_stats = target_branch
+        .and_then(|target| crate::vcs::diff_stats_between(project_root, target, source_branch).ok())
+        .map(|stats| stats.summary_line())
+        .unwrap_or_default();
+    let note = record
+        .metadata
+        .as_ref()
+        .and_then(|meta| meta.operator_note.clone())
+        .unwrap_or_default();
+    let values = [
+        ("subject", subject.trim().to_string()),
+        (
+            "title",
+            crate::plan::plan_title(plan).unwrap_or_else(|| slug.to_string()),
+        ),
+        ("slug", slug.to_string()),
+        ("branch", source_branch.to_string()),
+        ("target", target_branch.unwrap_or_default().to_string()),
+        ("session_id", crate::auditor::Auditor::session_id()),
+        ("note", note.trim().to_string()),
+        ("review_verdict", review_verdict),
+        ("diff_stats", diff_stats),
+        ("plan", plan.to_string()),
+    ];
+    match config::render_merge_commit_template(&template, &values) {
+        Ok(message) if !message.trim().is_empty() => message.trim().to_string(),
+        Ok(_) => {
+            display::warn("[merge] commit_template rendered an empty message; using the default");
+            merge_commit_message_with_plan(subject, plan_document)
+        }
+        Err(err) => {
+            display::warn(format!("{err}; using the default merge commit message"));
+            merge_commit_message_with_plan(subject, plan_document)
+        }
+    }
+}
+
+/// Messages for squash-by-step commits, one per group: `<subject> (step <n>/<total>: <title>)`
+/// over the squashed commit subjects for every step but the last, which carries the full plan merge
+/// message. Each gains the `Vizier-Step` trailer and, outside `style = "notes"`, the
+/// `Vizier-Plan`/`Vizier-Session` trailers the rebase strategy adds.
+pub(crate) fn step_squash_messages(
+    merge_message: &str,
+    subject: &str,
+    slug: &str,
+    groups: &[crate::vcs::StepGroup],
+    plan_document: Option<&str>,
+) -> Vec<String> {
+    let titles = plan_document
+        .map(crate::plan::execution_steps)
+        .unwrap_or_default();
+    let total = titles.len().max(
+        groups
+            .iter()
+            .filter_map(|group| group.step)
+            .max()
+            .unwrap_or(0) as usize,
+    );
+    let notes = matches!(
+        config::get_config().commits.meta.style,
+        config::CommitMetaStyle::Notes
+    );
+    groups
+        .iter()
+        .enumerate()
+        .map(|(index, group)| {
+            let step = group.step.unwrap_or(0);
+            let mut trailers = Vec::new();
+            if index + 1 == groups.len() {
+                trailers.push(format!("{}: {step}", crate::vcs::STEP_TRAILER));
+                return crate::vcs::append_trailers(merge_message, trailers);
+            }
+            let label = match titles.get((step as usize).wrapping_sub(1)) {
+                Some(title) => format!("step {step}/{total}: {title}"),
+                None => format!("step {step}/{total}"),
+            };
+            let squashed = group
+                .subjects
+                .iter()
+                .filter(|line| !line.is_empty())
+                .map(|line| format!("- {line}"))
+                .collect::<Vec<_>>()
+                .join("\n");
+            if !notes {
+                trailers.push(format!("Vizier-Plan: {slug}"));
+                trailers.push(format!(
+                    "Vizier-Session: {}",
+                    crate::auditor::Auditor::session_id()
+                ));
+            }
+            trailers.push(format!("{}: {step}", crate::vcs::STEP_TRAILER));
+            crate::vcs::append_trailers(
+                &format!("{} ({label})\n\n{squashed}", subject.trim()),
+                trailers,
+            )
+        })
+        .collect()
+}
+
+/// With `[merge] require_owner_ack`, blocks the merge until an owner of every affected narrative
+/// thread has recorded `vizier ack`.
+pub(crate) fn owner_ack_block(
+    project_root: &Path,
+    slug: &str,
+    threads: &[crate::thread_owners::OwnedThread],
+) -> Option<WorkflowNodeResult> {
+    if !config::get_config().merge.require_owner_ack || threads.is_empty() {
+        return None;
+    }
+    let acks = match crate::thread_owners::load_acks(project_root, slug) {
+        Ok(acks) => acks,
+        Err(err) => {
+            return Some(WorkflowNodeResult::failed(
+                format!(
+                    "git.integrate_plan_branch could not read acknowledgments for {slug}: {err}"
+                ),
+                Some(1),
+            ));
+        }
+    };
+    let missing = crate::thread_owners::unacknowledged(threads, &acks);
+    if missing.is_empty() {
+        return None;
+    }
+    let needed = missing
+        .iter()
+        .map(|thread| format!("{} ({})", thread.thread, thread.owners.join(", ")))
+        .collect::<Vec<_>>()
+        .join("; ");
+    Some(WorkflowNodeResult::blocked(
+        format!(
+            "git.integrate_plan_branch needs a thread owner acknowledgment for {needed}; run `vizier ack {slug} --as <owner>`"
+        ),
+        Some(10),
+    ))
+}
+
+/// Blocks the merge when it touches a narrative thread whose lifecycle state the branch left
+/// stale (still resolved by another plan, or an unknown `state:`).
+pub(crate) fn thread_state_block(
+    execution_root: &Path,
+    target: &str,
+    source_branch: &str,
+    slug: &str,
+    plan_document: Option<&str>,
+) -> Option<WorkflowNodeResult> {
+    let issues = match crate::thread_state::merge_state_issues(
+        execution_root,
+        target,
+        source_branch,
+        slug,
+        plan_document,
+    ) {
+        Ok(issues) => issues,
+        Err(err) => {
+            display::warn(format!(
+                "git.integrate_plan_branch could not read narrative thread states: {err}"
+            ));
+            return None;
+        }
+    };
+    if issues.is_empty() {
+        return None;
+    }
+    Some(WorkflowNodeResult::blocked(
+        format!(
+            "git.integrate_plan_branch found stale narrative thread state: {}",
+            issues.join("; ")
+        ),
+        Some(10),
+    ))
+}
+
+/// Prints the pre-merge impact report for `source_branch` and stores it as `impact.md` and
+/// `impact.json` in this invocation's session directory. Failures only warn; the merge proceeds.
+pub(crate) fn emit_impact_report(
+    project_root: &Path,
+    execution_root: &Path,
+    target: &str,
+    source_branch: &str,
+    plan_document: Option<&str>,
+) {
+    let report =
+        match crate::impact::impact_report(execution_root, target, source_branch, plan_document) {
+            Ok(report) => report,
+            Err(err) => {
+                display::warn(format!(
+                    "git.integrate_plan_branch could not build the impact report: {err}"
+                ));
+                return;
+            }
+        };
+    let rendered = report.render();
+    eprintln!("{}", rendered.trim_end());
+
+    let mut details = serde_json::json!({
+        "branch": report.branch,
+        "target": report.target,
+        "files": report.subsystems.iter().map(|subsystem| subsystem.files.len()).sum::<usize>(),
+        "api_changes": report.api_changes.len(),
+        "test_files": report.test_files.len(),
+        "source_files": report.source_files.len(),
+        "threads": report.threads,
+    });
+    if !config::get_config().no_session {
+        let session_dir = crate::sessions::sessions_root(project_root)
+            .join(crate::auditor::Auditor::session_id());
+        let written = fs::create_dir_all(&session_dir)
+            .and_then(|_| fs::write(session_dir.join("impact.md"), &rendered))
+            .and_then(|_| {
+                let json = serde_json::to_vec_pretty(&report).map_err(io::Error::other)?;
+                fs::write(session_dir.join("impact.json"), json)
+            });
+        match written {
+            Ok(()) => {
+                details["path"] = serde_json::Value::String(
+                    session_dir
+                        .join("impact.md")
+                        .strip_prefix(project_root)
+                        .unwrap_or(&session_dir)
+                        .display()
+                        .to_string(),
+                );
+            }
+            Err(err) => display::warn(format!(
+                "unable to store the impact report in {}: {err}",
+                session_dir.display()
+            )),
+        }
+    }
+    crate::auditor::Auditor::record_operation("impact_report", details);
+}
+
 pub(crate) fn git_blob_exists_at_revision(
     execution_root: &Path,
     revision: &str,
@@ -506,10 +1634,56 @@ pub(crate) fn load_plan_document_for_merge_message(
     source_branch: &str,
     slug: &str,
 ) -> Result<Option<String>, Box<dyn std::error::Error>> {
-    let plan_rel = crate::plan::plan_rel_path(slug)
-        .to_string_lossy()
-        .replace('\\', "/");
-    let tip_revision = format!("{source_branch}:{plan_rel}");
+    load_latest_plan_file(
+        execution_root,
+        source_branch,
+        &crate::plan::plan_rel_path(slug),
+    )
+}
+
+/// The plan's operator notes (`vizier note`) from `source_branch`, found the same way as its plan
+/// document.
+pub(crate) fn load_plan_notes_for_merge(
+    execution_root: &Path,
+    source_branch: &str,
+    slug: &str,
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    load_latest_plan_file(
+        execution_root,
+        source_branch,
+        &crate::plan::plan_notes_rel_path(slug),
+    )
+}
+
+/// Merge-conflict context for a plan: its document followed by its operator notes.
+fn plan_context_with_notes(
+    execution_root: &Path,
+    source_branch: &str,
+    slug: &str,
+    plan_document: Option<String>,
+) -> Option<String> {
+    let notes = match load_plan_notes_for_merge(execution_root, source_branch, slug) {
+        Ok(notes) => notes.filter(|notes| !notes.trim().is_empty()),
+        Err(err) => {
+            eprintln!("merge-conflict plan notes unavailable: {err}");
+            None
+        }
+    };
+    match (plan_document, notes) {
+        (Some(plan), Some(notes)) => Some(format!("{}\n\n{}", plan.trim_end(), notes.trim_end())),
+        (plan, notes) => plan.or(notes),
+    }
+}
+
+// The file at the branch tip, or its newest version in the branch history once the merge
+// cleanup commit has removed it.
+fn load_latest_plan_file(
+    execution_root: &Path,
+    source_branch: &str,
+    rel_path: &Path,
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    let rel = rel_path.to_string_lossy().replace('\\', "/");
+    let tip_revision = format!("{source_branch}:{rel}");
     if git_blob_exists_at_revision(execution_root, &tip_revision)? {
         return Ok(Some(git_show_blob_at_revision(
             execution_root,
@@ -517,12 +1691,12 @@ pub(crate) fn load_plan_document_for_merge_message(
         )?));
     }
 
-    let revisions =
-        crate::vcs::revisions_touching_path_in(execution_root, source_branch, &plan_rel).map_err(
-            |err| format!("unable to inspect revisions for `{source_branch}` `{plan_rel}`: {err}"),
-        )?;
+    let revisions = crate::vcs::revisions_touching_path_in(execution_root, source_branch, &rel)
+        .map_err(|err| {
+            format!("unable to inspect revisions for `{source_branch}` `{rel}`: {err}")
+        })?;
     for oid in revisions {
-        let revision = format!("{oid}:{plan_rel}");
+        let revision = format!("{oid}:{rel}");
         if !git_blob_exists_at_revision(execution_root, &revision)? {
             continue;
         }
@@ -531,11 +1705,19 @@ pub(crate) fn load_plan_document_for_merge_message(
     Ok(None)
 }
 
+/// Removes the plan doc and its operator notes from the source branch tip so they never land on
+/// the target. When `archive` is given, the archive is written in the same commit so it merges in
+/// their place.
 pub(crate) fn ensure_source_plan_doc_removed_before_merge(
     execution_root: &Path,
     source_branch: &str,
     target_branch: Option<&str>,
     slug: &str,
+    archive: Option<&crate::archive::PlanArchive>,
+    changelog: Option<(
+        &crate::changelog::ChangelogFragment,
+        &crate::config::ChangelogConfig,
+    )>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let plan_rel = crate::plan::plan_rel_path(slug)
         .to_string_lossy()
@@ -570,14 +1752,41 @@ pub(crate) fn ensure_source_plan_doc_removed_before_merge(
             })?;
         }
 
-        crate::vcs::stage_paths_allow_missing_in(execution_root, &[plan_rel.as_str()])
+        let mut staged = vec![plan_rel.clone()];
+        let notes_rel = crate::plan::plan_notes_rel_path(slug)
+            .to_string_lossy()
+            .replace('\\', "/");
+        let notes_abs = execution_root.join(&notes_rel);
+        if notes_abs.exists() {
+            fs::remove_file(&notes_abs).map_err(|err| {
+                format!("failed removing `{notes_rel}` on `{source_branch}`: {err}")
+            })?;
+            staged.push(notes_rel);
+        }
+        let message = match archive {
+            Some(archive) => {
+                staged.extend(
+                    crate::archive::write_archive(execution_root, archive).map_err(|err| {
+                        format!("failed writing plan archive for `{slug}`: {err}")
+                    })?,
+                );
+                format!("chore: archive implementation plan {slug}")
+            }
+            None => format!("chore: remove implementation plan doc {slug}"),
+        };
+        if let Some((fragment, config)) = changelog {
+            staged.extend(
+                crate::changelog::write_fragment(execution_root, config, fragment).map_err(
+                    |err| format!("failed writing changelog fragment for `{slug}`: {err}"),
+                )?,
+            );
+        }
+        let staged = staged.iter().map(String::as_str).collect::<Vec<_>>();
+        crate::vcs::stage_paths_allow_missing_in(execution_root, &staged)
             .map_err(|err| format!("failed to stage plan cleanup for `{plan_rel}`: {err}"))?;
-        crate::vcs::commit_staged_in(
-            execution_root,
-            &format!("chore: remove implementation plan doc {slug}"),
-            false,
-        )
-        .map_err(|err| format!("failed to commit plan cleanup on `{source_branch}`: {err}"))?;
+        let oid = crate::vcs::commit_staged_in(execution_root, &message, false)
+            .map_err(|err| format!("failed to commit plan cleanup on `{source_branch}`: {err}"))?;
+        crate::auditor::record_commit_note(execution_root, oid, Some(slug));
 
         Ok(())
     })();
@@ -591,6 +1800,212 @@ pub(crate) fn ensure_source_plan_doc_removed_before_merge(
     cleanup_result
 }
 
+/// Newest succeeded review job for `slug` with a non-empty critique on stdout.
+pub(crate) fn latest_review_critique(
+    project_root: &Path,
+    jobs_root: &Path,
+    slug: &str,
+) -> Option<String> {
+    let mut candidates = list_records(jobs_root)
+        .ok()?
+        .into_iter()
+        .filter(|record| record.status == JobStatus::Succeeded)
+        .filter(|record| {
+            record.metadata.as_ref().is_some_and(|meta| {
+                meta.plan.as_deref() == Some(slug)
+                    && (meta.scope.as_deref() == Some("review")
+                        || meta
+                            .workflow_node_id
+                            .as_deref()
+                            .is_some_and(|node| node.contains("review")))
+            })
+        })
+        .collect::<Vec<_>>();
+    candidates.sort_by_key(|record| std::cmp::Reverse(record.created_at));
+    candidates.into_iter().find_map(|record| {
+        fs::read_to_string(resolve_recorded_path(project_root, &record.stdout_path))
+            .ok()
+            .filter(|critique| !critique.trim().is_empty())
+    })
+}
+
+/// Archive for a plan about to merge: the plan document, a diff summary of `target...source`,
+/// and the latest review critique and operator notes when they exist.
+pub(crate) fn plan_archive_for_merge(
+    project_root: &Path,
+    jobs_root: &Path,
+    execution_root: &Path,
+    slug: &str,
+    source_branch: &str,
+    target_branch: Option<&str>,
+    plan_document: &str,
+) -> Result<crate::archive::PlanArchive, Box<dyn std::error::Error>> {
+    // Without an explicit target the merge lands on whatever is checked out.
+    let target = target_branch.unwrap_or("HEAD");
+    let tip = Repository::open(execution_root)?
+        .revparse_single(source_branch)?
+        .peel_to_commit()?
+        .id();
+    let mut archive = crate::archive::PlanArchive::new(
+        slug,
+        source_branch,
+        target_branch,
+        &tip.to_string(),
+        plan_document,
+    );
+    let diff = crate::vcs::diff_summary_between(execution_root, target, source_branch)?;
+    let files = diff
+        .files
+        .files
+        .iter()
+        .map(|file| {
+            let path = match file.old_path.as_deref() {
+                Some(old) => format!("{old} -> {}", file.path),
+                None => file.path.clone(),
+            };
+            let counts = if file.binary {
+                "binary".to_string()
+            } else {
+                format!("+{} -{}", file.insertions, file.deletions)
+            };
+            format!("{}\t{path}\t{counts}", file.status.code())
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    archive.summary = format!(
+        "# Merge summary: {}\n\n- Branch: `{source_branch}` at `{tip}`\n- Target: `{target}`\n- Changes: {}\n\n## Stats\n\n```\n{}\n```\n\n## Files\n\n```\n{files}\n```\n",
+        archive.metadata.title,
+        diff.files.summary_line(),
+        diff.stats.trim_end(),
+    );
+    archive.critique = latest_review_critique(project_root, jobs_root, slug);
+    archive.notes = load_plan_notes_for_merge(execution_root, source_branch, slug)?;
+    Ok(archive)
+}
+
+/// Compares a plan branch commit against the plan's `## Expected Surfaces`. Commits that only
+/// touch `.vizier/` (a freshly drafted plan) are skipped, as are plans without the section.
+pub(crate) fn expected_surface_check(
+    project_root: &Path,
+    execution_root: &Path,
+    record: &JobRecord,
+) -> Option<crate::surfaces::SurfaceCheck> {
+    let metadata = record.metadata.as_ref()?;
+    let slug = metadata.plan.as_deref()?;
+    let target = metadata
+        .target
+        .clone()
+        .or_else(|| crate::vcs::detect_primary_branch_in(project_root))?;
+    match crate::surfaces::check_branch(execution_root, slug, &target, "HEAD") {
+        Ok(Some(check)) if !check.touched.is_empty() || !check.unexpected.is_empty() => {
+            crate::auditor::Auditor::record_operation(
+                "expected_surfaces",
+                serde_json::json!({ "plan": slug, "check": check }),
+            );
+            Some(check)
+        }
+        Ok(_) => None,
+        Err(err) => {
+            display::warn(format!(
+                "unable to check expected surfaces for plan `{slug}`: {err}"
+            ));
+            None
+        }
+    }
+}
+
+/// With `[draft] auto_push`, mirrors the plan branch checked out at `execution_root` to `origin`
+/// after a commit, force-with-lease when the branch was rewritten. Returns the stderr line to
+/// report; push failures, including a remote copy someone else moved, only warn.
+pub(crate) fn auto_push_plan_branch(execution_root: &Path) -> Option<String> {
+    if !config::get_config().draft.auto_push {
+        return None;
+    }
+    let branch = current_branch_name(execution_root)?;
+    crate::plan::slug_from_branch(&branch)?;
+    let line = match crate::vcs::push_branch_with_lease_in(execution_root, "origin", &branch) {
+        Ok(outcome) => {
+            let how = match outcome {
+                crate::vcs::LeasePush::Created => "created",
+                crate::vcs::LeasePush::FastForward => "fast-forward",
+                crate::vcs::LeasePush::Forced => "forced with lease",
+                crate::vcs::LeasePush::UpToDate => "already up to date",
+            };
+            crate::auditor::Auditor::record_operation(
+                "auto_push",
+                serde_json::json!({ "branch": branch, "remote": "origin", "outcome": how }),
+            );
+            format!("[workflow-node] info auto-pushed `{branch}` to origin ({how})")
+        }
+        Err(err) => format!("[workflow-node] warning auto-push of `{branch}` skipped: {err}"),
+    };
+    eprintln!("{line}");
+    Some(line)
+}
+
+/// Patch-mode stand-in for a commit node: when the job was enqueued with `vizier run --patch`,
+/// writes the worktree's changes to the job's `command.patch` and restores the worktree instead
+/// of committing. `None` for jobs that commit normally.
+pub(crate) fn commit_patch_result(
+    project_root: &Path,
+    jobs_root: &Path,
+    execution_root: &Path,
+    record: &JobRecord,
+    operation: &str,
+) -> Result<Option<WorkflowNodeResult>, Box<dyn std::error::Error>> {
+    let patch_mode = record
+        .metadata
+        .as_ref()
+        .and_then(|metadata| metadata.commit_disposition)
+        == Some(crate::auditor::CommitDisposition::Patch);
+    if !patch_mode {
+        return Ok(None);
+    }
+    let captured = match crate::file_tracking::FileTracker::capture_patch(execution_root) {
+        Ok(Some(captured)) => captured,
+        Ok(None) => {
+            return Ok(Some(WorkflowNodeResult::succeeded(format!(
+                "{operation}: no changes to capture"
+            ))));
+        }
+        Err(err) => {
+            return Ok(Some(WorkflowNodeResult::failed(
+                format!("{operation} could not capture patch: {err}"),
+                Some(1),
+            )));
+        }
+    };
+    let patch_path = command_patch_path(jobs_root, &record.id);
+    if let Some(parent) = patch_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&patch_path, &captured.patch)?;
+    let patch_rel = relative_path(project_root, &patch_path);
+    crate::auditor::Auditor::record_operation(
+        "patch_captured",
+        serde_json::json!({ "patch": patch_rel, "paths": captured.paths }),
+    );
+
+    let line = format!(
+        "[workflow-node] info captured {} changed path(s) as {patch_rel}; worktree restored",
+        captured.paths.len()
+    );
+    eprintln!("{line}");
+    let mut result = WorkflowNodeResult::succeeded(format!(
+        "{operation} captured patch {patch_rel} instead of committing"
+    ));
+    result.stderr_lines.push(line);
+    result.artifacts_written = vec![JobArtifact::CommandPatch {
+        job_id: record.id.clone(),
+    }];
+    result.payload_refs = vec![patch_rel.clone()];
+    result.metadata = Some(JobMetadata {
+        patch_file: Some(patch_rel),
+        ..JobMetadata::default()
+    });
+    Ok(Some(result))
+}
+
 pub(crate) fn parse_string_list_json_arg(
     node: &WorkflowRuntimeNodeManifest,
     arg_key: &str,
@@ -698,10 +2113,13 @@ pub(crate) fn build_workflow_agent_request(
         config::AgentRuntimeResolution::ProvidedCommand => {
             metadata.insert("agent_command_source".to_string(), "configured".to_string());
         }
+        config::AgentRuntimeResolution::Mock => {
+            metadata.insert("agent_command_source".to_string(), "mock".to_string());
+        }
     }
 
     AgentRequest {
-        prompt,
+        prompt: crate::i18n::with_language_directive(prompt),
         repo_root,
         command: agent.agent_runtime.command.clone(),
         progress_filter: agent.agent_runtime.progress_filter.clone(),
@@ -710,6 +2128,7 @@ pub(crate) fn build_workflow_agent_request(
         scope: agent.scope,
         metadata,
         timeout: Some(DEFAULT_AGENT_TIMEOUT),
+        env: agent.env.clone(),
     }
 }
 
@@ -725,11 +2144,73 @@ pub(crate) fn execute_agent_request_blocking(
         runtime.block_on(runner.execute(request, None))
     });
 
-    match handle.join() {
-        Ok(result) => result,
-        Err(_) => Err(AgentError::Io(io::Error::other(
-            "agent worker thread panicked",
-        ))),
+    match handle.join() {
+        Ok(result) => result,
+        Err(_) => Err(AgentError::Io(io::Error::other(
+            "agent worker thread panicked",
+        ))),
+    }
+}
+
+/// Warns when a drafted plan exceeds `[draft]` limits, with how it compares to the sizes recorded
+/// for earlier plans. `suggest_split` adds the `--split` hint for unsplit drafts.
+pub(crate) fn warn_oversized_plan(
+    slug: &str,
+    size: crate::plan::PlanSize,
+    limits: &config::DraftConfig,
+    earlier: &[crate::plan::PlanSize],
+    suggest_split: bool,
+    stderr_lines: &mut Vec<String>,
+) {
+    let violations = crate::plan::plan_size_violations(size, limits);
+    if violations.is_empty() {
+        return;
+    }
+    let mut line = format!(
+        "[workflow-node] warn plan.persist: plan `{slug}` looks too large for one approve run: {}",
+        violations.join(", ")
+    );
+    if !earlier.is_empty() {
+        let smaller = earlier
+            .iter()
+            .filter(|earlier| earlier.steps + earlier.files < size.steps + size.files)
+            .count();
+        line.push_str(&format!(
+            "; larger than {smaller} of {} earlier plans",
+            earlier.len()
+        ));
+    }
+    if suggest_split {
+        line.push_str("; re-draft with `--split` to emit smaller dependent plans");
+    }
+    eprintln!("{line}");
+    stderr_lines.push(line);
+}
+
+/// Warns when a freshly drafted plan mentions files that other pending plans already change or
+/// plan to change. Overlap detection is best-effort; git errors skip the check.
+pub(crate) fn warn_plan_overlaps(
+    repo_root: &Path,
+    surface: &crate::overlap::PlanSurface,
+    target: Option<&str>,
+    stderr_lines: &mut Vec<String>,
+) {
+    let Some(target) = target
+        .map(str::to_string)
+        .or_else(|| crate::vcs::detect_primary_branch_in(repo_root))
+    else {
+        return;
+    };
+    let Ok(overlaps) = crate::overlap::find_overlaps(repo_root, surface, &target) else {
+        return;
+    };
+    for overlap in overlaps {
+        let line = format!(
+            "[workflow-node] warn plan.persist: {}",
+            overlap.message(&surface.slug)
+        );
+        eprintln!("{line}");
+        stderr_lines.push(line);
     }
 }
 
@@ -797,14 +2278,38 @@ pub(crate) fn workflow_prompt_text_from_record(
     };
 
     let variables = collect_prompt_template_variables(project_root, execution_root, record, node)?;
+    let mut summarize_diff = |prompt: String| summarize_diff_chunk(execution_root, record, prompt);
     render_prompt_template(
         &raw_prompt_text,
         &variables,
         execution_root,
         workflow_run_is_ephemeral(record),
+        &mut summarize_diff,
     )
 }
 
+// Oversized `{{diff:...}}` placeholders are summarized chunk by chunk with the same agent the
+// workflow node resolves, so operators do not need a separate summarizer profile.
+pub(crate) fn summarize_diff_chunk(
+    execution_root: &Path,
+    record: &JobRecord,
+    prompt: String,
+) -> Result<String, String> {
+    let settings = resolve_workflow_agent_settings(record).map_err(|err| err.to_string())?;
+    let runner = settings
+        .agent_runner()
+        .map_err(|err| err.to_string())?
+        .clone();
+    let request = build_workflow_agent_request(&settings, prompt, execution_root.to_path_buf());
+    let response =
+        execute_agent_request_blocking(runner, request).map_err(|err| err.to_string())?;
+    let summary = response.assistant_text.trim();
+    if summary.is_empty() {
+        return Err("agent returned an empty summary".to_string());
+    }
+    Ok(summary.to_string())
+}
+
 pub(crate) fn collect_prompt_template_variables(
     project_root: &Path,
     execution_root: &Path,
@@ -870,6 +2375,15 @@ pub(crate) fn collect_prompt_template_variables(
     variables
         .entry("execution_root".to_string())
         .or_insert_with(|| execution_root.to_string_lossy().to_string());
+    if let Some(baseline) = record
+        .metadata
+        .as_ref()
+        .and_then(|meta| meta.review_baseline.as_ref())
+    {
+        variables
+            .entry(REVIEW_BASELINE_VARIABLE.to_string())
+            .or_insert_with(|| baseline.clone());
+    }
     Ok(variables)
 }
 
@@ -878,14 +2392,17 @@ pub(crate) fn render_prompt_template(
     variables: &BTreeMap<String, String>,
     execution_root: &Path,
     ephemeral: bool,
+    summarize_diff: &mut dyn FnMut(String) -> Result<String, String>,
 ) -> Result<(String, Vec<String>), Box<dyn std::error::Error>> {
-    let mut rendered = String::with_capacity(template.len());
+    use crate::prompt_budget::PromptSegment;
+
+    let mut segments = Vec::new();
     let mut stderr_lines = Vec::new();
     let mut cursor = 0usize;
 
     while let Some(open_rel) = template[cursor..].find("{{") {
         let open = cursor + open_rel;
-        rendered.push_str(&template[cursor..open]);
+        segments.push(PromptSegment::Fixed(template[cursor..open].to_string()));
         let key_start = open + 2;
         let Some(close_rel) = template[key_start..].find("}}") else {
             return Err("prompt.resolve found unclosed placeholder; expected `}}`".into());
@@ -901,13 +2418,54 @@ pub(crate) fn render_prompt_template(
             variables,
             execution_root,
             ephemeral,
+            summarize_diff,
             &mut stderr_lines,
         )?;
-        rendered.push_str(&replacement);
+        segments.push(match crate::prompt_budget::tier_for_placeholder(key) {
+            Some(tier) => PromptSegment::Context {
+                source: key.to_string(),
+                tier,
+                text: replacement,
+            },
+            None => PromptSegment::Fixed(replacement),
+        });
         cursor = close + 2;
     }
 
-    rendered.push_str(&template[cursor..]);
+    segments.push(PromptSegment::Fixed(template[cursor..].to_string()));
+    let max_bytes = config::get_config().workflow.prompt_budget.max_bytes as usize;
+    let (rendered, report) = crate::prompt_budget::fit_prompt(&segments, max_bytes);
+    if let Some(report) = report {
+        let dropped_bytes = report
+            .dropped
+            .iter()
+            .map(|chunk| chunk.bytes)
+            .sum::<usize>();
+        let mut line = format!(
+            "[workflow-node] warn prompt.resolve prompt is {} bytes, over workflow.prompt_budget.max_bytes ({}); dropped {} chunk(s) ({dropped_bytes} bytes): {}",
+            report.original_bytes,
+            report.max_bytes,
+            report.dropped.len(),
+            report
+                .dropped
+                .iter()
+                .map(|chunk| format!("{} {} ({})", chunk.tier.as_str(), chunk.source, chunk.label))
+                .collect::<Vec<_>>()
+                .join("; ")
+        );
+        if !report.fits {
+            line.push_str(&format!(
+                "; still {} bytes without any droppable context",
+                report.final_bytes
+            ));
+        }
+        eprintln!("{line}");
+        stderr_lines.push(line);
+        crate::auditor::Auditor::record_operation(
+            "prompt_budget",
+            serde_json::to_value(&report).unwrap_or_default(),
+        );
+    }
     Ok((rendered, stderr_lines))
 }
 
@@ -916,14 +2474,103 @@ pub(crate) fn resolve_prompt_template_placeholder(
     variables: &BTreeMap<String, String>,
     execution_root: &Path,
     ephemeral: bool,
+    summarize_diff: &mut dyn FnMut(String) -> Result<String, String>,
     stderr_lines: &mut Vec<String>,
 ) -> Result<String, Box<dyn std::error::Error>> {
+    if let Some(spec) = key.strip_prefix("diff:") {
+        let rebased = variables
+            .get(REVIEW_BASELINE_VARIABLE)
+            .and_then(|baseline| rebase_diff_spec(spec, baseline, stderr_lines));
+        let spec = rebased.as_deref().unwrap_or(spec);
+        let cfg = config::get_config();
+        let context = crate::diff_context::diff_context_for_spec(
+            execution_root,
+            spec,
+            &cfg.workflow.diff_context,
+            summarize_diff,
+        )
+        .map_err(|err| {
+            format!(
+                "prompt.resolve could not compute diff `{}`: {err}",
+                spec.trim()
+            )
+        })?;
+        if context.summarized {
+            let line = format!(
+                "[workflow-node] info prompt.resolve summarized oversized diff placeholder `{}`",
+                spec.trim()
+            );
+            eprintln!("{line}");
+            stderr_lines.push(line);
+        }
+        for warning in context.warnings {
+            let line = format!("[workflow-node] warn {warning}");
+            eprintln!("{line}");
+            stderr_lines.push(line);
+        }
+        return Ok(context.text);
+    }
+
+    if let Some(spec) = key.strip_prefix("range_diff:") {
+        return resolve_range_diff_placeholder(
+            spec,
+            execution_root,
+            variables.get(REVIEW_BASELINE_VARIABLE).map(String::as_str),
+            ephemeral,
+            stderr_lines,
+        );
+    }
+
+    if let Some(spec) = key.strip_prefix("thread_owners:") {
+        return resolve_thread_owners_placeholder(spec, execution_root, stderr_lines);
+    }
+
+    if let Some(spec) = key.strip_prefix("diff_stats:") {
+        return resolve_diff_stats_placeholder(spec, execution_root);
+    }
+
+    if let Some(spec) = key.strip_prefix("expected_surfaces:") {
+        return resolve_expected_surfaces_placeholder(spec, execution_root, stderr_lines);
+    }
+
+    if let Some(branch) = key.strip_prefix("plan_notes:") {
+        return resolve_plan_notes_placeholder(branch, execution_root);
+    }
+
+    if let Some(flag) = key.strip_prefix("plan_split:") {
+        let enabled = variables
+            .get(flag.trim())
+            .and_then(|value| parse_bool_like(value))
+            .unwrap_or(false);
+        return Ok(if enabled {
+            crate::plan::PLAN_SPLIT_INSTRUCTIONS.to_string()
+        } else {
+            String::new()
+        });
+    }
+
+    if key.trim() == "review_checks" {
+        return Ok(resolve_review_checks_placeholder(
+            execution_root,
+            stderr_lines,
+        ));
+    }
+
     if let Some(path) = key.strip_prefix("file:") {
         let trimmed = path.trim();
         if trimmed.is_empty() {
             return Err("prompt.resolve placeholder `file:` requires a non-empty path".into());
         }
         let abs = resolve_path_in_execution_root(execution_root, trimmed);
+        if crate::never_send::NeverSend::from_config(execution_root).withholds(&abs) {
+            crate::never_send::record_withheld("file_placeholder", &[trimmed.to_string()]);
+            let line = format!(
+                "[workflow-node] warn prompt.resolve withheld placeholder file `{trimmed}` (matches [context] never_send)"
+            );
+            eprintln!("{line}");
+            stderr_lines.push(line);
+            return Ok(crate::never_send::WITHHELD_PLACEHOLDER.to_string());
+        }
         return match fs::read_to_string(&abs) {
             Ok(contents) => Ok(contents),
             Err(err)
@@ -957,6 +2604,466 @@ pub(crate) fn resolve_prompt_template_placeholder(
     Err(format!("prompt.resolve unresolved placeholder `{{{{{key}}}}}`").into())
 }
 
+/// Prompt variable carrying the commit passed with `vizier run --baseline`.
+pub(crate) const REVIEW_BASELINE_VARIABLE: &str = "review_baseline";
+
+// With `vizier run --baseline`, a `<target>..<branch>` diff starts at the baseline instead, so an
+// iterative review critiques only the commits since. Non-range specs are left alone.
+fn rebase_diff_spec(spec: &str, baseline: &str, stderr_lines: &mut Vec<String>) -> Option<String> {
+    let (from, to) = spec.trim().split_once("..")?;
+    let (from, to) = (from.trim(), to.trim());
+    if to.is_empty() {
+        return None;
+    }
+    let line = format!(
+        "[workflow-node] info prompt.resolve diffing `{to}` against baseline {} instead of `{from}`",
+        &baseline[..baseline.len().min(12)]
+    );
+    eprintln!("{line}");
+    stderr_lines.push(line);
+    crate::auditor::Auditor::record_operation(
+        "review_baseline",
+        serde_json::json!({ "baseline": baseline, "replaced": from, "branch": to }),
+    );
+    Some(format!("{baseline}..{to}"))
+}
+
+// `{{range_diff:<target>..<branch>}}` shows a repeat reviewer only what changed since the tip
+// recorded at the previous render (or since `vizier run --baseline`), then records the current
+// tip for next time.
+fn resolve_range_diff_placeholder(
+    spec: &str,
+    execution_root: &Path,
+    baseline: Option<&str>,
+    ephemeral: bool,
+    stderr_lines: &mut Vec<String>,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let Some((target, branch)) = spec
+        .trim()
+        .split_once("..")
+        .map(|(target, branch)| (target.trim(), branch.trim()))
+        .filter(|(target, branch)| !target.is_empty() && !branch.is_empty())
+    else {
+        return Err(format!(
+            "prompt.resolve placeholder `range_diff:{}` expects `<target>..<branch>`",
+            spec.trim()
+        )
+        .into());
+    };
+    let ledger_root = crate::review_ledger::ledger_root(execution_root)?;
+    let delta = match baseline {
+        Some(baseline) => {
+            crate::auditor::Auditor::record_operation(
+                "review_baseline",
+                serde_json::json!({ "baseline": baseline, "replaced": "review_ledger", "branch": branch }),
+            );
+            crate::review_ledger::review_delta_since(execution_root, target, branch, baseline)
+        }
+        None => crate::review_ledger::review_delta(execution_root, &ledger_root, target, branch),
+    };
+    let (mut delta, tip) = delta.map_err(|err| {
+        format!("prompt.resolve could not compute range-diff `{target}..{branch}`: {err}")
+    })?;
+    withhold_interdiffs(execution_root, &mut delta);
+    let text = crate::review_ledger::render_review_delta(&delta, branch);
+    for line in text.lines() {
+        let line = format!("[workflow-node] info range-diff {line}");
+        eprintln!("{line}");
+        stderr_lines.push(line);
+    }
+    if !ephemeral {
+        crate::review_ledger::record_reviewed_tip(&ledger_root, branch, target, tip)?;
+    }
+    Ok(text)
+}
+
+// Interdiffs quote both versions of a commit's patch, so one touching a `[context] never_send`
+// path is replaced wholesale. A commit that cannot be inspected is withheld too.
+fn withhold_interdiffs(execution_root: &Path, delta: &mut crate::review_ledger::ReviewDelta) {
+    let crate::review_ledger::ReviewDelta::Changed { range_diff, .. } = delta else {
+        return;
+    };
+    let never_send = crate::never_send::NeverSend::from_config(execution_root);
+    if never_send.is_empty() {
+        return;
+    }
+    let mut withheld = Vec::new();
+    for entry in &mut range_diff.entries {
+        if entry.interdiff.is_none() {
+            continue;
+        }
+        let mut touched = Vec::new();
+        for (_, oid) in entry.old.into_iter().chain(entry.new) {
+            match never_send.withheld_in_commit(execution_root, oid) {
+                Ok(paths) => touched.extend(paths),
+                Err(_) => touched.push(oid.to_string()),
+            }
+        }
+        if !touched.is_empty() {
+            entry.interdiff = Some(format!("{}\n", crate::never_send::WITHHELD_PLACEHOLDER));
+            withheld.extend(touched);
+        }
+    }
+    withheld.sort();
+    withheld.dedup();
+    crate::never_send::record_withheld("range_diff", &withheld);
+}
+
+// `{{expected_surfaces:<target>..<branch>}}` tells a reviewer which declared plan paths the
+// branch left untouched and which changes fall outside them.
+fn resolve_expected_surfaces_placeholder(
+    spec: &str,
+    execution_root: &Path,
+    stderr_lines: &mut Vec<String>,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let Some((target, branch)) = spec
+        .trim()
+        .split_once("..")
+        .map(|(target, branch)| (target.trim(), branch.trim()))
+        .filter(|(target, branch)| !target.is_empty() && !branch.is_empty())
+    else {
+        return Err(format!(
+            "prompt.resolve placeholder `expected_surfaces:{}` expects `<target>..<branch>`",
+            spec.trim()
+        )
+        .into());
+    };
+    let Some(slug) = crate::plan::slug_from_branch(branch) else {
+        return Ok(format!(
+            "Branch `{branch}` is not a plan branch; no expected surfaces to compare."
+        ));
+    };
+    let check =
+        crate::surfaces::check_branch(execution_root, &slug, target, branch).map_err(|err| {
+            format!("prompt.resolve could not check expected surfaces for `{branch}`: {err}")
+        })?;
+    let Some(check) = check else {
+        return Ok(format!(
+            "Plan `{slug}` declares no `## Expected Surfaces`; nothing to compare."
+        ));
+    };
+    let line = format!("[workflow-node] info {}", check.summary_line());
+    eprintln!("{line}");
+    stderr_lines.push(line);
+    crate::auditor::Auditor::record_operation(
+        "expected_surfaces",
+        serde_json::json!({ "plan": slug, "check": check }),
+    );
+    Ok(check.render())
+}
+
+/// The `<planScope>` block for a review agent working on a plan branch, with the stderr lines
+/// describing it. `None` when the branch has no plan document or nothing falls outside the plan.
+pub(crate) fn plan_scope_block(
+    project_root: &Path,
+    execution_root: &Path,
+    record: &JobRecord,
+    node: &WorkflowRuntimeNodeManifest,
+) -> (Option<String>, Vec<String>) {
+    let metadata = record.metadata.as_ref();
+    let branch = first_non_empty_arg(&node.args, &["branch"])
+        .or_else(|| metadata.and_then(|meta| meta.branch.clone()))
+        .or_else(|| {
+            metadata
+                .and_then(|meta| meta.plan.as_deref())
+                .map(crate::plan::default_branch_for_slug)
+        })
+        .or_else(|| {
+            crate::vcs::current_branch_name_in(execution_root)
+                .ok()
+                .flatten()
+        });
+    let Some(branch) = branch else {
+        return (None, Vec::new());
+    };
+    let Some(slug) = metadata
+        .and_then(|meta| meta.plan.clone())
+        .or_else(|| crate::plan::slug_from_branch(&branch))
+    else {
+        return (None, Vec::new());
+    };
+    let Some(target) = metadata
+        .and_then(|meta| meta.target.clone())
+        .or_else(|| crate::vcs::detect_primary_branch_in(project_root))
+    else {
+        return (None, Vec::new());
+    };
+
+    match crate::plan_scope::check_branch(execution_root, &slug, &target, &branch) {
+        Ok(Some(check)) => {
+            let line = format!("[workflow-node] info agent.invoke {}", check.summary_line());
+            eprintln!("{line}");
+            crate::auditor::Auditor::record_operation(
+                "plan_scope",
+                serde_json::json!({ "plan": slug, "branch": branch, "check": check }),
+            );
+            let block = (!check.is_clean()).then(|| check.render(&branch));
+            (block, vec![line])
+        }
+        Ok(None) => (None, Vec::new()),
+        Err(err) => {
+            let line = format!(
+                "[workflow-node] warn agent.invoke could not compare `{branch}` with its plan: {err}"
+            );
+            eprintln!("{line}");
+            (None, vec![line])
+        }
+    }
+}
+
+// `{{plan_notes:<branch>}}` puts the operator notes recorded with `vizier note` in front of the
+// reviewer.
+fn resolve_plan_notes_placeholder(
+    branch: &str,
+    execution_root: &Path,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let branch = branch.trim();
+    if branch.is_empty() {
+        return Err("prompt.resolve placeholder `plan_notes:` requires a branch".into());
+    }
+    let Some(slug) = crate::plan::slug_from_branch(branch) else {
+        return Ok(format!(
+            "Branch `{branch}` is not a plan branch; no operator notes."
+        ));
+    };
+    let notes = load_plan_notes_for_merge(execution_root, branch, &slug).map_err(|err| {
+        format!("prompt.resolve could not read operator notes for `{branch}`: {err}")
+    })?;
+    Ok(notes
+        .filter(|notes| !notes.trim().is_empty())
+        .unwrap_or_else(|| format!("No operator notes recorded for plan `{slug}`.")))
+}
+
+// `{{thread_owners:<target>..<branch>}}` names the owners of narrative threads the branch
+// affects, so reviewers can route the change.
+fn resolve_thread_owners_placeholder(
+    spec: &str,
+    execution_root: &Path,
+    stderr_lines: &mut Vec<String>,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let Some((target, branch)) = spec
+        .trim()
+        .split_once("..")
+        .map(|(target, branch)| (target.trim(), branch.trim()))
+        .filter(|(target, branch)| !target.is_empty() && !branch.is_empty())
+    else {
+        return Err(format!(
+            "prompt.resolve placeholder `thread_owners:{}` expects `<target>..<branch>`",
+            spec.trim()
+        )
+        .into());
+    };
+    let plan_document = crate::plan::slug_from_branch(branch).and_then(|slug| {
+        let plan_rel = crate::plan::plan_rel_path(&slug)
+            .to_string_lossy()
+            .replace('\\', "/");
+        crate::vcs::read_blob_at_revision_in(execution_root, &format!("{branch}:{plan_rel}")).ok()
+    });
+    let threads = crate::thread_owners::affected_threads(
+        execution_root,
+        target,
+        branch,
+        plan_document.as_deref(),
+    )
+    .map_err(|err| format!("prompt.resolve could not read thread owners for `{branch}`: {err}"))?;
+    let Some(summary) = crate::thread_owners::summary_line(&threads) else {
+        return Ok("No owned narrative threads are affected by this branch.".to_string());
+    };
+    let line = format!("[workflow-node] info {summary}");
+    eprintln!("{line}");
+    stderr_lines.push(line);
+    Ok(threads
+        .iter()
+        .map(|thread| format!("- `{}`: {}\n", thread.thread, thread.owners.join(", ")))
+        .collect())
+}
+
+// `{{diff_stats:<target>..<branch>}}` gives a reviewer the shape of the change (per-file line
+// counts, renames, binary files) without the full patch.
+fn resolve_diff_stats_placeholder(
+    spec: &str,
+    execution_root: &Path,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let Some((target, branch)) = spec
+        .trim()
+        .split_once("..")
+        .map(|(target, branch)| (target.trim(), branch.trim()))
+        .filter(|(target, branch)| !target.is_empty() && !branch.is_empty())
+    else {
+        return Err(format!(
+            "prompt.resolve placeholder `diff_stats:{}` expects `<target>..<branch>`",
+            spec.trim()
+        )
+        .into());
+    };
+    let stats = crate::vcs::diff_stats_between(execution_root, target, branch).map_err(|err| {
+        format!("prompt.resolve could not compute diff stats for `{branch}`: {err}")
+    })?;
+    if stats.is_empty() {
+        return Ok(format!("`{branch}` has no changes against `{target}`."));
+    }
+    let mut text = format!("{}\n", stats.summary_line());
+    for file in &stats.files {
+        let path = match file.old_path.as_deref() {
+            Some(old) => format!("`{old}` -> `{}`", file.path),
+            None => format!("`{}`", file.path),
+        };
+        let counts = if file.binary {
+            "binary".to_string()
+        } else {
+            format!("+{} -{}", file.insertions, file.deletions)
+        };
+        text.push_str(&format!("- {} {path} ({counts})\n", file.status.code()));
+    }
+    Ok(text)
+}
+
+/// Runs the configured `[review.checks]` concurrently, streaming labeled output to stderr, and
+/// substitutes the aggregated results.
+fn resolve_review_checks_placeholder(
+    execution_root: &Path,
+    stderr_lines: &mut Vec<String>,
+) -> String {
+    let cfg = config::get_config();
+    let commands = &cfg.review.checks.commands;
+    if commands.is_empty() {
+        return vizier_kernel::prompt::render_check_results(&[]);
+    }
+    let streamed = std::sync::Mutex::new(Vec::new());
+    let run = crate::review_checks::run_review_checks(
+        execution_root,
+        commands,
+        cfg.review.checks.jobs,
+        &|line| {
+            let line = format!("[workflow-node] {line}");
+            eprintln!("{line}");
+            if let Ok(mut streamed) = streamed.lock() {
+                streamed.push(line);
+            }
+        },
+    );
+    stderr_lines.extend(streamed.into_inner().unwrap_or_default());
+    for line in run.summary_lines() {
+        let line = format!("[workflow-node] info {line}");
+        eprintln!("{line}");
+        stderr_lines.push(line);
+    }
+    crate::auditor::Auditor::record_operation("review_checks", run.to_json());
+    run.render()
+}
+
+/// Operator notes recorded by `vizier jobs retry --note` ride along as corrective guidance on
+/// top of the resolved stage prompt.
+pub(crate) fn append_operator_note(prompt_text: String, note: Option<&str>) -> String {
+    let Some(note) = note.map(str::trim).filter(|note| !note.is_empty()) else {
+        return prompt_text;
+    };
+    format!(
+        "{}\n\n<operatorGuidance>\nA previous attempt at this step did not land. Keep the existing work in this worktree and apply this correction from the operator:\n{}\n</operatorGuidance>\n",
+        prompt_text.trim_end(),
+        note
+    )
+}
+
+const CONTEXT_DOCUMENT_MAX_BYTES: usize = 16 * 1024;
+const CONTEXT_DOCUMENTS_MAX_BYTES: usize = 64 * 1024;
+
+/// Appends the documents passed with `vizier run --context` as `<contextDocuments>`, each capped
+/// at 16 KiB and 64 KiB in total (truncation is marked in place). Returns one JSON entry per file
+/// for the session log; unreadable files are listed with their error instead of failing the node.
+/// Prepared documents are cached by content hash, so unchanged files are not re-read or re-cut.
+pub(crate) fn append_context_documents(
+    prompt_text: String,
+    files: Option<&[String]>,
+    project_root: &Path,
+) -> (String, Vec<serde_json::Value>) {
+    let Some(files) = files.filter(|files| !files.is_empty()) else {
+        return (prompt_text, Vec::new());
+    };
+    let mut cache = crate::context_cache::ContextCache::open(project_root);
+    let mut block = String::from(
+        "<contextDocuments>\nThe operator attached these documents as acceptance criteria and background. Check the work against them and cite a document by its path when a finding relies on it.\n",
+    );
+    let mut budget = CONTEXT_DOCUMENTS_MAX_BYTES;
+    let mut recorded = Vec::new();
+    let never_send = crate::never_send::NeverSend::from_config(project_root);
+    let mut withheld = Vec::new();
+    for file in files {
+        let path = Path::new(file);
+        let label = path
+            .strip_prefix(project_root)
+            .unwrap_or(path)
+            .display()
+            .to_string();
+        if never_send.withholds(path) {
+            recorded.push(serde_json::json!({
+                "path": label,
+                "error": "withheld by [context] never_send",
+            }));
+            withheld.push(label);
+            continue;
+        }
+        let key = format!("document:{label}");
+        let (hash, mut bytes) = match cache.file_hash(&key, path) {
+            Ok(hashed) => hashed,
+            Err(err) => {
+                recorded.push(serde_json::json!({ "path": label, "error": err.to_string() }));
+                continue;
+            }
+        };
+        let limit = CONTEXT_DOCUMENT_MAX_BYTES.min(budget);
+        let mut size = bytes.as_ref().map(Vec::len);
+        let prepared = cache.chunk(&key, &format!("{hash}:{limit}"), || {
+            let bytes = match bytes.take() {
+                Some(bytes) => bytes,
+                None => fs::read(path)?,
+            };
+            let contents = String::from_utf8_lossy(&bytes).into_owned();
+            size = Some(contents.len());
+            let mut end = contents.len().min(limit);
+            while !contents.is_char_boundary(end) {
+                end -= 1;
+            }
+            let mut chunk = format!(
+                "<document path=\"{label}\">\n{}\n",
+                contents[..end].trim_end()
+            );
+            if end < contents.len() {
+                chunk.push_str(&format!(
+                    "[truncated: {} of {} bytes shown]\n",
+                    end,
+                    contents.len()
+                ));
+            }
+            chunk.push_str("</document>\n");
+            Ok((chunk, end))
+        });
+        let prepared = match prepared {
+            Ok(prepared) => prepared,
+            Err(err) => {
+                recorded.push(serde_json::json!({ "path": label, "error": err.to_string() }));
+                continue;
+            }
+        };
+        let size = size
+            .or_else(|| fs::metadata(path).ok().map(|meta| meta.len() as usize))
+            .unwrap_or(prepared.covered);
+        let end = prepared.covered;
+        budget -= end;
+        block.push_str(&prepared.text);
+        recorded.push(serde_json::json!({
+            "path": label,
+            "bytes": size,
+            "included_bytes": end,
+            "truncated": end < size,
+            "cached": prepared.cached,
+        }));
+    }
+    crate::never_send::record_withheld("context_documents", &withheld);
+    block.push_str("</contextDocuments>\n");
+    (format!("{}\n\n{block}", prompt_text.trim_end()), recorded)
+}
+
 pub(crate) fn prompt_output_artifact(node: &WorkflowRuntimeNodeManifest) -> Option<JobArtifact> {
     let mut all = node.artifacts_by_outcome.succeeded.clone();
     all.extend(node.artifacts_by_outcome.failed.iter().cloned());
@@ -1154,10 +3261,14 @@ pub(crate) fn apply_workflow_routes(
     let source_context = workflow_execution_context_from_metadata(source_record.metadata.as_ref());
     for route in node.routes.for_outcome(outcome) {
         let Some(target) = run_manifest.nodes.get(&route.node_id) else {
-            display::warn(format!(
-                "workflow route target `{}` missing from run manifest {}",
-                route.node_id, run_manifest.run_id
-            ));
+            display::log(
+                display::Subsystem::Scheduler,
+                display::LogLevel::Warn,
+                format!(
+                    "workflow route target `{}` missing from run manifest {}",
+                    route.node_id, run_manifest.run_id
+                ),
+            );
             continue;
         };
 
@@ -1167,18 +3278,30 @@ pub(crate) fn apply_workflow_routes(
                     continue;
                 };
                 match apply_workflow_execution_context(jobs_root, &target.job_id, context, true) {
-                    Ok(true) => display::debug(format!(
-                        "workflow route {} -> {} propagated execution context",
-                        node.node_id, target.node_id
-                    )),
-                    Ok(false) => display::debug(format!(
-                        "workflow route {} -> {} skipped execution-context propagation (active or unchanged target)",
-                        node.node_id, target.node_id
-                    )),
-                    Err(err) => display::warn(format!(
-                        "workflow route {} -> {} context propagation failed: {}",
-                        node.node_id, target.node_id, err
-                    )),
+                    Ok(true) => display::log(
+                        display::Subsystem::Scheduler,
+                        display::LogLevel::Debug,
+                        format!(
+                            "workflow route {} -> {} propagated execution context",
+                            node.node_id, target.node_id
+                        ),
+                    ),
+                    Ok(false) => display::log(
+                        display::Subsystem::Scheduler,
+                        display::LogLevel::Debug,
+                        format!(
+                            "workflow route {} -> {} skipped execution-context propagation (active or unchanged target)",
+                            node.node_id, target.node_id
+                        ),
+                    ),
+                    Err(err) => display::log(
+                        display::Subsystem::Scheduler,
+                        display::LogLevel::Warn,
+                        format!(
+                            "workflow route {} -> {} context propagation failed: {}",
+                            node.node_id, target.node_id, err
+                        ),
+                    ),
                 }
             }
             WorkflowRouteMode::RetryJob => {
@@ -1196,6 +3319,7 @@ pub(crate) fn apply_workflow_routes(
                         &target.job_id,
                         source_context.as_ref(),
                         false,
+                        &RetryOptions::default(),
                     )
                 } else {
                     retry_job_internal(
@@ -1207,10 +3331,14 @@ pub(crate) fn apply_workflow_routes(
                     )
                 };
                 if let Err(err) = retry_result {
-                    display::warn(format!(
-                        "workflow route {} -> {} retry failed: {}",
-                        node.node_id, target.node_id, err
-                    ));
+                    display::log(
+                        display::Subsystem::Scheduler,
+                        display::LogLevel::Warn,
+                        format!(
+                            "workflow route {} -> {} retry failed: {}",
+                            node.node_id, target.node_id, err
+                        ),
+                    );
                 }
             }
         }
@@ -1285,7 +3413,13 @@ pub(crate) fn pause_succeeded_completion_if_configured() {
 pub(crate) fn workflow_node_has_natural_stdout(node: &WorkflowRuntimeNodeManifest) -> bool {
     matches!(
         node.executor_operation.as_deref(),
-        Some("agent.invoke" | "command.run" | "cicd.run")
+        Some(
+            "agent.invoke"
+                | "agent.generate_tests"
+                | "agent.review_fix_loop"
+                | "command.run"
+                | "cicd.run"
+        )
     ) || matches!(
         node.control_policy.as_deref(),
         Some("gate.stop_condition" | "gate.conflict_resolution" | "gate.cicd")
@@ -1344,20 +3478,51 @@ pub(crate) fn execute_workflow_node_job(
         "dispatching runtime handler",
     ));
 
+    // An operation already in progress belongs to the operator, not to this node; an interrupt
+    // must leave it alone.
+    let project_root_was_clean = Repository::open(project_root)
+        .map(|repo| repo.state() == git2::RepositoryState::Clean)
+        .unwrap_or(false);
+
     set_current_job_id(Some(job_id.to_string()));
+    let pre_hook = run_pre_phase_hook(project_root, &manifest, &record, node_manifest)?;
     let result = match (
+        pre_hook.as_ref(),
         node_manifest.executor_operation.as_deref(),
         node_manifest.control_policy.as_deref(),
     ) {
-        (Some(_), _) => execute_workflow_executor(project_root, jobs_root, &record, node_manifest),
-        (None, Some(_)) => execute_workflow_control(project_root, &record, node_manifest),
+        (Some(hook), _, _) if !hook.passed() => Ok(WorkflowNodeResult::failed(
+            format!(
+                "{} hook failed (exit {}); phase not started",
+                hook.hook, hook.exit_code
+            ),
+            Some(hook.exit_code),
+        )),
+        (_, Some(_), _) => {
+            execute_workflow_executor(project_root, jobs_root, &record, node_manifest)
+        }
+        (_, None, Some(_)) => execute_workflow_control(project_root, &record, node_manifest),
         _ => Ok(WorkflowNodeResult::failed(
             format!("workflow node {} has no runtime operation/policy", node_id),
             Some(1),
         )),
     };
     set_current_job_id(None);
+    if interrupt::is_interrupted() {
+        return record_interrupted_workflow_node(
+            project_root,
+            jobs_root,
+            job_id,
+            project_root_was_clean,
+        );
+    }
     let mut result = result?;
+    if let Some(hook) = pre_hook {
+        lifecycle_stderr_lines.extend(hook.lines);
+    }
+    if let Some(hook) = run_outcome_hook(project_root, &record, node_manifest, result.outcome)? {
+        result.stderr_lines.extend(hook.lines);
+    }
 
     let mut artifacts_written = node_manifest
         .artifacts_by_outcome
@@ -1448,16 +3613,37 @@ pub(crate) fn execute_workflow_node_job(
     };
     let metadata_update = merge_metadata(Some(metadata_update), result.metadata.clone());
     let binary = std::env::current_exe()?;
-    if result.outcome == WorkflowNodeOutcome::Succeeded {
-        display::debug(format!(
-            "workflow node {} (run {}, job {}) acquiring scheduler lock for succeeded completion",
-            node_id, run_id, job_id
+    // `vizier jobs cancel` or liveness reconciliation may have finalized the record while the
+    // node ran; its verdict stands.
+    let current = read_record(jobs_root, job_id)?;
+    if !job_is_active(current.status) {
+        display::warn(format!(
+            "workflow node {} (job {}) was already finalized as {}; not recording {}",
+            node_id,
+            job_id,
+            status_label(current.status),
+            result.outcome.as_str()
         ));
+        return Ok(current.exit_code.unwrap_or(exit_code));
+    }
+    if result.outcome == WorkflowNodeOutcome::Succeeded {
+        display::log(
+            display::Subsystem::Scheduler,
+            display::LogLevel::Debug,
+            format!(
+                "workflow node {} (run {}, job {}) acquiring scheduler lock for succeeded completion",
+                node_id, run_id, job_id
+            ),
+        );
         let _lock = SchedulerLock::acquire(jobs_root)?;
-        display::debug(format!(
-            "workflow node {} (run {}, job {}) acquired scheduler lock for succeeded completion",
-            node_id, run_id, job_id
-        ));
+        display::log(
+            display::Subsystem::Scheduler,
+            display::LogLevel::Debug,
+            format!(
+                "workflow node {} (run {}, job {}) acquired scheduler lock for succeeded completion",
+                node_id, run_id, job_id
+            ),
+        );
 
         let finalized_record = finalize_job_with_artifacts(
             project_root,
@@ -1469,10 +3655,14 @@ pub(crate) fn execute_workflow_node_job(
             metadata_update,
             Some(&artifacts_written),
         )?;
-        display::debug(format!(
-            "workflow node {} (run {}, job {}) finalized succeeded source record",
-            node_id, run_id, job_id
-        ));
+        display::log(
+            display::Subsystem::Scheduler,
+            display::LogLevel::Debug,
+            format!(
+                "workflow node {} (run {}, job {}) finalized succeeded source record",
+                node_id, run_id, job_id
+            ),
+        );
 
         #[cfg(test)]
         pause_succeeded_completion_if_configured();
@@ -1487,25 +3677,37 @@ pub(crate) fn execute_workflow_node_job(
             result.outcome,
             true,
         );
-        display::debug(format!(
-            "workflow node {} (run {}, job {}) applied succeeded routes",
-            node_id, run_id, job_id
-        ));
+        display::log(
+            display::Subsystem::Scheduler,
+            display::LogLevel::Debug,
+            format!(
+                "workflow node {} (run {}, job {}) applied succeeded routes",
+                node_id, run_id, job_id
+            ),
+        );
 
         let scheduler_outcome =
             scheduler_tick_locked_without_ephemeral_cleanup(project_root, jobs_root, &binary)?;
-        display::debug(format!(
-            "workflow node {} (run {}, job {}) advanced scheduler tick under lock (started={}, updated={})",
-            node_id,
-            run_id,
-            job_id,
-            scheduler_outcome.started.len(),
-            scheduler_outcome.updated.len()
-        ));
-        display::debug(format!(
-            "workflow node {} (run {}, job {}) releasing scheduler lock after succeeded completion",
-            node_id, run_id, job_id
-        ));
+        display::log(
+            display::Subsystem::Scheduler,
+            display::LogLevel::Debug,
+            format!(
+                "workflow node {} (run {}, job {}) advanced scheduler tick under lock (started={}, updated={})",
+                node_id,
+                run_id,
+                job_id,
+                scheduler_outcome.started.len(),
+                scheduler_outcome.updated.len()
+            ),
+        );
+        display::log(
+            display::Subsystem::Scheduler,
+            display::LogLevel::Debug,
+            format!(
+                "workflow node {} (run {}, job {}) releasing scheduler lock after succeeded completion",
+                node_id, run_id, job_id
+            ),
+        );
     } else {
         let finalized_record = finalize_job_with_artifacts(
             project_root,
@@ -1559,6 +3761,43 @@ pub fn run_workflow_node_command(
     }
 }
 
+/// Cleans up after a node stopped by SIGINT/SIGTERM and records the cleanup on its job without
+/// finalizing it. The worker then exits, and liveness reconciliation finalizes the job as failed
+/// (so failed-route retries apply) unless `vizier jobs cancel` already marked it cancelled.
+fn record_interrupted_workflow_node(
+    project_root: &Path,
+    jobs_root: &Path,
+    job_id: &str,
+    project_root_was_clean: bool,
+) -> Result<i32, Box<dyn std::error::Error>> {
+    let paths = paths_for(jobs_root, job_id);
+    let record = load_record(&paths)?;
+    let cleanup = attempt_interrupt_cleanup(project_root, &record, project_root_was_clean);
+    let mut summary = format!(
+        "workflow node job {job_id} interrupted by signal; cleanup {}",
+        cleanup.status.label()
+    );
+    if let Some(detail) = cleanup.error.as_deref() {
+        summary.push_str(&format!(" ({detail})"));
+    }
+    display::warn(summary);
+
+    // Reread: `vizier jobs cancel` may have finalized the job while cleanup ran.
+    let mut record = load_record(&paths)?;
+    if job_is_active(record.status) {
+        record.metadata = merge_metadata(
+            record.metadata.take(),
+            Some(JobMetadata {
+                cancel_cleanup_status: Some(cleanup.status),
+                cancel_cleanup_error: cleanup.error,
+                ..JobMetadata::default()
+            }),
+        );
+        persist_record(&paths, &record)?;
+    }
+    Ok(interrupt::INTERRUPTED_EXIT_CODE)
+}
+
 pub(crate) fn finalize_failed_workflow_node_if_active(
     project_root: &Path,
     jobs_root: &Path,
@@ -1,19 +1,62 @@
 pub mod agent;
+pub mod agent_cache;
+pub mod agent_env;
 pub mod agent_prompt;
+pub mod agent_protocol;
+pub mod archive;
+pub mod atomic_file;
 pub mod auditor;
+pub mod changelog;
+pub mod ci_import;
+pub mod command_audit;
 pub mod config;
+pub mod conflict_markers;
+pub mod context_cache;
+pub mod cost;
+pub mod diff_context;
 pub mod display;
+pub mod drift;
 pub mod file_tracking;
+pub mod forge;
+pub mod gate_cache;
+pub mod global_cache;
+pub mod i18n;
+pub mod impact;
+pub mod interrupt;
 pub mod jobs;
+pub mod migrations;
+pub mod narrative_history;
+pub mod narrative_merge;
+pub mod never_send;
+pub mod notifications;
 pub mod observer;
+pub mod overlap;
 pub mod plan;
+pub mod plan_scope;
+pub mod prompt_budget;
+pub mod review_checks;
+pub mod review_ledger;
+pub mod review_report;
+pub mod review_tools;
 pub mod scheduler;
+pub mod search;
+pub mod session_schema;
+pub mod sessions;
+pub mod stats;
+pub mod stop_condition;
+pub mod surfaces;
+pub mod temp_registry;
+pub mod thread_owners;
+pub mod thread_revise;
+pub mod thread_state;
 pub mod tools;
 pub mod tree;
 pub mod vcs;
 pub mod walker;
+pub mod watch;
 pub mod workflow_audit;
 pub mod workflow_template;
+pub mod worktrees;
 
 pub use vizier_kernel::prompts::{
     COMMIT_PROMPT, DOCUMENTATION_PROMPT, IMPLEMENTATION_PLAN_PROMPT, MERGE_CONFLICT_PROMPT,
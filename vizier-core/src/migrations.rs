@@ -0,0 +1,232 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+
+/// Layout version recorded in `.vizier/VERSION`; a missing file reads as version 0.
+pub const VERSION_FILE: &str = ".vizier/VERSION";
+pub const MIGRATION_BACKUP_DIR: &str = crate::temp_registry::MIGRATION_BACKUP_DIR;
+
+/// One ordered `.vizier` layout change. `needed` inspects the tree so a repository that never
+/// had the old layout is left untouched; `touches` lists the paths (relative to the repo root)
+/// that are backed up before `apply` and restored if any step fails.
+pub struct Migration {
+    pub version: u32,
+    pub description: &'static str,
+    pub touches: &'static [&'static str],
+    pub needed: fn(&Path) -> bool,
+    pub apply: fn(&Path) -> io::Result<()>,
+}
+
+pub const MIGRATIONS: &[Migration] = &[Migration {
+    version: 1,
+    description: "move legacy .vizier/.snapshot to .vizier/narrative/snapshot.md",
+    touches: &[".vizier/.snapshot", ".vizier/narrative/snapshot.md"],
+    needed: |root| {
+        root.join(".vizier/.snapshot").is_file()
+            && !root.join(".vizier/narrative/snapshot.md").exists()
+    },
+    apply: |root| {
+        fs::create_dir_all(root.join(".vizier/narrative"))?;
+        fs::rename(
+            root.join(".vizier/.snapshot"),
+            root.join(".vizier/narrative/snapshot.md"),
+        )
+    },
+}];
+
+/// Newest layout this build understands.
+pub fn current_version() -> u32 {
+    MIGRATIONS.last().map_or(0, |migration| migration.version)
+}
+
+/// What [`run_pending`] changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationReport {
+    pub from: u32,
+    pub to: u32,
+    pub applied: Vec<&'static str>,
+    pub backup: PathBuf,
+}
+
+pub fn read_version(project_root: &Path) -> io::Result<u32> {
+    match fs::read_to_string(project_root.join(VERSION_FILE)) {
+        Ok(raw) => raw.trim().parse().map_err(|err| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{VERSION_FILE} is not a layout version ({err})"),
+            )
+        }),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(0),
+        Err(err) => Err(err),
+    }
+}
+
+/// Records the current layout version; `vizier init` stamps new repositories with it.
+pub fn stamp_current(project_root: &Path) -> io::Result<()> {
+    write_version(project_root, current_version())
+}
+
+/// Applies every migration newer than `.vizier/VERSION` that the tree still needs, in order.
+/// The touched paths are backed up under [`MIGRATION_BACKUP_DIR`] first; when a step fails the
+/// backup is restored and the version is left as it was. Returns `None` when nothing needed
+/// migrating, without writing `VERSION`, so an already-current checkout stays clean.
+pub fn run_pending(project_root: &Path) -> io::Result<Option<MigrationReport>> {
+    if !project_root.join(".vizier").is_dir() {
+        return Ok(None);
+    }
+    let from = read_version(project_root)?;
+    let current = current_version();
+    if from > current {
+        return Err(io::Error::other(format!(
+            "{VERSION_FILE} is at layout version {from} but this vizier only understands up to \
+             {current}; upgrade vizier"
+        )));
+    }
+    let pending = MIGRATIONS
+        .iter()
+        .filter(|migration| migration.version > from && (migration.needed)(project_root))
+        .collect::<Vec<_>>();
+    if pending.is_empty() {
+        return Ok(None);
+    }
+
+    let backup = project_root.join(MIGRATION_BACKUP_DIR).join(format!(
+        "{}-v{from}-v{current}",
+        Utc::now().format("%Y%m%dT%H%M%SZ")
+    ));
+    let touched = pending
+        .iter()
+        .flat_map(|migration| migration.touches.iter().copied())
+        .collect::<Vec<_>>();
+    for path in &touched {
+        copy_path(&project_root.join(path), &backup.join(path))?;
+    }
+
+    let mut applied = Vec::new();
+    for migration in &pending {
+        if let Err(err) = (migration.apply)(project_root) {
+            let restored = match restore_backup(project_root, &backup, &touched) {
+                Ok(()) => format!("restored {}", backup.display()),
+                Err(restore_err) => {
+                    format!("restoring {} also failed: {restore_err}", backup.display())
+                }
+            };
+            let message = format!(
+                "layout migration v{} ({}) failed: {err}; {restored}",
+                migration.version, migration.description
+            );
+            return Err(io::Error::other(message));
+        }
+        applied.push(migration.description);
+    }
+    write_version(project_root, current)?;
+
+    Ok(Some(MigrationReport {
+        from,
+        to: current,
+        applied,
+        backup,
+    }))
+}
+
+fn write_version(project_root: &Path, version: u32) -> io::Result<()> {
+    fs::write(project_root.join(VERSION_FILE), format!("{version}\n"))
+}
+
+fn restore_backup(project_root: &Path, backup: &Path, touched: &[&str]) -> io::Result<()> {
+    for path in touched {
+        let live = project_root.join(path);
+        remove_path(&live)?;
+        copy_path(&backup.join(path), &live)?;
+    }
+    Ok(())
+}
+
+// Copies a file or directory tree; a missing source is not an error.
+fn copy_path(source: &Path, dest: &Path) -> io::Result<()> {
+    let metadata = match fs::symlink_metadata(source) {
+        Ok(metadata) => metadata,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err),
+    };
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if metadata.is_dir() {
+        fs::create_dir_all(dest)?;
+        for entry in fs::read_dir(source)? {
+            let entry = entry?;
+            copy_path(&entry.path(), &dest.join(entry.file_name()))?;
+        }
+        Ok(())
+    } else {
+        fs::copy(source, dest).map(|_| ())
+    }
+}
+
+fn remove_path(path: &Path) -> io::Result<()> {
+    match fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.is_dir() => fs::remove_dir_all(path),
+        Ok(_) => fs::remove_file(path),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn run_pending_moves_legacy_snapshot_and_records_version() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let root = dir.path();
+        fs::create_dir_all(root.join(".vizier")).expect("vizier dir");
+        assert_eq!(run_pending(root).expect("no-op"), None);
+        assert!(!root.join(VERSION_FILE).exists());
+
+        fs::write(root.join(".vizier/.snapshot"), "legacy\n").expect("legacy snapshot");
+        let report = run_pending(root).expect("migrate").expect("report");
+        assert_eq!((report.from, report.to), (0, current_version()));
+        assert_eq!(
+            fs::read_to_string(root.join(".vizier/narrative/snapshot.md")).expect("moved"),
+            "legacy\n"
+        );
+        assert!(report.backup.join(".vizier/.snapshot").is_file());
+        assert_eq!(read_version(root).expect("version"), current_version());
+
+        write_version(root, current_version() + 1).expect("future version");
+        let err = run_pending(root).expect_err("newer layout");
+        assert!(err.to_string().contains("upgrade vizier"), "{err}");
+    }
+
+    #[test]
+    fn restore_backup_puts_touched_paths_back() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let root = dir.path();
+        let backup = root.join("backup");
+        fs::create_dir_all(root.join(".vizier/narrative")).expect("narrative dir");
+        fs::write(root.join(".vizier/.snapshot"), "legacy\n").expect("legacy snapshot");
+        copy_path(
+            &root.join(".vizier/.snapshot"),
+            &backup.join(".vizier/.snapshot"),
+        )
+        .expect("backup");
+
+        fs::rename(
+            root.join(".vizier/.snapshot"),
+            root.join(".vizier/narrative/snapshot.md"),
+        )
+        .expect("half-applied move");
+        restore_backup(
+            root,
+            &backup,
+            &[".vizier/.snapshot", ".vizier/narrative/snapshot.md"],
+        )
+        .expect("restore");
+        assert!(root.join(".vizier/.snapshot").is_file());
+        assert!(!root.join(".vizier/narrative/snapshot.md").exists());
+    }
+}
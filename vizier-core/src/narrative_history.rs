@@ -0,0 +1,774 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use git2::{ObjectType, Oid, Repository, Sort, Tree};
+use serde::Serialize;
+
+/// Committed home of the narrative snapshot, glossary, and threads.
+pub const NARRATIVE_DIR: &str = ".vizier/narrative";
+const SNAPSHOT_FILE: &str = "snapshot.md";
+const GLOSSARY_FILE: &str = "glossary.md";
+const THREADS_DIR: &str = "threads";
+
+/// The narrative as committed at one revision, read from git objects rather than the worktree.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct NarrativeAt {
+    pub revision: String,
+    pub commit: String,
+    pub committed_at: String,
+    pub summary: String,
+    pub snapshot: Option<String>,
+    pub glossary: Option<String>,
+    /// Thread name (file stem under `threads/`) to document, sorted by name.
+    pub threads: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NarrativeChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// One narrative document that differs between two revisions, summarized by `##`-level section.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct NarrativeDocChange {
+    /// `snapshot`, `glossary`, or `threads/<name>`.
+    pub document: String,
+    pub kind: NarrativeChangeKind,
+    pub sections_added: Vec<String>,
+    pub sections_removed: Vec<String>,
+    pub sections_changed: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct NarrativeCommit {
+    pub commit: String,
+    pub summary: String,
+    /// Plan and session from the commit's trailers or its `refs/notes/vizier` note.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plan: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session: Option<String>,
+}
+
+/// Narrative-level changelog between two revisions.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct NarrativeChangelog {
+    pub from: String,
+    pub to: String,
+    pub changes: Vec<NarrativeDocChange>,
+    /// Commits reachable from `to` but not `from` that touched the narrative, oldest first.
+    pub commits: Vec<NarrativeCommit>,
+}
+
+pub fn narrative_at(repo_path: &Path, revision: &str) -> Result<NarrativeAt, git2::Error> {
+    let repo = Repository::discover(repo_path)?;
+    let commit = repo.revparse_single(revision)?.peel_to_commit()?;
+    let committed_at = chrono::DateTime::from_timestamp(commit.time().seconds(), 0)
+        .map(|time| time.to_rfc3339())
+        .unwrap_or_default();
+    let mut narrative = NarrativeAt {
+        revision: revision.to_string(),
+        commit: commit.id().to_string(),
+        committed_at,
+        summary: commit.summary().unwrap_or_default().to_string(),
+        ..NarrativeAt::default()
+    };
+    let Some(tree) = narrative_tree(&repo, &commit.tree()?)? else {
+        return Ok(narrative);
+    };
+    narrative.snapshot = read_entry(&repo, &tree, SNAPSHOT_FILE);
+    narrative.glossary = read_entry(&repo, &tree, GLOSSARY_FILE);
+    if let Some(threads) = tree
+        .get_name(THREADS_DIR)
+        .filter(|entry| entry.kind() == Some(ObjectType::Tree))
+        .and_then(|entry| repo.find_tree(entry.id()).ok())
+    {
+        for entry in threads.iter() {
+            let Some(name) = entry.name() else {
+                continue;
+            };
+            let Some(stem) = name.strip_suffix(".md") else {
+                continue;
+            };
+            if let Some(document) = read_entry(&repo, &threads, name) {
+                narrative.threads.insert(stem.to_string(), document);
+            }
+        }
+    }
+    Ok(narrative)
+}
+
+pub fn narrative_changelog(
+    repo_path: &Path,
+    from: &str,
+    to: &str,
+) -> Result<NarrativeChangelog, git2::Error> {
+    let before = narrative_at(repo_path, from)?;
+    let after = narrative_at(repo_path, to)?;
+
+    let mut changes = Vec::new();
+    let documents = |narrative: &NarrativeAt| {
+        let mut docs = BTreeMap::new();
+        if let Some(snapshot) = narrative.snapshot.as_ref() {
+            docs.insert("snapshot".to_string(), snapshot.clone());
+        }
+        if let Some(glossary) = narrative.glossary.as_ref() {
+            docs.insert("glossary".to_string(), glossary.clone());
+        }
+        for (name, document) in &narrative.threads {
+            docs.insert(format!("threads/{name}"), document.clone());
+        }
+        docs
+    };
+    let (old_docs, new_docs) = (documents(&before), documents(&after));
+    let mut names = old_docs.keys().chain(new_docs.keys()).collect::<Vec<_>>();
+    names.sort_by_key(|name| (name.starts_with("threads/"), name.to_string()));
+    names.dedup();
+    for name in names {
+        let old = old_docs.get(name).map(String::as_str);
+        let new = new_docs.get(name).map(String::as_str);
+        let kind = match (old, new) {
+            (None, Some(_)) => NarrativeChangeKind::Added,
+            (Some(_), None) => NarrativeChangeKind::Removed,
+            (Some(old), Some(new)) if old != new => NarrativeChangeKind::Changed,
+            _ => continue,
+        };
+        let old_sections = sections(old.unwrap_or_default());
+        let new_sections = sections(new.unwrap_or_default());
+        let mut change = NarrativeDocChange {
+            document: name.clone(),
+            kind,
+            sections_added: Vec::new(),
+            sections_removed: Vec::new(),
+            sections_changed: Vec::new(),
+        };
+        for (heading, body) in &new_sections {
+            match old_sections
+                .iter()
+                .find(|(existing, _)| existing == heading)
+            {
+                None => change.sections_added.push(heading.clone()),
+                Some((_, old_body)) if old_body != body => {
+                    change.sections_changed.push(heading.clone())
+                }
+                Some(_) => {}
+            }
+        }
+        for (heading, _) in &old_sections {
+            if !new_sections.iter().any(|(existing, _)| existing == heading) {
+                change.sections_removed.push(heading.clone());
+            }
+        }
+        changes.push(change);
+    }
+
+    Ok(NarrativeChangelog {
+        from: before.commit.clone(),
+        to: after.commit.clone(),
+        changes,
+        commits: narrative_commits(repo_path, &before.commit, &after.commit)?,
+    })
+}
+
+impl NarrativeChangelog {
+    /// Markdown changelog: one bullet per changed document with its touched sections, followed
+    /// by the commits that changed the narrative.
+    pub fn render(&self) -> String {
+        let short = |oid: &str| oid.chars().take(7).collect::<String>();
+        let mut out = format!(
+            "# Narrative changes {}..{}\n",
+            short(&self.from),
+            short(&self.to)
+        );
+        if self.changes.is_empty() {
+            out.push_str("\nNo narrative changes.\n");
+            return out;
+        }
+        out.push('\n');
+        for change in &self.changes {
+            let verb = match change.kind {
+                NarrativeChangeKind::Added => "added",
+                NarrativeChangeKind::Removed => "removed",
+                NarrativeChangeKind::Changed => "changed",
+            };
+            out.push_str(&format!("- `{}` {verb}\n", change.document));
+            if change.kind != NarrativeChangeKind::Changed {
+                continue;
+            }
+            for (label, headings) in [
+                ("added", &change.sections_added),
+                ("changed", &change.sections_changed),
+                ("removed", &change.sections_removed),
+            ] {
+                for heading in headings {
+                    out.push_str(&format!("  - {label}: {heading}\n"));
+                }
+            }
+        }
+        if !self.commits.is_empty() {
+            out.push_str("\n## Commits\n\n");
+            for commit in &self.commits {
+                let provenance = [
+                    commit.plan.as_ref().map(|plan| format!("plan {plan}")),
+                    commit
+                        .session
+                        .as_ref()
+                        .map(|session| format!("session {session}")),
+                ]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>();
+                if provenance.is_empty() {
+                    out.push_str(&format!("- {} {}\n", short(&commit.commit), commit.summary));
+                } else {
+                    out.push_str(&format!(
+                        "- {} {} ({})\n",
+                        short(&commit.commit),
+                        commit.summary,
+                        provenance.join(", ")
+                    ));
+                }
+            }
+        }
+        out
+    }
+}
+
+/// A plan Vizier merged, reconstructed from its archive and/or its merge commit.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct LandedPlan {
+    pub slug: String,
+    pub title: String,
+    pub target: Option<String>,
+    /// Merge commit on the current history, when one carries the plan.
+    pub commit: Option<String>,
+    pub landed_at: String,
+    /// First paragraph of the plan's `## Operator Spec` (or its first section).
+    pub intent: String,
+    /// `Changes:` line of the archived merge summary.
+    pub changes: Option<String>,
+}
+
+/// One recorded agent session: what ran, on which plan, and what the agent last said.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct LedgerActivity {
+    pub session: String,
+    pub at: String,
+    pub scope: Option<String>,
+    pub plan: Option<String>,
+    pub summary: String,
+    /// Distinct operation kinds the session recorded (`gate`, `stop_condition`, ...).
+    pub operations: Vec<String>,
+}
+
+/// Best-effort snapshot assembled from Vizier's own records instead of an agent pass.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct RebuiltSnapshot {
+    /// Newest first.
+    pub plans: Vec<LandedPlan>,
+    /// Newest first, capped by the caller's limit.
+    pub activity: Vec<LedgerActivity>,
+    pub document: String,
+}
+
+/// Rebuilds a snapshot from archived plans (`.vizier/archive`), merge commits on `HEAD` that
+/// carry a plan document or `Vizier-Plan` trailer, and the operations recorded in session logs.
+pub fn rebuild_snapshot_from_ledger(
+    project_root: &Path,
+    activity_limit: usize,
+) -> Result<RebuiltSnapshot, Box<dyn std::error::Error>> {
+    let mut plans = BTreeMap::<String, LandedPlan>::new();
+    for plan in merged_plans_on_head(project_root)? {
+        plans.entry(plan.slug.clone()).or_insert(plan);
+    }
+    for metadata in crate::archive::list_archives(project_root)? {
+        let Some(archive) = crate::archive::load_archive(project_root, &metadata.slug)? else {
+            continue;
+        };
+        let plan = plans
+            .entry(metadata.slug.clone())
+            .or_insert_with(|| LandedPlan {
+                slug: metadata.slug.clone(),
+                landed_at: metadata.archived_at.clone(),
+                ..LandedPlan::default()
+            });
+        plan.title = metadata.title.clone();
+        plan.target = metadata.target.clone().or(plan.target.take());
+        if plan.intent.is_empty() {
+            plan.intent = plan_intent(&archive.plan);
+        }
+        plan.changes = archive
+            .summary
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("- Changes:"))
+            .map(|changes| changes.trim().to_string());
+    }
+    let mut plans = plans.into_values().collect::<Vec<_>>();
+    plans.sort_by(|left, right| {
+        right
+            .landed_at
+            .cmp(&left.landed_at)
+            .then_with(|| left.slug.cmp(&right.slug))
+    });
+
+    let activity = ledger_activity(project_root, activity_limit)?;
+    let document = render_rebuilt_snapshot(&plans, &activity);
+    Ok(RebuiltSnapshot {
+        plans,
+        activity,
+        document,
+    })
+}
+
+fn merged_plans_on_head(project_root: &Path) -> Result<Vec<LandedPlan>, git2::Error> {
+    let repo = Repository::discover(project_root)?;
+    if repo.head().is_err() {
+        return Ok(Vec::new());
+    }
+    let mut walk = repo.revwalk()?;
+    walk.set_sorting(Sort::TIME)?;
+    walk.push_head()?;
+
+    let mut plans = Vec::new();
+    for oid in walk {
+        let commit = repo.find_commit(oid?)?;
+        let message = commit.message().unwrap_or_default();
+        let body = message
+            .split_once("\n\n")
+            .map(|(_, body)| body.trim_start())
+            .unwrap_or_default();
+        let fields = crate::plan::front_matter_fields(body);
+        let trailer = message
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("Vizier-Plan:"))
+            .map(|slug| slug.trim().to_string())
+            .or_else(|| crate::vcs::commit_provenance_value(&repo, &commit, "Vizier-Plan"));
+        let Some(slug) = fields
+            .get("plan")
+            .cloned()
+            .or(trailer)
+            .filter(|slug| !slug.is_empty())
+        else {
+            continue;
+        };
+        let title = crate::plan::plan_title(body)
+            .unwrap_or_else(|| commit.summary().unwrap_or(&slug).to_string());
+        plans.push(LandedPlan {
+            title,
+            target: None,
+            commit: Some(commit.id().to_string()),
+            landed_at: chrono::DateTime::from_timestamp(commit.time().seconds(), 0)
+                .map(|time| time.to_rfc3339())
+                .unwrap_or_default(),
+            intent: plan_intent(body),
+            changes: None,
+            slug,
+        });
+    }
+    Ok(plans)
+}
+
+fn plan_intent(plan_document: &str) -> String {
+    let sections = sections(plan_document);
+    let body = sections
+        .iter()
+        .find(|(heading, _)| heading.eq_ignore_ascii_case("Operator Spec"))
+        .or_else(|| {
+            sections
+                .iter()
+                .find(|(heading, body)| heading != "(preamble)" && !body.is_empty())
+        })
+        .map(|(_, body)| body.as_str())
+        .unwrap_or_default();
+    body.split("\n\n")
+        .next()
+        .unwrap_or_default()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn ledger_activity(
+    project_root: &Path,
+    limit: usize,
+) -> Result<Vec<LedgerActivity>, Box<dyn std::error::Error>> {
+    let mut activity = Vec::new();
+    for entry in crate::sessions::list_sessions(project_root)? {
+        if activity.len() >= limit {
+            break;
+        }
+        // Corrupt logs are `vizier sessions fsck`'s business; skip them here.
+        let Ok(Some(log)) = crate::sessions::read_session_json(&entry, "session.json") else {
+            continue;
+        };
+        let text = |pointer: &str| {
+            log.pointer(pointer)
+                .and_then(serde_json::Value::as_str)
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+        let Some(summary) = text("/outcome/summary") else {
+            continue;
+        };
+        let mut operations = log
+            .get("operations")
+            .and_then(serde_json::Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|operation| operation.get("kind").and_then(serde_json::Value::as_str))
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        operations.sort();
+        operations.dedup();
+        activity.push(LedgerActivity {
+            session: entry.id.clone(),
+            at: text("/updated_at").unwrap_or_else(|| entry.modified.to_rfc3339()),
+            scope: text("/model/scope"),
+            plan: text("/repo/branch").and_then(|branch| crate::plan::slug_from_branch(&branch)),
+            summary: summary.split_whitespace().collect::<Vec<_>>().join(" "),
+            operations,
+        });
+    }
+    Ok(activity)
+}
+
+fn render_rebuilt_snapshot(plans: &[LandedPlan], activity: &[LedgerActivity]) -> String {
+    let date = |at: &str| at.get(..10).unwrap_or(at).to_string();
+    let clip = |text: &str| {
+        const LIMIT: usize = 280;
+        if text.chars().count() <= LIMIT {
+            text.to_string()
+        } else {
+            format!("{}…", text.chars().take(LIMIT).collect::<String>())
+        }
+    };
+    let mut out = String::from(
+        "# Snapshot\n\n_Rebuilt by `vizier snapshot rebuild --from-ledger` from merged plans and session logs. It records what Vizier landed, not why the code looks the way it does; review and extend it before relying on it._\n",
+    );
+
+    out.push_str("\n## Landed Work\n\n");
+    if plans.is_empty() {
+        out.push_str("No merged plans recorded.\n");
+    }
+    for plan in plans {
+        let mut line = format!("- **{}** (`{}`", plan.title, plan.slug);
+        if !plan.landed_at.is_empty() {
+            line.push_str(&format!(", {}", date(&plan.landed_at)));
+        }
+        if let Some(target) = plan.target.as_deref() {
+            line.push_str(&format!(", into `{target}`"));
+        }
+        line.push(')');
+        if !plan.intent.is_empty() {
+            line.push_str(&format!(": {}", clip(&plan.intent)));
+        }
+        out.push_str(&line);
+        out.push('\n');
+        if let Some(changes) = plan.changes.as_deref() {
+            out.push_str(&format!("  - Changes: {changes}\n"));
+        }
+    }
+
+    if !activity.is_empty() {
+        out.push_str("\n## Recent Agent Activity\n\n");
+        for entry in activity {
+            let mut context = vec![date(&entry.at)];
+            context.extend(entry.scope.clone());
+            context.extend(entry.plan.as_ref().map(|plan| format!("`{plan}`")));
+            let mut line = format!("- {}: {}", context.join(", "), clip(&entry.summary));
+            if !entry.operations.is_empty() {
+                line.push_str(&format!(" (recorded: {})", entry.operations.join(", ")));
+            }
+            out.push_str(&line);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// Splits a markdown document into `(heading, body)` pairs at `#`/`##` headings; text before the
+/// first heading is keyed `(preamble)`. Deeper headings stay inside their parent section.
+fn sections(document: &str) -> Vec<(String, String)> {
+    let mut sections: Vec<(String, String)> = Vec::new();
+    let mut in_fence = false;
+    for line in document.lines() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+        }
+        let heading = (!in_fence)
+            .then(|| line.strip_prefix("## ").or_else(|| line.strip_prefix("# ")))
+            .flatten();
+        match heading {
+            Some(heading) => sections.push((heading.trim().to_string(), String::new())),
+            None => {
+                if sections.is_empty() {
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    sections.push(("(preamble)".to_string(), String::new()));
+                }
+                let body = &mut sections.last_mut().expect("section").1;
+                body.push_str(line.trim_end());
+                body.push('\n');
+            }
+        }
+    }
+    for (_, body) in &mut sections {
+        let trimmed = body.trim().to_string();
+        *body = trimmed;
+    }
+    sections
+}
+
+fn narrative_commits(
+    repo_path: &Path,
+    from: &str,
+    to: &str,
+) -> Result<Vec<NarrativeCommit>, git2::Error> {
+    let repo = Repository::discover(repo_path)?;
+    let mut walk = repo.revwalk()?;
+    walk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+    walk.push(Oid::from_str(to)?)?;
+    walk.hide(Oid::from_str(from)?)?;
+
+    let mut commits = Vec::new();
+    for oid in walk {
+        let commit = repo.find_commit(oid?)?;
+        let tree_id = |tree: &Tree<'_>| {
+            tree.get_path(Path::new(NARRATIVE_DIR))
+                .ok()
+                .map(|entry| entry.id())
+        };
+        let current = tree_id(&commit.tree()?);
+        let touched = if commit.parent_count() == 0 {
+            current.is_some()
+        } else {
+            commit
+                .parents()
+                .all(|parent| parent.tree().ok().and_then(|tree| tree_id(&tree)) != current)
+        };
+        if touched {
+            commits.push(NarrativeCommit {
+                commit: commit.id().to_string(),
+                summary: commit.summary().unwrap_or_default().to_string(),
+                plan: crate::vcs::commit_provenance_value(&repo, &commit, "Vizier-Plan"),
+                session: commit_session(&repo, &commit),
+            });
+        }
+    }
+    Ok(commits)
+}
+
+/// Session id under the configured `[commits.meta.labels] session_id`, else `Vizier-Session`.
+fn commit_session(repo: &Repository, commit: &git2::Commit<'_>) -> Option<String> {
+    let label = crate::config::get_config().commits.meta.labels.session_id;
+    crate::vcs::commit_provenance_value(repo, commit, &label)
+        .or_else(|| crate::vcs::commit_provenance_value(repo, commit, "Vizier-Session"))
+}
+
+fn narrative_tree<'repo>(
+    repo: &'repo Repository,
+    root: &Tree<'repo>,
+) -> Result<Option<Tree<'repo>>, git2::Error> {
+    match root.get_path(Path::new(NARRATIVE_DIR)) {
+        Ok(entry) if entry.kind() == Some(ObjectType::Tree) => {
+            Ok(Some(repo.find_tree(entry.id())?))
+        }
+        Ok(_) => Ok(None),
+        Err(err) if err.code() == git2::ErrorCode::NotFound => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+fn read_entry(repo: &Repository, tree: &Tree<'_>, name: &str) -> Option<String> {
+    let entry = tree.get_name(name)?;
+    let blob = repo.find_blob(entry.id()).ok()?;
+    Some(String::from_utf8_lossy(blob.content()).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn commit_files(repo: &Repository, message: &str, files: &[(&str, Option<&str>)]) -> Oid {
+        let root = repo.workdir().expect("workdir");
+        let mut index = repo.index().expect("index");
+        for (path, contents) in files {
+            let abs = root.join(path);
+            match contents {
+                Some(contents) => {
+                    fs::create_dir_all(abs.parent().expect("parent")).expect("dirs");
+                    fs::write(&abs, contents).expect("write");
+                    index.add_path(Path::new(path)).expect("add");
+                }
+                None => {
+                    fs::remove_file(&abs).expect("remove");
+                    index
+                        .remove_path(Path::new(path))
+                        .expect("remove from index");
+                }
+            }
+        }
+        index.write().expect("write index");
+        let tree = repo
+            .find_tree(index.write_tree().expect("tree"))
+            .expect("find tree");
+        let sig = git2::Signature::now("vizier", "vizier@example.com").expect("sig");
+        let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+        let parents = parent.iter().collect::<Vec<_>>();
+        repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+            .expect("commit")
+    }
+
+    #[test]
+    fn narrative_is_read_at_revisions_and_diffed_by_section() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let repo = Repository::init(dir.path()).expect("init");
+        let first = commit_files(
+            &repo,
+            "seed narrative",
+            &[
+                (
+                    ".vizier/narrative/snapshot.md",
+                    Some("# Snapshot\n\n## Auth\nTokens last a day.\n\n## Billing\nMonthly.\n"),
+                ),
+                (".vizier/narrative/threads/auth.md", Some("# Auth\n")),
+                (".vizier/narrative/threads/legacy.md", Some("# Legacy\n")),
+            ],
+        );
+        commit_files(
+            &repo,
+            "unrelated",
+            &[("src/lib.rs", Some("fn main() {}\n"))],
+        );
+        let last = commit_files(
+            &repo,
+            "rotate tokens",
+            &[
+                (
+                    ".vizier/narrative/snapshot.md",
+                    Some("# Snapshot\n\n## Auth\nTokens rotate nightly.\n\n## Search\nBM25.\n"),
+                ),
+                (".vizier/narrative/threads/legacy.md", None),
+                (".vizier/narrative/threads/search.md", Some("# Search\n")),
+            ],
+        );
+
+        let at_first = narrative_at(dir.path(), &first.to_string()).expect("narrative at first");
+        assert!(
+            at_first
+                .snapshot
+                .as_deref()
+                .is_some_and(|snapshot| snapshot.contains("Tokens last a day."))
+        );
+        assert_eq!(
+            at_first.threads.keys().collect::<Vec<_>>(),
+            vec!["auth", "legacy"]
+        );
+        assert_eq!(at_first.glossary, None);
+
+        let changelog =
+            narrative_changelog(dir.path(), &first.to_string(), &last.to_string()).expect("diff");
+        assert_eq!(
+            changelog
+                .changes
+                .iter()
+                .map(|change| (change.document.as_str(), change.kind))
+                .collect::<Vec<_>>(),
+            vec![
+                ("snapshot", NarrativeChangeKind::Changed),
+                ("threads/legacy", NarrativeChangeKind::Removed),
+                ("threads/search", NarrativeChangeKind::Added),
+            ]
+        );
+        let snapshot = &changelog.changes[0];
+        assert_eq!(snapshot.sections_added, vec!["Search"]);
+        assert_eq!(snapshot.sections_changed, vec!["Auth"]);
+        assert_eq!(snapshot.sections_removed, vec!["Billing"]);
+        assert_eq!(
+            changelog
+                .commits
+                .iter()
+                .map(|commit| commit.summary.as_str())
+                .collect::<Vec<_>>(),
+            vec!["rotate tokens"]
+        );
+        let rendered = changelog.render();
+        assert!(rendered.contains("- `snapshot` changed\n  - added: Search\n"));
+        assert!(rendered.contains("- `threads/legacy` removed\n"));
+    }
+
+    #[test]
+    fn rebuild_from_ledger_recovers_landed_plans_from_merge_commits() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let repo = Repository::init(dir.path()).expect("init");
+        commit_files(&repo, "seed", &[("src/lib.rs", Some("fn main() {}\n"))]);
+        commit_files(
+            &repo,
+            "feat: merge plan alpha\n\n---\nplan: alpha\n---\n\n# Alpha\n\n## Operator Spec\nDo X.\n",
+            &[("src/alpha.rs", Some("// alpha\n"))],
+        );
+
+        let rebuilt = rebuild_snapshot_from_ledger(dir.path(), 5).expect("rebuild");
+        assert_eq!(
+            rebuilt
+                .plans
+                .iter()
+                .map(|plan| (plan.slug.as_str(), plan.intent.as_str()))
+                .collect::<Vec<_>>(),
+            vec![("alpha", "Do X.")]
+        );
+        assert!(rebuilt.activity.is_empty());
+        assert!(rebuilt.document.starts_with("# Snapshot"));
+        assert!(rebuilt.document.contains("## Landed Work"));
+    }
+
+    #[test]
+    fn notes_supply_plan_and_session_for_clean_commit_messages() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let repo = Repository::init(dir.path()).expect("init");
+        let seed = commit_files(&repo, "seed", &[("src/lib.rs", Some("fn main() {}\n"))]);
+        let merge = commit_files(
+            &repo,
+            "feat: merge plan beta",
+            &[(
+                ".vizier/narrative/snapshot.md",
+                Some("# Snapshot\n\n## Beta\nb\n"),
+            )],
+        );
+        crate::vcs::write_commit_note_in(
+            dir.path(),
+            merge,
+            &[
+                "Vizier-Session: session-9".to_string(),
+                "Vizier-Plan: beta".to_string(),
+            ],
+        )
+        .expect("note");
+
+        let rebuilt = rebuild_snapshot_from_ledger(dir.path(), 5).expect("rebuild");
+        assert_eq!(
+            rebuilt
+                .plans
+                .iter()
+                .map(|plan| plan.slug.as_str())
+                .collect::<Vec<_>>(),
+            vec!["beta"]
+        );
+
+        let changelog =
+            narrative_changelog(dir.path(), &seed.to_string(), &merge.to_string()).expect("diff");
+        assert_eq!(changelog.commits[0].plan.as_deref(), Some("beta"));
+        assert_eq!(changelog.commits[0].session.as_deref(), Some("session-9"));
+        assert!(
+            changelog
+                .render()
+                .contains("feat: merge plan beta (plan beta, session session-9)")
+        );
+    }
+}
@@ -0,0 +1,342 @@
+//! Three-way merge for narrative documents (`.vizier/narrative/**/*.md`) at the section level.
+//!
+//! Two sessions that both touch `snapshot.md` usually edit different sections (or different
+//! thread documents), which a line-level merge still reports as a conflict when the edits sit
+//! close together. Merging by `#`/`##` section keeps every non-overlapping edit and leaves
+//! conflict markers only around sections both sides changed differently, so the merge-conflict
+//! agent sees just the genuinely overlapping edits.
+
+use std::fs;
+use std::path::Path;
+
+use git2::{Index, IndexEntry, Repository};
+
+use crate::conflict_markers::ConflictHunk;
+use crate::narrative_history::NARRATIVE_DIR;
+
+/// Stage bits of an index entry's flags (`GIT_INDEX_ENTRY_STAGEMASK`).
+const INDEX_STAGE_MASK: u16 = 0x3000;
+
+/// Whether `path` (repo-relative, `/`-separated) is a narrative document this merge handles.
+pub fn is_narrative_path(path: &str) -> bool {
+    path.strip_prefix(NARRATIVE_DIR)
+        .is_some_and(|rest| rest.starts_with('/'))
+        && path.ends_with(".md")
+}
+
+/// Result of merging one narrative document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocumentMerge {
+    pub text: String,
+    /// Headings of sections both sides changed differently; these carry conflict markers.
+    pub overlapping: Vec<String>,
+}
+
+impl DocumentMerge {
+    pub fn is_clean(&self) -> bool {
+        self.overlapping.is_empty()
+    }
+}
+
+/// Narrative conflicts settled by [`resolve_index_conflicts`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NarrativeResolution {
+    /// Documents merged cleanly and staged, with their merged text.
+    pub merged: Vec<(String, String)>,
+    /// Documents left conflicted, with their section-marked text.
+    pub overlapping: Vec<(String, String)>,
+}
+
+impl NarrativeResolution {
+    pub fn merged_paths(&self) -> Vec<String> {
+        self.merged.iter().map(|(path, _)| path.clone()).collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Section {
+    /// Heading line (`## Title`), or empty for text before the first heading.
+    heading: String,
+    /// Occurrence of this heading, so repeated headings stay distinct.
+    occurrence: usize,
+    text: String,
+}
+
+impl Section {
+    fn key(&self) -> (&str, usize) {
+        (self.heading.as_str(), self.occurrence)
+    }
+}
+
+/// Splits `document` at `#`/`##` headings outside code fences. Concatenating the sections'
+/// text reproduces the document exactly.
+fn split_sections(document: &str) -> Vec<Section> {
+    let mut sections: Vec<Section> = Vec::new();
+    let mut in_fence = false;
+    for line in document.split_inclusive('\n') {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+        }
+        let is_heading = !in_fence && (line.starts_with("# ") || line.starts_with("## "));
+        if is_heading || sections.is_empty() {
+            let heading = if is_heading {
+                line.trim_end().to_string()
+            } else {
+                String::new()
+            };
+            let occurrence = sections
+                .iter()
+                .filter(|section| section.heading == heading)
+                .count();
+            sections.push(Section {
+                heading,
+                occurrence,
+                text: String::new(),
+            });
+        }
+        sections.last_mut().expect("section").text.push_str(line);
+    }
+    sections
+}
+
+fn find<'a>(sections: &'a [Section], key: (&str, usize)) -> Option<&'a str> {
+    sections
+        .iter()
+        .find(|section| section.key() == key)
+        .map(|section| section.text.as_str())
+}
+
+/// Sections that differ only in trailing whitespace count as unchanged.
+fn same(left: Option<&str>, right: Option<&str>) -> bool {
+    left.map(str::trim_end) == right.map(str::trim_end)
+}
+
+fn with_newline(text: &str) -> String {
+    if text.is_empty() || text.ends_with('\n') {
+        text.to_string()
+    } else {
+        format!("{text}\n")
+    }
+}
+
+/// Merges `ours` and `theirs` against `base` section by section. A section changed on one side
+/// only takes that side's version (including deletion); sections added on one side are placed
+/// after the section that precedes them there.
+pub fn merge_document(
+    base: Option<&str>,
+    ours: &str,
+    theirs: &str,
+    ours_label: &str,
+    theirs_label: &str,
+) -> DocumentMerge {
+    let base = split_sections(base.unwrap_or_default());
+    let ours = split_sections(ours);
+    let theirs = split_sections(theirs);
+
+    // Our section order, with sections only they have slotted in after their predecessor.
+    let mut order: Vec<((String, usize), bool)> = ours
+        .iter()
+        .map(|section| ((section.heading.clone(), section.occurrence), false))
+        .collect();
+    for (idx, section) in theirs.iter().enumerate() {
+        if find(&ours, section.key()).is_some() || find(&base, section.key()).is_some() {
+            continue;
+        }
+        let position = theirs[..idx]
+            .iter()
+            .rev()
+            .find_map(|prev| {
+                order
+                    .iter()
+                    .position(|((heading, occurrence), _)| {
+                        (heading.as_str(), *occurrence) == prev.key()
+                    })
+                    .map(|pos| pos + 1)
+            })
+            .unwrap_or_else(|| usize::from(order.first().is_some_and(|(key, _)| key.0.is_empty())));
+        order.insert(
+            position,
+            ((section.heading.clone(), section.occurrence), true),
+        );
+    }
+    // Sections they kept but we deleted still need a decision.
+    for section in &theirs {
+        let known = order
+            .iter()
+            .any(|((heading, occurrence), _)| (heading.as_str(), *occurrence) == section.key());
+        if !known {
+            order.push(((section.heading.clone(), section.occurrence), false));
+        }
+    }
+
+    let mut text = String::new();
+    let mut overlapping = Vec::new();
+    let mut after_inserted = false;
+    for ((heading, occurrence), inserted) in &order {
+        let key = (heading.as_str(), *occurrence);
+        let b = find(&base, key);
+        let o = find(&ours, key);
+        let t = find(&theirs, key);
+        let chosen = if same(o, t) || same(t, b) {
+            o.map(with_newline)
+        } else if same(o, b) {
+            t.map(with_newline)
+        } else {
+            overlapping.push(if heading.is_empty() {
+                "(preamble)".to_string()
+            } else {
+                heading.trim_start_matches('#').trim().to_string()
+            });
+            let hunk = ConflictHunk {
+                ours_label: ours_label.to_string(),
+                theirs_label: theirs_label.to_string(),
+                ours: with_newline(o.unwrap_or_default()),
+                base: b.map(with_newline),
+                theirs: with_newline(t.unwrap_or_default()),
+            };
+            Some(hunk.render_markers())
+        };
+        if let Some(chosen) = chosen {
+            // Keep a blank line on either side of a section spliced in from their side.
+            if (*inserted || after_inserted) && !text.is_empty() && !text.ends_with("\n\n") {
+                text.push('\n');
+            }
+            text.push_str(&chosen);
+            after_inserted = *inserted;
+        }
+    }
+
+    DocumentMerge { text, overlapping }
+}
+
+/// Settles conflicted narrative documents in `index` with [`merge_document`]. Documents that
+/// merge cleanly are staged at stage 0; the rest keep their conflict entries. Conflicts where a
+/// side deleted the whole document, or that are not UTF-8, are left for the usual resolution.
+pub fn resolve_index_conflicts(
+    repo: &Repository,
+    index: &mut Index,
+    ours_label: &str,
+    theirs_label: &str,
+) -> Result<NarrativeResolution, git2::Error> {
+    let mut candidates = Vec::new();
+    for conflict in index.conflicts()?.flatten() {
+        let (Some(our), Some(their)) = (conflict.our, conflict.their) else {
+            continue;
+        };
+        let path = String::from_utf8_lossy(&our.path).to_string();
+        if is_narrative_path(&path) {
+            candidates.push((path, conflict.ancestor, our, their));
+        }
+    }
+
+    let mut resolution = NarrativeResolution::default();
+    for (path, ancestor, our, their) in candidates {
+        let read = |entry: &IndexEntry| -> Result<Option<String>, git2::Error> {
+            let blob = repo.find_blob(entry.id)?;
+            Ok(std::str::from_utf8(blob.content()).ok().map(str::to_string))
+        };
+        let base = match ancestor.as_ref() {
+            Some(entry) => match read(entry)? {
+                Some(text) => Some(text),
+                None => continue,
+            },
+            None => None,
+        };
+        let (Some(ours), Some(theirs)) = (read(&our)?, read(&their)?) else {
+            continue;
+        };
+
+        let merged = merge_document(base.as_deref(), &ours, &theirs, ours_label, theirs_label);
+        if !merged.is_clean() {
+            resolution.overlapping.push((path, merged.text));
+            continue;
+        }
+        let blob = repo.blob(merged.text.as_bytes())?;
+        let mut entry = our;
+        entry.id = blob;
+        entry.file_size = merged.text.len() as u32;
+        entry.flags &= !INDEX_STAGE_MASK;
+        index.conflict_remove(Path::new(&path))?;
+        index.add(&entry)?;
+        resolution.merged.push((path, merged.text));
+    }
+    Ok(resolution)
+}
+
+/// Writes a resolution into the repository's worktree: merged documents are written and staged,
+/// overlapping ones get their section-marked text in place of git's line-level markers.
+pub fn apply_to_worktree(
+    repo: &Repository,
+    index: &mut Index,
+    resolution: &NarrativeResolution,
+) -> Result<(), git2::Error> {
+    let Some(workdir) = repo.workdir() else {
+        return Ok(());
+    };
+    let write = |path: &str, text: &str| {
+        fs::write(workdir.join(path), text)
+            .map_err(|err| git2::Error::from_str(&format!("failed to write {path}: {err}")))
+    };
+    for (path, text) in &resolution.merged {
+        write(path, text)?;
+        index.add_path(Path::new(path))?;
+    }
+    for (path, text) in &resolution.overlapping {
+        write(path, text)?;
+    }
+    index.write()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str =
+        "# Snapshot\n\n## Jobs\nScheduler runs jobs.\n\n## Review\nReview is manual.\n";
+
+    #[test]
+    fn edits_to_different_sections_merge_cleanly() {
+        let ours = BASE.replace("Scheduler runs jobs.", "Scheduler runs jobs in order.");
+        let theirs = BASE.replace("Review is manual.", "Review runs checks first.");
+        let merged = merge_document(Some(BASE), &ours, &theirs, "HEAD", "draft/alpha");
+        assert!(merged.is_clean(), "{merged:?}");
+        assert_eq!(
+            merged.text,
+            "# Snapshot\n\n## Jobs\nScheduler runs jobs in order.\n\n## Review\nReview runs checks first.\n"
+        );
+    }
+
+    #[test]
+    fn added_and_deleted_sections_follow_the_side_that_changed() {
+        let ours = BASE.replace("## Review\nReview is manual.\n", "");
+        let theirs = BASE.replace("## Review\n", "## Merge\nMerges are gated.\n\n## Review\n");
+        let merged = merge_document(Some(BASE), ours.trim_end(), &theirs, "HEAD", "draft/alpha");
+        assert!(merged.is_clean(), "{merged:?}");
+        assert_eq!(
+            merged.text,
+            "# Snapshot\n\n## Jobs\nScheduler runs jobs.\n\n## Merge\nMerges are gated.\n\n"
+        );
+    }
+
+    #[test]
+    fn overlapping_section_edits_keep_markers_around_that_section_only() {
+        let ours = BASE
+            .replace("Scheduler runs jobs.", "Scheduler runs jobs serially.")
+            .replace("Review is manual.", "Review is automated.");
+        let theirs = BASE.replace("Scheduler runs jobs.", "Scheduler runs jobs in parallel.");
+        let merged = merge_document(Some(BASE), &ours, &theirs, "HEAD", "draft/alpha");
+        assert_eq!(merged.overlapping, vec!["Jobs".to_string()]);
+        assert!(merged.text.contains(
+            "<<<<<<< HEAD\n## Jobs\nScheduler runs jobs serially.\n\n||||||| base\n## Jobs\nScheduler runs jobs.\n\n=======\n## Jobs\nScheduler runs jobs in parallel.\n\n>>>>>>> draft/alpha\n"
+        ));
+        assert!(merged.text.ends_with("## Review\nReview is automated.\n"));
+    }
+
+    #[test]
+    fn narrative_paths_are_markdown_under_the_narrative_dir() {
+        assert!(is_narrative_path(".vizier/narrative/snapshot.md"));
+        assert!(is_narrative_path(".vizier/narrative/threads/jobs.md"));
+        assert!(!is_narrative_path(".vizier/narrative-notes.md"));
+        assert!(!is_narrative_path(".vizier/config.toml"));
+    }
+}
@@ -0,0 +1,223 @@
+use std::collections::BTreeSet;
+use std::path::{Component, Path, PathBuf};
+
+use git2::{Error, Oid, Repository};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+
+use crate::config;
+
+/// Audit operation kind recorded whenever [`NeverSend`] keeps a path out of a prompt.
+pub const WITHHELD_OPERATION: &str = "context_withheld";
+
+/// Stand-in text for a withheld file that a prompt asked for by name.
+pub const WITHHELD_PLACEHOLDER: &str = "(withheld: path matches [context] never_send)";
+
+/// `[context] never_send` compiled against a repository root. Every surface that copies
+/// repository contents into a prompt — diff placeholders, attached documents, `{{file:...}}`
+/// placeholders, the file walker — asks this before anything reaches an agent.
+#[derive(Clone, Debug)]
+pub struct NeverSend {
+    root: PathBuf,
+    canonical_root: Option<PathBuf>,
+    matcher: Option<Gitignore>,
+}
+
+impl NeverSend {
+    /// Compiles gitignore-style `patterns` relative to `root`. Patterns are validated when the
+    /// config loads, so a pattern that fails to compile here is skipped.
+    pub fn new(root: &Path, patterns: &[String]) -> Self {
+        let matcher = (!patterns.is_empty())
+            .then(|| {
+                let mut builder = GitignoreBuilder::new(root);
+                for pattern in patterns {
+                    let _ = builder.add_line(None, pattern);
+                }
+                builder.build().ok()
+            })
+            .flatten()
+            .filter(|matcher| !matcher.is_empty());
+        Self {
+            root: root.to_path_buf(),
+            canonical_root: root.canonicalize().ok(),
+            matcher,
+        }
+    }
+
+    /// The active config's `[context] never_send` rooted at `root`.
+    pub fn from_config(root: &Path) -> Self {
+        Self::new(root, &config::get_config().context.never_send)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.matcher.is_none()
+    }
+
+    /// Whether `path` (repo-relative, or absolute under the root) or any parent directory
+    /// matches a pattern. Paths outside the root are never withheld.
+    pub fn withholds(&self, path: &Path) -> bool {
+        let Some(matcher) = self.matcher.as_ref() else {
+            return false;
+        };
+        let relative = if path.is_absolute() {
+            let under = |root: &PathBuf| path.strip_prefix(root).ok();
+            match under(&self.root).or_else(|| self.canonical_root.as_ref().and_then(under)) {
+                Some(relative) => relative,
+                None => return false,
+            }
+        } else {
+            path
+        };
+        let relative = relative
+            .components()
+            .filter(|component| !matches!(component, Component::CurDir))
+            .collect::<PathBuf>();
+        if relative.as_os_str().is_empty() || relative.has_root() {
+            return false;
+        }
+        matcher
+            .matched_path_or_any_parents(&relative, false)
+            .is_ignore()
+    }
+
+    /// Drops every `diff --git` section whose old or new path is withheld, returning the rest
+    /// of the diff and the withheld paths.
+    pub fn filter_diff(&self, diff: &str) -> (String, Vec<String>) {
+        if self.is_empty() {
+            return (diff.to_string(), Vec::new());
+        }
+        let mut kept = String::with_capacity(diff.len());
+        let mut withheld = BTreeSet::new();
+        let mut skipping = false;
+        for line in diff.split_inclusive('\n') {
+            if let Some(header) = line.strip_prefix("diff --git ") {
+                let paths = header_paths(header.trim_end());
+                skipping = paths.iter().any(|path| self.withholds(Path::new(path)));
+                if skipping && let Some(path) = paths.last() {
+                    withheld.insert(path.clone());
+                }
+            }
+            if !skipping {
+                kept.push_str(line);
+            }
+        }
+        (kept, withheld.into_iter().collect())
+    }
+
+    /// Withheld paths a commit touches relative to its first parent.
+    pub fn withheld_in_commit(&self, repo_root: &Path, oid: Oid) -> Result<Vec<String>, Error> {
+        if self.is_empty() {
+            return Ok(Vec::new());
+        }
+        let repo = Repository::open(repo_root)?;
+        let commit = repo.find_commit(oid)?;
+        let parent_tree = match commit.parent(0) {
+            Ok(parent) => Some(parent.tree()?),
+            Err(_) => None,
+        };
+        let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?;
+        let mut withheld = BTreeSet::new();
+        for delta in diff.deltas() {
+            for path in [delta.old_file().path(), delta.new_file().path()]
+                .into_iter()
+                .flatten()
+            {
+                if self.withholds(path) {
+                    withheld.insert(path.to_string_lossy().into_owned());
+                }
+            }
+        }
+        Ok(withheld.into_iter().collect())
+    }
+}
+
+// `a/<old> b/<new>`; renames list both sides, so either can withhold the section.
+fn header_paths(header: &str) -> Vec<String> {
+    let Some((old, new)) = header.rsplit_once(" b/") else {
+        return vec![header.to_string()];
+    };
+    let old = old.strip_prefix("a/").unwrap_or(old);
+    if old == new {
+        vec![new.to_string()]
+    } else {
+        vec![old.to_string(), new.to_string()]
+    }
+}
+
+/// Records a `context_withheld` audit entry naming what `surface` kept out of a prompt.
+pub fn record_withheld(surface: &str, paths: &[String]) {
+    if paths.is_empty() {
+        return;
+    }
+    crate::auditor::Auditor::record_operation(
+        WITHHELD_OPERATION,
+        serde_json::json!({ "surface": surface, "paths": paths }),
+    );
+}
+
+/// Rejects `[context] never_send` entries that do not compile as gitignore patterns.
+pub fn validate_patterns(patterns: &[String]) -> Result<(), String> {
+    let mut builder = GitignoreBuilder::new("");
+    for pattern in patterns {
+        builder
+            .add_line(None, pattern)
+            .map_err(|err| format!("[context] never_send entry `{pattern}` is invalid: {err}"))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> NeverSend {
+        NeverSend::new(
+            Path::new("/repo"),
+            &["secrets/**".to_string(), "*.pem".to_string()],
+        )
+    }
+
+    #[test]
+    fn withholds_matching_paths_and_their_children() {
+        let policy = policy();
+        assert!(policy.withholds(Path::new("secrets/prod.env")));
+        assert!(policy.withholds(Path::new("./secrets/nested/key")));
+        assert!(policy.withholds(Path::new("/repo/deploy/tls.pem")));
+        assert!(!policy.withholds(Path::new("src/secrets.rs")));
+        assert!(!policy.withholds(Path::new("/elsewhere/secrets/prod.env")));
+        assert!(!NeverSend::new(Path::new("/repo"), &[]).withholds(Path::new("secrets/a")));
+    }
+
+    #[test]
+    fn filter_diff_drops_withheld_sections_including_renames() {
+        let diff = "\
+diff --git a/src/lib.rs b/src/lib.rs
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1 +1 @@
+-old
++new
+diff --git a/secrets/prod.env b/secrets/prod.env
+--- a/secrets/prod.env
++++ b/secrets/prod.env
+@@ -1 +1 @@
+-TOKEN=1
++TOKEN=2
+diff --git a/secrets/moved.env b/config/moved.env
+similarity index 100%
+rename from secrets/moved.env
+rename to config/moved.env
+";
+        let (kept, withheld) = policy().filter_diff(diff);
+        assert_eq!(
+            kept,
+            "diff --git a/src/lib.rs b/src/lib.rs\n--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1 +1 @@\n-old\n+new\n"
+        );
+        assert_eq!(withheld, vec!["config/moved.env", "secrets/prod.env"]);
+    }
+
+    #[test]
+    fn validate_patterns_rejects_bad_globs() {
+        assert!(validate_patterns(&["secrets/**".to_string()]).is_ok());
+        assert!(validate_patterns(&["secrets/{a,b".to_string()]).is_err());
+    }
+}
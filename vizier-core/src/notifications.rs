@@ -0,0 +1,882 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use lettre::message::header::ContentType;
+use lettre::message::{Mailbox, Message};
+use lettre::transport::smtp::authentication::{Credentials, Mechanism};
+use lettre::transport::smtp::client::{Tls, TlsParameters};
+use lettre::{SmtpTransport, Transport};
+use serde::{Deserialize, Serialize};
+
+use crate::config::{
+    self, EmailNotificationConfig, NotificationDelivery, NotificationEvent, SmtpTls,
+};
+use crate::display::{self, LogLevel, Subsystem};
+use crate::jobs::{JobRecord, JobStatus};
+
+pub const NOTIFICATIONS_DIR: &str = ".vizier/state/notifications";
+const PENDING_FILE: &str = "pending.jsonl";
+const DIGEST_STATE_FILE: &str = "digest.json";
+const OUTBOX_DIR: &str = "outbox";
+const DRAIN_LOCK_FILE: &str = "drain.lock";
+const SMTP_TIMEOUT: Duration = Duration::from_secs(15);
+// A drain lock older than this belongs to a drainer that died mid-send.
+const DRAIN_LOCK_STALE: Duration = Duration::from_secs(10 * 60);
+// Immediate emails are dropped after this many failed sends.
+const MAX_SEND_ATTEMPTS: u32 = 8;
+
+/// One event worth telling someone about.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Notification {
+    pub event: String,
+    pub subject: String,
+    pub body: String,
+    pub recorded_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct DigestState {
+    last_sent_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    last_attempt_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    failures: u32,
+}
+
+/// An immediate email waiting in the outbox, with its delivery attempts so far.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutboxEntry {
+    pub notification: Notification,
+    #[serde(default)]
+    pub attempts: u32,
+    #[serde(default)]
+    pub last_attempt_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub last_error: Option<String>,
+}
+
+/// How long to wait after the `failures`-th consecutive failed send: one minute, doubling up to
+/// six hours.
+fn retry_backoff(failures: u32) -> chrono::Duration {
+    let minutes = 1i64 << failures.saturating_sub(1).min(9);
+    chrono::Duration::minutes(minutes.min(6 * 60))
+}
+
+/// The event a finished job stands for, if any: a persisted plan, a merged plan branch, a failed
+/// gate, or some other failed job. Successful jobs of other kinds are not events.
+pub fn notification_for_job(record: &JobRecord) -> Option<(NotificationEvent, Notification)> {
+    let metadata = record.metadata.as_ref();
+    let operation = metadata
+        .and_then(|meta| meta.workflow_executor_operation.as_deref())
+        .unwrap_or_default();
+    let plan = metadata.and_then(|meta| meta.plan.as_deref());
+    let branch = metadata.and_then(|meta| meta.branch.as_deref());
+    let target = metadata.and_then(|meta| meta.target.as_deref());
+    let node = metadata
+        .and_then(|meta| meta.workflow_node_id.as_deref())
+        .unwrap_or(operation);
+    let blocked = metadata
+        .and_then(|meta| meta.workflow_node_outcome.as_deref())
+        .is_some_and(|outcome| outcome == "blocked");
+    let subject_of = plan.or(branch).unwrap_or(record.id.as_str());
+
+    let failed = record.status == JobStatus::Failed;
+    let (event, subject) = if operation.starts_with("gate.") && (failed || blocked) {
+        (
+            NotificationEvent::GateFailure,
+            format!("gate failed: {operation} on {subject_of}"),
+        )
+    } else if failed {
+        (
+            NotificationEvent::JobFailure,
+            format!("job failed: {node} ({subject_of})"),
+        )
+    } else if record.status != JobStatus::Succeeded {
+        return None;
+    } else if operation == "plan.persist" {
+        (
+            NotificationEvent::Draft,
+            format!("draft ready: {subject_of}"),
+        )
+    } else if operation == "git.integrate_plan_branch" {
+        (
+            NotificationEvent::Merge,
+            format!(
+                "merged {} into {}",
+                branch.unwrap_or(subject_of),
+                target.unwrap_or("target")
+            ),
+        )
+    } else {
+        return None;
+    };
+
+    let mut body = format!("{subject}\n\n");
+    let mut field = |label: &str, value: Option<&str>| {
+        if let Some(value) = value.filter(|value| !value.is_empty()) {
+            body.push_str(&format!("{label}: {value}\n"));
+        }
+    };
+    field("Job", Some(&record.id));
+    field(
+        "Run",
+        metadata.and_then(|meta| meta.workflow_run_id.as_deref()),
+    );
+    field("Node", Some(node));
+    field("Plan", plan);
+    field("Branch", branch);
+    field("Target", target);
+    field(
+        "Exit code",
+        record.exit_code.map(|code| code.to_string()).as_deref(),
+    );
+    field("Log", Some(&record.stderr_path));
+
+    Some((
+        event,
+        Notification {
+            event: event.as_str().to_string(),
+            subject,
+            body,
+            recorded_at: record.finished_at.unwrap_or_else(Utc::now),
+        },
+    ))
+}
+
+/// Queues the event a finished job stands for, per `[notifications.email.events]`: immediate
+/// events go to the outbox and wake a detached drainer, digest events wait in the pending file.
+/// Nothing here touches the network, so finalization never blocks on SMTP. Best-effort: queueing
+/// failures are logged, never surfaced to the job.
+pub fn notify_job_finished(project_root: &Path, record: &JobRecord) {
+    let cfg = config::get_config();
+    let email = &cfg.notifications.email;
+    if !email.enabled() {
+        return;
+    }
+    let Some((event, notification)) = notification_for_job(record) else {
+        return;
+    };
+    let result = match email.delivery(event) {
+        NotificationDelivery::Off => Ok(()),
+        NotificationDelivery::Immediate => queue_for_outbox(project_root, &notification)
+            .and_then(|()| spawn_drainer(project_root, &std::env::current_exe()?)),
+        NotificationDelivery::Digest => queue_for_digest(project_root, &notification),
+    };
+    if let Err(err) = result {
+        display::log(
+            Subsystem::Scheduler,
+            LogLevel::Warn,
+            format!(
+                "email notification for {} `{}` failed: {err}",
+                event.as_str(),
+                record.id
+            ),
+        );
+    }
+}
+
+pub fn queue_for_outbox(project_root: &Path, notification: &Notification) -> io::Result<()> {
+    let dir = project_root.join(NOTIFICATIONS_DIR).join(OUTBOX_DIR);
+    fs::create_dir_all(&dir)?;
+    write_outbox_entry(
+        &dir.join(format!("{}.json", uuid::Uuid::new_v4().simple())),
+        &OutboxEntry {
+            notification: notification.clone(),
+            attempts: 0,
+            last_attempt_at: None,
+            next_attempt_at: None,
+            last_error: None,
+        },
+    )
+}
+
+fn write_outbox_entry(path: &Path, entry: &OutboxEntry) -> io::Result<()> {
+    let bytes = serde_json::to_vec_pretty(entry)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    crate::atomic_file::write_atomic(path, &bytes)
+}
+
+fn load_outbox(project_root: &Path) -> io::Result<Vec<(PathBuf, OutboxEntry)>> {
+    let dir = project_root.join(NOTIFICATIONS_DIR).join(OUTBOX_DIR);
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    let mut outbox = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+            continue;
+        }
+        if let Some(parsed) = fs::read(&path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<OutboxEntry>(&bytes).ok())
+        {
+            outbox.push((path, parsed));
+        }
+    }
+    outbox.sort_by_key(|(_, entry)| entry.notification.recorded_at);
+    Ok(outbox)
+}
+
+pub fn queue_for_digest(project_root: &Path, notification: &Notification) -> io::Result<()> {
+    let dir = project_root.join(NOTIFICATIONS_DIR);
+    fs::create_dir_all(&dir)?;
+    let mut line = serde_json::to_string(notification)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    line.push('\n');
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dir.join(PENDING_FILE))?
+        .write_all(line.as_bytes())
+}
+
+/// Wakes a detached drainer when an outbox email or the digest is due. Called from every
+/// scheduler tick; only reads state files, so the tick never waits on SMTP.
+pub fn spawn_due_delivery(project_root: &Path, binary: &Path) {
+    let cfg = config::get_config();
+    let email = &cfg.notifications.email;
+    if !email.enabled() || !delivery_due(project_root, email, Utc::now()) {
+        return;
+    }
+    if let Err(err) = spawn_drainer(project_root, binary) {
+        display::log(
+            Subsystem::Scheduler,
+            LogLevel::Warn,
+            format!("could not start the email drainer: {err}"),
+        );
+    }
+}
+
+/// Whether any outbox email or the digest should be attempted at `now`.
+pub fn delivery_due(
+    project_root: &Path,
+    email: &EmailNotificationConfig,
+    now: DateTime<Utc>,
+) -> bool {
+    let outbox_due = load_outbox(project_root).is_ok_and(|outbox| {
+        outbox
+            .iter()
+            .any(|(_, entry)| entry.next_attempt_at.is_none_or(|at| at <= now))
+    });
+    outbox_due || digest_due_since(project_root, email, now).is_some()
+}
+
+// Starts `vizier __notifications-drain` in its own process group, unless a live drainer already
+// holds the lock. The child is reaped from a thread so long-lived callers do not collect zombies.
+fn spawn_drainer(project_root: &Path, binary: &Path) -> io::Result<()> {
+    let lock = project_root.join(NOTIFICATIONS_DIR).join(DRAIN_LOCK_FILE);
+    if lock.exists() && !lock_is_stale(&lock) {
+        return Ok(());
+    }
+    let mut command = Command::new(binary);
+    command
+        .arg("__notifications-drain")
+        .current_dir(project_root)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    let mut child = command.spawn()?;
+    std::thread::spawn(move || {
+        let _ = child.wait();
+    });
+    Ok(())
+}
+
+fn lock_is_stale(lock: &Path) -> bool {
+    fs::metadata(lock)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .is_none_or(|age| age > DRAIN_LOCK_STALE)
+}
+
+struct DrainLock(PathBuf);
+
+impl DrainLock {
+    fn acquire(project_root: &Path) -> io::Result<Option<Self>> {
+        let dir = project_root.join(NOTIFICATIONS_DIR);
+        fs::create_dir_all(&dir)?;
+        let path = dir.join(DRAIN_LOCK_FILE);
+        for _ in 0..2 {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    let _ = writeln!(file, "{}", std::process::id());
+                    return Ok(Some(Self(path)));
+                }
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {
+                    if !lock_is_stale(&path) {
+                        return Ok(None);
+                    }
+                    let _ = fs::remove_file(&path);
+                }
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl Drop for DrainLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+/// Entry point of the detached drainer: sends due outbox emails, then the digest if due. Exits
+/// quietly when another drainer holds the lock.
+pub fn drain_notifications(project_root: &Path) -> io::Result<()> {
+    let cfg = config::get_config();
+    let email = &cfg.notifications.email;
+    if !email.enabled() {
+        return Ok(());
+    }
+    let Some(_lock) = DrainLock::acquire(project_root)? else {
+        return Ok(());
+    };
+    // Emails queued while a pass was sending would otherwise wait for the next scheduler tick.
+    loop {
+        drain_outbox(project_root, email, Utc::now(), send_email)?;
+        if !load_outbox(project_root)?
+            .iter()
+            .any(|(_, entry)| entry.attempts == 0)
+        {
+            break;
+        }
+    }
+    if let Err(err) = flush_digest(project_root, email, Utc::now(), send_email) {
+        display::log(
+            Subsystem::Scheduler,
+            LogLevel::Warn,
+            format!("email digest failed: {err}"),
+        );
+    }
+    Ok(())
+}
+
+/// Sends every outbox email whose retry time has come. A failed send records the attempt and
+/// backs off; after `MAX_SEND_ATTEMPTS` failures the email is dropped with a warning. Returns how
+/// many emails went out.
+pub fn drain_outbox(
+    project_root: &Path,
+    email: &EmailNotificationConfig,
+    now: DateTime<Utc>,
+    mut send: impl FnMut(&EmailNotificationConfig, &str, &str) -> io::Result<()>,
+) -> io::Result<usize> {
+    let mut sent = 0;
+    for (path, mut entry) in load_outbox(project_root)? {
+        if entry.next_attempt_at.is_some_and(|at| at > now) {
+            continue;
+        }
+        let subject = format!("[vizier] {}", entry.notification.subject);
+        match send(email, &subject, &entry.notification.body) {
+            Ok(()) => {
+                fs::remove_file(&path)?;
+                sent += 1;
+            }
+            Err(err) => {
+                entry.attempts += 1;
+                entry.last_attempt_at = Some(now);
+                entry.next_attempt_at = Some(now + retry_backoff(entry.attempts));
+                entry.last_error = Some(err.to_string());
+                if entry.attempts >= MAX_SEND_ATTEMPTS {
+                    display::log(
+                        Subsystem::Scheduler,
+                        LogLevel::Warn,
+                        format!(
+                            "dropping email `{}` after {} failed attempts: {err}",
+                            entry.notification.subject, entry.attempts
+                        ),
+                    );
+                    fs::remove_file(&path)?;
+                } else {
+                    display::log(
+                        Subsystem::Scheduler,
+                        LogLevel::Warn,
+                        format!(
+                            "email `{}` failed (attempt {}): {err}",
+                            entry.notification.subject, entry.attempts
+                        ),
+                    );
+                    write_outbox_entry(&path, &entry)?;
+                }
+            }
+        }
+    }
+    Ok(sent)
+}
+
+fn load_digest_state(dir: &Path) -> DigestState {
+    fs::read(dir.join(DIGEST_STATE_FILE))
+        .ok()
+        .and_then(|bytes| serde_json::from_slice::<DigestState>(&bytes).ok())
+        .unwrap_or_default()
+}
+
+fn write_digest_state(dir: &Path, state: &DigestState) -> io::Result<()> {
+    let bytes = serde_json::to_vec_pretty(state)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    crate::atomic_file::write_atomic(&dir.join(DIGEST_STATE_FILE), &bytes)
+}
+
+// The start of the digest window when a digest should go out at `now`: `digest_hours` have passed
+// since the last digest (or the oldest queued event) and no failed attempt is still backing off.
+fn digest_due_since(
+    project_root: &Path,
+    email: &EmailNotificationConfig,
+    now: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    let dir = project_root.join(NOTIFICATIONS_DIR);
+    let pending = load_pending(&dir.join(PENDING_FILE)).ok()?;
+    let oldest = pending.iter().map(|entry| entry.recorded_at).min()?;
+    let state = load_digest_state(&dir);
+    let since = state.last_sent_at.unwrap_or(oldest);
+    if now - since < chrono::Duration::hours(i64::from(email.digest_hours)) {
+        return None;
+    }
+    if let Some(last_attempt) = state.last_attempt_at
+        && state.failures > 0
+        && now < last_attempt + retry_backoff(state.failures)
+    {
+        return None;
+    }
+    Some(since)
+}
+
+/// Sends pending notifications as one digest email when due. The pending file is claimed by
+/// rename first, so concurrent drainers never send the same digest twice; a failed send puts the
+/// events back and records the attempt so the next one backs off. Returns how many events went
+/// out.
+pub fn flush_digest(
+    project_root: &Path,
+    email: &EmailNotificationConfig,
+    now: DateTime<Utc>,
+    send: impl FnOnce(&EmailNotificationConfig, &str, &str) -> io::Result<()>,
+) -> io::Result<usize> {
+    let dir = project_root.join(NOTIFICATIONS_DIR);
+    let Some(since) = digest_due_since(project_root, email, now) else {
+        return Ok(0);
+    };
+
+    let claimed = dir.join(format!("{PENDING_FILE}.{}", uuid::Uuid::new_v4().simple()));
+    match fs::rename(dir.join(PENDING_FILE), &claimed) {
+        Ok(()) => {}
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(0),
+        Err(err) => return Err(err),
+    }
+    let pending = load_pending(&claimed)?;
+    let (subject, body) = render_digest(&pending, since, now);
+    let mut state = load_digest_state(&dir);
+    state.last_attempt_at = Some(now);
+    if let Err(err) = send(email, &subject, &body) {
+        for notification in &pending {
+            queue_for_digest(project_root, notification)?;
+        }
+        let _ = fs::remove_file(&claimed);
+        state.failures += 1;
+        write_digest_state(&dir, &state)?;
+        return Err(err);
+    }
+    let _ = fs::remove_file(&claimed);
+    state.last_sent_at = Some(now);
+    state.failures = 0;
+    write_digest_state(&dir, &state)?;
+    Ok(pending.len())
+}
+
+fn load_pending(path: &Path) -> io::Result<Vec<Notification>> {
+    match fs::read_to_string(path) {
+        Ok(text) => Ok(text
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect()),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(err) => Err(err),
+    }
+}
+
+/// Digest subject and body: counts per event, then every event grouped by kind, oldest first.
+pub fn render_digest(
+    pending: &[Notification],
+    since: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> (String, String) {
+    let mut counts = Vec::new();
+    let mut body = format!(
+        "Vizier activity from {} to {}\n",
+        since.format("%Y-%m-%d %H:%M UTC"),
+        now.format("%Y-%m-%d %H:%M UTC")
+    );
+    for event in NotificationEvent::ALL {
+        let mut entries = pending
+            .iter()
+            .filter(|entry| entry.event == event.as_str())
+            .collect::<Vec<_>>();
+        if entries.is_empty() {
+            continue;
+        }
+        entries.sort_by_key(|entry| entry.recorded_at);
+        counts.push(format!(
+            "{} {}",
+            entries.len(),
+            event.as_str().replace('_', " ")
+        ));
+        body.push_str(&format!("\n## {} ({})\n", event.as_str(), entries.len()));
+        for entry in entries {
+            body.push_str(&format!(
+                "- {} {}\n",
+                entry.recorded_at.format("%Y-%m-%d %H:%M"),
+                entry.subject
+            ));
+        }
+    }
+    (format!("[vizier] digest: {}", counts.join(", ")), body)
+}
+
+/// Sends one plain-text email to every `to` address over SMTP. Credentials are only offered over
+/// STARTTLS or implicit TLS; a `username` with `tls = "none"` is refused before connecting.
+pub fn send_email(email: &EmailNotificationConfig, subject: &str, body: &str) -> io::Result<()> {
+    let host = email
+        .smtp_host
+        .as_deref()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "smtp_host is not set"))?;
+    let message = build_message(email, subject, body)?;
+
+    let tls = match email.tls {
+        SmtpTls::None => Tls::None,
+        SmtpTls::StartTls => Tls::Required(tls_parameters(host)?),
+        SmtpTls::Implicit => Tls::Wrapper(tls_parameters(host)?),
+    };
+    let mut transport = SmtpTransport::builder_dangerous(host)
+        .port(email.smtp_port)
+        .tls(tls)
+        .timeout(Some(SMTP_TIMEOUT));
+    if let Some(username) = email.username.as_deref() {
+        if email.tls == SmtpTls::None {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "refusing to send SMTP credentials without TLS; set tls = \"starttls\" or \"implicit\"",
+            ));
+        }
+        let password = std::env::var(&email.password_env).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{} is not set", email.password_env),
+            )
+        })?;
+        transport = transport
+            .credentials(Credentials::new(username.to_string(), password))
+            .authentication(vec![Mechanism::Plain]);
+    }
+    transport
+        .build()
+        .send(&message)
+        .map(|_| ())
+        .map_err(io::Error::other)
+}
+
+fn tls_parameters(host: &str) -> io::Result<TlsParameters> {
+    TlsParameters::new(host.to_string()).map_err(io::Error::other)
+}
+
+// Lettre encodes non-ASCII headers per RFC 2047 and picks a transfer encoding the body needs.
+fn build_message(
+    email: &EmailNotificationConfig,
+    subject: &str,
+    body: &str,
+) -> io::Result<Message> {
+    let mailbox = |address: &str| {
+        address.parse::<Mailbox>().map_err(|err| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid address `{address}`: {err}"),
+            )
+        })
+    };
+    let mut builder = Message::builder()
+        .from(mailbox(&email.from)?)
+        .subject(subject.replace(['\r', '\n'], " "))
+        .header(ContentType::TEXT_PLAIN);
+    for to in &email.to {
+        builder = builder.to(mailbox(to)?);
+    }
+    builder.body(body.to_string()).map_err(io::Error::other)
+}
+
+pub fn pending_path(project_root: &Path) -> PathBuf {
+    project_root.join(NOTIFICATIONS_DIR).join(PENDING_FILE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader};
+    use std::net::TcpListener;
+
+    fn notification(event: NotificationEvent, subject: &str, at: &str) -> Notification {
+        Notification {
+            event: event.as_str().to_string(),
+            subject: subject.to_string(),
+            body: String::new(),
+            recorded_at: DateTime::parse_from_rfc3339(at)
+                .unwrap()
+                .with_timezone(&Utc),
+        }
+    }
+
+    #[test]
+    fn digest_waits_for_interval_and_requeues_on_failure() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let root = dir.path();
+        let email = EmailNotificationConfig {
+            smtp_host: Some("localhost".to_string()),
+            to: vec!["team@example.com".to_string()],
+            ..EmailNotificationConfig::default()
+        };
+        queue_for_digest(
+            root,
+            &notification(
+                NotificationEvent::Merge,
+                "merged draft/a into main",
+                "2026-01-01T09:00:00Z",
+            ),
+        )
+        .expect("queue");
+        queue_for_digest(
+            root,
+            &notification(
+                NotificationEvent::GateFailure,
+                "gate failed: gate.cicd on b",
+                "2026-01-01T10:00:00Z",
+            ),
+        )
+        .expect("queue");
+
+        let early = DateTime::parse_from_rfc3339("2026-01-01T20:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let sent =
+            flush_digest(root, &email, early, |_, _, _| panic!("not due yet")).expect("flush");
+        assert_eq!(sent, 0);
+
+        let due = DateTime::parse_from_rfc3339("2026-01-02T09:30:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let err = flush_digest(root, &email, due, |_, _, _| Err(io::Error::other("down")))
+            .expect_err("send fails");
+        assert_eq!(err.to_string(), "down");
+        assert_eq!(load_pending(&pending_path(root)).expect("pending").len(), 2);
+        let state = load_digest_state(&root.join(NOTIFICATIONS_DIR));
+        assert_eq!(state.last_attempt_at, Some(due));
+        assert_eq!(state.failures, 1);
+
+        let sent = flush_digest(
+            root,
+            &email,
+            due + chrono::Duration::seconds(30),
+            |_, _, _| panic!("still backing off"),
+        )
+        .expect("flush");
+        assert_eq!(sent, 0);
+
+        let retry = due + chrono::Duration::minutes(1);
+        let mut captured = None;
+        let sent = flush_digest(root, &email, retry, |_, subject, body| {
+            captured = Some((subject.to_string(), body.to_string()));
+            Ok(())
+        })
+        .expect("flush");
+        assert_eq!(sent, 2);
+        let (subject, body) = captured.expect("digest sent");
+        assert_eq!(subject, "[vizier] digest: 1 merge, 1 gate failure");
+        assert!(body.contains("## merge (1)\n- 2026-01-01 09:00 merged draft/a into main\n"));
+        assert!(!pending_path(root).exists());
+        let state = load_digest_state(&root.join(NOTIFICATIONS_DIR));
+        assert_eq!(state.last_sent_at, Some(retry));
+        assert_eq!(state.failures, 0);
+    }
+
+    #[test]
+    fn outbox_records_failed_attempts_and_backs_off() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let root = dir.path();
+        let email = EmailNotificationConfig {
+            smtp_host: Some("localhost".to_string()),
+            to: vec!["team@example.com".to_string()],
+            ..EmailNotificationConfig::default()
+        };
+        queue_for_outbox(
+            root,
+            &notification(
+                NotificationEvent::GateFailure,
+                "gate failed: gate.cicd on b",
+                "2026-01-01T10:00:00Z",
+            ),
+        )
+        .expect("queue");
+        let now = DateTime::parse_from_rfc3339("2026-01-01T10:00:05Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert!(delivery_due(root, &email, now));
+
+        let sent = drain_outbox(root, &email, now, |_, _, _| Err(io::Error::other("down")))
+            .expect("drain");
+        assert_eq!(sent, 0);
+        let outbox = load_outbox(root).expect("outbox");
+        assert_eq!(outbox.len(), 1);
+        let entry = &outbox[0].1;
+        assert_eq!(entry.attempts, 1);
+        assert_eq!(entry.last_attempt_at, Some(now));
+        assert_eq!(
+            entry.next_attempt_at,
+            Some(now + chrono::Duration::minutes(1))
+        );
+        assert_eq!(entry.last_error.as_deref(), Some("down"));
+        assert!(!delivery_due(root, &email, now));
+
+        let sent =
+            drain_outbox(root, &email, now, |_, _, _| panic!("still backing off")).expect("drain");
+        assert_eq!(sent, 0);
+
+        let retry = now + chrono::Duration::minutes(1);
+        let mut subjects = Vec::new();
+        let sent = drain_outbox(root, &email, retry, |_, subject, _| {
+            subjects.push(subject.to_string());
+            Ok(())
+        })
+        .expect("drain");
+        assert_eq!(sent, 1);
+        assert_eq!(subjects, vec!["[vizier] gate failed: gate.cicd on b"]);
+        assert!(load_outbox(root).expect("outbox").is_empty());
+    }
+
+    #[test]
+    fn outbox_drops_email_after_max_attempts() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let root = dir.path();
+        let email = EmailNotificationConfig::default();
+        queue_for_outbox(
+            root,
+            &notification(NotificationEvent::Merge, "merged a", "2026-01-01T10:00:00Z"),
+        )
+        .expect("queue");
+        let mut now = DateTime::parse_from_rfc3339("2026-01-01T10:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        for _ in 0..MAX_SEND_ATTEMPTS {
+            drain_outbox(root, &email, now, |_, _, _| Err(io::Error::other("down")))
+                .expect("drain");
+            now += chrono::Duration::hours(7);
+        }
+        assert!(load_outbox(root).expect("outbox").is_empty());
+    }
+
+    #[test]
+    fn send_email_encodes_subject_and_dot_stuffs_body() {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
+        let port = listener.local_addr().expect("addr").port();
+        let server = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().expect("accept");
+            let mut writer = stream.try_clone().expect("clone");
+            let mut reader = BufReader::new(stream);
+            let mut transcript = Vec::new();
+            writer.write_all(b"220 test ready\r\n").unwrap();
+            let mut in_data = false;
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap() == 0 {
+                    break;
+                }
+                let line = line.trim_end_matches(['\r', '\n']).to_string();
+                transcript.push(line.clone());
+                if in_data {
+                    if line == "." {
+                        in_data = false;
+                        writer.write_all(b"250 queued\r\n").unwrap();
+                    }
+                    continue;
+                }
+                let reply: &[u8] = match line.split_whitespace().next().unwrap_or_default() {
+                    "EHLO" => b"250 test\r\n",
+                    "DATA" => {
+                        in_data = true;
+                        b"354 go\r\n"
+                    }
+                    "QUIT" => {
+                        writer.write_all(b"221 bye\r\n").unwrap();
+                        break;
+                    }
+                    _ => b"250 ok\r\n",
+                };
+                writer.write_all(reply).unwrap();
+            }
+            transcript
+        });
+
+        let email = EmailNotificationConfig {
+            smtp_host: Some("127.0.0.1".to_string()),
+            smtp_port: port,
+            tls: SmtpTls::None,
+            from: "vizier@example.com".to_string(),
+            to: vec!["a@example.com".to_string(), "b@example.com".to_string()],
+            ..EmailNotificationConfig::default()
+        };
+        send_email(&email, "gate failed: café", "first\n.hidden\n").expect("send");
+        let transcript = server.join().expect("server");
+
+        assert!(transcript[0].starts_with("EHLO "), "{transcript:?}");
+        assert_eq!(transcript[1], "MAIL FROM:<vizier@example.com>");
+        assert_eq!(transcript[2], "RCPT TO:<a@example.com>");
+        assert_eq!(transcript[3], "RCPT TO:<b@example.com>");
+        let subject = transcript
+            .iter()
+            .find(|line| line.starts_with("Subject: "))
+            .expect("subject header");
+        assert!(
+            subject.is_ascii() && subject.contains("=?utf-8?"),
+            "{subject}"
+        );
+        assert!(
+            !transcript
+                .iter()
+                .any(|line| line.eq_ignore_ascii_case("Content-Transfer-Encoding: 8bit")),
+            "{transcript:?}"
+        );
+        assert!(
+            transcript.contains(&"..hidden".to_string()),
+            "{transcript:?}"
+        );
+        assert_eq!(transcript.last().map(String::as_str), Some("QUIT"));
+    }
+
+    #[test]
+    fn send_email_refuses_credentials_without_tls() {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
+        listener.set_nonblocking(true).expect("nonblocking");
+        let email = EmailNotificationConfig {
+            smtp_host: Some("127.0.0.1".to_string()),
+            smtp_port: listener.local_addr().expect("addr").port(),
+            tls: SmtpTls::None,
+            username: Some("bot".to_string()),
+            from: "vizier@example.com".to_string(),
+            to: vec!["a@example.com".to_string()],
+            ..EmailNotificationConfig::default()
+        };
+        let err = send_email(&email, "gate failed", "body").expect_err("refused");
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+        assert!(listener.accept().is_err(), "no connection should be opened");
+    }
+}
@@ -0,0 +1,329 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use git2::{BranchType, DiffOptions, Repository};
+use serde::Serialize;
+
+use crate::file_tracking::is_vizier_path;
+use crate::plan;
+
+/// Lines of slack around each hunk when deciding whether two edits land on the same region.
+const HUNK_PADDING: u32 = 3;
+const MAX_LISTED_FILES: usize = 5;
+
+/// How likely two overlapping plans are to conflict when the second one merges.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictLikelihood {
+    /// Both plans only mention the file so far.
+    Low,
+    /// One plan changed the file and the other plans to, or both changed different regions.
+    Medium,
+    /// Both plans changed the same region of the file.
+    High,
+}
+
+impl ConflictLikelihood {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+        }
+    }
+}
+
+/// Files a plan touches: paths its branch already changed relative to the target (with the
+/// target-side line range of each hunk) and paths its plan document mentions but has not
+/// changed yet. `.vizier/` paths are never part of a surface.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PlanSurface {
+    pub slug: String,
+    pub branch: String,
+    pub changed: BTreeMap<String, Vec<(u32, u32)>>,
+    pub planned: BTreeSet<String>,
+}
+
+impl PlanSurface {
+    /// Surface of a freshly drafted plan, which has no commits beyond its plan document.
+    pub fn from_plan_body(slug: &str, branch: &str, plan_body: &str) -> Self {
+        Self {
+            slug: slug.to_string(),
+            branch: branch.to_string(),
+            changed: BTreeMap::new(),
+            planned: plan::referenced_plan_paths(plan_body)
+                .into_iter()
+                .filter(|path| !is_vizier_path(path))
+                .collect(),
+        }
+    }
+
+    /// Surface of `branch` from its diff against the merge base with `target`, plus the paths
+    /// its committed plan document mentions.
+    pub fn for_branch(
+        repo_path: &Path,
+        slug: &str,
+        branch: &str,
+        target: &str,
+    ) -> Result<Self, git2::Error> {
+        let repo = Repository::open(repo_path)?;
+        surface_in(&repo, slug, branch, target)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changed.is_empty() && self.planned.is_empty()
+    }
+
+    fn paths(&self) -> BTreeSet<&str> {
+        self.changed
+            .keys()
+            .chain(self.planned.iter())
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+/// Another pending plan whose surface shares files with the plan being checked.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct PlanOverlap {
+    pub slug: String,
+    pub branch: String,
+    pub files: Vec<String>,
+    pub likelihood: ConflictLikelihood,
+}
+
+impl PlanOverlap {
+    /// One-line warning naming both plans, the shared files, and the conflict likelihood.
+    pub fn message(&self, slug: &str) -> String {
+        let mut files = self
+            .files
+            .iter()
+            .take(MAX_LISTED_FILES)
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(", ");
+        if self.files.len() > MAX_LISTED_FILES {
+            files.push_str(&format!(" (+{} more)", self.files.len() - MAX_LISTED_FILES));
+        }
+        format!(
+            "plan `{slug}` overlaps pending plan `{}` ({}) on {files}; conflict likelihood: {}",
+            self.slug,
+            self.branch,
+            self.likelihood.as_str()
+        )
+    }
+}
+
+/// Shared files between two surfaces and how likely they are to conflict; `None` when the
+/// surfaces are disjoint.
+pub fn compare(surface: &PlanSurface, other: &PlanSurface) -> Option<PlanOverlap> {
+    let shared = surface
+        .paths()
+        .intersection(&other.paths())
+        .map(|path| path.to_string())
+        .collect::<Vec<_>>();
+    let likelihood = shared
+        .iter()
+        .map(
+            |path| match (surface.changed.get(path), other.changed.get(path)) {
+                (Some(ours), Some(theirs)) if ranges_collide(ours, theirs) => {
+                    ConflictLikelihood::High
+                }
+                (Some(_), _) | (_, Some(_)) => ConflictLikelihood::Medium,
+                (None, None) => ConflictLikelihood::Low,
+            },
+        )
+        .max()?;
+    Some(PlanOverlap {
+        slug: other.slug.clone(),
+        branch: other.branch.clone(),
+        files: shared,
+        likelihood,
+    })
+}
+
+/// Compares `surface` with every other pending plan branch (plan-prefixed local branches not
+/// yet merged into `target`), most likely conflicts first.
+pub fn find_overlaps(
+    repo_path: &Path,
+    surface: &PlanSurface,
+    target: &str,
+) -> Result<Vec<PlanOverlap>, git2::Error> {
+    if surface.is_empty() {
+        return Ok(Vec::new());
+    }
+    let repo = Repository::open(repo_path)?;
+    let target_oid = repo
+        .find_branch(target, BranchType::Local)?
+        .get()
+        .peel_to_commit()?
+        .id();
+    let mut overlaps = Vec::new();
+    for entry in repo.branches(Some(BranchType::Local))? {
+        let (branch, _) = entry?;
+        let Some(name) = branch.name()?.map(str::to_string) else {
+            continue;
+        };
+        if name == surface.branch || name == target {
+            continue;
+        }
+        let Some(slug) = plan::slug_from_branch(&name) else {
+            continue;
+        };
+        let tip = branch.get().peel_to_commit()?.id();
+        if tip == target_oid || repo.graph_descendant_of(target_oid, tip)? {
+            continue;
+        }
+        let other = surface_in(&repo, &slug, &name, target)?;
+        if let Some(overlap) = compare(surface, &other) {
+            overlaps.push(overlap);
+        }
+    }
+    overlaps.sort_by(|left, right| {
+        right
+            .likelihood
+            .cmp(&left.likelihood)
+            .then_with(|| left.slug.cmp(&right.slug))
+    });
+    Ok(overlaps)
+}
+
+fn surface_in(
+    repo: &Repository,
+    slug: &str,
+    branch: &str,
+    target: &str,
+) -> Result<PlanSurface, git2::Error> {
+    let tip = repo
+        .find_branch(branch, BranchType::Local)?
+        .get()
+        .peel_to_commit()?;
+    let target_oid = repo
+        .find_branch(target, BranchType::Local)?
+        .get()
+        .peel_to_commit()?
+        .id();
+    let base = repo.find_commit(repo.merge_base(tip.id(), target_oid)?)?;
+
+    let mut options = DiffOptions::new();
+    options.context_lines(0);
+    let diff =
+        repo.diff_tree_to_tree(Some(&base.tree()?), Some(&tip.tree()?), Some(&mut options))?;
+    // Both callbacks record into the same map.
+    let changed: RefCell<BTreeMap<String, Vec<(u32, u32)>>> = RefCell::new(BTreeMap::new());
+    diff.foreach(
+        &mut |delta, _| {
+            if let Some(path) = delta_path(&delta) {
+                changed.borrow_mut().entry(path).or_default();
+            }
+            true
+        },
+        None,
+        Some(&mut |delta, hunk| {
+            if let Some(path) = delta_path(&delta) {
+                let start = hunk.old_start();
+                changed
+                    .borrow_mut()
+                    .entry(path)
+                    .or_default()
+                    .push((start, start + hunk.old_lines()));
+            }
+            true
+        }),
+        None,
+    )?;
+    let mut changed = changed.into_inner();
+    changed.retain(|path, _| !is_vizier_path(path));
+
+    let plan_body = tip
+        .tree()?
+        .get_path(&plan::plan_rel_path(slug))
+        .ok()
+        .and_then(|entry| repo.find_blob(entry.id()).ok())
+        .map(|blob| String::from_utf8_lossy(blob.content()).into_owned())
+        .unwrap_or_default();
+    let planned = plan::referenced_plan_paths(&plan_body)
+        .into_iter()
+        .filter(|path| !is_vizier_path(path) && !changed.contains_key(path))
+        .collect();
+
+    Ok(PlanSurface {
+        slug: slug.to_string(),
+        branch: branch.to_string(),
+        changed,
+        planned,
+    })
+}
+
+fn delta_path(delta: &git2::DiffDelta<'_>) -> Option<String> {
+    delta
+        .new_file()
+        .path()
+        .or_else(|| delta.old_file().path())
+        .map(|path| path.to_string_lossy().into_owned())
+}
+
+fn ranges_collide(ours: &[(u32, u32)], theirs: &[(u32, u32)]) -> bool {
+    ours.iter().any(|&(start, end)| {
+        theirs.iter().any(|&(other_start, other_end)| {
+            start <= other_end + HUNK_PADDING && other_start <= end + HUNK_PADDING
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn surface(slug: &str, changed: &[(&str, &[(u32, u32)])], planned: &[&str]) -> PlanSurface {
+        PlanSurface {
+            slug: slug.to_string(),
+            branch: format!("draft/{slug}"),
+            changed: changed
+                .iter()
+                .map(|(path, ranges)| (path.to_string(), ranges.to_vec()))
+                .collect(),
+            planned: planned.iter().map(|path| path.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn compare_estimates_likelihood_from_shared_regions() {
+        let alpha = surface("alpha", &[("src/lib.rs", &[(10, 14)])], &["README.md"]);
+
+        let same_region = surface("beta", &[("src/lib.rs", &[(16, 18)])], &[]);
+        let overlap = compare(&alpha, &same_region).expect("overlap");
+        assert_eq!(overlap.files, vec!["src/lib.rs".to_string()]);
+        assert_eq!(overlap.likelihood, ConflictLikelihood::High);
+
+        let other_region = surface("gamma", &[("src/lib.rs", &[(200, 210)])], &[]);
+        assert_eq!(
+            compare(&alpha, &other_region).expect("overlap").likelihood,
+            ConflictLikelihood::Medium
+        );
+
+        let planned_only = surface("delta", &[], &["README.md"]);
+        let overlap = compare(&alpha, &planned_only).expect("overlap");
+        assert_eq!(overlap.likelihood, ConflictLikelihood::Low);
+        assert_eq!(
+            overlap.message("alpha"),
+            "plan `alpha` overlaps pending plan `delta` (draft/delta) on README.md; conflict likelihood: low"
+        );
+
+        assert!(compare(&alpha, &surface("eps", &[("src/main.rs", &[(1, 2)])], &[])).is_none());
+    }
+
+    #[test]
+    fn from_plan_body_ignores_vizier_paths() {
+        let surface = PlanSurface::from_plan_body(
+            "alpha",
+            "draft/alpha",
+            "1. Edit `src/lib.rs:12` and `.vizier/config.toml`.\n",
+        );
+        assert_eq!(
+            surface.planned.into_iter().collect::<Vec<_>>(),
+            vec!["src/lib.rs".to_string()]
+        );
+    }
+}
@@ -1,7 +1,7 @@
 #![allow(dead_code)]
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::fs;
 use std::io::Write;
 use std::path::{Path, PathBuf};
@@ -9,11 +9,17 @@ use uuid::Uuid;
 
 pub const PLAN_DIR: &str = ".vizier/implementation-plans";
 pub const PLAN_STATE_DIR: &str = ".vizier/state/plans";
+/// Operator notes for a plan (`vizier note`), kept on the plan branch beside its plan doc.
+pub const PLAN_NOTES_DIR: &str = ".vizier/notes";
 
 pub fn plan_rel_path(slug: &str) -> PathBuf {
     Path::new(PLAN_DIR).join(format!("{slug}.md"))
 }
 
+pub fn plan_notes_rel_path(slug: &str) -> PathBuf {
+    Path::new(PLAN_NOTES_DIR).join(format!("{slug}.md"))
+}
+
 pub fn plan_state_rel_path(plan_id: &str) -> PathBuf {
     Path::new(PLAN_STATE_DIR).join(format!("{plan_id}.json"))
 }
@@ -22,8 +28,51 @@ pub fn new_plan_id() -> String {
     format!("pln_{}", Uuid::new_v4().simple())
 }
 
+/// Plan branch for `slug` under the configured `[branches] prefix` (`draft/<slug>` by default).
 pub fn default_branch_for_slug(slug: &str) -> String {
-    format!("draft/{slug}")
+    let branches = crate::config::get_config().branches;
+    let user = if branches
+        .prefix
+        .contains(crate::config::BRANCH_USER_PLACEHOLDER)
+    {
+        branch_user()
+    } else {
+        String::new()
+    };
+    branches.branch_for_slug(slug, &user)
+}
+
+/// Slug of `branch` when it follows the configured `[branches] prefix`.
+pub fn slug_from_branch(branch: &str) -> Option<String> {
+    crate::config::get_config()
+        .branches
+        .slug_for_branch(branch)
+        .map(str::to_string)
+}
+
+/// Operator name for the `{user}` branch placeholder: the local part of git `user.email`, then
+/// git `user.name`, then `$USER`/`$USERNAME`, normalized like a slug.
+pub fn branch_user() -> String {
+    let git_config = git2::Repository::discover(".")
+        .and_then(|repo| repo.config())
+        .or_else(|_| git2::Config::open_default())
+        .ok();
+    let from_git = |key: &str| {
+        git_config
+            .as_ref()
+            .and_then(|config| config.get_string(key).ok())
+    };
+    [
+        from_git("user.email").map(|email| email.split('@').next().unwrap_or("").to_string()),
+        from_git("user.name"),
+        std::env::var("USER").ok(),
+        std::env::var("USERNAME").ok(),
+    ]
+    .into_iter()
+    .flatten()
+    .map(|candidate| normalize_slug(&candidate))
+    .find(|candidate| !candidate.is_empty())
+    .unwrap_or_else(|| "user".to_string())
 }
 
 fn normalize_slug(input: &str) -> String {
@@ -93,17 +142,394 @@ pub fn sanitize_name_override(raw: &str) -> Result<String, String> {
     }
 }
 
+/// `key: value` pairs from a leading `---` front-matter block; empty when there is none.
+pub fn front_matter_fields(document: &str) -> HashMap<String, String> {
+    let document = document.replace("\r\n", "\n");
+    let Some(rest) = document.strip_prefix("---\n") else {
+        return HashMap::new();
+    };
+    let Some(end) = rest
+        .find("\n---\n")
+        .or_else(|| rest.strip_suffix("\n---").map(str::len))
+    else {
+        return HashMap::new();
+    };
+    rest[..end]
+        .lines()
+        .filter_map(|line| line.trim().split_once(':'))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect()
+}
+
+/// Accepts `a, b`, `[a, b]`, and path-style links (`threads/a.md`); each becomes a thread slug.
+pub fn parse_thread_links(value: &str) -> Vec<String> {
+    let mut threads = Vec::new();
+    for link in value
+        .trim()
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .split(',')
+    {
+        let link = link.trim().trim_matches(['"', '\'']);
+        let link = link.rsplit('/').next().unwrap_or(link);
+        let link = link.strip_suffix(".md").unwrap_or(link);
+        if !link.is_empty() && !threads.iter().any(|thread| thread == link) {
+            threads.push(link.to_string());
+        }
+    }
+    threads
+}
+
+/// Normalizes a plan label: lowercase letters, digits, and `-_./:`, so labels stay valid in
+/// front matter lists and compare case-insensitively.
+pub fn sanitize_label(raw: &str) -> Result<String, String> {
+    let label = raw.trim().to_ascii_lowercase();
+    if label.is_empty() {
+        return Err("label cannot be empty".to_string());
+    }
+    match label
+        .chars()
+        .find(|ch| !(ch.is_ascii_alphanumeric() || "-_./:".contains(*ch)))
+    {
+        Some(ch) => Err(format!(
+            "label `{}` contains `{ch}`; use letters, digits, and -_./:",
+            raw.trim()
+        )),
+        None => Ok(label),
+    }
+}
+
+/// Labels from a `labels:` front-matter value (`[a, b]` or `a, b`). Entries that are not valid
+/// labels are skipped rather than failing the whole plan.
+pub fn parse_labels(value: &str) -> Vec<String> {
+    let mut labels = Vec::new();
+    for raw in value
+        .trim()
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .split(',')
+    {
+        if let Ok(label) = sanitize_label(raw.trim().trim_matches(['"', '\'']))
+            && !labels.contains(&label)
+        {
+            labels.push(label);
+        }
+    }
+    labels
+}
+
+/// Renders labels as a front-matter list value (`[a, b]`).
+pub fn format_labels(labels: &[String]) -> String {
+    format!("[{}]", labels.join(", "))
+}
+
+/// Parent plan slug from a `spawned-by:` front-matter value; `None` when it is not a valid slug.
+pub fn parse_spawned_by(value: &str) -> Option<String> {
+    sanitize_name_override(value.trim().trim_matches(['"', '\''])).ok()
+}
+
+/// The plan this document was filed as a follow-up of (`spawned-by:`, or `spawned_by:`).
+pub fn spawned_by(document: &str) -> Option<String> {
+    let fields = front_matter_fields(document);
+    fields
+        .get("spawned-by")
+        .or_else(|| fields.get("spawned_by"))
+        .and_then(|value| parse_spawned_by(value))
+}
+
+/// Whether an operator edited the drafted plan before it was committed (`human_amended: true`).
+pub fn human_amended(document: &str) -> bool {
+    front_matter_fields(document)
+        .get("human_amended")
+        .is_some_and(|value| value == "true")
+}
+
+/// Adds `human_amended: true` to a rendered plan document's front matter.
+pub fn mark_human_amended(document: &str) -> String {
+    if human_amended(document) {
+        return document.to_string();
+    }
+    match document
+        .strip_prefix("---\n")
+        .and_then(|rest| rest.find("\n---\n").map(|end| end + 4))
+    {
+        Some(end) => format!(
+            "{}\nhuman_amended: true{}",
+            &document[..end],
+            &document[end..]
+        ),
+        None => document.to_string(),
+    }
+}
+
+/// Appends one note to a plan's notes document as a `- <stamp> <author>: <note>` bullet, starting
+/// the document when there is none yet. Further lines of a multi-line note are indented under it.
+pub fn append_plan_note(
+    existing: Option<&str>,
+    slug: &str,
+    stamp: &str,
+    author: &str,
+    note: &str,
+) -> String {
+    let mut document = match existing.map(trim_trailing_newlines) {
+        Some(existing) if !existing.trim().is_empty() => format!("{existing}\n"),
+        _ => format!("# Operator notes: {slug}\n\n"),
+    };
+    let mut lines = note.trim().lines();
+    document.push_str(&format!(
+        "- {stamp} {author}: {}\n",
+        lines.next().unwrap_or_default().trim_end()
+    ));
+    for line in lines.map(str::trim_end) {
+        if !line.is_empty() {
+            document.push_str("  ");
+        }
+        document.push_str(line);
+        document.push('\n');
+    }
+    document
+}
+
+/// First `# ` heading of a plan document, if it has one.
+pub fn plan_title(plan_body: &str) -> Option<String> {
+    plan_body
+        .lines()
+        .find_map(|line| line.strip_prefix("# "))
+        .map(|title| title.trim().to_string())
+        .filter(|title| !title.is_empty())
+}
+
 pub fn trim_trailing_newlines(text: &str) -> &str {
     let trimmed = text.trim_end_matches(['\n', '\r']);
     if trimmed.is_empty() { "" } else { trimmed }
 }
 
+/// Rough size of a drafted plan: its top-level steps and the distinct repo paths it mentions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlanSize {
+    pub steps: usize,
+    pub files: usize,
+}
+
+/// Counts unindented ordered-list items and `###`+ headings as steps, and backticked tokens that
+/// look like paths (`src/lib.rs`, `Cargo.toml`) as files. Fenced code blocks are skipped.
+pub fn estimate_plan_size(plan_body: &str) -> PlanSize {
+    let (steps, files) = scan_plan(plan_body);
+    PlanSize {
+        steps,
+        files: files.len(),
+    }
+}
+
+/// Repo paths a plan mentions in backticks (the same paths [`estimate_plan_size`] counts); the
+/// surface a plan expects to touch before it has any commits.
+pub fn referenced_plan_paths(plan_body: &str) -> BTreeSet<String> {
+    scan_plan(plan_body).1
+}
+
+/// Titles of the steps under a plan's `## Execution Plan` section, counted the way
+/// [`estimate_plan_size`] counts them; entry `n - 1` names step `n`.
+pub fn execution_steps(plan_body: &str) -> Vec<String> {
+    let mut steps = Vec::new();
+    let mut in_section = false;
+    let mut in_fence = false;
+    for line in plan_body.lines() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+        if let Some(heading) = line.strip_prefix("## ") {
+            in_section = heading.trim().eq_ignore_ascii_case("execution plan");
+            continue;
+        }
+        if !in_section {
+            continue;
+        }
+        let title = if let Some(heading) = line.strip_prefix("###") {
+            heading.trim_start_matches('#')
+        } else if is_ordered_list_item(line) {
+            line.trim_start_matches(|c: char| c.is_ascii_digit())[1..].trim_start()
+        } else {
+            continue;
+        };
+        let title = title.replace("**", "");
+        let title = title.trim().trim_end_matches(':');
+        steps.push(match title.char_indices().nth(STEP_TITLE_CHARS) {
+            Some((cut, _)) => format!("{}…", title[..cut].trim_end()),
+            None => title.to_string(),
+        });
+    }
+    steps
+}
+
+const STEP_TITLE_CHARS: usize = 72;
+
+/// Instructions appended to the approve prompt when `[merge] squash_granularity = "step"`.
+pub const STEP_COMMIT_INSTRUCTIONS: &str = "\
+<stepCommits>
+Plan merges here land one commit per `## Execution Plan` step. Each time you finish a step, \
+commit everything for it before starting the next: `git add -A && git commit -m \"<what the step \
+did>\" -m \"Vizier-Step: <n>\"`, where `<n>` is the step's number in the plan. Leave work after \
+the last step uncommitted; Vizier commits it.
+</stepCommits>
+";
+
+fn scan_plan(plan_body: &str) -> (usize, BTreeSet<String>) {
+    let mut steps = 0;
+    let mut files = BTreeSet::new();
+    let mut in_fence = false;
+    for line in plan_body.lines() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+        if line.starts_with("###") || is_ordered_list_item(line) {
+            steps += 1;
+        }
+        for (index, span) in line.split('`').enumerate() {
+            if index % 2 == 1
+                && let Some(path) = referenced_path(span)
+            {
+                files.insert(path);
+            }
+        }
+    }
+    (steps, files)
+}
+
+fn is_ordered_list_item(line: &str) -> bool {
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    digits > 0
+        && line[digits..].starts_with(['.', ')'])
+        && line[digits + 1..].starts_with(char::is_whitespace)
+}
+
+fn referenced_path(span: &str) -> Option<String> {
+    let span = span.trim().trim_start_matches("./");
+    if span.is_empty() || span.contains(char::is_whitespace) || span.contains("::") {
+        return None;
+    }
+    // Drop `:line` / `:line:col` suffixes so references to one file count once.
+    let path = span
+        .split_once(':')
+        .map_or(span, |(path, _)| path)
+        .trim_end_matches('/');
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    let has_extension = file_name.rsplit_once('.').is_some_and(|(stem, ext)| {
+        !stem.is_empty()
+            && (1..=5).contains(&ext.len())
+            && ext.chars().all(|ch| ch.is_ascii_alphanumeric())
+    });
+    let looks_like_path = path.contains('/') || has_extension;
+    (looks_like_path && !path.starts_with("http") && !path.starts_with('-'))
+        .then(|| path.to_string())
+}
+
+/// Reasons `size` exceeds `[draft]` limits; empty when the plan fits a single approve run.
+pub fn plan_size_violations(size: PlanSize, limits: &crate::config::DraftConfig) -> Vec<String> {
+    let mut violations = Vec::new();
+    if limits.max_steps > 0 && size.steps > limits.max_steps as usize {
+        violations.push(format!("{} steps (limit {})", size.steps, limits.max_steps));
+    }
+    if limits.max_files > 0 && size.files > limits.max_files as usize {
+        violations.push(format!(
+            "{} referenced files (limit {})",
+            size.files, limits.max_files
+        ));
+    }
+    violations
+}
+
+/// Sizes recorded for earlier plans under `.vizier/state/plans`, oldest first.
+pub fn recorded_plan_sizes(repo_root: &Path) -> Vec<PlanSize> {
+    let Ok(entries) = fs::read_dir(repo_root.join(PLAN_STATE_DIR)) else {
+        return Vec::new();
+    };
+    let mut records = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| fs::read_to_string(entry.path()).ok())
+        .filter_map(|raw| serde_json::from_str::<PlanRecord>(&raw).ok())
+        .filter_map(|record| record.size.map(|size| (record.created_at, size)))
+        .collect::<Vec<_>>();
+    records.sort_by(|left, right| left.0.cmp(&right.0));
+    records.into_iter().map(|(_, size)| size).collect()
+}
+
+/// Marker comment that opens each part of a split plan: `<!-- vizier:plan <slug> -->`.
+pub const PLAN_SPLIT_MARKER: &str = "vizier:plan";
+
+/// Instructions appended to the draft prompt when the operator asks for a split plan.
+pub const PLAN_SPLIT_INSTRUCTIONS: &str = "\
+## Split Output
+The operator asked for this work to be split into several smaller plans that can each be \
+approved and merged on their own. Emit two to five plans, ordered so each one only depends on \
+the plans before it. Start every plan with a marker line `<!-- vizier:plan <short-slug> -->` \
+(lowercase words joined by `-`), followed by that plan's full Markdown in the format above. \
+Do not write anything before the first marker.
+";
+
+/// One plan of a split draft.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlanPart {
+    pub slug: Option<String>,
+    pub body: String,
+}
+
+/// Splits `plan_body` at `<!-- vizier:plan <slug> -->` markers. Text before the first marker
+/// joins the first part; a body without markers yields a single unnamed part.
+pub fn split_plan_parts(plan_body: &str) -> Vec<PlanPart> {
+    let mut parts: Vec<PlanPart> = Vec::new();
+    let mut preamble = String::new();
+    for line in plan_body.lines() {
+        let marker = line
+            .trim()
+            .strip_prefix("<!--")
+            .and_then(|rest| rest.strip_suffix("-->"))
+            .map(str::trim)
+            .and_then(|inner| inner.strip_prefix(PLAN_SPLIT_MARKER))
+            .filter(|rest| rest.is_empty() || rest.starts_with(char::is_whitespace));
+        if let Some(rest) = marker {
+            let slug = sanitize_name_override(rest).ok();
+            let body = if parts.is_empty() {
+                std::mem::take(&mut preamble)
+            } else {
+                String::new()
+            };
+            parts.push(PlanPart { slug, body });
+            continue;
+        }
+        let body = match parts.last_mut() {
+            Some(part) => &mut part.body,
+            None => &mut preamble,
+        };
+        body.push_str(line);
+        body.push('\n');
+    }
+    if parts.is_empty() {
+        parts.push(PlanPart {
+            slug: None,
+            body: preamble,
+        });
+    }
+    parts
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn render_plan_document(
     plan_id: &str,
     slug: &str,
     branch_name: &str,
     spec_text: &str,
     plan_body: &str,
+    depends_on: Option<&str>,
+    labels: &[String],
+    spawned_by: Option<&str>,
 ) -> String {
     let mut doc = String::new();
 
@@ -111,6 +537,15 @@ pub fn render_plan_document(
     doc.push_str(&format!("plan_id: {plan_id}\n"));
     doc.push_str(&format!("plan: {slug}\n"));
     doc.push_str(&format!("branch: {branch_name}\n"));
+    if let Some(depends_on) = depends_on {
+        doc.push_str(&format!("depends_on: {depends_on}\n"));
+    }
+    if !labels.is_empty() {
+        doc.push_str(&format!("labels: {}\n", format_labels(labels)));
+    }
+    if let Some(spawned_by) = spawned_by {
+        doc.push_str(&format!("spawned-by: {spawned_by}\n"));
+    }
     doc.push_str("---\n\n");
 
     doc.push_str("## Operator Spec\n");
@@ -167,6 +602,10 @@ pub struct PlanRecord {
     pub status: Option<String>,
     #[serde(default)]
     pub summary: Option<String>,
+    #[serde(default)]
+    pub size: Option<PlanSize>,
+    #[serde(default)]
+    pub depends_on: Option<String>,
     pub created_at: String,
     pub updated_at: String,
     #[serde(default)]
@@ -184,6 +623,8 @@ pub struct PlanRecordUpsert {
     pub work_ref: Option<String>,
     pub status: Option<String>,
     pub summary: Option<String>,
+    pub size: Option<PlanSize>,
+    pub depends_on: Option<String>,
     pub updated_at: String,
     pub created_at: Option<String>,
     pub job_ids: Option<HashMap<String, String>>,
@@ -217,6 +658,8 @@ pub fn upsert_plan_record(
             work_ref: None,
             status: None,
             summary: None,
+            size: None,
+            depends_on: None,
             created_at: update
                 .created_at
                 .clone()
@@ -258,6 +701,12 @@ pub fn upsert_plan_record(
     if update.summary.is_some() {
         record.summary = update.summary;
     }
+    if update.size.is_some() {
+        record.size = update.size;
+    }
+    if update.depends_on.is_some() {
+        record.depends_on = update.depends_on;
+    }
     if let Some(job_ids) = update.job_ids {
         for (phase, job_id) in job_ids {
             if !phase.trim().is_empty() && !job_id.trim().is_empty() {
@@ -270,3 +719,167 @@ pub fn upsert_plan_record(
     fs::write(&abs_path, contents)?;
     Ok(rel_path)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn estimate_plan_size_counts_steps_and_distinct_paths() {
+        let plan = "\
+## Execution Plan
+1. Update `vizier-core/src/plan.rs:42` and `vizier-core/src/plan.rs`.
+2. Add `Cargo.toml` features; keep `AgentSettings::build` and `--split` as-is.
+   1. nested detail is not a step
+### Docs
+- Touch `docs/user/config-reference.md`.
+```rust
+1. not a step in `src/ignored.rs`
+```
+";
+        assert_eq!(estimate_plan_size(plan), PlanSize { steps: 3, files: 3 });
+
+        let limits = crate::config::DraftConfig {
+            max_steps: 2,
+            max_files: 0,
+            auto_push: false,
+        };
+        assert_eq!(
+            plan_size_violations(estimate_plan_size(plan), &limits),
+            vec!["3 steps (limit 2)".to_string()]
+        );
+    }
+
+    #[test]
+    fn execution_steps_lists_titles_from_the_execution_plan_section() {
+        let plan = "\
+## Overview
+1. not a step outside the section
+## Execution Plan
+1. **Parse** the `[merge]` table:
+   1. nested detail is not a step
+2) Land commits
+### Docs
+```text
+3. fenced, ignored
+```
+## Risks
+1. also ignored
+";
+        assert_eq!(
+            execution_steps(plan),
+            vec![
+                "Parse the `[merge]` table".to_string(),
+                "Land commits".to_string(),
+                "Docs".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn plan_notes_start_a_document_and_indent_continuation_lines() {
+        let first = append_plan_note(
+            None,
+            "alpha",
+            "2026-10-17T09:00:00Z",
+            "ana",
+            "found a flaky test in ci",
+        );
+        assert_eq!(
+            first,
+            "# Operator notes: alpha\n\n- 2026-10-17T09:00:00Z ana: found a flaky test in ci\n"
+        );
+        let second = append_plan_note(
+            Some(&first),
+            "alpha",
+            "2026-10-17T10:30:00Z",
+            "bo",
+            "retry passes\n\nsee upload_test.rs\n",
+        );
+        assert_eq!(
+            second,
+            format!("{first}- 2026-10-17T10:30:00Z bo: retry passes\n\n  see upload_test.rs\n")
+        );
+    }
+
+    #[test]
+    fn labels_are_sanitized_and_rendered() {
+        assert_eq!(
+            sanitize_label(" Team/Backend ").as_deref(),
+            Ok("team/backend")
+        );
+        assert!(sanitize_label("needs review").is_err());
+        assert!(sanitize_label("  ").is_err());
+
+        let labels = parse_labels("[urgent, \"Backend\", urgent, bad label]");
+        assert_eq!(labels, vec!["urgent".to_string(), "backend".to_string()]);
+        assert_eq!(format_labels(&labels), "[urgent, backend]");
+        assert!(parse_labels("[]").is_empty());
+    }
+
+    #[test]
+    fn spawned_by_round_trips_through_front_matter() {
+        let doc = render_plan_document(
+            "pln_child",
+            "child",
+            "draft/child",
+            "spec",
+            "plan",
+            None,
+            &[],
+            Some("parent"),
+        );
+        assert!(doc.contains("spawned-by: parent\n"), "{doc}");
+        assert_eq!(spawned_by(&doc).as_deref(), Some("parent"));
+        assert_eq!(
+            spawned_by("---\nplan: child\nspawned_by: \"Parent Plan\"\n---\n").as_deref(),
+            Some("parent-plan")
+        );
+        assert_eq!(spawned_by("---\nplan: child\nspawned-by: a/b\n---\n"), None);
+        assert_eq!(spawned_by("---\nplan: child\n---\n"), None);
+    }
+
+    #[test]
+    fn mark_human_amended_extends_front_matter_once() {
+        let doc = render_plan_document(
+            "pln_alpha",
+            "alpha",
+            "draft/alpha",
+            "spec",
+            "plan",
+            None,
+            &[],
+            None,
+        );
+        assert!(!human_amended(&doc));
+        let amended = mark_human_amended(&doc);
+        assert!(
+            amended.starts_with("---\nplan_id: pln_alpha\nplan: alpha\nbranch: draft/alpha\nhuman_amended: true\n---\n"),
+            "{amended}"
+        );
+        assert!(human_amended(&amended));
+        assert_eq!(mark_human_amended(&amended), amended);
+        assert_eq!(mark_human_amended("no front matter"), "no front matter");
+    }
+
+    #[test]
+    fn split_plan_parts_breaks_on_markers() {
+        let parts = split_plan_parts(
+            "intro\n<!-- vizier:plan Schema Changes -->\nfirst\n<!--vizier:plan-->\nsecond\n",
+        );
+        assert_eq!(
+            parts,
+            vec![
+                PlanPart {
+                    slug: Some("schema-changes".to_string()),
+                    body: "intro\nfirst\n".to_string(),
+                },
+                PlanPart {
+                    slug: None,
+                    body: "second\n".to_string(),
+                },
+            ]
+        );
+        assert_eq!(split_plan_parts("plain plan\n").len(), 1);
+    }
+}
@@ -0,0 +1,292 @@
+//! Work on a plan branch that its stored plan document does not describe: commits pushed after
+//! Vizier last committed to the branch (typically by hand after approve), and changed paths the
+//! plan never mentions. Review prompts carry the result so scope creep is called out explicitly.
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use git2::{Commit, DiffOptions, Repository, Sort};
+use serde::Serialize;
+
+use crate::file_tracking::is_vizier_path;
+use crate::{config, plan, surfaces};
+
+/// Critique heading review agents list unplanned work under.
+pub const UNPLANNED_WORK_HEADING: &str = "Unplanned Work";
+const MAX_LISTED_PATHS: usize = 40;
+
+/// A branch commit made after the last commit carrying Vizier provenance.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct LateCommit {
+    pub sha: String,
+    pub author: String,
+    pub subject: String,
+    /// Paths the commit changed, `.vizier/` excluded.
+    pub paths: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct PlanScopeCheck {
+    /// Commits after Vizier's last commit on the branch, oldest first.
+    pub late_commits: Vec<LateCommit>,
+    /// Whether any branch commit carried Vizier provenance. Without it late commits cannot be
+    /// told apart, so none are reported.
+    pub provenance: bool,
+    /// Paths changed on the branch that the plan neither mentions nor covers with an expected
+    /// surface.
+    pub unplanned_paths: Vec<String>,
+}
+
+impl PlanScopeCheck {
+    pub fn is_clean(&self) -> bool {
+        self.late_commits.is_empty() && self.unplanned_paths.is_empty()
+    }
+
+    pub fn summary_line(&self) -> String {
+        format!(
+            "plan scope: {} commit(s) after Vizier's last commit{}; {} changed path(s) the plan does not mention",
+            self.late_commits.len(),
+            if self.provenance {
+                ""
+            } else {
+                " (no Vizier provenance on the branch)"
+            },
+            self.unplanned_paths.len()
+        )
+    }
+
+    /// The `<planScope>` block appended to review prompts for `branch`.
+    pub fn render(&self, branch: &str) -> String {
+        let mut block = String::from("<planScope>\n");
+        if !self.late_commits.is_empty() {
+            block.push_str(&format!(
+                "Commits pushed to `{branch}` after Vizier last committed to it (not produced by approve):\n"
+            ));
+            for commit in &self.late_commits {
+                block.push_str(&format!(
+                    "- {} {} — {}",
+                    commit.sha, commit.author, commit.subject
+                ));
+                if !commit.paths.is_empty() {
+                    block.push_str(&format!(" ({})", commit.paths.join(", ")));
+                }
+                block.push('\n');
+            }
+        }
+        if !self.unplanned_paths.is_empty() {
+            block.push_str("Changed paths the stored plan never mentions:\n");
+            for path in self.unplanned_paths.iter().take(MAX_LISTED_PATHS) {
+                block.push_str(&format!("- `{path}`\n"));
+            }
+            if self.unplanned_paths.len() > MAX_LISTED_PATHS {
+                block.push_str(&format!(
+                    "- … {} more\n",
+                    self.unplanned_paths.len() - MAX_LISTED_PATHS
+                ));
+            }
+        }
+        block.push_str(&format!(
+            "Compare this work with the plan. Under a `## {UNPLANNED_WORK_HEADING}` section, list each change the plan does not describe with what it does, and repeat any that should not merge as it stands under `Action Items`.\n</planScope>\n"
+        ));
+        block
+    }
+}
+
+/// Compares `head` with the plan document for `slug` committed on it, over the changes since the
+/// merge base with `target`. `None` when `head` carries no plan document.
+pub fn check_branch(
+    repo_path: &Path,
+    slug: &str,
+    target: &str,
+    head: &str,
+) -> Result<Option<PlanScopeCheck>, git2::Error> {
+    let repo = Repository::discover(repo_path)?;
+    let head = repo.revparse_single(head)?.peel_to_commit()?;
+    let Some(plan_body) = head
+        .tree()?
+        .get_path(&plan::plan_rel_path(slug))
+        .ok()
+        .and_then(|entry| repo.find_blob(entry.id()).ok())
+        .map(|blob| String::from_utf8_lossy(blob.content()).into_owned())
+    else {
+        return Ok(None);
+    };
+    let target = repo.revparse_single(target)?.peel_to_commit()?;
+    let base = repo.merge_base(target.id(), head.id())?;
+
+    let mut walk = repo.revwalk()?;
+    walk.push(head.id())?;
+    walk.hide(base)?;
+    walk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+    let session_label = config::get_config().commits.meta.labels.session_id;
+    let mut late_commits = Vec::new();
+    let mut provenance = false;
+    for oid in walk {
+        let commit = repo.find_commit(oid?)?;
+        if has_vizier_provenance(&repo, &commit, &session_label) {
+            provenance = true;
+            late_commits.clear();
+            continue;
+        }
+        let sha = commit.id().to_string();
+        late_commits.push(LateCommit {
+            sha: sha[..12].to_string(),
+            author: commit.author().name().unwrap_or("unknown").to_string(),
+            subject: commit.summary().unwrap_or_default().to_string(),
+            paths: changed_paths(&repo, &commit)?,
+        });
+    }
+    if !provenance {
+        late_commits.clear();
+    }
+
+    let base_tree = repo.find_commit(base)?.tree()?;
+    let diff = repo.diff_tree_to_tree(
+        Some(&base_tree),
+        Some(&head.tree()?),
+        Some(&mut DiffOptions::new()),
+    )?;
+    let referenced = plan::referenced_plan_paths(&plan_body);
+    let expected = surfaces::expected_surfaces(&plan_body);
+    let unplanned_paths = diff
+        .deltas()
+        .filter_map(|delta| delta.new_file().path().or_else(|| delta.old_file().path()))
+        .map(|path| path.to_string_lossy().into_owned())
+        .filter(|path| !is_vizier_path(path))
+        .filter(|path| !plan_describes(&plan_body, &referenced, &expected, path))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+
+    Ok(Some(PlanScopeCheck {
+        late_commits,
+        provenance,
+        unplanned_paths,
+    }))
+}
+
+/// Whether the plan names `path`, a directory holding it, or an expected surface covering it.
+pub fn plan_describes(
+    plan_body: &str,
+    referenced: &BTreeSet<String>,
+    expected: &[String],
+    path: &str,
+) -> bool {
+    referenced
+        .iter()
+        .any(|entry| entry == path || path.starts_with(&format!("{entry}/")))
+        || expected.iter().any(|entry| surfaces::covers(entry, path))
+        || plan_body.contains(path)
+}
+
+// Vizier commits carry the session field in their message (header or trailer style) or a
+// `refs/notes/vizier` note.
+fn has_vizier_provenance(repo: &Repository, commit: &Commit<'_>, session_label: &str) -> bool {
+    let marked = commit.message().unwrap_or_default().lines().any(|line| {
+        let line = line.trim_start();
+        ["Vizier-Plan:", "Vizier-Session:"]
+            .iter()
+            .any(|key| line.starts_with(key))
+            || (!session_label.is_empty()
+                && line
+                    .strip_prefix(session_label)
+                    .is_some_and(|rest| rest.starts_with(':')))
+    });
+    marked || matches!(crate::vcs::read_commit_note(repo, commit.id()), Ok(Some(_)))
+}
+
+fn changed_paths(repo: &Repository, commit: &Commit<'_>) -> Result<Vec<String>, git2::Error> {
+    let parent_tree = match commit.parent(0) {
+        Ok(parent) => Some(parent.tree()?),
+        Err(_) => None,
+    };
+    let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?;
+    Ok(diff
+        .deltas()
+        .filter_map(|delta| delta.new_file().path().or_else(|| delta.old_file().path()))
+        .map(|path| path.to_string_lossy().into_owned())
+        .filter(|path| !is_vizier_path(path))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn commit_file(repo: &Repository, path: &str, contents: &str, message: &str) -> git2::Oid {
+        let root = repo.workdir().expect("workdir");
+        let abs = root.join(path);
+        fs::create_dir_all(abs.parent().expect("parent")).expect("mkdir");
+        fs::write(&abs, contents).expect("write");
+        let mut index = repo.index().expect("index");
+        index.add_path(Path::new(path)).expect("add");
+        index.write().expect("write index");
+        let tree = repo
+            .find_tree(index.write_tree().expect("tree"))
+            .expect("find tree");
+        let signature = git2::Signature::now("Dana", "dana@example.com").expect("signature");
+        let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            parent.as_ref().into_iter().collect::<Vec<_>>().as_slice(),
+        )
+        .expect("commit")
+    }
+
+    #[test]
+    fn check_branch_reports_commits_after_vizier_and_unplanned_paths() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let repo = Repository::init(dir.path()).expect("init");
+        let base = commit_file(&repo, "README.md", "hello\n", "init");
+        repo.branch("main", &repo.find_commit(base).expect("base"), true)
+            .expect("main");
+        commit_file(
+            &repo,
+            ".vizier/implementation-plans/alpha.md",
+            "## Execution Plan\n1. Update `src/parser.rs` and `docs/user/`\n",
+            "docs: add plan alpha",
+        );
+        commit_file(
+            &repo,
+            "src/parser.rs",
+            "fn parse() {}\n",
+            "feat: parser\n\nVizier-Session: s-1\nVizier-Plan: alpha",
+        );
+        commit_file(
+            &repo,
+            "docs/user/parser.md",
+            "parser\n",
+            "docs: parser notes",
+        );
+        commit_file(&repo, "src/telemetry.rs", "fn ping() {}\n", "add telemetry");
+
+        let check = check_branch(dir.path(), "alpha", "main", "HEAD")
+            .expect("check")
+            .expect("plan exists");
+        assert!(check.provenance);
+        assert_eq!(
+            check
+                .late_commits
+                .iter()
+                .map(|commit| commit.subject.as_str())
+                .collect::<Vec<_>>(),
+            vec!["docs: parser notes", "add telemetry"]
+        );
+        assert_eq!(check.late_commits[1].paths, vec!["src/telemetry.rs"]);
+        assert_eq!(check.unplanned_paths, vec!["src/telemetry.rs"]);
+        let block = check.render("draft/alpha");
+        assert!(block.contains("- `src/telemetry.rs`"), "{block}");
+        assert!(block.contains("## Unplanned Work"), "{block}");
+
+        assert!(
+            check_branch(dir.path(), "beta", "main", "HEAD")
+                .expect("check")
+                .is_none()
+        );
+    }
+}
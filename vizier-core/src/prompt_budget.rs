@@ -0,0 +1 @@
+pub use vizier_kernel::prompt_budget::*;
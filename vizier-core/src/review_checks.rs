@@ -0,0 +1,277 @@
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde_json::json;
+
+use crate::agent::ReviewCheckContext;
+
+/// Outcome of one `[review.checks]` batch, in configured command order.
+#[derive(Debug, Clone)]
+pub struct ReviewChecksRun {
+    pub results: Vec<ReviewCheckContext>,
+    pub jobs: usize,
+    pub wall_ms: u128,
+}
+
+impl ReviewChecksRun {
+    pub fn passed(&self) -> usize {
+        self.results.iter().filter(|check| check.success).count()
+    }
+
+    pub fn failed(&self) -> usize {
+        self.results.len() - self.passed()
+    }
+
+    /// Pass/fail table with per-check durations, followed by the full check output blocks.
+    pub fn render(&self) -> String {
+        let mut text = self.summary_lines().join("\n");
+        text.push_str("\n\n");
+        text.push_str(&vizier_kernel::prompt::render_check_results(&self.results));
+        text
+    }
+
+    pub fn summary_lines(&self) -> Vec<String> {
+        let mut lines = vec![format!(
+            "Review checks: {} passed, {} failed ({} wall, {} job{})",
+            self.passed(),
+            self.failed(),
+            format_duration_ms(self.wall_ms),
+            self.jobs,
+            if self.jobs == 1 { "" } else { "s" }
+        )];
+        lines.extend(self.results.iter().map(|check| {
+            format!(
+                "- [{}] {} ({})",
+                if check.success { "pass" } else { "fail" },
+                check.command.trim(),
+                format_duration_ms(check.duration_ms)
+            )
+        }));
+        lines
+    }
+
+    /// Session-log payload recorded via `Auditor::record_operation`.
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "jobs": self.jobs,
+            "wall_ms": self.wall_ms,
+            "passed": self.passed(),
+            "failed": self.failed(),
+            "checks": self
+                .results
+                .iter()
+                .map(|check| json!({
+                    "command": check.command,
+                    "success": check.success,
+                    "exit_code": check.status_code,
+                    "duration_ms": check.duration_ms,
+                }))
+                .collect::<Vec<_>>(),
+        })
+    }
+}
+
+/// Resolves the concurrency for `count` checks; `configured == 0` uses the available parallelism.
+pub fn effective_jobs(configured: u32, count: usize) -> usize {
+    let jobs = match configured {
+        0 => thread::available_parallelism()
+            .map(|value| value.get())
+            .unwrap_or(1),
+        value => value as usize,
+    };
+    jobs.min(count).max(1)
+}
+
+/// Runs `commands` through `sh -c` in `root`, at most `jobs` at a time. Each output line is
+/// passed to `on_line` as it arrives, prefixed with a `check <n>/<total>` label so interleaved
+/// output stays attributable.
+pub fn run_review_checks(
+    root: &Path,
+    commands: &[String],
+    jobs: u32,
+    on_line: &(dyn Fn(&str) + Sync),
+) -> ReviewChecksRun {
+    let jobs = effective_jobs(jobs, commands.len());
+    let started = Instant::now();
+    let next = AtomicUsize::new(0);
+    let slots = Mutex::new(vec![None::<ReviewCheckContext>; commands.len()]);
+
+    thread::scope(|scope| {
+        for _ in 0..jobs {
+            scope.spawn(|| {
+                loop {
+                    let index = next.fetch_add(1, Ordering::SeqCst);
+                    let Some(command) = commands.get(index) else {
+                        break;
+                    };
+                    let label = format!("check {}/{}", index + 1, commands.len());
+                    let result = run_check(root, command, &label, on_line);
+                    if let Ok(mut slots) = slots.lock() {
+                        slots[index] = Some(result);
+                    }
+                }
+            });
+        }
+    });
+
+    ReviewChecksRun {
+        results: slots
+            .into_inner()
+            .unwrap_or_default()
+            .into_iter()
+            .flatten()
+            .collect(),
+        jobs,
+        wall_ms: started.elapsed().as_millis(),
+    }
+}
+
+fn run_check(
+    root: &Path,
+    command: &str,
+    label: &str,
+    on_line: &(dyn Fn(&str) + Sync),
+) -> ReviewCheckContext {
+    on_line(&format!("[{label}] $ {}", command.trim()));
+    let started = Instant::now();
+    let child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .current_dir(root)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn();
+    let mut child = match child {
+        Ok(child) => child,
+        Err(err) => {
+            let message = format!("failed to spawn check: {err}");
+            on_line(&format!("[{label}] {message}"));
+            return ReviewCheckContext {
+                command: command.to_string(),
+                status_code: None,
+                success: false,
+                duration_ms: started.elapsed().as_millis(),
+                stdout: String::new(),
+                stderr: message,
+            };
+        }
+    };
+
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    let (stdout, stderr) = thread::scope(|scope| {
+        let out = scope.spawn(|| stream_lines(stdout, label, on_line));
+        let err = scope.spawn(|| stream_lines(stderr, label, on_line));
+        (
+            out.join().unwrap_or_default(),
+            err.join().unwrap_or_default(),
+        )
+    });
+    let status = child.wait();
+    let duration_ms = started.elapsed().as_millis();
+    let (status_code, success) = match &status {
+        Ok(status) => (status.code(), status.success()),
+        Err(_) => (None, false),
+    };
+    on_line(&format!(
+        "[{label}] {} in {}",
+        if success { "passed" } else { "failed" },
+        format_duration_ms(duration_ms)
+    ));
+
+    ReviewCheckContext {
+        command: command.to_string(),
+        status_code,
+        success,
+        duration_ms,
+        stdout,
+        stderr,
+    }
+}
+
+fn stream_lines<R: Read>(
+    reader: Option<R>,
+    label: &str,
+    on_line: &(dyn Fn(&str) + Sync),
+) -> String {
+    let Some(reader) = reader else {
+        return String::new();
+    };
+    let mut captured = String::new();
+    for line in BufReader::new(reader).lines().map_while(Result::ok) {
+        on_line(&format!("[{label}] {line}"));
+        captured.push_str(&line);
+        captured.push('\n');
+    }
+    captured
+}
+
+fn format_duration_ms(ms: u128) -> String {
+    let duration = Duration::from_millis(ms as u64);
+    if duration.as_secs() >= 1 {
+        format!("{:.1}s", duration.as_secs_f64())
+    } else {
+        format!("{ms}ms")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn review_checks_run_concurrently_and_keep_command_order() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let commands = vec![
+            "sleep 0.4; echo first".to_string(),
+            "sleep 0.4; echo second >&2; exit 3".to_string(),
+            "echo third".to_string(),
+        ];
+        let lines = Mutex::new(Vec::new());
+        let run = run_review_checks(temp.path(), &commands, 3, &|line| {
+            lines.lock().unwrap().push(line.to_string())
+        });
+
+        assert_eq!(run.jobs, 3);
+        assert!(
+            run.wall_ms < 800,
+            "checks should overlap: {}ms",
+            run.wall_ms
+        );
+        assert_eq!(
+            run.results
+                .iter()
+                .map(|check| check.command.as_str())
+                .collect::<Vec<_>>(),
+            commands.iter().map(String::as_str).collect::<Vec<_>>()
+        );
+        assert_eq!((run.passed(), run.failed()), (2, 1));
+        assert_eq!(run.results[1].status_code, Some(3));
+        assert_eq!(run.results[1].stderr, "second\n");
+
+        let lines = lines.into_inner().unwrap();
+        assert!(
+            lines.contains(&"[check 1/3] first".to_string()),
+            "{lines:?}"
+        );
+        assert!(
+            lines.contains(&"[check 2/3] second".to_string()),
+            "{lines:?}"
+        );
+        assert!(run.summary_lines()[0].starts_with("Review checks: 2 passed, 1 failed"));
+    }
+
+    #[test]
+    fn effective_jobs_is_bounded_by_check_count() {
+        assert_eq!(effective_jobs(8, 2), 2);
+        assert_eq!(effective_jobs(1, 5), 1);
+        assert_eq!(effective_jobs(0, 0), 1);
+        assert!(effective_jobs(0, 64) >= 1);
+    }
+}
@@ -0,0 +1,157 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use git2::{Oid, Repository};
+use serde::{Deserialize, Serialize};
+
+use crate::vcs::{self, RangeDiff, RangeDiffStatus};
+
+pub const REVIEW_LEDGER_DIR: &str = crate::temp_registry::REVIEW_LEDGER_DIR;
+
+/// The branch tip a reviewer last saw, so the next review can focus on what changed since.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReviewedTip {
+    pub branch: String,
+    pub target: String,
+    pub tip: String,
+    pub recorded_at: String,
+}
+
+/// What changed on a branch since its last recorded review.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReviewDelta {
+    FirstReview,
+    Unchanged {
+        tip: String,
+    },
+    Changed {
+        previous: String,
+        range_diff: RangeDiff,
+    },
+}
+
+pub fn ledger_path(project_root: &Path, branch: &str) -> PathBuf {
+    project_root
+        .join(REVIEW_LEDGER_DIR)
+        .join(format!("{}.json", branch.replace('/', "__")))
+}
+
+pub fn load_reviewed_tip(project_root: &Path, branch: &str) -> io::Result<Option<ReviewedTip>> {
+    let path = ledger_path(project_root, branch);
+    match fs::read(&path) {
+        Ok(bytes) => serde_json::from_slice(&bytes)
+            .map(Some)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+pub fn record_reviewed_tip(
+    project_root: &Path,
+    branch: &str,
+    target: &str,
+    tip: Oid,
+) -> io::Result<()> {
+    let path = ledger_path(project_root, branch);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let entry = ReviewedTip {
+        branch: branch.to_string(),
+        target: target.to_string(),
+        tip: tip.to_string(),
+        recorded_at: Utc::now().to_rfc3339(),
+    };
+    let bytes = serde_json::to_vec_pretty(&entry)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    fs::write(path, bytes)
+}
+
+/// Project root that owns the ledger for `repo_path`, which may be a linked worktree.
+pub fn ledger_root(repo_path: &Path) -> Result<PathBuf, git2::Error> {
+    let repo = Repository::open(repo_path)?;
+    let common = repo.commondir().to_path_buf();
+    Ok(common.parent().map(Path::to_path_buf).unwrap_or(common))
+}
+
+/// Compares `branch` against the tip recorded at its last review. A previous tip that no longer
+/// exists (e.g. garbage-collected after a rebase) is treated as a first review.
+pub fn review_delta(
+    repo_path: &Path,
+    project_root: &Path,
+    target: &str,
+    branch: &str,
+) -> Result<(ReviewDelta, Oid), Box<dyn std::error::Error>> {
+    let Some(previous) = load_reviewed_tip(project_root, branch)? else {
+        let repo = Repository::open(repo_path)?;
+        let tip = repo.revparse_single(branch)?.peel_to_commit()?.id();
+        return Ok((ReviewDelta::FirstReview, tip));
+    };
+    review_delta_since(repo_path, target, branch, &previous.tip)
+}
+
+/// Compares `branch` against an explicit `baseline` commit (`vizier run --baseline`) instead of
+/// the ledger's recorded tip.
+pub fn review_delta_since(
+    repo_path: &Path,
+    target: &str,
+    branch: &str,
+    baseline: &str,
+) -> Result<(ReviewDelta, Oid), Box<dyn std::error::Error>> {
+    let repo = Repository::open(repo_path)?;
+    let tip = repo.revparse_single(branch)?.peel_to_commit()?.id();
+    let Some(previous_tip) = Oid::from_str(baseline)
+        .ok()
+        .filter(|oid| repo.find_commit(*oid).is_ok())
+    else {
+        return Ok((ReviewDelta::FirstReview, tip));
+    };
+    if previous_tip == tip {
+        return Ok((
+            ReviewDelta::Unchanged {
+                tip: tip.to_string(),
+            },
+            tip,
+        ));
+    }
+    let range_diff = vcs::range_diff_in(repo_path, target, previous_tip, tip)?;
+    Ok((
+        ReviewDelta::Changed {
+            previous: previous_tip.to_string(),
+            range_diff,
+        },
+        tip,
+    ))
+}
+
+pub fn render_review_delta(delta: &ReviewDelta, branch: &str) -> String {
+    match delta {
+        ReviewDelta::FirstReview => {
+            format!("(no previous review recorded for `{branch}`; review the full diff)\n")
+        }
+        ReviewDelta::Unchanged { tip } => {
+            format!(
+                "(`{branch}` is unchanged since the last review at {})\n",
+                &tip[..7]
+            )
+        }
+        ReviewDelta::Changed {
+            previous,
+            range_diff,
+        } => {
+            let mut text = format!(
+                "Range-diff of `{branch}` since the last review at {} ({} unchanged, {} modified, {} added, {} dropped):\n",
+                &previous[..7],
+                range_diff.count(RangeDiffStatus::Unchanged),
+                range_diff.count(RangeDiffStatus::Modified),
+                range_diff.count(RangeDiffStatus::Added),
+                range_diff.count(RangeDiffStatus::Removed),
+            );
+            text.push_str(&range_diff.render());
+            text
+        }
+    }
+}
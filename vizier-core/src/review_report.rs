@@ -0,0 +1,435 @@
+use serde::Serialize;
+
+/// How urgent a review action item is, from its leading `[severity]` tag.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Blocker,
+    Major,
+    Minor,
+    Nit,
+    Unspecified,
+}
+
+impl Severity {
+    fn parse(tag: &str) -> Option<Self> {
+        match tag.trim().to_ascii_lowercase().as_str() {
+            "blocker" | "critical" => Some(Self::Blocker),
+            "major" | "high" => Some(Self::Major),
+            "minor" | "medium" | "low" => Some(Self::Minor),
+            "nit" | "nitpick" => Some(Self::Nit),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Blocker => "blocker",
+            Self::Major => "major",
+            Self::Minor => "minor",
+            Self::Nit => "nit",
+            Self::Unspecified => "unspecified",
+        }
+    }
+}
+
+/// A `path`, `path:line`, or `path:start-end` reference found in an action item.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct FileAnchor {
+    pub path: String,
+    pub line: Option<u32>,
+    pub end_line: Option<u32>,
+}
+
+impl std::fmt::Display for FileAnchor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.path)?;
+        if let Some(line) = self.line {
+            write!(f, ":{line}")?;
+        }
+        if let Some(end) = self.end_line {
+            write!(f, "-{end}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ActionItem {
+    pub severity: Severity,
+    /// The bullet text with the severity tag removed.
+    pub text: String,
+    pub anchors: Vec<FileAnchor>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ReviewSection {
+    pub title: String,
+    pub body: String,
+}
+
+/// A review critique split into its `##` sections, with the `Action Items` bullets parsed into
+/// severity and file anchors for tooling.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct ReviewReport {
+    pub sections: Vec<ReviewSection>,
+    pub action_items: Vec<ActionItem>,
+}
+
+impl ReviewReport {
+    pub fn parse(markdown: &str) -> Self {
+        let mut sections = Vec::new();
+        let mut title = String::new();
+        let mut body = Vec::new();
+        for line in markdown.lines() {
+            if let Some(heading) = heading_title(line) {
+                push_section(&mut sections, &title, &body);
+                title = heading;
+                body.clear();
+            } else {
+                body.push(line);
+            }
+        }
+        push_section(&mut sections, &title, &body);
+
+        let action_items = sections
+            .iter()
+            .filter(|section| is_action_items(&section.title))
+            .flat_map(|section| parse_action_items(&section.body))
+            .collect();
+        Self {
+            sections,
+            action_items,
+        }
+    }
+
+    /// `approved` when there are no action items, otherwise `changes requested` with a count
+    /// per severity, e.g. `changes requested (1 blocker, 2 minor)`.
+    pub fn verdict(&self) -> String {
+        if self.action_items.is_empty() {
+            return "approved".to_string();
+        }
+        let counts = [
+            Severity::Blocker,
+            Severity::Major,
+            Severity::Minor,
+            Severity::Nit,
+            Severity::Unspecified,
+        ]
+        .into_iter()
+        .filter_map(|severity| {
+            let count = self
+                .action_items
+                .iter()
+                .filter(|item| item.severity == severity)
+                .count();
+            (count > 0).then(|| format!("{count} {}", severity.as_str()))
+        })
+        .collect::<Vec<_>>();
+        format!("changes requested ({})", counts.join(", "))
+    }
+
+    /// Sections as written, with Action Items normalized to `- **severity** text`.
+    pub fn render_markdown(&self) -> String {
+        let mut out = String::new();
+        for section in &self.sections {
+            if !section.title.is_empty() {
+                out.push_str(&format!("## {}\n\n", section.title));
+            }
+            if is_action_items(&section.title) {
+                for item in &self.action_items {
+                    out.push_str(&format!("- **{}** {}\n", item.severity.as_str(), item.text));
+                }
+            } else if !section.body.is_empty() {
+                out.push_str(&section.body);
+                out.push('\n');
+            }
+            out.push('\n');
+        }
+        out.trim_end().to_string()
+    }
+
+    /// Same content without Markdown syntax, for logs and terminals.
+    pub fn render_plain(&self) -> String {
+        let mut out = String::new();
+        for section in &self.sections {
+            if !section.title.is_empty() {
+                out.push_str(&format!("{}\n", section.title));
+            }
+            if is_action_items(&section.title) {
+                for item in &self.action_items {
+                    out.push_str(&format!(
+                        "  [{}] {}\n",
+                        item.severity.as_str(),
+                        strip_inline_markup(&item.text)
+                    ));
+                }
+            } else {
+                for line in section.body.lines() {
+                    let line = line.trim_start_matches('#').trim_end();
+                    if line.trim().is_empty() {
+                        out.push('\n');
+                    } else {
+                        out.push_str(&format!("  {}\n", strip_inline_markup(line)));
+                    }
+                }
+            }
+            out.push('\n');
+        }
+        out.trim_end().to_string()
+    }
+}
+
+fn heading_title(line: &str) -> Option<String> {
+    let rest = line
+        .strip_prefix("## ")
+        .or_else(|| line.strip_prefix("# "))?;
+    Some(rest.trim().trim_end_matches(':').trim().to_string())
+}
+
+fn push_section(sections: &mut Vec<ReviewSection>, title: &str, body: &[&str]) {
+    let body = body.join("\n").trim().to_string();
+    if title.is_empty() && body.is_empty() {
+        return;
+    }
+    sections.push(ReviewSection {
+        title: title.to_string(),
+        body,
+    });
+}
+
+fn is_action_items(title: &str) -> bool {
+    title.eq_ignore_ascii_case("action items")
+}
+
+fn parse_action_items(body: &str) -> Vec<ActionItem> {
+    let mut bullets: Vec<String> = Vec::new();
+    for line in body.lines() {
+        let trimmed = line.trim();
+        if let Some(text) = bullet_text(line) {
+            bullets.push(text.to_string());
+        } else if !trimmed.is_empty()
+            && line.starts_with([' ', '\t'])
+            && let Some(last) = bullets.last_mut()
+        {
+            last.push(' ');
+            last.push_str(trimmed);
+        }
+    }
+    bullets
+        .into_iter()
+        .map(|bullet| {
+            let (severity, text) = split_severity(&bullet);
+            ActionItem {
+                severity,
+                anchors: find_anchors(&text),
+                text,
+            }
+        })
+        .collect()
+}
+
+// Top-level `-`, `*`, or `1.` bullets only; indented bullets continue the previous item.
+fn bullet_text(line: &str) -> Option<&str> {
+    if let Some(rest) = line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")) {
+        return Some(rest.trim());
+    }
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    if digits > 0 {
+        return line[digits..]
+            .strip_prefix(". ")
+            .or_else(|| line[digits..].strip_prefix(") "))
+            .map(str::trim);
+    }
+    None
+}
+
+// Accepts `[major]`, `(major)`, `**major**`, and `major:` prefixes.
+fn split_severity(bullet: &str) -> (Severity, String) {
+    let candidates = [("[", "]"), ("(", ")"), ("**", "**")];
+    for (open, close) in candidates {
+        if let Some(rest) = bullet.strip_prefix(open)
+            && let Some(end) = rest.find(close)
+            && let Some(severity) = Severity::parse(rest[..end].trim_end_matches(':'))
+        {
+            let text = rest[end + close.len()..].trim_start_matches(':');
+            return (severity, text.trim().to_string());
+        }
+    }
+    if let Some((tag, text)) = bullet.split_once(':')
+        && let Some(severity) = Severity::parse(tag)
+    {
+        return (severity, text.trim().to_string());
+    }
+    (Severity::Unspecified, bullet.to_string())
+}
+
+fn find_anchors(text: &str) -> Vec<FileAnchor> {
+    let mut anchors: Vec<FileAnchor> = Vec::new();
+    let mut candidates = Vec::new();
+    // Backticked spans may be bare paths; elsewhere only `path:line` counts, so prose like
+    // "e.g." or version numbers are not mistaken for files.
+    for (index, span) in text.split('`').enumerate() {
+        if index % 2 == 1 {
+            if !span.chars().any(char::is_whitespace) {
+                candidates.push((span, true));
+            }
+        } else {
+            candidates.extend(span.split_whitespace().map(|word| (word, false)));
+        }
+    }
+    for (candidate, backticked) in candidates {
+        let token = candidate.trim_matches(|ch: char| {
+            matches!(ch, ',' | ';' | '(' | ')' | '[' | ']' | '"' | '\'' | '*')
+        });
+        let token = token.strip_suffix('.').unwrap_or(token);
+        if let Some(anchor) = parse_anchor(token, backticked)
+            && !anchors.contains(&anchor)
+        {
+            anchors.push(anchor);
+        }
+    }
+    anchors
+}
+
+fn parse_anchor(token: &str, backticked: bool) -> Option<FileAnchor> {
+    if token.contains("://") {
+        return None;
+    }
+    let (path, lines) = match token.split_once(':') {
+        Some((path, lines)) => (path, Some(lines)),
+        None => (token, None),
+    };
+    let (line, end_line) = match lines {
+        Some(lines) => {
+            let lines = lines.trim_start_matches('L');
+            let (start, end) = match lines.split_once('-') {
+                Some((start, end)) => (start, Some(end.trim_start_matches('L'))),
+                None => (lines, None),
+            };
+            let start = start.parse::<u32>().ok()?;
+            let end = match end {
+                Some(end) => Some(end.parse::<u32>().ok()?),
+                None => None,
+            };
+            (Some(start), end)
+        }
+        None if backticked => (None, None),
+        None => return None,
+    };
+    if !looks_like_path(path) {
+        return None;
+    }
+    Some(FileAnchor {
+        path: path.trim_start_matches("./").to_string(),
+        line,
+        end_line,
+    })
+}
+
+fn looks_like_path(path: &str) -> bool {
+    if path.is_empty()
+        || !path
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '/' | '.' | '_' | '-'))
+    {
+        return false;
+    }
+    let name = path.rsplit('/').next().unwrap_or(path);
+    let has_extension = name.rsplit_once('.').is_some_and(|(stem, ext)| {
+        !stem.is_empty() && !ext.is_empty() && ext.chars().any(|ch| ch.is_ascii_alphabetic())
+    });
+    has_extension || (path.contains('/') && !path.starts_with('-'))
+}
+
+fn strip_inline_markup(text: &str) -> String {
+    text.replace("**", "").replace('`', "")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CRITIQUE: &str = "## Plan Alignment
+✅ Matches the plan.
+
+## Tests & Build
+`cargo test` passed.
+
+## Action Items
+- [blocker] `vizier-core/src/jobs/mod.rs:120-134` drops the lock before persisting.
+- **Minor**: add coverage in tests/src/run.rs:88 for the retry path,
+  including the `--continue` flag.
+- Align docs/user/config-reference.md with the new flag (e.g. the example).
+- Consider renaming the helper.
+";
+
+    #[test]
+    fn parse_extracts_sections_severity_and_anchors() {
+        let report = ReviewReport::parse(CRITIQUE);
+        assert_eq!(
+            report
+                .sections
+                .iter()
+                .map(|section| section.title.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Plan Alignment", "Tests & Build", "Action Items"]
+        );
+        assert_eq!(report.action_items.len(), 4);
+
+        let first = &report.action_items[0];
+        assert_eq!(first.severity, Severity::Blocker);
+        assert_eq!(
+            first.anchors,
+            vec![FileAnchor {
+                path: "vizier-core/src/jobs/mod.rs".to_string(),
+                line: Some(120),
+                end_line: Some(134),
+            }]
+        );
+
+        let second = &report.action_items[1];
+        assert_eq!(second.severity, Severity::Minor);
+        assert!(second.text.ends_with("including the `--continue` flag."));
+        assert_eq!(second.anchors[0].to_string(), "tests/src/run.rs:88");
+        assert_eq!(second.anchors.len(), 1, "{:?}", second.anchors);
+
+        let third = &report.action_items[2];
+        assert_eq!(third.severity, Severity::Unspecified);
+        assert!(third.anchors.is_empty(), "bare prose paths need a line");
+        assert!(report.action_items[3].anchors.is_empty());
+        assert_eq!(
+            report.verdict(),
+            "changes requested (1 blocker, 1 minor, 2 unspecified)"
+        );
+        assert_eq!(
+            ReviewReport::parse("## Summary\nLooks good.\n").verdict(),
+            "approved"
+        );
+    }
+
+    #[test]
+    fn renderers_normalize_action_items() {
+        let report = ReviewReport::parse(CRITIQUE);
+        let markdown = report.render_markdown();
+        assert!(markdown.starts_with("## Plan Alignment\n\n✅ Matches the plan."));
+        assert!(
+            markdown.contains(
+                "- **blocker** `vizier-core/src/jobs/mod.rs:120-134` drops the lock before persisting.\n"
+            ),
+            "{markdown}"
+        );
+        assert!(markdown.contains("- **unspecified** Consider renaming the helper."));
+
+        let plain = report.render_plain();
+        assert!(
+            plain.contains("Tests & Build\n  cargo test passed."),
+            "{plain}"
+        );
+        assert!(
+            plain.contains("  [minor] add coverage in tests/src/run.rs:88 for the retry path"),
+            "{plain}"
+        );
+        assert!(!plain.contains("**"));
+    }
+}
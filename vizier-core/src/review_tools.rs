@@ -0,0 +1,331 @@
+//! Repo-navigation tools offered to review agents so a critique can check the code around a diff
+//! instead of guessing. Each tool is a `vizier tool <name>` subcommand the agent runs from its
+//! worktree; the registry below is what the review prompt advertises. Calls are appended to the
+//! file named by [`TOOL_LOG_ENV`] and recorded in the reviewing job's session afterwards.
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use git2::{BlameOptions, Repository};
+use serde::{Deserialize, Serialize};
+
+use crate::never_send::{NeverSend, record_withheld};
+
+/// Set on review agents to the JSONL file their tool calls are appended to.
+pub const TOOL_LOG_ENV: &str = "VIZIER_TOOL_LOG";
+
+/// Lines `file` prints when no range is given, and the most any range may span.
+pub const MAX_SLICE_LINES: usize = 400;
+/// Matches `search` prints when no limit is given.
+pub const DEFAULT_SEARCH_LIMIT: usize = 50;
+const MAX_MATCH_CHARS: usize = 200;
+
+/// One tool in the review registry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RepoTool {
+    pub name: &'static str,
+    /// Arguments after `vizier tool <name>`.
+    pub usage: &'static str,
+    pub summary: &'static str,
+}
+
+pub const REVIEW_TOOLS: &[RepoTool] = &[
+    RepoTool {
+        name: "file",
+        usage: "<path> [--lines <start>:<end>]",
+        summary: "print a numbered slice of a file as it is in the worktree",
+    },
+    RepoTool {
+        name: "blame",
+        usage: "<path> --lines <start>:<end>",
+        summary: "show the commit, author, and date that last changed each committed line",
+    },
+    RepoTool {
+        name: "search",
+        usage: "<text> [--limit <n>]",
+        summary: "list tracked-file lines containing the text as path:line: text",
+    },
+];
+
+/// The `<reviewTools>` block appended to review prompts; `binary` is the vizier executable the
+/// agent should call.
+pub fn prompt_block(binary: &Path) -> String {
+    let mut block = String::from(
+        "<reviewTools>\nCheck the code around the diff before flagging it. These read-only commands run from the repository root:\n",
+    );
+    for tool in REVIEW_TOOLS {
+        block.push_str(&format!(
+            "- `{} tool {} {}`: {}\n",
+            binary.display(),
+            tool.name,
+            tool.usage,
+            tool.summary
+        ));
+    }
+    block.push_str("</reviewTools>\n");
+    block
+}
+
+/// Parses `<start>:<end>` (1-based, inclusive).
+pub fn parse_line_range(raw: &str) -> Result<(usize, usize), String> {
+    let invalid = || format!("invalid line range `{raw}`; expected <start>:<end>");
+    let (start, end) = raw.trim().split_once(':').ok_or_else(invalid)?;
+    let start = start.trim().parse::<usize>().map_err(|_| invalid())?;
+    let end = end.trim().parse::<usize>().map_err(|_| invalid())?;
+    if start == 0 || end < start {
+        return Err(invalid());
+    }
+    if end - start >= MAX_SLICE_LINES {
+        return Err(format!(
+            "line range `{raw}` spans more than {MAX_SLICE_LINES} lines"
+        ));
+    }
+    Ok((start, end))
+}
+
+/// Lines `start..=end` of `path` (the first [`MAX_SLICE_LINES`] without a range), each prefixed
+/// with its line number.
+pub fn file_slice(
+    repo_root: &Path,
+    path: &str,
+    lines: Option<(usize, usize)>,
+) -> Result<String, String> {
+    let (rel, abs) = resolve_repo_path(repo_root, path)?;
+    let text = fs::read_to_string(&abs).map_err(|err| format!("cannot read {rel}: {err}"))?;
+    let (start, end) = lines.unwrap_or((1, MAX_SLICE_LINES));
+    let total = text.lines().count();
+    if start > total.max(1) {
+        return Err(format!("{rel} has {total} lines"));
+    }
+    let mut out = String::new();
+    for (index, line) in text
+        .lines()
+        .enumerate()
+        .skip(start - 1)
+        .take(end - start + 1)
+    {
+        out.push_str(&format!("{:>5}  {line}\n", index + 1));
+    }
+    if lines.is_none() && total > end {
+        out.push_str(&format!(
+            "[{} more lines; pass --lines to see them]\n",
+            total - end
+        ));
+    }
+    Ok(out)
+}
+
+/// Blame for lines `start..=end` of the committed `path`, one `<sha> <date> <author> <line>: <text>`
+/// row per line.
+pub fn blame_range(repo_root: &Path, path: &str, lines: (usize, usize)) -> Result<String, String> {
+    let (rel, _) = resolve_repo_path(repo_root, path)?;
+    let repo = Repository::discover(repo_root).map_err(|err| err.to_string())?;
+    let committed = repo
+        .revparse_single(&format!("HEAD:{rel}"))
+        .and_then(|object| object.peel_to_blob())
+        .map_err(|_| format!("{rel} is not committed at HEAD"))?;
+    let text = String::from_utf8_lossy(committed.content()).into_owned();
+    let total = text.lines().count();
+    let (start, end) = (lines.0, lines.1.min(total));
+    if start > end {
+        return Err(format!("{rel} has {total} committed lines"));
+    }
+    let mut options = BlameOptions::new();
+    options.min_line(start).max_line(end);
+    let blame = repo
+        .blame_file(Path::new(&rel), Some(&mut options))
+        .map_err(|err| format!("cannot blame {rel}: {err}"))?;
+
+    let mut out = String::new();
+    for (index, line) in text
+        .lines()
+        .enumerate()
+        .skip(start - 1)
+        .take(end - start + 1)
+    {
+        let number = index + 1;
+        let (sha, date, author) = match blame.get_line(number) {
+            Some(hunk) => {
+                let signature = hunk.final_signature();
+                let date = chrono::DateTime::from_timestamp(signature.when().seconds(), 0)
+                    .map(|when| when.format("%Y-%m-%d").to_string())
+                    .unwrap_or_default();
+                let sha = hunk.final_commit_id().to_string();
+                (
+                    sha[..sha.len().min(12)].to_string(),
+                    date,
+                    signature.name().unwrap_or("unknown").to_string(),
+                )
+            }
+            None => ("-".to_string(), String::new(), String::new()),
+        };
+        out.push_str(&format!("{sha} {date} {author} {number}: {line}\n"));
+    }
+    Ok(out)
+}
+
+/// Tracked-file lines containing `needle` (case-sensitive), as `path:line: text`, stopping after
+/// `limit` matches. Binary files and `[context] never_send` paths are skipped.
+pub fn search(repo_root: &Path, needle: &str, limit: usize) -> Result<String, String> {
+    if needle.is_empty() {
+        return Err("search text is empty".to_string());
+    }
+    let repo = Repository::discover(repo_root).map_err(|err| err.to_string())?;
+    let index = repo.index().map_err(|err| err.to_string())?;
+    let never_send = NeverSend::from_config(repo_root);
+    let mut withheld = Vec::new();
+    let mut matches = Vec::new();
+    let mut truncated = false;
+    'entries: for entry in index.iter() {
+        let rel = String::from_utf8_lossy(&entry.path).into_owned();
+        if never_send.withholds(Path::new(&rel)) {
+            withheld.push(rel);
+            continue;
+        }
+        let Ok(bytes) = fs::read(repo_root.join(&rel)) else {
+            continue;
+        };
+        if bytes.contains(&0) {
+            continue;
+        }
+        for (index, line) in String::from_utf8_lossy(&bytes).lines().enumerate() {
+            if !line.contains(needle) {
+                continue;
+            }
+            if matches.len() == limit {
+                truncated = true;
+                break 'entries;
+            }
+            let line = line.trim();
+            let shown = match line.char_indices().nth(MAX_MATCH_CHARS) {
+                Some((cut, _)) => format!("{}…", &line[..cut]),
+                None => line.to_string(),
+            };
+            matches.push(format!("{rel}:{}: {shown}", index + 1));
+        }
+    }
+    record_withheld("tool.search", &withheld);
+
+    let mut out = matches.join("\n");
+    if matches.is_empty() {
+        out.push_str(&format!("no tracked file contains `{needle}`"));
+    }
+    out.push('\n');
+    if truncated {
+        out.push_str(&format!(
+            "[stopped after {limit} matches; narrow the text or raise --limit]\n"
+        ));
+    }
+    Ok(out)
+}
+
+// Repo-relative form of `path`, refusing paths outside the repository and withheld ones.
+fn resolve_repo_path(repo_root: &Path, path: &str) -> Result<(String, PathBuf), String> {
+    let root = repo_root
+        .canonicalize()
+        .map_err(|err| format!("cannot resolve repository root: {err}"))?;
+    let candidate = if Path::new(path).is_absolute() {
+        PathBuf::from(path)
+    } else {
+        root.join(path)
+    };
+    let abs = candidate
+        .canonicalize()
+        .map_err(|err| format!("cannot open {path}: {err}"))?;
+    let rel = abs
+        .strip_prefix(&root)
+        .map_err(|_| format!("{path} is outside the repository"))?
+        .to_string_lossy()
+        .replace('\\', "/");
+    if NeverSend::from_config(&root).withholds(Path::new(&rel)) {
+        record_withheld("tool.file", std::slice::from_ref(&rel));
+        return Err(format!("{rel} is withheld by [context] never_send"));
+    }
+    Ok((rel, abs))
+}
+
+/// One `vizier tool` invocation, as appended to the [`TOOL_LOG_ENV`] file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolCall {
+    pub tool: String,
+    pub args: Vec<String>,
+    pub ok: bool,
+    pub at: String,
+}
+
+/// Appends `tool`'s call to the [`TOOL_LOG_ENV`] file when the caller runs under a review agent.
+pub fn log_call(tool: &str, args: &[String], ok: bool) -> io::Result<()> {
+    let Some(path) = std::env::var_os(TOOL_LOG_ENV).filter(|path| !path.is_empty()) else {
+        return Ok(());
+    };
+    let call = ToolCall {
+        tool: tool.to_string(),
+        args: args.to_vec(),
+        ok,
+        at: Utc::now().to_rfc3339(),
+    };
+    let mut line = serde_json::to_string(&call).map_err(io::Error::other)?;
+    line.push('\n');
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(line.as_bytes())
+}
+
+/// Calls logged to `path`; a missing file means none were made.
+pub fn read_calls(path: &Path) -> Vec<ToolCall> {
+    fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_slices_search_and_blame_read_the_repository() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let root = dir.path();
+        let repo = Repository::init(root).expect("init repo");
+        fs::write(
+            root.join("lib.rs"),
+            "fn alpha() {}\nfn beta() {}\nfn gamma() {}\n",
+        )
+        .expect("write file");
+        let mut index = repo.index().expect("index");
+        index.add_path(Path::new("lib.rs")).expect("add");
+        index.write().expect("write index");
+        let tree = repo
+            .find_tree(index.write_tree().expect("tree"))
+            .expect("find tree");
+        let signature = git2::Signature::now("Ana", "ana@example.com").expect("signature");
+        let oid = repo
+            .commit(Some("HEAD"), &signature, &signature, "init", &tree, &[])
+            .expect("commit");
+
+        assert_eq!(
+            file_slice(root, "lib.rs", Some((2, 3))).expect("slice"),
+            "    2  fn beta() {}\n    3  fn gamma() {}\n"
+        );
+        assert!(file_slice(root, "../outside.rs", None).is_err());
+        assert_eq!(
+            search(root, "fn b", 10).expect("search"),
+            "lib.rs:2: fn beta() {}\n"
+        );
+        let blame = blame_range(root, "lib.rs", (1, 1)).expect("blame");
+        assert!(
+            blame.starts_with(&oid.to_string()[..12]) && blame.ends_with("Ana 1: fn alpha() {}\n"),
+            "{blame}"
+        );
+
+        assert_eq!(parse_line_range("3:5"), Ok((3, 5)));
+        assert!(parse_line_range("5:3").is_err());
+        assert!(parse_line_range("0:3").is_err());
+    }
+}
@@ -0,0 +1,210 @@
+pub use vizier_kernel::search::*;
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use sha2::{Digest, Sha256};
+
+use crate::{atomic_file, config, display, temp_registry::SEARCH_CACHE_DIR};
+
+const EMBEDDING_CACHE_FILE: &str = "embeddings.json";
+
+/// Ranks `documents` with the `[search] embedder` when one is configured, and by BM25 alone
+/// otherwise. An embedder that fails is reported and the BM25 ranking is returned instead.
+pub fn search_project(
+    project_root: &Path,
+    documents: &[SearchDocument],
+    query: &str,
+    limit: usize,
+) -> Vec<SearchHit> {
+    let Some(embedder) = CommandEmbedder::from_config(project_root) else {
+        return search(documents, query, limit);
+    };
+    match hybrid_search(documents, query, limit, &embedder) {
+        Ok(hits) => hits,
+        Err(err) => {
+            display::warn(format!(
+                "search embedder failed; ranking by BM25 only: {err}"
+            ));
+            search(documents, query, limit)
+        }
+    }
+}
+
+/// Embeds text by running an external command: a JSON array of strings goes in on stdin, and a
+/// JSON array holding one number array per string must come back on stdout. Vectors are cached
+/// under `.vizier/tmp/cache/search/` by a hash of the command and the text, so only passages that
+/// changed since the last search are sent.
+#[derive(Clone, Debug)]
+pub struct CommandEmbedder {
+    command: Vec<String>,
+    project_root: PathBuf,
+}
+
+impl CommandEmbedder {
+    pub fn new(project_root: &Path, command: Vec<String>) -> Self {
+        Self {
+            command,
+            project_root: project_root.to_path_buf(),
+        }
+    }
+
+    /// The configured embedder, or `None` when `[search] embedder` is unset.
+    pub fn from_config(project_root: &Path) -> Option<Self> {
+        let command = config::get_config().search.embedder;
+        (!command.is_empty()).then(|| Self::new(project_root, command))
+    }
+
+    fn cache_path(&self) -> PathBuf {
+        self.project_root
+            .join(SEARCH_CACHE_DIR)
+            .join(EMBEDDING_CACHE_FILE)
+    }
+
+    fn cache_key(&self, text: &str) -> String {
+        let mut hasher = Sha256::new();
+        for part in &self.command {
+            hasher.update(part.as_bytes());
+            hasher.update([0]);
+        }
+        hasher.update([0]);
+        hasher.update(text.as_bytes());
+        format!("{:x}", hasher.finalize())
+    }
+
+    fn run(&self, texts: &[&String]) -> io::Result<Vec<Vec<f32>>> {
+        let (program, args) = self
+            .command
+            .split_first()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "empty embedder command"))?;
+        let display_command = self.command.join(" ");
+        let mut child = Command::new(program)
+            .args(args)
+            .current_dir(&self.project_root)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|err| io::Error::new(err.kind(), format!("`{display_command}`: {err}")))?;
+
+        let input = serde_json::to_vec(texts)?;
+        let mut stdin = child.stdin.take().expect("embedder stdin is piped");
+        // Write from another thread so a command that prints before reading cannot deadlock us.
+        let writer = std::thread::spawn(move || stdin.write_all(&input));
+        let output = child.wait_with_output()?;
+        if let Ok(Err(err)) = writer.join()
+            && err.kind() != io::ErrorKind::BrokenPipe
+        {
+            return Err(err);
+        }
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let mut message = format!("`{display_command}` exited with {}", output.status);
+            if !stderr.trim().is_empty() {
+                message.push_str(&format!(": {}", stderr.trim()));
+            }
+            return Err(io::Error::other(message));
+        }
+        let vectors: Vec<Vec<f32>> = serde_json::from_slice(&output.stdout).map_err(|err| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("`{display_command}` did not print a JSON array of vectors: {err}"),
+            )
+        })?;
+        if vectors.len() != texts.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "`{display_command}` returned {} vectors for {} texts",
+                    vectors.len(),
+                    texts.len()
+                ),
+            ));
+        }
+        Ok(vectors)
+    }
+}
+
+impl Embedder for CommandEmbedder {
+    type Error = io::Error;
+
+    fn embed(&self, texts: &[String]) -> io::Result<Vec<Vec<f32>>> {
+        let cache_path = self.cache_path();
+        let mut cached: HashMap<String, Vec<f32>> = fs::read(&cache_path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default();
+
+        let keys = texts
+            .iter()
+            .map(|text| self.cache_key(text))
+            .collect::<Vec<_>>();
+        let mut seen = HashSet::new();
+        let (missing_keys, missing): (Vec<_>, Vec<_>) = keys
+            .iter()
+            .zip(texts)
+            .filter(|(key, _)| !cached.contains_key(*key) && seen.insert(*key))
+            .map(|(key, text)| (key.clone(), text))
+            .unzip();
+        if !missing.is_empty() {
+            cached.extend(missing_keys.into_iter().zip(self.run(&missing)?));
+        }
+
+        let vectors = keys
+            .iter()
+            .map(|key| cached.get(key).cloned().unwrap_or_default())
+            .collect::<Vec<_>>();
+        // Keep only what this search used, so the cache follows the corpus instead of growing.
+        // The cache is best-effort; failing to write it never costs the search its vectors.
+        let kept = keys
+            .into_iter()
+            .zip(vectors.iter().cloned())
+            .collect::<HashMap<_, _>>();
+        if let Some(parent) = cache_path.parent()
+            && fs::create_dir_all(parent).is_ok()
+            && let Ok(bytes) = serde_json::to_vec(&kept)
+        {
+            let _ = atomic_file::write_atomic(&cache_path, &bytes);
+        }
+        Ok(vectors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn command_embedder_runs_once_per_new_text() {
+        let tmp = tempfile::tempdir().unwrap();
+        let calls = tmp.path().join("calls");
+        let script = tmp.path().join("embed.sh");
+        fs::write(
+            &script,
+            format!(
+                "#!/bin/sh\necho call >> '{}'\ncat > /dev/null\necho '[[1, 0], [0, 1]]'\n",
+                calls.display()
+            ),
+        )
+        .unwrap();
+        let embedder = CommandEmbedder::new(
+            tmp.path(),
+            vec!["sh".to_string(), script.display().to_string()],
+        );
+
+        let texts = vec!["alpha".to_string(), "beta".to_string()];
+        let first = embedder.embed(&texts).unwrap();
+        assert_eq!(first, vec![vec![1.0, 0.0], vec![0.0, 1.0]]);
+        let second = embedder.embed(&texts).unwrap();
+        assert_eq!(first, second);
+        assert_eq!(fs::read_to_string(&calls).unwrap().lines().count(), 1);
+
+        let failing = CommandEmbedder::new(tmp.path(), vec!["false".to_string()]);
+        let err = failing.embed(&texts).unwrap_err();
+        assert!(err.to_string().contains("`false` exited"), "{err}");
+    }
+}
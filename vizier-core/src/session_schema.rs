@@ -0,0 +1,315 @@
+//! The published JSON Schema for `session.json` (`docs/schemas/session.schema.json`) and the
+//! checks run against it when a session log is written or loaded.
+//!
+//! The validator understands the subset of JSON Schema the published file uses: `type`,
+//! `const`, `enum`, `required`, `properties`, `items`, `additionalProperties`, and `minimum`.
+//! Unknown fields in a log are allowed so additive changes do not need a version bump; renaming
+//! or retyping a field does, along with a step in [`migrate_session_log`].
+
+use std::sync::OnceLock;
+
+use serde_json::{Map, Value, json};
+
+/// `schema` marker written into every session log.
+pub const SESSION_SCHEMA: &str = "vizier.session.v2";
+/// `schema_version` written into every session log.
+pub const SESSION_SCHEMA_VERSION: u64 = 2;
+/// The schema document, as published under `docs/schemas/`.
+pub const SESSION_SCHEMA_JSON: &str = include_str!("../../docs/schemas/session.schema.json");
+
+/// How many violations an error message lists before summarizing the rest.
+const MAX_REPORTED: usize = 5;
+
+fn schema() -> &'static Value {
+    static SCHEMA: OnceLock<Value> = OnceLock::new();
+    SCHEMA.get_or_init(|| {
+        serde_json::from_str(SESSION_SCHEMA_JSON).expect("embedded session schema is valid JSON")
+    })
+}
+
+/// Schema version a log claims: `schema_version`, else the `vizier.session.vN` marker, else 0
+/// for the bare message lists and `{ "messages": [...] }` wrappers older builds wrote.
+pub fn session_log_version(log: &Value) -> u64 {
+    if let Some(version) = log.get("schema_version").and_then(Value::as_u64) {
+        return version;
+    }
+    log.get("schema")
+        .and_then(Value::as_str)
+        .and_then(|marker| marker.strip_prefix("vizier.session.v"))
+        .and_then(|version| version.parse().ok())
+        .unwrap_or(0)
+}
+
+/// Every place `log` departs from the schema, as `<json pointer>: <problem>`.
+pub fn schema_violations(log: &Value) -> Vec<String> {
+    let mut violations = Vec::new();
+    check(log, schema(), "", &mut violations);
+    violations
+}
+
+/// Checks a log against the current schema, explaining how to recover when it cannot be used.
+pub fn validate_session_log(log: &Value) -> Result<(), String> {
+    let version = session_log_version(log);
+    if version > SESSION_SCHEMA_VERSION {
+        return Err(format!(
+            "session log uses schema v{version}, newer than the v{SESSION_SCHEMA_VERSION} this vizier understands; upgrade vizier to read it"
+        ));
+    }
+    if version < SESSION_SCHEMA_VERSION {
+        return Err(format!(
+            "session log uses schema v{version}; run `vizier sessions migrate` to upgrade it to v{SESSION_SCHEMA_VERSION}"
+        ));
+    }
+    let violations = schema_violations(log);
+    if violations.is_empty() {
+        return Ok(());
+    }
+    let mut detail = violations
+        .iter()
+        .take(MAX_REPORTED)
+        .cloned()
+        .collect::<Vec<_>>()
+        .join("; ");
+    if violations.len() > MAX_REPORTED {
+        detail.push_str(&format!("; and {} more", violations.len() - MAX_REPORTED));
+    }
+    Err(format!(
+        "session log does not match {SESSION_SCHEMA}: {detail}"
+    ))
+}
+
+/// Upgrades an older log to the current schema, filling fields that older builds did not
+/// record. `defaults` supplies the values a log cannot recover on its own (`id`, `created_at`,
+/// `updated_at`, `repo`). Returns `None` when the log is already current.
+pub fn migrate_session_log(
+    log: Value,
+    defaults: &Map<String, Value>,
+) -> Result<Option<Value>, String> {
+    let version = session_log_version(&log);
+    if version == SESSION_SCHEMA_VERSION {
+        return Ok(None);
+    }
+    if version > SESSION_SCHEMA_VERSION {
+        return Err(format!(
+            "schema v{version} is newer than this vizier (v{SESSION_SCHEMA_VERSION})"
+        ));
+    }
+
+    let mut object = match log {
+        // v0: a bare message list.
+        Value::Array(messages) => {
+            Map::from_iter([("messages".to_string(), Value::Array(messages))])
+        }
+        Value::Object(object) => object,
+        other => {
+            return Err(format!(
+                "expected a JSON object, found {}",
+                type_name(&other)
+            ));
+        }
+    };
+    let fill = [
+        ("tool_version", json!("unknown")),
+        ("workflow_type", json!("cli")),
+        ("mode", json!("default")),
+        ("config_effective", json!({})),
+        (
+            "system_prompt",
+            json!({"kind": "unknown", "scope": "default", "origin": "unknown", "path": null, "hash": ""}),
+        ),
+        (
+            "model",
+            json!({"provider": "unknown", "name": "unknown", "reasoning_effort": null, "scope": null}),
+        ),
+        ("messages", json!([])),
+        ("agent", Value::Null),
+        ("operations", json!([])),
+        ("artifacts", json!([])),
+        ("outcome", json!({"status": "completed", "summary": null})),
+    ];
+    for (key, value) in defaults
+        .iter()
+        .map(|(key, value)| (key.as_str(), value.clone()))
+        .chain(fill)
+    {
+        object.entry(key).or_insert(value);
+    }
+    object.insert("schema".to_string(), json!(SESSION_SCHEMA));
+    object.insert("schema_version".to_string(), json!(SESSION_SCHEMA_VERSION));
+
+    let migrated = Value::Object(object);
+    validate_session_log(&migrated)?;
+    Ok(Some(migrated))
+}
+
+fn check(value: &Value, schema: &Value, pointer: &str, out: &mut Vec<String>) {
+    let at = if pointer.is_empty() { "/" } else { pointer };
+    if let Some(expected) = schema.get("const")
+        && value != expected
+    {
+        out.push(format!("{at}: expected {expected}, found {value}"));
+        return;
+    }
+    if let Some(options) = schema.get("enum").and_then(Value::as_array)
+        && !options.contains(value)
+    {
+        let options = options
+            .iter()
+            .map(Value::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        out.push(format!("{at}: expected one of {options}, found {value}"));
+        return;
+    }
+    if let Some(types) = schema.get("type") {
+        let allowed = match types {
+            Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+            Value::String(name) => vec![name.as_str()],
+            _ => Vec::new(),
+        };
+        if !allowed.iter().any(|name| matches_type(value, name)) {
+            out.push(format!(
+                "{at}: expected {}, found {}",
+                allowed.join(" or "),
+                type_name(value)
+            ));
+            return;
+        }
+    }
+    if let (Some(minimum), Some(number)) = (
+        schema.get("minimum").and_then(Value::as_f64),
+        value.as_f64(),
+    ) && number < minimum
+    {
+        out.push(format!("{at}: expected at least {minimum}, found {value}"));
+    }
+
+    match value {
+        Value::Object(object) => {
+            for key in schema
+                .get("required")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+            {
+                if !object.contains_key(key) {
+                    out.push(format!("{at}: missing required field `{key}`"));
+                }
+            }
+            let properties = schema.get("properties").and_then(Value::as_object);
+            for (key, child) in object {
+                let child_pointer = format!("{pointer}/{}", escape_pointer(key));
+                match properties.and_then(|properties| properties.get(key)) {
+                    Some(child_schema) => check(child, child_schema, &child_pointer, out),
+                    None => {
+                        if let Some(extra) = schema
+                            .get("additionalProperties")
+                            .filter(|extra| extra.is_object())
+                        {
+                            check(child, extra, &child_pointer, out);
+                        }
+                    }
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    check(item, item_schema, &format!("{pointer}/{index}"), out);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn matches_type(value: &Value, name: &str) -> bool {
+    match name {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Object(_) => "object",
+        Value::Array(_) => "array",
+        Value::String(_) => "string",
+        Value::Number(number) if number.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::Bool(_) => "boolean",
+        Value::Null => "null",
+    }
+}
+
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn defaults() -> Map<String, Value> {
+        json!({
+            "id": "abc",
+            "created_at": "2026-01-01T00:00:00Z",
+            "updated_at": "2026-01-01T00:00:00Z",
+            "repo": {"root": "/repo", "branch": null, "head": null},
+        })
+        .as_object()
+        .cloned()
+        .expect("object")
+    }
+
+    #[test]
+    fn migration_upgrades_legacy_logs_and_validation_points_at_bad_fields() {
+        let legacy = json!([{"role": "User", "content": "hi"}]);
+        assert!(
+            validate_session_log(&legacy)
+                .expect_err("legacy log")
+                .contains("run `vizier sessions migrate`")
+        );
+
+        let migrated = migrate_session_log(legacy, &defaults())
+            .expect("migrate")
+            .expect("changed");
+        assert_eq!(migrated["schema"], SESSION_SCHEMA);
+        assert_eq!(migrated["messages"][0]["content"], "hi");
+        assert!(validate_session_log(&migrated).is_ok());
+        assert!(
+            migrate_session_log(migrated.clone(), &defaults())
+                .expect("current")
+                .is_none()
+        );
+
+        let mut broken = migrated;
+        broken["messages"][0]["role"] = json!("Robot");
+        broken["outcome"] = json!({});
+        broken.as_object_mut().expect("object").remove("artifacts");
+        let err = validate_session_log(&broken).expect_err("invalid");
+        assert!(err.contains("/messages/0/role: expected one of"), "{err}");
+        assert!(
+            err.contains("/outcome: missing required field `status`"),
+            "{err}"
+        );
+        assert!(
+            err.contains("/: missing required field `artifacts`"),
+            "{err}"
+        );
+
+        let future = json!({"schema": "vizier.session.v9"});
+        assert!(
+            validate_session_log(&future)
+                .expect_err("future")
+                .contains("upgrade vizier")
+        );
+    }
+}
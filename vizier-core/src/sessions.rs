@@ -0,0 +1,642 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use crate::atomic_file;
+use crate::config::SessionsConfig;
+use crate::session_schema;
+
+pub const SESSIONS_DIR: &str = ".vizier/sessions";
+pub const COMPRESSED_SUFFIX: &str = ".zst";
+// zstd's own default level; session JSON compresses well without slower settings.
+const ZSTD_LEVEL: i32 = 3;
+/// JSON artifacts the auditor writes into each session directory.
+pub const SESSION_ARTIFACTS: [&str; 2] = ["session.json", "outcome.json"];
+/// Suffix `fsck --repair` gives a corrupt artifact it moves aside.
+pub const CORRUPT_SUFFIX: &str = ".corrupt";
+
+/// One `.vizier/sessions/<id>/` directory on disk.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SessionEntry {
+    pub id: String,
+    pub path: PathBuf,
+    /// Newest modification time among the session's files.
+    pub modified: DateTime<Utc>,
+    pub size_bytes: u64,
+    /// File names inside the session directory, sorted.
+    pub files: Vec<String>,
+}
+
+impl SessionEntry {
+    /// True once every JSON artifact in the session has been compressed.
+    pub fn is_compressed(&self) -> bool {
+        !self.files.is_empty()
+            && self
+                .files
+                .iter()
+                .all(|name| !name.ends_with(".json") || name.ends_with(COMPRESSED_SUFFIX))
+            && self
+                .files
+                .iter()
+                .any(|name| name.ends_with(COMPRESSED_SUFFIX))
+    }
+
+    pub fn age_days(&self, now: DateTime<Utc>) -> i64 {
+        (now - self.modified).num_days()
+    }
+
+    /// Path of `name` (e.g. `session.json`) inside the session, preferring the plain file and
+    /// falling back to its compressed form.
+    pub fn artifact_path(&self, name: &str) -> Option<PathBuf> {
+        let plain = self.path.join(name);
+        if plain.is_file() {
+            return Some(plain);
+        }
+        let compressed = self.path.join(format!("{name}{COMPRESSED_SUFFIX}"));
+        compressed.is_file().then_some(compressed)
+    }
+}
+
+/// What `gc` does with an expired session.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SessionGcAction {
+    Remove,
+    Compress,
+}
+
+impl SessionGcAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Remove => "remove",
+            Self::Compress => "compress",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SessionGcCandidate {
+    pub entry: SessionEntry,
+    pub action: SessionGcAction,
+    pub reason: String,
+}
+
+pub fn sessions_root(project_root: &Path) -> PathBuf {
+    project_root.join(SESSIONS_DIR)
+}
+
+/// Lists session directories, newest first. A missing sessions directory yields an empty list.
+pub fn list_sessions(project_root: &Path) -> io::Result<Vec<SessionEntry>> {
+    let root = sessions_root(project_root);
+    if !root.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut sessions = Vec::new();
+    for entry in fs::read_dir(&root)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+        sessions.push(read_session_entry(&entry.path())?);
+    }
+    sessions.sort_by(|a, b| b.modified.cmp(&a.modified).then_with(|| b.id.cmp(&a.id)));
+    Ok(sessions)
+}
+
+pub fn find_session(project_root: &Path, id: &str) -> io::Result<Option<SessionEntry>> {
+    let path = sessions_root(project_root).join(id);
+    if id.is_empty() || id.contains(['/', '\\']) || !path.is_dir() {
+        return Ok(None);
+    }
+    read_session_entry(&path).map(Some)
+}
+
+fn read_session_entry(path: &Path) -> io::Result<SessionEntry> {
+    let mut files = Vec::new();
+    let mut size_bytes = 0;
+    let mut modified = fs::metadata(path)?.modified()?;
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        if !metadata.is_file() {
+            continue;
+        }
+        size_bytes += metadata.len();
+        modified = modified.max(metadata.modified()?);
+        files.push(entry.file_name().to_string_lossy().into_owned());
+    }
+    files.sort();
+
+    Ok(SessionEntry {
+        id: path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        path: path.to_path_buf(),
+        modified: DateTime::<Utc>::from(modified),
+        size_bytes,
+        files,
+    })
+}
+
+/// Picks sessions that fall outside the retention policy. `sessions` must be newest first (as
+/// returned by `list_sessions`); `protected` ids (the running session) are never selected.
+/// With `compress`, sessions that are already compressed are left alone.
+pub fn plan_session_gc(
+    sessions: &[SessionEntry],
+    policy: &SessionsConfig,
+    now: DateTime<Utc>,
+    protected: &[&str],
+) -> Vec<SessionGcCandidate> {
+    let action = if policy.compress {
+        SessionGcAction::Compress
+    } else {
+        SessionGcAction::Remove
+    };
+
+    sessions
+        .iter()
+        .enumerate()
+        .filter(|(_, entry)| !protected.contains(&entry.id.as_str()))
+        .filter_map(|(index, entry)| {
+            let mut reasons = Vec::new();
+            if policy.keep_last > 0 && index >= policy.keep_last as usize {
+                reasons.push(format!("beyond newest {}", policy.keep_last));
+            }
+            let age = entry.age_days(now);
+            if policy.keep_days > 0 && age > i64::from(policy.keep_days) {
+                reasons.push(format!("{age} days old (limit {})", policy.keep_days));
+            }
+            if reasons.is_empty() || (policy.compress && entry.is_compressed()) {
+                return None;
+            }
+            Some(SessionGcCandidate {
+                entry: entry.clone(),
+                action,
+                reason: reasons.join(", "),
+            })
+        })
+        .collect()
+}
+
+/// Applies one gc decision: removes the session directory or compresses its JSON files with
+/// zstd (each `*.json` becomes `*.json.zst`).
+pub fn apply_session_gc(candidate: &SessionGcCandidate) -> io::Result<()> {
+    match candidate.action {
+        SessionGcAction::Remove => fs::remove_dir_all(&candidate.entry.path),
+        SessionGcAction::Compress => compress_session(&candidate.entry),
+    }
+}
+
+fn compress_session(entry: &SessionEntry) -> io::Result<()> {
+    for name in entry.files.iter().filter(|name| name.ends_with(".json")) {
+        compress_artifact(&entry.path.join(name))?;
+    }
+    Ok(())
+}
+
+/// Replaces `source` with `source.zst`.
+fn compress_artifact(source: &Path) -> io::Result<()> {
+    let target = PathBuf::from(format!("{}{COMPRESSED_SUFFIX}", source.display()));
+    let raw = fs::read(source)?;
+    let compressed = zstd::encode_all(raw.as_slice(), ZSTD_LEVEL).map_err(|err| {
+        io::Error::other(format!("failed to compress {}: {err}", source.display()))
+    })?;
+    atomic_file::write_atomic(&target, &compressed)?;
+    fs::remove_file(source)
+}
+
+/// Reads a session artifact, transparently decompressing `*.zst` files.
+pub fn read_session_artifact(path: &Path) -> io::Result<String> {
+    if !path.to_string_lossy().ends_with(COMPRESSED_SUFFIX) {
+        return fs::read_to_string(path);
+    }
+
+    let compressed = fs::read(path)?;
+    let raw = zstd::decode_all(compressed.as_slice()).map_err(|err| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("failed to decompress {}: {err}", path.display()),
+        )
+    })?;
+    String::from_utf8(raw).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+/// What `fsck` found wrong with one session artifact.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SessionIssueKind {
+    /// The artifact is not valid JSON and no interrupted write holds a complete copy.
+    Truncated,
+    /// The artifact is missing or corrupt, but an interrupted write left a complete copy.
+    Recoverable,
+    /// A leftover temp file from an interrupted write that is no longer needed.
+    StaleTemp,
+}
+
+impl SessionIssueKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Truncated => "truncated",
+            Self::Recoverable => "recoverable",
+            Self::StaleTemp => "stale_temp",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SessionIssue {
+    pub session: String,
+    /// Artifact name (`session.json`, `outcome.json`).
+    pub artifact: String,
+    pub kind: SessionIssueKind,
+    /// The file the issue concerns: the corrupt artifact, or the temp file.
+    pub path: PathBuf,
+    pub detail: String,
+}
+
+enum ArtifactRead {
+    Missing,
+    Valid(Value),
+    Invalid(String),
+}
+
+fn read_artifact(path: Option<&Path>) -> io::Result<ArtifactRead> {
+    let Some(path) = path else {
+        return Ok(ArtifactRead::Missing);
+    };
+    let raw = match read_session_artifact(path) {
+        Ok(raw) => raw,
+        Err(err) => return Ok(ArtifactRead::Invalid(err.to_string())),
+    };
+    Ok(match serde_json::from_str(&raw) {
+        Ok(value) => ArtifactRead::Valid(value),
+        Err(err) => ArtifactRead::Invalid(err.to_string()),
+    })
+}
+
+/// The newest complete temp file an interrupted write left for `name`, with its contents.
+fn recoverable_temp(entry: &SessionEntry, name: &str) -> Option<(PathBuf, Value)> {
+    atomic_file::temp_siblings(&entry.path.join(name))
+        .into_iter()
+        .find_map(|tmp| {
+            let raw = fs::read_to_string(&tmp).ok()?;
+            let value = serde_json::from_str(&raw).ok()?;
+            Some((tmp, value))
+        })
+}
+
+/// Reads a session JSON artifact. When a crash left the artifact missing or truncated, the
+/// complete copy from the interrupted write is used instead (without touching disk); an
+/// artifact with no such copy is reported as corrupt.
+pub fn read_session_json(entry: &SessionEntry, name: &str) -> io::Result<Option<Value>> {
+    let read = read_artifact(entry.artifact_path(name).as_deref())?;
+    let err = match read {
+        ArtifactRead::Valid(value) => return Ok(Some(value)),
+        ArtifactRead::Missing => None,
+        ArtifactRead::Invalid(err) => Some(err),
+    };
+    if let Some((_, value)) = recoverable_temp(entry, name) {
+        return Ok(Some(value));
+    }
+    match err {
+        None => Ok(None),
+        Some(err) => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "{name} in session {} is truncated or corrupt ({err}); run `vizier sessions fsck --repair`",
+                entry.id
+            ),
+        )),
+    }
+}
+
+/// Reads a session's `session.json` and checks it against the published schema, pointing at
+/// `vizier sessions migrate` when the log predates it.
+pub fn read_session_log(entry: &SessionEntry) -> io::Result<Option<Value>> {
+    let Some(log) = read_session_json(entry, "session.json")? else {
+        return Ok(None);
+    };
+    session_schema::validate_session_log(&log).map_err(|err| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("session.json in session {}: {err}", entry.id),
+        )
+    })?;
+    Ok(Some(log))
+}
+
+/// What `migrate` did, or would do, with one session's `session.json`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SessionMigrationStatus {
+    Current,
+    Migrated,
+    Failed(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SessionMigration {
+    pub session: String,
+    pub from_version: u64,
+    pub status: SessionMigrationStatus,
+}
+
+/// Upgrades a session's `session.json` to the current schema in place, keeping it compressed
+/// when it was. `None` when the session has no log.
+pub fn migrate_session(
+    entry: &SessionEntry,
+    dry_run: bool,
+) -> io::Result<Option<SessionMigration>> {
+    let migration = |from_version, status| {
+        Ok(Some(SessionMigration {
+            session: entry.id.clone(),
+            from_version,
+            status,
+        }))
+    };
+    let log = match read_session_json(entry, "session.json") {
+        Ok(Some(log)) => log,
+        Ok(None) => return Ok(None),
+        Err(err) => return migration(0, SessionMigrationStatus::Failed(err.to_string())),
+    };
+
+    let from_version = session_schema::session_log_version(&log);
+    let modified = entry.modified.to_rfc3339();
+    let root = entry
+        .path
+        .ancestors()
+        .nth(3)
+        .map(|root| root.display().to_string())
+        .unwrap_or_default();
+    let defaults = serde_json::json!({
+        "id": entry.id,
+        "created_at": modified,
+        "updated_at": modified,
+        "repo": {"root": root, "branch": null, "head": null},
+    });
+    let defaults = defaults.as_object().cloned().unwrap_or_default();
+    let migrated = match session_schema::migrate_session_log(log, &defaults) {
+        Ok(Some(migrated)) => migrated,
+        Ok(None) => return migration(from_version, SessionMigrationStatus::Current),
+        Err(err) => return migration(from_version, SessionMigrationStatus::Failed(err)),
+    };
+
+    if !dry_run {
+        let compressed = entry
+            .artifact_path("session.json")
+            .is_some_and(|path| path.to_string_lossy().ends_with(COMPRESSED_SUFFIX));
+        let plain = entry.path.join("session.json");
+        let buffer = serde_json::to_vec_pretty(&migrated).map_err(io::Error::other)?;
+        atomic_file::write_atomic(&plain, &buffer)?;
+        if compressed {
+            compress_artifact(&plain)?;
+        }
+    }
+    migration(from_version, SessionMigrationStatus::Migrated)
+}
+
+/// Checks a session's JSON artifacts for truncation and leftover temp files.
+pub fn fsck_session(entry: &SessionEntry) -> io::Result<Vec<SessionIssue>> {
+    let mut issues = Vec::new();
+    for name in SESSION_ARTIFACTS {
+        let artifact = entry.artifact_path(name);
+        let read = read_artifact(artifact.as_deref())?;
+        let mut temps = atomic_file::temp_siblings(&entry.path.join(name));
+        let issue = |kind, path: PathBuf, detail: String| SessionIssue {
+            session: entry.id.clone(),
+            artifact: name.to_string(),
+            kind,
+            path,
+            detail,
+        };
+
+        if !matches!(read, ArtifactRead::Valid(_))
+            && let Some((tmp, _)) = recoverable_temp(entry, name)
+        {
+            temps.retain(|path| *path != tmp);
+            issues.push(issue(
+                SessionIssueKind::Recoverable,
+                tmp.clone(),
+                format!(
+                    "complete copy left by an interrupted write in {}",
+                    file_name(&tmp)
+                ),
+            ));
+        } else if let (ArtifactRead::Invalid(err), Some(path)) = (&read, artifact) {
+            issues.push(issue(SessionIssueKind::Truncated, path, err.clone()));
+        }
+        for tmp in temps {
+            issues.push(issue(
+                SessionIssueKind::StaleTemp,
+                tmp,
+                "leftover temp file from an interrupted write".to_string(),
+            ));
+        }
+    }
+    Ok(issues)
+}
+
+/// Repairs one issue: recoverable copies are moved into place, truncated artifacts are moved
+/// aside to `*.corrupt` so loaders stop tripping on them, and stale temp files are removed.
+/// Returns a short description of what was done.
+pub fn repair_session_issue(entry: &SessionEntry, issue: &SessionIssue) -> io::Result<String> {
+    match issue.kind {
+        SessionIssueKind::Recoverable => {
+            let target = entry.path.join(&issue.artifact);
+            fs::rename(&issue.path, &target)?;
+            Ok(format!(
+                "restored {} from {}",
+                issue.artifact,
+                file_name(&issue.path)
+            ))
+        }
+        SessionIssueKind::Truncated => {
+            let aside = PathBuf::from(format!("{}{CORRUPT_SUFFIX}", issue.path.display()));
+            fs::rename(&issue.path, &aside)?;
+            Ok(format!(
+                "moved {} aside to {}",
+                file_name(&issue.path),
+                file_name(&aside)
+            ))
+        }
+        SessionIssueKind::StaleTemp => {
+            fs::remove_file(&issue.path)?;
+            Ok(format!("removed {}", file_name(&issue.path)))
+        }
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn entry(id: &str, age_days: i64, files: &[&str], now: DateTime<Utc>) -> SessionEntry {
+        SessionEntry {
+            id: id.to_string(),
+            path: PathBuf::from(format!("/tmp/sessions/{id}")),
+            modified: now - Duration::days(age_days),
+            size_bytes: 10,
+            files: files.iter().map(|name| name.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn gc_plan_applies_keep_last_and_keep_days() {
+        let now = Utc::now();
+        let sessions = vec![
+            entry("a", 0, &["session.json"], now),
+            entry("b", 2, &["session.json"], now),
+            entry("c", 10, &["session.json"], now),
+            entry("d", 40, &["session.json"], now),
+        ];
+        let policy = SessionsConfig {
+            keep_days: 30,
+            keep_last: 2,
+            compress: false,
+        };
+
+        let plan = plan_session_gc(&sessions, &policy, now, &["d"]);
+        let ids = plan
+            .iter()
+            .map(|candidate| candidate.entry.id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["c"], "protected sessions are never collected");
+        assert_eq!(plan[0].action, SessionGcAction::Remove);
+
+        let plan = plan_session_gc(&sessions, &policy, now, &[]);
+        assert_eq!(plan.len(), 2);
+        assert!(plan[1].reason.contains("40 days old"), "{:?}", plan[1]);
+
+        let disabled = SessionsConfig::default();
+        assert!(plan_session_gc(&sessions, &disabled, now, &[]).is_empty());
+    }
+
+    #[test]
+    fn gc_plan_skips_sessions_already_compressed() {
+        let now = Utc::now();
+        let sessions = vec![
+            entry("fresh", 0, &["session.json"], now),
+            entry("packed", 50, &["outcome.json.zst", "session.json.zst"], now),
+            entry("stale", 50, &["outcome.json", "session.json"], now),
+        ];
+        let policy = SessionsConfig {
+            keep_days: 7,
+            keep_last: 0,
+            compress: true,
+        };
+
+        let plan = plan_session_gc(&sessions, &policy, now, &[]);
+        assert_eq!(plan.len(), 1);
+        assert_eq!(plan[0].entry.id, "stale");
+        assert_eq!(plan[0].action, SessionGcAction::Compress);
+        assert!(sessions[1].is_compressed());
+        assert!(!sessions[2].is_compressed());
+    }
+
+    #[test]
+    fn list_sessions_reports_size_and_newest_first() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let root = sessions_root(temp.path());
+        for (id, body) in [("old", "{}"), ("new", "{\"k\":1}")] {
+            fs::create_dir_all(root.join(id)).expect("session dir");
+            fs::write(root.join(id).join("session.json"), body).expect("session file");
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+
+        let sessions = list_sessions(temp.path()).expect("list sessions");
+        assert_eq!(
+            sessions
+                .iter()
+                .map(|entry| entry.id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["new", "old"]
+        );
+        assert_eq!(sessions[0].size_bytes, 7);
+        assert_eq!(
+            sessions[0].artifact_path("session.json"),
+            Some(root.join("new").join("session.json"))
+        );
+        assert!(find_session(temp.path(), "../new").expect("find").is_none());
+    }
+
+    #[test]
+    fn compress_session_round_trips_artifacts() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let dir = sessions_root(temp.path()).join("packed");
+        fs::create_dir_all(&dir).expect("session dir");
+        fs::write(dir.join("session.json"), "{\"id\":\"packed\"}").expect("session file");
+
+        let entry = find_session(temp.path(), "packed")
+            .expect("find")
+            .expect("session");
+        compress_session(&entry).expect("compress");
+        assert!(!dir.join("session.json").exists());
+
+        let entry = find_session(temp.path(), "packed")
+            .expect("find")
+            .expect("session");
+        assert!(entry.is_compressed());
+        assert_eq!(
+            read_session_json(&entry, "session.json").expect("read"),
+            Some(serde_json::json!({"id": "packed"}))
+        );
+    }
+
+    #[test]
+    fn fsck_recovers_interrupted_writes_and_sets_truncated_logs_aside() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let dir = sessions_root(temp.path()).join("s1");
+        fs::create_dir_all(&dir).expect("session dir");
+        fs::write(dir.join("session.json"), "{\"messages\": [").expect("truncated log");
+        fs::write(dir.join("outcome.json"), "{\"command\"").expect("truncated outcome");
+        fs::write(dir.join("outcome.json.tmp.9.0"), "{\"command\":\"save\"}").expect("temp");
+
+        let entry = find_session(temp.path(), "s1")
+            .expect("find")
+            .expect("session");
+        assert!(read_session_json(&entry, "session.json").is_err());
+        assert_eq!(
+            read_session_json(&entry, "outcome.json").expect("recovered"),
+            Some(serde_json::json!({"command": "save"}))
+        );
+
+        let issues = fsck_session(&entry).expect("fsck");
+        let kinds = issues
+            .iter()
+            .map(|issue| (issue.artifact.as_str(), issue.kind))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                ("session.json", SessionIssueKind::Truncated),
+                ("outcome.json", SessionIssueKind::Recoverable),
+            ]
+        );
+        for issue in &issues {
+            repair_session_issue(&entry, issue).expect("repair");
+        }
+
+        let entry = find_session(temp.path(), "s1")
+            .expect("find")
+            .expect("session");
+        assert!(fsck_session(&entry).expect("fsck").is_empty());
+        assert_eq!(
+            read_session_json(&entry, "session.json").expect("read"),
+            None
+        );
+        assert!(dir.join("session.json.corrupt").is_file());
+        assert_eq!(
+            fs::read_to_string(dir.join("outcome.json")).expect("outcome"),
+            "{\"command\":\"save\"}"
+        );
+    }
+}
@@ -0,0 +1,345 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::jobs::{self, JobRecord};
+use crate::{plan, sessions};
+
+/// Bucket label used when a session or job carries no scope or plan.
+pub const UNATTRIBUTED: &str = "(none)";
+
+/// Aggregated counters for one scope, one plan, or the whole repository.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct StatsBucket {
+    pub sessions: u64,
+    pub agent_runs: u64,
+    pub agent_wall_ms: u128,
+    /// Agent exit code (as a string, so it serializes as a JSON object key) to run count.
+    pub exit_codes: BTreeMap<String, u64>,
+    pub gate_runs: u64,
+    pub gate_failures: u64,
+    /// Workflow node attempts beyond the first.
+    pub retries: u64,
+    /// Token totals; `None` until at least one session recorded usage.
+    pub tokens: Option<u64>,
+}
+
+impl StatsBucket {
+    pub fn gate_failure_rate(&self) -> Option<f64> {
+        (self.gate_runs > 0).then(|| self.gate_failures as f64 / self.gate_runs as f64)
+    }
+
+    pub fn mean_agent_ms(&self) -> Option<u128> {
+        (self.agent_runs > 0).then(|| self.agent_wall_ms / self.agent_runs as u128)
+    }
+
+    fn record_session(&mut self, session: &SessionSample) {
+        self.sessions += 1;
+        if let Some(exit_code) = session.exit_code {
+            self.agent_runs += 1;
+            self.agent_wall_ms += session.duration_ms;
+            *self.exit_codes.entry(exit_code.to_string()).or_default() += 1;
+        }
+        if let Some(tokens) = session.tokens {
+            self.tokens = Some(self.tokens.unwrap_or(0) + tokens);
+        }
+    }
+
+    fn record_job(&mut self, job: &JobSample) {
+        self.retries += job.retries;
+        if let Some(failed) = job.gate_failed {
+            self.gate_runs += 1;
+            if failed {
+                self.gate_failures += 1;
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct StatsReport {
+    pub since: Option<DateTime<Utc>>,
+    pub sessions_scanned: usize,
+    pub jobs_scanned: usize,
+    pub totals: StatsBucket,
+    pub by_scope: BTreeMap<String, StatsBucket>,
+    pub by_plan: BTreeMap<String, StatsBucket>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct SessionSample {
+    scope: Option<String>,
+    plan: Option<String>,
+    exit_code: Option<i32>,
+    duration_ms: u128,
+    tokens: Option<u64>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct JobSample {
+    scope: Option<String>,
+    plan: Option<String>,
+    retries: u64,
+    gate_failed: Option<bool>,
+}
+
+/// Aggregates every session log under `.vizier/sessions` and every job record under `jobs_root`
+/// created at or after `since`.
+pub fn collect_stats(
+    project_root: &Path,
+    jobs_root: &Path,
+    since: Option<DateTime<Utc>>,
+) -> Result<StatsReport, Box<dyn std::error::Error>> {
+    let records = jobs::list_records(jobs_root)?
+        .into_iter()
+        .filter(|record| since.is_none_or(|since| record.created_at >= since))
+        .collect::<Vec<_>>();
+
+    // Jobs know which plan they ran for; sessions written by those jobs inherit it.
+    let mut session_plans = HashMap::new();
+    for record in &records {
+        if let (Some(session), Some(plan)) = (
+            record
+                .session_path
+                .as_deref()
+                .and_then(session_id_from_path),
+            record.metadata.as_ref().and_then(|meta| meta.plan.clone()),
+        ) {
+            session_plans.insert(session, plan);
+        }
+    }
+
+    let mut report = StatsReport {
+        since,
+        jobs_scanned: records.len(),
+        ..StatsReport::default()
+    };
+
+    for entry in sessions::list_sessions(project_root)? {
+        let Ok(Some(log)) = sessions::read_session_json(&entry, "session.json") else {
+            continue;
+        };
+        let created = log
+            .get("created_at")
+            .and_then(Value::as_str)
+            .and_then(|value| DateTime::parse_from_rfc3339(value).ok())
+            .map(|value| value.with_timezone(&Utc))
+            .unwrap_or(entry.modified);
+        if since.is_some_and(|since| created < since) {
+            continue;
+        }
+
+        let mut sample = session_sample(&log);
+        if let Some(plan) = session_plans.get(&entry.id) {
+            sample.plan = Some(plan.clone());
+        }
+        report.sessions_scanned += 1;
+        report.totals.record_session(&sample);
+        bucket(&mut report.by_scope, sample.scope.as_deref()).record_session(&sample);
+        bucket(&mut report.by_plan, sample.plan.as_deref()).record_session(&sample);
+    }
+
+    for record in &records {
+        let sample = job_sample(record);
+        if sample.retries == 0 && sample.gate_failed.is_none() {
+            continue;
+        }
+        report.totals.record_job(&sample);
+        bucket(&mut report.by_scope, sample.scope.as_deref()).record_job(&sample);
+        bucket(&mut report.by_plan, sample.plan.as_deref()).record_job(&sample);
+    }
+
+    Ok(report)
+}
+
+fn bucket<'a>(
+    buckets: &'a mut BTreeMap<String, StatsBucket>,
+    key: Option<&str>,
+) -> &'a mut StatsBucket {
+    buckets
+        .entry(key.unwrap_or(UNATTRIBUTED).to_string())
+        .or_default()
+}
+
+fn session_id_from_path(path: &str) -> Option<String> {
+    Path::new(path)
+        .parent()
+        .and_then(Path::file_name)
+        .map(|name| name.to_string_lossy().into_owned())
+}
+
+fn session_sample(log: &Value) -> SessionSample {
+    let agent = log.get("agent").filter(|agent| !agent.is_null());
+    SessionSample {
+        scope: log
+            .pointer("/model/scope")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        plan: log
+            .pointer("/repo/branch")
+            .and_then(Value::as_str)
+            .and_then(plan::slug_from_branch),
+        exit_code: agent
+            .and_then(|agent| agent.get("exit_code"))
+            .and_then(Value::as_i64)
+            .map(|code| code as i32),
+        duration_ms: agent
+            .and_then(|agent| agent.get("duration_ms"))
+            .and_then(Value::as_u64)
+            .unwrap_or(0) as u128,
+        tokens: agent
+            .and_then(|agent| agent.get("usage"))
+            .and_then(usage_tokens),
+    }
+}
+
+// Accepts either a precomputed `total_tokens` or separate input/output counts.
+fn usage_tokens(usage: &Value) -> Option<u64> {
+    if let Some(total) = usage.get("total_tokens").and_then(Value::as_u64) {
+        return Some(total);
+    }
+    let input = usage.get("input_tokens").and_then(Value::as_u64);
+    let output = usage.get("output_tokens").and_then(Value::as_u64);
+    match (input, output) {
+        (None, None) => None,
+        (input, output) => Some(input.unwrap_or(0) + output.unwrap_or(0)),
+    }
+}
+
+fn job_sample(record: &JobRecord) -> JobSample {
+    let Some(meta) = record.metadata.as_ref() else {
+        return JobSample::default();
+    };
+    let is_gate = meta
+        .workflow_control_policy
+        .as_deref()
+        .is_some_and(|policy| policy.starts_with("gate."));
+    JobSample {
+        scope: meta.scope.clone(),
+        plan: meta.plan.clone(),
+        retries: u64::from(meta.workflow_node_attempt.unwrap_or(1).saturating_sub(1)),
+        gate_failed: match meta.workflow_node_outcome.as_deref() {
+            Some(outcome) if is_gate => Some(matches!(outcome, "failed" | "blocked")),
+            _ => None,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jobs::{JobMetadata, JobStatus};
+    use chrono::Duration;
+    use std::fs;
+
+    fn write_session(root: &Path, id: &str, body: Value) {
+        let dir = sessions::sessions_root(root).join(id);
+        fs::create_dir_all(&dir).expect("session dir");
+        fs::write(dir.join("session.json"), body.to_string()).expect("session file");
+    }
+
+    fn write_job(jobs_root: &Path, id: &str, created_at: DateTime<Utc>, metadata: JobMetadata) {
+        let dir = jobs_root.join(id);
+        fs::create_dir_all(&dir).expect("job dir");
+        let record = JobRecord {
+            id: id.to_string(),
+            status: JobStatus::Succeeded,
+            command: vec!["vizier".to_string()],
+            child_args: Vec::new(),
+            created_at,
+            started_at: None,
+            finished_at: None,
+            pid: None,
+            exit_code: Some(0),
+            stdout_path: String::new(),
+            stderr_path: String::new(),
+            session_path: Some(".vizier/sessions/s-gate/session.json".to_string()),
+            outcome_path: None,
+            metadata: Some(metadata),
+            config_snapshot: None,
+            schedule: None,
+        };
+        fs::write(
+            dir.join("job.json"),
+            serde_json::to_string(&record).expect("record"),
+        )
+        .expect("job file");
+    }
+
+    #[test]
+    fn collect_stats_aggregates_sessions_and_jobs_by_scope_and_plan() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let root = temp.path();
+        let jobs_root = root.join(".vizier/jobs");
+        let now = Utc::now();
+
+        write_session(
+            root,
+            "s-gate",
+            serde_json::json!({
+                "created_at": now.to_rfc3339(),
+                "model": { "scope": "approve" },
+                "repo": { "branch": "main" },
+                "agent": {
+                    "exit_code": 0,
+                    "duration_ms": 1200,
+                    "usage": { "input_tokens": 100, "output_tokens": 20 },
+                },
+            }),
+        );
+        write_session(
+            root,
+            "s-fail",
+            serde_json::json!({
+                "created_at": now.to_rfc3339(),
+                "model": { "scope": "approve" },
+                "agent": { "exit_code": 1, "duration_ms": 800 },
+            }),
+        );
+        write_session(
+            root,
+            "s-old",
+            serde_json::json!({
+                "created_at": (now - Duration::days(30)).to_rfc3339(),
+                "model": { "scope": "draft" },
+                "agent": { "exit_code": 0, "duration_ms": 5 },
+            }),
+        );
+
+        let gate = JobMetadata {
+            scope: Some("approve".to_string()),
+            plan: Some("alpha".to_string()),
+            workflow_control_policy: Some("gate.cicd".to_string()),
+            workflow_node_outcome: Some("failed".to_string()),
+            workflow_node_attempt: Some(3),
+            ..JobMetadata::default()
+        };
+        write_job(&jobs_root, "job-gate", now, gate);
+
+        let report =
+            collect_stats(root, &jobs_root, Some(now - Duration::days(7))).expect("collect stats");
+        assert_eq!(
+            report.sessions_scanned, 2,
+            "the 30-day-old session is filtered"
+        );
+        assert_eq!(report.jobs_scanned, 1);
+
+        let approve = &report.by_scope["approve"];
+        assert_eq!(approve.agent_runs, 2);
+        assert_eq!(approve.agent_wall_ms, 2000);
+        assert_eq!(approve.mean_agent_ms(), Some(1000));
+        assert_eq!(approve.exit_codes.get("1"), Some(&1));
+        assert_eq!(approve.gate_failure_rate(), Some(1.0));
+        assert_eq!(approve.retries, 2);
+        assert_eq!(approve.tokens, Some(120));
+
+        let alpha = &report.by_plan["alpha"];
+        assert_eq!(alpha.sessions, 1, "job session inherits the job's plan");
+        assert_eq!(alpha.gate_failures, 1);
+        assert_eq!(report.by_plan[UNATTRIBUTED].sessions, 1);
+        assert!(!report.by_scope.contains_key("draft"));
+    }
+}
@@ -0,0 +1,268 @@
+use std::path::Path;
+use std::process::Command;
+
+use git2::{DiffFormat, DiffOptions, Repository};
+use serde::Serialize;
+
+pub use vizier_kernel::stop_condition::*;
+
+/// Where and how the conditions of one stop-gate attempt are measured.
+#[derive(Clone, Copy, Debug)]
+pub struct StopContext<'a> {
+    /// Worktree the approve agent edited.
+    pub root: &'a Path,
+    /// Branch the diff is measured against (from its merge base); uncommitted edits count too.
+    pub target: Option<&'a str>,
+    /// Script deciding `script_pass`.
+    pub script: Option<&'a str>,
+    /// Command deciding `tests_pass`.
+    pub tests: Option<&'a str>,
+    /// Gate context exported to the script and test commands.
+    pub env: &'a [(String, String)],
+}
+
+/// One condition's result for an attempt, as logged.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ConditionResult {
+    pub condition: String,
+    pub passed: bool,
+    pub detail: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StopEvaluation {
+    pub passed: bool,
+    pub results: Vec<ConditionResult>,
+    /// Output of the script and test commands that ran.
+    pub output: String,
+}
+
+/// Size of the branch diff the numeric conditions compare against.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DiffMeasure {
+    pub diff_lines: u64,
+    pub files_changed: u64,
+    pub todo_added: u64,
+}
+
+impl DiffMeasure {
+    fn value(&self, metric: &str) -> Option<u64> {
+        match metric {
+            "diff_lines" => Some(self.diff_lines),
+            "files_changed" => Some(self.files_changed),
+            "todo_added" => Some(self.todo_added),
+            _ => None,
+        }
+    }
+}
+
+const TODO_MARKERS: [&str; 3] = ["TODO", "FIXME", "XXX"];
+
+/// Evaluates every condition of `expression` (none are skipped, so each one is logged), then
+/// combines the results.
+pub fn evaluate(expression: &StopExpression, context: &StopContext<'_>) -> StopEvaluation {
+    let mut output = String::new();
+    let mut measure: Option<Result<DiffMeasure, String>> = None;
+    let mut results = Vec::new();
+    for condition in expression.conditions() {
+        let (passed, detail) = match condition {
+            StopCondition::Flag(name) if name == "tests_pass" => {
+                run_condition_command(context, context.tests, "tests", &mut output)
+            }
+            StopCondition::Flag(name) if name == "script_pass" => {
+                run_condition_command(context, context.script, "script", &mut output)
+            }
+            StopCondition::Flag(_) | StopCondition::Compare { .. } => {
+                let measured = measure
+                    .get_or_insert_with(|| measure_diff(context.root, context.target))
+                    .clone();
+                match (measured, condition) {
+                    (Err(err), _) => (false, err),
+                    (Ok(measure), StopCondition::Compare { metric, op, limit }) => {
+                        let value = measure.value(metric).unwrap_or_default();
+                        (op.holds(value, *limit), format!("{metric} = {value}"))
+                    }
+                    (Ok(measure), StopCondition::Flag(_)) => (
+                        measure.todo_added == 0,
+                        format!("todo_added = {}", measure.todo_added),
+                    ),
+                }
+            }
+        };
+        results.push(ConditionResult {
+            condition: condition.to_string(),
+            passed,
+            detail,
+        });
+    }
+    let passed = expression.evaluate(&results.iter().map(|r| r.passed).collect::<Vec<_>>());
+    StopEvaluation {
+        passed,
+        results,
+        output,
+    }
+}
+
+fn run_condition_command(
+    context: &StopContext<'_>,
+    command: Option<&str>,
+    what: &str,
+    output: &mut String,
+) -> (bool, String) {
+    let Some(command) = command.map(str::trim).filter(|command| !command.is_empty()) else {
+        return (
+            false,
+            format!("no stop-condition {what} command configured"),
+        );
+    };
+    match Command::new("sh")
+        .arg("-lc")
+        .arg(command)
+        .current_dir(context.root)
+        .envs(context.env.iter().map(|(key, value)| (key, value)))
+        .output()
+    {
+        Ok(result) => {
+            output.push_str(&String::from_utf8_lossy(&result.stdout));
+            output.push_str(&String::from_utf8_lossy(&result.stderr));
+            let code = result.status.code().unwrap_or(1);
+            (
+                result.status.success(),
+                format!("`{command}` exited {code}"),
+            )
+        }
+        Err(err) => (false, format!("`{command}` failed to start: {err}")),
+    }
+}
+
+/// Lines changed, files changed, and TODO/FIXME/XXX markers added in `root` since its merge
+/// base with `target` (or since `HEAD` without one), including uncommitted and untracked files.
+pub fn measure_diff(root: &Path, target: Option<&str>) -> Result<DiffMeasure, String> {
+    let measure = || -> Result<DiffMeasure, git2::Error> {
+        let repo = Repository::discover(root)?;
+        // An unborn HEAD measures everything in the worktree.
+        let head = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+        let base = match (
+            head,
+            target.and_then(|target| repo.revparse_single(target).ok()),
+        ) {
+            (Some(head), Some(target)) => {
+                let target = target.peel_to_commit()?;
+                Some(repo.find_commit(repo.merge_base(head.id(), target.id())?)?)
+            }
+            (head, _) => head,
+        };
+        let base_tree = base.map(|commit| commit.tree()).transpose()?;
+        let mut options = DiffOptions::new();
+        options
+            .include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .show_untracked_content(true);
+        let diff = repo.diff_tree_to_workdir_with_index(base_tree.as_ref(), Some(&mut options))?;
+        let stats = diff.stats()?;
+        let mut todo_added = 0;
+        diff.print(DiffFormat::Patch, |_, _, line| {
+            if line.origin() == '+' {
+                let text = String::from_utf8_lossy(line.content());
+                if TODO_MARKERS.iter().any(|marker| text.contains(marker)) {
+                    todo_added += 1;
+                }
+            }
+            true
+        })?;
+        Ok(DiffMeasure {
+            diff_lines: (stats.insertions() + stats.deletions()) as u64,
+            files_changed: stats.files_changed() as u64,
+            todo_added,
+        })
+    };
+    measure().map_err(|err| format!("could not measure the diff: {}", err.message()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn commit_all(repo: &Repository, message: &str) {
+        let mut index = repo.index().expect("index");
+        index
+            .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+            .expect("add");
+        index.write().expect("write index");
+        let tree = repo
+            .find_tree(index.write_tree().expect("tree"))
+            .expect("find tree");
+        let sig = git2::Signature::now("vizier", "vizier@example.com").expect("sig");
+        let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+        let parents = parent.iter().collect::<Vec<_>>();
+        repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+            .expect("commit");
+    }
+
+    #[test]
+    fn evaluate_logs_every_condition_against_the_branch_diff() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let repo = Repository::init(dir.path()).expect("init");
+        fs::write(dir.path().join("lib.rs"), "fn a() {}\n").expect("write");
+        commit_all(&repo, "base");
+        let base = repo.head().expect("head").peel_to_commit().expect("commit");
+        repo.branch("main", &base, true).expect("branch");
+
+        fs::write(
+            dir.path().join("lib.rs"),
+            "fn a() {}\n// TODO: finish\nfn b() {}\n",
+        )
+        .expect("write");
+        commit_all(&repo, "work");
+        fs::write(dir.path().join("new.rs"), "fn c() {}\n").expect("write");
+
+        let expression = StopExpression::parse(
+            "tests_pass && no_todo_added && diff_lines < 10 && files_changed == 2",
+        )
+        .expect("parse");
+        let context = StopContext {
+            root: dir.path(),
+            target: Some("main"),
+            script: None,
+            tests: Some("echo ran tests"),
+            env: &[],
+        };
+        let evaluation = evaluate(&expression, &context);
+        assert!(!evaluation.passed);
+        assert_eq!(
+            evaluation
+                .results
+                .iter()
+                .map(|result| (
+                    result.condition.as_str(),
+                    result.passed,
+                    result.detail.as_str()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("tests_pass", true, "`echo ran tests` exited 0"),
+                ("no_todo_added", false, "todo_added = 1"),
+                ("diff_lines < 10", true, "diff_lines = 3"),
+                ("files_changed == 2", true, "files_changed = 2"),
+            ]
+        );
+        assert!(evaluation.output.contains("ran tests"));
+
+        let relaxed = StopExpression::parse("todo_added <= 1 && script_pass").expect("parse");
+        let evaluation = evaluate(&relaxed, &context);
+        assert!(!evaluation.passed, "no script is configured");
+        assert_eq!(
+            evaluation.results[1].detail,
+            "no stop-condition script command configured"
+        );
+        let evaluation = evaluate(
+            &relaxed,
+            &StopContext {
+                script: Some("true"),
+                ..context
+            },
+        );
+        assert!(evaluation.passed);
+    }
+}
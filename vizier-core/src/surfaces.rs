@@ -0,0 +1,234 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use git2::{DiffOptions, Repository};
+use serde::Serialize;
+
+use crate::file_tracking::is_vizier_path;
+use crate::plan;
+
+/// Heading of the optional plan section listing the paths an approve run is expected to touch.
+pub const EXPECTED_SURFACES_HEADING: &str = "Expected Surfaces";
+
+/// Paths listed under `## Expected Surfaces`, one per `-`/`*` bullet. A backticked span wins over
+/// the rest of the bullet, so `` - `src/lib.rs` — parser entry point `` lists `src/lib.rs`.
+/// Entries ending in `/` cover a directory; `*` matches any run of characters.
+pub fn expected_surfaces(plan_body: &str) -> Vec<String> {
+    let mut entries = Vec::new();
+    let mut section_level = None;
+    let mut in_fence = false;
+    for line in plan_body.lines() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+        let level = line.chars().take_while(|ch| *ch == '#').count();
+        if level > 0 && line[level..].starts_with(' ') {
+            let heading = line[level..].trim();
+            if section_level.is_some_and(|open| level <= open) {
+                break;
+            }
+            if heading.eq_ignore_ascii_case(EXPECTED_SURFACES_HEADING) {
+                section_level = Some(level);
+            }
+            continue;
+        }
+        if section_level.is_none() {
+            continue;
+        }
+        let Some(item) = line
+            .trim_start()
+            .strip_prefix("- ")
+            .or_else(|| line.trim_start().strip_prefix("* "))
+        else {
+            continue;
+        };
+        let entry = match item.split('`').nth(1) {
+            Some(span) if item.contains('`') => span.trim(),
+            _ => item.split_whitespace().next().unwrap_or(""),
+        };
+        let entry = entry.trim_start_matches("./");
+        if !entry.is_empty() && !entries.iter().any(|existing| existing == entry) {
+            entries.push(entry.to_string());
+        }
+    }
+    entries
+}
+
+/// How a branch's changes line up with the plan's expected surfaces. `.vizier/` paths are
+/// ignored on both sides.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct SurfaceCheck {
+    pub expected: Vec<String>,
+    /// Changed paths covered by an expected entry.
+    pub touched: Vec<String>,
+    /// Expected entries no changed path matched.
+    pub missing: Vec<String>,
+    /// Changed paths no expected entry covers.
+    pub unexpected: Vec<String>,
+}
+
+impl SurfaceCheck {
+    pub fn is_clean(&self) -> bool {
+        self.missing.is_empty() && self.unexpected.is_empty()
+    }
+
+    pub fn summary_line(&self) -> String {
+        let mut line = format!(
+            "expected surfaces: {} of {} touched",
+            self.expected.len() - self.missing.len(),
+            self.expected.len()
+        );
+        if !self.missing.is_empty() {
+            line.push_str(&format!("; missing: {}", self.missing.join(", ")));
+        }
+        if !self.unexpected.is_empty() {
+            line.push_str(&format!("; unexpected: {}", self.unexpected.join(", ")));
+        }
+        line
+    }
+
+    /// Markdown block for review prompts.
+    pub fn render(&self) -> String {
+        let list = |paths: &[String]| {
+            if paths.is_empty() {
+                "- none\n".to_string()
+            } else {
+                paths.iter().map(|path| format!("- `{path}`\n")).collect()
+            }
+        };
+        format!(
+            "The plan declared {} expected surface(s); {} changed path(s) fall inside them.\n\nExpected but untouched:\n{}\nChanged outside the expected surfaces:\n{}",
+            self.expected.len(),
+            self.touched.len(),
+            list(&self.missing),
+            list(&self.unexpected)
+        )
+    }
+}
+
+pub fn check(expected: &[String], changed: &[String]) -> SurfaceCheck {
+    let changed = changed
+        .iter()
+        .filter(|path| !is_vizier_path(path))
+        .collect::<BTreeSet<_>>();
+    let expected = expected
+        .iter()
+        .filter(|entry| !is_vizier_path(entry))
+        .cloned()
+        .collect::<Vec<_>>();
+    let (touched, unexpected): (Vec<&String>, Vec<&String>) = changed
+        .iter()
+        .partition(|path| expected.iter().any(|entry| covers(entry, path)));
+    let missing = expected
+        .iter()
+        .filter(|entry| !changed.iter().any(|path| covers(entry, path)))
+        .cloned()
+        .collect();
+    SurfaceCheck {
+        expected,
+        touched: touched.into_iter().cloned().collect(),
+        missing,
+        unexpected: unexpected.into_iter().cloned().collect(),
+    }
+}
+
+/// Checks `head` against the expected surfaces its committed plan document for `slug` declares,
+/// using the diff from the merge base with `target`. `None` when the plan declares none.
+pub fn check_branch(
+    repo_path: &Path,
+    slug: &str,
+    target: &str,
+    head: &str,
+) -> Result<Option<SurfaceCheck>, git2::Error> {
+    let repo = Repository::discover(repo_path)?;
+    let head = repo.revparse_single(head)?.peel_to_commit()?;
+    let plan_body = head
+        .tree()?
+        .get_path(&plan::plan_rel_path(slug))
+        .ok()
+        .and_then(|entry| repo.find_blob(entry.id()).ok())
+        .map(|blob| String::from_utf8_lossy(blob.content()).into_owned())
+        .unwrap_or_default();
+    let expected = expected_surfaces(&plan_body);
+    if expected.is_empty() {
+        return Ok(None);
+    }
+
+    let target = repo.revparse_single(target)?.peel_to_commit()?;
+    let base = repo.find_commit(repo.merge_base(target.id(), head.id())?)?;
+    let diff = repo.diff_tree_to_tree(
+        Some(&base.tree()?),
+        Some(&head.tree()?),
+        Some(&mut DiffOptions::new()),
+    )?;
+    let changed = diff
+        .deltas()
+        .filter_map(|delta| delta.new_file().path().or_else(|| delta.old_file().path()))
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect::<Vec<_>>();
+    Ok(Some(check(&expected, &changed)))
+}
+
+pub(crate) fn covers(entry: &str, path: &str) -> bool {
+    if let Some(dir) = entry.strip_suffix('/') {
+        return path.starts_with(&format!("{dir}/"));
+    }
+    vizier_kernel::glob::glob_matches(entry, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expected_surfaces_reads_only_the_section_bullets() {
+        let plan = "\
+# Plan
+
+## Implementation Plan
+- Touch `src/other.rs`
+
+## Expected Surfaces
+- `src/lib.rs` — parser entry point
+- docs/user/
+* ./tests/*.rs
+- `src/lib.rs`
+
+## Risks
+- `src/risky.rs`
+";
+        assert_eq!(
+            expected_surfaces(plan),
+            vec!["src/lib.rs", "docs/user/", "tests/*.rs"]
+        );
+        assert!(expected_surfaces("## Implementation Plan\n- `src/lib.rs`\n").is_empty());
+    }
+
+    #[test]
+    fn check_flags_missing_and_unexpected_paths() {
+        let expected = vec![
+            "src/lib.rs".to_string(),
+            "docs/user/".to_string(),
+            "tests/*.rs".to_string(),
+        ];
+        let changed = vec![
+            "src/lib.rs".to_string(),
+            "tests/parse.rs".to_string(),
+            "Cargo.toml".to_string(),
+            ".vizier/implementation-plans/alpha.md".to_string(),
+        ];
+        let check = check(&expected, &changed);
+        assert_eq!(check.touched, vec!["src/lib.rs", "tests/parse.rs"]);
+        assert_eq!(check.missing, vec!["docs/user/"]);
+        assert_eq!(check.unexpected, vec!["Cargo.toml"]);
+        assert!(!check.is_clean());
+        assert_eq!(
+            check.summary_line(),
+            "expected surfaces: 2 of 3 touched; missing: docs/user/; unexpected: Cargo.toml"
+        );
+    }
+}
@@ -0,0 +1,364 @@
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{atomic_file, auditor::Auditor};
+
+/// Root of everything Vizier writes that is safe to lose; ignored by git and by narrative walks.
+pub const TMP_DIR: &str = ".vizier/tmp";
+/// Per-session scratch directories handed out by [`TempRegistry::scratch`].
+pub const SESSIONS_TMP_DIR: &str = ".vizier/tmp/sessions";
+pub const MERGE_CONFLICTS_DIR: &str = ".vizier/tmp/merge-conflicts";
+pub const GATE_CACHE_DIR: &str = ".vizier/tmp/cache/gates";
+pub const AGENT_CACHE_DIR: &str = ".vizier/tmp/cache/agent";
+pub const SEARCH_CACHE_DIR: &str = ".vizier/tmp/cache/search";
+pub const CONTEXT_CACHE_DIR: &str = ".vizier/tmp/context-cache";
+pub const REVIEW_LEDGER_DIR: &str = ".vizier/tmp/review-ledger";
+pub const MIGRATION_BACKUP_DIR: &str = ".vizier/tmp/migrations";
+pub const RESCUE_DIR: &str = ".vizier/tmp/rescue";
+
+const MANIFEST_FILE: &str = "manifest.json";
+const MANIFEST_LOCK_FILE: &str = "manifest.lock";
+
+/// One session's claim on `.vizier/tmp/sessions/<session>/`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TempSessionEntry {
+    pub pid: u32,
+    pub created_at: String,
+    #[serde(default)]
+    pub dirs: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct TempManifest {
+    #[serde(default)]
+    sessions: BTreeMap<String, TempSessionEntry>,
+}
+
+/// A session directory removed by [`TempRegistry::cleanup_orphans`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct OrphanedTempDir {
+    pub session: String,
+    pub path: PathBuf,
+    pub age_days: u64,
+    /// Whether the manifest knew about the directory; unregistered ones predate the registry or
+    /// lost their entry to a crash mid-write.
+    pub registered: bool,
+}
+
+/// Hands out scratch directories namespaced by session so concurrent commands never share one,
+/// and records them in a manifest so directories left by crashed runs can be collected later.
+#[derive(Clone, Debug)]
+pub struct TempRegistry {
+    project_root: PathBuf,
+}
+
+impl TempRegistry {
+    pub fn new(project_root: &Path) -> Self {
+        Self {
+            project_root: project_root.to_path_buf(),
+        }
+    }
+
+    /// Shared area under `.vizier/tmp/` (one of the `*_DIR` constants) resolved against the
+    /// project root; these outlive the command that writes them and are never collected here.
+    pub fn area(&self, rel: &str) -> PathBuf {
+        self.project_root.join(rel)
+    }
+
+    fn sessions_root(&self) -> PathBuf {
+        self.project_root.join(SESSIONS_TMP_DIR)
+    }
+
+    /// Creates `.vizier/tmp/sessions/<session>/<purpose>-<id>/`, removed when the guard drops.
+    pub fn scratch(&self, purpose: &str) -> io::Result<ScratchDir> {
+        let session = match Auditor::session_id() {
+            id if id.is_empty() => format!("pid-{}", std::process::id()),
+            id => id,
+        };
+        let name = format!("{purpose}-{}", &Uuid::new_v4().simple().to_string()[..8]);
+        let path = self.sessions_root().join(&session).join(&name);
+        fs::create_dir_all(&path)?;
+
+        self.update_manifest(|manifest| {
+            let entry =
+                manifest
+                    .sessions
+                    .entry(session.clone())
+                    .or_insert_with(|| TempSessionEntry {
+                        pid: std::process::id(),
+                        created_at: Utc::now().to_rfc3339(),
+                        dirs: Vec::new(),
+                    });
+            entry.pid = std::process::id();
+            entry.dirs.push(name.clone());
+        })?;
+
+        Ok(ScratchDir {
+            registry: self.clone(),
+            session,
+            name,
+            path,
+        })
+    }
+
+    /// Sessions currently holding scratch directories, keyed by session id.
+    pub fn sessions(&self) -> io::Result<BTreeMap<String, TempSessionEntry>> {
+        Ok(self.read_manifest()?.sessions)
+    }
+
+    /// Removes session directories older than `keep_days` whose owning process is gone, plus
+    /// unregistered directories past the same age. `keep_days == 0` disables collection.
+    pub fn cleanup_orphans(&self, keep_days: u32) -> io::Result<Vec<OrphanedTempDir>> {
+        let root = self.sessions_root();
+        if keep_days == 0 || !root.is_dir() {
+            return Ok(Vec::new());
+        }
+        let max_age = Duration::from_secs(u64::from(keep_days) * 86_400);
+        let now = SystemTime::now();
+        let mut removed = Vec::new();
+
+        let _lock = ManifestLock::acquire(&root)?;
+        let mut manifest = read_manifest_at(&root)?;
+        for entry in fs::read_dir(&root)? {
+            let entry = entry?;
+            if !entry.file_type()?.is_dir() {
+                continue;
+            }
+            let session = entry.file_name().to_string_lossy().into_owned();
+            let registered = manifest.sessions.get(&session);
+            if registered.is_some_and(|entry| pid_is_running(entry.pid)) {
+                continue;
+            }
+            let created = registered
+                .and_then(|entry| DateTime::parse_from_rfc3339(&entry.created_at).ok())
+                .map(SystemTime::from)
+                .or_else(|| entry.metadata().and_then(|meta| meta.modified()).ok());
+            let Some(age) = created.and_then(|created| now.duration_since(created).ok()) else {
+                continue;
+            };
+            if age < max_age {
+                continue;
+            }
+            fs::remove_dir_all(entry.path())?;
+            removed.push(OrphanedTempDir {
+                registered: registered.is_some(),
+                path: entry.path(),
+                age_days: age.as_secs() / 86_400,
+                session: session.clone(),
+            });
+            manifest.sessions.remove(&session);
+        }
+        // Entries whose directory vanished some other way are dropped with the rest.
+        manifest
+            .sessions
+            .retain(|session, _| root.join(session).is_dir());
+        write_manifest_at(&root, &manifest)?;
+        Ok(removed)
+    }
+
+    fn read_manifest(&self) -> io::Result<TempManifest> {
+        read_manifest_at(&self.sessions_root())
+    }
+
+    fn update_manifest(&self, update: impl FnOnce(&mut TempManifest)) -> io::Result<()> {
+        let root = self.sessions_root();
+        fs::create_dir_all(&root)?;
+        let _lock = ManifestLock::acquire(&root)?;
+        let mut manifest = read_manifest_at(&root)?;
+        update(&mut manifest);
+        write_manifest_at(&root, &manifest)
+    }
+}
+
+/// A registered scratch directory; dropping it deletes the directory and its manifest entry.
+#[derive(Debug)]
+pub struct ScratchDir {
+    registry: TempRegistry,
+    session: String,
+    name: String,
+    path: PathBuf,
+}
+
+impl ScratchDir {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+        let session_dir = self.registry.sessions_root().join(&self.session);
+        let _ = self.registry.update_manifest(|manifest| {
+            if let Some(entry) = manifest.sessions.get_mut(&self.session) {
+                entry.dirs.retain(|dir| dir != &self.name);
+                if entry.dirs.is_empty() {
+                    manifest.sessions.remove(&self.session);
+                }
+            }
+        });
+        // Only succeeds once the session's last scratch directory is gone.
+        let _ = fs::remove_dir(session_dir);
+    }
+}
+
+fn read_manifest_at(root: &Path) -> io::Result<TempManifest> {
+    match fs::read(root.join(MANIFEST_FILE)) {
+        // A manifest torn by a crash is rebuilt from scratch; directories it listed are then
+        // collected as unregistered once they age out.
+        Ok(bytes) => Ok(serde_json::from_slice(&bytes).unwrap_or_default()),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(TempManifest::default()),
+        Err(err) => Err(err),
+    }
+}
+
+fn write_manifest_at(root: &Path, manifest: &TempManifest) -> io::Result<()> {
+    let path = root.join(MANIFEST_FILE);
+    if manifest.sessions.is_empty() {
+        return match fs::remove_file(&path) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        };
+    }
+    let bytes = serde_json::to_vec_pretty(manifest).map_err(io::Error::other)?;
+    atomic_file::write_atomic(&path, &bytes)
+}
+
+struct ManifestLock {
+    path: PathBuf,
+}
+
+impl ManifestLock {
+    fn acquire(root: &Path) -> io::Result<Self> {
+        let path = root.join(MANIFEST_LOCK_FILE);
+        let mut wait_ms = 5u64;
+        for _ in 0..40 {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    let _ = writeln!(file, "pid={}", std::process::id());
+                    return Ok(Self { path });
+                }
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {
+                    // A lock left by a crashed process would otherwise wedge every command.
+                    if lock_is_stale(&path) {
+                        let _ = fs::remove_file(&path);
+                        continue;
+                    }
+                    thread::sleep(Duration::from_millis(wait_ms));
+                    wait_ms = (wait_ms * 2).min(80);
+                }
+                Err(err) => return Err(err),
+            }
+        }
+        Err(io::Error::new(
+            io::ErrorKind::WouldBlock,
+            format!("{} is held by another process", path.display()),
+        ))
+    }
+}
+
+impl Drop for ManifestLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn lock_is_stale(path: &Path) -> bool {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|contents| contents.trim().strip_prefix("pid=")?.parse::<u32>().ok())
+        .is_some_and(|pid| !pid_is_running(pid))
+}
+
+fn pid_is_running(pid: u32) -> bool {
+    pid == std::process::id() || crate::jobs::pid_is_running(pid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scratch_dirs_register_and_unregister_with_the_manifest() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let registry = TempRegistry::new(dir.path());
+
+        let first = registry.scratch("hunk").expect("first scratch");
+        let second = registry.scratch("hunk").expect("second scratch");
+        assert_ne!(first.path(), second.path());
+        assert!(first.path().starts_with(dir.path().join(SESSIONS_TMP_DIR)));
+        let sessions = registry.sessions().expect("sessions");
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(
+            sessions.values().next().map(|entry| entry.dirs.len()),
+            Some(2)
+        );
+
+        let first_path = first.path().to_path_buf();
+        drop(first);
+        assert!(!first_path.exists());
+        drop(second);
+        assert!(registry.sessions().expect("sessions").is_empty());
+        assert!(
+            fs::read_dir(dir.path().join(SESSIONS_TMP_DIR))
+                .expect("read sessions root")
+                .next()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn cleanup_removes_only_aged_dirs_of_dead_sessions() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let root = dir.path().join(SESSIONS_TMP_DIR);
+        let registry = TempRegistry::new(dir.path());
+        let old = (Utc::now() - chrono::Duration::days(10)).to_rfc3339();
+        let fresh = Utc::now().to_rfc3339();
+        let mut manifest = TempManifest::default();
+        for (session, pid, created_at) in [
+            ("crashed", u32::MAX - 1, old.clone()),
+            ("recent", u32::MAX - 1, fresh),
+            ("live", std::process::id(), old),
+        ] {
+            fs::create_dir_all(root.join(session).join("work")).expect("session dir");
+            manifest.sessions.insert(
+                session.to_string(),
+                TempSessionEntry {
+                    pid,
+                    created_at,
+                    dirs: vec!["work".to_string()],
+                },
+            );
+        }
+        write_manifest_at(&root, &manifest).expect("write manifest");
+
+        let removed = registry.cleanup_orphans(7).expect("cleanup");
+        assert_eq!(
+            removed
+                .iter()
+                .map(|orphan| (orphan.session.as_str(), orphan.registered))
+                .collect::<Vec<_>>(),
+            vec![("crashed", true)]
+        );
+        assert!(!root.join("crashed").exists());
+        assert!(root.join("recent").is_dir());
+        assert!(root.join("live").is_dir());
+        assert_eq!(
+            registry
+                .sessions()
+                .expect("sessions")
+                .keys()
+                .collect::<Vec<_>>(),
+            vec!["live", "recent"]
+        );
+        assert!(registry.cleanup_orphans(0).expect("disabled").is_empty());
+    }
+}
@@ -0,0 +1,332 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use git2::{Repository, Tree};
+use serde::{Deserialize, Serialize};
+
+use crate::plan;
+
+pub const THREADS_DIR: &str = ".vizier/narrative/threads";
+/// Recorded `vizier ack` entries, one JSON file per plan slug.
+pub const ACK_DIR: &str = ".vizier/state/acks";
+
+/// A narrative thread affected by a plan, with the owners its front matter declares.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct OwnedThread {
+    pub thread: String,
+    pub owners: Vec<String>,
+}
+
+/// One recorded acknowledgment of a plan by a thread owner.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlanAck {
+    pub owner: String,
+    pub acknowledged_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch_tip: Option<String>,
+}
+
+/// Owners from a thread's `owners:` (or `owner:`) front matter: `alice, bob@example.com` or
+/// `[alice, bob@example.com]`.
+pub fn thread_owners(document: &str) -> Vec<String> {
+    let fields = plan::front_matter_fields(document);
+    let Some(value) = fields.get("owners").or_else(|| fields.get("owner")) else {
+        return Vec::new();
+    };
+    let mut owners = Vec::new();
+    for owner in value
+        .trim()
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .split(',')
+    {
+        let owner = owner.trim().trim_matches(['"', '\'']);
+        if !owner.is_empty() && !owners.iter().any(|existing| existing == owner) {
+            owners.push(owner.to_string());
+        }
+    }
+    owners
+}
+
+/// True when `who` names `owner`: a case-insensitive match, or the local part of an email owner.
+pub fn owner_matches(owner: &str, who: &str) -> bool {
+    let who = who.trim();
+    owner.eq_ignore_ascii_case(who)
+        || owner
+            .split_once('@')
+            .is_some_and(|(local, _)| local.eq_ignore_ascii_case(who))
+}
+
+/// Threads `branch` affects: threads its `threads:` front matter links plus thread files it
+/// changed since the merge base with `target`, sorted by name.
+pub fn affected_thread_names(
+    repo_path: &Path,
+    target: &str,
+    branch: &str,
+    plan_document: Option<&str>,
+) -> Result<Vec<String>, git2::Error> {
+    let repo = Repository::discover(repo_path)?;
+    let tip = repo.revparse_single(branch)?.peel_to_commit()?;
+    let target = repo.revparse_single(target)?.peel_to_commit()?;
+    let base = repo.find_commit(repo.merge_base(target.id(), tip.id())?)?;
+
+    let mut threads = plan_document
+        .map(plan::front_matter_fields)
+        .and_then(|fields| {
+            fields
+                .get("threads")
+                .or_else(|| fields.get("thread"))
+                .map(|value| plan::parse_thread_links(value))
+        })
+        .unwrap_or_default()
+        .into_iter()
+        .collect::<BTreeSet<_>>();
+    let diff = repo.diff_tree_to_tree(Some(&base.tree()?), Some(&tip.tree()?), None)?;
+    for delta in diff.deltas() {
+        let Some(path) = delta.new_file().path().or_else(|| delta.old_file().path()) else {
+            continue;
+        };
+        if let Ok(rest) = path.strip_prefix(THREADS_DIR)
+            && rest.extension().is_some_and(|ext| ext == "md")
+            && let Some(stem) = rest.file_stem()
+        {
+            threads.insert(stem.to_string_lossy().into_owned());
+        }
+    }
+    Ok(threads.into_iter().collect())
+}
+
+/// Owned threads `branch` affects (see [`affected_thread_names`]). Owners are read from the
+/// branch's copy of each thread, falling back to the target's when the branch deleted it.
+/// Unowned threads are left out.
+pub fn affected_threads(
+    repo_path: &Path,
+    target: &str,
+    branch: &str,
+    plan_document: Option<&str>,
+) -> Result<Vec<OwnedThread>, git2::Error> {
+    let threads = affected_thread_names(repo_path, target, branch, plan_document)?;
+    let repo = Repository::discover(repo_path)?;
+    let tip_tree = repo.revparse_single(branch)?.peel_to_commit()?.tree()?;
+    let target_tree = repo.revparse_single(target)?.peel_to_commit()?.tree()?;
+    let mut owned = Vec::new();
+    for thread in threads {
+        let rel = Path::new(THREADS_DIR).join(format!("{thread}.md"));
+        let document = read_blob(&repo, &tip_tree, &rel)
+            .or_else(|| read_blob(&repo, &target_tree, &rel))
+            .unwrap_or_default();
+        let owners = thread_owners(&document);
+        if !owners.is_empty() {
+            owned.push(OwnedThread { thread, owners });
+        }
+    }
+    Ok(owned)
+}
+
+/// `thread owners: alice (auth), bob@example.com (auth, billing)`; `None` with no owned threads.
+pub fn summary_line(threads: &[OwnedThread]) -> Option<String> {
+    let mut owners: Vec<(String, Vec<&str>)> = Vec::new();
+    for thread in threads {
+        for owner in &thread.owners {
+            match owners.iter_mut().find(|(existing, _)| existing == owner) {
+                Some((_, names)) => names.push(&thread.thread),
+                None => owners.push((owner.clone(), vec![&thread.thread])),
+            }
+        }
+    }
+    if owners.is_empty() {
+        return None;
+    }
+    let rendered = owners
+        .iter()
+        .map(|(owner, names)| format!("{owner} ({})", names.join(", ")))
+        .collect::<Vec<_>>()
+        .join(", ");
+    Some(format!("thread owners: {rendered}"))
+}
+
+/// Threads none of whose owners has acknowledged the plan.
+pub fn unacknowledged<'a>(threads: &'a [OwnedThread], acks: &[PlanAck]) -> Vec<&'a OwnedThread> {
+    threads
+        .iter()
+        .filter(|thread| {
+            !thread.owners.iter().any(|owner| {
+                acks.iter()
+                    .any(|ack| owner_matches(owner, &ack.owner) || owner_matches(&ack.owner, owner))
+            })
+        })
+        .collect()
+}
+
+pub fn ack_path(project_root: &Path, slug: &str) -> PathBuf {
+    project_root.join(ACK_DIR).join(format!("{slug}.json"))
+}
+
+pub fn load_acks(project_root: &Path, slug: &str) -> io::Result<Vec<PlanAck>> {
+    match fs::read(ack_path(project_root, slug)) {
+        Ok(bytes) => serde_json::from_slice(&bytes)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(err) => Err(err),
+    }
+}
+
+/// Records `owner`'s acknowledgment of `slug`, replacing an earlier one by the same owner.
+pub fn record_ack(
+    project_root: &Path,
+    slug: &str,
+    owner: &str,
+    branch_tip: Option<String>,
+) -> io::Result<PlanAck> {
+    let mut acks = load_acks(project_root, slug)?;
+    acks.retain(|ack| !ack.owner.eq_ignore_ascii_case(owner));
+    let ack = PlanAck {
+        owner: owner.to_string(),
+        acknowledged_at: Utc::now().to_rfc3339(),
+        branch_tip,
+    };
+    acks.push(ack.clone());
+    let path = ack_path(project_root, slug);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let bytes = serde_json::to_vec_pretty(&acks)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    fs::write(path, bytes)?;
+    Ok(ack)
+}
+
+fn read_blob(repo: &Repository, tree: &Tree<'_>, path: &Path) -> Option<String> {
+    let entry = tree.get_path(path).ok()?;
+    let blob = repo.find_blob(entry.id()).ok()?;
+    Some(String::from_utf8_lossy(blob.content()).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit_files(
+        repo: &Repository,
+        refname: &str,
+        parent: Option<git2::Oid>,
+        files: &[(&str, &str)],
+    ) -> git2::Oid {
+        let root = repo.workdir().expect("workdir");
+        let mut index = repo.index().expect("index");
+        for (path, contents) in files {
+            let abs = root.join(path);
+            fs::create_dir_all(abs.parent().expect("parent")).expect("dirs");
+            fs::write(&abs, contents).expect("write");
+            index.add_path(Path::new(path)).expect("add");
+        }
+        let tree = repo
+            .find_tree(index.write_tree().expect("tree"))
+            .expect("find tree");
+        let sig = git2::Signature::now("vizier", "vizier@example.com").expect("sig");
+        let parents = parent
+            .map(|oid| repo.find_commit(oid).expect("parent"))
+            .into_iter()
+            .collect::<Vec<_>>();
+        let parents = parents.iter().collect::<Vec<_>>();
+        repo.commit(Some(refname), &sig, &sig, "commit", &tree, &parents)
+            .expect("commit")
+    }
+
+    #[test]
+    fn affected_threads_combine_linked_and_changed_threads() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let repo = Repository::init(dir.path()).expect("init");
+        let base = commit_files(
+            &repo,
+            "refs/heads/main",
+            None,
+            &[
+                (
+                    ".vizier/narrative/threads/auth.md",
+                    "---\nowners: alice\n---\n# Auth\n",
+                ),
+                (
+                    ".vizier/narrative/threads/billing.md",
+                    "---\nowners: bob\n---\n# Billing\n",
+                ),
+                (".vizier/narrative/threads/docs.md", "# Docs\n"),
+            ],
+        );
+        commit_files(
+            &repo,
+            "refs/heads/draft/alpha",
+            Some(base),
+            &[
+                (
+                    ".vizier/narrative/threads/auth.md",
+                    "---\nowners: alice\n---\n# Auth v2\n",
+                ),
+                (".vizier/narrative/threads/docs.md", "# Docs v2\n"),
+            ],
+        );
+
+        let threads = affected_threads(
+            dir.path(),
+            "main",
+            "draft/alpha",
+            Some("---\nplan: alpha\nthreads: [billing]\n---\n"),
+        )
+        .expect("threads");
+        assert_eq!(
+            threads,
+            vec![
+                OwnedThread {
+                    thread: "auth".to_string(),
+                    owners: vec!["alice".to_string()],
+                },
+                OwnedThread {
+                    thread: "billing".to_string(),
+                    owners: vec!["bob".to_string()],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn owners_parse_and_acks_cover_threads() {
+        assert_eq!(
+            thread_owners("---\nowners: [alice, \"bob@example.com\"]\n---\n# Auth\n"),
+            vec!["alice", "bob@example.com"]
+        );
+        assert!(thread_owners("# No front matter\n").is_empty());
+
+        let threads = vec![
+            OwnedThread {
+                thread: "auth".to_string(),
+                owners: vec!["alice".to_string(), "bob@example.com".to_string()],
+            },
+            OwnedThread {
+                thread: "billing".to_string(),
+                owners: vec!["bob@example.com".to_string()],
+            },
+        ];
+        assert_eq!(
+            summary_line(&threads).as_deref(),
+            Some("thread owners: alice (auth), bob@example.com (auth, billing)")
+        );
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        assert!(load_acks(dir.path(), "alpha").expect("no acks").is_empty());
+        record_ack(dir.path(), "alpha", "alice", None).expect("ack");
+        let acks = load_acks(dir.path(), "alpha").expect("acks");
+        assert_eq!(
+            unacknowledged(&threads, &acks)
+                .iter()
+                .map(|thread| thread.thread.as_str())
+                .collect::<Vec<_>>(),
+            vec!["billing"]
+        );
+        record_ack(dir.path(), "alpha", "Bob", None).expect("ack by email local part");
+        let acks = load_acks(dir.path(), "alpha").expect("acks");
+        assert!(unacknowledged(&threads, &acks).is_empty());
+    }
+}
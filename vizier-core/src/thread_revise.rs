@@ -0,0 +1,288 @@
+//! Bulk revision of narrative threads. `vizier thread revise` sends every thread to the agent in
+//! batches alongside the snapshot and applies one of three outcomes per thread: leave it alone,
+//! rewrite it, or delete it.
+
+use std::path::Path;
+
+use git2::{ObjectType, Repository};
+use serde::{Deserialize, Serialize};
+
+use crate::thread_owners::THREADS_DIR;
+use crate::thread_state;
+
+/// Bytes of thread documents sent in one agent request; larger sets are split across requests.
+pub const MAX_BATCH_BYTES: usize = 60_000;
+
+pub const REVISE_THREADS_INSTRUCTION: &str = "You are revising a project's narrative threads \
+against its current snapshot. For each <thread> below choose one outcome: `null` when the thread \
+is still accurate, `delete` when its work is finished, obsolete, or fully covered by another \
+thread or the snapshot, or `revise` when stale facts, completed items, or vague next steps should \
+be rewritten. Keep a revised thread's front matter, title, and voice. Do not edit files or run \
+commands. Reply with only a JSON array holding one object per thread: \
+{\"thread\": \"<name>\", \"outcome\": \"null\" | \"delete\" | \"revise\", \"document\": \
+\"<complete revised Markdown, revise only>\", \"reason\": \"<one short sentence>\"}.";
+
+/// A thread document as sent to the agent.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ThreadDocument {
+    pub thread: String,
+    pub document: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RevisionOutcome {
+    Unchanged,
+    Revised,
+    Deleted,
+}
+
+impl RevisionOutcome {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Unchanged => "unchanged",
+            Self::Revised => "revised",
+            Self::Deleted => "deleted",
+        }
+    }
+}
+
+/// The agent's decision for one thread. `document` holds the new contents of a revised thread.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ThreadRevision {
+    pub thread: String,
+    pub outcome: RevisionOutcome,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    #[serde(skip)]
+    pub document: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RawRevision {
+    thread: String,
+    #[serde(default)]
+    outcome: Option<String>,
+    #[serde(default)]
+    document: Option<String>,
+    #[serde(default)]
+    reason: Option<String>,
+}
+
+/// Thread documents committed on `revision`, sorted by name.
+pub fn threads_at_revision(
+    repo_path: &Path,
+    revision: &str,
+) -> Result<Vec<ThreadDocument>, git2::Error> {
+    let repo = Repository::discover(repo_path)?;
+    let tree = repo.revparse_single(revision)?.peel_to_commit()?.tree()?;
+    let Ok(entry) = tree.get_path(Path::new(THREADS_DIR)) else {
+        return Ok(Vec::new());
+    };
+    let Some(dir) = entry.to_object(&repo)?.into_tree().ok() else {
+        return Ok(Vec::new());
+    };
+    let mut threads = Vec::new();
+    for entry in dir.iter() {
+        if entry.kind() != Some(ObjectType::Blob) {
+            continue;
+        }
+        let Some(thread) = entry.name().and_then(|name| name.strip_suffix(".md")) else {
+            continue;
+        };
+        let blob = repo.find_blob(entry.id())?;
+        threads.push(ThreadDocument {
+            thread: thread.to_string(),
+            document: String::from_utf8_lossy(blob.content()).into_owned(),
+        });
+    }
+    threads.sort_by(|left, right| left.thread.cmp(&right.thread));
+    Ok(threads)
+}
+
+/// Splits `threads` into batches whose documents total at most `max_bytes`; a thread larger than
+/// the budget gets a batch of its own.
+pub fn batch_threads(threads: Vec<ThreadDocument>, max_bytes: usize) -> Vec<Vec<ThreadDocument>> {
+    let mut batches = Vec::new();
+    let mut current: Vec<ThreadDocument> = Vec::new();
+    let mut size = 0;
+    for thread in threads {
+        let len = thread.document.len();
+        if !current.is_empty() && size + len > max_bytes {
+            batches.push(std::mem::take(&mut current));
+            size = 0;
+        }
+        size += len;
+        current.push(thread);
+    }
+    if !current.is_empty() {
+        batches.push(current);
+    }
+    batches
+}
+
+/// The user message for one batch: the snapshot followed by each thread document.
+pub fn batch_message(snapshot: &str, batch: &[ThreadDocument]) -> String {
+    let mut message = format!("<snapshot>\n{}\n</snapshot>\n", snapshot.trim_end());
+    for thread in batch {
+        message.push_str(&format!(
+            "\n<thread name=\"{}\">\n{}\n</thread>\n",
+            thread.thread,
+            thread.document.trim_end()
+        ));
+    }
+    message
+}
+
+/// Reads the agent's JSON array for `batch`. Threads the response skips, names it does not
+/// recognise, and revisions that leave the document empty or unchanged all count as unchanged;
+/// a revised document keeps the thread's original lifecycle state.
+pub fn parse_revisions(
+    response: &str,
+    batch: &[ThreadDocument],
+) -> Result<Vec<ThreadRevision>, String> {
+    let start = response.find('[');
+    let end = response.rfind(']');
+    let raw: Vec<RawRevision> = match (start, end) {
+        (Some(start), Some(end)) if start < end => serde_json::from_str(&response[start..=end])
+            .map_err(|err| format!("agent reply is not a JSON array of thread outcomes: {err}"))?,
+        _ => return Err("agent reply did not contain a JSON array of thread outcomes".to_string()),
+    };
+
+    Ok(batch
+        .iter()
+        .map(|thread| {
+            let unchanged = |reason: Option<String>| ThreadRevision {
+                thread: thread.thread.clone(),
+                outcome: RevisionOutcome::Unchanged,
+                reason,
+                document: None,
+            };
+            let Some(entry) = raw
+                .iter()
+                .find(|entry| entry.thread.trim() == thread.thread)
+            else {
+                return unchanged(None);
+            };
+            let reason = entry
+                .reason
+                .as_deref()
+                .map(str::trim)
+                .filter(|reason| !reason.is_empty())
+                .map(str::to_string);
+            let outcome = entry
+                .outcome
+                .as_deref()
+                .map(|outcome| outcome.trim().to_ascii_lowercase());
+            match outcome.as_deref() {
+                Some("delete" | "deleted") => ThreadRevision {
+                    thread: thread.thread.clone(),
+                    outcome: RevisionOutcome::Deleted,
+                    reason,
+                    document: None,
+                },
+                Some("revise" | "revised") => {
+                    let Some(document) = entry
+                        .document
+                        .as_deref()
+                        .map(str::trim)
+                        .filter(|document| !document.is_empty())
+                    else {
+                        return unchanged(reason);
+                    };
+                    let mut document = match thread_state::thread_status(&thread.document) {
+                        Ok(status) => thread_state::with_status(document, &status),
+                        Err(_) => document.to_string(),
+                    };
+                    if !document.ends_with('\n') {
+                        document.push('\n');
+                    }
+                    if document == thread.document {
+                        return unchanged(reason);
+                    }
+                    ThreadRevision {
+                        thread: thread.thread.clone(),
+                        outcome: RevisionOutcome::Revised,
+                        reason,
+                        document: Some(document),
+                    }
+                }
+                _ => unchanged(reason),
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn thread(name: &str, document: &str) -> ThreadDocument {
+        ThreadDocument {
+            thread: name.to_string(),
+            document: document.to_string(),
+        }
+    }
+
+    #[test]
+    fn batches_respect_the_byte_budget() {
+        let threads = vec![
+            thread("a", &"x".repeat(40)),
+            thread("b", &"x".repeat(40)),
+            thread("c", &"x".repeat(120)),
+            thread("d", &"x".repeat(10)),
+        ];
+        let batches = batch_threads(threads, 100);
+        let names = batches
+            .iter()
+            .map(|batch| {
+                batch
+                    .iter()
+                    .map(|thread| thread.thread.as_str())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec![vec!["a", "b"], vec!["c"], vec!["d"]]);
+    }
+
+    #[test]
+    fn parses_outcomes_and_keeps_thread_state() {
+        let batch = vec![
+            thread(
+                "auth",
+                "---\nstate: blocked\nblocked_reason: infra\n---\n# Auth\n\nOld\n",
+            ),
+            thread("docs", "# Docs\n"),
+            thread("cli", "# CLI\n"),
+            thread("ops", "# Ops\n"),
+        ];
+        let response = r##"Here you go:
+[
+  {"thread": "auth", "outcome": "revise", "document": "# Auth\n\nNew", "reason": "Refreshed next steps"},
+  {"thread": "docs", "outcome": "delete", "reason": "Shipped"},
+  {"thread": "cli", "outcome": null},
+  {"thread": "ops", "outcome": "revise", "document": "# Ops\n"},
+  {"thread": "unknown", "outcome": "delete"}
+]"##;
+        let revisions = parse_revisions(response, &batch).expect("parse");
+        assert_eq!(
+            revisions
+                .iter()
+                .map(|revision| revision.outcome)
+                .collect::<Vec<_>>(),
+            vec![
+                RevisionOutcome::Revised,
+                RevisionOutcome::Deleted,
+                RevisionOutcome::Unchanged,
+                RevisionOutcome::Unchanged,
+            ]
+        );
+        assert_eq!(
+            revisions[0].document.as_deref(),
+            Some("---\nstate: blocked\nblocked_reason: infra\n---\n# Auth\n\nNew\n")
+        );
+        assert_eq!(revisions[1].reason.as_deref(), Some("Shipped"));
+
+        assert!(parse_revisions("no changes", &batch).is_err());
+    }
+}
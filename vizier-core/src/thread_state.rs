@@ -0,0 +1,390 @@
+//! Lifecycle state of narrative threads, kept in each thread document's front matter:
+//!
+//! ```text
+//! ---
+//! state: resolved
+//! resolved_by: auth-rewrite
+//! ---
+//! ```
+//!
+//! Threads without a `state:` key are open. Merges that touch a thread resolved by some other
+//! plan are blocked until the branch reopens it or resolves it again, so a thread's state tracks
+//! the last plan that changed it.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use git2::Repository;
+use serde::Serialize;
+
+use crate::plan;
+use crate::thread_owners::{self, THREADS_DIR};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThreadState {
+    Open,
+    Blocked,
+    Resolved,
+}
+
+impl ThreadState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Open => "open",
+            Self::Blocked => "blocked",
+            Self::Resolved => "resolved",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "open" => Some(Self::Open),
+            "blocked" => Some(Self::Blocked),
+            "resolved" => Some(Self::Resolved),
+            _ => None,
+        }
+    }
+}
+
+/// State fields read from a thread's front matter.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ThreadStatus {
+    pub state: ThreadState,
+    /// Plan slug that resolved the thread.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolved_by: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blocked_reason: Option<String>,
+}
+
+impl ThreadStatus {
+    pub fn open() -> Self {
+        Self {
+            state: ThreadState::Open,
+            resolved_by: None,
+            blocked_reason: None,
+        }
+    }
+}
+
+/// A thread document in the working tree with its state and owners.
+#[derive(Clone, Debug, Serialize)]
+pub struct ThreadSummary {
+    pub thread: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(flatten)]
+    pub status: ThreadStatus,
+    pub owners: Vec<String>,
+}
+
+/// The thread's lifecycle fields; an unknown `state:` value is an error rather than open.
+pub fn thread_status(document: &str) -> Result<ThreadStatus, String> {
+    let fields = plan::front_matter_fields(document);
+    let field = |key: &str| {
+        fields
+            .get(key)
+            .map(|value| value.trim().trim_matches(['"', '\'']).to_string())
+            .filter(|value| !value.is_empty())
+    };
+    let state = match field("state") {
+        Some(value) => ThreadState::parse(&value).ok_or_else(|| {
+            format!("unknown thread state `{value}`; use open, blocked, or resolved")
+        })?,
+        None => ThreadState::Open,
+    };
+    Ok(ThreadStatus {
+        state,
+        resolved_by: field("resolved_by").filter(|_| state == ThreadState::Resolved),
+        blocked_reason: field("blocked_reason").filter(|_| state == ThreadState::Blocked),
+    })
+}
+
+/// Checks that a thread may move from `from` to `to`: open and blocked threads can be resolved
+/// or swap between each other; resolved threads can be reopened or resolved again by another
+/// plan.
+pub fn check_transition(from: &ThreadStatus, to: &ThreadStatus) -> Result<(), String> {
+    if from == to {
+        return Err(format!("thread is already {}", to.state.as_str()));
+    }
+    if from.state == ThreadState::Resolved && to.state == ThreadState::Blocked {
+        return Err("a resolved thread must be reopened before it can be blocked".to_string());
+    }
+    Ok(())
+}
+
+/// `document` with its front matter carrying `status`; fields that do not apply to the new
+/// state are dropped and a front-matter block is added when the document has none.
+pub fn with_status(document: &str, status: &ThreadStatus) -> String {
+    let normalized = document.replace("\r\n", "\n");
+    let (front_matter, body) = match normalized.strip_prefix("---\n").and_then(|rest| {
+        rest.find("\n---\n")
+            .map(|end| (&rest[..end], &rest[end + 5..]))
+            .or_else(|| rest.strip_suffix("\n---").map(|front| (front, "")))
+    }) {
+        Some((front, body)) => (front, body),
+        None => ("", normalized.as_str()),
+    };
+
+    let mut out = String::from("---\n");
+    for line in front_matter.lines() {
+        let key = line.split_once(':').map(|(key, _)| key.trim());
+        if matches!(key, Some("state" | "resolved_by" | "blocked_reason")) {
+            continue;
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    if status.state != ThreadState::Open {
+        out.push_str(&format!("state: {}\n", status.state.as_str()));
+    }
+    if let Some(plan) = &status.resolved_by {
+        out.push_str(&format!("resolved_by: {plan}\n"));
+    }
+    if let Some(reason) = &status.blocked_reason {
+        out.push_str(&format!("blocked_reason: {}\n", reason.replace('\n', " ")));
+    }
+    if out == "---\n" {
+        return body.to_string();
+    }
+    out.push_str("---\n");
+    out.push_str(body);
+    out
+}
+
+pub fn thread_rel_path(thread: &str) -> PathBuf {
+    Path::new(THREADS_DIR).join(format!("{thread}.md"))
+}
+
+/// Threads under `.vizier/narrative/threads/` in the working tree, sorted by name. A thread
+/// whose `state:` cannot be parsed is listed as open.
+pub fn list_threads(project_root: &Path) -> io::Result<Vec<ThreadSummary>> {
+    let entries = match fs::read_dir(project_root.join(THREADS_DIR)) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    let mut threads = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().is_none_or(|ext| ext != "md") {
+            continue;
+        }
+        let Some(thread) = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+        else {
+            continue;
+        };
+        let document = fs::read_to_string(&path)?;
+        threads.push(ThreadSummary {
+            title: document
+                .lines()
+                .find_map(|line| line.strip_prefix("# "))
+                .map(|title| title.trim().to_string()),
+            status: thread_status(&document).unwrap_or_else(|_| ThreadStatus::open()),
+            owners: thread_owners::thread_owners(&document),
+            thread,
+        });
+    }
+    threads.sort_by(|left, right| left.thread.cmp(&right.thread));
+    Ok(threads)
+}
+
+/// Reasons a merge of `branch` (plan `slug`) would leave thread states stale: an affected thread
+/// with an unknown `state:`, or one still resolved by a different plan. Empty when the merge may
+/// proceed.
+pub fn merge_state_issues(
+    repo_path: &Path,
+    target: &str,
+    branch: &str,
+    slug: &str,
+    plan_document: Option<&str>,
+) -> Result<Vec<String>, git2::Error> {
+    let threads = thread_owners::affected_thread_names(repo_path, target, branch, plan_document)?;
+    let repo = Repository::discover(repo_path)?;
+    let tip_tree = repo.revparse_single(branch)?.peel_to_commit()?.tree()?;
+    let mut issues = Vec::new();
+    for thread in threads {
+        let Some(document) = tip_tree
+            .get_path(&thread_rel_path(&thread))
+            .ok()
+            .and_then(|entry| repo.find_blob(entry.id()).ok())
+            .map(|blob| String::from_utf8_lossy(blob.content()).into_owned())
+        else {
+            continue;
+        };
+        match thread_status(&document) {
+            Err(err) => issues.push(format!("thread `{thread}`: {err}")),
+            Ok(ThreadStatus {
+                state: ThreadState::Resolved,
+                resolved_by,
+                ..
+            }) if resolved_by.as_deref() != Some(slug) => issues.push(format!(
+                "thread `{thread}` is resolved{}; run `vizier thread reopen {thread} --plan {slug}` or `vizier thread resolve {thread} --by {slug}`",
+                resolved_by
+                    .map(|plan| format!(" by `{plan}`"))
+                    .unwrap_or_default()
+            )),
+            Ok(_) => {}
+        }
+    }
+    Ok(issues)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_round_trips_through_front_matter() {
+        let document = "---\nowners: alice\n---\n# Auth\n\nBody\n";
+        assert_eq!(
+            thread_status(document).expect("status"),
+            ThreadStatus::open()
+        );
+
+        let resolved = ThreadStatus {
+            state: ThreadState::Resolved,
+            resolved_by: Some("auth-rewrite".to_string()),
+            blocked_reason: None,
+        };
+        let updated = with_status(document, &resolved);
+        assert_eq!(
+            updated,
+            "---\nowners: alice\nstate: resolved\nresolved_by: auth-rewrite\n---\n# Auth\n\nBody\n"
+        );
+        assert_eq!(thread_status(&updated).expect("status"), resolved);
+        assert_eq!(with_status(&updated, &ThreadStatus::open()), document);
+
+        let blocked = ThreadStatus {
+            state: ThreadState::Blocked,
+            resolved_by: None,
+            blocked_reason: Some("waiting on infra".to_string()),
+        };
+        let bare = with_status("# Docs\n", &blocked);
+        assert_eq!(
+            bare,
+            "---\nstate: blocked\nblocked_reason: waiting on infra\n---\n# Docs\n"
+        );
+        assert_eq!(with_status(&bare, &ThreadStatus::open()), "# Docs\n");
+
+        assert!(thread_status("---\nstate: done\n---\n").is_err());
+    }
+
+    fn resolved_by(plan: &str) -> ThreadStatus {
+        ThreadStatus {
+            state: ThreadState::Resolved,
+            resolved_by: Some(plan.to_string()),
+            blocked_reason: None,
+        }
+    }
+
+    #[test]
+    fn transitions_reject_no_ops_and_blocking_resolved_threads() {
+        let open = ThreadStatus::open();
+        let blocked = ThreadStatus {
+            state: ThreadState::Blocked,
+            resolved_by: None,
+            blocked_reason: None,
+        };
+        assert!(check_transition(&open, &resolved_by("alpha")).is_ok());
+        assert!(check_transition(&blocked, &open).is_ok());
+        assert!(check_transition(&resolved_by("alpha"), &open).is_ok());
+        assert!(check_transition(&resolved_by("alpha"), &resolved_by("beta")).is_ok());
+        assert!(check_transition(&resolved_by("alpha"), &resolved_by("alpha")).is_err());
+        assert!(check_transition(&resolved_by("alpha"), &blocked).is_err());
+        assert!(check_transition(&open, &open).is_err());
+    }
+
+    #[test]
+    fn merges_touching_threads_resolved_by_other_plans_are_flagged() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let repo = Repository::init(dir.path()).expect("init");
+        let sig = git2::Signature::now("vizier", "vizier@example.com").expect("sig");
+        let commit = |refname: &str, parent: Option<git2::Oid>, files: &[(&str, &str)]| {
+            let mut index = repo.index().expect("index");
+            for (path, contents) in files {
+                let abs = dir.path().join(path);
+                fs::create_dir_all(abs.parent().expect("parent")).expect("dirs");
+                fs::write(&abs, contents).expect("write");
+                index.add_path(Path::new(path)).expect("add");
+            }
+            let tree = repo
+                .find_tree(index.write_tree().expect("tree"))
+                .expect("find tree");
+            let parents = parent
+                .map(|oid| repo.find_commit(oid).expect("parent"))
+                .into_iter()
+                .collect::<Vec<_>>();
+            let parents = parents.iter().collect::<Vec<_>>();
+            repo.commit(Some(refname), &sig, &sig, "commit", &tree, &parents)
+                .expect("commit")
+        };
+        let base = commit(
+            "refs/heads/main",
+            None,
+            &[
+                (
+                    ".vizier/narrative/threads/auth.md",
+                    "---
+state: resolved
+resolved_by: old-plan
+---
+# Auth
+",
+                ),
+                (
+                    ".vizier/narrative/threads/docs.md",
+                    "# Docs
+",
+                ),
+            ],
+        );
+        commit(
+            "refs/heads/draft/alpha",
+            Some(base),
+            &[
+                (
+                    ".vizier/narrative/threads/auth.md",
+                    "---
+state: resolved
+resolved_by: old-plan
+---
+# Auth v2
+",
+                ),
+                (
+                    ".vizier/narrative/threads/docs.md",
+                    "# Docs v2
+",
+                ),
+            ],
+        );
+        let issues =
+            merge_state_issues(dir.path(), "main", "draft/alpha", "alpha", None).expect("issues");
+        assert_eq!(issues.len(), 1, "{issues:?}");
+        assert!(issues[0].contains("thread `auth` is resolved by `old-plan`"));
+
+        commit(
+            "refs/heads/draft/alpha",
+            repo.refname_to_id("refs/heads/draft/alpha").ok(),
+            &[(
+                ".vizier/narrative/threads/auth.md",
+                "---
+state: resolved
+resolved_by: alpha
+---
+# Auth v2
+",
+            )],
+        );
+        assert!(
+            merge_state_issues(dir.path(), "main", "draft/alpha", "alpha", None)
+                .expect("issues")
+                .is_empty()
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use std::path::{Path, PathBuf};
 
+use crate::never_send::{NeverSend, record_withheld};
 use crate::{config, observer::CaptureGuard, vcs};
 
 pub const VIZIER_DIR: &str = ".vizier/";
@@ -135,7 +136,11 @@ pub fn build_llm_response(tool_output: String, guard: &CaptureGuard) -> String {
 pub fn diff() -> String {
     let guard = CaptureGuard::start();
     match vcs::get_diff(".", None, None) {
-        Ok(d) => build_llm_response(d, &guard),
+        Ok(d) => {
+            let (d, withheld) = NeverSend::from_config(Path::new(".")).filter_diff(&d);
+            record_withheld("tool.diff", &withheld);
+            build_llm_response(d, &guard)
+        }
         Err(e) => llm_error(&format!("Error getting diff: {}", e)),
     }
 }
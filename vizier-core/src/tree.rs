@@ -27,6 +27,21 @@ pub fn build_tree() -> std::io::Result<FileTree> {
     Ok(root)
 }
 
+/// Tree rooted at `root` holding `paths` (relative to `root`) in the order given.
+pub fn tree_from_paths<P: AsRef<std::path::Path>>(
+    root: std::path::PathBuf,
+    paths: &[P],
+) -> FileTree {
+    let mut tree = FileTree {
+        path: root,
+        children: Vec::new(),
+    };
+    for path in paths {
+        insert_path(&mut tree, path.as_ref());
+    }
+    tree
+}
+
 fn insert_path(tree: &mut FileTree, path: &std::path::Path) {
     if let Some(first) = path.components().next() {
         let first_path = tree.path.join(first);
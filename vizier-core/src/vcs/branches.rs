@@ -1,31 +1,99 @@
 use git2::build::CheckoutBuilder;
-use git2::{BranchType, Error, ErrorCode, Repository};
+use git2::{BranchType, Error, ErrorCode, Oid, Repository, Sort};
 use std::path::Path;
 
-/// Determine the repository's primary branch by preferring origin/HEAD, then main/master, then
-/// the most recently updated local branch.
+/// Where a detected target branch came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TargetSource {
+    /// `[branches] target`.
+    Config,
+    /// The branch `refs/remotes/origin/HEAD` points at.
+    RemoteHead,
+    /// A local `main`, `master`, or `trunk`.
+    CommonName,
+    /// The most recently updated local branch.
+    NewestBranch,
+}
+
+impl TargetSource {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Config => "config",
+            Self::RemoteHead => "origin_head",
+            Self::CommonName => "common_name",
+            Self::NewestBranch => "newest_branch",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Config => "[branches] target",
+            Self::RemoteHead => "origin/HEAD",
+            Self::CommonName => "common name",
+            Self::NewestBranch => "most recently updated branch",
+        }
+    }
+}
+
+/// A target branch picked without `--target`, and how it was picked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DetectedTarget {
+    pub branch: String,
+    pub source: TargetSource,
+}
+
+impl DetectedTarget {
+    /// `main (origin/HEAD)`.
+    pub fn describe(&self) -> String {
+        format!("{} ({})", self.branch, self.source.label())
+    }
+}
+
+/// Common primary branch names, in order of preference.
+const COMMON_TARGETS: &[&str] = &["main", "master", "trunk"];
+
+/// Determine the repository's primary branch: `[branches] target`, else the branch
+/// origin/HEAD points at, else main/master/trunk, else the most recently updated local branch.
 pub fn detect_primary_branch() -> Option<String> {
     let repo = Repository::discover(".").ok()?;
-    detect_primary_branch_in_repo(&repo)
+    detect_target_in_repo(&repo).map(|target| target.branch)
 }
 
 pub fn detect_primary_branch_in<P: AsRef<Path>>(repo_path: P) -> Option<String> {
+    detect_target_branch_in(repo_path).map(|target| target.branch)
+}
+
+/// [`detect_primary_branch_in`] with the source of the answer, for output that shows it.
+pub fn detect_target_branch_in<P: AsRef<Path>>(repo_path: P) -> Option<DetectedTarget> {
     let repo = Repository::open(repo_path).ok()?;
-    detect_primary_branch_in_repo(&repo)
+    detect_target_in_repo(&repo)
 }
 
-fn detect_primary_branch_in_repo(repo: &Repository) -> Option<String> {
+fn detect_target_in_repo(repo: &Repository) -> Option<DetectedTarget> {
+    let detected = |branch: &str, source| {
+        Some(DetectedTarget {
+            branch: branch.to_string(),
+            source,
+        })
+    };
+
+    if let Some(target) = crate::config::get_config().branches.target {
+        return detected(&target, TargetSource::Config);
+    }
+
+    // A clone records the remote's default branch here; `git remote set-head origin -a`
+    // refreshes it. The branch must also exist locally, since plans merge into local branches.
     if let Ok(ref_remote_head) = repo.find_reference("refs/remotes/origin/HEAD")
         && let Some(symbolic) = ref_remote_head.symbolic_target()
         && let Some(name) = symbolic.strip_prefix("refs/remotes/origin/")
         && repo.find_branch(name, BranchType::Local).is_ok()
     {
-        return Some(name.to_string());
+        return detected(name, TargetSource::RemoteHead);
     }
 
-    for candidate in ["main", "master"] {
+    for candidate in COMMON_TARGETS {
         if repo.find_branch(candidate, BranchType::Local).is_ok() {
-            return Some(candidate.to_string());
+            return detected(candidate, TargetSource::CommonName);
         }
     }
 
@@ -46,7 +114,97 @@ fn detect_primary_branch_in_repo(repo: &Repository) -> Option<String> {
         }
     }
 
-    newest.map(|(name, _)| name)
+    newest.and_then(|(name, _)| detected(&name, TargetSource::NewestBranch))
+}
+
+/// How far `branch` has moved away from `target`: commits only `branch` carries, commits only
+/// `target` carries, and the commit time of the oldest commit unique to `branch`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BranchDivergence {
+    pub ahead: usize,
+    pub behind: usize,
+    pub oldest_unique_commit_time: Option<i64>,
+}
+
+pub fn branch_divergence_in<P: AsRef<Path>>(
+    repo_path: P,
+    branch: &str,
+    target: &str,
+) -> Result<BranchDivergence, Error> {
+    let repo = Repository::open(repo_path)?;
+    let branch_oid = repo
+        .find_branch(branch, BranchType::Local)?
+        .get()
+        .peel_to_commit()?
+        .id();
+    let target_oid = repo
+        .find_branch(target, BranchType::Local)?
+        .get()
+        .peel_to_commit()?
+        .id();
+    let (ahead, behind) = repo.graph_ahead_behind(branch_oid, target_oid)?;
+
+    let mut walk = repo.revwalk()?;
+    walk.push(branch_oid)?;
+    walk.hide(target_oid)?;
+    let mut oldest_unique_commit_time: Option<i64> = None;
+    for oid in walk {
+        let seconds = repo.find_commit(oid?)?.time().seconds();
+        oldest_unique_commit_time = Some(match oldest_unique_commit_time {
+            Some(current) => current.min(seconds),
+            None => seconds,
+        });
+    }
+
+    Ok(BranchDivergence {
+        ahead,
+        behind,
+        oldest_unique_commit_time,
+    })
+}
+
+/// One non-merge commit on a plan branch, numbered from 1 in [`branch_commits_in`] order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BranchCommit {
+    pub oid: Oid,
+    pub summary: String,
+}
+
+/// Non-merge commits reachable from `branch` but not from `target`, oldest first. These are the
+/// plan's steps as `vizier pick` numbers them.
+pub fn branch_commits_in<P: AsRef<Path>>(
+    repo_path: P,
+    branch: &str,
+    target: &str,
+) -> Result<Vec<BranchCommit>, Error> {
+    let repo = Repository::open(repo_path)?;
+    let branch_oid = repo
+        .find_branch(branch, BranchType::Local)?
+        .get()
+        .peel_to_commit()?
+        .id();
+    let target_oid = repo
+        .find_branch(target, BranchType::Local)?
+        .get()
+        .peel_to_commit()?
+        .id();
+
+    let mut walk = repo.revwalk()?;
+    walk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+    walk.push(branch_oid)?;
+    walk.hide(target_oid)?;
+    let mut commits = Vec::new();
+    for oid in walk {
+        let commit = repo.find_commit(oid?)?;
+        if commit.parent_count() > 1 {
+            continue;
+        }
+        commits.push(BranchCommit {
+            oid: commit.id(),
+            summary: commit.summary().unwrap_or_default().to_string(),
+        });
+    }
+    Ok(commits)
 }
 
 pub fn branch_exists(name: &str) -> Result<bool, Error> {
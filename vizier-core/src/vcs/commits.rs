@@ -1,8 +1,9 @@
 use git2::build::CheckoutBuilder;
 use git2::{
-    Commit, DiffOptions, Error, ErrorCode, Index, IndexAddOption, ObjectType, Oid, Repository,
-    Signature, Sort, Status, StatusOptions,
+    BranchType, Commit, DiffOptions, Error, ErrorCode, Index, IndexAddOption, ObjectType, Oid,
+    Repository, Signature, Sort, Status, StatusOptions,
 };
+use std::collections::{BTreeMap, HashSet};
 use std::path::Path;
 
 use super::normalize_pathspec;
@@ -276,6 +277,201 @@ pub fn commit_staged_in<P: AsRef<Path>>(
     commit_staged_impl(&repo, message, allow_empty)
 }
 
+/// Commits created by [`commit_staged_split_in`]. A side is `None` when the index held no
+/// changes there.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SplitCommits {
+    pub code: Option<Oid>,
+    pub narrative: Option<Oid>,
+}
+
+/// Commits the index as two commits on HEAD: the changes outside `.vizier/` with `code_message`,
+/// then the `.vizier/` changes with `narrative_message`. When both sides changed, the code commit
+/// carries `Vizier-Split: code` and the narrative commit carries `Vizier-Split: narrative` plus
+/// `Vizier-Code-Commit: <code oid>`; when only one side changed it is committed alone with
+/// `code_message` and no trailers.
+pub fn commit_staged_split_in<P: AsRef<Path>>(
+    repo_path: P,
+    code_message: &str,
+    narrative_message: &str,
+) -> Result<SplitCommits, Error> {
+    const NARRATIVE_ROOT: &str = ".vizier";
+
+    let repo = Repository::open(repo_path)?;
+    let mut index = repo.index()?;
+    index.write()?;
+    let full_id = index.write_tree()?;
+    let full = repo.find_tree(full_id)?;
+    let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+    let parent_tree = parent.as_ref().map(|commit| commit.tree()).transpose()?;
+
+    // The code tree is the index with `.vizier` rolled back to HEAD.
+    let mut builder = repo.treebuilder(Some(&full))?;
+    match parent_tree
+        .as_ref()
+        .and_then(|tree| tree.get_name(NARRATIVE_ROOT))
+    {
+        Some(entry) => {
+            builder.insert(NARRATIVE_ROOT, entry.id(), entry.filemode())?;
+        }
+        None => {
+            if builder.get(NARRATIVE_ROOT)?.is_some() {
+                builder.remove(NARRATIVE_ROOT)?;
+            }
+        }
+    }
+    let code_id = builder.write()?;
+    let parent_id = parent_tree.as_ref().map(|tree| tree.id());
+    let code_changed = match parent_id {
+        Some(parent_id) => parent_id != code_id,
+        None => !repo.find_tree(code_id)?.is_empty(),
+    };
+    let narrative_changed = code_id != full_id;
+    if !code_changed && !narrative_changed {
+        return Err(Error::from_str("nothing to commit"));
+    }
+
+    let signature = repo
+        .signature()
+        .or_else(|_| Signature::now("Vizier", "vizier@local"))?;
+    let parents = parent.iter().collect::<Vec<_>>();
+    if !(code_changed && narrative_changed) {
+        let oid = repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            code_message,
+            &full,
+            &parents,
+        )?;
+        return Ok(SplitCommits {
+            code: code_changed.then_some(oid),
+            narrative: narrative_changed.then_some(oid),
+        });
+    }
+
+    let code_message =
+        super::merge::append_trailers(code_message, ["Vizier-Split: code".to_string()]);
+    let code = repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        &code_message,
+        &repo.find_tree(code_id)?,
+        &parents,
+    )?;
+    let narrative_message = super::merge::append_trailers(
+        narrative_message,
+        [
+            "Vizier-Split: narrative".to_string(),
+            format!("Vizier-Code-Commit: {code}"),
+        ],
+    );
+    let narrative = repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        &narrative_message,
+        &full,
+        &[&repo.find_commit(code)?],
+    )?;
+    Ok(SplitCommits {
+        code: Some(code),
+        narrative: Some(narrative),
+    })
+}
+
+/// Creates `branch` at `base` plus one commit writing `files` (repo-relative path, contents),
+/// without touching any worktree or index. Fails if `branch` already exists.
+pub fn commit_files_to_new_branch_in<P: AsRef<Path>>(
+    repo_path: P,
+    base: &str,
+    branch: &str,
+    files: &[(&Path, &str)],
+    message: &str,
+) -> Result<Oid, Error> {
+    let repo = Repository::open(repo_path)?;
+    let parent = repo.revparse_single(base)?.peel_to_commit()?;
+    let mut update = git2::build::TreeUpdateBuilder::new();
+    for (path, contents) in files {
+        let blob = repo.blob(contents.as_bytes())?;
+        update.upsert(path, blob, git2::FileMode::Blob);
+    }
+    let tree_id = update.create_updated(&repo, &parent.tree()?)?;
+    let tree = repo.find_tree(tree_id)?;
+    let signature = repo
+        .signature()
+        .or_else(|_| Signature::now("Vizier", "vizier@local"))?;
+    let oid = repo.commit(None, &signature, &signature, message, &tree, &[&parent])?;
+    repo.branch(branch, &repo.find_commit(oid)?, false)?;
+    Ok(oid)
+}
+
+/// Adds one commit writing `files` (repo-relative path, contents) on top of the existing
+/// `branch`, without touching any index. When `branch` is checked out in the repo's own worktree
+/// that worktree is updated with a safe checkout; a branch checked out in a linked worktree is
+/// refused, since moving its ref would leave that checkout looking like it reverted the change.
+pub fn commit_files_to_branch_in<P: AsRef<Path>>(
+    repo_path: P,
+    branch: &str,
+    files: &[(&Path, &str)],
+    message: &str,
+) -> Result<Oid, Error> {
+    commit_file_changes_to_branch_in(repo_path, branch, files, &[], message)
+}
+
+/// [`commit_files_to_branch_in`] that also deletes `removed` (repo-relative paths) in the same
+/// commit.
+pub fn commit_file_changes_to_branch_in<P: AsRef<Path>>(
+    repo_path: P,
+    branch: &str,
+    files: &[(&Path, &str)],
+    removed: &[&Path],
+    message: &str,
+) -> Result<Oid, Error> {
+    let repo_path = repo_path.as_ref();
+    let repo = Repository::open(repo_path)?;
+    let refname = format!("refs/heads/{branch}");
+    for name in repo.worktrees()?.iter().flatten() {
+        let Ok(worktree) = repo.find_worktree(name) else {
+            continue;
+        };
+        if worktree.validate().is_err() {
+            continue;
+        }
+        let checked_out = Repository::open(worktree.path())
+            .ok()
+            .and_then(|linked| linked.head().ok()?.name().map(str::to_string));
+        if checked_out.as_deref() == Some(refname.as_str()) {
+            return Err(Error::from_str(&format!(
+                "branch `{branch}` is checked out in worktree `{name}` at {}; make the change there",
+                worktree.path().display()
+            )));
+        }
+    }
+
+    let parent = repo
+        .find_branch(branch, BranchType::Local)?
+        .get()
+        .peel_to_commit()?;
+    let mut update = git2::build::TreeUpdateBuilder::new();
+    for (path, contents) in files {
+        let blob = repo.blob(contents.as_bytes())?;
+        update.upsert(path, blob, git2::FileMode::Blob);
+    }
+    for path in removed {
+        update.remove(path);
+    }
+    let tree_id = update.create_updated(&repo, &parent.tree()?)?;
+    let tree = repo.find_tree(tree_id)?;
+    let signature = repo
+        .signature()
+        .or_else(|_| Signature::now("Vizier", "vizier@local"))?;
+    let oid = repo.commit(None, &signature, &signature, message, &tree, &[&parent])?;
+    super::fast_forward_branch_in(repo_path, branch, parent.id(), oid)?;
+    Ok(oid)
+}
+
 /// Return up to `depth` commits whose messages match any of the `filters` (OR),
 /// Returns up to `depth` commits (newest -> oldest) whose *full* messages
 /// contain ANY of the provided `filters` (case-insensitive).
@@ -402,6 +598,57 @@ pub fn revisions_touching_path_in<P: AsRef<Path>>(
     Ok(revisions)
 }
 
+/// Snapshot every local branch tip so callers can later ask which commits landed since.
+pub fn branch_tips_in<P: AsRef<Path>>(repo_path: P) -> Result<BTreeMap<String, Oid>, Error> {
+    local_branch_tips(&Repository::open(repo_path)?)
+}
+
+fn local_branch_tips(repo: &Repository) -> Result<BTreeMap<String, Oid>, Error> {
+    let mut tips = BTreeMap::new();
+    for branch in repo.branches(Some(BranchType::Local))? {
+        let (branch, _) = branch?;
+        if let (Some(name), Some(target)) = (branch.name()?, branch.get().target()) {
+            tips.insert(name.to_string(), target);
+        }
+    }
+    Ok(tips)
+}
+
+/// Commits reachable from current local branch tips but from none of the `before` tips,
+/// returned oldest-first per branch as `(branch, oid, summary)`.
+pub fn commits_since_tips_in<P: AsRef<Path>>(
+    repo_path: P,
+    before: &BTreeMap<String, Oid>,
+) -> Result<Vec<(String, Oid, String)>, Error> {
+    let repo = Repository::open(repo_path)?;
+    let mut seen = HashSet::new();
+    let mut commits = Vec::new();
+    for (branch, tip) in local_branch_tips(&repo)? {
+        if before.get(&branch) == Some(&tip) {
+            continue;
+        }
+        let mut walk = repo.revwalk()?;
+        walk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+        walk.push(tip)?;
+        for previous in before.values() {
+            walk.hide(*previous)?;
+        }
+        for oid in walk {
+            let oid = oid?;
+            if !seen.insert(oid) {
+                continue;
+            }
+            let summary = repo
+                .find_commit(oid)?
+                .summary()
+                .unwrap_or_default()
+                .to_string();
+            commits.push((branch.clone(), oid, summary));
+        }
+    }
+    Ok(commits)
+}
+
 fn commit_touches_path(
     repo: &Repository,
     commit: &Commit<'_>,
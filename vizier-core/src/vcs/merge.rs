@@ -6,11 +6,15 @@ use git2::{
 use std::collections::HashSet;
 use std::path::Path;
 
+use crate::narrative_merge::{self, NarrativeResolution};
+
 #[derive(Debug, Clone)]
 pub struct MergeReady {
     pub head_oid: Oid,
     pub source_oid: Oid,
     pub tree_oid: Oid,
+    /// Narrative documents both sides edited, merged section by section.
+    pub narrative_merged: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -18,6 +22,8 @@ pub struct MergeConflict {
     pub head_oid: Oid,
     pub source_oid: Oid,
     pub files: Vec<String>,
+    /// Narrative documents both sides edited that merged cleanly section by section.
+    pub narrative_merged: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -93,13 +99,20 @@ fn prepare_merge_impl(repo: &Repository, source_branch: &str) -> Result<MergePre
     let source_commit = source_ref.get().peel_to_commit()?;
 
     let mut index = repo.merge_commits(&head_commit, &source_commit, None)?;
+    let mut narrative = NarrativeResolution::default();
+    if index.has_conflicts() {
+        narrative =
+            narrative_merge::resolve_index_conflicts(repo, &mut index, "HEAD", source_branch)?;
+    }
     if index.has_conflicts() {
         let conflicts = collect_conflict_paths(&mut index);
         materialize_conflicts(repo, source_branch)?;
+        narrative_merge::apply_to_worktree(repo, &mut repo.index()?, &narrative)?;
         return Ok(MergePreparation::Conflicted(MergeConflict {
             head_oid: head_commit.id(),
             source_oid: source_commit.id(),
             files: conflicts,
+            narrative_merged: narrative.merged_paths(),
         }));
     }
 
@@ -108,9 +121,303 @@ fn prepare_merge_impl(repo: &Repository, source_branch: &str) -> Result<MergePre
         head_oid: head_commit.id(),
         source_oid: source_commit.id(),
         tree_oid,
+        narrative_merged: narrative.merged_paths(),
     }))
 }
 
+/// Result of [`rebase_and_fast_forward_in`].
+#[derive(Debug, Clone)]
+pub enum RebaseMergeOutcome {
+    /// The checked-out branch was fast-forwarded onto these rebased commits, oldest first.
+    /// Empty when the target already contained the plan branch.
+    Landed(Vec<Oid>),
+    /// Replaying `commit` conflicted in `files`; the target and worktree are untouched.
+    Conflicted { commit: Oid, files: Vec<String> },
+}
+
+/// Rebase-and-merge: replays each plan commit from `source_branch` onto the checked-out branch
+/// in memory, appending `trailers` plus a `Rebased-from: <oid>` line to every message, then
+/// fast-forwards the branch. Authors are kept; the committer is the current signature. Commits
+/// that become empty are dropped, like `git rebase`. Plan branches with merge commits are
+/// rejected because a linear replay would flatten them silently.
+pub fn rebase_and_fast_forward_in<P: AsRef<Path>>(
+    repo_path: P,
+    source_branch: &str,
+    trailers: &[String],
+) -> Result<RebaseMergeOutcome, Error> {
+    let repo = Repository::open(repo_path)?;
+    let Some((head_commit, commits)) =
+        linear_plan_commits(&repo, source_branch, "rebase-and-merge needs")?
+    else {
+        return Ok(RebaseMergeOutcome::Landed(Vec::new()));
+    };
+
+    let sig = repo.signature()?;
+    let mut onto = head_commit;
+    let mut landed = Vec::new();
+    for oid in commits {
+        let commit = repo.find_commit(oid)?;
+        let tree_oid = match replay_tree(&repo, &commit, &onto)? {
+            Ok(tree_oid) => tree_oid,
+            Err(files) => return Ok(RebaseMergeOutcome::Conflicted { commit: oid, files }),
+        };
+        if tree_oid == onto.tree_id() {
+            continue;
+        }
+        let tree = repo.find_tree(tree_oid)?;
+        let message = append_trailers(
+            &String::from_utf8_lossy(commit.message_bytes()),
+            trailers
+                .iter()
+                .cloned()
+                .chain([format!("Rebased-from: {oid}")]),
+        );
+        let new_oid = repo.commit(None, &commit.author(), &sig, &message, &tree, &[&onto])?;
+        landed.push(new_oid);
+        onto = repo.find_commit(new_oid)?;
+    }
+
+    fast_forward_checked_out(&repo, &onto, &landed, source_branch, "rebase-and-merge")?;
+    Ok(RebaseMergeOutcome::Landed(landed))
+}
+
+/// Trailer an approve commit carries to mark the `## Execution Plan` step it finishes.
+pub const STEP_TRAILER: &str = "Vizier-Step";
+
+/// The step number in a message's `Vizier-Step: <n>` trailer.
+pub fn step_marker(message: &str) -> Option<u32> {
+    message.lines().rev().find_map(|line| {
+        let (key, value) = line.trim().split_once(':')?;
+        if !key.trim().eq_ignore_ascii_case(STEP_TRAILER) {
+            return None;
+        }
+        value.trim().parse().ok().filter(|step| *step > 0)
+    })
+}
+
+/// Plan-branch commits that squash into one target commit when squashing by step.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StepGroup {
+    /// The step the group finishes; `None` only when the branch carries no step markers.
+    pub step: Option<u32>,
+    /// Source commits, oldest first.
+    pub commits: Vec<Oid>,
+    pub subjects: Vec<String>,
+}
+
+/// Groups the commits `source_branch` would land on the checked-out branch by step marker. A
+/// marked commit closes its step, so unmarked work before it belongs to that step; commits after
+/// the last marker (stage commits, plan cleanup) join the last step, and repeated markers for
+/// the same step extend it. Empty when the target already contains the branch.
+pub fn plan_step_groups_in<P: AsRef<Path>>(
+    repo_path: P,
+    source_branch: &str,
+) -> Result<Vec<StepGroup>, Error> {
+    let repo = Repository::open(repo_path)?;
+    let Some((_, commits)) = linear_plan_commits(&repo, source_branch, "squashing by step needs")?
+    else {
+        return Ok(Vec::new());
+    };
+
+    let mut groups: Vec<StepGroup> = Vec::new();
+    let mut open = StepGroup {
+        step: None,
+        commits: Vec::new(),
+        subjects: Vec::new(),
+    };
+    for oid in commits {
+        let commit = repo.find_commit(oid)?;
+        let message = String::from_utf8_lossy(commit.message_bytes()).into_owned();
+        open.commits.push(oid);
+        open.subjects.push(
+            message
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_string(),
+        );
+        let Some(step) = step_marker(&message) else {
+            continue;
+        };
+        let finished = std::mem::replace(
+            &mut open,
+            StepGroup {
+                step: None,
+                commits: Vec::new(),
+                subjects: Vec::new(),
+            },
+        );
+        match groups.last_mut() {
+            Some(last) if last.step == Some(step) => {
+                last.commits.extend(finished.commits);
+                last.subjects.extend(finished.subjects);
+            }
+            _ => groups.push(StepGroup {
+                step: Some(step),
+                ..finished
+            }),
+        }
+    }
+    if !open.commits.is_empty() {
+        match groups.last_mut() {
+            Some(last) => {
+                last.commits.extend(open.commits);
+                last.subjects.extend(open.subjects);
+            }
+            None => groups.push(open),
+        }
+    }
+    Ok(groups)
+}
+
+/// Squash-by-step: replays each group from [`plan_step_groups_in`] onto the checked-out branch in
+/// memory and lands it as one commit with the matching entry of `messages`, then fast-forwards the
+/// branch. Groups whose changes are already on the target are dropped.
+pub fn squash_step_groups_in<P: AsRef<Path>>(
+    repo_path: P,
+    source_branch: &str,
+    groups: &[StepGroup],
+    messages: &[String],
+) -> Result<RebaseMergeOutcome, Error> {
+    if groups.len() != messages.len() {
+        return Err(Error::from_str("every step group needs a commit message"));
+    }
+    let repo = Repository::open(repo_path)?;
+    if linear_plan_commits(&repo, source_branch, "squashing by step needs")?.is_none() {
+        return Ok(RebaseMergeOutcome::Landed(Vec::new()));
+    }
+    let head_commit = repo.head()?.peel_to_commit()?;
+
+    let sig = repo.signature()?;
+    let mut base = head_commit;
+    let mut landed = Vec::new();
+    for (group, message) in groups.iter().zip(messages) {
+        // Replayed commits are scratch objects; only the group's final tree is kept.
+        let mut onto = base.clone();
+        for oid in &group.commits {
+            let commit = repo.find_commit(*oid)?;
+            let tree_oid = match replay_tree(&repo, &commit, &onto)? {
+                Ok(tree_oid) => tree_oid,
+                Err(files) => {
+                    return Ok(RebaseMergeOutcome::Conflicted {
+                        commit: *oid,
+                        files,
+                    });
+                }
+            };
+            let tree = repo.find_tree(tree_oid)?;
+            let scratch = repo.commit(None, &sig, &sig, "vizier: step replay", &tree, &[&onto])?;
+            onto = repo.find_commit(scratch)?;
+        }
+        if onto.tree_id() == base.tree_id() {
+            continue;
+        }
+        let tree = onto.tree()?;
+        let new_oid = repo.commit(None, &sig, &sig, message, &tree, &[&base])?;
+        landed.push(new_oid);
+        base = repo.find_commit(new_oid)?;
+    }
+
+    fast_forward_checked_out(&repo, &base, &landed, source_branch, "squash by step")?;
+    Ok(RebaseMergeOutcome::Landed(landed))
+}
+
+// The checked-out branch's tip and the plan commits to replay onto it, oldest first; `None`
+// when the branch already contains `source_branch`.
+fn linear_plan_commits<'repo>(
+    repo: &'repo Repository,
+    source_branch: &str,
+    needs: &str,
+) -> Result<Option<(git2::Commit<'repo>, Vec<Oid>)>, Error> {
+    if repo.state() != RepositoryState::Clean {
+        return Err(Error::from_str(
+            "cannot start a merge while another git operation is in progress",
+        ));
+    }
+    let head_ref = repo.head()?;
+    if !head_ref.is_branch() {
+        return Err(Error::from_str(
+            "cannot merge into detached HEAD; checkout a branch first",
+        ));
+    }
+    let head_commit = head_ref.peel_to_commit()?;
+    let source_commit = repo
+        .find_branch(source_branch, BranchType::Local)?
+        .get()
+        .peel_to_commit()?;
+    if head_commit.id() == source_commit.id()
+        || repo.graph_descendant_of(head_commit.id(), source_commit.id())?
+    {
+        return Ok(None);
+    }
+
+    let merge_base = repo.merge_base(head_commit.id(), source_commit.id())?;
+    let commits = collect_commits_from_base(repo, merge_base, source_commit.id())?;
+    for oid in &commits {
+        if repo.find_commit(*oid)?.parent_count() > 1 {
+            return Err(Error::from_str(&format!(
+                "`{source_branch}` contains merge commit {}; {needs} a linear plan branch (use squash or merge instead)",
+                &oid.to_string()[..7]
+            )));
+        }
+    }
+    Ok(Some((head_commit, commits)))
+}
+
+// Cherry-picks `commit` onto `onto` in memory, resolving narrative conflicts by section; the
+// inner `Err` lists the paths that still conflict.
+fn replay_tree(
+    repo: &Repository,
+    commit: &git2::Commit<'_>,
+    onto: &git2::Commit<'_>,
+) -> Result<Result<Oid, Vec<String>>, Error> {
+    let mut index = repo.cherrypick_commit(commit, onto, 0, None)?;
+    if index.has_conflicts() {
+        let label = commit.id().to_string()[..7].to_string();
+        narrative_merge::resolve_index_conflicts(repo, &mut index, "HEAD", &label)?;
+    }
+    if index.has_conflicts() {
+        return Ok(Err(collect_conflict_paths(&mut index)));
+    }
+    Ok(Ok(index.write_tree_to(repo)?))
+}
+
+fn fast_forward_checked_out(
+    repo: &Repository,
+    tip: &git2::Commit<'_>,
+    landed: &[Oid],
+    source_branch: &str,
+    action: &str,
+) -> Result<(), Error> {
+    if landed.is_empty() {
+        return Ok(());
+    }
+    let mut checkout = CheckoutBuilder::new();
+    checkout.safe();
+    repo.checkout_tree(tip.as_object(), Some(&mut checkout))?;
+    let mut reference = repo.head()?.resolve()?;
+    reference.set_target(tip.id(), &format!("vizier: {action} {source_branch}"))?;
+    Ok(())
+}
+
+/// Appends `trailers` to a commit message, joining an existing trailer block when the message
+/// already ends with one.
+pub(crate) fn append_trailers(message: &str, trailers: impl IntoIterator<Item = String>) -> String {
+    let body = message.trim_end();
+    let ends_with_trailers = body.rsplit_once("\n\n").is_some_and(|(_, last)| {
+        last.lines().all(|line| {
+            line.split_once(": ")
+                .is_some_and(|(key, _)| !key.is_empty() && !key.contains(' '))
+        })
+    });
+    let mut out = body.to_string();
+    out.push_str(if ends_with_trailers { "\n" } else { "\n\n" });
+    out.push_str(&trailers.into_iter().collect::<Vec<_>>().join("\n"));
+    out.push('\n');
+    out
+}
+
 pub fn build_squash_plan(source_branch: &str) -> Result<SquashPlan, Error> {
     build_squash_plan_in(".", source_branch)
 }
@@ -367,6 +674,26 @@ pub fn commit_in_progress_cherry_pick_in<P: AsRef<Path>>(
     commit_in_progress_cherry_pick_repo(&repo, message, expected_parent)
 }
 
+/// Abandons an in-progress merge, cherry-pick, or revert by clearing the sequencer state and
+/// resetting the index and worktree to `HEAD`. Returns the kind of operation that was aborted,
+/// or `None` when the repository was already clean.
+pub fn abort_in_progress_operation_in<P: AsRef<Path>>(
+    repo_path: P,
+) -> Result<Option<&'static str>, Error> {
+    let repo = Repository::open(repo_path)?;
+    let kind = match repo.state() {
+        RepositoryState::Merge => "merge",
+        RepositoryState::CherryPick | RepositoryState::CherryPickSequence => "cherry-pick",
+        RepositoryState::Revert | RepositoryState::RevertSequence => "revert",
+        _ => return Ok(None),
+    };
+
+    let head = repo.head()?.peel_to_commit()?;
+    repo.reset(head.as_object(), ResetType::Hard, None)?;
+    repo.cleanup_state()?;
+    Ok(Some(kind))
+}
+
 pub fn commit_ready_merge(message: &str, ready: MergeReady) -> Result<Oid, Error> {
     commit_ready_merge_in(".", message, ready)
 }
@@ -421,6 +748,215 @@ pub fn commit_squashed_merge_in<P: AsRef<Path>>(
     Ok(oid)
 }
 
+/// Commits the inverse of `commit` on top of HEAD (like `git revert --no-edit`). Merge commits
+/// are reverted against their first parent, i.e. the target branch's previous tip.
+pub fn revert_commit_in<P: AsRef<Path>>(repo_path: P, commit: Oid) -> Result<Oid, Error> {
+    let repo = Repository::open(repo_path)?;
+    let reverted = repo.find_commit(commit)?;
+    let head_commit = repo.head()?.peel_to_commit()?;
+    let mut opts = git2::RevertOptions::new();
+    if reverted.parent_count() > 1 {
+        opts.mainline(1);
+    }
+    repo.revert(&reverted, Some(&mut opts))?;
+
+    let mut index = repo.index()?;
+    if index.has_conflicts() {
+        repo.cleanup_state()?;
+        let mut checkout = CheckoutBuilder::new();
+        checkout.force();
+        repo.checkout_head(Some(&mut checkout))?;
+        return Err(Error::from_str(&format!(
+            "reverting {commit} conflicts with the current HEAD"
+        )));
+    }
+    let tree = repo.find_tree(index.write_tree()?)?;
+    let sig = repo.signature()?;
+    let message = format!(
+        "Revert \"{}\"\n\nThis reverts commit {commit}.\n",
+        reverted.summary().unwrap_or_default()
+    );
+    let oid = repo.commit(Some("HEAD"), &sig, &sig, &message, &tree, &[&head_commit])?;
+    repo.cleanup_state()?;
+    Ok(oid)
+}
+
+#[derive(Debug, Clone)]
+pub struct PickReady {
+    pub source_oid: Oid,
+    pub onto_oid: Oid,
+    pub tree_oid: Oid,
+}
+
+#[derive(Debug, Clone)]
+pub enum PickPreparation {
+    Ready(PickReady),
+    Conflicted { files: Vec<String> },
+}
+
+/// Cherry-picks `commit` onto the tip of branch `onto` in memory, leaving every checkout and ref
+/// untouched. Merge commits are rejected because a single plan step never is one.
+pub fn prepare_pick_in<P: AsRef<Path>>(
+    repo_path: P,
+    commit: Oid,
+    onto: &str,
+) -> Result<PickPreparation, Error> {
+    let repo = Repository::open(repo_path)?;
+    let picked = repo.find_commit(commit)?;
+    if picked.parent_count() != 1 {
+        return Err(Error::from_str(&format!(
+            "{commit} is not a single-parent commit and cannot be picked"
+        )));
+    }
+    let onto_commit = repo
+        .find_branch(onto, BranchType::Local)?
+        .get()
+        .peel_to_commit()?;
+
+    let mut index = repo.cherrypick_commit(&picked, &onto_commit, 0, None)?;
+    if index.has_conflicts() {
+        return Ok(PickPreparation::Conflicted {
+            files: collect_conflict_paths(&mut index),
+        });
+    }
+    let tree_oid = index.write_tree_to(&repo)?;
+    if tree_oid == onto_commit.tree_id() {
+        return Err(Error::from_str(&format!(
+            "the changes from {commit} are already on `{onto}`"
+        )));
+    }
+
+    Ok(PickPreparation::Ready(PickReady {
+        source_oid: commit,
+        onto_oid: onto_commit.id(),
+        tree_oid,
+    }))
+}
+
+/// Writes the picked tree as a commit on top of `ready.onto_oid` without moving any ref. The
+/// original author is kept; the committer is the current user.
+pub fn create_pick_commit_in<P: AsRef<Path>>(
+    repo_path: P,
+    ready: &PickReady,
+    message: &str,
+) -> Result<Oid, Error> {
+    let repo = Repository::open(repo_path)?;
+    let source = repo.find_commit(ready.source_oid)?;
+    let onto = repo.find_commit(ready.onto_oid)?;
+    let tree = repo.find_tree(ready.tree_oid)?;
+    let committer = repo.signature()?;
+    repo.commit(None, &source.author(), &committer, message, &tree, &[&onto])
+}
+
+#[derive(Debug, Clone)]
+pub enum BackportPreparation {
+    /// `head_oid` sits on top of `onto_oid`; `picked` lists the new commits, oldest first, and is
+    /// empty when the branch already had every change.
+    Ready {
+        onto_oid: Oid,
+        head_oid: Oid,
+        picked: Vec<Oid>,
+    },
+    Conflicted {
+        commit: Oid,
+        files: Vec<String>,
+    },
+}
+
+/// Cherry-picks `commits` in order onto the tip of branch `onto` in memory, writing a commit for
+/// each pick that changes the tree and moving no ref. Merge commits are replayed against their
+/// first parent, and narrative documents merge by section as in a rebase. Original authors are
+/// kept; the committer is the current user.
+pub fn prepare_backport_in<P: AsRef<Path>>(
+    repo_path: P,
+    commits: &[Oid],
+    onto: &str,
+) -> Result<BackportPreparation, Error> {
+    let repo = Repository::open(repo_path)?;
+    let onto_commit = repo
+        .find_branch(onto, BranchType::Local)?
+        .get()
+        .peel_to_commit()?;
+    let committer = repo.signature()?;
+
+    let mut head = onto_commit.clone();
+    let mut picked = Vec::new();
+    for &oid in commits {
+        let commit = repo.find_commit(oid)?;
+        let mainline = u32::from(commit.parent_count() > 1);
+        let mut index = repo.cherrypick_commit(&commit, &head, mainline, None)?;
+        if index.has_conflicts() {
+            let label = oid.to_string()[..7].to_string();
+            narrative_merge::resolve_index_conflicts(&repo, &mut index, onto, &label)?;
+        }
+        if index.has_conflicts() {
+            return Ok(BackportPreparation::Conflicted {
+                commit: oid,
+                files: collect_conflict_paths(&mut index),
+            });
+        }
+        let tree_oid = index.write_tree_to(&repo)?;
+        if tree_oid == head.tree_id() {
+            continue;
+        }
+        let message = format!(
+            "{}\n\n(cherry picked from commit {oid})\n",
+            commit.message().unwrap_or_default().trim_end()
+        );
+        let tree = repo.find_tree(tree_oid)?;
+        let new = repo.commit(
+            None,
+            &commit.author(),
+            &committer,
+            &message,
+            &tree,
+            &[&head],
+        )?;
+        head = repo.find_commit(new)?;
+        picked.push(new);
+    }
+
+    Ok(BackportPreparation::Ready {
+        onto_oid: onto_commit.id(),
+        head_oid: head.id(),
+        picked,
+    })
+}
+
+/// Moves `branch` from `expected` to `new`, refusing if the branch moved in the meantime. When
+/// the branch is checked out, the working tree is updated with a safe checkout first so local
+/// edits are never overwritten.
+pub fn fast_forward_branch_in<P: AsRef<Path>>(
+    repo_path: P,
+    branch: &str,
+    expected: Oid,
+    new: Oid,
+) -> Result<(), Error> {
+    let repo = Repository::open(repo_path)?;
+    let mut reference = repo
+        .find_branch(branch, BranchType::Local)?
+        .into_reference();
+    if reference.target() != Some(expected) {
+        return Err(Error::from_str(&format!(
+            "`{branch}` moved while it was being updated"
+        )));
+    }
+
+    let checked_out = repo
+        .head()
+        .ok()
+        .and_then(|head| head.name().map(str::to_string))
+        == reference.name().map(str::to_string);
+    if checked_out {
+        let commit = repo.find_commit(new)?;
+        let mut checkout = CheckoutBuilder::new();
+        checkout.safe();
+        repo.checkout_tree(commit.as_object(), Some(&mut checkout))?;
+    }
+    reference.set_target(new, &format!("vizier: fast-forward {branch}"))?;
+    Ok(())
+}
+
 pub fn commit_in_progress_merge(
     message: &str,
     head_oid: Oid,
@@ -539,7 +1075,7 @@ pub fn list_conflicted_paths_in<P: AsRef<Path>>(repo_path: P) -> Result<Vec<Stri
     Ok(collect_conflict_paths(&mut index))
 }
 
-fn collect_conflict_paths(index: &mut Index) -> Vec<String> {
+pub(super) fn collect_conflict_paths(index: &mut Index) -> Vec<String> {
     let mut files = Vec::new();
     if let Ok(mut conflicts) = index.conflicts() {
         for conflict in conflicts.by_ref().flatten() {
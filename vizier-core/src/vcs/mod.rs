@@ -1,33 +1,55 @@
 mod branches;
 mod commits;
 mod merge;
+mod notes;
+mod patches;
+mod range_diff;
+mod rebase;
 mod release;
 mod remotes;
 mod status;
 mod worktrees;
 
 pub use branches::{
-    branch_exists, branch_exists_in, checkout_branch, checkout_branch_in, create_branch_from,
-    create_branch_from_head_in, create_branch_from_in, current_branch_name_in, delete_branch,
-    delete_branch_in, detect_primary_branch, detect_primary_branch_in,
+    BranchCommit, BranchDivergence, DetectedTarget, TargetSource, branch_commits_in,
+    branch_divergence_in, branch_exists, branch_exists_in, checkout_branch, checkout_branch_in,
+    create_branch_from, create_branch_from_head_in, create_branch_from_in, current_branch_name_in,
+    delete_branch, delete_branch_in, detect_primary_branch, detect_primary_branch_in,
+    detect_target_branch_in,
 };
 pub use commits::{
-    StagedItem, StagedKind, add_and_commit, add_and_commit_in, amend_head_commit,
-    blob_exists_at_revision, blob_exists_at_revision_in, commit_paths_in_repo, commit_staged,
-    commit_staged_in, get_log, read_blob_at_revision, read_blob_at_revision_in, restore_staged,
-    revisions_touching_path, revisions_touching_path_in, snapshot_staged, stage, stage_all,
-    stage_all_in, stage_in, stage_paths_allow_missing, stage_paths_allow_missing_in, unstage,
-    unstage_in,
+    SplitCommits, StagedItem, StagedKind, add_and_commit, add_and_commit_in, amend_head_commit,
+    blob_exists_at_revision, blob_exists_at_revision_in, branch_tips_in,
+    commit_file_changes_to_branch_in, commit_files_to_branch_in, commit_files_to_new_branch_in,
+    commit_paths_in_repo, commit_staged, commit_staged_in, commit_staged_split_in,
+    commits_since_tips_in, get_log, read_blob_at_revision, read_blob_at_revision_in,
+    restore_staged, revisions_touching_path, revisions_touching_path_in, snapshot_staged, stage,
+    stage_all, stage_all_in, stage_in, stage_paths_allow_missing, stage_paths_allow_missing_in,
+    unstage, unstage_in,
 };
+pub(crate) use merge::append_trailers;
 pub use merge::{
-    CherryPickApply, CherryPickApplyConflict, CherryPickOutcome, MergeCommitSummary, MergeConflict,
-    MergePreparation, MergeReady, SquashPlan, apply_cherry_pick_sequence, build_squash_plan,
-    build_squash_plan_in, commit_in_progress_cherry_pick, commit_in_progress_cherry_pick_in,
-    commit_in_progress_merge, commit_in_progress_merge_in, commit_in_progress_squash,
-    commit_in_progress_squash_in, commit_ready_merge, commit_ready_merge_in, commit_soft_squash,
-    commit_squashed_merge, commit_squashed_merge_in, list_conflicted_paths,
-    list_conflicted_paths_in, prepare_merge, prepare_merge_in,
+    BackportPreparation, CherryPickApply, CherryPickApplyConflict, CherryPickOutcome,
+    MergeCommitSummary, MergeConflict, MergePreparation, MergeReady, PickPreparation, PickReady,
+    RebaseMergeOutcome, STEP_TRAILER, SquashPlan, StepGroup, abort_in_progress_operation_in,
+    apply_cherry_pick_sequence, build_squash_plan, build_squash_plan_in,
+    commit_in_progress_cherry_pick, commit_in_progress_cherry_pick_in, commit_in_progress_merge,
+    commit_in_progress_merge_in, commit_in_progress_squash, commit_in_progress_squash_in,
+    commit_ready_merge, commit_ready_merge_in, commit_soft_squash, commit_squashed_merge,
+    commit_squashed_merge_in, create_pick_commit_in, fast_forward_branch_in, list_conflicted_paths,
+    list_conflicted_paths_in, plan_step_groups_in, prepare_backport_in, prepare_merge,
+    prepare_merge_in, prepare_pick_in, rebase_and_fast_forward_in, revert_commit_in,
+    squash_step_groups_in, step_marker,
 };
+pub use notes::{
+    VIZIER_NOTES_REF, commit_provenance, commit_provenance_value, read_commit_note,
+    write_commit_note_in,
+};
+pub use patches::{
+    ImportedPatch, PatchAuthor, apply_patch_to_commit_in, create_patch_commit_in, parse_patch_input,
+};
+pub use range_diff::{RangeDiff, RangeDiffEntry, RangeDiffStatus, range_diff_in};
+pub use rebase::{RebaseProgress, abort_rebase_in, continue_rebase_in, start_rebase_in};
 pub use release::{
     ReleaseBump, ReleaseCommit, ReleaseNoteEntry, ReleaseNotes, ReleaseSectionKind, ReleaseTag,
     ReleaseVersion, build_release_notes, classify_commit, commits_since_release_tag,
@@ -35,17 +57,23 @@ pub use release::{
     latest_reachable_release_tag, parse_release_version_tag, release_tag_exists,
 };
 pub use remotes::{
-    AttemptOutcome, CredentialAttempt, CredentialStrategy, HelperScope, PushError, PushErrorKind,
-    RemoteScheme, SshKeyKind, origin_owner_repo, push_current_branch, push_current_branch_in,
+    AttemptOutcome, CredentialAttempt, CredentialStrategy, HelperScope, LeasePush, PushError,
+    PushErrorKind, RemoteScheme, SshKeyKind, fetch_branch_in, origin_owner_repo, pull_request_url,
+    push_branch_in, push_branch_with_lease_in, push_current_branch, push_current_branch_in,
+    remote_url_in,
 };
 pub use status::{
-    DiffSummary, apply_patch_file_with_index_in, apply_patch_with_index_in,
-    diff_binary_against_head_in, diff_summary_against_target, ensure_clean_worktree, get_diff,
-    repo_root, status_with_branch,
+    DiffFileStat, DiffFileStatus, DiffLimits, DiffStats, DiffSummary, FilePatch,
+    apply_patch_file_with_index_in, apply_patch_with_index_in, diff_binary_against_head_in,
+    diff_patch_between, diff_stats_between, diff_summary_against_target, diff_summary_between,
+    discard_worktree_changes_in, ensure_clean_worktree, ensure_clean_worktree_in,
+    for_each_file_patch, get_diff, get_diff_with_limits, is_bare_repo, repo_root,
+    status_with_branch,
 };
 pub use worktrees::{
-    add_worktree_for_branch, add_worktree_for_branch_in, find_worktree_name_by_path_in,
-    remove_worktree, remove_worktree_in,
+    ExternalWorktree, add_worktree_for_branch, add_worktree_for_branch_in,
+    external_worktree_for_branch_in, find_worktree_name_by_path_in, remove_worktree,
+    remove_worktree_in,
 };
 
 fn normalize_pathspec(path: &str) -> String {
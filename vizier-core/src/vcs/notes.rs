@@ -0,0 +1,69 @@
+use std::path::Path;
+
+use git2::{Commit, ErrorCode, Oid, Repository, Signature};
+
+/// Notes ref holding Vizier provenance when `[commits.meta] style = "notes"`.
+pub const VIZIER_NOTES_REF: &str = "refs/notes/vizier";
+
+/// Attaches `lines` (`Key: value`) to `commit` under [`VIZIER_NOTES_REF`], replacing any note
+/// already there. Nothing is written when `lines` is empty.
+pub fn write_commit_note_in<P: AsRef<Path>>(
+    repo_path: P,
+    commit: Oid,
+    lines: &[String],
+) -> Result<(), git2::Error> {
+    if lines.is_empty() {
+        return Ok(());
+    }
+    let repo = Repository::open(repo_path)?;
+    let sig = repo
+        .signature()
+        .or_else(|_| Signature::now("Vizier", "vizier@local"))?;
+    let mut text = lines.join("\n");
+    text.push('\n');
+    repo.note(&sig, &sig, Some(VIZIER_NOTES_REF), commit, &text, true)?;
+    Ok(())
+}
+
+/// The Vizier note on `commit`, if it has one.
+pub fn read_commit_note(repo: &Repository, commit: Oid) -> Result<Option<String>, git2::Error> {
+    match repo.find_note(Some(VIZIER_NOTES_REF), commit) {
+        Ok(note) => Ok(note.message().map(str::to_string)),
+        Err(err) if err.code() == ErrorCode::NotFound => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+/// `Key: value` provenance for `commit`: its message trailers followed by the lines of its Vizier
+/// note, so readers find the same fields whichever `[commits.meta] style` wrote them.
+pub fn commit_provenance(repo: &Repository, commit: &Commit<'_>) -> Vec<(String, String)> {
+    let mut fields = Vec::new();
+    if let Some(message) = commit.message()
+        && let Ok(trailers) = git2::message_trailers_strs(message)
+    {
+        fields.extend(
+            trailers
+                .iter()
+                .map(|(key, value)| (key.trim().to_string(), value.trim().to_string())),
+        );
+    }
+    if let Ok(Some(note)) = read_commit_note(repo, commit.id()) {
+        fields.extend(note.lines().filter_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            Some((key.trim().to_string(), value.trim().to_string()))
+        }));
+    }
+    fields
+}
+
+/// First provenance value recorded under `key` (case-insensitive) for `commit`.
+pub fn commit_provenance_value(
+    repo: &Repository,
+    commit: &Commit<'_>,
+    key: &str,
+) -> Option<String> {
+    commit_provenance(repo, commit)
+        .into_iter()
+        .find(|(field, value)| field.eq_ignore_ascii_case(key) && !value.is_empty())
+        .map(|(_, value)| value)
+}
@@ -0,0 +1,305 @@
+use std::path::Path;
+
+use chrono::DateTime;
+use git2::{Delta, Diff, Error, Oid, Repository, Signature, Time};
+
+/// Author identity carried by a `git format-patch` message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatchAuthor {
+    pub name: String,
+    pub email: String,
+    /// Seconds since the epoch and UTC offset in minutes, when the patch had a parseable `Date:`.
+    pub time: Option<(i64, i32)>,
+}
+
+/// One change to import: a bare unified diff, or a single message from a format-patch mbox.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedPatch {
+    pub author: Option<PatchAuthor>,
+    /// Subject plus body from the mbox message, with the `[PATCH n/m]` prefix removed.
+    pub message: Option<String>,
+    pub diff: String,
+}
+
+/// Splits patch input into the changes it contains. Input starting with an mbox `From ` line is
+/// read as a `git format-patch` series (cover letters and other diff-less messages are skipped);
+/// anything else is one unified diff, with any preamble before the first file header dropped.
+pub fn parse_patch_input(input: &str) -> Result<Vec<ImportedPatch>, String> {
+    let input = input.replace("\r\n", "\n");
+    let is_mbox = input
+        .lines()
+        .find(|line| !line.trim().is_empty())
+        .is_some_and(|line| line.starts_with("From "));
+
+    let patches = if is_mbox {
+        split_mbox(&input)
+            .into_iter()
+            .filter_map(parse_mbox_message)
+            .collect::<Vec<_>>()
+    } else {
+        extract_diff(&input)
+            .map(|diff| ImportedPatch {
+                author: None,
+                message: None,
+                diff,
+            })
+            .into_iter()
+            .collect()
+    };
+
+    if patches.is_empty() {
+        return Err("patch input contains no diff".to_string());
+    }
+    Ok(patches)
+}
+
+fn split_mbox(input: &str) -> Vec<Vec<&str>> {
+    let mut messages: Vec<Vec<&str>> = Vec::new();
+    for line in input.lines() {
+        if line.starts_with("From ") && (messages.is_empty() || is_mbox_separator(line)) {
+            messages.push(Vec::new());
+            continue;
+        }
+        if let Some(message) = messages.last_mut() {
+            message.push(line);
+        }
+    }
+    messages
+}
+
+// format-patch separators look like `From <40-hex sha> Mon Sep 17 00:00:00 2001`.
+fn is_mbox_separator(line: &str) -> bool {
+    line.split_whitespace()
+        .nth(1)
+        .is_some_and(|sha| sha.len() >= 40 && sha.chars().all(|ch| ch.is_ascii_hexdigit()))
+}
+
+fn parse_mbox_message(lines: Vec<&str>) -> Option<ImportedPatch> {
+    let mut headers: Vec<(String, String)> = Vec::new();
+    let mut index = 0;
+    while index < lines.len() {
+        let line = lines[index];
+        index += 1;
+        if line.is_empty() {
+            break;
+        }
+        if line.starts_with([' ', '\t']) {
+            if let Some((_, value)) = headers.last_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+            continue;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+        }
+    }
+    let header = |name: &str| {
+        headers
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    };
+
+    let rest = &lines[index..];
+    let diff_start = rest.iter().position(|line| is_diff_start(line))?;
+    let body_end = rest[..diff_start]
+        .iter()
+        .position(|line| *line == "---")
+        .unwrap_or(diff_start);
+    let diff_end = rest[diff_start..]
+        .iter()
+        .position(|line| *line == "-- ")
+        .map(|offset| diff_start + offset)
+        .unwrap_or(rest.len());
+
+    let subject = header("subject")
+        .map(strip_patch_prefix)
+        .unwrap_or_default();
+    let body = rest[..body_end].join("\n");
+    let message = [subject.trim(), body.trim()]
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    Some(ImportedPatch {
+        author: header("from").and_then(|from| parse_author(from, header("date"))),
+        message: (!message.is_empty()).then_some(message),
+        diff: format!("{}\n", rest[diff_start..diff_end].join("\n")),
+    })
+}
+
+fn strip_patch_prefix(subject: &str) -> String {
+    let subject = subject.trim();
+    if subject.starts_with('[')
+        && let Some(end) = subject.find(']')
+        && subject[1..end].to_ascii_uppercase().contains("PATCH")
+    {
+        return subject[end + 1..].trim().to_string();
+    }
+    subject.to_string()
+}
+
+fn parse_author(from: &str, date: Option<&str>) -> Option<PatchAuthor> {
+    let (name, email) = match (from.rfind('<'), from.rfind('>')) {
+        (Some(open), Some(close)) if open < close => (
+            from[..open].trim().trim_matches('"').to_string(),
+            from[open + 1..close].trim().to_string(),
+        ),
+        _ => (String::new(), from.trim().to_string()),
+    };
+    if email.is_empty() {
+        return None;
+    }
+    let name = if name.is_empty() { email.clone() } else { name };
+    let time = date
+        .and_then(|date| DateTime::parse_from_rfc2822(date.trim()).ok())
+        .map(|date| (date.timestamp(), date.offset().local_minus_utc() / 60));
+    Some(PatchAuthor { name, email, time })
+}
+
+fn is_diff_start(line: &str) -> bool {
+    line.starts_with("diff --git ") || line.starts_with("--- ")
+}
+
+fn extract_diff(input: &str) -> Option<String> {
+    let lines = input.lines().collect::<Vec<_>>();
+    let start = lines.iter().position(|line| is_diff_start(line))?;
+    Some(format!("{}\n", lines[start..].join("\n")))
+}
+
+/// Applies `diff` to the tree of `onto` in memory and returns the resulting tree. Nothing in the
+/// working tree, index, or refs changes; a patch that does not apply cleanly is an error.
+pub fn apply_patch_to_commit_in<P: AsRef<Path>>(
+    repo_path: P,
+    onto: Oid,
+    diff: &str,
+) -> Result<Oid, Error> {
+    let repo = Repository::open(repo_path)?;
+    let onto = repo.find_commit(onto)?;
+    let diff = Diff::from_buffer(diff.as_bytes())?;
+    let tree = onto.tree()?;
+    // libgit2 applies a file creation onto an existing file as an insertion; refuse it the way
+    // `git apply` does.
+    for delta in diff.deltas() {
+        if delta.status() == Delta::Added
+            && let Some(path) = delta.new_file().path()
+            && tree.get_path(path).is_ok()
+        {
+            return Err(Error::from_str(&format!(
+                "{} already exists on the branch",
+                path.display()
+            )));
+        }
+    }
+    let mut index = repo.apply_to_tree(&tree, &diff, None)?;
+    let tree_oid = index.write_tree_to(&repo)?;
+    if tree_oid == onto.tree_id() {
+        return Err(Error::from_str("the patch makes no changes"));
+    }
+    Ok(tree_oid)
+}
+
+/// Writes `tree` as a commit on top of `parent` without moving any ref. The patch author (and
+/// date) is kept when known; the committer is the current user.
+pub fn create_patch_commit_in<P: AsRef<Path>>(
+    repo_path: P,
+    parent: Oid,
+    tree: Oid,
+    author: Option<&PatchAuthor>,
+    message: &str,
+) -> Result<Oid, Error> {
+    let repo = Repository::open(repo_path)?;
+    let committer = repo
+        .signature()
+        .or_else(|_| Signature::now("Vizier", "vizier@local"))?;
+    let author = match author {
+        Some(PatchAuthor {
+            name,
+            email,
+            time: Some((seconds, offset)),
+        }) => Signature::new(name, email, &Time::new(*seconds, *offset))?,
+        Some(PatchAuthor { name, email, .. }) => Signature::now(name, email)?,
+        None => committer.clone(),
+    };
+    let parent = repo.find_commit(parent)?;
+    let tree = repo.find_tree(tree)?;
+    repo.commit(None, &author, &committer, message, &tree, &[&parent])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SERIES: &str = "From 0123456789abcdef0123456789abcdef01234567 Mon Sep 17 00:00:00 2001
+From: Ada Lovelace <ada@example.com>
+Date: Thu, 1 Jan 2026 12:00:00 +0100
+Subject: [PATCH 1/2] Add the engine
+ notes
+
+Explain the engine.
+---
+ engine.txt | 1 +
+ 1 file changed, 1 insertion(+)
+
+diff --git a/engine.txt b/engine.txt
+new file mode 100644
+index 0000000..e69de29
+--- /dev/null
++++ b/engine.txt
+@@ -0,0 +1 @@
++engine
+--\x20
+2.43.0
+
+From 89abcdef0123456789abcdef0123456789abcdef Mon Sep 17 00:00:00 2001
+From: Ada Lovelace <ada@example.com>
+Subject: [PATCH 2/2] Tune the engine
+
+---
+diff --git a/engine.txt b/engine.txt
+--- a/engine.txt
++++ b/engine.txt
+@@ -1 +1 @@
+-engine
++analytical engine
+--\x20
+2.43.0
+";
+
+    #[test]
+    fn parse_patch_input_reads_format_patch_series() {
+        let patches = parse_patch_input(SERIES).expect("parse series");
+        assert_eq!(patches.len(), 2);
+
+        let first = &patches[0];
+        assert_eq!(
+            first.message.as_deref(),
+            Some("Add the engine notes\n\nExplain the engine.")
+        );
+        let author = first.author.as_ref().expect("author");
+        assert_eq!(author.name, "Ada Lovelace");
+        assert_eq!(author.email, "ada@example.com");
+        assert_eq!(author.time, Some((1_767_265_200, 60)));
+        assert!(first.diff.starts_with("diff --git a/engine.txt"));
+        assert!(first.diff.ends_with("+engine\n"), "{}", first.diff);
+
+        assert_eq!(patches[1].message.as_deref(), Some("Tune the engine"));
+        assert_eq!(patches[1].author.as_ref().and_then(|a| a.time), None);
+    }
+
+    #[test]
+    fn parse_patch_input_reads_bare_diff_and_rejects_empty_input() {
+        let patches = parse_patch_input(
+            "commit abc\n\ndiff --git a/a b/a\n--- a/a\n+++ b/a\n@@ -1 +1 @@\n-x\n+y\n",
+        )
+        .expect("parse diff");
+        assert_eq!(patches.len(), 1);
+        assert_eq!(patches[0].message, None);
+        assert!(patches[0].diff.starts_with("diff --git a/a b/a"));
+
+        let err = parse_patch_input("just some text\n").expect_err("no diff");
+        assert!(err.contains("no diff"), "{err}");
+    }
+}
@@ -0,0 +1,233 @@
+use git2::{Commit, DiffFormat, Error, Oid, Patch, Repository, Sort};
+use std::path::Path;
+
+/// How one commit of the old range relates to the new range, mirroring `git range-diff`
+/// markers (`=`, `!`, `>`, `<`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeDiffStatus {
+    Unchanged,
+    Modified,
+    Added,
+    Removed,
+}
+
+impl RangeDiffStatus {
+    pub fn marker(self) -> char {
+        match self {
+            Self::Unchanged => '=',
+            Self::Modified => '!',
+            Self::Added => '>',
+            Self::Removed => '<',
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeDiffEntry {
+    pub status: RangeDiffStatus,
+    /// 1-based position and id in the old range.
+    pub old: Option<(usize, Oid)>,
+    /// 1-based position and id in the new range.
+    pub new: Option<(usize, Oid)>,
+    pub summary: String,
+    /// Diff between the old and new patch text for `Modified` pairs.
+    pub interdiff: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RangeDiff {
+    pub entries: Vec<RangeDiffEntry>,
+}
+
+impl RangeDiff {
+    pub fn count(&self, status: RangeDiffStatus) -> usize {
+        self.entries
+            .iter()
+            .filter(|entry| entry.status == status)
+            .count()
+    }
+
+    /// True when every commit carried over with an identical patch.
+    pub fn is_unchanged(&self) -> bool {
+        self.entries
+            .iter()
+            .all(|entry| entry.status == RangeDiffStatus::Unchanged)
+    }
+
+    /// Renders the pairing in `git range-diff` style, with interdiffs indented under `!` lines.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for entry in &self.entries {
+            let side = |slot: Option<(usize, Oid)>| match slot {
+                Some((index, oid)) => format!("{index}:  {}", &oid.to_string()[..7]),
+                None => "-:  -------".to_string(),
+            };
+            out.push_str(&format!(
+                "{} {} {} {}\n",
+                side(entry.old),
+                entry.status.marker(),
+                side(entry.new),
+                entry.summary
+            ));
+            if let Some(interdiff) = entry.interdiff.as_deref() {
+                for line in interdiff.lines() {
+                    out.push_str("    ");
+                    out.push_str(line);
+                    out.push('\n');
+                }
+            }
+        }
+        out
+    }
+}
+
+struct RangeCommit {
+    oid: Oid,
+    summary: String,
+    patch_id: Oid,
+    patch: String,
+}
+
+/// Pairs the commits of `base..old_tip` with those of `base..new_tip` (each range starting at
+/// its own merge base with `base`), matching first by patch id and then by subject.
+pub fn range_diff_in<P: AsRef<Path>>(
+    repo_path: P,
+    base: &str,
+    old_tip: Oid,
+    new_tip: Oid,
+) -> Result<RangeDiff, Error> {
+    let repo = Repository::open(repo_path)?;
+    let base_oid = repo.revparse_single(base)?.peel_to_commit()?.id();
+    let old = collect_range(&repo, base_oid, old_tip)?;
+    let new = collect_range(&repo, base_oid, new_tip)?;
+
+    let mut old_match = vec![None::<usize>; old.len()];
+    let mut new_match = vec![None::<usize>; new.len()];
+    for (new_index, commit) in new.iter().enumerate() {
+        if let Some(old_index) = (0..old.len())
+            .find(|&index| old_match[index].is_none() && old[index].patch_id == commit.patch_id)
+        {
+            old_match[old_index] = Some(new_index);
+            new_match[new_index] = Some(old_index);
+        }
+    }
+    for (new_index, commit) in new.iter().enumerate() {
+        if new_match[new_index].is_some() {
+            continue;
+        }
+        if let Some(old_index) = (0..old.len())
+            .find(|&index| old_match[index].is_none() && old[index].summary == commit.summary)
+        {
+            old_match[old_index] = Some(new_index);
+            new_match[new_index] = Some(old_index);
+        }
+    }
+
+    let removed = |index: usize| RangeDiffEntry {
+        status: RangeDiffStatus::Removed,
+        old: Some((index + 1, old[index].oid)),
+        new: None,
+        summary: old[index].summary.clone(),
+        interdiff: None,
+    };
+    let mut entries = Vec::new();
+    let mut next_old = 0usize;
+    for (new_index, commit) in new.iter().enumerate() {
+        let Some(old_index) = new_match[new_index] else {
+            entries.push(RangeDiffEntry {
+                status: RangeDiffStatus::Added,
+                old: None,
+                new: Some((new_index + 1, commit.oid)),
+                summary: commit.summary.clone(),
+                interdiff: None,
+            });
+            continue;
+        };
+        while next_old < old_index {
+            if old_match[next_old].is_none() {
+                entries.push(removed(next_old));
+            }
+            next_old += 1;
+        }
+        next_old = next_old.max(old_index + 1);
+
+        let previous = &old[old_index];
+        let (status, interdiff) = if previous.patch_id == commit.patch_id {
+            (RangeDiffStatus::Unchanged, None)
+        } else {
+            (
+                RangeDiffStatus::Modified,
+                Some(interdiff(&previous.patch, &commit.patch)?),
+            )
+        };
+        entries.push(RangeDiffEntry {
+            status,
+            old: Some((old_index + 1, previous.oid)),
+            new: Some((new_index + 1, commit.oid)),
+            summary: commit.summary.clone(),
+            interdiff,
+        });
+    }
+    entries.extend(
+        (next_old..old.len())
+            .filter(|&index| old_match[index].is_none())
+            .map(removed),
+    );
+
+    Ok(RangeDiff { entries })
+}
+
+fn collect_range(repo: &Repository, base: Oid, tip: Oid) -> Result<Vec<RangeCommit>, Error> {
+    let merge_base = repo.merge_base(base, tip)?;
+    let mut walk = repo.revwalk()?;
+    walk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+    walk.push(tip)?;
+    walk.hide(merge_base)?;
+
+    let mut commits = Vec::new();
+    for oid in walk {
+        let commit = repo.find_commit(oid?)?;
+        if commit.parent_count() > 1 {
+            continue;
+        }
+        commits.push(range_commit(repo, &commit)?);
+    }
+    Ok(commits)
+}
+
+fn range_commit(repo: &Repository, commit: &Commit<'_>) -> Result<RangeCommit, Error> {
+    let parent_tree = match commit.parent(0) {
+        Ok(parent) => Some(parent.tree()?),
+        Err(_) => None,
+    };
+    let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?;
+    let mut patch = String::new();
+    diff.print(DiffFormat::Patch, |_, _, line| {
+        let content = String::from_utf8_lossy(line.content());
+        // Blob ids change whenever the base moves; they are noise in an interdiff.
+        if !content.starts_with("index ") {
+            if matches!(line.origin(), '+' | '-' | ' ') {
+                patch.push(line.origin());
+            }
+            patch.push_str(&content);
+        }
+        true
+    })?;
+    Ok(RangeCommit {
+        oid: commit.id(),
+        summary: commit.summary().unwrap_or_default().to_string(),
+        patch_id: diff.patchid(None)?,
+        patch,
+    })
+}
+
+fn interdiff(old_patch: &str, new_patch: &str) -> Result<String, Error> {
+    let mut patch =
+        Patch::from_buffers(old_patch.as_bytes(), None, new_patch.as_bytes(), None, None)?;
+    let text = String::from_utf8_lossy(&patch.to_buf()?).into_owned();
+    Ok(text
+        .lines()
+        .skip_while(|line| !line.starts_with("@@"))
+        .map(|line| format!("{line}\n"))
+        .collect())
+}
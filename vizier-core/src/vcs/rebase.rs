@@ -0,0 +1,114 @@
+use git2::build::CheckoutBuilder;
+use git2::{BranchType, Error, ErrorCode, Oid, Rebase, RebaseOptions, Repository, Signature};
+use std::path::Path;
+
+use super::merge::collect_conflict_paths;
+use crate::narrative_merge;
+
+/// Where a rebase stopped: either every commit was replayed, or one of them conflicted and the
+/// rebase is paused (state lives in the repository's `rebase-merge` directory).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RebaseProgress {
+    Completed {
+        head: Oid,
+        applied: usize,
+    },
+    Conflicted {
+        commit: Oid,
+        summary: String,
+        files: Vec<String>,
+    },
+}
+
+/// Starts rebasing the branch checked out at `repo_path` onto the local branch `onto`.
+pub fn start_rebase_in<P: AsRef<Path>>(repo_path: P, onto: &str) -> Result<RebaseProgress, Error> {
+    let repo = Repository::open(repo_path)?;
+    let onto_commit = repo
+        .find_branch(onto, BranchType::Local)?
+        .get()
+        .peel_to_commit()?;
+    let upstream = repo.find_annotated_commit(onto_commit.id())?;
+    let mut opts = rebase_options();
+    let mut rebase = repo.rebase(None, Some(&upstream), None, Some(&mut opts))?;
+    drive_rebase(&repo, &mut rebase, 0)
+}
+
+/// Commits the resolved operation of a paused rebase and replays the remaining commits.
+pub fn continue_rebase_in<P: AsRef<Path>>(repo_path: P) -> Result<RebaseProgress, Error> {
+    let repo = Repository::open(repo_path)?;
+    let mut opts = rebase_options();
+    let mut rebase = repo.open_rebase(Some(&mut opts))?;
+    let mut index = repo.index()?;
+    if index.has_conflicts() {
+        return Err(Error::from_str(
+            "rebase cannot continue while the index still has conflicts",
+        ));
+    }
+    index.write()?;
+    let applied = commit_current_operation(&repo, &mut rebase)?;
+    drive_rebase(&repo, &mut rebase, applied)
+}
+
+/// Aborts a paused rebase, restoring the branch to its pre-rebase tip.
+pub fn abort_rebase_in<P: AsRef<Path>>(repo_path: P) -> Result<(), Error> {
+    let repo = Repository::open(repo_path)?;
+    let mut rebase = repo.open_rebase(None)?;
+    rebase.abort()
+}
+
+fn rebase_options<'cb>() -> RebaseOptions<'cb> {
+    let mut checkout = CheckoutBuilder::new();
+    checkout
+        .safe()
+        .allow_conflicts(true)
+        .conflict_style_merge(true);
+    let mut opts = RebaseOptions::new();
+    opts.checkout_options(checkout);
+    opts
+}
+
+fn drive_rebase(
+    repo: &Repository,
+    rebase: &mut Rebase<'_>,
+    mut applied: usize,
+) -> Result<RebaseProgress, Error> {
+    while let Some(operation) = rebase.next() {
+        let operation = operation?;
+        let mut index = repo.index()?;
+        if index.has_conflicts() {
+            let commit = repo.find_commit(operation.id())?;
+            let label = commit.id().to_string()[..7].to_string();
+            let narrative =
+                narrative_merge::resolve_index_conflicts(repo, &mut index, "onto", &label)?;
+            narrative_merge::apply_to_worktree(repo, &mut index, &narrative)?;
+        }
+        if index.has_conflicts() {
+            let commit = repo.find_commit(operation.id())?;
+            return Ok(RebaseProgress::Conflicted {
+                commit: commit.id(),
+                summary: commit.summary().unwrap_or_default().to_string(),
+                files: collect_conflict_paths(&mut index),
+            });
+        }
+        applied += commit_current_operation(repo, rebase)?;
+    }
+
+    rebase.finish(Some(&signature(repo)?))?;
+    let head = repo.head()?.peel_to_commit()?.id();
+    Ok(RebaseProgress::Completed { head, applied })
+}
+
+// Commits replayed onto the new base keep their original author; patches that are already
+// present upstream (`GIT_EAPPLIED`) are dropped instead of producing empty commits.
+fn commit_current_operation(repo: &Repository, rebase: &mut Rebase<'_>) -> Result<usize, Error> {
+    match rebase.commit(None, &signature(repo)?, None) {
+        Ok(_) => Ok(1),
+        Err(err) if err.code() == ErrorCode::Applied => Ok(0),
+        Err(err) => Err(err),
+    }
+}
+
+fn signature(repo: &Repository) -> Result<Signature<'static>, Error> {
+    repo.signature()
+        .or_else(|_| Signature::now("Vizier", "vizier@local"))
+}
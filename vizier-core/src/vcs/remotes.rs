@@ -1,11 +1,11 @@
 use git2::{
-    BranchType, Cred, CredentialType, Error, ErrorClass, ErrorCode, PushOptions, RemoteCallbacks,
-    Repository, RepositoryState,
+    BranchType, CertificateCheckStatus, Cred, CredentialType, Error, ErrorClass, ErrorCode,
+    FetchOptions, PushOptions, RemoteCallbacks, Repository, RepositoryState,
 };
 use std::cell::RefCell;
 use std::env;
 use std::fmt;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -40,6 +40,7 @@ pub enum SshKeyKind {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CredentialStrategy {
     CredentialHelper(HelperScope),
+    SshAgent,
     SshKey(SshKeyKind),
     Username,
     Default,
@@ -49,6 +50,7 @@ impl CredentialStrategy {
     pub fn label(&self) -> &'static str {
         match self {
             CredentialStrategy::CredentialHelper(_) => "helper",
+            CredentialStrategy::SshAgent => "ssh-agent",
             CredentialStrategy::SshKey(SshKeyKind::IdEd25519) => "file-id_ed25519",
             CredentialStrategy::SshKey(SshKeyKind::IdRsa) => "file-id_rsa",
             CredentialStrategy::Username => "username",
@@ -88,12 +90,27 @@ struct SshKeyPaths {
 #[derive(Debug)]
 pub enum PushErrorKind {
     General(String),
+    /// Every credential strategy failed or was rejected by the remote.
     Auth {
         remote: String,
         url: String,
         scheme: RemoteScheme,
         attempts: Vec<CredentialAttempt>,
     },
+    /// The remote could not be reached: DNS, connection, TLS, or HTTP transport errors.
+    Network {
+        remote: String,
+        url: String,
+        message: String,
+    },
+    /// The SSH host key is missing from `known_hosts` or does not match it, or the TLS
+    /// certificate failed verification.
+    HostKey {
+        remote: String,
+        url: String,
+        host: String,
+        message: String,
+    },
 }
 
 #[derive(Debug)]
@@ -144,9 +161,37 @@ impl fmt::Display for PushError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match &self.kind {
             PushErrorKind::General(message) => write!(f, "{message}"),
-            PushErrorKind::Auth { remote, .. } => {
-                write!(f, "authentication failed when pushing to {remote}")
+            PushErrorKind::Auth {
+                remote, attempts, ..
+            } => {
+                write!(f, "authentication failed when pushing to {remote}")?;
+                if !attempts.is_empty() {
+                    let summary = attempts
+                        .iter()
+                        .map(|attempt| match attempt.outcome.message() {
+                            Some(message) => format!("{}: {message}", attempt.strategy.label()),
+                            None => format!("{}: ok", attempt.strategy.label()),
+                        })
+                        .collect::<Vec<_>>()
+                        .join("; ");
+                    write!(f, " ({summary})")?;
+                }
+                Ok(())
             }
+            PushErrorKind::Network {
+                remote,
+                url,
+                message,
+            } => write!(f, "unable to reach {remote} ({url}): {message}"),
+            PushErrorKind::HostKey {
+                remote,
+                host,
+                message,
+                ..
+            } => write!(
+                f,
+                "could not verify the identity of {host} for {remote}: {message}"
+            ),
         }
     }
 }
@@ -230,6 +275,7 @@ pub(crate) fn build_credential_plan(
     }
 
     if allowed_types.contains(CredentialType::SSH_KEY) {
+        plan.push(CredentialStrategy::SshAgent);
         plan.push(CredentialStrategy::SshKey(SshKeyKind::IdEd25519));
         plan.push(CredentialStrategy::SshKey(SshKeyKind::IdRsa));
     }
@@ -367,6 +413,15 @@ impl CredentialExecutor for RealCredentialExecutor {
                     )
                 }
             }
+            CredentialStrategy::SshAgent => {
+                if cfg!(unix) && env::var_os("SSH_AUTH_SOCK").is_none_or(|sock| sock.is_empty()) {
+                    return StrategyResult::Skipped("SSH_AUTH_SOCK is not set".to_string());
+                }
+                match Cred::ssh_key_from_agent(username) {
+                    Ok(cred) => StrategyResult::Success(cred),
+                    Err(err) => StrategyResult::Failure(sanitize_error_message(&err)),
+                }
+            }
             CredentialStrategy::SshKey(kind) => {
                 let default_path = match kind {
                     SshKeyKind::IdEd25519 => "~/.ssh/id_ed25519",
@@ -445,44 +500,59 @@ fn push_current_branch_impl(repo: &Repository, remote_name: &str) -> Result<(),
         .target()
         .ok_or_else(|| PushError::general("HEAD does not reference a commit"))?;
 
-    if let Ok(branch) = repo.find_branch(branch_name, BranchType::Local)
-        && let Ok(upstream) = branch.upstream()
-        && let Some(upstream_oid) = upstream.get().target()
-    {
-        let is_descendant = repo
-            .graph_descendant_of(head_oid, upstream_oid)
-            .map_err(|err| {
-                PushError::from_git("unable to compute fast-forward relationship", err)
-            })?;
-        if !is_descendant {
-            return Err(PushError::general(
-                "push would not be a fast-forward; fetch and merge first",
-            ));
-        }
-    }
+    push_branch_ref(repo, remote_name, branch_ref, branch_name, head_oid, false)
+}
 
-    let mut remote = repo
-        .find_remote(remote_name)
-        .map_err(|err| PushError::from_git("unable to locate remote", err))?;
-    let remote_url = remote
-        .pushurl()
-        .or_else(|| remote.url())
-        .ok_or_else(|| PushError::general("remote has no configured URL"))?
-        .to_string();
-    let remote_scheme = classify_remote_scheme(&remote_url);
+/// What the remote callbacks saw during one connection, kept so a failed push or fetch can say
+/// whether authentication, the host key, or the network was at fault.
+#[derive(Default)]
+pub(crate) struct TransportLog {
+    pub(crate) attempts: Vec<CredentialAttempt>,
+    /// Host named by the last certificate or host-key check.
+    pub(crate) host: Option<String>,
+}
 
-    let config_for_cb = repo.config().ok().map(Rc::new);
-    let credential_attempts: Rc<RefCell<Vec<CredentialAttempt>>> =
-        Rc::new(RefCell::new(Vec::new()));
-    let plan_config = config_for_cb.clone();
+/// Remote callbacks that authenticate the way the git CLI does: credential helpers, then
+/// ssh-agent, then the default key files. libgit2 calls back again whenever the remote rejects
+/// the credential it was given, so each call marks the previous credential as rejected and moves
+/// on to the next untried strategy instead of replaying it. Host keys and TLS certificates are
+/// left to libgit2's own checks against `known_hosts` and the system trust store.
+fn credential_callbacks<'a>(
+    repo: &Repository,
+    log: Rc<RefCell<TransportLog>>,
+) -> RemoteCallbacks<'a> {
+    let plan_config = repo.config().ok().map(Rc::new);
 
     let mut callbacks = RemoteCallbacks::new();
     callbacks.credentials({
-        let attempts = Rc::clone(&credential_attempts);
+        let log = Rc::clone(&log);
         move |url, username_from_url, allowed_types| {
+            let Ok(mut log) = log.try_borrow_mut() else {
+                return Err(Error::from_str("credential negotiation is already running"));
+            };
+            // Usernames are asked for before a key; only real credentials can be rejected.
+            if let Some(rejected) = log.attempts.iter_mut().rev().find(|attempt| {
+                attempt.outcome == AttemptOutcome::Success
+                    && attempt.strategy != CredentialStrategy::Username
+            }) {
+                rejected.outcome = AttemptOutcome::Failure("rejected by remote".to_string());
+            }
+
             let helper_config = plan_config.clone();
             let has_helper = helper_config.is_some();
-            let plan = build_credential_plan(allowed_types, has_helper);
+            let mut plan = build_credential_plan(allowed_types, has_helper);
+            plan.retain(|strategy| {
+                !log.attempts
+                    .iter()
+                    .any(|attempt| &attempt.strategy == strategy)
+            });
+            if plan.is_empty() {
+                return Err(Error::new(
+                    ErrorCode::Auth,
+                    ErrorClass::Callback,
+                    "every credential strategy was rejected",
+                ));
+            }
             let executor = RealCredentialExecutor::new(helper_config);
             let ctx = CredentialRequestContext {
                 url,
@@ -490,32 +560,146 @@ fn push_current_branch_impl(repo: &Repository, remote_name: &str) -> Result<(),
                 default_username: username_from_url.unwrap_or("git"),
             };
 
-            let result = execute_credential_plan(&plan, &executor, &ctx);
-            match result {
-                CredentialResult::Success {
-                    cred,
-                    attempts: log,
-                } => {
-                    if let Ok(mut store) = attempts.try_borrow_mut() {
-                        store.extend(log);
-                    }
+            match execute_credential_plan(&plan, &executor, &ctx) {
+                CredentialResult::Success { cred, attempts } => {
+                    log.attempts.extend(attempts);
                     Ok(cred)
                 }
                 CredentialResult::Failure {
-                    attempts: log,
+                    attempts,
                     final_message,
                 } => {
-                    if let Ok(mut store) = attempts.try_borrow_mut() {
-                        store.extend(log);
-                    }
-
+                    log.attempts.extend(attempts);
                     let msg = final_message
                         .unwrap_or_else(|| "no credential strategy succeeded".to_string());
-                    Err(Error::from_str(&msg))
+                    Err(Error::new(ErrorCode::Auth, ErrorClass::Callback, msg))
                 }
             }
         }
     });
+    callbacks.certificate_check(move |_cert, host| {
+        if let Ok(mut log) = log.try_borrow_mut() {
+            log.host = Some(host.to_string());
+        }
+        Ok(CertificateCheckStatus::CertificatePassthrough)
+    });
+    callbacks
+}
+
+/// Host part of a remote URL (`https://host/...`, `ssh://user@host:22/...`, `user@host:path`).
+pub(crate) fn url_host(url: &str) -> String {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let authority = rest.split('/').next().unwrap_or(rest);
+    let host = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    host.split(':').next().unwrap_or(host).to_string()
+}
+
+/// Whether `url` goes over the network; socket errors surface as OS errors, which local paths
+/// also produce.
+fn is_network_url(url: &str) -> bool {
+    match classify_remote_scheme(url) {
+        RemoteScheme::Ssh | RemoteScheme::Https => true,
+        RemoteScheme::Other(scheme) => !matches!(scheme.as_str(), "file" | "unknown"),
+    }
+}
+
+/// Sorts a failed push or fetch into an auth, host-key, or network error, falling back to a
+/// general error for local failures.
+pub(crate) fn transport_error(
+    context: &str,
+    remote_name: &str,
+    url: &str,
+    log: &TransportLog,
+    err: Error,
+) -> PushError {
+    let all_attempts_failed = !log.attempts.is_empty()
+        && log
+            .attempts
+            .iter()
+            .all(|attempt| !matches!(attempt.outcome, AttemptOutcome::Success));
+    if all_attempts_failed || err.code() == ErrorCode::Auth {
+        let mut attempts = log.attempts.clone();
+        if attempts.is_empty() {
+            attempts.push(CredentialAttempt {
+                strategy: CredentialStrategy::Default,
+                outcome: AttemptOutcome::Failure(sanitize_error_message(&err)),
+            });
+        }
+        return PushError::auth(
+            remote_name.to_string(),
+            url.to_string(),
+            classify_remote_scheme(url),
+            attempts,
+        );
+    }
+
+    let kind = if err.code() == ErrorCode::Certificate {
+        PushErrorKind::HostKey {
+            remote: remote_name.to_string(),
+            url: url.to_string(),
+            host: log.host.clone().unwrap_or_else(|| url_host(url)),
+            message: sanitize_error_message(&err),
+        }
+    } else if matches!(
+        err.class(),
+        ErrorClass::Net | ErrorClass::Http | ErrorClass::Ssl | ErrorClass::Ssh
+    ) || (err.class() == ErrorClass::Os && is_network_url(url))
+    {
+        PushErrorKind::Network {
+            remote: remote_name.to_string(),
+            url: url.to_string(),
+            message: sanitize_error_message(&err),
+        }
+    } else {
+        return PushError::from_git(context, err);
+    };
+    PushError {
+        kind,
+        source: Some(Box::new(err)),
+    }
+}
+
+/// Pushes `refs/heads/<branch_name>` (at `head_oid`) to the same ref on `remote_name`, with the
+/// fast-forward check and tracking-ref update described on `push_current_branch_impl`. `force`
+/// skips the fast-forward check and pushes with a `+` refspec.
+fn push_branch_ref(
+    repo: &Repository,
+    remote_name: &str,
+    branch_ref: &str,
+    branch_name: &str,
+    head_oid: git2::Oid,
+    force: bool,
+) -> Result<(), PushError> {
+    if !force
+        && let Ok(branch) = repo.find_branch(branch_name, BranchType::Local)
+        && let Ok(upstream) = branch.upstream()
+        && let Some(upstream_oid) = upstream.get().target()
+    {
+        let is_descendant = repo
+            .graph_descendant_of(head_oid, upstream_oid)
+            .map_err(|err| {
+                PushError::from_git("unable to compute fast-forward relationship", err)
+            })?;
+        if !is_descendant {
+            return Err(PushError::general(
+                "push would not be a fast-forward; fetch and merge first",
+            ));
+        }
+    }
+
+    let mut remote = repo
+        .find_remote(remote_name)
+        .map_err(|err| PushError::from_git("unable to locate remote", err))?;
+    let remote_url = remote
+        .pushurl()
+        .or_else(|| remote.url())
+        .ok_or_else(|| PushError::general("remote has no configured URL"))?
+        .to_string();
+
+    let transport_log = Rc::new(RefCell::new(TransportLog::default()));
+    let mut callbacks = credential_callbacks(repo, Rc::clone(&transport_log));
 
     let push_statuses: Rc<RefCell<Vec<(String, String)>>> = Rc::new(RefCell::new(Vec::new()));
     let statuses_for_cb = Rc::clone(&push_statuses);
@@ -531,25 +715,16 @@ fn push_current_branch_impl(repo: &Repository, remote_name: &str) -> Result<(),
     let mut push_opts = PushOptions::new();
     push_opts.remote_callbacks(callbacks);
 
-    let refspec = format!("{branch_ref}:{branch_ref}");
+    let refspec = format!("{}{branch_ref}:{branch_ref}", if force { "+" } else { "" });
     let refspecs = [refspec.as_str()];
     if let Err(err) = remote.push(&refspecs, Some(&mut push_opts)) {
-        let attempts = credential_attempts.borrow().clone();
-        let all_attempts_failed = !attempts.is_empty()
-            && attempts
-                .iter()
-                .all(|attempt| !matches!(attempt.outcome, AttemptOutcome::Success));
-
-        if all_attempts_failed {
-            return Err(PushError::auth(
-                remote_name.to_string(),
-                remote_url,
-                remote_scheme,
-                attempts,
-            ));
-        } else {
-            return Err(PushError::from_git("failed to push to remote", err));
-        }
+        return Err(transport_error(
+            "failed to push to remote",
+            remote_name,
+            &remote_url,
+            &transport_log.borrow(),
+            err,
+        ));
     }
     remote
         .disconnect()
@@ -582,6 +757,161 @@ pub fn push_current_branch(remote_name: &str) -> Result<(), PushError> {
     push_current_branch_impl(&repo, remote_name)
 }
 
+/// Push a named local branch to `remote_name` without checking it out.
+pub fn push_branch_in<P: AsRef<std::path::Path>>(
+    repo_path: P,
+    remote_name: &str,
+    branch_name: &str,
+) -> Result<(), PushError> {
+    let repo = Repository::discover(repo_path)
+        .map_err(|err| PushError::from_git("failed to discover git repository", err))?;
+    let branch = repo
+        .find_branch(branch_name, BranchType::Local)
+        .map_err(|err| PushError::from_git("unable to locate local branch", err))?;
+    let branch_ref = branch
+        .get()
+        .name()
+        .ok_or_else(|| PushError::general("branch name is not valid UTF-8"))?
+        .to_string();
+    let oid = branch
+        .get()
+        .target()
+        .ok_or_else(|| PushError::general("branch does not reference a commit"))?;
+    push_branch_ref(&repo, remote_name, &branch_ref, branch_name, oid, false)
+}
+
+/// How [`push_branch_with_lease_in`] updated the remote branch.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LeasePush {
+    /// The remote had no such branch.
+    Created,
+    /// The remote tip was an ancestor of the local branch.
+    FastForward,
+    /// The local branch was rewritten (an amend or rebase) and the remote still held the tip
+    /// Vizier last pushed or fetched, so it was overwritten.
+    Forced,
+    /// The remote already held the local tip.
+    UpToDate,
+}
+
+/// Pushes `branch_name` like `git push --force-with-lease`: the lease is the remote-tracking
+/// ref `refs/remotes/<remote>/<branch>`, so a rewritten local branch replaces the remote copy
+/// only while the remote still points where Vizier last saw it. When someone else moved the
+/// remote copy and the local branch does not contain their commits, nothing is pushed and the
+/// error names both tips.
+pub fn push_branch_with_lease_in<P: AsRef<std::path::Path>>(
+    repo_path: P,
+    remote_name: &str,
+    branch_name: &str,
+) -> Result<LeasePush, PushError> {
+    let repo = Repository::discover(repo_path)
+        .map_err(|err| PushError::from_git("failed to discover git repository", err))?;
+    let branch_ref = format!("refs/heads/{branch_name}");
+    let local = repo
+        .refname_to_id(&branch_ref)
+        .map_err(|err| PushError::from_git("unable to locate local branch", err))?;
+    let lease = repo
+        .refname_to_id(&format!("refs/remotes/{remote_name}/{branch_name}"))
+        .ok();
+
+    // Fetch the remote tip into a scratch ref so the tracking ref keeps holding the lease.
+    let scratch_ref = format!("refs/vizier/lease/{remote_name}/{branch_name}");
+    if let Ok(mut stale) = repo.find_reference(&scratch_ref) {
+        let _ = stale.delete();
+    }
+    let mut remote = repo
+        .find_remote(remote_name)
+        .map_err(|err| PushError::from_git("unable to locate remote", err))?;
+    let remote_url = remote
+        .url()
+        .ok_or_else(|| PushError::general("remote has no configured URL"))?
+        .to_string();
+    let transport_log = Rc::new(RefCell::new(TransportLog::default()));
+    let mut fetch_opts = FetchOptions::new();
+    fetch_opts.remote_callbacks(credential_callbacks(&repo, Rc::clone(&transport_log)));
+    remote
+        .fetch(
+            &[format!("+{branch_ref}:{scratch_ref}").as_str()],
+            Some(&mut fetch_opts),
+            None,
+        )
+        .map_err(|err| {
+            transport_error(
+                "failed to fetch from remote",
+                remote_name,
+                &remote_url,
+                &transport_log.borrow(),
+                err,
+            )
+        })?;
+    let actual = match repo.find_reference(&scratch_ref) {
+        Ok(mut reference) => {
+            let oid = reference.target();
+            let _ = reference.delete();
+            oid
+        }
+        Err(_) => None,
+    };
+
+    let (outcome, force) = match actual {
+        None => (LeasePush::Created, false),
+        Some(actual) if actual == local => return Ok(LeasePush::UpToDate),
+        Some(actual) if repo.graph_descendant_of(local, actual).unwrap_or(false) => {
+            (LeasePush::FastForward, false)
+        }
+        Some(actual) if Some(actual) == lease => (LeasePush::Forced, true),
+        Some(actual) => {
+            let short = |oid: git2::Oid| oid.to_string().chars().take(7).collect::<String>();
+            return Err(PushError::general(format!(
+                "{remote_name}/{branch_name} moved to {} since Vizier last saw it{}; fetch and reconcile before pushing",
+                short(actual),
+                lease
+                    .map(|lease| format!(" at {}", short(lease)))
+                    .unwrap_or_default()
+            )));
+        }
+    };
+    push_branch_ref(&repo, remote_name, &branch_ref, branch_name, local, force)?;
+    Ok(outcome)
+}
+
+/// Fetch `branch_name` from `remote_name` into `refs/remotes/<remote>/<branch>` and return the
+/// fetched commit. Local branches are left alone.
+pub fn fetch_branch_in<P: AsRef<std::path::Path>>(
+    repo_path: P,
+    remote_name: &str,
+    branch_name: &str,
+) -> Result<git2::Oid, PushError> {
+    let repo = Repository::discover(repo_path)
+        .map_err(|err| PushError::from_git("failed to discover git repository", err))?;
+    let mut remote = repo
+        .find_remote(remote_name)
+        .map_err(|err| PushError::from_git("unable to locate remote", err))?;
+    let remote_url = remote
+        .url()
+        .ok_or_else(|| PushError::general("remote has no configured URL"))?
+        .to_string();
+
+    let transport_log = Rc::new(RefCell::new(TransportLog::default()));
+    let mut fetch_opts = FetchOptions::new();
+    fetch_opts.remote_callbacks(credential_callbacks(&repo, Rc::clone(&transport_log)));
+
+    let tracking_ref = format!("refs/remotes/{remote_name}/{branch_name}");
+    let refspec = format!("+refs/heads/{branch_name}:{tracking_ref}");
+    if let Err(err) = remote.fetch(&[refspec.as_str()], Some(&mut fetch_opts), None) {
+        return Err(transport_error(
+            "failed to fetch from remote",
+            remote_name,
+            &remote_url,
+            &transport_log.borrow(),
+            err,
+        ));
+    }
+
+    repo.refname_to_id(&tracking_ref)
+        .map_err(|err| PushError::from_git("fetched branch is missing", err))
+}
+
 pub fn push_current_branch_in<P: AsRef<std::path::Path>>(
     repo_path: P,
     remote_name: &str,
@@ -591,6 +921,22 @@ pub fn push_current_branch_in<P: AsRef<std::path::Path>>(
     push_current_branch_impl(&repo, remote_name)
 }
 
+/// The fetch URL configured for `remote`, if the remote exists.
+pub fn remote_url_in<P: AsRef<Path>>(repo_path: P, remote: &str) -> Option<String> {
+    let repo = Repository::discover(repo_path).ok()?;
+    let remote = repo.find_remote(remote).ok()?;
+    remote.url().map(str::to_string)
+}
+
+/// Browser URL that opens a pull request from `branch` into `target`, when `origin` is a
+/// GitHub remote.
+pub fn pull_request_url(repo_path: &str, target: &str, branch: &str) -> Option<String> {
+    let (owner, repo) = origin_owner_repo(repo_path).ok()?;
+    Some(format!(
+        "https://github.com/{owner}/{repo}/compare/{target}...{branch}?expand=1"
+    ))
+}
+
 /// Extract (owner, repo) from `origin`
 pub fn origin_owner_repo(repo_path: &str) -> Result<(String, String), Error> {
     let repo = Repository::discover(repo_path)?;
@@ -1,8 +1,9 @@
 use git2::{
-    ApplyLocation, BranchType, Diff, DiffDelta, DiffFindOptions, DiffFormat, DiffLine, DiffOptions,
-    DiffStatsFormat, Error, ErrorCode, Oid, Repository, RepositoryState, Status, StatusEntry,
-    StatusOptions, StatusShow, Tree,
+    ApplyLocation, BranchType, Diff, DiffFindOptions, DiffFormat, DiffLine, DiffOptions,
+    DiffStatsFormat, Error, ErrorCode, Oid, Patch, Repository, RepositoryState, Status,
+    StatusEntry, StatusOptions, StatusShow, Tree,
 };
+use serde::Serialize;
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -16,17 +17,22 @@ fn append_patch_line(buf: &mut Vec<u8>, line: DiffLine<'_>) {
     buf.extend_from_slice(line.content());
 }
 
-fn configure_diff_options(pathspec: Option<&str>) -> DiffOptions {
-    configure_diff_options_with_binary(pathspec, false)
-}
-
-fn configure_diff_options_with_binary(pathspec: Option<&str>, show_binary: bool) -> DiffOptions {
+fn configure_diff_options(
+    pathspec: Option<&str>,
+    show_binary: bool,
+    max_file_bytes: Option<u64>,
+) -> DiffOptions {
     let mut opts = DiffOptions::new();
 
     opts.ignore_submodules(true)
         .id_abbrev(40)
         .show_binary(show_binary);
 
+    // libgit2 treats blobs over `max_size` as binary without loading them.
+    if let Some(max) = max_file_bytes {
+        opts.max_size(i64::try_from(max).unwrap_or(i64::MAX));
+    }
+
     if let Some(spec) = pathspec {
         opts.pathspec(spec);
     }
@@ -38,23 +44,17 @@ fn diff_tree_to_workdir_tolerant<'repo>(
     repo: &'repo Repository,
     base: Option<&Tree<'repo>>,
     pathspec: Option<&str>,
-) -> Result<Diff<'repo>, Error> {
-    diff_tree_to_workdir_tolerant_with_binary(repo, base, pathspec, false)
-}
-
-fn diff_tree_to_workdir_tolerant_with_binary<'repo>(
-    repo: &'repo Repository,
-    base: Option<&Tree<'repo>>,
-    pathspec: Option<&str>,
     show_binary: bool,
+    max_file_bytes: Option<u64>,
 ) -> Result<Diff<'repo>, Error> {
-    let mut opts = configure_diff_options_with_binary(pathspec, show_binary);
+    let options = || configure_diff_options(pathspec, show_binary, max_file_bytes);
+    let mut opts = options();
 
     match repo.diff_tree_to_workdir_with_index(base, Some(&mut opts)) {
         Ok(diff) => Ok(diff),
         Err(err) if err.code() == ErrorCode::NotFound => {
-            let mut staged_opts = configure_diff_options_with_binary(pathspec, show_binary);
-            let mut workdir_opts = configure_diff_options_with_binary(pathspec, show_binary);
+            let mut staged_opts = options();
+            let mut workdir_opts = options();
 
             let index = repo.index()?;
             let mut staged_diff =
@@ -68,6 +68,38 @@ fn diff_tree_to_workdir_tolerant_with_binary<'repo>(
     }
 }
 
+/// Size caps applied per file by [`get_diff`] and [`for_each_file_patch`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiffLimits {
+    /// Blobs larger than this are not read; the file gets a placeholder instead of a patch.
+    pub max_file_bytes: u64,
+    /// A file's patch text is cut off after this many bytes.
+    pub max_patch_bytes: usize,
+}
+
+impl Default for DiffLimits {
+    fn default() -> Self {
+        Self {
+            max_file_bytes: 8 * 1024 * 1024,
+            max_patch_bytes: 512 * 1024,
+        }
+    }
+}
+
+/// One file's slice of a diff, produced by [`for_each_file_patch`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilePatch {
+    pub path: String,
+    /// `diff --git` header plus hunks, or the header plus a placeholder line for binary and
+    /// oversized files.
+    pub patch: String,
+    pub binary: bool,
+    /// The file exceeded [`DiffLimits::max_file_bytes`] and its contents were not read.
+    pub oversized: bool,
+    /// The patch was cut off at [`DiffLimits::max_patch_bytes`].
+    pub truncated: bool,
+}
+
 /// Return a unified diff (`git diff`-style patch) for the repository at `repo_path`,
 /// formatted newest → oldest changes where applicable.
 ///
@@ -77,6 +109,8 @@ fn diff_tree_to_workdir_tolerant_with_binary<'repo>(
 /// - If `target` is `<from>..<to>`, compare commit `<from>` to `<to>`.
 /// - If `target` does not resolve to a rev, treat it as a path and restrict the diff there.
 /// - If `exclude` is given, exclude those pathspecs (normalized) from the diff.
+///
+/// Binary files and files over the default [`DiffLimits`] appear as placeholder lines.
 pub fn get_diff(
     repo_path: &str,
     target: Option<&str>, // commit/range or directory path
@@ -84,9 +118,31 @@ pub fn get_diff(
     //       std::path::Pathbuf-convertable strings
     exclude: Option<&[&str]>,
 ) -> Result<String, Error> {
-    let repo = Repository::open(repo_path)?;
+    get_diff_with_limits(repo_path, target, exclude, DiffLimits::default())
+}
 
-    let diff = match target {
+/// [`get_diff`] with explicit per-file size caps.
+pub fn get_diff_with_limits(
+    repo_path: &str,
+    target: Option<&str>,
+    exclude: Option<&[&str]>,
+    limits: DiffLimits,
+) -> Result<String, Error> {
+    let mut out = String::new();
+    for_each_file_patch(repo_path, target, exclude, limits, |file| {
+        out.push_str(&file.patch);
+        true
+    })?;
+    Ok(out)
+}
+
+fn resolve_diff<'repo>(
+    repo: &'repo Repository,
+    target: Option<&str>,
+    max_file_bytes: u64,
+) -> Result<Diff<'repo>, Error> {
+    let max = Some(max_file_bytes);
+    match target {
         Some(spec) if spec.contains("..") => {
             let parts: Vec<_> = spec.split("..").collect();
             if parts.len() != 2 {
@@ -96,15 +152,15 @@ pub fn get_diff(
             let from = repo.revparse_single(parts[0])?.peel_to_tree()?;
             let to = repo.revparse_single(parts[1])?.peel_to_tree()?;
 
-            let mut opts = configure_diff_options(None);
-            repo.diff_tree_to_tree(Some(&from), Some(&to), Some(&mut opts))?
+            let mut opts = configure_diff_options(None, false, max);
+            repo.diff_tree_to_tree(Some(&from), Some(&to), Some(&mut opts))
         }
         Some(spec) => {
             // Try as rev first
             match repo.revparse_single(spec) {
                 Ok(obj) => {
                     let base = obj.peel_to_tree()?;
-                    diff_tree_to_workdir_tolerant(&repo, Some(&base), None)?
+                    diff_tree_to_workdir_tolerant(repo, Some(&base), None, false, max)
                 }
                 Err(_) => {
                     // Treat as a directory/file path
@@ -112,10 +168,12 @@ pub fn get_diff(
                     let head_tree = repo.head().ok().and_then(|h| h.peel_to_tree().ok());
 
                     diff_tree_to_workdir_tolerant(
-                        &repo,
+                        repo,
                         head_tree.as_ref(),
                         Some(normalized.as_str()),
-                    )?
+                        false,
+                        max,
+                    )
                 }
             }
         }
@@ -123,51 +181,123 @@ pub fn get_diff(
             // HEAD vs working dir (with index); handle unborn HEAD
             let head_tree = repo.head().ok().and_then(|h| h.peel_to_tree().ok());
 
-            diff_tree_to_workdir_tolerant(&repo, head_tree.as_ref(), None)?
+            diff_tree_to_workdir_tolerant(repo, head_tree.as_ref(), None, false, max)
         }
-    };
+    }
+}
+
+/// Streams the diff [`get_diff`] would build one file at a time, so callers can stop early
+/// and never hold more than one file's patch. `visit` returns `false` to stop.
+pub fn for_each_file_patch<F>(
+    repo_path: &str,
+    target: Option<&str>,
+    exclude: Option<&[&str]>,
+    limits: DiffLimits,
+    mut visit: F,
+) -> Result<(), Error>
+where
+    F: FnMut(FilePatch) -> bool,
+{
+    let repo = Repository::open(repo_path)?;
+    let diff = resolve_diff(&repo, target, limits.max_file_bytes)?;
 
     // Excluding files from the diff with our exclude vector
     // Originally tried adding things to the pathspec, but libgit2 didn't appreciate that and
     // instead decided to ignore all possible paths when putting together the diff.
     // So, we're left with this hack.
-    let mut buf = Vec::new();
-    let exclude = if let Some(e) = exclude {
-        e.iter().map(|p| p.to_string()).collect()
-    } else {
-        Vec::new()
-    };
+    let exclude = exclude.unwrap_or_default();
+
+    for (index, delta) in diff.deltas().enumerate() {
+        let Some(path) = delta
+            .new_file()
+            .path()
+            .or_else(|| delta.old_file().path())
+            .and_then(|p| p.to_str())
+            .map(str::to_string)
+        else {
+            continue;
+        };
+        if exclude
+            .iter()
+            .any(|excluded| Path::new(&path).starts_with(Path::new(excluded)))
+        {
+            continue;
+        }
 
-    diff.print(
-        DiffFormat::Patch,
-        |delta: DiffDelta<'_>, _, line: DiffLine<'_>| {
-            let file_path = delta
-                .new_file()
-                .path()
-                .or_else(|| delta.old_file().path())
-                .and_then(|p| p.to_str());
-
-            if let Some(path) = file_path {
-                let diff_path = std::path::Path::new(path);
-                if !exclude.iter().any(|excluded| {
-                    let exclude_path = std::path::Path::new(excluded);
-
-                    diff_path.starts_with(exclude_path)
-                }) {
+        let Some(mut patch) = Patch::from_diff(&diff, index)? else {
+            continue;
+        };
+        let loaded = patch.delta();
+        let binary = loaded.flags().is_binary();
+        let oversized = binary
+            && (loaded.old_file().size() > limits.max_file_bytes
+                || loaded.new_file().size() > limits.max_file_bytes);
+
+        let mut buf = Vec::new();
+        let mut truncated = false;
+        patch.print(&mut |_, _, line| {
+            match line.origin() {
+                // File headers always go through; hunks stop at the byte cap.
+                'F' => append_patch_line(&mut buf, line),
+                _ if binary => {}
+                _ if truncated => {}
+                _ => {
                     append_patch_line(&mut buf, line);
+                    if buf.len() > limits.max_patch_bytes {
+                        truncated = true;
+                    }
                 }
             }
             true
-        },
-    )?;
+        })?;
+
+        let old_path = delta
+            .old_file()
+            .path()
+            .and_then(|p| p.to_str())
+            .unwrap_or(&path)
+            .to_string();
+        let mut text = String::from_utf8_lossy(&buf).into_owned();
+        if truncated {
+            let mut cut = limits.max_patch_bytes.min(text.len());
+            while !text.is_char_boundary(cut) {
+                cut -= 1;
+            }
+            text.truncate(cut);
+            if !text.ends_with('\n') {
+                text.push('\n');
+            }
+            text.push_str(&format!(
+                "[diff for {path} truncated after {} bytes]\n",
+                limits.max_patch_bytes
+            ));
+        } else if oversized {
+            text.push_str(&format!(
+                "Binary files a/{old_path} and b/{path} differ (over {} bytes; contents not read)\n",
+                limits.max_file_bytes
+            ));
+        } else if binary {
+            text.push_str(&format!("Binary files a/{old_path} and b/{path} differ\n"));
+        }
 
-    Ok(String::from_utf8_lossy(&buf).into_owned())
+        let keep_going = visit(FilePatch {
+            path,
+            patch: text,
+            binary,
+            oversized,
+            truncated,
+        });
+        if !keep_going {
+            break;
+        }
+    }
+    Ok(())
 }
 
 pub fn diff_binary_against_head_in<P: AsRef<Path>>(repo_path: P) -> Result<Vec<u8>, Error> {
     let repo = Repository::open(repo_path)?;
     let head_tree = repo.head().ok().and_then(|h| h.peel_to_tree().ok());
-    let diff = diff_tree_to_workdir_tolerant_with_binary(&repo, head_tree.as_ref(), None, true)?;
+    let diff = diff_tree_to_workdir_tolerant(&repo, head_tree.as_ref(), None, true, None)?;
 
     let mut patch = Vec::new();
     diff.print(DiffFormat::Patch, |_, _, line| {
@@ -368,20 +498,170 @@ pub fn status_with_branch<P: AsRef<Path>>(repo_path: P) -> Result<String, Error>
     Ok(lines.join("\n"))
 }
 
-#[derive(Debug, Clone)]
-pub struct DiffSummary {
-    pub stats: String,
-    pub name_status: String,
+/// How a file changed between two trees, as reported by `git diff --name-status`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffFileStatus {
+    Added,
+    Copied,
+    Deleted,
+    Modified,
+    Renamed,
+    Typechange,
+    Conflicted,
 }
 
-/// Compute diff stats and name-status between `target...HEAD`, mirroring `git diff --stat` /
-/// `--name-status` while relying solely on libgit2.
-pub fn diff_summary_against_target<P: AsRef<Path>>(
-    repo_path: P,
+impl DiffFileStatus {
+    fn from_delta(delta: git2::Delta) -> Option<Self> {
+        use git2::Delta::*;
+        Some(match delta {
+            Added | Untracked => Self::Added,
+            Copied => Self::Copied,
+            Deleted => Self::Deleted,
+            Modified => Self::Modified,
+            Renamed => Self::Renamed,
+            Typechange => Self::Typechange,
+            Conflicted => Self::Conflicted,
+            Unmodified | Ignored | Unreadable => return None,
+        })
+    }
+
+    /// Single-letter `--name-status` code.
+    pub fn code(self) -> char {
+        match self {
+            Self::Added => 'A',
+            Self::Copied => 'C',
+            Self::Deleted => 'D',
+            Self::Modified => 'M',
+            Self::Renamed => 'R',
+            Self::Typechange => 'T',
+            Self::Conflicted => 'U',
+        }
+    }
+}
+
+/// Per-file line counts for one changed path. Binary files report zero lines.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DiffFileStat {
+    pub path: String,
+    /// Source path of a rename or copy.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_path: Option<String>,
+    pub status: DiffFileStatus,
+    pub insertions: usize,
+    pub deletions: usize,
+    pub binary: bool,
+}
+
+/// Structured `git diff --numstat` / `--name-status` data for a range, with renames detected.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DiffStats {
+    /// Changed files sorted by path.
+    pub files: Vec<DiffFileStat>,
+}
+
+impl DiffStats {
+    pub fn insertions(&self) -> usize {
+        self.files.iter().map(|file| file.insertions).sum()
+    }
+
+    pub fn deletions(&self) -> usize {
+        self.files.iter().map(|file| file.deletions).sum()
+    }
+
+    pub fn renames(&self) -> usize {
+        self.files
+            .iter()
+            .filter(|file| file.status == DiffFileStatus::Renamed)
+            .count()
+    }
+
+    pub fn binary_files(&self) -> usize {
+        self.files.iter().filter(|file| file.binary).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// One-line summary such as `3 files changed, +12 -4, 1 renamed, 1 binary`.
+    pub fn summary_line(&self) -> String {
+        let files = self.files.len();
+        let mut line = format!(
+            "{files} {} changed, +{} -{}",
+            if files == 1 { "file" } else { "files" },
+            self.insertions(),
+            self.deletions()
+        );
+        if self.renames() > 0 {
+            line.push_str(&format!(", {} renamed", self.renames()));
+        }
+        if self.binary_files() > 0 {
+            line.push_str(&format!(", {} binary", self.binary_files()));
+        }
+        line
+    }
+
+    /// `git diff --name-status` lines (`R\told\tnew` for renames and copies).
+    pub fn name_status(&self) -> String {
+        self.files
+            .iter()
+            .map(|file| match file.old_path.as_deref() {
+                Some(old) => format!("{}\t{old}\t{}", file.status.code(), file.path),
+                None => format!("{}\t{}", file.status.code(), file.path),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+fn collect_diff_stats(diff: &Diff<'_>) -> Result<DiffStats, Error> {
+    let mut files = Vec::new();
+    for (index, delta) in diff.deltas().enumerate() {
+        let Some(status) = DiffFileStatus::from_delta(delta.status()) else {
+            continue;
+        };
+        let path_of = |file: git2::DiffFile<'_>| {
+            file.path()
+                .map(|path| path.to_string_lossy().replace('\\', "/"))
+        };
+        let old_path = path_of(delta.old_file()).unwrap_or_default();
+        let path = path_of(delta.new_file()).unwrap_or_else(|| old_path.clone());
+        // Loading the patch fills in the binary flag libgit2 leaves unset on bare deltas.
+        let patch = Patch::from_diff(diff, index)?;
+        let binary = patch
+            .as_ref()
+            .map(|patch| patch.delta().flags().is_binary())
+            .unwrap_or_else(|| delta.flags().is_binary());
+        let (insertions, deletions) = match patch.as_ref() {
+            Some(patch) if !binary => {
+                let (_, insertions, deletions) = patch.line_stats()?;
+                (insertions, deletions)
+            }
+            _ => (0, 0),
+        };
+        files.push(DiffFileStat {
+            old_path: matches!(status, DiffFileStatus::Renamed | DiffFileStatus::Copied)
+                .then_some(old_path),
+            path,
+            status,
+            insertions,
+            deletions,
+            binary,
+        });
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(DiffStats { files })
+}
+
+/// Diff between the merge base of `target` and `head` and `head` (`target...head`), with
+/// rename detection.
+fn diff_against_merge_base<'repo>(
+    repo: &'repo Repository,
     target: &str,
-) -> Result<DiffSummary, Error> {
-    let repo = Repository::discover(repo_path)?;
-    let head = repo.head()?.peel_to_commit()?;
+    head: &str,
+) -> Result<Diff<'repo>, Error> {
+    let head = repo.revparse_single(head)?.peel_to_commit()?;
     let target_commit = repo.revparse_single(target)?.peel_to_commit()?;
     let base_oid = repo.merge_base(target_commit.id(), head.id())?;
     let base_tree = repo.find_commit(base_oid)?.tree()?;
@@ -392,63 +672,102 @@ pub fn diff_summary_against_target<P: AsRef<Path>>(
 
     let mut diff = repo.diff_tree_to_tree(Some(&base_tree), Some(&head_tree), Some(&mut opts))?;
     let mut find_opts = DiffFindOptions::new();
+    find_opts.renames(true);
     diff.find_similar(Some(&mut find_opts))?;
+    Ok(diff)
+}
 
-    let stats_buf = diff.stats()?.to_buf(DiffStatsFormat::FULL, 80)?;
-    let stats = String::from_utf8_lossy(stats_buf.as_ref())
-        .trim_end()
-        .to_string();
+/// Typed per-file stats for `target...head`, where `head` is any revision.
+pub fn diff_stats_between<P: AsRef<Path>>(
+    repo_path: P,
+    target: &str,
+    head: &str,
+) -> Result<DiffStats, Error> {
+    let repo = Repository::discover(repo_path)?;
+    let diff = diff_against_merge_base(&repo, target, head)?;
+    collect_diff_stats(&diff)
+}
 
-    let mut entries = Vec::new();
-    for delta in diff.deltas() {
-        use git2::Delta::*;
-        let code = match delta.status() {
-            Added => "A",
-            Copied => "C",
-            Deleted => "D",
-            Modified => "M",
-            Renamed => "R",
-            Typechange => "T",
-            Conflicted => "U",
-            Untracked => "A",
-            Unmodified | Ignored | Unreadable => continue,
-        };
+/// Unified patch for the same `target...head` range [`diff_stats_between`] counts.
+pub fn diff_patch_between<P: AsRef<Path>>(
+    repo_path: P,
+    target: &str,
+    head: &str,
+) -> Result<String, Error> {
+    let repo = Repository::discover(repo_path)?;
+    let diff = diff_against_merge_base(&repo, target, head)?;
+    let mut patch = Vec::new();
+    diff.print(DiffFormat::Patch, |_, _, line| {
+        append_patch_line(&mut patch, line);
+        true
+    })?;
+    Ok(String::from_utf8_lossy(&patch).into_owned())
+}
 
-        let old_path = delta
-            .old_file()
-            .path()
-            .and_then(|p| p.to_str())
-            .unwrap_or_default();
-        let new_path = delta
-            .new_file()
-            .path()
-            .and_then(|p| p.to_str())
-            .unwrap_or(old_path);
+#[derive(Debug, Clone)]
+pub struct DiffSummary {
+    pub stats: String,
+    pub name_status: String,
+    pub files: DiffStats,
+}
 
-        let line = match delta.status() {
-            Renamed | Copied => format!("{code}\t{old_path}\t{new_path}"),
-            _ => format!("{code}\t{new_path}"),
-        };
-        entries.push(line);
-    }
+/// Compute diff stats and name-status between `target...HEAD`, mirroring `git diff --stat` /
+/// `--name-status` while relying solely on libgit2.
+pub fn diff_summary_against_target<P: AsRef<Path>>(
+    repo_path: P,
+    target: &str,
+) -> Result<DiffSummary, Error> {
+    diff_summary_between(repo_path, target, "HEAD")
+}
 
-    entries.sort();
+/// Like [`diff_summary_against_target`] for `target...head`, where `head` is any revision.
+pub fn diff_summary_between<P: AsRef<Path>>(
+    repo_path: P,
+    target: &str,
+    head: &str,
+) -> Result<DiffSummary, Error> {
+    let repo = Repository::discover(repo_path)?;
+    let diff = diff_against_merge_base(&repo, target, head)?;
+
+    let stats_buf = diff.stats()?.to_buf(DiffStatsFormat::FULL, 80)?;
+    let stats = String::from_utf8_lossy(stats_buf.as_ref())
+        .trim_end()
+        .to_string();
+    let files = collect_diff_stats(&diff)?;
 
     Ok(DiffSummary {
         stats,
-        name_status: entries.join("\n").trim().to_string(),
+        name_status: files.name_status(),
+        files,
     })
 }
 
+/// The working tree root, or the git directory of a bare repository (where Vizier keeps its
+/// `.vizier/` state when it runs server-side).
 pub fn repo_root() -> Result<PathBuf, Error> {
     let repo = Repository::discover(".")?;
     repo.workdir()
+        .or_else(|| repo.is_bare().then(|| repo.path()))
         .map(|dir| dir.to_path_buf())
         .ok_or_else(|| Error::from_str("repository has no working directory"))
 }
 
+/// Whether `path` is the git directory of a bare repository.
+pub fn is_bare_repo<P: AsRef<Path>>(path: P) -> bool {
+    let path = path.as_ref();
+    // Cheap layout check first so walking up from a checkout never opens arbitrary directories.
+    path.join("HEAD").is_file()
+        && path.join("objects").is_dir()
+        && path.join("refs").is_dir()
+        && Repository::open(path).is_ok_and(|repo| repo.is_bare())
+}
+
 pub fn ensure_clean_worktree() -> Result<(), Error> {
-    let repo = Repository::discover(".")?;
+    ensure_clean_worktree_in(".")
+}
+
+pub fn ensure_clean_worktree_in<P: AsRef<Path>>(repo_path: P) -> Result<(), Error> {
+    let repo = Repository::discover(repo_path)?;
     let mut opts = StatusOptions::new();
     opts.include_untracked(true)
         .recurse_untracked_dirs(true)
@@ -470,6 +789,64 @@ pub fn ensure_clean_worktree() -> Result<(), Error> {
     }
 }
 
+/// Drop every uncommitted change in the worktree at `repo_path`, mirroring
+/// `git reset --hard HEAD && git clean -fd` (ignored files are left alone).
+///
+/// Returns the number of status entries that were discarded.
+pub fn discard_worktree_changes_in<P: AsRef<Path>>(repo_path: P) -> Result<usize, Error> {
+    let repo = Repository::open(repo_path)?;
+    let workdir = repo
+        .workdir()
+        .map(Path::to_path_buf)
+        .ok_or_else(|| Error::from_str("repository has no working directory"))?;
+
+    let mut opts = StatusOptions::new();
+    opts.include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .include_ignored(false)
+        .exclude_submodules(true);
+    let statuses = repo.statuses(Some(&mut opts))?;
+    let discarded = statuses.len();
+    let untracked = statuses
+        .iter()
+        .filter(|entry| entry.status() == Status::WT_NEW)
+        .filter_map(|entry| entry.path().map(|path| workdir.join(path)))
+        .collect::<Vec<_>>();
+    drop(statuses);
+
+    let head = repo.head()?.peel(git2::ObjectType::Commit)?;
+    repo.reset(&head, git2::ResetType::Hard, None)?;
+
+    for path in untracked {
+        let removed = if path.is_dir() {
+            fs::remove_dir_all(&path)
+        } else {
+            fs::remove_file(&path)
+        };
+        if let Err(err) = removed
+            && err.kind() != std::io::ErrorKind::NotFound
+        {
+            return Err(Error::from_str(&format!(
+                "failed to remove untracked path {}: {err}",
+                path.display()
+            )));
+        }
+        prune_empty_parents(&workdir, &path);
+    }
+
+    Ok(discarded)
+}
+
+fn prune_empty_parents(workdir: &Path, path: &Path) {
+    let mut current = path.parent();
+    while let Some(dir) = current {
+        if dir == workdir || !dir.starts_with(workdir) || fs::remove_dir(dir).is_err() {
+            break;
+        }
+        current = dir.parent();
+    }
+}
+
 fn is_ephemeral_vizier_path(path: &str) -> bool {
     const EPHEMERAL_PREFIXES: [&str; 4] = [
         ".vizier/jobs",
@@ -1,6 +1,6 @@
 use super::remotes::{
-    CredentialExecutor, CredentialRequestContext, CredentialResult, StrategyResult,
-    build_credential_plan, execute_credential_plan,
+    CredentialExecutor, CredentialRequestContext, CredentialResult, StrategyResult, TransportLog,
+    build_credential_plan, execute_credential_plan, transport_error, url_host,
 };
 use super::*;
 use git2::{Cred, CredentialType, IndexAddOption, Oid, Repository, RepositoryState, Signature};
@@ -95,6 +95,169 @@ fn raw_commit(repo: &Repository, msg: &str) -> Oid {
         .unwrap()
 }
 
+#[test]
+fn rebase_and_fast_forward_replays_plan_commits_linearly_with_trailers() {
+    let repo = TestRepo::new();
+    repo.write("README.md", "base\n");
+    raw_commit(repo.repo(), "base");
+    let base = repo.repo().head().unwrap().peel_to_commit().unwrap();
+    let target = repo.repo().head().unwrap().shorthand().unwrap().to_string();
+    repo.repo().branch("draft/alpha", &base, false).unwrap();
+
+    repo.write("main.txt", "target work\n");
+    let target_tip = raw_commit(repo.repo(), "target work");
+
+    checkout_branch_in(repo.path(), "draft/alpha").unwrap();
+    repo.write("a.txt", "one\n");
+    raw_commit(repo.repo(), "feat: add a\n\nBody text.");
+    repo.write("b.txt", "two\n");
+    raw_commit(repo.repo(), "feat: add b\n\nRefs: #12");
+    checkout_branch_in(repo.path(), &target).unwrap();
+
+    let outcome = rebase_and_fast_forward_in(
+        repo.path(),
+        "draft/alpha",
+        &["Vizier-Plan: alpha".to_string()],
+    )
+    .expect("rebase merge");
+    let RebaseMergeOutcome::Landed(landed) = outcome else {
+        panic!("unexpected conflict: {outcome:?}");
+    };
+    assert_eq!(landed.len(), 2);
+
+    let head = repo.repo().head().unwrap().peel_to_commit().unwrap();
+    assert_eq!(head.id(), landed[1]);
+    assert_eq!(head.parent_count(), 1);
+    assert_eq!(head.parent_id(0).unwrap(), landed[0]);
+    let first = repo.repo().find_commit(landed[0]).unwrap();
+    assert_eq!(first.parent_id(0).unwrap(), target_tip);
+    assert!(
+        first
+            .message()
+            .unwrap()
+            .starts_with("feat: add a\n\nBody text.\n\nVizier-Plan: alpha\nRebased-from: ")
+    );
+    assert!(
+        head.message()
+            .unwrap()
+            .starts_with("feat: add b\n\nRefs: #12\nVizier-Plan: alpha\nRebased-from: ")
+    );
+    assert!(repo.join("a.txt").exists() && repo.join("b.txt").exists());
+    assert!(repo.join("main.txt").exists());
+}
+
+#[test]
+fn squash_by_step_lands_one_commit_per_marked_step() {
+    let repo = TestRepo::new();
+    repo.write("README.md", "base\n");
+    raw_commit(repo.repo(), "base");
+    let base = repo.repo().head().unwrap().peel_to_commit().unwrap();
+    let target = repo.repo().head().unwrap().shorthand().unwrap().to_string();
+    repo.repo().branch("draft/alpha", &base, false).unwrap();
+
+    repo.write("main.txt", "target work\n");
+    let target_tip = raw_commit(repo.repo(), "target work");
+
+    checkout_branch_in(repo.path(), "draft/alpha").unwrap();
+    repo.write("a.txt", "one\n");
+    raw_commit(repo.repo(), "wip: parser");
+    repo.write("b.txt", "two\n");
+    raw_commit(repo.repo(), "feat: parser\n\nVizier-Step: 1");
+    repo.write("c.txt", "three\n");
+    raw_commit(repo.repo(), "feat: landing\n\nVizier-Step: 2");
+    repo.write("d.txt", "four\n");
+    raw_commit(repo.repo(), "chore: workflow stage commit");
+    checkout_branch_in(repo.path(), &target).unwrap();
+
+    assert_eq!(
+        step_marker("feat: x\n\nRefs: #1\nvizier-step: 3\n"),
+        Some(3)
+    );
+    assert_eq!(step_marker("feat: x\n\nVizier-Step: none"), None);
+
+    let groups = plan_step_groups_in(repo.path(), "draft/alpha").expect("step groups");
+    let summary: Vec<_> = groups
+        .iter()
+        .map(|group| (group.step, group.subjects.clone()))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (
+                Some(1),
+                vec!["wip: parser".to_string(), "feat: parser".to_string()]
+            ),
+            (
+                Some(2),
+                vec![
+                    "feat: landing".to_string(),
+                    "chore: workflow stage commit".to_string()
+                ]
+            ),
+        ]
+    );
+
+    let messages = vec!["step one".to_string(), "step two".to_string()];
+    let outcome = squash_step_groups_in(repo.path(), "draft/alpha", &groups, &messages)
+        .expect("squash by step");
+    let RebaseMergeOutcome::Landed(landed) = outcome else {
+        panic!("unexpected conflict: {outcome:?}");
+    };
+    assert_eq!(landed.len(), 2);
+    let first = repo.repo().find_commit(landed[0]).unwrap();
+    assert_eq!(first.parent_id(0).unwrap(), target_tip);
+    assert_eq!(first.message(), Some("step one"));
+    let first_tree = first.tree().unwrap();
+    assert!(first_tree.get_name("b.txt").is_some() && first_tree.get_name("c.txt").is_none());
+    let head = repo.repo().head().unwrap().peel_to_commit().unwrap();
+    assert_eq!(head.id(), landed[1]);
+    assert_eq!(head.parent_id(0).unwrap(), landed[0]);
+    assert!(repo.join("d.txt").exists() && repo.join("main.txt").exists());
+}
+
+#[test]
+fn commit_notes_carry_provenance_alongside_trailers() {
+    let repo = TestRepo::new();
+    repo.write("README.md", "base\n");
+    let plain = raw_commit(repo.repo(), "feat: plain subject");
+    repo.write("a.txt", "one\n");
+    let trailed = raw_commit(repo.repo(), "feat: add a\n\nVizier-Plan: alpha");
+
+    assert_eq!(read_commit_note(repo.repo(), plain).unwrap(), None);
+    write_commit_note_in(repo.path(), plain, &[]).unwrap();
+    assert_eq!(read_commit_note(repo.repo(), plain).unwrap(), None);
+
+    write_commit_note_in(
+        repo.path(),
+        plain,
+        &[
+            "Vizier-Session: session-1".to_string(),
+            "Vizier-Plan: beta".to_string(),
+        ],
+    )
+    .unwrap();
+    assert_eq!(
+        read_commit_note(repo.repo(), plain).unwrap().as_deref(),
+        Some("Vizier-Session: session-1\nVizier-Plan: beta\n")
+    );
+    let commit = repo.repo().find_commit(plain).unwrap();
+    assert_eq!(commit.message(), Some("feat: plain subject"));
+    assert_eq!(
+        commit_provenance_value(repo.repo(), &commit, "vizier-plan").as_deref(),
+        Some("beta")
+    );
+
+    let commit = repo.repo().find_commit(trailed).unwrap();
+    assert_eq!(
+        commit_provenance_value(repo.repo(), &commit, "Vizier-Plan").as_deref(),
+        Some("alpha")
+    );
+    assert_eq!(
+        commit_provenance_value(repo.repo(), &commit, "Vizier-Session"),
+        None
+    );
+}
+
 #[test]
 fn apply_cherry_pick_sequence_errors_when_head_moves() {
     let repo = TestRepo::new();
@@ -111,12 +274,149 @@ fn apply_cherry_pick_sequence_errors_when_head_moves() {
     );
 }
 
+#[test]
+fn target_detection_reports_its_source() {
+    let _guard = crate::config::test_config_lock().lock().unwrap();
+    let original = crate::config::get_config();
+    let repo = TestRepo::new();
+    repo.write("a.txt", "base\n");
+    raw_stage(repo.repo(), "a.txt");
+    let base = raw_commit(repo.repo(), "base");
+    let base = repo.repo().find_commit(base).expect("base");
+    repo.repo().branch("trunk", &base, true).expect("trunk");
+    repo.repo().branch("develop", &base, true).expect("develop");
+    repo.repo()
+        .set_head("refs/heads/develop")
+        .expect("switch to develop");
+    for name in ["master", "main"] {
+        if let Ok(mut branch) = repo.repo().find_branch(name, git2::BranchType::Local) {
+            branch.delete().expect("delete default branch");
+        }
+    }
+
+    let detected = detect_target_branch_in(repo.path()).expect("common name");
+    assert_eq!(detected.branch, "trunk");
+    assert_eq!(detected.source, TargetSource::CommonName);
+
+    repo.repo()
+        .reference("refs/remotes/origin/develop", base.id(), true, "fetch")
+        .expect("remote branch");
+    repo.repo()
+        .reference_symbolic(
+            "refs/remotes/origin/HEAD",
+            "refs/remotes/origin/develop",
+            true,
+            "clone",
+        )
+        .expect("origin/HEAD");
+    let detected = detect_target_branch_in(repo.path()).expect("remote head");
+    assert_eq!(detected.describe(), "develop (origin/HEAD)");
+
+    let mut cfg = original.clone();
+    cfg.branches.target = Some("release".to_string());
+    crate::config::set_config(cfg);
+    let detected = detect_target_branch_in(repo.path()).expect("config");
+    crate::config::set_config(original);
+    assert_eq!(detected.branch, "release");
+    assert_eq!(detected.source, TargetSource::Config);
+}
+
 fn raw_stage(repo: &Repository, rel: &str) {
     let mut idx = repo.index().unwrap();
     idx.add_path(Path::new(rel)).unwrap();
     idx.write().unwrap();
 }
 
+#[test]
+fn commit_staged_split_separates_code_and_narrative_with_trailers() {
+    let tr = TestRepo::new();
+    tr.write("src/lib.rs", "fn a() {}\n");
+    tr.write(".vizier/narrative/snapshot.md", "old\n");
+    let base = raw_commit(tr.repo(), "base");
+
+    tr.write("src/lib.rs", "fn b() {}\n");
+    tr.write(".vizier/narrative/snapshot.md", "new\n");
+    tr.write("unstaged.txt", "left alone\n");
+    raw_stage(tr.repo(), "src/lib.rs");
+    raw_stage(tr.repo(), ".vizier/narrative/snapshot.md");
+
+    let split = commit_staged_split_in(tr.path(), "feat: switch to b", "chore: update narrative")
+        .expect("split commit");
+    let code = tr
+        .repo()
+        .find_commit(split.code.expect("code commit"))
+        .unwrap();
+    let narrative = tr
+        .repo()
+        .find_commit(split.narrative.expect("narrative commit"))
+        .unwrap();
+    assert_eq!(code.parent_id(0).unwrap(), base);
+    assert_eq!(narrative.parent_id(0).unwrap(), code.id());
+    assert_eq!(tr.repo().head().unwrap().target(), Some(narrative.id()));
+    assert_eq!(
+        code.message().unwrap(),
+        "feat: switch to b\n\nVizier-Split: code\n"
+    );
+    assert_eq!(
+        narrative.message().unwrap(),
+        format!(
+            "chore: update narrative\n\nVizier-Split: narrative\nVizier-Code-Commit: {}\n",
+            code.id()
+        )
+    );
+
+    let blob = |commit: &git2::Commit, path: &str| {
+        let entry = commit.tree().unwrap().get_path(Path::new(path)).unwrap();
+        String::from_utf8(tr.repo().find_blob(entry.id()).unwrap().content().to_vec()).unwrap()
+    };
+    assert_eq!(blob(&code, "src/lib.rs"), "fn b() {}\n");
+    assert_eq!(blob(&code, ".vizier/narrative/snapshot.md"), "old\n");
+    assert_eq!(blob(&narrative, ".vizier/narrative/snapshot.md"), "new\n");
+    assert!(
+        code.tree()
+            .unwrap()
+            .get_path(Path::new("unstaged.txt"))
+            .is_err()
+    );
+
+    tr.write("src/lib.rs", "fn c() {}\n");
+    raw_stage(tr.repo(), "src/lib.rs");
+    let code_only = commit_staged_split_in(tr.path(), "feat: c", "chore: update narrative")
+        .expect("code-only commit");
+    assert!(code_only.narrative.is_none());
+    let commit = tr.repo().find_commit(code_only.code.unwrap()).unwrap();
+    assert_eq!(commit.message(), Some("feat: c"));
+
+    assert!(commit_staged_split_in(tr.path(), "empty", "empty").is_err());
+}
+
+#[test]
+fn bare_repo_detection_and_worktrees_from_a_bare_repo() {
+    let repo = TestRepo::new();
+    repo.write("file.txt", "hello\n");
+    raw_commit(repo.repo(), "initial");
+    let branch = repo.repo().head().unwrap().shorthand().unwrap().to_string();
+    assert!(!is_bare_repo(repo.path()));
+    assert!(!is_bare_repo(repo.join(".git")));
+
+    let server = tempfile::TempDir::new().expect("server tempdir");
+    let bare_path = server.path().join("repo.git");
+    git2::build::RepoBuilder::new()
+        .bare(true)
+        .clone(repo.path_str(), &bare_path)
+        .expect("clone bare");
+    assert!(is_bare_repo(&bare_path));
+
+    let worktree = bare_path.join(".vizier/tmp-worktrees/merge");
+    fs::create_dir_all(worktree.parent().unwrap()).expect("tmp-worktrees dir");
+    add_worktree_for_branch_in(&bare_path, "merge", &worktree, &branch)
+        .expect("worktree from a bare repo");
+    assert_eq!(
+        fs::read_to_string(worktree.join("file.txt")).expect("checked out"),
+        "hello\n"
+    );
+}
+
 #[test]
 fn push_current_branch_updates_remote_tracking() {
     let repo = TestRepo::new();
@@ -153,6 +453,162 @@ fn push_current_branch_updates_remote_tracking() {
     assert_eq!(tracking_ref.target(), Some(local_oid));
 }
 
+#[test]
+fn push_branch_pushes_named_branch_without_checkout() {
+    let repo = TestRepo::new();
+    let remote_dir = tempfile::TempDir::new().expect("remote tempdir");
+    Repository::init_bare(remote_dir.path()).expect("init bare remote");
+    let remote_path = remote_dir
+        .path()
+        .to_str()
+        .expect("remote path utf8")
+        .to_owned();
+    repo.repo()
+        .remote("origin", &remote_path)
+        .expect("configure remote");
+
+    repo.write("file.txt", "hello\n");
+    let oid = raw_commit(repo.repo(), "initial");
+    let commit = repo.repo().find_commit(oid).unwrap();
+    repo.repo()
+        .branch("draft/feature", &commit, false)
+        .expect("create branch");
+
+    push_branch_in(repo.path(), "origin", "draft/feature").expect("push succeeds");
+
+    let remote_repo = Repository::open(remote_dir.path()).expect("open remote repo");
+    let remote_ref = remote_repo
+        .find_reference("refs/heads/draft/feature")
+        .expect("remote branch exists");
+    assert_eq!(remote_ref.target(), Some(oid));
+    assert!(
+        repo.repo()
+            .find_reference("refs/remotes/origin/draft/feature")
+            .is_ok()
+    );
+    assert!(pull_request_url(&repo.path_utf8, "main", "draft/feature").is_none());
+}
+
+#[test]
+fn push_branch_with_lease_forces_rewrites_but_refuses_moved_remote() {
+    let repo = TestRepo::new();
+    let remote_dir = tempfile::TempDir::new().expect("remote tempdir");
+    let remote_repo = Repository::init_bare(remote_dir.path()).expect("init bare remote");
+    repo.repo()
+        .remote(
+            "origin",
+            remote_dir.path().to_str().expect("remote path utf8"),
+        )
+        .expect("configure remote");
+
+    repo.write("file.txt", "hello\n");
+    let base = raw_commit(repo.repo(), "initial");
+    let base_commit = repo.repo().find_commit(base).unwrap();
+    repo.repo()
+        .branch("draft/feature", &base_commit, false)
+        .expect("create branch");
+    assert_eq!(
+        push_branch_with_lease_in(repo.path(), "origin", "draft/feature").expect("create"),
+        LeasePush::Created
+    );
+    assert_eq!(
+        push_branch_with_lease_in(repo.path(), "origin", "draft/feature").expect("no-op"),
+        LeasePush::UpToDate
+    );
+
+    // Amend: the rewritten tip replaces the copy Vizier last pushed.
+    let sig = Signature::now("Tester", "tester@example.com").unwrap();
+    let amended = base_commit
+        .amend(None, None, None, None, Some("amended"), None)
+        .unwrap();
+    repo.repo()
+        .reference("refs/heads/draft/feature", amended, true, "amend")
+        .unwrap();
+    assert_eq!(
+        push_branch_with_lease_in(repo.path(), "origin", "draft/feature").expect("forced"),
+        LeasePush::Forced
+    );
+
+    // A teammate moves the remote copy; the local rewrite must not clobber it.
+    let remote_tree = remote_repo.find_commit(amended).unwrap().tree().unwrap();
+    remote_repo
+        .commit(
+            Some("refs/heads/draft/feature"),
+            &sig,
+            &sig,
+            "teammate",
+            &remote_tree,
+            &[&remote_repo.find_commit(amended).unwrap()],
+        )
+        .unwrap();
+    repo.repo()
+        .reference("refs/heads/draft/feature", base, true, "rewrite again")
+        .unwrap();
+    let err = push_branch_with_lease_in(repo.path(), "origin", "draft/feature")
+        .expect_err("diverged remote");
+    assert!(err.to_string().contains("moved"), "{err}");
+    assert_ne!(
+        remote_repo
+            .refname_to_id("refs/heads/draft/feature")
+            .unwrap(),
+        base
+    );
+}
+
+#[test]
+fn fetch_branch_updates_tracking_ref_only() {
+    let repo = TestRepo::new();
+    let remote_dir = tempfile::TempDir::new().expect("remote tempdir");
+    let remote_repo = Repository::init_bare(remote_dir.path()).expect("init bare remote");
+    repo.repo()
+        .remote(
+            "origin",
+            remote_dir.path().to_str().expect("remote path utf8"),
+        )
+        .expect("configure remote");
+
+    repo.write("file.txt", "hello\n");
+    let base = raw_commit(repo.repo(), "initial");
+    repo.repo()
+        .branch(
+            "draft/feature",
+            &repo.repo().find_commit(base).unwrap(),
+            false,
+        )
+        .expect("create branch");
+    push_branch_in(repo.path(), "origin", "draft/feature").expect("push succeeds");
+
+    // Another machine advances the branch on the shared remote.
+    let remote_base = remote_repo.find_commit(base).expect("pushed commit");
+    let sig = Signature::now("Build", "build@example.com").unwrap();
+    let advanced = remote_repo
+        .commit(
+            Some("refs/heads/draft/feature"),
+            &sig,
+            &sig,
+            "remote step",
+            &remote_base.tree().unwrap(),
+            &[&remote_base],
+        )
+        .expect("remote commit");
+
+    let fetched = fetch_branch_in(repo.path(), "origin", "draft/feature").expect("fetch succeeds");
+    assert_eq!(fetched, advanced);
+    assert_eq!(
+        repo.repo()
+            .refname_to_id("refs/remotes/origin/draft/feature")
+            .unwrap(),
+        advanced
+    );
+    assert_eq!(
+        repo.repo()
+            .refname_to_id("refs/heads/draft/feature")
+            .unwrap(),
+        base,
+        "local branch is not moved by fetch"
+    );
+}
+
 #[test]
 fn push_current_branch_rejects_detached_head() {
     let repo = TestRepo::new();
@@ -215,6 +671,7 @@ impl CredentialExecutor for RecordingExecutor {
 #[test]
 fn credential_plan_attempts_file_keys_when_agent_fails() {
     let plan = build_credential_plan(CredentialType::SSH_KEY, false);
+    assert_eq!(plan.first(), Some(&CredentialStrategy::SshAgent));
     assert!(plan.contains(&CredentialStrategy::SshKey(SshKeyKind::IdEd25519)));
     assert!(plan.contains(&CredentialStrategy::SshKey(SshKeyKind::IdRsa)));
 
@@ -239,13 +696,97 @@ fn credential_plan_attempts_file_keys_when_agent_fails() {
 
     let invoked = executor.invoked.borrow();
     let expected = vec![
+        CredentialStrategy::SshAgent,
         CredentialStrategy::SshKey(SshKeyKind::IdEd25519),
         CredentialStrategy::SshKey(SshKeyKind::IdRsa),
-        CredentialStrategy::Default,
     ];
     assert_eq!(&expected, invoked.as_slice());
 }
 
+#[test]
+fn transport_errors_separate_auth_host_key_and_network_failures() {
+    let url = "ssh://git@example.com:2222/team/repo.git";
+    assert_eq!(url_host(url), "example.com");
+    assert_eq!(url_host("git@github.com:team/repo.git"), "github.com");
+    assert_eq!(url_host("https://host.test/repo.git"), "host.test");
+
+    let rejected = TransportLog {
+        attempts: vec![CredentialAttempt {
+            strategy: CredentialStrategy::SshAgent,
+            outcome: AttemptOutcome::Failure("rejected by remote".to_string()),
+        }],
+        host: None,
+    };
+    let err = transport_error(
+        "push",
+        "origin",
+        url,
+        &rejected,
+        git2::Error::from_str("callback error"),
+    );
+    assert!(matches!(err.kind(), PushErrorKind::Auth { .. }));
+    assert!(
+        err.to_string().contains("ssh-agent: rejected by remote"),
+        "{err}"
+    );
+
+    let err = transport_error(
+        "push",
+        "origin",
+        url,
+        &TransportLog::default(),
+        git2::Error::new(
+            git2::ErrorCode::Certificate,
+            git2::ErrorClass::Ssh,
+            "invalid or unknown remote ssh hostkey",
+        ),
+    );
+    match err.kind() {
+        PushErrorKind::HostKey { host, .. } => assert_eq!(host, "example.com"),
+        other => panic!("unexpected error variant: {other:?}"),
+    }
+
+    let err = transport_error(
+        "push",
+        "origin",
+        url,
+        &TransportLog::default(),
+        git2::Error::new(
+            git2::ErrorCode::GenericError,
+            git2::ErrorClass::Net,
+            "failed to connect to example.com: Connection refused",
+        ),
+    );
+    assert!(matches!(err.kind(), PushErrorKind::Network { .. }));
+    assert!(
+        err.to_string().starts_with("unable to reach origin"),
+        "{err}"
+    );
+}
+
+#[test]
+fn push_to_unreachable_remote_reports_network_error() {
+    let repo = TestRepo::new();
+    repo.write("note.txt", "one\n");
+    raw_commit(repo.repo(), "init");
+    let branch = repo
+        .repo()
+        .head()
+        .expect("head")
+        .shorthand()
+        .expect("branch")
+        .to_string();
+    repo.repo()
+        .remote("origin", "http://127.0.0.1:9/repo.git")
+        .expect("configure remote");
+
+    let err = push_branch_in(repo.path(), "origin", &branch).expect_err("push should fail");
+    assert!(
+        matches!(err.kind(), PushErrorKind::Network { .. }),
+        "unexpected error: {err:?}"
+    );
+}
+
 // --- normalize_pathspec --------------------------------------------------
 
 #[test]
@@ -344,6 +885,55 @@ fn diff_head_vs_workdir_and_path_and_exclude() {
     assert!(!d_b_ex.contains("b/inner/keep.txt"));
 }
 
+#[test]
+fn diff_caps_binary_oversized_and_long_files() {
+    let repo = TestRepo::new();
+
+    repo.write("notes.txt", "start\n");
+    fs::write(repo.join("logo.png"), [0u8, 1, 2, 3]).unwrap();
+    repo.write("asset.dat", "small\n");
+    raw_commit(repo.repo(), "base");
+
+    repo.write("notes.txt", &"line of notes\n".repeat(60));
+    fs::write(repo.join("logo.png"), [0u8, 9, 8, 7, 6]).unwrap();
+    repo.write("asset.dat", &"x".repeat(4096));
+
+    let limits = super::DiffLimits {
+        max_file_bytes: 1024,
+        max_patch_bytes: 256,
+    };
+    let diff = super::get_diff_with_limits(repo.path_str(), None, None, limits).expect("diff");
+    assert!(
+        diff.contains("Binary files a/logo.png and b/logo.png differ\n"),
+        "{diff}"
+    );
+    assert!(
+        diff.contains(
+            "Binary files a/asset.dat and b/asset.dat differ (over 1024 bytes; contents not read)"
+        ),
+        "{diff}"
+    );
+    assert!(
+        diff.contains("[diff for notes.txt truncated after 256 bytes]"),
+        "{diff}"
+    );
+    assert!(diff.len() < 2048, "{diff}");
+
+    let mut seen = Vec::new();
+    super::for_each_file_patch(repo.path_str(), None, None, limits, |file| {
+        seen.push((file.path, file.binary, file.oversized, file.truncated));
+        seen.len() < 2
+    })
+    .expect("stream");
+    assert_eq!(
+        seen,
+        vec![
+            ("asset.dat".to_string(), true, true, false),
+            ("logo.png".to_string(), true, false, false),
+        ]
+    );
+}
+
 #[test]
 fn diff_single_rev_to_workdir() {
     let repo = TestRepo::new();
@@ -480,6 +1070,63 @@ fn diff_summary_reports_stats_and_name_status() {
     );
 }
 
+#[test]
+fn diff_stats_report_line_counts_renames_and_binary_files() {
+    let repo = TestRepo::new();
+
+    repo.write("modify.txt", "one\ntwo\nthree\n");
+    repo.write(
+        "old_name.txt",
+        "alpha\nbeta\ngamma\ndelta\nepsilon\nzeta\neta\ntheta\n",
+    );
+    raw_commit(repo.repo(), "base");
+
+    let head_commit = repo.repo().head().unwrap().peel_to_commit().unwrap();
+    repo.repo()
+        .branch("target", &head_commit, true)
+        .expect("create target branch");
+
+    repo.write("modify.txt", "one\n2\nthree\nfour\n");
+    fs::rename(repo.join("old_name.txt"), repo.join("new_name.txt")).unwrap();
+    fs::write(repo.join("logo.bin"), [0u8, 159, 146, 150, 0, 1]).unwrap();
+    raw_commit(repo.repo(), "topic");
+
+    let stats = super::diff_stats_between(repo.path(), "target", "HEAD").expect("diff stats");
+    let modify = stats
+        .files
+        .iter()
+        .find(|file| file.path == "modify.txt")
+        .expect("modified file");
+    assert_eq!((modify.insertions, modify.deletions), (2, 1));
+    assert_eq!(modify.status, super::DiffFileStatus::Modified);
+
+    let renamed = stats
+        .files
+        .iter()
+        .find(|file| file.path == "new_name.txt")
+        .expect("renamed file");
+    assert_eq!(renamed.status, super::DiffFileStatus::Renamed);
+    assert_eq!(renamed.old_path.as_deref(), Some("old_name.txt"));
+
+    let binary = stats
+        .files
+        .iter()
+        .find(|file| file.path == "logo.bin")
+        .expect("binary file");
+    assert!(binary.binary);
+    assert_eq!((binary.insertions, binary.deletions), (0, 0));
+
+    assert_eq!(
+        stats.summary_line(),
+        "3 files changed, +2 -1, 1 renamed, 1 binary"
+    );
+    assert!(
+        stats
+            .name_status()
+            .contains("R\told_name.txt\tnew_name.txt")
+    );
+}
+
 // --- unborn HEAD (no untracked): stage-only then diff --------------------
 
 #[test]
@@ -882,3 +1529,500 @@ fn commit_in_progress_cherry_pick_completes_and_cleans_state() {
     let new_head = repo.repo().head().unwrap().peel_to_commit().unwrap().id();
     assert_eq!(new_head, committed, "HEAD should advance to cherry-pick");
 }
+
+#[test]
+fn discard_worktree_changes_resets_tracked_and_removes_untracked() {
+    let repo = TestRepo::new();
+
+    repo.write("keep.txt", "base\n");
+    raw_commit(repo.repo(), "base");
+
+    repo.append("keep.txt", "agent edit\n");
+    repo.write("scratch/nested/new.txt", "untracked\n");
+    raw_stage(repo.repo(), "keep.txt");
+
+    let discarded = super::discard_worktree_changes_in(repo.path()).expect("discard changes");
+    assert_eq!(discarded, 2);
+    assert_eq!(fs::read_to_string(repo.join("keep.txt")).unwrap(), "base\n");
+    assert!(
+        !repo.join("scratch").exists(),
+        "untracked dirs should be pruned"
+    );
+
+    let status = super::status_with_branch(repo.path()).expect("status");
+    assert_eq!(
+        status.lines().count(),
+        1,
+        "worktree should be clean:\n{status}"
+    );
+}
+
+#[test]
+fn commits_since_tips_reports_new_commits_per_branch() {
+    let repo = TestRepo::new();
+    repo.write("a.txt", "base\n");
+    let base = raw_commit(repo.repo(), "base");
+    let base_commit = repo.repo().find_commit(base).unwrap();
+    repo.repo()
+        .branch("draft/side", &base_commit, false)
+        .expect("create side branch");
+
+    let before = super::branch_tips_in(repo.path()).expect("tips before");
+    assert_eq!(before.len(), 2);
+
+    repo.append("a.txt", "one\n");
+    let first = raw_commit(repo.repo(), "first change");
+    repo.append("a.txt", "two\n");
+    let second = raw_commit(repo.repo(), "second change");
+
+    let commits = super::commits_since_tips_in(repo.path(), &before).expect("commits since");
+    let oids = commits.iter().map(|(_, oid, _)| *oid).collect::<Vec<_>>();
+    assert_eq!(oids, vec![first, second]);
+    assert_eq!(commits[1].2, "second change");
+    assert!(
+        commits.iter().all(|(branch, _, _)| branch != "draft/side"),
+        "unchanged branches should not report commits: {commits:?}"
+    );
+}
+
+fn rebase_fixture(draft_contents: &str, target_contents: &str) -> (TestRepo, String) {
+    let repo = TestRepo::new();
+    repo.write("a.txt", "base\n");
+    let base = raw_commit(repo.repo(), "base");
+    let target = repo.repo().head().unwrap().shorthand().unwrap().to_string();
+    {
+        let base_commit = repo.repo().find_commit(base).unwrap();
+        repo.repo()
+            .branch("draft/alpha", &base_commit, false)
+            .expect("create draft branch");
+    }
+
+    repo.write("a.txt", target_contents);
+    raw_commit(repo.repo(), "target change");
+
+    checkout_branch_in(repo.path(), "draft/alpha").expect("checkout draft");
+    repo.write("a.txt", draft_contents);
+    raw_commit(repo.repo(), "draft change");
+    (repo, target)
+}
+
+fn narrative_merge_fixture(draft_snapshot: &str, target_snapshot: &str) -> (TestRepo, String) {
+    let repo = TestRepo::new();
+    repo.write(
+        ".vizier/narrative/snapshot.md",
+        "# Snapshot\n\n## Jobs\nScheduler runs jobs.\n\n## Review\nReview is manual.\n",
+    );
+    let base = raw_commit(repo.repo(), "base");
+    let target = repo.repo().head().unwrap().shorthand().unwrap().to_string();
+    {
+        let base_commit = repo.repo().find_commit(base).unwrap();
+        repo.repo()
+            .branch("draft/alpha", &base_commit, false)
+            .expect("create draft branch");
+    }
+    repo.write(".vizier/narrative/snapshot.md", target_snapshot);
+    raw_commit(repo.repo(), "target narrative");
+
+    checkout_branch_in(repo.path(), "draft/alpha").expect("checkout draft");
+    repo.write(".vizier/narrative/snapshot.md", draft_snapshot);
+    raw_commit(repo.repo(), "draft narrative");
+    checkout_branch_in(repo.path(), &target).expect("checkout target");
+    (repo, target)
+}
+
+#[test]
+fn prepare_merge_merges_concurrent_narrative_sections() {
+    // Both sides append a section at the end, which git alone reports as a conflict.
+    let (repo, _target) = narrative_merge_fixture(
+        "# Snapshot\n\n## Jobs\nScheduler runs jobs.\n\n## Review\nReview is manual.\n\n## Release\nReleases are tagged.\n",
+        "# Snapshot\n\n## Jobs\nScheduler runs jobs in order.\n\n## Review\nReview is manual.\n\n## Merge\nMerges are gated.\n",
+    );
+
+    let prep = prepare_merge_in(repo.path(), "draft/alpha").expect("prepare merge");
+    let MergePreparation::Ready(ready) = prep else {
+        panic!("expected narrative edits to merge cleanly, got {prep:?}");
+    };
+    assert_eq!(
+        ready.narrative_merged,
+        vec![".vizier/narrative/snapshot.md".to_string()]
+    );
+    commit_ready_merge_in(repo.path(), "merge", ready).expect("commit merge");
+    assert_eq!(
+        fs::read_to_string(repo.join(".vizier/narrative/snapshot.md")).unwrap(),
+        "# Snapshot\n\n## Jobs\nScheduler runs jobs in order.\n\n## Review\nReview is manual.\n\n## Release\nReleases are tagged.\n\n## Merge\nMerges are gated.\n"
+    );
+}
+
+#[test]
+fn prepare_merge_leaves_overlapping_narrative_sections_conflicted() {
+    let (repo, _target) = narrative_merge_fixture(
+        "# Snapshot\n\n## Jobs\nScheduler runs jobs in parallel.\n\n## Review\nReview runs checks.\n",
+        "# Snapshot\n\n## Jobs\nScheduler runs jobs serially.\n\n## Review\nReview is manual.\n",
+    );
+
+    let prep = prepare_merge_in(repo.path(), "draft/alpha").expect("prepare merge");
+    let MergePreparation::Conflicted(conflict) = prep else {
+        panic!("expected overlapping edits to conflict, got {prep:?}");
+    };
+    assert_eq!(
+        conflict.files,
+        vec![".vizier/narrative/snapshot.md".to_string()]
+    );
+    let contents = fs::read_to_string(repo.join(".vizier/narrative/snapshot.md")).unwrap();
+    assert!(contents.contains("<<<<<<< HEAD\n## Jobs\nScheduler runs jobs serially."));
+    assert!(
+        contents.ends_with("## Review\nReview runs checks.\n"),
+        "{contents}"
+    );
+    assert_eq!(repo.repo().state(), RepositoryState::Merge);
+}
+
+#[test]
+fn start_rebase_replays_draft_commits_onto_target() {
+    let (repo, target) = rebase_fixture("base\n", "base\n");
+    repo.write("b.txt", "draft only\n");
+    raw_commit(repo.repo(), "draft adds b");
+    let target_tip = repo
+        .repo()
+        .revparse_single(&target)
+        .unwrap()
+        .peel_to_commit()
+        .unwrap()
+        .id();
+
+    let progress = start_rebase_in(repo.path(), &target).expect("rebase");
+    let RebaseProgress::Completed { head, applied } = progress else {
+        panic!("expected completed rebase, got {progress:?}");
+    };
+    assert_eq!(applied, 1, "the no-op draft commit should be dropped");
+    let head_commit = repo.repo().find_commit(head).unwrap();
+    assert_eq!(head_commit.parent_id(0).unwrap(), target_tip);
+    assert_eq!(head_commit.summary(), Some("draft adds b"));
+    assert_eq!(
+        repo.repo()
+            .find_branch("draft/alpha", git2::BranchType::Local)
+            .unwrap()
+            .get()
+            .target(),
+        Some(head)
+    );
+}
+
+#[test]
+fn rebase_pauses_on_conflict_and_continues_after_resolution() {
+    let (repo, target) = rebase_fixture("draft\n", "target\n");
+
+    let progress = start_rebase_in(repo.path(), &target).expect("rebase");
+    let RebaseProgress::Conflicted { files, summary, .. } = progress else {
+        panic!("expected conflicted rebase, got {progress:?}");
+    };
+    assert_eq!(files, vec!["a.txt".to_string()]);
+    assert_eq!(summary, "draft change");
+    assert!(
+        continue_rebase_in(repo.path()).is_err(),
+        "continue should refuse while conflicts remain"
+    );
+
+    repo.write("a.txt", "resolved\n");
+    stage_paths_allow_missing_in(repo.path(), &["a.txt"]).expect("stage resolution");
+    let progress = continue_rebase_in(repo.path()).expect("continue");
+    assert!(
+        matches!(progress, RebaseProgress::Completed { applied: 1, .. }),
+        "unexpected progress: {progress:?}"
+    );
+    assert_eq!(
+        fs::read_to_string(repo.path().join("a.txt")).unwrap(),
+        "resolved\n"
+    );
+    assert_eq!(repo.repo().state(), RepositoryState::Clean);
+}
+
+#[test]
+fn revert_commit_restores_previous_tree() {
+    let repo = TestRepo::new();
+    repo.write("a.txt", "base\n");
+    raw_commit(repo.repo(), "base");
+    repo.write("a.txt", "merged\n");
+    repo.write("b.txt", "new\n");
+    let merged = raw_commit(repo.repo(), "feat: merge plan alpha");
+
+    let revert = revert_commit_in(repo.path(), merged).expect("revert");
+    let head = repo.repo().head().unwrap().peel_to_commit().unwrap();
+    assert_eq!(head.id(), revert);
+    assert_eq!(head.parent_id(0).unwrap(), merged);
+    assert!(
+        head.message()
+            .unwrap()
+            .contains(&format!("This reverts commit {merged}")),
+        "{:?}",
+        head.message()
+    );
+    assert_eq!(fs::read_to_string(repo.join("a.txt")).unwrap(), "base\n");
+    assert!(!repo.join("b.txt").exists());
+}
+
+#[test]
+fn pick_applies_one_branch_commit_onto_target() {
+    let repo = TestRepo::new();
+    repo.write("base.txt", "base\n");
+    let base = raw_commit(repo.repo(), "base");
+    repo.repo()
+        .branch("target", &repo.repo().find_commit(base).unwrap(), false)
+        .unwrap();
+    repo.write("a.txt", "step one\n");
+    raw_commit(repo.repo(), "step one");
+    repo.write("b.txt", "step two\n");
+    let step_two = raw_commit(repo.repo(), "step two");
+    let source = current_branch_name_in(repo.path()).unwrap().unwrap();
+
+    let commits = branch_commits_in(repo.path(), &source, "target").expect("branch commits");
+    assert_eq!(
+        commits
+            .iter()
+            .map(|commit| commit.summary.as_str())
+            .collect::<Vec<_>>(),
+        vec!["step one", "step two"]
+    );
+    assert_eq!(commits[1].oid, step_two);
+
+    let PickPreparation::Ready(ready) =
+        prepare_pick_in(repo.path(), step_two, "target").expect("prepare pick")
+    else {
+        panic!("expected a clean pick");
+    };
+    assert_eq!(ready.onto_oid, base);
+    let picked = create_pick_commit_in(repo.path(), &ready, "hotfix: step two").expect("commit");
+    fast_forward_branch_in(repo.path(), "target", base, picked).expect("fast-forward");
+
+    let tip = repo
+        .repo()
+        .find_branch("target", git2::BranchType::Local)
+        .unwrap()
+        .get()
+        .peel_to_commit()
+        .unwrap();
+    assert_eq!(tip.id(), picked);
+    assert_eq!(tip.parent_id(0).unwrap(), base);
+    let tree = tip.tree().unwrap();
+    assert!(tree.get_path(Path::new("b.txt")).is_ok());
+    assert!(tree.get_path(Path::new("a.txt")).is_err());
+
+    let err = prepare_pick_in(repo.path(), step_two, "target").expect_err("already applied");
+    assert!(err.message().contains("already on `target`"), "{err}");
+    fast_forward_branch_in(repo.path(), "target", base, picked).expect_err("stale expectation");
+}
+
+#[test]
+fn imported_patch_commits_onto_branch_tip_with_patch_author() {
+    let repo = TestRepo::new();
+    repo.write("a.txt", "one\n");
+    let base = raw_commit(repo.repo(), "base");
+
+    let patches = parse_patch_input(
+        "From 0123456789abcdef0123456789abcdef01234567 Mon Sep 17 00:00:00 2001
+From: Ada Lovelace <ada@example.com>
+Date: Thu, 1 Jan 2026 12:00:00 +0000
+Subject: [PATCH] Extend a
+
+---
+diff --git a/a.txt b/a.txt
+--- a/a.txt
++++ b/a.txt
+@@ -1 +1,2 @@
+ one
++two
+",
+    )
+    .expect("parse patch");
+    let patch = &patches[0];
+    let tree = apply_patch_to_commit_in(repo.path(), base, &patch.diff).expect("apply");
+    let oid = create_patch_commit_in(
+        repo.path(),
+        base,
+        tree,
+        patch.author.as_ref(),
+        patch.message.as_deref().unwrap_or_default(),
+    )
+    .expect("commit");
+
+    let commit = repo.repo().find_commit(oid).unwrap();
+    assert_eq!(commit.parent_id(0).unwrap(), base);
+    assert_eq!(commit.summary(), Some("Extend a"));
+    assert_eq!(commit.author().email(), Some("ada@example.com"));
+    assert_eq!(commit.author().when().seconds(), 1_767_268_800);
+    assert_eq!(commit.committer().email(), Some("tester@example.com"));
+    let blob = commit
+        .tree()
+        .unwrap()
+        .get_path(Path::new("a.txt"))
+        .unwrap()
+        .to_object(repo.repo())
+        .unwrap()
+        .peel_to_blob()
+        .unwrap();
+    assert_eq!(blob.content(), b"one\ntwo\n");
+    assert_eq!(
+        repo.repo().head().unwrap().target(),
+        Some(base),
+        "no ref moves"
+    );
+
+    let stale =
+        "diff --git a/a.txt b/a.txt\n--- a/a.txt\n+++ b/a.txt\n@@ -1 +1 @@\n-missing\n+three\n";
+    apply_patch_to_commit_in(repo.path(), base, stale).expect_err("context mismatch");
+    let existing = "diff --git a/a.txt b/a.txt\nnew file mode 100644\n--- /dev/null\n+++ b/a.txt\n@@ -0,0 +1 @@\n+dup\n";
+    let err = apply_patch_to_commit_in(repo.path(), base, existing).expect_err("file exists");
+    assert!(err.message().contains("already exists"), "{err}");
+}
+
+#[test]
+fn range_diff_pairs_rebased_commits() {
+    let repo = TestRepo::new();
+    repo.write("base.txt", "base\n");
+    let base = raw_commit(repo.repo(), "base");
+    repo.write("x.txt", "one\n");
+    raw_commit(repo.repo(), "add x");
+    repo.write("y.txt", "two\n");
+    raw_commit(repo.repo(), "add y");
+    repo.write("w.txt", "dropped\n");
+    let old_tip = raw_commit(repo.repo(), "add w");
+
+    let mut checkout = git2::build::CheckoutBuilder::new();
+    checkout.force().remove_untracked(true);
+    repo.repo().set_head_detached(base).unwrap();
+    repo.repo().checkout_head(Some(&mut checkout)).unwrap();
+    repo.write("z.txt", "upstream\n");
+    let upstream = raw_commit(repo.repo(), "upstream");
+    repo.repo()
+        .branch("target", &repo.repo().find_commit(upstream).unwrap(), true)
+        .unwrap();
+    repo.write("x.txt", "one\n");
+    raw_commit(repo.repo(), "add x");
+    repo.write("y.txt", "TWO\n");
+    raw_commit(repo.repo(), "add y");
+    repo.write("v.txt", "fresh\n");
+    let new_tip = raw_commit(repo.repo(), "add v");
+
+    let diff = range_diff_in(repo.path(), "target", old_tip, new_tip).expect("range diff");
+    let statuses = diff
+        .entries
+        .iter()
+        .map(|entry| (entry.status, entry.summary.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        statuses,
+        vec![
+            (RangeDiffStatus::Unchanged, "add x"),
+            (RangeDiffStatus::Modified, "add y"),
+            (RangeDiffStatus::Added, "add v"),
+            (RangeDiffStatus::Removed, "add w"),
+        ]
+    );
+    let interdiff = diff.entries[1].interdiff.as_deref().expect("interdiff");
+    assert!(interdiff.contains("-+two"), "{interdiff}");
+    assert!(interdiff.contains("++TWO"), "{interdiff}");
+    let rendered = diff.render();
+    assert!(rendered.starts_with("1:  "), "{rendered}");
+    assert!(rendered.contains(" = 1:  "), "{rendered}");
+    assert!(!diff.is_unchanged());
+}
+
+#[test]
+fn abort_in_progress_operation_resets_a_conflicted_cherry_pick() {
+    let repo = TestRepo::new();
+    repo.write("a", "base\n");
+    let base = raw_commit(repo.repo(), "base");
+    repo.write("a", "ours\n");
+    raw_commit(repo.repo(), "ours");
+
+    let base_commit = repo.repo().find_commit(base).unwrap();
+    let side_branch = repo.repo().branch("side", &base_commit, false).unwrap();
+    let mut index = repo.repo().index().unwrap();
+    let side_tree = {
+        let blob = repo.repo().blob(b"theirs\n").unwrap();
+        let mut builder = repo
+            .repo()
+            .treebuilder(Some(&base_commit.tree().unwrap()))
+            .unwrap();
+        builder.insert("a", blob, 0o100644).unwrap();
+        repo.repo().find_tree(builder.write().unwrap()).unwrap()
+    };
+    let sig = repo.repo().signature().unwrap();
+    let theirs = repo
+        .repo()
+        .commit(
+            side_branch.get().name(),
+            &sig,
+            &sig,
+            "theirs",
+            &side_tree,
+            &[&base_commit],
+        )
+        .unwrap();
+    repo.repo()
+        .cherrypick(&repo.repo().find_commit(theirs).unwrap(), None)
+        .unwrap();
+    index.read(true).unwrap();
+    assert!(index.has_conflicts());
+    assert_eq!(repo.repo().state(), RepositoryState::CherryPick);
+
+    assert_eq!(
+        abort_in_progress_operation_in(repo.path()).unwrap(),
+        Some("cherry-pick")
+    );
+    assert_eq!(repo.repo().state(), RepositoryState::Clean);
+    assert_eq!(fs::read_to_string(repo.join("a")).unwrap(), "ours\n");
+    assert_eq!(abort_in_progress_operation_in(repo.path()).unwrap(), None);
+}
+
+#[test]
+fn add_worktree_refuses_branch_held_by_external_worktree() {
+    let repo = TestRepo::new();
+    repo.write("a", "base\n");
+    let base = raw_commit(repo.repo(), "base");
+    let base_commit = repo.repo().find_commit(base).unwrap();
+    repo.repo()
+        .branch("draft/alpha", &base_commit, false)
+        .unwrap();
+
+    let outside = tempfile::TempDir::new().unwrap();
+    let user_path = outside.path().join("alpha");
+    add_worktree_for_branch_in(repo.path(), "alpha", &user_path, "draft/alpha").unwrap();
+    let found = external_worktree_for_branch_in(repo.path(), "draft/alpha")
+        .unwrap()
+        .expect("external worktree");
+    assert_eq!(found.name, "alpha");
+    assert_eq!(found.path, user_path.canonicalize().unwrap());
+
+    let vizier_path = repo.join(".vizier/tmp-worktrees/rebase-alpha");
+    let err = add_worktree_for_branch_in(
+        repo.path(),
+        "vizier-rebase-alpha",
+        &vizier_path,
+        "draft/alpha",
+    )
+    .expect_err("second checkout of the branch");
+    assert!(
+        err.message()
+            .contains("already checked out in worktree `alpha`"),
+        "{err}"
+    );
+    assert!(!vizier_path.exists());
+
+    // Vizier-owned worktrees under .vizier/ are not reported as external.
+    repo.repo()
+        .branch("draft/beta", &base_commit, false)
+        .unwrap();
+    fs::create_dir_all(repo.join(".vizier/tmp-worktrees")).unwrap();
+    add_worktree_for_branch_in(
+        repo.path(),
+        "vizier-beta",
+        repo.join(".vizier/tmp-worktrees/beta"),
+        "draft/beta",
+    )
+    .unwrap();
+    assert_eq!(
+        external_worktree_for_branch_in(repo.path(), "draft/beta").unwrap(),
+        None
+    );
+}
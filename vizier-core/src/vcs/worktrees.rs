@@ -1,6 +1,13 @@
 use git2::{BranchType, Error, Repository, WorktreeAddOptions, WorktreePruneOptions};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+/// A linked worktree that the operator created outside Vizier.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternalWorktree {
+    pub name: String,
+    pub path: PathBuf,
+}
 
 pub fn add_worktree_for_branch(
     worktree_name: &str,
@@ -18,6 +25,17 @@ pub fn add_worktree_for_branch_in<P: AsRef<Path>, Q: AsRef<Path>>(
 ) -> Result<(), Error> {
     let repo = Repository::open(repo_path)?;
     let path = path.as_ref();
+    // Checking the branch out a second time would leave two worktrees writing the same ref;
+    // callers that want the existing checkout look it up with `external_worktree_for_branch_in`.
+    if let Some(existing) = external_worktree_in(&repo, branch_name)? {
+        return Err(Error::from_str(&format!(
+            "branch `{branch_name}` is already checked out in worktree `{}` at {}; reuse that \
+             checkout or remove it with `git worktree remove {}`",
+            existing.name,
+            existing.path.display(),
+            existing.path.display()
+        )));
+    }
     let target_refname = format!("refs/heads/{branch_name}");
     match add_worktree_for_reference(&repo, worktree_name, path, &target_refname) {
         Ok(()) => Ok(()),
@@ -28,6 +46,55 @@ pub fn add_worktree_for_branch_in<P: AsRef<Path>, Q: AsRef<Path>>(
     }
 }
 
+/// Finds a linked worktree outside `.vizier/` that has `branch_name` checked out. Vizier's own
+/// temp worktrees and stale (prunable) entries are ignored.
+pub fn external_worktree_for_branch_in<P: AsRef<Path>>(
+    repo_path: P,
+    branch_name: &str,
+) -> Result<Option<ExternalWorktree>, Error> {
+    let repo = Repository::open(repo_path)?;
+    external_worktree_in(&repo, branch_name)
+}
+
+fn external_worktree_in(
+    repo: &Repository,
+    branch_name: &str,
+) -> Result<Option<ExternalWorktree>, Error> {
+    let Some(workdir) = repo.workdir() else {
+        return Ok(None);
+    };
+    let vizier_root = workdir
+        .join(".vizier")
+        .canonicalize()
+        .unwrap_or_else(|_| workdir.join(".vizier"));
+    let target_refname = format!("refs/heads/{branch_name}");
+    for name in repo.worktrees()?.iter().flatten() {
+        let Ok(worktree) = repo.find_worktree(name) else {
+            continue;
+        };
+        if worktree.validate().is_err() {
+            continue;
+        }
+        let path = worktree
+            .path()
+            .canonicalize()
+            .unwrap_or_else(|_| worktree.path().to_path_buf());
+        if path.starts_with(&vizier_root) {
+            continue;
+        }
+        let checked_out = Repository::open(&path)
+            .ok()
+            .and_then(|linked| linked.head().ok()?.name().map(str::to_string));
+        if checked_out.as_deref() == Some(target_refname.as_str()) {
+            return Ok(Some(ExternalWorktree {
+                name: name.to_string(),
+                path,
+            }));
+        }
+    }
+    Ok(None)
+}
+
 fn add_worktree_for_reference(
     repo: &Repository,
     worktree_name: &str,
@@ -70,6 +70,8 @@ pub fn default_walker() -> ignore::Walk {
         .build()
 }
 
+/// Files an agent may be pointed at: everything not gitignored plus the `.vizier` directory's
+/// own files, minus anything matching `[context] never_send`.
 pub fn get_non_ignored_files() -> Vec<std::path::PathBuf> {
     let mut files = default_walker()
         .filter_map(Result::ok)
@@ -101,5 +103,9 @@ pub fn get_non_ignored_files() -> Vec<std::path::PathBuf> {
         }
     }
 
+    if let Ok(root) = std::env::current_dir() {
+        let never_send = crate::never_send::NeverSend::from_config(&root);
+        files.retain(|path| !never_send.withholds(path));
+    }
     files
 }
@@ -0,0 +1,252 @@
+//! Worktree watching behind `vizier watch`: filesystem events from `notify`, reduced to the paths
+//! git would see, and a batcher that waits for edits to settle before handing a batch of changed
+//! paths to the suggestion step.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use git2::{Repository, StatusOptions};
+use notify::event::{CreateKind, RemoveKind};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use tokio::sync::mpsc;
+
+use crate::file_tracking::is_vizier_path;
+
+/// Uncommitted paths (tracked changes and untracked files, outside `.vizier/`), so work left
+/// before the watcher started is picked up too.
+pub fn dirty_paths(repo_root: &Path) -> Result<Vec<String>, git2::Error> {
+    let repo = Repository::open(repo_root)?;
+    let mut opts = StatusOptions::new();
+    opts.include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .include_ignored(false)
+        .exclude_submodules(true);
+
+    Ok(repo
+        .statuses(Some(&mut opts))?
+        .iter()
+        .filter_map(|entry| entry.path().map(str::to_string))
+        .filter(|path| !is_vizier_path(path))
+        .collect())
+}
+
+/// Filesystem events for the working tree, reduced to repo-relative paths outside `.git/` and
+/// `.vizier/` that `.gitignore` does not exclude. Each non-ignored directory gets its own
+/// non-recursive watch, so build output such as `target/` never costs one.
+pub struct WorktreeWatcher {
+    root: PathBuf,
+    repo: Repository,
+    watcher: RecommendedWatcher,
+    events: mpsc::UnboundedReceiver<notify::Result<Event>>,
+}
+
+impl WorktreeWatcher {
+    pub fn start(repo_root: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let (tx, events) = mpsc::unbounded_channel();
+        let watcher = notify::recommended_watcher(move |event| {
+            let _ = tx.send(event);
+        })?;
+        let mut worktree = Self {
+            root: repo_root.to_path_buf(),
+            repo: Repository::open(repo_root)?,
+            watcher,
+            events,
+        };
+        worktree.watch_tree(repo_root);
+        Ok(worktree)
+    }
+
+    /// Waits for the next event that touches a path git would see and returns those paths, or
+    /// `None` once the watcher has shut down.
+    pub async fn next_changes(&mut self) -> Option<notify::Result<Vec<String>>> {
+        loop {
+            let event = match self.events.recv().await? {
+                Ok(event) => event,
+                Err(err) => return Some(Err(err)),
+            };
+            // Reads, including vizier's own diff of the batch, are not changes.
+            if matches!(
+                event.kind,
+                EventKind::Access(_) | EventKind::Remove(RemoveKind::Folder)
+            ) {
+                continue;
+            }
+            let mut changed = Vec::new();
+            for path in &event.paths {
+                if path.is_dir() {
+                    if matches!(event.kind, EventKind::Create(_)) {
+                        // Files written before the new directory's watch existed raise no event.
+                        changed.extend(self.watch_tree(path));
+                    }
+                    continue;
+                }
+                if matches!(event.kind, EventKind::Create(CreateKind::Folder)) {
+                    continue;
+                }
+                if let Some(relative) = self.visible_path(path) {
+                    changed.push(relative);
+                }
+            }
+            if !changed.is_empty() {
+                return Some(Ok(changed));
+            }
+        }
+    }
+
+    /// Watches `dir` and every non-ignored directory under it, returning the files found there.
+    fn watch_tree(&mut self, dir: &Path) -> Vec<String> {
+        let mut files = Vec::new();
+        let walker = ignore::WalkBuilder::new(dir)
+            .hidden(false)
+            .filter_entry(|entry| entry.file_name() != ".git")
+            .build();
+        for entry in walker.flatten() {
+            let Some(relative) = self.visible_path(entry.path()) else {
+                continue;
+            };
+            if entry.file_type().is_some_and(|kind| kind.is_dir()) {
+                let _ = self
+                    .watcher
+                    .watch(entry.path(), RecursiveMode::NonRecursive);
+            } else {
+                files.push(relative);
+            }
+        }
+        files
+    }
+
+    /// The repo-relative form of `path` when git would report it: outside `.git/` and
+    /// `.vizier/`, and not matched by the repository's ignore rules.
+    fn visible_path(&self, path: &Path) -> Option<String> {
+        let relative = path.strip_prefix(&self.root).ok()?;
+        if relative.as_os_str().is_empty() {
+            return Some(String::new());
+        }
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        if relative == ".git" || relative.starts_with(".git/") || is_vizier_path(&relative) {
+            return None;
+        }
+        if self
+            .repo
+            .is_path_ignored(Path::new(&relative))
+            .unwrap_or(false)
+        {
+            return None;
+        }
+        Some(relative)
+    }
+}
+
+/// Collects changed paths and releases them as one batch once no new change has been seen for
+/// the settle period.
+#[derive(Debug)]
+pub struct ChangeBatcher {
+    settle: Duration,
+    changed: BTreeSet<String>,
+    last_change: Option<Instant>,
+}
+
+impl ChangeBatcher {
+    pub fn new(settle: Duration) -> Self {
+        Self {
+            settle,
+            changed: BTreeSet::new(),
+            last_change: None,
+        }
+    }
+
+    /// Adds changed paths; any new path or repeated change restarts the settle period.
+    pub fn record(&mut self, paths: impl IntoIterator<Item = String>, now: Instant) {
+        let mut paths = paths.into_iter().peekable();
+        if paths.peek().is_none() {
+            return;
+        }
+        self.changed.extend(paths);
+        self.last_change = Some(now);
+    }
+
+    /// When the pending batch settles, if anything is pending.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.last_change.map(|changed_at| changed_at + self.settle)
+    }
+
+    /// Releases the pending batch once the settle period has passed since the last change.
+    pub fn take_settled(&mut self, now: Instant) -> Option<Vec<String>> {
+        if self.deadline().is_none_or(|deadline| now < deadline) {
+            return None;
+        }
+        self.last_change = None;
+        Some(std::mem::take(&mut self.changed).into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(entries: &[&str]) -> Vec<String> {
+        entries.iter().map(|path| path.to_string()).collect()
+    }
+
+    #[test]
+    fn batcher_waits_for_changes_to_settle() {
+        let start = Instant::now();
+        let settle = Duration::from_secs(10);
+        let mut batcher = ChangeBatcher::new(settle);
+
+        batcher.record(paths(&[]), start);
+        assert_eq!(batcher.take_settled(start + settle), None);
+        batcher.record(paths(&["src/a.rs"]), start + Duration::from_secs(1));
+        assert_eq!(batcher.take_settled(start + Duration::from_secs(8)), None);
+        batcher.record(
+            paths(&["src/a.rs", "src/b.rs"]),
+            start + Duration::from_secs(8),
+        );
+        assert_eq!(
+            batcher.take_settled(start + Duration::from_secs(12)),
+            None,
+            "a new change restarts the settle period"
+        );
+        assert_eq!(
+            batcher.take_settled(start + Duration::from_secs(18)),
+            Some(paths(&["src/a.rs", "src/b.rs"]))
+        );
+        assert_eq!(
+            batcher.take_settled(start + Duration::from_secs(40)),
+            None,
+            "nothing changed since the last batch"
+        );
+    }
+
+    #[tokio::test]
+    async fn watcher_reports_visible_paths_and_skips_ignored_ones() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        Repository::init(&root).unwrap();
+        std::fs::write(root.join(".gitignore"), "target/\n").unwrap();
+        std::fs::create_dir_all(root.join("target")).unwrap();
+        std::fs::create_dir_all(root.join(".vizier")).unwrap();
+        let mut watcher = WorktreeWatcher::start(&root).unwrap();
+
+        std::fs::write(root.join("target/out.o"), "ignored").unwrap();
+        std::fs::write(root.join(".vizier/state.json"), "{}").unwrap();
+        std::fs::create_dir_all(root.join("src/nested")).unwrap();
+        std::fs::write(root.join("src/nested/lib.rs"), "fn main() {}").unwrap();
+
+        let mut seen = BTreeSet::new();
+        while !seen.contains("src/nested/lib.rs") {
+            let changes = tokio::time::timeout(Duration::from_secs(5), watcher.next_changes())
+                .await
+                .expect("a change within five seconds")
+                .expect("watcher running")
+                .expect("watch event");
+            seen.extend(changes);
+        }
+        assert!(
+            seen.iter()
+                .all(|path| !path.starts_with("target") && !path.starts_with(".vizier")),
+            "{seen:?}"
+        );
+    }
+}
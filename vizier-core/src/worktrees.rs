@@ -0,0 +1,115 @@
+//! Where Vizier checks out the worktrees it creates: plan workspaces from `vizier cd` and the
+//! throwaway worktrees workflow nodes, merge gates, and integrations run in.
+//!
+//! By default they live under `.vizier/tmp-worktrees` in the repository. `[worktrees] dir` (or
+//! `--worktree-dir`) moves them to an external directory, with one subdirectory per repository
+//! so several clones can share it. Repositories on slow network mounts, or whose IDE indexers
+//! descend into nested worktrees, benefit most.
+
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+
+use crate::config;
+
+/// Worktree root inside the repository when `[worktrees] dir` is unset.
+pub const DEFAULT_WORKTREES_DIR: &str = ".vizier/tmp-worktrees";
+
+/// The directory new worktrees for `project_root` are created under.
+pub fn worktrees_root(project_root: &Path) -> PathBuf {
+    match config::get_config().worktrees.dir {
+        Some(dir) => external_root(project_root, &dir),
+        None => project_root.join(DEFAULT_WORKTREES_DIR),
+    }
+}
+
+/// `dir` (absolute, `~/`-relative, or relative to the repository root) plus this repository's
+/// namespace: its directory name and a hash of its canonical path.
+pub fn external_root(project_root: &Path, dir: &Path) -> PathBuf {
+    let base = match dir.strip_prefix("~") {
+        Ok(home_relative) => match std::env::var_os("HOME") {
+            Some(home) => PathBuf::from(home).join(home_relative),
+            None => dir.to_path_buf(),
+        },
+        Err(_) if dir.is_relative() => project_root.join(dir),
+        Err(_) => dir.to_path_buf(),
+    };
+    base.join(repo_namespace(project_root))
+}
+
+fn repo_namespace(project_root: &Path) -> String {
+    let canonical = project_root
+        .canonicalize()
+        .unwrap_or_else(|_| project_root.to_path_buf());
+    let digest = Sha256::digest(canonical.to_string_lossy().as_bytes());
+    let hash = digest[..6]
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>();
+    let name = canonical
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "repo".to_string());
+    format!("{name}-{hash}")
+}
+
+/// Whether `path` sits under a root Vizier creates worktrees in: the configured root or, for
+/// worktrees recorded before a relocation, `.vizier/tmp-worktrees`.
+pub fn is_managed_worktree_path(project_root: &Path, path: &Path) -> bool {
+    path.starts_with(worktrees_root(project_root))
+        || path.starts_with(project_root.join(DEFAULT_WORKTREES_DIR))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn external_roots_are_namespaced_per_repository() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let alpha = temp.path().join("alpha");
+        let beta = temp.path().join("nested").join("alpha");
+        std::fs::create_dir_all(&alpha).expect("alpha");
+        std::fs::create_dir_all(&beta).expect("beta");
+        let shared = Path::new("/var/tmp/vizier");
+
+        let alpha_root = external_root(&alpha, shared);
+        let beta_root = external_root(&beta, shared);
+        assert!(alpha_root.starts_with(shared));
+        assert_ne!(alpha_root, beta_root);
+        assert!(
+            alpha_root
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("alpha-")),
+            "{}",
+            alpha_root.display()
+        );
+        assert_eq!(alpha_root, external_root(&alpha, shared));
+        assert!(external_root(&alpha, Path::new("../worktrees")).starts_with(alpha.join("..")));
+    }
+
+    #[test]
+    fn managed_paths_include_the_legacy_root_after_relocation() {
+        let _guard = config::test_config_lock().lock().unwrap();
+        let original = config::get_config();
+        let temp = tempfile::tempdir().expect("tempdir");
+        let root = temp.path();
+
+        let mut cfg = original.clone();
+        cfg.worktrees.dir = Some(PathBuf::from("/var/tmp/vizier"));
+        config::set_config(cfg);
+        let external = worktrees_root(root);
+        assert!(external.starts_with("/var/tmp/vizier"));
+        assert!(is_managed_worktree_path(root, &external.join("merge-1")));
+        assert!(is_managed_worktree_path(
+            root,
+            &root.join(".vizier/tmp-worktrees/merge-1")
+        ));
+        assert!(!is_managed_worktree_path(root, Path::new("/var/tmp/other")));
+
+        config::set_config(original);
+        assert_eq!(worktrees_root(root), root.join(DEFAULT_WORKTREES_DIR));
+    }
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
 use crate::config::AgentOutputHandling;
@@ -72,10 +73,13 @@ impl AgentRunRecord {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum CommitDisposition {
     Auto,
     Hold,
+    /// Capture edits as a patch file and restore the working tree instead of committing.
+    Patch,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -159,3 +163,85 @@ impl AuditResult {
         self.pending()
     }
 }
+
+pub const COMMAND_OUTCOME_SCHEMA: &str = "vizier.command_outcome.v1";
+
+/// Exit code for invocations stopped by SIGINT/SIGTERM (the shell convention for SIGINT).
+pub const INTERRUPTED_EXIT_CODE: i32 = 130;
+
+/// Normalized result of one `vizier` invocation, written as `outcome.json` beside the session
+/// log so wrappers never have to scrape human-readable summaries.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct CommandOutcome {
+    pub schema: String,
+    pub session_id: String,
+    pub command: String,
+    pub argv: Vec<String>,
+    pub flags: BTreeMap<String, Vec<String>>,
+    pub agent: CommandOutcomeAgent,
+    pub commits: Vec<CommandOutcomeCommit>,
+    pub gates: Vec<CommandOutcomeGate>,
+    #[serde(default)]
+    pub phases: Vec<CommandOutcomePhase>,
+    pub exit: CommandOutcomeExit,
+    pub started_at: String,
+    pub finished_at: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CommandOutcomeAgent {
+    pub selector: String,
+    pub backend: String,
+    pub label: String,
+    pub command: Vec<String>,
+    pub invoked: bool,
+    pub exit_code: Option<i32>,
+    pub duration_ms: Option<u128>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CommandOutcomeCommit {
+    pub branch: String,
+    pub oid: String,
+    pub summary: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CommandOutcomeGate {
+    pub job_id: Option<String>,
+    pub node_id: String,
+    pub policy: String,
+    pub outcome: String,
+    pub exit_code: Option<i32>,
+    pub summary: Option<String>,
+}
+
+/// Wall-clock time spent in one named phase (`worktree setup`, `agent run`, `gate`, `commit`).
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CommandOutcomePhase {
+    pub name: String,
+    pub label: Option<String>,
+    pub duration_ms: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CommandOutcomeExit {
+    pub status: String,
+    pub code: i32,
+    pub error: Option<String>,
+}
+
+impl CommandOutcomeExit {
+    pub fn from_code(code: i32, error: Option<String>) -> Self {
+        Self {
+            status: match code {
+                0 => "succeeded",
+                INTERRUPTED_EXIT_CODE => "cancelled",
+                _ => "failed",
+            }
+            .to_string(),
+            code,
+            error,
+        }
+    }
+}
@@ -29,6 +29,83 @@ impl Default for ApproveStopConditionConfig {
         Self {
             script: None,
             retries: 3,
+            conditions: None,
+            tests: None,
+        }
+    }
+}
+
+impl Default for DraftConfig {
+    fn default() -> Self {
+        Self {
+            max_steps: 12,
+            max_files: 15,
+            auto_push: false,
+        }
+    }
+}
+
+impl Default for ChangelogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dir: ".changelog".to_string(),
+            style: ChangelogStyle::KeepAChangelog,
+            default_type: "changed".to_string(),
+            file: "CHANGELOG.md".to_string(),
+        }
+    }
+}
+
+impl Default for BranchesConfig {
+    fn default() -> Self {
+        Self {
+            prefix: "draft/".to_string(),
+            target: None,
+        }
+    }
+}
+
+impl Default for RemoteConfig {
+    fn default() -> Self {
+        Self {
+            host: None,
+            path: None,
+            git_remote: "origin".to_string(),
+            vizier: "vizier".to_string(),
+            ssh: vec!["ssh".to_string()],
+        }
+    }
+}
+
+impl Default for EmailNotificationConfig {
+    fn default() -> Self {
+        Self {
+            smtp_host: None,
+            smtp_port: 587,
+            tls: SmtpTls::StartTls,
+            username: None,
+            password_env: "VIZIER_SMTP_PASSWORD".to_string(),
+            from: "vizier@localhost".to_string(),
+            to: Vec::new(),
+            digest_hours: 24,
+            events: HashMap::from([
+                (NotificationEvent::Draft, NotificationDelivery::Digest),
+                (NotificationEvent::Merge, NotificationDelivery::Digest),
+                (
+                    NotificationEvent::GateFailure,
+                    NotificationDelivery::Immediate,
+                ),
+                (NotificationEvent::JobFailure, NotificationDelivery::Digest),
+            ]),
+        }
+    }
+}
+
+impl Default for MockAgentConfig {
+    fn default() -> Self {
+        Self {
+            fixtures: std::path::PathBuf::from(".vizier/mock-agent"),
         }
     }
 }
@@ -38,8 +115,15 @@ impl Default for MergeConfig {
         Self {
             squash_default: true,
             squash_mainline: None,
+            strategy: None,
+            squash_granularity: SquashGranularity::Plan,
             cicd_gate: MergeCicdGateConfig::default(),
+            gates: Vec::new(),
+            auto_revert: false,
+            protected: Vec::new(),
             conflicts: MergeConflictsConfig::default(),
+            commit_template: None,
+            require_owner_ack: false,
         }
     }
 }
@@ -132,6 +216,8 @@ impl Default for DisplayListConfig {
                 "Plan".to_string(),
                 "Branch".to_string(),
                 "Summary".to_string(),
+                "Labels".to_string(),
+                "Lineage".to_string(),
             ],
             job_fields: vec![
                 "Job".to_string(),
@@ -160,6 +246,9 @@ impl Default for DisplayJobsListConfig {
                 "Job".to_string(),
                 "Status".to_string(),
                 "Created".to_string(),
+                "Priority".to_string(),
+                "Queue position".to_string(),
+                "Estimated start".to_string(),
                 "Wait".to_string(),
                 "Waited on".to_string(),
                 "Dependencies".to_string(),
@@ -231,6 +320,8 @@ impl Default for DisplayJobsShowConfig {
                 "Agent label".to_string(),
                 "Agent command".to_string(),
                 "Agent exit".to_string(),
+                "Cost estimate".to_string(),
+                "Cost actual".to_string(),
                 "Cancel cleanup".to_string(),
                 "Cancel cleanup error".to_string(),
                 "Config snapshot".to_string(),
@@ -250,6 +341,33 @@ impl Default for BackgroundConfig {
     }
 }
 
+impl Default for WorkflowDriftConfig {
+    fn default() -> Self {
+        Self {
+            max_commits_behind: 50,
+            max_age_days: 14,
+            strict: false,
+        }
+    }
+}
+
+impl Default for WorkflowDiffContextConfig {
+    fn default() -> Self {
+        Self {
+            max_bytes: 40_000,
+            chunk_bytes: 12_000,
+            raw_hunk_bytes: 16_000,
+            summarizer: DiffSummarizer::Agent,
+        }
+    }
+}
+
+impl Default for WorkflowPromptBudgetConfig {
+    fn default() -> Self {
+        Self { max_bytes: 480_000 }
+    }
+}
+
 impl Default for WorkflowTemplateConfig {
     fn default() -> Self {
         Self {
@@ -288,6 +406,7 @@ impl Default for Config {
         let selector = default_selector_for_backend(BackendKind::Agent).to_string();
         Self {
             no_session: false,
+            profile: None,
             agent_selector: selector.clone(),
             backend: backend_kind_for_selector(&selector),
             agent_runtime: AgentRuntimeOptions::default(),
@@ -299,6 +418,22 @@ impl Default for Config {
             commits: CommitConfig::default(),
             display: DisplaySettings::default(),
             jobs: JobsConfig::default(),
+            sessions: SessionsConfig::default(),
+            tmp: TmpConfig::default(),
+            search: SearchConfig::default(),
+            agent_stall: AgentStallConfig::default(),
+            context: ContextConfig::default(),
+            worktrees: WorktreesConfig::default(),
+            hooks: HooksConfig::default(),
+            forge: ForgeConfig::default(),
+            output: OutputConfig::default(),
+            draft: DraftConfig::default(),
+            mock_agent: MockAgentConfig::default(),
+            branches: BranchesConfig::default(),
+            remote: RemoteConfig::default(),
+            notifications: NotificationsConfig::default(),
+            command_audit: CommandAuditConfig::default(),
+            changelog: ChangelogConfig::default(),
             workflow: WorkflowConfig::default(),
             commands: HashMap::new(),
             agent_defaults: AgentOverrides::default(),
@@ -9,12 +9,36 @@ impl ApproveStopConditionConfig {
         if let Some(retries) = layer.retries {
             self.retries = retries;
         }
+
+        if let Some(conditions) = layer.conditions.as_ref() {
+            self.conditions = Some(conditions.clone());
+        }
+
+        if let Some(tests) = layer.tests.as_ref() {
+            self.tests = Some(tests.clone());
+        }
+    }
+}
+
+impl ApproveCostConfig {
+    fn apply_layer(&mut self, layer: &ApproveCostLayer) {
+        if let Some(rate) = layer.input_micro_usd_per_mtok {
+            self.input_micro_usd_per_mtok = rate;
+        }
+
+        if let Some(rate) = layer.output_micro_usd_per_mtok {
+            self.output_micro_usd_per_mtok = rate;
+        }
     }
 }
 
 impl ApproveConfig {
     fn apply_layer(&mut self, layer: &ApproveLayer) {
         self.stop_condition.apply_layer(&layer.stop_condition);
+        self.cost.apply_layer(&layer.cost);
+        if let Some(generate_tests) = layer.generate_tests {
+            self.generate_tests = generate_tests;
+        }
     }
 }
 
@@ -31,6 +55,18 @@ impl MergeConfig {
         self.cicd_gate.apply_layer(&layer.cicd_gate);
         self.conflicts.apply_layer(&layer.conflicts);
 
+        if let Some(gates) = layer.gates.as_ref() {
+            self.gates = gates.clone();
+        }
+
+        if let Some(auto_revert) = layer.auto_revert {
+            self.auto_revert = auto_revert;
+        }
+
+        if let Some(protected) = layer.protected.as_ref() {
+            self.protected = protected.clone();
+        }
+
         if let Some(default_squash) = layer.squash_default {
             self.squash_default = default_squash;
         }
@@ -38,6 +74,22 @@ impl MergeConfig {
         if let Some(mainline) = layer.squash_mainline {
             self.squash_mainline = Some(mainline);
         }
+
+        if let Some(strategy) = layer.strategy {
+            self.strategy = Some(strategy);
+        }
+
+        if let Some(granularity) = layer.squash_granularity {
+            self.squash_granularity = granularity;
+        }
+
+        if let Some(template) = layer.commit_template.as_ref() {
+            self.commit_template = Some(template.clone());
+        }
+
+        if let Some(require) = layer.require_owner_ack {
+            self.require_owner_ack = require;
+        }
     }
 }
 
@@ -281,6 +333,9 @@ impl DisplayListsConfig {
 impl DisplaySettings {
     fn apply_layer(&mut self, layer: &DisplayLayer) {
         self.lists.apply_layer(&layer.lists);
+        if let Some(log) = layer.log.as_ref() {
+            self.log = Some(log.clone());
+        }
     }
 }
 
@@ -295,6 +350,196 @@ impl JobsCancelConfig {
 impl JobsConfig {
     fn apply_layer(&mut self, layer: &JobsLayer) {
         self.cancel.apply_layer(&layer.cancel);
+        if let Some(max_concurrent) = layer.max_concurrent {
+            self.max_concurrent = max_concurrent;
+        }
+    }
+}
+
+impl SessionsConfig {
+    fn apply_layer(&mut self, layer: &SessionsLayer) {
+        if let Some(keep_days) = layer.keep_days {
+            self.keep_days = keep_days;
+        }
+        if let Some(keep_last) = layer.keep_last {
+            self.keep_last = keep_last;
+        }
+        if let Some(compress) = layer.compress {
+            self.compress = compress;
+        }
+    }
+}
+
+impl AgentStallConfig {
+    fn apply_layer(&mut self, layer: &AgentStallLayer) {
+        if let Some(warn_after_secs) = layer.warn_after_secs {
+            self.warn_after_secs = warn_after_secs;
+        }
+        if let Some(kill_after_secs) = layer.kill_after_secs {
+            self.kill_after_secs = kill_after_secs;
+        }
+        if let Some(retries) = layer.retries {
+            self.retries = retries;
+        }
+    }
+}
+
+impl ContextConfig {
+    // Layers add to the list rather than replace it, so a repo config cannot release a path a
+    // global config withholds.
+    fn apply_layer(&mut self, layer: &ContextLayer) {
+        for pattern in layer.never_send.iter().flatten() {
+            if !self.never_send.contains(pattern) {
+                self.never_send.push(pattern.clone());
+            }
+        }
+    }
+}
+
+impl WorktreesConfig {
+    fn apply_layer(&mut self, layer: &WorktreesLayer) {
+        if let Some(dir) = layer.dir.as_ref() {
+            self.dir = (!dir.as_os_str().is_empty()).then(|| dir.clone());
+        }
+    }
+}
+
+impl ForgeConfig {
+    fn apply_layer(&mut self, layer: &ForgeLayer) {
+        if let Some(cli) = layer.cli {
+            self.cli = cli;
+        }
+    }
+}
+
+impl HooksConfig {
+    fn apply_layer(&mut self, layer: &HooksLayer) {
+        for (hook, script) in &layer.scripts {
+            if script.trim().is_empty() {
+                self.scripts.remove(hook);
+            } else {
+                self.scripts.insert(hook.clone(), script.clone());
+            }
+        }
+    }
+}
+
+impl DraftConfig {
+    fn apply_layer(&mut self, layer: &DraftLayer) {
+        if let Some(max_steps) = layer.max_steps {
+            self.max_steps = max_steps;
+        }
+        if let Some(max_files) = layer.max_files {
+            self.max_files = max_files;
+        }
+        if let Some(auto_push) = layer.auto_push {
+            self.auto_push = auto_push;
+        }
+    }
+}
+
+impl BranchesConfig {
+    fn apply_layer(&mut self, layer: &BranchesLayer) {
+        if let Some(prefix) = layer.prefix.as_ref() {
+            self.prefix = prefix.clone();
+        }
+        if let Some(target) = layer.target.as_ref() {
+            self.target = (!target.is_empty()).then(|| target.clone());
+        }
+    }
+}
+
+impl RemoteConfig {
+    fn apply_layer(&mut self, layer: &RemoteLayer) {
+        if let Some(host) = layer.host.as_ref() {
+            self.host = Some(host.clone());
+        }
+        if let Some(path) = layer.path.as_ref() {
+            self.path = Some(path.clone());
+        }
+        if let Some(git_remote) = layer.git_remote.as_ref() {
+            self.git_remote = git_remote.clone();
+        }
+        if let Some(vizier) = layer.vizier.as_ref() {
+            self.vizier = vizier.clone();
+        }
+        if let Some(ssh) = layer.ssh.as_ref() {
+            self.ssh = ssh.clone();
+        }
+    }
+}
+
+impl EmailNotificationConfig {
+    fn apply_layer(&mut self, layer: &EmailNotificationLayer) {
+        if let Some(host) = layer.smtp_host.as_ref() {
+            self.smtp_host = Some(host.clone());
+        }
+        if let Some(port) = layer.smtp_port {
+            self.smtp_port = port;
+        }
+        if let Some(tls) = layer.tls {
+            self.tls = tls;
+        }
+        if let Some(username) = layer.username.as_ref() {
+            self.username = Some(username.clone());
+        }
+        if let Some(password_env) = layer.password_env.as_ref() {
+            self.password_env = password_env.clone();
+        }
+        if let Some(from) = layer.from.as_ref() {
+            self.from = from.clone();
+        }
+        if let Some(to) = layer.to.as_ref() {
+            self.to = to.clone();
+        }
+        if let Some(hours) = layer.digest_hours {
+            self.digest_hours = hours;
+        }
+        for (event, delivery) in &layer.events {
+            self.events.insert(*event, *delivery);
+        }
+    }
+}
+
+impl CommandAuditConfig {
+    fn apply_layer(&mut self, layer: &CommandAuditLayer) {
+        if let Some(enabled) = layer.enabled {
+            self.enabled = enabled;
+        }
+        if let Some(allow) = layer.allow.as_ref() {
+            self.allow = allow.clone();
+        }
+        if let Some(abort) = layer.abort.as_ref() {
+            self.abort = abort.clone();
+        }
+    }
+}
+
+impl ChangelogConfig {
+    fn apply_layer(&mut self, layer: &ChangelogLayer) {
+        if let Some(enabled) = layer.enabled {
+            self.enabled = enabled;
+        }
+        if let Some(dir) = layer.dir.as_ref() {
+            self.dir = dir.clone();
+        }
+        if let Some(style) = layer.style {
+            self.style = style;
+        }
+        if let Some(default_type) = layer.default_type.as_ref() {
+            self.default_type = default_type.clone();
+        }
+        if let Some(file) = layer.file.as_ref() {
+            self.file = file.clone();
+        }
+    }
+}
+
+impl MockAgentConfig {
+    fn apply_layer(&mut self, layer: &MockAgentLayer) {
+        if let Some(fixtures) = layer.fixtures.as_ref() {
+            self.fixtures = fixtures.clone();
+        }
     }
 }
 
@@ -357,6 +602,53 @@ impl WorkflowConfig {
         self.background.apply_layer(&layer.background);
         self.global_workflows.apply_layer(&layer.global_workflows);
         self.templates.apply_layer(&layer.templates);
+        self.drift.apply_layer(&layer.drift);
+        self.diff_context.apply_layer(&layer.diff_context);
+        self.prompt_budget.apply_layer(&layer.prompt_budget);
+    }
+}
+
+impl WorkflowPromptBudgetConfig {
+    fn apply_layer(&mut self, layer: &WorkflowPromptBudgetLayer) {
+        if let Some(max_bytes) = layer.max_bytes {
+            self.max_bytes = max_bytes;
+        }
+    }
+}
+
+impl WorkflowDiffContextConfig {
+    fn apply_layer(&mut self, layer: &WorkflowDiffContextLayer) {
+        if let Some(max_bytes) = layer.max_bytes {
+            self.max_bytes = max_bytes;
+        }
+
+        if let Some(chunk_bytes) = layer.chunk_bytes {
+            self.chunk_bytes = chunk_bytes;
+        }
+
+        if let Some(raw_hunk_bytes) = layer.raw_hunk_bytes {
+            self.raw_hunk_bytes = raw_hunk_bytes;
+        }
+
+        if let Some(summarizer) = layer.summarizer {
+            self.summarizer = summarizer;
+        }
+    }
+}
+
+impl WorkflowDriftConfig {
+    fn apply_layer(&mut self, layer: &WorkflowDriftLayer) {
+        if let Some(max_commits_behind) = layer.max_commits_behind {
+            self.max_commits_behind = max_commits_behind;
+        }
+
+        if let Some(max_age_days) = layer.max_age_days {
+            self.max_age_days = max_age_days;
+        }
+
+        if let Some(strict) = layer.strict {
+            self.strict = strict;
+        }
     }
 }
 
@@ -378,10 +670,20 @@ impl MergeCicdGateConfig {
 
 impl Config {
     pub fn from_layers(layers: &[ConfigLayer]) -> Self {
+        Self::from_layers_with_profile(layers, None)
+    }
+
+    /// Merge `layers` in order; when `profile` is set, each layer's matching
+    /// `[profiles.<name>]` overlay is applied right after that layer.
+    pub fn from_layers_with_profile(layers: &[ConfigLayer], profile: Option<&str>) -> Self {
         let mut config = Self::default();
         for layer in layers {
             config.apply_layer(layer);
+            if let Some(overlay) = profile.and_then(|name| layer.profiles.get(name)) {
+                config.apply_layer(overlay);
+            }
         }
+        config.profile = profile.map(str::to_string);
         config
     }
 
@@ -401,12 +703,45 @@ impl Config {
         if let Some(commands) = layer.review.checks.as_ref() {
             self.review.checks.commands = commands.clone();
         }
+        if let Some(jobs) = layer.review.check_jobs {
+            self.review.checks.jobs = jobs;
+        }
+        if let Some(max_iterations) = layer.review.fix_loop_max_iterations {
+            self.review.fix_loop.max_iterations = max_iterations;
+        }
+        if let Some(tools) = layer.review.tools {
+            self.review.tools = tools;
+        }
 
         self.merge.apply_layer(&layer.merge);
         self.release.apply_layer(&layer.release);
         self.commits.apply_layer(&layer.commits);
         self.display.apply_layer(&layer.display);
         self.jobs.apply_layer(&layer.jobs);
+        self.sessions.apply_layer(&layer.sessions);
+        if let Some(keep_days) = layer.tmp.keep_days {
+            self.tmp.keep_days = keep_days;
+        }
+        if let Some(embedder) = layer.search.embedder.as_ref() {
+            self.search.embedder = embedder.clone();
+        }
+        self.agent_stall.apply_layer(&layer.agent_stall);
+        self.context.apply_layer(&layer.context);
+        self.worktrees.apply_layer(&layer.worktrees);
+        self.hooks.apply_layer(&layer.hooks);
+        self.forge.apply_layer(&layer.forge);
+        if let Some(language) = layer.output.language.as_ref() {
+            self.output.language = language.clone();
+        }
+        self.draft.apply_layer(&layer.draft);
+        self.mock_agent.apply_layer(&layer.mock_agent);
+        self.branches.apply_layer(&layer.branches);
+        self.remote.apply_layer(&layer.remote);
+        self.notifications
+            .email
+            .apply_layer(&layer.notifications.email);
+        self.command_audit.apply_layer(&layer.command_audit);
+        self.changelog.apply_layer(&layer.changelog);
         self.workflow.apply_layer(&layer.workflow);
 
         for (alias, selector) in layer.commands.iter() {
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 
 use super::{PromptKind, PromptOrigin, PromptSelection, SystemPrompt};
@@ -7,6 +7,8 @@ use super::{PromptKind, PromptOrigin, PromptSelection, SystemPrompt};
 pub enum BackendKind {
     Agent,
     Gemini,
+    /// Replays canned responses from `[mock_agent] fixtures`; no agent process is spawned.
+    Mock,
 }
 
 impl BackendKind {
@@ -14,12 +16,16 @@ impl BackendKind {
         match value.to_ascii_lowercase().as_str() {
             "agent" | "codex" => Some(Self::Agent),
             "gemini" => Some(Self::Gemini),
+            "mock" => Some(Self::Mock),
             _ => None,
         }
     }
 
     pub fn requires_agent_runner(&self) -> bool {
-        matches!(self, BackendKind::Agent | BackendKind::Gemini)
+        matches!(
+            self,
+            BackendKind::Agent | BackendKind::Gemini | BackendKind::Mock
+        )
     }
 }
 
@@ -28,6 +34,7 @@ impl std::fmt::Display for BackendKind {
         match self {
             BackendKind::Agent => write!(f, "agent"),
             BackendKind::Gemini => write!(f, "gemini"),
+            BackendKind::Mock => write!(f, "mock"),
         }
     }
 }
@@ -311,6 +318,32 @@ pub struct AgentOverrides {
     pub agent_runtime: Option<AgentRuntimeOverride>,
     pub documentation: DocumentationSettingsOverride,
     pub prompt_overrides: HashMap<PromptKind, PromptOverrides>,
+    pub env: AgentEnvSettings,
+}
+
+/// Variables added to the agent child environment from `[agents.<scope>.env]` and
+/// `[agents.<scope>] env_file`. Values may reference `${env:NAME}` or `${file:PATH}`; those are
+/// resolved when the agent is spawned.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AgentEnvSettings {
+    pub vars: BTreeMap<String, String>,
+    pub env_file: Option<PathBuf>,
+}
+
+impl AgentEnvSettings {
+    pub fn is_empty(&self) -> bool {
+        self.vars.is_empty() && self.env_file.is_none()
+    }
+
+    /// Narrower scopes add to (and override keys of) broader ones; the last `env_file` wins.
+    pub fn merge(&mut self, other: &AgentEnvSettings) {
+        for (key, value) in &other.vars {
+            self.vars.insert(key.clone(), value.clone());
+        }
+        if let Some(env_file) = other.env_file.as_ref() {
+            self.env_file = Some(env_file.clone());
+        }
+    }
 }
 
 /// Prompt-level overrides live under `[agents.<scope>.prompts.<kind>]`,
@@ -336,6 +369,7 @@ impl AgentOverrides {
             && self.agent_runtime.is_none()
             && self.documentation.is_empty()
             && self.prompt_overrides.is_empty()
+            && self.env.is_empty()
     }
 
     pub fn merge(&mut self, other: &AgentOverrides) {
@@ -356,6 +390,8 @@ impl AgentOverrides {
         for (kind, overrides) in other.prompt_overrides.iter() {
             self.prompt_overrides.insert(*kind, overrides.clone());
         }
+
+        self.env.merge(&other.env);
     }
 }
 
@@ -462,8 +498,13 @@ impl AgentRuntimeOptions {
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum AgentRuntimeResolution {
-    BundledShim { label: String, path: PathBuf },
+    BundledShim {
+        label: String,
+        path: PathBuf,
+    },
     ProvidedCommand,
+    /// The `mock` backend answers from fixtures instead of running a command.
+    Mock,
 }
 
 #[derive(Clone, Debug)]
@@ -485,6 +526,8 @@ pub struct PromptTemplate {
 #[derive(Clone)]
 pub struct Config {
     pub no_session: bool,
+    /// Named `[profiles.<name>]` overlay selected via `--profile`/`VIZIER_PROFILE`, if any.
+    pub profile: Option<String>,
     pub agent_selector: String,
     pub backend: BackendKind,
     pub agent_runtime: AgentRuntimeOptions,
@@ -496,6 +539,22 @@ pub struct Config {
     pub commits: CommitConfig,
     pub display: DisplaySettings,
     pub jobs: JobsConfig,
+    pub sessions: SessionsConfig,
+    pub tmp: TmpConfig,
+    pub search: SearchConfig,
+    pub agent_stall: AgentStallConfig,
+    pub context: ContextConfig,
+    pub worktrees: WorktreesConfig,
+    pub hooks: HooksConfig,
+    pub forge: ForgeConfig,
+    pub output: OutputConfig,
+    pub draft: DraftConfig,
+    pub mock_agent: MockAgentConfig,
+    pub branches: BranchesConfig,
+    pub remote: RemoteConfig,
+    pub notifications: NotificationsConfig,
+    pub command_audit: CommandAuditConfig,
+    pub changelog: ChangelogConfig,
     pub workflow: WorkflowConfig,
     pub commands: HashMap<CommandAlias, TemplateSelector>,
     pub agent_defaults: AgentOverrides,
@@ -509,21 +568,74 @@ pub struct Config {
 pub struct ApproveStopConditionConfig {
     pub script: Option<PathBuf>,
     pub retries: u32,
+    /// Named conditions Vizier evaluates itself (see [`crate::stop_condition`]); a list in the
+    /// config file is stored as the conjunction of its entries.
+    pub conditions: Option<String>,
+    /// Command whose exit status decides the `tests_pass` condition.
+    pub tests: Option<String>,
 }
 
 #[derive(Clone, Default)]
 pub struct ApproveConfig {
     pub stop_condition: ApproveStopConditionConfig,
+    pub cost: ApproveCostConfig,
+    /// Ask the agent for tests covering the plan's acceptance criteria once the implementation
+    /// passes its stop gate, committed separately as `tests:`.
+    pub generate_tests: bool,
 }
 
-#[derive(Clone, Default)]
+/// Per-million-token prices behind the approve cost estimate, in micro-dollars so config
+/// layers stay `Eq` (`3.0` USD is `3_000_000`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ApproveCostConfig {
+    pub input_micro_usd_per_mtok: u64,
+    pub output_micro_usd_per_mtok: u64,
+}
+
+impl Default for ApproveCostConfig {
+    fn default() -> Self {
+        Self {
+            input_micro_usd_per_mtok: 3_000_000,
+            output_micro_usd_per_mtok: 15_000_000,
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct ReviewConfig {
     pub checks: ReviewChecksConfig,
+    pub fix_loop: ReviewFixLoopConfig,
+    /// Offer review agents the repo-navigation tools (`vizier tool file|blame|search`).
+    pub tools: bool,
+}
+
+impl Default for ReviewConfig {
+    fn default() -> Self {
+        Self {
+            checks: ReviewChecksConfig::default(),
+            fix_loop: ReviewFixLoopConfig::default(),
+            tools: true,
+        }
+    }
 }
 
 #[derive(Clone, Default)]
 pub struct ReviewChecksConfig {
     pub commands: Vec<String>,
+    /// Maximum checks run concurrently; `0` uses the available parallelism.
+    pub jobs: u32,
+}
+
+#[derive(Clone)]
+pub struct ReviewFixLoopConfig {
+    /// Most critique→fix→recheck iterations a `--fix-loop` review runs before giving up.
+    pub max_iterations: u32,
+}
+
+impl Default for ReviewFixLoopConfig {
+    fn default() -> Self {
+        Self { max_iterations: 3 }
+    }
 }
 
 #[derive(Clone, Default)]
@@ -534,9 +646,23 @@ pub struct MergeConflictsConfig {
 #[derive(Clone)]
 pub struct MergeConfig {
     pub cicd_gate: MergeCicdGateConfig,
+    /// Ordered `[[merge.gates]]`, each labelled with the stage it runs in.
+    pub gates: Vec<MergeGateConfig>,
+    /// Revert the merge commit when a post-merge gate fails.
+    pub auto_revert: bool,
+    /// Target branch patterns (`*` wildcards) that are never merged into locally.
+    pub protected: Vec<String>,
     pub conflicts: MergeConflictsConfig,
     pub squash_default: bool,
     pub squash_mainline: Option<u32>,
+    /// `[merge] strategy`; when unset, the merge node's `squash` arg picks squash or merge.
+    pub strategy: Option<MergeStrategy>,
+    /// `[merge] squash_granularity`: one squashed commit per plan, or one per plan step.
+    pub squash_granularity: SquashGranularity,
+    /// `[merge] commit_template` text (inline or read from its file) for plan merge commits.
+    pub commit_template: Option<String>,
+    /// Block plan merges until an owner of every affected narrative thread ran `vizier ack`.
+    pub require_owner_ack: bool,
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
@@ -554,6 +680,8 @@ pub enum CommitMetaStyle {
     Header,
     Trailers,
     Both,
+    /// Keeps the message clean and records the fields in `refs/notes/vizier`.
+    Notes,
     None,
 }
 
@@ -563,6 +691,7 @@ impl CommitMetaStyle {
             "header" => Some(Self::Header),
             "trailers" | "trailer" => Some(Self::Trailers),
             "both" => Some(Self::Both),
+            "notes" | "note" => Some(Self::Notes),
             "none" => Some(Self::None),
             _ => None,
         }
@@ -674,6 +803,64 @@ pub struct CommitImplementationConfig {
     pub fields: Vec<CommitImplementationField>,
 }
 
+/// How `git.integrate_plan_branch` lands a plan branch on its target.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// One squashed commit on the target.
+    Squash,
+    /// A merge commit joining the plan branch.
+    Merge,
+    /// Plan commits replayed one by one onto the target, which is then fast-forwarded.
+    Rebase,
+}
+
+impl MergeStrategy {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().replace('_', "-").as_str() {
+            "squash" => Some(Self::Squash),
+            "merge" | "no-squash" => Some(Self::Merge),
+            "rebase" | "rebase-and-merge" | "rebase-merge" => Some(Self::Rebase),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Squash => "squash",
+            Self::Merge => "merge",
+            Self::Rebase => "rebase",
+        }
+    }
+}
+
+/// How many commits a squash merge lands on the target.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SquashGranularity {
+    /// One commit for the whole plan branch.
+    #[default]
+    Plan,
+    /// One commit per `## Execution Plan` step, grouped by the `Vizier-Step` trailers approve
+    /// commits carry.
+    Step,
+}
+
+impl SquashGranularity {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "plan" | "branch" => Some(Self::Plan),
+            "step" | "steps" => Some(Self::Step),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Plan => "plan",
+            Self::Step => "step",
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum CommitMergePlanMode {
     Full,
@@ -764,6 +951,8 @@ pub struct DisplayListsConfig {
 #[derive(Clone, Debug, PartialEq, Eq, Default)]
 pub struct DisplaySettings {
     pub lists: DisplayListsConfig,
+    /// Per-subsystem stderr log filters (`agent=debug,git=warn`); `--log` entries win.
+    pub log: Option<String>,
 }
 
 #[derive(Clone, Default)]
@@ -774,6 +963,388 @@ pub struct JobsCancelConfig {
 #[derive(Clone, Default)]
 pub struct JobsConfig {
     pub cancel: JobsCancelConfig,
+    /// Jobs the scheduler runs at once; further ready jobs stay queued (0 = unlimited).
+    pub max_concurrent: usize,
+}
+
+/// Naming scheme for plan branches: `prefix` followed by the plan slug. A `{user}` placeholder
+/// in the prefix stands for one branch path segment naming the operator.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BranchesConfig {
+    pub prefix: String,
+    /// Branch plans target when `--target` is not given; `None` detects it from the repository.
+    pub target: Option<String>,
+}
+
+pub const BRANCH_USER_PLACEHOLDER: &str = "{user}";
+
+impl BranchesConfig {
+    /// Plan branch for `slug`; `user` fills the `{user}` placeholder when the prefix has one.
+    pub fn branch_for_slug(&self, slug: &str, user: &str) -> String {
+        format!(
+            "{}{slug}",
+            self.prefix.replacen(BRANCH_USER_PLACEHOLDER, user, 1)
+        )
+    }
+
+    /// Slug of `branch` when it follows the scheme. `{user}` matches any single non-empty path
+    /// segment, so plan branches of every operator are recognized.
+    pub fn slug_for_branch<'a>(&self, branch: &'a str) -> Option<&'a str> {
+        let rest = match self.prefix.split_once(BRANCH_USER_PLACEHOLDER) {
+            None => branch.strip_prefix(self.prefix.as_str())?,
+            Some((before, after)) => {
+                let rest = branch.strip_prefix(before)?;
+                let segment_end = rest.find('/').unwrap_or(rest.len());
+                (1..=segment_end)
+                    .filter(|&index| rest.is_char_boundary(index))
+                    .find_map(|index| rest[index..].strip_prefix(after))?
+            }
+        };
+        (!rest.is_empty()).then_some(rest)
+    }
+}
+
+/// Build host `vizier run --remote` executes workflows on. `ssh` is the command prefix the host
+/// is appended to; `path` is the remote checkout and `git_remote` the remote both sides share.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RemoteConfig {
+    pub host: Option<String>,
+    pub path: Option<String>,
+    pub git_remote: String,
+    pub vizier: String,
+    pub ssh: Vec<String>,
+}
+
+/// Scheduler events notifications are sent for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum NotificationEvent {
+    /// A plan was persisted to its draft branch.
+    Draft,
+    /// A plan branch was merged into its target.
+    Merge,
+    /// A gate (stop condition, conflict resolution, CI/CD) failed.
+    GateFailure,
+    /// Any other workflow job failed.
+    JobFailure,
+}
+
+impl NotificationEvent {
+    pub const ALL: [Self; 4] = [
+        Self::Draft,
+        Self::Merge,
+        Self::GateFailure,
+        Self::JobFailure,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Draft => "draft",
+            Self::Merge => "merge",
+            Self::GateFailure => "gate_failure",
+            Self::JobFailure => "job_failure",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().replace('-', "_").as_str() {
+            "draft" => Some(Self::Draft),
+            "merge" => Some(Self::Merge),
+            "gate_failure" => Some(Self::GateFailure),
+            "job_failure" => Some(Self::JobFailure),
+            _ => None,
+        }
+    }
+}
+
+/// When an event is emailed: as it happens, batched into the next digest, or never.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NotificationDelivery {
+    Immediate,
+    Digest,
+    Off,
+}
+
+impl NotificationDelivery {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Immediate => "immediate",
+            Self::Digest => "digest",
+            Self::Off => "off",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "immediate" | "now" => Some(Self::Immediate),
+            "digest" => Some(Self::Digest),
+            "off" | "none" | "false" => Some(Self::Off),
+            _ => None,
+        }
+    }
+}
+
+/// How the SMTP connection is secured: STARTTLS after connecting, TLS from the first byte
+/// (usually port 465), or not at all (local relays only).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SmtpTls {
+    StartTls,
+    Implicit,
+    None,
+}
+
+impl SmtpTls {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "starttls" => Some(Self::StartTls),
+            "implicit" | "tls" | "smtps" => Some(Self::Implicit),
+            "none" | "off" => Some(Self::None),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NotificationsConfig {
+    pub email: EmailNotificationConfig,
+}
+
+/// SMTP email for scheduler events; disabled until `smtp_host` and `to` are set. The password is
+/// read from the `password_env` environment variable so it never lands in a config file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EmailNotificationConfig {
+    pub smtp_host: Option<String>,
+    pub smtp_port: u16,
+    pub tls: SmtpTls,
+    pub username: Option<String>,
+    pub password_env: String,
+    pub from: String,
+    pub to: Vec<String>,
+    /// Hours between digest emails; events set to `digest` wait for the next one.
+    pub digest_hours: u32,
+    pub events: HashMap<NotificationEvent, NotificationDelivery>,
+}
+
+impl EmailNotificationConfig {
+    pub fn enabled(&self) -> bool {
+        self.smtp_host.is_some() && !self.to.is_empty()
+    }
+
+    pub fn delivery(&self, event: NotificationEvent) -> NotificationDelivery {
+        self.events
+            .get(&event)
+            .copied()
+            .unwrap_or(NotificationDelivery::Off)
+    }
+}
+
+/// Post-hoc audit of shell commands an agent backend reports starting. Patterns are globs (`*`,
+/// `?`) matched against the whole command; `abort` wins over `allow`, and anything unmatched is
+/// asked about on a TTY and aborts the run otherwise. The command itself may already have run.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CommandAuditConfig {
+    pub enabled: bool,
+    pub allow: Vec<String>,
+    pub abort: Vec<String>,
+}
+
+/// Keep a Changelog section types, in the order assembled releases list them.
+pub const CHANGELOG_TYPES: [&str; 6] = [
+    "added",
+    "changed",
+    "deprecated",
+    "removed",
+    "fixed",
+    "security",
+];
+
+/// Changelog fragments written on plan merges and rolled into `file` by
+/// `vizier changelog assemble`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChangelogConfig {
+    pub enabled: bool,
+    /// Repo-relative directory fragments are written to.
+    pub dir: String,
+    pub style: ChangelogStyle,
+    /// Change type used when no `Changelog-Type:` trailer names one.
+    pub default_type: String,
+    /// Repo-relative changelog that fragments are assembled into.
+    pub file: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChangelogStyle {
+    /// `<dir>/<slug>.md` holding `### <Type>` sections of bullets.
+    KeepAChangelog,
+    /// `<dir>/<slug>.<type>.md` holding the entry text.
+    Towncrier,
+}
+
+impl ChangelogStyle {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value
+            .trim()
+            .to_ascii_lowercase()
+            .replace(['-', '_'], "")
+            .as_str()
+        {
+            "keepachangelog" => Some(Self::KeepAChangelog),
+            "towncrier" => Some(Self::Towncrier),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::KeepAChangelog => "keep-a-changelog",
+            Self::Towncrier => "towncrier",
+        }
+    }
+}
+
+/// Size limits a drafted plan is checked against before it is considered safe for a single
+/// approve run; a zero limit disables that check.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DraftConfig {
+    pub max_steps: u32,
+    pub max_files: u32,
+    /// Push the plan branch to `origin` after each workflow commit on it.
+    pub auto_push: bool,
+}
+
+/// Fixture directory the `mock` backend replays responses from, relative to the repo root.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MockAgentConfig {
+    pub fixtures: PathBuf,
+}
+
+/// Language for human-facing output and agent-written prose.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OutputConfig {
+    /// Normalized language tag (`de`, `pt-br`); `None` keeps English.
+    pub language: Option<String>,
+}
+
+/// Retention for `.vizier/sessions/<id>/`; a zero limit disables that check.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SessionsConfig {
+    pub keep_days: u32,
+    pub keep_last: u32,
+    /// Compress expired sessions to `*.json.zst` instead of deleting them.
+    pub compress: bool,
+}
+
+/// Collection of `.vizier/tmp/sessions/` scratch directories left behind by crashed commands.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TmpConfig {
+    /// Orphaned scratch directories older than this are removed at startup; 0 disables.
+    pub keep_days: u32,
+}
+
+impl Default for TmpConfig {
+    fn default() -> Self {
+        Self { keep_days: 7 }
+    }
+}
+
+/// Ranking inputs for `vizier search`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SearchConfig {
+    /// Command that embeds passages for the semantic ranking; empty searches with BM25 alone.
+    pub embedder: Vec<String>,
+}
+
+/// How long an agent may go without writing a line to stdout or stderr before Vizier says so;
+/// zero disables the warning or the kill.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AgentStallConfig {
+    /// Warn after this much silence, and again each time it grows by the same amount.
+    pub warn_after_secs: u64,
+    /// Stop the agent once it has been silent this long.
+    pub kill_after_secs: u64,
+    /// Fresh attempts after a stalled agent was stopped.
+    pub retries: u32,
+}
+
+impl Default for AgentStallConfig {
+    fn default() -> Self {
+        Self {
+            warn_after_secs: 300,
+            kill_after_secs: 0,
+            retries: 0,
+        }
+    }
+}
+
+/// Paths whose contents never reach an agent, whatever the prompt asks for.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ContextConfig {
+    /// Gitignore-style patterns relative to the repository root (`secrets/**`).
+    pub never_send: Vec<String>,
+}
+
+/// Where plan workspaces and throwaway job worktrees are checked out.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WorktreesConfig {
+    /// Directory outside the repository that holds worktrees, namespaced per repository;
+    /// `None` keeps them under `.vizier/tmp-worktrees`.
+    pub dir: Option<PathBuf>,
+}
+
+/// Phases `[hooks]` can wrap with `pre_<phase>` and `post_<phase>` scripts.
+pub const HOOK_PHASES: &[&str] = &["draft", "approve", "review", "merge", "commit"];
+
+/// Hook name for scripts run whenever a phase's node fails.
+pub const ON_FAILURE_HOOK: &str = "on_failure";
+
+/// Shell scripts run around lifecycle phases, keyed by hook name (`pre_draft`, `post_merge`,
+/// `on_failure`).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HooksConfig {
+    pub scripts: BTreeMap<String, String>,
+}
+
+impl HooksConfig {
+    pub fn script(&self, hook: &str) -> Option<&str> {
+        self.scripts.get(hook).map(String::as_str)
+    }
+}
+
+/// Which locally installed forge CLI handles remote operations (opening pull requests, fetching
+/// issues, publishing reviews), so Vizier needs no API token of its own.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ForgeCli {
+    /// `gh` for GitHub remotes and `glab` for GitLab remotes, whichever is on `PATH`.
+    #[default]
+    Auto,
+    Gh,
+    Glab,
+    Off,
+}
+
+impl ForgeCli {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "auto" => Some(Self::Auto),
+            "gh" => Some(Self::Gh),
+            "glab" => Some(Self::Glab),
+            "off" | "none" => Some(Self::Off),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Gh => "gh",
+            Self::Glab => "glab",
+            Self::Off => "off",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ForgeConfig {
+    pub cli: ForgeCli,
 }
 
 #[derive(Clone)]
@@ -799,12 +1370,64 @@ pub struct WorkflowTemplateConfig {
     pub patch: String,
 }
 
+/// Staleness thresholds for draft branches; `0` disables a threshold.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WorkflowDriftConfig {
+    pub max_commits_behind: u32,
+    pub max_age_days: u32,
+    pub strict: bool,
+}
+
+/// How `{{diff:<spec>}}` prompt placeholders shrink diffs larger than `max_bytes`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WorkflowDiffContextConfig {
+    pub max_bytes: u32,
+    pub chunk_bytes: u32,
+    pub raw_hunk_bytes: u32,
+    pub summarizer: DiffSummarizer,
+}
+
+/// Byte ceiling for a resolved workflow prompt; past it, context chunks are dropped in priority
+/// order. `0` disables the budget.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WorkflowPromptBudgetConfig {
+    pub max_bytes: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiffSummarizer {
+    /// Summarize each per-file chunk with the workflow's agent.
+    Agent,
+    /// Skip summaries and keep only per-file stats plus the most relevant raw hunks.
+    None,
+}
+
+impl DiffSummarizer {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "agent" => Some(Self::Agent),
+            "none" | "off" => Some(Self::None),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Agent => "agent",
+            Self::None => "none",
+        }
+    }
+}
+
 #[derive(Clone, Default)]
 pub struct WorkflowConfig {
     pub no_commit_default: bool,
     pub background: BackgroundConfig,
     pub global_workflows: WorkflowGlobalWorkflowsConfig,
     pub templates: WorkflowTemplateConfig,
+    pub drift: WorkflowDriftConfig,
+    pub diff_context: WorkflowDiffContextConfig,
+    pub prompt_budget: WorkflowPromptBudgetConfig,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -814,6 +1437,115 @@ pub struct MergeCicdGateConfig {
     pub retries: u32,
 }
 
+/// When a merge gate runs relative to the merge commit landing on the target branch.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MergeGateStage {
+    /// Runs against the plan branch before anything is merged; failure leaves the target alone.
+    PreMerge,
+    /// Runs on the target checkout after the merge commit; failure may revert the merge.
+    PostMerge,
+}
+
+impl MergeGateStage {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().replace('_', "-").as_str() {
+            "pre-merge" | "pre" => Some(Self::PreMerge),
+            "post-merge" | "post" | "verify" => Some(Self::PostMerge),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::PreMerge => "pre-merge",
+            Self::PostMerge => "post-merge",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MergeGateConfig {
+    pub name: String,
+    pub stage: MergeGateStage,
+    pub script: PathBuf,
+}
+
+impl MergeConfig {
+    /// Gates for `stage` in configured order.
+    pub fn staged_gates(&self, stage: MergeGateStage) -> Vec<MergeGateConfig> {
+        self.gates
+            .iter()
+            .filter(|gate| gate.stage == stage)
+            .cloned()
+            .collect()
+    }
+
+    /// True when `branch` matches one of the `[merge] protected` patterns.
+    pub fn is_protected(&self, branch: &str) -> bool {
+        self.protected
+            .iter()
+            .any(|pattern| crate::glob::glob_matches(pattern.trim(), branch))
+    }
+}
+
+/// Placeholders `[merge] commit_template` may use.
+pub const MERGE_COMMIT_TEMPLATE_PLACEHOLDERS: &[&str] = &[
+    "subject",
+    "title",
+    "slug",
+    "branch",
+    "target",
+    "session_id",
+    "note",
+    "review_verdict",
+    "diff_stats",
+    "plan",
+];
+
+/// Substitutes `{name}` placeholders in a merge commit template. `{{` and `}}` are literal
+/// braces, as is any brace not wrapping a lowercase name. A `{name}` outside
+/// [`MERGE_COMMIT_TEMPLATE_PLACEHOLDERS`] is an error; a known placeholder missing from `values`
+/// renders empty.
+pub fn render_merge_commit_template(
+    template: &str,
+    values: &[(&str, String)],
+) -> Result<String, String> {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(index) = rest.find(['{', '}']) {
+        out.push_str(&rest[..index]);
+        let tail = &rest[index..];
+        if let Some(after) = tail.strip_prefix("{{").or_else(|| tail.strip_prefix("}}")) {
+            out.push_str(&tail[..1]);
+            rest = after;
+            continue;
+        }
+        let Some(end) = tail.strip_prefix('{').and_then(|body| body.find('}')) else {
+            out.push_str(&tail[..1]);
+            rest = &tail[1..];
+            continue;
+        };
+        let name = &tail[1..end + 1];
+        if name.is_empty() || !name.chars().all(|ch| ch.is_ascii_lowercase() || ch == '_') {
+            out.push('{');
+            rest = &tail[1..];
+            continue;
+        }
+        if !MERGE_COMMIT_TEMPLATE_PLACEHOLDERS.contains(&name) {
+            return Err(format!(
+                "unknown placeholder `{{{name}}}` in [merge] commit_template (expected one of: {})",
+                MERGE_COMMIT_TEMPLATE_PLACEHOLDERS.join(", ")
+            ));
+        }
+        if let Some((_, value)) = values.iter().find(|(key, _)| *key == name) {
+            out.push_str(value);
+        }
+        rest = &tail[end + 2..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum BuildPipeline {
     Approve,
@@ -987,9 +1719,16 @@ pub struct MergeConflictsLayer {
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct MergeLayer {
     pub cicd_gate: MergeCicdGateLayer,
+    pub gates: Option<Vec<MergeGateConfig>>,
+    pub auto_revert: Option<bool>,
+    pub protected: Option<Vec<String>>,
     pub conflicts: MergeConflictsLayer,
     pub squash_default: Option<bool>,
     pub squash_mainline: Option<u32>,
+    pub strategy: Option<MergeStrategy>,
+    pub squash_granularity: Option<SquashGranularity>,
+    pub commit_template: Option<String>,
+    pub require_owner_ack: Option<bool>,
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
@@ -1108,11 +1847,15 @@ pub struct DisplayListsLayer {
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct DisplayLayer {
     pub lists: DisplayListsLayer,
+    pub log: Option<String>,
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct ReviewLayer {
     pub checks: Option<Vec<String>>,
+    pub check_jobs: Option<u32>,
+    pub fix_loop_max_iterations: Option<u32>,
+    pub tools: Option<bool>,
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
@@ -1123,6 +1866,121 @@ pub struct JobsCancelLayer {
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct JobsLayer {
     pub cancel: JobsCancelLayer,
+    pub max_concurrent: Option<usize>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BranchesLayer {
+    pub prefix: Option<String>,
+    /// An empty name goes back to detection.
+    pub target: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RemoteLayer {
+    pub host: Option<String>,
+    pub path: Option<String>,
+    pub git_remote: Option<String>,
+    pub vizier: Option<String>,
+    pub ssh: Option<Vec<String>>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NotificationsLayer {
+    pub email: EmailNotificationLayer,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EmailNotificationLayer {
+    pub smtp_host: Option<String>,
+    pub smtp_port: Option<u16>,
+    pub tls: Option<SmtpTls>,
+    pub username: Option<String>,
+    pub password_env: Option<String>,
+    pub from: Option<String>,
+    pub to: Option<Vec<String>>,
+    pub digest_hours: Option<u32>,
+    pub events: HashMap<NotificationEvent, NotificationDelivery>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CommandAuditLayer {
+    pub enabled: Option<bool>,
+    pub allow: Option<Vec<String>>,
+    pub abort: Option<Vec<String>>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ChangelogLayer {
+    pub enabled: Option<bool>,
+    pub dir: Option<String>,
+    pub style: Option<ChangelogStyle>,
+    pub default_type: Option<String>,
+    pub file: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MockAgentLayer {
+    pub fixtures: Option<PathBuf>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SessionsLayer {
+    pub keep_days: Option<u32>,
+    pub keep_last: Option<u32>,
+    pub compress: Option<bool>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TmpLayer {
+    pub keep_days: Option<u32>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SearchLayer {
+    pub embedder: Option<Vec<String>>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AgentStallLayer {
+    pub warn_after_secs: Option<u64>,
+    pub kill_after_secs: Option<u64>,
+    pub retries: Option<u32>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ContextLayer {
+    pub never_send: Option<Vec<String>>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WorktreesLayer {
+    /// An empty path moves worktrees back into the repository.
+    pub dir: Option<PathBuf>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HooksLayer {
+    /// An empty script removes an inherited hook.
+    pub scripts: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ForgeLayer {
+    pub cli: Option<ForgeCli>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OutputLayer {
+    /// `Some(None)` resets an inherited language back to English.
+    pub language: Option<Option<String>>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DraftLayer {
+    pub max_steps: Option<u32>,
+    pub max_files: Option<u32>,
+    pub auto_push: Option<bool>,
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
@@ -1148,23 +2006,56 @@ pub struct WorkflowTemplateLayer {
     pub patch: Option<String>,
 }
 
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WorkflowDriftLayer {
+    pub max_commits_behind: Option<u32>,
+    pub max_age_days: Option<u32>,
+    pub strict: Option<bool>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WorkflowDiffContextLayer {
+    pub max_bytes: Option<u32>,
+    pub chunk_bytes: Option<u32>,
+    pub raw_hunk_bytes: Option<u32>,
+    pub summarizer: Option<DiffSummarizer>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WorkflowPromptBudgetLayer {
+    pub max_bytes: Option<u32>,
+}
+
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct WorkflowLayer {
     pub no_commit_default: Option<bool>,
     pub background: BackgroundLayer,
     pub global_workflows: WorkflowGlobalWorkflowsLayer,
     pub templates: WorkflowTemplateLayer,
+    pub drift: WorkflowDriftLayer,
+    pub diff_context: WorkflowDiffContextLayer,
+    pub prompt_budget: WorkflowPromptBudgetLayer,
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct ApproveStopConditionLayer {
     pub script: Option<PathBuf>,
     pub retries: Option<u32>,
+    pub conditions: Option<String>,
+    pub tests: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ApproveCostLayer {
+    pub input_micro_usd_per_mtok: Option<u64>,
+    pub output_micro_usd_per_mtok: Option<u64>,
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct ApproveLayer {
     pub stop_condition: ApproveStopConditionLayer,
+    pub cost: ApproveCostLayer,
+    pub generate_tests: Option<bool>,
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
@@ -1179,12 +2070,30 @@ pub struct ConfigLayer {
     pub commits: CommitLayer,
     pub display: DisplayLayer,
     pub jobs: JobsLayer,
+    pub sessions: SessionsLayer,
+    pub tmp: TmpLayer,
+    pub search: SearchLayer,
+    pub agent_stall: AgentStallLayer,
+    pub context: ContextLayer,
+    pub worktrees: WorktreesLayer,
+    pub hooks: HooksLayer,
+    pub forge: ForgeLayer,
+    pub output: OutputLayer,
+    pub draft: DraftLayer,
+    pub mock_agent: MockAgentLayer,
+    pub branches: BranchesLayer,
+    pub remote: RemoteLayer,
+    pub notifications: NotificationsLayer,
+    pub command_audit: CommandAuditLayer,
+    pub changelog: ChangelogLayer,
     pub workflow: WorkflowLayer,
     pub commands: HashMap<CommandAlias, TemplateSelector>,
     pub agent_defaults: Option<AgentOverrides>,
     pub agent_commands: HashMap<CommandAlias, AgentOverrides>,
     pub agent_templates: HashMap<TemplateSelector, AgentOverrides>,
     pub agent_scopes: HashMap<CommandScope, AgentOverrides>,
+    /// `[profiles.<name>]` overlays; applied on top of this layer only when selected.
+    pub profiles: HashMap<String, ConfigLayer>,
 }
 
 impl Config {
@@ -1380,6 +2289,7 @@ impl Config {
 pub fn backend_kind_for_selector(selector: &str) -> BackendKind {
     match selector.trim().to_ascii_lowercase().as_str() {
         "gemini" => BackendKind::Gemini,
+        "mock" => BackendKind::Mock,
         _ => BackendKind::Agent,
     }
 }
@@ -1387,6 +2297,7 @@ pub fn backend_kind_for_selector(selector: &str) -> BackendKind {
 pub fn default_selector_for_backend(backend: BackendKind) -> &'static str {
     match backend {
         BackendKind::Gemini => "gemini",
+        BackendKind::Mock => "mock",
         BackendKind::Agent => "codex",
     }
 }
@@ -1404,6 +2315,39 @@ pub fn normalize_selector_value(value: &str) -> Option<String> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn merge_protected_patterns_match_wildcards() {
+        let cfg = MergeConfig {
+            protected: vec!["main".to_string(), "release/*".to_string()],
+            ..MergeConfig::default()
+        };
+        assert!(cfg.is_protected("main"));
+        assert!(cfg.is_protected("release/1.2"));
+        assert!(!cfg.is_protected("release"));
+        assert!(!cfg.is_protected("mainline"));
+        assert!(!MergeConfig::default().is_protected("main"));
+    }
+
+    #[test]
+    fn merge_commit_template_substitutes_known_placeholders() {
+        let rendered = render_merge_commit_template(
+            "feat({slug}): {title}\n\nReview: {review_verdict}\nNote: {note}\n{{raw}} {\"json\": 1}",
+            &[
+                ("slug", "alpha".to_string()),
+                ("title", "Rotate tokens".to_string()),
+                ("review_verdict", "approved".to_string()),
+            ],
+        )
+        .expect("render");
+        assert_eq!(
+            rendered,
+            "feat(alpha): Rotate tokens\n\nReview: approved\nNote: \n{raw} {\"json\": 1}"
+        );
+
+        let err = render_merge_commit_template("{sluggo}", &[]).expect_err("unknown");
+        assert!(err.contains("unknown placeholder `{sluggo}`"), "{err}");
+    }
+
     #[test]
     fn normalize_selector_trims_and_lowercases() {
         assert_eq!(
@@ -1443,10 +2387,40 @@ mod tests {
         assert_eq!(normalized.label.as_deref(), Some("custom"));
     }
 
+    #[test]
+    fn branches_scheme_round_trips_slugs() {
+        let default = BranchesConfig::default();
+        assert_eq!(default.branch_for_slug("alpha", "ignored"), "draft/alpha");
+        assert_eq!(default.slug_for_branch("draft/alpha"), Some("alpha"));
+        assert_eq!(default.slug_for_branch("draft/"), None);
+        assert_eq!(default.slug_for_branch("feature/alpha"), None);
+
+        let namespaced = BranchesConfig {
+            prefix: "vizier/draft/".to_string(),
+            target: None,
+        };
+        assert_eq!(
+            namespaced.branch_for_slug("alpha", "ignored"),
+            "vizier/draft/alpha"
+        );
+        assert_eq!(namespaced.slug_for_branch("draft/alpha"), None);
+
+        let per_user = BranchesConfig {
+            prefix: "draft/{user}/".to_string(),
+            target: None,
+        };
+        assert_eq!(per_user.branch_for_slug("alpha", "sam"), "draft/sam/alpha");
+        assert_eq!(per_user.slug_for_branch("draft/sam/alpha"), Some("alpha"));
+        assert_eq!(per_user.slug_for_branch("draft/kim/beta"), Some("beta"));
+        assert_eq!(per_user.slug_for_branch("draft/alpha"), None);
+        assert_eq!(per_user.slug_for_branch("draft//alpha"), None);
+    }
+
     #[test]
     fn selector_to_backend_is_case_insensitive() {
         assert_eq!(backend_kind_for_selector("GEMINI"), BackendKind::Gemini);
         assert_eq!(backend_kind_for_selector("codex"), BackendKind::Agent);
+        assert_eq!(backend_kind_for_selector("Mock"), BackendKind::Mock);
     }
 
     #[test]
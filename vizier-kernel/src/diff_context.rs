@@ -0,0 +1,422 @@
+use crate::config::{DiffSummarizer, WorkflowDiffContextConfig};
+
+/// Instructions prepended to every per-file chunk handed to the summarizing agent.
+pub const DIFF_CHUNK_SUMMARY_PROMPT: &str = r#"<instruction>
+Summarize this slice of a git diff for a reader who will not see the raw patch.
+- Reply with 1-5 terse bullet points and nothing else.
+- Say what changed and why it matters: behavior, public APIs, data formats, config keys, tests.
+- Name the functions, types, and files involved; do not restate unchanged context lines.
+</instruction>
+"#;
+
+/// One `diff --git` section of a unified diff.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileDiff {
+    pub path: String,
+    /// Everything before the first hunk (`diff --git`, `index`, `---`/`+++`, mode lines).
+    pub header: String,
+    pub hunks: Vec<DiffHunk>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DiffHunk {
+    pub text: String,
+    pub added: usize,
+    pub removed: usize,
+}
+
+impl DiffHunk {
+    fn changed_lines(&self) -> usize {
+        self.added + self.removed
+    }
+}
+
+impl FileDiff {
+    pub fn added(&self) -> usize {
+        self.hunks.iter().map(|hunk| hunk.added).sum()
+    }
+
+    pub fn removed(&self) -> usize {
+        self.hunks.iter().map(|hunk| hunk.removed).sum()
+    }
+
+    /// Lockfiles, snapshots, and minified bundles rarely explain a change; their hunks are
+    /// the last to be kept verbatim.
+    pub fn is_low_signal(&self) -> bool {
+        let name = self.path.rsplit('/').next().unwrap_or(&self.path);
+        name.ends_with(".lock")
+            || name.ends_with("-lock.json")
+            || name.ends_with("-lock.yaml")
+            || name.ends_with(".snap")
+            || name.ends_with(".min.js")
+            || name.ends_with(".min.css")
+    }
+
+    /// Splits the file's hunks into pieces of at most `max_bytes`, keeping hunks whole unless
+    /// a single hunk is larger than the limit.
+    pub fn chunks(&self, max_bytes: usize) -> Vec<String> {
+        let max_bytes = max_bytes.max(1);
+        let mut chunks = Vec::new();
+        let mut current = String::new();
+        for hunk in &self.hunks {
+            if !current.is_empty() && current.len() + hunk.text.len() > max_bytes {
+                chunks.push(std::mem::take(&mut current));
+            }
+            if hunk.text.len() > max_bytes {
+                let mut clipped = clip_to_boundary(&hunk.text, max_bytes).to_string();
+                clipped.push_str("\n[hunk truncated]\n");
+                chunks.push(clipped);
+                continue;
+            }
+            current.push_str(&hunk.text);
+        }
+        if !current.is_empty() || chunks.is_empty() {
+            chunks.push(current);
+        }
+        chunks
+    }
+}
+
+/// Parses `git diff` patch output into per-file sections.
+pub fn split_unified_diff(diff: &str) -> Vec<FileDiff> {
+    let mut files: Vec<FileDiff> = Vec::new();
+    let mut in_hunk = false;
+    for line in diff.split_inclusive('\n') {
+        if line.starts_with("diff --git ") {
+            files.push(FileDiff {
+                path: path_from_git_header(line),
+                header: line.to_string(),
+                hunks: Vec::new(),
+            });
+            in_hunk = false;
+            continue;
+        }
+        let Some(file) = files.last_mut() else {
+            continue;
+        };
+        if line.starts_with("@@") {
+            file.hunks.push(DiffHunk {
+                text: line.to_string(),
+                added: 0,
+                removed: 0,
+            });
+            in_hunk = true;
+            continue;
+        }
+        match file.hunks.last_mut() {
+            Some(hunk) if in_hunk => {
+                if line.starts_with('+') {
+                    hunk.added += 1;
+                } else if line.starts_with('-') {
+                    hunk.removed += 1;
+                }
+                hunk.text.push_str(line);
+            }
+            _ => {
+                if let Some(path) = line.strip_prefix("+++ b/") {
+                    file.path = path.trim_end().to_string();
+                }
+                file.header.push_str(line);
+            }
+        }
+    }
+    files
+}
+
+fn path_from_git_header(line: &str) -> String {
+    let rest = line.trim_end().trim_start_matches("diff --git ");
+    rest.rsplit_once(" b/")
+        .map(|(_, path)| path.to_string())
+        .unwrap_or_else(|| rest.to_string())
+}
+
+/// Builds the prompt asking the agent to summarize one chunk of `file`.
+pub fn build_diff_chunk_summary_prompt(
+    file: &FileDiff,
+    part: usize,
+    parts: usize,
+    chunk: &str,
+) -> String {
+    format!(
+        "{}\n<diffChunk path=\"{}\" part=\"{part}/{parts}\">\n{}{}</diffChunk>\n",
+        DIFF_CHUNK_SUMMARY_PROMPT, file.path, file.header, chunk
+    )
+}
+
+/// Prompt-ready diff text plus any summarizer failures worth surfacing to the operator.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DiffContext {
+    pub text: String,
+    pub summarized: bool,
+    pub warnings: Vec<String>,
+}
+
+/// Returns `diff` untouched when it fits `config.max_bytes`; otherwise replaces it with
+/// per-file summaries (via `summarize`, when the summarizer is `agent`) followed by the most
+/// relevant raw hunks that fit `config.raw_hunk_bytes`.
+pub fn build_diff_context<F>(
+    diff: &str,
+    config: &WorkflowDiffContextConfig,
+    mut summarize: F,
+) -> DiffContext
+where
+    F: FnMut(String) -> Result<String, String>,
+{
+    let max_bytes = config.max_bytes as usize;
+    if max_bytes == 0 || diff.len() <= max_bytes {
+        return DiffContext {
+            text: diff.to_string(),
+            summarized: false,
+            warnings: Vec::new(),
+        };
+    }
+
+    let files = split_unified_diff(diff);
+    let mut warnings = Vec::new();
+    let mut out = format!(
+        "[diff summarized: {} bytes across {} files exceeded workflow.diff_context.max_bytes ({max_bytes})]\n\n",
+        diff.len(),
+        files.len()
+    );
+
+    out.push_str(match config.summarizer {
+        DiffSummarizer::Agent => "## File Summaries\n",
+        DiffSummarizer::None => "## Files Changed\n",
+    });
+    for file in &files {
+        out.push_str(&format!(
+            "### {} (+{} -{})\n",
+            file.path,
+            file.added(),
+            file.removed()
+        ));
+        if config.summarizer == DiffSummarizer::None {
+            continue;
+        }
+        let chunks = file.chunks(config.chunk_bytes as usize);
+        for (index, chunk) in chunks.iter().enumerate() {
+            let prompt = build_diff_chunk_summary_prompt(file, index + 1, chunks.len(), chunk);
+            match summarize(prompt) {
+                Ok(summary) => {
+                    out.push_str(summary.trim());
+                    out.push('\n');
+                }
+                Err(err) => {
+                    warnings.push(format!("diff summary for {} failed: {err}", file.path));
+                    out.push_str("(summary unavailable)\n");
+                }
+            }
+        }
+        out.push('\n');
+    }
+
+    let selected = select_relevant_hunks(&files, config.raw_hunk_bytes as usize);
+    let total_hunks = files.iter().map(|file| file.hunks.len()).sum::<usize>();
+    out.push_str(&format!(
+        "\n## Most Relevant Hunks ({} of {total_hunks})\n",
+        selected.len()
+    ));
+    let mut last_file = None;
+    for (file_index, hunk_index) in selected {
+        let file = &files[file_index];
+        if last_file != Some(file_index) {
+            out.push_str(&file.header);
+            last_file = Some(file_index);
+        }
+        out.push_str(&file.hunks[hunk_index].text);
+    }
+
+    DiffContext {
+        text: out,
+        summarized: true,
+        warnings,
+    }
+}
+
+/// Picks hunks to keep verbatim: high-signal files first, then the hunks that change the most
+/// lines, skipping any that would overflow `budget`. Returned in original diff order.
+pub fn select_relevant_hunks(files: &[FileDiff], budget: usize) -> Vec<(usize, usize)> {
+    let mut candidates = files
+        .iter()
+        .enumerate()
+        .flat_map(|(file_index, file)| {
+            file.hunks
+                .iter()
+                .enumerate()
+                .map(move |(hunk_index, hunk)| (file_index, hunk_index, file, hunk))
+        })
+        .collect::<Vec<_>>();
+    candidates.sort_by(|a, b| {
+        a.2.is_low_signal()
+            .cmp(&b.2.is_low_signal())
+            .then(b.3.changed_lines().cmp(&a.3.changed_lines()))
+    });
+
+    let mut used = 0usize;
+    let mut headers_used = vec![false; files.len()];
+    let mut selected = Vec::new();
+    for (file_index, hunk_index, file, hunk) in candidates {
+        let header_cost = if headers_used[file_index] {
+            0
+        } else {
+            file.header.len()
+        };
+        let cost = header_cost + hunk.text.len();
+        if used + cost > budget {
+            continue;
+        }
+        used += cost;
+        headers_used[file_index] = true;
+        selected.push((file_index, hunk_index));
+    }
+    selected.sort_unstable();
+    selected
+}
+
+fn clip_to_boundary(text: &str, max_bytes: usize) -> &str {
+    let mut end = max_bytes.min(text.len());
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file_diff(path: &str, hunks: &[(usize, usize)]) -> String {
+        let mut out = format!(
+            "diff --git a/{path} b/{path}\nindex 1111111..2222222 100644\n--- a/{path}\n+++ b/{path}\n"
+        );
+        for (index, (added, removed)) in hunks.iter().enumerate() {
+            out.push_str(&format!(
+                "@@ -{0},1 +{0},1 @@ fn hunk_{index}()\n",
+                index * 10 + 1
+            ));
+            for line in 0..*removed {
+                out.push_str(&format!("-old {path} {index} {line}\n"));
+            }
+            for line in 0..*added {
+                out.push_str(&format!("+new {path} {index} {line}\n"));
+            }
+            out.push_str(" context\n");
+        }
+        out
+    }
+
+    fn config(summarizer: DiffSummarizer) -> WorkflowDiffContextConfig {
+        WorkflowDiffContextConfig {
+            max_bytes: 400,
+            chunk_bytes: 200,
+            raw_hunk_bytes: 300,
+            summarizer,
+        }
+    }
+
+    #[test]
+    fn split_unified_diff_counts_changes_per_hunk() {
+        let diff = format!(
+            "{}{}",
+            file_diff("src/a.rs", &[(2, 1), (0, 3)]),
+            file_diff("Cargo.lock", &[(1, 1)])
+        );
+        let files = split_unified_diff(&diff);
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].path, "src/a.rs");
+        assert_eq!(files[0].hunks.len(), 2);
+        assert_eq!((files[0].added(), files[0].removed()), (2, 4));
+        assert!(files[0].header.ends_with("+++ b/src/a.rs\n"));
+        assert!(files[1].is_low_signal());
+        let rebuilt = files
+            .iter()
+            .map(|file| {
+                let hunks = file
+                    .hunks
+                    .iter()
+                    .map(|h| h.text.as_str())
+                    .collect::<String>();
+                format!("{}{hunks}", file.header)
+            })
+            .collect::<String>();
+        assert_eq!(rebuilt, diff);
+    }
+
+    #[test]
+    fn small_diffs_pass_through_untouched() {
+        let diff = file_diff("src/a.rs", &[(1, 1)]);
+        let context = build_diff_context(&diff, &config(DiffSummarizer::Agent), |_| {
+            panic!("small diffs must not be summarized")
+        });
+        assert!(!context.summarized);
+        assert_eq!(context.text, diff);
+    }
+
+    #[test]
+    fn large_diffs_are_summarized_per_chunk_with_relevant_hunks() {
+        let diff = format!(
+            "{}{}",
+            file_diff("Cargo.lock", &[(12, 12)]),
+            file_diff("src/a.rs", &[(1, 0), (6, 2), (2, 0)])
+        );
+        let mut prompts = Vec::new();
+        let context = build_diff_context(&diff, &config(DiffSummarizer::Agent), |prompt| {
+            prompts.push(prompt);
+            if prompts.len() == 1 {
+                Err("agent offline".to_string())
+            } else {
+                Ok(format!("- summary {}", prompts.len()))
+            }
+        });
+
+        assert!(context.summarized);
+        assert!(prompts.len() > 2, "expected chunked prompts: {prompts:?}");
+        assert!(prompts[0].contains("path=\"Cargo.lock\" part=\"1/"));
+        assert!(prompts[0].starts_with(DIFF_CHUNK_SUMMARY_PROMPT));
+        assert_eq!(context.warnings.len(), 1);
+        assert!(
+            context
+                .text
+                .contains("### Cargo.lock (+12 -12)\n(summary unavailable)")
+        );
+        assert!(context.text.contains("### src/a.rs (+9 -2)\n- summary"));
+
+        let raw = context
+            .text
+            .split_once("## Most Relevant Hunks")
+            .map(|(_, raw)| raw)
+            .expect("raw hunk section");
+        assert!(raw.contains("@@ -11,1 +11,1 @@ fn hunk_1()"), "{raw}");
+        assert!(!raw.contains("diff --git a/Cargo.lock"), "{raw}");
+    }
+
+    #[test]
+    fn none_summarizer_skips_agent_calls() {
+        let diff = file_diff("src/a.rs", &[(20, 20)]);
+        let context = build_diff_context(&diff, &config(DiffSummarizer::None), |_| {
+            panic!("summarizer `none` must not call the agent")
+        });
+        assert!(context.summarized);
+        assert!(
+            context
+                .text
+                .contains("## Files Changed\n### src/a.rs (+20 -20)")
+        );
+    }
+
+    #[test]
+    fn oversized_hunks_are_clipped_on_char_boundaries() {
+        let file = FileDiff {
+            path: "notes.md".to_string(),
+            header: String::new(),
+            hunks: vec![DiffHunk {
+                text: "@@ -1 +1 @@\n+ééééé\n".to_string(),
+                added: 1,
+                removed: 0,
+            }],
+        };
+        let chunks = file.chunks(16);
+        assert_eq!(chunks.len(), 1);
+        assert!(chunks[0].ends_with("[hunk truncated]\n"));
+    }
+}
@@ -0,0 +1,99 @@
+use serde::Serialize;
+
+use crate::config::WorkflowDriftConfig;
+
+/// How far a draft branch has fallen behind its target.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct PlanDrift {
+    pub branch: String,
+    pub target: String,
+    pub commits_ahead: usize,
+    pub commits_behind: usize,
+    /// Whole days since the oldest commit unique to the draft branch, when there is one.
+    pub age_days: Option<u64>,
+}
+
+impl PlanDrift {
+    /// Human-readable reasons the draft exceeds `thresholds`; empty when it is fresh enough.
+    pub fn stale_reasons(&self, thresholds: &WorkflowDriftConfig) -> Vec<String> {
+        let mut reasons = Vec::new();
+        let max_behind = thresholds.max_commits_behind as usize;
+        if max_behind > 0 && self.commits_behind > max_behind {
+            reasons.push(format!(
+                "{} commits behind {} (limit {max_behind})",
+                self.commits_behind, self.target
+            ));
+        }
+        let max_age = u64::from(thresholds.max_age_days);
+        if let Some(age_days) = self.age_days
+            && max_age > 0
+            && age_days > max_age
+        {
+            reasons.push(format!("drafted {age_days} days ago (limit {max_age})"));
+        }
+        reasons
+    }
+
+    pub fn is_stale(&self, thresholds: &WorkflowDriftConfig) -> bool {
+        !self.stale_reasons(thresholds).is_empty()
+    }
+
+    /// One-line warning naming the plan, why it is stale, and how to refresh it.
+    pub fn stale_message(&self, slug: &str, thresholds: &WorkflowDriftConfig) -> Option<String> {
+        let reasons = self.stale_reasons(thresholds);
+        if reasons.is_empty() {
+            return None;
+        }
+        Some(format!(
+            "plan `{slug}` ({}) is stale: {}; run `vizier rebase {slug}` to refresh it",
+            self.branch,
+            reasons.join(", ")
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drift(behind: usize, age_days: Option<u64>) -> PlanDrift {
+        PlanDrift {
+            branch: "draft/alpha".to_string(),
+            target: "main".to_string(),
+            commits_ahead: 1,
+            commits_behind: behind,
+            age_days,
+        }
+    }
+
+    #[test]
+    fn stale_reasons_respect_thresholds() {
+        let thresholds = WorkflowDriftConfig {
+            max_commits_behind: 5,
+            max_age_days: 7,
+            strict: false,
+        };
+        assert!(drift(5, Some(7)).stale_reasons(&thresholds).is_empty());
+        assert_eq!(
+            drift(6, Some(8)).stale_reasons(&thresholds),
+            vec![
+                "6 commits behind main (limit 5)".to_string(),
+                "drafted 8 days ago (limit 7)".to_string(),
+            ]
+        );
+        let message = drift(6, None)
+            .stale_message("alpha", &thresholds)
+            .expect("stale message");
+        assert!(message.contains("vizier rebase alpha"), "{message}");
+    }
+
+    #[test]
+    fn zero_thresholds_disable_checks() {
+        let thresholds = WorkflowDriftConfig {
+            max_commits_behind: 0,
+            max_age_days: 0,
+            strict: true,
+        };
+        assert!(!drift(500, Some(365)).is_stale(&thresholds));
+    }
+}
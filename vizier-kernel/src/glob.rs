@@ -0,0 +1,46 @@
+//! The one glob dialect config patterns use: `[merge] protected` branches, expected surfaces in
+//! plans, and `[command_audit]` command patterns all match through [`glob_matches`].
+
+use globset::GlobBuilder;
+
+/// Whole-string glob match. `*` matches any run of characters including `/`, `?` exactly one
+/// character, and `[...]`/`{a,b}` are classes and alternations. A pattern equal to `text`
+/// always matches, so literal paths and commands containing glob metacharacters still work; a
+/// pattern that is not a valid glob matches nothing else.
+pub fn glob_matches(pattern: &str, text: &str) -> bool {
+    if pattern == text {
+        return true;
+    }
+    GlobBuilder::new(pattern)
+        .literal_separator(false)
+        .build()
+        .is_ok_and(|glob| glob.compile_matcher().is_match(text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn star_crosses_separators_and_question_matches_one() {
+        assert!(glob_matches("release/*", "release/1.0/hotfix"));
+        assert!(glob_matches("git push*", "git push --force origin main"));
+        assert!(glob_matches("git ?iff*", "git diff HEAD"));
+        assert!(!glob_matches("git diff", "git diff HEAD"));
+        assert!(!glob_matches("main", "maintenance"));
+    }
+
+    #[test]
+    fn literal_text_matches_itself_even_with_metacharacters() {
+        assert!(glob_matches("src/routes/[id].tsx", "src/routes/[id].tsx"));
+        assert!(glob_matches("echo {", "echo {"));
+        assert!(!glob_matches("echo {", "echo {x"));
+    }
+
+    #[test]
+    fn many_stars_do_not_blow_up() {
+        let pattern = "*a".repeat(30);
+        let text = "a".repeat(29);
+        assert!(!glob_matches(&pattern, &text));
+    }
+}
@@ -1,9 +1,15 @@
 pub mod audit;
 pub mod config;
+pub mod diff_context;
+pub mod drift;
+pub mod glob;
 pub mod ports;
 pub mod prompt;
+pub mod prompt_budget;
 pub mod prompts;
 pub mod scheduler;
+pub mod search;
+pub mod stop_condition;
 pub mod workflow_audit;
 pub mod workflow_template;
 
@@ -308,6 +308,31 @@ pub struct ReviewPromptInput<'a> {
     pub context: Option<&'a PromptContext>,
 }
 
+/// Renders review check results as the per-command blocks embedded in review prompts.
+pub fn render_check_results(check_results: &[ReviewCheckContext]) -> String {
+    if check_results.is_empty() {
+        return "No review checks were executed before this critique.\n".to_string();
+    }
+    let mut text = String::new();
+    for check in check_results {
+        let status_label = if check.success { "success" } else { "failure" };
+        let status_code = check
+            .status_code
+            .map(|code| code.to_string())
+            .unwrap_or_else(|| "signal".to_string());
+        text.push_str(&format!(
+            "### Command: {}\nstatus: {} (code={})\nduration_ms: {}\nstdout:\n{}\n\nstderr:\n{}\n\n",
+            check.command.trim(),
+            status_label,
+            status_code,
+            check.duration_ms,
+            check.stdout.trim(),
+            check.stderr.trim(),
+        ));
+    }
+    text
+}
+
 pub fn build_review_prompt(
     prompt_selection: &PromptSelection,
     input: ReviewPromptInput<'_>,
@@ -355,26 +380,7 @@ pub fn build_review_prompt(
     prompt.push_str("</diffSummary>\n\n");
 
     prompt.push_str("<checkResults>\n");
-    if input.check_results.is_empty() {
-        prompt.push_str("No review checks were executed before this critique.\n");
-    } else {
-        for check in input.check_results {
-            let status_label = if check.success { "success" } else { "failure" };
-            let status_code = check
-                .status_code
-                .map(|code| code.to_string())
-                .unwrap_or_else(|| "signal".to_string());
-            prompt.push_str(&format!(
-                "### Command: {}\nstatus: {} (code={})\nduration_ms: {}\nstdout:\n{}\n\nstderr:\n{}\n\n",
-                check.command.trim(),
-                status_label,
-                status_code,
-                check.duration_ms,
-                check.stdout.trim(),
-                check.stderr.trim(),
-            ));
-        }
-    }
+    prompt.push_str(&render_check_results(input.check_results));
     prompt.push_str("</checkResults>\n\n");
 
     prompt.push_str("<cicdGate>\n");
@@ -548,6 +554,80 @@ pub fn build_cicd_failure_prompt(input: CicdFailurePromptInput<'_>) -> Result<St
     Ok(prompt)
 }
 
+pub struct TestGenerationPromptInput<'a> {
+    pub plan_slug: &'a str,
+    pub plan_branch: &'a str,
+    pub plan_document: &'a str,
+    /// `[approve.stop_condition] tests`, the command the new tests must pass under.
+    pub test_command: Option<&'a str>,
+    pub bounds: &'a str,
+}
+
+pub fn build_test_generation_prompt(input: TestGenerationPromptInput<'_>) -> String {
+    let mut prompt = String::new();
+    prompt.push_str("You are assisting after `vizier approve` implemented the plan below on its branch. Add or extend automated tests so that every acceptance criterion in the plan is covered, following the repository's existing test layout and conventions. Do not change the implementation except where a test exposes a real defect, and do not weaken or delete existing tests. Run the tests you touched and keep iterating until they pass. Do not commit; Vizier commits your changes separately as a `tests:` commit. End with a brief summary listing the criteria covered and the tests that cover them.\n\n");
+
+    prompt.push_str(&format!("<{AGENT_BOUNDS_TAG}>\n"));
+    prompt.push_str(input.bounds);
+    prompt.push_str(&format!("\n</{AGENT_BOUNDS_TAG}>\n\n"));
+
+    prompt.push_str("<planMetadata>\n");
+    prompt.push_str(&format!(
+        "plan_slug: {}\nplan_branch: {}\n",
+        input.plan_slug, input.plan_branch
+    ));
+    if let Some(command) = input.test_command {
+        prompt.push_str(&format!("test_command: {command}\n"));
+    }
+    prompt.push_str("</planMetadata>\n\n");
+
+    prompt.push_str("<planDocument>\n");
+    prompt.push_str(input.plan_document.trim());
+    prompt.push_str("\n</planDocument>\n");
+    prompt
+}
+
+pub struct ReviewFixPromptInput<'a> {
+    pub plan_slug: &'a str,
+    pub plan_branch: &'a str,
+    pub target_branch: &'a str,
+    pub plan_document: &'a str,
+    /// The critique whose Action Items this iteration addresses.
+    pub critique: &'a str,
+    pub iteration: u32,
+    pub max_iterations: u32,
+    pub bounds: &'a str,
+}
+
+pub fn build_review_fix_prompt(input: ReviewFixPromptInput<'_>) -> String {
+    let mut prompt = String::new();
+    prompt.push_str("You are fixing the plan branch below after a Vizier review requested changes. Address every bullet under `Action Items` in <reviewCritique>, starting with the most severe, and stay within the plan's scope. Where an item is a hypothesis you can disprove from the code, leave the code alone and say why. Do not commit; Vizier commits your changes, reruns the review checks, and asks for a fresh review. End with a brief summary listing each action item and how it was resolved.\n\n");
+
+    prompt.push_str(&format!("<{AGENT_BOUNDS_TAG}>\n"));
+    prompt.push_str(input.bounds);
+    prompt.push_str(&format!("\n</{AGENT_BOUNDS_TAG}>\n\n"));
+
+    prompt.push_str("<planMetadata>\n");
+    prompt.push_str(&format!(
+        "plan_slug: {}\nplan_branch: {}\ntarget_branch: {}\nfix_iteration: {} of {}\n",
+        input.plan_slug,
+        input.plan_branch,
+        input.target_branch,
+        input.iteration,
+        input.max_iterations
+    ));
+    prompt.push_str("</planMetadata>\n\n");
+
+    prompt.push_str("<planDocument>\n");
+    prompt.push_str(input.plan_document.trim());
+    prompt.push_str("\n</planDocument>\n\n");
+
+    prompt.push_str("<reviewCritique>\n");
+    prompt.push_str(input.critique.trim());
+    prompt.push_str("\n</reviewCritique>\n");
+    prompt
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
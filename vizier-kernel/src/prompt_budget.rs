@@ -0,0 +1,390 @@
+//! Fits a resolved workflow prompt under `[workflow.prompt_budget] max_bytes` by dropping whole
+//! context chunks in a fixed priority order, instead of clipping the text or failing the node.
+
+use serde::Serialize;
+
+use crate::diff_context::split_unified_diff;
+
+/// Priority of a context chunk; earlier tiers are kept longest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContextTier {
+    /// The stored plan and what derives from it (operator notes, expected surfaces).
+    Plan,
+    /// Diffs, one chunk per file.
+    Diff,
+    /// Snapshot sections that mention a path or file name the diffs touch.
+    LinkedSnapshot,
+    /// Narrative threads, thread owners, and the remaining snapshot sections.
+    Threads,
+    /// Any other file a placeholder pulled in.
+    Other,
+}
+
+impl ContextTier {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Plan => "plan",
+            Self::Diff => "diff",
+            Self::LinkedSnapshot => "linked_snapshot",
+            Self::Threads => "threads",
+            Self::Other => "other",
+        }
+    }
+}
+
+/// The tier a `{{...}}` placeholder's expansion belongs to; `None` for plain variables, which
+/// are never dropped.
+pub fn tier_for_placeholder(key: &str) -> Option<ContextTier> {
+    let key = key.trim();
+    if ["diff:", "range_diff:", "diff_stats:"]
+        .iter()
+        .any(|prefix| key.starts_with(prefix))
+    {
+        return Some(ContextTier::Diff);
+    }
+    if key.starts_with("plan_notes:") || key.starts_with("expected_surfaces:") {
+        return Some(ContextTier::Plan);
+    }
+    if key.starts_with("thread_owners:") {
+        return Some(ContextTier::Threads);
+    }
+    let path = key.strip_prefix("file:")?.trim();
+    Some(if path.contains("implementation-plans/") {
+        ContextTier::Plan
+    } else if path.ends_with("narrative/snapshot.md") {
+        ContextTier::LinkedSnapshot
+    } else if path.contains("narrative/") {
+        ContextTier::Threads
+    } else {
+        ContextTier::Other
+    })
+}
+
+/// One piece of a rendered prompt: literal template text or a variable (never dropped), or a
+/// placeholder expansion that may lose chunks.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PromptSegment {
+    Fixed(String),
+    Context {
+        source: String,
+        tier: ContextTier,
+        text: String,
+    },
+}
+
+/// A chunk removed to fit the budget.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct DroppedChunk {
+    pub tier: ContextTier,
+    pub source: String,
+    pub label: String,
+    pub bytes: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct PromptBudgetReport {
+    pub max_bytes: usize,
+    pub original_bytes: usize,
+    pub final_bytes: usize,
+    /// In the order they were dropped.
+    pub dropped: Vec<DroppedChunk>,
+    /// False when the prompt is still over budget with every context chunk dropped.
+    pub fits: bool,
+}
+
+#[derive(Clone, Debug)]
+struct Chunk {
+    segment: usize,
+    tier: ContextTier,
+    label: String,
+    text: String,
+    dropped: bool,
+}
+
+/// Concatenates `segments`, dropping context chunks while the result exceeds `max_bytes`
+/// (`0` means no budget). The lowest tier goes first, and within a tier the chunk nearest the
+/// end of the prompt. A segment that loses chunks ends with a one-line note naming them. The
+/// report is `None` when nothing had to be dropped.
+pub fn fit_prompt(
+    segments: &[PromptSegment],
+    max_bytes: usize,
+) -> (String, Option<PromptBudgetReport>) {
+    let original = segments
+        .iter()
+        .map(|segment| match segment {
+            PromptSegment::Fixed(text) | PromptSegment::Context { text, .. } => text.as_str(),
+        })
+        .collect::<String>();
+    if max_bytes == 0 || original.len() <= max_bytes {
+        return (original, None);
+    }
+
+    let touched = touched_names(segments);
+    let mut chunks = Vec::new();
+    for (index, segment) in segments.iter().enumerate() {
+        let PromptSegment::Context { tier, text, .. } = segment else {
+            continue;
+        };
+        for (label, text) in split_chunks(*tier, text) {
+            let tier = match tier {
+                ContextTier::LinkedSnapshot if !mentions_any(&text, &touched) => {
+                    ContextTier::Threads
+                }
+                tier => *tier,
+            };
+            chunks.push(Chunk {
+                segment: index,
+                tier,
+                label,
+                text,
+                dropped: false,
+            });
+        }
+    }
+
+    let mut order = (0..chunks.len()).collect::<Vec<_>>();
+    order.sort_by(|a, b| chunks[*b].tier.cmp(&chunks[*a].tier).then(b.cmp(a)));
+    let mut dropped = Vec::new();
+    let mut text = original.clone();
+    for index in order {
+        if text.len() <= max_bytes {
+            break;
+        }
+        chunks[index].dropped = true;
+        dropped.push(index);
+        text = render(segments, &chunks);
+    }
+
+    let report = PromptBudgetReport {
+        max_bytes,
+        original_bytes: original.len(),
+        final_bytes: text.len(),
+        dropped: dropped
+            .into_iter()
+            .map(|index| {
+                let chunk = &chunks[index];
+                DroppedChunk {
+                    tier: chunk.tier,
+                    source: segment_source(&segments[chunk.segment]).to_string(),
+                    label: chunk.label.clone(),
+                    bytes: chunk.text.len(),
+                }
+            })
+            .collect(),
+        fits: text.len() <= max_bytes,
+    };
+    (text, Some(report))
+}
+
+fn render(segments: &[PromptSegment], chunks: &[Chunk]) -> String {
+    let mut out = String::new();
+    for (index, segment) in segments.iter().enumerate() {
+        match segment {
+            PromptSegment::Fixed(text) => out.push_str(text),
+            PromptSegment::Context { source, .. } => {
+                let mut labels = Vec::new();
+                for chunk in chunks.iter().filter(|chunk| chunk.segment == index) {
+                    if chunk.dropped {
+                        labels.push(chunk.label.as_str());
+                    } else {
+                        out.push_str(&chunk.text);
+                    }
+                }
+                if !labels.is_empty() {
+                    if !out.is_empty() && !out.ends_with('\n') {
+                        out.push('\n');
+                    }
+                    out.push_str(&format!(
+                        "[prompt budget: dropped {} chunk(s) of {source}: {}]\n",
+                        labels.len(),
+                        labels.join(", ")
+                    ));
+                }
+            }
+        }
+    }
+    out
+}
+
+fn segment_source(segment: &PromptSegment) -> &str {
+    match segment {
+        PromptSegment::Fixed(_) => "",
+        PromptSegment::Context { source, .. } => source,
+    }
+}
+
+// Diffs split per file; everything else at Markdown headings outside code fences. Chunks
+// concatenate back to `text` exactly.
+fn split_chunks(tier: ContextTier, text: &str) -> Vec<(String, String)> {
+    let mut chunks: Vec<(String, String)> = Vec::new();
+    let mut in_fence = false;
+    for line in text.split_inclusive('\n') {
+        let starts_chunk = if tier == ContextTier::Diff {
+            line.starts_with("diff --git ")
+        } else {
+            if line.trim_start().starts_with("```") {
+                in_fence = !in_fence;
+            }
+            !in_fence && is_heading(line)
+        };
+        if starts_chunk || chunks.is_empty() {
+            let label = if !starts_chunk {
+                "(preamble)".to_string()
+            } else if tier == ContextTier::Diff {
+                split_unified_diff(line)
+                    .first()
+                    .map(|file| file.path.clone())
+                    .unwrap_or_else(|| line.trim().to_string())
+            } else {
+                line.trim().trim_start_matches('#').trim().to_string()
+            };
+            chunks.push((label, String::new()));
+        }
+        if let Some((_, chunk)) = chunks.last_mut() {
+            chunk.push_str(line);
+        }
+    }
+    chunks
+}
+
+fn is_heading(line: &str) -> bool {
+    let hashes = line.chars().take_while(|ch| *ch == '#').count();
+    (1..=6).contains(&hashes) && line[hashes..].starts_with(' ')
+}
+
+// Paths the diffs touch plus their file names, for linking snapshot sections.
+fn touched_names(segments: &[PromptSegment]) -> Vec<String> {
+    let mut names = Vec::new();
+    for segment in segments {
+        let PromptSegment::Context {
+            tier: ContextTier::Diff,
+            text,
+            ..
+        } = segment
+        else {
+            continue;
+        };
+        for file in split_unified_diff(text) {
+            if let Some((_, name)) = file.path.rsplit_once('/') {
+                names.push(name.to_string());
+            }
+            names.push(file.path);
+        }
+    }
+    names.sort();
+    names.dedup();
+    names
+}
+
+fn mentions_any(text: &str, names: &[String]) -> bool {
+    names
+        .iter()
+        .any(|name| !name.is_empty() && text.contains(name.as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context(source: &str, text: &str) -> PromptSegment {
+        PromptSegment::Context {
+            source: source.to_string(),
+            tier: tier_for_placeholder(source).expect("context placeholder"),
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn tier_for_placeholder_orders_plan_diff_snapshot_threads_other() {
+        assert_eq!(
+            tier_for_placeholder("file:.vizier/implementation-plans/a.md"),
+            Some(ContextTier::Plan)
+        );
+        assert_eq!(
+            tier_for_placeholder("diff:main..draft/a"),
+            Some(ContextTier::Diff)
+        );
+        assert_eq!(
+            tier_for_placeholder("file:.vizier/narrative/snapshot.md"),
+            Some(ContextTier::LinkedSnapshot)
+        );
+        assert_eq!(
+            tier_for_placeholder("file:.vizier/narrative/threads/x.md"),
+            Some(ContextTier::Threads)
+        );
+        assert_eq!(
+            tier_for_placeholder("file:README.md"),
+            Some(ContextTier::Other)
+        );
+        assert_eq!(tier_for_placeholder("persist_plan.spec_text"), None);
+        assert!(ContextTier::Plan < ContextTier::Diff && ContextTier::Threads < ContextTier::Other);
+    }
+
+    #[test]
+    fn fit_prompt_drops_lowest_priority_chunks_at_boundaries() {
+        let diff = "diff --git a/src/lib.rs b/src/lib.rs\n+++ b/src/lib.rs\n@@ -1 +1 @@\n+fn lib() {}\n\
+diff --git a/src/big.rs b/src/big.rs\n+++ b/src/big.rs\n@@ -1 +1 @@\n+big\n";
+        let snapshot = format!(
+            "## Parser\nlib.rs owns parsing.\n## Telemetry\n{}\n",
+            "unrelated ".repeat(30)
+        );
+        let segments = vec![
+            PromptSegment::Fixed("<instruction>review</instruction>\n".to_string()),
+            context(
+                "file:.vizier/implementation-plans/a.md",
+                "## Plan\nDo it.\n",
+            ),
+            context("diff:main..HEAD", diff),
+            context("file:.vizier/narrative/snapshot.md", &snapshot),
+            context("file:notes/extra.md", &"x".repeat(400)),
+        ];
+
+        let (full, report) = fit_prompt(&segments, 0);
+        assert!(report.is_none());
+        assert!(full.contains("Telemetry"));
+
+        let budget = full.len() - 300;
+        let (text, report) = fit_prompt(&segments, budget);
+        let report = report.expect("over budget");
+        assert!(report.fits, "{report:?}");
+        assert!(text.len() <= budget);
+        assert_eq!(report.dropped.len(), 1);
+        assert_eq!(report.dropped[0].tier, ContextTier::Other);
+        assert!(
+            text.contains("[prompt budget: dropped 1 chunk(s) of file:notes/extra.md: (preamble)]")
+        );
+
+        let budget = text.len() - 30;
+        let (text, report) = fit_prompt(&segments, budget);
+        let report = report.expect("over budget");
+        assert_eq!(
+            report
+                .dropped
+                .iter()
+                .map(|chunk| (chunk.tier, chunk.label.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (ContextTier::Other, "(preamble)"),
+                (ContextTier::Threads, "Telemetry"),
+            ]
+        );
+        assert!(text.contains("## Parser\nlib.rs owns parsing."), "{text}");
+        assert!(text.contains("diff --git a/src/big.rs"), "{text}");
+        assert!(text.contains("## Plan\nDo it."));
+        assert!(text.starts_with("<instruction>review</instruction>\n"));
+    }
+
+    #[test]
+    fn fit_prompt_reports_when_fixed_text_alone_exceeds_budget() {
+        let segments = vec![
+            PromptSegment::Fixed("y".repeat(100)),
+            context("diff:HEAD", "diff --git a/a b/a\n+a\n"),
+        ];
+        let (text, report) = fit_prompt(&segments, 50);
+        let report = report.expect("over budget");
+        assert!(!report.fits);
+        assert_eq!(report.dropped[0].label, "a");
+        assert!(text.starts_with(&"y".repeat(100)));
+    }
+}
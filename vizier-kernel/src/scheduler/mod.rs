@@ -100,6 +100,7 @@ pub enum JobWaitKind {
     Locks,
     PinnedHead,
     Preconditions,
+    Capacity,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
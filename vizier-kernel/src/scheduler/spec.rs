@@ -20,6 +20,8 @@ pub struct SchedulerFacts {
     pub has_child_args: HashSet<String>,
     pub job_order: Vec<String>,
     pub lock_state: LockState,
+    /// Jobs allowed to run at once (`[jobs] max_concurrent`); 0 = unlimited.
+    pub max_concurrent: usize,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -72,12 +74,22 @@ pub struct JobAfterDependencyStatus {
 
 pub fn evaluate_job(facts: &SchedulerFacts, job_id: &str) -> SchedulerDecision {
     let mut lock_state = facts.lock_state.clone();
-    evaluate_job_with_lock_state(facts, job_id, &mut lock_state)
+    let mut running = running_jobs(facts);
+    evaluate_job_with_lock_state(facts, job_id, &mut lock_state, &mut running)
+}
+
+fn running_jobs(facts: &SchedulerFacts) -> usize {
+    facts
+        .job_statuses
+        .values()
+        .filter(|status| **status == JobStatus::Running)
+        .count()
 }
 
 pub fn evaluate_all(facts: &SchedulerFacts) -> HashMap<String, SchedulerDecision> {
     let mut decisions = HashMap::new();
     let mut lock_state = facts.lock_state.clone();
+    let mut running = running_jobs(facts);
 
     for job_id in &facts.job_order {
         let status = match facts.job_statuses.get(job_id) {
@@ -88,7 +100,7 @@ pub fn evaluate_all(facts: &SchedulerFacts) -> HashMap<String, SchedulerDecision
             continue;
         }
 
-        let decision = evaluate_job_with_lock_state(facts, job_id, &mut lock_state);
+        let decision = evaluate_job_with_lock_state(facts, job_id, &mut lock_state, &mut running);
         decisions.insert(job_id.clone(), decision);
     }
 
@@ -106,6 +118,7 @@ fn evaluate_job_with_lock_state(
     facts: &SchedulerFacts,
     job_id: &str,
     lock_state: &mut LockState,
+    running: &mut usize,
 ) -> SchedulerDecision {
     match after_dependency_state(facts, job_id) {
         DependencyState::Blocked { detail } => {
@@ -276,7 +289,24 @@ fn evaluate_job_with_lock_state(
         };
     }
 
+    if facts.max_concurrent > 0 && *running >= facts.max_concurrent {
+        note_waited(&mut waited_on, JobWaitKind::Capacity);
+        return SchedulerDecision {
+            next_status: JobStatus::Queued,
+            wait_reason: Some(JobWaitReason {
+                kind: JobWaitKind::Capacity,
+                detail: Some(format!(
+                    "waiting for a free slot ({} of {} running)",
+                    *running, facts.max_concurrent
+                )),
+            }),
+            waited_on,
+            action: SchedulerAction::UpdateStatus,
+        };
+    }
+
     lock_state.acquire(&locks);
+    *running += 1;
     SchedulerDecision {
         next_status: JobStatus::Running,
         wait_reason: None,
@@ -1035,6 +1065,33 @@ mod tests {
         assert_eq!(reason.kind, JobWaitKind::Locks);
     }
 
+    #[test]
+    fn max_concurrent_queues_jobs_beyond_capacity_in_job_order() {
+        let mut facts = SchedulerFacts {
+            max_concurrent: 2,
+            ..SchedulerFacts::default()
+        };
+        facts
+            .job_statuses
+            .insert("job-running".to_string(), JobStatus::Running);
+        for job_id in ["job-first", "job-second", "job-third"] {
+            facts.job_order.push(job_id.to_string());
+            facts
+                .job_statuses
+                .insert(job_id.to_string(), JobStatus::Queued);
+            facts.has_child_args.insert(job_id.to_string());
+        }
+        let decisions = evaluate_all(&facts);
+        assert_eq!(decisions["job-first"].action, SchedulerAction::Start);
+        for job_id in ["job-second", "job-third"] {
+            let decision = &decisions[job_id];
+            assert_eq!(decision.next_status, JobStatus::Queued);
+            let reason = decision.wait_reason.as_ref().expect("wait reason");
+            assert_eq!(reason.kind, JobWaitKind::Capacity);
+            assert!(decision.waited_on.contains(&JobWaitKind::Capacity));
+        }
+    }
+
     #[test]
     fn missing_child_args_fails_after_locks() {
         let job_id = "job";
@@ -0,0 +1,530 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+
+// BM25 tuning constants (Robertson/Sparck Jones defaults).
+const BM25_K1: f64 = 1.2;
+const BM25_B: f64 = 0.75;
+// Reciprocal-rank-fusion damping; 60 is the usual choice from the RRF paper.
+const RRF_K: f64 = 60.0;
+const MAX_PASSAGE_LINES: usize = 12;
+const MAX_SNIPPET_CHARS: usize = 160;
+
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "by", "for", "from", "in", "into", "is", "it", "of",
+    "on", "or", "that", "the", "this", "to", "was", "we", "with",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchSourceKind {
+    Snapshot,
+    Glossary,
+    Thread,
+    Plan,
+    Session,
+    Archive,
+}
+
+impl SearchSourceKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Snapshot => "snapshot",
+            Self::Glossary => "glossary",
+            Self::Thread => "thread",
+            Self::Plan => "plan",
+            Self::Session => "session",
+            Self::Archive => "archive",
+        }
+    }
+}
+
+/// One searchable source. `first_line` anchors `text` inside `path` so callers can index a
+/// fragment of a larger file (for example a summary embedded in session JSON).
+#[derive(Clone, Debug)]
+pub struct SearchDocument {
+    pub path: String,
+    pub kind: SearchSourceKind,
+    pub text: String,
+    pub first_line: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SearchHit {
+    pub path: String,
+    pub kind: SearchSourceKind,
+    pub line: usize,
+    pub snippet: String,
+    pub score: f64,
+    pub lexical_rank: Option<usize>,
+    pub semantic_rank: Option<usize>,
+}
+
+/// Source of embedding vectors for the semantic ranking.
+pub trait Embedder {
+    type Error: std::error::Error + Send + Sync + 'static;
+
+    /// One vector per text, in the same order.
+    fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, Self::Error>;
+}
+
+struct Passage<'a> {
+    document: &'a SearchDocument,
+    start_line: usize,
+    lines: Vec<(usize, &'a str)>,
+    terms: Vec<String>,
+}
+
+/// Rank passages from `documents` against `query` by BM25 over stemmed terms.
+pub fn search(documents: &[SearchDocument], query: &str, limit: usize) -> Vec<SearchHit> {
+    let query_terms = tokenize(query);
+    if query_terms.is_empty() || limit == 0 {
+        return Vec::new();
+    }
+    let passages = documents
+        .iter()
+        .flat_map(split_passages)
+        .collect::<Vec<_>>();
+    let lexical = rank_lexical(&passages, &query_terms);
+    fuse(&passages, &query_terms, &lexical, &[], limit)
+}
+
+/// Like [`search`], fused by reciprocal rank with a semantic ranking: the `limit` passages whose
+/// embeddings are nearest the query's by cosine similarity. Paraphrases that share no terms with
+/// the query surface through the second ranking.
+pub fn hybrid_search<E: Embedder>(
+    documents: &[SearchDocument],
+    query: &str,
+    limit: usize,
+    embedder: &E,
+) -> Result<Vec<SearchHit>, E::Error> {
+    let query_terms = tokenize(query);
+    if query_terms.is_empty() || limit == 0 {
+        return Ok(Vec::new());
+    }
+    let passages = documents
+        .iter()
+        .flat_map(split_passages)
+        .collect::<Vec<_>>();
+    if passages.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut texts = vec![query.trim().to_string()];
+    texts.extend(passages.iter().map(|passage| {
+        passage
+            .lines
+            .iter()
+            .map(|(_, line)| line.trim())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }));
+    let vectors = embedder.embed(&texts)?;
+    let query_vector = vectors.first().map(Vec::as_slice).unwrap_or_default();
+    let similarity = (0..passages.len())
+        .map(|index| {
+            vectors
+                .get(index + 1)
+                .map_or(0.0, |vector| cosine(query_vector, vector))
+        })
+        .collect::<Vec<_>>();
+    let mut semantic = rank_positive(&similarity, 0.0);
+    semantic.truncate(limit);
+
+    let lexical = rank_lexical(&passages, &query_terms);
+    Ok(fuse(&passages, &query_terms, &lexical, &semantic, limit))
+}
+
+fn rank_lexical(passages: &[Passage<'_>], query_terms: &[String]) -> Vec<usize> {
+    if passages.is_empty() {
+        return Vec::new();
+    }
+    rank_positive(&bm25_scores(passages, query_terms), 0.0)
+}
+
+fn fuse(
+    passages: &[Passage<'_>],
+    query_terms: &[String],
+    lexical: &[usize],
+    semantic: &[usize],
+    limit: usize,
+) -> Vec<SearchHit> {
+    let mut fused: HashMap<usize, (f64, Option<usize>, Option<usize>)> = HashMap::new();
+    for (rank, index) in lexical.iter().enumerate() {
+        let entry = fused.entry(*index).or_insert((0.0, None, None));
+        entry.0 += 1.0 / (RRF_K + rank as f64 + 1.0);
+        entry.1 = Some(rank + 1);
+    }
+    for (rank, index) in semantic.iter().enumerate() {
+        let entry = fused.entry(*index).or_insert((0.0, None, None));
+        entry.0 += 1.0 / (RRF_K + rank as f64 + 1.0);
+        entry.2 = Some(rank + 1);
+    }
+
+    let mut ranked = fused.into_iter().collect::<Vec<_>>();
+    ranked.sort_by(|left, right| {
+        right
+            .1
+            .0
+            .total_cmp(&left.1.0)
+            .then_with(|| {
+                passages[left.0]
+                    .document
+                    .path
+                    .cmp(&passages[right.0].document.path)
+            })
+            .then_with(|| {
+                passages[left.0]
+                    .start_line
+                    .cmp(&passages[right.0].start_line)
+            })
+    });
+
+    let query_set = query_terms.iter().collect::<HashSet<_>>();
+    ranked
+        .into_iter()
+        .take(limit)
+        .map(|(index, (score, lexical_rank, semantic_rank))| {
+            let passage = &passages[index];
+            let (line, snippet) = best_line(passage, &query_set);
+            SearchHit {
+                path: passage.document.path.clone(),
+                kind: passage.document.kind,
+                line,
+                snippet,
+                score,
+                lexical_rank,
+                semantic_rank,
+            }
+        })
+        .collect()
+}
+
+fn split_passages(document: &SearchDocument) -> Vec<Passage<'_>> {
+    let mut passages = Vec::new();
+    let mut current: Option<Passage<'_>> = None;
+
+    for (offset, line) in document.text.lines().enumerate() {
+        let line_no = document.first_line + offset;
+        let is_heading = line.trim_start().starts_with('#');
+        // A heading stays attached to the body below it so it never competes as its own hit.
+        let heading_only = current.as_ref().is_some_and(|passage| {
+            passage
+                .lines
+                .iter()
+                .all(|(_, line)| line.trim_start().starts_with('#'))
+        });
+        let flush = ((line.trim().is_empty() || is_heading) && !heading_only)
+            || current
+                .as_ref()
+                .is_some_and(|passage| passage.lines.len() >= MAX_PASSAGE_LINES);
+        if flush && let Some(passage) = current.take() {
+            passages.push(passage);
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let passage = current.get_or_insert_with(|| Passage {
+            document,
+            start_line: line_no,
+            lines: Vec::new(),
+            terms: Vec::new(),
+        });
+        passage.lines.push((line_no, line));
+        passage.terms.extend(tokenize(line));
+    }
+    if let Some(passage) = current {
+        passages.push(passage);
+    }
+
+    passages.retain(|passage| !passage.terms.is_empty());
+    passages
+}
+
+fn bm25_scores(passages: &[Passage<'_>], query_terms: &[String]) -> Vec<f64> {
+    let count = passages.len() as f64;
+    let average_len = passages
+        .iter()
+        .map(|passage| passage.terms.len())
+        .sum::<usize>() as f64
+        / count;
+
+    let unique_query = query_terms.iter().collect::<HashSet<_>>();
+    let mut document_frequency: HashMap<&String, usize> = HashMap::new();
+    for passage in passages {
+        let present = passage.terms.iter().collect::<HashSet<_>>();
+        for term in &unique_query {
+            if present.contains(term) {
+                *document_frequency.entry(*term).or_default() += 1;
+            }
+        }
+    }
+
+    passages
+        .iter()
+        .map(|passage| {
+            let len = passage.terms.len() as f64;
+            unique_query
+                .iter()
+                .map(|term| {
+                    let tf = passage.terms.iter().filter(|t| t == term).count() as f64;
+                    if tf == 0.0 {
+                        return 0.0;
+                    }
+                    let df = *document_frequency.get(term).unwrap_or(&0) as f64;
+                    let idf = ((count - df + 0.5) / (df + 0.5) + 1.0).ln();
+                    idf * (tf * (BM25_K1 + 1.0))
+                        / (tf + BM25_K1 * (1.0 - BM25_B + BM25_B * len / average_len))
+                })
+                .sum()
+        })
+        .collect()
+}
+
+fn rank_positive(scores: &[f64], threshold: f64) -> Vec<usize> {
+    let mut indices = scores
+        .iter()
+        .enumerate()
+        .filter(|(_, score)| **score > threshold)
+        .map(|(index, _)| index)
+        .collect::<Vec<_>>();
+    indices.sort_by(|left, right| scores[*right].total_cmp(&scores[*left]));
+    indices
+}
+
+fn cosine(left: &[f32], right: &[f32]) -> f64 {
+    let dot = left
+        .iter()
+        .zip(right)
+        .map(|(a, b)| f64::from(*a) * f64::from(*b))
+        .sum::<f64>();
+    let norm = |vector: &[f32]| {
+        vector
+            .iter()
+            .map(|value| f64::from(*value).powi(2))
+            .sum::<f64>()
+            .sqrt()
+    };
+    let denominator = norm(left) * norm(right);
+    if denominator > 0.0 {
+        dot / denominator
+    } else {
+        0.0
+    }
+}
+
+fn best_line(passage: &Passage<'_>, query: &HashSet<&String>) -> (usize, String) {
+    // Without a shared term (a semantic-only hit), show the body rather than its heading.
+    let first_body = passage
+        .lines
+        .iter()
+        .position(|(_, line)| !line.trim_start().starts_with('#'))
+        .unwrap_or(0);
+    let mut best = (first_body, 0usize);
+    for (index, (_, line)) in passage.lines.iter().enumerate() {
+        let overlap = tokenize(line)
+            .iter()
+            .filter(|term| query.contains(term))
+            .count();
+        if overlap > best.1 {
+            best = (index, overlap);
+        }
+    }
+    let (line_no, line) = passage.lines[best.0];
+    (line_no, truncate_snippet(line.trim()))
+}
+
+fn truncate_snippet(line: &str) -> String {
+    if line.chars().count() <= MAX_SNIPPET_CHARS {
+        return line.to_string();
+    }
+    let mut truncated = line.chars().take(MAX_SNIPPET_CHARS - 3).collect::<String>();
+    truncated.push_str("...");
+    truncated
+}
+
+/// Lowercase, split on non-alphanumerics, drop stopwords, and apply a light suffix stemmer.
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.split(|ch: char| !ch.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+        .filter(|word| !STOPWORDS.contains(&word.as_str()))
+        .map(|word| stem(&word))
+        .collect()
+}
+
+fn stem(word: &str) -> String {
+    let mut root = word.to_string();
+    for suffix in [
+        "ions", "ion", "ings", "ing", "ies", "ied", "ors", "or", "ed", "s",
+    ] {
+        if let Some(stripped) = word.strip_suffix(suffix)
+            && stripped.chars().count() >= 3
+        {
+            root = if matches!(suffix, "ies" | "ied") {
+                format!("{stripped}y")
+            } else {
+                stripped.to_string()
+            };
+            break;
+        }
+    }
+    if root.chars().count() > 3
+        && let Some(stripped) = root.strip_suffix('e')
+    {
+        root = stripped.to_string();
+    }
+    root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn doc(path: &str, kind: SearchSourceKind, text: &str) -> SearchDocument {
+        SearchDocument {
+            path: path.to_string(),
+            kind,
+            text: text.to_string(),
+            first_line: 1,
+        }
+    }
+
+    #[test]
+    fn tokenize_stems_and_drops_stopwords() {
+        assert_eq!(
+            tokenize("Rotating the auth tokens"),
+            vec!["rotat".to_string(), "auth".to_string(), "token".to_string()]
+        );
+        assert_eq!(
+            tokenize("rotation rotated"),
+            vec!["rotat".to_string(), "rotat".to_string()]
+        );
+    }
+
+    #[test]
+    fn lexical_match_reports_anchored_line() {
+        let documents = vec![
+            doc(
+                "threads/auth.md",
+                SearchSourceKind::Thread,
+                "# Auth\n\nIntro paragraph.\n\nWe rotate the auth token nightly.\nOther note.\n",
+            ),
+            doc(
+                "snapshot.md",
+                SearchSourceKind::Snapshot,
+                "Scheduler locks and job retries.\n",
+            ),
+        ];
+        let hits = search(&documents, "auth token rotation", 5);
+        assert_eq!(hits[0].path, "threads/auth.md");
+        assert_eq!(hits[0].line, 5);
+        assert_eq!(hits[0].snippet, "We rotate the auth token nightly.");
+        assert!(hits[0].lexical_rank.is_some());
+    }
+
+    /// Embeds text as counts over a few hand-picked concepts, so synonyms land together.
+    struct ConceptEmbedder;
+
+    impl Embedder for ConceptEmbedder {
+        type Error = std::io::Error;
+
+        fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, Self::Error> {
+            const CONCEPTS: [&[&str]; 2] = [&["secret", "credential", "key"], &["table", "column"]];
+            Ok(texts
+                .iter()
+                .map(|text| {
+                    let text = text.to_lowercase();
+                    CONCEPTS
+                        .iter()
+                        .map(|words| {
+                            words.iter().filter(|word| text.contains(*word)).count() as f32
+                        })
+                        .collect()
+                })
+                .collect())
+        }
+    }
+
+    struct FailingEmbedder;
+
+    impl Embedder for FailingEmbedder {
+        type Error = std::io::Error;
+
+        fn embed(&self, _texts: &[String]) -> Result<Vec<Vec<f32>>, Self::Error> {
+            Err(std::io::Error::other("embedder unavailable"))
+        }
+    }
+
+    #[test]
+    fn semantic_ranking_finds_paraphrased_passage() {
+        let documents = vec![
+            doc(
+                "threads/creds.md",
+                SearchSourceKind::Thread,
+                "Credentials get rotated by the authentication service.\n",
+            ),
+            doc(
+                "threads/ui.md",
+                SearchSourceKind::Thread,
+                "Render tables with aligned columns.\n",
+            ),
+        ];
+        assert!(
+            search(&documents, "secret rollover", 5).is_empty(),
+            "no shared terms, so BM25 alone finds nothing"
+        );
+        let hits = hybrid_search(&documents, "secret rollover", 5, &ConceptEmbedder).unwrap();
+        assert_eq!(
+            hits.len(),
+            1,
+            "unrelated passages should not match: {hits:?}"
+        );
+        assert_eq!(hits[0].path, "threads/creds.md");
+        assert_eq!(
+            hits[0].snippet,
+            "Credentials get rotated by the authentication service."
+        );
+        assert_eq!(hits[0].lexical_rank, None);
+        assert_eq!(hits[0].semantic_rank, Some(1));
+    }
+
+    #[test]
+    fn hybrid_search_surfaces_embedder_errors() {
+        let documents = vec![doc("a.md", SearchSourceKind::Plan, "anything\n")];
+        assert!(hybrid_search(&documents, "anything", 5, &FailingEmbedder).is_err());
+    }
+
+    #[test]
+    fn passages_respect_document_line_offsets() {
+        let documents = vec![SearchDocument {
+            path: "sessions/abc/session.json".to_string(),
+            kind: SearchSourceKind::Session,
+            text: "Summary mentions merge sentinel cleanup.".to_string(),
+            first_line: 42,
+        }];
+        let hits = search(&documents, "sentinel", 3);
+        assert_eq!(hits[0].line, 42);
+        assert_eq!(hits[0].kind.label(), "session");
+    }
+
+    #[test]
+    fn headings_attach_to_following_body() {
+        let documents = vec![doc(
+            "threads/auth.md",
+            SearchSourceKind::Thread,
+            "# Auth\n\nBackground notes.\n\n## Credentials\n\nRotate the auth token daily.\n",
+        )];
+        let hits = search(&documents, "auth token rotation", 5);
+        assert_eq!(hits[0].line, 7);
+        assert_eq!(hits.len(), 2);
+    }
+
+    #[test]
+    fn empty_query_returns_no_hits() {
+        let documents = vec![doc("a.md", SearchSourceKind::Plan, "anything\n")];
+        assert!(search(&documents, "the and of", 5).is_empty());
+        assert!(search(&documents, "anything", 0).is_empty());
+    }
+}
@@ -0,0 +1,387 @@
+use std::fmt;
+
+/// Boolean conditions Vizier evaluates itself.
+pub const FLAG_CONDITIONS: [&str; 3] = ["tests_pass", "script_pass", "no_todo_added"];
+/// Numeric measurements of the branch diff that conditions compare against a limit.
+pub const NUMERIC_CONDITIONS: [&str; 3] = ["diff_lines", "files_changed", "todo_added"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompareOp {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+impl CompareOp {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Lt => "<",
+            Self::Le => "<=",
+            Self::Gt => ">",
+            Self::Ge => ">=",
+            Self::Eq => "==",
+            Self::Ne => "!=",
+        }
+    }
+
+    pub fn holds(&self, left: u64, right: u64) -> bool {
+        match self {
+            Self::Lt => left < right,
+            Self::Le => left <= right,
+            Self::Gt => left > right,
+            Self::Ge => left >= right,
+            Self::Eq => left == right,
+            Self::Ne => left != right,
+        }
+    }
+}
+
+/// One named condition of a stop-condition expression.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StopCondition {
+    Flag(String),
+    Compare {
+        metric: String,
+        op: CompareOp,
+        limit: u64,
+    },
+}
+
+impl StopCondition {
+    pub fn metric(&self) -> &str {
+        match self {
+            Self::Flag(name) => name,
+            Self::Compare { metric, .. } => metric,
+        }
+    }
+}
+
+impl fmt::Display for StopCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Flag(name) => write!(f, "{name}"),
+            Self::Compare { metric, op, limit } => write!(f, "{metric} {} {limit}", op.as_str()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Node {
+    Literal(bool),
+    Condition(usize),
+    Not(Box<Node>),
+    And(Box<Node>, Box<Node>),
+    Or(Box<Node>, Box<Node>),
+}
+
+/// A parsed stop-condition expression, such as
+/// `tests_pass && no_todo_added && diff_lines < 400`.
+///
+/// Conditions combine with `&&`/`and`, `||`/`or`, `!`/`not`, and parentheses. Every condition is
+/// listed once in [`StopExpression::conditions`] so callers can evaluate and log each one before
+/// combining the results with [`StopExpression::evaluate`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StopExpression {
+    root: Node,
+    conditions: Vec<StopCondition>,
+}
+
+impl StopExpression {
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let tokens = tokenize(source)?;
+        if tokens.is_empty() {
+            return Err("stop-condition expression is empty".to_string());
+        }
+        let mut parser = Parser {
+            tokens,
+            position: 0,
+            conditions: Vec::new(),
+        };
+        let root = parser.or()?;
+        if let Some(token) = parser.tokens.get(parser.position) {
+            return Err(format!("unexpected `{token}` in stop-condition expression"));
+        }
+        Ok(Self {
+            root,
+            conditions: parser.conditions,
+        })
+    }
+
+    /// A list of conditions that must all hold.
+    pub fn all_of(conditions: &[String]) -> Result<Self, String> {
+        Self::parse(&conjunction(conditions))
+    }
+
+    pub fn conditions(&self) -> &[StopCondition] {
+        &self.conditions
+    }
+
+    /// Combines per-condition results, given in [`StopExpression::conditions`] order.
+    pub fn evaluate(&self, results: &[bool]) -> bool {
+        fn eval(node: &Node, results: &[bool]) -> bool {
+            match node {
+                Node::Literal(value) => *value,
+                Node::Condition(index) => results.get(*index).copied().unwrap_or(false),
+                Node::Not(inner) => !eval(inner, results),
+                Node::And(left, right) => eval(left, results) && eval(right, results),
+                Node::Or(left, right) => eval(left, results) || eval(right, results),
+            }
+        }
+        eval(&self.root, results)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    Ident(String),
+    Number(u64),
+    Compare(CompareOp),
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Ident(name) => write!(f, "{name}"),
+            Token::Number(value) => write!(f, "{value}"),
+            Token::Compare(op) => write!(f, "{}", op.as_str()),
+            Token::And => write!(f, "&&"),
+            Token::Or => write!(f, "||"),
+            Token::Not => write!(f, "!"),
+            Token::Open => write!(f, "("),
+            Token::Close => write!(f, ")"),
+        }
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let chars = source.chars().collect::<Vec<_>>();
+    let mut tokens = Vec::new();
+    let mut index = 0;
+    while index < chars.len() {
+        let ch = chars[index];
+        let next = chars.get(index + 1).copied();
+        let (token, width) = match (ch, next) {
+            (c, _) if c.is_whitespace() => {
+                index += 1;
+                continue;
+            }
+            ('&', Some('&')) => (Token::And, 2),
+            ('|', Some('|')) => (Token::Or, 2),
+            ('<', Some('=')) => (Token::Compare(CompareOp::Le), 2),
+            ('>', Some('=')) => (Token::Compare(CompareOp::Ge), 2),
+            ('=', Some('=')) => (Token::Compare(CompareOp::Eq), 2),
+            ('!', Some('=')) => (Token::Compare(CompareOp::Ne), 2),
+            ('<', _) => (Token::Compare(CompareOp::Lt), 1),
+            ('>', _) => (Token::Compare(CompareOp::Gt), 1),
+            ('!', _) => (Token::Not, 1),
+            ('(', _) => (Token::Open, 1),
+            (')', _) => (Token::Close, 1),
+            (c, _) if c.is_ascii_digit() => {
+                let start = index;
+                while index < chars.len() && (chars[index].is_ascii_digit() || chars[index] == '_')
+                {
+                    index += 1;
+                }
+                let digits = chars[start..index]
+                    .iter()
+                    .filter(|c| **c != '_')
+                    .collect::<String>();
+                let value = digits
+                    .parse::<u64>()
+                    .map_err(|_| format!("number `{digits}` is out of range"))?;
+                tokens.push(Token::Number(value));
+                continue;
+            }
+            (c, _) if c.is_ascii_alphabetic() || c == '_' => {
+                let start = index;
+                while index < chars.len()
+                    && (chars[index].is_ascii_alphanumeric() || chars[index] == '_')
+                {
+                    index += 1;
+                }
+                let word = chars[start..index].iter().collect::<String>();
+                tokens.push(match word.as_str() {
+                    "and" => Token::And,
+                    "or" => Token::Or,
+                    "not" => Token::Not,
+                    _ => Token::Ident(word),
+                });
+                continue;
+            }
+            (c, _) => return Err(format!("unexpected `{c}` in stop-condition expression")),
+        };
+        tokens.push(token);
+        index += width;
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    conditions: Vec<StopCondition>,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn advance(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn or(&mut self) -> Result<Node, String> {
+        let mut node = self.and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.position += 1;
+            node = Node::Or(Box::new(node), Box::new(self.and()?));
+        }
+        Ok(node)
+    }
+
+    fn and(&mut self) -> Result<Node, String> {
+        let mut node = self.unary()?;
+        while self.peek() == Some(&Token::And) {
+            self.position += 1;
+            node = Node::And(Box::new(node), Box::new(self.unary()?));
+        }
+        Ok(node)
+    }
+
+    fn unary(&mut self) -> Result<Node, String> {
+        if self.peek() == Some(&Token::Not) {
+            self.position += 1;
+            return Ok(Node::Not(Box::new(self.unary()?)));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Node, String> {
+        match self.advance() {
+            Some(Token::Open) => {
+                let node = self.or()?;
+                match self.advance() {
+                    Some(Token::Close) => Ok(node),
+                    _ => Err("missing `)` in stop-condition expression".to_string()),
+                }
+            }
+            Some(Token::Ident(name)) if name == "true" => Ok(Node::Literal(true)),
+            Some(Token::Ident(name)) if name == "false" => Ok(Node::Literal(false)),
+            Some(Token::Ident(name)) => {
+                let condition = if let Some(Token::Compare(op)) = self.peek().cloned() {
+                    self.position += 1;
+                    let Some(Token::Number(limit)) = self.advance() else {
+                        return Err(format!("`{name} {}` needs a number", op.as_str()));
+                    };
+                    if !NUMERIC_CONDITIONS.contains(&name.as_str()) {
+                        return Err(unknown_condition(&name, &NUMERIC_CONDITIONS));
+                    }
+                    StopCondition::Compare {
+                        metric: name,
+                        op,
+                        limit,
+                    }
+                } else {
+                    if NUMERIC_CONDITIONS.contains(&name.as_str()) {
+                        return Err(format!("`{name}` needs a comparison, e.g. `{name} < 100`"));
+                    }
+                    if !FLAG_CONDITIONS.contains(&name.as_str()) {
+                        return Err(unknown_condition(&name, &FLAG_CONDITIONS));
+                    }
+                    StopCondition::Flag(name)
+                };
+                let index = match self.conditions.iter().position(|seen| *seen == condition) {
+                    Some(index) => index,
+                    None => {
+                        self.conditions.push(condition);
+                        self.conditions.len() - 1
+                    }
+                };
+                Ok(Node::Condition(index))
+            }
+            Some(token) => Err(format!("unexpected `{token}` in stop-condition expression")),
+            None => Err("stop-condition expression ends early".to_string()),
+        }
+    }
+}
+
+/// The expression source for a list of conditions that must all hold.
+pub fn conjunction(conditions: &[String]) -> String {
+    conditions
+        .iter()
+        .map(|condition| format!("({})", condition.trim()))
+        .collect::<Vec<_>>()
+        .join(" && ")
+}
+
+fn unknown_condition(name: &str, expected: &[&str]) -> String {
+    format!(
+        "unknown stop condition `{name}` (expected one of: {})",
+        expected.join(", ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_named_conditions_and_combines_results() {
+        let expr = StopExpression::parse(
+            "tests_pass && (no_todo_added or todo_added <= 2) && diff_lines < 1_000",
+        )
+        .expect("parse");
+        assert_eq!(
+            expr.conditions()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec![
+                "tests_pass",
+                "no_todo_added",
+                "todo_added <= 2",
+                "diff_lines < 1000"
+            ]
+        );
+        assert!(expr.evaluate(&[true, false, true, true]));
+        assert!(!expr.evaluate(&[true, false, false, true]));
+        assert!(!expr.evaluate(&[false, true, true, true]));
+
+        let list = StopExpression::all_of(&[
+            "tests_pass".to_string(),
+            "not script_pass || files_changed > 3".to_string(),
+        ])
+        .expect("list");
+        assert_eq!(list.conditions().len(), 3);
+        assert!(list.evaluate(&[true, false, false]));
+        assert!(!list.evaluate(&[true, true, false]));
+    }
+
+    #[test]
+    fn rejects_unknown_or_malformed_conditions() {
+        for (source, message) in [
+            ("", "empty"),
+            ("tests_passed", "unknown stop condition `tests_passed`"),
+            ("diff_lines", "needs a comparison"),
+            ("tests_pass < 3", "unknown stop condition `tests_pass`"),
+            ("diff_lines < many", "needs a number"),
+            ("(tests_pass", "missing `)`"),
+            ("tests_pass &&", "ends early"),
+            ("tests_pass; rm", "unexpected `;`"),
+        ] {
+            let err = StopExpression::parse(source).expect_err(source);
+            assert!(err.contains(message), "{source}: {err}");
+        }
+    }
+}
@@ -650,6 +650,10 @@ fn canonical_executor_operation(value: &str) -> Option<(WorkflowExecutorClass, &
         }
         "cap.env.shell.cicd.run" => Some((WorkflowExecutorClass::EnvironmentShell, "cicd.run")),
         "cap.agent.invoke" => Some((WorkflowExecutorClass::Agent, "agent.invoke")),
+        "cap.agent.generate_tests" => Some((WorkflowExecutorClass::Agent, "agent.generate_tests")),
+        "cap.agent.review_fix_loop" => {
+            Some((WorkflowExecutorClass::Agent, "agent.review_fix_loop"))
+        }
         _ => None,
     }
 }
@@ -935,6 +939,12 @@ pub fn validate_workflow_capability_contracts(template: &WorkflowTemplate) -> Re
 
         match resolution.identity.executor_operation.as_deref() {
             Some("agent.invoke") => validate_agent_invoke_contract(template, node)?,
+            Some("agent.generate_tests") => {
+                validate_plan_agent_contract(template, node, "agent.generate_tests")?
+            }
+            Some("agent.review_fix_loop") => {
+                validate_plan_agent_contract(template, node, "agent.review_fix_loop")?
+            }
             Some("prompt.resolve") => {
                 if let Some(executor_class) = resolution.identity.executor_class {
                     validate_prompt_resolve_contract(template, node, executor_class)?;
@@ -993,6 +1003,14 @@ const NON_EMPTY_ANY_OF_ARG_REQUIREMENTS: &[NonEmptyAnyOfArgRequirement] = &[
         operation: "worktree.prepare",
         keys: &["branch", "slug", "plan"],
     },
+    NonEmptyAnyOfArgRequirement {
+        operation: "agent.generate_tests",
+        keys: &["slug", "plan"],
+    },
+    NonEmptyAnyOfArgRequirement {
+        operation: "agent.review_fix_loop",
+        keys: &["slug", "plan"],
+    },
     NonEmptyAnyOfArgRequirement {
         operation: "git.integrate_plan_branch",
         keys: &["branch", "source_branch", "plan_branch", "slug", "plan"],
@@ -1072,6 +1090,33 @@ fn validate_agent_invoke_contract(
     Ok(())
 }
 
+// Agent operations that build their own prompt from the plan (`agent.generate_tests`,
+// `agent.review_fix_loop`).
+fn validate_plan_agent_contract(
+    template: &WorkflowTemplate,
+    node: &WorkflowNode,
+    operation: &str,
+) -> Result<(), String> {
+    if !matches!(node.kind, WorkflowNodeKind::Agent) {
+        return Err(executor_contract_error(
+            template,
+            operation,
+            node,
+            &format!("uses kind {:?}; expected agent", node.kind),
+        ));
+    }
+    if has_nonempty_arg(node, "command") || has_nonempty_arg(node, "script") {
+        return Err(executor_contract_error(
+            template,
+            operation,
+            node,
+            "must not declare args.command or args.script; the prompt is built from the plan",
+        ));
+    }
+
+    Ok(())
+}
+
 fn validate_prompt_resolve_contract(
     template: &WorkflowTemplate,
     node: &WorkflowNode,